@@ -24,7 +24,9 @@ use crate::{
     RequestData, ResponseData,
     types::{
       code::{CodeIndexParams, CodeRequest, CodeResponse, CodeSearchParams},
-      watch::{WatchRequest, WatchStartParams, WatchStatusParams, WatchStopParams},
+      watch::{
+        WatchPauseParams, WatchRequest, WatchResumeParams, WatchStartParams, WatchStatusParams, WatchStopParams,
+      },
     },
   },
 };
@@ -90,7 +92,15 @@ impl ActorTestContext {
     // Create daemon settings from the test config
     let daemon_settings = Arc::new(DaemonSettings::from_config(&self.config));
 
-    let handle = ProjectActor::spawn(config, self.embedding.clone(), None, daemon_settings, cancel.clone()).await?;
+    let handle = ProjectActor::spawn(
+      config,
+      self.embedding.clone(),
+      None,
+      None,
+      daemon_settings,
+      cancel.clone(),
+    )
+    .await?;
 
     Ok((handle, cancel))
   }
@@ -159,6 +169,7 @@ pub async fn trigger_index(handle: &ProjectHandle) -> Result<crate::ipc::code::C
       ProjectActorPayload::Request(RequestData::Code(CodeRequest::Index(CodeIndexParams {
         force: false,
         stream: false,
+        since: None,
       }))),
     )
     .await
@@ -188,6 +199,10 @@ pub async fn search_code(
         visibility: vec![],
         chunk_type: vec![],
         min_caller_count: None,
+        current_branch: None,
+        diff_branch: None,
+        infer_language: None,
+        include_generated: None,
       }))),
     )
     .await
@@ -202,7 +217,7 @@ pub async fn search_code(
 
 /// Start the file watcher via the ProjectHandle.
 pub async fn start_watcher(handle: &ProjectHandle) -> Result<(), String> {
-  let payload = ProjectActorPayload::Request(RequestData::Watch(WatchRequest::Start(WatchStartParams)));
+  let payload = ProjectActorPayload::Request(RequestData::Watch(WatchRequest::Start(WatchStartParams::default())));
   let response = handle
     .request("test-start-watcher".to_string(), payload)
     .await
@@ -230,6 +245,36 @@ pub async fn stop_watcher(handle: &ProjectHandle) -> Result<(), String> {
   }
 }
 
+/// Pause the file watcher via the ProjectHandle.
+pub async fn pause_watcher(handle: &ProjectHandle) -> Result<(), String> {
+  let payload = ProjectActorPayload::Request(RequestData::Watch(WatchRequest::Pause(WatchPauseParams)));
+  let response = handle
+    .request("test-pause-watcher".to_string(), payload)
+    .await
+    .map_err(|e| e.to_string())?;
+
+  match response {
+    ProjectActorResponse::Done(ResponseData::Watch(_)) => Ok(()),
+    ProjectActorResponse::Error { code, message, .. } => Err(format!("Pause watcher error {}: {}", code, message)),
+    other => Err(format!("Unexpected response: {:?}", other)),
+  }
+}
+
+/// Resume a paused file watcher via the ProjectHandle.
+pub async fn resume_watcher(handle: &ProjectHandle) -> Result<(), String> {
+  let payload = ProjectActorPayload::Request(RequestData::Watch(WatchRequest::Resume(WatchResumeParams)));
+  let response = handle
+    .request("test-resume-watcher".to_string(), payload)
+    .await
+    .map_err(|e| e.to_string())?;
+
+  match response {
+    ProjectActorResponse::Done(ResponseData::Watch(_)) => Ok(()),
+    ProjectActorResponse::Error { code, message, .. } => Err(format!("Resume watcher error {}: {}", code, message)),
+    other => Err(format!("Unexpected response: {:?}", other)),
+  }
+}
+
 /// Get watcher status via the ProjectHandle.
 pub async fn get_watcher_status(handle: &ProjectHandle) -> Result<crate::ipc::types::watch::WatchStatusResult, String> {
   let payload = ProjectActorPayload::Request(RequestData::Watch(WatchRequest::Status(WatchStatusParams)));
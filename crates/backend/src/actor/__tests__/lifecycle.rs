@@ -8,7 +8,8 @@ mod tests {
   use std::time::Duration;
 
   use crate::actor::__tests__::helpers::{
-    ActorTestContext, search_code, start_watcher, stop_watcher, trigger_index, wait_for, wait_for_scan_complete,
+    ActorTestContext, get_watcher_status, pause_watcher, resume_watcher, search_code, start_watcher, stop_watcher,
+    trigger_index, wait_for, wait_for_scan_complete,
   };
 
   // ==========================================================================
@@ -476,4 +477,51 @@ mod tests {
 
     cancel.cancel();
   }
+
+  /// Test: Pause watcher -> file changes -> changes picked up by the reconcile on resume.
+  #[tokio::test]
+  async fn test_watcher_pause_defers_changes_until_resume() {
+    let ctx = ActorTestContext::new().await;
+
+    ctx.write_source_file("src/lib.rs", "pub fn initial() {}").await;
+
+    let (handle, cancel) = ctx.spawn_project_actor().await.expect("spawn actor");
+    trigger_index(&handle).await.expect("index should succeed");
+
+    start_watcher(&handle).await.expect("start watcher");
+    wait_for_scan_complete(&handle, Duration::from_secs(5)).await;
+
+    pause_watcher(&handle).await.expect("pause watcher");
+    let status = get_watcher_status(&handle).await.expect("status");
+    assert!(
+      status.running && status.paused,
+      "Paused watcher should still be running"
+    );
+
+    ctx
+      .write_source_file("src/while_paused.rs", "pub fn created_while_paused() {}")
+      .await;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let search = search_code(&handle, "created_while_paused").await.expect("search");
+    assert!(
+      !search.chunks.iter().any(|c| c.content.contains("created_while_paused")),
+      "File created while watcher paused should not be indexed"
+    );
+
+    // Resume reconciles with disk, so the file is indexed without a new event
+    resume_watcher(&handle).await.expect("resume watcher");
+    assert!(!get_watcher_status(&handle).await.expect("status").paused);
+
+    let found = wait_for(Duration::from_secs(10), || async {
+      search_code(&handle, "created_while_paused")
+        .await
+        .map(|s| s.chunks.iter().any(|c| c.content.contains("created_while_paused")))
+        .unwrap_or(false)
+    })
+    .await;
+    assert!(found, "File should be indexed after watcher resume");
+
+    cancel.cancel();
+  }
 }
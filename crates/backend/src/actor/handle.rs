@@ -12,6 +12,7 @@ use std::sync::{
 use tokio::sync::mpsc;
 
 use super::message::{IndexJob, IndexProgress, ProjectActorMessage, ProjectActorPayload, ProjectActorResponse};
+use crate::{context::files::ReindexStats, domain::memory::MemoryActor, ipc::types::code::CodeReindexMetrics};
 
 // ============================================================================
 // Project Handle
@@ -40,12 +41,23 @@ impl ProjectHandle {
     &self,
     id: String,
     payload: ProjectActorPayload,
+  ) -> Result<mpsc::Receiver<ProjectActorResponse>, SendError> {
+    self.send_as(id, payload, MemoryActor::System).await
+  }
+
+  /// Send a request on behalf of `actor` and get a receiver for responses
+  pub async fn send_as(
+    &self,
+    id: String,
+    payload: ProjectActorPayload,
+    actor: MemoryActor,
   ) -> Result<mpsc::Receiver<ProjectActorResponse>, SendError> {
     let (reply_tx, reply_rx) = mpsc::channel(32);
     let msg = ProjectActorMessage {
       id,
       reply: reply_tx,
       payload,
+      actor,
     };
     self.tx.send(msg).await.map_err(|_| SendError::ActorGone)?;
     Ok(reply_rx)
@@ -83,6 +95,7 @@ impl ProjectHandle {
 pub struct IndexerHandle {
   pub tx: mpsc::Sender<IndexJob>,
   pending: Arc<AtomicUsize>,
+  stats: Arc<ReindexStats>,
 }
 
 impl IndexerHandle {
@@ -92,12 +105,28 @@ impl IndexerHandle {
     Self {
       tx,
       pending: Arc::new(AtomicUsize::new(0)),
+      stats: Arc::new(ReindexStats::default()),
     }
   }
 
   /// Create a new handle with shared pending counter (for actor to decrement)
   pub fn with_pending(tx: mpsc::Sender<IndexJob>, pending: Arc<AtomicUsize>) -> Self {
-    Self { tx, pending }
+    Self {
+      tx,
+      pending,
+      stats: Arc::new(ReindexStats::default()),
+    }
+  }
+
+  /// Share the actor's embedding reuse counters
+  pub fn with_stats(mut self, stats: Arc<ReindexStats>) -> Self {
+    self.stats = stats;
+    self
+  }
+
+  /// Snapshot of embedding reuse since the indexer started
+  pub fn reindex_stats(&self) -> CodeReindexMetrics {
+    self.stats.snapshot()
   }
 
   /// Get current pending job count
@@ -23,6 +23,7 @@
 //! - **Incremental mode** (≤100 files): Small buffers, short timeouts, low latency
 
 use std::{
+  collections::HashMap,
   path::{Path, PathBuf},
   sync::{
     Arc,
@@ -38,12 +39,22 @@ use tracing::{debug, error, info, trace, warn};
 use super::{
   handle::IndexerHandle,
   message::{IndexJob, IndexProgress},
-  pipeline::run_pipeline,
+  pipeline::{decode_warning, run_pipeline},
 };
 use crate::{
-  context::files::{Chunk, Indexer},
+  context::files::{
+    Chunk, FileMetadata, Indexer,
+    depth::{DepthRules, IndexDepth},
+    extract::read_text,
+    guard::IndexGuard,
+  },
+  context::redact::Redactor,
   db::ProjectDb,
-  domain::config::IndexConfig,
+  domain::{
+    config::{IndexConfig, RedactionConfig},
+    git::GitHead,
+    path::{CaseCache, is_case_insensitive, relative_path},
+  },
   embedding::EmbeddingProvider,
 };
 
@@ -58,6 +69,9 @@ pub struct IndexerConfig {
   pub root: PathBuf,
   /// Index configuration from domain::config
   pub index: IndexConfig,
+  /// Secret redaction applied to chunks before embedding; only the secret
+  /// patterns are used, see [`Redactor::for_code`]
+  pub redaction: RedactionConfig,
   /// Batch size for embedding requests (calculated from EmbeddingConfig)
   pub embedding_batch_size: usize,
   /// Context length for embedding validation/truncation (from EmbeddingConfig)
@@ -276,6 +290,8 @@ pub struct IndexerActor {
   indexer: Indexer,
   /// Shared counter for pending jobs (decremented after each job completes)
   pending: Arc<AtomicUsize>,
+  /// Whether the project's filesystem ignores case (probed on start)
+  case_insensitive: bool,
 }
 
 impl IndexerActor {
@@ -292,14 +308,19 @@ impl IndexerActor {
   ) -> Self {
     // Generate a deterministic UUID from the project_id string using UUID v5
     let project_uuid = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, db.project_id.as_str().as_bytes());
+    let indexer = Indexer::new(project_uuid)
+      .with_guard(IndexGuard::from_config(&config.index))
+      .with_depth(DepthRules::new(&config.root, &config.index))
+      .with_redactor(Redactor::for_code(&config.redaction));
     Self {
       config,
       db,
       embedding,
       job_rx,
       cancel,
-      indexer: Indexer::new(project_uuid),
+      indexer,
       pending,
+      case_insensitive: false,
     }
   }
 
@@ -316,8 +337,9 @@ impl IndexerActor {
     let (tx, rx) = mpsc::channel(256);
     let pending = Arc::new(AtomicUsize::new(0));
     let actor = Self::new(config, db, embedding, rx, cancel, pending.clone());
+    let stats = actor.indexer.stats();
     tokio::spawn(actor.run());
-    IndexerHandle::with_pending(tx, pending)
+    IndexerHandle::with_pending(tx, pending).with_stats(stats)
   }
 
   /// Main actor loop
@@ -331,8 +353,10 @@ impl IndexerActor {
   pub async fn run(mut self) {
     info!(root = ?self.config.root, "IndexerActor started");
 
-    // Batch collection for file jobs
-    let mut file_batch: Vec<PathBuf> = Vec::new();
+    self.case_insensitive = is_case_insensitive(&self.config.root).await;
+
+    // Batch collection for file jobs, with previous content for incremental parsing
+    let mut file_batch: Vec<(PathBuf, Option<String>)> = Vec::new();
     let batch_size = self.config.index.watcher_batch_size;
     let batch_timeout = Duration::from_millis(self.config.index.watcher_batch_timeout_ms);
     let mut batch_timer = tokio::time::interval(batch_timeout);
@@ -369,9 +393,9 @@ impl IndexerActor {
               }
               break;
             }
-            Some(IndexJob::File { path, old_content: _ }) => {
+            Some(IndexJob::File { path, old_content }) => {
               // Accumulate file jobs for batching
-              file_batch.push(path);
+              file_batch.push((path, old_content));
 
               // Flush if batch is full
               if file_batch.len() >= batch_size {
@@ -407,18 +431,34 @@ impl IndexerActor {
   }
 
   /// Flush accumulated file batch through the pipeline
-  async fn flush_file_batch(&mut self, batch: &mut Vec<PathBuf>) {
-    let files: Vec<PathBuf> = std::mem::take(batch);
-    let count = files.len();
+  async fn flush_file_batch(&mut self, batch: &mut Vec<(PathBuf, Option<String>)>) {
+    let count = batch.len();
 
     if count == 0 {
       return;
     }
 
-    debug!(count, "Flushing file batch");
+    // Keep the earliest previous content when a file changed more than once,
+    // since that is what the index was built from
+    let mut files = Vec::with_capacity(count);
+    let mut old_contents: HashMap<PathBuf, Arc<String>> = HashMap::new();
+    let drained: Vec<_> = batch.drain(..).collect();
+    let mut case_cache = CaseCache::default();
+    for (path, old_content) in drained {
+      // Events for both casings of a renamed file collapse to one path
+      let path = self.canonical_path(path, &mut case_cache).await;
+      if let Some(old) = old_content {
+        old_contents.entry(path.clone()).or_insert_with(|| Arc::new(old));
+      }
+      if !files.contains(&path) {
+        files.push(path);
+      }
+    }
+
+    debug!(count, incremental = old_contents.len(), "Flushing file batch");
 
     // Use the batch pipeline for efficient processing
-    if let Err(e) = self.batch_index(files, None).await {
+    if let Err(e) = self.batch_index_pipeline(files, old_contents, None).await {
       error!(error = %e, "Failed to index file batch");
     }
 
@@ -446,14 +486,15 @@ impl IndexerActor {
   /// Reads the file content, parses it into chunks, generates embeddings,
   /// and stores everything in the database.
   async fn index_file(&mut self, path: &Path, old_content: Option<&str>) -> Result<(), IndexError> {
-    let relative = path
-      .strip_prefix(&self.config.root)
-      .map_err(|_| IndexError::InvalidPath(path.to_path_buf()))?;
+    let path = &self.canonical_path(path.to_path_buf(), &mut CaseCache::default()).await;
+    let relative = relative_path(path, &self.config.root).ok_or_else(|| IndexError::InvalidPath(path.to_path_buf()))?;
 
-    debug!(file = %relative.display(), "Indexing file");
+    debug!(file = %relative, "Indexing file");
 
     // Read file content
-    let content = tokio::fs::read_to_string(path).await?;
+    let decoded = read_text(path).await?;
+    decode_warning(&relative, &decoded);
+    let content = decoded.text;
 
     // Use unified Indexer to scan and chunk
     let metadata = self
@@ -461,33 +502,62 @@ impl IndexerActor {
       .scan_file(path, &self.config.root)
       .ok_or_else(|| IndexError::UnsupportedFile(path.to_path_buf()))?;
 
+    let is_code = matches!(metadata, FileMetadata::Code { .. });
+    if let Some(skipped) = self.indexer.guard().check_content(&relative, &content, is_code) {
+      self.indexer.record_skipped(&self.db, skipped).await;
+      return Ok(());
+    }
+
     let chunks = self
       .indexer
       .chunk_file(&content, &metadata, old_content)
       .map_err(|e| IndexError::Parse(e.to_string()))?;
+    let shallow = self.indexer.depth(&metadata) == IndexDepth::Shallow;
+    let chunks = if shallow {
+      Indexer::shallow_chunks(chunks)
+    } else {
+      chunks
+    };
 
     if chunks.is_empty() {
-      trace!(file = %relative.display(), "No chunks produced, skipping");
+      trace!(file = %relative, "No chunks produced, skipping");
+      return Ok(());
+    }
+    if let Some(skipped) = self.indexer.guard().check_chunks(&relative, chunks.len()) {
+      self.indexer.record_skipped(&self.db, skipped).await;
       return Ok(());
     }
 
-    let relative_str = relative.to_string_lossy();
-
-    // Generate embeddings
-    let embeddings = self.embed_unified_chunks(&chunks).await?;
-
-    // Prepare chunks with embeddings
-    let chunks_with_embeddings: Vec<(Chunk, Vec<f32>)> = chunks.into_iter().zip(embeddings).collect();
+    // Generate embeddings; shallow files are stored with zero vectors
+    let embeddings = if shallow {
+      vec![vec![0.0; self.db.vector_dim]; chunks.len()]
+    } else {
+      self.embed_unified_chunks(&chunks).await?
+    };
+
+    // Prepare chunks with embeddings, stamped with the current checkout
+    let head = GitHead::read(&self.config.root).await;
+    let chunks_with_embeddings: Vec<(Chunk, Vec<f32>)> = chunks
+      .into_iter()
+      .map(|mut chunk| {
+        if let Some(head) = &head {
+          chunk.set_git_head(head);
+        }
+        chunk
+      })
+      .zip(embeddings)
+      .collect();
     let chunk_count = chunks_with_embeddings.len();
 
     // Store via unified Indexer
     self
       .indexer
-      .store_chunks(&self.db, &relative_str, chunks_with_embeddings)
+      .store_chunks(&self.db, &relative, chunks_with_embeddings)
       .await?;
+    self.db.delete_skipped_files(&[relative.clone()]).await.ok();
 
     debug!(
-        file = %relative.display(),
+        file = %relative,
         chunks = chunk_count,
         "File indexed successfully"
     );
@@ -497,27 +567,11 @@ impl IndexerActor {
 
   /// Delete all chunks for a file from the index
   async fn delete_file(&self, path: &Path) -> Result<(), IndexError> {
-    let relative = path
-      .strip_prefix(&self.config.root)
-      .map_err(|_| IndexError::InvalidPath(path.to_path_buf()))?;
-
-    debug!(file = %relative.display(), "Deleting chunks for file");
+    let relative = relative_path(path, &self.config.root).ok_or_else(|| IndexError::InvalidPath(path.to_path_buf()))?;
 
-    let relative_str = relative.to_string_lossy();
+    debug!(file = %relative, "Deleting chunks for file");
 
-    // Delete code chunks
-    self.db.delete_chunks_for_file(&relative_str).await?;
-
-    // Delete document chunks and metadata (no-op for code files)
-    self.db.delete_document_chunks_by_source(&relative_str).await.ok();
-    self.db.delete_document_by_source(&relative_str).await.ok();
-
-    // Delete indexed_files entry
-    self
-      .db
-      .delete_indexed_file(self.db.project_id.as_str(), &relative_str)
-      .await
-      .ok();
+    self.remove_from_index(&relative).await?;
 
     // Optimize indexes after delete to compact deleted rows
     // This ensures deleted content is immediately removed from vector search results
@@ -533,24 +587,19 @@ impl IndexerActor {
   /// This is more efficient than delete + re-index because it preserves
   /// existing embeddings and other computed data.
   async fn rename_file(&self, from: &Path, to: &Path) -> Result<(), IndexError> {
-    let from_rel = from
-      .strip_prefix(&self.config.root)
-      .map_err(|_| IndexError::InvalidPath(from.to_path_buf()))?;
-    let to_rel = to
-      .strip_prefix(&self.config.root)
-      .map_err(|_| IndexError::InvalidPath(to.to_path_buf()))?;
+    let from_rel = relative_path(from, &self.config.root).ok_or_else(|| IndexError::InvalidPath(from.to_path_buf()))?;
+    let to = &self.canonical_path(to.to_path_buf(), &mut CaseCache::default()).await;
+    let to_rel = relative_path(to, &self.config.root).ok_or_else(|| IndexError::InvalidPath(to.to_path_buf()))?;
 
     debug!(
-        from = %from_rel.display(),
-        to = %to_rel.display(),
+        from = %from_rel,
+        to = %to_rel,
         "Renaming file in index"
     );
 
-    let from_str = from_rel.to_string_lossy();
-    let to_str = to_rel.to_string_lossy();
-
     // Use the unified indexer which handles both code and document files
-    self.indexer.rename_file(&self.db, &from_str, &to_str).await?;
+    self.indexer.rename_file(&self.db, &from_rel, &to_rel).await?;
+    self.db.delete_skipped_files(&[from_rel]).await.ok();
 
     Ok(())
   }
@@ -575,7 +624,7 @@ impl IndexerActor {
     }
 
     // Always use the streaming pipeline (legacy path kept for potential debugging)
-    self.batch_index_pipeline(files, progress).await
+    self.batch_index_pipeline(files, HashMap::new(), progress).await
   }
 
   /// Batch index using the streaming pipeline
@@ -584,9 +633,12 @@ impl IndexerActor {
   /// - Bounded memory usage through backpressure
   /// - No sync points (files stream through continuously)
   /// - Efficient batching of embeddings and DB writes
+  ///
+  /// Files with previous content in `old_contents` are reparsed incrementally.
   async fn batch_index_pipeline(
     &self,
     files: Vec<PathBuf>,
+    old_contents: HashMap<PathBuf, Arc<String>>,
     progress: Option<mpsc::Sender<IndexProgress>>,
   ) -> Result<(), IndexError> {
     let total = files.len();
@@ -611,6 +663,7 @@ impl IndexerActor {
       self.indexer.clone(),
       self.config.root.clone(),
       files,
+      old_contents,
       self.db.clone(),
       self.embedding.clone(),
       config,
@@ -639,6 +692,53 @@ impl IndexerActor {
   // Helper Methods
   // ========================================================================
 
+  /// Remove a file's chunks, document metadata and tracking entry
+  async fn remove_from_index(&self, relative: &str) -> Result<(), IndexError> {
+    // Delete code chunks
+    self.db.delete_chunks_for_file(relative).await?;
+
+    // Delete document chunks and metadata (no-op for code files)
+    self.db.delete_document_chunks_by_source(relative).await.ok();
+    self.db.delete_document_by_source(relative).await.ok();
+
+    // Delete indexed_files entry
+    self
+      .db
+      .delete_indexed_file(self.db.project_id.as_str(), relative)
+      .await
+      .ok();
+
+    // Forget an earlier skip
+    self.db.delete_skipped_files(&[relative.to_string()]).await.ok();
+
+    Ok(())
+  }
+
+  /// Resolve `path` to its on-disk casing on case-insensitive filesystems.
+  ///
+  /// A path reported with a stale casing means the file was renamed by case
+  /// only, so anything indexed under the old casing is removed first.
+  /// Directory listings come from `cache`, shared across a batch of paths.
+  async fn canonical_path(&self, path: PathBuf, cache: &mut CaseCache) -> PathBuf {
+    if !self.case_insensitive {
+      return path;
+    }
+    let Some(relative) = relative_path(&path, &self.config.root) else {
+      return path;
+    };
+
+    match cache.resolve(&self.config.root, &relative).await {
+      Some(canonical) if canonical != relative => {
+        debug!(from = %relative, to = %canonical, "Resolved stale path casing");
+        if let Err(e) = self.remove_from_index(&relative).await {
+          warn!(file = %relative, error = %e, "Failed to remove stale casing from index");
+        }
+        self.config.root.join(canonical)
+      }
+      _ => path,
+    }
+  }
+
   /// Generate embeddings for unified chunks
   ///
   /// Uses batch embedding for efficiency. Works with the unified Chunk type.
@@ -17,7 +17,10 @@ use std::{path::PathBuf, sync::Arc};
 
 use tokio::sync::mpsc;
 
-use crate::ipc::{RequestData, ResponseData};
+use crate::{
+  domain::memory::MemoryActor,
+  ipc::{RequestData, ResponseData, code::CodeDecodeWarning},
+};
 
 /// Unique identifier for a request (for correlation in logs and responses)
 pub type RequestId = String;
@@ -35,6 +38,8 @@ pub struct ProjectActorMessage {
   pub reply: mpsc::Sender<ProjectActorResponse>,
   /// The actual request payload
   pub payload: ProjectActorPayload,
+  /// Who sent the request, recorded in memory history
+  pub actor: MemoryActor,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -50,6 +55,14 @@ pub enum ProjectActorPayload {
     /// Maximum session age in hours
     max_age_hours: u64,
   },
+  /// Compact fragmented tables and rebuild stale vector indexes (scheduler-triggered)
+  MaintainIndexes,
+  /// Flag memories whose referenced code changed or disappeared (scheduler-triggered)
+  ValidateMemories,
+  /// Retry queued extractions whose backoff has elapsed (scheduler-triggered)
+  RetryExtractions,
+  /// Rewrite the active context file if it is enabled and due (scheduler-triggered)
+  RefreshContextFile,
   /// Shutdown this project actor
   Shutdown,
 }
@@ -204,6 +217,8 @@ pub struct IndexProgress {
   pub current_file: Option<String>,
   /// Number of chunks created so far (populated during writing stage)
   pub chunks_created: usize,
+  /// Set when the current file was not valid UTF-8 (reading stage)
+  pub decode_warning: Option<CodeDecodeWarning>,
 }
 
 impl IndexProgress {
@@ -215,6 +230,7 @@ impl IndexProgress {
       total,
       current_file: None,
       chunks_created: 0,
+      decode_warning: None,
     }
   }
 
@@ -224,6 +240,12 @@ impl IndexProgress {
     self
   }
 
+  /// Attach a decode warning for the current file
+  pub fn with_decode_warning(mut self, warning: Option<CodeDecodeWarning>) -> Self {
+    self.decode_warning = warning;
+    self
+  }
+
   /// Set the number of chunks created
   pub fn with_chunks_created(mut self, count: usize) -> Self {
     self.chunks_created = count;
@@ -277,6 +299,15 @@ impl PipelineFile {
       old_content: None,
     }
   }
+
+  /// Create a file entry with previous content
+  pub fn file_with_old_content(path: PathBuf, relative: String, old_content: Arc<String>) -> Self {
+    Self::File {
+      path,
+      relative,
+      old_content: Some(old_content),
+    }
+  }
 }
 
 /// File content loaded by reader stage
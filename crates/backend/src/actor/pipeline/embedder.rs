@@ -81,6 +81,8 @@ impl ProcessedFile {
 struct PendingBatch {
   files: Vec<PendingFile>,
   texts_to_embed: Vec<String>,
+  /// Index in `texts_to_embed` of each chunk content hash queued
+  queued: HashMap<String, usize>,
 }
 
 struct PendingFile {
@@ -88,6 +90,8 @@ struct PendingFile {
   chunks: Vec<Chunk>,
   existing_embeddings: HashMap<String, Vec<f32>>,
   needs_embedding: Vec<usize>,
+  /// Index in the batch's texts of each chunk in `needs_embedding`
+  slots: HashMap<usize, usize>,
   /// Character count of original content (for document metadata)
   char_count: Option<usize>,
   /// Content hash of original content (for document metadata)
@@ -99,16 +103,30 @@ impl PendingBatch {
     Self {
       files: Vec::new(),
       texts_to_embed: Vec::new(),
+      queued: HashMap::new(),
     }
   }
 
-  fn add_file(&mut self, file: PendingFile, indexer: &Indexer, validation_config: &TextValidationConfig) {
+  fn add_file(&mut self, mut file: PendingFile, indexer: &Indexer, validation_config: &TextValidationConfig) {
     for &idx in &file.needs_embedding {
-      if let Some(chunk) = file.chunks.get(idx) {
-        let text = indexer.prepare_embedding_text(chunk);
-        let (validated, _) = crate::embedding::validation::validate_and_truncate(&text, validation_config);
-        self.texts_to_embed.push(validated);
+      let Some(chunk) = file.chunks.get(idx) else {
+        continue;
+      };
+      // Identical chunks, e.g. a helper copied into several files, are embedded once
+      let key = indexer.cache_key(chunk);
+      if let Some(&slot) = key.as_ref().and_then(|key| self.queued.get(key)) {
+        file.slots.insert(idx, slot);
+        continue;
       }
+
+      let text = indexer.prepare_embedding_text(chunk);
+      let (validated, _) = crate::embedding::validation::validate_and_truncate(&text, validation_config);
+      let slot = self.texts_to_embed.len();
+      self.texts_to_embed.push(validated);
+      if let Some(key) = key {
+        self.queued.insert(key, slot);
+      }
+      file.slots.insert(idx, slot);
     }
     self.files.push(file);
   }
@@ -122,15 +140,14 @@ impl PendingBatch {
   }
 
   fn finalize(self, embeddings: Vec<Vec<f32>>, fallback_dim: usize, indexer: &Indexer) -> Vec<ProcessedFile> {
-    let mut embedding_iter = embeddings.into_iter();
     let mut processed_files = Vec::with_capacity(self.files.len());
 
     for file in self.files {
       let mut chunks_with_vectors: Vec<(Chunk, Vec<f32>)> = Vec::with_capacity(file.chunks.len());
 
       for (idx, chunk) in file.chunks.into_iter().enumerate() {
-        let vector = if file.needs_embedding.contains(&idx) {
-          embedding_iter.next().unwrap_or_else(|| vec![0.0; fallback_dim])
+        let vector = if let Some(&slot) = file.slots.get(&idx) {
+          embeddings.get(slot).cloned().unwrap_or_else(|| vec![0.0; fallback_dim])
         } else if let Some(hash) = indexer.cache_key(&chunk) {
           file
             .existing_embeddings
@@ -189,6 +206,7 @@ impl EmbeddingBatchBuilder {
       chunks,
       existing_embeddings,
       needs_embedding,
+      slots: HashMap::new(),
       char_count,
       content_hash,
     };
@@ -27,6 +27,7 @@ mod scanner;
 mod writer;
 
 use std::{
+  collections::HashMap,
   path::PathBuf,
   sync::{Arc, atomic::AtomicUsize},
 };
@@ -35,6 +36,7 @@ use tokio::sync::{Mutex, mpsc};
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 
+pub(crate) use self::reader::decode_warning;
 use self::{
   embedder::embedder_stage,
   parser::{parser_done_aggregator, parser_worker},
@@ -62,11 +64,13 @@ use crate::{
 /// Creates all stages, connects them with channels, and runs until completion
 /// or cancellation. Handles both code and document files automatically.
 ///
+/// Files with previous content in `old_contents` are reparsed incrementally.
 /// The `project_id` is used to update the indexed_files table for startup scan detection.
 pub async fn run_pipeline(
   indexer: Indexer,
   root: PathBuf,
   files: Vec<PathBuf>,
+  old_contents: HashMap<PathBuf, Arc<String>>,
   db: Arc<ProjectDb>,
   embedding_provider: Arc<dyn EmbeddingProvider>,
   config: PipelineConfig,
@@ -102,7 +106,7 @@ pub async fn run_pipeline(
   let scanner_cancel = pipeline_cancel.clone();
   let scanner_root = root.clone();
   tokio::spawn(async move {
-    scanner_stage(scanner_root, files, scanner_tx, None, scanner_cancel).await;
+    scanner_stage(scanner_root, files, old_contents, scanner_tx, None, scanner_cancel).await;
   });
 
   // Spawn reader workers with shared progress counter
@@ -17,7 +17,7 @@ use tracing::{debug, trace};
 use super::DoneTracker;
 use crate::{
   actor::message::{IndexProgress, PipelineContent, PipelineStage},
-  context::files::{Chunk, FileMetadata, Indexer},
+  context::files::{EmbeddingSelection, FileMetadata, Indexer, depth::IndexDepth, overlaps_changed_lines},
   db::ProjectDb,
 };
 
@@ -93,6 +93,17 @@ pub async fn parser_worker(
           }
         };
 
+        let is_code = matches!(metadata, FileMetadata::Code { .. });
+        if let Some(skipped) = indexer.guard().check_content(&relative, &content, is_code) {
+          indexer.record_skipped(&db, skipped).await;
+          continue;
+        }
+
+        // Diff the syntax trees before chunking, which then reuses the edited tree
+        let changed_lines = old_content
+          .as_deref()
+          .and_then(|old| indexer.changed_lines(&content, &metadata, old));
+
         // Use Indexer to chunk the content
         let chunks = match indexer.chunk_file(&content, &metadata, old_content.as_deref().map(|s| s.as_str())) {
           Ok(c) => c,
@@ -101,11 +112,21 @@ pub async fn parser_worker(
             continue;
           }
         };
+        let shallow = indexer.depth(&metadata) == IndexDepth::Shallow;
+        let chunks = if shallow {
+          Indexer::shallow_chunks(chunks)
+        } else {
+          chunks
+        };
 
         if chunks.is_empty() {
           trace!(worker_id, file = %relative, "No chunks produced");
           continue;
         }
+        if let Some(skipped) = indexer.guard().check_chunks(&relative, chunks.len()) {
+          indexer.record_skipped(&db, skipped).await;
+          continue;
+        }
 
         // Query DB for existing embeddings
         let existing_embeddings = indexer
@@ -113,21 +134,56 @@ pub async fn parser_worker(
           .await
           .unwrap_or_default();
 
-        // Determine which chunks need new embeddings
-        let mut needs_embedding: Vec<usize> = Vec::new();
-        let mut reusable: HashMap<String, Vec<f32>> = HashMap::new();
+        // Determine which chunks need new embeddings. After an edit, only chunks
+        // overlapping its changed lines are re-embedded; shallow files are stored
+        // with zero vectors instead.
+        let EmbeddingSelection {
+          mut needs_embedding,
+          mut reusable,
+          mut reused,
+          ast_unchanged,
+        } = if shallow {
+          EmbeddingSelection::default()
+        } else {
+          indexer.select_for_embedding(&chunks, &existing_embeddings, changed_lines.as_deref())
+        };
 
-        for (idx, chunk) in chunks.iter().enumerate() {
-          if let Some(key) = indexer.cache_key(chunk) {
-            if let Some(vec) = existing_embeddings.get(&key) {
-              reusable.insert(key, vec.clone());
-            } else {
-              needs_embedding.push(idx);
+        // Code chunks copied from another indexed file reuse that file's embedding,
+        // unless the edit touched them
+        let edited = |idx: usize| {
+          changed_lines
+            .as_deref()
+            .is_some_and(|ranges| overlaps_changed_lines(&chunks[idx], ranges))
+        };
+        let missing: Vec<String> = needs_embedding
+          .iter()
+          .filter(|&&idx| !edited(idx))
+          .filter_map(|&idx| indexer.cache_key(&chunks[idx]))
+          .collect();
+        if is_code && !missing.is_empty() {
+          let copies = db.get_embeddings_by_content_hash(&missing).await.unwrap_or_default();
+          needs_embedding.retain(|&idx| {
+            if edited(idx) {
+              return true;
             }
-          } else {
-            needs_embedding.push(idx);
-          }
+            let Some(key) = indexer.cache_key(&chunks[idx]) else {
+              return true;
+            };
+            match copies.get(&key) {
+              Some(vec) => {
+                reusable.insert(key, vec.clone());
+                reused += 1;
+                false
+              }
+              None => true,
+            }
+          });
         }
+        indexer.stats().record_file(
+          needs_embedding.len(),
+          reused,
+          changed_lines.as_ref().map(|_| ast_unchanged),
+        );
 
         // Compute document metadata for document files
         let (char_count, content_hash) = match &metadata {
@@ -147,8 +203,10 @@ pub async fn parser_worker(
             worker_id,
             file = %relative,
             total_chunks = chunks.len(),
-            reused = reusable.len(),
+            reused,
             need_embedding = needs_embedding.len(),
+            incremental = changed_lines.is_some(),
+            shallow,
             "Parsed file"
         );
 
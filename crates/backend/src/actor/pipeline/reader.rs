@@ -7,10 +7,14 @@ use std::sync::{
 
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use super::DoneTracker;
-use crate::actor::message::{IndexProgress, PipelineContent, PipelineFile, PipelineStage};
+use crate::{
+  actor::message::{IndexProgress, PipelineContent, PipelineFile, PipelineStage},
+  context::files::{encoding::DecodedText, extract::read_text},
+  ipc::code::CodeDecodeWarning,
+};
 
 /// Reader worker - reads file content from disk.
 ///
@@ -18,6 +22,8 @@ use crate::actor::message::{IndexProgress, PipelineContent, PipelineFile, Pipeli
 /// Each worker pulls from a shared receiver and sends to the parser stage.
 ///
 /// Failed reads are logged and skipped rather than failing the pipeline.
+/// Content that is not valid UTF-8 is decoded best-effort and reported as a
+/// decode warning on the reading progress update.
 #[allow(clippy::too_many_arguments)]
 pub async fn reader_worker(
   worker_id: usize,
@@ -52,17 +58,22 @@ pub async fn reader_worker(
         relative,
         old_content,
       }) => {
-        // Read file content
-        match tokio::fs::read_to_string(&path).await {
-          Ok(content) => {
+        // Read file content (PDFs are converted to text)
+        match read_text(&path).await {
+          Ok(decoded) => {
+            let warning = decode_warning(&relative, &decoded);
+
             // Increment shared counter and send progress
             let global_processed = processed_counter.fetch_add(1, Ordering::Relaxed) + 1;
             if let Some(ref ptx) = progress_tx {
-              let progress =
-                IndexProgress::new(PipelineStage::Reading, global_processed, total_files).with_current_file(&relative);
+              let progress = IndexProgress::new(PipelineStage::Reading, global_processed, total_files)
+                .with_current_file(&relative)
+                .with_decode_warning(warning);
               let _ = ptx.send(progress).await;
             }
 
+            let content = decoded.text;
+
             let msg = match old_content {
               Some(old) => PipelineContent::file_with_old_content(relative, content, old),
               None => PipelineContent::file(relative, content),
@@ -97,6 +108,25 @@ pub async fn reader_worker(
   trace!(worker_id, local_processed, "Reader worker finished");
 }
 
+/// Log and describe content that was not read as plain UTF-8.
+pub(crate) fn decode_warning(relative: &str, decoded: &DecodedText) -> Option<CodeDecodeWarning> {
+  if !decoded.needs_warning() {
+    return None;
+  }
+
+  warn!(
+    file = %relative,
+    encoding = decoded.encoding.as_str(),
+    replaced = decoded.replaced,
+    "File is not valid UTF-8, decoded best-effort"
+  );
+  Some(CodeDecodeWarning {
+    file_path: relative.to_string(),
+    encoding: decoded.encoding.as_str().to_string(),
+    replaced: decoded.replaced,
+  })
+}
+
 /// Aggregates Done signals from reader workers and forwards to parser stage.
 pub async fn reader_done_aggregator(
   worker_count: usize,
@@ -1,17 +1,21 @@
 //! Scanner stage - enumerates files and sends them to the Reader stage.
 
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
-use crate::actor::message::{IndexProgress, PipelineFile, PipelineStage};
+use crate::{
+  actor::message::{IndexProgress, PipelineFile, PipelineStage},
+  domain::path::relative_path,
+};
 
 /// Scanner stage - enumerates files and sends them to the Reader stage.
 ///
 /// For bulk indexing, this sends all files from a pre-computed list.
 /// For watcher-triggered updates, files are injected directly into Reader.
+/// Files with an entry in `old_contents` carry it along for incremental parsing.
 ///
 /// # Cancellation
 ///
@@ -20,6 +24,7 @@ use crate::actor::message::{IndexProgress, PipelineFile, PipelineStage};
 pub async fn scanner_stage(
   root: PathBuf,
   files: Vec<PathBuf>,
+  mut old_contents: HashMap<PathBuf, Arc<String>>,
   tx: mpsc::Sender<PipelineFile>,
   progress_tx: Option<mpsc::Sender<IndexProgress>>,
   cancel: CancellationToken,
@@ -35,9 +40,9 @@ pub async fn scanner_stage(
     }
 
     // Compute relative path
-    let relative = match path.strip_prefix(&root) {
-      Ok(rel) => rel.to_string_lossy().to_string(),
-      Err(_) => {
+    let relative = match relative_path(&path, &root) {
+      Some(rel) => rel,
+      None => {
         warn!(path = %path.display(), "File not under root, skipping");
         continue;
       }
@@ -50,7 +55,10 @@ pub async fn scanner_stage(
     }
 
     // Send to reader
-    let msg = PipelineFile::file(path.clone(), relative);
+    let msg = match old_contents.remove(&path) {
+      Some(old) => PipelineFile::file_with_old_content(path, relative, old),
+      None => PipelineFile::file(path, relative),
+    };
 
     tokio::select! {
       biased;
@@ -9,7 +9,7 @@ use std::{
 use chrono::Utc;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 #[cfg(feature = "statm")]
 /// Get current process memory usage in MB from /proc/self/statm
@@ -39,9 +39,13 @@ use crate::{
   },
   context::files::{Chunk, Indexer},
   db::{IndexedFile, ProjectDb},
-  domain::document::Document,
+  disk,
+  domain::{document::Document, git::GitHead},
 };
 
+/// How often a paused writer checks whether disk space was freed
+const DISK_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Configuration for the writer stage
 #[derive(Debug, Clone)]
 pub struct WriterConfig {
@@ -184,6 +188,7 @@ pub async fn writer_stage(
               }
 
               if accumulator.should_flush_count(config.flush_count) {
+                wait_for_disk_space(&cancel).await;
                 let files = accumulator.take();
 
                 #[cfg(feature = "statm")]
@@ -231,6 +236,7 @@ pub async fn writer_stage(
           }
           Some(EmbeddedChunks::Done) | None => {
             if !accumulator.is_empty() {
+              wait_for_disk_space(&cancel).await;
               let files = accumulator.take();
               match flush_to_db(&indexer, &db, files, project_root, project_id).await {
                 Ok((f, c)) => {
@@ -278,6 +284,7 @@ pub async fn writer_stage(
 
       _ = interval.tick() => {
         if accumulator.should_flush_time(config.flush_timeout) {
+          wait_for_disk_space(&cancel).await;
           let files = accumulator.take();
           match flush_to_db(&indexer, &db, files, project_root, project_id).await {
             Ok((f, c)) => {
@@ -303,11 +310,33 @@ pub async fn writer_stage(
   }
 }
 
+/// Hold writes back while the daemon is read-only for lack of disk space.
+///
+/// The stages before the writer stall on their bounded channels, so the whole
+/// pipeline pauses until the scheduler finds enough free space. Returns early
+/// when cancelled; the flush that follows then fails and is logged.
+async fn wait_for_disk_space(cancel: &CancellationToken) {
+  if !disk::is_read_only() {
+    return;
+  }
+  warn!(
+    reason = disk::read_only_reason().unwrap_or_default(),
+    "Index writes paused until disk space is freed"
+  );
+  while disk::is_read_only() {
+    tokio::select! {
+      _ = cancel.cancelled() => return,
+      _ = tokio::time::sleep(DISK_RECHECK_INTERVAL) => {}
+    }
+  }
+  info!("Disk space freed, index writes resumed");
+}
+
 #[tracing::instrument(level = "trace", skip_all)]
 async fn flush_to_db(
   indexer: &Indexer,
   db: &ProjectDb,
-  files: Vec<ProcessedFile>,
+  mut files: Vec<ProcessedFile>,
   project_root: Option<&PathBuf>,
   project_id: Option<&str>,
 ) -> Result<(usize, usize), PipelineError> {
@@ -355,6 +384,17 @@ async fn flush_to_db(
     }
   }
 
+  // Stamp code chunks with the checkout they were indexed from
+  if let Some(root) = project_root
+    && let Some(head) = GitHead::read(root).await
+  {
+    for file in &mut files {
+      for (chunk, _) in &mut file.chunks_with_vectors {
+        chunk.set_git_head(&head);
+      }
+    }
+  }
+
   #[cfg(feature = "statm")]
   let mem_after_metadata = get_memory_usage_mb().await.map(|(r, _)| r).unwrap_or(0.0);
 
@@ -362,7 +402,9 @@ async fn flush_to_db(
   // - store_chunks_batch: code_chunks + documents tables
   // - save_indexed_files_batch: indexed_files table
   // - upsert_document_metadata_batch: document_metadata table
+  // - delete_skipped_files: skipped_files table (files indexed after an earlier skip)
 
+  let written: Vec<String> = files.iter().map(|f| f.relative.clone()).collect();
   let chunks_future = indexer.store_chunks_batch(db, files);
 
   let indexed_files_future = async {
@@ -383,8 +425,14 @@ async fn flush_to_db(
     }
   };
 
-  // Run all three in parallel
-  let (chunks_result, _, _) = tokio::join!(chunks_future, indexed_files_future, doc_metadata_future);
+  let skipped_future = async {
+    if let Err(e) = db.delete_skipped_files(&written).await {
+      warn!(error = %e, count = written.len(), "Failed to clear skipped_files entries");
+    }
+  };
+
+  // Run all four in parallel
+  let (chunks_result, _, _, _) = tokio::join!(chunks_future, indexed_files_future, doc_metadata_future, skipped_future);
 
   if let Err(e) = chunks_result {
     error!(error = %e, file_count = total_files, "Failed to batch store chunks");
@@ -22,7 +22,14 @@
 //!                            Response Channel (mpsc, supports streaming)
 //! ```
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+  path::PathBuf,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+  },
+  time::Duration,
+};
 
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
@@ -32,32 +39,45 @@ use uuid::Uuid;
 use super::{
   handle::{IndexerHandle, ProjectHandle},
   indexer::{IndexerActor, IndexerConfig},
-  message::{ProjectActorMessage, ProjectActorPayload, ProjectActorResponse},
-  watcher::{WatcherConfig, WatcherTask},
+  message::{IndexJob, ProjectActorMessage, ProjectActorPayload, ProjectActorResponse},
+  watcher::{EventStorm, WatcherConfig, WatcherTask, build_gitignore},
 };
 use crate::{
-  db::{DbError, ProjectDb},
+  context::{
+    files::{Indexer, depth::DepthRules, guard::IndexGuard},
+    memory::extract::classifier::ExtractionRules,
+    redact::Redactor,
+  },
+  db::{DbError, ProjectDb, SearchKind},
+  disk,
   domain::{
     code::Language,
-    config::{Config, DaemonSettings},
+    config::{Config, DaemonSettings, RedactionConfig},
+    memory::MemoryActor,
+    path::{self, PathFilter, relative_path},
     project::ProjectId,
   },
   embedding::EmbeddingProvider,
   ipc::{
     RequestData, ResponseData,
     code::{CodeIndexResult, CodeItem, CodeMemoriesResponse},
-    hook::{HookParams, HookResult},
+    entity::{EntityRequest, EntityResponse},
+    exploration::{ExplorationRequest, ExplorationResponse},
+    hook::{HookBatchParams, HookBatchResult, HookParams, HookResult},
     memory::{
-      MemoryDeleteParams, MemoryDeleteResult, MemoryHardDeleteParams, MemoryItem, MemoryListDeletedParams,
-      MemoryReinforceParams, MemoryRestoreParams, MemorySetSalienceParams, MemorySummary, MemoryTimelineParams,
+      ExtractionCaptureParams, ExtractionJobItem, ExtractionJobsParams, ExtractionRetryParams, ExtractionRetryResult,
+      MemoryArchiveParams, MemoryArchiveResult, MemoryDeleteParams, MemoryDeleteResult, MemoryExtractParams,
+      MemoryHardDeleteParams, MemoryItem, MemoryListDeletedParams, MemoryReinforceParams, MemoryRestoreParams,
+      MemorySetSalienceParams, MemorySummary, MemoryTimelineParams,
     },
     project::ProjectResponse,
     relationship::{RelatedMemoryItem, RelationshipInfo, RelationshipListParams, RelationshipResponse},
-    search::{ContextParams, ExploreParams},
+    search::{ContextParams, ExploreParams, ExplorePlanParams, PackParams},
     types::{
       code::{
-        CodeCalleesParams, CodeCallersParams, CodeContextFullParams, CodeContextParams, CodeIndexParams,
-        CodeListParams, CodeMemoriesParams, CodeRelatedParams, CodeRequest, CodeResponse, CodeSearchParams,
+        CodeCalleesParams, CodeCallersParams, CodeContextFullParams, CodeContextParams, CodeDefinitionParams,
+        CodeIndexChangedParams, CodeIndexParams, CodeIndexPlanParams, CodeListParams, CodeMemoriesParams,
+        CodeReferencesParams, CodeRelatedParams, CodeRequest, CodeResponse, CodeSearchParams, CodeSkippedParams,
         CodeStatsParams,
       },
       docs::{DocContextParams, DocsIngestParams, DocsRequest, DocsResponse},
@@ -67,7 +87,10 @@ use crate::{
       },
       project::ProjectRequest,
       relationship::RelationshipRequest,
-      watch::{StartupScanInfo, WatchRequest, WatchResponse, WatchStartResult, WatchStatusResult, WatchStopResult},
+      watch::{
+        StartupScanInfo, WatchPauseResult, WatchRequest, WatchResponse, WatchResumeResult, WatchStartResult,
+        WatchStatusResult, WatchStopResult, WatchStormInfo,
+      },
     },
   },
   rerank::RerankerProvider,
@@ -134,6 +157,8 @@ pub struct ProjectActor {
   /// Project-level config (tools, decay, search, index, docs, workspace, hooks)
   project_config: Arc<Config>,
   embedding: Arc<dyn EmbeddingProvider>,
+  /// Price per million embedded tokens, for index plans
+  embedding_cost_per_million: Option<f64>,
   /// Reranker provider for cross-encoder reranking (None if disabled)
   reranker: Option<Arc<dyn RerankerProvider>>,
   /// LLM provider for memory extraction (None if unavailable)
@@ -142,13 +167,30 @@ pub struct ProjectActor {
   project_uuid: Uuid,
   /// Hook state for session tracking and deduplication
   hook_state: service::hooks::HookState,
+  /// Extraction prompt/response capture (None unless hooks.debug_capture is set)
+  capture: Option<service::hooks::CaptureStore>,
+  /// Deterministic extraction rules from hooks.extraction_rules
+  extraction_rules: ExtractionRules,
+  /// Private paths and directories from hooks.private_paths / hooks.private_dirs
+  privacy_rules: service::hooks::PrivacyRules,
+  /// Secret detectors from the redaction config
+  redactor: Redactor,
   indexer: IndexerHandle,
   watcher_handle: Option<JoinHandle<()>>,
   watcher_cancel: Option<CancellationToken>,
+  /// Shared with the watcher; set while indexing is paused
+  watcher_paused: Arc<AtomicBool>,
   /// Whether a code scan/index operation is in progress
   scan_in_progress: bool,
   /// Latest scan progress [processed, total] if scan is in progress
   scan_progress: Option<(usize, usize)>,
+  /// When the active context file was last refreshed
+  context_file_refreshed: Option<std::time::Instant>,
+  /// Settled event storms reported by the watcher
+  storm_tx: mpsc::Sender<EventStorm>,
+  storm_rx: mpsc::Receiver<EventStorm>,
+  /// The last event storm coalesced into a rescan, and when
+  last_storm: Option<(EventStorm, std::time::Instant)>,
   request_rx: mpsc::Receiver<ProjectActorMessage>,
   cancel: CancellationToken,
 }
@@ -164,12 +206,14 @@ impl ProjectActor {
   /// * `config` - Project-specific actor config (id, root, data_dir)
   /// * `embedding` - Shared embedding provider
   /// * `reranker` - Optional reranker provider for cross-encoder reranking
+  /// * `llm_provider` - Shared LLM worker pool for memory extraction (None if unavailable)
   /// * `daemon_settings` - Daemon-level settings (embedding batch size, hooks, etc.)
   /// * `cancel` - Cancellation token for coordinated shutdown
   pub async fn spawn(
     config: ProjectActorConfig,
     embedding: Arc<dyn EmbeddingProvider>,
     reranker: Option<Arc<dyn RerankerProvider>>,
+    llm_provider: Option<Box<dyn llm::LlmProvider>>,
     daemon_settings: Arc<DaemonSettings>,
     cancel: CancellationToken,
   ) -> Result<ProjectHandle, ProjectActorError> {
@@ -188,6 +232,17 @@ impl ProjectActor {
       .map_err(ProjectActorError::Database)?;
     let db = Arc::new(db);
 
+    // Memories stored before entity extraction get their entities in the background
+    tokio::spawn({
+      let db = Arc::clone(&db);
+      let aliases = project_config.aliases.clone();
+      async move {
+        if let Err(e) = service::memory::entity::backfill(&db, Some(&aliases)).await {
+          warn!(err = %e, "Failed to backfill memory entities");
+        }
+      }
+    });
+
     // Spawn indexer actor with a child cancellation token
     // Use daemon-level embedding settings (from global config, not project config)
     let embedding_batch_size = daemon_settings.embedding_batch_size.unwrap_or(512);
@@ -195,6 +250,7 @@ impl ProjectActor {
     let indexer_config = IndexerConfig {
       root: config.root.clone(),
       index: project_config.index.clone(),
+      redaction: project_config.redaction.clone(),
       embedding_batch_size,
       embedding_context_length: daemon_settings.embedding_context_length,
       log_cache_stats: daemon_settings.log_cache_stats,
@@ -203,36 +259,41 @@ impl ProjectActor {
 
     // Create message channel
     let (tx, rx) = mpsc::channel(256);
+    let (storm_tx, storm_rx) = mpsc::channel(4);
 
     // Generate deterministic project UUID from project ID (for memory creation)
     let project_uuid = Uuid::new_v5(&Uuid::NAMESPACE_OID, config.id.as_str().as_bytes());
 
-    // Create LLM provider for memory extraction (if available)
-    let llm_provider = match llm::create_provider() {
-      Ok(provider) => {
-        debug!("LLM provider available: {}", provider.name());
-        Some(provider)
-      }
-      Err(e) => {
-        debug!("LLM provider not available: {}", e);
-        None
-      }
-    };
+    let capture =
+      service::hooks::CaptureStore::from_config(&config.id.data_dir(&config.data_dir), &project_config.hooks);
+    let extraction_rules = ExtractionRules::from_config(&project_config.hooks.extraction_rules);
+    let privacy_rules = service::hooks::PrivacyRules::from_config(&config.root, &project_config.hooks);
+    let redactor = Redactor::from_config(&project_config.redaction);
 
     let actor = Self {
       config,
       db,
       project_config,
       embedding,
+      embedding_cost_per_million: daemon_settings.embedding_cost_per_million,
       reranker,
       llm_provider,
       project_uuid,
       hook_state: service::hooks::HookState::new(),
+      capture,
+      extraction_rules,
+      privacy_rules,
+      redactor,
       indexer,
       watcher_handle: None,
       watcher_cancel: None,
+      watcher_paused: Arc::new(AtomicBool::new(false)),
       scan_in_progress: false,
       scan_progress: None,
+      context_file_refreshed: None,
+      storm_tx,
+      storm_rx,
+      last_storm: None,
       request_rx: rx,
       cancel,
     };
@@ -293,6 +354,10 @@ impl ProjectActor {
             }
           }
         }
+
+        Some(storm) = self.storm_rx.recv() => {
+          self.handle_event_storm(storm).await;
+        }
       }
     }
 
@@ -318,11 +383,16 @@ impl ProjectActor {
 
   /// Handle an incoming message
   async fn handle_message(&mut self, msg: ProjectActorMessage) {
-    let ProjectActorMessage { id, reply, payload } = msg;
+    let ProjectActorMessage {
+      id,
+      reply,
+      payload,
+      actor,
+    } = msg;
 
     match payload {
       ProjectActorPayload::Request(req) => {
-        self.handle_request(&id, req, reply).await;
+        self.handle_request(&id, req, actor, reply).await;
       }
       ProjectActorPayload::ApplyDecay => {
         let result = self.apply_decay().await;
@@ -347,6 +417,42 @@ impl ProjectActor {
         };
         let _ = reply.send(response).await;
       }
+      ProjectActorPayload::ValidateMemories => {
+        let response = match self.validate_memories().await {
+          Ok(message) => {
+            ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(message)))
+          }
+          Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+        };
+        let _ = reply.send(response).await;
+      }
+      ProjectActorPayload::RefreshContextFile => {
+        let response = match self.refresh_context_file().await {
+          Ok(message) => {
+            ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(message)))
+          }
+          Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+        };
+        let _ = reply.send(response).await;
+      }
+      ProjectActorPayload::RetryExtractions => {
+        let response = match self.retry_due_extractions().await {
+          Ok(message) => {
+            ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(message)))
+          }
+          Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+        };
+        let _ = reply.send(response).await;
+      }
+      ProjectActorPayload::MaintainIndexes => {
+        let response = match self.maintain_indexes().await {
+          Ok(message) => {
+            ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(message)))
+          }
+          Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+        };
+        let _ = reply.send(response).await;
+      }
       ProjectActorPayload::Shutdown => {
         let _ = reply
           .send(ProjectActorResponse::Done(ResponseData::System(
@@ -361,12 +467,22 @@ impl ProjectActor {
   }
 
   /// Route a request to the appropriate handler
-  async fn handle_request(&mut self, id: &str, request: RequestData, reply: mpsc::Sender<ProjectActorResponse>) {
+  async fn handle_request(
+    &mut self,
+    id: &str,
+    request: RequestData,
+    actor: MemoryActor,
+    reply: mpsc::Sender<ProjectActorResponse>,
+  ) {
     debug!(request_id = id, request_type = ?std::mem::discriminant(&request), "Handling request");
 
     match request {
+      // Retries extract with the hook state, which memory handlers can't borrow mutably
+      RequestData::Memory(MemoryRequest::ExtractionRetry(params)) => {
+        self.handle_extraction_retry(params, reply).await;
+      }
       RequestData::Memory(mem_req) => {
-        self.handle_memory(id, mem_req, reply).await;
+        self.handle_memory(id, mem_req, actor, reply).await;
       }
       RequestData::Code(code_req) => {
         self.handle_code(id, code_req, reply).await;
@@ -380,6 +496,9 @@ impl ProjectActor {
       RequestData::Relationship(rel_req) => {
         self.handle_relationship(id, rel_req, reply).await;
       }
+      RequestData::Entity(entity_req) => {
+        self.handle_entity(id, entity_req, reply).await;
+      }
       RequestData::Project(proj_req) => {
         self.handle_project(id, proj_req, reply).await;
       }
@@ -392,9 +511,21 @@ impl ProjectActor {
       RequestData::Context(params) => {
         self.handle_context(id, params, reply).await;
       }
+      RequestData::Pack(params) => {
+        self.handle_pack(id, params, reply).await;
+      }
+      RequestData::ExplorePlan(params) => {
+        self.handle_explore_plan(id, params, reply).await;
+      }
+      RequestData::Exploration(req) => {
+        self.handle_exploration(id, req, reply).await;
+      }
       RequestData::Hook(params) => {
         self.handle_hook(id, params, reply).await;
       }
+      RequestData::HookBatch(params) => {
+        self.handle_hook_batch(id, params, reply).await;
+      }
     }
   }
 
@@ -405,11 +536,15 @@ impl ProjectActor {
   /// Create a memory service context
   fn memory_context(&self) -> service::memory::MemoryContext<'_> {
     service::memory::MemoryContext::new(&self.db, self.embedding.as_ref(), self.project_id())
+      .with_root(&self.config.root)
+      .with_aliases(&self.project_config.aliases)
+      .with_visibility(&self.project_config.visibility)
+      .with_redactor(&self.redactor)
   }
 
   /// Create a code service context
   fn code_context(&self) -> service::code::CodeContext<'_> {
-    service::code::CodeContext::new(&self.db, self.embedding.as_ref())
+    service::code::CodeContext::new(&self.db, self.embedding.as_ref()).with_aliases(&self.project_config.aliases)
   }
 
   /// Create an explore service context
@@ -420,6 +555,61 @@ impl ProjectActor {
       Some(&self.project_config.search),
       self.reranker.as_deref(),
     )
+    .with_aliases(&self.project_config.aliases)
+  }
+
+  /// Record a search and its (id, similarity) hits for `stats search`, in
+  /// the background so the reply isn't held up
+  fn log_search(&self, kind: SearchKind, query: &str, hits: Vec<(String, Option<f32>)>) {
+    if !self.project_config.search.log_queries {
+      return;
+    }
+    let scores: Vec<f32> = hits.iter().filter_map(|(_, s)| *s).collect();
+    let avg_similarity = (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32);
+    let result_ids: Vec<String> = hits.into_iter().map(|(id, _)| id).collect();
+    let db = Arc::clone(&self.db);
+    let query = query.to_string();
+    tokio::spawn(async move {
+      if let Err(e) = db.record_search(kind, &query, &result_ids, avg_similarity).await {
+        warn!(err = %e, "Failed to log search");
+      }
+    });
+  }
+
+  /// Count a result being opened or marked helpful as a click on the search
+  /// that returned it
+  fn log_search_click(&self, result_id: &str) {
+    if !self.project_config.search.log_queries {
+      return;
+    }
+    let db = Arc::clone(&self.db);
+    let result_id = result_id.to_string();
+    tokio::spawn(async move {
+      if let Err(e) = db.record_search_click(&result_id).await {
+        warn!(err = %e, "Failed to log search click");
+      }
+    });
+  }
+
+  /// Health check entry for the data directory's disk
+  fn disk_health(disk: &disk::DiskStatus) -> crate::ipc::system::HealthCheck {
+    let status = match disk.free_bytes {
+      None => "unknown",
+      Some(_) if disk.read_only => "full",
+      Some(free) if free < disk.min_free_bytes.saturating_mul(2) => "low",
+      Some(_) => "ok",
+    };
+    crate::ipc::system::HealthCheck {
+      name: "disk".to_string(),
+      status: status.to_string(),
+      message: disk.free_bytes.map(|free| {
+        format!(
+          "{} free, {} kept free",
+          disk::format_bytes(free),
+          disk::format_bytes(disk.min_free_bytes)
+        )
+      }),
+    }
   }
 
   /// Get the project UUID
@@ -450,116 +640,166 @@ impl ProjectActor {
     }
 
     // Perform startup scan if project was previously indexed
-    let scan_info =
-      if let Some(scan_result) = service::code::startup_scan::startup_scan(&self.db, &self.config.root).await {
-        let files_queued = if scan_result.was_indexed && scan_result.has_changes() {
-          info!(
-            project_id = %self.config.id,
-            added = scan_result.added.len(),
-            modified = scan_result.modified.len(),
-            deleted = scan_result.deleted.len(),
-            moved = scan_result.moved.len(),
-            "Startup scan detected changes, queueing reindex"
-          );
+    let scan_info = self.reconcile_with_disk().await;
 
-          // Handle deleted files - remove from DB (both code and document tables)
-          for deleted_path in &scan_result.deleted {
-            // Delete code chunks
-            if let Err(e) = self.db.delete_chunks_for_file(deleted_path).await {
-              warn!(path = %deleted_path, error = %e, "Failed to delete code chunks for removed file");
-            }
-            // Delete document chunks and metadata (no-op for code files)
-            if let Err(e) = self.db.delete_document_chunks_by_source(deleted_path).await {
-              warn!(path = %deleted_path, error = %e, "Failed to delete document chunks for removed file");
-            }
-            if let Err(e) = self.db.delete_document_by_source(deleted_path).await {
-              warn!(path = %deleted_path, error = %e, "Failed to delete document metadata for removed file");
-            }
-            // Delete indexed_files entry
-            if let Err(e) = self.db.delete_indexed_file(self.config.id.as_str(), deleted_path).await {
-              warn!(path = %deleted_path, error = %e, "Failed to delete indexed_file entry");
-            }
-          }
+    let cancel = self.cancel.child_token();
+    let watcher_config = WatcherConfig {
+      root: self.config.root.clone(),
+      index: self.project_config.index.clone(),
+    };
 
-          // Optimize indexes after deletes to ensure deleted rows are compacted
-          // and no longer appear in vector search results
-          if !scan_result.deleted.is_empty()
-            && let Err(e) = self.db.optimize_indexes().await
-          {
-            warn!(error = %e, "Failed to optimize indexes after startup scan deletes");
-          }
+    self.watcher_paused.store(false, Ordering::Relaxed);
+    let task = WatcherTask::new(watcher_config, self.indexer.clone(), cancel.clone())
+      .map_err(|e| ProjectActorError::Watcher(e.to_string()))?
+      .with_storm_reports(self.storm_tx.clone())
+      .with_pause_flag(Arc::clone(&self.watcher_paused));
+    let handle = tokio::spawn(task.run());
 
-          // Handle moved files - update paths in DB
-          for (old_path, new_path) in &scan_result.moved {
-            let new_relative = new_path
-              .strip_prefix(&self.config.root)
-              .map(|p| p.to_string_lossy().to_string())
-              .unwrap_or_else(|_| new_path.to_string_lossy().to_string());
+    self.watcher_handle = Some(handle);
+    self.watcher_cancel = Some(cancel);
 
-            // Handle both code and document files - one will be a no-op depending on file type
-            if let Err(e) = self.db.rename_file(old_path, &new_relative).await {
-              warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename code chunks");
-            }
-            if let Err(e) = self.db.rename_document(old_path, &new_relative).await {
-              warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename document chunks");
-            }
-            if let Err(e) = self
-              .db
-              .rename_indexed_file(self.config.id.as_str(), old_path, &new_relative)
-              .await
-            {
-              warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename indexed_file entry");
-            }
+    info!(project_id = %self.config.id, "Started watcher for {:?}", self.config.root);
+    Ok(scan_info)
+  }
+
+  /// Bring the index in line with the filesystem
+  ///
+  /// Removes deleted files, follows moves and queues added and modified files
+  /// for reindexing. Returns `None` if the scan could not run.
+  async fn reconcile_with_disk(&self) -> Option<StartupScanInfo> {
+    let index_config = &self.project_config.index;
+    let filter = PathFilter::new(&self.config.root, &index_config.include, &index_config.exclude);
+    if let Some(scan_result) = service::code::startup_scan::startup_scan(&self.db, &self.config.root, &filter).await {
+      let files_queued = if scan_result.was_indexed && scan_result.has_changes() {
+        info!(
+          project_id = %self.config.id,
+          added = scan_result.added.len(),
+          modified = scan_result.modified.len(),
+          deleted = scan_result.deleted.len(),
+          moved = scan_result.moved.len(),
+          "Startup scan detected changes, queueing reindex"
+        );
+
+        // Handle deleted files - remove from DB (both code and document tables)
+        for deleted_path in &scan_result.deleted {
+          // Delete code chunks
+          if let Err(e) = self.db.delete_chunks_for_file(deleted_path).await {
+            warn!(path = %deleted_path, error = %e, "Failed to delete code chunks for removed file");
+          }
+          // Delete document chunks and metadata (no-op for code files)
+          if let Err(e) = self.db.delete_document_chunks_by_source(deleted_path).await {
+            warn!(path = %deleted_path, error = %e, "Failed to delete document chunks for removed file");
           }
+          if let Err(e) = self.db.delete_document_by_source(deleted_path).await {
+            warn!(path = %deleted_path, error = %e, "Failed to delete document metadata for removed file");
+          }
+          // Delete indexed_files entry
+          if let Err(e) = self.db.delete_indexed_file(self.config.id.as_str(), deleted_path).await {
+            warn!(path = %deleted_path, error = %e, "Failed to delete indexed_file entry");
+          }
+        }
 
-          // Queue added and modified files for reindexing
-          let files_to_index = scan_result.files_to_index();
-          let queued = files_to_index.len();
-          if !files_to_index.is_empty() {
-            debug!(
-              project_id = %self.config.id,
-              file_count = queued,
-              "Queueing files for reindex"
-            );
-            if let Err(e) = self.indexer.index_batch(files_to_index, None).await {
-              warn!(error = %e, "Failed to queue startup scan files for reindex");
-            }
+        // Optimize indexes after deletes to ensure deleted rows are compacted
+        // and no longer appear in vector search results
+        if !scan_result.deleted.is_empty()
+          && let Err(e) = self.db.optimize_indexes().await
+        {
+          warn!(error = %e, "Failed to optimize indexes after startup scan deletes");
+        }
+
+        // Handle moved files - update paths in DB
+        for (old_path, new_path) in &scan_result.moved {
+          let new_relative =
+            relative_path(new_path, &self.config.root).unwrap_or_else(|| path::normalize(&new_path.to_string_lossy()));
+
+          // Handle both code and document files - one will be a no-op depending on file type
+          if let Err(e) = self.db.rename_file(old_path, &new_relative).await {
+            warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename code chunks");
           }
-          queued
-        } else if !scan_result.was_indexed {
-          debug!(project_id = %self.config.id, "Project not previously indexed, skipping startup scan");
-          0
-        } else {
-          debug!(project_id = %self.config.id, "No changes detected during startup scan");
-          0
-        };
+          if let Err(e) = self.db.rename_document(old_path, &new_relative).await {
+            warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename document chunks");
+          }
+          if let Err(e) = self
+            .db
+            .rename_indexed_file(self.config.id.as_str(), old_path, &new_relative)
+            .await
+          {
+            warn!(from = %old_path, to = %new_relative, error = %e, "Failed to rename indexed_file entry");
+          }
+        }
 
-        Some(StartupScanInfo {
-          was_indexed: scan_result.was_indexed,
-          files_added: scan_result.added.len(),
-          files_modified: scan_result.modified.len(),
-          files_deleted: scan_result.deleted.len(),
-          files_moved: scan_result.moved.len(),
-          files_queued,
-        })
+        // Queue added and modified files for reindexing
+        let files_to_index = scan_result.files_to_index();
+        let queued = files_to_index.len();
+        if !files_to_index.is_empty() {
+          debug!(
+            project_id = %self.config.id,
+            file_count = queued,
+            "Queueing files for reindex"
+          );
+          if let Err(e) = self.indexer.index_batch(files_to_index, None).await {
+            warn!(error = %e, "Failed to queue startup scan files for reindex");
+          }
+        }
+        queued
+      } else if !scan_result.was_indexed {
+        debug!(project_id = %self.config.id, "Project not previously indexed, skipping startup scan");
+        0
       } else {
-        None
+        debug!(project_id = %self.config.id, "No changes detected during startup scan");
+        0
       };
 
-    let cancel = self.cancel.child_token();
-    let watcher_config = WatcherConfig {
-      root: self.config.root.clone(),
-      index: self.project_config.index.clone(),
-    };
+      Some(StartupScanInfo {
+        was_indexed: scan_result.was_indexed,
+        files_added: scan_result.added.len(),
+        files_modified: scan_result.modified.len(),
+        files_deleted: scan_result.deleted.len(),
+        files_moved: scan_result.moved.len(),
+        files_queued,
+      })
+    } else {
+      None
+    }
+  }
 
-    let handle = WatcherTask::spawn(watcher_config, self.indexer.clone(), cancel.clone())
-      .map_err(|e| ProjectActorError::Watcher(e.to_string()))?;
+  /// Rescan once an event storm has settled
+  ///
+  /// The watcher dropped the individual changes, so the whole tree is
+  /// reconciled with the index instead.
+  async fn handle_event_storm(&mut self, storm: EventStorm) {
+    let scan = self.reconcile_with_disk().await;
+    info!(
+      project_id = %self.config.id,
+      changes = storm.changes,
+      files_queued = scan.as_ref().map(|s| s.files_queued).unwrap_or(0),
+      "Coalesced file event storm into one rescan"
+    );
+    self.last_storm = Some((storm, std::time::Instant::now()));
+  }
 
-    self.watcher_handle = Some(handle);
-    self.watcher_cancel = Some(cancel);
+  /// Pause indexing of file changes, keeping the watcher running
+  fn pause_watcher(&self) -> Result<(), ProjectActorError> {
+    if self.watcher_cancel.is_none() {
+      return Err(ProjectActorError::Watcher("Watcher is not running".to_string()));
+    }
+    if !self.watcher_paused.swap(true, Ordering::Relaxed) {
+      info!(project_id = %self.config.id, "Paused watcher");
+    }
+    Ok(())
+  }
 
-    info!(project_id = %self.config.id, "Started watcher for {:?}", self.config.root);
-    Ok(scan_info)
+  /// Resume a paused watcher, reconciling changes made while it was paused
+  async fn resume_watcher(&self) -> Result<Option<StartupScanInfo>, ProjectActorError> {
+    if self.watcher_cancel.is_none() {
+      return Err(ProjectActorError::Watcher("Watcher is not running".to_string()));
+    }
+    if !self.watcher_paused.swap(false, Ordering::Relaxed) {
+      debug!(project_id = %self.config.id, "Watcher was not paused");
+      return Ok(None);
+    }
+    info!(project_id = %self.config.id, "Resumed watcher, reconciling changes made while paused");
+    Ok(self.reconcile_with_disk().await)
   }
 
   /// Stop the file watcher for this project
@@ -624,27 +864,206 @@ impl ProjectActor {
     Ok(cleaned)
   }
 
+  /// Compact fragmented tables and rebuild stale vector indexes.
+  ///
+  /// Skipped while a scan is running; indexing optimizes tables itself when
+  /// it finishes and the next scheduled run picks up anything left over.
+  async fn maintain_indexes(&self) -> Result<String, ProjectActorError> {
+    if self.scan_in_progress {
+      debug!(project_id = %self.config.id, "Skipping index maintenance (scan in progress)");
+      return Ok("Index maintenance skipped: scan in progress".to_string());
+    }
+    // Compaction rewrites whole tables, needing room for both copies
+    if disk::is_read_only() {
+      debug!(project_id = %self.config.id, "Skipping index maintenance (read-only)");
+      return Ok("Index maintenance skipped: read-only".to_string());
+    }
+
+    let report = self.db.maintain_indexes().await.map_err(ProjectActorError::Database)?;
+
+    debug!(
+      project_id = %self.config.id,
+      compacted = ?report.compacted,
+      rebuilt = ?report.rebuilt,
+      "Index maintenance complete"
+    );
+
+    Ok(format!(
+      "Index maintenance: {} tables compacted, {} vector indexes rebuilt",
+      report.compacted.len(),
+      report.rebuilt.len()
+    ))
+  }
+
+  /// Check codebase and gotcha memories against the code index.
+  ///
+  /// Skipped while a scan is running so half-indexed files aren't reported
+  /// as removed.
+  async fn validate_memories(&self) -> Result<String, ProjectActorError> {
+    if self.scan_in_progress {
+      debug!(project_id = %self.config.id, "Skipping memory validation (scan in progress)");
+      return Ok("Memory validation skipped: scan in progress".to_string());
+    }
+
+    let ctx = self.memory_context();
+    let stats = service::memory::stale::validate_stale(&ctx)
+      .await
+      .map_err(|e| ProjectActorError::Internal(e.to_string()))?;
+
+    debug!(
+      project_id = %self.config.id,
+      checked = stats.checked,
+      marked = stats.marked,
+      cleared = stats.cleared,
+      "Memory validation complete"
+    );
+
+    Ok(format!(
+      "Memory validation: {} checked, {} marked stale, {} cleared",
+      stats.checked, stats.marked, stats.cleared
+    ))
+  }
+
+  /// Retry queued extractions that are due, if an LLM is available
+  async fn retry_due_extractions(&mut self) -> Result<String, ProjectActorError> {
+    let hook_ctx = service::hooks::HookContext::new(
+      &self.db,
+      self.embedding.as_ref(),
+      self.llm_provider.as_deref().filter(|llm| llm.is_available()),
+      self.project_uuid,
+      &self.project_config.hooks,
+    )
+    .with_capture(self.capture.as_ref())
+    .with_root(&self.config.root)
+    .with_rules(&self.extraction_rules)
+    .with_redactor(&self.redactor)
+    .with_aliases(&self.project_config.aliases)
+    .with_visibility(&self.project_config.visibility);
+
+    let report = service::hooks::retry_due_extractions(&hook_ctx, &mut self.hook_state.seen_hashes)
+      .await
+      .map_err(|e| ProjectActorError::Internal(e.to_string()))?;
+
+    if report.attempted > 0 {
+      debug!(
+        project_id = %self.config.id,
+        attempted = report.attempted,
+        succeeded = report.succeeded,
+        dead = report.dead,
+        "Extraction retry complete"
+      );
+    }
+
+    Ok(format!(
+      "Extraction retry: {} attempted, {} succeeded, {} dead-lettered",
+      report.attempted, report.succeeded, report.dead
+    ))
+  }
+
+  /// Rewrite the active context file when enabled and its interval has passed
+  async fn refresh_context_file(&mut self) -> Result<String, ProjectActorError> {
+    let hooks = &self.project_config.hooks;
+    if !hooks.active_context_file {
+      return Ok("Active context file disabled".to_string());
+    }
+    let interval = std::time::Duration::from_secs(hooks.active_context_interval_mins.max(1) * 60);
+    if self.context_file_refreshed.is_some_and(|at| at.elapsed() < interval) {
+      return Ok("Active context file is up to date".to_string());
+    }
+
+    let written = service::project::context_file::refresh(
+      &self.db,
+      &self.config.root,
+      self.hook_state.open_tasks(),
+      hooks.active_context_max_tokens,
+    )
+    .await
+    .map_err(|e| ProjectActorError::Internal(e.to_string()))?;
+    self.context_file_refreshed = Some(std::time::Instant::now());
+
+    if written {
+      debug!(project_id = %self.config.id, "Active context file refreshed");
+    }
+    Ok(format!(
+      "Active context file {}",
+      if written { "written" } else { "unchanged" }
+    ))
+  }
+
+  async fn handle_extraction_retry(
+    &mut self,
+    params: ExtractionRetryParams,
+    reply: mpsc::Sender<ProjectActorResponse>,
+  ) {
+    let hook_ctx = service::hooks::HookContext::new(
+      &self.db,
+      self.embedding.as_ref(),
+      self.llm_provider.as_deref().filter(|llm| llm.is_available()),
+      self.project_uuid,
+      &self.project_config.hooks,
+    )
+    .with_capture(self.capture.as_ref())
+    .with_root(&self.config.root)
+    .with_rules(&self.extraction_rules)
+    .with_redactor(&self.redactor)
+    .with_aliases(&self.project_config.aliases)
+    .with_visibility(&self.project_config.visibility);
+
+    let response =
+      match service::hooks::retry_extractions(&hook_ctx, &params.ids, &mut self.hook_state.seen_hashes).await {
+        Ok(report) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::ExtractionRetry(
+          ExtractionRetryResult {
+            attempted: report.attempted,
+            succeeded: report.succeeded,
+            dead: report.dead,
+            memories_created: report.memories_created,
+          },
+        ))),
+        Err(e) => Self::service_error_response(e),
+      };
+
+    let _ = reply.send(response).await;
+  }
+
   // ========================================================================
   // Memory Handler
   // ========================================================================
 
-  async fn handle_memory(&self, _id: &str, req: MemoryRequest, reply: mpsc::Sender<ProjectActorResponse>) {
-    let ctx = self.memory_context();
+  async fn handle_memory(
+    &self,
+    _id: &str,
+    req: MemoryRequest,
+    actor: MemoryActor,
+    reply: mpsc::Sender<ProjectActorResponse>,
+  ) {
+    let ctx = self.memory_context().with_actor(actor);
 
     let response = match req {
       MemoryRequest::Search(params) => {
+        let query = params.query.clone();
         match service::memory::search(&ctx, params, &self.project_config, self.reranker.as_deref()).await {
-          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Search(
-            crate::ipc::types::memory::MemorySearchResult {
-              items: result.items,
-              search_quality: Some(result.search_quality),
-            },
-          ))),
+          Ok(result) => {
+            self.log_search(
+              SearchKind::Memory,
+              &query,
+              result.items.iter().map(|m| (m.id.clone(), m.similarity)).collect(),
+            );
+            ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Search(
+              crate::ipc::types::memory::MemorySearchResult {
+                items: result.items,
+                search_quality: Some(result.search_quality),
+                groups: result.groups,
+              },
+            )))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
       MemoryRequest::Get(params) => match service::memory::get(&ctx, params).await {
-        Ok(detail) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Get(detail))),
+        Ok(detail) => {
+          self.log_search_click(&detail.id);
+          ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Get(detail)))
+        }
         Err(e) => Self::service_error_response(e),
       },
       MemoryRequest::Add(params) => match service::memory::add(&ctx, params).await {
@@ -667,6 +1086,18 @@ impl ProjectActor {
           Err(e) => Self::service_error_response(e),
         }
       }
+      MemoryRequest::Feedback(params) => {
+        let helpful = params.helpful;
+        match service::memory::feedback(&ctx, params).await {
+          Ok(result) => {
+            if helpful {
+              self.log_search_click(&result.id);
+            }
+            ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Feedback(result)))
+          }
+          Err(e) => Self::service_error_response(e),
+        }
+      }
       MemoryRequest::Delete(MemoryDeleteParams { memory_id }) => {
         match service::memory::delete(&ctx, &memory_id).await {
           Ok(memory) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Delete(MemoryDeleteResult {
@@ -727,6 +1158,9 @@ impl ProjectActor {
             scope_path: None,
             scope_module: None,
             importance: None,
+            visibility: None,
+            status: None,
+            due: None,
           },
         )
         .await
@@ -748,6 +1182,122 @@ impl ProjectActor {
           Err(e) => Self::service_error_response(e),
         }
       }
+      MemoryRequest::ExtractionCapture(ExtractionCaptureParams { segment_id }) => {
+        let project_data_dir = self.config.id.data_dir(&self.config.data_dir);
+        match service::hooks::load_capture(&project_data_dir, &segment_id).await {
+          Ok(capture) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::ExtractionCapture(capture))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::ExtractionJobs(ExtractionJobsParams { dead }) => {
+        let status = dead.then_some(crate::db::ExtractionJobStatus::Dead);
+        match service::hooks::list_extraction_jobs(&self.db, status).await {
+          Ok(jobs) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::ExtractionJobs(
+            jobs.iter().map(ExtractionJobItem::from).collect(),
+          ))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::ExtractionRetry(_) => {
+        ProjectActorResponse::error(-32603, "Extraction retry is handled before memory requests".to_string())
+      }
+      MemoryRequest::Extract(MemoryExtractParams { text, source }) => {
+        let hook_ctx = service::hooks::HookContext::new(
+          &self.db,
+          self.embedding.as_ref(),
+          self.llm_provider.as_deref().filter(|llm| llm.is_available()),
+          self.project_uuid,
+          &self.project_config.hooks,
+        )
+        .with_capture(self.capture.as_ref())
+        .with_root(&self.config.root)
+        .with_rules(&self.extraction_rules)
+        .with_redactor(&self.redactor)
+        .with_aliases(&self.project_config.aliases)
+        .with_visibility(&self.project_config.visibility);
+
+        match service::hooks::extract_text(&hook_ctx, &text, source.as_deref()).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Extract(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::SyncPush(params) => {
+        let state_dir = self.config.id.data_dir(&self.config.data_dir);
+        match service::memory::sync::push(&ctx, &self.project_config.sync, &state_dir, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::SyncPush(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::SyncPull(params) => {
+        let state_dir = self.config.id.data_dir(&self.config.data_dir);
+        match service::memory::sync::pull(&ctx, &self.project_config.sync, &state_dir, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::SyncPull(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::SyncConflicts(_) => {
+        let state_dir = self.config.id.data_dir(&self.config.data_dir);
+        match service::memory::sync::conflicts(&state_dir).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::SyncConflicts(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::SyncResolve(params) => {
+        let state_dir = self.config.id.data_dir(&self.config.data_dir);
+        match service::memory::sync::resolve(&ctx, &state_dir, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::SyncResolve(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::Goals(params) => match service::memory::goals::list(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Goals(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::GoalUpdate(params) => match service::memory::goals::update(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::GoalUpdate(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::History(params) => match service::memory::history(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::History(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::Retro(params) => {
+        let llm = self.llm_provider.as_deref().filter(|llm| llm.is_available());
+        match service::memory::retro::write(&ctx, llm, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Retro(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::Archive(MemoryArchiveParams { memory_ids }) => {
+        match service::memory::archive(&ctx, &memory_ids).await {
+          Ok(archived) => {
+            ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Archive(MemoryArchiveResult {
+              archived: archived.iter().map(|m| m.id.to_string()).collect(),
+            })))
+          }
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      MemoryRequest::Undo(params) => match service::memory::undo(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Undo(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::Tags(params) => match service::memory::tags::list(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::Tags(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::TagRename(params) => match service::memory::tags::rename(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::TagUpdate(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::TagMerge(params) => match service::memory::tags::merge(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::TagUpdate(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      MemoryRequest::TagDelete(params) => match service::memory::tags::delete(&ctx, params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Memory(MemoryResponse::TagUpdate(result))),
+        Err(e) => Self::service_error_response(e),
+      },
     };
 
     let _ = reply.send(response).await;
@@ -771,6 +1321,10 @@ impl ProjectActor {
         visibility,
         chunk_type,
         min_caller_count,
+        current_branch,
+        diff_branch,
+        infer_language,
+        include_generated,
       }) => {
         // Language can come from either explicit param or file_pattern (e.g., "*.rs")
         let resolved_language = language.or_else(|| {
@@ -789,6 +1343,14 @@ impl ProjectActor {
           chunk_type,
           min_caller_count,
           adaptive_limit: false,
+          git: (current_branch.unwrap_or(false) || diff_branch.is_some()).then(|| service::code::GitScope {
+            root: self.config.root.clone(),
+            current_branch: current_branch.unwrap_or(false),
+            diff_branch,
+          }),
+          infer_language: infer_language.unwrap_or(true),
+          include_generated: include_generated.unwrap_or(false),
+          max_per_file: self.project_config.search.max_per_file,
         };
         let config = service::code::RankingConfig::default();
 
@@ -801,34 +1363,82 @@ impl ProjectActor {
         )
         .await
         {
-          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Search(
-            crate::ipc::types::code::CodeSearchResult {
-              query: result.query,
-              chunks: result.results,
-              search_quality: Some(result.search_quality),
-            },
-          ))),
+          Ok(result) => {
+            self.log_search(
+              SearchKind::Code,
+              &result.query,
+              result
+                .results
+                .iter()
+                .map(|c| (c.id.clone(), c.confidence.or(c.similarity)))
+                .collect(),
+            );
+            ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Search(
+              crate::ipc::types::code::CodeSearchResult {
+                query: result.query,
+                chunks: result.results,
+                search_quality: Some(result.search_quality),
+                inferred_language: result.inferred_language,
+              },
+            )))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
-      CodeRequest::Callers(CodeCallersParams { chunk_id, limit }) => {
+      CodeRequest::Callers(CodeCallersParams {
+        chunk_id,
+        symbol,
+        limit,
+        depth,
+      }) => {
         let params = service::code::CallersParams {
-          chunk_id: Some(chunk_id),
-          symbol: None,
+          chunk_id,
+          symbol,
           limit,
+          depth,
         };
         match service::code::get_callers_response(&self.db, params).await {
           Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Callers(result))),
           Err(e) => Self::service_error_response(e),
         }
       }
-      CodeRequest::Callees(CodeCalleesParams { chunk_id, limit }) => {
-        let params = service::code::CalleesParams { chunk_id, limit };
+      CodeRequest::Callees(CodeCalleesParams { chunk_id, limit, depth }) => {
+        let params = service::code::CalleesParams { chunk_id, limit, depth };
         match service::code::get_callees_response(&self.db, params).await {
           Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Callees(result))),
           Err(e) => Self::service_error_response(e),
         }
       }
+      CodeRequest::Definition(CodeDefinitionParams {
+        symbol,
+        file_path,
+        limit,
+      }) => {
+        let params = service::code::DefinitionParams {
+          symbol,
+          file_path: file_path.as_deref().map(path::normalize),
+          limit,
+        };
+        match service::code::get_definition_response(&self.db, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Definition(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      CodeRequest::References(CodeReferencesParams {
+        symbol,
+        file_path,
+        limit,
+      }) => {
+        let params = service::code::ReferencesParams {
+          symbol,
+          file_path: file_path.as_deref().map(path::normalize),
+          limit,
+        };
+        match service::code::get_references_response(&self.db, params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::References(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
       CodeRequest::Related(CodeRelatedParams { chunk_id, limit }) => {
         let params = service::code::RelatedParams {
           chunk_id,
@@ -843,12 +1453,23 @@ impl ProjectActor {
       CodeRequest::ContextFull(CodeContextFullParams { chunk_id, depth }) => {
         let params = service::code::ContextFullParams { chunk_id, depth };
         match service::code::get_full_context(&ctx, params).await {
-          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::ContextFull(result))),
+          Ok(result) => {
+            self.log_search_click(&result.chunk.id);
+            ProjectActorResponse::Done(ResponseData::Code(CodeResponse::ContextFull(result)))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
       CodeRequest::Stats(CodeStatsParams {}) => match service::code::get_stats(&self.db).await {
-        Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Stats(result))),
+        Ok(mut result) => {
+          result.reindex = Some(self.indexer.reindex_stats());
+          ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Stats(result)))
+        }
+        Err(e) => Self::service_error_response(e),
+      },
+      CodeRequest::Plan(CodeIndexPlanParams { since }) => self.handle_code_plan(since).await,
+      CodeRequest::Skipped(CodeSkippedParams) => match service::code::index::list_skipped(&self.db).await {
+        Ok(items) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Skipped(items))),
         Err(e) => Self::service_error_response(e),
       },
       CodeRequest::List(CodeListParams { limit }) => match self.db.list_code_chunks(None, limit).await {
@@ -870,7 +1491,10 @@ impl ProjectActor {
           after,
         };
         match service::code::context::get_file_context(&self.db, &self.config.root, params).await {
-          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Context(result))),
+          Ok(result) => {
+            self.log_search_click(&result.chunk_id);
+            ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Context(result)))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
@@ -878,9 +1502,15 @@ impl ProjectActor {
         // Get memories related to a code chunk
         self.handle_code_memories(&chunk_id, limit).await
       }
-      CodeRequest::Index(CodeIndexParams { force, stream }) => {
+      CodeRequest::Index(CodeIndexParams { force, stream, since }) => {
         // Indexing goes through the IndexerActor
-        self.handle_code_index(force, stream, reply.clone()).await
+        self.handle_code_index(force, stream, since, reply.clone()).await
+      }
+      CodeRequest::IndexChanged(CodeIndexChangedParams { since }) => {
+        match service::code::index::index_changed(&self.indexer, &self.config.root, &since).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Code(CodeResponse::IndexChanged(result))),
+          Err(e) => Self::service_error_response(e),
+        }
       }
     };
 
@@ -915,8 +1545,13 @@ impl ProjectActor {
     &mut self,
     _force: bool,
     stream: bool,
+    since: Option<String>,
     reply: mpsc::Sender<ProjectActorResponse>,
   ) -> ProjectActorResponse {
+    let indexed = ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(
+      "indexed".to_string(),
+    )));
+
     // Mark scan as in progress
     self.scan_in_progress = true;
     self.scan_progress = None;
@@ -928,19 +1563,45 @@ impl ProjectActor {
         .await;
     }
 
-    // Scan for files
-    let scan_params = service::code::index::ScanParams {
-      max_file_size: self.project_config.index.max_file_size as u64,
+    // Scan for files, or ask git which files changed
+    let scan_params = self.scan_params();
+    let scan_result = match since {
+      Some(since) => {
+        let changed = match service::code::index::scan_changed(&self.config.root, &since, &scan_params).await {
+          Ok(changed) => changed,
+          Err(e) => {
+            self.scan_in_progress = false;
+            let _ = reply.send(Self::service_error_response(e)).await;
+            return indexed;
+          }
+        };
+        if let Err(e) = service::code::index::remove_deleted(&self.indexer, changed.deleted).await {
+          warn!(project_id = %self.config.id, error = %e, "Failed to remove deleted files from index");
+        }
+        changed.scan
+      }
+      None => service::code::index::scan_directory(&self.config.root, &scan_params),
     };
-    let scan_result = service::code::index::scan_directory(&self.config.root, &scan_params);
     let total_files = scan_result.files.len();
+    if let Err(e) = self.db.save_skipped_files(&scan_result.skipped).await {
+      warn!(project_id = %self.config.id, error = %e, "Failed to record skipped files");
+    }
 
     debug!(
       files_scanned = total_files,
+      files_skipped = scan_result.skipped.len(),
       scan_ms = scan_result.duration.as_millis() as u64,
       "File scan complete"
     );
 
+    // Refuse runs the disk can't hold rather than filling it part way
+    if let Err(e) = disk::ensure_space(self.db.path(), scan_result.estimated_index_bytes(self.db.vector_dim)) {
+      warn!(project_id = %self.config.id, error = %e, "Not starting index run");
+      self.scan_in_progress = false;
+      let _ = reply.send(ProjectActorResponse::error(-32000, e.to_string())).await;
+      return indexed;
+    }
+
     // Update scan progress with total
     self.scan_progress = Some((0, total_files));
 
@@ -1001,12 +1662,45 @@ impl ProjectActor {
       files_per_second: result.files_per_second,
       bytes_processed: result.bytes_processed,
       total_bytes: result.total_bytes,
+      decode_warnings: result.decode_warnings,
     })));
 
     let _ = reply.send(response).await;
-    ProjectActorResponse::Done(ResponseData::System(crate::ipc::system::SystemResponse::Ping(
-      "indexed".to_string(),
-    )))
+    indexed
+  }
+
+  /// Guard rails and globs from the project's `[index]` config
+  fn scan_params(&self) -> service::code::index::ScanParams {
+    let index_config = &self.project_config.index;
+    service::code::index::ScanParams {
+      guard: IndexGuard::from_config(index_config),
+      filter: PathFilter::new(&self.config.root, &index_config.include, &index_config.exclude),
+    }
+  }
+
+  /// Estimate an index run: scan and chunk without embedding or writing
+  async fn handle_code_plan(&self, since: Option<String>) -> ProjectActorResponse {
+    let scan_params = self.scan_params();
+    let scan_result = match since {
+      Some(since) => match service::code::index::scan_changed(&self.config.root, &since, &scan_params).await {
+        Ok(changed) => changed.scan,
+        Err(e) => return Self::service_error_response(e),
+      },
+      None => service::code::index::scan_directory(&self.config.root, &scan_params),
+    };
+
+    let indexer = Indexer::new(self.project_uuid)
+      .with_guard(scan_params.guard)
+      .with_depth(DepthRules::new(&self.config.root, &self.project_config.index));
+    let plan = service::code::plan::plan_indexing(
+      &self.db,
+      &self.config.root,
+      scan_result,
+      indexer,
+      self.embedding_cost_per_million,
+    )
+    .await;
+    ProjectActorResponse::Done(ResponseData::Code(CodeResponse::Plan(plan)))
   }
 
   // ========================================================================
@@ -1028,6 +1722,8 @@ impl ProjectActor {
       expand_top: params.expand_top.unwrap_or(3),
       limit: params.limit.unwrap_or(10),
       depth: params.depth.unwrap_or(5),
+      include_generated: params.include_generated.unwrap_or(false),
+      max_per_file: self.project_config.search.max_per_file,
     };
 
     let response = match service::explore::search(&ctx, &search_params).await {
@@ -1085,6 +1781,7 @@ impl ProjectActor {
               file_path: r.file,
               line: r.lines.map(|(start, _)| start),
               symbols: r.symbols,
+              more_in_file: r.more_in_file,
               hints: Some(crate::ipc::search::ExploreHints {
                 caller_count: r.hints.callers.unwrap_or(0),
                 callee_count: r.hints.callees.unwrap_or(0),
@@ -1095,6 +1792,21 @@ impl ProjectActor {
           })
           .collect();
 
+        self.log_search(
+          SearchKind::Explore,
+          &params.query,
+          items.iter().map(|i| (i.id.clone(), Some(i.similarity))).collect(),
+        );
+
+        if params.record == Some(true) {
+          let data_dir = self.config.id.data_dir(&self.config.data_dir);
+          if let Err(e) =
+            service::explore::notebook::record_query(&data_dir, &params.query, params.scope.as_deref(), &items).await
+          {
+            warn!("Failed to record query in exploration: {}", e);
+          }
+        }
+
         ProjectActorResponse::Done(ResponseData::Explore(crate::ipc::search::ExploreResult {
           query: params.query,
           results: items,
@@ -1106,6 +1818,38 @@ impl ProjectActor {
     let _ = reply.send(response).await;
   }
 
+  async fn handle_exploration(&self, _id: &str, req: ExplorationRequest, reply: mpsc::Sender<ProjectActorResponse>) {
+    use service::explore::notebook;
+
+    let data_dir = self.config.id.data_dir(&self.config.data_dir);
+    let result = match req {
+      ExplorationRequest::Pin(params) => notebook::pin(&self.db, &data_dir, &params)
+        .await
+        .map(ExplorationResponse::Pin),
+      ExplorationRequest::Note(params) => notebook::note(&data_dir, &params.text)
+        .await
+        .map(ExplorationResponse::Note),
+      ExplorationRequest::Get(params) => notebook::get(&data_dir, params.name.as_deref())
+        .await
+        .map(ExplorationResponse::Get),
+      ExplorationRequest::Save(params) => notebook::save(&data_dir, &params).await.map(ExplorationResponse::Save),
+      ExplorationRequest::List(_) => notebook::list(&data_dir).await.map(ExplorationResponse::List),
+      ExplorationRequest::Export(params) => notebook::export(&data_dir, params.name.as_deref())
+        .await
+        .map(ExplorationResponse::Export),
+      ExplorationRequest::Delete(params) => notebook::delete(&data_dir, &params.name)
+        .await
+        .map(ExplorationResponse::Delete),
+      ExplorationRequest::Clear(_) => notebook::clear(&data_dir).await.map(ExplorationResponse::Clear),
+    };
+
+    let response = match result {
+      Ok(response) => ProjectActorResponse::Done(ResponseData::Exploration(response)),
+      Err(e) => Self::service_error_response(e),
+    };
+    let _ = reply.send(response).await;
+  }
+
   async fn handle_context(&self, _id: &str, params: ContextParams, reply: mpsc::Sender<ProjectActorResponse>) {
     let ctx = self.explore_context();
 
@@ -1155,6 +1899,7 @@ impl ProjectActor {
                     signature: caller.signature,
                     docstring: None,
                     parent_definition: None,
+                    generated: None,
                     similarity: None,
                     confidence: None,
                     file_hash: None,
@@ -1163,6 +1908,9 @@ impl ProjectActor {
                     calls: vec![],
                     caller_count: None,
                     callee_count: None,
+                    depth: None,
+                    also_in: vec![],
+                    more_in_file: None,
                   })
                   .collect(),
               ),
@@ -1184,6 +1932,7 @@ impl ProjectActor {
                     signature: callee.signature,
                     docstring: None,
                     parent_definition: None,
+                    generated: None,
                     similarity: None,
                     confidence: None,
                     file_hash: None,
@@ -1192,6 +1941,9 @@ impl ProjectActor {
                     calls: vec![],
                     caller_count: None,
                     callee_count: None,
+                    depth: None,
+                    also_in: vec![],
+                    more_in_file: None,
                   })
                   .collect(),
               ),
@@ -1250,6 +2002,9 @@ impl ProjectActor {
           }
         };
 
+        for item in &items {
+          self.log_search_click(&item.id);
+        }
         ProjectActorResponse::Done(ResponseData::Context(items))
       }
       Err(e) => Self::service_error_response(e),
@@ -1258,6 +2013,49 @@ impl ProjectActor {
     let _ = reply.send(response).await;
   }
 
+  async fn handle_pack(&self, _id: &str, params: PackParams, reply: mpsc::Sender<ProjectActorResponse>) {
+    let ctx = self.explore_context();
+
+    let pack_params = service::explore::PackParams {
+      query: params.query,
+      scope: params
+        .scope
+        .as_deref()
+        .and_then(ExploreScope::from_str)
+        .unwrap_or_default(),
+      budget: params.budget.unwrap_or(service::explore::DEFAULT_PACK_BUDGET),
+    };
+
+    let response = match service::explore::pack(&ctx, &pack_params).await {
+      Ok(pack) => ProjectActorResponse::Done(ResponseData::Pack(pack)),
+      Err(e) => Self::service_error_response(e),
+    };
+
+    let _ = reply.send(response).await;
+  }
+
+  async fn handle_explore_plan(&self, _id: &str, params: ExplorePlanParams, reply: mpsc::Sender<ProjectActorResponse>) {
+    let ctx = self.explore_context();
+
+    let plan_params = service::explore::PlanParams {
+      task: params.task,
+      scope: params
+        .scope
+        .as_deref()
+        .and_then(ExploreScope::from_str)
+        .unwrap_or_default(),
+      budget: params.budget.unwrap_or(service::explore::DEFAULT_PACK_BUDGET),
+      hops: params.hops.unwrap_or(service::explore::DEFAULT_PLAN_HOPS),
+    };
+
+    let response = match service::explore::plan(&ctx, &plan_params).await {
+      Ok(pack) => ProjectActorResponse::Done(ResponseData::Pack(pack)),
+      Err(e) => Self::service_error_response(e),
+    };
+
+    let _ = reply.send(response).await;
+  }
+
   // ========================================================================
   // Watch Handler
   // ========================================================================
@@ -1270,6 +2068,7 @@ impl ProjectActor {
           path: self.config.root.to_string_lossy().to_string(),
           project_id: self.config.id.to_string(),
           startup_scan: scan_info,
+          roots: Vec::new(),
         }))),
         Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
       },
@@ -1279,8 +2078,28 @@ impl ProjectActor {
           status: "stopped".to_string(),
           path: self.config.root.to_string_lossy().to_string(),
           project_id: self.config.id.to_string(),
+          roots: Vec::new(),
         })))
       }
+      WatchRequest::Pause(_) => match self.pause_watcher() {
+        Ok(()) => ProjectActorResponse::Done(ResponseData::Watch(WatchResponse::Pause(WatchPauseResult {
+          status: "paused".to_string(),
+          path: self.config.root.to_string_lossy().to_string(),
+          project_id: self.config.id.to_string(),
+          roots: Vec::new(),
+        }))),
+        Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+      },
+      WatchRequest::Resume(_) => match self.resume_watcher().await {
+        Ok(scan_info) => ProjectActorResponse::Done(ResponseData::Watch(WatchResponse::Resume(WatchResumeResult {
+          status: "resumed".to_string(),
+          path: self.config.root.to_string_lossy().to_string(),
+          project_id: self.config.id.to_string(),
+          reconcile_scan: scan_info,
+          roots: Vec::new(),
+        }))),
+        Err(e) => ProjectActorResponse::error(-32000, e.to_string()),
+      },
       WatchRequest::Status(_) => {
         let running = self.watcher_cancel.is_some();
         ProjectActorResponse::Done(ResponseData::Watch(WatchResponse::Status(WatchStatusResult {
@@ -1288,8 +2107,15 @@ impl ProjectActor {
           root: Some(self.config.root.to_string_lossy().to_string()),
           pending_changes: self.indexer.pending_count(),
           project_id: self.config.id.to_string(),
+          paused: running && self.watcher_paused.load(Ordering::Relaxed),
           scanning: self.scan_in_progress,
           scan_progress: self.scan_progress.map(|(current, total)| [current, total]),
+          last_storm: self.last_storm.map(|(storm, at)| WatchStormInfo {
+            changes: storm.changes,
+            duration_ms: storm.duration.as_millis() as u64,
+            secs_ago: at.elapsed().as_secs(),
+          }),
+          roots: Vec::new(),
         })))
       }
     };
@@ -1306,6 +2132,7 @@ impl ProjectActor {
     let response = match req {
       DocsRequest::Search(params) => {
         let ctx = service::docs::DocsContext::new(&self.db, self.embedding.as_ref());
+        let query = params.query.clone();
         let search_params = service::docs::SearchParams::from(params);
         match service::docs::search(
           &ctx,
@@ -1315,22 +2142,28 @@ impl ProjectActor {
         )
         .await
         {
-          Ok(items) => ProjectActorResponse::Done(ResponseData::Docs(DocsResponse::Search(items))),
+          Ok(items) => {
+            self.log_search(
+              SearchKind::Docs,
+              &query,
+              items.iter().map(|d| (d.id.clone(), d.similarity)).collect(),
+            );
+            ProjectActorResponse::Done(ResponseData::Docs(DocsResponse::Search(items)))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
       DocsRequest::Context(DocContextParams { doc_id, before, after }) => {
         let params = service::docs::ContextParams { doc_id, before, after };
         match service::docs::get_context(&self.db, params).await {
-          Ok(result) => ProjectActorResponse::Done(ResponseData::Docs(DocsResponse::GetContext(result))),
+          Ok(result) => {
+            self.log_search_click(&result.chunk_id);
+            ProjectActorResponse::Done(ResponseData::Docs(DocsResponse::GetContext(result)))
+          }
           Err(e) => Self::service_error_response(e),
         }
       }
-      DocsRequest::Ingest(DocsIngestParams {
-        directory,
-        file,
-        stream,
-      }) => self.handle_docs_ingest(directory, file, stream, reply.clone()).await,
+      DocsRequest::Ingest(params) => self.handle_docs_ingest(params, reply.clone()).await,
     };
 
     // For Ingest with streaming, response is already sent
@@ -1342,15 +2175,22 @@ impl ProjectActor {
   /// Handle document ingest request with optional streaming
   async fn handle_docs_ingest(
     &self,
-    directory: Option<String>,
-    file: Option<String>,
-    stream: bool,
+    request: DocsIngestParams,
     reply: mpsc::Sender<ProjectActorResponse>,
   ) -> ProjectActorResponse {
-    let ctx = service::docs::IngestContext::new(self.db.clone(), self.embedding.clone());
+    let ctx =
+      service::docs::IngestContext::new(self.db.clone(), self.embedding.clone()).with_redactor(self.redactor.clone());
+    let stream = request.stream;
+    let initial_message = if request.url.is_some() {
+      "Fetching pages..."
+    } else {
+      "Scanning for documents..."
+    };
     let params = service::docs::IngestParams {
-      directory,
-      file,
+      directory: request.directory,
+      file: request.file,
+      url: request.url,
+      depth: request.depth.unwrap_or(0),
       project_id: self.project_uuid,
       root: self.config.root.clone(),
     };
@@ -1358,7 +2198,7 @@ impl ProjectActor {
     // Send initial progress if streaming
     if stream {
       let _ = reply
-        .send(ProjectActorResponse::progress("Scanning for documents...", Some(0)))
+        .send(ProjectActorResponse::progress(initial_message, Some(0)))
         .await;
     }
 
@@ -1479,6 +2319,38 @@ impl ProjectActor {
     let _ = reply.send(response).await;
   }
 
+  // ========================================================================
+  // Entity Handler
+  // ========================================================================
+
+  async fn handle_entity(&self, _id: &str, req: EntityRequest, reply: mpsc::Sender<ProjectActorResponse>) {
+    let response = match req {
+      EntityRequest::List(params) => match service::memory::entity::list(&self.db, params).await {
+        Ok(items) => ProjectActorResponse::Done(ResponseData::Entity(EntityResponse::List(items))),
+        Err(e) => Self::service_error_response(e),
+      },
+      EntityRequest::Get(params) => {
+        match service::memory::entity::get(&self.db, params, Some(&self.project_config.aliases)).await {
+          Ok(detail) => ProjectActorResponse::Done(ResponseData::Entity(EntityResponse::Get(detail))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      EntityRequest::Top(params) => match service::memory::entity::top(&self.db, params).await {
+        Ok(items) => ProjectActorResponse::Done(ResponseData::Entity(EntityResponse::Top(items))),
+        Err(e) => Self::service_error_response(e),
+      },
+      EntityRequest::Show(params) => {
+        let ctx = self.code_context();
+        match service::memory::entity::show(&ctx, Some(&self.project_config.search), params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Entity(EntityResponse::Show(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+    };
+
+    let _ = reply.send(response).await;
+  }
+
   // ========================================================================
   // Project Handler
   // ========================================================================
@@ -1489,6 +2361,17 @@ impl ProjectActor {
         match service::project::info(&self.db, &self.config.id, &self.config.root).await {
           Ok(mut result) => {
             result.db_path = self.config.data_dir.to_string_lossy().to_string();
+            if result.memory_count == 0 {
+              let moved =
+                service::project::registry::find_moved(&self.config.data_dir, &self.config.id, &self.config.root).await;
+              if !moved.is_empty() {
+                result.moved_from = Some(moved.into_iter().map(Into::into).collect());
+              }
+            }
+            let duplicates = service::project::registry::duplicates_of(&self.config.data_dir, &self.config.id).await;
+            if !duplicates.is_empty() {
+              result.duplicates = Some(duplicates.into_iter().map(Into::into).collect());
+            }
             ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::Info(result)))
           }
           Err(e) => Self::service_error_response(e),
@@ -1506,30 +2389,75 @@ impl ProjectActor {
         // CleanAll is handled at the router level
         ProjectActorResponse::internal_error("Project clean-all should be handled by router")
       }
-      ProjectRequest::Sessions(params) => {
-        // Build filter based on params
-        let filter = if params.active_only.unwrap_or(false) {
-          Some("ended_at IS NULL")
-        } else {
-          None
-        };
-
-        match self.db.list_sessions(filter, params.limit).await {
-          Ok(sessions) => {
-            use crate::ipc::project::SessionItem;
-            let items: Vec<SessionItem> = sessions
-              .into_iter()
-              .map(|s| SessionItem {
-                id: s.id,
-                started_at: s.started_at.to_rfc3339(),
-                ended_at: s.ended_at.map(|e| e.to_rfc3339()),
-                summary: s.summary,
-                user_prompt: s.user_prompt,
-              })
-              .collect();
-            ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::Sessions(items)))
-          }
-          Err(e) => Self::service_error_response(ServiceError::from(e)),
+      ProjectRequest::Migrate(_)
+      | ProjectRequest::Duplicates(_)
+      | ProjectRequest::Merge(_)
+      | ProjectRequest::Export(_)
+      | ProjectRequest::Import(_) => {
+        // These need the project's store closed or span several, so the daemon handles them
+        ProjectActorResponse::internal_error(
+          "Project migrate, merge, export and import should be handled by the daemon",
+        )
+      }
+      ProjectRequest::Sessions(params) => match service::project::sessions::list(&self.db, &params).await {
+        Ok(items) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::Sessions(items))),
+        Err(e) => Self::service_error_response(e),
+      },
+      ProjectRequest::SessionShow(params) => match service::project::sessions::show(&self.db, &params).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::SessionShow(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      ProjectRequest::SessionInjections(params) => {
+        match service::project::sessions::injections(&self.db, &params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::SessionInjections(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      ProjectRequest::SearchStats(params) => {
+        match service::project::search_stats::search_stats(&self.db, &params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::SearchStats(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      ProjectRequest::Repair(_) => match service::project::repair::repair(&self.db).await {
+        Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::Repair(result))),
+        Err(e) => Self::service_error_response(e),
+      },
+      ProjectRequest::AuditSecrets(_) => {
+        // Audit with every detector even when redaction is switched off
+        let redactor = Redactor::from_config(&RedactionConfig {
+          enabled: true,
+          ..self.project_config.redaction.clone()
+        });
+        match service::project::audit::audit_secrets(&self.db, &redactor).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::AuditSecrets(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      ProjectRequest::ContextPack(params) => {
+        match service::project::claude_md::pack(&self.db, &self.config.root, &params).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::ContextPack(result))),
+          Err(e) => Self::service_error_response(e),
+        }
+      }
+      ProjectRequest::SessionImport(params) => {
+        let hook_ctx = service::hooks::HookContext::new(
+          &self.db,
+          self.embedding.as_ref(),
+          self.llm_provider.as_deref(),
+          self.project_uuid,
+          &self.project_config.hooks,
+        )
+        .with_capture(self.capture.as_ref())
+        .with_root(&self.config.root)
+        .with_rules(&self.extraction_rules)
+        .with_redactor(&self.redactor)
+        .with_aliases(&self.project_config.aliases)
+        .with_visibility(&self.project_config.visibility);
+
+        match service::hooks::import_transcript(&hook_ctx, std::path::Path::new(&params.path)).await {
+          Ok(result) => ProjectActorResponse::Done(ResponseData::Project(ProjectResponse::SessionImport(result))),
+          Err(e) => Self::service_error_response(e),
         }
       }
     };
@@ -1549,16 +2477,39 @@ impl ProjectActor {
       SystemRequest::Ping(_) => {
         ProjectActorResponse::Done(ResponseData::System(SystemResponse::Ping("pong".to_string())))
       }
-      SystemRequest::HealthCheck(_) => ProjectActorResponse::Done(ResponseData::System(SystemResponse::HealthCheck(
-        crate::ipc::system::HealthCheckResult {
-          healthy: true,
-          checks: vec![crate::ipc::system::HealthCheck {
-            name: "database".to_string(),
-            status: "ok".to_string(),
-            message: None,
-          }],
-        },
-      ))),
+      SystemRequest::HealthCheck(_) => {
+        let disk = disk::status(self.db.path());
+        let quarantined: Vec<String> = self
+          .db
+          .quarantined_tables()
+          .await
+          .into_iter()
+          .map(|q| q.table)
+          .collect();
+        ProjectActorResponse::Done(ResponseData::System(SystemResponse::HealthCheck(
+          crate::ipc::system::HealthCheckResult {
+            healthy: !disk.read_only && quarantined.is_empty(),
+            checks: vec![
+              crate::ipc::system::HealthCheck {
+                name: "database".to_string(),
+                status: if disk.read_only { "read_only" } else { "ok" }.to_string(),
+                message: disk.reason.clone(),
+              },
+              crate::ipc::system::HealthCheck {
+                name: "tables".to_string(),
+                status: if quarantined.is_empty() { "ok" } else { "quarantined" }.to_string(),
+                message: (!quarantined.is_empty()).then(|| {
+                  format!(
+                    "Unreadable and set aside: {}. Run `ccengram db repair`.",
+                    quarantined.join(", ")
+                  )
+                }),
+              },
+              Self::disk_health(&disk),
+            ],
+          },
+        )))
+      }
       SystemRequest::ProjectStats(_) => {
         match service::project::stats(&self.db, &self.config.id, &self.project_uuid, &self.config.root).await {
           Ok(result) => ProjectActorResponse::Done(ResponseData::System(SystemResponse::ProjectStats(result))),
@@ -1578,33 +2529,99 @@ impl ProjectActor {
         }
       }
       // These are handled at the daemon level, not here
-      SystemRequest::Metrics(_) | SystemRequest::Shutdown(_) | SystemRequest::Status(_) => {
-        ProjectActorResponse::method_not_found(&format!("{:?}", request))
-      }
+      SystemRequest::Metrics(_)
+      | SystemRequest::Shutdown(_)
+      | SystemRequest::Status(_)
+      | SystemRequest::Usage(_)
+      | SystemRequest::Egress(_) => ProjectActorResponse::method_not_found(&format!("{:?}", request)),
     };
 
     let _ = reply.send(response).await;
   }
 
+  /// Queue a file Claude just edited for reindexing, so code search sees the
+  /// edit within seconds while the watcher is off
+  async fn reindex_edited_file(&self, hook_data: &serde_json::Value) {
+    if !self.project_config.hooks.reindex_edited_files || self.watcher_cancel.is_some() {
+      return;
+    }
+    let Some(edit) = service::hooks::edited_file(hook_data) else {
+      return;
+    };
+
+    let path = self.config.root.join(&edit.path);
+    let Some(relative) = relative_path(&path, &self.config.root) else {
+      debug!(path = %edit.path, "Edited file is outside the project, not reindexing");
+      return;
+    };
+    if let Ok(Some(gitignore)) = build_gitignore(&self.config.root)
+      && gitignore
+        .matched_path_or_any_parents(std::path::Path::new(&relative), false)
+        .is_ignore()
+    {
+      return;
+    }
+
+    debug!(file = %relative, incremental = edit.old_content.is_some(), "Reindexing edited file");
+    let job = IndexJob::File {
+      path,
+      old_content: edit.old_content,
+    };
+    if let Err(e) = self.indexer.send(job).await {
+      warn!(error = %e, "Failed to queue edited file for reindexing");
+    }
+  }
+
   async fn handle_hook(&mut self, _id: &str, params: HookParams, reply: mpsc::Sender<ProjectActorResponse>) {
-    // Parse hook event from hook_name
-    let event = match params.hook_name.parse::<service::hooks::HookEvent>() {
-      Ok(e) => e,
-      Err(e) => {
-        let response = Self::service_error_response(e);
-        let _ = reply.send(response).await;
-        return;
-      }
+    let response = match self.run_hook(params).await {
+      Ok(data) => ProjectActorResponse::Done(ResponseData::Hook(HookResult { data })),
+      Err(e) => Self::service_error_response(e),
     };
 
-    // Build hook context (use project-level hooks config, merged with global defaults)
+    let _ = reply.send(response).await;
+  }
+
+  /// Handle batched hook events in order; a failed event doesn't stop the rest
+  async fn handle_hook_batch(&mut self, _id: &str, params: HookBatchParams, reply: mpsc::Sender<ProjectActorResponse>) {
+    let mut processed = 0;
+    let mut failed = 0;
+    for event in params.events {
+      let hook_name = event.hook_name.clone();
+      match self.run_hook(event).await {
+        Ok(_) => processed += 1,
+        Err(e) => {
+          warn!(hook = %hook_name, "Batched hook event failed: {}", e);
+          failed += 1;
+        }
+      }
+    }
+    debug!(processed, failed, "Handled hook batch");
+
+    let response = ProjectActorResponse::Done(ResponseData::HookBatch(HookBatchResult { processed, failed }));
+    let _ = reply.send(response).await;
+  }
+
+  async fn run_hook(&mut self, params: HookParams) -> Result<serde_json::Value, ServiceError> {
+    // Parse hook event from hook_name
+    let event = params.hook_name.parse::<service::hooks::HookEvent>()?;
+
+    // Build hook context (use project-level hooks config, merged with global defaults).
+    // An LLM over its monthly budget reports unavailable, pausing extraction.
     let hook_ctx = service::hooks::HookContext::new(
       &self.db,
       self.embedding.as_ref(),
-      self.llm_provider.as_deref(),
+      self.llm_provider.as_deref().filter(|llm| llm.is_available()),
       self.project_uuid,
       &self.project_config.hooks,
-    );
+    )
+    .with_capture(self.capture.as_ref())
+    .with_root(&self.config.root)
+    .with_rules(&self.extraction_rules)
+    .with_redactor(&self.redactor)
+    .with_aliases(&self.project_config.aliases)
+    .with_visibility(&self.project_config.visibility)
+    .with_privacy(&self.privacy_rules)
+    .with_injection(&self.project_config.injection);
 
     // For SessionStart, provide project info
     let session_info = if event == service::hooks::HookEvent::SessionStart {
@@ -1623,22 +2640,24 @@ impl ProjectActor {
       None
     };
 
-    // Merge session_id into data for handlers (session_id comes from HookParams, not data)
+    // Merge session_id and cwd into data for handlers (they come from HookParams, not data)
     let mut hook_data = params.data.clone();
-    if let Some(session_id) = &params.session_id
-      && let Some(obj) = hook_data.as_object_mut()
-    {
-      obj.insert("session_id".to_string(), serde_json::Value::String(session_id.clone()));
+    if let Some(obj) = hook_data.as_object_mut() {
+      if let Some(session_id) = &params.session_id {
+        obj.insert("session_id".to_string(), serde_json::Value::String(session_id.clone()));
+      }
+      if let Some(cwd) = &params.cwd {
+        obj.insert("cwd".to_string(), serde_json::Value::String(cwd.clone()));
+      }
     }
 
     // Dispatch to hook service
     let result = service::hooks::dispatch(&hook_ctx, &mut self.hook_state, event, &hook_data, session_info).await;
 
-    let response = match result {
-      Ok(data) => ProjectActorResponse::Done(ResponseData::Hook(HookResult { data })),
-      Err(e) => Self::service_error_response(e),
-    };
+    if event == service::hooks::HookEvent::PostToolUse && result.is_ok() {
+      self.reindex_edited_file(&hook_data).await;
+    }
 
-    let _ = reply.send(response).await;
+    result
   }
 }
@@ -34,11 +34,17 @@ use super::{
   project::{ProjectActor, ProjectActorConfig, ProjectActorError},
 };
 use crate::{
-  domain::{config::DaemonSettings, project::ProjectId},
-  embedding::EmbeddingProvider,
+  db::EmbeddingCacheDb,
+  domain::{config::DaemonSettings, memory::MemoryActor, project::ProjectId},
+  embedding::{EmbeddingProvider, PersistentCacheProvider},
   rerank::RerankerProvider,
+  service::project::registry,
+  usage::{MeteredEmbedding, MeteredLlm, ProjectTag, UsageTracker},
 };
 
+/// Longest `stop_project` waits for an actor to finish shutting down
+const STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -84,6 +90,12 @@ pub struct ProjectRouter {
   /// subsequent lookups for the same path instant.
   path_cache: DashMap<PathBuf, ProjectId>,
 
+  /// Further workspace roots watched together with a project
+  ///
+  /// Keyed by the project the watch session was started from, so stopping
+  /// or checking its watcher covers every root.
+  watch_groups: DashMap<ProjectId, Vec<PathBuf>>,
+
   /// Base data directory for project databases
   ///
   /// Each project gets its own subdirectory: `{data_dir}/projects/{project_id}/`
@@ -100,6 +112,21 @@ pub struct ProjectRouter {
   /// Shared across all projects, same as embedding provider.
   reranker: Option<Arc<dyn RerankerProvider>>,
 
+  /// Optional LLM worker pool for memory extraction
+  ///
+  /// Clones share the pool's concurrency limit, so LLM work is bounded
+  /// daemon-wide rather than per project.
+  llm: Option<Box<dyn llm::LlmProvider>>,
+
+  /// Persistent document embedding cache, shared by all projects
+  ///
+  /// Wraps each project's provider above usage metering so cache hits are
+  /// not counted as provider calls.
+  embedding_cache: Option<Arc<EmbeddingCacheDb>>,
+
+  /// Usage tracker that per-project providers report to
+  usage: Option<Arc<UsageTracker>>,
+
   /// Daemon-level settings (embedding batch size, hooks config, etc.)
   ///
   /// These settings are read from the global config at daemon startup and
@@ -121,26 +148,50 @@ impl ProjectRouter {
   ///
   /// * `data_dir` - Base directory for project databases
   /// * `embedding` - Shared embedding provider
+  /// * `reranker` - Optional shared reranker provider
+  /// * `llm` - Optional shared LLM worker pool
   /// * `daemon_settings` - Daemon-level settings from global config
   /// * `cancel` - Parent cancellation token for coordinated shutdown
   pub fn new(
     data_dir: PathBuf,
     embedding: Arc<dyn EmbeddingProvider>,
     reranker: Option<Arc<dyn RerankerProvider>>,
+    llm: Option<Box<dyn llm::LlmProvider>>,
     daemon_settings: DaemonSettings,
     cancel: CancellationToken,
   ) -> Self {
     Self {
       projects: DashMap::new(),
       path_cache: DashMap::new(),
+      watch_groups: DashMap::new(),
       data_dir,
       embedding,
       reranker,
+      llm,
+      embedding_cache: None,
+      usage: None,
       daemon_settings: Arc::new(daemon_settings),
       cancel,
     }
   }
 
+  /// Share a persistent embedding cache between all projects
+  pub fn with_embedding_cache(mut self, cache: Arc<EmbeddingCacheDb>) -> Self {
+    self.embedding_cache = Some(cache);
+    self
+  }
+
+  /// Record LLM and embedding usage of every project
+  pub fn with_usage(mut self, usage: Arc<UsageTracker>) -> Self {
+    self.usage = Some(usage);
+    self
+  }
+
+  /// The usage tracker, if usage tracking is enabled
+  pub fn usage(&self) -> Option<&Arc<UsageTracker>> {
+    self.usage.as_ref()
+  }
+
   /// Get or create a ProjectActor for the given path
   ///
   /// This method is idempotent - calling it multiple times with the same
@@ -166,7 +217,12 @@ impl ProjectRouter {
       cached_id.value().clone()
     } else {
       // Compute project ID (this resolves git root if available)
-      let id = ProjectId::from_path(path).await;
+      let mut id = ProjectId::from_path(path).await;
+      // A clone whose store was merged into another project is served by that project
+      if let Some(target) = registry::merged_target(&self.data_dir, &id).await {
+        debug!(project_id = %id, merged_into = %target.id, "Routing merged project");
+        id = target.id;
+      }
       self.path_cache.insert(canonical, id.clone());
       id
     };
@@ -197,7 +253,14 @@ impl ProjectRouter {
   /// into the DashMap.
   async fn spawn_project(&self, id: ProjectId, path: &Path) -> Result<ProjectHandle, ProjectRouterError> {
     // Resolve the actual project root (git root or the path itself)
-    let root = crate::domain::project::resolve_project_path(path).await;
+    let mut root = crate::domain::project::resolve_project_path(path).await;
+
+    // Routed here from a merged clone: run the actor on the kept project's root
+    if ProjectId::from_path_exact(&root) != id
+      && let Some(recorded) = registry::read(&id.data_dir(&self.data_dir)).await
+    {
+      root = recorded.path;
+    }
 
     // Check again after resolving - another task might have inserted
     if let Some(handle) = self.projects.get(&id) {
@@ -205,6 +268,9 @@ impl ProjectRouter {
       return Ok(handle.value().clone());
     }
 
+    // No data directory yet means the project is new, or was moved from elsewhere
+    let is_new = !id.data_dir(&self.data_dir).exists();
+
     // Create config for the actor
     let config = ProjectActorConfig {
       id: id.clone(),
@@ -212,11 +278,17 @@ impl ProjectRouter {
       data_dir: self.data_dir.clone(),
     };
 
+    let (embedding, llm) = self.project_providers(ProjectTag {
+      id: id.to_string(),
+      path: root.to_string_lossy().to_string(),
+    });
+
     // Spawn the actor with a child cancellation token
     let handle = ProjectActor::spawn(
       config,
-      self.embedding.clone(),
+      embedding,
       self.reranker.clone(),
+      llm,
       Arc::clone(&self.daemon_settings),
       self.cancel.child_token(),
     )
@@ -225,6 +297,28 @@ impl ProjectRouter {
 
     info!(project_id = %id, root = %root.display(), "Spawned new ProjectActor");
 
+    if let Err(e) = registry::record(&self.data_dir, &id, &root).await {
+      warn!(project_id = %id, "Failed to record project: {}", e);
+    }
+    if is_new {
+      for moved in registry::find_moved(&self.data_dir, &id, &root).await {
+        warn!(
+          project_id = %id,
+          old_path = %moved.path.display(),
+          "Project may have moved; run `ccengram projects migrate {} {}` to keep its data",
+          moved.id,
+          root.display()
+        );
+      }
+      for clone in registry::duplicates_of(&self.data_dir, &id).await {
+        warn!(
+          project_id = %id,
+          clone_path = %clone.path.display(),
+          "Project is another clone of the same repository; run `ccengram projects duplicates` to merge their memories"
+        );
+      }
+    }
+
     // Insert into the map
     // Using entry API to handle race condition - if another task inserted
     // while we were spawning, use their handle instead
@@ -246,6 +340,25 @@ impl ProjectRouter {
     Ok(final_handle)
   }
 
+  /// Embedding and LLM providers for one project
+  ///
+  /// Usage metering is attributed to `project`; the persistent cache sits on
+  /// top so only real provider calls are metered.
+  fn project_providers(&self, project: ProjectTag) -> (Arc<dyn EmbeddingProvider>, Option<Box<dyn llm::LlmProvider>>) {
+    let mut embedding = self.embedding.clone();
+    let mut llm = self.llm.clone();
+
+    if let Some(usage) = &self.usage {
+      embedding = Arc::new(MeteredEmbedding::new(embedding, Arc::clone(usage), project.clone()));
+      llm = llm.map(|inner| Box::new(MeteredLlm::new(inner, Arc::clone(usage), project)) as Box<dyn llm::LlmProvider>);
+    }
+    if let Some(cache) = &self.embedding_cache {
+      embedding = Arc::new(PersistentCacheProvider::with_cache(embedding, Arc::clone(cache)));
+    }
+
+    (embedding, llm)
+  }
+
   /// List all active project IDs
   ///
   /// Returns a snapshot of active projects. The actual set may change
@@ -254,6 +367,35 @@ impl ProjectRouter {
     self.projects.iter().map(|entry| entry.key().clone()).collect()
   }
 
+  /// Further roots watched together with project `id`
+  pub fn watch_roots(&self, id: &ProjectId) -> Vec<PathBuf> {
+    self.watch_groups.get(id).map(|g| g.value().clone()).unwrap_or_default()
+  }
+
+  /// Record the further roots watched together with project `id`
+  pub fn set_watch_roots(&self, id: &ProjectId, roots: Vec<PathBuf>) {
+    if roots.is_empty() {
+      self.watch_groups.remove(id);
+    } else {
+      self.watch_groups.insert(id.clone(), roots);
+    }
+  }
+
+  /// Forget the further roots watched together with project `id`, returning them
+  pub fn take_watch_roots(&self, id: &ProjectId) -> Vec<PathBuf> {
+    self.watch_groups.remove(id).map(|(_, roots)| roots).unwrap_or_default()
+  }
+
+  /// Base data directory holding every project's data
+  pub fn data_dir(&self) -> &Path {
+    &self.data_dir
+  }
+
+  /// Daemon-level settings shared by every project actor
+  pub fn daemon_settings(&self) -> &DaemonSettings {
+    &self.daemon_settings
+  }
+
   /// Get embedding provider info for metrics.
   pub fn embedding_info(&self) -> (String, String, usize) {
     (
@@ -283,6 +425,7 @@ impl ProjectRouter {
         id: format!("shutdown-{}", id),
         reply: reply_tx,
         payload: ProjectActorPayload::Shutdown,
+        actor: MemoryActor::System,
       };
 
       // Best-effort send - actor might already be dead
@@ -292,6 +435,22 @@ impl ProjectRouter {
     }
   }
 
+  /// Shutdown a project and wait for its actor to stop
+  ///
+  /// Also forgets cached paths resolving to the project, so the next request
+  /// for one of them resolves its ProjectId again. Used before moving a
+  /// project's data directory.
+  pub async fn stop_project(&self, id: &ProjectId) {
+    let handle = self.get(id);
+    self.shutdown_project(id).await;
+    if let Some(handle) = handle
+      && tokio::time::timeout(STOP_TIMEOUT, handle.tx.closed()).await.is_err()
+    {
+      warn!(project_id = %id, "ProjectActor did not stop in time");
+    }
+    self.path_cache.retain(|_, cached| cached != id);
+  }
+
   /// Shutdown all active projects
   ///
   /// Iterates through all projects and sends shutdown messages. This is
@@ -342,7 +501,14 @@ mod tests {
       .expect("embedding provider required");
     let daemon_settings = DaemonSettings::from_config(&config);
     let cancel = CancellationToken::new();
-    let router = ProjectRouter::new(PathBuf::from("/tmp/data"), embedding, None, daemon_settings, cancel);
+    let router = ProjectRouter::new(
+      PathBuf::from("/tmp/data"),
+      embedding,
+      None,
+      None,
+      daemon_settings,
+      cancel,
+    );
 
     // Should not panic when shutting down nonexistent project
     let fake_id = ProjectId::from_path_exact(Path::new("/fake/project"));
@@ -357,7 +523,14 @@ mod tests {
       .expect("embedding provider required");
     let daemon_settings = DaemonSettings::from_config(&config);
     let cancel = CancellationToken::new();
-    let router = ProjectRouter::new(PathBuf::from("/tmp/data"), embedding, None, daemon_settings, cancel);
+    let router = ProjectRouter::new(
+      PathBuf::from("/tmp/data"),
+      embedding,
+      None,
+      None,
+      daemon_settings,
+      cancel,
+    );
 
     // Should not panic when no projects exist
     router.shutdown_all().await;
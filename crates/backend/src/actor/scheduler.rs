@@ -7,7 +7,10 @@ use super::{
   lifecycle::{activity::KeepAlive, session::SessionTracker},
   router::ProjectRouter,
 };
-use crate::domain::config::{DaemonConfig, DecayConfig};
+use crate::{
+  disk,
+  domain::config::{DaemonConfig, DecayConfig, IndexConfig},
+};
 
 /// Configuration for idle shutdown behavior (background mode only).
 ///
@@ -33,6 +36,8 @@ pub struct SchedulerConfig {
   pub decay: DecayConfig,
   /// Daemon lifecycle settings (log retention, idle check interval, etc.)
   pub daemon: DaemonConfig,
+  /// Index settings (background optimization interval)
+  pub index: IndexConfig,
   /// Optional idle shutdown configuration (background mode only)
   pub idle_shutdown: Option<IdleShutdownConfig>,
 }
@@ -43,6 +48,11 @@ pub struct SchedulerConfig {
 /// - Memory decay (periodic salience reduction)
 /// - Stale session cleanup
 /// - Log file rotation
+/// - Table compaction and vector index rebuilds
+/// - Stale memory validation against the code index
+/// - Retries of failed extractions
+/// - Active context file refresh (per-project cadence)
+/// - Free disk space checks (read-only mode when low)
 /// - Idle shutdown check (background mode only)
 ///
 /// This version uses `ProjectRouter` instead of `ProjectRegistry` and
@@ -68,17 +78,40 @@ impl Scheduler {
     let cleanup_interval = Duration::from_secs(self.config.decay.session_cleanup_hours * 3600);
     let log_cleanup_interval = Duration::from_secs(24 * 3600); // Once per day
     let idle_check_interval = Duration::from_secs(self.config.daemon.idle_check_interval_secs);
+    // interval() panics on zero; the tick handler checks whether optimization is enabled
+    let optimize_interval = Duration::from_secs(self.config.index.optimize_interval_mins.max(1) * 60);
+    let stale_interval = Duration::from_secs(self.config.decay.stale_check_interval_hours.max(1) * 3600);
+    let usage_flush_interval = Duration::from_secs(60);
+    let extraction_retry_interval = Duration::from_secs(60);
+    // Projects refresh on their own interval; this only checks whether one is due
+    let context_file_interval = Duration::from_secs(5 * 60);
+    let disk_check_interval = Duration::from_secs(60);
 
     let mut decay_timer = interval(decay_interval);
     let mut cleanup_timer = interval(cleanup_interval);
     let mut log_cleanup_timer = interval(log_cleanup_interval);
     let mut idle_timer = interval(idle_check_interval);
+    let mut optimize_timer = interval(optimize_interval);
+    let mut stale_timer = interval(stale_interval);
+    let mut usage_flush_timer = interval(usage_flush_interval);
+    let mut extraction_retry_timer = interval(extraction_retry_interval);
+    let mut context_file_timer = interval(context_file_interval);
+    let mut disk_check_timer = interval(disk_check_interval);
 
     // Skip the immediate ticks
     decay_timer.tick().await;
     cleanup_timer.tick().await;
     log_cleanup_timer.tick().await;
     idle_timer.tick().await;
+    optimize_timer.tick().await;
+    stale_timer.tick().await;
+    usage_flush_timer.tick().await;
+    extraction_retry_timer.tick().await;
+    context_file_timer.tick().await;
+    disk_check_timer.tick().await;
+
+    disk::set_min_free(self.config.daemon.min_free_disk_mb * 1024 * 1024);
+    self.check_disk_space();
 
     // Run log cleanup once at startup if retention is enabled
     if self.config.daemon.log_retention_days > 0 {
@@ -118,6 +151,38 @@ impl Scheduler {
           }
         }
 
+        _ = optimize_timer.tick() => {
+          if self.config.index.optimize_interval_mins > 0 {
+            debug!("Running scheduled index maintenance");
+            self.maintain_indexes().await;
+          }
+        }
+
+        _ = stale_timer.tick() => {
+          if self.config.decay.stale_check_interval_hours > 0 {
+            debug!("Running scheduled memory validation");
+            self.validate_memories().await;
+          }
+        }
+
+        _ = usage_flush_timer.tick() => {
+          if let Some(usage) = self.router.usage() {
+            usage.flush().await;
+          }
+        }
+
+        _ = extraction_retry_timer.tick() => {
+          self.retry_extractions().await;
+        }
+
+        _ = context_file_timer.tick() => {
+          self.refresh_context_files().await;
+        }
+
+        _ = disk_check_timer.tick() => {
+          self.check_disk_space();
+        }
+
         _ = idle_timer.tick() => {
             if self.check_idle_shutdown(&cancel).await {
                 break;
@@ -188,6 +253,100 @@ impl Scheduler {
     }
   }
 
+  /// Compact tables and rebuild stale vector indexes in all projects.
+  async fn maintain_indexes(&self) {
+    let project_ids = self.router.list();
+    if project_ids.is_empty() {
+      return;
+    }
+
+    tracing::debug!("Maintaining indexes in {} projects", project_ids.len());
+
+    for id in &project_ids {
+      if let Some(handle) = self.router.get(id) {
+        match handle
+          .request(
+            format!("maintain-{}", id),
+            super::message::ProjectActorPayload::MaintainIndexes,
+          )
+          .await
+        {
+          Ok(_) => tracing::trace!(project_id = %id, "Index maintenance complete"),
+          Err(e) => tracing::warn!(project_id = %id, error = %e, "Failed to maintain indexes"),
+        }
+      }
+    }
+  }
+
+  /// Flag stale codebase memories in all projects.
+  async fn validate_memories(&self) {
+    let project_ids = self.router.list();
+    if project_ids.is_empty() {
+      return;
+    }
+
+    tracing::debug!("Validating memories in {} projects", project_ids.len());
+
+    for id in &project_ids {
+      if let Some(handle) = self.router.get(id) {
+        match handle
+          .request(
+            format!("validate-{}", id),
+            super::message::ProjectActorPayload::ValidateMemories,
+          )
+          .await
+        {
+          Ok(_) => tracing::trace!(project_id = %id, "Memory validation complete"),
+          Err(e) => tracing::warn!(project_id = %id, error = %e, "Failed to validate memories"),
+        }
+      }
+    }
+  }
+
+  /// Retry due extractions in all projects.
+  async fn retry_extractions(&self) {
+    for id in &self.router.list() {
+      if let Some(handle) = self.router.get(id) {
+        match handle
+          .request(
+            format!("extraction-retry-{}", id),
+            super::message::ProjectActorPayload::RetryExtractions,
+          )
+          .await
+        {
+          Ok(_) => tracing::trace!(project_id = %id, "Extraction retry complete"),
+          Err(e) => tracing::warn!(project_id = %id, error = %e, "Failed to retry extractions"),
+        }
+      }
+    }
+  }
+
+  /// Refresh the active context file in projects where it is due.
+  async fn refresh_context_files(&self) {
+    for id in &self.router.list() {
+      if let Some(handle) = self.router.get(id) {
+        match handle
+          .request(
+            format!("context-file-{}", id),
+            super::message::ProjectActorPayload::RefreshContextFile,
+          )
+          .await
+        {
+          Ok(_) => tracing::trace!(project_id = %id, "Context file check complete"),
+          Err(e) => tracing::warn!(project_id = %id, error = %e, "Failed to refresh context file"),
+        }
+      }
+    }
+  }
+
+  /// Enter or leave read-only mode depending on free space in the data directory.
+  fn check_disk_space(&self) {
+    let status = disk::refresh(self.router.data_dir());
+    if let Some(free) = status.free_bytes {
+      tracing::trace!(free, read_only = status.read_only, "Disk space checked");
+    }
+  }
+
   /// Cleanup old log files based on retention policy.
   fn cleanup_old_logs(&self) -> usize {
     use std::time::SystemTime;
@@ -219,7 +378,8 @@ impl Scheduler {
         None => continue,
       };
 
-      if !file_name.starts_with("ccengram.log") {
+      // ccengram.log and the split hook and scheduler logs, with their rotations
+      if !(file_name.starts_with("ccengram") && file_name.contains(".log")) {
         continue;
       }
 
@@ -22,6 +22,27 @@
 //! Uses the `ignore` crate's `Gitignore` struct for efficient filtering.
 //! Files matching .gitignore patterns are silently skipped.
 //!
+//! # Event Storms
+//!
+//! Bulk operations like `git checkout` or `npm install` change thousands of
+//! files at once. When more than `watcher_storm_threshold` changes arrive
+//! within one storm window, the watcher stops tracking individual files and
+//! drops what is pending. Once a full window passes below the threshold, it
+//! reports the storm on its storm channel and the project actor reconciles
+//! the index with the filesystem in a single rescan.
+//!
+//! # Pausing
+//!
+//! While the owner's pause flag is set, events are dropped but the notify
+//! watcher, gitignore matcher and content cache stay alive. The owner
+//! reconciles the index with the filesystem when it resumes.
+//!
+//! # Case-Insensitive Filesystems
+//!
+//! On macOS and Windows a rename that only changes case is reported as the old
+//! casing disappearing. Such deletes are sent as renames to the on-disk casing
+//! so the file keeps a single entry in the index.
+//!
 //! # Lifecycle
 //!
 //! The watcher runs until:
@@ -31,7 +52,10 @@
 use std::{
   collections::HashMap,
   path::{Path, PathBuf},
-  sync::Arc,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+  },
   time::{Duration, Instant},
 };
 
@@ -44,7 +68,11 @@ use tracing::{debug, info, trace, warn};
 use super::{handle::IndexerHandle, message::IndexJob};
 use crate::{
   context::files::is_document_extension,
-  domain::{code::Language, config::IndexConfig},
+  domain::{
+    code::Language,
+    config::IndexConfig,
+    path::{PathFilter, canonical_case, is_case_insensitive, relative_path},
+  },
 };
 
 // ============================================================================
@@ -82,6 +110,20 @@ impl WatcherConfig {
   pub fn max_cached_file_size(&self) -> usize {
     self.index.max_cached_file_size
   }
+
+  /// Get the storm detection window from IndexConfig
+  pub fn storm_window(&self) -> Duration {
+    Duration::from_millis(self.index.watcher_storm_window_ms)
+  }
+}
+
+/// A burst of file events that was coalesced into one rescan
+#[derive(Debug, Clone, Copy)]
+pub struct EventStorm {
+  /// Changes seen while the storm lasted
+  pub changes: usize,
+  /// How long the storm lasted
+  pub duration: Duration,
 }
 
 // ============================================================================
@@ -161,6 +203,75 @@ impl PendingChange {
   }
 }
 
+/// Detects bursts of changes too large to index file by file
+struct StormDetector {
+  /// Changes per window that start a storm (0 disables detection)
+  threshold: usize,
+  window: Duration,
+  window_start: Instant,
+  /// Changes seen in the current window
+  count: usize,
+  /// Start and changes seen so far of the storm in progress
+  storm: Option<(Instant, usize)>,
+}
+
+impl StormDetector {
+  fn new(threshold: usize, window: Duration) -> Self {
+    Self {
+      threshold,
+      window,
+      window_start: Instant::now(),
+      count: 0,
+      storm: None,
+    }
+  }
+
+  fn in_storm(&self) -> bool {
+    self.storm.is_some()
+  }
+
+  /// Record `changes` new changes, returning whether a storm is in progress
+  fn record(&mut self, now: Instant, changes: usize) -> bool {
+    if self.threshold == 0 || changes == 0 {
+      return self.in_storm();
+    }
+    if now.duration_since(self.window_start) >= self.window {
+      self.window_start = now;
+      self.count = 0;
+    }
+    self.count += changes;
+
+    match self.storm {
+      Some((_, ref mut total)) => *total += changes,
+      None if self.count >= self.threshold => self.storm = Some((self.window_start, self.count)),
+      None => {}
+    }
+    self.in_storm()
+  }
+
+  /// End the storm once a full window has passed below the threshold
+  fn settle(&mut self, now: Instant) -> Option<EventStorm> {
+    let (started, changes) = self.storm?;
+    if now.duration_since(self.window_start) < self.window {
+      return None;
+    }
+    if self.count >= self.threshold {
+      // Still storming: judge the next window on its own
+      self.window_start = now;
+      self.count = 0;
+      return None;
+    }
+
+    self.storm = None;
+    self.window_start = now;
+    self.count = 0;
+    Some(EventStorm {
+      changes,
+      duration: now.duration_since(started),
+    })
+  }
+}
+
 /// LRU-style content cache for incremental parsing
 struct ContentCache {
   /// file_path -> (content, last_access)
@@ -253,8 +364,18 @@ pub struct WatcherTask {
   event_rx: mpsc::Receiver<Result<Event, notify::Error>>,
   // Gitignore matcher
   gitignore: Option<Gitignore>,
+  // Include and exclude globs from config
+  filter: PathFilter,
   // Content cache for incremental parsing
   content_cache: ContentCache,
+  // Whether the watched filesystem ignores case (probed on start)
+  case_insensitive: bool,
+  // Event storm detection
+  storm: StormDetector,
+  // Where settled storms are reported for a rescan
+  storm_tx: Option<mpsc::Sender<EventStorm>>,
+  // Set by the owner while indexing is paused
+  paused: Arc<AtomicBool>,
 }
 
 impl WatcherTask {
@@ -267,6 +388,7 @@ impl WatcherTask {
 
     // Build gitignore matcher
     let gitignore = build_gitignore(&config.root)?;
+    let filter = PathFilter::new(&config.root, &config.index.include, &config.index.exclude);
 
     // Create a channel for notify events
     // The sync callback will use blocking_send, so we need a reasonable buffer
@@ -292,6 +414,7 @@ impl WatcherTask {
 
     // Create content cache using config values
     let content_cache = ContentCache::new(config.content_cache_size(), config.max_cached_file_size());
+    let storm = StormDetector::new(config.index.watcher_storm_threshold, config.storm_window());
 
     info!(root = %config.root.display(), "File watcher initialized");
 
@@ -302,21 +425,25 @@ impl WatcherTask {
       _watcher: watcher,
       event_rx,
       gitignore,
+      filter,
       content_cache,
+      case_insensitive: false,
+      storm,
+      storm_tx: None,
+      paused: Arc::new(AtomicBool::new(false)),
     })
   }
 
-  /// Spawn the watcher task and return a handle to cancel it
-  ///
-  /// This is a convenience method that spawns the task and returns
-  /// a `CancellationToken` that can be used to stop it.
-  pub fn spawn(
-    config: WatcherConfig,
-    indexer: IndexerHandle,
-    cancel: CancellationToken,
-  ) -> Result<tokio::task::JoinHandle<()>, WatcherError> {
-    let task = Self::new(config, indexer, cancel)?;
-    Ok(tokio::spawn(task.run()))
+  /// Report settled event storms on `tx` so the owner can rescan
+  pub fn with_storm_reports(mut self, tx: mpsc::Sender<EventStorm>) -> Self {
+    self.storm_tx = Some(tx);
+    self
+  }
+
+  /// Drop events while `paused` is set
+  pub fn with_pause_flag(mut self, paused: Arc<AtomicBool>) -> Self {
+    self.paused = paused;
+    self
   }
 
   /// Run the watcher task
@@ -327,6 +454,8 @@ impl WatcherTask {
   pub async fn run(mut self) {
     info!(root = %self.config.root.display(), "WatcherTask started");
 
+    self.case_insensitive = is_case_insensitive(&self.config.root).await;
+
     // Pending changes being debounced (keyed by path)
     let mut pending: HashMap<PathBuf, PendingChange> = HashMap::new();
 
@@ -346,8 +475,23 @@ impl WatcherTask {
         // Process incoming file events
         event = self.event_rx.recv() => {
           match event {
+            Some(Ok(_)) if self.paused.load(Ordering::Relaxed) => {
+              // Changes made while paused are picked up by the reconciliation on resume
+              pending.clear();
+            }
             Some(Ok(event)) => {
-              self.process_event(&mut pending, event);
+              let changes = self.process_event(&mut pending, event);
+              let was_storm = self.storm.in_storm();
+              if self.storm.record(Instant::now(), changes) {
+                if !was_storm {
+                  warn!(
+                    root = %self.config.root.display(),
+                    threshold = self.storm.threshold,
+                    "File event storm detected, pausing per-file indexing until it settles"
+                  );
+                }
+                pending.clear();
+              }
             }
             Some(Err(e)) => {
               warn!(error = %e, "Watcher error");
@@ -361,6 +505,9 @@ impl WatcherTask {
 
         // Check for settled (debounced) events
         _ = debounce_interval.tick() => {
+          if let Some(storm) = self.storm.settle(Instant::now()) {
+            self.report_storm(storm).await;
+          }
           self.flush_settled(&mut pending).await;
         }
       }
@@ -375,18 +522,42 @@ impl WatcherTask {
     info!(root = %self.config.root.display(), "WatcherTask stopped");
   }
 
-  /// Check if a file should be ignored (gitignore match)
-  fn is_ignored(&self, path: &PathBuf) -> bool {
-    if let Some(ref gitignore) = self.gitignore {
-      let is_dir = path.is_dir();
-      // Use matched_path_or_any_parents to correctly handle ignored directories
-      // e.g., .git/ pattern should ignore all files under .git/
-      gitignore.matched_path_or_any_parents(path, is_dir).is_ignore()
-    } else {
-      false
+  /// Hand a settled storm over for a rescan
+  async fn report_storm(&self, storm: EventStorm) {
+    info!(
+      root = %self.config.root.display(),
+      changes = storm.changes,
+      duration_ms = storm.duration.as_millis() as u64,
+      "File event storm settled, coalescing into one rescan"
+    );
+    match self.storm_tx {
+      Some(ref tx) => {
+        if let Err(e) = tx.send(storm).await {
+          warn!(error = %e, "Failed to report event storm");
+        }
+      }
+      None => warn!("No rescan available for event storm; changes made during it are not indexed"),
     }
   }
 
+  /// Check if a file should be ignored (gitignore match or config globs)
+  fn is_ignored(&self, path: &PathBuf) -> bool {
+    // Match on the normalized relative path so Windows verbatim and
+    // differently-cased roots still line up with the gitignore root
+    let Some(relative) = relative_path(path, &self.config.root) else {
+      return false;
+    };
+    let is_dir = path.is_dir();
+    // Use matched_path_or_any_parents to correctly handle ignored directories
+    // e.g., .git/ pattern should ignore all files under .git/
+    let gitignored = self.gitignore.as_ref().is_some_and(|gitignore| {
+      gitignore
+        .matched_path_or_any_parents(Path::new(&relative), is_dir)
+        .is_ignore()
+    });
+    gitignored || self.filter.is_excluded(&relative, is_dir)
+  }
+
   /// Check if a file is a supported type for indexing (code or document)
   fn is_indexable(&self, path: &Path) -> bool {
     // Skip directories
@@ -401,8 +572,9 @@ impl WatcherTask {
       .is_some_and(|ext| Language::from_extension(ext).is_some() || is_document_extension(ext))
   }
 
-  /// Process a single notify event into pending changes
-  fn process_event(&mut self, pending: &mut HashMap<PathBuf, PendingChange>, event: Event) {
+  /// Process a single notify event into pending changes, returning how many it recorded
+  fn process_event(&mut self, pending: &mut HashMap<PathBuf, PendingChange>, event: Event) -> usize {
+    let mut recorded = 0;
     for path in &event.paths {
       // Skip directories
       if path.is_dir() {
@@ -446,6 +618,7 @@ impl WatcherTask {
                   // But we still need to handle the "from" as a delete
                   if !self.is_ignored(from) {
                     pending.insert(from.clone(), PendingChange::new(ChangeKind::Deleted));
+                    recorded += 1;
                   }
                   continue;
                 }
@@ -472,7 +645,7 @@ impl WatcherTask {
 
                 // We've handled this specially, skip the normal flow
                 // Note: we need to return early since we've processed both paths
-                return;
+                return recorded + 1;
               }
               // Fallback if somehow only one path
               ChangeKind::Modified
@@ -529,7 +702,9 @@ impl WatcherTask {
       } else {
         pending.insert(path.clone(), PendingChange::new(kind));
       }
+      recorded += 1;
     }
+    recorded
   }
 
   /// Flush pending changes that have settled (debounce period has passed)
@@ -566,6 +741,19 @@ impl WatcherTask {
     }
   }
 
+  /// New location of a file whose "deletion" was a case-only rename.
+  ///
+  /// Case-insensitive filesystems report the old casing as removed while the
+  /// file is still present under its new name.
+  async fn case_renamed_to(&self, path: &Path) -> Option<PathBuf> {
+    if !self.case_insensitive {
+      return None;
+    }
+    let relative = relative_path(path, &self.config.root)?;
+    let canonical = canonical_case(&self.config.root, &relative).await?;
+    (canonical != relative).then(|| self.config.root.join(canonical))
+  }
+
   /// Send a change to the indexer
   async fn send_change(&mut self, path: PathBuf, change: PendingChange) {
     // Get old content from cache for incremental parsing
@@ -583,7 +771,10 @@ impl WatcherTask {
 
     let job = match change.kind {
       ChangeKind::Created | ChangeKind::Modified => IndexJob::File { path, old_content },
-      ChangeKind::Deleted => IndexJob::Delete { path },
+      ChangeKind::Deleted => match self.case_renamed_to(&path).await {
+        Some(to) => IndexJob::Rename { from: path, to },
+        None => IndexJob::Delete { path },
+      },
       // path is the key (new location), from is stored in ChangeKind
       ChangeKind::Renamed { from } => IndexJob::Rename { from, to: path },
     };
@@ -599,7 +790,7 @@ impl WatcherTask {
 // ============================================================================
 
 /// Build a gitignore matcher for the given root directory
-fn build_gitignore(root: &PathBuf) -> Result<Option<Gitignore>, WatcherError> {
+pub(crate) fn build_gitignore(root: &PathBuf) -> Result<Option<Gitignore>, WatcherError> {
   let gitignore_path = root.join(".gitignore");
 
   if !gitignore_path.exists() {
@@ -670,6 +861,39 @@ mod tests {
     assert!(matches!(pending.kind, ChangeKind::Renamed { .. }));
   }
 
+  #[test]
+  fn test_storm_detector_coalesces_bursts() {
+    let window = Duration::from_secs(1);
+    let start = Instant::now();
+    let mut storm = StormDetector::new(100, window);
+    storm.window_start = start;
+
+    assert!(!storm.record(start, 40), "below the threshold");
+    assert!(
+      storm.record(start + Duration::from_millis(500), 70),
+      "threshold crossed"
+    );
+    assert!(storm.record(start + Duration::from_millis(1500), 500));
+    assert!(
+      storm.settle(start + Duration::from_millis(1600)).is_none(),
+      "window not over"
+    );
+    assert!(
+      storm.settle(start + Duration::from_millis(2600)).is_none(),
+      "a window above the threshold keeps the storm going"
+    );
+
+    let settled = storm
+      .settle(start + Duration::from_millis(3700))
+      .expect("a quiet window ends it");
+    assert_eq!(settled.changes, 610);
+    assert!(!storm.in_storm());
+    assert!(!storm.record(start + Duration::from_millis(3800), 10));
+
+    let mut disabled = StormDetector::new(0, window);
+    assert!(!disabled.record(Instant::now(), 1_000_000));
+  }
+
   #[test]
   fn test_content_cache() {
     let mut cache = ContentCache::new(3, 1024);
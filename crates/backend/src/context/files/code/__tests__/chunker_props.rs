@@ -0,0 +1,294 @@
+//! Property tests for the chunker and the tree-sitter parser.
+//!
+//! The watcher hands the chunker whatever is on disk, mid-edit files and
+//! binary junk included, so these feed it arbitrary text and randomly mutated
+//! real sources. Nothing may panic, every chunk must name a valid line range
+//! whose content matches it, and no definition may fall outside the chunks.
+
+use proptest::prelude::*;
+
+use crate::{
+  context::files::code::{
+    chunker::{Chunker, ChunkerConfig},
+    parser::TreeSitterParser,
+  },
+  domain::code::{CodeChunk, Language},
+};
+
+const RUST_SAMPLE: &str = r#"use std::collections::HashMap;
+use crate::config::Config;
+
+/// A cache keyed by name
+pub struct Cache {
+    entries: HashMap<String, String>,
+}
+
+impl Cache {
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, value: String) {
+        let note = "café ☕";
+        self.entries.insert(key, value);
+        println!("{}", note);
+    }
+}
+
+fn main() {
+    let mut cache = Cache { entries: HashMap::new() };
+    cache.insert("a".into(), "b".into());
+}
+"#;
+
+const PYTHON_SAMPLE: &str = r#"import os
+from typing import Optional
+
+class Store:
+    """Keeps things."""
+
+    def __init__(self, root):
+        self.root = root
+
+    def load(self, name: str) -> Optional[str]:
+        path = os.path.join(self.root, name)
+        return open(path).read()
+
+def main():
+    store = Store("/tmp")
+    print(store.load("données"))
+"#;
+
+const TYPESCRIPT_SAMPLE: &str = r#"import { readFile } from "fs/promises";
+import type { Config } from "./config";
+
+export interface Entry {
+  name: string;
+}
+
+export class Loader {
+  constructor(private config: Config) {}
+
+  async load(name: string): Promise<Entry> {
+    const text = await readFile(name, "utf8");
+    return JSON.parse(text);
+  }
+}
+
+export function run(config: Config) {
+  const loader = new Loader(config);
+  return loader.load("entrée.json");
+}
+"#;
+
+const GO_SAMPLE: &str = r#"package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+type Greeter struct {
+	Name string
+}
+
+func (g *Greeter) Greet() string {
+	return fmt.Sprintf("héllo %s", strings.ToUpper(g.Name))
+}
+
+func main() {
+	g := &Greeter{Name: "world"}
+	fmt.Println(g.Greet())
+}
+"#;
+
+fn sample() -> impl Strategy<Value = (&'static str, Language)> {
+  prop_oneof![
+    Just((RUST_SAMPLE, Language::Rust)),
+    Just((PYTHON_SAMPLE, Language::Python)),
+    Just((TYPESCRIPT_SAMPLE, Language::TypeScript)),
+    Just((TYPESCRIPT_SAMPLE, Language::JavaScript)),
+    Just((GO_SAMPLE, Language::Go)),
+  ]
+}
+
+fn language() -> impl Strategy<Value = Language> {
+  prop_oneof![
+    Just(Language::Rust),
+    Just(Language::Python),
+    Just(Language::TypeScript),
+    Just(Language::Tsx),
+    Just(Language::JavaScript),
+    Just(Language::Go),
+    Just(Language::Java),
+    Just(Language::C),
+    Just(Language::Cpp),
+  ]
+}
+
+/// Text that looks enough like code to get past the parser's first few tokens
+fn code_like() -> impl Strategy<Value = String> {
+  prop_oneof![
+    any::<String>(),
+    "[a-z_ (){}\\[\\];:=<>\"'/*#.,\n\t\r]{0,400}",
+    "(fn|def|class|func|import|use|struct|impl|return|[a-z]+|[{}();\n ]|é|你){0,120}",
+  ]
+}
+
+/// One edit: where, how many chars to delete, and what to insert
+fn edits() -> impl Strategy<Value = Vec<(usize, usize, String)>> {
+  prop::collection::vec(
+    (any::<usize>(), 0usize..40, "([a-z{}();\"'\n ]|é|\u{1F600}){0,12}"),
+    1..6,
+  )
+}
+
+/// Apply edits to `source`, snapping every position to a char boundary
+fn mutate(source: &str, edits: &[(usize, usize, String)]) -> String {
+  let mut text = source.to_string();
+  for (pos, delete, insert) in edits {
+    let mut start = pos % (text.len() + 1);
+    while !text.is_char_boundary(start) {
+      start -= 1;
+    }
+    let mut end = (start + delete).min(text.len());
+    while !text.is_char_boundary(end) {
+      end += 1;
+    }
+    text.replace_range(start..end, insert);
+  }
+  text
+}
+
+fn line_chunker() -> Chunker {
+  Chunker::with_owned_parser(ChunkerConfig {
+    target_lines: 5,
+    max_lines: 10,
+    use_ast_chunking: false,
+  })
+}
+
+/// Ranges are 1-indexed, inside the file, sorted, and hold exactly their lines
+fn assert_valid_chunks(source: &str, chunks: &[CodeChunk]) {
+  let lines: Vec<&str> = source.lines().collect();
+  let last_line = (lines.len() as u32).max(1);
+
+  assert!(!chunks.is_empty(), "every file yields at least one chunk");
+  for chunk in chunks {
+    assert!(
+      1 <= chunk.start_line && chunk.start_line <= chunk.end_line && chunk.end_line <= last_line,
+      "chunk {}-{} outside 1-{}",
+      chunk.start_line,
+      chunk.end_line,
+      last_line
+    );
+    let expected = lines[(chunk.start_line - 1) as usize..(chunk.end_line as usize).min(lines.len())].join("\n");
+    assert!(
+      chunk.content == source || chunk.content == expected,
+      "chunk {}-{} content does not match its lines",
+      chunk.start_line,
+      chunk.end_line
+    );
+  }
+  assert!(chunks.windows(2).all(|w| w[0].start_line <= w[1].start_line));
+}
+
+/// Line-based chunks tile the file with no gaps or overlaps
+fn assert_tiles_file(source: &str, chunks: &[CodeChunk]) {
+  let last_line = (source.lines().count() as u32).max(1);
+  assert_eq!(chunks[0].start_line, 1);
+  for pair in chunks.windows(2) {
+    assert_eq!(
+      pair[1].start_line,
+      pair[0].end_line + 1,
+      "gap or overlap between chunks"
+    );
+  }
+  assert_eq!(chunks[chunks.len() - 1].end_line, last_line);
+}
+
+/// Every line of every definition the parser finds lands in some chunk
+fn assert_covers_definitions(source: &str, language: Language, chunks: &[CodeChunk]) {
+  let total_lines = source.lines().count() as u32;
+  let definitions = TreeSitterParser::new().extract_definitions(source, language);
+  for def in definitions {
+    for line in def.start_line..=def.end_line.min(total_lines) {
+      assert!(
+        chunks.iter().any(|c| c.start_line <= line && line <= c.end_line),
+        "line {} of {:?} `{}` is in no chunk",
+        line,
+        def.kind,
+        def.name
+      );
+    }
+  }
+}
+
+proptest! {
+  #![proptest_config(ProptestConfig::with_cases(64))]
+
+  #[test]
+  fn prop_parser_survives_arbitrary_input(source in code_like(), language in language()) {
+    let mut parser = TreeSitterParser::new();
+    for def in parser.extract_definitions(&source, language) {
+      prop_assert!(1 <= def.start_line && def.start_line <= def.end_line);
+    }
+    parser.extract_imports(&source, language);
+    parser.extract_calls(&source, language);
+    parser.extract_imports_and_calls(&source, language);
+  }
+
+  #[test]
+  fn prop_chunks_are_valid_for_arbitrary_input(source in code_like(), language in language()) {
+    let mut chunker = Chunker::with_owned_parser(ChunkerConfig::default());
+    let chunks = chunker.chunk(&source, "fuzz", language, "hash", None);
+    assert_valid_chunks(&source, &chunks);
+    assert_covers_definitions(&source, language, &chunks);
+  }
+
+  #[test]
+  fn prop_chunks_are_valid_for_mutated_sources((base, language) in sample(), edits in edits()) {
+    let source = mutate(base, &edits);
+    let mut chunker = Chunker::with_owned_parser(ChunkerConfig::default());
+    let chunks = chunker.chunk(&source, "fuzz", language, "hash", None);
+    assert_valid_chunks(&source, &chunks);
+    assert_covers_definitions(&source, language, &chunks);
+
+    let mut parser = TreeSitterParser::new();
+    for def in parser.extract_definitions(&source, language) {
+      prop_assert!(1 <= def.start_line && def.start_line <= def.end_line);
+    }
+    parser.extract_imports_and_calls(&source, language);
+  }
+
+  #[test]
+  fn prop_line_chunks_tile_the_file(source in code_like(), (base, language) in sample(), edits in edits()) {
+    let mut chunker = line_chunker();
+    for source in [source, mutate(base, &edits)] {
+      let chunks = chunker.chunk(&source, "fuzz", language, "hash", None);
+      assert_valid_chunks(&source, &chunks);
+      assert_tiles_file(&source, &chunks);
+    }
+  }
+
+  /// Incremental parsing may recover from errors differently than a full
+  /// parse, so only the invariants are checked, not equality
+  #[test]
+  fn prop_incremental_chunking_is_valid((base, language) in sample(), first in edits(), second in edits()) {
+    let old = mutate(base, &first);
+    let new = mutate(&old, &second);
+
+    let mut parser = TreeSitterParser::new();
+    if let Some(ranges) = parser.parse_edit(&old, &new, language) {
+      for (start, end) in ranges {
+        prop_assert!(1 <= start && start <= end);
+      }
+    }
+
+    let mut chunker = Chunker::with_owned_parser(ChunkerConfig::default());
+    chunker.chunk(&old, "fuzz", language, "hash", None);
+    let chunks = chunker.chunk(&new, "fuzz", language, "hash", Some(old.as_str()));
+    assert_valid_chunks(&new, &chunks);
+    chunker.changed_lines(&new, &old, language);
+  }
+}
@@ -0,0 +1 @@
+mod chunker_props;
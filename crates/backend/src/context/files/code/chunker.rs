@@ -139,6 +139,19 @@ impl Chunker {
     }
   }
 
+  /// Lines whose syntax tree changed between `old_source` and `source`.
+  ///
+  /// Ranges are 1-indexed and inclusive. The new tree stays cached, so a following
+  /// [`chunk`](Self::chunk) call with `old_content` reuses it instead of reparsing.
+  /// Returns `None` for languages without AST chunking and for edits too large to
+  /// parse incrementally.
+  pub fn changed_lines(&mut self, source: &str, old_source: &str, language: Language) -> Option<Vec<(u32, u32)>> {
+    if !self.config.use_ast_chunking || !self.supports_language(language) {
+      return None;
+    }
+    self.with_parser(|p| p.parse_edit(old_source, source, language))
+  }
+
   /// Chunk source code with incremental parsing support.
   ///
   /// When `old_content` is provided, uses incremental tree-sitter parsing which
@@ -177,65 +190,6 @@ impl Chunker {
       "Starting file chunking"
     );
 
-    // Skip files with very few newlines - likely minified code, data files, or assets.
-    // These are expensive to embed and produce poor quality chunks.
-    // Heuristic: >10KB and >500 chars per line = probably minified/data/asset
-    let line_count = source.lines().count();
-    let chars_per_line = if line_count > 0 {
-      source.len() / line_count
-    } else {
-      source.len()
-    };
-    if source.len() > 10_000 && chars_per_line > 500 {
-      debug!(
-        file = %file_path,
-        size_bytes = source.len(),
-        line_count,
-        chars_per_line,
-        "Skipping file with very few newlines (likely minified code or data)"
-      );
-      return Vec::new();
-    }
-
-    // Skip highly repetitive files - likely data dumps (JSON fixtures, CSV-like data, etc.)
-    // These produce poor quality embeddings and waste resources.
-    // Only check files >20KB to avoid overhead on small files.
-    if source.len() > 20_000 {
-      use std::collections::HashSet;
-
-      let mut unique_lines = HashSet::new();
-      let mut total_lines_checked = 0usize;
-
-      for line in source.lines() {
-        total_lines_checked += 1;
-        unique_lines.insert(line);
-
-        // Early exit: after 500 lines, if <20% unique, it's repetitive data
-        if total_lines_checked >= 500 && unique_lines.len() * 5 < total_lines_checked {
-          debug!(
-            file = %file_path,
-            size_bytes = source.len(),
-            lines_checked = total_lines_checked,
-            unique_lines = unique_lines.len(),
-            "Skipping highly repetitive file (likely data dump)"
-          );
-          return Vec::new();
-        }
-      }
-
-      // Final check for files with 100-500 lines
-      if total_lines_checked > 100 && unique_lines.len() * 5 < total_lines_checked {
-        debug!(
-          file = %file_path,
-          size_bytes = source.len(),
-          total_lines = total_lines_checked,
-          unique_lines = unique_lines.len(),
-          "Skipping highly repetitive file (likely data dump)"
-        );
-        return Vec::new();
-      }
-    }
-
     // Warn about large files that may take longer to process
     if source.len() > 100_000 {
       debug!(
@@ -286,13 +240,14 @@ impl Chunker {
     file_hash: &str,
     old_content: Option<&str>,
   ) -> Vec<CodeChunk> {
-    // Use incremental parsing if old content is available
-    let parsed = self.with_parser(|p| {
-      if old_content.is_some() {
-        p.parse_file_incremental(source, language, None)
-      } else {
+    // Use incremental parsing if old content is available. The cached tree may
+    // belong to another file, so the edit is applied to the old content's tree.
+    let parsed = self.with_parser(|p| match old_content {
+      Some(old) if !p.is_cached(source, language) => {
+        p.parse_edit(old, source, language);
         p.parse_file(source, language)
       }
+      _ => p.parse_file(source, language),
     });
 
     if !parsed {
@@ -494,7 +449,8 @@ impl Chunker {
       imports: combined_imports,
       calls,
       start_line: (actual_start + 1) as u32,
-      end_line: def.end_line,
+      // A node ending at the start of the next row can report a line past the end
+      end_line: end_idx as u32,
       file_hash: file_hash.to_string(),
       indexed_at: Utc::now(),
       tokens_estimate,
@@ -508,6 +464,10 @@ impl Chunker {
       content_hash: Some(content_hash),
       caller_count: 0, // Computed during reference extraction
       callee_count: 0, // Computed during reference extraction
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     }
   }
 
@@ -555,6 +515,10 @@ impl Chunker {
       content_hash: Some(content_hash),
       caller_count: 0,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     }
   }
 
@@ -933,7 +897,7 @@ impl Chunker {
         imports,
         calls,
         start_line: 1,
-        end_line: total_lines as u32,
+        end_line: (total_lines as u32).max(1),
         file_hash: file_hash.to_string(),
         indexed_at: Utc::now(),
         tokens_estimate: (source.len() / CHARS_PER_TOKEN) as u32,
@@ -947,6 +911,10 @@ impl Chunker {
         content_hash: Some(content_hash),
         caller_count: 0,
         callee_count: 0,
+        git_branch: None,
+        git_commit: None,
+        shallow: false,
+        generated: false,
       }];
     }
 
@@ -990,6 +958,10 @@ impl Chunker {
           content_hash: Some(content_hash),
           caller_count: 0,
           callee_count: 0,
+          git_branch: None,
+          git_commit: None,
+          shallow: false,
+          generated: false,
         });
 
         current_start = boundary;
@@ -1028,6 +1000,10 @@ impl Chunker {
         content_hash: Some(content_hash),
         caller_count: 0,
         callee_count: 0,
+        git_branch: None,
+        git_commit: None,
+        shallow: false,
+        generated: false,
       });
     }
 
@@ -1312,6 +1288,10 @@ impl Chunker {
         content_hash: Some(content_hash),
         caller_count: 0,
         callee_count: 0,
+        git_branch: None,
+        git_commit: None,
+        shallow: false,
+        generated: false,
       });
     }
 
@@ -1600,36 +1580,6 @@ pub fn main() {
     );
   }
 
-  #[test]
-  fn test_skip_repetitive_data_files() {
-    // Simulate a large repetitive JSON-like data file (>20KB, <20% unique lines)
-    // This should be skipped by the repetition detection heuristic
-    let repeated_line = r#"  {"id": 1, "name": "test", "value": 12345},"#;
-    let unique_header = r#"{"data": ["#;
-    let unique_footer = r#"]}"#;
-
-    // Create ~25KB of repetitive content (~600 identical lines)
-    let mut source = String::with_capacity(30_000);
-    source.push_str(unique_header);
-    source.push('\n');
-    for _ in 0..600 {
-      source.push_str(repeated_line);
-      source.push('\n');
-    }
-    source.push_str(unique_footer);
-
-    assert!(source.len() > 20_000, "test file should be >20KB");
-
-    let mut chunker = Chunker::default();
-    let chunks = chunker.chunk(&source, "data.json", Language::Json, "hash123", None);
-
-    assert!(
-      chunks.is_empty(),
-      "highly repetitive files should produce no chunks, got {} chunks",
-      chunks.len()
-    );
-  }
-
   #[test]
   fn test_allow_non_repetitive_large_files() {
     // A large file with diverse content should NOT be skipped
@@ -1,3 +1,6 @@
 pub mod chunker;
 mod parser;
 pub mod tokenize;
+
+#[cfg(test)]
+mod __tests__;
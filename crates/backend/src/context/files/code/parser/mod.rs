@@ -16,6 +16,7 @@
 //! ```
 
 mod languages;
+pub mod resolve;
 mod sitter;
 
 pub use sitter::*;
@@ -0,0 +1,268 @@
+//! Import resolution
+//!
+//! Maps the import strings extracted by [`TreeSitterParser`](super::TreeSitterParser)
+//! to the project modules they may refer to. Resolution is purely path based:
+//! module paths are returned relative to the project root without extensions
+//! and matched against indexed file paths by suffix, so no package manifests
+//! or compiler settings are consulted.
+
+use crate::domain::code::Language;
+
+/// File stems that stand for their parent directory as a module
+const MODULE_INDEX_STEMS: &[&str] = &["mod", "lib", "main", "index", "__init__"];
+
+/// Resolve an import to the module paths it may refer to.
+///
+/// `from_file` is the project-relative path of the importing file, used for
+/// relative imports. Returns candidate module paths without extensions, most
+/// specific first. Imports of external packages that can't be mapped to a path
+/// return an empty list.
+pub fn resolve_import(import: &str, from_file: &str, lang: Language) -> Vec<String> {
+  let import = import
+    .trim()
+    .trim_matches(|c| c == '"' || c == '\'' || c == '<' || c == '>');
+  if import.is_empty() {
+    return Vec::new();
+  }
+
+  let mut candidates = match lang {
+    Language::Rust => resolve_rust(import, from_file),
+    Language::TypeScript | Language::JavaScript | Language::Tsx | Language::Jsx => resolve_js(import, from_file),
+    Language::Python => resolve_python(import, from_file),
+    Language::Go => suffixes(&import.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>()),
+    Language::C | Language::Cpp => {
+      let relative = join(&parent_dir(from_file), strip_extension(import));
+      vec![relative, strip_extension(import).to_string()]
+    }
+    _ => {
+      let segments: Vec<&str> = import
+        .split(['.', ':', '/', '\\'])
+        .filter(|s| !s.is_empty() && *s != "*")
+        .collect();
+      with_parent(&segments)
+    }
+  };
+
+  candidates.retain(|c| !c.is_empty());
+  candidates.dedup();
+  candidates
+}
+
+/// Whether `import` names `symbol` directly, e.g. `crate::db::ProjectDb` or `ProjectDb` for `ProjectDb`
+pub fn import_binds(import: &str, symbol: &str) -> bool {
+  import
+    .rsplit(['.', ':', '/', '\\'])
+    .find(|s| !s.is_empty())
+    .is_some_and(|last| last == symbol)
+}
+
+/// Whether the file at `file_path` implements the module path `module`.
+///
+/// Go modules are packages, so any file in the matching directory implements them.
+pub fn module_matches(file_path: &str, module: &str, lang: Language) -> bool {
+  let file_path = file_path.replace('\\', "/");
+  let dir = parent_dir(&file_path);
+
+  if lang == Language::Go {
+    return ends_with_path(&dir, module);
+  }
+
+  let stem = strip_extension(&file_path);
+  ends_with_path(stem, module) || (MODULE_INDEX_STEMS.contains(&file_name(stem)) && ends_with_path(&dir, module))
+}
+
+fn resolve_rust(import: &str, from_file: &str) -> Vec<String> {
+  let segments: Vec<&str> = import.split("::").filter(|s| !s.is_empty() && *s != "*").collect();
+  let Some((&first, rest)) = segments.split_first() else {
+    return Vec::new();
+  };
+
+  let module_dir = {
+    let stem = strip_extension(from_file);
+    if MODULE_INDEX_STEMS.contains(&file_name(stem)) {
+      parent_dir(stem)
+    } else {
+      stem.to_string()
+    }
+  };
+
+  match first {
+    // `crate::` paths are relative to the crate root, which file suffixes can match
+    "crate" => with_parent(rest),
+    "self" => with_parent(rest).iter().map(|m| join(&module_dir, m)).collect(),
+    "super" => {
+      let mut base = parent_dir(&module_dir);
+      let mut rest = rest;
+      while let Some((&"super", tail)) = rest.split_first() {
+        base = parent_dir(&base);
+        rest = tail;
+      }
+      with_parent(rest).iter().map(|m| join(&base, m)).collect()
+    }
+    _ => with_parent(&segments),
+  }
+}
+
+fn resolve_js(import: &str, from_file: &str) -> Vec<String> {
+  let path = strip_extension(import);
+  if path.starts_with("./") || path.starts_with("../") {
+    vec![join(&parent_dir(from_file), path)]
+  } else if let Some(aliased) = path.strip_prefix("@/").or_else(|| path.strip_prefix("~/")) {
+    vec![aliased.to_string()]
+  } else {
+    // Bare specifiers name packages outside the project
+    Vec::new()
+  }
+}
+
+fn resolve_python(import: &str, from_file: &str) -> Vec<String> {
+  let dots = import.chars().take_while(|c| *c == '.').count();
+  let segments: Vec<&str> = import[dots..]
+    .split('.')
+    .filter(|s| !s.is_empty() && *s != "*")
+    .collect();
+
+  if dots == 0 {
+    return with_parent(&segments);
+  }
+
+  let mut base = parent_dir(from_file);
+  for _ in 1..dots {
+    base = parent_dir(&base);
+  }
+  if segments.is_empty() {
+    return vec![base];
+  }
+  with_parent(&segments).iter().map(|m| join(&base, m)).collect()
+}
+
+/// The full path plus its parent, since the last segment may be a symbol rather than a module
+fn with_parent(segments: &[&str]) -> Vec<String> {
+  match segments {
+    [] => Vec::new(),
+    [only] => vec![only.to_string()],
+    _ => vec![segments.join("/"), segments[..segments.len() - 1].join("/")],
+  }
+}
+
+/// Trailing sub-paths of at least two segments, longest first
+fn suffixes(segments: &[&str]) -> Vec<String> {
+  if segments.len() < 2 {
+    return segments.iter().map(|s| s.to_string()).collect();
+  }
+  (0..segments.len() - 1).map(|i| segments[i..].join("/")).collect()
+}
+
+/// Join a directory and a relative path, normalizing `.` and `..` components
+fn join(dir: &str, path: &str) -> String {
+  let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+  for part in path.split('/') {
+    match part {
+      "" | "." => {}
+      ".." => {
+        parts.pop();
+      }
+      _ => parts.push(part),
+    }
+  }
+  parts.join("/")
+}
+
+fn parent_dir(path: &str) -> String {
+  path
+    .rsplit_once('/')
+    .map(|(dir, _)| dir.to_string())
+    .unwrap_or_default()
+}
+
+fn file_name(path: &str) -> &str {
+  path.rsplit('/').next().unwrap_or(path)
+}
+
+fn strip_extension(path: &str) -> &str {
+  match path.rsplit_once('.') {
+    Some((stem, ext)) if !stem.is_empty() && !ext.contains('/') && !stem.ends_with('.') => stem,
+    _ => path,
+  }
+}
+
+/// Whether `path` ends with `suffix` on a path component boundary
+fn ends_with_path(path: &str, suffix: &str) -> bool {
+  path == suffix || path.ends_with(&format!("/{}", suffix))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_resolve_rust_imports() {
+    assert_eq!(
+      resolve_import(
+        "crate::db::connection::ProjectDb",
+        "src/service/code/context.rs",
+        Language::Rust
+      ),
+      vec!["db/connection/ProjectDb", "db/connection"],
+      "crate paths should resolve to the item's module and its parent"
+    );
+    assert_eq!(
+      resolve_import("super::util::Resolver", "src/service/code/context.rs", Language::Rust),
+      vec!["src/service/code/util/Resolver", "src/service/code/util"],
+      "super should resolve from the parent of the file's module"
+    );
+    assert_eq!(
+      resolve_import("super::util", "src/service/code/mod.rs", Language::Rust),
+      vec!["src/service/util"],
+      "mod.rs is the module of its directory"
+    );
+  }
+
+  #[test]
+  fn test_resolve_relative_js_and_python_imports() {
+    assert_eq!(
+      resolve_import("../utils/format.js", "src/components/Button.tsx", Language::Tsx),
+      vec!["src/utils/format"],
+      "relative imports should resolve against the importing file's directory"
+    );
+    assert!(
+      resolve_import("react", "src/App.tsx", Language::Tsx).is_empty(),
+      "packages should not resolve to project paths"
+    );
+    assert_eq!(
+      resolve_import("..models.user", "app/api/routes.py", Language::Python),
+      vec!["app/models/user", "app/models"],
+      "each leading dot beyond the first should go up one package"
+    );
+  }
+
+  #[test]
+  fn test_module_matches() {
+    assert!(
+      module_matches("crates/backend/src/db/connection.rs", "db/connection", Language::Rust),
+      "module paths should match file paths by suffix"
+    );
+    assert!(
+      module_matches("crates/backend/src/db/mod.rs", "db", Language::Rust),
+      "mod.rs should implement its directory's module"
+    );
+    assert!(
+      !module_matches("crates/backend/src/mydb/connection.rs", "db/connection", Language::Rust),
+      "matches should respect path component boundaries"
+    );
+    assert!(
+      resolve_import("github.com/acme/app/internal/store", "main.go", Language::Go)
+        .iter()
+        .any(|m| module_matches("internal/store/sqlite.go", m, Language::Go)),
+      "Go packages should match files in their directory"
+    );
+    assert!(
+      import_binds("crate::db::ProjectDb", "ProjectDb"),
+      "imports ending in the symbol should bind it"
+    );
+    assert!(
+      !import_binds("crate::db", "ProjectDb"),
+      "module imports should not bind a symbol"
+    );
+  }
+}
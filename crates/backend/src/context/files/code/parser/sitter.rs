@@ -92,6 +92,14 @@ impl TreeSitterParser {
     hasher.finish()
   }
 
+  /// Whether the cached tree for `lang` was parsed from `content`
+  pub fn is_cached(&self, content: &str, lang: Language) -> bool {
+    self
+      .tree_cache
+      .get(&lang)
+      .is_some_and(|cached| cached.content_hash == Self::hash_content(content))
+  }
+
   /// Parse and cache a file's tree for subsequent queries.
   /// Returns true if parsing was successful.
   pub fn parse_file(&mut self, content: &str, lang: Language) -> bool {
@@ -184,6 +192,51 @@ impl TreeSitterParser {
     false
   }
 
+  /// Reparse a file after an edit, returning the lines whose syntax tree changed.
+  ///
+  /// The tree for `old_content` is used as the base for an incremental parse of
+  /// `content`. Ranges are 1-indexed and inclusive, covering the edited text plus
+  /// any nodes whose structure changed outside it (e.g. an unclosed comment).
+  /// Returns `None` when the file fails to parse or the edit is too large to parse
+  /// incrementally; the new tree is cached either way.
+  pub fn parse_edit(&mut self, old_content: &str, content: &str, lang: Language) -> Option<Vec<(u32, u32)>> {
+    if old_content == content {
+      return self.parse_file(content, lang).then(Vec::new);
+    }
+    if !self.parse_file(old_content, lang) {
+      return None;
+    }
+    let Some(input_edit) = Self::diff_content(old_content, content) else {
+      self.parse_file(content, lang);
+      return None;
+    };
+
+    let parser = self.parsers.get_mut(&lang)?;
+    let cached = self.tree_cache.get_mut(&lang)?;
+    cached.tree.edit(&input_edit);
+    let tree = parser.parse(content, Some(&cached.tree))?;
+
+    let mut ranges: Vec<(u32, u32)> = cached
+      .tree
+      .changed_ranges(&tree)
+      .map(|r| (r.start_point.row as u32 + 1, r.end_point.row as u32 + 1))
+      .collect();
+    ranges.push((
+      input_edit.start_position.row as u32 + 1,
+      input_edit.new_end_position.row as u32 + 1,
+    ));
+
+    self.tree_cache.insert(
+      lang,
+      CachedTree {
+        content_hash: Self::hash_content(content),
+        tree,
+        content: content.to_string(),
+      },
+    );
+    Some(ranges)
+  }
+
   /// Compute InputEdit from TextEdit
   fn compute_input_edit(edit: &TextEdit, old_content: &str) -> InputEdit {
     let start_byte = edit.start_offset;
@@ -233,18 +286,25 @@ impl TreeSitterParser {
   /// Compute InputEdit by diffing old and new content.
   /// Returns None if changes are too large (>50% of file changed).
   fn diff_content(old: &str, new: &str) -> Option<InputEdit> {
-    // Find common prefix
-    let prefix_len = old.bytes().zip(new.bytes()).take_while(|(a, b)| a == b).count();
+    // Find common prefix, backed off to a char boundary since the first
+    // differing byte may sit inside a multi-byte character
+    let mut prefix_len = old.bytes().zip(new.bytes()).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(prefix_len) || !new.is_char_boundary(prefix_len) {
+      prefix_len -= 1;
+    }
 
-    // Find common suffix (excluding prefix)
+    // Find common suffix (excluding prefix), likewise ending on a char boundary
     let old_suffix = &old[prefix_len..];
     let new_suffix = &new[prefix_len..];
-    let suffix_len = old_suffix
+    let mut suffix_len = old_suffix
       .bytes()
       .rev()
       .zip(new_suffix.bytes().rev())
       .take_while(|(a, b)| a == b)
       .count();
+    while !old.is_char_boundary(old.len() - suffix_len) || !new.is_char_boundary(new.len() - suffix_len) {
+      suffix_len -= 1;
+    }
 
     let start_byte = prefix_len;
     let old_end_byte = old.len().saturating_sub(suffix_len);
@@ -1583,6 +1643,29 @@ fn helper() {
     );
   }
 
+  #[test]
+  fn test_diff_content_splits_on_char_boundaries() {
+    // "é" and "è" share their first byte, "é" and "©" their last
+    let old = "fn main() { let s = \"café\"; }";
+    let new = "fn main() { let s = \"cafè\"; }";
+    let edit = TreeSitterParser::diff_content(old, new).expect("should find edit");
+    assert!(old.is_char_boundary(edit.start_byte));
+
+    let old = "fn main() { let t = \"é\"; }";
+    let new = "fn main() { let t = \"©\"; }";
+
+    let edit = TreeSitterParser::diff_content(old, new).expect("should find edit");
+    assert!(old.is_char_boundary(edit.start_byte) && new.is_char_boundary(edit.start_byte));
+    assert!(old.is_char_boundary(edit.old_end_byte));
+    assert!(new.is_char_boundary(edit.new_end_byte));
+
+    let mut parser = TreeSitterParser::new();
+    let ranges = parser
+      .parse_edit(old, new, Language::Rust)
+      .expect("should reparse incrementally");
+    assert!(!ranges.is_empty());
+  }
+
   #[test]
   fn test_diff_content_large_change_returns_none() {
     let old = "fn a() {} fn b() {} fn c() {}";
@@ -1658,4 +1741,35 @@ fn main() {
       "should not find deleted function"
     );
   }
+
+  #[test]
+  fn test_parse_edit_reports_changed_lines() {
+    let mut parser = TreeSitterParser::new();
+
+    let original = "fn first() {\n    one();\n}\n\nfn second() {\n    two();\n}\n\nfn third() {\n    three();\n}\n";
+    let modified = original.replace("two();", "two(2);");
+
+    let ranges = parser
+      .parse_edit(original, &modified, Language::Rust)
+      .expect("small edits should parse incrementally");
+    assert!(
+      ranges.iter().any(|&(start, end)| start <= 6 && end >= 6),
+      "the edited line should be reported, got {:?}",
+      ranges
+    );
+    assert!(
+      ranges.iter().all(|&(start, end)| start > 3 && end < 9),
+      "untouched functions should not be reported, got {:?}",
+      ranges
+    );
+
+    let defs = parser.extract_definitions(&modified, Language::Rust);
+    assert_eq!(defs.len(), 3, "the cached tree should be the edited one");
+
+    assert_eq!(
+      parser.parse_edit(&modified, &modified, Language::Rust),
+      Some(Vec::new()),
+      "unchanged content should report no changes"
+    );
+  }
 }
@@ -0,0 +1,134 @@
+//! Index depth for dependency code
+//!
+//! Vendored libraries, virtualenvs and generated protobuf code change in bulk
+//! whenever dependencies are updated, and embedding thousands of their chunks
+//! adds little to search. Files under such paths are indexed shallowly: only
+//! their definitions are stored, without embeddings, so go-to-definition still
+//! resolves into them while semantic search leaves them out.
+
+use std::path::Path;
+
+use crate::{config::IndexConfig, domain::path::PathFilter};
+
+/// Directory names that hold third-party code
+const DEPENDENCY_DIRS: &[&str] = &[
+  "vendor",
+  "vendored",
+  "third_party",
+  "third-party",
+  "thirdparty",
+  "external",
+  "bower_components",
+  "node_modules",
+  "site-packages",
+  ".venv",
+  "venv",
+  "Pods",
+];
+
+/// File name suffixes of code generated by protoc and its plugins
+const PROTOBUF_SUFFIXES: &[&str] = &[
+  ".pb.go",
+  ".pb.cc",
+  ".pb.h",
+  ".pb.swift",
+  ".pb.rs",
+  "_pb2.py",
+  "_pb2.pyi",
+  "_pb2_grpc.py",
+  "_pb.js",
+  "_pb.d.ts",
+  "_pb.ts",
+];
+
+/// How deeply a file is indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexDepth {
+  /// Every chunk, embedded
+  Full,
+  /// Definitions only, without embeddings
+  Shallow,
+}
+
+/// Decides the depth of each file from the `[index]` config
+#[derive(Debug, Clone, Default)]
+pub struct DepthRules {
+  detect_dependencies: bool,
+  /// Only exclude globs, so `is_excluded` means "matches one"
+  shallow: PathFilter,
+  deep: PathFilter,
+}
+
+impl DepthRules {
+  pub fn new(root: &Path, config: &IndexConfig) -> Self {
+    Self {
+      detect_dependencies: config.detect_dependencies,
+      shallow: PathFilter::new(root, &[], &config.shallow),
+      deep: PathFilter::new(root, &[], &config.deep),
+    }
+  }
+
+  /// Depth for `relative` (a normalized path under the root)
+  pub fn depth(&self, relative: &str) -> IndexDepth {
+    if self.deep.is_excluded(relative, false) {
+      return IndexDepth::Full;
+    }
+    if self.shallow.is_excluded(relative, false) || (self.detect_dependencies && is_dependency(relative)) {
+      return IndexDepth::Shallow;
+    }
+    IndexDepth::Full
+  }
+}
+
+/// Whether `relative` is under a dependency directory or is generated protobuf code
+pub fn is_dependency(relative: &str) -> bool {
+  let mut components = relative.split('/');
+  let file_name = components.next_back().unwrap_or_default();
+  components.any(|dir| DEPENDENCY_DIRS.contains(&dir)) || PROTOBUF_SUFFIXES.iter().any(|s| file_name.ends_with(s))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_detects_dependency_paths() {
+    assert!(is_dependency("third_party/zlib/inflate.c"));
+    assert!(is_dependency("go/vendor/github.com/pkg/errors/errors.go"));
+    assert!(is_dependency("services/.venv/lib/python3.12/site-packages/six.py"));
+    assert!(is_dependency("api/gen/user.pb.go"));
+    assert!(is_dependency("clients/python/user_pb2_grpc.py"));
+
+    assert!(!is_dependency("src/vendor.rs"), "only directories named vendor count");
+    assert!(!is_dependency("src/protobuf/encode.rs"));
+  }
+
+  #[test]
+  fn test_config_globs_override_detection() {
+    let root = Path::new("/repo");
+    let config = IndexConfig {
+      shallow: vec!["deps/".to_string()],
+      deep: vec!["third_party/our-fork/".to_string()],
+      ..IndexConfig::default()
+    };
+    let rules = DepthRules::new(root, &config);
+
+    assert_eq!(rules.depth("src/main.rs"), IndexDepth::Full);
+    assert_eq!(rules.depth("deps/lib/util.rs"), IndexDepth::Shallow);
+    assert_eq!(rules.depth("third_party/zlib/inflate.c"), IndexDepth::Shallow);
+    assert_eq!(
+      rules.depth("third_party/our-fork/src/lib.rs"),
+      IndexDepth::Full,
+      "deep globs win over detection"
+    );
+
+    let undetected = DepthRules::new(
+      root,
+      &IndexConfig {
+        detect_dependencies: false,
+        ..IndexConfig::default()
+      },
+    );
+    assert_eq!(undetected.depth("third_party/zlib/inflate.c"), IndexDepth::Full);
+  }
+}
@@ -0,0 +1,195 @@
+//! Text decoding for files that are not valid UTF-8
+//!
+//! Source files occasionally arrive as UTF-16 (Windows editors write a BOM) or
+//! in a legacy single-byte encoding. Rather than skipping them, content is
+//! decoded best-effort. Decoding never merges or drops line breaks, so line
+//! numbers computed from the decoded text match the file on disk.
+
+/// Encoding a file was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+  Utf8,
+  /// UTF-8 with a byte order mark, which is stripped
+  Utf8Bom,
+  Utf16Le,
+  Utf16Be,
+  /// ISO-8859-1, used when bytes are not valid UTF-8 but look like text
+  Latin1,
+}
+
+impl TextEncoding {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Utf8 => "utf-8",
+      Self::Utf8Bom => "utf-8-bom",
+      Self::Utf16Le => "utf-16le",
+      Self::Utf16Be => "utf-16be",
+      Self::Latin1 => "latin-1",
+    }
+  }
+}
+
+/// Decoded file content
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+  pub text: String,
+  pub encoding: TextEncoding,
+  /// Invalid sequences replaced with U+FFFD
+  pub replaced: usize,
+}
+
+impl DecodedText {
+  /// Plain UTF-8 text that needed no decoding
+  pub fn utf8(text: String) -> Self {
+    Self {
+      text,
+      encoding: TextEncoding::Utf8,
+      replaced: 0,
+    }
+  }
+
+  /// Whether decoding changed how the file would be read as UTF-8
+  pub fn needs_warning(&self) -> bool {
+    !matches!(self.encoding, TextEncoding::Utf8 | TextEncoding::Utf8Bom) || self.replaced > 0
+  }
+}
+
+/// Decode file bytes, detecting BOMs and falling back to Latin-1 or lossy UTF-8.
+///
+/// Bytes without a BOM that are not valid UTF-8 are read as Latin-1 unless
+/// they contain NUL bytes, which suggest binary content; those are decoded as
+/// lossy UTF-8 so the replacement count reflects the damage.
+pub fn decode(bytes: &[u8]) -> DecodedText {
+  if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+    let (text, replaced) = utf8_lossy(rest);
+    return DecodedText {
+      text,
+      encoding: TextEncoding::Utf8Bom,
+      replaced,
+    };
+  }
+  if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+    return utf16(rest, TextEncoding::Utf16Le, u16::from_le_bytes);
+  }
+  if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+    return utf16(rest, TextEncoding::Utf16Be, u16::from_be_bytes);
+  }
+
+  if let Ok(text) = std::str::from_utf8(bytes) {
+    return DecodedText::utf8(text.to_string());
+  }
+
+  if !bytes.contains(&0) {
+    return DecodedText {
+      text: bytes.iter().map(|&b| b as char).collect(),
+      encoding: TextEncoding::Latin1,
+      replaced: 0,
+    };
+  }
+
+  let (text, replaced) = utf8_lossy(bytes);
+  DecodedText {
+    text,
+    encoding: TextEncoding::Utf8,
+    replaced,
+  }
+}
+
+fn utf8_lossy(bytes: &[u8]) -> (String, usize) {
+  let mut text = String::with_capacity(bytes.len());
+  let mut replaced = 0;
+  for chunk in bytes.utf8_chunks() {
+    text.push_str(chunk.valid());
+    if !chunk.invalid().is_empty() {
+      text.push(char::REPLACEMENT_CHARACTER);
+      replaced += 1;
+    }
+  }
+  (text, replaced)
+}
+
+fn utf16(bytes: &[u8], encoding: TextEncoding, to_unit: fn([u8; 2]) -> u16) -> DecodedText {
+  let units = bytes.chunks(2).map(|pair| match pair {
+    [a, b] => to_unit([*a, *b]),
+    // A trailing odd byte can't form a code unit
+    _ => 0xFFFD,
+  });
+
+  let mut text = String::with_capacity(bytes.len() / 2);
+  let mut replaced = 0;
+  for unit in char::decode_utf16(units) {
+    match unit {
+      Ok(c) => text.push(c),
+      Err(_) => {
+        text.push(char::REPLACEMENT_CHARACTER);
+        replaced += 1;
+      }
+    }
+  }
+  if bytes.len() % 2 == 1 {
+    replaced += 1;
+  }
+
+  DecodedText {
+    text,
+    encoding,
+    replaced,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_decode_detects_boms() {
+    let decoded = decode(b"\xEF\xBB\xBFfn main() {}\n");
+    assert_eq!(decoded.encoding, TextEncoding::Utf8Bom, "utf-8 bom detected");
+    assert_eq!(decoded.text, "fn main() {}\n", "bom stripped");
+    assert!(!decoded.needs_warning(), "utf-8 with bom is not worth a warning");
+
+    let le: Vec<u8> = [0xFF, 0xFE]
+      .into_iter()
+      .chain("a\nb".encode_utf16().flat_map(u16::to_le_bytes))
+      .collect();
+    let decoded = decode(&le);
+    assert_eq!(decoded.encoding, TextEncoding::Utf16Le, "utf-16le detected");
+    assert_eq!(decoded.text, "a\nb", "utf-16le decoded");
+
+    let be: Vec<u8> = [0xFE, 0xFF]
+      .into_iter()
+      .chain("é\n".encode_utf16().flat_map(u16::to_be_bytes))
+      .collect();
+    let decoded = decode(&be);
+    assert_eq!(decoded.encoding, TextEncoding::Utf16Be, "utf-16be detected");
+    assert_eq!(decoded.text, "é\n", "utf-16be decoded");
+  }
+
+  #[test]
+  fn test_decode_falls_back_to_latin1() {
+    let decoded = decode(b"// caf\xE9\nlet x = 1;\n");
+    assert_eq!(
+      decoded.encoding,
+      TextEncoding::Latin1,
+      "invalid utf-8 text read as latin-1"
+    );
+    assert_eq!(
+      decoded.text, "// café\nlet x = 1;\n",
+      "latin-1 bytes map to code points"
+    );
+    assert_eq!(decoded.text.lines().count(), 2, "line count preserved");
+    assert!(decoded.needs_warning(), "non utf-8 encodings are reported");
+  }
+
+  #[test]
+  fn test_decode_binary_is_lossy() {
+    let decoded = decode(b"a\0\xFF\nb\n");
+    assert_eq!(
+      decoded.encoding,
+      TextEncoding::Utf8,
+      "nul bytes prevent the latin-1 fallback"
+    );
+    assert_eq!(decoded.replaced, 1, "invalid byte replaced");
+    assert_eq!(decoded.text, "a\0\u{FFFD}\nb\n", "line breaks preserved");
+  }
+}
@@ -0,0 +1,296 @@
+//! Text extraction for rich document formats
+//!
+//! PDF files are converted to plain text when read, since they can't be
+//! represented as UTF-8. HTML is read as text and stripped at chunking time,
+//! keeping the main article content and dropping navigation, scripts and markup.
+
+use std::path::Path;
+
+use super::{
+  FileIndexError,
+  encoding::{self, DecodedText},
+};
+
+/// Rich document formats that need text extraction before chunking.
+///
+/// Only recognized by document ingestion, since HTML is also a code language.
+const RICH_DOCUMENT_EXTENSIONS: &[&str] = &["html", "htm", "xhtml", "pdf"];
+
+/// Elements dropped together with their content
+const SKIPPED_ELEMENTS: &[&str] = &[
+  "script", "style", "noscript", "template", "svg", "head", "nav", "header", "footer", "aside", "form",
+];
+
+/// Elements that start a new line of text
+const BLOCK_ELEMENTS: &[&str] = &[
+  "p",
+  "div",
+  "br",
+  "li",
+  "ul",
+  "ol",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "tr",
+  "table",
+  "section",
+  "article",
+  "main",
+  "pre",
+  "blockquote",
+  "hr",
+  "dt",
+  "dd",
+];
+
+/// Check if a file extension is a rich document format
+pub fn is_rich_document_extension(ext: &str) -> bool {
+  RICH_DOCUMENT_EXTENSIONS.contains(&ext.to_lowercase().as_str())
+}
+
+/// Source format of a document file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+  /// Plain text or lightweight markup (Markdown, reStructuredText, ...)
+  Text,
+  Html,
+  Pdf,
+}
+
+impl DocumentFormat {
+  pub fn from_extension(ext: &str) -> Self {
+    match ext.to_lowercase().as_str() {
+      "html" | "htm" | "xhtml" => Self::Html,
+      "pdf" => Self::Pdf,
+      _ => Self::Text,
+    }
+  }
+
+  pub fn from_path(path: &Path) -> Self {
+    path
+      .extension()
+      .and_then(|e| e.to_str())
+      .map(Self::from_extension)
+      .unwrap_or(Self::Text)
+  }
+}
+
+/// Read a file as text, extracting the text layer of PDF files.
+///
+/// Content that is not valid UTF-8 is decoded best-effort; see [`encoding`].
+#[tracing::instrument(level = "trace")]
+pub async fn read_text(path: &Path) -> Result<DecodedText, FileIndexError> {
+  let bytes = tokio::fs::read(path).await?;
+  if DocumentFormat::from_path(path) != DocumentFormat::Pdf {
+    return Ok(encoding::decode(&bytes));
+  }
+
+  let text = tokio::task::spawn_blocking(move || pdf_to_text(&bytes))
+    .await
+    .map_err(|e| FileIndexError::Extraction(e.to_string()))??;
+  Ok(DecodedText::utf8(text))
+}
+
+/// Extract the text layer of a PDF.
+pub fn pdf_to_text(bytes: &[u8]) -> Result<String, FileIndexError> {
+  let text = pdf_extract::extract_text_from_mem(bytes).map_err(|e| FileIndexError::Extraction(e.to_string()))?;
+  Ok(normalize_whitespace(&text))
+}
+
+/// Convert HTML to readable plain text.
+///
+/// When the page has an `<article>` or `<main>` element only its content is
+/// kept. Boilerplate elements (navigation, headers, footers, scripts) are
+/// dropped, block elements become line breaks and entities are decoded.
+pub fn html_to_text(html: &str) -> String {
+  // ASCII lowercasing keeps byte offsets identical between the two strings
+  let lower = html.to_ascii_lowercase();
+  let (start, end) = main_content_range(&lower).unwrap_or((0, html.len()));
+  let html = &html[start..end];
+  let lower = &lower[start..end];
+
+  let mut out = String::with_capacity(html.len());
+  let mut pos = 0;
+
+  while let Some(rel) = html[pos..].find('<') {
+    let tag_start = pos + rel;
+    out.push_str(&html[pos..tag_start]);
+
+    if lower[tag_start..].starts_with("<!--") {
+      pos = lower[tag_start..]
+        .find("-->")
+        .map(|p| tag_start + p + 3)
+        .unwrap_or(html.len());
+      continue;
+    }
+
+    let Some(tag_len) = html[tag_start..].find('>') else {
+      pos = html.len();
+      break;
+    };
+    let tag_end = tag_start + tag_len + 1;
+    let tag = &lower[tag_start + 1..tag_end - 1];
+    let closing = tag.starts_with('/');
+    let name = tag_name(tag);
+
+    if !closing && SKIPPED_ELEMENTS.contains(&name) && !tag.ends_with('/') {
+      pos = skip_element(lower, tag_end, name);
+      continue;
+    }
+
+    if BLOCK_ELEMENTS.contains(&name) {
+      out.push('\n');
+    }
+    pos = tag_end;
+  }
+  out.push_str(&html[pos..]);
+
+  normalize_whitespace(&decode_entities(&out))
+}
+
+/// Byte range of the inner content of the first `<article>` or `<main>` element
+fn main_content_range(lower: &str) -> Option<(usize, usize)> {
+  ["article", "main"].iter().find_map(|name| {
+    let open = lower.find(&format!("<{}", name))?;
+    let content_start = open + lower[open..].find('>')? + 1;
+    let content_end = lower.rfind(&format!("</{}", name))?;
+    (content_end > content_start).then_some((content_start, content_end))
+  })
+}
+
+/// Find the position just after the closing tag of `name`
+fn skip_element(lower: &str, from: usize, name: &str) -> usize {
+  let close = format!("</{}", name);
+  match lower[from..].find(&close) {
+    Some(p) => {
+      let close_start = from + p;
+      lower[close_start..]
+        .find('>')
+        .map(|g| close_start + g + 1)
+        .unwrap_or(lower.len())
+    }
+    None => lower.len(),
+  }
+}
+
+fn tag_name(tag: &str) -> &str {
+  let tag = tag.trim_start_matches('/');
+  let end = tag.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(tag.len());
+  &tag[..end]
+}
+
+fn decode_entities(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(amp) = rest.find('&') {
+    out.push_str(&rest[..amp]);
+    rest = &rest[amp..];
+
+    let decoded = rest.find(';').filter(|&semi| semi <= 10).and_then(|semi| {
+      let entity = &rest[1..semi];
+      let ch = match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => entity
+          .strip_prefix("#x")
+          .or_else(|| entity.strip_prefix("#X"))
+          .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+          .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+          .and_then(char::from_u32),
+      };
+      ch.map(|c| (c, semi + 1))
+    });
+
+    match decoded {
+      Some((c, len)) => {
+        out.push(c);
+        rest = &rest[len..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+
+  out
+}
+
+/// Collapse runs of spaces within lines and runs of blank lines
+fn normalize_whitespace(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut blank_run = 0;
+
+  for line in text.lines() {
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.is_empty() {
+      blank_run += 1;
+      if blank_run > 1 || out.is_empty() {
+        continue;
+      }
+    } else {
+      blank_run = 0;
+    }
+    out.push_str(&line);
+    out.push('\n');
+  }
+
+  out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_html_to_text_strips_boilerplate() {
+    let html = r#"<!DOCTYPE html>
+<html>
+<head><title>Guide</title><style>body { color: red; }</style></head>
+<body>
+  <nav><a href="/">Home</a> | <a href="/docs">Docs</a></nav>
+  <h1>Install&nbsp;Guide</h1>
+  <!-- tracking pixel -->
+  <p>Run <code>cargo build</code> &amp; wait.</p>
+  <script>console.log("<p>not text</p>");</script>
+  <footer>Copyright 2024</footer>
+</body>
+</html>"#;
+
+    let text = html_to_text(html);
+    assert_eq!(
+      text, "Install Guide\n\nRun cargo build & wait.",
+      "should keep body text and drop nav, scripts, styles and footer"
+    );
+  }
+
+  #[test]
+  fn test_html_to_text_prefers_article() {
+    let html = "<body><div>Sidebar links</div><article><h2>Title</h2><p>Body &#x41;&#66;</p></article></body>";
+
+    assert_eq!(
+      html_to_text(html),
+      "Title\n\nBody AB",
+      "only article content should be kept"
+    );
+  }
+
+  #[test]
+  fn test_decode_entities_leaves_unknown() {
+    assert_eq!(
+      decode_entities("a &unknown; b & c &lt;"),
+      "a &unknown; b & c <",
+      "unknown entities and bare ampersands should be kept"
+    );
+  }
+}
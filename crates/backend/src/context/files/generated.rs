@@ -0,0 +1,85 @@
+//! Generated code detection
+//!
+//! Files written by code generators are marked either by a header comment
+//! ("@generated", Go's "Code generated ... DO NOT EDIT") or by a naming
+//! convention. Their chunks are tagged so search can rank hand-written code
+//! above them and explore can leave them out.
+
+/// Header lines searched for a generator marker
+const HEADER_LINES: usize = 20;
+
+/// Lowercased header markers left by code generators
+const MARKERS: &[&str] = &[
+  "@generated",
+  "do not edit",
+  "<auto-generated",
+  "auto-generated by",
+  "autogenerated by",
+  "code generated by",
+];
+
+/// Directory names that only hold generated code
+const GENERATED_DIRS: &[&str] = &["generated", "__generated__"];
+
+/// Lowercased file name suffixes of generated code
+const GENERATED_SUFFIXES: &[&str] = &[
+  ".g.dart",
+  ".freezed.dart",
+  ".g.cs",
+  ".designer.cs",
+  ".gen.go",
+  "_gen.go",
+  ".gen.ts",
+];
+
+/// Lowercased file name infixes of generated code, as in `schema.generated.ts`
+const GENERATED_INFIXES: &[&str] = &[".generated.", "_generated."];
+
+/// Whether the file at `relative` with `content` was written by a code generator
+pub fn is_generated(relative: &str, content: &str) -> bool {
+  is_generated_path(relative)
+    || content
+      .lines()
+      .take(HEADER_LINES)
+      .map(str::to_lowercase)
+      .any(|line| MARKERS.iter().any(|m| line.contains(m)))
+}
+
+/// Whether `relative` follows a generated code naming convention
+pub fn is_generated_path(relative: &str) -> bool {
+  let mut components = relative.split('/');
+  let file_name = components.next_back().unwrap_or_default().to_lowercase();
+  components.any(|dir| GENERATED_DIRS.contains(&dir))
+    || GENERATED_SUFFIXES.iter().any(|s| file_name.ends_with(s))
+    || GENERATED_INFIXES.iter().any(|s| file_name.contains(s))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_detects_generated_paths() {
+    assert!(is_generated_path("src/__generated__/schema.ts"));
+    assert!(is_generated_path("lib/models/user.g.dart"));
+    assert!(is_generated_path("Forms/MainForm.Designer.cs"));
+    assert!(is_generated_path("api/types.generated.ts"));
+    assert!(is_generated_path("internal/mocks/store_gen.go"));
+
+    assert!(!is_generated_path("src/generator.rs"));
+    assert!(!is_generated_path("src/codegen/emit.rs"));
+  }
+
+  #[test]
+  fn test_detects_generated_markers() {
+    let go = "// Code generated by stringer -type=Kind; DO NOT EDIT.\n\npackage kind\n";
+    assert!(is_generated("kind/kind_string.go", go));
+
+    let js = "/**\n * @generated SignedSource<<abc>>\n */\nexport const query = {};\n";
+    assert!(is_generated("src/query.js", js));
+
+    let late = format!("{}// DO NOT EDIT below this line\n", "fn f() {}\n".repeat(HEADER_LINES));
+    assert!(!is_generated("src/lib.rs", &late), "only the header is checked");
+    assert!(!is_generated("src/lib.rs", "pub fn generate() {}\n"));
+  }
+}
@@ -0,0 +1,228 @@
+//! Indexing guard rails
+//!
+//! Some files cost a lot to embed and add nothing to search: anything over the
+//! size limit, binary content that happens to have a source extension,
+//! minified bundles, repetitive data dumps, and generated files that would
+//! produce thousands of chunks. The guard decides which files to leave out
+//! and says why, so the skip can be recorded in the `skipped_files` table.
+
+use std::collections::HashSet;
+
+use crate::{
+  config::IndexConfig,
+  db::{SkipReason, SkippedFile},
+};
+
+/// Files smaller than this are never treated as minified
+const MINIFIED_MIN_BYTES: usize = 10_000;
+
+/// Files smaller than this are never checked for repetition
+const REPETITIVE_MIN_BYTES: usize = 20_000;
+
+/// Lines after which the repetition check can stop early
+const REPETITIVE_EARLY_LINES: usize = 500;
+
+/// Bytes looked at when sniffing for binary content
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Limits a file must stay within to be indexed
+#[derive(Debug, Clone)]
+pub struct IndexGuard {
+  max_file_size: usize,
+  /// 0 = no limit
+  max_chunks_per_file: usize,
+  skip_binary: bool,
+  skip_minified: bool,
+  minified_line_chars: usize,
+}
+
+impl Default for IndexGuard {
+  fn default() -> Self {
+    Self::from_config(&IndexConfig::default())
+  }
+}
+
+impl IndexGuard {
+  pub fn from_config(config: &IndexConfig) -> Self {
+    Self {
+      max_file_size: config.max_file_size,
+      max_chunks_per_file: config.max_chunks_per_file,
+      skip_binary: config.skip_binary,
+      skip_minified: config.skip_minified,
+      minified_line_chars: config.minified_line_chars.max(1),
+    }
+  }
+
+  /// Check a file's size before it is read
+  pub fn check_size(&self, relative: &str, size: u64) -> Option<SkippedFile> {
+    (size > self.max_file_size as u64).then(|| {
+      SkippedFile::new(
+        relative,
+        SkipReason::TooLarge,
+        format!("{} bytes > {} bytes", size, self.max_file_size),
+      )
+    })
+  }
+
+  /// Check decoded content before it is chunked.
+  ///
+  /// Documents are only checked for binary content: their size limit is
+  /// `docs.max_file_size`, and minified or repetitive text is still prose.
+  pub fn check_content(&self, relative: &str, content: &str, is_code: bool) -> Option<SkippedFile> {
+    if is_code && let Some(skipped) = self.check_size(relative, content.len() as u64) {
+      return Some(skipped);
+    }
+    if self.skip_binary
+      && let Some(detail) = binary(content)
+    {
+      return Some(SkippedFile::new(relative, SkipReason::Binary, detail));
+    }
+    if !self.skip_minified || !is_code {
+      return None;
+    }
+    if let Some(chars_per_line) = self.minified(content) {
+      return Some(SkippedFile::new(
+        relative,
+        SkipReason::Minified,
+        format!("{} chars per line", chars_per_line),
+      ));
+    }
+    repetitive(content).map(|(unique, total)| {
+      SkippedFile::new(
+        relative,
+        SkipReason::Repetitive,
+        format!("{} unique of {} lines", unique, total),
+      )
+    })
+  }
+
+  /// Check how many chunks a file produced
+  pub fn check_chunks(&self, relative: &str, chunks: usize) -> Option<SkippedFile> {
+    (self.max_chunks_per_file > 0 && chunks > self.max_chunks_per_file).then(|| {
+      SkippedFile::new(
+        relative,
+        SkipReason::TooManyChunks,
+        format!("{} chunks > {}", chunks, self.max_chunks_per_file),
+      )
+    })
+  }
+
+  /// Average line length, when it is long enough to mean minified code
+  fn minified(&self, content: &str) -> Option<usize> {
+    if content.len() <= MINIFIED_MIN_BYTES {
+      return None;
+    }
+    let chars_per_line = content.len() / content.lines().count().max(1);
+    (chars_per_line > self.minified_line_chars).then_some(chars_per_line)
+  }
+}
+
+/// Why the start of `content` looks binary, if it does
+fn binary(content: &str) -> Option<String> {
+  let mut end = content.len().min(BINARY_SNIFF_BYTES);
+  while !content.is_char_boundary(end) {
+    end -= 1;
+  }
+  let head = &content[..end];
+
+  if head.contains('\0') {
+    return Some("contains NUL bytes".to_string());
+  }
+  let total = head.chars().count();
+  let replaced = head.chars().filter(|&c| c == char::REPLACEMENT_CHARACTER).count();
+  (total > 0 && replaced * 10 > total).then(|| format!("{} of {} characters undecodable", replaced, total))
+}
+
+/// Unique and total line counts when fewer than 20% of lines are distinct
+fn repetitive(content: &str) -> Option<(usize, usize)> {
+  if content.len() <= REPETITIVE_MIN_BYTES {
+    return None;
+  }
+
+  let mut unique = HashSet::new();
+  let mut total = 0usize;
+  for line in content.lines() {
+    total += 1;
+    unique.insert(line);
+    if total >= REPETITIVE_EARLY_LINES && unique.len() * 5 < total {
+      return Some((unique.len(), total));
+    }
+  }
+  (total > 100 && unique.len() * 5 < total).then_some((unique.len(), total))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_check_content_reasons() {
+    let guard = IndexGuard::default();
+
+    let minified = format!("var a={};\n", "x".repeat(20_000));
+    assert_eq!(
+      guard.check_content("app.min.js", &minified, true).map(|s| s.reason),
+      Some(SkipReason::Minified)
+    );
+    assert!(
+      guard.check_content("notes.md", &minified, false).is_none(),
+      "documents skip the minified check"
+    );
+
+    assert_eq!(
+      guard.check_content("logo.rs", "fn \0\0\0", true).map(|s| s.reason),
+      Some(SkipReason::Binary)
+    );
+
+    let mut data = String::from("{\"data\": [\n");
+    for _ in 0..600 {
+      data.push_str("  {\"id\": 1, \"name\": \"test\", \"value\": 12345},\n");
+    }
+    data.push_str("]}");
+    assert_eq!(
+      guard.check_content("data.json", &data, true).map(|s| s.reason),
+      Some(SkipReason::Repetitive)
+    );
+
+    let mut source = String::new();
+    for i in 0..600 {
+      source.push_str(&format!("pub fn function_{}() {{ println!(\"{}\"); }}\n", i, i * 7));
+    }
+    assert!(
+      guard.check_content("large.rs", &source, true).is_none(),
+      "diverse code is indexed"
+    );
+  }
+
+  #[test]
+  fn test_limits_follow_config() {
+    let config = IndexConfig {
+      max_file_size: 100,
+      max_chunks_per_file: 2,
+      ..IndexConfig::default()
+    };
+    let guard = IndexGuard::from_config(&config);
+
+    assert_eq!(
+      guard.check_size("big.rs", 101).map(|s| s.reason),
+      Some(SkipReason::TooLarge)
+    );
+    assert!(guard.check_size("ok.rs", 100).is_none());
+    assert_eq!(
+      guard.check_chunks("gen.rs", 3).map(|s| s.reason),
+      Some(SkipReason::TooManyChunks)
+    );
+
+    let relaxed = IndexGuard::from_config(&IndexConfig {
+      max_chunks_per_file: 0,
+      skip_minified: false,
+      ..IndexConfig::default()
+    });
+    assert!(relaxed.check_chunks("gen.rs", 100_000).is_none(), "0 means no limit");
+    let minified = "x".repeat(20_000);
+    assert!(
+      relaxed.check_content("app.min.js", &minified, true).is_none(),
+      "minified check disabled"
+    );
+  }
+}
@@ -9,22 +9,47 @@
 //! Indexer
 //!   ├── Code files (.rs, .ts, .py, etc.) → AST-aware chunking via tree-sitter
 //!   └── Document files (.md, .txt, etc.) → Sentence-aware text chunking
+//!         └── Rich documents (.html, .pdf) → Text extraction, docs ingestion only
 //! ```
 
 pub mod code;
-
-use std::{collections::HashMap, path::Path};
+pub mod depth;
+pub mod encoding;
+pub mod extract;
+pub mod generated;
+pub mod guard;
+
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  path::Path,
+  sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+  },
+};
 
 use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
 use uuid::Uuid;
 
+use super::redact::Redactor;
+
 pub use self::code::chunker::Chunker;
+use self::{
+  depth::{DepthRules, IndexDepth},
+  extract::{DocumentFormat, html_to_text, is_rich_document_extension},
+  guard::IndexGuard,
+};
 use crate::{
-  db::ProjectDb,
+  db::{ProjectDb, SkippedFile},
   domain::{
     code::{CodeChunk, Language},
     document::{ChunkParams, DocumentChunk, DocumentId, DocumentSource, chunk_text},
+    git::GitHead,
+    path::relative_path,
   },
+  ipc::types::code::CodeReindexMetrics,
 };
 
 #[cfg(feature = "statm")]
@@ -47,6 +72,8 @@ async fn get_rss_mb() -> f64 {
 pub enum FileIndexError {
   #[error("io error: {0}")]
   IoError(String),
+  #[error("text extraction failed: {0}")]
+  Extraction(String),
 }
 
 impl From<std::io::Error> for FileIndexError {
@@ -88,6 +115,14 @@ impl Chunk {
       Chunk::Document(_) => "", // Documents use content_hash at chunk level
     }
   }
+
+  /// Record the git checkout a code chunk was indexed from
+  pub fn set_git_head(&mut self, head: &GitHead) {
+    if let Chunk::Code(c) = self {
+      c.git_branch = head.branch.clone();
+      c.git_commit = head.commit.clone();
+    }
+  }
 }
 
 // ============================================================================
@@ -105,9 +140,70 @@ pub enum FileMetadata {
     relative_path: String,
     title: String,
     project_id: Uuid,
+    format: DocumentFormat,
   },
 }
 
+// ============================================================================
+// Reindex Statistics
+// ============================================================================
+
+/// Embedding reuse counters, shared by all clones of an [`Indexer`]
+#[derive(Debug, Default)]
+pub struct ReindexStats {
+  files: AtomicU64,
+  incremental_files: AtomicU64,
+  chunks_embedded: AtomicU64,
+  embeddings_reused: AtomicU64,
+  ast_unchanged_chunks: AtomicU64,
+}
+
+impl ReindexStats {
+  /// Record the embedding decisions for one parsed file
+  pub fn record_file(&self, embedded: usize, reused: usize, ast_unchanged: Option<usize>) {
+    self.files.fetch_add(1, Ordering::Relaxed);
+    self.chunks_embedded.fetch_add(embedded as u64, Ordering::Relaxed);
+    self.embeddings_reused.fetch_add(reused as u64, Ordering::Relaxed);
+    if let Some(unchanged) = ast_unchanged {
+      self.incremental_files.fetch_add(1, Ordering::Relaxed);
+      self.ast_unchanged_chunks.fetch_add(unchanged as u64, Ordering::Relaxed);
+    }
+  }
+
+  pub fn snapshot(&self) -> CodeReindexMetrics {
+    CodeReindexMetrics {
+      files: self.files.load(Ordering::Relaxed),
+      incremental_files: self.incremental_files.load(Ordering::Relaxed),
+      chunks_embedded: self.chunks_embedded.load(Ordering::Relaxed),
+      embeddings_reused: self.embeddings_reused.load(Ordering::Relaxed),
+      ast_unchanged_chunks: self.ast_unchanged_chunks.load(Ordering::Relaxed),
+    }
+  }
+}
+
+/// Which chunks of a parsed file go to the embedder
+#[derive(Debug, Default)]
+pub struct EmbeddingSelection {
+  /// Indexes of the chunks to embed
+  pub needs_embedding: Vec<usize>,
+  /// Stored embeddings the other chunks keep, by cache key
+  pub reusable: HashMap<String, Vec<f32>>,
+  /// Chunks that keep a stored embedding
+  pub reused: usize,
+  /// Chunks outside the changed lines of an edit
+  pub ast_unchanged: usize,
+}
+
+/// Whether a chunk spans any of the changed line ranges of an edit
+pub fn overlaps_changed_lines(chunk: &Chunk, ranges: &[(u32, u32)]) -> bool {
+  match chunk {
+    Chunk::Code(c) => ranges
+      .iter()
+      .any(|&(start, end)| start <= c.end_line && end >= c.start_line),
+    Chunk::Document(_) => true,
+  }
+}
+
 // ============================================================================
 // Unified Indexer
 // ============================================================================
@@ -124,6 +220,16 @@ pub struct Indexer {
   chunk_params: ChunkParams,
   /// Project ID for document chunks
   project_id: Uuid,
+  /// Treat HTML and PDF files as documents (docs ingestion only)
+  rich_documents: bool,
+  /// Size, binary, minified and chunk count limits
+  guard: IndexGuard,
+  /// Which files are indexed shallowly
+  depth: DepthRules,
+  /// Secrets scrubbed from chunks before embedding
+  redactor: Redactor,
+  /// Embedding reuse counters (shared across clones)
+  stats: Arc<ReindexStats>,
 }
 
 impl Indexer {
@@ -133,6 +239,90 @@ impl Indexer {
       chunker: Chunker::default(),
       chunk_params: ChunkParams::default(),
       project_id,
+      rich_documents: false,
+      guard: IndexGuard::default(),
+      depth: DepthRules::default(),
+      redactor: Redactor::default(),
+      stats: Arc::new(ReindexStats::default()),
+    }
+  }
+
+  /// Create an indexer for document ingestion.
+  ///
+  /// HTML and PDF files are indexed as documents with their text extracted.
+  /// HTML is otherwise indexed as code.
+  pub fn for_documents(project_id: Uuid) -> Self {
+    Self {
+      rich_documents: true,
+      ..Self::new(project_id)
+    }
+  }
+
+  /// Apply the configured indexing guard rails
+  pub fn with_guard(mut self, guard: IndexGuard) -> Self {
+    self.guard = guard;
+    self
+  }
+
+  /// Limits files must stay within to be indexed
+  pub fn guard(&self) -> &IndexGuard {
+    &self.guard
+  }
+
+  /// Apply the configured shallow and deep paths
+  pub fn with_depth(mut self, depth: DepthRules) -> Self {
+    self.depth = depth;
+    self
+  }
+
+  /// Replace secrets in chunk content with placeholders
+  pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+    self.redactor = redactor;
+    self
+  }
+
+  /// How deeply to index a file. Documents are always indexed in full.
+  pub fn depth(&self, metadata: &FileMetadata) -> IndexDepth {
+    match metadata {
+      FileMetadata::Code { relative_path, .. } => self.depth.depth(relative_path),
+      FileMetadata::Document { .. } => IndexDepth::Full,
+    }
+  }
+
+  /// Reduce chunks of a shallow file to its definitions.
+  ///
+  /// The definitions keep their symbols for go-to-definition, but lose their
+  /// embedding text and calls so neither search nor the call graph picks
+  /// them up.
+  pub fn shallow_chunks(chunks: Vec<Chunk>) -> Vec<Chunk> {
+    chunks
+      .into_iter()
+      .filter_map(|chunk| match chunk {
+        Chunk::Code(mut c) if c.definition_name.is_some() => {
+          c.shallow = true;
+          c.embedding_text = None;
+          c.calls.clear();
+          c.callee_count = 0;
+          Some(Chunk::Code(c))
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Record a file the guard refused.
+  ///
+  /// A failed write is logged rather than failing the file, which is skipped
+  /// either way.
+  pub async fn record_skipped(&self, db: &ProjectDb, skipped: SkippedFile) {
+    debug!(
+      file = %skipped.file_path,
+      reason = skipped.reason.as_str(),
+      detail = %skipped.detail,
+      "Skipping file"
+    );
+    if let Err(e) = db.save_skipped_files(std::slice::from_ref(&skipped)).await {
+      warn!(file = %skipped.file_path, error = %e, "Failed to record skipped file");
     }
   }
 
@@ -158,15 +348,16 @@ impl Indexer {
   /// Scan a file and extract metadata. Returns None if file type is not supported.
   pub fn scan_file(&self, path: &Path, root: &Path) -> Option<FileMetadata> {
     let extension = path.extension()?.to_str()?;
-    let relative_path = path.strip_prefix(root).ok()?.to_string_lossy().to_string();
+    let relative_path = relative_path(path, root)?;
 
     // Check if it's a document file
-    if is_document_extension(extension) {
+    if is_document_extension(extension) || (self.rich_documents && is_rich_document_extension(extension)) {
       let title = Self::extract_title(path);
       return Some(FileMetadata::Document {
         relative_path,
         title,
         project_id: self.project_id,
+        format: DocumentFormat::from_extension(extension),
       });
     }
 
@@ -182,6 +373,61 @@ impl Indexer {
     None
   }
 
+  /// Embedding reuse counters for files chunked by this indexer and its clones
+  pub fn stats(&self) -> Arc<ReindexStats> {
+    self.stats.clone()
+  }
+
+  /// Lines whose syntax tree changed since `old_content`, for code files.
+  ///
+  /// Call right before [`chunk_file`](Self::chunk_file) with the same content so
+  /// chunking reuses the incrementally parsed tree.
+  pub fn changed_lines(
+    &mut self,
+    content: &str,
+    metadata: &FileMetadata,
+    old_content: &str,
+  ) -> Option<Vec<(u32, u32)>> {
+    match metadata {
+      FileMetadata::Code { language, .. } => self.chunker.changed_lines(content, old_content, *language),
+      FileMetadata::Document { .. } => None,
+    }
+  }
+
+  /// Decide which chunks need new embeddings.
+  ///
+  /// With `changed_lines` from an incremental parse the edit decides: chunks
+  /// overlapping a changed range are re-embedded and the rest keep the vector
+  /// stored for their content. Without a diff, any chunk whose cache key has
+  /// a stored vector reuses it.
+  pub fn select_for_embedding(
+    &self,
+    chunks: &[Chunk],
+    existing: &HashMap<String, Vec<f32>>,
+    changed_lines: Option<&[(u32, u32)]>,
+  ) -> EmbeddingSelection {
+    let mut selection = EmbeddingSelection::default();
+    for (idx, chunk) in chunks.iter().enumerate() {
+      let edited = changed_lines.map(|ranges| overlaps_changed_lines(chunk, ranges));
+      if edited == Some(false) {
+        selection.ast_unchanged += 1;
+      }
+
+      let stored = self
+        .cache_key(chunk)
+        .filter(|_| edited != Some(true))
+        .and_then(|key| existing.get(&key).map(|vector| (key, vector)));
+      match stored {
+        Some((key, vector)) => {
+          selection.reusable.insert(key, vector.clone());
+          selection.reused += 1;
+        }
+        None => selection.needs_embedding.push(idx),
+      }
+    }
+    selection
+  }
+
   /// Chunk file content based on its type
   pub fn chunk_file(
     &mut self,
@@ -195,17 +441,32 @@ impl Indexer {
         relative_path,
       } => {
         let file_hash = Self::compute_file_hash(content);
+        let generated = generated::is_generated(relative_path, content);
         let chunks = self
           .chunker
           .chunk(content, relative_path, *language, &file_hash, old_content);
-        Ok(chunks.into_iter().map(Chunk::Code).collect())
+        Ok(
+          chunks
+            .into_iter()
+            .map(|mut c| {
+              c.generated = generated;
+              self.redact_code(&mut c);
+              Chunk::Code(c)
+            })
+            .collect(),
+        )
       }
       FileMetadata::Document {
         relative_path,
         title,
         project_id,
+        format,
       } => {
-        let raw_chunks = chunk_text(content, &self.chunk_params);
+        let text = match format {
+          DocumentFormat::Html => Cow::Owned(html_to_text(content)),
+          DocumentFormat::Text | DocumentFormat::Pdf => Cow::Borrowed(content),
+        };
+        let raw_chunks = chunk_text(&text, &self.chunk_params);
         let total_chunks = raw_chunks.len();
         let document_id = DocumentId::new();
 
@@ -216,7 +477,7 @@ impl Indexer {
             Chunk::Document(DocumentChunk::new(
               document_id,
               *project_id,
-              chunk_content,
+              self.redactor.redact_string(chunk_content),
               title.clone(),
               relative_path.clone(),
               DocumentSource::File,
@@ -232,6 +493,17 @@ impl Indexer {
     }
   }
 
+  /// Scrub secrets from the stored and embedded text of a code chunk
+  fn redact_code(&self, chunk: &mut CodeChunk) {
+    if self.redactor.is_empty() {
+      return;
+    }
+    chunk.content = self.redactor.redact_string(std::mem::take(&mut chunk.content));
+    for text in [&mut chunk.embedding_text, &mut chunk.docstring].into_iter().flatten() {
+      *text = self.redactor.redact_string(std::mem::take(text));
+    }
+  }
+
   /// Prepare text for embedding
   pub fn prepare_embedding_text(&self, chunk: &Chunk) -> String {
     match chunk {
@@ -488,6 +760,45 @@ mod tests {
     assert!(metadata.is_none());
   }
 
+  #[test]
+  fn test_indexer_rich_documents_only_for_ingestion() {
+    let root = PathBuf::from("/project");
+    let html = PathBuf::from("/project/vendor/api.html");
+    let pdf = PathBuf::from("/project/design/spec.pdf");
+
+    let indexer = Indexer::new(test_project_id());
+    assert!(
+      !matches!(indexer.scan_file(&html, &root), Some(FileMetadata::Document { .. })),
+      "HTML should not be a document during code indexing"
+    );
+    assert!(
+      indexer.scan_file(&pdf, &root).is_none(),
+      "PDF should be ignored during code indexing"
+    );
+
+    let indexer = Indexer::for_documents(test_project_id());
+    assert!(
+      matches!(
+        indexer.scan_file(&html, &root),
+        Some(FileMetadata::Document {
+          format: DocumentFormat::Html,
+          ..
+        })
+      ),
+      "HTML should be an HTML document during ingestion"
+    );
+    assert!(
+      matches!(
+        indexer.scan_file(&pdf, &root),
+        Some(FileMetadata::Document {
+          format: DocumentFormat::Pdf,
+          ..
+        })
+      ),
+      "PDF should be a PDF document during ingestion"
+    );
+  }
+
   #[test]
   fn test_indexer_chunk_code_file() {
     let mut indexer = Indexer::new(test_project_id());
@@ -515,6 +826,165 @@ fn goodbye() {
     }
   }
 
+  #[test]
+  fn test_shallow_chunks_keep_definitions() {
+    let mut indexer = Indexer::new(test_project_id()).with_depth(DepthRules::new(
+      Path::new("/repo"),
+      &crate::config::IndexConfig::default(),
+    ));
+    let metadata = FileMetadata::Code {
+      language: Language::Rust,
+      relative_path: "third_party/greet/src/lib.rs".to_string(),
+    };
+    assert_eq!(indexer.depth(&metadata), IndexDepth::Shallow);
+
+    let content = r#"
+use std::fmt;
+
+pub fn hello() {
+    println!("Hello, {}!", name());
+}
+"#;
+    let chunks = Indexer::shallow_chunks(indexer.chunk_file(content, &metadata, None).unwrap());
+    assert!(!chunks.is_empty());
+    for chunk in &chunks {
+      let Chunk::Code(c) = chunk else {
+        panic!("expected a code chunk");
+      };
+      assert!(c.shallow);
+      assert!(c.definition_name.is_some(), "only definitions are kept");
+      assert!(c.embedding_text.is_none(), "shallow chunks are not searchable");
+      assert!(c.calls.is_empty(), "shallow chunks stay out of the call graph");
+    }
+  }
+
+  #[test]
+  fn test_generated_files_tag_chunks() {
+    let mut indexer = Indexer::new(test_project_id());
+    let metadata = FileMetadata::Code {
+      language: Language::Rust,
+      relative_path: "src/bindings.rs".to_string(),
+    };
+
+    let content = "// @generated by bindgen\n\npub fn raw_init() -> i32 {\n    0\n}\n";
+    let chunks = indexer.chunk_file(content, &metadata, None).unwrap();
+    assert!(!chunks.is_empty());
+    assert!(chunks.iter().all(|c| matches!(c, Chunk::Code(c) if c.generated)));
+
+    let chunks = indexer
+      .chunk_file("pub fn init() -> i32 {\n    0\n}\n", &metadata, None)
+      .unwrap();
+    assert!(chunks.iter().all(|c| matches!(c, Chunk::Code(c) if !c.generated)));
+  }
+
+  #[test]
+  fn test_indexer_changed_lines_isolate_edited_chunk() {
+    let mut indexer = Indexer::new(test_project_id());
+    let metadata = FileMetadata::Code {
+      language: Language::Rust,
+      relative_path: "test.rs".to_string(),
+    };
+
+    let old = "fn hello() {\n    println!(\"Hello, world!\");\n}\n\nfn goodbye() {\n    println!(\"Goodbye!\");\n}\n";
+    let new = old.replace("Goodbye!", "See you later!");
+    let old_chunks = indexer.chunk_file(old, &metadata, None).unwrap();
+
+    let ranges = indexer
+      .changed_lines(&new, &metadata, old)
+      .expect("a small edit should be diffed incrementally");
+    let new_chunks = indexer.chunk_file(&new, &metadata, Some(old)).unwrap();
+
+    let code = |chunks: &[Chunk], name: &str| {
+      chunks
+        .iter()
+        .find_map(|c| match c {
+          Chunk::Code(c) if c.definition_name.as_deref() == Some(name) => Some(c.clone()),
+          _ => None,
+        })
+        .expect("definition chunk should exist")
+    };
+    let changed = |c: &CodeChunk| {
+      ranges
+        .iter()
+        .any(|&(start, end)| start <= c.end_line && end >= c.start_line)
+    };
+
+    let hello = code(&new_chunks, "hello");
+    assert!(
+      !changed(&hello),
+      "untouched function should be outside the changed lines"
+    );
+    assert_eq!(
+      hello.content_hash,
+      code(&old_chunks, "hello").content_hash,
+      "untouched function should keep its embedding cache key"
+    );
+    assert!(
+      changed(&code(&new_chunks, "goodbye")),
+      "edited function should overlap the changed lines"
+    );
+
+    let metadata = FileMetadata::Document {
+      relative_path: "test.md".to_string(),
+      title: "Test".to_string(),
+      project_id: test_project_id(),
+      format: DocumentFormat::Text,
+    };
+    assert!(
+      indexer.changed_lines("new text", &metadata, "old text").is_none(),
+      "documents have no syntax tree to diff"
+    );
+  }
+
+  #[test]
+  fn test_edit_to_one_function_reembeds_only_its_chunk() {
+    let mut indexer = Indexer::new(test_project_id());
+    let metadata = FileMetadata::Code {
+      language: Language::Rust,
+      relative_path: "test.rs".to_string(),
+    };
+
+    let old = "fn hello() {\n    println!(\"Hello, world!\");\n}\n\nfn middle() {\n    let x = 1;\n}\n\nfn goodbye() {\n    println!(\"Goodbye!\");\n}\n";
+    let new = old.replace("let x = 1;", "let x = 2;");
+    let old_chunks = indexer.chunk_file(old, &metadata, None).unwrap();
+    let existing: HashMap<String, Vec<f32>> = old_chunks
+      .iter()
+      .filter_map(|c| indexer.cache_key(c))
+      .map(|key| (key, vec![1.0; 4]))
+      .collect();
+
+    let ranges = indexer
+      .changed_lines(&new, &metadata, old)
+      .expect("a small edit should be diffed incrementally");
+    let new_chunks = indexer.chunk_file(&new, &metadata, Some(old)).unwrap();
+    let selection = indexer.select_for_embedding(&new_chunks, &existing, Some(&ranges));
+
+    let embedded: Vec<Option<&str>> = selection
+      .needs_embedding
+      .iter()
+      .map(|&idx| match &new_chunks[idx] {
+        Chunk::Code(c) => c.definition_name.as_deref(),
+        Chunk::Document(_) => None,
+      })
+      .collect();
+    assert_eq!(
+      embedded,
+      vec![Some("middle")],
+      "only the edited function is re-embedded"
+    );
+    assert_eq!(selection.reused, new_chunks.len() - 1);
+    assert_eq!(selection.ast_unchanged, new_chunks.len() - 1);
+
+    // An edit that restores the stored content is still re-embedded: the diff decides
+    let reverted = indexer.select_for_embedding(&old_chunks, &existing, Some(&ranges));
+    assert_eq!(reverted.needs_embedding.len(), 1);
+
+    // Without a diff, stored vectors are reused by content alone
+    let full = indexer.select_for_embedding(&old_chunks, &existing, None);
+    assert!(full.needs_embedding.is_empty());
+    assert_eq!(full.ast_unchanged, 0);
+  }
+
   #[test]
   fn test_indexer_chunk_document_file() {
     let mut indexer = Indexer::new(test_project_id());
@@ -522,6 +992,7 @@ fn goodbye() {
       relative_path: "test.md".to_string(),
       title: "Test".to_string(),
       project_id: test_project_id(),
+      format: DocumentFormat::Text,
     };
 
     let content = "This is a test document with some content.";
@@ -564,6 +1035,10 @@ fn goodbye() {
       content_hash: Some("hash123".to_string()),
       caller_count: 0,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     });
 
     assert_eq!(indexer.prepare_embedding_text(&code_chunk), "[ENRICHED] fn test() {}");
@@ -613,6 +1088,10 @@ fn goodbye() {
       content_hash: Some("hash123".to_string()),
       caller_count: 0,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     });
 
     assert_eq!(indexer.cache_key(&code_chunk), Some("hash123".to_string()));
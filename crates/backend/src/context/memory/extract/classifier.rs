@@ -1,8 +1,19 @@
 //! Content extraction utilities for memories.
 //!
-//! Extracts concepts, file references, and identifiers from memory content.
+//! Extracts concepts, file references, and identifiers from memory content,
+//! and applies the deterministic extraction rules from `hooks.extraction_rules`.
 
-use tracing::trace;
+use llm::ExtractedMemory;
+use regex::Regex;
+use tracing::{trace, warn};
+
+use crate::domain::{
+  config::{ExtractionRuleAction, ExtractionRuleConfig},
+  memory::MemoryType,
+};
+
+/// Confidence given to rule-captured memories; the user asked for them explicitly
+const RULE_CONFIDENCE: f32 = 0.9;
 
 /// Extract concepts from memory content
 pub fn extract_concepts(content: &str) -> Vec<String> {
@@ -99,6 +110,129 @@ pub fn extract_files(content: &str) -> Vec<String> {
   files
 }
 
+/// Extraction rules compiled from config
+#[derive(Debug, Default)]
+pub struct ExtractionRules {
+  rules: Vec<ExtractionRule>,
+}
+
+#[derive(Debug)]
+struct ExtractionRule {
+  pattern: Option<Regex>,
+  /// Lowercased
+  keywords: Vec<String>,
+  action: ExtractionRuleAction,
+  memory_type: MemoryType,
+  tags: Vec<String>,
+}
+
+impl ExtractionRule {
+  fn matches(&self, line: &str) -> bool {
+    if self.pattern.as_ref().is_some_and(|p| p.is_match(line)) {
+      return true;
+    }
+    if self.keywords.is_empty() {
+      return false;
+    }
+    let line = line.to_lowercase();
+    self.keywords.iter().any(|k| line.contains(k.as_str()))
+  }
+
+  /// Content captured from a matching line: the first regex group, or the line
+  fn captured(&self, line: &str) -> String {
+    self
+      .pattern
+      .as_ref()
+      .and_then(|p| p.captures(line))
+      .and_then(|c| c.get(1))
+      .map_or(line, |m| m.as_str())
+      .trim()
+      .to_string()
+  }
+}
+
+impl ExtractionRules {
+  /// Compile rules from config, skipping invalid and empty ones with a warning
+  pub fn from_config(rules: &[ExtractionRuleConfig]) -> Self {
+    let rules = rules
+      .iter()
+      .filter_map(|rule| {
+        let pattern = match rule.pattern.as_deref().map(Regex::new).transpose() {
+          Ok(pattern) => pattern,
+          Err(e) => {
+            warn!(pattern = ?rule.pattern, "Skipping extraction rule with invalid pattern: {}", e);
+            return None;
+          }
+        };
+        let keywords: Vec<String> = rule
+          .keywords
+          .iter()
+          .map(|k| k.trim().to_lowercase())
+          .filter(|k| !k.is_empty())
+          .collect();
+        if pattern.is_none() && keywords.is_empty() {
+          warn!("Skipping extraction rule without a pattern or keywords");
+          return None;
+        }
+
+        Some(ExtractionRule {
+          pattern,
+          keywords,
+          action: rule.action,
+          memory_type: rule.memory_type.unwrap_or(MemoryType::Codebase),
+          tags: rule.tags.clone(),
+        })
+      })
+      .collect();
+    Self { rules }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rules.is_empty()
+  }
+
+  /// Memories forced by capture rules, one per matching line.
+  ///
+  /// The first capture rule that matches a line decides its type and tags.
+  pub fn capture(&self, text: &str) -> Vec<ExtractedMemory> {
+    let mut captured: Vec<ExtractedMemory> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+      let Some(rule) = self
+        .rules
+        .iter()
+        .find(|r| r.action == ExtractionRuleAction::Capture && r.matches(line))
+      else {
+        continue;
+      };
+
+      let content = rule.captured(line);
+      if content.is_empty() || captured.iter().any(|m| m.content == content) {
+        continue;
+      }
+      captured.push(ExtractedMemory {
+        content,
+        summary: None,
+        memory_type: rule.memory_type,
+        tags: rule.tags.clone(),
+        confidence: RULE_CONFIDENCE,
+        entities: Vec::new(),
+      });
+    }
+
+    trace!(count = captured.len(), "Extraction rules captured lines");
+    captured
+  }
+
+  /// Whether a suppress rule matches any line of `content`
+  pub fn suppresses(&self, content: &str) -> bool {
+    self
+      .rules
+      .iter()
+      .filter(|r| r.action == ExtractionRuleAction::Suppress)
+      .any(|r| content.lines().any(|line| r.matches(line)))
+  }
+}
+
 fn find_backtick_content(content: &str) -> Vec<String> {
   let mut results = Vec::new();
   let mut in_backtick = false;
@@ -237,4 +371,38 @@ mod tests {
     assert!(!looks_like_file_path("hello"));
     assert!(!looks_like_file_path("a.toolongextension"));
   }
+
+  #[test]
+  fn test_extraction_rules() {
+    let rules = ExtractionRules::from_config(&[
+      ExtractionRuleConfig {
+        pattern: Some(r"^DECISION:\s*(.+)".to_string()),
+        memory_type: Some(MemoryType::Decision),
+        tags: vec!["decision-log".to_string()],
+        ..Default::default()
+      },
+      ExtractionRuleConfig {
+        keywords: vec!["Scratch".to_string()],
+        action: ExtractionRuleAction::Suppress,
+        ..Default::default()
+      },
+      ExtractionRuleConfig {
+        pattern: Some("(unclosed".to_string()),
+        ..Default::default()
+      },
+    ]);
+
+    let captured = rules.capture("Some context\nDECISION: Use Postgres for the job queue\n  DECISION:   \n");
+    assert_eq!(captured.len(), 1, "only lines with content are captured");
+    assert_eq!(captured[0].content, "Use Postgres for the job queue");
+    assert_eq!(captured[0].memory_type, MemoryType::Decision);
+    assert_eq!(captured[0].tags, vec!["decision-log".to_string()]);
+
+    assert!(
+      rules.suppresses("Notes\nthis is a SCRATCH experiment"),
+      "keywords ignore case"
+    );
+    assert!(!rules.suppresses("Use Postgres for the job queue"));
+    assert_eq!(rules.rules.len(), 2, "invalid pattern is skipped");
+  }
 }
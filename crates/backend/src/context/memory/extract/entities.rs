@@ -0,0 +1,243 @@
+//! Rule-based entity extraction.
+//!
+//! Finds the entities a memory names without an LLM: files from path-like
+//! words, technologies and services from lists of well-known names, services
+//! named as "X API" or "X service", and people from @mentions.
+
+use llm::{EntityType, ExtractedEntity};
+use tracing::trace;
+
+use super::classifier::extract_files;
+
+/// Well-known languages, libraries and tools, as (lowercased spelling, name)
+const TECHNOLOGIES: &[(&str, &str)] = &[
+  ("rust", "Rust"),
+  ("python", "Python"),
+  ("typescript", "TypeScript"),
+  ("javascript", "JavaScript"),
+  ("java", "Java"),
+  ("kotlin", "Kotlin"),
+  ("swift", "Swift"),
+  ("ruby", "Ruby"),
+  ("php", "PHP"),
+  ("elixir", "Elixir"),
+  ("haskell", "Haskell"),
+  ("scala", "Scala"),
+  ("golang", "Go"),
+  ("react", "React"),
+  ("vue", "Vue"),
+  ("svelte", "Svelte"),
+  ("angular", "Angular"),
+  ("next.js", "Next.js"),
+  ("nextjs", "Next.js"),
+  ("node.js", "Node.js"),
+  ("nodejs", "Node.js"),
+  ("deno", "Deno"),
+  ("django", "Django"),
+  ("flask", "Flask"),
+  ("fastapi", "FastAPI"),
+  ("rails", "Rails"),
+  ("tokio", "Tokio"),
+  ("axum", "Axum"),
+  ("serde", "Serde"),
+  ("lancedb", "LanceDB"),
+  ("postgres", "PostgreSQL"),
+  ("postgresql", "PostgreSQL"),
+  ("mysql", "MySQL"),
+  ("sqlite", "SQLite"),
+  ("redis", "Redis"),
+  ("mongodb", "MongoDB"),
+  ("kafka", "Kafka"),
+  ("rabbitmq", "RabbitMQ"),
+  ("elasticsearch", "Elasticsearch"),
+  ("docker", "Docker"),
+  ("kubernetes", "Kubernetes"),
+  ("k8s", "Kubernetes"),
+  ("terraform", "Terraform"),
+  ("graphql", "GraphQL"),
+  ("grpc", "gRPC"),
+  ("webpack", "Webpack"),
+  ("vite", "Vite"),
+  ("tailwind", "Tailwind"),
+  ("pytest", "pytest"),
+  ("jest", "Jest"),
+  ("vitest", "Vitest"),
+  ("cargo", "Cargo"),
+  ("npm", "npm"),
+  ("pnpm", "pnpm"),
+  ("yarn", "Yarn"),
+  ("nginx", "nginx"),
+  ("ollama", "Ollama"),
+  ("tree-sitter", "tree-sitter"),
+];
+
+/// Well-known hosted services and APIs, as (lowercased spelling, name)
+const SERVICES: &[(&str, &str)] = &[
+  ("github", "GitHub"),
+  ("gitlab", "GitLab"),
+  ("stripe", "Stripe"),
+  ("aws", "AWS"),
+  ("s3", "S3"),
+  ("gcp", "GCP"),
+  ("azure", "Azure"),
+  ("vercel", "Vercel"),
+  ("netlify", "Netlify"),
+  ("heroku", "Heroku"),
+  ("cloudflare", "Cloudflare"),
+  ("sentry", "Sentry"),
+  ("datadog", "Datadog"),
+  ("slack", "Slack"),
+  ("twilio", "Twilio"),
+  ("sendgrid", "SendGrid"),
+  ("auth0", "Auth0"),
+  ("okta", "Okta"),
+  ("openai", "OpenAI"),
+  ("anthropic", "Anthropic"),
+  ("openrouter", "OpenRouter"),
+  ("deepinfra", "DeepInfra"),
+  ("supabase", "Supabase"),
+  ("firebase", "Firebase"),
+  ("jira", "Jira"),
+];
+
+/// Names that are also plain English words, only matched when capitalized
+const COMMON_WORDS: &[&str] = &["rust", "swift", "react", "rails", "flask", "jest", "slack"];
+
+/// Words ending a service name ("the Billing API", "Payments service")
+const SERVICE_SUFFIXES: &[&str] = &["api", "service", "server"];
+
+/// Capitalized words that start sentences rather than name a service
+const NOT_NAMES: &[&str] = &[
+  "The", "This", "That", "A", "An", "Our", "Their", "Its", "Each", "Every", "New", "Any",
+];
+
+/// Decorators and annotations that look like @mentions
+const NOT_HANDLES: &[&str] = &[
+  "property",
+  "staticmethod",
+  "classmethod",
+  "dataclass",
+  "override",
+  "deprecated",
+  "test",
+  "param",
+  "returns",
+];
+
+/// Extract the entities named in memory content
+pub fn extract_entities(content: &str) -> Vec<ExtractedEntity> {
+  trace!(text_len = content.len(), "Extracting entities");
+
+  let mut entities = Vec::new();
+  let mut push = |name: &str, entity_type: EntityType| {
+    let entity = ExtractedEntity {
+      name: name.to_string(),
+      entity_type,
+    };
+    if !entities.contains(&entity) {
+      entities.push(entity);
+    }
+  };
+
+  let words: Vec<&str> = content
+    .split_whitespace()
+    .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '.' && c != '-' && c != '@' && c != '/'))
+    .map(|w| w.trim_end_matches('.'))
+    .filter(|w| !w.is_empty())
+    .collect();
+
+  for (i, word) in words.iter().enumerate() {
+    let lower = word.to_lowercase();
+    let known = !COMMON_WORDS.contains(&lower.as_str()) || word.starts_with(|c: char| c.is_uppercase());
+    if let Some((_, name)) = TECHNOLOGIES.iter().find(|(spelling, _)| *spelling == lower && known) {
+      push(name, EntityType::Technology);
+    } else if let Some((_, name)) = SERVICES.iter().find(|(spelling, _)| *spelling == lower && known) {
+      push(name, EntityType::Service);
+    } else if let Some(handle) = word.strip_prefix('@')
+      && is_handle(handle)
+      && !in_backticks(content, word)
+    {
+      push(handle, EntityType::Person);
+    } else if SERVICE_SUFFIXES.contains(&lower.as_str()) && i > 0 && is_service_name(words[i - 1]) {
+      push(&format!("{} {}", words[i - 1], word), EntityType::Service);
+    }
+  }
+
+  for file in extract_files(content) {
+    // "Node.js" and friends look like files
+    if !TECHNOLOGIES
+      .iter()
+      .any(|(spelling, _)| *spelling == file.to_lowercase())
+    {
+      push(&file, EntityType::File);
+    }
+  }
+
+  trace!(count = entities.len(), "Entities extracted");
+  entities
+}
+
+fn is_handle(s: &str) -> bool {
+  (2..=39).contains(&s.len())
+    && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    && !s.starts_with('-')
+    && !NOT_HANDLES.contains(&s.to_lowercase().as_str())
+}
+
+fn is_service_name(s: &str) -> bool {
+  s.len() >= 2
+    && s.chars().next().is_some_and(|c| c.is_uppercase())
+    && s.chars().all(|c| c.is_alphanumeric())
+    && !NOT_NAMES.contains(&s)
+}
+
+/// Whether `word` only occurs inside backtick code spans
+fn in_backticks(content: &str, word: &str) -> bool {
+  content
+    .split('`')
+    .enumerate()
+    .filter(|(_, part)| part.contains(word))
+    .all(|(i, _)| i % 2 == 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn names(content: &str, entity_type: EntityType) -> Vec<String> {
+    extract_entities(content)
+      .into_iter()
+      .filter(|e| e.entity_type == entity_type)
+      .map(|e| e.name)
+      .collect()
+  }
+
+  #[test]
+  fn test_extract_entities_by_type() {
+    let content = "@alice moved the Billing API from postgres to Redis; see src/billing.rs and Stripe webhooks.";
+
+    assert_eq!(names(content, EntityType::Person), vec!["alice"]);
+    assert_eq!(names(content, EntityType::Technology), vec!["PostgreSQL", "Redis"]);
+    assert_eq!(names(content, EntityType::Service), vec!["Billing API", "Stripe"]);
+    assert_eq!(names(content, EntityType::File), vec!["src/billing.rs"]);
+  }
+
+  #[test]
+  fn test_extract_entities_skips_lookalikes() {
+    let content = "The API wraps `@cache` and @property getters so views react to changes; Node.js runs it.";
+
+    assert!(
+      names(content, EntityType::Person).is_empty(),
+      "decorators are not people"
+    );
+    assert!(
+      names(content, EntityType::Service).is_empty(),
+      "'The API' names no service"
+    );
+    assert!(
+      names(content, EntityType::File).is_empty(),
+      "technologies spelled like files are not files"
+    );
+    assert_eq!(names(content, EntityType::Technology), vec!["Node.js"]);
+  }
+}
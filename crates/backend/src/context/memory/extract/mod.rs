@@ -1,3 +1,4 @@
 pub mod classifier;
 pub mod decay;
 pub mod dedup;
+pub mod entities;
@@ -1,2 +1,3 @@
 pub mod files;
 pub mod memory;
+pub mod redact;
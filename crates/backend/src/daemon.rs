@@ -24,10 +24,14 @@
 //! 4. Create lifecycle trackers (activity, sessions)
 //! 5. Create `Server` with all dependencies (no two-phase init)
 //! 6. Spawn `Scheduler` for background tasks
-//! 7. Run server until cancelled
-//! 8. Graceful shutdown: cancel children, wait for tasks, shutdown projects
+//! 7. Replay hook events spooled while the daemon was down
+//! 8. Run server until cancelled
+//! 9. Graceful shutdown: cancel children, wait for tasks, shutdown projects
 
-use std::{path::PathBuf, sync::Arc};
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
 
 use tokio::signal;
 use tokio_util::sync::CancellationToken;
@@ -39,13 +43,15 @@ use crate::{
   actor::{
     IdleShutdownConfig, ProjectRouter, Scheduler, SchedulerConfig,
     lifecycle::{activity::KeepAlive, session::SessionTracker},
+    message::{ProjectActorPayload, ProjectActorResponse},
   },
-  dirs,
+  data_dir, dirs,
   domain::config::{Config, DaemonSettings, RerankerProviderKind},
-  embedding::EmbeddingProvider,
-  ipc::{Client, IpcError},
+  embedding::{EmbeddingProvider, PersistentCacheProvider},
+  ipc::{Client, IpcError, RequestData, spool},
   rerank::{DeepInfraReranker, RerankerProvider},
   server::{DaemonState, Server, ServerConfig},
+  usage::UsageTracker,
 };
 
 // ============================================================================
@@ -151,7 +157,9 @@ impl Daemon {
   /// # Errors
   ///
   /// Returns an error if:
-  /// - The daemon fails to start within the timeout
+  /// - The data dir holds other data versions but none for this build
+  /// - The daemon fails to start within the timeout, e.g. because its
+  ///   legacy data import failed
   /// - Connection to the daemon fails after startup
   pub async fn connect_or_start(cwd: PathBuf) -> Result<Client, IpcError> {
     let running = dirs::is_daemon_running();
@@ -160,6 +168,11 @@ impl Daemon {
       return Client::connect(cwd).await;
     }
 
+    // A daemon that refuses its data dir exits silently, so check before spawning
+    data_dir::check(&dirs::default_data_root())
+      .await
+      .map_err(|e| IpcError::Connection(e.to_string()))?;
+
     info!("Daemon is not running, starting in background...");
     let pid = Self::spawn_background().await?;
     debug!("Spawned daemon with PID {}", pid);
@@ -183,6 +196,14 @@ impl Daemon {
       tokio::time::sleep(delay).await;
     }
 
+    let dir = data_dir::version_dir(&dirs::default_data_root(), data_dir::DATA_VERSION);
+    if let Some(err) = data_dir::legacy::last_error(&dir).await {
+      return Err(IpcError::Connection(format!(
+        "Importing unversioned data failed: {}\nThe daemon retries on its next start, or run `ccengram data import` to retry now.",
+        err.trim()
+      )));
+    }
+
     Err(IpcError::Connection("Failed to connect to daemon after startup".into()))
   }
 
@@ -282,6 +303,26 @@ impl Daemon {
     info!("Socket: {:?}", self.runtime_config.socket_path);
     info!("Data dir: {:?}", self.runtime_config.data_dir);
 
+    // Refuse to start on data this build cannot safely open
+    if let Err(e) = data_dir::open(&dirs::default_data_root()).await {
+      error!("{}", e);
+      return;
+    }
+
+    // Strict local mode: refuse to start rather than send content to a cloud provider
+    let strict_local = self.runtime_config.config.daemon.strict_local;
+    if strict_local {
+      let network = self.runtime_config.config.network_providers();
+      if !network.is_empty() {
+        error!(
+          "Strict local mode refuses network providers ({}). Configure local providers or set daemon.strict_local = false",
+          network.join(", ")
+        );
+        return;
+      }
+      info!("Strict local mode: network providers are refused");
+    }
+
     // Master cancellation token - propagates to all children
     let cancel = CancellationToken::new();
 
@@ -291,6 +332,51 @@ impl Daemon {
       panic!("Failed to create embedding provider");
     };
 
+    // Convert data left by a build from before versioning, once
+    let data_root = dirs::default_data_root();
+    if matches!(data_dir::legacy::needs_import(&data_root).await, Ok(true)) {
+      info!("Unversioned data found, importing it into this version");
+      match data_dir::legacy::import(&data_root, &self.runtime_config.config, Some(embedding.clone()), false).await {
+        Ok(report) => info!(
+          projects = report.projects.len(),
+          rows = report.rows(),
+          reembedded = report.reembedded(),
+          "Legacy data imported; details in {}",
+          data_dir::legacy::REPORT_FILE
+        ),
+        Err(e) => {
+          error!("Legacy data import failed, it will be retried on next start: {}", e);
+          let dir = data_dir::version_dir(&data_root, data_dir::DATA_VERSION);
+          if let Err(write_err) = data_dir::legacy::record_error(&dir, &e).await {
+            warn!(err = %write_err, "Failed to record legacy import error");
+          }
+          return;
+        }
+      }
+    }
+
+    // Persist document embeddings so re-indexing reuses them across restarts
+    let embedding_cache = if self.runtime_config.config.embedding.persistent_cache {
+      match PersistentCacheProvider::open_cache(&self.runtime_config.data_dir, embedding.dimensions()).await {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+          warn!(err = %e, "Failed to open embedding cache, continuing without it");
+          None
+        }
+      }
+    } else {
+      None
+    };
+
+    // Record token usage and cost of every provider call
+    let usage = match UsageTracker::open(&self.runtime_config.data_dir, &self.runtime_config.config).await {
+      Ok(tracker) => Some(Arc::new(tracker)),
+      Err(e) => {
+        warn!(err = %e, "Failed to open usage log, usage tracking disabled");
+        None
+      }
+    };
+
     info!(
       "Embedding provider: {} ({}, {} dims)",
       embedding.name(),
@@ -345,17 +431,52 @@ impl Daemon {
       None
     };
 
+    // Create LLM worker pool for memory extraction (shared, bounded concurrency)
+    let llm_config = &self.runtime_config.config.llm;
+    let llm: Option<Box<dyn llm::LlmProvider>> = match llm::create_provider() {
+      // The Claude CLI sends prompts to Anthropic's API
+      Ok(_) if strict_local => {
+        info!("Strict local mode: LLM extraction disabled");
+        None
+      }
+      Ok(provider) => {
+        info!(
+          "LLM provider: {} (max_concurrency: {}, extraction model: {})",
+          provider.name(),
+          llm_config.max_concurrency,
+          llm_config.tasks.extraction.model
+        );
+        Some(Box::new(llm::WorkerPool::new(
+          provider,
+          llm_config.max_concurrency,
+          llm_config.tasks.routes(),
+        )))
+      }
+      Err(e) => {
+        debug!("LLM provider not available: {}", e);
+        None
+      }
+    };
+
     // Create daemon-level settings to pass to project actors
     let daemon_settings = DaemonSettings::from_config(&self.runtime_config.config);
 
     // Create the project router (replaces ProjectRegistry)
-    let router = Arc::new(ProjectRouter::new(
+    let mut router = ProjectRouter::new(
       self.runtime_config.data_dir.clone(),
       embedding,
       reranker,
+      llm,
       daemon_settings,
       cancel.child_token(),
-    ));
+    );
+    if let Some(cache) = embedding_cache {
+      router = router.with_embedding_cache(cache);
+    }
+    if let Some(usage) = usage {
+      router = router.with_usage(usage);
+    }
+    let router = Arc::new(router);
 
     // Create lifecycle trackers
     let activity = Arc::new(KeepAlive::new());
@@ -402,6 +523,7 @@ impl Daemon {
     let scheduler_config = SchedulerConfig {
       decay: self.runtime_config.config.decay.clone(),
       daemon: self.runtime_config.config.daemon.clone(),
+      index: self.runtime_config.config.index.clone(),
       idle_shutdown,
     };
 
@@ -418,6 +540,16 @@ impl Daemon {
       self.runtime_config.config.daemon.log_retention_days
     );
 
+    // Replay hook events captured while the daemon was down
+    let replay_handle = {
+      let router = Arc::clone(&router);
+      let spool_path = self.runtime_config.data_dir.join(spool::SPOOL_FILE_NAME);
+      let cancel = cancel.clone();
+      tokio::spawn(async move {
+        replay_hook_spool(&router, &spool_path, cancel).await;
+      })
+    };
+
     // Handle ctrl-c gracefully
     let cancel_for_signal = cancel.clone();
     tokio::spawn(async move {
@@ -438,8 +570,87 @@ impl Daemon {
     cancel.cancel();
 
     let _ = scheduler_handle.await;
+    let _ = replay_handle.await;
     router.shutdown_all().await;
+    if let Some(usage) = router.usage() {
+      usage.flush().await;
+    }
 
     info!("Daemon shutdown complete");
   }
 }
+
+/// Replay hook events spooled by the CLI while the daemon was unreachable.
+///
+/// Events go through the normal hook pipeline of their project actor in capture
+/// order. Anything left unprocessed at shutdown is written back to the spool.
+/// The drained events stay on disk until then, so a crash mid-replay replays
+/// them again on the next start.
+async fn replay_hook_spool(router: &ProjectRouter, spool_path: &Path, cancel: CancellationToken) {
+  let mut drained = match spool::drain(spool_path).await {
+    Ok(drained) => drained,
+    Err(e) => {
+      warn!("Failed to read hook spool: {}", e);
+      return;
+    }
+  };
+
+  if drained.entries.is_empty() {
+    if let Err(e) = drained.finish().await {
+      warn!("Failed to clear drained hook spool: {}", e);
+    }
+    return;
+  }
+  let entries = std::mem::take(&mut drained.entries);
+
+  info!("Replaying {} spooled hook events", entries.len());
+
+  let mut replayed = 0;
+  let mut entries = entries.into_iter().enumerate();
+  for (i, entry) in entries.by_ref() {
+    let Some(cwd) = entry.params.cwd.clone() else {
+      warn!(hook = %entry.params.hook_name, "Spooled hook event has no cwd, skipping");
+      continue;
+    };
+
+    let handle = match router.get_or_create(Path::new(&cwd)).await {
+      Ok(h) => h,
+      Err(e) => {
+        warn!(cwd = %cwd, "Failed to get project for spooled hook event: {}", e);
+        continue;
+      }
+    };
+
+    let payload = ProjectActorPayload::Request(RequestData::Hook(entry.params));
+    match handle.request(format!("spool-{}", i), payload).await {
+      Ok(ProjectActorResponse::Error { message, .. }) => {
+        warn!(cwd = %cwd, "Spooled hook event failed: {}", message);
+      }
+      Ok(_) => replayed += 1,
+      Err(e) => warn!(cwd = %cwd, "Failed to replay spooled hook event: {}", e),
+    }
+
+    if cancel.is_cancelled() {
+      break;
+    }
+  }
+
+  let remaining: Vec<_> = entries.map(|(_, entry)| entry.params).collect();
+  if !remaining.is_empty() {
+    info!(
+      "Shutdown during replay, returning {} hook events to spool",
+      remaining.len()
+    );
+    for params in remaining {
+      if let Err(e) = spool::append(spool_path, params).await {
+        warn!("Failed to re-spool hook event, keeping the drained spool: {}", e);
+        return;
+      }
+    }
+  }
+
+  if let Err(e) = drained.finish().await {
+    warn!("Failed to clear drained hook spool: {}", e);
+  }
+  info!("Replayed {} spooled hook events", replayed);
+}
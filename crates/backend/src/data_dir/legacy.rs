@@ -0,0 +1,558 @@
+//! Import of unversioned (version 0) data.
+//!
+//! Stores written before the data directory was versioned can't be opened in
+//! place: their tables predate columns this build requires, and their vectors
+//! may come from a model of another width. Instead of copying the files, each
+//! project's tables are read row by row and written into a fresh database in
+//! this build's version directory, converted to the current schema. Vectors
+//! are kept when their width matches the configured model and recomputed
+//! otherwise.
+//!
+//! The import runs once, when the daemon first starts on a legacy root or on
+//! `ccengram data import`. What was migrated is saved next to the new data as
+//! `legacy_import.json`; a daemon whose import failed leaves the error in
+//! `legacy_import_error.txt` and retries on its next start.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use arrow_array::{RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{
+  DATA_VERSION, DataDirError, DataDirLayout, UNVERSIONED_ENTRIES, append_file, copy_recursive, version_dir,
+  write_version,
+};
+use crate::{
+  config::Config,
+  context::memory::extract::dedup::compute_hashes,
+  db::{LegacyStore, ProjectDb, legacy_vector_dim},
+  domain::project::ProjectId,
+  embedding::{EmbeddingMode, EmbeddingProvider},
+  ipc::spool::SPOOL_FILE_NAME,
+};
+
+/// Report written into the version directory after an import
+pub const REPORT_FILE: &str = "legacy_import.json";
+
+/// Error of the last failed import, in the version directory until the next attempt
+pub const ERROR_FILE: &str = "legacy_import_error.txt";
+
+/// Root entries carried over as is. The embedding cache is keyed by model
+/// width and refills on its own, so it is left behind.
+const COPIED_ENTRIES: &[&str] = &["usage", "repl_history", SPOOL_FILE_NAME];
+
+/// Rows embedded per provider call when vectors are recomputed
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// What a legacy import migrated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyImportReport {
+  pub imported_at: DateTime<Utc>,
+  /// Model vectors were recomputed with, where widths differed
+  pub embedding_model: String,
+  pub dimensions: usize,
+  pub projects: Vec<LegacyProjectReport>,
+  /// Root entries copied without conversion
+  pub copied: Vec<String>,
+}
+
+impl LegacyImportReport {
+  pub fn rows(&self) -> usize {
+    self.projects.iter().flat_map(|p| &p.tables).map(|t| t.rows).sum()
+  }
+
+  pub fn reembedded(&self) -> usize {
+    self.projects.iter().flat_map(|p| &p.tables).map(|t| t.reembedded).sum()
+  }
+}
+
+/// What was migrated for one project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyProjectReport {
+  pub id: String,
+  pub tables: Vec<LegacyTableReport>,
+  /// Legacy tables that were not migrated, with the reason
+  pub skipped: Vec<String>,
+}
+
+/// Rows migrated from one legacy table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyTableReport {
+  pub name: String,
+  pub rows: usize,
+  /// Rows whose vectors were recomputed
+  pub reembedded: usize,
+  /// Width of the legacy vectors, for tables that store them
+  pub from_dimensions: Option<usize>,
+}
+
+/// Embedding provider, created on first use so imports that keep every
+/// vector never start one
+struct Embedder<'a> {
+  config: &'a Config,
+  provider: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+impl Embedder<'_> {
+  /// Embed each text; rows without one get a zero vector
+  async fn embed(&mut self, texts: &[Option<String>], dimensions: usize) -> Result<Vec<Vec<f32>>, DataDirError> {
+    let provider = match &self.provider {
+      Some(provider) => provider.clone(),
+      None => {
+        let provider = <dyn EmbeddingProvider>::from_config(&self.config.embedding).await?;
+        self.provider = Some(provider.clone());
+        provider
+      }
+    };
+
+    let mut vectors = vec![vec![0.0; dimensions]; texts.len()];
+    let present: Vec<(usize, &str)> = texts
+      .iter()
+      .enumerate()
+      .filter_map(|(i, text)| text.as_deref().map(|t| (i, t)))
+      .collect();
+    for chunk in present.chunks(EMBED_BATCH_SIZE) {
+      let batch: Vec<&str> = chunk.iter().map(|(_, text)| *text).collect();
+      let embedded = provider.embed_batch(&batch, EmbeddingMode::Document).await?;
+      for ((row, _), vector) in chunk.iter().zip(embedded) {
+        vectors[*row] = vector;
+      }
+    }
+    Ok(vectors)
+  }
+}
+
+/// Whether `root` holds unversioned data and no data for this build
+pub async fn needs_import(root: &Path) -> Result<bool, DataDirError> {
+  Ok(DataDirLayout::scan(root).await?.needs_legacy_import())
+}
+
+/// Convert the unversioned data under `root` into this build's version.
+///
+/// `embedding` is used to recompute vectors of another width; when `None`, a
+/// provider is created from `config` if one is needed. The legacy data is
+/// left in place unless `move_data` is set. The version marker is written
+/// last, so an interrupted import starts over on the next run.
+pub async fn import(
+  root: &Path,
+  config: &Config,
+  embedding: Option<Arc<dyn EmbeddingProvider>>,
+  move_data: bool,
+) -> Result<LegacyImportReport, DataDirError> {
+  let layout = DataDirLayout::scan(root).await?;
+  if layout.has(DATA_VERSION) {
+    return Err(DataDirError::AlreadyExists(DATA_VERSION));
+  }
+  if !layout.unversioned {
+    return Err(DataDirError::MissingVersion(0));
+  }
+
+  let mut sources: Vec<PathBuf> = Vec::new();
+  for name in ["projects"].iter().chain(COPIED_ENTRIES) {
+    let path = root.join(name);
+    if tokio::fs::try_exists(&path).await? {
+      sources.push(path);
+    }
+  }
+  crate::disk::ensure_space(root, sources.iter().map(|p| crate::disk::dir_size(p)).sum())?;
+
+  let dir = version_dir(root, DATA_VERSION);
+  tokio::fs::create_dir_all(&dir).await?;
+  info!(root = %root.display(), dir = %dir.display(), "Importing legacy data");
+  if let Err(e) = tokio::fs::remove_file(dir.join(ERROR_FILE)).await
+    && e.kind() != std::io::ErrorKind::NotFound
+  {
+    return Err(e.into());
+  }
+
+  let config = Arc::new(config.clone());
+  let mut embedder = Embedder {
+    config: &config,
+    provider: embedding,
+  };
+  let mut report = LegacyImportReport {
+    imported_at: Utc::now(),
+    embedding_model: config.embedding.model.clone(),
+    dimensions: config.embedding.dimensions,
+    projects: Vec::new(),
+    copied: Vec::new(),
+  };
+
+  let projects = root.join("projects");
+  if is_dir(&projects).await {
+    let mut entries = Vec::new();
+    let mut read_dir = tokio::fs::read_dir(&projects).await?;
+    while let Some(entry) = read_dir.next_entry().await? {
+      if is_dir(&entry.path()).await {
+        entries.push(entry);
+      }
+    }
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+      let id = entry.file_name().to_string_lossy().to_string();
+      let target = dir.join("projects").join(&id);
+      let project = import_project(&entry.path(), &target, &id, &config, &mut embedder).await?;
+      report.projects.push(project);
+    }
+  }
+
+  for name in COPIED_ENTRIES {
+    let (source, target) = (root.join(name), dir.join(name));
+    if !tokio::fs::try_exists(&source).await? {
+      continue;
+    }
+    // Hooks keep spooling into the new version while the import is pending
+    if *name == SPOOL_FILE_NAME && tokio::fs::try_exists(&target).await? {
+      append_file(&source, &target).await?;
+    } else {
+      if is_dir(&target).await {
+        tokio::fs::remove_dir_all(&target).await?;
+      }
+      copy_recursive(&source, &target).await?;
+    }
+    report.copied.push(name.to_string());
+  }
+
+  let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+  tokio::fs::write(dir.join(REPORT_FILE), json).await?;
+  write_version(&dir, DATA_VERSION).await?;
+
+  if move_data {
+    for name in UNVERSIONED_ENTRIES {
+      let path = root.join(name);
+      if is_dir(&path).await {
+        tokio::fs::remove_dir_all(&path).await?;
+      } else if tokio::fs::try_exists(&path).await? {
+        tokio::fs::remove_file(&path).await?;
+      }
+    }
+  }
+
+  info!(
+    projects = report.projects.len(),
+    rows = report.rows(),
+    reembedded = report.reembedded(),
+    moved = move_data,
+    "Imported legacy data"
+  );
+  Ok(report)
+}
+
+/// The report of the import that created this build's data, if there was one
+pub async fn last_report(dir: &Path) -> Option<LegacyImportReport> {
+  let json = tokio::fs::read_to_string(dir.join(REPORT_FILE)).await.ok()?;
+  serde_json::from_str(&json).ok()
+}
+
+/// Keep why an import into `dir` failed, for the CLI to report
+pub async fn record_error(dir: &Path, error: &DataDirError) -> std::io::Result<()> {
+  tokio::fs::create_dir_all(dir).await?;
+  tokio::fs::write(dir.join(ERROR_FILE), error.to_string()).await
+}
+
+/// Why the last import into `dir` failed, if it did and none has run since
+pub async fn last_error(dir: &Path) -> Option<String> {
+  tokio::fs::read_to_string(dir.join(ERROR_FILE)).await.ok()
+}
+
+async fn is_dir(path: &Path) -> bool {
+  tokio::fs::metadata(path).await.is_ok_and(|m| m.is_dir())
+}
+
+/// Migrate one project directory into `target`
+async fn import_project(
+  source: &Path,
+  target: &Path,
+  id: &str,
+  config: &Arc<Config>,
+  embedder: &mut Embedder<'_>,
+) -> Result<LegacyProjectReport, DataDirError> {
+  // Left over from an interrupted import
+  if tokio::fs::try_exists(target).await? {
+    tokio::fs::remove_dir_all(target).await?;
+  }
+  tokio::fs::create_dir_all(target).await?;
+
+  // Files beside the database carry over as is
+  let mut entries = tokio::fs::read_dir(source).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_name() != "lancedb" {
+      copy_recursive(&entry.path(), &target.join(entry.file_name())).await?;
+    }
+  }
+
+  let mut report = LegacyProjectReport {
+    id: id.to_string(),
+    ..Default::default()
+  };
+  let legacy_path = source.join("lancedb");
+  if !is_dir(&legacy_path).await {
+    return Ok(report);
+  }
+
+  let store = LegacyStore::open(&legacy_path).await?;
+  let db = ProjectDb::open_at_path(ProjectId::from_dir_name(id), target.join("lancedb"), config.clone()).await?;
+
+  for name in store.table_names().await? {
+    if db.table_named(&name).is_none() {
+      report.skipped.push(format!("{}: not used by this version", name));
+      continue;
+    }
+    let (schema, batches) = match store.read_table(&name).await {
+      Ok(table) => table,
+      Err(e) => {
+        warn!(project = id, table = %name, err = %e, "Legacy table unreadable, skipping");
+        report.skipped.push(format!("{}: unreadable ({})", name, e));
+        continue;
+      }
+    };
+
+    let from_dimensions = legacy_vector_dim(&schema);
+    let reembed = match db.table_vector_dim(&name).await? {
+      Some(dimensions) => from_dimensions != Some(dimensions),
+      None => false,
+    };
+
+    let mut table = LegacyTableReport {
+      name: name.clone(),
+      rows: 0,
+      reembedded: 0,
+      from_dimensions,
+    };
+    for batch in batches {
+      let texts = db.legacy_embedding_texts(&name, &batch)?;
+      let batch = if name == "memories" {
+        with_content_hashes(batch, &texts)?
+      } else {
+        batch
+      };
+      let vectors = if reembed {
+        table.reembedded += texts.iter().filter(|t| t.is_some()).count();
+        Some(embedder.embed(&texts, db.vector_dim).await?)
+      } else {
+        None
+      };
+      table.rows += db.import_legacy_rows(&name, &batch, vectors.as_deref()).await?;
+    }
+
+    info!(
+      project = id,
+      table = %name,
+      rows = table.rows,
+      reembedded = table.reembedded,
+      "Imported legacy table"
+    );
+    report.tables.push(table);
+  }
+
+  db.optimize_indexes().await?;
+  Ok(report)
+}
+
+/// Add the dedup hashes to memories stored before they were computed
+fn with_content_hashes(batch: RecordBatch, texts: &[Option<String>]) -> Result<RecordBatch, DataDirError> {
+  if batch.column_by_name("content_hash").is_some() {
+    return Ok(batch);
+  }
+
+  let (hashes, simhashes): (Vec<String>, Vec<u64>) = texts
+    .iter()
+    .map(|text| compute_hashes(text.as_deref().unwrap_or_default()))
+    .unzip();
+  let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+  fields.push(Field::new("content_hash", DataType::Utf8, false));
+  fields.push(Field::new("simhash", DataType::UInt64, false));
+  let mut columns = batch.columns().to_vec();
+  columns.push(Arc::new(StringArray::from(hashes)));
+  columns.push(Arc::new(UInt64Array::from(simhashes)));
+
+  RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| DataDirError::Database(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use arrow_array::{FixedSizeListArray, Float32Array, RecordBatchIterator};
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::data_dir::check;
+
+  /// Write a memories table the way an unversioned build stored it
+  async fn write_legacy_project(project_dir: &Path, dimensions: usize) {
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("id", DataType::Utf8, false),
+      Field::new("content", DataType::Utf8, false),
+      Field::new("created_at", DataType::Int64, false),
+      Field::new(
+        "vector",
+        DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), dimensions as i32),
+        true,
+      ),
+    ]));
+    let batch = RecordBatch::try_new(
+      schema.clone(),
+      vec![
+        Arc::new(StringArray::from(vec![
+          uuid::Uuid::now_v7().to_string(),
+          uuid::Uuid::now_v7().to_string(),
+        ])),
+        Arc::new(StringArray::from(vec!["Deploys go through staging", "Use pnpm"])),
+        Arc::new(arrow_array::Int64Array::from(vec![
+          1_700_000_000_000,
+          1_700_000_001_000,
+        ])),
+        Arc::new(
+          FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float32, true)),
+            dimensions as i32,
+            Arc::new(Float32Array::from(vec![0.5; dimensions * 2])),
+            None,
+          )
+          .unwrap(),
+        ),
+      ],
+    )
+    .unwrap();
+
+    let connection = lancedb::connect(project_dir.join("lancedb").to_string_lossy().as_ref())
+      .execute()
+      .await
+      .unwrap();
+    connection
+      .create_table(
+        "memories",
+        RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone()),
+      )
+      .execute()
+      .await
+      .unwrap();
+    connection
+      .create_table("watch_state", RecordBatchIterator::new(vec![Ok(batch)], schema))
+      .execute()
+      .await
+      .unwrap();
+    tokio::fs::write(project_dir.join("notes.txt"), "kept").await.unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_legacy_data_is_converted() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    let config = Config::default();
+    let project_dir = root.join("projects/0123456789abcdef");
+    tokio::fs::create_dir_all(&project_dir).await.unwrap();
+    write_legacy_project(&project_dir, config.embedding.dimensions).await;
+    tokio::fs::write(root.join(SPOOL_FILE_NAME), "old\n").await.unwrap();
+    // Hooks keep spooling into the new version while the import is pending
+    let dir = version_dir(root, DATA_VERSION);
+    tokio::fs::create_dir_all(&dir).await.unwrap();
+    tokio::fs::write(dir.join(SPOOL_FILE_NAME), "new\n").await.unwrap();
+
+    assert!(needs_import(root).await.unwrap());
+    let report = import(root, &config, None, false).await.unwrap();
+
+    let project = &report.projects[0];
+    assert_eq!(project.id, "0123456789abcdef");
+    assert_eq!(project.tables.len(), 1);
+    assert_eq!((project.tables[0].rows, project.tables[0].reembedded), (2, 0));
+    assert_eq!(
+      project.tables[0].from_dimensions,
+      Some(config.embedding.dimensions),
+      "matching vectors are kept without an embedding provider"
+    );
+    assert_eq!(project.skipped.len(), 1, "tables this build doesn't have are reported");
+    assert_eq!(report.copied, vec![SPOOL_FILE_NAME.to_string()]);
+
+    let target = dir.join("projects/0123456789abcdef");
+    assert_eq!(
+      tokio::fs::read_to_string(target.join("notes.txt")).await.unwrap(),
+      "kept"
+    );
+    assert_eq!(
+      tokio::fs::read_to_string(dir.join(SPOOL_FILE_NAME)).await.unwrap(),
+      "new\nold\n",
+      "spooled hook events from both sides are kept"
+    );
+
+    let db = ProjectDb::open_at_path(
+      ProjectId::from_dir_name("0123456789abcdef"),
+      target.join("lancedb"),
+      Arc::new(config.clone()),
+    )
+    .await
+    .unwrap();
+    let memories = db.list_memories(None, None).await.unwrap();
+    assert_eq!(memories.len(), 2);
+    assert!(
+      memories.iter().all(|m| !m.content_hash.is_empty()),
+      "dedup hashes are filled in"
+    );
+
+    assert!(root.join("projects").exists(), "the legacy data is left in place");
+    assert!(check(root).await.unwrap().has(DATA_VERSION), "root opens once imported");
+    assert!(!needs_import(root).await.unwrap());
+    assert_eq!(last_report(&dir).await.unwrap().rows(), 2);
+    assert!(
+      matches!(
+        import(root, &config, None, false).await,
+        Err(DataDirError::AlreadyExists(_))
+      ),
+      "import never overwrites existing data"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_failed_import_is_retried_over_partial_data() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    let config = Config::default();
+    let project_dir = root.join("projects/0123456789abcdef");
+    tokio::fs::create_dir_all(&project_dir).await.unwrap();
+    write_legacy_project(&project_dir, config.embedding.dimensions).await;
+
+    // What an import that died midway through the project leaves behind
+    let dir = version_dir(root, DATA_VERSION);
+    let target = dir.join("projects/0123456789abcdef");
+    tokio::fs::create_dir_all(&target).await.unwrap();
+    write_legacy_project(&target, config.embedding.dimensions).await;
+    tokio::fs::write(target.join("half-copied.txt"), "").await.unwrap();
+    record_error(&dir, &DataDirError::Database("connection reset".to_string()))
+      .await
+      .unwrap();
+
+    assert!(
+      needs_import(root).await.unwrap(),
+      "without the version marker the import is pending"
+    );
+    assert!(check(root).await.is_ok(), "a pending import still opens");
+    assert!(last_error(&dir).await.unwrap().contains("connection reset"));
+
+    let report = import(root, &config, None, false).await.unwrap();
+    assert_eq!(report.rows(), 2);
+    assert!(last_error(&dir).await.is_none(), "a new attempt clears the old error");
+    assert!(
+      !target.join("half-copied.txt").exists(),
+      "partial project data is replaced"
+    );
+
+    let db = ProjectDb::open_at_path(
+      ProjectId::from_dir_name("0123456789abcdef"),
+      target.join("lancedb"),
+      Arc::new(config.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+      db.list_memories(None, None).await.unwrap().len(),
+      2,
+      "rows from the failed attempt are not duplicated"
+    );
+    assert!(!needs_import(root).await.unwrap());
+  }
+}
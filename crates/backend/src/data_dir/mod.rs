@@ -0,0 +1,404 @@
+//! Versioned data directory.
+//!
+//! Each data layout version lives in its own subdirectory of the data root:
+//!
+//! ```text
+//! ~/.local/share/ccengram/
+//!   v1/          <- this build
+//!     projects/
+//!     embedding_cache/
+//!     ...
+//!   v2/          <- a newer build, left alone
+//! ```
+//!
+//! Builds only open their own version, so older and newer installs can share
+//! a root without touching each other's databases. Data written before
+//! versioning sits directly in the root and is treated as version 0; it is
+//! converted into this build's version the first time the daemon starts (see
+//! [`legacy`]).
+//!
+//! When this build's version is missing but other versioned data is present,
+//! opening is refused rather than guessing: older data has to be imported with
+//! `ccengram data import`, and an empty store next to newer data has to be
+//! created with `ccengram data init`.
+
+pub mod legacy;
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+
+use crate::ipc::spool::SPOOL_FILE_NAME;
+
+/// Data layout version written by this build
+pub const DATA_VERSION: u32 = 1;
+
+/// Marker written into each version directory once it is complete
+const VERSION_FILE: &str = "VERSION";
+
+/// Entries that make up an unversioned (version 0) data dir
+const UNVERSIONED_ENTRIES: &[&str] = &["projects", "embedding_cache", "usage", SPOOL_FILE_NAME, "repl_history"];
+
+#[derive(Error, Debug)]
+pub enum DataDirError {
+  #[error(
+    "{root} holds data from an older ccengram (version {found}) but none for this build (version {current}).\n\
+     Run `ccengram data import` to copy it into {root}/v{current}, or `ccengram data init` to start empty."
+  )]
+  NeedsImport { root: String, found: u32, current: u32 },
+  #[error(
+    "{root} only holds data from a newer ccengram (version {found}); this build uses version {current}.\n\
+     Upgrade ccengram, or run `ccengram data init` to start an empty store alongside it."
+  )]
+  OnlyNewer { root: String, found: u32, current: u32 },
+  #[error("Data version {0} does not exist")]
+  MissingVersion(u32),
+  #[error("Data for version {0} already exists")]
+  AlreadyExists(u32),
+  #[error("Cannot import version {from} into version {current}; only older data can be imported")]
+  CannotImport { from: u32, current: u32 },
+  #[error("Unversioned data is converted by the legacy import, not copied")]
+  LegacyLayout,
+  #[error("Database error: {0}")]
+  Database(String),
+  #[error(transparent)]
+  Embedding(#[from] crate::embedding::EmbeddingError),
+  #[error(transparent)]
+  DiskSpace(#[from] crate::disk::LowDiskSpace),
+  #[error("IO error: {0}")]
+  Io(#[from] std::io::Error),
+}
+
+impl From<crate::db::DbError> for DataDirError {
+  fn from(e: crate::db::DbError) -> Self {
+    DataDirError::Database(e.to_string())
+  }
+}
+
+/// Data versions found under a data root
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DataDirLayout {
+  /// Whether unversioned (version 0) data sits directly in the root
+  pub unversioned: bool,
+  /// Complete version directories, ascending
+  pub versions: Vec<u32>,
+}
+
+impl DataDirLayout {
+  /// Scan a data root. A missing root is an empty layout.
+  pub async fn scan(root: &Path) -> Result<Self, DataDirError> {
+    let mut layout = Self::default();
+    let mut entries = match tokio::fs::read_dir(root).await {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(layout),
+      Err(e) => return Err(e.into()),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+      let name = entry.file_name();
+      let name = name.to_string_lossy();
+      if UNVERSIONED_ENTRIES.contains(&name.as_ref()) {
+        layout.unversioned = true;
+      } else if let Some(version) = parse_version_dir(&name)
+        && tokio::fs::metadata(entry.path().join(VERSION_FILE))
+          .await
+          .is_ok_and(|m| m.is_file())
+      {
+        layout.versions.push(version);
+      }
+    }
+    layout.versions.sort_unstable();
+    Ok(layout)
+  }
+
+  pub fn has(&self, version: u32) -> bool {
+    if version == 0 {
+      self.unversioned
+    } else {
+      self.versions.contains(&version)
+    }
+  }
+
+  /// Newest version older than this build's, counting unversioned data as 0
+  pub fn newest_older(&self) -> Option<u32> {
+    self
+      .versions
+      .iter()
+      .copied()
+      .filter(|v| *v < DATA_VERSION)
+      .max()
+      .or(self.unversioned.then_some(0))
+  }
+
+  /// Oldest version newer than this build's
+  pub fn oldest_newer(&self) -> Option<u32> {
+    self.versions.iter().copied().find(|v| *v > DATA_VERSION)
+  }
+
+  /// Whether only unversioned data is here for this build to convert
+  pub fn needs_legacy_import(&self) -> bool {
+    self.unversioned && self.newest_older() == Some(0) && !self.has(DATA_VERSION)
+  }
+}
+
+/// Directory holding a data version under `root`
+pub fn version_dir(root: &Path, version: u32) -> PathBuf {
+  root.join(format!("v{}", version))
+}
+
+/// Check that this build can open its data under `root`.
+///
+/// Succeeds when this build's version already exists, when only unversioned
+/// data is present for the legacy import to convert, or when the root holds
+/// no data at all. Otherwise the open is ambiguous and refused.
+pub async fn check(root: &Path) -> Result<DataDirLayout, DataDirError> {
+  let layout = DataDirLayout::scan(root).await?;
+  if layout.has(DATA_VERSION) {
+    if layout.unversioned || layout.versions.len() > 1 {
+      debug!(?layout, "Other data versions present, ignoring");
+    }
+    return Ok(layout);
+  }
+  if layout.needs_legacy_import() {
+    return Ok(layout);
+  }
+
+  let root_display = root.display().to_string();
+  if let Some(found) = layout.newest_older() {
+    return Err(DataDirError::NeedsImport {
+      root: root_display,
+      found,
+      current: DATA_VERSION,
+    });
+  }
+  if let Some(found) = layout.oldest_newer() {
+    return Err(DataDirError::OnlyNewer {
+      root: root_display,
+      found,
+      current: DATA_VERSION,
+    });
+  }
+  Ok(layout)
+}
+
+/// Check the root and create this build's version directory if needed.
+///
+/// While a legacy import is pending the directory is created without its
+/// version marker, which [`legacy::import`] writes once done. Returns the
+/// directory to store data in.
+pub async fn open(root: &Path) -> Result<PathBuf, DataDirError> {
+  let layout = check(root).await?;
+  let dir = version_dir(root, DATA_VERSION);
+  if layout.needs_legacy_import() {
+    tokio::fs::create_dir_all(&dir).await?;
+  } else if !layout.has(DATA_VERSION) {
+    write_version(&dir, DATA_VERSION).await?;
+    info!(dir = %dir.display(), version = DATA_VERSION, "Created data directory");
+  }
+  Ok(dir)
+}
+
+/// Create an empty store for this build, whatever else is in the root
+pub async fn init(root: &Path) -> Result<PathBuf, DataDirError> {
+  let dir = version_dir(root, DATA_VERSION);
+  if DataDirLayout::scan(root).await?.has(DATA_VERSION) {
+    return Err(DataDirError::AlreadyExists(DATA_VERSION));
+  }
+  write_version(&dir, DATA_VERSION).await?;
+  info!(dir = %dir.display(), version = DATA_VERSION, "Created data directory");
+  Ok(dir)
+}
+
+/// Import an older data version into this build's version.
+///
+/// The data is copied, or moved when `move_data` is set, into this build's
+/// version directory and upgraded there. Copies that would leave less than
+/// the minimum free disk space are refused before anything is written. The version marker is written last,
+/// so an interrupted import is not opened and can be rerun. Hook events
+/// spooled while the import was pending are kept. `from` defaults to the
+/// newest older version; unversioned data goes through [`legacy::import`]
+/// instead.
+pub async fn import(root: &Path, from: Option<u32>, move_data: bool) -> Result<PathBuf, DataDirError> {
+  let layout = DataDirLayout::scan(root).await?;
+  if layout.has(DATA_VERSION) {
+    return Err(DataDirError::AlreadyExists(DATA_VERSION));
+  }
+  let from = match from.or_else(|| layout.newest_older()) {
+    Some(from) if from >= DATA_VERSION => {
+      return Err(DataDirError::CannotImport {
+        from,
+        current: DATA_VERSION,
+      });
+    }
+    Some(0) => return Err(DataDirError::LegacyLayout),
+    Some(from) if layout.has(from) => from,
+    Some(from) => return Err(DataDirError::MissingVersion(from)),
+    None => return Err(DataDirError::MissingVersion(0)),
+  };
+
+  let mut entries = Vec::new();
+  let mut read_dir = tokio::fs::read_dir(version_dir(root, from)).await?;
+  while let Some(entry) = read_dir.next_entry().await? {
+    if entry.file_name() != VERSION_FILE {
+      entries.push(entry.path());
+    }
+  }
+
+  if !move_data {
+    crate::disk::ensure_space(root, entries.iter().map(|p| crate::disk::dir_size(p)).sum())?;
+  }
+
+  let dir = version_dir(root, DATA_VERSION);
+  tokio::fs::create_dir_all(&dir).await?;
+
+  for source in &entries {
+    let Some(name) = source.file_name() else {
+      continue;
+    };
+    let target = dir.join(name);
+    if name == SPOOL_FILE_NAME && tokio::fs::try_exists(&target).await? {
+      append_file(source, &target).await?;
+      if move_data {
+        tokio::fs::remove_file(source).await?;
+      }
+    } else if move_data {
+      tokio::fs::rename(source, &target).await?;
+    } else {
+      copy_recursive(source, &target).await?;
+    }
+  }
+
+  for version in from..DATA_VERSION {
+    upgrade(&dir, version)?;
+  }
+  write_version(&dir, DATA_VERSION).await?;
+
+  if move_data {
+    tokio::fs::remove_dir_all(version_dir(root, from)).await?;
+  }
+
+  info!(
+    from = from,
+    to = DATA_VERSION,
+    entries = entries.len(),
+    moved = move_data,
+    "Imported data directory"
+  );
+  Ok(dir)
+}
+
+/// Upgrade a data directory from `version` to the next version in place.
+///
+/// Version 0 is converted by [`legacy::import`]; no later version has changed
+/// the layout yet, and databases migrate their own schemas on open.
+fn upgrade(dir: &Path, version: u32) -> Result<(), DataDirError> {
+  debug!(dir = %dir.display(), version, "No layout changes to the next version");
+  Ok(())
+}
+
+async fn write_version(dir: &Path, version: u32) -> Result<(), DataDirError> {
+  tokio::fs::create_dir_all(dir).await?;
+  tokio::fs::write(dir.join(VERSION_FILE), format!("{}\n", version)).await?;
+  Ok(())
+}
+
+fn parse_version_dir(name: &str) -> Option<u32> {
+  name.strip_prefix('v')?.parse().ok()
+}
+
+/// Append `source` to `target`, keeping both sets of lines
+async fn append_file(source: &Path, target: &Path) -> std::io::Result<()> {
+  let content = tokio::fs::read(source).await?;
+  let mut file = tokio::fs::OpenOptions::new().append(true).open(target).await?;
+  file.write_all(&content).await?;
+  file.flush().await
+}
+
+/// Copy a file or directory tree
+pub(crate) async fn copy_recursive(source: &Path, target: &Path) -> std::io::Result<()> {
+  if tokio::fs::metadata(source).await?.is_dir() {
+    tokio::fs::create_dir_all(target).await?;
+    let mut entries = tokio::fs::read_dir(source).await?;
+    while let Some(entry) = entries.next_entry().await? {
+      Box::pin(copy_recursive(&entry.path(), &target.join(entry.file_name()))).await?;
+    }
+    Ok(())
+  } else {
+    tokio::fs::copy(source, target).await.map(|_| ())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_unversioned_data_awaits_legacy_import() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    tokio::fs::create_dir_all(root.join("projects/abc/lancedb"))
+      .await
+      .unwrap();
+
+    let layout = check(root).await.unwrap();
+    assert!(layout.needs_legacy_import(), "unversioned data is converted on startup");
+    assert!(
+      matches!(import(root, None, false).await, Err(DataDirError::LegacyLayout)),
+      "unversioned data is never copied as is"
+    );
+
+    let dir = open(root).await.unwrap();
+    assert!(dir.is_dir());
+    assert!(
+      !dir.join(VERSION_FILE).exists(),
+      "the marker waits for the import to finish"
+    );
+    assert!(
+      check(root).await.unwrap().needs_legacy_import(),
+      "an unfinished import runs again"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_newer_data_is_left_alone() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    write_version(&version_dir(root, DATA_VERSION + 1), DATA_VERSION + 1)
+      .await
+      .unwrap();
+
+    assert!(
+      matches!(check(root).await, Err(DataDirError::OnlyNewer { .. })),
+      "a downgrade does not silently start empty"
+    );
+    assert!(
+      matches!(
+        import(root, Some(DATA_VERSION + 1), false).await,
+        Err(DataDirError::CannotImport { .. })
+      ),
+      "newer data cannot be imported"
+    );
+
+    let dir = init(root).await.unwrap();
+    assert!(dir.join(VERSION_FILE).is_file(), "init creates this build's version");
+    assert_eq!(
+      DataDirLayout::scan(root).await.unwrap().versions,
+      vec![DATA_VERSION, DATA_VERSION + 1],
+      "both versions coexist"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_empty_root_opens() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().join("ccengram");
+
+    let dir = open(&root).await.unwrap();
+    assert!(dir.join(VERSION_FILE).is_file(), "version directory is created");
+    assert_eq!(DataDirLayout::scan(&root).await.unwrap().versions, vec![DATA_VERSION]);
+  }
+}
@@ -0,0 +1,325 @@
+// Call graph edge operations
+//
+// Edges are written alongside code chunks: whenever a file's chunks are
+// upserted, deleted or renamed, its edges are replaced to match.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, RecordBatch, RecordBatchIterator, StringArray};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    FilterBuilder,
+    connection::{DbError, ProjectDb, Result},
+    schema::call_edges_schema,
+  },
+  domain::code::{CallEdge, CodeChunk},
+};
+
+impl ProjectDb {
+  /// Replace the call edges of `file_paths` with the calls made by `chunks`
+  #[tracing::instrument(level = "trace", skip(self, chunks), fields(file_count = file_paths.len()))]
+  pub async fn replace_call_edges<'a>(
+    &self,
+    file_paths: &[&str],
+    chunks: impl IntoIterator<Item = &'a CodeChunk>,
+  ) -> Result<()> {
+    self.delete_call_edges_for_files(file_paths).await?;
+
+    let edges: Vec<CallEdge> = chunks.into_iter().flat_map(CodeChunk::call_edges).collect();
+    self.add_call_edges(&edges).await
+  }
+
+  /// Append call edges
+  pub async fn add_call_edges(&self, edges: &[CallEdge]) -> Result<()> {
+    self.check_writable()?;
+    if edges.is_empty() {
+      return Ok(());
+    }
+
+    trace!(
+      table = "call_edges",
+      operation = "add",
+      count = edges.len(),
+      "Adding call edges"
+    );
+    let batch = call_edges_to_batch(edges)?;
+    let iter = RecordBatchIterator::new(vec![Ok(batch)], call_edges_schema());
+    self.call_edges_table().add(Box::new(iter)).execute().await?;
+    Ok(())
+  }
+
+  /// Delete the call edges made from chunks in the given files
+  pub async fn delete_call_edges_for_files(&self, file_paths: &[&str]) -> Result<()> {
+    self.check_writable()?;
+    if file_paths.is_empty() {
+      return Ok(());
+    }
+
+    self
+      .call_edges_table()
+      .delete(&FilterBuilder::new().add_in("file_path", file_paths).build_or_empty())
+      .await?;
+    Ok(())
+  }
+
+  /// Point the call edges of a renamed file at its new path
+  pub async fn rename_call_edges_file(&self, old_path: &str, new_path: &str) -> Result<()> {
+    self.check_writable()?;
+    self
+      .call_edges_table()
+      .update()
+      .only_if(FilterBuilder::new().add_eq("file_path", old_path).build_or_empty())
+      .column("file_path", FilterBuilder::literal(new_path))
+      .execute()
+      .await?;
+    Ok(())
+  }
+
+  /// Get the calls made by the given chunks
+  #[tracing::instrument(level = "trace", skip(self), fields(count = caller_ids.len()))]
+  pub async fn get_call_edges_from(&self, caller_ids: &[Uuid]) -> Result<Vec<CallEdge>> {
+    if caller_ids.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let ids: Vec<String> = caller_ids.iter().map(Uuid::to_string).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    self
+      .query_call_edges(&FilterBuilder::new().add_in("caller_id", &ids).build_or_empty())
+      .await
+  }
+
+  /// Get the calls to any of the given symbol names.
+  ///
+  /// Matches both the bare name (`new` for `HashMap::new`) and the call as written.
+  #[tracing::instrument(level = "trace", skip(self), fields(count = names.len()))]
+  pub async fn get_call_edges_to(&self, names: &[String]) -> Result<Vec<CallEdge>> {
+    if names.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let filter = FilterBuilder::new().add_any([
+      FilterBuilder::new().add_in("callee_name", &names),
+      FilterBuilder::new().add_in("callee", &names),
+    ]);
+    self.query_call_edges(&filter.build_or_empty()).await
+  }
+
+  /// Rebuild all call edges from the stored code chunks
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn rebuild_call_edges(&self) -> Result<usize> {
+    self.check_writable()?;
+    self.call_edges_table().delete("true").await?;
+
+    let mut stream = self.code_chunks_table().query().execute().await?;
+    let mut total = 0;
+    while let Some(batch) = stream.try_next().await? {
+      let mut edges = Vec::new();
+      for i in 0..batch.num_rows() {
+        edges.extend(super::codes::batch_to_code_chunk(&batch, i)?.call_edges());
+      }
+      total += edges.len();
+      self.add_call_edges(&edges).await?;
+    }
+
+    debug!(
+      table = "call_edges",
+      edges = total,
+      "Rebuilt call edges from code chunks"
+    );
+    Ok(total)
+  }
+
+  async fn query_call_edges(&self, filter: &str) -> Result<Vec<CallEdge>> {
+    let results: Vec<RecordBatch> = self
+      .call_edges_table()
+      .query()
+      .only_if(filter)
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut edges = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        edges.push(batch_to_call_edge(&batch, i)?);
+      }
+    }
+    Ok(edges)
+  }
+}
+
+fn call_edges_to_batch(edges: &[CallEdge]) -> Result<RecordBatch> {
+  let caller_ids: Vec<String> = edges.iter().map(|e| e.caller_id.to_string()).collect();
+  let file_paths: Vec<&str> = edges.iter().map(|e| e.file_path.as_str()).collect();
+  let caller_symbols: Vec<Option<&str>> = edges.iter().map(|e| e.caller_symbol.as_deref()).collect();
+  let callees: Vec<&str> = edges.iter().map(|e| e.callee.as_str()).collect();
+  let callee_names: Vec<&str> = edges.iter().map(|e| e.callee_name.as_str()).collect();
+
+  let batch = RecordBatch::try_new(
+    call_edges_schema(),
+    vec![
+      Arc::new(StringArray::from(caller_ids)),
+      Arc::new(StringArray::from(file_paths)),
+      Arc::new(StringArray::from(caller_symbols)),
+      Arc::new(StringArray::from(callees)),
+      Arc::new(StringArray::from(callee_names)),
+    ],
+  )?;
+
+  Ok(batch)
+}
+
+fn batch_to_call_edge(batch: &RecordBatch, row: usize) -> Result<CallEdge> {
+  let column = |name: &str| -> Result<&StringArray> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+
+  let caller_symbol = column("caller_symbol")?;
+
+  Ok(CallEdge {
+    caller_id: Uuid::parse_str(column("caller_id")?.value(row))
+      .map_err(|_| DbError::NotFound("invalid caller_id".into()))?,
+    file_path: column("file_path")?.value(row).to_string(),
+    caller_symbol: (!caller_symbol.is_null(row)).then(|| caller_symbol.value(row).to_string()),
+    callee: column("callee")?.value(row).to_string(),
+    callee_name: column("callee_name")?.value(row).to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use chrono::Utc;
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{
+    config::Config,
+    domain::{
+      code::{ChunkType, Language},
+      project::ProjectId,
+    },
+  };
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  fn chunk(file_path: &str, name: &str, calls: &[&str], line: u32) -> CodeChunk {
+    CodeChunk {
+      id: Uuid::new_v4(),
+      file_path: file_path.to_string(),
+      content: format!("fn {}() {{}}", name),
+      language: Language::Rust,
+      chunk_type: ChunkType::Function,
+      symbols: vec![name.to_string()],
+      start_line: line,
+      end_line: line + 2,
+      file_hash: "hash".to_string(),
+      indexed_at: Utc::now(),
+      tokens_estimate: 4,
+      imports: Vec::new(),
+      calls: calls.iter().map(|c| c.to_string()).collect(),
+      definition_kind: Some("function".to_string()),
+      definition_name: Some(name.to_string()),
+      visibility: None,
+      signature: None,
+      docstring: None,
+      parent_definition: None,
+      embedding_text: None,
+      content_hash: Some(format!("{}-{}", file_path, name)),
+      caller_count: 0,
+      callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
+    }
+  }
+
+  #[test]
+  fn test_call_edges_dedupe_and_name() {
+    let c = chunk(
+      "src/a.rs",
+      "run",
+      &["db.query", "HashMap::new", "db.query", "helper"],
+      1,
+    );
+    let edges = c.call_edges();
+
+    let names: Vec<&str> = edges.iter().map(|e| e.callee_name.as_str()).collect();
+    assert_eq!(
+      names,
+      vec!["query", "new", "helper"],
+      "calls should be deduplicated and reduced to their final segment"
+    );
+    assert_eq!(edges[0].caller_symbol.as_deref(), Some("run"));
+  }
+
+  #[tokio::test]
+  async fn test_replace_call_edges_for_file() {
+    let (_temp, db) = create_test_db().await;
+    let caller = chunk("src/a.rs", "run", &["helper", "Config::load"], 1);
+    let other = chunk("src/b.rs", "main", &["run"], 1);
+
+    db.replace_call_edges(&["src/a.rs"], [&caller]).await.unwrap();
+    db.replace_call_edges(&["src/b.rs"], [&other]).await.unwrap();
+
+    let to_helper = db.get_call_edges_to(&["helper".to_string()]).await.unwrap();
+    assert_eq!(to_helper.len(), 1, "helper should have one caller");
+    assert_eq!(to_helper[0].caller_id, caller.id);
+
+    let to_load = db.get_call_edges_to(&["load".to_string()]).await.unwrap();
+    assert_eq!(to_load.len(), 1, "qualified calls should match by bare name");
+
+    let reindexed = chunk("src/a.rs", "run", &["helper"], 1);
+    db.replace_call_edges(&["src/a.rs"], [&reindexed]).await.unwrap();
+    assert!(
+      db.get_call_edges_from(&[caller.id]).await.unwrap().is_empty(),
+      "edges from the old chunk should be replaced"
+    );
+    assert_eq!(db.get_call_edges_from(&[reindexed.id]).await.unwrap().len(), 1);
+    assert_eq!(
+      db.get_call_edges_from(&[other.id]).await.unwrap().len(),
+      1,
+      "edges from other files should be kept"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_rebuild_call_edges_from_chunks() {
+    let (_temp, db) = create_test_db().await;
+    let caller = chunk("src/a.rs", "run", &["helper"], 1);
+    let vector = vec![0.0f32; db.vector_dim];
+    db.upsert_code_chunks("src/a.rs", &[(caller.clone(), vector)])
+      .await
+      .unwrap();
+
+    db.call_edges_table().delete("true").await.unwrap();
+    assert!(db.get_call_edges_from(&[caller.id]).await.unwrap().is_empty());
+
+    assert_eq!(db.rebuild_call_edges().await.unwrap(), 1, "one edge should be rebuilt");
+    assert_eq!(db.get_call_edges_from(&[caller.id]).await.unwrap().len(), 1);
+  }
+}
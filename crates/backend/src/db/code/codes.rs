@@ -1,24 +1,32 @@
 // Code chunks table operations
 
-use std::sync::Arc;
+use std::{
+  collections::{BTreeSet, HashMap},
+  sync::Arc,
+};
 
 use arrow_array::{
-  Array, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray, UInt32Array,
+  Array, BooleanArray, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray,
+  UInt32Array,
 };
 use chrono::{TimeZone, Utc};
 use futures::TryStreamExt;
-use lancedb::query::{ExecutableQuery, QueryBase};
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
 use tracing::{debug, trace};
 use uuid::Uuid;
 
 use crate::{
   db::{
-    connection::{DbError, ProjectDb, Result},
+    FilterBuilder,
+    connection::{DbError, ProjectDb, Result, id_prefix_filter},
     schema::code_chunks_schema,
   },
   domain::code::{ChunkType, CodeChunk, Language},
 };
 
+/// Shallow chunks carry zero vectors and never match a vector search
+const NOT_SHALLOW: &str = "(shallow IS NULL OR shallow = false)";
+
 impl ProjectDb {
   /// Upsert code chunks for a file using merge_insert
   ///
@@ -28,6 +36,7 @@ impl ProjectDb {
   /// - Deletes old chunks for this file that aren't in the new set
   #[tracing::instrument(level = "trace", skip(self, chunks), fields(batch_size = chunks.len()))]
   pub async fn upsert_code_chunks(&self, file_path: &str, chunks: &[(CodeChunk, Vec<f32>)]) -> Result<()> {
+    self.check_writable()?;
     if chunks.is_empty() {
       // If no chunks, just delete any existing chunks for this file
       return self.delete_chunks_for_file(file_path).await;
@@ -53,15 +62,16 @@ impl ProjectDb {
     // - when_matched_update_all: update existing chunks at same span
     // - when_not_matched_insert_all: insert new chunks
     // - when_not_matched_by_source_delete: delete old chunks for this file not in new data
-    let escaped_path = file_path.replace('\'', "''");
     let mut builder = table.merge_insert(&["file_path", "start_line", "end_line"]);
     builder
       .when_matched_update_all(None)
       .when_not_matched_insert_all()
-      .when_not_matched_by_source_delete(Some(format!("file_path = '{}'", escaped_path)));
+      .when_not_matched_by_source_delete(FilterBuilder::new().add_eq("file_path", file_path).build());
     builder.execute(Box::new(iter)).await?;
 
-    Ok(())
+    self
+      .replace_call_edges(&[file_path], chunks.iter().map(|(c, _)| c))
+      .await
   }
 
   /// Batch upsert code chunks for multiple files using a single merge_insert
@@ -70,18 +80,16 @@ impl ProjectDb {
   /// multiple files at once. Uses `file_path IN (...)` for the delete filter.
   #[tracing::instrument(level = "trace", skip(self, chunks), fields(file_count = file_paths.len(), chunk_count = chunks.len()))]
   pub async fn upsert_code_chunks_batch(&self, file_paths: &[&str], chunks: &[(CodeChunk, Vec<f32>)]) -> Result<()> {
+    self.check_writable()?;
     if chunks.is_empty() {
       // Delete all chunks for these files with a single bulk delete
       if !file_paths.is_empty() {
         let table = self.code_chunks_table();
-        let paths_filter = file_paths
-          .iter()
-          .map(|p| format!("'{}'", p.replace('\'', "''")))
-          .collect::<Vec<_>>()
-          .join(", ");
-        table.delete(&format!("file_path IN ({})", paths_filter)).await?;
+        table
+          .delete(&FilterBuilder::new().add_in("file_path", file_paths).build_or_empty())
+          .await?;
       }
-      return Ok(());
+      return self.delete_call_edges_for_files(file_paths).await;
     }
 
     trace!(
@@ -98,22 +106,15 @@ impl ProjectDb {
     let batch = code_chunks_to_batch(chunks, self.vector_dim)?;
     let iter = RecordBatchIterator::new(vec![Ok(batch)], code_chunks_schema(self.vector_dim));
 
-    // Build IN clause for delete filter: file_path IN ('path1', 'path2', ...)
-    let paths_filter = file_paths
-      .iter()
-      .map(|p| format!("'{}'", p.replace('\'', "''")))
-      .collect::<Vec<_>>()
-      .join(", ");
-
     // Use file_path + start_line + end_line as key to handle nested structures
     let mut builder = table.merge_insert(&["file_path", "start_line", "end_line"]);
     builder
       .when_matched_update_all(None)
       .when_not_matched_insert_all()
-      .when_not_matched_by_source_delete(Some(format!("file_path IN ({})", paths_filter)));
+      .when_not_matched_by_source_delete(FilterBuilder::new().add_in("file_path", file_paths).build());
     builder.execute(Box::new(iter)).await?;
 
-    Ok(())
+    self.replace_call_edges(file_paths, chunks.iter().map(|(c, _)| c)).await
   }
 
   /// Get a code chunk by ID
@@ -143,18 +144,22 @@ impl ProjectDb {
 
   /// Delete all chunks for a file
   pub async fn delete_chunks_for_file(&self, file_path: &str) -> Result<()> {
+    self.check_writable()?;
     debug!(table = "code_chunks", operation = "delete_for_file", file = %file_path, "Deleting chunks for file");
     let table = self.code_chunks_table();
-    let escaped_path = file_path.replace('\'', "''");
-    table.delete(&format!("file_path = '{}'", escaped_path)).await?;
-    Ok(())
+    table
+      .delete(&FilterBuilder::new().add_eq("file_path", file_path).build_or_empty())
+      .await?;
+    self.delete_call_edges_for_files(&[file_path]).await
   }
 
   /// Delete a code chunk by ID
   pub async fn delete_code_chunk(&self, id: &Uuid) -> Result<()> {
+    self.check_writable()?;
     debug!(table = "code_chunks", operation = "delete", id = %id, "Deleting code chunk");
     let table = self.code_chunks_table();
     table.delete(&format!("id = '{}'", id)).await?;
+    self.call_edges_table().delete(&format!("caller_id = '{}'", id)).await?;
     Ok(())
   }
 
@@ -163,6 +168,7 @@ impl ProjectDb {
   /// This is more efficient than delete + re-index because it preserves existing
   /// embeddings and other computed data.
   pub async fn rename_file(&self, old_path: &str, new_path: &str) -> Result<usize> {
+    self.check_writable()?;
     debug!(
       table = "code_chunks",
       operation = "rename_file",
@@ -182,17 +188,13 @@ impl ProjectDb {
       return Ok(0);
     }
 
-    // LanceDB update: set file_path = new_path where file_path = old_path
-    // Escape single quotes in paths
-    let old_escaped = old_path.replace('\'', "''");
-    let new_escaped = new_path.replace('\'', "''");
-
     table
       .update()
-      .only_if(format!("file_path = '{}'", old_escaped))
-      .column("file_path", format!("'{}'", new_escaped))
+      .only_if(FilterBuilder::new().add_eq("file_path", old_path).build_or_empty())
+      .column("file_path", FilterBuilder::literal(new_path))
       .execute()
       .await?;
+    self.rename_call_edges_file(old_path, new_path).await?;
 
     debug!(
       old_path = %old_path,
@@ -204,7 +206,7 @@ impl ProjectDb {
     Ok(count)
   }
 
-  /// Search code chunks by vector similarity
+  /// Search code chunks by vector similarity, leaving out shallow chunks
   pub async fn search_code_chunks(
     &self,
     query_vector: &[f32],
@@ -222,11 +224,11 @@ impl ProjectDb {
 
     let table = self.code_chunks_table();
 
-    let query = if let Some(f) = filter {
-      table.vector_search(query_vector.to_vec())?.limit(limit).only_if(f)
-    } else {
-      table.vector_search(query_vector.to_vec())?.limit(limit)
+    let filter = match filter {
+      Some(f) => format!("({}) AND {}", f, NOT_SHALLOW),
+      None => NOT_SHALLOW.to_string(),
     };
+    let query = self.vector_query(table, query_vector)?.limit(limit).only_if(filter);
 
     let results: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
 
@@ -336,11 +338,45 @@ impl ProjectDb {
     Ok(chunks)
   }
 
+  /// Distinct git commits recorded on code chunks, other than `except`
+  pub async fn code_chunk_commits(&self, except: Option<&str>) -> Result<Vec<String>> {
+    let mut filter = FilterBuilder::new().add_is_not_null("git_commit");
+    if let Some(commit) = except {
+      filter = filter.add_ne("git_commit", commit);
+    }
+    let filter = filter.build_or_empty();
+
+    let results: Vec<RecordBatch> = self
+      .code_chunks_table()
+      .query()
+      .only_if(filter)
+      .select(Select::columns(&["git_commit"]))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut commits = BTreeSet::new();
+    for batch in results {
+      if let Some(column) = batch
+        .column_by_name("git_commit")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      {
+        for i in 0..column.len() {
+          if column.is_valid(i) {
+            commits.insert(column.value(i).to_string());
+          }
+        }
+      }
+    }
+
+    Ok(commits.into_iter().collect())
+  }
+
   /// Get chunks for a specific file
   pub async fn get_chunks_for_file(&self, file_path: &str) -> Result<Vec<CodeChunk>> {
-    self
-      .list_code_chunks(Some(&format!("file_path = '{}'", file_path)), None)
-      .await
+    let filter = FilterBuilder::new().add_eq("file_path", file_path).build_or_empty();
+    self.list_code_chunks(Some(&filter), None).await
   }
 
   /// Get chunks with their embeddings for a file
@@ -353,7 +389,7 @@ impl ProjectDb {
 
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("file_path = '{}'", file_path))
+      .only_if(FilterBuilder::new().add_eq("file_path", file_path).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -371,16 +407,73 @@ impl ProjectDb {
     Ok(chunks_with_embeddings)
   }
 
+  /// Get one embedding per content hash among `hashes`
+  ///
+  /// Identical chunks in copied or vendored files share an embedding, so a
+  /// chunk already embedded elsewhere in the project isn't embedded again.
+  #[tracing::instrument(level = "trace", skip(self, hashes), fields(count = hashes.len()))]
+  pub async fn get_embeddings_by_content_hash(&self, hashes: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+    if hashes.is_empty() {
+      return Ok(HashMap::new());
+    }
+
+    let results: Vec<RecordBatch> = self
+      .code_chunks_table()
+      .query()
+      .only_if(format!("{} AND {}", content_hash_filter(hashes), NOT_SHALLOW))
+      .select(Select::columns(&["content_hash", "vector"]))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut embeddings = HashMap::new();
+    for batch in results {
+      let Some(column) = batch
+        .column_by_name("content_hash")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      else {
+        continue;
+      };
+      for i in 0..batch.num_rows() {
+        if column.is_valid(i) && !embeddings.contains_key(column.value(i)) {
+          embeddings.insert(
+            column.value(i).to_string(),
+            extract_vector_from_batch(&batch, i, self.vector_dim)?,
+          );
+        }
+      }
+    }
+
+    Ok(embeddings)
+  }
+
+  /// Get the chunks with one of `hashes`, grouped by content hash
+  ///
+  /// A hash with several chunks is code copied into each of their files.
+  pub async fn get_code_chunks_by_content_hash(&self, hashes: &[String]) -> Result<HashMap<String, Vec<CodeChunk>>> {
+    if hashes.is_empty() {
+      return Ok(HashMap::new());
+    }
+
+    let mut copies: HashMap<String, Vec<CodeChunk>> = HashMap::new();
+    for chunk in self.list_code_chunks(Some(&content_hash_filter(hashes)), None).await? {
+      if let Some(hash) = chunk.content_hash.clone() {
+        copies.entry(hash).or_default().push(chunk);
+      }
+    }
+    for chunks in copies.values_mut() {
+      chunks.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.start_line.cmp(&b.start_line)));
+    }
+
+    Ok(copies)
+  }
+
   /// Find code chunks by ID prefix
   ///
   /// Searches for code chunks whose ID starts with the given prefix.
   pub async fn find_code_chunks_by_prefix(&self, prefix: &str) -> Result<Vec<CodeChunk>> {
-    if prefix.len() < 6 {
-      return Err(DbError::InvalidInput("ID prefix must be at least 6 characters".into()));
-    }
-
-    // Use LIKE query for prefix matching
-    let filter = format!("id LIKE '{}%'", prefix);
+    let filter = id_prefix_filter(prefix)?;
     self.list_code_chunks(Some(&filter), Some(10)).await
   }
 
@@ -490,6 +583,12 @@ fn code_chunks_to_batch(chunks: &[(CodeChunk, Vec<f32>)], vector_dim: usize) ->
   let caller_counts: Vec<u32> = chunks.iter().map(|(c, _)| c.caller_count).collect();
   let callee_counts: Vec<u32> = chunks.iter().map(|(c, _)| c.callee_count).collect();
 
+  // Git checkout
+  let git_branches: Vec<Option<&str>> = chunks.iter().map(|(c, _)| c.git_branch.as_deref()).collect();
+  let git_commits: Vec<Option<&str>> = chunks.iter().map(|(c, _)| c.git_commit.as_deref()).collect();
+  let shallows: Vec<bool> = chunks.iter().map(|(c, _)| c.shallow).collect();
+  let generated: Vec<bool> = chunks.iter().map(|(c, _)| c.generated).collect();
+
   // Vectors - flatten all into one array
   let mut all_vectors: Vec<f32> = Vec::with_capacity(n * vector_dim);
   for (_, vec) in chunks {
@@ -529,6 +628,10 @@ fn code_chunks_to_batch(chunks: &[(CodeChunk, Vec<f32>)], vector_dim: usize) ->
       Arc::new(UInt32Array::from(caller_counts)),
       Arc::new(UInt32Array::from(callee_counts)),
       Arc::new(vector_list),
+      Arc::new(StringArray::from(git_branches)),
+      Arc::new(StringArray::from(git_commits)),
+      Arc::new(BooleanArray::from(shallows)),
+      Arc::new(BooleanArray::from(generated)),
     ],
   )?;
 
@@ -551,8 +654,14 @@ fn extract_vector_from_batch(batch: &RecordBatch, row: usize, vector_dim: usize)
     .ok_or_else(|| DbError::NotFound("vector column missing or null".into()))
 }
 
+/// Filter matching chunks with one of `hashes`
+fn content_hash_filter(hashes: &[String]) -> String {
+  let hashes: Vec<&str> = hashes.iter().map(String::as_str).collect();
+  FilterBuilder::new().add_in("content_hash", &hashes).build_or_empty()
+}
+
 /// Convert a RecordBatch row to a CodeChunk
-fn batch_to_code_chunk(batch: &RecordBatch, row: usize) -> Result<CodeChunk> {
+pub(super) fn batch_to_code_chunk(batch: &RecordBatch, row: usize) -> Result<CodeChunk> {
   let get_string = |name: &str| -> Result<String> {
     batch
       .column_by_name(name)
@@ -656,6 +765,17 @@ fn batch_to_code_chunk(batch: &RecordBatch, row: usize) -> Result<CodeChunk> {
   let caller_count = get_u32_opt("caller_count").unwrap_or(0);
   let callee_count = get_u32_opt("callee_count").unwrap_or(0);
 
+  let git_branch = get_string_opt("git_branch").filter(|s| !s.is_empty());
+  let git_commit = get_string_opt("git_commit").filter(|s| !s.is_empty());
+  let shallow = batch
+    .column_by_name("shallow")
+    .and_then(|c| c.as_any().downcast_ref::<BooleanArray>())
+    .is_some_and(|a| a.is_valid(row) && a.value(row));
+  let generated = batch
+    .column_by_name("generated")
+    .and_then(|c| c.as_any().downcast_ref::<BooleanArray>())
+    .is_some_and(|a| a.is_valid(row) && a.value(row));
+
   Ok(CodeChunk {
     id: Uuid::parse_str(&id_str).map_err(|_| DbError::NotFound("invalid id".into()))?,
     file_path: get_string("file_path")?,
@@ -680,6 +800,10 @@ fn batch_to_code_chunk(batch: &RecordBatch, row: usize) -> Result<CodeChunk> {
     content_hash,
     caller_count,
     callee_count,
+    git_branch,
+    git_commit,
+    shallow,
+    generated,
   })
 }
 
@@ -739,6 +863,10 @@ mod tests {
       content_hash: Some(hash.to_string()),
       caller_count: 0,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     }
   }
 
@@ -776,6 +904,129 @@ mod tests {
     assert_eq!(chunks.len(), 2, "should list both chunks");
   }
 
+  #[tokio::test]
+  async fn test_chunks_by_content_hash() {
+    let (_temp, db) = create_test_db().await;
+
+    let mut vendored = create_test_chunk_with_hash("hash_copied");
+    vendored.file_path = "/test/vendor/util.rs".to_string();
+    let mut original = create_test_chunk_with_hash("hash_copied");
+    original.file_path = "/test/util.rs".to_string();
+    let mut other = create_test_chunk_with_hash("hash_other");
+    other.file_path = "/test/main.rs".to_string();
+    let mut vec = dummy_vector(db.vector_dim);
+    vec[0] = 1.0;
+
+    db.upsert_code_chunks("/test/vendor/util.rs", &[(vendored, vec.clone())])
+      .await
+      .unwrap();
+    db.upsert_code_chunks("/test/util.rs", &[(original, vec.clone())])
+      .await
+      .unwrap();
+    db.upsert_code_chunks("/test/main.rs", &[(other, dummy_vector(db.vector_dim))])
+      .await
+      .unwrap();
+
+    let embeddings = db
+      .get_embeddings_by_content_hash(&["hash_copied".to_string(), "hash_missing".to_string()])
+      .await
+      .unwrap();
+    assert_eq!(embeddings.len(), 1, "one embedding per hash");
+    assert_eq!(embeddings["hash_copied"], vec);
+
+    let copies = db
+      .get_code_chunks_by_content_hash(&["hash_copied".to_string()])
+      .await
+      .unwrap();
+    let files: Vec<&str> = copies["hash_copied"].iter().map(|c| c.file_path.as_str()).collect();
+    assert_eq!(files, vec!["/test/util.rs", "/test/vendor/util.rs"]);
+  }
+
+  #[tokio::test]
+  async fn test_upgraded_table_matches_fresh_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.lancedb");
+    let config = Arc::new(Config::default());
+    let fresh = code_chunks_schema(config.embedding.dimensions);
+    let added = ["git_branch", "git_commit", "shallow", "generated"];
+    let before: Vec<_> = fresh
+      .fields()
+      .iter()
+      .filter(|f| !added.contains(&f.name().as_str()))
+      .cloned()
+      .collect();
+    lancedb::connect(db_path.to_string_lossy().as_ref())
+      .execute()
+      .await
+      .unwrap()
+      .create_empty_table("code_chunks", Arc::new(arrow_schema::Schema::new(before)))
+      .execute()
+      .await
+      .unwrap();
+
+    let db = ProjectDb::open_at_path(ProjectId::from_path(Path::new("/test")).await, db_path, config)
+      .await
+      .unwrap();
+    let names =
+      |schema: &arrow_schema::Schema| -> Vec<String> { schema.fields().iter().map(|f| f.name().clone()).collect() };
+    assert_eq!(
+      names(&db.code_chunks_table().schema().await.unwrap()),
+      names(&fresh),
+      "added columns land where fresh tables have them"
+    );
+
+    let mut chunk = create_test_chunk();
+    chunk.git_branch = Some("main".to_string());
+    chunk.git_commit = Some("aaa".to_string());
+    chunk.generated = true;
+    db.upsert_code_chunks(
+      &chunk.file_path.clone(),
+      &[(chunk.clone(), dummy_vector(db.vector_dim))],
+    )
+    .await
+    .expect("chunks write to an upgraded table");
+
+    let retrieved = db.get_code_chunk(&chunk.id).await.unwrap().unwrap();
+    assert_eq!(retrieved.git_branch.as_deref(), Some("main"));
+    assert_eq!(retrieved.git_commit.as_deref(), Some("aaa"));
+    assert!(retrieved.generated, "columns added on upgrade roundtrip");
+  }
+
+  #[tokio::test]
+  async fn test_git_checkout_roundtrip() {
+    let (_temp, db) = create_test_db().await;
+
+    let mut main = create_test_chunk_with_hash("hash_main");
+    main.file_path = "/test/a.rs".to_string();
+    main.git_branch = Some("main".to_string());
+    main.git_commit = Some("aaa".to_string());
+    let mut feature = create_test_chunk_with_hash("hash_feature");
+    feature.file_path = "/test/b.rs".to_string();
+    feature.git_branch = Some("feature".to_string());
+    feature.git_commit = Some("bbb".to_string());
+    let untracked = create_test_chunk_with_hash("hash_untracked");
+    let vec = dummy_vector(db.vector_dim);
+
+    db.upsert_code_chunks("/test/a.rs", &[(main.clone(), vec.clone())])
+      .await
+      .unwrap();
+    db.upsert_code_chunks("/test/b.rs", &[(feature, vec.clone())])
+      .await
+      .unwrap();
+    db.upsert_code_chunks(&untracked.file_path.clone(), &[(untracked, vec)])
+      .await
+      .unwrap();
+
+    let retrieved = db.get_code_chunk(&main.id).await.unwrap().unwrap();
+    assert_eq!(retrieved.git_branch.as_deref(), Some("main"), "branch should roundtrip");
+    assert_eq!(retrieved.git_commit.as_deref(), Some("aaa"), "commit should roundtrip");
+
+    let commits = db.code_chunk_commits(None).await.unwrap();
+    assert_eq!(commits, vec!["aaa", "bbb"], "untracked chunks have no commit");
+    let others = db.code_chunk_commits(Some("aaa")).await.unwrap();
+    assert_eq!(others, vec!["bbb"], "current commit should be excluded");
+  }
+
   #[tokio::test]
   async fn test_delete_chunks_for_file() {
     let (_temp, db) = create_test_db().await;
@@ -800,6 +1051,36 @@ mod tests {
     assert_eq!(chunks[0].file_path, "/test/other.rs");
   }
 
+  #[tokio::test]
+  async fn test_chunks_for_file_with_quote_in_path() {
+    let (_temp, db) = create_test_db().await;
+
+    let mut quoted = create_test_chunk_with_hash("hash_quoted");
+    quoted.file_path = "/test/it's.rs".to_string();
+    let mut other = create_test_chunk_with_hash("hash_other");
+    other.file_path = "/test/other.rs".to_string();
+    let vec = dummy_vector(db.vector_dim);
+    db.upsert_code_chunks("/test/it's.rs", &[(quoted, vec.clone())])
+      .await
+      .unwrap();
+    db.upsert_code_chunks("/test/other.rs", &[(other, vec)]).await.unwrap();
+
+    let chunks = db.get_chunks_for_file("/test/it's.rs").await.unwrap();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].file_path, "/test/it's.rs");
+    assert_eq!(
+      db.get_chunks_with_embeddings_for_file("/test/it's.rs")
+        .await
+        .unwrap()
+        .len(),
+      1
+    );
+
+    // A path that would rewrite an unescaped filter matches nothing
+    let injected = db.get_chunks_for_file("x' OR '1' = '1").await.unwrap();
+    assert!(injected.is_empty());
+  }
+
   #[tokio::test]
   async fn test_rename_file() {
     let (_temp, db) = create_test_db().await;
@@ -842,4 +1123,45 @@ mod tests {
     let renamed = db.rename_file("/nonexistent.rs", "/new.rs").await.unwrap();
     assert_eq!(renamed, 0);
   }
+
+  #[tokio::test]
+  async fn test_int8_quantized_search() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let mut config = Config::default();
+    config.embedding.dimensions = 16;
+    config.index.quantization = crate::config::VectorQuantization::Int8;
+    let db = ProjectDb::open_at_path(project_id, temp_dir.path().join("test.lancedb"), Arc::new(config))
+      .await
+      .unwrap();
+
+    // Distinct deterministic vectors, enough rows to train the index
+    let vector_for = |k: usize| -> Vec<f32> { (0..16).map(|d| ((k * 31 + d * 7) as f32 * 0.37).sin()).collect() };
+    let paths: Vec<String> = (0..1000).map(|k| format!("/test/file_{}.rs", k)).collect();
+    let chunks: Vec<(CodeChunk, Vec<f32>)> = paths
+      .iter()
+      .enumerate()
+      .map(|(k, path)| {
+        let mut chunk = create_test_chunk_with_hash(&format!("hash_{}", k));
+        chunk.file_path = path.clone();
+        (chunk, vector_for(k))
+      })
+      .collect();
+    let path_refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+    db.upsert_code_chunks_batch(&path_refs, &chunks).await.unwrap();
+    db.optimize_indexes().await.unwrap();
+
+    let indices = db.code_chunks_table().list_indices().await.unwrap();
+    assert!(
+      indices.iter().any(|idx| idx.columns.iter().any(|c| c == "vector")),
+      "int8 quantization should create a vector index"
+    );
+
+    let results = db.search_code_chunks(&vector_for(42), 5, None).await.unwrap();
+    assert_eq!(
+      results.first().map(|(c, _)| c.file_path.as_str()),
+      Some("/test/file_42.rs"),
+      "re-ranked search should return the exact match first"
+    );
+  }
 }
@@ -1 +1,2 @@
+mod call_edges;
 mod codes;
@@ -1,26 +1,37 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
 
-use lancedb::{Connection, ObjectStoreRegistry, Session, Table, connect, index::Index};
+use arrow_schema::Schema;
+use lancedb::{
+  Connection, ObjectStoreRegistry, Session, Table, connect, index::Index, query::VectorQuery, table::NewColumnTransform,
+};
 use thiserror::Error;
-use tracing::{debug, error, trace};
+use tracing::{debug, error, trace, warn};
 
+use super::encryption::ContentCipher;
 use crate::{
-  config::Config,
+  config::{Config, IndexConfig, VectorQuantization},
   db::schema::{
-    code_chunks_schema, document_metadata_schema, documents_schema, indexed_files_schema, memories_schema,
-    memory_relationships_schema, session_memories_schema, sessions_schema,
+    call_edges_schema, code_chunks_schema, document_metadata_schema, documents_schema, entities_schema,
+    extraction_jobs_schema, indexed_files_schema, memories_schema, memory_entities_schema, memory_events_schema,
+    memory_relationships_schema, search_logs_schema, session_injections_schema, session_memories_schema,
+    sessions_schema, skipped_files_schema,
   },
+  disk,
   domain::project::ProjectId,
 };
 
 #[derive(Error, Debug)]
 pub enum DbError {
   #[error("LanceDB error: {0}")]
-  Lance(#[from] lancedb::Error),
+  Lance(#[source] lancedb::Error),
   #[error("Arrow error: {0}")]
   Arrow(#[from] arrow::error::ArrowError),
   #[error("IO error: {0}")]
-  Io(#[from] std::io::Error),
+  Io(#[source] std::io::Error),
   #[error("Not found: {0}")]
   NotFound(String),
   #[error("Serialization error: {0}")]
@@ -31,10 +42,67 @@ pub enum DbError {
   Query(String),
   #[error("Ambiguous prefix '{prefix}' matches {count} items. Use more characters.")]
   AmbiguousPrefix { prefix: String, count: usize },
+  #[error("Database is read-only: {0}. Free up disk space; writes resume automatically.")]
+  ReadOnly(String),
+  #[error("Encryption error: {0}")]
+  Encryption(String),
+}
+
+// Write failures from a full disk switch the daemon to read-only mode, so the
+// writes after them are refused instead of piling up partial table versions
+impl From<lancedb::Error> for DbError {
+  fn from(e: lancedb::Error) -> Self {
+    disk::note_error(&e);
+    Self::Lance(e)
+  }
+}
+
+impl From<std::io::Error> for DbError {
+  fn from(e: std::io::Error) -> Self {
+    disk::note_error(&e);
+    Self::Io(e)
+  }
 }
 
 pub type Result<T> = std::result::Result<T, DbError>;
 
+/// Filter matching IDs that start with `prefix`.
+///
+/// Prefixes reach here from user input, so anything but the hex digits and
+/// dashes of a UUID is refused rather than interpolated into the filter.
+pub(in crate::db) fn id_prefix_filter(prefix: &str) -> Result<String> {
+  if prefix.len() < 6 {
+    return Err(DbError::InvalidInput("ID prefix must be at least 6 characters".into()));
+  }
+  if !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+    return Err(DbError::InvalidInput(format!("'{}' is not a valid ID prefix", prefix)));
+  }
+  Ok(format!("id LIKE '{}%'", prefix))
+}
+
+/// Storage and index health for a single table
+#[derive(Debug, Clone)]
+pub struct TableHealth {
+  pub name: String,
+  pub rows: usize,
+  pub bytes: usize,
+  pub fragments: usize,
+  pub small_fragments: usize,
+  /// Vector index type, if the table has one
+  pub vector_index: Option<String>,
+  pub indexed_rows: usize,
+  pub unindexed_rows: usize,
+}
+
+/// Work done by a background maintenance pass
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceReport {
+  /// Tables compacted because they crossed the fragment threshold
+  pub compacted: Vec<String>,
+  /// Tables whose vector index was retrained from scratch
+  pub rebuilt: Vec<String>,
+}
+
 /// Database connection for a specific project
 ///
 /// Uses a shared Session with controlled cache sizes to avoid LanceDB's
@@ -42,10 +110,18 @@ pub type Result<T> = std::result::Result<T, DbError>;
 /// All table handles are held permanently for zero per-operation overhead.
 pub struct ProjectDb {
   pub project_id: ProjectId,
+  /// LanceDB directory
+  path: PathBuf,
   #[allow(dead_code)] // idk i might need this later
   pub connection: Connection,
   pub vector_dim: usize,
   session: Arc<Session>,
+  /// Vector index and maintenance settings
+  index: IndexConfig,
+  /// Seals memory and document content when encryption at rest is enabled
+  cipher: ContentCipher,
+  /// Table rows covered when each vector index was trained (or first seen)
+  vector_index_baseline: tokio::sync::Mutex<HashMap<String, usize>>,
 
   // Table handles held permanently - Table is Send + Sync
   // Dropping tables doesn't free cached memory (Session holds caches)
@@ -54,9 +130,17 @@ pub struct ProjectDb {
   sessions_table: Table, // renamed to avoid confusion with Session
   documents: Table,
   session_memories: Table,
+  session_injections: Table,
   memory_relationships: Table,
   document_metadata: Table,
   indexed_files: Table,
+  skipped_files: Table,
+  call_edges: Table,
+  extraction_jobs: Table,
+  entities: Table,
+  memory_entities: Table,
+  memory_events: Table,
+  search_logs: Table,
 }
 
 impl ProjectDb {
@@ -71,6 +155,9 @@ impl ProjectDb {
   /// Creates a shared Session with controlled cache sizes (from config.database)
   /// and opens all table handles permanently.
   pub async fn open_at_path(project_id: ProjectId, db_path: PathBuf, config: Arc<Config>) -> Result<Self> {
+    // Refuse to open without the key rather than store plaintext
+    let cipher = ContentCipher::from_config(&config.encryption)?;
+
     // Ensure directory exists
     if let Some(parent) = db_path.parent() {
       tokio::fs::create_dir_all(parent).await?;
@@ -108,35 +195,70 @@ impl ProjectDb {
       }
     };
 
+    // Tables a partial write left unreadable are set aside and recreated
+    // empty below, so the rest of the project keeps working
+    let quarantined = super::recovery::quarantine_unreadable(&db_path).await?;
+    if !quarantined.is_empty() {
+      warn!(
+        tables = ?quarantined.iter().map(|q| q.table.as_str()).collect::<Vec<_>>(),
+        "Quarantined unreadable tables; run `ccengram db repair` to restore them"
+      );
+    }
+
     // Ensure tables exist before opening handles
     debug!("Initializing database schema");
-    Self::ensure_tables_static(&connection, config.embedding.dimensions).await?;
+    let created_call_edges = Self::ensure_tables_static(&connection, config.embedding.dimensions).await?;
 
     // Open all table handles once, hold permanently
     // Table is Send + Sync, so concurrent access is safe
     debug!("Opening table handles");
     let memories = connection.open_table("memories").execute().await?;
+    add_missing_columns(&memories, &memories_schema(config.embedding.dimensions)).await?;
     let code_chunks = connection.open_table("code_chunks").execute().await?;
+    add_missing_columns(&code_chunks, &code_chunks_schema(config.embedding.dimensions)).await?;
     let sessions_table = connection.open_table("sessions").execute().await?;
     let documents = connection.open_table("documents").execute().await?;
     let session_memories = connection.open_table("session_memories").execute().await?;
+    add_missing_columns(&session_memories, &session_memories_schema()).await?;
+    let session_injections = connection.open_table("session_injections").execute().await?;
     let memory_relationships = connection.open_table("memory_relationships").execute().await?;
     let document_metadata = connection.open_table("document_metadata").execute().await?;
     let indexed_files = connection.open_table("indexed_files").execute().await?;
+    let skipped_files = connection.open_table("skipped_files").execute().await?;
+    let call_edges = connection.open_table("call_edges").execute().await?;
+    let extraction_jobs = connection.open_table("extraction_jobs").execute().await?;
+    let entities = connection.open_table("entities").execute().await?;
+    let memory_entities = connection.open_table("memory_entities").execute().await?;
+    let memory_events = connection.open_table("memory_events").execute().await?;
+    add_missing_columns(&memory_events, &memory_events_schema()).await?;
+    let search_logs = connection.open_table("search_logs").execute().await?;
+    add_missing_columns(&search_logs, &search_logs_schema()).await?;
 
     let db = Self {
       project_id,
+      path: db_path,
       connection,
       vector_dim: config.embedding.dimensions,
       session,
+      index: config.index.clone(),
+      cipher,
+      vector_index_baseline: tokio::sync::Mutex::new(HashMap::new()),
       memories,
       code_chunks,
       sessions_table,
       documents,
       session_memories,
+      session_injections,
       memory_relationships,
       document_metadata,
       indexed_files,
+      skipped_files,
+      call_edges,
+      extraction_jobs,
+      entities,
+      memory_entities,
+      memory_events,
+      search_logs,
     };
 
     // Create scalar indexes for improved query and merge_insert performance
@@ -146,11 +268,23 @@ impl ProjectDb {
     // Create FTS indexes for keyword search (idempotent)
     db.create_fts_indexes().await?;
 
+    // Bring vector indexes in line with the quantization setting
+    if let Err(e) = db.sync_vector_indexes().await {
+      warn!(err = %e, "Failed to sync vector indexes");
+    }
+
+    // Databases indexed before call edges existed get them from stored chunks
+    if created_call_edges {
+      db.rebuild_call_edges().await?;
+    }
+
     Ok(db)
   }
 
   /// Ensure all required tables exist (static version for use before struct creation)
-  async fn ensure_tables_static(connection: &Connection, vector_dim: usize) -> Result<()> {
+  ///
+  /// Returns whether the call_edges table had to be created.
+  async fn ensure_tables_static(connection: &Connection, vector_dim: usize) -> Result<bool> {
     let table_names = connection.table_names().execute().await?;
     debug!(existing_tables = table_names.len(), "Checking required tables");
 
@@ -194,6 +328,30 @@ impl ProjectDb {
         .await?;
     }
 
+    if !table_names.contains(&"session_injections".to_string()) {
+      debug!("Creating session_injections table");
+      connection
+        .create_empty_table("session_injections", session_injections_schema())
+        .execute()
+        .await?;
+    }
+
+    if !table_names.contains(&"memory_events".to_string()) {
+      debug!("Creating memory_events table");
+      connection
+        .create_empty_table("memory_events", memory_events_schema())
+        .execute()
+        .await?;
+    }
+
+    if !table_names.contains(&"search_logs".to_string()) {
+      debug!("Creating search_logs table");
+      connection
+        .create_empty_table("search_logs", search_logs_schema())
+        .execute()
+        .await?;
+    }
+
     if !table_names.contains(&"memory_relationships".to_string()) {
       debug!("Creating memory_relationships table");
       connection
@@ -218,7 +376,48 @@ impl ProjectDb {
         .await?;
     }
 
-    Ok(())
+    if !table_names.contains(&"skipped_files".to_string()) {
+      debug!("Creating skipped_files table");
+      connection
+        .create_empty_table("skipped_files", skipped_files_schema())
+        .execute()
+        .await?;
+    }
+
+    if !table_names.contains(&"extraction_jobs".to_string()) {
+      debug!("Creating extraction_jobs table");
+      connection
+        .create_empty_table("extraction_jobs", extraction_jobs_schema())
+        .execute()
+        .await?;
+    }
+
+    if !table_names.contains(&"entities".to_string()) {
+      debug!("Creating entities table");
+      connection
+        .create_empty_table("entities", entities_schema())
+        .execute()
+        .await?;
+    }
+
+    if !table_names.contains(&"memory_entities".to_string()) {
+      debug!("Creating memory_entities table");
+      connection
+        .create_empty_table("memory_entities", memory_entities_schema())
+        .execute()
+        .await?;
+    }
+
+    let create_call_edges = !table_names.contains(&"call_edges".to_string());
+    if create_call_edges {
+      debug!("Creating call_edges table");
+      connection
+        .create_empty_table("call_edges", call_edges_schema())
+        .execute()
+        .await?;
+    }
+
+    Ok(create_call_edges)
   }
 
   // ============================================================================
@@ -226,11 +425,30 @@ impl ProjectDb {
   // Tables are held permanently; dropping them doesn't free cached memory.
   // ============================================================================
 
+  /// LanceDB directory of this database
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Refuse writes while the daemon is in read-only mode
+  pub fn check_writable(&self) -> Result<()> {
+    if !disk::is_read_only() {
+      return Ok(());
+    }
+    let reason = disk::read_only_reason().unwrap_or_else(|| "disk space is low".to_string());
+    Err(DbError::ReadOnly(reason))
+  }
+
   /// Get the memories table
   pub fn memories_table(&self) -> &Table {
     &self.memories
   }
 
+  /// Cipher for memory and document content columns
+  pub fn cipher(&self) -> &ContentCipher {
+    &self.cipher
+  }
+
   /// Get the code_chunks table
   pub fn code_chunks_table(&self) -> &Table {
     &self.code_chunks
@@ -251,6 +469,21 @@ impl ProjectDb {
     &self.session_memories
   }
 
+  /// Get the session_injections table
+  pub fn session_injections_table(&self) -> &Table {
+    &self.session_injections
+  }
+
+  /// Get the memory_events table
+  pub fn memory_events_table(&self) -> &Table {
+    &self.memory_events
+  }
+
+  /// Get the search_logs table
+  pub fn search_logs_table(&self) -> &Table {
+    &self.search_logs
+  }
+
   /// Get the memory_relationships table
   pub fn memory_relationships_table(&self) -> &Table {
     &self.memory_relationships
@@ -266,6 +499,36 @@ impl ProjectDb {
     &self.indexed_files
   }
 
+  /// Get the skipped_files table
+  pub fn skipped_files_table(&self) -> &Table {
+    &self.skipped_files
+  }
+
+  /// Get the extraction_jobs table
+  pub fn extraction_jobs_table(&self) -> &Table {
+    &self.extraction_jobs
+  }
+
+  /// Get the entities table
+  pub fn entities_table(&self) -> &Table {
+    &self.entities
+  }
+
+  /// Get the memory_entities table
+  pub fn memory_entities_table(&self) -> &Table {
+    &self.memory_entities
+  }
+
+  /// Get the call_edges table
+  pub fn call_edges_table(&self) -> &Table {
+    &self.call_edges
+  }
+
+  /// Get a table by name
+  pub fn table_named(&self, name: &str) -> Option<&Table> {
+    self.all_tables().into_iter().find(|table| table.name() == name)
+  }
+
   // ============================================================================
   // Cache Statistics (for debugging memory usage)
   // ============================================================================
@@ -310,11 +573,14 @@ impl ProjectDb {
   pub async fn create_scalar_indexes(&self) -> Result<()> {
     debug!("Creating scalar indexes for improved query performance");
 
-    // code_chunks: merge_insert uses (file_path, start_line), queries filter by file_path, id
+    // code_chunks: merge_insert uses (file_path, start_line), queries filter by file_path, id, content_hash
     self
       .create_scalar_index_if_missing(&self.code_chunks, "file_path")
       .await?;
     self.create_scalar_index_if_missing(&self.code_chunks, "id").await?;
+    self
+      .create_scalar_index_if_missing(&self.code_chunks, "content_hash")
+      .await?;
 
     // memories: merge_insert uses id, queries filter by id, is_deleted
     self.create_scalar_index_if_missing(&self.memories, "id").await?;
@@ -336,6 +602,11 @@ impl ProjectDb {
       .create_scalar_index_if_missing(&self.indexed_files, "project_id")
       .await?;
 
+    // skipped_files: merge_insert and deletes use file_path
+    self
+      .create_scalar_index_if_missing(&self.skipped_files, "file_path")
+      .await?;
+
     // document_metadata: queries filter by source, id
     self
       .create_scalar_index_if_missing(&self.document_metadata, "source")
@@ -352,6 +623,16 @@ impl ProjectDb {
       .create_scalar_index_if_missing(&self.session_memories, "memory_id")
       .await?;
 
+    // session_injections: queries by session_id
+    self
+      .create_scalar_index_if_missing(&self.session_injections, "session_id")
+      .await?;
+
+    // memory_events: queries by memory_id
+    self
+      .create_scalar_index_if_missing(&self.memory_events, "memory_id")
+      .await?;
+
     // memory_relationships: queries by from_memory_id, to_memory_id
     self
       .create_scalar_index_if_missing(&self.memory_relationships, "from_memory_id")
@@ -360,9 +641,28 @@ impl ProjectDb {
       .create_scalar_index_if_missing(&self.memory_relationships, "to_memory_id")
       .await?;
 
+    // memory_entities: junction table queries by memory_id, entity_id
+    self
+      .create_scalar_index_if_missing(&self.memory_entities, "memory_id")
+      .await?;
+    self
+      .create_scalar_index_if_missing(&self.memory_entities, "entity_id")
+      .await?;
+
     // sessions: queries by id
     self.create_scalar_index_if_missing(&self.sessions_table, "id").await?;
 
+    // call_edges: traversal by caller_id and callee_name, replaced by file_path
+    self
+      .create_scalar_index_if_missing(&self.call_edges, "caller_id")
+      .await?;
+    self
+      .create_scalar_index_if_missing(&self.call_edges, "callee_name")
+      .await?;
+    self
+      .create_scalar_index_if_missing(&self.call_edges, "file_path")
+      .await?;
+
     debug!("Scalar index creation complete");
     Ok(())
   }
@@ -404,6 +704,12 @@ impl ProjectDb {
       .create_fts_index_if_missing(&self.code_chunks, "embedding_text", FtsIndexBuilder::default())
       .await?;
 
+    // Encrypted content can't be tokenized, so keyword search skips it
+    if self.cipher.is_enabled() {
+      debug!("FTS index creation complete (content encrypted)");
+      return Ok(());
+    }
+
     // memories: FTS on content (natural language)
     self
       .create_fts_index_if_missing(&self.memories, "content", FtsIndexBuilder::default())
@@ -446,6 +752,7 @@ impl ProjectDb {
   /// This drops and recreates the indexes.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn rebuild_fts_indexes(&self) -> Result<()> {
+    self.check_writable()?;
     use lancedb::index::scalar::FtsIndexBuilder;
 
     debug!("Rebuilding FTS indexes");
@@ -458,6 +765,11 @@ impl ProjectDb {
       .execute()
       .await?;
 
+    if self.cipher.is_enabled() {
+      debug!("FTS index rebuild complete (content encrypted)");
+      return Ok(());
+    }
+
     self
       .memories
       .create_index(&["content"], Index::FTS(FtsIndexBuilder::default()))
@@ -476,109 +788,212 @@ impl ProjectDb {
     Ok(())
   }
 
-  /// Create vector indexes for improved similarity search performance
+  /// Create or drop vector indexes to match the quantization setting
   ///
-  /// Vector indexes (IVF_PQ) accelerate `vector_search()` queries from O(n) to O(log n).
-  /// IVF_PQ is chosen over HNSW for lower memory usage (disk-based), which aligns
-  /// with our bounded cache strategy (256 MB index cache).
+  /// With int8 quantization, embedding tables with enough rows get an
+  /// IVF_HNSW_SQ index: vectors are scalar-quantized to 8 bits, so the index
+  /// is roughly a quarter of the full-precision size and searches touch far
+  /// less memory. The original vectors stay in the table for re-ranking.
+  /// Without quantization, any vector index is dropped so search stays exact.
   ///
-  /// Requirements:
-  /// - Tables should have at least 1,000+ rows for effective index training
-  /// - Index training is a one-time operation per table
-  ///
-  /// Configuration:
-  /// - num_partitions: 256 (good for 10K-1M rows, heuristic: sqrt(row_count))
-  /// - num_sub_vectors: vector_dim / 16 (optimal compression vs accuracy)
-  /// - distance_type: Cosine (match embedding model)
+  /// Idempotent - tables that already match are skipped.
   #[tracing::instrument(level = "trace", skip(self))]
-  pub async fn _create_vector_indexes(&self) -> Result<()> {
-    use lancedb::{DistanceType, index::vector::IvfPqIndexBuilder};
-
-    debug!("Creating vector indexes for similarity search acceleration");
-
-    // Calculate optimal sub-vectors based on dimension
-    // 4096 / 16 = 256 sub-vectors (sweet spot for compression vs accuracy)
-    let num_sub_vectors = (self.vector_dim / 16).max(1) as u32;
-
-    // code_chunks: primary table for code search
-    let code_count = self.code_chunks.count_rows(None).await?;
-    if code_count >= 1000 {
-      debug!(table = "code_chunks", rows = code_count, "Creating IVF_PQ vector index");
-      self
-        .code_chunks
-        .create_index(
-          &["vector"],
-          Index::IvfPq(
-            IvfPqIndexBuilder::default()
-              .distance_type(DistanceType::Cosine)
-              .num_partitions(256)
-              .num_sub_vectors(num_sub_vectors),
-          ),
-        )
-        .execute()
-        .await?;
-    } else {
-      debug!(
-        table = "code_chunks",
-        rows = code_count,
-        "Skipping vector index (need >= 1000 rows)"
-      );
+  pub async fn sync_vector_indexes(&self) -> Result<()> {
+    for table in [&self.code_chunks, &self.memories, &self.documents] {
+      self.sync_vector_index(table).await?;
     }
+    Ok(())
+  }
 
-    // memories: semantic memory search
-    let mem_count = self.memories.count_rows(None).await?;
-    if mem_count >= 1000 {
-      debug!(table = "memories", rows = mem_count, "Creating IVF_PQ vector index");
-      self
-        .memories
-        .create_index(
-          &["vector"],
-          Index::IvfPq(
-            IvfPqIndexBuilder::default()
-              .distance_type(DistanceType::Cosine)
-              .num_partitions(256)
-              .num_sub_vectors(num_sub_vectors),
-          ),
-        )
-        .execute()
-        .await?;
-    } else {
-      debug!(
-        table = "memories",
-        rows = mem_count,
-        "Skipping vector index (need >= 1000 rows)"
-      );
+  async fn sync_vector_index(&self, table: &Table) -> Result<()> {
+    match (self.index.quantization, Self::find_vector_index(table).await?) {
+      (VectorQuantization::None, Some(name)) => {
+        debug!(table = %table.name(), index = %name, "Dropping vector index (quantization disabled)");
+        table.drop_index(&name).await?;
+      }
+      (VectorQuantization::Int8, None) => {
+        let rows = table.count_rows(None).await?;
+        if rows < self.index.vector_index_min_rows {
+          trace!(table = %table.name(), rows, "Skipping vector index (too few rows)");
+          return Ok(());
+        }
+        self.create_vector_index(table, rows).await?;
+      }
+      _ => {}
     }
 
-    // documents: document chunk search
-    let doc_count = self.documents.count_rows(None).await?;
-    if doc_count >= 1000 {
-      debug!(table = "documents", rows = doc_count, "Creating IVF_PQ vector index");
-      self
-        .documents
-        .create_index(
-          &["vector"],
-          Index::IvfPq(
-            IvfPqIndexBuilder::default()
-              .distance_type(DistanceType::Cosine)
-              .num_partitions(256)
-              .num_sub_vectors(num_sub_vectors),
-          ),
-        )
-        .execute()
-        .await?;
-    } else {
-      debug!(
-        table = "documents",
-        rows = doc_count,
-        "Skipping vector index (need >= 1000 rows)"
-      );
-    }
+    Ok(())
+  }
+
+  /// Name of the index on the `vector` column, if any
+  async fn find_vector_index(table: &Table) -> Result<Option<String>> {
+    let indices = table.list_indices().await?;
+    Ok(
+      indices
+        .into_iter()
+        .find(|idx| idx.columns.iter().any(|c| c == "vector"))
+        .map(|idx| idx.name),
+    )
+  }
+
+  /// Train an IVF_HNSW_SQ index over the `vector` column, replacing any existing one
+  async fn create_vector_index(&self, table: &Table, rows: usize) -> Result<()> {
+    use lancedb::{DistanceType, index::vector::IvfHnswSqIndexBuilder};
+
+    // sqrt(rows) partitions is the usual IVF heuristic
+    let num_partitions = match self.index.ivf_num_partitions {
+      0 => ((rows as f64).sqrt() as u32).clamp(1, 256),
+      n => n,
+    };
+    debug!(table = %table.name(), rows, num_partitions, "Creating IVF_HNSW_SQ vector index");
+
+    table
+      .create_index(
+        &["vector"],
+        Index::IvfHnswSq(
+          IvfHnswSqIndexBuilder::default()
+            .distance_type(DistanceType::Cosine)
+            .num_partitions(num_partitions)
+            .m(self.index.hnsw_m)
+            .ef_construction(self.index.hnsw_ef_construction),
+        ),
+      )
+      .replace(true)
+      .execute()
+      .await?;
 
-    debug!("Vector index creation complete");
+    self
+      .vector_index_baseline
+      .lock()
+      .await
+      .insert(table.name().to_string(), rows);
     Ok(())
   }
 
+  /// Start a vector search, re-ranking quantized candidates with full-precision vectors
+  pub(in crate::db) fn vector_query(&self, table: &Table, query_vector: &[f32]) -> Result<VectorQuery> {
+    let query = table.vector_search(query_vector.to_vec())?;
+    Ok(match self.index.quantization {
+      VectorQuantization::None => query,
+      VectorQuantization::Int8 => query
+        .nprobes(self.index.search_nprobes.max(1))
+        .refine_factor(self.index.quantization_refine_factor.max(1)),
+    })
+  }
+
+  fn all_tables(&self) -> [&Table; 16] {
+    [
+      &self.memories,
+      &self.code_chunks,
+      &self.sessions_table,
+      &self.documents,
+      &self.session_memories,
+      &self.session_injections,
+      &self.memory_relationships,
+      &self.document_metadata,
+      &self.indexed_files,
+      &self.skipped_files,
+      &self.call_edges,
+      &self.extraction_jobs,
+      &self.entities,
+      &self.memory_entities,
+      &self.memory_events,
+      &self.search_logs,
+    ]
+  }
+
+  /// Row, fragment and vector index statistics for every table
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn table_health(&self) -> Result<Vec<TableHealth>> {
+    let mut health = Vec::new();
+
+    for table in self.all_tables() {
+      let stats = table.stats().await?;
+      let mut entry = TableHealth {
+        name: table.name().to_string(),
+        rows: stats.num_rows,
+        bytes: stats.total_bytes,
+        fragments: stats.fragment_stats.num_fragments,
+        small_fragments: stats.fragment_stats.num_small_fragments,
+        vector_index: None,
+        indexed_rows: 0,
+        unindexed_rows: 0,
+      };
+
+      if let Some(name) = Self::find_vector_index(table).await?
+        && let Some(index_stats) = table.index_stats(&name).await?
+      {
+        entry.vector_index = Some(index_stats.index_type.to_string());
+        entry.indexed_rows = index_stats.num_indexed_rows;
+        entry.unindexed_rows = index_stats.num_unindexed_rows;
+      }
+
+      health.push(entry);
+    }
+
+    Ok(health)
+  }
+
+  /// Background maintenance pass
+  ///
+  /// Retrains vector indexes once the table has grown by more than
+  /// `vector_index_rebuild_ratio` since the index was trained, then compacts
+  /// tables whose fragment count crossed `optimize_max_fragments`.
+  /// Incremental index updates put new rows in the existing IVF partitions,
+  /// so enough growth skews the centroids and hurts recall until retrained.
+  /// Indexes trained before this process started are measured from the row
+  /// count they covered when first seen.
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn maintain_indexes(&self) -> Result<MaintenanceReport> {
+    self.check_writable()?;
+    use lancedb::table::OptimizeAction;
+
+    let mut report = MaintenanceReport::default();
+
+    self.sync_vector_indexes().await?;
+
+    if self.index.quantization == VectorQuantization::Int8 {
+      for table in [&self.code_chunks, &self.memories, &self.documents] {
+        let Some(name) = Self::find_vector_index(table).await? else {
+          continue;
+        };
+        let Some(stats) = table.index_stats(&name).await? else {
+          continue;
+        };
+
+        let rows = table.count_rows(None).await?;
+        let baseline = *self
+          .vector_index_baseline
+          .lock()
+          .await
+          .entry(table.name().to_string())
+          .or_insert(stats.num_indexed_rows);
+
+        let growth = rows.saturating_sub(baseline) as f64;
+        if growth > baseline as f64 * self.index.vector_index_rebuild_ratio {
+          debug!(table = %table.name(), baseline, rows, "Rebuilding stale vector index");
+          self.create_vector_index(table, rows).await?;
+          report.rebuilt.push(table.name().to_string());
+        }
+      }
+    }
+
+    for table in self.all_tables() {
+      let stats = table.stats().await?;
+      if stats.fragment_stats.num_fragments >= self.index.optimize_max_fragments.max(1) {
+        debug!(
+          table = %table.name(),
+          fragments = stats.fragment_stats.num_fragments,
+          "Compacting fragmented table"
+        );
+        table.optimize(OptimizeAction::All).await?;
+        report.compacted.push(table.name().to_string());
+      }
+    }
+
+    Ok(report)
+  }
+
   /// Optimize all tables after batch write operations
   ///
   /// This updates scalar indexes to include newly written data. Without optimization,
@@ -589,25 +1004,97 @@ impl ProjectDb {
   /// - Periodically during long-running indexing (e.g., every N flushes)
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn optimize_indexes(&self) -> Result<()> {
+    self.check_writable()?;
     use lancedb::table::OptimizeAction;
 
     debug!("Optimizing indexes after batch writes");
 
+    // Tables may have grown past the vector index threshold
+    if let Err(e) = self.sync_vector_indexes().await {
+      warn!(err = %e, "Failed to sync vector indexes");
+    }
+
     // Optimize tables that receive frequent writes during indexing
     self.code_chunks.optimize(OptimizeAction::All).await?;
     self.indexed_files.optimize(OptimizeAction::All).await?;
+    self.skipped_files.optimize(OptimizeAction::All).await?;
     self.documents.optimize(OptimizeAction::All).await?;
     self.document_metadata.optimize(OptimizeAction::All).await?;
+    self.call_edges.optimize(OptimizeAction::All).await?;
 
     // These tables have less frequent writes but still benefit from optimization
     self.memories.optimize(OptimizeAction::All).await?;
     self.sessions_table.optimize(OptimizeAction::All).await?;
     self.session_memories.optimize(OptimizeAction::All).await?;
+    self.session_injections.optimize(OptimizeAction::All).await?;
+    self.memory_events.optimize(OptimizeAction::All).await?;
     self.memory_relationships.optimize(OptimizeAction::All).await?;
+    self.extraction_jobs.optimize(OptimizeAction::All).await?;
+    self.search_logs.optimize(OptimizeAction::All).await?;
 
     debug!("Index optimization complete");
     Ok(())
   }
+
+  // ============================================================================
+  // Project Migration
+  // ============================================================================
+
+  /// Rebind rows stored under another project's ID to this database's project
+  ///
+  /// Used after a project's data directory has been moved to the ID of its
+  /// new root. Memories, sessions and documents carry the project UUID;
+  /// indexed files carry the ProjectId string.
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn rebind_project(&self, from: &ProjectId) -> Result<()> {
+    self.check_writable()?;
+    let (old_uuid, new_uuid) = (from.uuid().to_string(), self.project_id.uuid().to_string());
+    for table in [
+      &self.memories,
+      &self.sessions_table,
+      &self.documents,
+      &self.document_metadata,
+    ] {
+      table
+        .update()
+        .only_if(format!("project_id = '{}'", old_uuid))
+        .column("project_id", format!("'{}'", new_uuid))
+        .execute()
+        .await?;
+    }
+
+    self
+      .indexed_files
+      .update()
+      .only_if(format!("project_id = '{}'", from.as_str()))
+      .column("project_id", format!("'{}'", self.project_id.as_str()))
+      .execute()
+      .await?;
+
+    debug!(from = %from, to = %self.project_id, "Rebound project rows");
+    Ok(())
+  }
+}
+
+/// Add the columns of `schema` that a table created by an older version lacks,
+/// filled with nulls
+pub(super) async fn add_missing_columns(table: &Table, schema: &Schema) -> Result<()> {
+  let existing = table.schema().await?;
+  let missing: Vec<_> = schema
+    .fields()
+    .iter()
+    .filter(|field| existing.field_with_name(field.name()).is_err())
+    .map(|field| field.as_ref().clone())
+    .collect();
+  if missing.is_empty() {
+    return Ok(());
+  }
+
+  debug!(table = %table.name(), count = missing.len(), "Adding missing columns");
+  table
+    .add_columns(NewColumnTransform::AllNulls(Arc::new(Schema::new(missing))), None)
+    .await?;
+  Ok(())
 }
 
 #[cfg(test)]
@@ -658,5 +1145,54 @@ mod tests {
       tables.contains(&"documents".to_string()),
       "documents table should exist"
     );
+    assert!(
+      tables.contains(&"call_edges".to_string()),
+      "call_edges table should exist"
+    );
+    assert!(
+      tables.contains(&"memory_entities".to_string()),
+      "memory_entities table should exist"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_maintain_indexes_compacts_fragmented_tables() {
+    use crate::domain::memory::{Memory, Sector};
+
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test/project")).await;
+    let mut config = Config::default();
+    config.index.optimize_max_fragments = 3;
+
+    let db = ProjectDb::open_at_path(project_id, temp_dir.path().join("test.lancedb"), Arc::new(config))
+      .await
+      .unwrap();
+
+    // Each insert writes its own fragment
+    let project_uuid = uuid::Uuid::new_v4();
+    for i in 0..3 {
+      let memory = Memory::new(project_uuid, format!("memory {}", i), Sector::Semantic);
+      db.add_memory(&memory, &vec![0.0; db.vector_dim]).await.unwrap();
+    }
+
+    let memories_health = |health: Vec<TableHealth>| health.into_iter().find(|t| t.name == "memories").unwrap();
+
+    let before = memories_health(db.table_health().await.unwrap());
+    assert_eq!(before.rows, 3, "all memories should be counted");
+    assert_eq!(before.fragments, 3, "each insert should add a fragment");
+
+    let report = db.maintain_indexes().await.unwrap();
+    assert!(
+      report.compacted.contains(&"memories".to_string()),
+      "fragmented table should be compacted"
+    );
+    assert!(
+      !report.compacted.contains(&"sessions".to_string()),
+      "empty tables should be left alone"
+    );
+
+    let after = memories_health(db.table_health().await.unwrap());
+    assert_eq!(after.rows, 3, "compaction must not lose rows");
+    assert_eq!(after.fragments, 1, "compaction should merge fragments");
   }
 }
@@ -14,6 +14,7 @@ use lancedb::query::{ExecutableQuery, QueryBase};
 
 use crate::{
   db::{
+    FilterBuilder,
     connection::{DbError, ProjectDb, Result},
     schema::document_metadata_schema,
   },
@@ -24,9 +25,13 @@ impl ProjectDb {
   /// Add or update document metadata
   #[tracing::instrument(level = "trace", skip(self, doc), fields(id = %doc.id))]
   pub async fn upsert_document_metadata(&self, doc: &Document) -> Result<()> {
+    self.check_writable()?;
     // Delete existing if present
     let table = self.document_metadata_table();
-    table.delete(&format!("id = '{}'", doc.id)).await.ok();
+    table
+      .delete(&FilterBuilder::new().add_eq("id", &doc.id.to_string()).build_or_empty())
+      .await
+      .ok();
 
     // Insert new
     let batch = document_to_batch(doc)?;
@@ -41,6 +46,7 @@ impl ProjectDb {
   /// Uses a single delete with IN clause and a single batch insert.
   #[tracing::instrument(level = "trace", skip(self, docs), fields(count = docs.len()))]
   pub async fn upsert_document_metadata_batch(&self, docs: &[Document]) -> Result<()> {
+    self.check_writable()?;
     if docs.is_empty() {
       return Ok(());
     }
@@ -48,12 +54,12 @@ impl ProjectDb {
     let table = self.document_metadata_table();
 
     // Delete all existing docs with one query using IN clause
-    let ids_filter = docs
-      .iter()
-      .map(|d| format!("'{}'", d.id))
-      .collect::<Vec<_>>()
-      .join(", ");
-    table.delete(&format!("id IN ({})", ids_filter)).await.ok();
+    let ids: Vec<String> = docs.iter().map(|d| d.id.to_string()).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    table
+      .delete(&FilterBuilder::new().add_in("id", &ids).build_or_empty())
+      .await
+      .ok();
 
     // Insert all docs in one batch
     let batch = documents_to_batch(docs)?;
@@ -67,12 +73,9 @@ impl ProjectDb {
   pub async fn get_document_by_source(&self, source: &str) -> Result<Option<Document>> {
     let table = self.document_metadata_table();
 
-    // Escape single quotes in source path
-    let escaped_source = source.replace('\'', "''");
-
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("source = '{}'", escaped_source))
+      .only_if(FilterBuilder::new().add_eq("source", source).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -88,9 +91,11 @@ impl ProjectDb {
   /// Delete document metadata by source path
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_document_by_source(&self, source: &str) -> Result<()> {
-    let escaped_source = source.replace('\'', "''");
+    self.check_writable()?;
     let table = self.document_metadata_table();
-    table.delete(&format!("source = '{}'", escaped_source)).await?;
+    table
+      .delete(&FilterBuilder::new().add_eq("source", source).build_or_empty())
+      .await?;
     Ok(())
   }
 }
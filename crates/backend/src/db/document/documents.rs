@@ -11,7 +11,8 @@ use uuid::Uuid;
 
 use crate::{
   db::{
-    connection::{DbError, ProjectDb, Result},
+    ContentCipher, FilterBuilder,
+    connection::{DbError, ProjectDb, Result, id_prefix_filter},
     schema::documents_schema,
   },
   domain::document::{DocumentChunk, DocumentId, DocumentSource},
@@ -31,6 +32,7 @@ impl ProjectDb {
     chunks: &[DocumentChunk],
     vectors: &[Vec<f32>],
   ) -> Result<()> {
+    self.check_writable()?;
     if chunks.is_empty() {
       // If no chunks, just delete any existing chunks for this source
       return self.delete_document_chunks_by_source(source).await;
@@ -49,7 +51,7 @@ impl ProjectDb {
     let batches: Vec<RecordBatch> = chunks
       .iter()
       .zip(vectors.iter())
-      .map(|(chunk, vec)| chunk_to_batch(chunk, vec, self.vector_dim))
+      .map(|(chunk, vec)| chunk_to_batch(chunk, vec, self.vector_dim, self.cipher()))
       .collect::<Result<Vec<_>>>()?;
 
     let schema = documents_schema(self.vector_dim);
@@ -58,12 +60,11 @@ impl ProjectDb {
     // Use merge_insert with source + chunk_index as the key
     // This uniquely identifies each chunk's position and avoids collisions when
     // multiple chunks have identical content (same content_hash).
-    let escaped_source = source.replace('\'', "''");
     let mut builder = table.merge_insert(&["source", "chunk_index"]);
     builder
       .when_matched_update_all(None)
       .when_not_matched_insert_all()
-      .when_not_matched_by_source_delete(Some(format!("source = '{}'", escaped_source)));
+      .when_not_matched_by_source_delete(FilterBuilder::new().add_eq("source", source).build());
     builder.execute(Box::new(iter)).await?;
 
     Ok(())
@@ -80,16 +81,14 @@ impl ProjectDb {
     chunks: &[DocumentChunk],
     vectors: &[Vec<f32>],
   ) -> Result<()> {
+    self.check_writable()?;
     let table = self.documents_table();
 
     // Single bulk delete for all sources
     if !sources.is_empty() {
-      let sources_filter = sources
-        .iter()
-        .map(|s| format!("'{}'", s.replace('\'', "''")))
-        .collect::<Vec<_>>()
-        .join(", ");
-      table.delete(&format!("source IN ({})", sources_filter)).await?;
+      table
+        .delete(&FilterBuilder::new().add_in("source", sources).build_or_empty())
+        .await?;
     }
 
     if chunks.is_empty() {
@@ -105,7 +104,7 @@ impl ProjectDb {
     );
 
     // Create batched RecordBatch with all chunks and add them
-    let batch = chunks_to_batch(chunks, vectors, self.vector_dim)?;
+    let batch = chunks_to_batch(chunks, vectors, self.vector_dim, self.cipher())?;
     let schema = documents_schema(self.vector_dim);
     let iter = RecordBatchIterator::new(vec![Ok(batch)], schema);
     table.add(Box::new(iter)).execute().await?;
@@ -117,11 +116,10 @@ impl ProjectDb {
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn get_document_chunk(&self, id: &DocumentId) -> Result<Option<DocumentChunk>> {
     let table = self.documents_table();
-    let id_str = id.to_string();
 
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("id = '{}'", id_str))
+      .only_if(FilterBuilder::new().add_eq("id", &id.to_string()).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -136,7 +134,7 @@ impl ProjectDb {
       return Ok(None);
     }
 
-    Ok(Some(batch_to_chunk(batch, 0)?))
+    Ok(Some(batch_to_chunk(batch, 0, self.cipher())?))
   }
 
   /// Search document chunks by full-text search (BM25)
@@ -160,6 +158,11 @@ impl ProjectDb {
       "FTS searching documents"
     );
 
+    // Encrypted content has no FTS index; hybrid search falls back to vectors
+    if self.cipher().is_enabled() {
+      return Ok(Vec::new());
+    }
+
     let table = self.documents_table();
 
     let fts_query = FullTextSearchQuery::new(query.to_owned())
@@ -177,7 +180,7 @@ impl ProjectDb {
     let mut chunks = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        let chunk = batch_to_chunk(&batch, i)?;
+        let chunk = batch_to_chunk(&batch, i, self.cipher())?;
         let score = batch
           .column_by_name("_score")
           .and_then(|col| col.as_any().downcast_ref::<Float32Array>())
@@ -217,9 +220,9 @@ impl ProjectDb {
     let table = self.documents_table();
 
     let query = if let Some(f) = filter {
-      table.vector_search(query_vector.to_vec())?.limit(limit).only_if(f)
+      self.vector_query(table, query_vector)?.limit(limit).only_if(f)
     } else {
-      table.vector_search(query_vector.to_vec())?.limit(limit)
+      self.vector_query(table, query_vector)?.limit(limit)
     };
 
     let results: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
@@ -227,7 +230,7 @@ impl ProjectDb {
     let mut chunks = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        let chunk = batch_to_chunk(&batch, i)?;
+        let chunk = batch_to_chunk(&batch, i, self.cipher())?;
         let distance = batch
           .column_by_name("_distance")
           .and_then(|col| col.as_any().downcast_ref::<Float32Array>())
@@ -264,7 +267,7 @@ impl ProjectDb {
     let mut chunks = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        chunks.push(batch_to_chunk(&batch, i)?);
+        chunks.push(batch_to_chunk(&batch, i, self.cipher())?);
       }
     }
 
@@ -274,9 +277,12 @@ impl ProjectDb {
   /// Delete a single document chunk
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_document_chunk(&self, id: &DocumentId) -> Result<()> {
+    self.check_writable()?;
     debug!(table = "documents", operation = "delete_chunk", id = %id, "Deleting document chunk");
     let table = self.documents_table();
-    table.delete(&format!("id = '{}'", id)).await?;
+    table
+      .delete(&FilterBuilder::new().add_eq("id", &id.to_string()).build_or_empty())
+      .await?;
     Ok(())
   }
 
@@ -285,12 +291,7 @@ impl ProjectDb {
   /// Searches for document chunks whose ID starts with the given prefix.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn find_document_chunks_by_prefix(&self, prefix: &str) -> Result<Vec<DocumentChunk>> {
-    if prefix.len() < 6 {
-      return Err(DbError::InvalidInput("ID prefix must be at least 6 characters".into()));
-    }
-
-    // Use LIKE query for prefix matching
-    let filter = format!("id LIKE '{}%'", prefix);
+    let filter = id_prefix_filter(prefix)?;
     self.list_document_chunks(Some(&filter), Some(10)).await
   }
 
@@ -340,10 +341,13 @@ impl ProjectDb {
     let start_index = center_index.saturating_sub(chunks_before);
     let end_index = center_index + chunks_after;
 
-    let filter = format!(
-      "document_id = '{}' AND chunk_index >= {} AND chunk_index <= {}",
-      document_id, start_index, end_index
-    );
+    let filter = FilterBuilder::new()
+      .add_eq("document_id", &document_id.to_string())
+      .add_raw(format!(
+        "chunk_index >= {} AND chunk_index <= {}",
+        start_index, end_index
+      ))
+      .build_or_empty();
 
     let mut chunks = self.list_document_chunks(Some(&filter), None).await?;
 
@@ -359,6 +363,7 @@ impl ProjectDb {
   /// before re-indexing.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_document_chunks_by_source(&self, source: &str) -> Result<()> {
+    self.check_writable()?;
     debug!(
       table = "documents",
       operation = "delete_chunks_by_source",
@@ -366,8 +371,9 @@ impl ProjectDb {
       "Deleting document chunks by source"
     );
     let table = self.documents_table();
-    let escaped = source.replace('\'', "''");
-    table.delete(&format!("source = '{}'", escaped)).await?;
+    table
+      .delete(&FilterBuilder::new().add_eq("source", source).build_or_empty())
+      .await?;
     Ok(())
   }
 
@@ -377,6 +383,7 @@ impl ProjectDb {
   /// More efficient than delete + re-index since embeddings are preserved.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn rename_document(&self, from: &str, to: &str) -> Result<usize> {
+    self.check_writable()?;
     debug!(
       table = "documents",
       operation = "rename",
@@ -388,21 +395,17 @@ impl ProjectDb {
     let table = self.documents_table();
 
     // Count chunks before rename
-    let filter = format!("source = '{}'", from.replace('\'', "''"));
+    let filter = FilterBuilder::new().add_eq("source", from).build_or_empty();
     let count = table.count_rows(Some(filter.clone())).await?;
 
     if count == 0 {
       return Ok(0);
     }
 
-    // Update source field
-    let old_escaped = from.replace('\'', "''");
-    let new_escaped = to.replace('\'', "''");
-
     table
       .update()
-      .only_if(format!("source = '{}'", old_escaped))
-      .column("source", format!("'{}'", new_escaped))
+      .only_if(filter)
+      .column("source", FilterBuilder::literal(to))
       .execute()
       .await?;
 
@@ -411,13 +414,18 @@ impl ProjectDb {
 }
 
 /// Convert multiple DocumentChunks to a single Arrow RecordBatch (true batch insert)
-fn chunks_to_batch(chunks: &[DocumentChunk], vectors: &[Vec<f32>], vector_dim: usize) -> Result<RecordBatch> {
+fn chunks_to_batch(
+  chunks: &[DocumentChunk],
+  vectors: &[Vec<f32>],
+  vector_dim: usize,
+  cipher: &ContentCipher,
+) -> Result<RecordBatch> {
   let n = chunks.len();
 
   let ids: Vec<String> = chunks.iter().map(|c| c.id.to_string()).collect();
   let document_ids: Vec<String> = chunks.iter().map(|c| c.document_id.to_string()).collect();
   let project_ids: Vec<String> = chunks.iter().map(|c| c.project_id.to_string()).collect();
-  let contents: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+  let contents: Vec<String> = chunks.iter().map(|c| cipher.seal(&c.content)).collect::<Result<_>>()?;
   let titles: Vec<&str> = chunks.iter().map(|c| c.title.as_str()).collect();
   let sources: Vec<&str> = chunks.iter().map(|c| c.source.as_str()).collect();
   let source_types: Vec<String> = chunks.iter().map(|c| c.source_type.as_str().to_string()).collect();
@@ -464,11 +472,16 @@ fn chunks_to_batch(chunks: &[DocumentChunk], vectors: &[Vec<f32>], vector_dim: u
 }
 
 /// Convert a DocumentChunk to an Arrow RecordBatch
-fn chunk_to_batch(chunk: &DocumentChunk, vector: &[f32], vector_dim: usize) -> Result<RecordBatch> {
+fn chunk_to_batch(
+  chunk: &DocumentChunk,
+  vector: &[f32],
+  vector_dim: usize,
+  cipher: &ContentCipher,
+) -> Result<RecordBatch> {
   let id = StringArray::from(vec![chunk.id.to_string()]);
   let document_id = StringArray::from(vec![chunk.document_id.to_string()]);
   let project_id = StringArray::from(vec![chunk.project_id.to_string()]);
-  let content = StringArray::from(vec![chunk.content.clone()]);
+  let content = StringArray::from(vec![cipher.seal(&chunk.content)?]);
   let title = StringArray::from(vec![chunk.title.clone()]);
   let source = StringArray::from(vec![chunk.source.clone()]);
   let source_type = StringArray::from(vec![chunk.source_type.as_str().to_string()]);
@@ -512,7 +525,7 @@ fn chunk_to_batch(chunk: &DocumentChunk, vector: &[f32], vector_dim: usize) -> R
 }
 
 /// Convert a RecordBatch row to a DocumentChunk
-fn batch_to_chunk(batch: &RecordBatch, row: usize) -> Result<DocumentChunk> {
+fn batch_to_chunk(batch: &RecordBatch, row: usize, cipher: &ContentCipher) -> Result<DocumentChunk> {
   let get_string = |name: &str| -> Result<String> {
     batch
       .column_by_name(name)
@@ -566,7 +579,7 @@ fn batch_to_chunk(batch: &RecordBatch, row: usize) -> Result<DocumentChunk> {
       .parse()
       .map_err(|_| DbError::NotFound("invalid document_id".into()))?,
     project_id: Uuid::parse_str(&project_id_str).map_err(|_| DbError::NotFound("invalid project_id".into()))?,
-    content: get_string("content")?,
+    content: cipher.open(get_string("content")?)?,
     title: get_string("title")?,
     source: get_string("source")?,
     source_type,
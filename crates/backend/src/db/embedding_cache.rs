@@ -0,0 +1,246 @@
+// Persistent embedding cache shared by all projects
+//
+// Document embeddings are keyed by a SHA-256 of the model id and the exact
+// text sent to the provider, so re-indexing after a daemon restart, a branch
+// switch or in another checkout of the same code reuses stored vectors.
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use arrow_array::{Array, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::Utc;
+use futures::TryStreamExt;
+use lancedb::{
+  ObjectStoreRegistry, Session, Table, connect,
+  query::{ExecutableQuery, QueryBase},
+};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::db::{connection::Result, schema::embedding_cache_schema};
+
+/// Index/metadata cache sizes for the cache database. Lookups are exact key
+/// filters, so it needs far less than a project database.
+const CACHE_SESSION_BYTES: usize = 16 * 1024 * 1024;
+
+/// Content-hash keyed embedding store, one table per vector dimension
+pub struct EmbeddingCacheDb {
+  table: Table,
+  vector_dim: usize,
+}
+
+impl EmbeddingCacheDb {
+  /// Open or create the cache database at `db_path`
+  pub async fn open(db_path: &Path, vector_dim: usize) -> Result<Self> {
+    tokio::fs::create_dir_all(db_path).await?;
+
+    let registry = Arc::new(ObjectStoreRegistry::default());
+    let session = Arc::new(Session::new(CACHE_SESSION_BYTES, CACHE_SESSION_BYTES, registry));
+    let connection = connect(db_path.to_string_lossy().as_ref())
+      .session(session)
+      .execute()
+      .await?;
+
+    // Dimension changes get a fresh table rather than a schema mismatch
+    let table_name = format!("embeddings_{}", vector_dim);
+    let table_names = connection.table_names().execute().await?;
+    if !table_names.contains(&table_name) {
+      debug!(table = %table_name, "Creating embedding cache table");
+      connection
+        .create_empty_table(&table_name, embedding_cache_schema(vector_dim))
+        .execute()
+        .await?;
+    }
+    let table = connection.open_table(&table_name).execute().await?;
+
+    Ok(Self { table, vector_dim })
+  }
+
+  /// Cache key for a text embedded with a given model
+  pub fn key(model_id: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model_id.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(text.as_bytes());
+    hex::encode(hasher.finalize())
+  }
+
+  /// Look up cached vectors, returning only the keys that were found
+  #[tracing::instrument(level = "trace", skip(self, keys), fields(count = keys.len()))]
+  pub async fn get_many(&self, keys: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+    if keys.is_empty() {
+      return Ok(HashMap::new());
+    }
+
+    // Keys are hex digests, so they need no escaping
+    let filter = keys.iter().map(|k| format!("'{}'", k)).collect::<Vec<_>>().join(", ");
+
+    let results: Vec<RecordBatch> = self
+      .table
+      .query()
+      .only_if(format!("key IN ({})", filter))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut found = HashMap::with_capacity(keys.len());
+    for batch in &results {
+      let Some(key_col) = batch
+        .column_by_name("key")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      else {
+        continue;
+      };
+      let Some(vector_col) = batch
+        .column_by_name("vector")
+        .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>())
+      else {
+        continue;
+      };
+
+      for row in 0..batch.num_rows() {
+        if vector_col.is_null(row) {
+          continue;
+        }
+        let values = vector_col.value(row);
+        if let Some(floats) = values.as_any().downcast_ref::<Float32Array>() {
+          found.insert(key_col.value(row).to_string(), floats.values().to_vec());
+        }
+      }
+    }
+
+    Ok(found)
+  }
+
+  /// Store vectors, skipping keys that are already cached
+  #[tracing::instrument(level = "trace", skip(self, entries), fields(count = entries.len()))]
+  pub async fn put_many(&self, model_id: &str, entries: &[(String, Vec<f32>)]) -> Result<()> {
+    let entries: Vec<&(String, Vec<f32>)> = entries.iter().filter(|(_, v)| v.len() == self.vector_dim).collect();
+    if entries.is_empty() {
+      return Ok(());
+    }
+
+    let n = entries.len();
+    let keys: Vec<String> = entries.iter().map(|(k, _)| k.clone()).collect();
+    let mut all_vectors: Vec<f32> = Vec::with_capacity(n * self.vector_dim);
+    for (_, vector) in &entries {
+      all_vectors.extend_from_slice(vector);
+    }
+
+    let field = Arc::new(arrow_schema::Field::new("item", arrow_schema::DataType::Float32, true));
+    let vector_list = FixedSizeListArray::try_new(
+      field,
+      self.vector_dim as i32,
+      Arc::new(Float32Array::from(all_vectors)),
+      None,
+    )?;
+
+    let schema = embedding_cache_schema(self.vector_dim);
+    let batch = RecordBatch::try_new(
+      schema.clone(),
+      vec![
+        Arc::new(StringArray::from(keys)),
+        Arc::new(StringArray::from(vec![model_id.to_string(); n])),
+        Arc::new(Int64Array::from(vec![Utc::now().timestamp_millis(); n])),
+        Arc::new(vector_list),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], schema);
+
+    let mut builder = self.table.merge_insert(&["key"]);
+    builder.when_not_matched_insert_all();
+    builder.execute(Box::new(batches)).await?;
+
+    Ok(())
+  }
+
+  /// Number of cached vectors
+  pub async fn count(&self) -> Result<usize> {
+    Ok(self.table.count_rows(None).await?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_put_and_get_many() {
+    let temp = TempDir::new().unwrap();
+    let cache = EmbeddingCacheDb::open(&temp.path().join("embedding_cache"), 4)
+      .await
+      .unwrap();
+
+    let hit = EmbeddingCacheDb::key("model-a", "fn main() {}");
+    let miss = EmbeddingCacheDb::key("model-a", "fn other() {}");
+    cache
+      .put_many("model-a", &[(hit.clone(), vec![0.1, 0.2, 0.3, 0.4])])
+      .await
+      .unwrap();
+
+    let found = cache.get_many(&[hit.clone(), miss.clone()]).await.unwrap();
+    assert_eq!(
+      found.get(&hit),
+      Some(&vec![0.1, 0.2, 0.3, 0.4]),
+      "stored vector should be returned"
+    );
+    assert!(!found.contains_key(&miss), "unknown key should not be returned");
+
+    // Re-inserting the same key must not duplicate rows
+    cache
+      .put_many("model-a", &[(hit.clone(), vec![0.1, 0.2, 0.3, 0.4])])
+      .await
+      .unwrap();
+    assert_eq!(cache.count().await.unwrap(), 1, "duplicate keys should be skipped");
+  }
+
+  #[tokio::test]
+  async fn test_cache_survives_reopen() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("embedding_cache");
+    let key = EmbeddingCacheDb::key("model-a", "struct Foo;");
+
+    {
+      let cache = EmbeddingCacheDb::open(&path, 2).await.unwrap();
+      cache
+        .put_many("model-a", &[(key.clone(), vec![1.0, 2.0])])
+        .await
+        .unwrap();
+    }
+
+    let cache = EmbeddingCacheDb::open(&path, 2).await.unwrap();
+    let found = cache.get_many(std::slice::from_ref(&key)).await.unwrap();
+    assert_eq!(
+      found.get(&key),
+      Some(&vec![1.0, 2.0]),
+      "vector should persist across reopen"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_wrong_dimension_vectors_are_skipped() {
+    let temp = TempDir::new().unwrap();
+    let cache = EmbeddingCacheDb::open(&temp.path().join("embedding_cache"), 3)
+      .await
+      .unwrap();
+
+    let key = EmbeddingCacheDb::key("model-a", "bad");
+    cache.put_many("model-a", &[(key, vec![1.0])]).await.unwrap();
+    assert_eq!(
+      cache.count().await.unwrap(),
+      0,
+      "mismatched vectors should not be cached"
+    );
+  }
+
+  #[test]
+  fn test_key_depends_on_model() {
+    assert_ne!(
+      EmbeddingCacheDb::key("model-a", "text"),
+      EmbeddingCacheDb::key("model-b", "text"),
+      "different models must not share cache entries"
+    );
+  }
+}
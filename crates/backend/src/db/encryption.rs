@@ -0,0 +1,206 @@
+//! Encryption at rest for memory and document content.
+//!
+//! With `[encryption]` enabled, the content and summary of memories and the
+//! content of document chunks are sealed with AES-256-GCM as rows are built
+//! and opened again as they are read, so nothing above `ProjectDb` sees the
+//! difference. Sealed values carry a prefix: rows written before encryption
+//! was enabled stay readable and are sealed the next time they are written.
+//!
+//! The key comes from the environment variable named by `key_env` or, when
+//! that is unset, from the OS keychain, where one is generated on first use.
+
+use std::sync::Mutex;
+
+use aes_gcm::{
+  Aes256Gcm, Key, KeyInit, Nonce,
+  aead::{Aead, AeadCore, OsRng},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use tracing::info;
+
+use super::connection::{DbError, Result};
+use crate::config::EncryptionConfig;
+
+/// Marks a sealed value: `enc:v1:<base64 of nonce + ciphertext>`
+const PREFIX: &str = "enc:v1:";
+
+const NONCE_LEN: usize = 12;
+
+const KEYCHAIN_SERVICE: &str = "ccengram";
+const KEYCHAIN_ACCOUNT: &str = "encryption-key";
+
+/// Key read from the keychain, so it is looked up (or generated) once per
+/// daemon rather than once per project
+static KEYCHAIN_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+
+/// Seals and opens content columns; passes them through when encryption is off
+#[derive(Clone, Default)]
+pub struct ContentCipher {
+  cipher: Option<Aes256Gcm>,
+}
+
+impl ContentCipher {
+  pub fn new(key: &[u8; 32]) -> Self {
+    Self {
+      cipher: Some(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))),
+    }
+  }
+
+  /// Load the key for `config`, or a pass-through cipher when disabled
+  pub fn from_config(config: &EncryptionConfig) -> Result<Self> {
+    if !config.enabled {
+      return Ok(Self::default());
+    }
+
+    let key = match std::env::var(&config.key_env) {
+      Ok(encoded) if !encoded.trim().is_empty() => {
+        decode_key(&encoded).map_err(|e| DbError::Encryption(format!("{}: {}", config.key_env, e)))?
+      }
+      _ => keychain_key()?,
+    };
+    Ok(Self::new(&key))
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.cipher.is_some()
+  }
+
+  /// Whether a stored value was encrypted by some cipher
+  pub fn is_sealed(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+  }
+
+  /// Encrypt `text` for storage
+  pub fn seal(&self, text: &str) -> Result<String> {
+    let Some(cipher) = &self.cipher else {
+      return Ok(text.to_string());
+    };
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let sealed = cipher
+      .encrypt(&nonce, text.as_bytes())
+      .map_err(|_| DbError::Encryption("failed to encrypt content".to_string()))?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&sealed);
+    Ok(format!("{}{}", PREFIX, STANDARD.encode(payload)))
+  }
+
+  /// Decrypt a stored value; values stored unencrypted are returned as is
+  pub fn open(&self, stored: String) -> Result<String> {
+    let Some(encoded) = stored.strip_prefix(PREFIX) else {
+      return Ok(stored);
+    };
+    let Some(cipher) = &self.cipher else {
+      return Err(DbError::Encryption(
+        "content is encrypted but [encryption] is disabled; enable it with the key it was written with".to_string(),
+      ));
+    };
+
+    let payload = STANDARD
+      .decode(encoded)
+      .map_err(|e| DbError::Encryption(format!("malformed encrypted content: {}", e)))?;
+    if payload.len() < NONCE_LEN {
+      return Err(DbError::Encryption(
+        "malformed encrypted content: too short".to_string(),
+      ));
+    }
+    let (nonce, sealed) = payload.split_at(NONCE_LEN);
+    let plain = cipher.decrypt(Nonce::from_slice(nonce), sealed).map_err(|_| {
+      DbError::Encryption("failed to decrypt content; the key differs from the one it was written with".to_string())
+    })?;
+
+    String::from_utf8(plain).map_err(|e| DbError::Encryption(format!("decrypted content is not UTF-8: {}", e)))
+  }
+}
+
+fn decode_key(encoded: &str) -> std::result::Result<[u8; 32], String> {
+  let bytes = STANDARD
+    .decode(encoded.trim())
+    .map_err(|e| format!("key is not base64: {}", e))?;
+  bytes
+    .try_into()
+    .map_err(|bytes: Vec<u8>| format!("key must be 32 bytes, got {}", bytes.len()))
+}
+
+/// The key kept in the OS keychain, generating and storing one if missing
+fn keychain_key() -> Result<[u8; 32]> {
+  let mut cached = KEYCHAIN_KEY.lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(key) = *cached {
+    return Ok(key);
+  }
+
+  let keychain_error = |e: keyring::Error| DbError::Encryption(format!("OS keychain: {}", e));
+  let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).map_err(keychain_error)?;
+  let key = match entry.get_password() {
+    Ok(encoded) => decode_key(&encoded).map_err(|e| DbError::Encryption(format!("OS keychain: {}", e)))?,
+    Err(keyring::Error::NoEntry) => {
+      let key: [u8; 32] = Aes256Gcm::generate_key(OsRng).into();
+      entry.set_password(&STANDARD.encode(key)).map_err(keychain_error)?;
+      info!(
+        service = KEYCHAIN_SERVICE,
+        account = KEYCHAIN_ACCOUNT,
+        "Generated an encryption key in the OS keychain"
+      );
+      key
+    }
+    Err(e) => return Err(keychain_error(e)),
+  };
+
+  *cached = Some(key);
+  Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_seal_and_open() {
+    let cipher = ContentCipher::new(&[7u8; 32]);
+    let sealed = cipher.seal("The deploy script needs AWS_PROFILE=prod").unwrap();
+    assert!(sealed.starts_with(PREFIX));
+    assert!(!sealed.contains("AWS_PROFILE"));
+    assert_ne!(
+      sealed,
+      cipher.seal("The deploy script needs AWS_PROFILE=prod").unwrap(),
+      "fresh nonce per value"
+    );
+    assert_eq!(cipher.open(sealed).unwrap(), "The deploy script needs AWS_PROFILE=prod");
+
+    assert_eq!(
+      cipher.open("written before encryption".to_string()).unwrap(),
+      "written before encryption"
+    );
+  }
+
+  #[test]
+  fn test_open_needs_the_same_key() {
+    let sealed = ContentCipher::new(&[7u8; 32]).seal("secret").unwrap();
+    assert!(matches!(
+      ContentCipher::new(&[8u8; 32]).open(sealed.clone()),
+      Err(DbError::Encryption(_))
+    ));
+    assert!(matches!(
+      ContentCipher::default().open(sealed),
+      Err(DbError::Encryption(_))
+    ));
+    assert_eq!(ContentCipher::default().seal("plain").unwrap(), "plain");
+  }
+
+  #[test]
+  fn test_key_from_env() {
+    let config = EncryptionConfig {
+      enabled: true,
+      key_env: "CCENGRAM_TEST_ENCRYPTION_KEY".to_string(),
+    };
+    unsafe { std::env::set_var(&config.key_env, STANDARD.encode([3u8; 32])) };
+    let cipher = ContentCipher::from_config(&config).unwrap();
+    assert!(cipher.is_enabled());
+    let sealed = cipher.seal("x").unwrap();
+    assert_eq!(ContentCipher::new(&[3u8; 32]).open(sealed).unwrap(), "x");
+
+    unsafe { std::env::set_var(&config.key_env, STANDARD.encode([3u8; 16])) };
+    assert!(ContentCipher::from_config(&config).is_err(), "short keys are rejected");
+  }
+}
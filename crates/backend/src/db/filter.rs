@@ -2,6 +2,10 @@
 //!
 //! This module provides a fluent API for building filter strings used in
 //! database queries, with proper value escaping to prevent SQL injection.
+//! Every filter that carries user content (tags, concepts, symbols, file paths,
+//! session IDs) is built through it rather than interpolated by hand.
+
+use chrono::{DateTime, Utc};
 
 /// Builder for constructing safe filter strings.
 ///
@@ -45,6 +49,16 @@ impl FilterBuilder {
     self
   }
 
+  /// Add an inequality condition with proper escaping.
+  pub fn add_ne(mut self, column: &str, value: &str) -> Self {
+    self.conditions.push(format!(
+      "{} != '{}'",
+      Self::escape_column(column),
+      Self::escape_value(value)
+    ));
+    self
+  }
+
   /// Add an equality condition only if the value is Some.
   pub fn add_eq_opt(self, column: &str, value: Option<&str>) -> Self {
     match value {
@@ -201,6 +215,43 @@ impl FilterBuilder {
     }
   }
 
+  /// Add a condition that holds when any of `alternatives` does.
+  pub fn add_any(mut self, alternatives: impl IntoIterator<Item = FilterBuilder>) -> Self {
+    let alternatives: Vec<String> = alternatives
+      .into_iter()
+      .filter_map(FilterBuilder::build)
+      .map(|f| format!("({})", f))
+      .collect();
+    if !alternatives.is_empty() {
+      self.conditions.push(format!("({})", alternatives.join(" OR ")));
+    }
+    self
+  }
+
+  /// Add a condition on a millisecond timestamp column being before `at` (<).
+  pub fn add_before(mut self, column: &str, at: DateTime<Utc>) -> Self {
+    self
+      .conditions
+      .push(format!("{} < {}", Self::escape_column(column), at.timestamp_millis()));
+    self
+  }
+
+  /// Add a condition on a millisecond timestamp column being after `at` (>).
+  pub fn add_after(mut self, column: &str, at: DateTime<Utc>) -> Self {
+    self
+      .conditions
+      .push(format!("{} > {}", Self::escape_column(column), at.timestamp_millis()));
+    self
+  }
+
+  /// Add a condition on a millisecond timestamp column being at or after `at` (>=).
+  pub fn add_since(mut self, column: &str, at: DateTime<Utc>) -> Self {
+    self
+      .conditions
+      .push(format!("{} >= {}", Self::escape_column(column), at.timestamp_millis()));
+    self
+  }
+
   /// Add a minimum integer value condition (>= for u32).
   pub fn add_min_u32(mut self, column: &str, value: u32) -> Self {
     self
@@ -238,6 +289,11 @@ impl FilterBuilder {
     self.conditions.is_empty()
   }
 
+  /// Quote a string as an SQL literal, for update expressions.
+  pub fn literal(value: &str) -> String {
+    format!("'{}'", Self::escape_value(value))
+  }
+
   /// Escape a column name (basic protection).
   fn escape_column(column: &str) -> &str {
     // TODO: For now, just ensure no special chars - could add quoting later
@@ -252,8 +308,13 @@ impl FilterBuilder {
 
   /// Escape a value for use in a LIKE pattern.
   fn escape_like_value(value: &str) -> String {
-    // Escape both SQL quotes and LIKE special chars
-    value.replace('\'', "''").replace('%', "\\%").replace('_', "\\_")
+    // Escape the escape char first, then SQL quotes and LIKE special chars.
+    // A trailing backslash would otherwise swallow the closing wildcard.
+    value
+      .replace('\\', "\\\\")
+      .replace('\'', "''")
+      .replace('%', "\\%")
+      .replace('_', "\\_")
   }
 }
 
@@ -302,6 +363,44 @@ mod tests {
     assert_eq!(filter, Some("content LIKE '%100\\% complete\\_test%'".to_string()));
   }
 
+  #[test]
+  fn test_like_escaping_backslash_and_quotes() {
+    let filter = FilterBuilder::new()
+      .add_like("concepts", "it's C:\\")
+      .add_prefix("scope_path", "a\\_b")
+      .build();
+    assert_eq!(
+      filter,
+      Some("concepts LIKE '%it''s C:\\\\%' AND scope_path LIKE 'a\\\\\\_b%'".to_string())
+    );
+  }
+
+  #[test]
+  fn test_like_injection_prevention() {
+    let filter = FilterBuilder::new()
+      .exclude_deleted()
+      .add_like("concepts", "x%' OR '1'='1")
+      .build();
+    assert_eq!(
+      filter,
+      Some("is_deleted = false AND concepts LIKE '%x\\%'' OR ''1''=''1%'".to_string())
+    );
+  }
+
+  #[test]
+  fn test_timestamp_bounds() {
+    let at = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+    let filter = FilterBuilder::new()
+      .add_before("created_at", at)
+      .add_after("updated_at", at)
+      .add_since("last_accessed", at)
+      .build();
+    assert_eq!(
+      filter,
+      Some("created_at < 1700000000123 AND updated_at > 1700000000123 AND last_accessed >= 1700000000123".to_string())
+    );
+  }
+
   #[test]
   fn test_optional_conditions() {
     let filter = FilterBuilder::new()
@@ -409,4 +508,21 @@ mod tests {
       )
     );
   }
+
+  #[test]
+  fn test_any_groups_alternatives() {
+    let filter = FilterBuilder::new()
+      .add_eq("language", "rust")
+      .add_any([
+        FilterBuilder::new().add_in("callee_name", &["it's"]),
+        FilterBuilder::new().add_in("callee", &["it's"]),
+        FilterBuilder::new(),
+      ])
+      .build();
+    assert_eq!(
+      filter,
+      Some("language = 'rust' AND ((callee_name IN ('it''s')) OR (callee IN ('it''s')))".to_string()),
+      "OR stays inside the AND, empty alternatives are dropped, values are escaped"
+    );
+  }
 }
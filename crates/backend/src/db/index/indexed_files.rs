@@ -40,6 +40,7 @@ impl ProjectDb {
   /// Save or update file metadata after indexing
   #[tracing::instrument(level = "trace", skip(self, file), fields(file_path = %file.file_path))]
   pub async fn save_indexed_file(&self, file: &IndexedFile) -> Result<()> {
+    self.check_writable()?;
     let table = self.indexed_files_table();
 
     // Delete existing entry for this file path first
@@ -66,6 +67,7 @@ impl ProjectDb {
   /// - Deletes old entries for these file paths that aren't in the new set
   #[tracing::instrument(level = "trace", skip(self, files), fields(count = files.len()))]
   pub async fn save_indexed_files_batch(&self, files: &[IndexedFile]) -> Result<()> {
+    self.check_writable()?;
     if files.is_empty() {
       return Ok(());
     }
@@ -171,6 +173,7 @@ impl ProjectDb {
   /// Delete metadata for a specific file
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_indexed_file(&self, project_id: &str, file_path: &str) -> Result<()> {
+    self.check_writable()?;
     let table = self.indexed_files_table();
     table
       .delete(&format!(
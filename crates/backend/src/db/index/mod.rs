@@ -1,3 +1,5 @@
 mod indexed_files;
+mod skipped_files;
 
 pub use indexed_files::IndexedFile;
+pub use skipped_files::{SkipReason, SkippedFile};
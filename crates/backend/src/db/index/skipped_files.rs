@@ -0,0 +1,236 @@
+// Skipped files operations
+//
+// Files the indexing guard rails refused are recorded here with the reason,
+// so `ccengram index code --skipped` can explain why a file isn't searchable.
+// An entry is removed once the file is indexed or deleted.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::ExecutableQuery;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{
+  connection::{DbError, ProjectDb, Result},
+  schema::skipped_files_schema,
+};
+
+/// Why a file was not indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+  /// Larger than `index.max_file_size`
+  TooLarge,
+  /// Contains NUL bytes or mostly undecodable bytes
+  Binary,
+  /// Very long lines, e.g. a minified bundle
+  Minified,
+  /// Few distinct lines, e.g. a data dump or fixture
+  Repetitive,
+  /// Would produce more than `index.max_chunks_per_file` chunks
+  TooManyChunks,
+}
+
+impl SkipReason {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      SkipReason::TooLarge => "too_large",
+      SkipReason::Binary => "binary",
+      SkipReason::Minified => "minified",
+      SkipReason::Repetitive => "repetitive",
+      SkipReason::TooManyChunks => "too_many_chunks",
+    }
+  }
+}
+
+impl std::str::FromStr for SkipReason {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "too_large" => Ok(SkipReason::TooLarge),
+      "binary" => Ok(SkipReason::Binary),
+      "minified" => Ok(SkipReason::Minified),
+      "repetitive" => Ok(SkipReason::Repetitive),
+      "too_many_chunks" => Ok(SkipReason::TooManyChunks),
+      _ => Err(format!("Unknown skip reason: {}", s)),
+    }
+  }
+}
+
+/// A file left out of the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedFile {
+  /// Relative path from project root
+  pub file_path: String,
+  pub reason: SkipReason,
+  /// What was measured, e.g. "2400000 bytes > 1048576 bytes"
+  pub detail: String,
+  pub skipped_at: DateTime<Utc>,
+}
+
+impl SkippedFile {
+  pub fn new(file_path: impl Into<String>, reason: SkipReason, detail: impl Into<String>) -> Self {
+    Self {
+      file_path: file_path.into(),
+      reason,
+      detail: detail.into(),
+      skipped_at: Utc::now(),
+    }
+  }
+}
+
+impl ProjectDb {
+  /// Record skipped files, replacing earlier entries for the same paths
+  #[tracing::instrument(level = "trace", skip(self, files), fields(count = files.len()))]
+  pub async fn save_skipped_files(&self, files: &[SkippedFile]) -> Result<()> {
+    self.check_writable()?;
+    if files.is_empty() {
+      return Ok(());
+    }
+
+    let batch = RecordBatch::try_new(
+      skipped_files_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(
+          files.iter().map(|f| f.file_path.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(files.iter().map(|f| f.reason.as_str()))),
+        Arc::new(StringArray::from_iter_values(files.iter().map(|f| f.detail.as_str()))),
+        Arc::new(Int64Array::from_iter_values(
+          files.iter().map(|f| f.skipped_at.timestamp_millis()),
+        )),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], skipped_files_schema());
+
+    let mut builder = self.skipped_files_table().merge_insert(&["file_path"]);
+    builder.when_matched_update_all(None).when_not_matched_insert_all();
+    builder.execute(Box::new(batches)).await?;
+    Ok(())
+  }
+
+  /// List every skipped file, ordered by path
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_skipped_files(&self) -> Result<Vec<SkippedFile>> {
+    let results: Vec<RecordBatch> = self
+      .skipped_files_table()
+      .query()
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut files = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        files.push(batch_to_skipped_file(&batch, i)?);
+      }
+    }
+    files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    Ok(files)
+  }
+
+  /// Forget skipped entries for files that were indexed or deleted
+  #[tracing::instrument(level = "trace", skip(self, file_paths), fields(count = file_paths.len()))]
+  pub async fn delete_skipped_files(&self, file_paths: &[String]) -> Result<()> {
+    self.check_writable()?;
+    if file_paths.is_empty() {
+      return Ok(());
+    }
+
+    let paths_filter = file_paths
+      .iter()
+      .map(|p| format!("'{}'", p.replace('\'', "''")))
+      .collect::<Vec<_>>()
+      .join(", ");
+    self
+      .skipped_files_table()
+      .delete(&format!("file_path IN ({})", paths_filter))
+      .await?;
+    Ok(())
+  }
+}
+
+/// Convert a RecordBatch row to a SkippedFile
+fn batch_to_skipped_file(batch: &RecordBatch, row: usize) -> Result<SkippedFile> {
+  let get_string = |name: &str| -> Result<String> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .map(|a| a.value(row).to_string())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+
+  let skipped_at_ts = batch
+    .column_by_name("skipped_at")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .filter(|a| !a.is_null(row))
+    .map(|a| a.value(row))
+    .ok_or_else(|| DbError::NotFound("column skipped_at".into()))?;
+
+  Ok(SkippedFile {
+    file_path: get_string("file_path")?,
+    reason: get_string("reason")?.parse::<SkipReason>().map_err(DbError::NotFound)?,
+    detail: get_string("detail")?,
+    skipped_at: Utc
+      .timestamp_millis_opt(skipped_at_ts)
+      .single()
+      .ok_or_else(|| DbError::NotFound("invalid skipped_at timestamp".into()))?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_skipped_files_replace_and_delete() {
+    let (_temp, db) = create_test_db().await;
+
+    db.save_skipped_files(&[
+      SkippedFile::new("dist/app.min.js", SkipReason::Minified, "12000 chars per line"),
+      SkippedFile::new("assets/logo.rs", SkipReason::Binary, "contains NUL bytes"),
+    ])
+    .await
+    .unwrap();
+    db.save_skipped_files(&[SkippedFile::new(
+      "dist/app.min.js",
+      SkipReason::TooLarge,
+      "2.0 MB > 1.0 MB",
+    )])
+    .await
+    .unwrap();
+
+    let files = db.list_skipped_files().await.unwrap();
+    assert_eq!(files.len(), 2, "a path is recorded once");
+    assert_eq!(files[0].file_path, "assets/logo.rs", "ordered by path");
+    assert_eq!(files[1].reason, SkipReason::TooLarge, "latest reason wins");
+
+    db.delete_skipped_files(&["assets/logo.rs".to_string()]).await.unwrap();
+    let files = db.list_skipped_files().await.unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].file_path, "dist/app.min.js");
+  }
+}
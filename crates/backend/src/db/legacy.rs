@@ -0,0 +1,407 @@
+// Rows from legacy stores
+//
+// Stores written before the data directory was versioned hold the same tables
+// with older column sets and types: timestamps as Arrow timestamps or RFC 3339
+// strings, columns that have since been added or made non-nullable, and
+// vectors from whatever model was configured then. These helpers read such a
+// store and conform its rows to the current schema so they can be appended
+// to a freshly created database.
+
+use std::{path::Path, sync::Arc};
+
+use arrow::{
+  compute::{can_cast_types, cast, is_not_null, kernels::zip::zip},
+  datatypes::TimeUnit,
+};
+use arrow_array::{
+  Array, ArrayRef, BooleanArray, FixedSizeListArray, Float32Array, Float64Array, Int64Array, RecordBatch,
+  RecordBatchIterator, StringArray, UInt32Array, UInt64Array, new_null_array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use lancedb::{Connection, connect, query::ExecutableQuery};
+use uuid::Uuid;
+
+use crate::db::{DbError, ProjectDb, Result, encryption::ContentCipher};
+
+/// Columns holding a JSON array
+const JSON_ARRAY_COLUMNS: &[&str] = &[
+  "tags",
+  "concepts",
+  "files",
+  "categories",
+  "symbols",
+  "imports",
+  "calls",
+  "result_ids",
+];
+
+/// Columns encrypted at rest, by table
+const SEALED_COLUMNS: &[(&str, &str)] = &[
+  ("memories", "content"),
+  ("memories", "summary"),
+  ("documents", "content"),
+  ("search_logs", "query"),
+];
+
+/// A LanceDB directory written by an older build, opened read-only
+pub struct LegacyStore {
+  connection: Connection,
+}
+
+impl LegacyStore {
+  pub async fn open(path: &Path) -> Result<Self> {
+    let connection = connect(path.to_string_lossy().as_ref()).execute().await?;
+    Ok(Self { connection })
+  }
+
+  pub async fn table_names(&self) -> Result<Vec<String>> {
+    Ok(self.connection.table_names().execute().await?)
+  }
+
+  /// Every row of a table, with the schema it was written with
+  pub async fn read_table(&self, name: &str) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let table = self.connection.open_table(name).execute().await?;
+    let schema = table.schema().await?;
+    let batches = table.query().execute().await?.try_collect().await?;
+    Ok((schema, batches))
+  }
+}
+
+/// Width of a schema's `vector` column, if it has one
+pub fn legacy_vector_dim(schema: &Schema) -> Option<usize> {
+  match schema.field_with_name("vector").ok()?.data_type() {
+    DataType::FixedSizeList(_, size) => Some(*size as usize),
+    _ => None,
+  }
+}
+
+impl ProjectDb {
+  /// Width of the vectors `table` stores, if it stores any
+  pub async fn table_vector_dim(&self, table: &str) -> Result<Option<usize>> {
+    let live = self
+      .table_named(table)
+      .ok_or_else(|| DbError::NotFound(format!("table {}", table)))?;
+    Ok(legacy_vector_dim(&live.schema().await?))
+  }
+
+  /// Text each legacy row was embedded from, decrypted.
+  ///
+  /// `None` marks a row that is stored with a zero vector, like a shallow
+  /// code chunk.
+  pub fn legacy_embedding_texts(&self, table: &str, batch: &RecordBatch) -> Result<Vec<Option<String>>> {
+    let strings = |name: &str| {
+      batch
+        .column_by_name(name)
+        .and_then(|c| cast(c, &DataType::Utf8).ok())
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>().cloned())
+    };
+    let content = strings("content");
+    let embedding_text = strings("embedding_text");
+    let shallow = batch
+      .column_by_name("shallow")
+      .and_then(|c| c.as_any().downcast_ref::<BooleanArray>().cloned());
+
+    let mut texts = Vec::with_capacity(batch.num_rows());
+    for row in 0..batch.num_rows() {
+      let value = |array: &Option<StringArray>| {
+        array
+          .as_ref()
+          .filter(|a| !a.is_null(row))
+          .map(|a| a.value(row).to_string())
+      };
+      let text = match table {
+        "code_chunks" if shallow.as_ref().is_some_and(|s| !s.is_null(row) && s.value(row)) => None,
+        "code_chunks" => value(&embedding_text)
+          .filter(|t| !t.trim().is_empty())
+          .or_else(|| value(&content)),
+        _ => value(&content),
+      };
+      texts.push(match text {
+        Some(text) => Some(self.cipher().open(text)?),
+        None => None,
+      });
+    }
+    Ok(texts)
+  }
+
+  /// Append legacy rows to `table`, converted to its current schema.
+  ///
+  /// Columns are matched by name and cast where the type changed; missing
+  /// columns are filled with nulls or, for required ones, with the defaults
+  /// new rows get. `vectors` replaces the stored vectors, for rows embedded
+  /// with a model of another width. Returns the number of rows added.
+  pub async fn import_legacy_rows(
+    &self,
+    table: &str,
+    batch: &RecordBatch,
+    vectors: Option<&[Vec<f32>]>,
+  ) -> Result<usize> {
+    self.check_writable()?;
+    let live = self
+      .table_named(table)
+      .ok_or_else(|| DbError::NotFound(format!("table {}", table)))?;
+    let schema = live.schema().await?;
+    let rows = batch.num_rows();
+    if rows == 0 {
+      return Ok(0);
+    }
+
+    let created_at = batch
+      .column_by_name("created_at")
+      .and_then(|c| conform_column(c, &DataType::Int64))
+      .and_then(|c| c.as_any().downcast_ref::<Int64Array>().cloned());
+    let now = Utc::now().timestamp_millis();
+    let times: Vec<i64> = (0..rows)
+      .map(|row| {
+        created_at
+          .as_ref()
+          .filter(|a| !a.is_null(row))
+          .map_or(now, |a| a.value(row))
+      })
+      .collect();
+
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+      let name = field.name().as_str();
+      if name == "vector" {
+        columns.push(self.legacy_vectors(field, batch, vectors)?);
+        continue;
+      }
+
+      let column = batch
+        .column_by_name(name)
+        .and_then(|c| conform_column(c, field.data_type()));
+      let column = match (name, column) {
+        ("project_id", _) => self.project_id_column(table, rows),
+        (_, Some(column)) if field.is_nullable() || column.null_count() == 0 => column,
+        (_, Some(column)) => zip(&is_not_null(&column)?, &column, &default_column(field, rows, &times))?,
+        (_, None) if field.is_nullable() => new_null_array(field.data_type(), rows),
+        (_, None) => default_column(field, rows, &times),
+      };
+
+      let sealed = SEALED_COLUMNS.contains(&(table, name)) && self.cipher().is_enabled();
+      columns.push(if sealed { self.seal_column(&column)? } else { column });
+    }
+
+    let converted = RecordBatch::try_new(schema.clone(), columns)?;
+    live
+      .add(Box::new(RecordBatchIterator::new(vec![Ok(converted)], schema)))
+      .execute()
+      .await?;
+    Ok(rows)
+  }
+
+  /// The `vector` column: replacement vectors resized to this database's
+  /// width, or the stored ones cast to the current type
+  fn legacy_vectors(&self, field: &Field, batch: &RecordBatch, vectors: Option<&[Vec<f32>]>) -> Result<ArrayRef> {
+    if let Some(vectors) = vectors {
+      let mut flat = Vec::with_capacity(vectors.len() * self.vector_dim);
+      for vector in vectors {
+        flat.extend(
+          vector
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0.0))
+            .take(self.vector_dim),
+        );
+      }
+      let list = FixedSizeListArray::try_new(
+        Arc::new(Field::new("item", DataType::Float32, true)),
+        self.vector_dim as i32,
+        Arc::new(Float32Array::from(flat)),
+        None,
+      )?;
+      return Ok(Arc::new(list));
+    }
+
+    batch
+      .column_by_name("vector")
+      .and_then(|c| conform_column(c, field.data_type()))
+      .ok_or_else(|| DbError::InvalidInput("legacy vectors do not match the configured dimensions".into()))
+  }
+
+  /// This project's ID in the form `table` stores it
+  fn project_id_column(&self, table: &str, rows: usize) -> ArrayRef {
+    let id = match table {
+      "indexed_files" => self.project_id.as_str().to_string(),
+      "memories" | "sessions" | "documents" | "document_metadata" => self.project_id.uuid().to_string(),
+      _ => String::new(),
+    };
+    Arc::new(StringArray::from(vec![id; rows]))
+  }
+
+  /// Encrypt every value not already encrypted
+  fn seal_column(&self, column: &ArrayRef) -> Result<ArrayRef> {
+    let Some(strings) = column.as_any().downcast_ref::<StringArray>() else {
+      return Ok(column.clone());
+    };
+    let sealed = strings
+      .iter()
+      .map(|value| match value {
+        Some(text) if !ContentCipher::is_sealed(text) => self.cipher().seal(text).map(Some),
+        other => Ok(other.map(str::to_string)),
+      })
+      .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(StringArray::from(sealed)))
+  }
+}
+
+/// Convert a legacy column to `to`, or `None` when the types don't convert
+fn conform_column(column: &ArrayRef, to: &DataType) -> Option<ArrayRef> {
+  let from = column.data_type();
+  if from == to {
+    return Some(column.clone());
+  }
+  match (from, to) {
+    (DataType::Timestamp(..), DataType::Int64) => {
+      let millis = cast(column, &DataType::Timestamp(TimeUnit::Millisecond, None)).ok()?;
+      cast(&millis, &DataType::Int64).ok()
+    }
+    (DataType::Utf8 | DataType::LargeUtf8, DataType::Int64) => {
+      let strings = cast(column, &DataType::Utf8).ok()?;
+      let strings = strings.as_any().downcast_ref::<StringArray>()?;
+      Some(Arc::new(
+        strings.iter().map(|v| v.and_then(parse_millis)).collect::<Int64Array>(),
+      ))
+    }
+    _ if can_cast_types(from, to) => cast(column, to).ok(),
+    _ => None,
+  }
+}
+
+/// A timestamp stored as text: Unix milliseconds or RFC 3339
+fn parse_millis(value: &str) -> Option<i64> {
+  value.parse::<i64>().ok().or_else(|| {
+    DateTime::parse_from_rfc3339(value)
+      .ok()
+      .map(|t| t.with_timezone(&Utc).timestamp_millis())
+  })
+}
+
+/// Values a new row gets for a required column; timestamps use the row's
+/// creation time
+fn default_column(field: &Field, rows: usize, times: &[i64]) -> ArrayRef {
+  let name = field.name().as_str();
+  match field.data_type() {
+    DataType::Utf8 => {
+      let values: Vec<String> = match name {
+        "id" => (0..rows).map(|_| Uuid::now_v7().to_string()).collect(),
+        "sector" => vec!["semantic".to_string(); rows],
+        "tier" => vec!["project".to_string(); rows],
+        _ if JSON_ARRAY_COLUMNS.contains(&name) => vec!["[]".to_string(); rows],
+        _ => vec![String::new(); rows],
+      };
+      Arc::new(StringArray::from(values))
+    }
+    DataType::Int64 => Arc::new(Int64Array::from(times.to_vec())),
+    DataType::Float32 => {
+      let value = if matches!(name, "importance" | "salience" | "confidence") {
+        0.5
+      } else {
+        0.0
+      };
+      Arc::new(Float32Array::from(vec![value; rows]))
+    }
+    DataType::Float64 => Arc::new(Float64Array::from(vec![0.0; rows])),
+    DataType::UInt32 => Arc::new(UInt32Array::from(vec![0u32; rows])),
+    DataType::UInt64 => Arc::new(UInt64Array::from(vec![0u64; rows])),
+    DataType::Boolean => Arc::new(BooleanArray::from(vec![false; rows])),
+    other => new_null_array(other, rows),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{
+    config::Config,
+    domain::{memory::Sector, project::ProjectId},
+  };
+
+  #[tokio::test]
+  async fn test_legacy_rows_conform_to_current_schema() {
+    let temp = TempDir::new().unwrap();
+    let legacy_path = temp.path().join("legacy.lancedb");
+
+    // An older memories table: fewer columns, string timestamps, 4-wide vectors
+    let legacy_schema = Arc::new(Schema::new(vec![
+      Field::new("id", DataType::Utf8, false),
+      Field::new("content", DataType::Utf8, false),
+      Field::new("sector", DataType::Utf8, true),
+      Field::new("importance", DataType::Float64, true),
+      Field::new("created_at", DataType::Utf8, true),
+      Field::new(
+        "vector",
+        DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), 4),
+        true,
+      ),
+    ]));
+    let id = Uuid::now_v7().to_string();
+    let batch = RecordBatch::try_new(
+      legacy_schema.clone(),
+      vec![
+        Arc::new(StringArray::from(vec![id.clone()])),
+        Arc::new(StringArray::from(vec!["Retries use exponential backoff"])),
+        Arc::new(StringArray::from(vec![None::<&str>])),
+        Arc::new(Float64Array::from(vec![0.9])),
+        Arc::new(StringArray::from(vec!["2024-03-01T12:00:00Z"])),
+        Arc::new(
+          FixedSizeListArray::try_new(
+            Arc::new(Field::new("item", DataType::Float32, true)),
+            4,
+            Arc::new(Float32Array::from(vec![0.1, 0.2, 0.3, 0.4])),
+            None,
+          )
+          .unwrap(),
+        ),
+      ],
+    )
+    .unwrap();
+    let connection = connect(legacy_path.to_string_lossy().as_ref()).execute().await.unwrap();
+    connection
+      .create_table("memories", RecordBatchIterator::new(vec![Ok(batch)], legacy_schema))
+      .execute()
+      .await
+      .unwrap();
+
+    let store = LegacyStore::open(&legacy_path).await.unwrap();
+    assert_eq!(store.table_names().await.unwrap(), vec!["memories"]);
+    let (schema, batches) = store.read_table("memories").await.unwrap();
+    assert_eq!(legacy_vector_dim(&schema), Some(4));
+
+    let project_id = ProjectId::from_dir_name("0123456789abcdef");
+    let db = ProjectDb::open_at_path(
+      project_id.clone(),
+      temp.path().join("current.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    let texts = db.legacy_embedding_texts("memories", &batches[0]).unwrap();
+    assert_eq!(texts, vec![Some("Retries use exponential backoff".to_string())]);
+
+    // Widths differ, so the row comes with a fresh vector
+    let vectors = vec![vec![1.0; db.vector_dim]];
+    let added = db
+      .import_legacy_rows("memories", &batches[0], Some(&vectors))
+      .await
+      .unwrap();
+    assert_eq!(added, 1);
+
+    let memory = db.get_memory(&id.parse().unwrap()).await.unwrap().unwrap();
+    assert_eq!(memory.content, "Retries use exponential backoff");
+    assert_eq!(memory.project_id, project_id.uuid());
+    assert_eq!(memory.sector, Sector::Semantic, "required columns get defaults");
+    assert!((memory.importance - 0.9).abs() < 1e-6, "types are cast");
+    assert_eq!(memory.created_at.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+    assert_eq!(
+      memory.updated_at, memory.created_at,
+      "missing timestamps use the creation time"
+    );
+    assert!(memory.tags.is_empty());
+  }
+}
@@ -0,0 +1,261 @@
+// Entity table operations
+//
+// Named entities (people, technologies, services, files) mentioned by
+// memories, and the memory_entities junction table linking them.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    DbError, ProjectDb, Result,
+    schema::{entities_schema, memory_entities_schema},
+  },
+  domain::memory::{Entity, EntityType, MemoryId},
+};
+
+/// A memory-entity linkage record
+#[derive(Debug, Clone)]
+pub struct EntityLink {
+  pub memory_id: String,
+  pub entity_id: Uuid,
+  pub linked_at: DateTime<Utc>,
+}
+
+impl ProjectDb {
+  /// Add entities that aren't stored yet
+  ///
+  /// Entity IDs are derived from type and name, so existing rows are kept as
+  /// they are.
+  #[tracing::instrument(level = "trace", skip(self, entities), fields(batch_size = entities.len()))]
+  pub async fn add_entities(&self, entities: &[Entity]) -> Result<()> {
+    self.check_writable()?;
+    if entities.is_empty() {
+      return Ok(());
+    }
+
+    let batch = RecordBatch::try_new(
+      entities_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(entities.iter().map(|e| e.id.to_string()))),
+        Arc::new(StringArray::from_iter_values(entities.iter().map(|e| e.name.as_str()))),
+        Arc::new(StringArray::from_iter_values(
+          entities.iter().map(|e| e.entity_type.as_str()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+          entities.iter().map(|e| e.created_at.timestamp_millis()),
+        )),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], entities_schema());
+
+    let mut builder = self.entities_table().merge_insert(&["id"]);
+    builder.when_not_matched_insert_all();
+    builder.execute(Box::new(batches)).await?;
+    Ok(())
+  }
+
+  /// Link a memory to entities, skipping links that already exist
+  #[tracing::instrument(level = "trace", skip(self, entity_ids))]
+  pub async fn link_entities(&self, memory_id: &MemoryId, entity_ids: &[Uuid]) -> Result<()> {
+    self.check_writable()?;
+    if entity_ids.is_empty() {
+      return Ok(());
+    }
+    debug!(
+      table = "memory_entities",
+      operation = "link",
+      memory_id = %memory_id,
+      count = entity_ids.len(),
+      "Linking entities to memory"
+    );
+
+    let now = Utc::now().timestamp_millis();
+    let len = entity_ids.len();
+    let batch = RecordBatch::try_new(
+      memory_entities_schema(),
+      vec![
+        Arc::new(StringArray::from(vec![memory_id.to_string(); len])),
+        Arc::new(StringArray::from_iter_values(
+          entity_ids.iter().map(|id| id.to_string()),
+        )),
+        Arc::new(Int64Array::from(vec![now; len])),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], memory_entities_schema());
+
+    let mut builder = self.memory_entities_table().merge_insert(&["memory_id", "entity_id"]);
+    builder.when_not_matched_insert_all();
+    builder.execute(Box::new(batches)).await?;
+    Ok(())
+  }
+
+  /// List entities matching an optional filter
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_entities(&self, filter: Option<&str>) -> Result<Vec<Entity>> {
+    let table = self.entities_table();
+    let query = match filter {
+      Some(f) => table.query().only_if(f),
+      None => table.query(),
+    };
+
+    let results: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+
+    let mut entities = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        entities.push(batch_to_entity(&batch, i)?);
+      }
+    }
+
+    Ok(entities)
+  }
+
+  /// Get an entity by ID
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn get_entity(&self, id: &Uuid) -> Result<Option<Entity>> {
+    Ok(
+      self
+        .list_entities(Some(&format!("id = '{}'", id)))
+        .await?
+        .into_iter()
+        .next(),
+    )
+  }
+
+  /// Count stored entities
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn count_entities(&self) -> Result<usize> {
+    Ok(self.entities_table().count_rows(None).await?)
+  }
+
+  /// List memory-entity links matching an optional filter
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_entity_links(&self, filter: Option<&str>) -> Result<Vec<EntityLink>> {
+    let table = self.memory_entities_table();
+    let query = match filter {
+      Some(f) => table.query().only_if(f),
+      None => table.query(),
+    };
+
+    let results: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+
+    let mut links = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        links.push(batch_to_link(&batch, i)?);
+      }
+    }
+
+    Ok(links)
+  }
+}
+
+/// Convert a RecordBatch row to an Entity
+fn batch_to_entity(batch: &RecordBatch, row: usize) -> Result<Entity> {
+  let get_string = |name: &str| -> Result<String> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .map(|a| a.value(row).to_string())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+
+  let created_at = batch
+    .column_by_name("created_at")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .map(|a| a.value(row))
+    .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
+    .ok_or_else(|| DbError::NotFound("invalid created_at timestamp".into()))?;
+
+  Ok(Entity {
+    id: Uuid::parse_str(&get_string("id")?).map_err(|_| DbError::NotFound("invalid id".into()))?,
+    name: get_string("name")?,
+    entity_type: get_string("entity_type")?
+      .parse::<EntityType>()
+      .map_err(DbError::NotFound)?,
+    created_at,
+  })
+}
+
+/// Convert a RecordBatch row to an EntityLink
+fn batch_to_link(batch: &RecordBatch, row: usize) -> Result<EntityLink> {
+  let get_string = |name: &str| -> Result<String> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .map(|a| a.value(row).to_string())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+
+  let linked_at = batch
+    .column_by_name("linked_at")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .map(|a| a.value(row))
+    .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
+    .ok_or_else(|| DbError::NotFound("invalid linked_at timestamp".into()))?;
+
+  Ok(EntityLink {
+    memory_id: get_string("memory_id")?,
+    entity_id: Uuid::parse_str(&get_string("entity_id")?).map_err(|_| DbError::NotFound("invalid entity_id".into()))?,
+    linked_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_entities_and_links_are_not_duplicated() {
+    let (_temp, db) = create_test_db().await;
+    let memory = MemoryId::new();
+    let postgres = Entity::new("PostgreSQL", EntityType::Technology);
+    let stripe = Entity::new("Stripe", EntityType::Service);
+
+    db.add_entities(&[postgres.clone(), stripe.clone()]).await.unwrap();
+    db.add_entities(&[Entity::new("postgresql", EntityType::Technology)])
+      .await
+      .unwrap();
+    assert_eq!(
+      db.count_entities().await.unwrap(),
+      2,
+      "names differing in case are one entity"
+    );
+
+    db.link_entities(&memory, &[postgres.id, stripe.id]).await.unwrap();
+    db.link_entities(&memory, &[postgres.id]).await.unwrap();
+    let links = db
+      .list_entity_links(Some(&format!("memory_id = '{}'", memory)))
+      .await
+      .unwrap();
+    assert_eq!(links.len(), 2, "repeated links are skipped");
+
+    let stored = db.get_entity(&postgres.id).await.unwrap().unwrap();
+    assert_eq!(stored.name, "PostgreSQL", "the first spelling is kept");
+  }
+}
@@ -12,16 +12,18 @@ use uuid::Uuid;
 
 use crate::{
   db::{
-    connection::{DbError, ProjectDb, Result},
+    ContentCipher,
+    connection::{DbError, ProjectDb, Result, id_prefix_filter},
     schema::memories_schema,
   },
-  domain::memory::{Memory, MemoryId, MemoryType, Sector, Tier},
+  domain::memory::{GoalStatus, Memory, MemoryId, MemoryType, Sector, Tier, Visibility},
 };
 
 impl ProjectDb {
   /// Add a new memory to the database
   #[tracing::instrument(level = "trace", skip(self, memory, vector), fields(id = %memory.id))]
   pub async fn add_memory(&self, memory: &Memory, vector: &[f32]) -> Result<()> {
+    self.check_writable()?;
     let table = self.memories_table();
 
     debug!(
@@ -32,7 +34,7 @@ impl ProjectDb {
       "Adding memory"
     );
 
-    let batch = memory_to_batch(memory, vector, self.vector_dim)?;
+    let batch = memory_to_batch(memory, vector, self.vector_dim, self.cipher())?;
     let batches = RecordBatchIterator::new(vec![Ok(batch)], memories_schema(self.vector_dim));
 
     table.add(Box::new(batches)).execute().await?;
@@ -62,7 +64,7 @@ impl ProjectDb {
       return Ok(None);
     }
 
-    Ok(Some(batch_to_memory(batch, 0)?))
+    Ok(Some(batch_to_memory(batch, 0, self.cipher())?))
   }
 
   /// Update a memory
@@ -72,6 +74,7 @@ impl ProjectDb {
   /// want to lose the embedding.
   #[tracing::instrument(level = "trace", skip(self, memory, vector), fields(id = %memory.id))]
   pub async fn update_memory(&self, memory: &Memory, vector: Option<&[f32]>) -> Result<()> {
+    self.check_writable()?;
     let table = self.memories_table();
 
     debug!(
@@ -92,7 +95,7 @@ impl ProjectDb {
       .or(existing_vector.as_deref())
       .expect("this is logically infallible");
 
-    let batch = memory_to_batch(memory, vector_to_use, self.vector_dim)?;
+    let batch = memory_to_batch(memory, vector_to_use, self.vector_dim, self.cipher())?;
     let batches = RecordBatchIterator::new(vec![Ok(batch)], memories_schema(self.vector_dim));
 
     let mut merge_insert = table.merge_insert(&["id"]);
@@ -239,6 +242,7 @@ impl ProjectDb {
   /// Existing vectors are preserved.
   #[tracing::instrument(level = "trace", skip(self, memories), fields(count = memories.len()))]
   pub async fn batch_update_memories(&self, memories: &[Memory]) -> Result<usize> {
+    self.check_writable()?;
     if memories.is_empty() {
       return Ok(0);
     }
@@ -261,7 +265,7 @@ impl ProjectDb {
       .iter()
       .map(|m| {
         let vector = vectors.get(&m.id).map(|v| v.as_slice()).unwrap_or(&[]);
-        memory_to_batch(m, vector, self.vector_dim)
+        memory_to_batch(m, vector, self.vector_dim, self.cipher())
       })
       .collect::<Result<Vec<_>>>()?;
 
@@ -285,12 +289,60 @@ impl ProjectDb {
   /// Delete a memory by ID (hard delete)
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_memory(&self, id: &MemoryId) -> Result<()> {
+    self.check_writable()?;
     debug!(table = "memories", operation = "delete", id = %id, "Deleting memory");
     let table = self.memories_table();
     table.delete(&format!("id = '{}'", id)).await?;
     Ok(())
   }
 
+  /// Delete all private memories and every trace of them on disk.
+  ///
+  /// Deleted rows stay in data files, index files and older table versions
+  /// until compacted and pruned, so the indexes are dropped, the table is
+  /// rewritten and its history removed. Opening the store recreates the
+  /// indexes. Meant for copies of a store, such as a snapshot being exported.
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn purge_private_memories(&self) -> Result<usize> {
+    use lancedb::table::{CompactionOptions, OptimizeAction};
+
+    self.check_writable()?;
+    let table = self.memories_table();
+    let filter = format!("visibility = '{}'", Visibility::Private.as_str());
+    let count = table.count_rows(Some(filter.clone())).await?;
+    if count == 0 {
+      return Ok(0);
+    }
+
+    debug!(
+      table = "memories",
+      operation = "purge",
+      count,
+      "Purging private memories"
+    );
+    table.delete(&filter).await?;
+    for index in table.list_indices().await? {
+      table.drop_index(&index.name).await?;
+    }
+    table
+      .optimize(OptimizeAction::Compact {
+        options: CompactionOptions {
+          materialize_deletions_threshold: 0.0,
+          ..CompactionOptions::default()
+        },
+        remap_options: None,
+      })
+      .await?;
+    table
+      .optimize(OptimizeAction::Prune {
+        older_than: Some(chrono::Duration::zero()),
+        delete_unverified: Some(true),
+        error_if_tagged_old_versions: Some(false),
+      })
+      .await?;
+    Ok(count)
+  }
+
   /// Reinforce a memory (increment salience with diminishing returns)
   ///
   /// Formula: new_salience = min(salience + amount * (1.0 - salience), 1.0)
@@ -299,6 +351,7 @@ impl ProjectDb {
   /// Race conditions are acceptable for salience updates.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn reinforce_memory(&self, id: &MemoryId, amount: f32) -> Result<()> {
+    self.check_writable()?;
     let amount = amount.clamp(0.01, 0.5);
 
     // Read current salience
@@ -335,6 +388,7 @@ impl ProjectDb {
   /// Race conditions are acceptable for salience updates.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn deemphasize_memory(&self, id: &MemoryId, amount: f32) -> Result<()> {
+    self.check_writable()?;
     let amount = amount.clamp(0.01, 0.5);
 
     // Read current salience
@@ -361,11 +415,42 @@ impl ProjectDb {
     Ok(())
   }
 
+  /// Count one piece of search feedback on a memory
+  ///
+  /// Note: Uses read-modify-write like the salience updates; a lost vote in a
+  /// race is acceptable.
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn record_memory_feedback(&self, id: &MemoryId, helpful: bool) -> Result<()> {
+    self.check_writable()?;
+
+    let memory = self
+      .get_memory(id)
+      .await?
+      .ok_or_else(|| DbError::NotFound(format!("Memory {} not found", id)))?;
+
+    let (column, count) = if helpful {
+      ("helpful_count", memory.helpful_count + 1)
+    } else {
+      ("irrelevant_count", memory.irrelevant_count + 1)
+    };
+
+    self
+      .memories_table()
+      .update()
+      .only_if(format!("id = '{}'", id))
+      .column(column, format!("{}", count))
+      .execute()
+      .await?;
+
+    Ok(())
+  }
+
   /// Atomically supersede a memory
   ///
   /// Marks the memory as superseded by another, setting valid_until and superseded_by.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn supersede_memory(&self, id: &MemoryId, superseded_by: &MemoryId) -> Result<()> {
+    self.check_writable()?;
     let table = self.memories_table();
     let now_millis = Utc::now().timestamp_millis();
 
@@ -384,6 +469,7 @@ impl ProjectDb {
   /// Atomically set a memory's salience to a specific value
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn set_memory_salience(&self, id: &MemoryId, salience: f32) -> Result<()> {
+    self.check_writable()?;
     let table = self.memories_table();
     let now_millis = Utc::now().timestamp_millis();
     let salience = salience.clamp(0.05, 1.0);
@@ -402,6 +488,7 @@ impl ProjectDb {
   /// Atomically promote a memory from Session to Project tier
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn promote_memory_to_project(&self, id: &MemoryId) -> Result<()> {
+    self.check_writable()?;
     let table = self.memories_table();
     let now_millis = Utc::now().timestamp_millis();
 
@@ -437,6 +524,11 @@ impl ProjectDb {
       "FTS searching memories"
     );
 
+    // Encrypted content has no FTS index; hybrid search falls back to vectors
+    if self.cipher().is_enabled() {
+      return Ok(Vec::new());
+    }
+
     let table = self.memories_table();
 
     let fts_query = FullTextSearchQuery::new(query.to_owned())
@@ -454,7 +546,7 @@ impl ProjectDb {
     let mut memories = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        let memory = batch_to_memory(&batch, i)?;
+        let memory = batch_to_memory(&batch, i, self.cipher())?;
         let score = batch
           .column_by_name("_score")
           .and_then(|col| col.as_any().downcast_ref::<Float32Array>())
@@ -494,9 +586,9 @@ impl ProjectDb {
     let table = self.memories_table();
 
     let query = if let Some(f) = filter {
-      table.vector_search(query_vector.to_vec())?.limit(limit).only_if(f)
+      self.vector_query(table, query_vector)?.limit(limit).only_if(f)
     } else {
-      table.vector_search(query_vector.to_vec())?.limit(limit)
+      self.vector_query(table, query_vector)?.limit(limit)
     };
 
     let results: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
@@ -504,7 +596,7 @@ impl ProjectDb {
     let mut memories = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        let memory = batch_to_memory(&batch, i)?;
+        let memory = batch_to_memory(&batch, i, self.cipher())?;
         // Get distance score from _distance column if present
         let distance = batch
           .column_by_name("_distance")
@@ -542,7 +634,7 @@ impl ProjectDb {
     let mut memories = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        memories.push(batch_to_memory(&batch, i)?);
+        memories.push(batch_to_memory(&batch, i, self.cipher())?);
       }
     }
 
@@ -555,14 +647,9 @@ impl ProjectDb {
   /// Requires minimum 6 characters for safety. Returns up to 10 matches.
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn find_by_prefix(&self, prefix: &str) -> Result<Vec<Memory>> {
-    if prefix.len() < 6 {
-      return Err(DbError::InvalidInput("ID prefix must be at least 6 characters".into()));
-    }
-
+    let filter = id_prefix_filter(prefix)?;
     let table = self.memories_table();
 
-    // Use LIKE query for prefix matching
-    let filter = format!("id LIKE '{}%'", prefix);
     let results: Vec<RecordBatch> = table
       .query()
       .only_if(filter)
@@ -575,7 +662,7 @@ impl ProjectDb {
     let mut memories = Vec::new();
     for batch in results {
       for i in 0..batch.num_rows() {
-        memories.push(batch_to_memory(&batch, i)?);
+        memories.push(batch_to_memory(&batch, i, self.cipher())?);
       }
     }
 
@@ -615,14 +702,15 @@ impl ProjectDb {
 }
 
 /// Convert a Memory to an Arrow RecordBatch
-fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize) -> Result<RecordBatch> {
+fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize, cipher: &ContentCipher) -> Result<RecordBatch> {
   let id = StringArray::from(vec![memory.id.to_string()]);
   let project_id = StringArray::from(vec![memory.project_id.to_string()]);
-  let content = StringArray::from(vec![memory.content.clone()]);
-  let summary = StringArray::from(vec![memory.summary.clone()]);
+  let content = StringArray::from(vec![cipher.seal(&memory.content)?]);
+  let summary = StringArray::from(vec![memory.summary.as_deref().map(|s| cipher.seal(s)).transpose()?]);
   let sector = StringArray::from(vec![memory.sector.as_str().to_string()]);
   let tier = StringArray::from(vec![memory.tier.as_str().to_string()]);
   let memory_type = StringArray::from(vec![memory.memory_type.map(|t| t.as_str().to_string())]);
+  let visibility = StringArray::from(vec![memory.visibility.as_str().to_string()]);
   let importance = Float32Array::from(vec![memory.importance]);
   let salience = Float32Array::from(vec![memory.salience]);
   let confidence = Float32Array::from(vec![memory.confidence]);
@@ -631,11 +719,25 @@ fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize) -> Result
   let concepts = StringArray::from(vec![serde_json::to_string(&memory.concepts)?]);
   let files = StringArray::from(vec![serde_json::to_string(&memory.files)?]);
   let categories = StringArray::from(vec![serde_json::to_string(&memory.categories)?]);
+  let file_anchors = StringArray::from(vec![if memory.file_anchors.is_empty() {
+    None
+  } else {
+    Some(serde_json::to_string(&memory.file_anchors)?)
+  }]);
+  let code_refs = StringArray::from(vec![if memory.code_refs.is_empty() {
+    None
+  } else {
+    Some(serde_json::to_string(&memory.code_refs)?)
+  }]);
+  let stale = BooleanArray::from(vec![memory.stale]);
+  let stale_reason = StringArray::from(vec![memory.stale_reason.clone()]);
   let context = StringArray::from(vec![memory.context.clone()]);
   let session_id = StringArray::from(vec![memory.session_id.clone()]);
   let segment_id = StringArray::from(vec![memory.segment_id.map(|id| id.to_string())]);
   let scope_path = StringArray::from(vec![memory.scope_path.clone()]);
   let scope_module = StringArray::from(vec![memory.scope_module.clone()]);
+  let goal_status = StringArray::from(vec![memory.goal_status.map(|s| s.as_str().to_string())]);
+  let due = StringArray::from(vec![memory.due.clone()]);
   let created_at = Int64Array::from(vec![memory.created_at.timestamp_millis()]);
   let updated_at = Int64Array::from(vec![memory.updated_at.timestamp_millis()]);
   let last_accessed = Int64Array::from(vec![memory.last_accessed.timestamp_millis()]);
@@ -649,6 +751,8 @@ fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize) -> Result
   let decay_rate = Float32Array::from(vec![memory.decay_rate]);
   let next_decay_at = Int64Array::from(vec![memory.next_decay_at.map(|t| t.timestamp_millis())]);
   let embedding_model_id = StringArray::from(vec![memory.embedding_model_id.clone()]);
+  let helpful_count = UInt32Array::from(vec![memory.helpful_count]);
+  let irrelevant_count = UInt32Array::from(vec![memory.irrelevant_count]);
 
   // Handle vector - pad or truncate to match expected dimensions
   let mut vec_padded = vector.to_vec();
@@ -695,6 +799,15 @@ fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize) -> Result
       Arc::new(next_decay_at),
       Arc::new(embedding_model_id),
       Arc::new(vector_list),
+      Arc::new(file_anchors),
+      Arc::new(code_refs),
+      Arc::new(stale),
+      Arc::new(stale_reason),
+      Arc::new(visibility),
+      Arc::new(goal_status),
+      Arc::new(due),
+      Arc::new(helpful_count),
+      Arc::new(irrelevant_count),
     ],
   )?;
 
@@ -702,7 +815,7 @@ fn memory_to_batch(memory: &Memory, vector: &[f32], vector_dim: usize) -> Result
 }
 
 /// Convert a RecordBatch row to a Memory
-fn batch_to_memory(batch: &RecordBatch, row: usize) -> Result<Memory> {
+fn batch_to_memory(batch: &RecordBatch, row: usize, cipher: &ContentCipher) -> Result<Memory> {
   let get_string = |name: &str| -> Result<String> {
     batch
       .column_by_name(name)
@@ -763,6 +876,14 @@ fn batch_to_memory(batch: &RecordBatch, row: usize) -> Result<Memory> {
       .and_then(|a| if a.is_null(row) { None } else { Some(a.value(row)) })
   };
 
+  // Nullable so rows written before search feedback read as no feedback
+  let get_count = |name: &str| -> u32 {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<UInt32Array>())
+      .map_or(0, |a| if a.is_null(row) { 0 } else { a.value(row) })
+  };
+
   let get_bool = |name: &str| -> Result<bool> {
     batch
       .column_by_name(name)
@@ -787,6 +908,17 @@ fn batch_to_memory(batch: &RecordBatch, row: usize) -> Result<Memory> {
   let concepts_json = get_string("concepts")?;
   let files_json = get_string("files")?;
   let categories_json = get_optional_string("categories").unwrap_or_else(|| "[]".to_string());
+  let file_anchors = get_optional_string("file_anchors")
+    .and_then(|json| serde_json::from_str(&json).ok())
+    .unwrap_or_default();
+  let code_refs = get_optional_string("code_refs")
+    .and_then(|json| serde_json::from_str(&json).ok())
+    .unwrap_or_default();
+  // Nullable so rows written before staleness tracking read as fresh
+  let stale = batch
+    .column_by_name("stale")
+    .and_then(|c| c.as_any().downcast_ref::<BooleanArray>())
+    .is_some_and(|a| !a.is_null(row) && a.value(row));
 
   let sector = sector_str.parse::<Sector>().map_err(DbError::NotFound)?;
 
@@ -804,9 +936,15 @@ fn batch_to_memory(batch: &RecordBatch, row: usize) -> Result<Memory> {
     "pattern" => Some(MemoryType::Pattern),
     "turn_summary" => Some(MemoryType::TurnSummary),
     "task_completion" => Some(MemoryType::TaskCompletion),
+    "goal" => Some(MemoryType::Goal),
     _ => None,
   });
 
+  // Nullable so rows written before visibility levels read as shared
+  let visibility = get_optional_string("visibility")
+    .and_then(|s| s.parse::<Visibility>().ok())
+    .unwrap_or_default();
+
   let created_at = Utc
     .timestamp_millis_opt(get_i64("created_at")?)
     .single()
@@ -832,21 +970,30 @@ fn batch_to_memory(batch: &RecordBatch, row: usize) -> Result<Memory> {
   Ok(Memory {
     id: id_str.parse().map_err(|_| DbError::NotFound("invalid id".into()))?,
     project_id: Uuid::parse_str(&project_id_str).map_err(|_| DbError::NotFound("invalid project_id".into()))?,
-    content: get_string("content")?,
-    summary: get_optional_string("summary"),
+    content: cipher.open(get_string("content")?)?,
+    summary: get_optional_string("summary").map(|s| cipher.open(s)).transpose()?,
     sector,
     tier,
     memory_type,
+    visibility,
     importance: get_f32("importance")?,
     salience: get_f32("salience")?,
     confidence: get_f32("confidence")?,
     access_count: get_u32("access_count")?,
+    helpful_count: get_count("helpful_count"),
+    irrelevant_count: get_count("irrelevant_count"),
     tags: serde_json::from_str(&tags_json)?,
     concepts: serde_json::from_str(&concepts_json)?,
     files: serde_json::from_str(&files_json)?,
     categories: serde_json::from_str(&categories_json)?,
+    file_anchors,
+    code_refs,
+    stale,
+    stale_reason: get_optional_string("stale_reason"),
     scope_path: get_optional_string("scope_path"),
     scope_module: get_optional_string("scope_module"),
+    goal_status: get_optional_string("goal_status").and_then(|s| s.parse::<GoalStatus>().ok()),
+    due: get_optional_string("due"),
     decay_rate: get_optional_f32("decay_rate"),
     next_decay_at,
     embedding_model_id: get_optional_string("embedding_model_id"),
@@ -910,6 +1057,89 @@ mod tests {
     assert_eq!(retrieved.content, memory.content);
   }
 
+  #[tokio::test]
+  async fn test_upgraded_table_matches_fresh_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.lancedb");
+    let config = Arc::new(Config::default());
+    let fresh = memories_schema(config.embedding.dimensions);
+    let added = [
+      "file_anchors",
+      "code_refs",
+      "stale",
+      "stale_reason",
+      "visibility",
+      "goal_status",
+      "due",
+      "helpful_count",
+      "irrelevant_count",
+    ];
+    let before: Vec<_> = fresh
+      .fields()
+      .iter()
+      .filter(|f| !added.contains(&f.name().as_str()))
+      .cloned()
+      .collect();
+    lancedb::connect(db_path.to_string_lossy().as_ref())
+      .execute()
+      .await
+      .unwrap()
+      .create_empty_table("memories", Arc::new(arrow_schema::Schema::new(before)))
+      .execute()
+      .await
+      .unwrap();
+
+    let db = ProjectDb::open_at_path(
+      crate::domain::project::ProjectId::from_path(Path::new("/test")).await,
+      db_path,
+      config,
+    )
+    .await
+    .unwrap();
+    let names =
+      |schema: &arrow_schema::Schema| -> Vec<String> { schema.fields().iter().map(|f| f.name().clone()).collect() };
+    assert_eq!(
+      names(&db.memories_table().schema().await.unwrap()),
+      names(&fresh),
+      "added columns land where fresh tables have them"
+    );
+
+    let mut memory = create_test_memory();
+    memory.content_hash = "upgraded".to_string();
+    memory.visibility = Visibility::Private;
+    memory.helpful_count = 2;
+    db.add_memory(&memory, &dummy_vector(db.vector_dim))
+      .await
+      .expect("memories write to an upgraded table");
+
+    let retrieved = db.get_memory(&memory.id).await.unwrap().unwrap();
+    assert_eq!(retrieved.visibility, Visibility::Private);
+    assert_eq!(retrieved.helpful_count, 2, "columns added on upgrade roundtrip");
+  }
+
+  #[tokio::test]
+  async fn test_purge_private_memories() {
+    let (_temp, db) = create_test_db().await;
+    let mut shared = create_test_memory();
+    shared.content_hash = "shared".to_string();
+    let mut private = create_test_memory();
+    private.content_hash = "private".to_string();
+    private.visibility = Visibility::Private;
+    db.add_memory(&shared, &dummy_vector(db.vector_dim)).await.unwrap();
+    db.add_memory(&private, &dummy_vector(db.vector_dim)).await.unwrap();
+
+    assert_eq!(
+      db.get_memory(&private.id).await.unwrap().map(|m| m.visibility),
+      Some(Visibility::Private),
+      "visibility round-trips"
+    );
+
+    assert_eq!(db.purge_private_memories().await.unwrap(), 1);
+    assert!(db.get_memory(&private.id).await.unwrap().is_none());
+    assert!(db.get_memory(&shared.id).await.unwrap().is_some());
+    assert_eq!(db.purge_private_memories().await.unwrap(), 0);
+  }
+
   #[tokio::test]
   async fn test_list_memories() {
     let (_temp, db) = create_test_db().await;
@@ -926,6 +1156,35 @@ mod tests {
     assert_eq!(memories.len(), 2);
   }
 
+  #[tokio::test]
+  async fn test_find_by_prefix_rejects_filter_syntax() {
+    let (_temp, db) = create_test_db().await;
+    let memory = create_test_memory();
+    db.add_memory(&memory, &dummy_vector(db.vector_dim)).await.unwrap();
+
+    let prefix = &memory.id.to_string()[..8];
+    assert_eq!(db.find_by_prefix(prefix).await.unwrap().len(), 1);
+
+    // Would match every row if interpolated into the LIKE pattern
+    let result = db.find_by_prefix("%' OR '1'='1").await;
+    assert!(matches!(result, Err(DbError::InvalidInput(_))));
+  }
+
+  #[tokio::test]
+  async fn test_record_memory_feedback() {
+    let (_temp, db) = create_test_db().await;
+    let mut memory = create_test_memory();
+    memory.content_hash = "test_hash".to_string();
+    db.add_memory(&memory, &dummy_vector(db.vector_dim)).await.unwrap();
+
+    db.record_memory_feedback(&memory.id, true).await.unwrap();
+    db.record_memory_feedback(&memory.id, true).await.unwrap();
+    db.record_memory_feedback(&memory.id, false).await.unwrap();
+
+    let memory = db.get_memory(&memory.id).await.unwrap().unwrap();
+    assert_eq!((memory.helpful_count, memory.irrelevant_count), (2, 1));
+  }
+
   #[tokio::test]
   async fn test_delete_memory() {
     let (_temp, db) = create_test_db().await;
@@ -942,4 +1201,50 @@ mod tests {
     let after = db.get_memory(&memory.id).await.unwrap();
     assert!(after.is_none());
   }
+
+  #[tokio::test]
+  async fn test_encrypted_memory_content() {
+    let mut config = Config::default();
+    config.encryption.enabled = true;
+    config.encryption.key_env = "CCENGRAM_TEST_MEMORY_KEY".to_string();
+    unsafe {
+      std::env::set_var(
+        &config.encryption.key_env,
+        "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=",
+      )
+    };
+
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = crate::domain::project::ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(project_id, temp_dir.path().join("test.lancedb"), Arc::new(config))
+      .await
+      .unwrap();
+
+    let mut memory = create_test_memory();
+    memory.summary = Some("Test summary".to_string());
+    db.add_memory(&memory, &dummy_vector(db.vector_dim)).await.unwrap();
+
+    let stored: Vec<RecordBatch> = db
+      .memories_table()
+      .query()
+      .execute()
+      .await
+      .unwrap()
+      .try_collect()
+      .await
+      .unwrap();
+    let raw = stored[0]
+      .column_by_name("content")
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .unwrap()
+      .value(0);
+    assert!(
+      !raw.contains("Test memory content"),
+      "content is not stored in plaintext"
+    );
+
+    let retrieved = db.get_memory(&memory.id).await.unwrap().unwrap();
+    assert_eq!(retrieved.content, "Test memory content");
+    assert_eq!(retrieved.summary.as_deref(), Some("Test summary"));
+  }
 }
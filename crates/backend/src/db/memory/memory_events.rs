@@ -0,0 +1,331 @@
+// Memory event log table operations
+//
+// Records every change to a memory with who made it, so `memory history` can
+// show how a memory came to look the way it does. Events outlive hard deletes.
+// Changes recorded together share a batch ID, so `memory undo` can revert a
+// whole archive run at once.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{
+  db::{DbError, FilterBuilder, ProjectDb, Result, schema::memory_events_schema},
+  domain::memory::{MemoryActor, MemoryEventAction},
+};
+
+/// A change about to be recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryChange {
+  pub memory_id: String,
+  pub action: MemoryEventAction,
+  pub salience_before: Option<f32>,
+  pub salience_after: Option<f32>,
+  /// Action-specific detail, e.g. the memory that superseded this one
+  pub detail: Option<String>,
+}
+
+impl MemoryChange {
+  pub fn new(memory_id: impl ToString, action: MemoryEventAction) -> Self {
+    Self {
+      memory_id: memory_id.to_string(),
+      action,
+      salience_before: None,
+      salience_after: None,
+      detail: None,
+    }
+  }
+
+  pub fn with_salience(mut self, before: Option<f32>, after: Option<f32>) -> Self {
+    self.salience_before = before;
+    self.salience_after = after;
+    self
+  }
+
+  pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+    self.detail = Some(detail.into());
+    self
+  }
+}
+
+/// A recorded change to a memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEvent {
+  pub id: Uuid,
+  pub memory_id: String,
+  pub action: MemoryEventAction,
+  pub actor: MemoryActor,
+  pub salience_before: Option<f32>,
+  pub salience_after: Option<f32>,
+  pub detail: Option<String>,
+  pub created_at: DateTime<Utc>,
+  /// Shared by the changes recorded together; the event's own ID for events
+  /// recorded before batches were
+  pub batch_id: Uuid,
+}
+
+impl ProjectDb {
+  /// Record changes made to memories by `actor` as one batch
+  #[tracing::instrument(level = "trace", skip(self, changes))]
+  pub async fn record_memory_events(&self, actor: MemoryActor, changes: &[MemoryChange]) -> Result<()> {
+    self.check_writable()?;
+    if changes.is_empty() {
+      return Ok(());
+    }
+    debug!(
+      table = "memory_events",
+      operation = "record",
+      actor = actor.as_str(),
+      count = changes.len(),
+      "Recording memory events"
+    );
+
+    let now = Utc::now().timestamp_millis();
+    let len = changes.len();
+    let batch_id = Uuid::now_v7().to_string();
+    let batch = RecordBatch::try_new(
+      memory_events_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(
+          (0..len).map(|_| Uuid::now_v7().to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+          changes.iter().map(|c| c.memory_id.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(changes.iter().map(|c| c.action.as_str()))),
+        Arc::new(StringArray::from(vec![actor.as_str(); len])),
+        Arc::new(Float32Array::from(
+          changes.iter().map(|c| c.salience_before).collect::<Vec<_>>(),
+        )),
+        Arc::new(Float32Array::from(
+          changes.iter().map(|c| c.salience_after).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(
+          changes.iter().map(|c| c.detail.as_deref()).collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from(vec![now; len])),
+        Arc::new(StringArray::from(vec![batch_id.as_str(); len])),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], memory_events_schema());
+
+    self.memory_events_table().add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
+  /// Get every event recorded for a memory, oldest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn get_memory_events(&self, memory_id: &str) -> Result<Vec<MemoryEvent>> {
+    let results: Vec<RecordBatch> = self
+      .memory_events_table()
+      .query()
+      .only_if(FilterBuilder::new().add_eq("memory_id", memory_id).build_or_empty())
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut events = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        events.push(batch_to_event(&batch, i)?);
+      }
+    }
+    // UUIDv7 ids break ties between events recorded in the same millisecond
+    events.sort_by_key(|e| (e.created_at, e.id));
+
+    Ok(events)
+  }
+
+  /// Get every event with one of `actions`, newest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_memory_events(&self, actions: &[MemoryEventAction]) -> Result<Vec<MemoryEvent>> {
+    if actions.is_empty() {
+      return Ok(Vec::new());
+    }
+    let actions: Vec<&str> = actions.iter().map(MemoryEventAction::as_str).collect();
+    let results: Vec<RecordBatch> = self
+      .memory_events_table()
+      .query()
+      .only_if(FilterBuilder::new().add_in("action", &actions).build_or_empty())
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut events = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        events.push(batch_to_event(&batch, i)?);
+      }
+    }
+    events.sort_by_key(|e| std::cmp::Reverse((e.created_at, e.id)));
+
+    Ok(events)
+  }
+}
+
+/// Convert a RecordBatch row to a MemoryEvent
+fn batch_to_event(batch: &RecordBatch, row: usize) -> Result<MemoryEvent> {
+  let strings = |name: &str| -> Result<&StringArray> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+  let get_string = |name: &str| -> Result<String> { Ok(strings(name)?.value(row).to_string()) };
+  let get_optional_string = |name: &str| -> Result<Option<String>> {
+    let array = strings(name)?;
+    Ok((!array.is_null(row)).then(|| array.value(row).to_string()))
+  };
+  let get_optional_f32 = |name: &str| -> Option<f32> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<Float32Array>())
+      .filter(|a| !a.is_null(row))
+      .map(|a| a.value(row))
+  };
+
+  let created_at_ts = batch
+    .column_by_name("created_at")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .map(|a| a.value(row))
+    .ok_or_else(|| DbError::NotFound("column created_at".into()))?;
+
+  let id_str = get_string("id")?;
+  let id = Uuid::parse_str(&id_str).map_err(|_| DbError::NotFound("invalid id".into()))?;
+  let batch_id = match get_optional_string("batch_id") {
+    Ok(Some(batch_id)) => Uuid::parse_str(&batch_id).map_err(|_| DbError::NotFound("invalid batch_id".into()))?,
+    _ => id,
+  };
+  let action = get_string("action")?
+    .parse::<MemoryEventAction>()
+    .map_err(DbError::NotFound)?;
+  let actor = get_string("actor")?.parse::<MemoryActor>().map_err(DbError::NotFound)?;
+  let created_at = Utc
+    .timestamp_millis_opt(created_at_ts)
+    .single()
+    .ok_or_else(|| DbError::NotFound("invalid created_at timestamp".into()))?;
+
+  Ok(MemoryEvent {
+    id,
+    memory_id: get_string("memory_id")?,
+    action,
+    actor,
+    salience_before: get_optional_f32("salience_before"),
+    salience_after: get_optional_f32("salience_after"),
+    detail: get_optional_string("detail")?,
+    created_at,
+    batch_id,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_record_and_get_memory_events() {
+    let (_temp, db) = create_test_db().await;
+
+    db.record_memory_events(
+      MemoryActor::Hook,
+      &[MemoryChange::new("m1", MemoryEventAction::Added).with_salience(None, Some(0.8))],
+    )
+    .await
+    .unwrap();
+    db.record_memory_events(
+      MemoryActor::Mcp,
+      &[
+        MemoryChange::new("m1", MemoryEventAction::Reinforced).with_salience(Some(0.8), Some(0.82)),
+        MemoryChange::new("m2", MemoryEventAction::Deleted),
+      ],
+    )
+    .await
+    .unwrap();
+    db.record_memory_events(
+      MemoryActor::Cli,
+      &[MemoryChange::new("m1", MemoryEventAction::Superseded).with_detail("m3")],
+    )
+    .await
+    .unwrap();
+
+    let events = db.get_memory_events("m1").await.unwrap();
+    let actions: Vec<_> = events.iter().map(|e| (e.action, e.actor)).collect();
+    assert_eq!(
+      actions,
+      vec![
+        (MemoryEventAction::Added, MemoryActor::Hook),
+        (MemoryEventAction::Reinforced, MemoryActor::Mcp),
+        (MemoryEventAction::Superseded, MemoryActor::Cli),
+      ],
+      "only this memory's events, oldest first"
+    );
+    assert_eq!(events[0].salience_before, None);
+    assert_eq!(events[1].salience_before, Some(0.8));
+    assert_eq!(events[1].salience_after, Some(0.82));
+    assert_eq!(events[2].detail.as_deref(), Some("m3"));
+    assert_ne!(events[0].batch_id, events[1].batch_id, "each call is its own batch");
+
+    let m2 = db.get_memory_events("m2").await.unwrap();
+    assert_eq!(
+      m2[0].batch_id, events[1].batch_id,
+      "changes recorded together share a batch"
+    );
+
+    let deletes_and_supersedes = db
+      .list_memory_events(&[MemoryEventAction::Deleted, MemoryEventAction::Superseded])
+      .await
+      .unwrap();
+    let listed: Vec<_> = deletes_and_supersedes.iter().map(|e| e.memory_id.as_str()).collect();
+    assert_eq!(listed, vec!["m1", "m2"], "newest first");
+  }
+
+  #[tokio::test]
+  async fn test_events_for_quoted_memory_id() {
+    let (_temp, db) = create_test_db().await;
+    db.record_memory_events(
+      MemoryActor::Cli,
+      &[
+        MemoryChange::new("it's", MemoryEventAction::Added),
+        MemoryChange::new("m2", MemoryEventAction::Added),
+      ],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+      db.get_memory_events("it's").await.unwrap().len(),
+      1,
+      "quoted ID is matched literally"
+    );
+    assert!(
+      db.get_memory_events("x' OR '1' = '1").await.unwrap().is_empty(),
+      "quotes can't widen the filter"
+    );
+  }
+}
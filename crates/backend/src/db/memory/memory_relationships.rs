@@ -21,6 +21,7 @@ impl ProjectDb {
   /// Add a relationship between two memories
   #[tracing::instrument(level = "trace", skip(self, relationship))]
   pub async fn add_relationship(&self, relationship: &MemoryRelationship) -> Result<()> {
+    self.check_writable()?;
     let table = self.memory_relationships_table();
 
     let batch = relationship_to_batch(relationship)?;
@@ -74,6 +75,7 @@ impl ProjectDb {
   /// Delete a relationship by ID
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn delete_relationship(&self, id: &Uuid) -> Result<()> {
+    self.check_writable()?;
     let table = self.memory_relationships_table();
     table.delete(&format!("id = '{}'", id)).await?;
     Ok(())
@@ -1,2 +1,7 @@
+mod entities;
 mod memories;
+mod memory_events;
 mod memory_relationships;
+
+pub use entities::EntityLink;
+pub use memory_events::{MemoryChange, MemoryEvent};
@@ -1,12 +1,30 @@
 mod connection;
 mod document;
+mod embedding_cache;
+mod encryption;
+mod filter;
 mod index;
+mod legacy;
 mod memory;
+mod recovery;
 mod schema;
+mod search_logs;
 mod session;
+mod usage;
 
 pub mod code;
 
 pub(in crate::db) use connection::Result;
-pub use connection::{DbError, ProjectDb};
-pub use index::IndexedFile;
+pub use connection::{DbError, MaintenanceReport, ProjectDb, TableHealth};
+pub use embedding_cache::EmbeddingCacheDb;
+pub use encryption::ContentCipher;
+pub use filter::FilterBuilder;
+pub use index::{IndexedFile, SkipReason, SkippedFile};
+pub use legacy::{LegacyStore, legacy_vector_dim};
+pub use memory::{EntityLink, MemoryChange, MemoryEvent};
+pub use recovery::{QuarantinedTable, Salvage};
+pub use search_logs::{SearchKind, SearchLog};
+pub use session::{
+  ExtractionJob, ExtractionJobStatus, Injection, InjectionRule, SessionInjection, SessionMemoryLink, UsageType,
+};
+pub use usage::{UsageDb, UsageKind, UsageRecord};
@@ -0,0 +1,310 @@
+// Recovery from unreadable tables
+//
+// A table left unreadable by a partial write or a bad version bump used to
+// fail the whole project. Each table is probed when a database opens; one
+// that can't be read is moved to the project's quarantine directory and
+// recreated empty, so the other tables keep serving. Repair later copies
+// what it can from the newest readable version of the quarantined copy.
+
+use std::path::{Path, PathBuf};
+
+use arrow_array::{RecordBatch, RecordBatchIterator};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use lancedb::{
+  Connection, connect,
+  query::{ExecutableQuery, QueryBase},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{
+  db::{DbError, ProjectDb, Result},
+  disk,
+};
+
+/// Directory next to the LanceDB directory holding quarantined tables
+const QUARANTINE_DIR: &str = "quarantine";
+
+/// Record of the tables in the quarantine directory
+const QUARANTINE_LOG: &str = "quarantine.json";
+
+/// A table set aside because it could not be read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedTable {
+  pub table: String,
+  /// Where the unreadable table was moved
+  pub path: PathBuf,
+  /// The error that made it unreadable
+  pub reason: String,
+  pub quarantined_at: DateTime<Utc>,
+}
+
+/// Rows copied back from a quarantined table
+#[derive(Debug, Clone, Copy)]
+pub struct Salvage {
+  /// Newest table version that could be read
+  pub version: u64,
+  pub rows: usize,
+}
+
+/// Probe every table under `db_path` and quarantine the ones that can't be read.
+///
+/// Failures caused by a full disk or missing permissions are returned as
+/// errors instead: the table is fine, the machine isn't.
+pub(super) async fn quarantine_unreadable(db_path: &Path) -> Result<Vec<QuarantinedTable>> {
+  if !tokio::fs::try_exists(db_path).await.unwrap_or(false) {
+    return Ok(Vec::new());
+  }
+
+  // A separate connection keeps the broken tables out of the shared session's caches
+  let connection = connect(db_path.to_string_lossy().as_ref()).execute().await?;
+  let mut quarantined = Vec::new();
+  for name in connection.table_names().execute().await? {
+    let Err(e) = probe(&connection, &name).await else {
+      continue;
+    };
+    if !is_corruption(&e) {
+      return Err(e.into());
+    }
+
+    warn!(table = %name, err = %e, "Table is unreadable, moving it to quarantine");
+    quarantined.push(quarantine(db_path, &name, &e.to_string()).await?);
+  }
+
+  if !quarantined.is_empty() {
+    let mut log = pending(db_path).await;
+    log.extend(quarantined.iter().cloned());
+    save(db_path, &log).await?;
+  }
+  Ok(quarantined)
+}
+
+/// Tables quarantined under `db_path` that have not been repaired
+pub(super) async fn pending(db_path: &Path) -> Vec<QuarantinedTable> {
+  match tokio::fs::read_to_string(quarantine_dir(db_path).join(QUARANTINE_LOG)).await {
+    Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+      warn!(err = %e, "Ignoring unreadable quarantine log");
+      Vec::new()
+    }),
+    Err(_) => Vec::new(),
+  }
+}
+
+async fn save(db_path: &Path, log: &[QuarantinedTable]) -> Result<()> {
+  let dir = quarantine_dir(db_path);
+  tokio::fs::create_dir_all(&dir).await?;
+  tokio::fs::write(dir.join(QUARANTINE_LOG), serde_json::to_string_pretty(log)?).await?;
+  Ok(())
+}
+
+fn quarantine_dir(db_path: &Path) -> PathBuf {
+  db_path.parent().unwrap_or(db_path).join(QUARANTINE_DIR)
+}
+
+/// Open a table and read from it, touching its manifest, schema and data files
+async fn probe(connection: &Connection, name: &str) -> lancedb::Result<()> {
+  let table = connection.open_table(name).execute().await?;
+  table.count_rows(None).await?;
+  let _: Vec<RecordBatch> = table.query().limit(1).execute().await?.try_collect().await?;
+  Ok(())
+}
+
+/// Whether an error means the table itself is damaged
+fn is_corruption(err: &lancedb::Error) -> bool {
+  if disk::is_out_of_space(err) {
+    return false;
+  }
+  let mut current: Option<&(dyn std::error::Error + 'static)> = Some(err);
+  while let Some(err) = current {
+    if let Some(io) = err.downcast_ref::<std::io::Error>()
+      && io.kind() == std::io::ErrorKind::PermissionDenied
+    {
+      return false;
+    }
+    current = err.source();
+  }
+  true
+}
+
+async fn quarantine(db_path: &Path, name: &str, reason: &str) -> Result<QuarantinedTable> {
+  let dir = quarantine_dir(db_path);
+  tokio::fs::create_dir_all(&dir).await?;
+
+  let now = Utc::now();
+  let target = dir.join(format!("{}-{}.lance", name, now.format("%Y%m%dT%H%M%S%3f")));
+  tokio::fs::rename(db_path.join(format!("{}.lance", name)), &target).await?;
+
+  Ok(QuarantinedTable {
+    table: name.to_string(),
+    path: target,
+    reason: reason.to_string(),
+    quarantined_at: now,
+  })
+}
+
+/// Table versions in a table directory, newest first, with their manifest files
+async fn manifests(table_dir: &Path) -> Vec<(u64, PathBuf)> {
+  let mut found = Vec::new();
+  let Ok(mut entries) = tokio::fs::read_dir(table_dir.join("_versions")).await else {
+    return found;
+  };
+  while let Ok(Some(entry)) = entries.next_entry().await {
+    let path = entry.path();
+    if let Some(version) = manifest_version(&path) {
+      found.push((version, path));
+    }
+  }
+  found.sort_by(|a, b| b.0.cmp(&a.0));
+  found
+}
+
+/// Version of a manifest file.
+///
+/// Newer datasets name manifests `u64::MAX - version`, zero-padded to 20
+/// digits so the newest sorts first; older ones use the plain version.
+fn manifest_version(path: &Path) -> Option<u64> {
+  if path.extension()? != "manifest" {
+    return None;
+  }
+  let stem = path.file_stem()?.to_str()?;
+  let number: u64 = stem.parse().ok()?;
+  Some(if stem.len() == 20 { u64::MAX - number } else { number })
+}
+
+impl ProjectDb {
+  /// Tables quarantined because they could not be read, oldest first
+  pub async fn quarantined_tables(&self) -> Vec<QuarantinedTable> {
+    pending(self.path()).await
+  }
+
+  /// Copy the rows of the newest readable version of a quarantined table
+  /// into the live table, skipping rows whose `keys` are already there.
+  ///
+  /// Unreadable versions are dropped from the quarantined copy one at a time,
+  /// newest first. Returns `None` when no version can be read.
+  pub async fn salvage_quarantined(&self, entry: &QuarantinedTable, keys: &[&str]) -> Result<Option<Salvage>> {
+    self.check_writable()?;
+    let live = self
+      .table_named(&entry.table)
+      .ok_or_else(|| DbError::NotFound(format!("table {}", entry.table)))?;
+    let (Some(dir), Some(name)) = (entry.path.parent(), entry.path.file_stem().and_then(|s| s.to_str())) else {
+      return Ok(None);
+    };
+    let connection = connect(dir.to_string_lossy().as_ref()).execute().await?;
+
+    for (version, manifest) in manifests(&entry.path).await {
+      let read = async {
+        let table = connection.open_table(name).execute().await?;
+        let batches: Vec<RecordBatch> = table.query().execute().await?.try_collect().await?;
+        Ok::<_, lancedb::Error>((table.schema().await?, batches))
+      };
+      match read.await {
+        Ok((schema, batches)) => {
+          let rows = batches.iter().map(|b| b.num_rows()).sum();
+          if rows > 0 {
+            let batches = RecordBatchIterator::new(batches.into_iter().map(Ok), schema);
+            let mut builder = live.merge_insert(keys);
+            builder.when_not_matched_insert_all();
+            builder.execute(Box::new(batches)).await?;
+          }
+          info!(table = %entry.table, version, rows, "Salvaged quarantined table");
+          return Ok(Some(Salvage { version, rows }));
+        }
+        Err(e) => {
+          debug!(table = %entry.table, version, err = %e, "Table version unreadable, trying the one before");
+          tokio::fs::remove_file(&manifest).await?;
+        }
+      }
+    }
+    Ok(None)
+  }
+
+  /// Forget a repaired table and delete its quarantined copy
+  pub async fn release_quarantined(&self, entry: &QuarantinedTable) -> Result<()> {
+    let mut log = pending(self.path()).await;
+    log.retain(|e| e != entry);
+    save(self.path(), &log).await?;
+
+    if let Err(e) = tokio::fs::remove_dir_all(&entry.path).await
+      && e.kind() != std::io::ErrorKind::NotFound
+    {
+      return Err(e.into());
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::Path, sync::Arc};
+
+  use tempfile::TempDir;
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::{
+    config::Config,
+    domain::{
+      memory::{Memory, Sector},
+      project::ProjectId,
+    },
+  };
+
+  #[test]
+  fn test_manifest_versions_in_both_naming_schemes() {
+    assert_eq!(manifest_version(Path::new("_versions/7.manifest")), Some(7));
+    assert_eq!(
+      manifest_version(Path::new("_versions/18446744073709551608.manifest")),
+      Some(7),
+      "zero-padded names count down from u64::MAX"
+    );
+    assert_eq!(manifest_version(Path::new("_versions/7.txn")), None);
+  }
+
+  #[tokio::test]
+  async fn test_unreadable_table_is_quarantined_and_salvaged() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.lancedb");
+    let project_id = ProjectId::from_path(Path::new("/test/project")).await;
+    let config = Arc::new(Config::default());
+
+    let db = ProjectDb::open_at_path(project_id.clone(), db_path.clone(), config.clone())
+      .await
+      .unwrap();
+    let memory = Memory::new(Uuid::new_v4(), "Kept across a bad write".to_string(), Sector::Semantic);
+    db.add_memory(&memory, &vec![0.0; db.vector_dim]).await.unwrap();
+    db.add_memory(
+      &Memory::new(Uuid::new_v4(), "Lost".to_string(), Sector::Semantic),
+      &vec![0.0; db.vector_dim],
+    )
+    .await
+    .unwrap();
+    drop(db);
+
+    // Simulate a partial write: the newest manifest is garbage
+    let (_, newest) = manifests(&db_path.join("memories.lance")).await.remove(0);
+    tokio::fs::write(&newest, b"not a manifest").await.unwrap();
+
+    let db = ProjectDb::open_at_path(project_id, db_path, config).await.unwrap();
+    let quarantined = db.quarantined_tables().await;
+    assert_eq!(quarantined.len(), 1, "only the damaged table is quarantined");
+    assert_eq!(quarantined[0].table, "memories");
+    assert!(
+      db.get_memory(&memory.id).await.unwrap().is_none(),
+      "memories start empty"
+    );
+
+    let salvage = db
+      .salvage_quarantined(&quarantined[0], &["id"])
+      .await
+      .unwrap()
+      .expect("an older version is readable");
+    assert_eq!(salvage.rows, 1, "rows from before the bad write come back");
+    assert!(db.get_memory(&memory.id).await.unwrap().is_some());
+
+    db.release_quarantined(&quarantined[0]).await.unwrap();
+    assert!(db.quarantined_tables().await.is_empty());
+    assert!(!quarantined[0].path.exists(), "the quarantined copy is removed");
+  }
+}
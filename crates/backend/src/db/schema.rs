@@ -43,6 +43,17 @@ pub fn memories_schema(vector_dim: usize) -> Arc<Schema> {
       DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), vector_dim as i32),
       false,
     ),
+    // Columns added later go last, in the order they were added, so upgraded
+    // tables gain them in the same positions as fresh ones.
+    Field::new("file_anchors", DataType::Utf8, true), // JSON array of git blame anchors
+    Field::new("code_refs", DataType::Utf8, true),    // JSON array of indexed files/symbols referenced
+    Field::new("stale", DataType::Boolean, true),
+    Field::new("stale_reason", DataType::Utf8, true),
+    Field::new("visibility", DataType::Utf8, true),      // private or team
+    Field::new("goal_status", DataType::Utf8, true),     // open, blocked or done (goals only)
+    Field::new("due", DataType::Utf8, true),             // Goal due hint
+    Field::new("helpful_count", DataType::UInt32, true), // Search feedback
+    Field::new("irrelevant_count", DataType::UInt32, true),
   ]))
 }
 
@@ -79,6 +90,26 @@ pub fn code_chunks_schema(vector_dim: usize) -> Arc<Schema> {
       DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), vector_dim as i32),
       false,
     ),
+    // Columns added later go last, in the order they were added, so upgraded
+    // tables gain them in the same positions as fresh ones.
+    // Git checkout the chunk was indexed from, for branch-scoped search
+    Field::new("git_branch", DataType::Utf8, true),
+    Field::new("git_commit", DataType::Utf8, true),
+    // Dependency definition stored with a zero vector, left out of vector search
+    Field::new("shallow", DataType::Boolean, true),
+    // Generated code, down-ranked in search and left out of explore
+    Field::new("generated", DataType::Boolean, true),
+  ]))
+}
+
+/// Schema for the call_edges table (one row per distinct call made by a code chunk)
+pub fn call_edges_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("caller_id", DataType::Utf8, false), // Code chunk making the call
+    Field::new("file_path", DataType::Utf8, false), // File of the calling chunk
+    Field::new("caller_symbol", DataType::Utf8, true), // Definition name of the calling chunk
+    Field::new("callee", DataType::Utf8, false),    // Call as written, e.g. "HashMap::new"
+    Field::new("callee_name", DataType::Utf8, false), // Final path segment, e.g. "new"
   ]))
 }
 
@@ -130,6 +161,64 @@ pub fn session_memories_schema() -> Arc<Schema> {
     Field::new("memory_id", DataType::Utf8, false),
     Field::new("usage_type", DataType::Utf8, false), // created, recalled, updated, reinforced
     Field::new("linked_at", DataType::Int64, false), // Unix timestamp ms
+    Field::new("hook_event", DataType::Utf8, true),  // hook that recorded the link
+  ]))
+}
+
+/// Schema for the session_injections table (memories hooks put into a session's context)
+pub fn session_injections_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false),
+    Field::new("session_id", DataType::Utf8, false),
+    Field::new("memory_id", DataType::Utf8, false),
+    Field::new("hook_event", DataType::Utf8, false), // hook whose context carried the memory
+    Field::new("rule", DataType::Utf8, false),       // relevance, scope, compaction, edit_warning, goal, topical
+    Field::new("score", DataType::Float32, true),
+    Field::new("detail", DataType::Utf8, true),
+    Field::new("injected_at", DataType::Int64, false), // Unix timestamp ms
+  ]))
+}
+
+/// Schema for the memory_events table (mutation history of each memory)
+pub fn memory_events_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false),
+    Field::new("memory_id", DataType::Utf8, false),
+    Field::new("action", DataType::Utf8, false), // added, updated, deleted, reinforced, superseded, ...
+    Field::new("actor", DataType::Utf8, false),  // hook, mcp, cli, system
+    Field::new("salience_before", DataType::Float32, true),
+    Field::new("salience_after", DataType::Float32, true),
+    Field::new("detail", DataType::Utf8, true),
+    Field::new("created_at", DataType::Int64, false), // Unix timestamp ms
+    Field::new("batch_id", DataType::Utf8, true),     // shared by changes recorded together
+  ]))
+}
+
+/// Schema for the search_logs table (searches run and whether their results were used)
+pub fn search_logs_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false),
+    Field::new("kind", DataType::Utf8, false),  // memory, code, docs, explore
+    Field::new("query", DataType::Utf8, false), // encrypted when [encryption] is on
+    Field::new("result_count", DataType::UInt32, false),
+    Field::new("avg_similarity", DataType::Float32, true),
+    Field::new("result_ids", DataType::Utf8, false), // JSON array of returned IDs
+    Field::new("clicks", DataType::UInt32, false),   // results later opened or marked helpful
+    Field::new("created_at", DataType::Int64, false), // Unix timestamp ms
+  ]))
+}
+
+/// Schema for the extraction_jobs table (failed extractions awaiting retry)
+pub fn extraction_jobs_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false),
+    Field::new("session_id", DataType::Utf8, true),
+    Field::new("segment", DataType::Utf8, false), // JSON extraction context
+    Field::new("status", DataType::Utf8, false),  // pending, dead
+    Field::new("attempts", DataType::Int64, false),
+    Field::new("last_error", DataType::Utf8, true),
+    Field::new("created_at", DataType::Int64, false),
+    Field::new("next_attempt_at", DataType::Int64, false),
   ]))
 }
 
@@ -148,6 +237,25 @@ pub fn memory_relationships_schema() -> Arc<Schema> {
   ]))
 }
 
+/// Schema for the entities table (named entities mentioned by memories)
+pub fn entities_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false), // derived from entity_type + lowercased name
+    Field::new("name", DataType::Utf8, false),
+    Field::new("entity_type", DataType::Utf8, false), // person, technology, service, file
+    Field::new("created_at", DataType::Int64, false),
+  ]))
+}
+
+/// Schema for the memory_entities junction table
+pub fn memory_entities_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("memory_id", DataType::Utf8, false),
+    Field::new("entity_id", DataType::Utf8, false),
+    Field::new("linked_at", DataType::Int64, false), // Unix timestamp ms
+  ]))
+}
+
 /// Schema for the document_metadata table (tracks documents for update detection)
 pub fn document_metadata_schema() -> Arc<Schema> {
   Arc::new(Schema::new(vec![
@@ -182,3 +290,47 @@ pub fn indexed_files_schema() -> Arc<Schema> {
     Field::new("last_indexed_at", DataType::Int64, false), // Unix timestamp ms when file was last indexed
   ]))
 }
+
+/// Schema for the skipped_files table (files the indexing guard rails refused)
+pub fn skipped_files_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("file_path", DataType::Utf8, false), // Relative path from project root
+    Field::new("reason", DataType::Utf8, false),    // too_large, binary, minified, repetitive, too_many_chunks
+    Field::new("detail", DataType::Utf8, false),    // What was measured, e.g. "312 chars per line"
+    Field::new("skipped_at", DataType::Int64, false), // Unix timestamp ms
+  ]))
+}
+
+/// Schema for the daemon-wide LLM and embedding usage log
+pub fn usage_schema() -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("id", DataType::Utf8, false),
+    Field::new("recorded_at", DataType::Int64, false), // Unix timestamp ms
+    Field::new("kind", DataType::Utf8, false),         // llm, embedding
+    Field::new("project_id", DataType::Utf8, true),
+    Field::new("project_path", DataType::Utf8, true),
+    Field::new("provider", DataType::Utf8, false),
+    Field::new("model", DataType::Utf8, false),
+    Field::new("task", DataType::Utf8, true), // LLM task (extraction, classification, ...)
+    Field::new("requests", DataType::Int64, false),
+    Field::new("input_tokens", DataType::Int64, false),
+    Field::new("output_tokens", DataType::Int64, false),
+    Field::new("cost_usd", DataType::Float64, true),
+    Field::new("input_chars", DataType::Int64, true), // Characters of text sent
+    Field::new("remote", DataType::Boolean, true),    // Whether the provider is off this machine
+  ]))
+}
+
+/// Schema for the daemon-wide embedding cache (content hash -> vector)
+pub fn embedding_cache_schema(vector_dim: usize) -> Arc<Schema> {
+  Arc::new(Schema::new(vec![
+    Field::new("key", DataType::Utf8, false), // SHA-256 of model id + embedded text
+    Field::new("model_id", DataType::Utf8, false),
+    Field::new("created_at", DataType::Int64, false),
+    Field::new(
+      "vector",
+      DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), vector_dim as i32),
+      false,
+    ),
+  ]))
+}
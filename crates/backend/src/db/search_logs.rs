@@ -0,0 +1,298 @@
+// Search log table operations
+//
+// Records every search run against a project: what was asked, how many results
+// came back and how close they were. A result opened or marked helpful soon
+// after counts as a click on the search that returned it, so `stats search`
+// can show which queries miss and which find nothing worth using.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray, UInt32Array};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::db::{DbError, ProjectDb, Result, schema::search_logs_schema};
+
+/// How long after a search a result being used still counts as a click on it
+const CLICK_WINDOW_MINUTES: i64 = 30;
+
+/// What was searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+  Memory,
+  Code,
+  Docs,
+  Explore,
+}
+
+impl SearchKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      SearchKind::Memory => "memory",
+      SearchKind::Code => "code",
+      SearchKind::Docs => "docs",
+      SearchKind::Explore => "explore",
+    }
+  }
+}
+
+impl std::str::FromStr for SearchKind {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "memory" => Ok(SearchKind::Memory),
+      "code" => Ok(SearchKind::Code),
+      "docs" => Ok(SearchKind::Docs),
+      "explore" => Ok(SearchKind::Explore),
+      _ => Err(format!("Unknown search kind: {}", s)),
+    }
+  }
+}
+
+/// A recorded search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchLog {
+  pub id: Uuid,
+  pub kind: SearchKind,
+  pub query: String,
+  pub result_count: u32,
+  /// Mean similarity of the results, when the search scored them
+  pub avg_similarity: Option<f32>,
+  pub result_ids: Vec<String>,
+  /// Results later opened or marked helpful
+  pub clicks: u32,
+  pub created_at: DateTime<Utc>,
+}
+
+impl ProjectDb {
+  /// Record a search and the IDs it returned, best first
+  #[tracing::instrument(level = "trace", skip(self, query, result_ids))]
+  pub async fn record_search(
+    &self,
+    kind: SearchKind,
+    query: &str,
+    result_ids: &[String],
+    avg_similarity: Option<f32>,
+  ) -> Result<()> {
+    self.check_writable()?;
+    debug!(
+      table = "search_logs",
+      operation = "record",
+      kind = kind.as_str(),
+      results = result_ids.len(),
+      "Recording search"
+    );
+
+    let batch = RecordBatch::try_new(
+      search_logs_schema(),
+      vec![
+        Arc::new(StringArray::from(vec![Uuid::now_v7().to_string()])),
+        Arc::new(StringArray::from(vec![kind.as_str()])),
+        Arc::new(StringArray::from(vec![self.cipher().seal(query)?])),
+        Arc::new(UInt32Array::from(vec![result_ids.len() as u32])),
+        Arc::new(Float32Array::from(vec![avg_similarity])),
+        Arc::new(StringArray::from(vec![serde_json::to_string(result_ids)?])),
+        Arc::new(UInt32Array::from(vec![0u32])),
+        Arc::new(Int64Array::from(vec![Utc::now().timestamp_millis()])),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], search_logs_schema());
+
+    self.search_logs_table().add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
+  /// Count a click on the latest recent search that returned `result_id`.
+  ///
+  /// Returns whether a search was found to credit.
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn record_search_click(&self, result_id: &str) -> Result<bool> {
+    self.check_writable()?;
+    // Result IDs are UUIDs; anything else can't have been returned by a search
+    if result_id.is_empty() || !result_id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+      return Ok(false);
+    }
+
+    let since = Utc::now() - Duration::minutes(CLICK_WINDOW_MINUTES);
+    let results: Vec<RecordBatch> = self
+      .search_logs_table()
+      .query()
+      .only_if(format!(
+        "created_at >= {} AND result_ids LIKE '%\"{}\"%'",
+        since.timestamp_millis(),
+        result_id
+      ))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut latest: Option<(i64, String)> = None;
+    for batch in &results {
+      let ids = batch
+        .column_by_name("id")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+      let created = batch
+        .column_by_name("created_at")
+        .and_then(|c| c.as_any().downcast_ref::<Int64Array>());
+      let (Some(ids), Some(created)) = (ids, created) else {
+        continue;
+      };
+      for i in 0..batch.num_rows() {
+        if latest.as_ref().is_none_or(|(at, _)| created.value(i) > *at) {
+          latest = Some((created.value(i), ids.value(i).to_string()));
+        }
+      }
+    }
+
+    let Some((_, id)) = latest else {
+      return Ok(false);
+    };
+    self
+      .search_logs_table()
+      .update()
+      .only_if(format!("id = '{}'", id))
+      .column("clicks", "clicks + 1")
+      .execute()
+      .await?;
+    Ok(true)
+  }
+
+  /// Get every search recorded since `since`, newest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_search_logs(&self, since: DateTime<Utc>) -> Result<Vec<SearchLog>> {
+    let results: Vec<RecordBatch> = self
+      .search_logs_table()
+      .query()
+      .only_if(format!("created_at >= {}", since.timestamp_millis()))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut logs = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        logs.push(self.batch_to_search_log(&batch, i)?);
+      }
+    }
+    logs.sort_by_key(|l| std::cmp::Reverse((l.created_at, l.id)));
+
+    Ok(logs)
+  }
+
+  /// Convert a RecordBatch row to a SearchLog
+  fn batch_to_search_log(&self, batch: &RecordBatch, row: usize) -> Result<SearchLog> {
+    let get_string = |name: &str| -> Result<String> {
+      batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        .map(|a| a.value(row).to_string())
+        .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+    };
+    let get_u32 = |name: &str| -> u32 {
+      batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<UInt32Array>())
+        .map(|a| a.value(row))
+        .unwrap_or(0)
+    };
+
+    let avg_similarity = batch
+      .column_by_name("avg_similarity")
+      .and_then(|c| c.as_any().downcast_ref::<Float32Array>())
+      .filter(|a| !a.is_null(row))
+      .map(|a| a.value(row));
+    let created_at_ts = batch
+      .column_by_name("created_at")
+      .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+      .map(|a| a.value(row))
+      .ok_or_else(|| DbError::NotFound("column created_at".into()))?;
+
+    let id_str = get_string("id")?;
+    let kind = get_string("kind")?.parse::<SearchKind>().map_err(DbError::NotFound)?;
+    let created_at = Utc
+      .timestamp_millis_opt(created_at_ts)
+      .single()
+      .ok_or_else(|| DbError::NotFound("invalid created_at timestamp".into()))?;
+
+    Ok(SearchLog {
+      id: Uuid::parse_str(&id_str).map_err(|_| DbError::NotFound("invalid id".into()))?,
+      kind,
+      query: self.cipher().open(get_string("query")?)?,
+      result_count: get_u32("result_count"),
+      avg_similarity,
+      result_ids: serde_json::from_str(&get_string("result_ids")?)?,
+      clicks: get_u32("clicks"),
+      created_at,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_record_and_click_searches() {
+    let (_temp, db) = create_test_db().await;
+    let hit = Uuid::now_v7().to_string();
+    let other = Uuid::now_v7().to_string();
+
+    db.record_search(
+      SearchKind::Memory,
+      "retry policy",
+      &[hit.clone(), other.clone()],
+      Some(0.7),
+    )
+    .await
+    .unwrap();
+    db.record_search(SearchKind::Code, "it's \"quoted\"", &[hit.clone()], None)
+      .await
+      .unwrap();
+    db.record_search(SearchKind::Docs, "nothing here", &[], None)
+      .await
+      .unwrap();
+
+    assert!(db.record_search_click(&hit).await.unwrap());
+    assert!(db.record_search_click(&other).await.unwrap());
+    assert!(!db.record_search_click(&Uuid::now_v7().to_string()).await.unwrap());
+    assert!(!db.record_search_click("%").await.unwrap(), "not an ID");
+
+    let logs = db.list_search_logs(Utc::now() - Duration::hours(1)).await.unwrap();
+    assert_eq!(logs.len(), 3);
+    assert_eq!(logs[0].kind, SearchKind::Docs, "newest first");
+    assert_eq!(logs[0].result_count, 0);
+
+    // The click on `hit` goes to the latest search that returned it
+    assert_eq!((logs[1].query.as_str(), logs[1].clicks), ("it's \"quoted\"", 1));
+    assert_eq!((logs[2].query.as_str(), logs[2].clicks), ("retry policy", 1));
+    assert_eq!(logs[2].result_ids, vec![hit, other]);
+    assert_eq!(logs[2].avg_similarity, Some(0.7));
+    assert_eq!(logs[1].avg_similarity, None);
+  }
+}
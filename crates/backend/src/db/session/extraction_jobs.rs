@@ -0,0 +1,270 @@
+// Extraction job queue
+//
+// Segments whose LLM extraction failed are stored here and retried with
+// exponential backoff. Jobs that keep failing are marked dead and stay in the
+// table until they are retried by hand.
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::db::{
+  connection::{DbError, ProjectDb, Result},
+  schema::extraction_jobs_schema,
+};
+
+/// State of a queued extraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionJobStatus {
+  /// Waiting for its next attempt
+  Pending,
+  /// Out of attempts; only retried on request
+  Dead,
+}
+
+impl ExtractionJobStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Pending => "pending",
+      Self::Dead => "dead",
+    }
+  }
+}
+
+impl std::str::FromStr for ExtractionJobStatus {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s {
+      "pending" => Ok(Self::Pending),
+      "dead" => Ok(Self::Dead),
+      _ => Err(format!("Unknown extraction job status: {}", s)),
+    }
+  }
+}
+
+/// A segment waiting for extraction to be retried
+#[derive(Debug, Clone)]
+pub struct ExtractionJob {
+  pub id: Uuid,
+  /// Claude session the segment came from
+  pub session_id: Option<String>,
+  pub segment: llm::ExtractionContext,
+  pub status: ExtractionJobStatus,
+  /// Failed attempts so far, including the original one
+  pub attempts: u32,
+  pub last_error: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub next_attempt_at: DateTime<Utc>,
+}
+
+impl ProjectDb {
+  /// Insert or replace an extraction job
+  #[tracing::instrument(level = "trace", skip(self, job), fields(id = %job.id))]
+  pub async fn save_extraction_job(&self, job: &ExtractionJob) -> Result<()> {
+    self.check_writable()?;
+    debug!(
+      table = "extraction_jobs",
+      operation = "save",
+      job_id = %job.id,
+      status = job.status.as_str(),
+      attempts = job.attempts,
+      "Saving extraction job"
+    );
+
+    let table = self.extraction_jobs_table();
+    let batch = job_to_batch(job)?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], extraction_jobs_schema());
+
+    table.delete(&format!("id = '{}'", job.id)).await?;
+    table.add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
+  /// Pending jobs whose next attempt is due at `now`, oldest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn due_extraction_jobs(&self, now: DateTime<Utc>) -> Result<Vec<ExtractionJob>> {
+    self
+      .query_extraction_jobs(&format!(
+        "status = 'pending' AND next_attempt_at <= {}",
+        now.timestamp_millis()
+      ))
+      .await
+  }
+
+  /// Jobs with the given status, or all jobs, oldest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_extraction_jobs(&self, status: Option<ExtractionJobStatus>) -> Result<Vec<ExtractionJob>> {
+    match status {
+      Some(status) => {
+        self
+          .query_extraction_jobs(&format!("status = '{}'", status.as_str()))
+          .await
+      }
+      None => self.query_extraction_jobs("id IS NOT NULL").await,
+    }
+  }
+
+  /// Remove a job once its segment has been extracted
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn delete_extraction_job(&self, id: &Uuid) -> Result<()> {
+    self.check_writable()?;
+    self.extraction_jobs_table().delete(&format!("id = '{}'", id)).await?;
+    Ok(())
+  }
+
+  async fn query_extraction_jobs(&self, filter: &str) -> Result<Vec<ExtractionJob>> {
+    let results: Vec<RecordBatch> = self
+      .extraction_jobs_table()
+      .query()
+      .only_if(filter)
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut jobs = Vec::new();
+    for batch in &results {
+      for row in 0..batch.num_rows() {
+        jobs.push(batch_to_job(batch, row)?);
+      }
+    }
+    jobs.sort_by_key(|j| j.created_at);
+    Ok(jobs)
+  }
+}
+
+fn job_to_batch(job: &ExtractionJob) -> Result<RecordBatch> {
+  let segment = serde_json::to_string(&job.segment)?;
+
+  let batch = RecordBatch::try_new(
+    extraction_jobs_schema(),
+    vec![
+      Arc::new(StringArray::from(vec![job.id.to_string()])),
+      Arc::new(StringArray::from(vec![job.session_id.clone()])),
+      Arc::new(StringArray::from(vec![segment])),
+      Arc::new(StringArray::from(vec![job.status.as_str()])),
+      Arc::new(Int64Array::from(vec![job.attempts as i64])),
+      Arc::new(StringArray::from(vec![job.last_error.clone()])),
+      Arc::new(Int64Array::from(vec![job.created_at.timestamp_millis()])),
+      Arc::new(Int64Array::from(vec![job.next_attempt_at.timestamp_millis()])),
+    ],
+  )?;
+
+  Ok(batch)
+}
+
+fn batch_to_job(batch: &RecordBatch, row: usize) -> Result<ExtractionJob> {
+  let string = |name: &str| {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .filter(|c| !c.is_null(row))
+      .map(|c| c.value(row).to_string())
+  };
+  let timestamp = |name: &str| -> Result<DateTime<Utc>> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+      .and_then(|c| Utc.timestamp_millis_opt(c.value(row)).single())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+  let attempts = batch
+    .column_by_name("attempts")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .map(|c| c.value(row).max(0) as u32)
+    .unwrap_or(0);
+
+  let id = string("id").ok_or_else(|| DbError::NotFound("column id".into()))?;
+  let segment = string("segment").ok_or_else(|| DbError::NotFound("column segment".into()))?;
+  let status = string("status").ok_or_else(|| DbError::NotFound("column status".into()))?;
+
+  Ok(ExtractionJob {
+    id: Uuid::parse_str(&id).map_err(|_| DbError::NotFound("invalid extraction job id".into()))?,
+    session_id: string("session_id"),
+    segment: serde_json::from_str(&segment)?,
+    status: status.parse().map_err(DbError::InvalidInput)?,
+    attempts,
+    last_error: string("last_error"),
+    created_at: timestamp("created_at")?,
+    next_attempt_at: timestamp("next_attempt_at")?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use chrono::Duration;
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  fn job(next_attempt_at: DateTime<Utc>) -> ExtractionJob {
+    ExtractionJob {
+      id: Uuid::now_v7(),
+      session_id: Some("session-1".to_string()),
+      segment: llm::ExtractionContext {
+        user_prompt: Some("Fix the login bug".to_string()),
+        tool_call_count: 3,
+        ..Default::default()
+      },
+      status: ExtractionJobStatus::Pending,
+      attempts: 1,
+      last_error: Some("timeout".to_string()),
+      created_at: Utc::now(),
+      next_attempt_at,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_due_and_dead_jobs() {
+    let (_temp, db) = create_test_db().await;
+    let now = Utc::now();
+
+    let due = job(now - Duration::seconds(5));
+    let later = job(now + Duration::hours(1));
+    let mut dead = job(now - Duration::seconds(5));
+    dead.status = ExtractionJobStatus::Dead;
+    for j in [&due, &later, &dead] {
+      db.save_extraction_job(j).await.unwrap();
+    }
+
+    let found = db.due_extraction_jobs(now).await.unwrap();
+    assert_eq!(found.len(), 1, "only pending jobs past their retry time are due");
+    assert_eq!(found[0].id, due.id, "due job is returned");
+    assert_eq!(
+      found[0].segment.user_prompt.as_deref(),
+      Some("Fix the login bug"),
+      "segment roundtrips through JSON"
+    );
+
+    let dead_jobs = db.list_extraction_jobs(Some(ExtractionJobStatus::Dead)).await.unwrap();
+    assert_eq!(dead_jobs.len(), 1, "dead jobs are listed by status");
+
+    db.delete_extraction_job(&due.id).await.unwrap();
+    let all = db.list_extraction_jobs(None).await.unwrap();
+    assert_eq!(all.len(), 2, "deleted job is gone");
+  }
+}
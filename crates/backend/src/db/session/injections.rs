@@ -0,0 +1,305 @@
+// Session injection log table operations
+//
+// Records every memory the hooks put into a session's context, and why:
+// - Relevance: ranked against the working directory and git activity at session start
+// - Scope: same, boosted for being about the working directory or a recently touched file
+// - Compaction: restated after compaction
+// - EditWarning: warned about before Claude edited a file
+
+use std::sync::Arc;
+
+use arrow_array::{Array, Float32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::db::{DbError, FilterBuilder, ProjectDb, Result, schema::session_injections_schema};
+
+/// Why a memory was injected into a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionRule {
+  /// Ranked relevant to where the session started
+  Relevance,
+  /// About the working directory or a recently touched file
+  Scope,
+  /// Restated after compaction
+  Compaction,
+  /// Warned about before an edit to a file it names
+  EditWarning,
+  /// Goal still open or blocked when the session started
+  Goal,
+  /// Matched the prompt it was injected with
+  Topical,
+}
+
+impl InjectionRule {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      InjectionRule::Relevance => "relevance",
+      InjectionRule::Scope => "scope",
+      InjectionRule::Compaction => "compaction",
+      InjectionRule::EditWarning => "edit_warning",
+      InjectionRule::Goal => "goal",
+      InjectionRule::Topical => "topical",
+    }
+  }
+}
+
+impl std::str::FromStr for InjectionRule {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "relevance" => Ok(InjectionRule::Relevance),
+      "scope" => Ok(InjectionRule::Scope),
+      "compaction" => Ok(InjectionRule::Compaction),
+      "edit_warning" => Ok(InjectionRule::EditWarning),
+      "goal" => Ok(InjectionRule::Goal),
+      "topical" => Ok(InjectionRule::Topical),
+      _ => Err(format!("Unknown injection rule: {}", s)),
+    }
+  }
+}
+
+/// A memory about to be injected, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Injection {
+  pub memory_id: String,
+  pub rule: InjectionRule,
+  /// Rank score for relevance and scope, salience otherwise
+  pub score: Option<f32>,
+  /// Rule-specific detail, e.g. the file an edit warning was for
+  pub detail: Option<String>,
+}
+
+/// A recorded injection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInjection {
+  pub id: Uuid,
+  /// Claude session ID string
+  pub session_id: String,
+  pub memory_id: String,
+  /// Hook event whose context carried the memory
+  pub hook_event: String,
+  pub rule: InjectionRule,
+  pub score: Option<f32>,
+  pub detail: Option<String>,
+  pub injected_at: DateTime<Utc>,
+}
+
+impl ProjectDb {
+  /// Record memories injected into a session by a hook event
+  #[tracing::instrument(level = "trace", skip(self, injections))]
+  pub async fn record_injections(&self, session_id: &str, hook_event: &str, injections: &[Injection]) -> Result<()> {
+    self.check_writable()?;
+    if injections.is_empty() {
+      return Ok(());
+    }
+    debug!(
+      table = "session_injections",
+      operation = "record",
+      session_id = %session_id,
+      count = injections.len(),
+      hook_event = %hook_event,
+      "Recording session injections"
+    );
+
+    let now = Utc::now().timestamp_millis();
+    let len = injections.len();
+    let batch = RecordBatch::try_new(
+      session_injections_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(
+          (0..len).map(|_| Uuid::now_v7().to_string()),
+        )),
+        Arc::new(StringArray::from(vec![session_id; len])),
+        Arc::new(StringArray::from_iter_values(
+          injections.iter().map(|i| i.memory_id.as_str()),
+        )),
+        Arc::new(StringArray::from(vec![hook_event; len])),
+        Arc::new(StringArray::from_iter_values(
+          injections.iter().map(|i| i.rule.as_str()),
+        )),
+        Arc::new(Float32Array::from(
+          injections.iter().map(|i| i.score).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(
+          injections.iter().map(|i| i.detail.as_deref()).collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from(vec![now; len])),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], session_injections_schema());
+
+    self.session_injections_table().add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
+  /// Get every injection recorded for a session, oldest first
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn get_session_injections(&self, session_id: &str) -> Result<Vec<SessionInjection>> {
+    let results: Vec<RecordBatch> = self
+      .session_injections_table()
+      .query()
+      .only_if(FilterBuilder::new().add_eq("session_id", session_id).build_or_empty())
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut injections = Vec::new();
+    for batch in results {
+      for i in 0..batch.num_rows() {
+        injections.push(batch_to_injection(&batch, i)?);
+      }
+    }
+    injections.sort_by_key(|i| i.injected_at);
+
+    Ok(injections)
+  }
+}
+
+/// Convert a RecordBatch row to a SessionInjection
+fn batch_to_injection(batch: &RecordBatch, row: usize) -> Result<SessionInjection> {
+  let strings = |name: &str| -> Result<&StringArray> {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .ok_or_else(|| DbError::NotFound(format!("column {}", name)))
+  };
+  let get_string = |name: &str| -> Result<String> { Ok(strings(name)?.value(row).to_string()) };
+  let get_optional_string = |name: &str| -> Result<Option<String>> {
+    let array = strings(name)?;
+    Ok((!array.is_null(row)).then(|| array.value(row).to_string()))
+  };
+
+  let score = batch
+    .column_by_name("score")
+    .and_then(|c| c.as_any().downcast_ref::<Float32Array>())
+    .filter(|a| !a.is_null(row))
+    .map(|a| a.value(row));
+  let injected_at_ts = batch
+    .column_by_name("injected_at")
+    .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+    .map(|a| a.value(row))
+    .ok_or_else(|| DbError::NotFound("column injected_at".into()))?;
+
+  let id_str = get_string("id")?;
+  let rule = get_string("rule")?
+    .parse::<InjectionRule>()
+    .map_err(DbError::NotFound)?;
+  let injected_at = Utc
+    .timestamp_millis_opt(injected_at_ts)
+    .single()
+    .ok_or_else(|| DbError::NotFound("invalid injected_at timestamp".into()))?;
+
+  Ok(SessionInjection {
+    id: Uuid::parse_str(&id_str).map_err(|_| DbError::NotFound("invalid id".into()))?,
+    session_id: get_string("session_id")?,
+    memory_id: get_string("memory_id")?,
+    hook_event: get_string("hook_event")?,
+    rule,
+    score,
+    detail: get_optional_string("detail")?,
+    injected_at,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_record_and_get_injections() {
+    let (_temp, db) = create_test_db().await;
+
+    let session_start = [
+      Injection {
+        memory_id: "m1".to_string(),
+        rule: InjectionRule::Scope,
+        score: Some(0.8),
+        detail: None,
+      },
+      Injection {
+        memory_id: "m2".to_string(),
+        rule: InjectionRule::Relevance,
+        score: Some(0.5),
+        detail: None,
+      },
+    ];
+    db.record_injections("session-1", "SessionStart", &session_start)
+      .await
+      .unwrap();
+    let warning = Injection {
+      memory_id: "m1".to_string(),
+      rule: InjectionRule::EditWarning,
+      score: None,
+      detail: Some("src/lib.rs".to_string()),
+    };
+    db.record_injections("session-1", "PreToolUse", &[warning])
+      .await
+      .unwrap();
+    db.record_injections("session-2", "SessionStart", &session_start[..1])
+      .await
+      .unwrap();
+
+    let injections = db.get_session_injections("session-1").await.unwrap();
+    assert_eq!(injections.len(), 3, "only this session's injections");
+    assert!(injections.iter().any(|i| i.memory_id == "m2"
+      && i.rule == InjectionRule::Relevance
+      && i.score == Some(0.5)
+      && i.hook_event == "SessionStart"));
+    assert!(
+      injections.iter().any(|i| i.rule == InjectionRule::EditWarning
+        && i.score.is_none()
+        && i.detail.as_deref() == Some("src/lib.rs")),
+      "nullable score and detail round-trip"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_session_id_with_quote() {
+    let (_temp, db) = create_test_db().await;
+    let injection = Injection {
+      memory_id: "m1".to_string(),
+      rule: InjectionRule::Scope,
+      score: None,
+      detail: None,
+    };
+    db.record_injections("it's-a-session", "SessionStart", std::slice::from_ref(&injection))
+      .await
+      .unwrap();
+    db.record_injections("other", "SessionStart", &[injection])
+      .await
+      .unwrap();
+
+    let injections = db.get_session_injections("it's-a-session").await.unwrap();
+    assert_eq!(injections.len(), 1, "quoted session ID is matched literally");
+    assert!(
+      db.get_session_injections("x' OR '1' = '1").await.unwrap().is_empty(),
+      "quotes can't widen the filter"
+    );
+  }
+}
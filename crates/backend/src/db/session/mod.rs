@@ -1,2 +1,8 @@
+mod extraction_jobs;
+mod injections;
 mod session_memories;
 mod sessions;
+
+pub use extraction_jobs::{ExtractionJob, ExtractionJobStatus};
+pub use injections::{Injection, InjectionRule, SessionInjection};
+pub use session_memories::{SessionMemoryLink, UsageType};
@@ -6,16 +6,18 @@
 // - Updated: Memory was modified in this session
 // - Reinforced: Memory was confirmed/used repeatedly
 
-use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use std::{collections::HashSet, sync::Arc};
+
+use arrow_array::{Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
 use chrono::{DateTime, TimeZone, Utc};
 use futures::TryStreamExt;
 use lancedb::query::{ExecutableQuery, QueryBase};
 use serde::{Deserialize, Serialize};
-use tracing::warn;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 use crate::{
-  db::{DbError, ProjectDb, Result},
+  db::{DbError, FilterBuilder, ProjectDb, Result, schema::session_memories_schema},
   domain::memory::{MemoryId, Tier},
 };
 
@@ -33,6 +35,17 @@ pub enum UsageType {
   Reinforced,
 }
 
+impl UsageType {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UsageType::Created => "created",
+      UsageType::Recalled => "recalled",
+      UsageType::Updated => "updated",
+      UsageType::Reinforced => "reinforced",
+    }
+  }
+}
+
 impl std::str::FromStr for UsageType {
   type Err = String;
 
@@ -56,9 +69,54 @@ pub struct SessionMemoryLink {
   pub memory_id: String,
   pub usage_type: UsageType,
   pub linked_at: DateTime<Utc>,
+  /// Hook event that recorded the link (e.g. `Stop`, `PostToolUse`)
+  pub hook_event: Option<String>,
 }
 
 impl ProjectDb {
+  /// Link memories to a session with the given usage type
+  #[tracing::instrument(level = "trace", skip(self, memory_ids))]
+  pub async fn link_memories(
+    &self,
+    session_id: &str,
+    memory_ids: &[String],
+    usage_type: UsageType,
+    hook_event: Option<&str>,
+  ) -> Result<()> {
+    self.check_writable()?;
+    if memory_ids.is_empty() {
+      return Ok(());
+    }
+    debug!(
+      table = "session_memories",
+      operation = "link",
+      session_id = %session_id,
+      count = memory_ids.len(),
+      usage_type = usage_type.as_str(),
+      "Linking memories to session"
+    );
+
+    let now = Utc::now().timestamp_millis();
+    let len = memory_ids.len();
+    let batch = RecordBatch::try_new(
+      session_memories_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(
+          (0..len).map(|_| Uuid::now_v7().to_string()),
+        )),
+        Arc::new(StringArray::from(vec![session_id; len])),
+        Arc::new(StringArray::from_iter_values(memory_ids)),
+        Arc::new(StringArray::from(vec![usage_type.as_str(); len])),
+        Arc::new(Int64Array::from(vec![now; len])),
+        Arc::new(StringArray::from(vec![hook_event; len])),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], session_memories_schema());
+
+    self.session_memories_table().add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
   /// Get all memory links for a session
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn get_session_memory_links(&self, session_id: &str) -> Result<Vec<SessionMemoryLink>> {
@@ -66,7 +124,7 @@ impl ProjectDb {
 
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("session_id = '{}'", session_id))
+      .only_if(FilterBuilder::new().add_eq("session_id", session_id).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -89,7 +147,7 @@ impl ProjectDb {
 
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("memory_id = '{}'", memory_id))
+      .only_if(FilterBuilder::new().add_eq("memory_id", memory_id).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -145,11 +203,17 @@ impl ProjectDb {
     Ok(promoted_count)
   }
 
-  /// Count how many times a memory was used across sessions
+  /// Count how many distinct sessions a memory was used in
   #[tracing::instrument(level = "trace", skip(self))]
   pub async fn get_memory_usage_count(&self, memory_id: &str) -> Result<usize> {
     let links = self.get_memory_session_links(memory_id).await?;
-    Ok(links.len())
+    Ok(
+      links
+        .iter()
+        .map(|l| l.session_id.as_str())
+        .collect::<HashSet<_>>()
+        .len(),
+    )
   }
 
   /// Promote high-salience session-tier memories to project tier
@@ -221,6 +285,13 @@ fn batch_to_link(batch: &RecordBatch, row: usize) -> Result<SessionMemoryLink> {
 
   let usage_type = usage_type_str.parse::<UsageType>().map_err(DbError::NotFound)?;
 
+  // Absent in tables created before hook provenance was recorded
+  let hook_event = batch
+    .column_by_name("hook_event")
+    .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+    .filter(|a| !a.is_null(row))
+    .map(|a| a.value(row).to_string());
+
   let linked_at = Utc
     .timestamp_millis_opt(linked_at_ts)
     .single()
@@ -232,7 +303,126 @@ fn batch_to_link(batch: &RecordBatch, row: usize) -> Result<SessionMemoryLink> {
     memory_id,
     usage_type,
     linked_at,
+    hook_event,
   })
 }
 
-// Tests removed - the methods they tested (link_memory, get_session_stats) were dead code
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, domain::project::ProjectId};
+
+  async fn create_test_db() -> (TempDir, ProjectDb) {
+    let temp_dir = TempDir::new().unwrap();
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp_dir.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    (temp_dir, db)
+  }
+
+  #[tokio::test]
+  async fn test_link_memories_and_usage_count() {
+    let (_temp, db) = create_test_db().await;
+    let ids = vec!["m1".to_string(), "m2".to_string()];
+
+    db.link_memories("session-1", &ids, UsageType::Created, Some("Stop"))
+      .await
+      .unwrap();
+    db.link_memories("session-1", &ids[..1], UsageType::Recalled, Some("PostToolUse"))
+      .await
+      .unwrap();
+    db.link_memories("session-2", &ids[..1], UsageType::Reinforced, None)
+      .await
+      .unwrap();
+
+    let links = db.get_session_memory_links("session-1").await.unwrap();
+    assert_eq!(links.len(), 3, "all links for the session");
+    assert!(
+      links
+        .iter()
+        .any(|l| l.memory_id == "m2" && l.usage_type == UsageType::Created && l.hook_event.as_deref() == Some("Stop")),
+      "usage type and hook event are stored"
+    );
+
+    assert_eq!(
+      db.get_memory_usage_count("m1").await.unwrap(),
+      2,
+      "usage counts distinct sessions"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_link_into_table_without_hook_event() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.lancedb");
+    let before: Vec<_> = session_memories_schema()
+      .fields()
+      .iter()
+      .filter(|f| f.name() != "hook_event")
+      .cloned()
+      .collect();
+    lancedb::connect(db_path.to_string_lossy().as_ref())
+      .execute()
+      .await
+      .unwrap()
+      .create_empty_table("session_memories", Arc::new(arrow_schema::Schema::new(before)))
+      .execute()
+      .await
+      .unwrap();
+
+    let db = ProjectDb::open_at_path(
+      ProjectId::from_path(Path::new("/test")).await,
+      db_path,
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    db.link_memories(
+      "session-1",
+      &["m1".to_string()],
+      UsageType::Recalled,
+      Some("PostToolUse"),
+    )
+    .await
+    .expect("links write to a table created before hook events were recorded");
+
+    let links = db.get_session_memory_links("session-1").await.unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+      links[0].hook_event.as_deref(),
+      Some("PostToolUse"),
+      "hook event roundtrips"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_links_with_quoted_ids() {
+    let (_temp, db) = create_test_db().await;
+    let ids = vec!["it's-a-memory".to_string(), "m2".to_string()];
+
+    db.link_memories("it's-a-session", &ids, UsageType::Created, None)
+      .await
+      .unwrap();
+    db.link_memories("other", &ids[1..], UsageType::Recalled, None)
+      .await
+      .unwrap();
+
+    assert_eq!(db.get_session_memory_links("it's-a-session").await.unwrap().len(), 2);
+    let links = db.get_memory_session_links("it's-a-memory").await.unwrap();
+    assert_eq!(links.len(), 1, "quoted memory ID is matched literally");
+    assert_eq!(links[0].session_id, "it's-a-session");
+    assert!(
+      db.get_session_memory_links("x' OR '1' = '1").await.unwrap().is_empty(),
+      "quotes can't widen the filter"
+    );
+  }
+}
@@ -11,6 +11,7 @@ use tracing::{debug, trace};
 use uuid::Uuid;
 
 use crate::db::{
+  FilterBuilder,
   connection::{DbError, ProjectDb, Result},
   schema::sessions_schema,
 };
@@ -51,6 +52,7 @@ impl ProjectDb {
   /// Add a new session to the database
   #[tracing::instrument(level = "trace", skip(self, session), fields(id = %session.id))]
   pub async fn add_session(&self, session: &Session) -> Result<()> {
+    self.check_writable()?;
     debug!(
       table = "sessions",
       operation = "insert",
@@ -75,7 +77,7 @@ impl ProjectDb {
 
     let results: Vec<RecordBatch> = table
       .query()
-      .only_if(format!("id = '{}'", id))
+      .only_if(FilterBuilder::new().add_eq("id", id).build_or_empty())
       .execute()
       .await?
       .try_collect()
@@ -115,6 +117,7 @@ impl ProjectDb {
   /// Update a session
   #[tracing::instrument(level = "trace", skip(self, session), fields(id = %session.id))]
   pub async fn update_session(&self, session: &Session) -> Result<()> {
+    self.check_writable()?;
     trace!(
       table = "sessions",
       operation = "update",
@@ -128,7 +131,9 @@ impl ProjectDb {
     let batches = RecordBatchIterator::new(vec![Ok(batch)], sessions_schema());
 
     // Delete old and insert new
-    let _ = table.delete(&format!("id = '{}'", session.id)).await;
+    let _ = table
+      .delete(&FilterBuilder::new().add_eq("id", &session.id).build_or_empty())
+      .await;
     table.add(Box::new(batches)).execute().await?;
 
     Ok(())
@@ -0,0 +1,282 @@
+// Daemon-wide LLM and embedding usage log
+//
+// Every inference call and embedding batch sent to a provider is recorded
+// with its token counts and cost, tagged with the project that made it. The
+// log is shared by all projects so monthly spend can be checked against a
+// single budget.
+
+use std::{path::Path, sync::Arc};
+
+use arrow_array::{Array, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::TryStreamExt;
+use lancedb::{
+  ObjectStoreRegistry, Session, Table, connect,
+  query::{ExecutableQuery, QueryBase},
+};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{
+  config::CHARS_PER_TOKEN,
+  db::{
+    connection::{Result, add_missing_columns},
+    schema::usage_schema,
+  },
+};
+
+/// Index/metadata cache sizes for the usage database. It is append-only and
+/// scanned by time range, so it needs very little.
+const USAGE_SESSION_BYTES: usize = 4 * 1024 * 1024;
+
+const USAGE_TABLE: &str = "usage";
+
+/// What kind of provider call a usage record describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+  Llm,
+  Embedding,
+}
+
+impl UsageKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Llm => "llm",
+      Self::Embedding => "embedding",
+    }
+  }
+}
+
+impl std::str::FromStr for UsageKind {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s {
+      "llm" => Ok(Self::Llm),
+      "embedding" => Ok(Self::Embedding),
+      _ => Err(format!("Unknown usage kind: {}", s)),
+    }
+  }
+}
+
+/// One provider call (or batch of calls) and what it cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+  pub recorded_at: DateTime<Utc>,
+  pub kind: UsageKind,
+  pub project_id: Option<String>,
+  pub project_path: Option<String>,
+  pub provider: String,
+  pub model: String,
+  pub task: Option<String>,
+  pub requests: u64,
+  pub input_tokens: u64,
+  pub output_tokens: u64,
+  /// Cost in USD, when the provider reports it or a price is configured
+  pub cost_usd: Option<f64>,
+  /// Characters of text sent to the provider
+  pub input_chars: u64,
+  /// Whether the provider runs off this machine
+  pub remote: bool,
+}
+
+/// Append-only usage log
+pub struct UsageDb {
+  table: Table,
+}
+
+impl UsageDb {
+  /// Open or create the usage database at `db_path`
+  pub async fn open(db_path: &Path) -> Result<Self> {
+    tokio::fs::create_dir_all(db_path).await?;
+
+    let registry = Arc::new(ObjectStoreRegistry::default());
+    let session = Arc::new(Session::new(USAGE_SESSION_BYTES, USAGE_SESSION_BYTES, registry));
+    let connection = connect(db_path.to_string_lossy().as_ref())
+      .session(session)
+      .execute()
+      .await?;
+
+    let table_names = connection.table_names().execute().await?;
+    if !table_names.iter().any(|t| t == USAGE_TABLE) {
+      debug!(table = USAGE_TABLE, "Creating usage table");
+      connection
+        .create_empty_table(USAGE_TABLE, usage_schema())
+        .execute()
+        .await?;
+    }
+    // Rows written before `input_chars` and `remote` existed are read with
+    // estimates, see `batch_to_record`
+    let table = connection.open_table(USAGE_TABLE).execute().await?;
+    add_missing_columns(&table, &usage_schema()).await?;
+
+    Ok(Self { table })
+  }
+
+  /// Append usage records
+  #[tracing::instrument(level = "trace", skip(self, records), fields(count = records.len()))]
+  pub async fn add_records(&self, records: &[UsageRecord]) -> Result<()> {
+    if records.is_empty() {
+      return Ok(());
+    }
+
+    let batch = RecordBatch::try_new(
+      usage_schema(),
+      vec![
+        Arc::new(StringArray::from_iter_values(
+          records.iter().map(|_| Uuid::now_v7().to_string()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+          records.iter().map(|r| r.recorded_at.timestamp_millis()),
+        )),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.kind.as_str()))),
+        Arc::new(StringArray::from_iter(records.iter().map(|r| r.project_id.as_deref()))),
+        Arc::new(StringArray::from_iter(
+          records.iter().map(|r| r.project_path.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+          records.iter().map(|r| r.provider.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.model.as_str()))),
+        Arc::new(StringArray::from_iter(records.iter().map(|r| r.task.as_deref()))),
+        Arc::new(Int64Array::from_iter_values(records.iter().map(|r| r.requests as i64))),
+        Arc::new(Int64Array::from_iter_values(
+          records.iter().map(|r| r.input_tokens as i64),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+          records.iter().map(|r| r.output_tokens as i64),
+        )),
+        Arc::new(Float64Array::from_iter(records.iter().map(|r| r.cost_usd))),
+        Arc::new(Int64Array::from_iter_values(
+          records.iter().map(|r| r.input_chars as i64),
+        )),
+        Arc::new(BooleanArray::from_iter(records.iter().map(|r| Some(r.remote)))),
+      ],
+    )?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)], usage_schema());
+
+    self.table.add(Box::new(batches)).execute().await?;
+    Ok(())
+  }
+
+  /// Records made at or after `since`
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn list_since(&self, since: DateTime<Utc>) -> Result<Vec<UsageRecord>> {
+    let results: Vec<RecordBatch> = self
+      .table
+      .query()
+      .only_if(format!("recorded_at >= {}", since.timestamp_millis()))
+      .execute()
+      .await?
+      .try_collect()
+      .await?;
+
+    let mut records = Vec::new();
+    for batch in &results {
+      for row in 0..batch.num_rows() {
+        if let Some(record) = batch_to_record(batch, row) {
+          records.push(record);
+        }
+      }
+    }
+    records.sort_by_key(|r| r.recorded_at);
+    Ok(records)
+  }
+}
+
+fn batch_to_record(batch: &RecordBatch, row: usize) -> Option<UsageRecord> {
+  let string = |name: &str| {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+      .filter(|c| !c.is_null(row))
+      .map(|c| c.value(row).to_string())
+  };
+  let int = |name: &str| {
+    batch
+      .column_by_name(name)
+      .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+      .filter(|c| !c.is_null(row))
+      .map(|c| c.value(row))
+  };
+  let cost_usd = batch
+    .column_by_name("cost_usd")
+    .and_then(|c| c.as_any().downcast_ref::<Float64Array>())
+    .filter(|c| !c.is_null(row))
+    .map(|c| c.value(row));
+  let remote = batch
+    .column_by_name("remote")
+    .and_then(|c| c.as_any().downcast_ref::<BooleanArray>())
+    .filter(|c| !c.is_null(row))
+    .map(|c| c.value(row));
+
+  let provider = string("provider").unwrap_or_default();
+  let input_tokens = int("input_tokens").unwrap_or(0).max(0) as u64;
+  // Older rows predate these columns: estimate characters from tokens, and
+  // treat everything but the bundled local providers as remote
+  let input_chars = int("input_chars").map_or(input_tokens * CHARS_PER_TOKEN as u64, |c| c.max(0) as u64);
+  let remote = remote.unwrap_or_else(|| !matches!(provider.as_str(), "llamacpp" | "ollama"));
+
+  Some(UsageRecord {
+    recorded_at: Utc.timestamp_millis_opt(int("recorded_at")?).single()?,
+    kind: string("kind")?.parse().ok()?,
+    project_id: string("project_id"),
+    project_path: string("project_path"),
+    provider,
+    model: string("model").unwrap_or_default(),
+    task: string("task"),
+    requests: int("requests").unwrap_or(0).max(0) as u64,
+    input_tokens,
+    output_tokens: int("output_tokens").unwrap_or(0).max(0) as u64,
+    cost_usd,
+    input_chars,
+    remote,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn record(kind: UsageKind, recorded_at: DateTime<Utc>, cost_usd: Option<f64>) -> UsageRecord {
+    UsageRecord {
+      recorded_at,
+      kind,
+      project_id: Some("proj".to_string()),
+      project_path: Some("/tmp/proj".to_string()),
+      provider: "test".to_string(),
+      model: "model".to_string(),
+      task: None,
+      requests: 1,
+      input_tokens: 100,
+      output_tokens: 10,
+      cost_usd,
+      input_chars: 400,
+      remote: true,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_add_and_list_since() {
+    let temp = TempDir::new().unwrap();
+    let db = UsageDb::open(&temp.path().join("usage")).await.unwrap();
+
+    let now = Utc::now();
+    let old = record(UsageKind::Llm, now - Duration::days(40), Some(1.0));
+    let recent = record(UsageKind::Embedding, now, None);
+    db.add_records(&[old, recent.clone()]).await.unwrap();
+
+    let found = db.list_since(now - Duration::days(1)).await.unwrap();
+    assert_eq!(found.len(), 1, "only records inside the range are returned");
+    assert_eq!(found[0].kind, UsageKind::Embedding, "kind roundtrips");
+    assert_eq!(found[0].cost_usd, None, "missing cost stays missing");
+    assert_eq!(found[0].project_path, recent.project_path, "project roundtrips");
+    assert_eq!(found[0].input_chars, 400, "characters roundtrip");
+    assert!(found[0].remote, "remote flag roundtrips");
+  }
+}
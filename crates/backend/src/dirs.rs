@@ -15,13 +15,20 @@ pub fn is_daemon_running() -> bool {
   std::os::unix::net::UnixStream::connect(socket_path).is_ok()
 }
 
-/// Get the default base path for CCEngram data
+/// Get the default data directory for this build's data version
+///
+/// This is `{data_root}/v{DATA_VERSION}`; see [`crate::data_dir`].
+pub fn default_data_dir() -> std::path::PathBuf {
+  crate::data_dir::version_dir(&default_data_root(), crate::data_dir::DATA_VERSION)
+}
+
+/// Get the root holding every data version
 ///
 /// Respects the following environment variables (in order of precedence):
 /// 1. DATA_DIR - explicit data directory override
 /// 2. XDG_DATA_HOME - standard XDG data home directory
 /// 3. dirs::data_local_dir() - platform default
-pub fn default_data_dir() -> std::path::PathBuf {
+pub fn default_data_root() -> std::path::PathBuf {
   // Check explicit override first
   if let Ok(dir) = std::env::var("DATA_DIR") {
     return std::path::PathBuf::from(dir);
@@ -38,6 +45,16 @@ pub fn default_data_dir() -> std::path::PathBuf {
     .join("ccengram")
 }
 
+/// Get the default path of the offline hook spool
+pub fn default_spool_path() -> std::path::PathBuf {
+  default_data_dir().join(crate::ipc::spool::SPOOL_FILE_NAME)
+}
+
+/// Get the default directory of the per-session hook batch buffers
+pub fn default_batch_dir() -> std::path::PathBuf {
+  default_data_dir().join(crate::ipc::batch::BATCH_DIR_NAME)
+}
+
 /// Get the default config directory
 ///
 /// Respects the following environment variables (in order of precedence):
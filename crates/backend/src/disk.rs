@@ -0,0 +1,253 @@
+//! Disk space safeguards.
+//!
+//! A LanceDB write that runs out of space leaves a half-written table version
+//! behind, and every write after it fails the same way. The daemon watches the
+//! free space of its data directory and switches to read-only mode while it is
+//! below `daemon.min_free_disk_mb`, or once a write fails because the disk is
+//! full: database writes are refused, index runs pause, and searches keep
+//! working. Read-only mode ends when the scheduler finds enough free space
+//! again.
+//!
+//! Every project store lives in the one data directory, so the state is shared
+//! by the whole daemon.
+
+use std::{
+  path::Path,
+  sync::{
+    Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+  },
+};
+
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Default free space kept on the data directory's disk
+pub const DEFAULT_MIN_FREE_BYTES: u64 = 500 * 1024 * 1024;
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+static READ_ONLY_REASON: Mutex<Option<String>> = Mutex::new(None);
+static MIN_FREE_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MIN_FREE_BYTES);
+
+/// A large write that would leave less than the minimum free space
+#[derive(Error, Debug)]
+#[error(
+  "Not enough disk space at {path}: {} free, {} needed (including {} kept free)",
+  format_bytes(*.free),
+  format_bytes(*.needed),
+  format_bytes(*.reserve)
+)]
+pub struct LowDiskSpace {
+  pub path: String,
+  pub free: u64,
+  pub needed: u64,
+  pub reserve: u64,
+}
+
+/// Free space and read-only state of the data directory's disk
+#[derive(Debug, Clone)]
+pub struct DiskStatus {
+  /// Bytes available to unprivileged writes, if the platform reports it
+  pub free_bytes: Option<u64>,
+  pub min_free_bytes: u64,
+  pub read_only: bool,
+  pub reason: Option<String>,
+}
+
+/// Set the free space to keep, from `daemon.min_free_disk_mb`
+pub fn set_min_free(bytes: u64) {
+  MIN_FREE_BYTES.store(bytes, Ordering::Relaxed);
+}
+
+/// Free space to keep on the data directory's disk
+pub fn min_free() -> u64 {
+  MIN_FREE_BYTES.load(Ordering::Relaxed)
+}
+
+/// Bytes available on the filesystem holding `path`.
+///
+/// `path` does not need to exist yet; its nearest existing ancestor is
+/// checked. Returns `None` where the platform can't tell.
+#[cfg(unix)]
+pub fn free_bytes(path: &Path) -> Option<u64> {
+  use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+  let existing = path.ancestors().find(|p| p.exists())?;
+  let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+  let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+    return None;
+  }
+  // Field widths differ between platforms
+  #[allow(clippy::unnecessary_cast)]
+  let free = stat.f_bavail as u64 * stat.f_frsize as u64;
+  Some(free)
+}
+
+#[cfg(not(unix))]
+pub fn free_bytes(_path: &Path) -> Option<u64> {
+  None
+}
+
+/// Check that `needed` bytes can be written under `path` while keeping the
+/// minimum free space. Passes when free space can't be determined.
+pub fn ensure_space(path: &Path, needed: u64) -> Result<(), LowDiskSpace> {
+  let Some(free) = free_bytes(path) else {
+    return Ok(());
+  };
+  let reserve = min_free();
+  if free < needed.saturating_add(reserve) {
+    return Err(LowDiskSpace {
+      path: path.display().to_string(),
+      free,
+      needed: needed.saturating_add(reserve),
+      reserve,
+    });
+  }
+  Ok(())
+}
+
+/// Total size of the files under `path`
+pub fn dir_size(path: &Path) -> u64 {
+  walkdir::WalkDir::new(path)
+    .into_iter()
+    .filter_map(Result::ok)
+    .filter_map(|entry| entry.metadata().ok())
+    .filter(|metadata| metadata.is_file())
+    .map(|metadata| metadata.len())
+    .sum()
+}
+
+/// Whether writes are currently refused
+pub fn is_read_only() -> bool {
+  READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Why writes are refused, while they are
+pub fn read_only_reason() -> Option<String> {
+  if !is_read_only() {
+    return None;
+  }
+  READ_ONLY_REASON.lock().ok().and_then(|reason| reason.clone())
+}
+
+/// Refuse writes until the scheduler finds enough free space
+pub fn enter_read_only(reason: impl Into<String>) {
+  let reason = reason.into();
+  if !READ_ONLY.swap(true, Ordering::Relaxed) {
+    warn!(reason = %reason, "Disk space low, switching to read-only mode");
+  }
+  if let Ok(mut current) = READ_ONLY_REASON.lock() {
+    *current = Some(reason);
+  }
+}
+
+fn leave_read_only(free: u64) {
+  if READ_ONLY.swap(false, Ordering::Relaxed) {
+    info!(free = %format_bytes(free), "Disk space recovered, leaving read-only mode");
+  }
+  if let Ok(mut current) = READ_ONLY_REASON.lock() {
+    *current = None;
+  }
+}
+
+/// Switch to read-only mode if `err` was caused by a full disk
+pub fn note_error(err: &(dyn std::error::Error + 'static)) {
+  if is_out_of_space(err) {
+    enter_read_only(format!("a write failed: {}", err));
+  }
+}
+
+/// Check the free space under `data_dir`, entering or leaving read-only mode
+pub fn refresh(data_dir: &Path) -> DiskStatus {
+  let free = free_bytes(data_dir);
+  let min_free = min_free();
+  match free {
+    Some(free) if free < min_free => enter_read_only(format!(
+      "{} free on the data directory's disk, below the {} minimum",
+      format_bytes(free),
+      format_bytes(min_free)
+    )),
+    Some(free) => leave_read_only(free),
+    None => {}
+  }
+  status(data_dir)
+}
+
+/// Current free space and read-only state, without changing it
+pub fn status(data_dir: &Path) -> DiskStatus {
+  DiskStatus {
+    free_bytes: free_bytes(data_dir),
+    min_free_bytes: min_free(),
+    read_only: is_read_only(),
+    reason: read_only_reason(),
+  }
+}
+
+/// Whether an error, or any error it wraps, means the disk is full
+pub fn is_out_of_space(err: &(dyn std::error::Error + 'static)) -> bool {
+  let mut current = Some(err);
+  while let Some(err) = current {
+    if let Some(io) = err.downcast_ref::<std::io::Error>()
+      && is_enospc(io)
+    {
+      return true;
+    }
+    // LanceDB and object_store pass most IO errors on as text
+    if err.to_string().contains("No space left on device") {
+      return true;
+    }
+    current = err.source();
+  }
+  false
+}
+
+fn is_enospc(err: &std::io::Error) -> bool {
+  #[cfg(unix)]
+  if err.raw_os_error() == Some(libc::ENOSPC) {
+    return true;
+  }
+  err.kind() == std::io::ErrorKind::StorageFull
+}
+
+/// Bytes as MB or GB, for messages
+pub fn format_bytes(bytes: u64) -> String {
+  const MB: f64 = 1024.0 * 1024.0;
+  let mb = bytes as f64 / MB;
+  if mb >= 1024.0 {
+    format!("{:.1} GB", mb / 1024.0)
+  } else {
+    format!("{:.0} MB", mb)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_out_of_space_is_found_in_wrapped_errors() {
+    #[derive(Debug, Error)]
+    #[error("write failed")]
+    struct Wrapper(#[source] std::io::Error);
+
+    let full = std::io::Error::from(std::io::ErrorKind::StorageFull);
+    assert!(is_out_of_space(&Wrapper(full)), "source chain is searched");
+
+    let text = std::io::Error::other("Generic LocalFileSystem error: No space left on device (os error 28)");
+    assert!(is_out_of_space(&text), "stringified errors are recognized");
+
+    let other = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+    assert!(!is_out_of_space(&Wrapper(other)));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_ensure_space_keeps_reserve() {
+    let dir = tempfile::TempDir::new().expect("temp dir");
+    let free = free_bytes(&dir.path().join("not/created/yet")).expect("free space of an ancestor");
+
+    let err = ensure_space(dir.path(), free).expect_err("the reserve doesn't fit on top");
+    assert_eq!(err.reserve, min_free());
+  }
+}
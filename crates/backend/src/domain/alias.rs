@@ -0,0 +1,127 @@
+//! Project jargon.
+//!
+//! Teams shorten things: `k8s` for Kubernetes, `FE` for the frontend, a
+//! codename for a service. The `[aliases]` config maps each alias to the term
+//! memories and code actually use. Search queries are expanded with the other
+//! side of every alias they mention, and extracted entities and tags are
+//! stored under the canonical term, so either spelling finds the same
+//! memories.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Alias to canonical term, matched case-insensitively on whole words
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Aliases(BTreeMap<String, String>);
+
+impl Aliases {
+  pub fn new(map: BTreeMap<String, String>) -> Self {
+    Self(map)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Canonical term for `term` when it is an alias
+  pub fn canonical(&self, term: &str) -> Option<&str> {
+    let term = term.trim();
+    self
+      .0
+      .iter()
+      .find(|(alias, _)| alias.eq_ignore_ascii_case(term))
+      .map(|(_, canonical)| canonical.as_str())
+  }
+
+  /// `term`, or its canonical term when it is an alias
+  pub fn normalize(&self, term: &str) -> String {
+    self.canonical(term).unwrap_or(term).to_string()
+  }
+
+  /// Normalize tags, dropping those that become duplicates
+  pub fn normalize_tags(&self, tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+      let tag = self.normalize(&tag);
+      if !normalized.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+        normalized.push(tag);
+      }
+    }
+    normalized
+  }
+
+  /// Expand a search query with the other side of each alias it mentions.
+  ///
+  /// `"k8s rollout"` becomes `"k8s rollout kubernetes"`, and a query naming
+  /// the canonical term picks up its aliases, so both vector and full-text
+  /// search see the wording memories and code use.
+  pub fn expand(&self, query: &str) -> String {
+    let lower = query.to_lowercase();
+    let mut extra: Vec<&str> = Vec::new();
+    for (alias, canonical) in &self.0 {
+      let has_alias = contains_word(&lower, &alias.to_lowercase());
+      let has_canonical = contains_word(&lower, &canonical.to_lowercase());
+      let missing = match (has_alias, has_canonical) {
+        (true, false) => canonical.as_str(),
+        (false, true) => alias.as_str(),
+        _ => continue,
+      };
+      if !extra.iter().any(|e| e.eq_ignore_ascii_case(missing)) {
+        extra.push(missing);
+      }
+    }
+
+    if extra.is_empty() {
+      return query.to_string();
+    }
+    format!("{} {}", query, extra.join(" "))
+  }
+}
+
+/// Whether `text` contains `word` with no letter or digit either side
+fn contains_word(text: &str, word: &str) -> bool {
+  if word.is_empty() {
+    return false;
+  }
+  text.match_indices(word).any(|(start, _)| {
+    let before = text[..start].chars().next_back();
+    let after = text[start + word.len()..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn aliases() -> Aliases {
+    Aliases::new(
+      [
+        ("k8s".to_string(), "kubernetes".to_string()),
+        ("FE".to_string(), "frontend".to_string()),
+      ]
+      .into(),
+    )
+  }
+
+  #[test]
+  fn test_expand_adds_the_other_side() {
+    let aliases = aliases();
+    assert_eq!(aliases.expand("k8s rollout"), "k8s rollout kubernetes");
+    assert_eq!(aliases.expand("Frontend build"), "Frontend build FE");
+    assert_eq!(aliases.expand("k8s vs kubernetes"), "k8s vs kubernetes");
+    assert_eq!(
+      aliases.expand("feature flags"),
+      "feature flags",
+      "aliases match whole words only"
+    );
+  }
+
+  #[test]
+  fn test_normalize_tags_maps_aliases_and_dedupes() {
+    let tags = vec!["fe".to_string(), "frontend".to_string(), "auth".to_string()];
+    assert_eq!(aliases().normalize_tags(tags), vec!["frontend", "auth"]);
+  }
+}
@@ -77,6 +77,18 @@ pub struct CodeChunk {
   /// Pre-computed during indexing to avoid expensive LIKE queries
   #[serde(default)]
   pub callee_count: u32,
+  /// Branch checked out when this chunk was indexed
+  #[serde(default)]
+  pub git_branch: Option<String>,
+  /// Commit checked out when this chunk was indexed
+  #[serde(default)]
+  pub git_commit: Option<String>,
+  /// Indexed as a dependency: a definition stored without an embedding
+  #[serde(default)]
+  pub shallow: bool,
+  /// Written by a code generator, ranked below hand-written code
+  #[serde(default)]
+  pub generated: bool,
 }
 
 impl CodeChunk {
@@ -84,6 +96,49 @@ impl CodeChunk {
   pub fn estimate_tokens(content: &str) -> u32 {
     (content.len() / CHARS_PER_TOKEN) as u32
   }
+
+  /// Call graph edges for the distinct calls made by this chunk
+  pub fn call_edges(&self) -> Vec<CallEdge> {
+    let mut seen = std::collections::HashSet::new();
+    self
+      .calls
+      .iter()
+      .filter(|call| !call.is_empty() && seen.insert(call.as_str()))
+      .map(|call| CallEdge {
+        caller_id: self.id,
+        file_path: self.file_path.clone(),
+        caller_symbol: self.definition_name.clone().or_else(|| self.symbols.first().cloned()),
+        callee: call.clone(),
+        callee_name: CallEdge::name_of(call).to_string(),
+      })
+      .collect()
+  }
+}
+
+/// A call from a code chunk to a named symbol.
+///
+/// Callees are stored by name rather than chunk ID so edges stay valid when
+/// the defining file is re-indexed; they're resolved to definitions at query time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallEdge {
+  pub caller_id: Uuid,
+  pub file_path: String,
+  /// Definition name of the calling chunk
+  pub caller_symbol: Option<String>,
+  /// Call as written, e.g. "HashMap::new" or "db.query"
+  pub callee: String,
+  /// Final path segment of the call, e.g. "new" or "query"
+  pub callee_name: String,
+}
+
+impl CallEdge {
+  /// Symbol name a call refers to, without its type or receiver path
+  pub fn name_of(call: &str) -> &str {
+    call
+      .rsplit(['.', ':', '>'])
+      .find(|segment| !segment.is_empty())
+      .unwrap_or(call)
+  }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
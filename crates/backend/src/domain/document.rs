@@ -185,7 +185,6 @@ pub struct Document {
 }
 
 impl Document {
-  #[allow(dead_code)]
   pub fn new(
     project_id: Uuid,
     title: String,
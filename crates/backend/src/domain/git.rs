@@ -0,0 +1,518 @@
+//! Git checkout state used to scope indexed code to a branch.
+//!
+//! HEAD is read straight from the `.git` directory so indexing never shells
+//! out; branch diffs need merge-base resolution and go through the `git` CLI,
+//! as does the recent activity used to pick memories at session start.
+
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+};
+
+use tokio::process::Command;
+
+/// Branch and commit checked out in a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitHead {
+  /// Branch name, `None` when HEAD is detached
+  pub branch: Option<String>,
+  /// Full commit hash, `None` for a branch with no commits yet
+  pub commit: Option<String>,
+}
+
+impl GitHead {
+  /// Read the checked out branch and commit of the repository at `root`.
+  ///
+  /// Returns `None` if `root` is not a git checkout.
+  pub async fn read(root: &Path) -> Option<Self> {
+    let git_dir = git_dir(root).await?;
+    let head = tokio::fs::read_to_string(git_dir.join("HEAD")).await.ok()?;
+    let head = head.trim();
+
+    let Some(reference) = head.strip_prefix("ref:").map(str::trim) else {
+      return Some(Self {
+        branch: None,
+        commit: Some(head.to_string()),
+      });
+    };
+
+    let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string();
+    let common_dir = common_dir(&git_dir).await;
+    let commit = resolve_ref(&git_dir, &common_dir, reference).await;
+
+    Some(Self {
+      branch: Some(branch),
+      commit,
+    })
+  }
+}
+
+/// Locate the git directory for a checkout, following worktree `.git` files.
+///
+/// `root` may be a subdirectory of the checkout.
+async fn git_dir(root: &Path) -> Option<PathBuf> {
+  for dir in root.ancestors() {
+    let dot_git = dir.join(".git");
+    let Ok(metadata) = tokio::fs::metadata(&dot_git).await else {
+      continue;
+    };
+    if metadata.is_dir() {
+      return Some(dot_git);
+    }
+
+    let content = tokio::fs::read_to_string(&dot_git).await.ok()?;
+    let path = content.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim();
+    return Some(dir.join(path));
+  }
+  None
+}
+
+/// Worktrees keep branch refs in the main repository's git directory.
+async fn common_dir(git_dir: &Path) -> PathBuf {
+  match tokio::fs::read_to_string(git_dir.join("commondir")).await {
+    Ok(content) => git_dir.join(content.trim()),
+    Err(_) => git_dir.to_path_buf(),
+  }
+}
+
+async fn resolve_ref(git_dir: &Path, common_dir: &Path, reference: &str) -> Option<String> {
+  for dir in [git_dir, common_dir] {
+    if let Ok(content) = tokio::fs::read_to_string(dir.join(reference)).await {
+      return Some(content.trim().to_string());
+    }
+  }
+
+  let packed = tokio::fs::read_to_string(common_dir.join("packed-refs")).await.ok()?;
+  packed.lines().find_map(|line| {
+    let (hash, name) = line.split_once(' ')?;
+    (name.trim() == reference).then(|| hash.to_string())
+  })
+}
+
+/// Identity of the repository at `root`, shared by every clone of it.
+///
+/// The `origin` remote URL, normalized so SSH and HTTPS clones match, or the
+/// root commit for repositories without an `origin`. `None` outside git.
+pub async fn repo_fingerprint(root: &Path) -> Option<String> {
+  let git_dir = git_dir(root).await?;
+  let config = tokio::fs::read_to_string(common_dir(&git_dir).await.join("config"))
+    .await
+    .unwrap_or_default();
+  if let Some(url) = origin_url(&config) {
+    return Some(format!("remote:{}", normalize_remote_url(url)));
+  }
+
+  let roots = git(root, &["rev-list", "--max-parents=0", "HEAD"]).await.ok()?;
+  let first = roots.lines().filter(|l| !l.is_empty()).min()?;
+  Some(format!("root:{}", first))
+}
+
+/// `url` of `[remote "origin"]` in a git config file
+fn origin_url(config: &str) -> Option<&str> {
+  let mut in_origin = false;
+  for line in config.lines().map(str::trim) {
+    if line.starts_with('[') {
+      in_origin = line.replace(' ', "") == "[remote\"origin\"]";
+      continue;
+    }
+    if in_origin
+      && let Some((key, value)) = line.split_once('=')
+      && key.trim() == "url"
+    {
+      return Some(value.trim());
+    }
+  }
+  None
+}
+
+/// Reduce a remote URL to `host/path`, dropping scheme, user, port and `.git`
+fn normalize_remote_url(url: &str) -> String {
+  let url = url.trim().trim_end_matches('/');
+  let url = url.strip_suffix(".git").unwrap_or(url);
+
+  let (rest, scp_style) = match url.split_once("://") {
+    Some((_, rest)) => (rest, false),
+    None => (url, true),
+  };
+  let rest = match rest.split_once('@') {
+    Some((user, host_path)) if !user.contains('/') => host_path,
+    _ => rest,
+  };
+
+  // `host:path` for scp-style URLs, `host:port/path` otherwise
+  let (host, path) = match rest.split_once([':', '/']) {
+    Some((host, path)) if rest.as_bytes()[host.len()] == b':' && !scp_style => {
+      let path = path.trim_start_matches(|c: char| c.is_ascii_digit());
+      (host, path.trim_start_matches('/'))
+    }
+    Some((host, path)) => (host, path.trim_start_matches('/')),
+    None => (rest, ""),
+  };
+  format!("{}/{}", host.to_ascii_lowercase(), path)
+}
+
+/// Files that differ between the merge base of `base` and HEAD and the working tree.
+///
+/// Paths are relative to `root`, matching indexed `file_path` values when the
+/// project is a subdirectory of the repository; files outside it are left out.
+pub async fn changed_since_branch(root: &Path, base: &str) -> Result<HashSet<String>, String> {
+  check_rev(base)?;
+  let merge_base = git(root, &["merge-base", base, "HEAD"]).await?;
+  let merge_base = merge_base.trim();
+  changed_since_commit(root, merge_base).await
+}
+
+/// Files that differ between `commit` and the working tree, relative to `root`.
+pub async fn changed_since_commit(root: &Path, commit: &str) -> Result<HashSet<String>, String> {
+  check_rev(commit)?;
+  let output = git(root, &["diff", "--name-only", "--relative", commit, "--"]).await?;
+  Ok(output.lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// Files that differ between two commits, relative to `root`.
+///
+/// Unlike a diff against the working tree the result never changes, so
+/// callers can cache it by the pair of commits.
+pub async fn changed_between(root: &Path, from: &str, to: &str) -> Result<HashSet<String>, String> {
+  check_rev(from)?;
+  check_rev(to)?;
+  let output = git(root, &["diff", "--name-only", "--relative", from, to, "--"]).await?;
+  Ok(output.lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// Recent work in a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentActivity {
+  /// Subjects of the latest commits, newest first
+  pub subjects: Vec<String>,
+  /// Files changed in the working tree, then files touched by the latest
+  /// commits, newest first and without duplicates
+  pub files: Vec<String>,
+}
+
+/// Subjects and files of the last `commits` commits, plus uncommitted changes.
+pub async fn recent_activity(root: &Path, commits: usize) -> Result<RecentActivity, String> {
+  let count = format!("-{commits}");
+  let log = git(root, &["log", &count, "--format=%x1e%s", "--name-only", "--relative"]).await?;
+
+  let mut changed: Vec<String> = changed_since_commit(root, "HEAD")
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+  changed.sort();
+
+  let mut activity = parse_log(&log);
+  for file in changed.into_iter().rev() {
+    if !activity.files.contains(&file) {
+      activity.files.insert(0, file);
+    }
+  }
+  Ok(activity)
+}
+
+/// Parse `git log --format=%x1e%s --name-only` output.
+fn parse_log(output: &str) -> RecentActivity {
+  let mut activity = RecentActivity::default();
+  for record in output.split('\x1e').skip(1) {
+    let mut lines = record.lines();
+    let subject = lines.next().unwrap_or_default().trim();
+    if !subject.is_empty() {
+      activity.subjects.push(subject.to_string());
+    }
+    for file in lines.map(str::trim).filter(|l| !l.is_empty()) {
+      if !activity.files.iter().any(|f| f == file) {
+        activity.files.push(file.to_string());
+      }
+    }
+  }
+  activity
+}
+
+/// Most recent commit touching a range of lines, from `git blame`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+  /// Full commit hash; all zeros for uncommitted changes
+  pub commit: String,
+  pub author: Option<String>,
+  /// Committer timestamp (Unix seconds)
+  pub committed_at: i64,
+}
+
+/// Newest commit among the blamed lines of `relative`, optionally limited to
+/// the inclusive 1-based line range `lines`.
+pub async fn blame_latest(root: &Path, relative: &str, lines: Option<(u32, u32)>) -> Result<BlameCommit, String> {
+  if relative.starts_with('-') {
+    return Err(format!("invalid path: '{relative}'"));
+  }
+
+  let range = lines.map(|(start, end)| format!("{start},{end}"));
+  let mut args = vec!["blame", "--porcelain"];
+  if let Some(range) = &range {
+    args.extend(["-L", range]);
+  }
+  args.extend(["--", relative]);
+
+  let output = git(root, &args).await?;
+  parse_blame_latest(&output).ok_or_else(|| format!("no blame output for {relative}"))
+}
+
+/// Pick the commit with the newest committer time from porcelain blame output.
+fn parse_blame_latest(output: &str) -> Option<BlameCommit> {
+  let mut commits: Vec<BlameCommit> = Vec::new();
+  let mut current: Option<usize> = None;
+
+  for line in output.lines() {
+    if line.starts_with('\t') {
+      continue;
+    }
+
+    let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+    if key.len() == 40 && key.bytes().all(|b| b.is_ascii_hexdigit()) {
+      current = Some(match commits.iter().position(|c| c.commit == key) {
+        Some(index) => index,
+        None => {
+          commits.push(BlameCommit {
+            commit: key.to_string(),
+            author: None,
+            committed_at: 0,
+          });
+          commits.len() - 1
+        }
+      });
+      continue;
+    }
+
+    let Some(commit) = current.and_then(|i| commits.get_mut(i)) else {
+      continue;
+    };
+    match key {
+      "author" => commit.author = Some(value.to_string()),
+      "committer-time" => commit.committed_at = value.parse().unwrap_or(0),
+      _ => {}
+    }
+  }
+
+  commits.into_iter().reduce(|newest, c| {
+    if c.committed_at > newest.committed_at {
+      c
+    } else {
+      newest
+    }
+  })
+}
+
+/// Reject revisions that git would parse as options.
+fn check_rev(rev: &str) -> Result<(), String> {
+  if rev.is_empty() || rev.starts_with('-') {
+    return Err(format!("invalid revision: '{rev}'"));
+  }
+  Ok(())
+}
+
+async fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+  let output = Command::new("git")
+    .arg("-C")
+    .arg(root)
+    .args(args)
+    .output()
+    .await
+    .map_err(|e| format!("failed to run git: {e}"))?;
+
+  if !output.status.success() {
+    return Err(format!(
+      "git {} failed: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+  #[test]
+  fn test_remote_urls_of_one_repo_match() {
+    let expected = "github.com/acme/app";
+    for url in [
+      "git@github.com:acme/app.git",
+      "https://github.com/acme/app",
+      "https://user@GitHub.com/acme/app.git/",
+      "ssh://git@github.com:22/acme/app.git",
+    ] {
+      assert_eq!(normalize_remote_url(url), expected, "{url}");
+    }
+
+    let config = "[core]\n\tbare = false\n[remote \"upstream\"]\n\turl = git@x.org:a/b\n[remote \"origin\"]\n\turl = git@github.com:acme/app.git\n";
+    assert_eq!(origin_url(config), Some("git@github.com:acme/app.git"));
+  }
+
+  #[test]
+  fn test_parse_log_keeps_files_in_order() {
+    let output =
+      "\x1eFix retry budget\n\nsrc/http/client.rs\nsrc/http/mod.rs\n\x1eAdd retries\n\nsrc/http/client.rs\nREADME.md\n";
+
+    assert_eq!(
+      parse_log(output),
+      RecentActivity {
+        subjects: vec!["Fix retry budget".to_string(), "Add retries".to_string()],
+        files: vec![
+          "src/http/client.rs".to_string(),
+          "src/http/mod.rs".to_string(),
+          "README.md".to_string(),
+        ],
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn test_read_branch_from_loose_and_packed_refs() {
+    let temp = TempDir::new().unwrap();
+    let git = temp.path().join(".git");
+    tokio::fs::create_dir_all(git.join("refs/heads")).await.unwrap();
+    tokio::fs::write(git.join("HEAD"), "ref: refs/heads/feature\n")
+      .await
+      .unwrap();
+    tokio::fs::write(
+      git.join("packed-refs"),
+      format!("# pack-refs\n{COMMIT} refs/heads/feature\n"),
+    )
+    .await
+    .unwrap();
+
+    let head = GitHead::read(temp.path()).await.unwrap();
+    assert_eq!(head.branch.as_deref(), Some("feature"), "branch from HEAD ref");
+    assert_eq!(head.commit.as_deref(), Some(COMMIT), "commit from packed-refs");
+
+    let loose = "fedcba9876543210fedcba9876543210fedcba98";
+    tokio::fs::write(git.join("refs/heads/feature"), format!("{loose}\n"))
+      .await
+      .unwrap();
+    let head = GitHead::read(temp.path()).await.unwrap();
+    assert_eq!(head.commit.as_deref(), Some(loose), "loose ref wins over packed-refs");
+  }
+
+  #[tokio::test]
+  async fn test_read_detached_worktree_head() {
+    let temp = TempDir::new().unwrap();
+    let worktree_git = temp.path().join("main/.git/worktrees/wt");
+    tokio::fs::create_dir_all(&worktree_git).await.unwrap();
+    tokio::fs::write(worktree_git.join("HEAD"), format!("{COMMIT}\n"))
+      .await
+      .unwrap();
+
+    let checkout = temp.path().join("wt");
+    tokio::fs::create_dir_all(&checkout).await.unwrap();
+    tokio::fs::write(checkout.join(".git"), "gitdir: ../main/.git/worktrees/wt\n")
+      .await
+      .unwrap();
+
+    let head = GitHead::read(&checkout).await.unwrap();
+    assert_eq!(head.branch, None, "detached HEAD has no branch");
+    assert_eq!(head.commit.as_deref(), Some(COMMIT), "commit from detached HEAD");
+  }
+
+  #[tokio::test]
+  async fn test_changed_since_rejects_option_like_revisions() {
+    let temp = TempDir::new().unwrap();
+    for rev in ["", "--output=/tmp/x", "-p"] {
+      assert!(
+        changed_since_commit(temp.path(), rev).await.is_err(),
+        "'{rev}' should be rejected before running git"
+      );
+    }
+  }
+
+  #[tokio::test]
+  async fn test_changed_paths_are_relative_to_subdirectory_project() {
+    let temp = TempDir::new().unwrap();
+    let repo = temp.path();
+    let project = repo.join("app");
+    tokio::fs::create_dir_all(project.join("src")).await.unwrap();
+    tokio::fs::write(project.join("src/main.rs"), "fn main() {}\n")
+      .await
+      .unwrap();
+    tokio::fs::write(repo.join("README.md"), "readme\n").await.unwrap();
+    for args in [
+      &["init", "-q", "-b", "main"][..],
+      &["add", "."],
+      &[
+        "-c",
+        "user.name=t",
+        "-c",
+        "user.email=t@t",
+        "commit",
+        "-q",
+        "-m",
+        "init",
+      ],
+    ] {
+      git(repo, args).await.unwrap();
+    }
+    let first = git(repo, &["rev-parse", "HEAD"]).await.unwrap().trim().to_string();
+
+    tokio::fs::write(project.join("src/main.rs"), "fn main() { run() }\n")
+      .await
+      .unwrap();
+    tokio::fs::write(repo.join("README.md"), "changed\n").await.unwrap();
+    git(
+      repo,
+      &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qam", "edit"],
+    )
+    .await
+    .unwrap();
+    let second = git(repo, &["rev-parse", "HEAD"]).await.unwrap().trim().to_string();
+    tokio::fs::write(project.join("lib.rs"), "").await.unwrap();
+    git(repo, &["add", "app/lib.rs"]).await.unwrap();
+
+    let head = GitHead::read(&project).await.unwrap();
+    assert_eq!(
+      head.commit.as_deref(),
+      Some(second.as_str()),
+      "HEAD found from a subdirectory"
+    );
+
+    let committed = changed_between(&project, &first, &second).await.unwrap();
+    assert_eq!(
+      committed,
+      HashSet::from(["src/main.rs".to_string()]),
+      "project-relative, files outside the project left out"
+    );
+    let uncommitted = changed_since_commit(&project, &second).await.unwrap();
+    assert_eq!(
+      uncommitted,
+      HashSet::from(["lib.rs".to_string()]),
+      "staged file relative to the project"
+    );
+  }
+
+  #[test]
+  fn test_parse_blame_picks_newest_commit() {
+    let old = "1111111111111111111111111111111111111111";
+    let new = "2222222222222222222222222222222222222222";
+    let output = format!(
+      "{old} 1 1 1\nauthor Alice\ncommitter-time 100\nfilename a.rs\n\tfn a() {{}}\n\
+       {new} 2 2 1\nauthor Bob\ncommitter-time 200\nfilename a.rs\n\tfn b() {{}}\n\
+       {old} 3 3\n\tfn c() {{}}\n"
+    );
+
+    let latest = parse_blame_latest(&output).unwrap();
+    assert_eq!(latest.commit, new, "newest commit wins");
+    assert_eq!(latest.author.as_deref(), Some("Bob"), "author of newest commit");
+    assert_eq!(latest.committed_at, 200, "committer time parsed");
+    assert_eq!(parse_blame_latest(""), None, "empty blame has no commit");
+  }
+
+  #[tokio::test]
+  async fn test_read_outside_repository() {
+    let temp = TempDir::new().unwrap();
+    assert_eq!(GitHead::read(temp.path()).await, None, "no .git means no head");
+  }
+}
@@ -124,8 +124,44 @@ impl Tier {
   }
 }
 
-// Re-export MemoryType from llm crate
-pub use llm::MemoryType;
+/// Who a memory may be shared with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+  /// Personal, kept out of snapshots and the `CLAUDE.md` primer
+  Private,
+  /// Shared with everyone working on the project
+  #[default]
+  Team,
+}
+
+impl Visibility {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Visibility::Private => "private",
+      Visibility::Team => "team",
+    }
+  }
+
+  pub fn is_shared(&self) -> bool {
+    *self == Visibility::Team
+  }
+}
+
+impl std::str::FromStr for Visibility {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "private" => Ok(Visibility::Private),
+      "team" | "shared" => Ok(Visibility::Team),
+      _ => Err(format!("Unknown visibility: {}", s)),
+    }
+  }
+}
+
+// Re-export MemoryType and EntityType from llm crate
+pub use llm::{EntityType, MemoryType};
 
 impl Sector {
   /// Derive sector from memory type
@@ -138,10 +174,179 @@ impl Sector {
       MemoryType::Pattern => Sector::Procedural,
       MemoryType::TurnSummary => Sector::Reflective,
       MemoryType::TaskCompletion => Sector::Episodic,
+      MemoryType::Goal => Sector::Reflective,
+    }
+  }
+}
+
+/// Where a goal memory stands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+  #[default]
+  Open,
+  /// Waiting on something outside the agent's control
+  Blocked,
+  Done,
+}
+
+impl GoalStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      GoalStatus::Open => "open",
+      GoalStatus::Blocked => "blocked",
+      GoalStatus::Done => "done",
+    }
+  }
+}
+
+impl std::str::FromStr for GoalStatus {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "open" => Ok(GoalStatus::Open),
+      "blocked" => Ok(GoalStatus::Blocked),
+      "done" | "completed" => Ok(GoalStatus::Done),
+      _ => Err(format!("Unknown goal status: {} (expected open, blocked or done)", s)),
+    }
+  }
+}
+
+/// A change recorded in a memory's history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryEventAction {
+  Added,
+  /// Content, status or other fields changed
+  Updated,
+  Deleted,
+  HardDeleted,
+  Restored,
+  Reinforced,
+  Deemphasized,
+  /// Replaced by a newer memory
+  Superseded,
+  SalienceSet,
+  /// Marked helpful or irrelevant for a search
+  Feedback,
+}
+
+impl MemoryEventAction {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      MemoryEventAction::Added => "added",
+      MemoryEventAction::Updated => "updated",
+      MemoryEventAction::Deleted => "deleted",
+      MemoryEventAction::HardDeleted => "hard_deleted",
+      MemoryEventAction::Restored => "restored",
+      MemoryEventAction::Reinforced => "reinforced",
+      MemoryEventAction::Deemphasized => "deemphasized",
+      MemoryEventAction::Superseded => "superseded",
+      MemoryEventAction::SalienceSet => "salience_set",
+      MemoryEventAction::Feedback => "feedback",
+    }
+  }
+}
+
+impl std::str::FromStr for MemoryEventAction {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "added" => Ok(MemoryEventAction::Added),
+      "updated" => Ok(MemoryEventAction::Updated),
+      "deleted" => Ok(MemoryEventAction::Deleted),
+      "hard_deleted" => Ok(MemoryEventAction::HardDeleted),
+      "restored" => Ok(MemoryEventAction::Restored),
+      "reinforced" => Ok(MemoryEventAction::Reinforced),
+      "deemphasized" => Ok(MemoryEventAction::Deemphasized),
+      "superseded" => Ok(MemoryEventAction::Superseded),
+      "salience_set" => Ok(MemoryEventAction::SalienceSet),
+      "feedback" => Ok(MemoryEventAction::Feedback),
+      _ => Err(format!("Unknown memory event action: {}", s)),
+    }
+  }
+}
+
+/// Who made a change to a memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryActor {
+  /// Extraction or tracking in a Claude Code hook
+  Hook,
+  /// An MCP tool call
+  Mcp,
+  /// The `ccengram` command line or TUI
+  Cli,
+  /// The daemon itself, e.g. goal tracking
+  #[default]
+  System,
+}
+
+impl MemoryActor {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      MemoryActor::Hook => "hook",
+      MemoryActor::Mcp => "mcp",
+      MemoryActor::Cli => "cli",
+      MemoryActor::System => "system",
+    }
+  }
+}
+
+impl std::str::FromStr for MemoryActor {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "hook" => Ok(MemoryActor::Hook),
+      "mcp" => Ok(MemoryActor::Mcp),
+      "cli" => Ok(MemoryActor::Cli),
+      "system" => Ok(MemoryActor::System),
+      _ => Err(format!("Unknown memory actor: {}", s)),
     }
   }
 }
 
+/// Git blame anchor for a file referenced by a memory.
+///
+/// Records the newest commit touching the referenced lines when the memory was
+/// created, so later changes to that code can flag the memory as stale.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileAnchor {
+  /// Path relative to the project root
+  pub path: String,
+  /// First referenced line (1-based), `None` for the whole file
+  pub start_line: Option<u32>,
+  pub end_line: Option<u32>,
+  pub commit: String,
+  pub author: Option<String>,
+  /// Committer timestamp (Unix seconds)
+  pub committed_at: i64,
+}
+
+/// Kind of code a memory references
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeReferenceKind {
+  File,
+  Symbol,
+}
+
+/// A file or symbol a memory references that was in the code index when the
+/// memory was created. Stale validation checks these against the current index.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeReference {
+  pub kind: CodeReferenceKind,
+  /// Relative file path or symbol name
+  pub name: String,
+  /// Indexed content hash of a file at creation time
+  pub content_hash: Option<String>,
+}
+
 /// Core memory entity
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +359,8 @@ pub struct Memory {
   pub tier: Tier,
   #[serde(rename = "type")]
   pub memory_type: Option<MemoryType>,
+  #[serde(default)]
+  pub visibility: Visibility,
 
   // Importance and relevance
   pub importance: f32,   // User-assigned (0-1)
@@ -161,6 +368,12 @@ pub struct Memory {
   pub confidence: f32,   // Extraction confidence (0-1)
   pub access_count: u32, // How many times recalled
 
+  // Search feedback (thumbs up/down on returned results)
+  #[serde(default)]
+  pub helpful_count: u32,
+  #[serde(default)]
+  pub irrelevant_count: u32,
+
   // Content metadata
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub tags: Vec<String>,
@@ -170,11 +383,27 @@ pub struct Memory {
   pub files: Vec<String>,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub categories: Vec<String>,
+  /// Git blame anchors for `files`
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub file_anchors: Vec<FileAnchor>,
+  /// Indexed files and symbols referenced at creation time
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub code_refs: Vec<CodeReference>,
+
+  // Staleness (set by validation against the code index)
+  #[serde(default)]
+  pub stale: bool,
+  pub stale_reason: Option<String>,
 
   // Scope (for codebase memories)
   pub scope_path: Option<String>,
   pub scope_module: Option<String>,
 
+  // Goal tracking (for goal memories)
+  pub goal_status: Option<GoalStatus>,
+  /// Free-form due hint, e.g. a date or "before the 2.0 release"
+  pub due: Option<String>,
+
   // Decay scheduling (internal)
   pub decay_rate: Option<f32>,              // Cached decay rate
   pub next_decay_at: Option<DateTime<Utc>>, // Next scheduled decay time
@@ -222,16 +451,25 @@ impl Memory {
       sector,
       tier: Tier::Project,
       memory_type: None,
+      visibility: Visibility::Team,
       importance: 0.5,
       salience: 1.0,
       confidence: 0.5,
       access_count: 0,
+      helpful_count: 0,
+      irrelevant_count: 0,
       tags: Vec::new(),
       concepts: Vec::new(),
       files: Vec::new(),
       categories: Vec::new(),
+      file_anchors: Vec::new(),
+      code_refs: Vec::new(),
+      stale: false,
+      stale_reason: None,
       scope_path: None,
       scope_module: None,
+      goal_status: None,
+      due: None,
       decay_rate: None,
       next_decay_at: None,
       embedding_model_id: None,
@@ -312,6 +550,15 @@ impl Memory {
     self.updated_at = now;
   }
 
+  /// Quality from search feedback, from -1 (always irrelevant) to 1 (always
+  /// helpful). Smoothed so one vote moves it a third of the way and a memory
+  /// without feedback scores 0.
+  pub fn feedback_score(&self) -> f32 {
+    let helpful = self.helpful_count as f32;
+    let irrelevant = self.irrelevant_count as f32;
+    (helpful - irrelevant) / (helpful + irrelevant + 2.0)
+  }
+
   /// Calculate effective score for ranking (combines salience, importance, sector boost)
   pub fn effective_score(&self) -> f32 {
     self.salience * self.importance * self.sector.search_boost()
@@ -406,6 +653,35 @@ impl MemoryRelationship {
   }
 }
 
+/// A named entity mentioned by memories
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entity {
+  pub id: Uuid,
+  pub name: String,
+  pub entity_type: EntityType,
+  pub created_at: DateTime<Utc>,
+}
+
+impl Entity {
+  pub fn new(name: &str, entity_type: EntityType) -> Self {
+    Self {
+      id: Self::id_for(name, entity_type),
+      name: name.to_string(),
+      entity_type,
+      created_at: Utc::now(),
+    }
+  }
+
+  /// ID of the entity with this name and type.
+  ///
+  /// Derived from the type and lowercased name, so every mention of an entity
+  /// maps to the same row whatever its casing.
+  pub fn id_for(name: &str, entity_type: EntityType) -> Uuid {
+    let key = format!("{}:{}", entity_type.as_str(), name.to_lowercase());
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -443,6 +719,22 @@ mod tests {
     assert_eq!(memory.salience, 0.3);
   }
 
+  #[test]
+  fn test_memory_feedback_score() {
+    let mut memory = Memory::new(Uuid::new_v4(), "test".into(), Sector::Semantic);
+    assert_eq!(memory.feedback_score(), 0.0, "no feedback is neutral");
+
+    memory.helpful_count = 1;
+    assert!((memory.feedback_score() - 1.0 / 3.0).abs() < 1e-6);
+
+    memory.irrelevant_count = 1;
+    assert_eq!(memory.feedback_score(), 0.0, "mixed feedback cancels out");
+
+    memory.irrelevant_count = 8;
+    assert!(memory.feedback_score() < -0.6);
+    assert!(memory.feedback_score() > -1.0, "never reaches the bound");
+  }
+
   #[test]
   fn test_memory_decay() {
     let mut memory = Memory::new(Uuid::new_v4(), "test".into(), Sector::Episodic);
@@ -4,8 +4,11 @@
 //! These types represent the core business logic and are independent of persistence
 //! or IPC concerns.
 
+pub mod alias;
 pub mod code;
 pub mod config;
 pub mod document;
+pub mod git;
 pub mod memory;
+pub mod path;
 pub mod project;
@@ -0,0 +1,411 @@
+//! Platform-independent path handling.
+//!
+//! The index stores file paths relative to the project root with forward
+//! slashes, so databases, filters and scope comparisons behave the same on
+//! Windows and Unix. Everything that turns a filesystem path into a stored or
+//! compared string goes through here.
+//!
+//! Case-insensitive filesystems (the macOS and Windows defaults) are probed
+//! per project so renames that only change case map back to one path.
+//!
+//! [`PathFilter`] holds the `include` / `exclude` globs from `[index]`, which
+//! the scanner, startup scan and watcher apply on top of gitignore rules.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use ignore::overrides::{Override, OverrideBuilder};
+use tracing::warn;
+
+/// Normalize a path string to forward slashes.
+///
+/// Strips the Windows verbatim prefix (`\\?\`), lowercases drive letters so
+/// `C:\repo` and `c:/repo` compare equal, and drops leading `./` and trailing
+/// separators.
+pub fn normalize(path: &str) -> String {
+  let path = match path.strip_prefix(r"\\?\UNC\") {
+    Some(unc) => format!("//{unc}"),
+    None => path.strip_prefix(r"\\?\").unwrap_or(path).to_string(),
+  };
+
+  let mut normalized = path.replace('\\', "/");
+  if has_drive_letter(&normalized) {
+    normalized[..1].make_ascii_lowercase();
+  }
+
+  while let Some(rest) = normalized.strip_prefix("./") {
+    normalized = rest.to_string();
+  }
+
+  let min_len = if has_drive_letter(&normalized) { 3 } else { 1 };
+  while normalized.len() > min_len && normalized.ends_with('/') {
+    normalized.pop();
+  }
+
+  normalized
+}
+
+/// Path of `path` relative to `root` in normalized form.
+///
+/// Returns `None` if `path` is not under `root`. Drive-letter and UNC paths
+/// are compared case-insensitively, as Windows filesystems are.
+pub fn relative_path(path: &Path, root: &Path) -> Option<String> {
+  if let Ok(rel) = path.strip_prefix(root) {
+    return Some(normalize(&rel.to_string_lossy()));
+  }
+
+  let path = normalize(&path.to_string_lossy());
+  let root = normalize(&root.to_string_lossy());
+
+  let rest = if is_windows_path(&root) {
+    let prefix = path.get(..root.len())?;
+    prefix.eq_ignore_ascii_case(&root).then(|| &path[root.len()..])?
+  } else {
+    path.strip_prefix(root.as_str())?
+  };
+
+  match rest {
+    "" => Some(String::new()),
+    _ if root.ends_with('/') => Some(rest.to_string()),
+    _ => rest.strip_prefix('/').map(String::from),
+  }
+}
+
+/// Stable key for a project root, used for project identity.
+///
+/// Windows paths are case-folded so the same checkout always hashes the same.
+pub fn project_key(path: &Path) -> String {
+  let normalized = normalize(&path.to_string_lossy());
+  if is_windows_path(&normalized) {
+    normalized.to_ascii_lowercase()
+  } else {
+    normalized
+  }
+}
+
+/// Whether the filesystem holding `root` resolves paths case-insensitively.
+///
+/// Probes by looking up `root` with the ASCII case of its last component
+/// flipped, falling back to the platform default when it has no letters.
+pub async fn is_case_insensitive(root: &Path) -> bool {
+  let platform_default = cfg!(any(target_os = "macos", target_os = "windows"));
+  let Some(name) = root.file_name().and_then(|n| n.to_str()) else {
+    return platform_default;
+  };
+
+  let flipped: String = name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_uppercase() {
+        c.to_ascii_lowercase()
+      } else {
+        c.to_ascii_uppercase()
+      }
+    })
+    .collect();
+  if flipped == name {
+    return platform_default;
+  }
+
+  tokio::fs::metadata(root).await.is_ok() && tokio::fs::metadata(root.with_file_name(flipped)).await.is_ok()
+}
+
+/// On-disk casing of a normalized relative path under `root`.
+///
+/// Each component is matched exactly first, then case-insensitively, so on a
+/// case-insensitive filesystem `foo.rs` resolves to `Foo.rs` if that is how
+/// the file is named. Returns `None` if a component does not exist.
+pub async fn canonical_case(root: &Path, relative: &str) -> Option<String> {
+  CaseCache::default().resolve(root, relative).await
+}
+
+/// [`canonical_case`] for many paths at once, listing each directory only once.
+///
+/// Listings are never refreshed, so a cache should live for one batch of
+/// paths rather than across filesystem changes.
+#[derive(Debug, Default)]
+pub struct CaseCache {
+  dirs: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl CaseCache {
+  /// On-disk casing of `relative` under `root`, see [`canonical_case`].
+  pub async fn resolve(&mut self, root: &Path, relative: &str) -> Option<String> {
+    let mut dir = root.to_path_buf();
+    let mut parts = Vec::new();
+
+    for part in relative.split('/').filter(|p| !p.is_empty()) {
+      let names = self.list(&dir).await?;
+      let folded = part.to_lowercase();
+      let name = names
+        .iter()
+        .find(|name| *name == part)
+        .or_else(|| names.iter().find(|name| name.to_lowercase() == folded))?
+        .clone();
+      dir.push(&name);
+      parts.push(name);
+    }
+
+    Some(parts.join("/"))
+  }
+
+  async fn list(&mut self, dir: &Path) -> Option<&Vec<String>> {
+    if !self.dirs.contains_key(dir) {
+      let names = read_names(dir).await;
+      self.dirs.insert(dir.to_path_buf(), names);
+    }
+    self.dirs.get(dir)?.as_ref()
+  }
+}
+
+async fn read_names(dir: &Path) -> Option<Vec<String>> {
+  let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+  let mut names = Vec::new();
+  while let Some(entry) = entries.next_entry().await.ok()? {
+    names.push(entry.file_name().to_string_lossy().into_owned());
+  }
+  Some(names)
+}
+
+/// Lookup key for a relative path, case-folded when the filesystem ignores case.
+pub fn case_key(relative: &str, case_insensitive: bool) -> String {
+  if case_insensitive {
+    relative.to_lowercase()
+  } else {
+    relative.to_string()
+  }
+}
+
+fn has_drive_letter(path: &str) -> bool {
+  let bytes = path.as_bytes();
+  bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+fn is_windows_path(normalized: &str) -> bool {
+  has_drive_letter(normalized) || normalized.starts_with("//")
+}
+
+/// Include and exclude globs for indexing, relative to the project root.
+///
+/// Globs use gitignore syntax. With any `include` globs, only files matching
+/// one are indexed; a path matching an `exclude` glob, or under a directory
+/// that does, is never indexed.
+#[derive(Debug, Clone)]
+pub struct PathFilter(Override);
+
+impl PathFilter {
+  /// Build a filter for `root`, skipping globs that fail to parse
+  pub fn new(root: &Path, include: &[String], exclude: &[String]) -> Self {
+    if include.is_empty() && exclude.is_empty() {
+      return Self::default();
+    }
+
+    let mut builder = OverrideBuilder::new(root);
+    let globs = include
+      .iter()
+      .cloned()
+      .chain(exclude.iter().map(|glob| format!("!{}", glob)));
+    for glob in globs {
+      if let Err(e) = builder.add(&glob) {
+        warn!(glob = %glob, error = %e, "Skipping invalid index glob");
+      }
+    }
+
+    match builder.build() {
+      Ok(overrides) => Self(overrides),
+      Err(e) => {
+        warn!(error = %e, "Failed to build index globs, indexing all files");
+        Self::default()
+      }
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// The globs as walker overrides
+  pub fn overrides(&self) -> &Override {
+    &self.0
+  }
+
+  /// Whether `relative` (a normalized path under the root) is left out
+  pub fn is_excluded(&self, relative: &str, is_dir: bool) -> bool {
+    if self.is_empty() {
+      return false;
+    }
+    let path = Path::new(relative);
+    self.0.matched(path, is_dir).is_ignore()
+      || path
+        .ancestors()
+        .skip(1)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .any(|dir| self.0.matched(dir, true).is_ignore())
+  }
+}
+
+impl Default for PathFilter {
+  fn default() -> Self {
+    Self(Override::empty())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalize_windows_paths() {
+    assert_eq!(
+      normalize(r"src\lib\mod.rs"),
+      "src/lib/mod.rs",
+      "backslashes become slashes"
+    );
+    assert_eq!(
+      normalize(r"C:\Users\dev\repo\"),
+      "c:/Users/dev/repo",
+      "drive lowercased, trailing dropped"
+    );
+    assert_eq!(normalize(r"\\?\C:\repo"), "c:/repo", "verbatim prefix stripped");
+    assert_eq!(
+      normalize(r"\\?\UNC\server\share\repo"),
+      "//server/share/repo",
+      "verbatim UNC"
+    );
+    assert_eq!(normalize(r"C:\"), "c:/", "drive root keeps its separator");
+    assert_eq!(normalize("./src/main.rs"), "src/main.rs", "leading ./ dropped");
+    assert_eq!(normalize("/home/dev/repo"), "/home/dev/repo", "unix paths unchanged");
+  }
+
+  #[test]
+  fn test_relative_path_across_platforms() {
+    let cases = [
+      (
+        r"C:\Users\dev\repo\src\main.rs",
+        r"C:\Users\dev\repo",
+        Some("src/main.rs"),
+      ),
+      (
+        r"\\?\C:\Users\dev\repo\src\main.rs",
+        r"c:\users\dev\repo",
+        Some("src/main.rs"),
+      ),
+      (r"D:\other\main.rs", r"C:\Users\dev\repo", None),
+      (r"C:\Users\dev\repository\main.rs", r"C:\Users\dev\repo", None),
+      ("/home/dev/repo/src/main.rs", "/home/dev/repo", Some("src/main.rs")),
+      ("/home/dev/repo", "/home/dev/repo", Some("")),
+      ("/home/dev/other/main.rs", "/home/dev/repo", None),
+    ];
+
+    for (path, root, expected) in cases {
+      assert_eq!(
+        relative_path(Path::new(path), Path::new(root)).as_deref(),
+        expected,
+        "relative_path({path}, {root})"
+      );
+    }
+  }
+
+  #[tokio::test]
+  async fn test_canonical_case_resolves_on_disk_names() {
+    let temp = tempfile::TempDir::new().unwrap();
+    tokio::fs::create_dir_all(temp.path().join("Src/Lib")).await.unwrap();
+    tokio::fs::write(temp.path().join("Src/Lib/Foo.rs"), "").await.unwrap();
+
+    assert_eq!(
+      canonical_case(temp.path(), "src/lib/foo.rs").await.as_deref(),
+      Some("Src/Lib/Foo.rs"),
+      "each component should take its on-disk casing"
+    );
+    assert_eq!(
+      canonical_case(temp.path(), "Src/Lib/Foo.rs").await.as_deref(),
+      Some("Src/Lib/Foo.rs"),
+      "exact casing is kept"
+    );
+    assert_eq!(
+      canonical_case(temp.path(), "src/missing.rs").await,
+      None,
+      "missing files have no canonical casing"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_case_cache_lists_each_directory_once() {
+    let temp = tempfile::TempDir::new().unwrap();
+    tokio::fs::create_dir_all(temp.path().join("Src")).await.unwrap();
+    tokio::fs::write(temp.path().join("Src/Foo.rs"), "").await.unwrap();
+
+    let mut cache = CaseCache::default();
+    assert_eq!(
+      cache.resolve(temp.path(), "src/foo.rs").await.as_deref(),
+      Some("Src/Foo.rs")
+    );
+
+    // A file created after the listing is not seen until the next batch
+    tokio::fs::write(temp.path().join("Src/Bar.rs"), "").await.unwrap();
+    assert_eq!(
+      cache.resolve(temp.path(), "src/bar.rs").await,
+      None,
+      "listing is reused"
+    );
+    assert_eq!(
+      CaseCache::default().resolve(temp.path(), "src/bar.rs").await.as_deref(),
+      Some("Src/Bar.rs")
+    );
+  }
+
+  #[tokio::test]
+  async fn test_is_case_insensitive_matches_filesystem() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let root = temp.path().join("Probe");
+    tokio::fs::create_dir_all(&root).await.unwrap();
+
+    let flipped_exists = tokio::fs::metadata(temp.path().join("pROBE")).await.is_ok();
+    assert_eq!(
+      is_case_insensitive(&root).await,
+      flipped_exists,
+      "probe should agree with a direct lookup"
+    );
+    assert_eq!(case_key("Src/Foo.rs", true), "src/foo.rs", "folded when insensitive");
+    assert_eq!(case_key("Src/Foo.rs", false), "Src/Foo.rs", "kept when sensitive");
+  }
+
+  #[test]
+  fn test_project_key_is_case_insensitive_on_windows() {
+    assert_eq!(
+      project_key(Path::new(r"C:\Users\Dev\Repo")),
+      project_key(Path::new(r"\\?\c:\users\dev\repo\")),
+      "same windows checkout should produce the same key"
+    );
+    assert_ne!(
+      project_key(Path::new("/home/Dev/Repo")),
+      project_key(Path::new("/home/dev/repo")),
+      "unix paths stay case-sensitive"
+    );
+  }
+
+  #[test]
+  fn test_path_filter_include_and_exclude() {
+    let root = Path::new("/repo");
+    let filter = PathFilter::new(
+      root,
+      &["src/**".to_string(), "*.md".to_string()],
+      &["src/generated/".to_string(), "*.min.js".to_string()],
+    );
+
+    assert!(!filter.is_excluded("src/main.rs", false));
+    assert!(!filter.is_excluded("README.md", false));
+    assert!(filter.is_excluded("scripts/build.rs", false), "not included");
+    assert!(
+      filter.is_excluded("src/generated/api.rs", false),
+      "under an excluded directory"
+    );
+    assert!(filter.is_excluded("src/vendor/app.min.js", false), "excluded by name");
+
+    let empty = PathFilter::new(root, &[], &[]);
+    assert!(empty.is_empty());
+    assert!(!empty.is_excluded("anything/at/all.rs", false));
+  }
+}
@@ -121,14 +121,24 @@ impl ProjectId {
     };
 
     let mut hasher = DefaultHasher::new();
-    path.to_string_lossy().hash(&mut hasher);
+    super::path::project_key(path).hash(&mut hasher);
     format!("{:016x}", hasher.finish())
   }
 
+  /// The ProjectId a project's data directory is named after
+  pub fn from_dir_name(name: &str) -> Self {
+    ProjectId(name.to_string())
+  }
+
   pub fn as_str(&self) -> &str {
     &self.0
   }
 
+  /// Deterministic UUID stored as `project_id` on memories, sessions and documents
+  pub fn uuid(&self) -> uuid::Uuid {
+    uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, self.0.as_bytes())
+  }
+
   pub fn data_dir(&self, base: &Path) -> PathBuf {
     base.join("projects").join(&self.0)
   }
@@ -146,6 +156,12 @@ pub struct ProjectMetadata {
   pub path: PathBuf,
   pub name: String,
   pub created_at: chrono::DateTime<chrono::Utc>,
+  /// Repository identity shared by all clones (see [`super::git::repo_fingerprint`])
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub fingerprint: Option<String>,
+  /// Project whose store this one was merged into; requests for it go there
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub merged_into: Option<ProjectId>,
 }
 
 #[cfg(test)]
@@ -176,6 +192,17 @@ mod tests {
     let _ = fs::remove_dir_all(&temp).await;
   }
 
+  #[test]
+  fn test_project_id_windows_spellings_match() {
+    // Nonexistent paths skip canonicalization, so this runs on any platform
+    let plain = ProjectId::from_path_exact(Path::new(r"C:\Users\Dev\Repo"));
+    let verbatim = ProjectId::from_path_exact(Path::new(r"\\?\c:\users\dev\repo\"));
+    let slashes = ProjectId::from_path_exact(Path::new("c:/Users/Dev/Repo"));
+
+    assert_eq!(plain, verbatim, "verbatim prefix and case should not change identity");
+    assert_eq!(plain, slashes, "separator style should not change identity");
+  }
+
   #[tokio::test]
   async fn test_project_id_exact_differs() {
     let temp = std::env::temp_dir().join(format!("test_exact_{}", std::process::id()));
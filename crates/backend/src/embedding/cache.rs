@@ -0,0 +1,228 @@
+// Persistent document embedding cache
+//
+// Each project's provider is wrapped with the daemon's shared cache database,
+// so every project reuses vectors for text that has been embedded before, including across daemon restarts. Query
+// embeddings are not persisted; they have their own short-lived cache in the
+// project actor. Cache failures are logged and fall through to the provider.
+
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use tracing::{debug, warn};
+
+use super::{EmbeddingError, EmbeddingMode, EmbeddingProvider};
+use crate::db::{DbError, EmbeddingCacheDb};
+
+/// Directory name of the cache database under the data directory
+pub const EMBEDDING_CACHE_DIR: &str = "embedding_cache";
+
+/// Embedding provider that persists document embeddings by content hash
+pub struct PersistentCacheProvider {
+  inner: Arc<dyn EmbeddingProvider>,
+  cache: Arc<EmbeddingCacheDb>,
+}
+
+impl PersistentCacheProvider {
+  /// Open the cache database under `data_dir`, to share between providers
+  pub async fn open_cache(data_dir: &Path, vector_dim: usize) -> Result<Arc<EmbeddingCacheDb>, DbError> {
+    let cache = EmbeddingCacheDb::open(&data_dir.join(EMBEDDING_CACHE_DIR), vector_dim).await?;
+    Ok(Arc::new(cache))
+  }
+
+  /// Wrap `inner` with an already open cache
+  pub fn with_cache(inner: Arc<dyn EmbeddingProvider>, cache: Arc<EmbeddingCacheDb>) -> Self {
+    Self { inner, cache }
+  }
+
+  fn key(&self, text: &str) -> String {
+    EmbeddingCacheDb::key(self.inner.model_id(), text)
+  }
+}
+
+#[async_trait]
+impl EmbeddingProvider for PersistentCacheProvider {
+  fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  fn model_id(&self) -> &str {
+    self.inner.model_id()
+  }
+
+  fn dimensions(&self) -> usize {
+    self.inner.dimensions()
+  }
+
+  async fn embed(&self, text: &str, mode: EmbeddingMode) -> Result<Vec<f32>, EmbeddingError> {
+    if mode == EmbeddingMode::Query {
+      return self.inner.embed(text, mode).await;
+    }
+
+    let mut vectors = self.embed_batch(&[text], mode).await?;
+    vectors
+      .pop()
+      .ok_or(EmbeddingError::BatchSizeMismatch { expected: 1, got: 0 })
+  }
+
+  async fn embed_batch(&self, texts: &[&str], mode: EmbeddingMode) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    if mode == EmbeddingMode::Query || texts.is_empty() {
+      return self.inner.embed_batch(texts, mode).await;
+    }
+
+    let keys: Vec<String> = texts.iter().map(|t| self.key(t)).collect();
+    let mut cached = match self.cache.get_many(&keys).await {
+      Ok(found) => found,
+      Err(e) => {
+        warn!(err = %e, "Embedding cache lookup failed, embedding without cache");
+        Default::default()
+      }
+    };
+
+    // Embed each distinct missing text once
+    let mut seen = HashSet::new();
+    let mut miss_keys: Vec<&String> = Vec::new();
+    let mut miss_texts: Vec<&str> = Vec::new();
+    for (key, text) in keys.iter().zip(texts) {
+      if !cached.contains_key(key) && seen.insert(key) {
+        miss_keys.push(key);
+        miss_texts.push(text);
+      }
+    }
+
+    debug!(
+      total = texts.len(),
+      hits = texts.len() - miss_texts.len(),
+      misses = miss_texts.len(),
+      "Embedding cache lookup"
+    );
+
+    if !miss_texts.is_empty() {
+      let embedded = self.inner.embed_batch(&miss_texts, mode).await?;
+      if embedded.len() != miss_texts.len() {
+        return Err(EmbeddingError::BatchSizeMismatch {
+          expected: miss_texts.len(),
+          got: embedded.len(),
+        });
+      }
+
+      let entries: Vec<(String, Vec<f32>)> = miss_keys.into_iter().cloned().zip(embedded).collect();
+      if let Err(e) = self.cache.put_many(self.inner.model_id(), &entries).await {
+        warn!(err = %e, "Failed to store embeddings in cache");
+      }
+      cached.extend(entries);
+    }
+
+    keys
+      .iter()
+      .map(|key| {
+        cached.get(key).cloned().ok_or(EmbeddingError::BatchSizeMismatch {
+          expected: texts.len(),
+          got: cached.len(),
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use tempfile::TempDir;
+
+  use super::*;
+
+  /// Counts texts sent to the provider and embeds each as its length
+  struct CountingProvider {
+    embedded: AtomicUsize,
+  }
+
+  #[async_trait]
+  impl EmbeddingProvider for CountingProvider {
+    fn name(&self) -> &str {
+      "counting"
+    }
+    fn model_id(&self) -> &str {
+      "counting-model"
+    }
+    fn dimensions(&self) -> usize {
+      2
+    }
+
+    async fn embed(&self, text: &str, _mode: EmbeddingMode) -> Result<Vec<f32>, EmbeddingError> {
+      self.embedded.fetch_add(1, Ordering::SeqCst);
+      Ok(vec![text.len() as f32, 1.0])
+    }
+
+    async fn embed_batch(&self, texts: &[&str], _mode: EmbeddingMode) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+      self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+      Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+    }
+  }
+
+  async fn cached_provider(data_dir: &Path) -> (Arc<CountingProvider>, PersistentCacheProvider) {
+    let inner = Arc::new(CountingProvider {
+      embedded: AtomicUsize::new(0),
+    });
+    let cache = PersistentCacheProvider::open_cache(data_dir, inner.dimensions())
+      .await
+      .unwrap();
+    let provider = PersistentCacheProvider::with_cache(inner.clone(), cache);
+    (inner, provider)
+  }
+
+  #[tokio::test]
+  async fn test_documents_reuse_cache_across_restart() {
+    let temp = TempDir::new().unwrap();
+
+    {
+      let (inner, provider) = cached_provider(temp.path()).await;
+      let vectors = provider
+        .embed_batch(&["a", "bb", "a"], EmbeddingMode::Document)
+        .await
+        .unwrap();
+      assert_eq!(
+        vectors,
+        vec![vec![1.0, 1.0], vec![2.0, 1.0], vec![1.0, 1.0]],
+        "vectors in input order"
+      );
+      assert_eq!(inner.embedded.load(Ordering::SeqCst), 2, "duplicate text embedded once");
+    }
+
+    // A new provider over the same data dir simulates a daemon restart
+    let (inner, provider) = cached_provider(temp.path()).await;
+    let vectors = provider
+      .embed_batch(&["bb", "ccc"], EmbeddingMode::Document)
+      .await
+      .unwrap();
+    assert_eq!(
+      vectors,
+      vec![vec![2.0, 1.0], vec![3.0, 1.0]],
+      "cached and fresh vectors merged"
+    );
+    assert_eq!(
+      inner.embedded.load(Ordering::SeqCst),
+      1,
+      "only the new text should be embedded"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_queries_bypass_cache() {
+    let temp = TempDir::new().unwrap();
+    let (inner, provider) = cached_provider(temp.path()).await;
+
+    provider.embed("query", EmbeddingMode::Query).await.unwrap();
+    provider.embed("query", EmbeddingMode::Query).await.unwrap();
+    assert_eq!(
+      inner.embedded.load(Ordering::SeqCst),
+      2,
+      "queries should always hit the provider"
+    );
+    assert_eq!(
+      provider.cache.count().await.unwrap(),
+      0,
+      "queries should not be persisted"
+    );
+  }
+}
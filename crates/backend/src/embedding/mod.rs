@@ -1,3 +1,4 @@
+mod cache;
 mod ollama;
 mod openai_compat;
 mod rate_limit;
@@ -9,6 +10,7 @@ pub mod llamacpp;
 
 use std::sync::Arc;
 
+pub use cache::PersistentCacheProvider;
 pub use ollama::OllamaProvider;
 pub use openai_compat::OpenAiCompatibleProvider;
 use resilient::{ResilientProvider, RetryConfig};
@@ -0,0 +1,203 @@
+//! Hook batch buffer.
+//!
+//! Claude Code runs a hook process per event, so a session with hundreds of
+//! tool uses would open a daemon connection per PostToolUse. Instead each
+//! PostToolUse process appends its event to a per-session buffer file. The
+//! process that finds the buffer empty becomes the flusher: it waits out a
+//! short window, drains everything buffered by then and sends it as one
+//! `HookBatch` request. Every other process exits as soon as its event is
+//! written.
+//!
+//! Appends and drains hold an exclusive `flock` on the buffer, so an event is
+//! either drained by the current flusher or lands in an empty buffer and
+//! starts the next window.
+
+use std::{
+  fs::File,
+  io::{Read, Write},
+  os::unix::fs::MetadataExt,
+  path::{Path, PathBuf},
+};
+
+use fs4::fs_std::FileExt;
+use tracing::warn;
+
+use super::{IpcError, hook::HookParams};
+
+/// Directory of the per-session buffers inside the data directory
+pub const BATCH_DIR_NAME: &str = "hook_batches";
+
+/// Path of the buffer for `session_id` inside `dir`
+pub fn buffer_path(dir: &Path, session_id: &str) -> PathBuf {
+  let name: String = session_id
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  dir.join(format!("{}.jsonl", name))
+}
+
+/// Run `f` on `file` while holding an exclusive lock on it.
+///
+/// Taking the lock blocks until other hook processes release it, so this runs
+/// on the blocking pool. The lock is released when the file is closed.
+async fn with_lock<T: Send + 'static>(
+  file: tokio::fs::File,
+  f: impl FnOnce(&mut File) -> Result<T, IpcError> + Send + 'static,
+) -> Result<T, IpcError> {
+  let mut file = file.into_std().await;
+  tokio::task::spawn_blocking(move || {
+    file.lock_exclusive()?;
+    f(&mut file)
+  })
+  .await
+  .map_err(|e| IpcError::Io(format!("Hook batch task failed: {}", e)))?
+}
+
+/// Append a hook event to the buffer at `path`.
+///
+/// Returns `true` when the buffer was empty, meaning the caller is the one
+/// that must flush it.
+pub async fn append(path: &Path, params: &HookParams) -> Result<bool, IpcError> {
+  if let Some(parent) = path.parent() {
+    tokio::fs::create_dir_all(parent).await?;
+  }
+
+  let mut line = serde_json::to_string(params)?;
+  line.push('\n');
+
+  loop {
+    let file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)
+      .await?;
+    let line = line.clone();
+    let appended = with_lock(file, move |file| {
+      let metadata = file.metadata()?;
+      // Removed by a session end drain after we opened it; start a new buffer
+      if metadata.nlink() == 0 {
+        return Ok(None);
+      }
+      file.write_all(line.as_bytes())?;
+      file.flush()?;
+      Ok(Some(metadata.len() == 0))
+    })
+    .await?;
+
+    if let Some(was_empty) = appended {
+      return Ok(was_empty);
+    }
+  }
+}
+
+/// Take every event out of the buffer at `path`, in the order they were appended.
+///
+/// With `remove`, the buffer file is deleted as well (used once the session
+/// ends). It is removed while still locked, and appends that were waiting on
+/// the lock see it is gone and start a new buffer.
+pub async fn drain(path: &Path, remove: bool) -> Result<Vec<HookParams>, IpcError> {
+  let file = match tokio::fs::OpenOptions::new().read(true).write(true).open(path).await {
+    Ok(file) => file,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e.into()),
+  };
+
+  let path = path.to_path_buf();
+  let content = with_lock(file, move |file| {
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+    file.set_len(0)?;
+    if remove && let Err(e) = std::fs::remove_file(&path) {
+      // Another drain already removed it
+      if e.kind() != std::io::ErrorKind::NotFound {
+        return Err(e.into());
+      }
+    }
+    Ok(content)
+  })
+  .await?;
+
+  Ok(parse(&content))
+}
+
+fn parse(content: &str) -> Vec<HookParams> {
+  content
+    .lines()
+    .filter(|l| !l.trim().is_empty())
+    .filter_map(|line| match serde_json::from_str::<HookParams>(line) {
+      Ok(params) => Some(params),
+      Err(e) => {
+        warn!("Skipping invalid hook batch entry: {}", e);
+        None
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn params(tool_use_id: &str) -> HookParams {
+    HookParams {
+      hook_name: "PostToolUse".to_string(),
+      session_id: Some("s1".to_string()),
+      cwd: Some("/tmp/project".to_string()),
+      data: serde_json::json!({ "tool_name": "Read", "tool_use_id": tool_use_id }),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_first_append_flushes_and_drain_empties() {
+    let dir = TempDir::new().unwrap();
+    let path = buffer_path(dir.path(), "s1");
+
+    assert!(
+      append(&path, &params("toolu_1")).await.unwrap(),
+      "first event starts a window"
+    );
+    assert!(!append(&path, &params("toolu_2")).await.unwrap());
+
+    let drained = drain(&path, false).await.unwrap();
+    let ids: Vec<_> = drained
+      .iter()
+      .map(|p| p.data["tool_use_id"].as_str().unwrap())
+      .collect();
+    assert_eq!(ids, vec!["toolu_1", "toolu_2"]);
+
+    assert!(
+      append(&path, &params("toolu_3")).await.unwrap(),
+      "an event after a drain starts the next window"
+    );
+    assert_eq!(drain(&path, true).await.unwrap().len(), 1);
+    assert!(!path.exists());
+    assert!(drain(&path, false).await.unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_append_after_session_end_starts_new_buffer() {
+    let dir = TempDir::new().unwrap();
+    let path = buffer_path(dir.path(), "s1");
+    append(&path, &params("toolu_1")).await.unwrap();
+
+    // An append that opened the buffer before SessionEnd removed it
+    let stale = tokio::fs::OpenOptions::new().append(true).open(&path).await.unwrap();
+    drain(&path, true).await.unwrap();
+    let linked = with_lock(stale, |file| Ok(file.metadata()?.nlink() > 0)).await.unwrap();
+    assert!(!linked, "the removed buffer is detected under the lock");
+
+    assert!(
+      append(&path, &params("toolu_2")).await.unwrap(),
+      "the event lands in a new buffer"
+    );
+    assert_eq!(drain(&path, false).await.unwrap().len(), 1);
+  }
+}
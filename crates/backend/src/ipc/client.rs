@@ -9,12 +9,14 @@ use std::{
 
 use futures::{SinkExt, StreamExt};
 use tokio::{net::UnixStream, sync::mpsc};
-use tokio_util::codec::{Framed, LinesCodec};
+use tokio_util::codec::Framed;
 use tracing::{debug, error, warn};
 
-use super::{IpcError, Request, RequestData, Response, ResponseData, ResponseScenario};
-
-type FramedStream = Framed<UnixStream, LinesCodec>;
+use super::{
+  IpcError, Request, RequestData, Response, ResponseData, ResponseScenario,
+  framing::{self, Framing, MessageCodec, MessageStream},
+};
+use crate::domain::memory::MemoryActor;
 
 /// Progress info for a pipeline stage.
 #[derive(Debug, Clone, Default)]
@@ -68,6 +70,7 @@ pub struct Client {
   cwd: PathBuf,
   request_tx: mpsc::Sender<OutboundRequest>,
   counter: Arc<AtomicU64>,
+  actor: Option<MemoryActor>,
 }
 
 impl Client {
@@ -75,24 +78,42 @@ impl Client {
     Self::connect_to(cwd, &crate::dirs::default_socket_path()).await
   }
 
+  /// Connect to the daemon at `socket_path`, asking for this process's
+  /// preferred framing (see [`framing::prefer`])
   pub async fn connect_to(cwd: PathBuf, socket_path: &Path) -> Result<Self, IpcError> {
+    Self::connect_with(cwd, socket_path, framing::preferred()).await
+  }
+
+  /// Connect to the daemon at `socket_path`, asking for `framing`.
+  ///
+  /// Falls back to JSON lines if the daemon doesn't support it.
+  pub async fn connect_with(cwd: PathBuf, socket_path: &Path, framing: Framing) -> Result<Self, IpcError> {
     let stream = UnixStream::connect(socket_path).await?;
-    let framed = Framed::new(stream, LinesCodec::new());
+    let mut framed = Framed::new(stream, MessageCodec::new());
+    let framing = framing::negotiate(&mut framed, framing).await?;
     let (sink, read_stream) = framed.split();
 
     let (request_tx, request_rx) = mpsc::channel(64);
-    tokio::spawn(Self::multiplexer(sink, read_stream, request_rx));
+    tokio::spawn(Self::multiplexer(framing, sink, read_stream, request_rx));
 
     Ok(Self {
       cwd,
       request_tx,
       counter: Arc::new(AtomicU64::new(1)),
+      actor: None,
     })
   }
 
+  /// Identify requests as coming from `actor` (the daemon assumes the CLI)
+  pub fn with_actor(mut self, actor: MemoryActor) -> Self {
+    self.actor = Some(actor);
+    self
+  }
+
   async fn multiplexer(
-    mut sink: futures::stream::SplitSink<FramedStream, String>,
-    mut stream: futures::stream::SplitStream<FramedStream>,
+    framing: Framing,
+    mut sink: futures::stream::SplitSink<MessageStream, Vec<u8>>,
+    mut stream: futures::stream::SplitStream<MessageStream>,
     mut request_rx: mpsc::Receiver<OutboundRequest>,
   ) {
     let mut pending: HashMap<String, mpsc::Sender<Response>> = HashMap::new();
@@ -101,10 +122,10 @@ impl Client {
       tokio::select! {
         Some(outbound) = request_rx.recv() => {
           let id = outbound.request.id.clone();
-          match serde_json::to_string(&outbound.request) {
-            Ok(json) => {
+          match framing.encode(&outbound.request) {
+            Ok(frame) => {
               pending.insert(id.clone(), outbound.response_tx);
-              if let Err(e) = sink.send(json).await {
+              if let Err(e) = sink.send(frame).await {
                 error!("failed to send request: {e}");
                 if let Some(tx) = pending.remove(&id) {
                   let _ = tx.send(Response::error(id, IpcError::Connection(e.to_string()))).await;
@@ -112,15 +133,15 @@ impl Client {
               }
             }
             Err(e) => {
-              let _ = outbound.response_tx.send(Response::error(id, IpcError::Serde(e.to_string()))).await;
+              let _ = outbound.response_tx.send(Response::error(id, e)).await;
             }
           }
         }
 
         result = stream.next() => {
           match result {
-            Some(Ok(line)) => {
-              match serde_json::from_str::<Response>(&line) {
+            Some(Ok(frame)) => {
+              match framing.decode::<Response>(&frame) {
                 Ok(response) => {
                   let id = response.id.clone();
                   let is_final = match &response.scenario {
@@ -265,6 +286,7 @@ impl Client {
     let request = Request {
       id: id.to_string(),
       cwd: self.cwd.to_string_lossy().to_string(),
+      actor: self.actor,
       data: data.into(),
     };
 
@@ -321,6 +343,7 @@ impl Client {
     let request = Request {
       id: "fire-and-forget".to_string(),
       cwd: cwd.to_string_lossy().to_string(),
+      actor: None,
       data: req.into(),
     };
 
@@ -0,0 +1,374 @@
+//! Wire framing for IPC messages.
+//!
+//! Messages are JSON lines by default, which is what MCP, hooks and anything
+//! else that speaks to the socket expect. A client can ask for binary framing
+//! instead: its first line is `{"framing":"cbor"}`, and once the daemon echoes
+//! it back both sides switch to length-prefixed CBOR frames. Large explore and
+//! context results then skip JSON escaping and number formatting. A daemon
+//! that doesn't know the handshake answers with a parse error, and the client
+//! stays on JSON lines.
+//!
+//! The handshake can also offer `"compression":"zstd"`. When the daemon echoes
+//! that too, every binary frame starts with a flag byte and frames of at least
+//! [`COMPRESS_THRESHOLD`] bytes are zstd-compressed, so `memory_list` and
+//! `code_list` exports and full-context results on big files shrink on the
+//! wire. A daemon without compression drops the unknown field from its echo
+//! and frames stay uncompressed. JSON lines are never compressed.
+
+use std::{io::Read, sync::OnceLock};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::net::UnixStream;
+use tokio_util::{
+  bytes::{BufMut, Bytes, BytesMut},
+  codec::{Decoder, Encoder, Framed, LengthDelimitedCodec, LinesCodec},
+};
+
+use super::IpcError;
+
+/// Largest binary frame accepted (the codec's default is 8MB)
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+
+/// Smallest message compressed when compression is negotiated
+pub const COMPRESS_THRESHOLD: usize = 64 * 1024;
+
+/// zstd level used for compressed frames
+const ZSTD_LEVEL: i32 = 3;
+
+/// Flag byte of an uncompressed frame
+const FRAME_PLAIN: u8 = 0;
+
+/// Flag byte of a zstd-compressed frame
+const FRAME_ZSTD: u8 = 1;
+
+/// How messages are encoded on the socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Framing {
+  /// One JSON document per line
+  #[default]
+  Json,
+  /// Length-prefixed CBOR frames
+  Cbor,
+}
+
+impl Framing {
+  /// Serialize a message for this framing
+  pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, IpcError> {
+    match self {
+      Framing::Json => Ok(serde_json::to_vec(value)?),
+      Framing::Cbor => {
+        let mut buf = Vec::new();
+        ciborium::into_writer(value, &mut buf).map_err(|e| IpcError::Serde(e.to_string()))?;
+        Ok(buf)
+      }
+    }
+  }
+
+  /// Deserialize a message received in this framing
+  pub fn decode<T: DeserializeOwned>(self, frame: &[u8]) -> Result<T, IpcError> {
+    match self {
+      Framing::Json => Ok(serde_json::from_slice(frame)?),
+      Framing::Cbor => ciborium::from_reader(frame).map_err(|e| IpcError::Serde(e.to_string())),
+    }
+  }
+}
+
+/// Compression applied to large binary frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+  Zstd,
+}
+
+impl Compression {
+  fn compress(self, data: &[u8]) -> Result<Vec<u8>, IpcError> {
+    match self {
+      Compression::Zstd => Ok(zstd::bulk::compress(data, ZSTD_LEVEL)?),
+    }
+  }
+
+  /// Decompress a frame, refusing one that expands past `limit` bytes
+  fn decompress(self, data: &[u8], limit: usize) -> Result<Vec<u8>, IpcError> {
+    let mut out = Vec::new();
+    match self {
+      Compression::Zstd => {
+        zstd::stream::Decoder::new(data)?
+          .take(limit as u64 + 1)
+          .read_to_end(&mut out)?;
+      }
+    }
+    if out.len() > limit {
+      return Err(IpcError::Codec(format!("decompressed frame exceeds {} bytes", limit)));
+    }
+    Ok(out)
+  }
+}
+
+static PREFERRED: OnceLock<Framing> = OnceLock::new();
+
+/// Ask for `framing` on every connection this process opens.
+///
+/// Only the first call takes effect. Processes that never call it use JSON.
+pub fn prefer(framing: Framing) {
+  let _ = PREFERRED.set(framing);
+}
+
+/// Framing this process asks for when connecting
+pub fn preferred() -> Framing {
+  PREFERRED.get().copied().unwrap_or_default()
+}
+
+/// First line of a connection that wants a framing other than JSON
+#[derive(Debug, Serialize, Deserialize)]
+struct Hello {
+  framing: Framing,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  compression: Option<Compression>,
+}
+
+/// Codec for either framing, switchable once the handshake is done
+#[derive(Debug)]
+pub struct MessageCodec {
+  framing: Framing,
+  compression: Option<Compression>,
+  lines: LinesCodec,
+  frames: LengthDelimitedCodec,
+}
+
+impl MessageCodec {
+  pub fn new() -> Self {
+    Self {
+      framing: Framing::Json,
+      compression: None,
+      lines: LinesCodec::new(),
+      frames: LengthDelimitedCodec::builder()
+        .max_frame_length(MAX_FRAME_LEN)
+        .new_codec(),
+    }
+  }
+
+  pub fn framing(&self) -> Framing {
+    self.framing
+  }
+
+  pub fn compression(&self) -> Option<Compression> {
+    self.compression
+  }
+
+  /// Strip the flag byte of a binary frame, decompressing it if flagged
+  fn unwrap_frame(&self, mut frame: BytesMut) -> Result<BytesMut, IpcError> {
+    let Some(compression) = self.compression else {
+      return Ok(frame);
+    };
+    if frame.is_empty() {
+      return Err(IpcError::Codec("frame is missing its flag byte".into()));
+    }
+    let body = frame.split_off(1);
+    match frame[0] {
+      FRAME_PLAIN => Ok(body),
+      FRAME_ZSTD => Ok(BytesMut::from(compression.decompress(&body, MAX_FRAME_LEN)?.as_slice())),
+      flag => Err(IpcError::Codec(format!("unknown frame flag {}", flag))),
+    }
+  }
+
+  /// Prefix a binary frame with its flag byte, compressing it if large enough
+  fn wrap_frame(&self, item: Vec<u8>) -> Result<Bytes, IpcError> {
+    let Some(compression) = self.compression else {
+      return Ok(Bytes::from(item));
+    };
+    let (flag, body) = if item.len() >= COMPRESS_THRESHOLD {
+      (FRAME_ZSTD, compression.compress(&item)?)
+    } else {
+      (FRAME_PLAIN, item)
+    };
+    let mut frame = BytesMut::with_capacity(body.len() + 1);
+    frame.put_u8(flag);
+    frame.put_slice(&body);
+    Ok(frame.freeze())
+  }
+}
+
+impl Default for MessageCodec {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Decoder for MessageCodec {
+  type Item = BytesMut;
+  type Error = IpcError;
+
+  fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, IpcError> {
+    match self.framing {
+      Framing::Json => Ok(self.lines.decode(src)?.map(|line| BytesMut::from(line.as_bytes()))),
+      Framing::Cbor => self
+        .frames
+        .decode(src)?
+        .map(|frame| self.unwrap_frame(frame))
+        .transpose(),
+    }
+  }
+
+  fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, IpcError> {
+    match self.framing {
+      Framing::Json => Ok(self.lines.decode_eof(src)?.map(|line| BytesMut::from(line.as_bytes()))),
+      Framing::Cbor => self
+        .frames
+        .decode_eof(src)?
+        .map(|frame| self.unwrap_frame(frame))
+        .transpose(),
+    }
+  }
+}
+
+impl Encoder<Vec<u8>> for MessageCodec {
+  type Error = IpcError;
+
+  fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), IpcError> {
+    match self.framing {
+      Framing::Json => {
+        dst.reserve(item.len() + 1);
+        dst.put_slice(&item);
+        dst.put_u8(b'\n');
+        Ok(())
+      }
+      Framing::Cbor => {
+        let frame = self.wrap_frame(item)?;
+        Ok(self.frames.encode(frame, dst)?)
+      }
+    }
+  }
+}
+
+/// A socket carrying IPC messages
+pub type MessageStream = Framed<UnixStream, MessageCodec>;
+
+/// Client side of the handshake: ask for `wanted` and return the framing in use.
+///
+/// Binary framing always offers zstd compression as well.
+pub async fn negotiate(framed: &mut MessageStream, wanted: Framing) -> Result<Framing, IpcError> {
+  if wanted == Framing::Json {
+    return Ok(Framing::Json);
+  }
+
+  let hello = Hello {
+    framing: wanted,
+    compression: Some(Compression::Zstd),
+  };
+  framed.send(Framing::Json.encode(&hello)?).await?;
+  let reply = framed
+    .next()
+    .await
+    .ok_or_else(|| IpcError::Connection("connection closed during handshake".into()))??;
+
+  match Framing::Json.decode::<Hello>(&reply) {
+    Ok(hello) if hello.framing == wanted => {
+      let codec = framed.codec_mut();
+      codec.framing = wanted;
+      codec.compression = hello.compression;
+      Ok(wanted)
+    }
+    _ => Ok(Framing::Json),
+  }
+}
+
+/// Daemon side of the handshake.
+///
+/// Reads the first frame: a handshake is answered and the framing switched,
+/// anything else is returned as the connection's first JSON message. Returns
+/// `None` after a handshake or when the client hung up without sending one.
+pub async fn accept(framed: &mut MessageStream) -> Result<Option<BytesMut>, IpcError> {
+  let Some(first) = framed.next().await.transpose()? else {
+    return Ok(None);
+  };
+  let Ok(mut hello) = Framing::Json.decode::<Hello>(&first) else {
+    return Ok(Some(first));
+  };
+  if hello.framing == Framing::Json {
+    hello.compression = None;
+  }
+
+  framed.send(Framing::Json.encode(&hello)?).await?;
+  let codec = framed.codec_mut();
+  codec.framing = hello.framing;
+  codec.compression = hello.compression;
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ipc::{Response, ResponseData, ResponseScenario, system::SystemResponse};
+
+  #[test]
+  fn test_cbor_frame_round_trip() {
+    let response = Response::success("7", ResponseData::System(SystemResponse::Ping("pong".to_string())));
+    let mut codec = MessageCodec::new();
+    codec.framing = Framing::Cbor;
+
+    let mut buf = BytesMut::new();
+    codec
+      .encode(Framing::Cbor.encode(&response).unwrap(), &mut buf)
+      .unwrap();
+    let frame = codec.decode(&mut buf).unwrap().expect("a whole frame");
+    assert!(buf.is_empty());
+
+    let decoded: Response = Framing::Cbor.decode(&frame).unwrap();
+    assert_eq!(decoded.id, "7");
+    assert!(matches!(
+      decoded.scenario,
+      ResponseScenario::Result {
+        data: ResponseData::System(SystemResponse::Ping(ref s))
+      } if s == "pong"
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_handshake_switches_to_cbor() {
+    let (client, server) = UnixStream::pair().unwrap();
+    let mut client = Framed::new(client, MessageCodec::new());
+    let mut server = Framed::new(server, MessageCodec::new());
+
+    let daemon = tokio::spawn(async move {
+      assert!(accept(&mut server).await.unwrap().is_none());
+      server
+    });
+    assert_eq!(negotiate(&mut client, Framing::Cbor).await.unwrap(), Framing::Cbor);
+    let server = daemon.await.unwrap();
+    assert_eq!(server.codec().framing(), Framing::Cbor);
+    assert_eq!(server.codec().compression(), Some(Compression::Zstd));
+    assert_eq!(client.codec().compression(), Some(Compression::Zstd));
+  }
+
+  #[test]
+  fn test_large_frames_are_compressed() {
+    let mut codec = MessageCodec::new();
+    codec.framing = Framing::Cbor;
+    codec.compression = Some(Compression::Zstd);
+
+    let large = vec![b'a'; COMPRESS_THRESHOLD * 2];
+    let mut buf = BytesMut::new();
+    codec.encode(large.clone(), &mut buf).unwrap();
+    assert!(buf.len() < large.len() / 10, "repetitive frame should shrink");
+    assert_eq!(codec.decode(&mut buf).unwrap().unwrap().as_ref(), large.as_slice());
+
+    let small = b"small".to_vec();
+    codec.encode(small.clone(), &mut buf).unwrap();
+    assert_eq!(buf.len(), 4 + 1 + small.len(), "length prefix, flag byte, body");
+    assert_eq!(codec.decode(&mut buf).unwrap().unwrap().as_ref(), small.as_slice());
+  }
+
+  #[test]
+  fn test_decompression_is_bounded() {
+    let data = vec![b'a'; 4096];
+    let compressed = Compression::Zstd.compress(&data).unwrap();
+
+    assert_eq!(Compression::Zstd.decompress(&compressed, 4096).unwrap(), data);
+    assert!(
+      matches!(Compression::Zstd.decompress(&compressed, 4095), Err(IpcError::Codec(_))),
+      "a frame expanding past the limit is refused"
+    );
+  }
+}
@@ -1,9 +1,14 @@
 //! IPC module - client/server communication and type definitions
 use serde::{Deserialize, Serialize};
 
+pub use crate::domain::memory::MemoryActor;
+
 pub mod types;
 
+pub mod batch;
 pub mod client;
+pub mod framing;
+pub mod spool;
 
 pub use client::{Client, IpcRequest, StreamUpdate, collect_stream};
 pub use types::*;
@@ -53,6 +58,8 @@ impl From<tokio_util::codec::LinesCodecError> for IpcError {
 pub struct Request {
   pub id: String,
   pub cwd: String, // path of the project making the request
+  /// Who is asking, recorded in the history of memories the request changes
+  pub actor: Option<MemoryActor>,
   #[serde(flatten)]
   pub data: RequestData,
 }
@@ -67,11 +74,16 @@ pub enum RequestData {
   Watch(watch::WatchRequest),
   Docs(docs::DocsRequest),
   Relationship(relationship::RelationshipRequest),
+  Entity(entity::EntityRequest),
   Project(project::ProjectRequest),
   Hook(hook::HookParams),
+  HookBatch(hook::HookBatchParams),
   // Unified Search
   Explore(search::ExploreParams),
   Context(search::ContextParams),
+  Pack(search::PackParams),
+  ExplorePlan(search::ExplorePlanParams),
+  Exploration(exploration::ExplorationRequest),
 }
 
 // ============================================================================
@@ -265,9 +277,13 @@ pub enum ResponseData {
   Watch(watch::WatchResponse),
   Docs(docs::DocsResponse),
   Relationship(relationship::RelationshipResponse),
+  Entity(entity::EntityResponse),
   Project(project::ProjectResponse),
   Hook(hook::HookResult),
+  HookBatch(hook::HookBatchResult),
   // Unified Search
   Explore(search::ExploreResult),
   Context(Vec<search::ContextItem>),
+  Pack(search::PackResult),
+  Exploration(exploration::ExplorationResponse),
 }
@@ -0,0 +1,283 @@
+//! Offline hook spool.
+//!
+//! Hook events captured while the daemon is unreachable are appended to a JSONL
+//! file in the data directory. On startup the daemon drains the spool and replays
+//! each event through the normal hook pipeline.
+
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+use super::{IpcError, hook::HookParams};
+
+/// File name of the spool inside the data directory
+pub const SPOOL_FILE_NAME: &str = "hook_spool.jsonl";
+
+/// Upper bound on the spool size; events are dropped once it is reached
+const MAX_SPOOL_BYTES: u64 = 16 * 1024 * 1024;
+
+/// A hook event captured while the daemon was down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpooledHook {
+  pub captured_at: DateTime<Utc>,
+  pub params: HookParams,
+}
+
+impl SpooledHook {
+  pub fn new(params: HookParams) -> Self {
+    Self {
+      captured_at: Utc::now(),
+      params,
+    }
+  }
+
+  /// Key used to drop duplicate captures of the same turn.
+  ///
+  /// Uses the tool use ID when Claude Code provides one, otherwise a hash of the
+  /// event payload, scoped to the session and hook name.
+  pub fn dedup_key(&self) -> String {
+    let turn_id = self
+      .params
+      .data
+      .get("tool_use_id")
+      .and_then(|v| v.as_str())
+      .map(String::from)
+      .unwrap_or_else(|| {
+        let mut hasher = Sha256::new();
+        hasher.update(self.params.data.to_string().as_bytes());
+        hex::encode(&hasher.finalize()[..16])
+      });
+
+    format!(
+      "{}:{}:{}",
+      self.params.session_id.as_deref().unwrap_or("-"),
+      self.params.hook_name,
+      turn_id
+    )
+  }
+}
+
+/// Append a hook event to the spool at `path`.
+#[tracing::instrument(level = "trace", skip(params))]
+pub async fn append(path: &Path, params: HookParams) -> Result<(), IpcError> {
+  if let Some(parent) = path.parent() {
+    tokio::fs::create_dir_all(parent).await?;
+  }
+
+  if let Ok(meta) = tokio::fs::metadata(path).await
+    && meta.len() >= MAX_SPOOL_BYTES
+  {
+    warn!(size = meta.len(), "Hook spool is full, dropping event");
+    return Ok(());
+  }
+
+  let mut line = serde_json::to_string(&SpooledHook::new(params))?;
+  line.push('\n');
+
+  let mut file = tokio::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .await?;
+  file.write_all(line.as_bytes()).await?;
+  file.flush().await?;
+
+  Ok(())
+}
+
+/// Events taken out of the spool, kept on disk until they have been replayed.
+#[derive(Debug)]
+pub struct Drained {
+  pub entries: Vec<SpooledHook>,
+  file: PathBuf,
+}
+
+impl Drained {
+  /// Delete the drained events once they have been replayed or re-spooled.
+  pub async fn finish(self) -> Result<(), IpcError> {
+    match tokio::fs::remove_file(&self.file).await {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// Take every spooled event out of the spool at `path`.
+///
+/// The spool is moved aside before reading so events appended during replay
+/// land in a fresh file. Events left over from a drain that never finished
+/// are kept and returned first. Unparseable lines are skipped and duplicates
+/// are removed, keeping the first capture in order.
+#[tracing::instrument(level = "trace")]
+pub async fn drain(path: &Path) -> Result<Drained, IpcError> {
+  let draining = path.with_extension("jsonl.draining");
+  let incoming = path.with_extension("jsonl.incoming");
+
+  if tokio::fs::try_exists(&draining).await? {
+    // A previous drain was interrupted; add new events after its leftovers
+    merge_into(&incoming, &draining).await?;
+    match tokio::fs::rename(path, &incoming).await {
+      Ok(()) => merge_into(&incoming, &draining).await?,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+      Err(e) => return Err(e.into()),
+    }
+  } else {
+    match tokio::fs::rename(path, &draining).await {
+      Ok(()) => {}
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+        return Ok(Drained {
+          entries: Vec::new(),
+          file: draining,
+        });
+      }
+      Err(e) => return Err(e.into()),
+    }
+  }
+
+  let content = tokio::fs::read_to_string(&draining).await?;
+  let entries = parse(&content);
+
+  debug!(count = entries.len(), "Drained hook spool");
+  Ok(Drained {
+    entries,
+    file: draining,
+  })
+}
+
+/// Append the contents of `from` to `to` and delete `from`, if it exists.
+async fn merge_into(from: &Path, to: &Path) -> Result<(), IpcError> {
+  let content = match tokio::fs::read(from).await {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+    Err(e) => return Err(e.into()),
+  };
+
+  let mut file = tokio::fs::OpenOptions::new().append(true).open(to).await?;
+  file.write_all(&content).await?;
+  file.flush().await?;
+  tokio::fs::remove_file(from).await?;
+  Ok(())
+}
+
+fn parse(content: &str) -> Vec<SpooledHook> {
+  let mut seen = HashSet::new();
+  let mut entries = Vec::new();
+
+  for line in content.lines().filter(|l| !l.trim().is_empty()) {
+    match serde_json::from_str::<SpooledHook>(line) {
+      Ok(entry) => {
+        if seen.insert(entry.dedup_key()) {
+          entries.push(entry);
+        }
+      }
+      Err(e) => warn!("Skipping invalid hook spool entry: {}", e),
+    }
+  }
+
+  entries
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn params(session: &str, hook: &str, data: serde_json::Value) -> HookParams {
+    HookParams {
+      hook_name: hook.to_string(),
+      session_id: Some(session.to_string()),
+      cwd: Some("/tmp/project".to_string()),
+      data,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_drain_dedups_and_clears_spool() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(SPOOL_FILE_NAME);
+
+    let tool = serde_json::json!({ "tool_name": "Read", "tool_use_id": "toolu_1" });
+    let prompt = serde_json::json!({ "prompt": "fix the bug" });
+
+    append(&path, params("s1", "PostToolUse", tool.clone())).await.unwrap();
+    append(&path, params("s1", "PostToolUse", tool.clone())).await.unwrap();
+    append(&path, params("s1", "UserPromptSubmit", prompt.clone()))
+      .await
+      .unwrap();
+    append(&path, params("s2", "UserPromptSubmit", prompt)).await.unwrap();
+
+    let drained = drain(&path).await.unwrap();
+    let hooks: Vec<_> = drained.entries.iter().map(|e| e.params.hook_name.as_str()).collect();
+    assert_eq!(
+      hooks,
+      vec!["PostToolUse", "UserPromptSubmit", "UserPromptSubmit"],
+      "duplicate turn within a session should be dropped, order preserved"
+    );
+
+    drained.finish().await.unwrap();
+
+    assert!(
+      drain(&path).await.unwrap().entries.is_empty(),
+      "spool should be empty after drain"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_unfinished_drain_is_kept_until_finished() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(SPOOL_FILE_NAME);
+
+    append(&path, params("s1", "Stop", serde_json::json!({ "n": 1 })))
+      .await
+      .unwrap();
+    let interrupted = drain(&path).await.unwrap();
+    assert_eq!(interrupted.entries.len(), 1, "first drain takes the event");
+
+    // The daemon died before replay finished, so the events come back
+    let retried = drain(&path).await.unwrap();
+    assert_eq!(retried.entries.len(), 1, "unfinished drain is returned again");
+    retried.finish().await.unwrap();
+
+    assert!(drain(&path).await.unwrap().entries.is_empty(), "finished drain is gone");
+  }
+
+  #[tokio::test]
+  async fn test_leftover_drain_keeps_new_events() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join(SPOOL_FILE_NAME);
+
+    append(&path, params("s1", "Stop", serde_json::json!({ "n": 1 })))
+      .await
+      .unwrap();
+    drain(&path).await.unwrap();
+
+    // Captured while the daemon was down again, after the interrupted drain
+    append(&path, params("s1", "Stop", serde_json::json!({ "n": 2 })))
+      .await
+      .unwrap();
+
+    let drained = drain(&path).await.unwrap();
+    let numbers: Vec<_> = drained
+      .entries
+      .iter()
+      .map(|e| e.params.data["n"].as_i64().unwrap())
+      .collect();
+    assert_eq!(numbers, vec![1, 2], "leftover events first, then the new ones");
+  }
+
+  #[test]
+  fn test_parse_skips_invalid_lines() {
+    let valid = serde_json::to_string(&SpooledHook::new(params("s1", "Stop", serde_json::json!({})))).unwrap();
+    let content = format!("not json\n{}\n\n", valid);
+
+    assert_eq!(parse(&content).len(), 1, "only the valid entry should be parsed");
+  }
+}
@@ -1,5 +1,5 @@
 //! Code IPC types - requests, responses, and conversions
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -17,13 +17,18 @@ pub enum CodeRequest {
   Search(CodeSearchParams),
   Context(CodeContextParams),
   Index(CodeIndexParams),
+  IndexChanged(CodeIndexChangedParams),
   List(CodeListParams),
   Stats(CodeStatsParams),
+  Skipped(CodeSkippedParams),
+  Plan(CodeIndexPlanParams),
   Memories(CodeMemoriesParams),
   Callers(CodeCallersParams),
   Callees(CodeCalleesParams),
   Related(CodeRelatedParams),
   ContextFull(CodeContextFullParams),
+  Definition(CodeDefinitionParams),
+  References(CodeReferencesParams),
 }
 
 #[serde_with::skip_serializing_none]
@@ -52,6 +57,22 @@ pub struct CodeSearchParams {
   /// Minimum caller count filter. Only returns code that is called
   /// by at least this many other code chunks (indicates importance/centrality).
   pub min_caller_count: Option<u32>,
+
+  // === Git scoping ===
+  /// Only return code that matches the current checkout. Chunks indexed on
+  /// another branch or commit are dropped if their file has changed since.
+  pub current_branch: Option<bool>,
+
+  /// Only return code from files that differ between this branch and HEAD.
+  pub diff_branch: Option<String>,
+
+  /// Infer the language filter from languages or frameworks the query names
+  /// (e.g. "in the python worker") when `language` is not given. Default: true.
+  pub infer_language: Option<bool>,
+
+  /// Rank generated code alongside hand-written code instead of below it.
+  /// Default: false.
+  pub include_generated: Option<bool>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -69,6 +90,15 @@ pub struct CodeIndexParams {
   pub force: bool,
   #[serde(default)]
   pub stream: bool,
+  /// Only index files changed since this git revision instead of scanning
+  pub since: Option<String>,
+}
+
+/// Reindex files git reports as changed, used by git hooks
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeIndexChangedParams {
+  /// Commit to diff the working tree against
+  pub since: String,
 }
 
 #[serde_with::skip_serializing_none]
@@ -80,11 +110,27 @@ pub struct CodeListParams {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CodeStatsParams;
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeSkippedParams;
+
+/// Estimate an index run without embedding or writing anything
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeIndexPlanParams {
+  /// Only plan files changed since this git revision
+  pub since: Option<String>,
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeCallersParams {
-  pub chunk_id: String,
+  /// Chunk defining the function (takes precedence over `symbol`)
+  pub chunk_id: Option<String>,
+  /// Function/method name to find callers of
+  pub symbol: Option<String>,
   pub limit: Option<usize>,
+  /// Levels of callers to follow (1 = direct callers only)
+  pub depth: Option<usize>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -92,6 +138,28 @@ pub struct CodeCallersParams {
 pub struct CodeCalleesParams {
   pub chunk_id: String,
   pub limit: Option<usize>,
+  /// Levels of callees to follow (1 = direct callees only)
+  pub depth: Option<usize>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeDefinitionParams {
+  /// Symbol to resolve, optionally qualified (e.g. `Config::load`)
+  pub symbol: String,
+  /// File the symbol is referenced from, used to follow its imports
+  pub file_path: Option<String>,
+  pub limit: Option<usize>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeReferencesParams {
+  /// Symbol to find references to, optionally qualified (e.g. `Config::load`)
+  pub symbol: String,
+  /// File the symbol is referenced from, used to pick its definition
+  pub file_path: Option<String>,
+  pub limit: Option<usize>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -127,14 +195,19 @@ pub enum CodeResponse {
   Search(CodeSearchResult),
   Context(CodeContextResponse),
   Index(CodeIndexResult),
+  IndexChanged(CodeIndexChangedResult),
   List(Vec<CodeItem>),
   ImportChunk(CodeImportChunkResult),
   Stats(CodeStatsResult),
+  Skipped(Vec<CodeSkippedItem>),
+  Plan(CodeIndexPlan),
   Memories(CodeMemoriesResponse),
   Callers(CodeCallersResponse),
   Callees(CodeCalleesResponse),
   Related(CodeRelatedResponse),
   ContextFull(CodeContextFullResponse),
+  Definition(CodeDefinitionResponse),
+  References(CodeReferencesResponse),
 }
 
 /// Unified code chunk item - consolidates CodeChunkItem, CodeChunkDetail, CodeListItem
@@ -173,6 +246,9 @@ pub struct CodeItem {
   /// Parent definition name for nested items (e.g., "UserRepo" for method in impl UserRepo)
   #[serde(skip_serializing_if = "Option::is_none")]
   pub parent_definition: Option<String>,
+  /// Set when the chunk comes from a file written by a code generator
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub generated: Option<bool>,
 
   // Search-specific
   #[serde(skip_serializing_if = "Option::is_none")]
@@ -199,6 +275,36 @@ pub struct CodeItem {
   pub caller_count: Option<u32>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub callee_count: Option<u32>,
+  /// Call graph distance from the queried chunk (1 = direct caller/callee)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub depth: Option<usize>,
+
+  /// Other places holding identical code, e.g. a copied helper or vendored file
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub also_in: Vec<CodeLocation>,
+
+  /// Hits from this file left out by `search.max_per_file`; set on the
+  /// file's last result shown
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub more_in_file: Option<usize>,
+}
+
+/// Where a chunk of code appears
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeLocation {
+  pub file_path: String,
+  pub start_line: u32,
+  pub end_line: u32,
+}
+
+impl From<&CodeChunk> for CodeLocation {
+  fn from(c: &CodeChunk) -> Self {
+    Self {
+      file_path: c.file_path.clone(),
+      start_line: c.start_line,
+      end_line: c.end_line,
+    }
+  }
 }
 
 /// Search quality information based on distance scores.
@@ -278,6 +384,9 @@ pub struct CodeSearchResult {
   /// refining the query for better results.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub search_quality: Option<SearchQuality>,
+  /// Language filter inferred from the query, when one was applied
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub inferred_language: Option<String>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -324,6 +433,73 @@ pub struct CodeIndexResult {
   pub files_per_second: f64,
   pub bytes_processed: u64,
   pub total_bytes: u64,
+  /// Files that were not valid UTF-8 and were decoded best-effort
+  #[serde(default)]
+  pub decode_warnings: Vec<CodeDecodeWarning>,
+}
+
+/// A file indexed from content that was not valid UTF-8
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CodeDecodeWarning {
+  pub file_path: String,
+  /// Encoding the file was decoded from
+  pub encoding: String,
+  /// Invalid byte sequences replaced with U+FFFD
+  pub replaced: usize,
+}
+
+/// A file the indexing guard rails left out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSkippedItem {
+  pub file_path: String,
+  /// too_large, binary, minified, repetitive or too_many_chunks
+  pub reason: String,
+  /// What was measured, e.g. "2400000 bytes > 1048576 bytes"
+  pub detail: String,
+  pub skipped_at: String,
+}
+
+/// What an index run would do, from `ccengram index code --plan`
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodeIndexPlan {
+  /// Files found by the scan, including skipped ones
+  pub files_scanned: usize,
+  /// Files with at least one chunk to embed
+  pub files_to_index: usize,
+  /// Files whose chunks are all indexed already
+  pub files_unchanged: usize,
+  /// Dependency files indexed shallowly: definitions only, nothing to embed
+  #[serde(default)]
+  pub files_shallow: usize,
+  /// Files that could not be read or chunked
+  pub failed_files: usize,
+  pub chunks: usize,
+  pub chunks_to_embed: usize,
+  /// Chunks whose embedding is reused from the index
+  pub chunks_reused: usize,
+  /// Tokens sent to the embedding provider, estimated from text length
+  pub estimated_tokens: u64,
+  /// Unset unless `embedding.cost_per_million_tokens` is configured
+  pub estimated_cost_usd: Option<f64>,
+  pub total_bytes: u64,
+  /// Chunks to embed per language
+  #[serde(default)]
+  pub languages: BTreeMap<String, usize>,
+  /// Files the guard rails would leave out
+  #[serde(default)]
+  pub skipped: Vec<CodeSkippedItem>,
+  pub duration_ms: u64,
+}
+
+/// Result of queueing changed files for reindexing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeIndexChangedResult {
+  pub since: String,
+  /// Changed files queued for indexing
+  pub files_queued: usize,
+  /// Removed files deleted from the index
+  pub files_deleted: usize,
 }
 
 /// Code index dry run response
@@ -364,9 +540,26 @@ pub struct CodeStatsResult {
   pub total_tokens_estimate: u64,
   pub total_lines: u64,
   pub average_chunks_per_file: f32,
-  pub language_breakdown: HashMap<String, usize>,
-  pub chunk_type_breakdown: HashMap<String, usize>,
+  pub language_breakdown: BTreeMap<String, usize>,
+  pub chunk_type_breakdown: BTreeMap<String, usize>,
   pub index_health_score: u32,
+  /// Embedding reuse since the project was loaded
+  pub reindex: Option<CodeReindexMetrics>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeReindexMetrics {
+  /// Files parsed for indexing
+  pub files: u64,
+  /// Files reparsed incrementally from their previous content
+  pub incremental_files: u64,
+  /// Chunks sent to the embedding provider
+  pub chunks_embedded: u64,
+  /// Chunks that kept their existing embedding
+  pub embeddings_reused: u64,
+  /// Chunks outside the changed lines of incremental edits
+  pub ast_unchanged_chunks: u64,
 }
 
 #[serde_with::skip_serializing_none]
@@ -382,6 +575,9 @@ pub struct CodeCallersResponse {
   pub symbol: String,
   pub callers: Vec<CodeItem>,
   pub count: usize,
+  /// Levels of callers followed
+  #[serde(default = "default_call_depth")]
+  pub depth: usize,
 }
 
 #[serde_with::skip_serializing_none]
@@ -391,6 +587,13 @@ pub struct CodeCalleesResponse {
   pub calls: Vec<String>,
   pub callees: Vec<CodeCalleeItem>,
   pub unresolved: Vec<String>,
+  /// Levels of callees followed
+  #[serde(default = "default_call_depth")]
+  pub depth: usize,
+}
+
+fn default_call_depth() -> usize {
+  1
 }
 
 #[serde_with::skip_serializing_none]
@@ -405,6 +608,77 @@ pub struct CodeCalleeItem {
   pub end_line: Option<u32>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub language: Option<String>,
+  /// Call graph distance from the queried chunk (1 = direct callee)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub depth: Option<usize>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeDefinitionResponse {
+  pub symbol: String,
+  /// Defining chunks, most likely first
+  pub definitions: Vec<CodeDefinitionItem>,
+  pub count: usize,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeDefinitionItem {
+  pub id: String,
+  pub file_path: String,
+  pub start_line: u32,
+  pub end_line: u32,
+  pub language: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub definition_kind: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub definition_name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub parent_definition: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub signature: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub docstring: Option<String>,
+  /// How the definition was matched: same_file, import, imported_module, same_directory or global
+  pub resolution: String,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeReferencesResponse {
+  pub symbol: String,
+  /// Definition the references were ranked against, if found
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub definition: Option<CodeDefinitionItem>,
+  /// Referencing chunks, nearest to the definition first
+  pub references: Vec<CodeReferenceItem>,
+  pub count: usize,
+  /// References found before the limit was applied
+  pub total: usize,
+  /// More chunks mention the symbol than were scanned, so `total` is a lower bound
+  #[serde(default)]
+  pub truncated: bool,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeReferenceItem {
+  pub id: String,
+  pub file_path: String,
+  pub start_line: u32,
+  pub end_line: u32,
+  pub language: String,
+  /// Symbol of the referencing chunk
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub definition_name: Option<String>,
+  /// How the chunk references the symbol: call or mention
+  pub kind: String,
+  /// Closeness to the definition: same_file, imports, same_directory or elsewhere
+  pub proximity: String,
+  /// Lines mentioning the symbol
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub lines: Vec<u32>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -550,6 +824,7 @@ impl CodeItem {
       signature: c.signature.clone(),
       docstring: c.docstring.clone(),
       parent_definition: c.parent_definition.clone(),
+      generated: c.generated.then_some(true),
       similarity: opts.similarity,
       confidence: opts.confidence,
       file_hash: if opts.include_details {
@@ -582,6 +857,9 @@ impl CodeItem {
       } else {
         None
       },
+      depth: None,
+      also_in: Vec::new(),
+      more_in_file: None,
     }
   }
 
@@ -628,6 +906,7 @@ impl From<&CodeChunk> for CodeCalleeItem {
       start_line: c.start_line,
       end_line: Some(c.end_line),
       language: Some(format!("{:?}", c.language).to_lowercase()),
+      depth: None,
     }
   }
 }
@@ -642,6 +921,41 @@ impl CodeCalleeItem {
       start_line: c.start_line,
       end_line: Some(c.end_line),
       language: Some(format!("{:?}", c.language).to_lowercase()),
+      depth: None,
+    }
+  }
+}
+
+impl CodeDefinitionItem {
+  pub fn from_chunk(c: &CodeChunk, resolution: &str) -> Self {
+    Self {
+      id: c.id.to_string(),
+      file_path: c.file_path.clone(),
+      start_line: c.start_line,
+      end_line: c.end_line,
+      language: format!("{:?}", c.language).to_lowercase(),
+      definition_kind: c.definition_kind.clone(),
+      definition_name: c.definition_name.clone(),
+      parent_definition: c.parent_definition.clone(),
+      signature: c.signature.clone(),
+      docstring: c.docstring.clone(),
+      resolution: resolution.to_string(),
+    }
+  }
+}
+
+impl CodeReferenceItem {
+  pub fn from_chunk(c: &CodeChunk, kind: &str, proximity: &str, lines: Vec<u32>) -> Self {
+    Self {
+      id: c.id.to_string(),
+      file_path: c.file_path.clone(),
+      start_line: c.start_line,
+      end_line: c.end_line,
+      language: format!("{:?}", c.language).to_lowercase(),
+      definition_name: c.definition_name.clone(),
+      kind: kind.to_string(),
+      proximity: proximity.to_string(),
+      lines,
     }
   }
 }
@@ -717,6 +1031,18 @@ impl_ipc_request!(
   v => RequestData::Code(CodeRequest::Stats(v)),
   v => ResponseData::Code(CodeResponse::Stats(v))
 );
+impl_ipc_request!(
+  CodeSkippedParams => Vec<CodeSkippedItem>,
+  ResponseData::Code(CodeResponse::Skipped(v)) => v,
+  v => RequestData::Code(CodeRequest::Skipped(v)),
+  v => ResponseData::Code(CodeResponse::Skipped(v))
+);
+impl_ipc_request!(
+  CodeIndexPlanParams => CodeIndexPlan,
+  ResponseData::Code(CodeResponse::Plan(v)) => v,
+  v => RequestData::Code(CodeRequest::Plan(v)),
+  v => ResponseData::Code(CodeResponse::Plan(v))
+);
 impl_ipc_request!(
   CodeMemoriesParams => CodeMemoriesResponse,
   ResponseData::Code(CodeResponse::Memories(v)) => v,
@@ -747,3 +1073,21 @@ impl_ipc_request!(
   v => RequestData::Code(CodeRequest::ContextFull(v)),
   v => ResponseData::Code(CodeResponse::ContextFull(v))
 );
+impl_ipc_request!(
+  CodeDefinitionParams => CodeDefinitionResponse,
+  ResponseData::Code(CodeResponse::Definition(v)) => v,
+  v => RequestData::Code(CodeRequest::Definition(v)),
+  v => ResponseData::Code(CodeResponse::Definition(v))
+);
+impl_ipc_request!(
+  CodeIndexChangedParams => CodeIndexChangedResult,
+  ResponseData::Code(CodeResponse::IndexChanged(v)) => v,
+  v => RequestData::Code(CodeRequest::IndexChanged(v)),
+  v => ResponseData::Code(CodeResponse::IndexChanged(v))
+);
+impl_ipc_request!(
+  CodeReferencesParams => CodeReferencesResponse,
+  ResponseData::Code(CodeResponse::References(v)) => v,
+  v => RequestData::Code(CodeRequest::References(v)),
+  v => ResponseData::Code(CodeResponse::References(v))
+);
@@ -23,6 +23,10 @@ pub struct DocsIngestParams {
   pub directory: Option<String>,
   /// Single file to ingest (can be absolute or relative to project root)
   pub file: Option<String>,
+  /// Web page to fetch and ingest
+  pub url: Option<String>,
+  /// How many links deep to follow same-domain links from `url` (default: 0)
+  pub depth: Option<usize>,
   /// Whether to stream progress updates
   #[serde(default)]
   pub stream: bool,
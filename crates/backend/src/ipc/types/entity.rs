@@ -0,0 +1,151 @@
+//! Entity IPC types - requests, responses, and conversions
+use serde::{Deserialize, Serialize};
+
+use super::{code::CodeItem, memory::MemorySummary};
+
+// ============================================================================
+// Request types
+// ============================================================================
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "data")]
+pub enum EntityRequest {
+  List(EntityListParams),
+  Get(EntityGetParams),
+  Top(EntityTopParams),
+  Show(EntityShowParams),
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityListParams {
+  /// person, technology, service or file
+  pub entity_type: Option<String>,
+  pub limit: Option<usize>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityGetParams {
+  /// Entity ID, ID prefix or name
+  pub entity_id: String,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityTopParams {
+  /// person, technology, service or file
+  pub entity_type: Option<String>,
+  pub limit: Option<usize>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityShowParams {
+  /// Entity ID, ID prefix or name
+  pub entity_id: String,
+  /// Maximum memories and code chunks returned (default: 5 each)
+  pub limit: Option<usize>,
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "data")]
+pub enum EntityResponse {
+  List(Vec<EntityItem>),
+  Get(EntityDetail),
+  Top(Vec<EntityItem>),
+  Show(EntityShowResult),
+}
+
+/// Entity with the number of memories mentioning it
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityItem {
+  pub id: String,
+  pub name: String,
+  pub entity_type: String,
+  /// Active memories mentioning the entity
+  pub mentions: usize,
+  /// When a memory last mentioned the entity
+  pub last_seen: Option<String>,
+}
+
+/// Entity with the memories mentioning it, most recent first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDetail {
+  #[serde(flatten)]
+  pub entity: EntityItem,
+  pub memories: Vec<MemorySummary>,
+}
+
+/// Everything known about one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityShowResult {
+  pub entity: EntityItem,
+  /// Mentions per day, oldest first
+  pub timeline: Vec<EntityTimelineEntry>,
+  /// Entities mentioned by the same memories, most shared first
+  pub co_occurring: Vec<EntityCoOccurrence>,
+  /// Most salient memories mentioning the entity
+  pub memories: Vec<MemorySummary>,
+  /// Code chunks of the file, or matching the entity name
+  pub code: Vec<CodeItem>,
+}
+
+/// Active memories created on one day that mention the entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityTimelineEntry {
+  /// Day as YYYY-MM-DD (UTC)
+  pub date: String,
+  pub mentions: usize,
+}
+
+/// An entity mentioned alongside another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityCoOccurrence {
+  pub id: String,
+  pub name: String,
+  pub entity_type: String,
+  /// Active memories mentioning both entities
+  pub shared: usize,
+}
+
+// ============================================================================
+// IpcRequest implementations
+// ============================================================================
+
+use crate::{
+  impl_ipc_request,
+  ipc::{RequestData, ResponseData},
+};
+
+impl_ipc_request!(
+  EntityListParams => Vec<EntityItem>,
+  ResponseData::Entity(EntityResponse::List(v)) => v,
+  v => RequestData::Entity(EntityRequest::List(v)),
+  v => ResponseData::Entity(EntityResponse::List(v))
+);
+impl_ipc_request!(
+  EntityGetParams => EntityDetail,
+  ResponseData::Entity(EntityResponse::Get(v)) => v,
+  v => RequestData::Entity(EntityRequest::Get(v)),
+  v => ResponseData::Entity(EntityResponse::Get(v))
+);
+impl_ipc_request!(
+  EntityTopParams => Vec<EntityItem>,
+  ResponseData::Entity(EntityResponse::Top(v)) => v,
+  v => RequestData::Entity(EntityRequest::Top(v)),
+  v => ResponseData::Entity(EntityResponse::Top(v))
+);
+impl_ipc_request!(
+  EntityShowParams => EntityShowResult,
+  ResponseData::Entity(EntityResponse::Show(v)) => v,
+  v => RequestData::Entity(EntityRequest::Show(v)),
+  v => ResponseData::Entity(EntityResponse::Show(v))
+);
@@ -0,0 +1,217 @@
+//! Exploration IPC types - saved notebooks of queries, pins and notes
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Request types
+// ============================================================================
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "data")]
+pub enum ExplorationRequest {
+  Pin(ExplorationPinParams),
+  Note(ExplorationNoteParams),
+  Get(ExplorationGetParams),
+  Save(ExplorationSaveParams),
+  List(ExplorationListParams),
+  Export(ExplorationExportParams),
+  Delete(ExplorationDeleteParams),
+  Clear(ExplorationClearParams),
+}
+
+/// Parameters for pinning a result to the draft exploration
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationPinParams {
+  /// Memory, code or document ID or prefix
+  pub id: String,
+  /// Why the result matters
+  pub note: Option<String>,
+}
+
+/// Parameters for adding a note to the draft exploration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationNoteParams {
+  pub text: String,
+}
+
+/// Parameters for reading an exploration
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationGetParams {
+  /// Saved exploration to read; the draft when omitted
+  pub name: Option<String>,
+}
+
+/// Parameters for saving the draft exploration under a name
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationSaveParams {
+  pub name: String,
+  /// Replace a saved exploration with the same name
+  pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationListParams;
+
+/// Parameters for rendering an exploration as Markdown
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationExportParams {
+  /// Saved exploration to export; the draft when omitted
+  pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationDeleteParams {
+  pub name: String,
+}
+
+/// Parameters for discarding the draft exploration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationClearParams;
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "data")]
+pub enum ExplorationResponse {
+  Pin(Exploration),
+  Note(Exploration),
+  Get(Exploration),
+  Save(Exploration),
+  List(Vec<ExplorationSummary>),
+  Export(ExplorationExportResult),
+  Delete(ExplorationDeleteResult),
+  Clear(ExplorationDeleteResult),
+}
+
+/// Queries run, results pinned and notes taken while investigating, in order
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Exploration {
+  /// None for the draft
+  pub name: Option<String>,
+  /// When the first entry was added
+  pub started_at: Option<String>,
+  pub saved_at: Option<String>,
+  #[serde(default)]
+  pub entries: Vec<ExplorationEntry>,
+}
+
+/// One cell of an exploration
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ExplorationEntry {
+  Query {
+    query: String,
+    scope: Option<String>,
+    /// Top results when the query ran
+    #[serde(default)]
+    results: Vec<ExplorationItem>,
+    at: String,
+  },
+  Pin {
+    item: ExplorationItem,
+    note: Option<String>,
+    at: String,
+  },
+  Note {
+    text: String,
+    at: String,
+  },
+}
+
+/// A memory, code chunk or document chunk referenced by an exploration
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplorationItem {
+  pub id: String,
+  /// "code" | "memory" | "doc"
+  pub result_type: String,
+  pub preview: String,
+  pub file_path: Option<String>,
+  pub line: Option<u32>,
+}
+
+/// A saved exploration in a listing
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationSummary {
+  pub name: String,
+  pub started_at: Option<String>,
+  pub saved_at: Option<String>,
+  pub queries: usize,
+  pub pins: usize,
+  pub notes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationExportResult {
+  pub name: Option<String>,
+  pub markdown: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationDeleteResult {
+  pub deleted: bool,
+}
+
+// ============================================================================
+// IpcRequest implementations
+// ============================================================================
+
+use crate::{
+  impl_ipc_request,
+  ipc::{RequestData, ResponseData},
+};
+
+impl_ipc_request!(
+  ExplorationPinParams => Exploration,
+  ResponseData::Exploration(ExplorationResponse::Pin(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Pin(v)),
+  v => ResponseData::Exploration(ExplorationResponse::Pin(v))
+);
+impl_ipc_request!(
+  ExplorationNoteParams => Exploration,
+  ResponseData::Exploration(ExplorationResponse::Note(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Note(v))
+);
+impl_ipc_request!(
+  ExplorationGetParams => Exploration,
+  ResponseData::Exploration(ExplorationResponse::Get(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Get(v))
+);
+impl_ipc_request!(
+  ExplorationSaveParams => Exploration,
+  ResponseData::Exploration(ExplorationResponse::Save(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Save(v))
+);
+impl_ipc_request!(
+  ExplorationListParams => Vec<ExplorationSummary>,
+  ResponseData::Exploration(ExplorationResponse::List(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::List(v)),
+  v => ResponseData::Exploration(ExplorationResponse::List(v))
+);
+impl_ipc_request!(
+  ExplorationExportParams => ExplorationExportResult,
+  ResponseData::Exploration(ExplorationResponse::Export(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Export(v)),
+  v => ResponseData::Exploration(ExplorationResponse::Export(v))
+);
+impl_ipc_request!(
+  ExplorationDeleteParams => ExplorationDeleteResult,
+  ResponseData::Exploration(ExplorationResponse::Delete(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Delete(v)),
+  v => ResponseData::Exploration(ExplorationResponse::Delete(v))
+);
+impl_ipc_request!(
+  ExplorationClearParams => ExplorationDeleteResult,
+  ResponseData::Exploration(ExplorationResponse::Clear(v)) => v,
+  v => RequestData::Exploration(ExplorationRequest::Clear(v))
+);
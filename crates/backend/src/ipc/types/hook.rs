@@ -24,6 +24,7 @@ pub struct HookResult {
 }
 
 /// Result from SessionStart hook
+#[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionStartHookResult {
   pub status: String,
@@ -31,6 +32,8 @@ pub struct SessionStartHookResult {
   pub project_name: String,
   pub project_path: String,
   pub watcher_started: bool,
+  /// Relevant memories as Markdown, for the hook's `additionalContext`
+  pub additional_context: Option<String>,
 }
 
 /// Result from SessionEnd hook
@@ -42,10 +45,22 @@ pub struct SessionEndHookResult {
 }
 
 /// Result from UserPromptSubmit hook
+#[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPromptHookResult {
   pub status: String,
   pub memories_created: Vec<String>,
+  /// Memories about the prompt as Markdown, for the hook's `additionalContext`
+  pub additional_context: Option<String>,
+}
+
+/// Result from PreToolUse hook
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreToolUseHookResult {
+  pub status: String,
+  /// Memories about the file being edited as Markdown, for the hook's `additionalContext`
+  pub additional_context: Option<String>,
 }
 
 /// Result from PostToolUse hook
@@ -55,11 +70,14 @@ pub struct PostToolUseHookResult {
 }
 
 /// Result from PreCompact hook
+#[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreCompactHookResult {
   pub status: String,
   pub background_extraction: bool,
   pub memories_created: Vec<String>,
+  /// Condensed session summary as Markdown, injected by the SessionStart after compaction
+  pub additional_context: Option<String>,
 }
 
 /// Result from Stop hook
@@ -76,9 +94,34 @@ pub struct SimpleHookResult {
   pub status: String,
 }
 
+/// Several hook events sent in one request, handled in order.
+///
+/// Events are routed by the request's `cwd`, so a batch only holds events
+/// from one project.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HookBatchParams {
+  pub events: Vec<HookParams>,
+}
+
+/// Result from a hook batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookBatchResult {
+  /// Events handled successfully
+  pub processed: usize,
+  /// Events whose handler failed; the rest of the batch still runs
+  pub failed: usize,
+}
+
 impl_ipc_request!(
   HookParams => HookResult,
   ResponseData::Hook(v) => v,
   v => RequestData::Hook(v),
   v => ResponseData::Hook(v)
 );
+
+impl_ipc_request!(
+  HookBatchParams => HookBatchResult,
+  ResponseData::HookBatch(v) => v,
+  v => RequestData::HookBatch(v),
+  v => ResponseData::HookBatch(v)
+);
@@ -1,7 +1,10 @@
 //! Memory IPC types - requests, responses, and conversions
 use serde::{Deserialize, Serialize};
 
-use crate::domain::memory::Memory;
+use crate::{
+  db::{ExtractionJob, MemoryEvent},
+  domain::{config::SavedSearchConfig, memory::Memory},
+};
 
 // ============================================================================
 // Request types
@@ -17,6 +20,7 @@ pub enum MemoryRequest {
   List(MemoryListParams),
   Reinforce(MemoryReinforceParams),
   Deemphasize(MemoryDeemphasizeParams),
+  Feedback(MemoryFeedbackParams),
   ListDeleted(MemoryListDeletedParams),
   Delete(MemoryDeleteParams),
   HardDelete(MemoryHardDeleteParams),
@@ -25,6 +29,24 @@ pub enum MemoryRequest {
   Timeline(MemoryTimelineParams),
   Related(MemoryRelatedParams),
   SetSalience(MemorySetSalienceParams),
+  ExtractionCapture(ExtractionCaptureParams),
+  ExtractionJobs(ExtractionJobsParams),
+  ExtractionRetry(ExtractionRetryParams),
+  Extract(MemoryExtractParams),
+  SyncPush(SyncPushParams),
+  SyncPull(SyncPullParams),
+  SyncConflicts(SyncConflictsParams),
+  SyncResolve(SyncResolveParams),
+  Goals(GoalsParams),
+  GoalUpdate(GoalUpdateParams),
+  History(MemoryHistoryParams),
+  Retro(RetroParams),
+  Archive(MemoryArchiveParams),
+  Undo(MemoryUndoParams),
+  Tags(TagListParams),
+  TagRename(TagRenameParams),
+  TagMerge(TagMergeParams),
+  TagDelete(TagDeleteParams),
 }
 
 #[serde_with::skip_serializing_none]
@@ -42,6 +64,11 @@ pub struct MemorySearchParams {
   pub limit: Option<usize>,
   #[serde(default)]
   pub include_superseded: bool,
+  /// Group results by `scope_path`, `memory_type` or `tag`. `limit` then
+  /// caps the number of groups.
+  pub group_by: Option<String>,
+  /// Top hits kept per group (default: 3)
+  pub per_group: Option<usize>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -57,6 +84,12 @@ pub struct MemoryAddParams {
   pub scope_path: Option<String>,
   pub scope_module: Option<String>,
   pub importance: Option<f32>,
+  /// `private` or `team`; defaults to `[visibility]` for the memory's type
+  pub visibility: Option<String>,
+  /// Goal status (`open`, `blocked` or `done`); only for `goal` memories
+  pub status: Option<String>,
+  /// Free-form due hint such as "before the 2.0 release"; only for `goal` memories
+  pub due: Option<String>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -88,6 +121,17 @@ pub struct MemoryDeemphasizeParams {
   pub amount: Option<f32>,
 }
 
+/// Whether a memory returned by a search was useful for the query
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFeedbackParams {
+  pub memory_id: String,
+  /// `true` for helpful, `false` for irrelevant
+  pub helpful: bool,
+  /// The query the memory was returned for, kept in memory history
+  pub query: Option<String>,
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct MemorySupersedeParams {
@@ -139,6 +183,143 @@ pub struct MemoryTimelineParams {
   pub memory_id: String,
 }
 
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryHistoryParams {
+  /// Memory ID or unique prefix; hard-deleted memories need the full ID
+  pub memory_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionCaptureParams {
+  /// Segment ID or unique prefix
+  pub segment_id: String,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionJobsParams {
+  /// Only dead-lettered jobs
+  #[serde(default)]
+  pub dead: bool,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionRetryParams {
+  /// Job IDs or unique prefixes; every queued job when empty
+  #[serde(default)]
+  pub ids: Vec<String>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryExtractParams {
+  /// Notes, a PR description or any other text to extract memories from
+  pub text: String,
+  /// Where the text came from, such as a file name
+  pub source: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPushParams {
+  /// Report what would change without writing files
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPullParams {
+  /// Report what would change without touching the memory store
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflictsParams {}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResolveParams {
+  /// Memory ID or unique prefix
+  pub id: String,
+  /// "local", "remote" or "edit"
+  pub take: String,
+  /// Replacement content when `take` is "edit"
+  pub content: Option<String>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct GoalsParams {
+  /// `open`, `blocked`, `done`, `active` (open and blocked, the default) or `all`
+  pub status: Option<String>,
+  pub limit: Option<usize>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RetroParams {
+  /// Task completion or goal ID or prefix
+  pub id: String,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryArchiveParams {
+  /// Memory IDs or prefixes, soft-deleted together as one undoable batch
+  pub memory_ids: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUndoParams {
+  /// Report what would be reverted without reverting it
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TagListParams {
+  /// Only tags used by at least this many memories
+  pub min_count: Option<usize>,
+  pub limit: Option<usize>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TagRenameParams {
+  pub from: String,
+  pub to: String,
+  /// Report the memories that would change without changing them
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TagMergeParams {
+  /// Tags replaced by `into`
+  pub tags: Vec<String>,
+  pub into: String,
+  /// Report the memories that would change without changing them
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TagDeleteParams {
+  /// Tags removed from every memory
+  pub tags: Vec<String>,
+  /// Report the memories that would change without changing them
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct GoalUpdateParams {
+  /// Memory ID or unique prefix
+  pub id: String,
+  pub status: Option<String>,
+  /// New due hint; an empty string clears it
+  pub due: Option<String>,
+}
+
 // ============================================================================
 // Response types
 // ============================================================================
@@ -155,6 +336,7 @@ pub enum MemoryResponse {
   Get(MemoryFullDetail),
   Add(MemoryAddResult),
   Update(MemoryUpdateResult),
+  Feedback(MemoryFeedbackResult),
   Delete(MemoryDeleteResult),
   List(Vec<MemoryItem>),
   Timeline(MemoryTimelineResult),
@@ -162,6 +344,22 @@ pub enum MemoryResponse {
   Supersede(MemorySupersedeResult),
   Restore(MemoryRestoreResult),
   ListDeleted(Vec<MemoryItem>),
+  ExtractionCapture(ExtractionCapture),
+  ExtractionJobs(Vec<ExtractionJobItem>),
+  ExtractionRetry(ExtractionRetryResult),
+  Extract(MemoryExtractResult),
+  SyncPush(SyncPushResult),
+  SyncPull(SyncPullResult),
+  SyncConflicts(Vec<SyncConflictItem>),
+  SyncResolve(SyncResolveResult),
+  Goals(Vec<MemoryItem>),
+  GoalUpdate(MemoryItem),
+  History(MemoryHistoryResult),
+  Retro(RetroResult),
+  Archive(MemoryArchiveResult),
+  Undo(MemoryUndoResult),
+  Tags(Vec<TagItem>),
+  TagUpdate(TagUpdateResult),
 }
 
 /// Memory search result with items and quality metadata.
@@ -174,6 +372,21 @@ pub struct MemorySearchResult {
   /// refining the query for better results.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub search_quality: Option<SearchQuality>,
+  /// Results grouped by the requested field, best group first. When set,
+  /// `items` is empty.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub groups: Option<Vec<MemoryGroup>>,
+}
+
+/// One group of a grouped memory search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryGroup {
+  /// Scope path, memory type or tag shared by the group
+  pub key: String,
+  /// Matching memories in the group, including those not shown
+  pub count: usize,
+  /// Top hits, in rank order
+  pub items: Vec<MemoryItem>,
 }
 
 /// Memory item for search and list results
@@ -203,6 +416,12 @@ pub struct MemoryItem {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub superseded_by: Option<String>,
 
+  /// Referenced code changed or disappeared from the index
+  #[serde(default)]
+  pub stale: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub stale_reason: Option<String>,
+
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub tags: Vec<String>,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -212,6 +431,12 @@ pub struct MemoryItem {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub scope_module: Option<String>,
 
+  // Goal tracking - only on goal memories
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub goal_status: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub due: Option<String>,
+
   pub created_at: String,
   pub last_accessed: String,
 }
@@ -228,6 +453,8 @@ pub struct MemoryFullDetail {
   pub tier: String,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub memory_type: Option<String>,
+  /// `private` memories stay out of snapshots and the `CLAUDE.md` primer
+  pub visibility: String,
   pub salience: f32,
   pub importance: f32,
   pub confidence: f32,
@@ -235,6 +462,11 @@ pub struct MemoryFullDetail {
   pub is_deleted: bool,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub superseded_by: Option<String>,
+  /// Referenced code changed or disappeared from the index
+  #[serde(default)]
+  pub stale: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub stale_reason: Option<String>,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub tags: Vec<String>,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -249,6 +481,10 @@ pub struct MemoryFullDetail {
   pub scope_path: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub scope_module: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub goal_status: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub due: Option<String>,
   pub created_at: String,
   pub updated_at: String,
   pub last_accessed: String,
@@ -256,7 +492,30 @@ pub struct MemoryFullDetail {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub valid_until: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub segment_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub relationships: Option<Vec<MemoryRelationshipItem>>,
+  /// Blame anchors for referenced files, checked against the current checkout
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub file_anchors: Option<Vec<MemoryFileAnchor>>,
+  /// Set when code referenced by the memory changed after it was created
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub possibly_stale: Option<bool>,
+}
+
+/// Referenced file anchor in get response
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFileAnchor {
+  pub path: String,
+  pub start_line: Option<u32>,
+  pub end_line: Option<u32>,
+  /// Newest commit touching the lines when the memory was created
+  pub commit: String,
+  pub author: Option<String>,
+  /// Newest commit touching the lines now, `None` if the file is gone
+  pub current_commit: Option<String>,
+  pub changed: bool,
 }
 
 /// Memory relationship in get response
@@ -342,6 +601,253 @@ pub struct MemoryRelatedResult {
   pub count: usize,
 }
 
+/// Prompts and raw LLM responses recorded for one extraction segment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionCapture {
+  pub segment_id: String,
+  /// Extraction path that produced the segment (`extraction` or `high_priority`)
+  pub kind: String,
+  pub captured_at: String,
+  /// Memories stored from this segment
+  #[serde(default)]
+  pub memory_ids: Vec<String>,
+  pub exchanges: Vec<CapturedExchange>,
+}
+
+/// A failed extraction waiting in the retry queue
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionJobItem {
+  pub id: String,
+  pub session_id: Option<String>,
+  /// `pending` or `dead`
+  pub status: String,
+  pub attempts: u32,
+  pub last_error: Option<String>,
+  pub created_at: String,
+  pub next_attempt_at: String,
+  /// User prompt that started the segment
+  pub user_prompt: Option<String>,
+  pub tool_call_count: usize,
+}
+
+impl From<&ExtractionJob> for ExtractionJobItem {
+  fn from(job: &ExtractionJob) -> Self {
+    Self {
+      id: job.id.to_string(),
+      session_id: job.session_id.clone(),
+      status: job.status.as_str().to_string(),
+      attempts: job.attempts,
+      last_error: job.last_error.clone(),
+      created_at: job.created_at.to_rfc3339(),
+      next_attempt_at: job.next_attempt_at.to_rfc3339(),
+      user_prompt: job.segment.user_prompt.clone(),
+      tool_call_count: job.segment.tool_call_count,
+    }
+  }
+}
+
+/// A recorded change to a memory
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEventItem {
+  /// `added`, `updated`, `deleted`, `hard_deleted`, `restored`, `reinforced`,
+  /// `deemphasized`, `superseded` or `salience_set`
+  pub action: String,
+  /// `hook`, `mcp`, `cli` or `system`
+  pub actor: String,
+  pub salience_before: Option<f32>,
+  pub salience_after: Option<f32>,
+  pub detail: Option<String>,
+  pub created_at: String,
+}
+
+impl From<&MemoryEvent> for MemoryEventItem {
+  fn from(event: &MemoryEvent) -> Self {
+    Self {
+      action: event.action.as_str().to_string(),
+      actor: event.actor.as_str().to_string(),
+      salience_before: event.salience_before,
+      salience_after: event.salience_after,
+      detail: event.detail.clone(),
+      created_at: event.created_at.to_rfc3339(),
+    }
+  }
+}
+
+/// Mutation history of a memory, oldest change first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryHistoryResult {
+  pub memory_id: String,
+  pub events: Vec<MemoryEventItem>,
+}
+
+/// Retrospective written for a task or goal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetroResult {
+  /// ID of the stored retrospective memory
+  pub id: String,
+  pub subject_id: String,
+  pub summary: String,
+  pub went_well: Vec<String>,
+  pub went_wrong: Vec<String>,
+  pub deviations: Vec<String>,
+  pub lessons: Vec<String>,
+  /// Memories the retrospective was written from, per group
+  pub plan: usize,
+  pub completions: usize,
+  pub errors: usize,
+  pub decisions: usize,
+}
+
+/// A tag with the number of current memories using it
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagItem {
+  pub tag: String,
+  pub memories: usize,
+  /// When a memory using the tag was last created or updated
+  pub last_used: Option<String>,
+}
+
+/// Memories whose tags a rename, merge or delete changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagUpdateResult {
+  pub memory_ids: Vec<String>,
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+/// Memories soft-deleted by an archive run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryArchiveResult {
+  pub archived: Vec<String>,
+}
+
+/// A change reverted by undo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUndoItem {
+  pub memory_id: String,
+  /// The reverted action, `deleted` or `superseded`
+  pub action: String,
+  pub content: String,
+  /// Salience after the revert
+  pub salience: f32,
+}
+
+/// The batch reverted by undo; no batch ID when there was nothing to undo
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUndoResult {
+  pub batch_id: Option<String>,
+  /// Who made the reverted changes
+  pub actor: Option<String>,
+  pub created_at: Option<String>,
+  pub reverted: Vec<MemoryUndoItem>,
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+/// Outcome of retrying queued extractions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionRetryResult {
+  pub attempted: usize,
+  pub succeeded: usize,
+  /// Jobs that failed again and are dead-lettered
+  pub dead: usize,
+  pub memories_created: Vec<String>,
+}
+
+/// Outcome of extracting memories from user-provided text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryExtractResult {
+  /// Parts the text was split into to fit the extraction budget
+  pub parts: usize,
+  /// Parts the classifier found nothing worth remembering in
+  pub skipped: usize,
+  /// Segment ID of the extraction, for `ccengram extract inspect`
+  pub segment_id: String,
+  pub memories_created: Vec<String>,
+  /// Existing memories that extracted candidates duplicated
+  pub duplicates: Vec<String>,
+}
+
+/// Outcome of writing shared memories to the sync directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncPushResult {
+  /// Sync directory, relative to the project root
+  pub dir: String,
+  /// Memory files created or rewritten
+  pub written: usize,
+  pub unchanged: usize,
+  /// Files turned into deletion markers for deleted memories
+  pub tombstoned: usize,
+  /// Files removed because their memory is now private
+  pub removed: usize,
+  /// Files changed in the directory since the last sync, left for `pull`
+  pub stale: usize,
+  /// Memories skipped because of an unresolved conflict
+  pub conflicted: usize,
+  pub dry_run: bool,
+}
+
+/// Outcome of merging the sync directory into the memory store
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncPullResult {
+  /// Sync directory, relative to the project root
+  pub dir: String,
+  pub added: usize,
+  pub updated: usize,
+  pub deleted: usize,
+  /// Files whose memory is the same or newer locally
+  pub unchanged: usize,
+  /// Memories edited on both sides, left for `sync resolve`
+  pub conflicts: usize,
+  /// Files that could not be read, with the reason
+  pub invalid: Vec<String>,
+  pub dry_run: bool,
+}
+
+/// A synced memory that could not be merged automatically.
+///
+/// Versions are the memory content, absent when that side deleted it.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflictItem {
+  pub id: String,
+  /// "content" or "edit_delete"
+  pub kind: String,
+  /// Content last synced, absent if never synced
+  pub base: Option<String>,
+  pub local: Option<String>,
+  pub remote: Option<String>,
+  pub local_updated_at: String,
+  pub remote_updated_at: String,
+  pub detected_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResolveResult {
+  pub id: String,
+  pub take: String,
+}
+
+/// A single LLM request and its raw response
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedExchange {
+  pub task: Option<String>,
+  pub model: String,
+  pub system_prompt: Option<String>,
+  pub prompt: String,
+  pub response: Option<String>,
+  pub error: Option<String>,
+  pub duration_ms: Option<u64>,
+  /// Whether the prompt or response was cut to the capture size limit
+  #[serde(default)]
+  pub truncated: bool,
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryAddResult {
@@ -359,6 +865,17 @@ pub struct MemoryUpdateResult {
   pub message: String,
 }
 
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFeedbackResult {
+  pub id: String,
+  pub helpful_count: u32,
+  pub irrelevant_count: u32,
+  /// Feedback score used in ranking, from -1 to 1
+  pub feedback_score: f32,
+  pub message: String,
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryDeleteResult {
@@ -387,6 +904,20 @@ pub struct MemorySupersedeResult {
 // Conversions from domain types
 // ============================================================================
 
+impl From<&SavedSearchConfig> for MemorySearchParams {
+  fn from(saved: &SavedSearchConfig) -> Self {
+    Self {
+      query: saved.query.clone(),
+      sector: saved.sector.clone(),
+      memory_type: saved.memory_type.clone(),
+      min_salience: saved.min_salience,
+      scope_path: saved.scope.clone(),
+      include_superseded: saved.include_superseded,
+      ..Default::default()
+    }
+  }
+}
+
 impl From<&Memory> for MemoryItem {
   fn from(m: &Memory) -> Self {
     Self::from_memory(m, None, None)
@@ -408,10 +939,14 @@ impl MemoryItem {
       rank_score,
       is_superseded: m.is_superseded(),
       superseded_by: m.superseded_by.map(|id| id.to_string()),
+      stale: m.stale,
+      stale_reason: m.stale_reason.clone(),
       tags: m.tags.clone(),
       categories: m.categories.clone(),
       scope_path: m.scope_path.clone(),
       scope_module: m.scope_module.clone(),
+      goal_status: m.goal_status.map(|s| s.as_str().to_string()),
+      due: m.due.clone(),
       created_at: m.created_at.to_rfc3339(),
       last_accessed: m.last_accessed.to_rfc3339(),
     }
@@ -426,6 +961,84 @@ impl MemoryItem {
   }
 }
 
+/// Field memory search results are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryGroupBy {
+  ScopePath,
+  MemoryType,
+  Tag,
+}
+
+impl MemoryGroupBy {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::ScopePath => "scope_path",
+      Self::MemoryType => "memory_type",
+      Self::Tag => "tag",
+    }
+  }
+
+  /// Group an item falls under. A memory with several tags is grouped under
+  /// its first, so each memory appears in exactly one group.
+  pub fn key<'a>(&self, item: &'a MemoryItem) -> &'a str {
+    match self {
+      Self::ScopePath => item
+        .scope_path
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .unwrap_or("(unscoped)"),
+      Self::MemoryType => item.memory_type.as_deref().unwrap_or("(untyped)"),
+      Self::Tag => item.tags.first().map(String::as_str).unwrap_or("(untagged)"),
+    }
+  }
+
+  /// Group ranked items, keeping the first `per_group` of each. Groups are
+  /// ordered by their best hit.
+  pub fn group(&self, items: Vec<MemoryItem>, per_group: usize) -> Vec<MemoryGroup> {
+    let mut groups: Vec<MemoryGroup> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for item in items {
+      if !seen.insert(item.id.clone()) {
+        continue;
+      }
+      let key = self.key(&item);
+      let index = match groups.iter().position(|g| g.key == key) {
+        Some(index) => index,
+        None => {
+          groups.push(MemoryGroup {
+            key: key.to_string(),
+            count: 0,
+            items: Vec::new(),
+          });
+          groups.len() - 1
+        }
+      };
+      let group = &mut groups[index];
+      group.count += 1;
+      if group.items.len() < per_group {
+        group.items.push(item);
+      }
+    }
+    groups
+  }
+}
+
+impl std::str::FromStr for MemoryGroupBy {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "scope_path" | "scope" => Ok(Self::ScopePath),
+      "memory_type" | "type" => Ok(Self::MemoryType),
+      "tag" | "tags" => Ok(Self::Tag),
+      _ => Err(format!(
+        "Unknown group_by: {} (expected scope_path, memory_type or tag)",
+        s
+      )),
+    }
+  }
+}
+
 impl From<&Memory> for MemoryFullDetail {
   fn from(m: &Memory) -> Self {
     Self {
@@ -435,12 +1048,15 @@ impl From<&Memory> for MemoryFullDetail {
       sector: m.sector.as_str().to_string(),
       tier: m.tier.as_str().to_string(),
       memory_type: m.memory_type.map(|t| t.as_str().to_string()),
+      visibility: m.visibility.as_str().to_string(),
       salience: m.salience,
       importance: m.importance,
       confidence: m.confidence,
       access_count: m.access_count,
       is_deleted: m.is_deleted,
       superseded_by: m.superseded_by.map(|id| id.to_string()),
+      stale: m.stale,
+      stale_reason: m.stale_reason.clone(),
       tags: m.tags.clone(),
       categories: m.categories.clone(),
       concepts: m.concepts.clone(),
@@ -448,12 +1064,17 @@ impl From<&Memory> for MemoryFullDetail {
       context: m.context.clone(),
       scope_path: m.scope_path.clone(),
       scope_module: m.scope_module.clone(),
+      goal_status: m.goal_status.map(|s| s.as_str().to_string()),
+      due: m.due.clone(),
       created_at: m.created_at.to_rfc3339(),
       updated_at: m.updated_at.to_rfc3339(),
       last_accessed: m.last_accessed.to_rfc3339(),
       valid_from: m.valid_from.to_rfc3339(),
       valid_until: m.valid_until.map(|t| t.to_rfc3339()),
+      segment_id: m.segment_id.map(|id| id.to_string()),
       relationships: None,
+      file_anchors: None,
+      possibly_stale: None,
     }
   }
 }
@@ -463,6 +1084,12 @@ impl MemoryFullDetail {
     self.relationships = Some(relationships);
     self
   }
+
+  pub fn with_file_anchors(mut self, anchors: Vec<MemoryFileAnchor>) -> Self {
+    self.possibly_stale = Some(anchors.iter().any(|a| a.changed));
+    self.file_anchors = Some(anchors);
+    self
+  }
 }
 
 impl From<&Memory> for MemorySummary {
@@ -542,6 +1169,12 @@ impl_ipc_request!(
   ResponseData::Memory(MemoryResponse::Update(v)) => v,
   v => RequestData::Memory(MemoryRequest::Deemphasize(v))
 );
+impl_ipc_request!(
+  MemoryFeedbackParams => MemoryFeedbackResult,
+  ResponseData::Memory(MemoryResponse::Feedback(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Feedback(v)),
+  v => ResponseData::Memory(MemoryResponse::Feedback(v))
+);
 impl_ipc_request!(
   MemoryListDeletedParams => Vec<MemoryItem>,
   ResponseData::Memory(MemoryResponse::ListDeleted(v)) => v,
@@ -587,3 +1220,107 @@ impl_ipc_request!(
   v => RequestData::Memory(MemoryRequest::Related(v)),
   v => ResponseData::Memory(MemoryResponse::Related(v))
 );
+impl_ipc_request!(
+  ExtractionCaptureParams => ExtractionCapture,
+  ResponseData::Memory(MemoryResponse::ExtractionCapture(v)) => v,
+  v => RequestData::Memory(MemoryRequest::ExtractionCapture(v)),
+  v => ResponseData::Memory(MemoryResponse::ExtractionCapture(v))
+);
+impl_ipc_request!(
+  ExtractionJobsParams => Vec<ExtractionJobItem>,
+  ResponseData::Memory(MemoryResponse::ExtractionJobs(v)) => v,
+  v => RequestData::Memory(MemoryRequest::ExtractionJobs(v))
+);
+impl_ipc_request!(
+  ExtractionRetryParams => ExtractionRetryResult,
+  ResponseData::Memory(MemoryResponse::ExtractionRetry(v)) => v,
+  v => RequestData::Memory(MemoryRequest::ExtractionRetry(v)),
+  v => ResponseData::Memory(MemoryResponse::ExtractionRetry(v))
+);
+impl_ipc_request!(
+  MemoryExtractParams => MemoryExtractResult,
+  ResponseData::Memory(MemoryResponse::Extract(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Extract(v)),
+  v => ResponseData::Memory(MemoryResponse::Extract(v))
+);
+impl_ipc_request!(
+  SyncPushParams => SyncPushResult,
+  ResponseData::Memory(MemoryResponse::SyncPush(v)) => v,
+  v => RequestData::Memory(MemoryRequest::SyncPush(v)),
+  v => ResponseData::Memory(MemoryResponse::SyncPush(v))
+);
+impl_ipc_request!(
+  SyncPullParams => SyncPullResult,
+  ResponseData::Memory(MemoryResponse::SyncPull(v)) => v,
+  v => RequestData::Memory(MemoryRequest::SyncPull(v)),
+  v => ResponseData::Memory(MemoryResponse::SyncPull(v))
+);
+impl_ipc_request!(
+  SyncConflictsParams => Vec<SyncConflictItem>,
+  ResponseData::Memory(MemoryResponse::SyncConflicts(v)) => v,
+  v => RequestData::Memory(MemoryRequest::SyncConflicts(v)),
+  v => ResponseData::Memory(MemoryResponse::SyncConflicts(v))
+);
+impl_ipc_request!(
+  SyncResolveParams => SyncResolveResult,
+  ResponseData::Memory(MemoryResponse::SyncResolve(v)) => v,
+  v => RequestData::Memory(MemoryRequest::SyncResolve(v)),
+  v => ResponseData::Memory(MemoryResponse::SyncResolve(v))
+);
+impl_ipc_request!(
+  GoalsParams => Vec<MemoryItem>,
+  ResponseData::Memory(MemoryResponse::Goals(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Goals(v))
+);
+impl_ipc_request!(
+  GoalUpdateParams => MemoryItem,
+  ResponseData::Memory(MemoryResponse::GoalUpdate(v)) => v,
+  v => RequestData::Memory(MemoryRequest::GoalUpdate(v)),
+  v => ResponseData::Memory(MemoryResponse::GoalUpdate(v))
+);
+impl_ipc_request!(
+  MemoryHistoryParams => MemoryHistoryResult,
+  ResponseData::Memory(MemoryResponse::History(v)) => v,
+  v => RequestData::Memory(MemoryRequest::History(v)),
+  v => ResponseData::Memory(MemoryResponse::History(v))
+);
+impl_ipc_request!(
+  RetroParams => RetroResult,
+  ResponseData::Memory(MemoryResponse::Retro(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Retro(v)),
+  v => ResponseData::Memory(MemoryResponse::Retro(v))
+);
+impl_ipc_request!(
+  MemoryArchiveParams => MemoryArchiveResult,
+  ResponseData::Memory(MemoryResponse::Archive(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Archive(v)),
+  v => ResponseData::Memory(MemoryResponse::Archive(v))
+);
+impl_ipc_request!(
+  MemoryUndoParams => MemoryUndoResult,
+  ResponseData::Memory(MemoryResponse::Undo(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Undo(v)),
+  v => ResponseData::Memory(MemoryResponse::Undo(v))
+);
+impl_ipc_request!(
+  TagListParams => Vec<TagItem>,
+  ResponseData::Memory(MemoryResponse::Tags(v)) => v,
+  v => RequestData::Memory(MemoryRequest::Tags(v)),
+  v => ResponseData::Memory(MemoryResponse::Tags(v))
+);
+impl_ipc_request!(
+  TagRenameParams => TagUpdateResult,
+  ResponseData::Memory(MemoryResponse::TagUpdate(v)) => v,
+  v => RequestData::Memory(MemoryRequest::TagRename(v)),
+  v => ResponseData::Memory(MemoryResponse::TagUpdate(v))
+);
+impl_ipc_request!(
+  TagMergeParams => TagUpdateResult,
+  ResponseData::Memory(MemoryResponse::TagUpdate(v)) => v,
+  v => RequestData::Memory(MemoryRequest::TagMerge(v))
+);
+impl_ipc_request!(
+  TagDeleteParams => TagUpdateResult,
+  ResponseData::Memory(MemoryResponse::TagUpdate(v)) => v,
+  v => RequestData::Memory(MemoryRequest::TagDelete(v))
+);
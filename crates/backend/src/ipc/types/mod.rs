@@ -7,6 +7,8 @@
 
 pub mod code;
 pub mod docs;
+pub mod entity;
+pub mod exploration;
 pub mod hook;
 pub mod memory;
 pub mod project;
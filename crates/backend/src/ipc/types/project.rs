@@ -14,6 +14,18 @@ pub enum ProjectRequest {
   Clean(ProjectCleanParams),
   CleanAll(ProjectCleanAllParams),
   Sessions(SessionListParams),
+  SessionShow(SessionShowParams),
+  SessionInjections(SessionInjectionsParams),
+  SessionImport(SessionImportParams),
+  Migrate(ProjectMigrateParams),
+  Duplicates(ProjectDuplicatesParams),
+  Merge(ProjectMergeParams),
+  Export(ProjectExportParams),
+  Import(ProjectImportParams),
+  Repair(ProjectRepairParams),
+  AuditSecrets(ProjectAuditSecretsParams),
+  ContextPack(ContextPackParams),
+  SearchStats(SearchStatsParams),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -22,6 +34,40 @@ pub struct ProjectListParams;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectCleanAllParams;
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectDuplicatesParams;
+
+/// Parameters for restoring tables quarantined because they could not be read
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectRepairParams;
+
+/// Parameters for scanning stored memories and chunks for secrets
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectAuditSecretsParams;
+
+/// Parameters for building the memory primer for `CLAUDE.md`
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContextPackParams {
+  /// Token budget for the primer (default: 4000)
+  pub budget: Option<usize>,
+  /// Write the primer into the file's managed section
+  #[serde(default)]
+  pub write: bool,
+  /// Absolute path of the file to write. If None, `CLAUDE.md` at the project root.
+  pub file: Option<String>,
+}
+
+/// Parameters for the query analytics report
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SearchStatsParams {
+  /// Days of searches to cover (default: 30)
+  pub days: Option<u32>,
+  /// Queries listed in each table (default: 10)
+  pub limit: Option<usize>,
+}
+
 /// Parameters for session list request
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -32,6 +78,28 @@ pub struct SessionListParams {
   pub active_only: Option<bool>,
 }
 
+/// Parameters for showing a session and the memories it touched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionShowParams {
+  /// Session ID or prefix
+  pub session_id: String,
+}
+
+/// Parameters for listing the memories hooks injected into a session
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionInjectionsParams {
+  /// Session ID or prefix. If None, the most recently started active session.
+  pub session_id: Option<String>,
+}
+
+/// Parameters for importing a Claude Code session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionImportParams {
+  /// Absolute path to the transcript JSONL file
+  pub path: String,
+}
+
 /// Parameters for project info request
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -48,6 +116,48 @@ pub struct ProjectCleanParams {
   pub project: Option<String>,
 }
 
+/// Parameters for moving a project's data to its new location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMigrateParams {
+  /// Old project path, project ID or ID prefix
+  pub from: String,
+  /// New project path
+  pub to: String,
+}
+
+/// Parameters for merging the store of one clone into another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMergeParams {
+  /// Project path, ID or ID prefix whose memories are moved and whose store is removed
+  pub from: String,
+  /// Project path, ID or ID prefix whose store is kept
+  pub into: String,
+}
+
+/// Parameters for writing a project snapshot archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExportParams {
+  /// Archive to write (`.tar.zst`)
+  pub path: String,
+  /// Project path, ID or ID prefix. If None, uses cwd from request.
+  pub project: Option<String>,
+  /// Overwrite an existing archive
+  #[serde(default)]
+  pub force: bool,
+  /// Keep private memories, for moving a project between one's own machines
+  #[serde(default)]
+  pub include_private: bool,
+}
+
+/// Parameters for restoring a project snapshot archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectImportParams {
+  /// Archive to read
+  pub path: String,
+  /// Project path to import into. If None, uses cwd from request.
+  pub project: Option<String>,
+}
+
 // ============================================================================
 // Response types
 // ============================================================================
@@ -62,6 +172,18 @@ pub enum ProjectResponse {
   CleanAll(ProjectCleanAllResult),
   Stats(ProjectStatsResult),
   Sessions(Vec<SessionItem>),
+  SessionShow(SessionShowResult),
+  SessionInjections(SessionInjectionsResult),
+  SessionImport(SessionImportResult),
+  Migrate(ProjectMigrateResult),
+  Duplicates(Vec<ProjectDuplicateGroup>),
+  Merge(ProjectMergeResult),
+  Export(ProjectExportResult),
+  Import(ProjectImportResult),
+  Repair(ProjectRepairResult),
+  AuditSecrets(ProjectAuditSecretsResult),
+  ContextPack(ContextPackResult),
+  SearchStats(SearchStatsResult),
 }
 
 /// Lightweight project item for list responses
@@ -72,6 +194,16 @@ pub struct ProjectListItem {
   pub name: String,
 }
 
+impl From<crate::domain::project::ProjectMetadata> for ProjectListItem {
+  fn from(project: crate::domain::project::ProjectMetadata) -> Self {
+    Self {
+      id: project.id.to_string(),
+      path: project.path.to_string_lossy().to_string(),
+      name: project.name,
+    }
+  }
+}
+
 /// Detailed project info response
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +216,10 @@ pub struct ProjectInfoResult {
   pub document_count: usize,
   pub session_count: usize,
   pub db_path: String,
+  /// Recorded projects whose root vanished and that may be this one, moved
+  pub moved_from: Option<Vec<ProjectListItem>>,
+  /// Other recorded clones of the same repository
+  pub duplicates: Option<Vec<ProjectListItem>>,
 }
 
 /// Result from cleaning a single project
@@ -95,6 +231,186 @@ pub struct ProjectCleanResult {
   pub documents_deleted: usize,
 }
 
+/// Result of moving a project's data
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMigrateResult {
+  pub from_id: String,
+  pub to_id: String,
+  /// Old root, when it was recorded or given as a path
+  pub from_path: Option<String>,
+  pub to_path: String,
+  /// Memories whose file paths or scope were rewritten
+  pub memories_updated: usize,
+  /// Documents whose source path was rewritten
+  pub documents_updated: usize,
+}
+
+/// Recorded projects that are clones of one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDuplicateGroup {
+  /// Richest index first; merging keeps the first project's store
+  pub projects: Vec<ProjectDuplicateItem>,
+}
+
+/// One clone in a duplicate group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDuplicateItem {
+  pub id: String,
+  pub path: String,
+  pub name: String,
+  pub memories: usize,
+  pub indexed_files: usize,
+}
+
+/// Result of merging two project stores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMergeResult {
+  pub kept_id: String,
+  pub kept_path: String,
+  pub removed_id: String,
+  pub removed_path: String,
+  pub memories_copied: usize,
+  /// Memories the kept store already had
+  pub duplicates_skipped: usize,
+}
+
+/// Result of writing a project snapshot
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExportResult {
+  pub project_id: String,
+  pub project_path: Option<String>,
+  pub archive: String,
+  /// Compressed archive size
+  pub bytes: u64,
+  pub tables: Vec<String>,
+  /// The project's `.claude/ccengram.toml` was included
+  pub config_included: bool,
+  /// Private memories left out of the archive
+  pub private_excluded: usize,
+}
+
+/// Result of restoring a project snapshot
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectImportResult {
+  /// Project the snapshot was exported from
+  pub from_id: String,
+  pub to_id: String,
+  pub from_path: Option<String>,
+  pub to_path: String,
+  /// Version of ccengram that wrote the snapshot
+  pub ccengram_version: String,
+  pub exported_at: String,
+  pub tables: Vec<String>,
+  /// Memories whose file paths or scope were rewritten
+  pub memories_updated: usize,
+  /// Documents whose source path was rewritten
+  pub documents_updated: usize,
+  /// The snapshot's config was written because the project had none
+  pub config_restored: bool,
+}
+
+/// Result of repairing quarantined tables
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectRepairResult {
+  pub tables: Vec<RepairedTableItem>,
+  /// Code tables were rebuilt empty and need a full re-index
+  pub reindex_code: bool,
+  /// Document tables could not be salvaged and need re-ingesting
+  pub reingest_docs: bool,
+}
+
+/// What repair did for one quarantined table
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairedTableItem {
+  pub table: String,
+  pub quarantined_at: String,
+  /// Why the table was quarantined
+  pub reason: String,
+  /// "salvaged" | "rebuilt" | "reindex" | "unrecovered"
+  pub status: String,
+  /// Table version the rows were copied from
+  pub salvaged_version: Option<u64>,
+  pub salvaged_rows: Option<usize>,
+  /// Where the quarantined copy was kept, when nothing could be recovered
+  pub kept_at: Option<String>,
+}
+
+/// Secrets found in data stored before redaction was enabled
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectAuditSecretsResult {
+  pub memories_scanned: usize,
+  pub code_chunks_scanned: usize,
+  pub document_chunks_scanned: usize,
+  pub findings: Vec<SecretFindingItem>,
+}
+
+/// One secret found by the audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretFindingItem {
+  /// "memory" | "code" | "document"
+  pub item_type: String,
+  pub id: String,
+  /// `path:line` for code, the source for documents, the field for memories
+  pub location: String,
+  /// Detector that found it, e.g. `aws_access_key`
+  pub kind: String,
+  /// The first few characters of the secret
+  pub preview: String,
+}
+
+/// Memory primer built for `CLAUDE.md`
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextPackResult {
+  pub markdown: String,
+  /// Estimated tokens in `markdown`
+  pub tokens: usize,
+  /// Memories listed
+  pub memories: usize,
+  /// Memories left out to stay within the budget
+  pub omitted: usize,
+  /// File the primer was written to, when writing was asked for
+  pub path: Option<String>,
+  /// Whether the file changed
+  pub written: bool,
+}
+
+/// What has been searched and what came back empty, from the search log
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchStatsResult {
+  pub days: u32,
+  pub searches: usize,
+  /// Searches that returned nothing
+  pub zero_result_searches: usize,
+  /// Searches with at least one result opened or marked helpful
+  pub clicked_searches: usize,
+  /// Mean result similarity across searches that scored their results
+  pub avg_similarity: Option<f32>,
+  /// Most frequent queries
+  pub top_queries: Vec<SearchQueryStat>,
+  /// Most frequent queries that found nothing
+  pub zero_result_queries: Vec<SearchQueryStat>,
+}
+
+/// One query, grouped case-insensitively
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchQueryStat {
+  pub query: String,
+  /// Kinds searched: memory, code, docs or explore
+  pub kinds: Vec<String>,
+  pub count: usize,
+  pub avg_results: f32,
+  pub avg_similarity: Option<f32>,
+  pub clicks: u32,
+  pub last_searched: String,
+}
+
 /// Result from cleaning all projects
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectCleanAllResult {
@@ -112,9 +428,26 @@ pub struct ProjectStatsResult {
   pub documents: usize,
   pub sessions: usize,
   /// Memory count by sector (semantic, episodic, procedural, reflective)
-  pub memories_by_sector: Option<std::collections::HashMap<String, usize>>,
+  pub memories_by_sector: Option<std::collections::BTreeMap<String, usize>>,
   /// Average salience across all memories
   pub average_salience: Option<f32>,
+  /// Per-table storage and vector index health
+  pub tables: Option<Vec<TableStatsItem>>,
+}
+
+/// Storage and index statistics for one database table
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStatsItem {
+  pub name: String,
+  pub rows: usize,
+  pub bytes: usize,
+  pub fragments: usize,
+  pub small_fragments: usize,
+  /// Vector index type (e.g. IVF_HNSW_SQ), if the table has one
+  pub vector_index: Option<String>,
+  pub indexed_rows: usize,
+  pub unindexed_rows: usize,
 }
 
 /// Session item for list responses
@@ -126,6 +459,76 @@ pub struct SessionItem {
   pub ended_at: Option<String>,
   pub summary: Option<String>,
   pub user_prompt: Option<String>,
+  /// Distinct memories linked to the session
+  pub memory_count: Option<usize>,
+}
+
+/// A session and the memories it created or used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionShowResult {
+  pub session: SessionItem,
+  /// Memory links in the order they were recorded
+  pub memories: Vec<SessionMemoryItem>,
+}
+
+/// A memory linked to a session
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMemoryItem {
+  pub memory_id: String,
+  /// How the session used the memory: created, recalled, updated or reinforced
+  pub usage_type: String,
+  /// Hook event that recorded the link
+  pub hook_event: Option<String>,
+  pub linked_at: String,
+  /// Memory content, or None if the memory no longer exists
+  pub content: Option<String>,
+  pub summary: Option<String>,
+  pub memory_type: Option<String>,
+  pub sector: Option<String>,
+  pub is_deleted: bool,
+}
+
+/// Memories injected into a session's context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInjectionsResult {
+  pub session_id: String,
+  /// Injections in the order they were made
+  pub injections: Vec<SessionInjectionItem>,
+}
+
+/// A memory a hook injected into a session, and why
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInjectionItem {
+  pub memory_id: String,
+  /// Hook event whose context carried the memory
+  pub hook_event: String,
+  /// Why it was picked: relevance, scope, goal, compaction, edit_warning or topical
+  pub rule: String,
+  /// Rank score for relevance and scope, salience otherwise
+  pub score: Option<f32>,
+  /// Rule-specific detail, e.g. the file an edit warning was for
+  pub detail: Option<String>,
+  pub injected_at: String,
+  /// Memory content, or None if the memory no longer exists
+  pub content: Option<String>,
+  pub summary: Option<String>,
+  pub memory_type: Option<String>,
+  pub is_deleted: bool,
+}
+
+/// Result of importing a session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionImportResult {
+  pub session_id: String,
+  /// User turns reconstructed from the transcript
+  pub turns: usize,
+  /// Turns with enough work to run extraction on
+  pub segments_extracted: usize,
+  pub memories_created: Vec<String>,
+  /// Transcript lines that could not be parsed
+  pub skipped_lines: usize,
 }
 
 // ============================================================================
@@ -161,9 +564,81 @@ impl_ipc_request!(
   v => RequestData::Project(ProjectRequest::CleanAll(v)),
   v => ResponseData::Project(ProjectResponse::CleanAll(v))
 );
+impl_ipc_request!(
+  ProjectMigrateParams => ProjectMigrateResult,
+  ResponseData::Project(ProjectResponse::Migrate(v)) => v,
+  v => RequestData::Project(ProjectRequest::Migrate(v)),
+  v => ResponseData::Project(ProjectResponse::Migrate(v))
+);
+impl_ipc_request!(
+  ProjectDuplicatesParams => Vec<ProjectDuplicateGroup>,
+  ResponseData::Project(ProjectResponse::Duplicates(v)) => v,
+  v => RequestData::Project(ProjectRequest::Duplicates(v)),
+  v => ResponseData::Project(ProjectResponse::Duplicates(v))
+);
+impl_ipc_request!(
+  ProjectMergeParams => ProjectMergeResult,
+  ResponseData::Project(ProjectResponse::Merge(v)) => v,
+  v => RequestData::Project(ProjectRequest::Merge(v)),
+  v => ResponseData::Project(ProjectResponse::Merge(v))
+);
+impl_ipc_request!(
+  ProjectExportParams => ProjectExportResult,
+  ResponseData::Project(ProjectResponse::Export(v)) => v,
+  v => RequestData::Project(ProjectRequest::Export(v)),
+  v => ResponseData::Project(ProjectResponse::Export(v))
+);
+impl_ipc_request!(
+  ProjectImportParams => ProjectImportResult,
+  ResponseData::Project(ProjectResponse::Import(v)) => v,
+  v => RequestData::Project(ProjectRequest::Import(v)),
+  v => ResponseData::Project(ProjectResponse::Import(v))
+);
+impl_ipc_request!(
+  ProjectRepairParams => ProjectRepairResult,
+  ResponseData::Project(ProjectResponse::Repair(v)) => v,
+  v => RequestData::Project(ProjectRequest::Repair(v)),
+  v => ResponseData::Project(ProjectResponse::Repair(v))
+);
+impl_ipc_request!(
+  ProjectAuditSecretsParams => ProjectAuditSecretsResult,
+  ResponseData::Project(ProjectResponse::AuditSecrets(v)) => v,
+  v => RequestData::Project(ProjectRequest::AuditSecrets(v)),
+  v => ResponseData::Project(ProjectResponse::AuditSecrets(v))
+);
+impl_ipc_request!(
+  ContextPackParams => ContextPackResult,
+  ResponseData::Project(ProjectResponse::ContextPack(v)) => v,
+  v => RequestData::Project(ProjectRequest::ContextPack(v)),
+  v => ResponseData::Project(ProjectResponse::ContextPack(v))
+);
+impl_ipc_request!(
+  SearchStatsParams => SearchStatsResult,
+  ResponseData::Project(ProjectResponse::SearchStats(v)) => v,
+  v => RequestData::Project(ProjectRequest::SearchStats(v)),
+  v => ResponseData::Project(ProjectResponse::SearchStats(v))
+);
 impl_ipc_request!(
   SessionListParams => Vec<SessionItem>,
   ResponseData::Project(ProjectResponse::Sessions(v)) => v,
   v => RequestData::Project(ProjectRequest::Sessions(v)),
   v => ResponseData::Project(ProjectResponse::Sessions(v))
 );
+impl_ipc_request!(
+  SessionShowParams => SessionShowResult,
+  ResponseData::Project(ProjectResponse::SessionShow(v)) => v,
+  v => RequestData::Project(ProjectRequest::SessionShow(v)),
+  v => ResponseData::Project(ProjectResponse::SessionShow(v))
+);
+impl_ipc_request!(
+  SessionInjectionsParams => SessionInjectionsResult,
+  ResponseData::Project(ProjectResponse::SessionInjections(v)) => v,
+  v => RequestData::Project(ProjectRequest::SessionInjections(v)),
+  v => ResponseData::Project(ProjectResponse::SessionInjections(v))
+);
+impl_ipc_request!(
+  SessionImportParams => SessionImportResult,
+  ResponseData::Project(ProjectResponse::SessionImport(v)) => v,
+  v => RequestData::Project(ProjectRequest::SessionImport(v)),
+  v => ResponseData::Project(ProjectResponse::SessionImport(v))
+);
@@ -13,6 +13,10 @@ pub struct ExploreParams {
   pub expand_top: Option<usize>,
   pub limit: Option<usize>,
   pub depth: Option<usize>,
+  /// Search generated code too (default: false)
+  pub include_generated: Option<bool>,
+  /// Add the query and its top results to the draft exploration
+  pub record: Option<bool>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -23,6 +27,29 @@ pub struct ContextParams {
   pub depth: Option<usize>,
 }
 
+/// Parameters for building a token-budgeted context pack
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackParams {
+  pub query: String,
+  /// Token budget for the packed content (default 8000)
+  pub budget: Option<usize>,
+  pub scope: Option<String>, // "code" | "memory" | "docs" | "all"
+}
+
+/// Parameters for a planned, multi-hop context pack for a task
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorePlanParams {
+  /// What the caller is about to work on
+  pub task: String,
+  /// Token budget for the packed content (default 8000)
+  pub budget: Option<usize>,
+  pub scope: Option<String>, // "code" | "memory" | "docs" | "all"
+  /// Call-graph hops from the best code results (default 1, max 3)
+  pub hops: Option<usize>,
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExploreResult {
@@ -45,6 +72,8 @@ pub struct ExploreResultItem {
   pub line: Option<u32>,
   #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub symbols: Vec<String>,
+  /// Results from this file left out by `search.max_per_file`
+  pub more_in_file: Option<usize>,
   pub hints: Option<ExploreHints>,
   pub context: Option<ExploreContext>,
 }
@@ -104,6 +133,54 @@ pub struct ContextItem {
   pub related_memories: Option<Vec<super::memory::MemoryItem>>,
 }
 
+/// Memories, code and doc excerpts relevant to a query, fitted to a token budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackResult {
+  pub query: String,
+  pub budget: usize,
+  /// Estimated tokens used by the packed items
+  pub tokens: usize,
+  /// Items in pack order: memories, then code, then docs, best first
+  pub items: Vec<PackItem>,
+  /// Relevant results left out because the budget was spent
+  pub omitted: usize,
+  /// Searches and call-graph hops run by a planned pack, in order
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub steps: Vec<PackStep>,
+}
+
+/// One search or call-graph hop of a planned pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackStep {
+  pub kind: String, // "search" | "callers" | "callees"
+  /// Query searched for, or the symbol whose neighbours were followed
+  pub target: String,
+  /// Results the step found, before deduplication
+  pub found: usize,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackItem {
+  pub id: String,
+  pub item_type: String, // "memory" | "code" | "doc"
+  /// Memory type, code symbol or document title
+  pub title: String,
+  /// File path or document source
+  pub source: Option<String>,
+  pub lines: Option<(u32, u32)>,
+  pub language: Option<String>,
+  pub content: String,
+  pub tokens: usize,
+  pub score: f32,
+  /// Content was cut to fit the remaining budget
+  #[serde(default)]
+  pub truncated: bool,
+  /// How a planned pack found the item, e.g. "search: billing" or "callee of charge_card"
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub provenance: Vec<String>,
+}
+
 impl_ipc_request!(
   ExploreParams => ExploreResult,
   ResponseData::Explore(v) => v,
@@ -116,3 +193,14 @@ impl_ipc_request!(
   v => RequestData::Context(v),
   v => ResponseData::Context(v)
 );
+impl_ipc_request!(
+  PackParams => PackResult,
+  ResponseData::Pack(v) => v,
+  v => RequestData::Pack(v),
+  v => ResponseData::Pack(v)
+);
+impl_ipc_request!(
+  ExplorePlanParams => PackResult,
+  ResponseData::Pack(v) => v,
+  v => RequestData::ExplorePlan(v)
+);
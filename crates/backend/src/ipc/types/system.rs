@@ -15,6 +15,8 @@ pub enum SystemRequest {
   Status(StatusParams),
   ProjectStats(ProjectStatsParams),
   Resolve(ResolveParams),
+  Usage(UsageParams),
+  Egress(EgressParams),
 }
 
 #[serde_with::skip_serializing_none]
@@ -28,6 +30,8 @@ pub enum SystemResponse {
   Status(StatusResult),
   ProjectStats(super::project::ProjectStatsResult),
   Resolve(ResolveResult),
+  Usage(UsageResult),
+  Egress(EgressResult),
 }
 
 // ============================================================================
@@ -57,6 +61,20 @@ pub struct ResolveParams {
   pub id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UsageParams {
+  /// Days of history to include (default: 30)
+  pub days: Option<u32>,
+  /// Only include usage from projects whose path contains this
+  pub project: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EgressParams {
+  /// Days of history to include (default: 7)
+  pub days: Option<u32>,
+}
+
 // ============================================================================
 // Status result
 // ============================================================================
@@ -64,6 +82,7 @@ pub struct ResolveParams {
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusResult {
+  /// "running" | "shutting_down"
   pub status: String,
   pub version: String,
   pub pid: u32,
@@ -164,6 +183,75 @@ pub struct ResolveResult {
   pub entity_type: String,
 }
 
+// ============================================================================
+// Usage result
+// ============================================================================
+
+/// Token counts and cost of a group of provider calls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+  pub requests: u64,
+  pub input_tokens: u64,
+  pub output_tokens: u64,
+  /// Sum of known costs; calls without a cost count as free
+  pub cost_usd: f64,
+}
+
+/// LLM extraction and embedding usage of one project on one day
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageDayItem {
+  /// Day in `YYYY-MM-DD` (UTC)
+  pub date: String,
+  pub project: Option<String>,
+  pub llm: UsageTotals,
+  pub embedding: UsageTotals,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageResult {
+  pub days: u32,
+  /// Per-day, per-project rows, newest day first
+  pub rows: Vec<UsageDayItem>,
+  pub llm: UsageTotals,
+  pub embedding: UsageTotals,
+  /// Spend in the current calendar month (UTC), across all projects
+  pub month_cost_usd: f64,
+  pub monthly_budget_usd: Option<f64>,
+  pub budget_exceeded: bool,
+}
+
+// ============================================================================
+// Egress result
+// ============================================================================
+
+/// Text sent to one provider and model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EgressItem {
+  /// "llm" or "embedding"
+  pub kind: String,
+  pub provider: String,
+  pub model: String,
+  /// Embedded chunks, or LLM calls
+  pub requests: u64,
+  pub chars: u64,
+  pub input_tokens: u64,
+  /// Paths of the projects whose content was sent
+  pub projects: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressResult {
+  pub days: u32,
+  /// Whether the daemon runs in strict local mode
+  pub strict_local: bool,
+  /// Providers off this machine, most characters first
+  pub remote: Vec<EgressItem>,
+  /// Providers on this machine, for comparison
+  pub local: Vec<EgressItem>,
+}
+
 // ============================================================================
 // IpcRequest implementations
 // ============================================================================
@@ -214,3 +302,15 @@ impl_ipc_request!(
   v => RequestData::System(SystemRequest::Resolve(v)),
   v => ResponseData::System(SystemResponse::Resolve(v))
 );
+impl_ipc_request!(
+  UsageParams => UsageResult,
+  ResponseData::System(SystemResponse::Usage(v)) => v,
+  v => RequestData::System(SystemRequest::Usage(v)),
+  v => ResponseData::System(SystemResponse::Usage(v))
+);
+impl_ipc_request!(
+  EgressParams => EgressResult,
+  ResponseData::System(SystemResponse::Egress(v)) => v,
+  v => RequestData::System(SystemRequest::Egress(v)),
+  v => ResponseData::System(SystemResponse::Egress(v))
+);
@@ -17,10 +17,17 @@ pub enum WatchRequest {
   Start(WatchStartParams),
   Stop(WatchStopParams),
   Status(WatchStatusParams),
+  Pause(WatchPauseParams),
+  Resume(WatchResumeParams),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct WatchStartParams;
+pub struct WatchStartParams {
+  /// Further workspace roots to watch in the same session, absolute or
+  /// relative to the request's cwd. Each root is indexed as its own project.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WatchStopParams;
@@ -28,6 +35,12 @@ pub struct WatchStopParams;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WatchStatusParams;
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WatchPauseParams;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WatchResumeParams;
+
 // ============================================================================
 // Response types
 // ============================================================================
@@ -39,6 +52,8 @@ pub enum WatchResponse {
   Status(WatchStatusResult),
   Start(WatchStartResult),
   Stop(WatchStopResult),
+  Pause(WatchPauseResult),
+  Resume(WatchResumeResult),
 }
 
 #[serde_with::skip_serializing_none]
@@ -48,8 +63,17 @@ pub struct WatchStatusResult {
   pub root: Option<String>,
   pub pending_changes: usize,
   pub project_id: String,
+  /// Whether indexing of file changes is paused
+  #[serde(default)]
+  pub paused: bool,
   pub scanning: bool,
   pub scan_progress: Option<[usize; 2]>,
+  /// The last event storm coalesced into a rescan
+  #[serde(default)]
+  pub last_storm: Option<WatchStormInfo>,
+  /// Status of the other roots watched in the same session
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<WatchStatusResult>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -60,6 +84,21 @@ pub struct WatchStartResult {
   pub project_id: String,
   /// Startup scan results (if project was previously indexed)
   pub startup_scan: Option<StartupScanInfo>,
+  /// Results for the other roots watched in the same session
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<WatchStartResult>,
+}
+
+/// A burst of file events (e.g. `git checkout`) that the watcher coalesced
+/// into one rescan instead of indexing file by file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchStormInfo {
+  /// Changes seen while the storm lasted
+  pub changes: usize,
+  /// How long the storm lasted
+  pub duration_ms: u64,
+  /// Seconds since the storm settled
+  pub secs_ago: u64,
 }
 
 /// Information about the startup scan performed when watcher starts
@@ -86,6 +125,33 @@ pub struct WatchStopResult {
   pub status: String,
   pub path: String,
   pub project_id: String,
+  /// Results for the other roots watched in the same session
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<WatchStopResult>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchPauseResult {
+  pub status: String,
+  pub path: String,
+  pub project_id: String,
+  /// Results for the other roots watched in the same session
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<WatchPauseResult>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchResumeResult {
+  pub status: String,
+  pub path: String,
+  pub project_id: String,
+  /// Changes made while paused, found by the reconciliation scan
+  pub reconcile_scan: Option<StartupScanInfo>,
+  /// Results for the other roots watched in the same session
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub roots: Vec<WatchResumeResult>,
 }
 
 impl_ipc_request!(
@@ -106,3 +172,15 @@ impl_ipc_request!(
   v => RequestData::Watch(WatchRequest::Status(v)),
   v => ResponseData::Watch(WatchResponse::Status(v))
 );
+impl_ipc_request!(
+  WatchPauseParams => WatchPauseResult,
+  ResponseData::Watch(WatchResponse::Pause(v)) => v,
+  v => RequestData::Watch(WatchRequest::Pause(v)),
+  v => ResponseData::Watch(WatchResponse::Pause(v))
+);
+impl_ipc_request!(
+  WatchResumeParams => WatchResumeResult,
+  ResponseData::Watch(WatchResponse::Resume(v)) => v,
+  v => RequestData::Watch(WatchRequest::Resume(v)),
+  v => ResponseData::Watch(WatchResponse::Resume(v))
+);
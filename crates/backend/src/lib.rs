@@ -5,15 +5,19 @@ mod embedding;
 mod rerank;
 mod server;
 mod service;
+mod usage;
 
 mod domain;
 pub use domain::{config, project};
 
+pub mod data_dir;
 pub mod dirs;
+pub mod disk;
 pub mod ipc;
 
 mod daemon;
 pub use daemon::{Daemon, RuntimeConfig};
+pub use embedding::{EmbeddingError, EmbeddingMode, EmbeddingProvider};
 //
 // --- all the different lovely profiling tools ---
 //
@@ -27,16 +27,13 @@
 //! ```
 
 use std::{
-  path::PathBuf,
+  path::{Path, PathBuf},
   sync::{Arc, atomic::AtomicU64},
 };
 
 use futures::{SinkExt, StreamExt};
 use tokio::net::{UnixListener, UnixStream};
-use tokio_util::{
-  codec::{Framed, LinesCodec},
-  sync::CancellationToken,
-};
+use tokio_util::{codec::Framed, sync::CancellationToken};
 use tracing::{debug, error, info, trace, warn};
 
 use crate::{
@@ -48,12 +45,31 @@ use crate::{
     },
     message::{ProjectActorPayload, ProjectActorResponse},
   },
+  domain::{
+    config::Config,
+    memory::MemoryActor,
+    project::{ProjectId, resolve_project_path},
+  },
   ipc::{
     IpcError, Request, RequestData, Response, ResponseData,
+    framing::{self, MessageCodec},
+    hook::HookParams,
+    project::{
+      ProjectExportParams, ProjectExportResult, ProjectImportParams, ProjectImportResult, ProjectMergeParams,
+      ProjectMergeResult, ProjectMigrateParams, ProjectMigrateResult, ProjectRequest, ProjectResponse,
+    },
     system::{
       DaemonMetrics, EmbeddingProviderInfo, MemoryUsageMetrics, MetricsResult, ProjectsMetrics, RequestsMetrics,
       SessionsMetrics, StatusResult, SystemRequest, SystemResponse,
     },
+    watch::{
+      WatchPauseResult, WatchRequest, WatchResponse, WatchResumeResult, WatchStartParams, WatchStartResult,
+      WatchStatusResult, WatchStopResult,
+    },
+  },
+  service::{
+    project::{merge, migrate, snapshot},
+    util::ServiceError,
   },
 };
 
@@ -287,13 +303,17 @@ async fn handle_connection(
   cancel: CancellationToken,
 ) -> Result<(), IpcError> {
   debug!("Client connected");
-  let framed = Framed::new(stream, LinesCodec::new());
-  let (mut sink, mut stream) = framed.split();
+  let mut framed = Framed::new(stream, MessageCodec::new());
+  // A client may ask for binary framing before its first request
+  let first = framing::accept(&mut framed).await?;
+  let framing = framed.codec().framing();
+  let (mut sink, stream) = framed.split();
+  let mut stream = futures::stream::iter(first.map(Ok)).chain(stream);
   let mut request_count = 0u64;
 
   while let Some(result) = stream.next().await {
-    let line = match result {
-      Ok(l) => l,
+    let frame = match result {
+      Ok(f) => f,
       Err(e) => {
         warn!(error = %e, "Error reading from client");
         break;
@@ -304,19 +324,17 @@ async fn handle_connection(
     activity.touch();
     request_count += 1;
 
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
+    if frame.trim_ascii().is_empty() {
       continue;
     }
 
     // Parse request
-    let request: Request = match serde_json::from_str(trimmed) {
+    let request: Request = match framing.decode(&frame) {
       Ok(r) => r,
       Err(e) => {
-        warn!("Invalid request JSON: {}", e);
+        warn!("Invalid request: {}", e);
         let response = Response::rpc_error("unknown", -32700, format!("Parse error: {}", e));
-        let json = serde_json::to_string(&response)?;
-        sink.send(json).await?;
+        sink.send(framing.encode(&response)?).await?;
         continue;
       }
     };
@@ -325,25 +343,17 @@ async fn handle_connection(
     trace!(method = ?request.data, id = %request.id, cwd = %request.cwd, "Processing request");
 
     // Track sessions for lifecycle management
-    if let RequestData::Hook(ref params) = request.data
-      && let Some(ref session_id) = params.session_id
-    {
-      let sid = SessionId::from(session_id.as_str());
-      match params.hook_name.as_str() {
-        "SessionStart" => {
-          sessions.register(sid).await;
-        }
-        "SessionEnd" => {
-          sessions.unregister(&sid).await;
-        }
-        _ => {
-          // Touch session on any other hook to keep it alive
-          sessions.touch(&sid).await;
+    match request.data {
+      RequestData::Hook(ref params) => track_session(&sessions, params).await,
+      RequestData::HookBatch(ref batch) => {
+        for params in &batch.events {
+          track_session(&sessions, params).await;
         }
       }
+      _ => {}
     }
 
-    // Handle daemon-level system requests directly (Status, Metrics, Shutdown)
+    // Handle daemon-level system requests directly (Ping, Status, Metrics, Shutdown, Usage)
     // These don't need a project context
     if let RequestData::System(ref sys_req) = request.data
       && let Some(response) = handle_daemon_request(
@@ -357,21 +367,35 @@ async fn handle_connection(
       )
       .await
     {
-      let json = serde_json::to_string(&response)?;
-      sink.send(json).await?;
+      sink.send(framing.encode(&response)?).await?;
       let elapsed = start.elapsed();
       debug!(id = %request.id, elapsed_ms = elapsed.as_millis() as u64, "Daemon request completed");
       continue;
     }
 
+    // Migrating and merging span several project stores, so they can't run inside one actor
+    if let RequestData::Project(ref project_req) = request.data
+      && let Some(response) = handle_store_request(&request.id, project_req, &router, &request.cwd).await
+    {
+      sink.send(framing.encode(&response)?).await?;
+      continue;
+    }
+
+    // A watch session spanning several workspace roots fans out to each root's project
+    if let RequestData::Watch(ref watch_req) = request.data
+      && let Some(response) = handle_watch_request(&request.id, watch_req, &router, &request.cwd).await
+    {
+      sink.send(framing.encode(&response)?).await?;
+      continue;
+    }
+
     // Get or create project actor for this request's cwd
     let project_path = PathBuf::from(&request.cwd);
     let handle = match router.get_or_create(&project_path).await {
       Ok(h) => h,
       Err(e) => {
         let response = Response::rpc_error(&request.id, -32000, format!("Failed to get project: {}", e));
-        let json = serde_json::to_string(&response)?;
-        sink.send(json).await?;
+        sink.send(framing.encode(&response)?).await?;
         continue;
       }
     };
@@ -380,12 +404,12 @@ async fn handle_connection(
     let payload = ProjectActorPayload::Request(request.data);
 
     // Send request to project actor and get response channel
-    let mut reply_rx = match handle.send(request.id.clone(), payload).await {
+    let actor = request.actor.unwrap_or(MemoryActor::Cli);
+    let mut reply_rx = match handle.send_as(request.id.clone(), payload, actor).await {
       Ok(rx) => rx,
       Err(e) => {
         let response = Response::rpc_error(&request.id, -32000, format!("Failed to send to actor: {}", e));
-        let json = serde_json::to_string(&response)?;
-        sink.send(json).await?;
+        sink.send(framing.encode(&response)?).await?;
         continue;
       }
     };
@@ -393,8 +417,7 @@ async fn handle_connection(
     // Stream responses until we get a final one
     while let Some(response) = reply_rx.recv().await {
       let ipc_response = convert_actor_response(&request.id, response.clone());
-      let json = serde_json::to_string(&ipc_response)?;
-      sink.send(json).await?;
+      sink.send(framing.encode(&ipc_response)?).await?;
 
       if response.is_final() {
         break;
@@ -413,6 +436,26 @@ async fn handle_connection(
   Ok(())
 }
 
+/// Register, end or keep alive the session a hook event belongs to
+async fn track_session(sessions: &SessionTracker, params: &HookParams) {
+  let Some(ref session_id) = params.session_id else {
+    return;
+  };
+  let sid = SessionId::from(session_id.as_str());
+  match params.hook_name.as_str() {
+    "SessionStart" => {
+      sessions.register(sid).await;
+    }
+    "SessionEnd" => {
+      sessions.unregister(&sid).await;
+    }
+    _ => {
+      // Touch session on any other hook to keep it alive
+      sessions.touch(&sid).await;
+    }
+  }
+}
+
 /// Convert an actor response to an IPC response.
 ///
 /// This handles the different response types:
@@ -448,6 +491,224 @@ fn convert_actor_response(request_id: &str, response: ProjectActorResponse) -> R
   }
 }
 
+/// Handle project requests that move data into, out of or between project stores.
+///
+/// Returns `None` for requests that should be routed to a ProjectActor.
+async fn handle_store_request(
+  request_id: &str,
+  request: &ProjectRequest,
+  router: &ProjectRouter,
+  cwd: &str,
+) -> Option<Response> {
+  let cwd = Path::new(cwd);
+  let result = match request {
+    ProjectRequest::Migrate(params) => migrate_project(router, params, cwd).await.map(ProjectResponse::Migrate),
+    ProjectRequest::Duplicates(_) => merge::duplicates(router.data_dir())
+      .await
+      .map(ProjectResponse::Duplicates),
+    ProjectRequest::Merge(params) => merge_projects(router, params, cwd).await.map(ProjectResponse::Merge),
+    ProjectRequest::Export(params) => export_project(router, params, cwd).await.map(ProjectResponse::Export),
+    ProjectRequest::Import(params) => import_project(router, params, cwd).await.map(ProjectResponse::Import),
+    _ => return None,
+  };
+
+  Some(match result {
+    Ok(response) => Response::success(request_id, ResponseData::Project(response)),
+    Err(e) => Response::rpc_error(request_id, e.code(), e.to_string()),
+  })
+}
+
+/// Move a project's data to its new location.
+///
+/// Both the old and the new project's actors are stopped first; the next
+/// request for the new path spawns an actor on the moved data.
+async fn migrate_project(
+  router: &ProjectRouter,
+  params: &ProjectMigrateParams,
+  cwd: &Path,
+) -> Result<ProjectMigrateResult, ServiceError> {
+  let plan = migrate::prepare(router.data_dir(), params, cwd).await?;
+  router.stop_project(&plan.from.id).await;
+  router.stop_project(&plan.to_id).await;
+  migrate::run(router.data_dir(), &plan).await
+}
+
+/// Merge one clone's store into another's.
+///
+/// Both projects' actors are stopped first; requests for the merged clone
+/// are routed to the kept project afterwards.
+async fn merge_projects(
+  router: &ProjectRouter,
+  params: &ProjectMergeParams,
+  cwd: &Path,
+) -> Result<ProjectMergeResult, ServiceError> {
+  let plan = merge::prepare(router.data_dir(), params, cwd).await?;
+  router.stop_project(&plan.from.id).await;
+  router.stop_project(&plan.into.id).await;
+  merge::run(router.data_dir(), &plan).await
+}
+
+/// Write a project's snapshot archive.
+///
+/// The actor is stopped first so the archive holds a consistent copy of the
+/// tables; the next request for the project spawns it again.
+async fn export_project(
+  router: &ProjectRouter,
+  params: &ProjectExportParams,
+  cwd: &Path,
+) -> Result<ProjectExportResult, ServiceError> {
+  let plan = snapshot::prepare_export(router.data_dir(), params, cwd).await?;
+  router.stop_project(&plan.project.id).await;
+  snapshot::export(router.data_dir(), &plan).await
+}
+
+/// Restore a snapshot archive into a project.
+///
+/// The target's actor is stopped first; the next request for it spawns an
+/// actor on the imported data.
+async fn import_project(
+  router: &ProjectRouter,
+  params: &ProjectImportParams,
+  cwd: &Path,
+) -> Result<ProjectImportResult, ServiceError> {
+  let plan = snapshot::prepare_import(params, cwd).await?;
+  router.stop_project(&plan.to_id).await;
+  snapshot::import(router.data_dir(), &plan).await
+}
+
+/// Handle watch requests for a session that watches several workspace roots.
+///
+/// Each root is its own project, with its own watcher, gitignore rules and
+/// index, so the request goes to every root's actor and the results are
+/// combined: the project at `cwd` reports at the top level and the others
+/// under `roots`. Returns `None` when only the project at `cwd` is watched.
+async fn handle_watch_request(
+  request_id: &str,
+  request: &WatchRequest,
+  router: &ProjectRouter,
+  cwd: &str,
+) -> Option<Response> {
+  let cwd = Path::new(cwd);
+  let primary_root = resolve_project_path(cwd).await;
+  let primary_id = ProjectId::from_path(cwd).await;
+
+  let roots = match request {
+    WatchRequest::Start(params) => {
+      let config = Config::load_for_project(&primary_root).await;
+      let requested = params
+        .roots
+        .iter()
+        .map(|r| cwd.join(r))
+        .chain(config.index.watch_roots.iter().map(|r| primary_root.join(r)));
+      let mut roots = router.watch_roots(&primary_id);
+      let mut ids = vec![primary_id.clone()];
+      for root in &roots {
+        ids.push(ProjectId::from_path(root).await);
+      }
+      for path in requested {
+        let root = resolve_project_path(&path).await;
+        let id = ProjectId::from_path(&root).await;
+        if !ids.contains(&id) {
+          ids.push(id);
+          roots.push(root);
+        }
+      }
+      router.set_watch_roots(&primary_id, roots.clone());
+      roots
+    }
+    WatchRequest::Stop(_) => router.take_watch_roots(&primary_id),
+    WatchRequest::Status(_) | WatchRequest::Pause(_) | WatchRequest::Resume(_) => router.watch_roots(&primary_id),
+  };
+  if roots.is_empty() {
+    return None;
+  }
+
+  // Each root's actor gets a plain request for its own project
+  let single = match request {
+    WatchRequest::Start(_) => WatchRequest::Start(WatchStartParams::default()),
+    other => other.clone(),
+  };
+
+  let mut response = match watch_root(router, cwd, request_id, single.clone()).await {
+    Ok(response) => response,
+    Err((code, message)) => return Some(Response::rpc_error(request_id, code, message)),
+  };
+  for root in roots {
+    let path = root.to_string_lossy().to_string();
+    let result = watch_root(router, &root, request_id, single.clone()).await;
+    if let Err((_, ref message)) = result {
+      warn!(root = %path, "Watch request failed for workspace root: {}", message);
+    }
+    match (&mut response, result) {
+      (WatchResponse::Start(combined), Ok(WatchResponse::Start(result))) => combined.roots.push(result),
+      (WatchResponse::Start(combined), Err((_, message))) => combined.roots.push(WatchStartResult {
+        status: format!("failed: {}", message),
+        project_id: ProjectId::from_path(&root).await.to_string(),
+        path,
+        startup_scan: None,
+        roots: Vec::new(),
+      }),
+      (WatchResponse::Stop(combined), Ok(WatchResponse::Stop(result))) => combined.roots.push(result),
+      (WatchResponse::Stop(combined), Err((_, message))) => combined.roots.push(WatchStopResult {
+        status: format!("failed: {}", message),
+        project_id: ProjectId::from_path(&root).await.to_string(),
+        path,
+        roots: Vec::new(),
+      }),
+      (WatchResponse::Status(combined), Ok(WatchResponse::Status(result))) => combined.roots.push(result),
+      (WatchResponse::Pause(combined), Ok(WatchResponse::Pause(result))) => combined.roots.push(result),
+      (WatchResponse::Pause(combined), Err((_, message))) => combined.roots.push(WatchPauseResult {
+        status: format!("failed: {}", message),
+        project_id: ProjectId::from_path(&root).await.to_string(),
+        path,
+        roots: Vec::new(),
+      }),
+      (WatchResponse::Resume(combined), Ok(WatchResponse::Resume(result))) => combined.roots.push(result),
+      (WatchResponse::Resume(combined), Err((_, message))) => combined.roots.push(WatchResumeResult {
+        status: format!("failed: {}", message),
+        project_id: ProjectId::from_path(&root).await.to_string(),
+        path,
+        reconcile_scan: None,
+        roots: Vec::new(),
+      }),
+      (WatchResponse::Status(combined), _) => combined.roots.push(WatchStatusResult {
+        running: false,
+        root: Some(path),
+        pending_changes: 0,
+        project_id: ProjectId::from_path(&root).await.to_string(),
+        paused: false,
+        scanning: false,
+        scan_progress: None,
+        last_storm: None,
+        roots: Vec::new(),
+      }),
+      _ => {}
+    }
+  }
+
+  Some(Response::success(request_id, ResponseData::Watch(response)))
+}
+
+/// Send a watch request to the project at `path` and wait for its result
+async fn watch_root(
+  router: &ProjectRouter,
+  path: &Path,
+  request_id: &str,
+  request: WatchRequest,
+) -> Result<WatchResponse, (i32, String)> {
+  let handle = router
+    .get_or_create(path)
+    .await
+    .map_err(|e| (-32000, format!("Failed to get project: {}", e)))?;
+  let payload = ProjectActorPayload::Request(RequestData::Watch(request));
+  match handle.request(request_id.to_string(), payload).await {
+    Ok(ProjectActorResponse::Done(ResponseData::Watch(response))) => Ok(response),
+    Ok(ProjectActorResponse::Error { code, message }) => Err((code, message)),
+    Ok(_) => Err((-32000, "Unexpected watch response".to_string())),
+    Err(e) => Err((-32000, format!("Failed to send to actor: {}", e))),
+  }
+}
+
 /// Handle daemon-level system requests that don't need a project context.
 ///
 /// Returns `Some(Response)` if the request was handled, `None` if it should
@@ -462,6 +723,11 @@ async fn handle_daemon_request(
   cancel: &CancellationToken,
 ) -> Option<Response> {
   match sys_req {
+    // Answered here so liveness probes don't open a project
+    SystemRequest::Ping(_) => Some(Response::success(
+      request_id,
+      ResponseData::System(SystemResponse::Ping("pong".to_string())),
+    )),
     SystemRequest::Status(_) => {
       let uptime = daemon_state.start_time.elapsed().as_secs();
       let idle_secs = activity.idle_duration().as_secs();
@@ -469,7 +735,12 @@ async fn handle_daemon_request(
       let projects = router.list().len();
 
       let result = StatusResult {
-        status: "running".to_string(),
+        status: if cancel.is_cancelled() {
+          "shutting_down"
+        } else {
+          "running"
+        }
+        .to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         pid: daemon_state.pid,
         projects,
@@ -543,6 +814,35 @@ async fn handle_daemon_request(
         }),
       ))
     }
+    SystemRequest::Usage(params) => {
+      let Some(usage) = router.usage() else {
+        return Some(Response::rpc_error(
+          request_id,
+          -32000,
+          "Usage tracking is not available",
+        ));
+      };
+      let response = match crate::service::usage::summary(usage, params).await {
+        Ok(result) => Response::success(request_id, ResponseData::System(SystemResponse::Usage(result))),
+        Err(e) => Response::rpc_error(request_id, e.code(), e.to_string()),
+      };
+      Some(response)
+    }
+    SystemRequest::Egress(params) => {
+      let Some(usage) = router.usage() else {
+        return Some(Response::rpc_error(
+          request_id,
+          -32000,
+          "Usage tracking is not available",
+        ));
+      };
+      let strict_local = router.daemon_settings().strict_local;
+      let response = match crate::service::usage::egress(usage, params, strict_local).await {
+        Ok(result) => Response::success(request_id, ResponseData::System(SystemResponse::Egress(result))),
+        Err(e) => Response::rpc_error(request_id, e.code(), e.to_string()),
+      };
+      Some(response)
+    }
     // Other requests fall through to ProjectActor
     _ => None,
   }
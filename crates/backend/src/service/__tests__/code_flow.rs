@@ -69,6 +69,10 @@ pub async fn handle_login(request: LoginRequest) -> Response {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let search_result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -116,6 +120,10 @@ pub async fn handle_login(request: LoginRequest) -> Response {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -212,6 +220,10 @@ pub fn add_numbers(a: i32, b: i32) -> i32 {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -318,6 +330,10 @@ pub(crate) fn crate_auth_utility(token: &str) -> bool {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -385,6 +401,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       chunk_type: vec!["function".to_string()],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -443,6 +463,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       content_hash: Some("central_hash_001".to_string()),
       caller_count: 50, // Called by many other functions
       callee_count: 1,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     };
 
     let isolated_chunk = CodeChunk {
@@ -469,6 +493,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       content_hash: Some("isolated_hash_001".to_string()),
       caller_count: 0, // Never called
       callee_count: 1,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     };
 
     // Generate embeddings and add chunks directly
@@ -518,6 +546,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -582,6 +614,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       content_hash: Some("popular_hash_001".to_string()),
       caller_count: 15,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     };
 
     let unpopular_chunk = CodeChunk {
@@ -608,6 +644,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       content_hash: Some("unpopular_hash_001".to_string()),
       caller_count: 2,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     };
 
     // Generate embeddings and add chunks
@@ -657,6 +697,10 @@ pub fn get_user_by_id(id: u64) -> Option<User> {
       chunk_type: vec![],
       min_caller_count: Some(10), // Only functions with 10+ callers
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -747,6 +791,10 @@ pub fn to_uppercase(s: &str) -> String {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -824,6 +872,10 @@ pub fn unique_exact_match_function_xyz123() {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(&code_ctx, search_params, &RankingConfig::default(), None, None)
@@ -881,6 +933,10 @@ pub fn add_numbers(a: i32, b: i32) -> i32 {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let relevant_result = search::search(&code_ctx, relevant_params, &RankingConfig::default(), None, None)
@@ -905,6 +961,10 @@ pub fn add_numbers(a: i32, b: i32) -> i32 {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let unrelated_result = search::search(&code_ctx, unrelated_params, &RankingConfig::default(), None, None)
@@ -973,6 +1033,10 @@ pub fn utility_function_{}() {{
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: true,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let adaptive_result = search::search(&code_ctx, adaptive_params, &RankingConfig::default(), None, None)
@@ -989,6 +1053,10 @@ pub fn utility_function_{}() {{
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let normal_result = search::search(&code_ctx, normal_params, &RankingConfig::default(), None, None)
@@ -1096,6 +1164,10 @@ pub fn verify_stock_availability(items: &[Item]) -> Result<(), StockError> {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(
@@ -1168,6 +1240,10 @@ pub fn route_request(method: &str, path: &str) -> Handler {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(
@@ -1263,6 +1339,10 @@ pub fn send_notification_email(recipient: &str, subject: &str, body: &str) -> Re
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(
@@ -1305,6 +1385,10 @@ pub fn send_notification_email(recipient: &str, subject: &str, body: &str) -> Re
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let natural_result = search::search(
@@ -1412,6 +1496,10 @@ pub fn send_http_request(url: &str, method: &str, body: Option<&str>) -> Result<
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(
@@ -1527,6 +1615,10 @@ pub fn redis_cache_set(key: &str, value: &str, ttl_secs: u64) -> Result<(), Cach
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let hybrid_result = search::search(
@@ -1554,6 +1646,10 @@ pub fn redis_cache_set(key: &str, value: &str, ttl_secs: u64) -> Result<(), Cach
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let vector_result = search::search(
@@ -1686,6 +1782,10 @@ pub fn track_user_event(user_id: &str, event: &str, metadata: &HashMap<String, S
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     let result = search::search(
@@ -1789,6 +1889,10 @@ pub fn cache_set(key: &str, value: Value, ttl: Duration) {
       chunk_type: vec![],
       min_caller_count: None,
       adaptive_limit: false,
+      git: None,
+      infer_language: false,
+      include_generated: false,
+      max_per_file: 0,
     };
 
     // Explicitly pass None for reranker - should work fine
@@ -1807,4 +1911,264 @@ pub fn cache_set(key: &str, value: Value, ttl: Duration) {
       "search without reranker should still return results"
     );
   }
+
+  /// Test transitive call graph traversal over the call_edges table.
+  ///
+  /// Validates:
+  /// 1. Indexing records call edges for each chunk
+  /// 2. Direct callers/callees are returned at depth 1
+  /// 3. Increasing depth follows the graph and reports each item's distance
+  #[tokio::test]
+  async fn test_transitive_callers_and_callees() {
+    use crate::service::code::{CalleesParams, CallersParams, get_callees_response, get_callers_response};
+
+    let ctx = TestContext::new().await;
+
+    ctx
+      .index_code(
+        "src/store.rs",
+        "pub fn load_record(id: u64) -> Record {\n    Record::new(id)\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/service.rs",
+        "pub fn fetch_record(id: u64) -> Record {\n    load_record(id)\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/handler.rs",
+        "pub fn handle_request(id: u64) -> Response {\n    Response::ok(fetch_record(id))\n}\n",
+        Language::Rust,
+      )
+      .await;
+
+    let direct = get_callers_response(
+      &ctx.db,
+      CallersParams {
+        chunk_id: None,
+        symbol: Some("load_record".to_string()),
+        limit: None,
+        depth: None,
+      },
+    )
+    .await
+    .expect("callers query should succeed");
+    assert_eq!(direct.count, 1, "load_record should have one direct caller");
+    assert_eq!(direct.callers[0].file_path, "src/service.rs");
+
+    let transitive = get_callers_response(
+      &ctx.db,
+      CallersParams {
+        chunk_id: None,
+        symbol: Some("load_record".to_string()),
+        limit: None,
+        depth: Some(2),
+      },
+    )
+    .await
+    .expect("transitive callers query should succeed");
+    let callers: Vec<(&str, Option<usize>)> = transitive
+      .callers
+      .iter()
+      .map(|c| (c.file_path.as_str(), c.depth))
+      .collect();
+    assert_eq!(
+      callers,
+      vec![("src/service.rs", Some(1)), ("src/handler.rs", Some(2))],
+      "depth 2 should include the caller's caller"
+    );
+
+    let handler_id = transitive.callers[1].id.clone();
+    let callees = get_callees_response(
+      &ctx.db,
+      CalleesParams {
+        chunk_id: handler_id,
+        limit: None,
+        depth: Some(3),
+      },
+    )
+    .await
+    .expect("callees query should succeed");
+    let resolved: Vec<(&str, Option<usize>)> = callees.callees.iter().map(|c| (c.call.as_str(), c.depth)).collect();
+    assert_eq!(
+      resolved,
+      vec![("fetch_record", Some(1)), ("load_record", Some(2))],
+      "callees should be followed through indexed definitions"
+    );
+    assert!(
+      callees.unresolved.contains(&"Response::ok".to_string()),
+      "external calls should be reported as unresolved, got {:?}",
+      callees.unresolved
+    );
+  }
+
+  /// Test go-to-definition resolution through imports.
+  ///
+  /// Validates:
+  /// 1. Without file context, every definition of the name is returned
+  /// 2. With file context, the definition in the imported module ranks first
+  /// 3. Signature and line range come from the defining chunk
+  #[tokio::test]
+  async fn test_definition_resolves_through_imports() {
+    use crate::service::code::{DefinitionParams, get_definition_response};
+
+    let ctx = TestContext::new().await;
+
+    ctx
+      .index_code(
+        "src/a_legacy/config.rs",
+        "pub fn load_config(path: &str) -> LegacyConfig {\n    LegacyConfig::read(path)\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/config/loader.rs",
+        "/// Load the project configuration.\npub fn load_config(path: &str) -> Config {\n    Config::read(path)\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/app.rs",
+        "use crate::config::loader::load_config;\n\npub fn start() {\n    let config = load_config(\"ccengram.toml\");\n    run(config);\n}\n",
+        Language::Rust,
+      )
+      .await;
+
+    let unscoped = get_definition_response(
+      &ctx.db,
+      DefinitionParams {
+        symbol: "load_config".to_string(),
+        file_path: None,
+        limit: None,
+      },
+    )
+    .await
+    .expect("definition query should succeed");
+    assert_eq!(unscoped.count, 2, "both definitions should be found");
+    assert!(
+      unscoped.definitions.iter().all(|d| d.resolution == "global"),
+      "without file context definitions are only matched by name"
+    );
+
+    let scoped = get_definition_response(
+      &ctx.db,
+      DefinitionParams {
+        symbol: "load_config".to_string(),
+        file_path: Some("src/app.rs".to_string()),
+        limit: None,
+      },
+    )
+    .await
+    .expect("definition query should succeed");
+    let best = &scoped.definitions[0];
+    assert_eq!(
+      (best.file_path.as_str(), best.resolution.as_str()),
+      ("src/config/loader.rs", "import"),
+      "the imported definition should rank first"
+    );
+    assert!(
+      best
+        .signature
+        .as_deref()
+        .is_some_and(|s| s.contains("fn load_config(path: &str) -> Config")),
+      "signature should be returned, got {:?}",
+      best.signature
+    );
+    assert!(
+      best.start_line <= 2 && best.end_line >= 4,
+      "line range should cover the function"
+    );
+  }
+
+  /// Test find-references ranking.
+  ///
+  /// Validates:
+  /// 1. Callers and plain mentions are both found
+  /// 2. References are ordered by proximity to the definition
+  /// 3. Longer identifiers containing the symbol are not references
+  #[tokio::test]
+  async fn test_references_rank_by_definition_proximity() {
+    use crate::service::code::{ReferencesParams, get_references_response};
+
+    let ctx = TestContext::new().await;
+
+    ctx
+      .index_code(
+        "src/config/loader.rs",
+        "pub fn load_config(path: &str) -> Config {\n    Config::read(path)\n}\n\npub fn reload() -> Config {\n    load_config(\"ccengram.toml\")\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/config/defaults.rs",
+        "pub fn loader() -> fn(&str) -> Config {\n    load_config\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/app.rs",
+        "use crate::config::loader::load_config;\n\npub fn start() {\n    let config = load_config(\"ccengram.toml\");\n    run(config);\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/tools/report.rs",
+        "pub fn report() {\n    let config = load_config(\"report.toml\");\n    print(config);\n}\n",
+        Language::Rust,
+      )
+      .await;
+    ctx
+      .index_code(
+        "src/tools/cache.rs",
+        "pub fn load_config_cached() -> Config {\n    CACHE.get()\n}\n",
+        Language::Rust,
+      )
+      .await;
+
+    let response = get_references_response(
+      &ctx.db,
+      ReferencesParams {
+        symbol: "load_config".to_string(),
+        file_path: None,
+        limit: None,
+      },
+    )
+    .await
+    .expect("references query should succeed");
+
+    assert_eq!(
+      response.definition.as_ref().map(|d| d.file_path.as_str()),
+      Some("src/config/loader.rs"),
+      "the definition should be resolved"
+    );
+    let found: Vec<(&str, &str, &str)> = response
+      .references
+      .iter()
+      .map(|r| (r.file_path.as_str(), r.proximity.as_str(), r.kind.as_str()))
+      .collect();
+    assert_eq!(
+      found,
+      vec![
+        ("src/config/loader.rs", "same_file", "call"),
+        ("src/app.rs", "imports", "call"),
+        ("src/config/defaults.rs", "same_directory", "mention"),
+        ("src/tools/report.rs", "elsewhere", "call"),
+      ],
+      "references should be ordered by proximity to the definition"
+    );
+    assert_eq!(response.total, 4, "total should count all references");
+    assert!(
+      response.references.iter().all(|r| !r.lines.is_empty()),
+      "each reference should report the lines mentioning the symbol"
+    );
+  }
 }
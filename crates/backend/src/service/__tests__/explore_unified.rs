@@ -57,6 +57,9 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, memory_params).await.expect("add memory");
 
@@ -67,6 +70,7 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       expand_top: 0,
       limit: 10,
       depth: 3,
+      ..Default::default()
     };
 
     let all_result = search(&explore_ctx, &all_params).await.expect("search all");
@@ -85,6 +89,7 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       expand_top: 0,
       limit: 10,
       depth: 3,
+      ..Default::default()
     };
 
     let code_result = search(&explore_ctx, &code_params).await.expect("search code");
@@ -99,6 +104,7 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       expand_top: 0,
       limit: 10,
       depth: 3,
+      ..Default::default()
     };
 
     let memory_result = search(&explore_ctx, &memory_params).await.expect("search memory");
@@ -129,6 +135,9 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
         scope_path: None,
         scope_module: None,
         importance: None,
+        visibility: None,
+        status: None,
+        due: None,
       };
       memory::add(&mem_ctx, params).await.expect("add memory");
     }
@@ -139,6 +148,7 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       expand_top: 0,
       limit: 10,
       depth: 3,
+      ..Default::default()
     };
 
     let result = search(&explore_ctx, &params).await.expect("search");
@@ -164,6 +174,7 @@ pub async fn authenticate_oauth(provider: &str, token: &str) -> Result<User, Aut
       expand_top: 0,
       limit: 10,
       depth: 3,
+      ..Default::default()
     };
 
     let result = search(&explore_ctx, &params).await;
@@ -213,6 +224,9 @@ pub fn validate_jwt_token(token: &str) -> Result<Claims, AuthError> {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     let add_result = memory::add(&mem_ctx, memory_params).await.expect("add memory");
     let memory_id = add_result.id;
@@ -289,6 +303,9 @@ impl UserRepository {
         scope_path: None,
         scope_module: None,
         importance: None,
+        visibility: None,
+        status: None,
+        due: None,
       };
       memory::add(&mem_ctx, params).await.expect("add memory");
     }
@@ -344,6 +361,9 @@ impl UserRepository {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, auth_memory).await.expect("add auth memory");
 
@@ -357,6 +377,9 @@ impl UserRepository {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, db_memory).await.expect("add db memory");
 
@@ -434,6 +457,9 @@ async fn get_pending_migrations(db: &Database) -> Result<Vec<Migration>, Migrati
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     let add_result = memory::add(&mem_ctx, memory_params).await.expect("add memory");
 
@@ -500,6 +526,9 @@ pub async fn handle_login(req: LoginRequest) -> Result<LoginResponse, AuthError>
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     let add_result = memory::add(&mem_ctx, memory_params).await.expect("add memory");
 
@@ -582,6 +611,9 @@ pub async fn send_email(to: &str, subject: &str, body: &str) -> Result<(), Email
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     let add_result = memory::add(&mem_ctx, memory_params).await.expect("add memory");
 
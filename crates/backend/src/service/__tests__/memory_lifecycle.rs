@@ -7,13 +7,18 @@
 mod tests {
   use crate::{
     context::memory::extract::decay::MemoryDecay,
+    domain::memory::MemoryActor,
     ipc::types::{
-      memory::{MemoryAddParams, MemoryGetParams, MemoryListParams, MemoryRelatedParams, MemorySearchParams},
+      memory::{
+        MemoryAddParams, MemoryFeedbackParams, MemoryGetParams, MemoryHistoryParams, MemoryListParams,
+        MemoryRelatedParams, MemorySearchParams, MemoryUndoParams, TagDeleteParams, TagListParams, TagMergeParams,
+        TagRenameParams,
+      },
       relationship::RelationshipAddParams,
     },
     service::{
       __tests__::helpers::TestContext,
-      memory::{self, relationship},
+      memory::{self, relationship, tags},
     },
   };
 
@@ -29,6 +34,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     }
   }
 
@@ -44,6 +52,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     }
   }
 
@@ -76,6 +87,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: Some(0.7),
+      visibility: None,
+      status: None,
+      due: None,
     };
 
     let result = memory::add(&mem_ctx, add_params).await.expect("add memory");
@@ -128,6 +142,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     let second_result = memory::add(&mem_ctx, second_add).await.expect("add second memory");
     let second_id = second_result.id.clone();
@@ -249,6 +266,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: Some(0.9),
+      visibility: None,
+      status: None,
+      due: None,
     };
     let result = memory::add(&mem_ctx, add_p).await.expect("add memory");
 
@@ -277,6 +297,100 @@ mod tests {
     );
   }
 
+  /// Test search feedback accumulates without touching salience.
+  #[tokio::test]
+  async fn test_memory_feedback() {
+    let ctx = TestContext::new().await;
+    let mem_ctx = ctx.memory_context();
+
+    let add_p = add_params("Integration tests share one Postgres container per run");
+    let memory_id = memory::add(&mem_ctx, add_p).await.expect("add memory").id;
+
+    let feedback = |helpful: bool| MemoryFeedbackParams {
+      memory_id: memory_id.clone(),
+      helpful,
+      query: Some("test database setup".to_string()),
+    };
+    memory::feedback(&mem_ctx, feedback(true)).await.expect("helpful");
+    memory::feedback(&mem_ctx, feedback(true)).await.expect("helpful");
+    let result = memory::feedback(&mem_ctx, feedback(false)).await.expect("irrelevant");
+    assert_eq!((result.helpful_count, result.irrelevant_count), (2, 1));
+    assert!((result.feedback_score - 0.2).abs() < 1e-6, "(2 - 1) / (2 + 1 + 2)");
+
+    let stored = ctx.db.get_memory(&memory_id.parse().unwrap()).await.unwrap().unwrap();
+    assert_eq!((stored.helpful_count, stored.irrelevant_count), (2, 1));
+    assert_eq!(stored.salience, 1.0, "feedback leaves salience alone");
+
+    let history = memory::history(
+      &mem_ctx,
+      MemoryHistoryParams {
+        memory_id: memory_id.clone(),
+      },
+    )
+    .await
+    .expect("history");
+    let last = history.events.last().expect("feedback event");
+    assert_eq!(last.action, "feedback");
+    assert_eq!(last.detail.as_deref(), Some("irrelevant for \"test database setup\""));
+  }
+
+  /// Timeline neighbours come back nearest first, and concepts carrying LIKE
+  /// wildcards or quotes only match themselves when finding related memories.
+  #[tokio::test]
+  async fn test_memory_timeline_and_related_filters() {
+    let ctx = TestContext::new().await;
+    let mem_ctx = ctx.memory_context();
+
+    let contents = [
+      "The staging deploy runs from the release branch",
+      "Feature flags live in the shared settings service",
+      "Log retention for the audit trail is ninety days",
+      "Cache keys are prefixed with the tenant slug",
+    ];
+    let concepts = ["deploy_window", "deployXwindow", "it's 100%", "it's 1000"];
+    let mut ids = Vec::new();
+    for (hours_ago, (content, concept)) in (1..=4).rev().zip(contents.iter().zip(concepts)) {
+      let id = memory::add(&mem_ctx, add_params(content)).await.expect("add memory").id;
+      let mut stored = ctx.db.get_memory(&id.parse().unwrap()).await.unwrap().unwrap();
+      stored.created_at = chrono::Utc::now() - chrono::Duration::hours(hours_ago);
+      stored.concepts = vec![concept.to_string()];
+      ctx.db.update_memory(&stored, None).await.unwrap();
+      ids.push(id);
+    }
+
+    let timeline = memory::timeline(&mem_ctx, &ids[2], 5, 5).await.expect("timeline");
+    let before: Vec<&str> = timeline.before.iter().map(|m| m.id.as_str()).collect();
+    let after: Vec<&str> = timeline.after.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(before, vec![ids[1].as_str(), ids[0].as_str()]);
+    assert_eq!(after, vec![ids[3].as_str()]);
+
+    let timeline = memory::timeline(&mem_ctx, &ids[2], 1, 0).await.expect("timeline");
+    assert_eq!(timeline.before.len(), 1);
+    assert_eq!(timeline.before[0].id, ids[1]);
+    assert!(timeline.after.is_empty());
+
+    let by_concept = |result: &crate::ipc::types::memory::MemoryRelatedResult, concept: &str| {
+      result
+        .related
+        .iter()
+        .filter(|r| r.relationship == format!("entity:{}", concept))
+        .map(|r| r.id.clone())
+        .collect::<Vec<_>>()
+    };
+    let params = |memory_id: &str| MemoryRelatedParams {
+      memory_id: memory_id.to_string(),
+      limit: Some(10),
+    };
+
+    // "_" is a single-char wildcard in LIKE; unescaped it would match "deployXwindow"
+    let result = memory::related(&mem_ctx, params(&ids[0])).await.expect("related");
+    assert!(by_concept(&result, "deploy_window").is_empty());
+
+    // Likewise a bare "%" would match "it's 1000", and the quote must not end the string
+    let result = memory::related(&mem_ctx, params(&ids[2])).await.expect("related");
+    assert!(by_concept(&result, "it's 100%").is_empty());
+  }
+
   /// Test supersede operation.
   #[tokio::test]
   async fn test_memory_supersede() {
@@ -330,6 +444,229 @@ mod tests {
     assert!(get_result.is_err(), "Hard deleted memory should not be retrievable");
   }
 
+  /// Test every mutation is recorded in the memory's history with its actor.
+  #[tokio::test]
+  async fn test_memory_history() {
+    let ctx = TestContext::new().await;
+    let mcp = ctx.memory_context().with_actor(MemoryActor::Mcp);
+    let cli = ctx.memory_context().with_actor(MemoryActor::Cli);
+
+    let add_p = add_params("The staging database is rebuilt every Sunday night");
+    let memory_id = memory::add(&mcp, add_p).await.expect("add memory").id;
+    memory::deemphasize(&cli, &memory_id, Some(0.3))
+      .await
+      .expect("deemphasize");
+    memory::delete(&cli, &memory_id).await.expect("delete");
+    memory::restore(&cli, &memory_id).await.expect("restore");
+    memory::hard_delete(&cli, &memory_id).await.expect("hard delete");
+
+    let history = memory::history(
+      &cli,
+      MemoryHistoryParams {
+        memory_id: memory_id.clone(),
+      },
+    )
+    .await
+    .expect("history outlives a hard delete");
+    let actions: Vec<(&str, &str)> = history
+      .events
+      .iter()
+      .map(|e| (e.action.as_str(), e.actor.as_str()))
+      .collect();
+    assert_eq!(
+      actions,
+      vec![
+        ("added", "mcp"),
+        ("deemphasized", "cli"),
+        ("deleted", "cli"),
+        ("restored", "cli"),
+        ("hard_deleted", "cli"),
+      ]
+    );
+    let deemphasized = &history.events[1];
+    assert_eq!(deemphasized.salience_before, Some(1.0));
+    assert!(deemphasized.salience_after.is_some_and(|s| (s - 0.7).abs() < 1e-6));
+
+    let missing = memory::history(
+      &cli,
+      MemoryHistoryParams {
+        memory_id: uuid::Uuid::now_v7().to_string(),
+      },
+    )
+    .await;
+    assert!(missing.is_err(), "unknown memories have no history");
+  }
+
+  /// Test undo reverts the newest batch still in effect, an archive run at once.
+  #[tokio::test]
+  async fn test_memory_undo() {
+    let ctx = TestContext::new().await;
+    let mem_ctx = ctx.memory_context();
+
+    let old_id = memory::add(&mem_ctx, add_params("Deploys run from the Jenkins pipeline"))
+      .await
+      .expect("add old memory")
+      .id;
+    let new_id = memory::add(&mem_ctx, add_params("Deploys run from GitHub Actions on merge to main"))
+      .await
+      .expect("add new memory")
+      .id;
+    let faded_id = memory::add(&mem_ctx, add_params("The flaky login test was quarantined in March"))
+      .await
+      .expect("add faded memory")
+      .id;
+    memory::supersede(&mem_ctx, &old_id, &new_id).await.expect("supersede");
+    memory::set_salience(&mem_ctx, &faded_id, 0.3)
+      .await
+      .expect("set salience");
+    let archived = memory::archive(&mem_ctx, &[new_id.clone(), faded_id.clone()])
+      .await
+      .expect("archive");
+    assert_eq!(archived.len(), 2);
+
+    let preview = memory::undo(&mem_ctx, MemoryUndoParams { dry_run: true })
+      .await
+      .expect("undo dry run");
+    assert_eq!(preview.reverted.len(), 2, "the archive run is one batch");
+    let still_deleted = memory::list_deleted(&mem_ctx, None).await.expect("list deleted");
+    assert_eq!(still_deleted.len(), 2, "a dry run changes nothing");
+
+    let undone = memory::undo(&mem_ctx, MemoryUndoParams::default())
+      .await
+      .expect("undo archive");
+    assert_eq!(undone.batch_id, preview.batch_id);
+    let faded = undone
+      .reverted
+      .iter()
+      .find(|item| item.memory_id == faded_id)
+      .expect("faded memory restored");
+    assert!((faded.salience - 0.3).abs() < 1e-6, "salience from before the archive");
+    assert!(
+      memory::list_deleted(&mem_ctx, None)
+        .await
+        .expect("list deleted")
+        .is_empty()
+    );
+
+    let undone = memory::undo(&mem_ctx, MemoryUndoParams::default())
+      .await
+      .expect("undo supersede");
+    let actions: Vec<(&str, &str)> = undone
+      .reverted
+      .iter()
+      .map(|item| (item.memory_id.as_str(), item.action.as_str()))
+      .collect();
+    assert_eq!(actions, vec![(old_id.as_str(), "superseded")]);
+    let old = memory::get(
+      &mem_ctx,
+      MemoryGetParams {
+        memory_id: old_id.clone(),
+        include_related: Some(false),
+      },
+    )
+    .await
+    .expect("get old memory");
+    assert_eq!(old.superseded_by, None);
+
+    let nothing = memory::undo(&mem_ctx, MemoryUndoParams::default())
+      .await
+      .expect("undo with nothing left");
+    assert!(nothing.batch_id.is_none());
+  }
+
+  /// Test tags are renamed, merged and deleted across memories.
+  #[tokio::test]
+  async fn test_tag_curation() {
+    let ctx = TestContext::new().await;
+    let mem_ctx = ctx.memory_context();
+
+    let tagged = [
+      ("Migrations run with sqlx migrate on deploy", vec!["postgres", "deploy"]),
+      ("Connection pool size is 20 per instance", vec!["pg", "postgres"]),
+      ("The read replica lags up to five seconds", vec!["postgresql", "misc"]),
+    ];
+    let mut ids = Vec::new();
+    for (content, tag_names) in tagged {
+      let mut params = add_params(content);
+      params.tags = Some(tag_names.into_iter().map(String::from).collect());
+      ids.push(memory::add(&mem_ctx, params).await.expect("add memory").id);
+    }
+
+    let listed = tags::list(&mem_ctx, TagListParams::default()).await.expect("list tags");
+    assert_eq!(listed[0].tag, "postgres");
+    assert_eq!(listed[0].memories, 2);
+
+    let taken = tags::rename(
+      &mem_ctx,
+      TagRenameParams {
+        from: "postgres".to_string(),
+        to: "postgresql".to_string(),
+        dry_run: false,
+      },
+    )
+    .await;
+    assert!(taken.is_err(), "renaming onto a tag in use needs a merge");
+
+    let merged = tags::merge(
+      &mem_ctx,
+      TagMergeParams {
+        tags: vec!["postgres".to_string(), "pg".to_string()],
+        into: "postgresql".to_string(),
+        dry_run: false,
+      },
+    )
+    .await
+    .expect("merge tags");
+    assert_eq!(merged.memory_ids.len(), 2);
+
+    let preview = tags::delete(
+      &mem_ctx,
+      TagDeleteParams {
+        tags: vec!["misc".to_string()],
+        dry_run: true,
+      },
+    )
+    .await
+    .expect("delete dry run");
+    assert_eq!(preview.memory_ids, vec![ids[2].clone()]);
+
+    let listed = tags::list(&mem_ctx, TagListParams::default()).await.expect("list tags");
+    let names: Vec<(&str, usize)> = listed.iter().map(|t| (t.tag.as_str(), t.memories)).collect();
+    assert_eq!(
+      names,
+      vec![("postgresql", 3), ("deploy", 1), ("misc", 1)],
+      "synonyms merged, dry run left misc in place"
+    );
+
+    let pool = memory::get(
+      &mem_ctx,
+      MemoryGetParams {
+        memory_id: ids[1].clone(),
+        include_related: Some(false),
+      },
+    )
+    .await
+    .expect("get pool memory");
+    assert_eq!(
+      pool.tags,
+      vec!["postgresql".to_string()],
+      "no repeated tag after the merge"
+    );
+
+    let history = memory::history(
+      &mem_ctx,
+      MemoryHistoryParams {
+        memory_id: ids[1].clone(),
+      },
+    )
+    .await
+    .expect("history");
+    assert!(
+      history.events.iter().any(|e| e.action == "updated"),
+      "the merge is recorded in history"
+    );
+  }
+
   /// Test relationship list operation.
   #[tokio::test]
   async fn test_relationship_list() {
@@ -379,6 +716,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, semantic_decision)
       .await
@@ -394,6 +734,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, semantic_codebase)
       .await
@@ -409,6 +752,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, procedural_pattern)
       .await
@@ -426,6 +772,8 @@ mod tests {
       session_id: None,
       limit: Some(10),
       include_superseded: false,
+      group_by: None,
+      per_group: None,
     };
     let sector_result = memory::search(&mem_ctx, search_by_sector, &ctx.config, None)
       .await
@@ -452,6 +800,8 @@ mod tests {
       session_id: None,
       limit: Some(10),
       include_superseded: false,
+      group_by: None,
+      per_group: None,
     };
     let type_result = memory::search(&mem_ctx, search_by_type, &ctx.config, None)
       .await
@@ -476,6 +826,8 @@ mod tests {
       session_id: None,
       limit: Some(10),
       include_superseded: false,
+      group_by: None,
+      per_group: None,
     };
     let combined_result = memory::search(&mem_ctx, search_combined, &ctx.config, None)
       .await
@@ -547,6 +899,9 @@ mod tests {
       scope_path: None,
       scope_module: None,
       importance: None,
+      visibility: None,
+      status: None,
+      due: None,
     };
     memory::add(&mem_ctx, add_params).await.expect("add memory");
 
@@ -562,6 +917,8 @@ mod tests {
       session_id: None,
       limit: Some(10),
       include_superseded: false,
+      group_by: None,
+      per_group: None,
     };
 
     let result = memory::search(&mem_ctx, search_params, &ctx.config, None)
@@ -5,9 +5,12 @@
 
 use std::{collections::HashSet, path::Path};
 
+use uuid::Uuid;
+
 use super::search::CodeContext;
 use crate::{
-  db::ProjectDb,
+  context::files::encoding,
+  db::{FilterBuilder, ProjectDb},
   domain::code::CodeChunk,
   ipc::types::{
     code::{
@@ -16,63 +19,195 @@ use crate::{
     },
     memory::MemoryItem,
   },
-  service::util::{Resolver, ServiceError},
+  service::util::{ResolveError, Resolver, ServiceError},
 };
 
 // ============================================================================
 // Call Graph Navigation
 // ============================================================================
 
-/// Get chunks that call symbols defined in a given chunk.
+/// Maximum levels followed by transitive caller/callee queries
+pub const MAX_CALL_DEPTH: usize = 5;
+
+/// Maximum chunks returned by a transitive callee query
+const MAX_TRANSITIVE_CALLEES: usize = 200;
+
+/// A call resolved to the chunk defining it.
+#[derive(Debug, Clone)]
+pub struct ResolvedCall {
+  /// The call as written in the calling chunk
+  pub call: String,
+  /// Chunk defining the called symbol
+  pub chunk: CodeChunk,
+  /// Distance from the starting chunk (1 = called directly)
+  pub depth: usize,
+}
+
+/// Find chunks that call any of `symbols`, following callers up to `depth` levels.
 ///
-/// Uses LIKE queries to find chunks that have symbols in their calls list.
+/// Uses the call_edges table. Each caller is returned with its distance from
+/// the starting symbols, nearest first.
 ///
 /// # Arguments
 /// * `db` - Project database
 /// * `symbols` - Symbols to find callers for
 /// * `exclude_id` - Optional chunk ID to exclude (usually the source chunk)
+/// * `depth` - Levels of callers to follow, clamped to [`MAX_CALL_DEPTH`]
 /// * `limit` - Maximum number of results
-///
-/// # Returns
-/// List of caller chunks
-pub async fn get_callers(
+pub async fn find_callers(
   db: &ProjectDb,
   symbols: &[String],
-  exclude_id: Option<uuid::Uuid>,
+  exclude_id: Option<Uuid>,
+  depth: usize,
   limit: usize,
-) -> Result<Vec<CodeChunk>, ServiceError> {
-  let mut callers = Vec::new();
-  let mut seen_ids: HashSet<uuid::Uuid> = HashSet::new();
+) -> Result<Vec<(CodeChunk, usize)>, ServiceError> {
+  let mut found = Vec::new();
+  let mut seen_ids: HashSet<Uuid> = exclude_id.into_iter().collect();
+  let mut seen_symbols: HashSet<String> = symbols.iter().cloned().collect();
+  let mut frontier = symbols.to_vec();
+
+  for level in 1..=depth.clamp(1, MAX_CALL_DEPTH) {
+    if frontier.is_empty() || found.len() >= limit {
+      break;
+    }
+
+    let edges = db.get_call_edges_to(&frontier).await?;
+    let caller_ids: Vec<Uuid> = edges
+      .iter()
+      .map(|e| e.caller_id)
+      .filter(|id| seen_ids.insert(*id))
+      .collect();
+
+    let mut callers = get_chunks_by_ids(db, &caller_ids).await?;
+    callers.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
 
-  if let Some(id) = exclude_id {
-    seen_ids.insert(id);
+    frontier = Vec::new();
+    for caller in callers {
+      if found.len() >= limit {
+        break;
+      }
+      frontier.extend(
+        caller
+          .symbols
+          .iter()
+          .filter(|s| seen_symbols.insert(s.to_string()))
+          .cloned(),
+      );
+      found.push((caller, level));
+    }
   }
 
-  // Find chunks that call any of these symbols
-  for symbol in symbols {
-    if callers.len() >= limit {
+  Ok(found)
+}
+
+/// Find the definitions called by `root`, following callees up to `depth` levels.
+///
+/// Uses the call_edges table and resolves each called name to the chunks
+/// defining it.
+///
+/// # Arguments
+/// * `db` - Project database
+/// * `root` - Chunk to find callees for
+/// * `depth` - Levels of callees to follow, clamped to [`MAX_CALL_DEPTH`]
+/// * `limit_per_call` - Maximum definitions resolved for each call
+///
+/// # Returns
+/// Tuple of (resolved callees nearest first, calls made by `root` that don't
+/// resolve to an indexed definition)
+pub async fn find_callees(
+  db: &ProjectDb,
+  root: &CodeChunk,
+  depth: usize,
+  limit_per_call: usize,
+) -> Result<(Vec<ResolvedCall>, Vec<String>), ServiceError> {
+  let mut resolved = Vec::new();
+  let mut unresolved = Vec::new();
+  let mut seen_ids = HashSet::from([root.id]);
+  let mut frontier = vec![root.id];
+
+  for level in 1..=depth.clamp(1, MAX_CALL_DEPTH) {
+    if frontier.is_empty() || resolved.len() >= MAX_TRANSITIVE_CALLEES {
       break;
     }
 
-    let filter = format!("calls LIKE '%\"{}%'", symbol.replace('\'', "''"));
-    if let Ok(chunks) = db.list_code_chunks(Some(&filter), Some(limit)).await {
-      for caller in chunks {
-        if seen_ids.insert(caller.id) {
-          callers.push(caller);
-          if callers.len() >= limit {
-            break;
-          }
+    let mut edges = db.get_call_edges_from(&frontier).await?;
+    edges.sort_by(|a, b| a.callee.cmp(&b.callee));
+    edges.dedup_by(|a, b| a.callee == b.callee);
+
+    frontier = Vec::new();
+    for edge in edges {
+      let definitions = find_definitions(db, &edge.callee_name, limit_per_call).await?;
+      if definitions.is_empty() {
+        if level == 1 {
+          unresolved.push(edge.callee);
+        }
+        continue;
+      }
+
+      for chunk in definitions {
+        if seen_ids.insert(chunk.id) {
+          frontier.push(chunk.id);
+          resolved.push(ResolvedCall {
+            call: edge.callee.clone(),
+            chunk,
+            depth: level,
+          });
         }
       }
     }
   }
 
-  Ok(callers)
+  resolved.truncate(MAX_TRANSITIVE_CALLEES);
+  Ok((resolved, unresolved))
+}
+
+/// Find chunks whose symbols include `name`
+pub(super) async fn find_definitions(db: &ProjectDb, name: &str, limit: usize) -> Result<Vec<CodeChunk>, ServiceError> {
+  // Symbols are stored as a JSON array, so quoting the name matches whole entries
+  let filter = FilterBuilder::new()
+    .add_like("symbols", &format!("\"{}\"", name))
+    .build_or_empty();
+  let chunks = db.list_code_chunks(Some(&filter), Some(limit)).await?;
+  Ok(
+    chunks
+      .into_iter()
+      .filter(|c| c.symbols.iter().any(|s| s == name))
+      .collect(),
+  )
+}
+
+pub(super) async fn get_chunks_by_ids(db: &ProjectDb, ids: &[Uuid]) -> Result<Vec<CodeChunk>, ServiceError> {
+  if ids.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let ids: Vec<String> = ids.iter().map(Uuid::to_string).collect();
+  let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+  let filter = FilterBuilder::new().add_in("id", &ids).build_or_empty();
+  Ok(db.list_code_chunks(Some(&filter), None).await?)
 }
 
-/// Get the definitions of functions/methods that a chunk calls.
+/// Get chunks that directly call symbols defined in a given chunk.
 ///
-/// Uses symbol lookup to find definitions of called symbols.
+/// # Arguments
+/// * `db` - Project database
+/// * `symbols` - Symbols to find callers for
+/// * `exclude_id` - Optional chunk ID to exclude (usually the source chunk)
+/// * `limit` - Maximum number of results
+///
+/// # Returns
+/// List of caller chunks
+pub async fn get_callers(
+  db: &ProjectDb,
+  symbols: &[String],
+  exclude_id: Option<Uuid>,
+  limit: usize,
+) -> Result<Vec<CodeChunk>, ServiceError> {
+  let callers = find_callers(db, symbols, exclude_id, 1, limit).await?;
+  Ok(callers.into_iter().map(|(chunk, _)| chunk).collect())
+}
+
+/// Get the definitions of functions/methods that a chunk directly calls.
 ///
 /// # Arguments
 /// * `db` - Project database
@@ -85,43 +220,22 @@ pub async fn get_callers(
 pub async fn get_callees(
   db: &ProjectDb,
   chunk_id: &str,
-  exclude_id: Option<uuid::Uuid>,
+  exclude_id: Option<Uuid>,
   limit: usize,
 ) -> Result<(Vec<(String, CodeChunk)>, Vec<String>), ServiceError> {
-  let mut callees = Vec::new();
-  let mut unresolved = Vec::new();
-  let mut seen_symbols: HashSet<String> = HashSet::new();
-
-  // Get the chunk to access its calls list
-  let chunk = match db.get_code_chunk_by_id_or_prefix(chunk_id).await? {
-    Some(c) => c,
-    None => return Ok((callees, unresolved)),
+  let chunk = match Resolver::code_chunk(db, chunk_id).await {
+    Ok(chunk) => chunk,
+    Err(ResolveError::NotFound { .. }) => return Ok((Vec::new(), Vec::new())),
+    Err(e) => return Err(e.into()),
   };
 
-  // Look up definitions for each called symbol
-  for target_symbol in &chunk.calls {
-    if callees.len() >= limit {
-      break;
-    }
-
-    if seen_symbols.contains(target_symbol) {
-      continue;
-    }
-    seen_symbols.insert(target_symbol.clone());
-
-    // Find chunk that defines this symbol
-    let filter = format!("symbols LIKE '%\"{}%'", target_symbol.replace('\'', "''"));
-    if let Ok(chunks) = db.list_code_chunks(Some(&filter), Some(1)).await
-      && let Some(callee) = chunks.into_iter().next()
-      && callee.symbols.iter().any(|s| s == target_symbol)
-    {
-      if exclude_id != Some(callee.id) {
-        callees.push((target_symbol.clone(), callee));
-      }
-    } else {
-      unresolved.push(target_symbol.clone());
-    }
-  }
+  let (resolved, unresolved) = find_callees(db, &chunk, 1, 1).await?;
+  let callees = resolved
+    .into_iter()
+    .filter(|r| exclude_id != Some(r.chunk.id))
+    .take(limit)
+    .map(|r| (r.call, r.chunk))
+    .collect();
 
   Ok((callees, unresolved))
 }
@@ -142,7 +256,7 @@ pub async fn get_siblings(
   exclude_id: Option<uuid::Uuid>,
   limit: usize,
 ) -> Result<Vec<CodeChunk>, ServiceError> {
-  let filter = format!("file_path = '{}'", file_path.replace('\'', "''"));
+  let filter = FilterBuilder::new().add_eq("file_path", file_path).build_or_empty();
   let chunks = db.list_code_chunks(Some(&filter), None).await?;
 
   let siblings: Vec<CodeChunk> = chunks
@@ -180,10 +294,10 @@ pub async fn get_related_memories(
     .unwrap_or_default();
 
   if !file_name.is_empty() {
-    let filter = format!(
-      "is_deleted = false AND content LIKE '%{}%'",
-      file_name.replace('\'', "''")
-    );
+    let filter = FilterBuilder::new()
+      .exclude_deleted()
+      .add_like("content", &file_name)
+      .build_or_empty();
     if let Ok(found) = db.list_memories(Some(&filter), Some(limit)).await {
       for m in found {
         if seen_ids.insert(m.id) {
@@ -199,7 +313,10 @@ pub async fn get_related_memories(
       break;
     }
 
-    let filter = format!("is_deleted = false AND content LIKE '%{}%'", symbol.replace('\'', "''"));
+    let filter = FilterBuilder::new()
+      .exclude_deleted()
+      .add_like("content", symbol)
+      .build_or_empty();
     if let Ok(found) = db.list_memories(Some(&filter), Some(limit - memories.len())).await {
       for m in found {
         if seen_ids.insert(m.id) {
@@ -341,37 +458,47 @@ pub struct CallersParams {
   pub symbol: Option<String>,
   /// Maximum results
   pub limit: Option<usize>,
+  /// Levels of callers to follow (default: 1)
+  pub depth: Option<usize>,
 }
 
 /// Get callers for a chunk or symbol.
 pub async fn get_callers_response(db: &ProjectDb, params: CallersParams) -> Result<CodeCallersResponse, ServiceError> {
   let limit = params.limit.unwrap_or(20);
+  let depth = params.depth.unwrap_or(1).clamp(1, MAX_CALL_DEPTH);
 
-  // Resolve the symbol
-  let symbol = if let Some(ref chunk_id) = params.chunk_id {
+  // Resolve the symbols to find callers of
+  let (symbol, symbols, exclude_id) = if let Some(ref chunk_id) = params.chunk_id {
     let chunk = Resolver::code_chunk(db, chunk_id).await?;
-    chunk
+    let symbol = chunk
       .symbols
       .first()
       .cloned()
-      .ok_or_else(|| ServiceError::validation("Chunk has no symbols"))?
+      .ok_or_else(|| ServiceError::validation("Chunk has no symbols"))?;
+    (symbol, chunk.symbols, Some(chunk.id))
   } else if let Some(ref sym) = params.symbol {
-    sym.clone()
+    (sym.clone(), vec![sym.clone()], None)
   } else {
     return Err(ServiceError::validation("Must provide chunk_id or symbol"));
   };
 
-  // Find chunks that call this symbol
-  let filter = format!("calls LIKE '%\"{}%'", symbol.replace('\'', "''"));
-  let callers = db.list_code_chunks(Some(&filter), Some(limit)).await?;
+  let callers = find_callers(db, &symbols, exclude_id, depth, limit).await?;
 
-  let items: Vec<CodeItem> = callers.into_iter().map(|c| CodeItem::from_caller(&c)).collect();
+  let items: Vec<CodeItem> = callers
+    .into_iter()
+    .map(|(c, level)| {
+      let mut item = CodeItem::from_caller(&c);
+      item.depth = Some(level);
+      item
+    })
+    .collect();
 
   let count = items.len();
   Ok(CodeCallersResponse {
     symbol,
     callers: items,
     count,
+    depth,
   })
 }
 
@@ -382,52 +509,33 @@ pub struct CalleesParams {
   pub chunk_id: String,
   /// Maximum results per call
   pub limit: Option<usize>,
+  /// Levels of callees to follow (default: 1)
+  pub depth: Option<usize>,
 }
 
 /// Get callees for a chunk.
 pub async fn get_callees_response(db: &ProjectDb, params: CalleesParams) -> Result<CodeCalleesResponse, ServiceError> {
   let limit_per_call = params.limit.unwrap_or(3);
+  let depth = params.depth.unwrap_or(1).clamp(1, MAX_CALL_DEPTH);
 
   let chunk = Resolver::code_chunk(db, &params.chunk_id).await?;
+  let (resolved, unresolved) = find_callees(db, &chunk, depth, limit_per_call).await?;
 
-  if chunk.calls.is_empty() {
-    return Ok(CodeCalleesResponse {
-      chunk_id: chunk.id.to_string(),
-      calls: chunk.calls,
-      callees: vec![],
-      unresolved: vec![],
-    });
-  }
-
-  let mut callees: Vec<CodeCalleeItem> = Vec::new();
-  let mut unresolved = Vec::new();
-  let mut seen_ids = HashSet::new();
-
-  for call in &chunk.calls {
-    let filter = format!("symbols LIKE '%\"{}%'", call.replace('\'', "''"));
-    match db.list_code_chunks(Some(&filter), Some(limit_per_call)).await {
-      Ok(matches) => {
-        if matches.is_empty() {
-          unresolved.push(call.clone());
-        } else {
-          for m in matches {
-            if seen_ids.insert(m.id) {
-              callees.push(CodeCalleeItem::from_chunk_with_call(&m, call));
-            }
-          }
-        }
-      }
-      Err(_) => {
-        unresolved.push(call.clone());
-      }
-    }
-  }
+  let callees: Vec<CodeCalleeItem> = resolved
+    .into_iter()
+    .map(|r| {
+      let mut item = CodeCalleeItem::from_chunk_with_call(&r.chunk, &r.call);
+      item.depth = Some(r.depth);
+      item
+    })
+    .collect();
 
   Ok(CodeCalleesResponse {
     chunk_id: chunk.id.to_string(),
     calls: chunk.calls,
     callees,
     unresolved,
+    depth,
   })
 }
 
@@ -477,7 +585,7 @@ pub async fn get_related(ctx: &CodeContext<'_>, params: RelatedParams) -> Result
       }
       "shared_imports" => {
         for import in &chunk.imports {
-          let filter = format!("imports LIKE '%{}%'", import.replace('\'', "''"));
+          let filter = FilterBuilder::new().add_like("imports", import).build_or_empty();
           if let Ok(matches) = ctx.db.list_code_chunks(Some(&filter), Some(10)).await {
             for m in matches {
               if seen_ids.insert(m.id) {
@@ -499,25 +607,19 @@ pub async fn get_related(ctx: &CodeContext<'_>, params: RelatedParams) -> Result
         }
       }
       "callers" => {
-        if let Some(symbol) = chunk.symbols.first() {
-          let filter = format!("calls LIKE '%\"{}%'", symbol.replace('\'', "''"));
-          if let Ok(callers) = ctx.db.list_code_chunks(Some(&filter), Some(10)).await {
-            for c in callers {
-              if seen_ids.insert(c.id) {
-                related.push((c, 0.8, "caller".to_string()));
-              }
+        if let Ok(callers) = get_callers(ctx.db, &chunk.symbols, Some(chunk.id), 10).await {
+          for c in callers {
+            if seen_ids.insert(c.id) {
+              related.push((c, 0.8, "caller".to_string()));
             }
           }
         }
       }
       "callees" => {
-        for call in &chunk.calls {
-          let filter = format!("symbols LIKE '%\"{}%'", call.replace('\'', "''"));
-          if let Ok(matches) = ctx.db.list_code_chunks(Some(&filter), Some(5)).await {
-            for m in matches {
-              if seen_ids.insert(m.id) {
-                related.push((m, 0.8, format!("callee:{}", call)));
-              }
+        if let Ok((callees, _)) = find_callees(ctx.db, &chunk, 1, 5).await {
+          for r in callees {
+            if seen_ids.insert(r.chunk.id) {
+              related.push((r.chunk, 0.8, format!("callee:{}", r.call)));
             }
           }
         }
@@ -585,9 +687,10 @@ pub async fn get_file_context(
 
   // Read the file and extract context
   let file_path = root_path.join(&chunk.file_path);
-  let content = tokio::fs::read_to_string(&file_path)
+  let bytes = tokio::fs::read(&file_path)
     .await
     .map_err(|e| ServiceError::project(format!("Failed to read file: {}", e)))?;
+  let content = encoding::decode(&bytes).text;
 
   let lines: Vec<&str> = content.lines().collect();
   let total_lines = lines.len();
@@ -0,0 +1,185 @@
+//! Go-to-definition resolution.
+//!
+//! Resolves a symbol to the chunks defining it. Candidates come from the
+//! definitions recorded by the parser at index time; when the file the symbol
+//! is referenced from is known, its imports are resolved to project modules
+//! and used to rank the definition the reference most likely points at first.
+
+use std::collections::HashSet;
+
+use super::context::find_definitions;
+use crate::{
+  context::files::code::parser::resolve::{import_binds, module_matches, resolve_import},
+  db::ProjectDb,
+  domain::code::{CallEdge, CodeChunk},
+  ipc::types::code::{CodeDefinitionItem, CodeDefinitionResponse},
+  service::util::ServiceError,
+};
+
+/// Maximum candidate chunks considered for a symbol
+const MAX_DEFINITION_CANDIDATES: usize = 100;
+
+/// How a definition was matched to the reference, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Resolution {
+  /// Defined in the referencing file
+  SameFile,
+  /// Defined in a module the referencing file imports the symbol from
+  Import,
+  /// Defined in a module the referencing file imports
+  ImportedModule,
+  /// Defined next to the referencing file
+  SameDirectory,
+  /// Only matched by name
+  Global,
+}
+
+impl Resolution {
+  pub(super) fn as_str(&self) -> &'static str {
+    match self {
+      Self::SameFile => "same_file",
+      Self::Import => "import",
+      Self::ImportedModule => "imported_module",
+      Self::SameDirectory => "same_directory",
+      Self::Global => "global",
+    }
+  }
+}
+
+/// Parameters for definition lookup.
+#[derive(Debug, Clone)]
+pub struct DefinitionParams {
+  /// Symbol to resolve, optionally qualified (`Config::load`, `utils.format`)
+  pub symbol: String,
+  /// File the symbol is referenced from
+  pub file_path: Option<String>,
+  /// Maximum definitions returned (default: 5)
+  pub limit: Option<usize>,
+}
+
+/// Resolve a symbol to its defining chunks, most likely first.
+pub async fn get_definition_response(
+  db: &ProjectDb,
+  params: DefinitionParams,
+) -> Result<CodeDefinitionResponse, ServiceError> {
+  let symbol = params.symbol.trim();
+  if symbol.is_empty() {
+    return Err(ServiceError::validation("symbol must not be empty"));
+  }
+  let limit = params.limit.unwrap_or(5);
+
+  let mut ranked = rank_definitions(db, symbol, params.file_path.as_deref()).await?;
+  ranked.truncate(limit);
+
+  let definitions: Vec<CodeDefinitionItem> = ranked
+    .iter()
+    .map(|(resolution, chunk)| CodeDefinitionItem::from_chunk(chunk, resolution.as_str()))
+    .collect();
+
+  Ok(CodeDefinitionResponse {
+    symbol: symbol.to_string(),
+    count: definitions.len(),
+    definitions,
+  })
+}
+
+/// Chunks defining `symbol`, most likely first for a reference from `file_path`
+pub(super) async fn rank_definitions(
+  db: &ProjectDb,
+  symbol: &str,
+  file_path: Option<&str>,
+) -> Result<Vec<(Resolution, CodeChunk)>, ServiceError> {
+  let name = CallEdge::name_of(symbol);
+  let qualifier = symbol
+    .strip_suffix(name)
+    .map(|q| q.trim_end_matches(['.', ':', '>', '-']))
+    .filter(|q| !q.is_empty())
+    .map(CallEdge::name_of);
+
+  let mut candidates = find_definitions(db, name, MAX_DEFINITION_CANDIDATES).await?;
+  // Prefer chunks the parser recorded as the definition over chunks that only mention it
+  if candidates.iter().any(|c| c.definition_name.as_deref() == Some(name)) {
+    candidates.retain(|c| c.definition_name.as_deref() == Some(name));
+  }
+  if let Some(qualifier) = qualifier
+    && candidates
+      .iter()
+      .any(|c| c.parent_definition.as_deref() == Some(qualifier))
+  {
+    candidates.retain(|c| c.parent_definition.as_deref() == Some(qualifier));
+  }
+
+  let file_path = file_path.map(|p| p.trim_start_matches("./"));
+  let imports = match file_path {
+    Some(file_path) => ImportedModules::for_file(db, file_path, name, qualifier).await?,
+    None => ImportedModules::default(),
+  };
+
+  let mut ranked: Vec<(Resolution, CodeChunk)> = candidates
+    .into_iter()
+    .map(|chunk| (resolve(&chunk, file_path, &imports), chunk))
+    .collect();
+  ranked.sort_by(|(ra, a), (rb, b)| (ra, &a.file_path, a.start_line).cmp(&(rb, &b.file_path, b.start_line)));
+  Ok(ranked)
+}
+
+/// Modules imported by the referencing file
+#[derive(Debug, Default)]
+struct ImportedModules {
+  /// Modules the symbol (or its qualifier) is imported from
+  binding: Vec<String>,
+  /// All resolved modules
+  all: Vec<String>,
+}
+
+impl ImportedModules {
+  async fn for_file(
+    db: &ProjectDb,
+    file_path: &str,
+    name: &str,
+    qualifier: Option<&str>,
+  ) -> Result<Self, ServiceError> {
+    let chunks = db.get_chunks_for_file(file_path).await?;
+    let mut seen = HashSet::new();
+    let mut modules = Self::default();
+
+    for chunk in &chunks {
+      for import in chunk.imports.iter().filter(|i| seen.insert(i.as_str())) {
+        let resolved = resolve_import(import, file_path, chunk.language);
+        if import_binds(import, name) || qualifier.is_some_and(|q| import_binds(import, q)) {
+          modules.binding.extend(resolved.iter().cloned());
+        }
+        modules.all.extend(resolved);
+      }
+    }
+
+    Ok(modules)
+  }
+}
+
+fn resolve(chunk: &CodeChunk, file_path: Option<&str>, imports: &ImportedModules) -> Resolution {
+  let Some(file_path) = file_path else {
+    return Resolution::Global;
+  };
+  let matches = |modules: &[String]| {
+    modules
+      .iter()
+      .any(|m| module_matches(&chunk.file_path, m, chunk.language))
+  };
+
+  if chunk.file_path == file_path {
+    Resolution::SameFile
+  } else if matches(&imports.binding) {
+    Resolution::Import
+  } else if matches(&imports.all) {
+    Resolution::ImportedModule
+  } else if parent_dir(&chunk.file_path) == parent_dir(file_path) {
+    Resolution::SameDirectory
+  } else {
+    Resolution::Global
+  }
+}
+
+pub(super) fn parent_dir(path: &str) -> &str {
+  path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
@@ -12,8 +12,19 @@ use tokio::sync::mpsc;
 use tracing::warn;
 
 use crate::{
-  actor::{handle::IndexerHandle, message::IndexProgress},
-  domain::code::Language,
+  actor::{
+    handle::IndexerHandle,
+    message::{IndexJob, IndexProgress},
+  },
+  context::files::{guard::IndexGuard, is_document_extension},
+  db::{ProjectDb, SkippedFile},
+  domain::{
+    code::Language,
+    git,
+    path::{PathFilter, relative_path},
+  },
+  ipc::types::code::{CodeDecodeWarning, CodeIndexChangedResult, CodeSkippedItem},
+  service::util::ServiceError,
 };
 
 /// Result of scanning a directory for code files.
@@ -23,23 +34,43 @@ pub struct ScanResult {
   pub files: Vec<PathBuf>,
   /// Total bytes across all files
   pub total_bytes: u64,
+  /// Files left out for being over the size limit
+  pub skipped: Vec<SkippedFile>,
   /// Time taken to scan
   pub duration: Duration,
 }
 
-/// Parameters for scanning.
+/// Average bytes of source text per chunk, for space estimates
+const ESTIMATED_CHUNK_BYTES: u64 = 1024;
+
+impl ScanResult {
+  /// Disk space indexing the scanned files is expected to take.
+  ///
+  /// Chunk text is stored twice (content and embedding text) next to one
+  /// f32 vector per chunk. Reindexed files replace their old chunks, so the
+  /// estimate errs high.
+  pub fn estimated_index_bytes(&self, vector_dim: usize) -> u64 {
+    let chunks = self.total_bytes.div_ceil(ESTIMATED_CHUNK_BYTES);
+    self.total_bytes * 2 + chunks * vector_dim as u64 * 4
+  }
+}
+
+/// Files git reports as changed since a revision.
 #[derive(Debug, Clone)]
-pub struct ScanParams {
-  /// Maximum file size to include
-  pub max_file_size: u64,
+pub struct ChangedScan {
+  /// Changed files that still exist and should be indexed
+  pub scan: ScanResult,
+  /// Changed files that no longer exist and should be removed from the index
+  pub deleted: Vec<PathBuf>,
 }
 
-impl Default for ScanParams {
-  fn default() -> Self {
-    Self {
-      max_file_size: 1024 * 1024, // 1MB default
-    }
-  }
+/// Parameters for scanning.
+#[derive(Debug, Clone, Default)]
+pub struct ScanParams {
+  /// Size limit from config (the other guard rails apply once files are read)
+  pub guard: IndexGuard,
+  /// Include and exclude globs from config
+  pub filter: PathFilter,
 }
 
 /// Result of indexing files.
@@ -69,9 +100,13 @@ pub struct IndexResult {
   pub bytes_processed: u64,
   /// Total bytes
   pub total_bytes: u64,
+  /// Files decoded from content that was not valid UTF-8
+  pub decode_warnings: Vec<CodeDecodeWarning>,
 }
 
-/// Scan a directory for code files, respecting .gitignore.
+/// Scan a directory for code files, respecting .gitignore and the configured globs.
+///
+/// Files over the size limit are not read; they are returned as skipped.
 ///
 /// # Arguments
 /// * `root` - Root directory to scan
@@ -83,13 +118,14 @@ pub fn scan_directory(root: &Path, params: &ScanParams) -> ScanResult {
   let start = Instant::now();
   let mut files: Vec<PathBuf> = Vec::new();
   let mut total_bytes: u64 = 0;
+  let mut skipped = Vec::new();
 
   let walker = WalkBuilder::new(root)
     .hidden(true) // Respect hidden files (.gitignore default)
     .git_ignore(true) // Respect .gitignore
     .git_global(true) // Respect global gitignore
     .git_exclude(true) // Respect .git/info/exclude
-    .max_filesize(Some(params.max_file_size))
+    .overrides(params.filter.overrides().clone())
     .build();
 
   for entry in walker.flatten() {
@@ -104,10 +140,14 @@ pub fn scan_directory(root: &Path, params: &ScanParams) -> ScanResult {
     if let Some(ext) = path.extension().and_then(|e| e.to_str())
       && Language::from_extension(ext).is_some()
     {
-      // Track file size
-      if let Ok(metadata) = std::fs::metadata(path) {
-        total_bytes += metadata.len();
+      let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+      if let Some(relative) = relative_path(path, root)
+        && let Some(too_large) = params.guard.check_size(&relative, size)
+      {
+        skipped.push(too_large);
+        continue;
       }
+      total_bytes += size;
       files.push(path.to_path_buf());
     }
   }
@@ -115,10 +155,142 @@ pub fn scan_directory(root: &Path, params: &ScanParams) -> ScanResult {
   ScanResult {
     files,
     total_bytes,
+    skipped,
     duration: start.elapsed(),
   }
 }
 
+/// Collect the code files git reports as changed since `since`.
+///
+/// Replaces the full directory walk when only a few files moved, e.g. in CI or
+/// after a pull. Files over the size limit, in unsupported languages or left
+/// out by the configured globs are skipped, matching [`scan_directory`].
+pub async fn scan_changed(root: &Path, since: &str, params: &ScanParams) -> Result<ChangedScan, ServiceError> {
+  let start = Instant::now();
+  let (files, deleted) = changed_files(root, since, |ext| Language::from_extension(ext).is_some()).await?;
+
+  let mut kept = Vec::with_capacity(files.len());
+  let mut total_bytes = 0;
+  let mut skipped = Vec::new();
+  for path in files {
+    let Some(relative) = relative_path(&path, root) else {
+      continue;
+    };
+    if params.filter.is_excluded(&relative, false) {
+      continue;
+    }
+    let Ok(metadata) = tokio::fs::metadata(&path).await else {
+      continue;
+    };
+    if let Some(too_large) = params.guard.check_size(&relative, metadata.len()) {
+      skipped.push(too_large);
+      continue;
+    }
+    total_bytes += metadata.len();
+    kept.push(path);
+  }
+
+  Ok(ChangedScan {
+    scan: ScanResult {
+      files: kept,
+      total_bytes,
+      skipped,
+      duration: start.elapsed(),
+    },
+    deleted,
+  })
+}
+
+/// List the files the indexing guard rails left out, ordered by path.
+pub async fn list_skipped(db: &ProjectDb) -> Result<Vec<CodeSkippedItem>, ServiceError> {
+  Ok(db.list_skipped_files().await?.into_iter().map(skipped_item).collect())
+}
+
+/// Convert a skipped file to its IPC form
+pub(super) fn skipped_item(file: SkippedFile) -> CodeSkippedItem {
+  CodeSkippedItem {
+    file_path: file.file_path,
+    reason: file.reason.as_str().to_string(),
+    detail: file.detail,
+    skipped_at: file.skipped_at.to_rfc3339(),
+  }
+}
+
+/// Remove deleted files from the index.
+pub async fn remove_deleted(indexer: &IndexerHandle, deleted: Vec<PathBuf>) -> Result<(), ServiceError> {
+  for path in deleted {
+    indexer
+      .send(IndexJob::Delete { path })
+      .await
+      .map_err(|e| ServiceError::Internal(e.to_string()))?;
+  }
+  Ok(())
+}
+
+/// Reindex the files git reports as changed since a commit.
+///
+/// Lets git hooks keep the index fresh without the watcher running. Changed
+/// files that still exist are queued as one batch; removed files are deleted
+/// from the index. Files the indexer does not handle are skipped.
+pub async fn index_changed(
+  indexer: &IndexerHandle,
+  root: &Path,
+  since: &str,
+) -> Result<CodeIndexChangedResult, ServiceError> {
+  let (to_index, deleted) = changed_files(root, since, |ext| {
+    Language::from_extension(ext).is_some() || is_document_extension(ext)
+  })
+  .await?;
+
+  let queued = to_index.len();
+  let files_deleted = deleted.len();
+  remove_deleted(indexer, deleted).await?;
+
+  if !to_index.is_empty() {
+    indexer
+      .index_batch(to_index, None)
+      .await
+      .map_err(|e| ServiceError::Internal(e.to_string()))?;
+  }
+
+  Ok(CodeIndexChangedResult {
+    since: since.to_string(),
+    files_queued: queued,
+    files_deleted,
+  })
+}
+
+/// Split files changed since `since` into existing and deleted paths,
+/// keeping only extensions accepted by `indexable`.
+async fn changed_files(
+  root: &Path,
+  since: &str,
+  indexable: impl Fn(&str) -> bool,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>), ServiceError> {
+  let changed = git::changed_since_commit(root, since)
+    .await
+    .map_err(ServiceError::Validation)?;
+
+  let mut existing = Vec::new();
+  let mut deleted = Vec::new();
+  for relative in changed {
+    let path = root.join(&relative);
+    if !path.extension().and_then(|e| e.to_str()).is_some_and(&indexable) {
+      continue;
+    }
+
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+      existing.push(path);
+    } else {
+      deleted.push(path);
+    }
+  }
+
+  existing.sort();
+  deleted.sort();
+  Ok((existing, deleted))
+}
+
 /// Run the full indexing pipeline.
 ///
 /// # Arguments
@@ -149,6 +321,7 @@ pub async fn run_indexing(
       files_per_second: 0.0,
       bytes_processed: 0,
       total_bytes: 0,
+      decode_warnings: Vec::new(),
     };
   }
 
@@ -178,13 +351,17 @@ pub async fn run_indexing(
       files_per_second: 0.0,
       bytes_processed: 0,
       total_bytes,
+      decode_warnings: Vec::new(),
     };
   }
 
   // Wait for progress updates, forwarding to caller and capturing final result
   let mut chunks_created = 0;
+  let mut decode_warnings = Vec::new();
+
+  while let Some(mut progress) = internal_rx.recv().await {
+    decode_warnings.extend(progress.decode_warning.take());
 
-  while let Some(progress) = internal_rx.recv().await {
     // Forward to caller if they want progress updates
     if let Some(ref tx) = progress_tx {
       let _ = tx.send(progress.clone()).await;
@@ -198,7 +375,8 @@ pub async fn run_indexing(
   }
 
   // Drain any remaining progress messages
-  while let Ok(progress) = internal_rx.try_recv() {
+  while let Ok(mut progress) = internal_rx.try_recv() {
+    decode_warnings.extend(progress.decode_warning.take());
     if let Some(ref tx) = progress_tx {
       let _ = tx.send(progress.clone()).await;
     }
@@ -229,5 +407,6 @@ pub async fn run_indexing(
     files_per_second,
     bytes_processed: total_bytes,
     total_bytes,
+    decode_warnings,
   }
 }
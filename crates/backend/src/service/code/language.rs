@@ -0,0 +1,165 @@
+//! Language filters inferred from query text.
+//!
+//! Agents often say which language they mean in the query itself ("retry
+//! loop in the python worker", "django views") instead of passing
+//! `language`. When a query names exactly one language, or a framework that
+//! only exists in one, code search narrows to it.
+//!
+//! Words that are also plain English ("go", "c", "swift", "react") only
+//! count with a cue next to them: "in go", "go code", "react component".
+//! Data and markup formats are never inferred, since "parse the yaml config"
+//! usually means the code that parses it.
+
+use crate::domain::code::Language;
+
+/// A query term and the languages it stands for
+struct Term {
+  word: &'static str,
+  /// Only counts next to a cue word
+  needs_cue: bool,
+  languages: &'static [Language],
+}
+
+const TYPESCRIPT: &[Language] = &[Language::TypeScript, Language::Tsx];
+const JAVASCRIPT: &[Language] = &[Language::JavaScript, Language::Jsx];
+const REACT: &[Language] = &[Language::Tsx, Language::Jsx];
+
+const fn term(word: &'static str, languages: &'static [Language]) -> Term {
+  Term {
+    word,
+    needs_cue: false,
+    languages,
+  }
+}
+
+const fn cued(word: &'static str, languages: &'static [Language]) -> Term {
+  Term {
+    word,
+    needs_cue: true,
+    languages,
+  }
+}
+
+const TERMS: &[Term] = &[
+  term("typescript", TYPESCRIPT),
+  term("ts", TYPESCRIPT),
+  term("javascript", JAVASCRIPT),
+  term("js", JAVASCRIPT),
+  term("nodejs", JAVASCRIPT),
+  cued("react", REACT),
+  term("tsx", &[Language::Tsx]),
+  term("jsx", &[Language::Jsx]),
+  term("rust", &[Language::Rust]),
+  term("python", &[Language::Python]),
+  term("py", &[Language::Python]),
+  term("django", &[Language::Python]),
+  term("flask", &[Language::Python]),
+  term("fastapi", &[Language::Python]),
+  term("golang", &[Language::Go]),
+  cued("go", &[Language::Go]),
+  term("java", &[Language::Java]),
+  term("kotlin", &[Language::Kotlin]),
+  term("scala", &[Language::Scala]),
+  term("csharp", &[Language::CSharp]),
+  term("c#", &[Language::CSharp]),
+  term("dotnet", &[Language::CSharp]),
+  term("cpp", &[Language::Cpp]),
+  term("c++", &[Language::Cpp]),
+  cued("c", &[Language::C]),
+  cued("swift", &[Language::Swift]),
+  term("swiftui", &[Language::Swift]),
+  term("ruby", &[Language::Ruby]),
+  term("rails", &[Language::Ruby]),
+  term("php", &[Language::Php]),
+  term("laravel", &[Language::Php]),
+  cued("lua", &[Language::Lua]),
+  term("elixir", &[Language::Elixir]),
+  term("phoenix", &[Language::Elixir]),
+  term("haskell", &[Language::Haskell]),
+  term("ocaml", &[Language::Ocaml]),
+  term("clojure", &[Language::Clojure]),
+  cued("zig", &[Language::Zig]),
+  cued("nim", &[Language::Nim]),
+  cued("shell", &[Language::Shell]),
+  term("bash", &[Language::Shell]),
+];
+
+/// Words before a cued term that make it a language ("in go")
+const CUES_BEFORE: &[&str] = &["in", "using", "with"];
+
+/// Words after a cued term that make it a language ("go code")
+const CUES_AFTER: &[&str] = &[
+  "code",
+  "file",
+  "files",
+  "module",
+  "modules",
+  "function",
+  "functions",
+  "script",
+  "scripts",
+  "package",
+  "service",
+  "worker",
+  "side",
+  "version",
+  "implementation",
+  "component",
+  "components",
+  "hook",
+  "hooks",
+];
+
+/// Languages a query asks for, or `None` when it names none or more than one
+pub fn infer(query: &str) -> Option<&'static [Language]> {
+  let lower = query.to_lowercase();
+  let words: Vec<&str> = lower
+    .split(|c: char| !(c.is_alphanumeric() || c == '#' || c == '+'))
+    .filter(|w| !w.is_empty())
+    .collect();
+
+  let mut found: Option<&'static [Language]> = None;
+  for (i, word) in words.iter().enumerate() {
+    let Some(term) = TERMS.iter().find(|t| t.word == *word) else {
+      continue;
+    };
+    if term.needs_cue {
+      let before = i.checked_sub(1).map(|j| words[j]);
+      let after = words.get(i + 1).copied();
+      let cued = before.is_some_and(|w| CUES_BEFORE.contains(&w)) || after.is_some_and(|w| CUES_AFTER.contains(&w));
+      if !cued {
+        continue;
+      }
+    }
+    match found {
+      None => found = Some(term.languages),
+      Some(languages) if languages == term.languages => {}
+      Some(_) => return None,
+    }
+  }
+  found
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_infer_from_language_and_framework_names() {
+    assert_eq!(infer("retry loop in the Python worker"), Some(&[Language::Python][..]));
+    assert_eq!(infer("django views for signup"), Some(&[Language::Python][..]));
+    assert_eq!(infer("typescript api client"), Some(TYPESCRIPT));
+    assert_eq!(infer("where is C++ allocation"), Some(&[Language::Cpp][..]));
+    assert_eq!(infer("error handling in go"), Some(&[Language::Go][..]));
+    assert_eq!(infer("go code for retries"), Some(&[Language::Go][..]));
+  }
+
+  #[test]
+  fn test_infer_skips_plain_words_and_mixed_languages() {
+    assert_eq!(infer("how do requests go through the router"), None);
+    assert_eq!(infer("plan a swift rollout"), None);
+    assert_eq!(infer("react to queue errors"), None);
+    assert_eq!(infer("parse the yaml config"), None);
+    assert_eq!(infer("rust bindings for the python sdk"), None);
+  }
+}
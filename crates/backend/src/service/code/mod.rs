@@ -4,20 +4,31 @@
 //! - Query expansion and intent detection
 //! - Code search with ranking and symbol boosting
 //! - Code context retrieval (callers, callees, siblings, related)
+//! - Go-to-definition resolution
+//! - Find-references
 //! - Code statistics
 //! - Code indexing (file scanning)
+//! - Index run planning
 //! - Code chunk import
 //!
 //! ## Services
 //!
 //! - [`search`] - Code search with vector/text fallback and ranking
+//! - [`language`] - Language filters inferred from query text
 //! - [`context`] - Call graph navigation and context retrieval
+//! - [`definition`] - Symbol definition lookup with import resolution
+//! - [`references`] - Symbol references from call edges and identifier matches
 //! - [`stats`] - Code index statistics
 //! - [`index`] - File scanning for code indexing
+//! - [`plan`] - Estimated chunks, tokens and cost of an index run
 //! - [`import`] - Direct chunk import
 
 pub mod context;
+pub mod definition;
 pub mod index;
+pub mod language;
+pub mod plan;
+pub mod references;
 pub mod search;
 pub mod startup_scan;
 pub mod stats;
@@ -27,7 +38,9 @@ pub use context::{
   CalleesParams, CallersParams, ContextFullParams, RelatedParams, get_callees_response, get_callers_response,
   get_full_context, get_related, get_related_memories,
 };
+pub use definition::{DefinitionParams, get_definition_response};
+pub use references::{ReferencesParams, get_references_response};
 // Re-export commonly used items from search
-pub use search::{CodeContext, RankingConfig, SearchParams, search};
+pub use search::{CodeContext, GitScope, RankingConfig, SearchParams, search};
 // Re-export commonly used items from stats
 pub use stats::get_stats;
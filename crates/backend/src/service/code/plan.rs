@@ -0,0 +1,180 @@
+//! Index run planning.
+//!
+//! Runs the reader and parser stages over scanned files without embedding or
+//! writing anything, so the cost of an index run is known before it starts.
+//! Chunks whose content hash is already indexed reuse their embedding, so on
+//! an indexed project only changed code counts towards the estimate. Shallow
+//! dependency files are never embedded and cost nothing.
+
+use std::{collections::BTreeMap, path::Path, time::Instant};
+
+use tracing::debug;
+
+use super::index::{ScanResult, skipped_item};
+use crate::{
+  config::CHARS_PER_TOKEN,
+  context::files::{FileMetadata, Indexer, depth::IndexDepth, extract::read_text},
+  db::ProjectDb,
+  domain::path::relative_path,
+  ipc::types::code::CodeIndexPlan,
+};
+
+/// Estimate what indexing the scanned files would take.
+///
+/// `indexer` should carry the project's guard rails and depth rules; files it
+/// would refuse are listed as skipped. Estimated cost is only set when `cost_per_million` is.
+pub async fn plan_indexing(
+  db: &ProjectDb,
+  root: &Path,
+  scan: ScanResult,
+  mut indexer: Indexer,
+  cost_per_million: Option<f64>,
+) -> CodeIndexPlan {
+  let start = Instant::now();
+  let mut plan = CodeIndexPlan {
+    files_scanned: scan.files.len() + scan.skipped.len(),
+    total_bytes: scan.total_bytes,
+    skipped: scan.skipped.into_iter().map(skipped_item).collect(),
+    ..CodeIndexPlan::default()
+  };
+  let mut embedding_chars = 0;
+  let mut languages: BTreeMap<String, usize> = BTreeMap::new();
+
+  for path in &scan.files {
+    let (Some(metadata), Some(relative)) = (indexer.scan_file(path, root), relative_path(path, root)) else {
+      continue;
+    };
+    let content = match read_text(path).await {
+      Ok(decoded) => decoded.text,
+      Err(e) => {
+        debug!(file = %relative, error = %e, "Failed to read file for plan");
+        plan.failed_files += 1;
+        continue;
+      }
+    };
+
+    let is_code = matches!(metadata, FileMetadata::Code { .. });
+    if let Some(skipped) = indexer.guard().check_content(&relative, &content, is_code) {
+      plan.skipped.push(skipped_item(skipped));
+      continue;
+    }
+    let chunks = match indexer.chunk_file(&content, &metadata, None) {
+      Ok(chunks) => chunks,
+      Err(e) => {
+        debug!(file = %relative, error = %e, "Failed to chunk file for plan");
+        plan.failed_files += 1;
+        continue;
+      }
+    };
+    let shallow = indexer.depth(&metadata) == IndexDepth::Shallow;
+    let chunks = if shallow {
+      Indexer::shallow_chunks(chunks)
+    } else {
+      chunks
+    };
+    if let Some(skipped) = indexer.guard().check_chunks(&relative, chunks.len()) {
+      plan.skipped.push(skipped_item(skipped));
+      continue;
+    }
+    if shallow {
+      plan.chunks += chunks.len();
+      plan.files_shallow += 1;
+      continue;
+    }
+
+    let existing = indexer.get_existing_embeddings(db, &relative).await.unwrap_or_default();
+    let mut to_embed = 0;
+    for chunk in &chunks {
+      if indexer.cache_key(chunk).is_some_and(|key| existing.contains_key(&key)) {
+        plan.chunks_reused += 1;
+      } else {
+        to_embed += 1;
+        embedding_chars += indexer.prepare_embedding_text(chunk).len();
+      }
+    }
+
+    plan.chunks += chunks.len();
+    plan.chunks_to_embed += to_embed;
+    if to_embed == 0 {
+      plan.files_unchanged += 1;
+      continue;
+    }
+    plan.files_to_index += 1;
+    if let FileMetadata::Code { language, .. } = &metadata {
+      *languages.entry(language.as_db_str().to_string()).or_default() += to_embed;
+    }
+  }
+
+  plan.estimated_tokens = embedding_chars.div_ceil(CHARS_PER_TOKEN) as u64;
+  plan.estimated_cost_usd = cost_per_million.map(|price| plan.estimated_tokens as f64 / 1_000_000.0 * price);
+  plan.languages = languages;
+  plan.skipped.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+  plan.duration_ms = (scan.duration + start.elapsed()).as_millis() as u64;
+  plan
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use tempfile::TempDir;
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::{
+    config::{Config, IndexConfig},
+    context::files::guard::IndexGuard,
+    domain::project::ProjectId,
+    service::code::index::{ScanParams, scan_directory},
+  };
+
+  #[tokio::test]
+  async fn test_plan_counts_chunks_without_writing() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().join("repo");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+      root.join("src/lib.rs"),
+      "pub fn add(a: i32, b: i32) -> i32 {\n  a + b\n}\n\npub fn sub(a: i32, b: i32) -> i32 {\n  a - b\n}\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/big.rs"), format!("// {}\n", "x".repeat(200))).unwrap();
+
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(
+      project_id,
+      temp.path().join("test.lancedb"),
+      Arc::new(Config::default()),
+    )
+    .await
+    .unwrap();
+    let guard = IndexGuard::from_config(&IndexConfig {
+      max_file_size: 150,
+      ..IndexConfig::default()
+    });
+    let scan = scan_directory(
+      &root,
+      &ScanParams {
+        guard: guard.clone(),
+        ..ScanParams::default()
+      },
+    );
+    let indexer = Indexer::new(Uuid::nil()).with_guard(guard);
+
+    let plan = plan_indexing(&db, &root, scan, indexer, Some(0.02)).await;
+    assert_eq!(plan.files_scanned, 2);
+    assert_eq!(plan.files_to_index, 1);
+    assert!(plan.chunks_to_embed > 0);
+    assert_eq!(plan.chunks_reused, 0, "nothing is indexed yet");
+    assert!(plan.estimated_tokens > 0);
+    assert!(plan.estimated_cost_usd.is_some_and(|cost| cost > 0.0));
+    assert_eq!(plan.languages.get("rust"), Some(&plan.chunks_to_embed));
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].reason, "too_large");
+
+    assert!(
+      db.list_skipped_files().await.unwrap().is_empty(),
+      "a plan records nothing"
+    );
+  }
+}
@@ -0,0 +1,219 @@
+//! Find-references.
+//!
+//! Collects the chunks referencing a symbol from two sources: the call edges
+//! recorded at index time, and chunks whose content mentions the symbol as a
+//! whole identifier (type annotations, imports, non-call uses). References are
+//! ranked by proximity to the module defining the symbol, so uses inside and
+//! next to the definition come before distant ones.
+
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use super::{
+  context::get_chunks_by_ids,
+  definition::{parent_dir, rank_definitions},
+};
+use crate::{
+  context::files::code::parser::resolve::{module_matches, resolve_import},
+  db::{FilterBuilder, ProjectDb},
+  domain::code::{CallEdge, CodeChunk},
+  ipc::types::code::{CodeDefinitionItem, CodeReferenceItem, CodeReferencesResponse},
+  service::util::ServiceError,
+};
+
+/// Maximum chunks scanned for identifier mentions
+const MAX_MENTION_CANDIDATES: usize = 500;
+
+/// How close a reference is to the definition's module, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Proximity {
+  /// In the file defining the symbol
+  SameFile,
+  /// In a file importing the defining module
+  Imports,
+  /// Next to the defining file
+  SameDirectory,
+  /// Anywhere else, or the definition is unknown
+  Elsewhere,
+}
+
+impl Proximity {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::SameFile => "same_file",
+      Self::Imports => "imports",
+      Self::SameDirectory => "same_directory",
+      Self::Elsewhere => "elsewhere",
+    }
+  }
+}
+
+/// How a chunk references the symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ReferenceKind {
+  /// Recorded call edge
+  Call,
+  /// Identifier match in the chunk content
+  Mention,
+}
+
+impl ReferenceKind {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Call => "call",
+      Self::Mention => "mention",
+    }
+  }
+}
+
+/// Parameters for references lookup.
+#[derive(Debug, Clone)]
+pub struct ReferencesParams {
+  /// Symbol to find references to, optionally qualified (`Config::load`)
+  pub symbol: String,
+  /// File the symbol is referenced from, used to pick its definition
+  pub file_path: Option<String>,
+  /// Maximum references returned (default: 50)
+  pub limit: Option<usize>,
+}
+
+/// Find the chunks referencing a symbol, nearest to its definition first.
+pub async fn get_references_response(
+  db: &ProjectDb,
+  params: ReferencesParams,
+) -> Result<CodeReferencesResponse, ServiceError> {
+  let symbol = params.symbol.trim();
+  if symbol.is_empty() {
+    return Err(ServiceError::validation("symbol must not be empty"));
+  }
+  let limit = params.limit.unwrap_or(50);
+  let name = CallEdge::name_of(symbol);
+
+  let definition = rank_definitions(db, symbol, params.file_path.as_deref())
+    .await?
+    .into_iter()
+    .next();
+  let definition_chunk = definition.as_ref().map(|(_, chunk)| chunk);
+
+  let mut seen = HashSet::new();
+  let mut references: Vec<(Proximity, ReferenceKind, CodeChunk)> = Vec::new();
+
+  let edges = db.get_call_edges_to(&[name.to_string()]).await?;
+  let caller_ids: Vec<Uuid> = edges
+    .iter()
+    .filter(|e| e.callee_name == name)
+    .map(|e| e.caller_id)
+    .filter(|id| seen.insert(*id))
+    .collect();
+  for chunk in get_chunks_by_ids(db, &caller_ids).await? {
+    references.push((proximity(&chunk, definition_chunk), ReferenceKind::Call, chunk));
+  }
+
+  // One past the cap tells whether mentions were left unscanned
+  let filter = FilterBuilder::new().add_like("content", name).build_or_empty();
+  let mut candidates = db
+    .list_code_chunks(Some(&filter), Some(MAX_MENTION_CANDIDATES + 1))
+    .await?;
+  let truncated = candidates.len() > MAX_MENTION_CANDIDATES;
+  candidates.truncate(MAX_MENTION_CANDIDATES);
+  for chunk in candidates {
+    // The definition itself mentions the name but isn't a reference to it
+    if chunk.definition_name.as_deref() == Some(name) || !contains_identifier(&chunk.content, name) {
+      continue;
+    }
+    if seen.insert(chunk.id) {
+      references.push((proximity(&chunk, definition_chunk), ReferenceKind::Mention, chunk));
+    }
+  }
+
+  references.sort_by(|(pa, ka, a), (pb, kb, b)| {
+    (pa, ka, &a.file_path, a.start_line).cmp(&(pb, kb, &b.file_path, b.start_line))
+  });
+  let total = references.len();
+  references.truncate(limit);
+
+  let items: Vec<CodeReferenceItem> = references
+    .iter()
+    .map(|(proximity, kind, chunk)| {
+      CodeReferenceItem::from_chunk(chunk, kind.as_str(), proximity.as_str(), identifier_lines(chunk, name))
+    })
+    .collect();
+
+  Ok(CodeReferencesResponse {
+    symbol: symbol.to_string(),
+    definition: definition.map(|(resolution, chunk)| CodeDefinitionItem::from_chunk(&chunk, resolution.as_str())),
+    count: items.len(),
+    total,
+    truncated,
+    references: items,
+  })
+}
+
+fn proximity(chunk: &CodeChunk, definition: Option<&CodeChunk>) -> Proximity {
+  let Some(definition) = definition else {
+    return Proximity::Elsewhere;
+  };
+
+  if chunk.file_path == definition.file_path {
+    Proximity::SameFile
+  } else if chunk.imports.iter().any(|import| {
+    resolve_import(import, &chunk.file_path, chunk.language)
+      .iter()
+      .any(|m| module_matches(&definition.file_path, m, definition.language))
+  }) {
+    Proximity::Imports
+  } else if parent_dir(&chunk.file_path) == parent_dir(&definition.file_path) {
+    Proximity::SameDirectory
+  } else {
+    Proximity::Elsewhere
+  }
+}
+
+/// Line numbers in `chunk` where `name` appears as an identifier
+fn identifier_lines(chunk: &CodeChunk, name: &str) -> Vec<u32> {
+  chunk
+    .content
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| contains_identifier(line, name))
+    .map(|(i, _)| chunk.start_line + i as u32)
+    .collect()
+}
+
+/// Whether `text` contains `name` delimited by non-identifier characters
+fn contains_identifier(text: &str, name: &str) -> bool {
+  if name.is_empty() {
+    return false;
+  }
+  let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+  text.match_indices(name).any(|(start, _)| {
+    let before = text[..start].chars().next_back();
+    let after = text[start + name.len()..].chars().next();
+    !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_contains_identifier_respects_boundaries() {
+    assert!(contains_identifier("let db = ProjectDb::open(path);", "ProjectDb"));
+    assert!(contains_identifier("fn run(db: &ProjectDb)", "ProjectDb"));
+    assert!(
+      !contains_identifier("let db = ProjectDbPool::new();", "ProjectDb"),
+      "longer identifiers should not match"
+    );
+    assert!(
+      !contains_identifier("get_user_name()", "user"),
+      "snake_case parts should not match"
+    );
+    assert!(
+      contains_identifier("let username = user;", "user"),
+      "a later occurrence should match when an earlier one doesn't"
+    );
+  }
+}
@@ -4,17 +4,28 @@
 //! including vector search, optional FTS keyword search with RRF fusion,
 //! optional cross-encoder reranking, and multi-signal ranking.
 
-use std::{cmp::Ordering, collections::HashMap};
+use std::{
+  cmp::Ordering,
+  collections::{HashMap, HashSet},
+  path::{Path, PathBuf},
+  sync::{Arc, LazyLock, Mutex},
+};
 
 use tracing::{debug, warn};
 
+use super::language;
 use crate::{
-  db::ProjectDb,
-  domain::{code::CodeChunk, config::SearchConfig},
+  db::{FilterBuilder, ProjectDb},
+  domain::{
+    alias::Aliases,
+    code::{CodeChunk, Language},
+    config::SearchConfig,
+    git,
+  },
   embedding::EmbeddingProvider,
-  ipc::types::code::{CodeItem, SearchQuality},
+  ipc::types::code::{CodeItem, CodeLocation, SearchQuality},
   rerank::{RerankCandidate, RerankRequest, RerankerProvider},
-  service::util::{FilterBuilder, ServiceError, fusion},
+  service::util::{ServiceError, diversity::cap_per_file, fusion},
 };
 
 // ============================================================================
@@ -29,12 +40,24 @@ pub struct CodeContext<'a> {
   pub db: &'a ProjectDb,
   /// Optional embedding provider for vector search
   pub embedding: &'a dyn EmbeddingProvider,
+  /// Project jargon, expanded in search queries
+  pub aliases: Option<&'a Aliases>,
 }
 
 impl<'a> CodeContext<'a> {
   /// Create a new code context
   pub fn new(db: &'a ProjectDb, embedding: &'a dyn EmbeddingProvider) -> Self {
-    Self { db, embedding }
+    Self {
+      db,
+      embedding,
+      aliases: None,
+    }
+  }
+
+  /// Expand search queries with the project's aliases
+  pub fn with_aliases(mut self, aliases: &'a Aliases) -> Self {
+    self.aliases = Some(aliases);
+    self
   }
 
   /// Get an embedding for the given text, if a provider is available
@@ -85,6 +108,33 @@ pub struct SearchParams {
   ///
   /// Default: false (returns up to `limit` results regardless of confidence)
   pub adaptive_limit: bool,
+
+  /// Restrict results to what is current in a git checkout
+  pub git: Option<GitScope>,
+
+  /// Infer a language filter from the query when `language` is not set.
+  /// Falls back to all languages when nothing in the inferred one matches.
+  pub infer_language: bool,
+
+  /// Rank generated code like hand-written code instead of demoting it
+  pub include_generated: bool,
+
+  /// Most results kept per file; 0 keeps all
+  pub max_per_file: usize,
+}
+
+/// Git scoping for code search.
+///
+/// Chunks record the branch and commit they were indexed from, so an index
+/// built across branch switches can be narrowed back to the working tree.
+#[derive(Debug, Clone)]
+pub struct GitScope {
+  /// Repository root used to read HEAD and run diffs
+  pub root: PathBuf,
+  /// Drop chunks indexed from another commit whose file has changed since
+  pub current_branch: bool,
+  /// Only return chunks from files that differ between this branch and HEAD
+  pub diff_branch: Option<String>,
 }
 
 /// Configuration for code search ranking.
@@ -124,6 +174,8 @@ pub struct SearchResult {
   pub query: String,
   /// Search quality metadata
   pub search_quality: SearchQuality,
+  /// Language filter inferred from the query, when it was applied
+  pub inferred_language: Option<String>,
 }
 
 // ============================================================================
@@ -139,20 +191,70 @@ pub struct SearchResult {
 /// When a reranker is provided, the top candidates after fusion are reranked
 /// with position-aware score blending.
 pub async fn search(
+  ctx: &CodeContext<'_>,
+  mut params: SearchParams,
+  config: &RankingConfig,
+  search_config: Option<&SearchConfig>,
+  reranker: Option<&dyn RerankerProvider>,
+) -> Result<SearchResult, ServiceError> {
+  if let Some(aliases) = ctx.aliases {
+    params.query = aliases.expand(&params.query);
+  }
+
+  // A language named in the query narrows the search, unless nothing in it matches
+  if params.infer_language
+    && params.language.is_none()
+    && let Some(languages) = language::infer(&params.query)
+  {
+    let names: Vec<&str> = languages.iter().map(Language::as_db_str).collect();
+    let result = search_scoped(ctx, params.clone(), config, search_config, reranker, &names).await?;
+    if !result.results.is_empty() {
+      debug!(languages = ?names, "Code search narrowed to inferred language");
+      return Ok(SearchResult {
+        inferred_language: Some(names[0].to_string()),
+        ..result
+      });
+    }
+    debug!(languages = ?names, "No results in inferred language, searching all");
+  }
+
+  search_scoped(ctx, params, config, search_config, reranker, &[]).await
+}
+
+/// Search restricted to `languages` on top of the filters in `params`; an
+/// empty slice searches all languages.
+async fn search_scoped(
   ctx: &CodeContext<'_>,
   params: SearchParams,
   config: &RankingConfig,
   search_config: Option<&SearchConfig>,
   reranker: Option<&dyn RerankerProvider>,
+  languages: &[&str],
 ) -> Result<SearchResult, ServiceError> {
   let limit = params.limit.unwrap_or(10);
 
+  let git_filters = match &params.git {
+    Some(scope) => match git_conditions(ctx.db, scope).await? {
+      Some(conditions) => conditions,
+      None => {
+        return Ok(SearchResult {
+          results: Vec::new(),
+          query: params.query,
+          search_quality: SearchQuality::from_distances(&[]),
+          inferred_language: None,
+        });
+      }
+    },
+    None => Vec::new(),
+  };
+
   // Build filter using FilterBuilder for all metadata filters
-  let filter = FilterBuilder::new()
+  let mut filter = FilterBuilder::new()
     .add_eq_opt(
       "language",
       params.language.as_ref().map(|l| l.to_lowercase()).as_deref(),
     )
+    .add_in("language", languages)
     .add_in_opt(
       "visibility",
       if params.visibility.is_empty() {
@@ -169,8 +271,11 @@ pub async fn search(
         Some(&params.chunk_type)
       },
     )
-    .add_min_u32_opt("caller_count", params.min_caller_count)
-    .build();
+    .add_min_u32_opt("caller_count", params.min_caller_count);
+  for condition in git_filters {
+    filter = filter.add_raw(condition);
+  }
+  let filter = filter.build();
 
   debug!("Code search: query='{}'", params.query);
 
@@ -219,6 +324,100 @@ pub async fn search(
   }
 }
 
+/// Build filter conditions for a git scope.
+///
+/// Returns `None` when nothing can match, i.e. no files differ from `diff_branch`.
+async fn git_conditions(db: &ProjectDb, scope: &GitScope) -> Result<Option<Vec<String>>, ServiceError> {
+  let mut conditions = Vec::new();
+
+  if let Some(branch) = &scope.diff_branch {
+    let changed = git::changed_since_branch(&scope.root, branch)
+      .await
+      .map_err(ServiceError::Validation)?;
+    if changed.is_empty() {
+      return Ok(None);
+    }
+    let paths: Vec<&str> = changed.iter().map(String::as_str).collect();
+    conditions.extend(FilterBuilder::new().add_in("file_path", &paths).build());
+  }
+
+  if scope.current_branch
+    && let Some(head) = git::GitHead::read(&scope.root).await
+  {
+    // Uncommitted changes are shared by every indexed commit, so diff the
+    // working tree once and add each commit's cached diff to HEAD
+    let uncommitted = match &head.commit {
+      Some(head_commit) => git::changed_since_commit(&scope.root, head_commit)
+        .await
+        .unwrap_or_else(|e| {
+          warn!(error = %e, "Cannot diff working tree, ignoring uncommitted changes");
+          HashSet::new()
+        }),
+      None => HashSet::new(),
+    };
+
+    // Chunks from other commits are still current if their file is unchanged
+    for commit in db.code_chunk_commits(head.commit.as_deref()).await? {
+      let committed = match &head.commit {
+        Some(head_commit) => changed_between_cached(&scope.root, &commit, head_commit).await,
+        None => git::changed_since_commit(&scope.root, &commit).await.map(Arc::new),
+      };
+      let committed = match committed {
+        Ok(changed) => changed,
+        Err(e) => {
+          warn!(commit = %commit, error = %e, "Cannot diff against indexed commit, keeping its chunks");
+          continue;
+        }
+      };
+      let changed: HashSet<&str> = committed.iter().chain(&uncommitted).map(String::as_str).collect();
+      if changed.is_empty() {
+        continue;
+      }
+      let paths: Vec<&str> = changed.into_iter().collect();
+      // IS NOT NULL keeps untracked chunks from evaluating to NULL under NOT
+      if let Some(stale) = FilterBuilder::new()
+        .add_is_not_null("git_commit")
+        .add_eq("git_commit", &commit)
+        .add_in("file_path", &paths)
+        .build()
+      {
+        conditions.push(format!("NOT ({stale})"));
+      }
+    }
+  }
+
+  Ok(Some(conditions))
+}
+
+/// Diffs between indexed commits and HEAD, keyed by repository root and the
+/// two commits. Entries for an old HEAD are dropped once the cache is full.
+static COMMIT_DIFFS: LazyLock<Mutex<HashMap<(PathBuf, String, String), Arc<HashSet<String>>>>> =
+  LazyLock::new(Default::default);
+
+/// Most commit pairs kept in [`COMMIT_DIFFS`]
+const COMMIT_DIFF_CACHE_SIZE: usize = 256;
+
+/// Files changed between `from` and `to`, running `git diff` only on a cache miss.
+async fn changed_between_cached(root: &Path, from: &str, to: &str) -> Result<Arc<HashSet<String>>, String> {
+  let key = (root.to_path_buf(), from.to_string(), to.to_string());
+  let cached = COMMIT_DIFFS
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .get(&key)
+    .cloned();
+  if let Some(changed) = cached {
+    return Ok(changed);
+  }
+
+  let changed = Arc::new(git::changed_between(root, from, to).await?);
+  let mut cache = COMMIT_DIFFS.lock().unwrap_or_else(|e| e.into_inner());
+  if cache.len() >= COMMIT_DIFF_CACHE_SIZE {
+    cache.retain(|(_, _, head), _| head == to);
+  }
+  cache.insert(key, changed.clone());
+  Ok(changed)
+}
+
 /// Hybrid search: parallel vector + FTS, RRF fusion, optional reranking.
 #[allow(clippy::too_many_arguments)]
 async fn search_hybrid(
@@ -295,7 +494,14 @@ async fn search_hybrid(
     })
     .collect();
 
+  if !params.include_generated {
+    demote_generated(&mut final_results);
+  }
   final_results.sort_by(|a, b| b.rank_score.partial_cmp(&a.rank_score).unwrap_or(Ordering::Equal));
+  collapse_copies(&mut final_results);
+  let more_in_file = cap_per_file(&mut final_results, params.max_per_file, |r| {
+    Some(r.chunk.file_path.as_str())
+  });
 
   // Build search quality from confidence scores
   let distances: Vec<f32> = final_results.iter().map(|r| 1.0 - r.confidence.min(1.0)).collect();
@@ -307,23 +513,14 @@ async fn search_hybrid(
     limit
   };
 
-  let items: Vec<CodeItem> = final_results
-    .into_iter()
-    .take(effective_limit)
-    .map(|r| {
-      let mut item = CodeItem::from_search_with_confidence(&r.chunk, r.rank_score, r.confidence);
-      if params.include_context {
-        item.imports = r.chunk.imports.clone();
-        item.calls = r.chunk.calls.clone();
-      }
-      item
-    })
-    .collect();
+  final_results.truncate(effective_limit);
+  let items = to_items(ctx.db, final_results, &more_in_file, params.include_context).await;
 
   Ok(SearchResult {
     results: items,
     query: params.query.clone(),
     search_quality,
+    inferred_language: None,
   })
 }
 
@@ -376,7 +573,14 @@ async fn search_vector_only(
       })
       .collect();
 
+    if !params.include_generated {
+      demote_generated(&mut final_results);
+    }
     final_results.sort_by(|a, b| b.rank_score.partial_cmp(&a.rank_score).unwrap_or(Ordering::Equal));
+    collapse_copies(&mut final_results);
+    let more_in_file = cap_per_file(&mut final_results, params.max_per_file, |r| {
+      Some(r.chunk.file_path.as_str())
+    });
 
     let distances: Vec<f32> = final_results.iter().map(|r| 1.0 - r.confidence.min(1.0)).collect();
     let search_quality = SearchQuality::from_distances(&distances);
@@ -387,28 +591,25 @@ async fn search_vector_only(
       limit
     };
 
-    let items: Vec<CodeItem> = final_results
-      .into_iter()
-      .take(effective_limit)
-      .map(|r| {
-        let mut item = CodeItem::from_search_with_confidence(&r.chunk, r.rank_score, r.confidence);
-        if params.include_context {
-          item.imports = r.chunk.imports.clone();
-          item.calls = r.chunk.calls.clone();
-        }
-        item
-      })
-      .collect();
+    final_results.truncate(effective_limit);
+    let items = to_items(ctx.db, final_results, &more_in_file, params.include_context).await;
 
     return Ok(SearchResult {
       results: items,
       query: params.query.clone(),
       search_quality,
+      inferred_language: None,
     });
   }
 
   // No reranker: use existing ranking with symbol boost
-  let ranked = rank_results(results, &params.query, config);
+  let mut ranked = rank_results(results, &params.query, config);
+  if !params.include_generated {
+    demote_generated(&mut ranked);
+    ranked.sort_by(|a, b| b.rank_score.partial_cmp(&a.rank_score).unwrap_or(Ordering::Equal));
+  }
+  collapse_copies(&mut ranked);
+  let more_in_file = cap_per_file(&mut ranked, params.max_per_file, |r| Some(r.chunk.file_path.as_str()));
 
   let distances: Vec<f32> = ranked.iter().map(|r| r.distance).collect();
   let search_quality = SearchQuality::from_distances(&distances);
@@ -419,23 +620,14 @@ async fn search_vector_only(
     limit
   };
 
-  let items: Vec<CodeItem> = ranked
-    .into_iter()
-    .take(effective_limit)
-    .map(|r| {
-      let mut item = CodeItem::from_search_with_confidence(&r.chunk, r.rank_score, r.confidence);
-      if params.include_context {
-        item.imports = r.chunk.imports.clone();
-        item.calls = r.chunk.calls.clone();
-      }
-      item
-    })
-    .collect();
+  ranked.truncate(effective_limit);
+  let items = to_items(ctx.db, ranked, &more_in_file, params.include_context).await;
 
   Ok(SearchResult {
     results: items,
     query: params.query.clone(),
     search_quality,
+    inferred_language: None,
   })
 }
 
@@ -500,6 +692,9 @@ async fn rerank_candidates_with_provider(
 // Ranking
 // ============================================================================
 
+/// Rank score multiplier for generated code
+const GENERATED_PENALTY: f32 = 0.5;
+
 /// Result of ranking a code chunk, including both scores.
 #[derive(Debug, Clone)]
 pub struct RankedResult {
@@ -551,6 +746,67 @@ pub fn rank_results(results: Vec<(CodeChunk, f32)>, query: &str, config: &Rankin
   scored
 }
 
+/// Scale down the rank score of generated chunks so hand-written code with
+/// a similar match comes first. Callers re-sort afterwards.
+fn demote_generated(results: &mut [RankedResult]) {
+  for result in results.iter_mut().filter(|r| r.chunk.generated) {
+    result.rank_score *= GENERATED_PENALTY;
+  }
+}
+
+/// Keep the best-ranked of each set of identical chunks, so copies of one
+/// helper don't crowd out other results. `to_items` lists where the others are.
+fn collapse_copies(results: &mut Vec<RankedResult>) {
+  let mut seen = HashSet::new();
+  results.retain(|r| match &r.chunk.content_hash {
+    Some(hash) => seen.insert(hash.clone()),
+    None => true,
+  });
+}
+
+/// Convert ranked results to items, listing the other places each result's
+/// code appears and, by position, the hits each file lost to the per-file cap
+async fn to_items(
+  db: &ProjectDb,
+  results: Vec<RankedResult>,
+  more_in_file: &HashMap<usize, usize>,
+  include_context: bool,
+) -> Vec<CodeItem> {
+  let hashes: Vec<String> = results.iter().filter_map(|r| r.chunk.content_hash.clone()).collect();
+  let copies = db.get_code_chunks_by_content_hash(&hashes).await.unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to look up copies of code results");
+    HashMap::new()
+  });
+
+  results
+    .into_iter()
+    .enumerate()
+    .map(|(i, r)| {
+      let mut item = CodeItem::from_search_with_confidence(&r.chunk, r.rank_score, r.confidence);
+      item.more_in_file = more_in_file.get(&i).copied();
+      if include_context {
+        item.imports = r.chunk.imports.clone();
+        item.calls = r.chunk.calls.clone();
+      }
+      if let Some(chunks) = r.chunk.content_hash.as_ref().and_then(|hash| copies.get(hash)) {
+        item.also_in = also_in(&r.chunk, chunks);
+      }
+      item
+    })
+    .collect()
+}
+
+/// Locations of `copies` of `chunk` in other files, one per place
+fn also_in(chunk: &CodeChunk, copies: &[CodeChunk]) -> Vec<CodeLocation> {
+  let mut locations: Vec<CodeLocation> = copies
+    .iter()
+    .filter(|c| c.file_path != chunk.file_path)
+    .map(CodeLocation::from)
+    .collect();
+  locations.dedup();
+  locations
+}
+
 /// Calculate adaptive result limit based on confidence distribution.
 ///
 /// When enabled, this reduces the result count when top results are very confident,
@@ -711,6 +967,10 @@ mod tests {
       content_hash: None,
       caller_count: 0,
       callee_count: 0,
+      git_branch: None,
+      git_commit: None,
+      shallow: false,
+      generated: false,
     }
   }
 
@@ -899,4 +1159,64 @@ mod tests {
       result.distance
     );
   }
+
+  #[test]
+  fn test_generated_code_ranks_below_handwritten() {
+    let config = RankingConfig::default();
+
+    let mut generated = create_test_chunk(
+      vec!["parse"],
+      vec![],
+      vec![],
+      "parser.generated.rs",
+      Some("parse"),
+      Some("pub"),
+    );
+    generated.generated = true;
+    let handwritten = create_test_chunk(vec!["parse"], vec![], vec![], "parser.rs", Some("parse"), Some("pub"));
+
+    // The generated chunk is the closer vector match
+    let mut ranked = rank_results(vec![(generated, 0.20), (handwritten, 0.30)], "parse", &config);
+    assert!(ranked[0].chunk.generated);
+
+    demote_generated(&mut ranked);
+    ranked.sort_by(|a, b| b.rank_score.partial_cmp(&a.rank_score).unwrap_or(Ordering::Equal));
+    assert_eq!(ranked[0].chunk.file_path, "parser.rs");
+  }
+
+  #[test]
+  fn test_copies_collapse_into_one_result() {
+    let config = RankingConfig::default();
+    let copy = |file_path: &str| {
+      let mut chunk = create_test_chunk(vec!["slugify"], vec![], vec![], file_path, Some("slugify"), Some("pub"));
+      chunk.content_hash = Some("hash_slugify".to_string());
+      chunk
+    };
+    let other = create_test_chunk(vec!["render"], vec![], vec![], "src/render.rs", Some("render"), None);
+
+    let original = copy("src/util.rs");
+    let vendored = copy("vendor/util/src/lib.rs");
+    let results = vec![
+      (original.clone(), 0.10),
+      (vendored.clone(), 0.10),
+      (other, 0.30),
+      (copy("src/util.rs"), 0.10),
+    ];
+    let mut ranked = rank_results(results, "slugify", &config);
+    collapse_copies(&mut ranked);
+    assert_eq!(ranked.len(), 2, "one result per distinct chunk");
+
+    let kept = &ranked[0].chunk;
+    assert_eq!(kept.file_path, "src/util.rs", "ties keep the first result");
+    let copies = [original, vendored, copy("src/util.rs")];
+    assert_eq!(
+      also_in(kept, &copies),
+      vec![CodeLocation {
+        file_path: "vendor/util/src/lib.rs".to_string(),
+        start_line: 1,
+        end_line: 10,
+      }],
+      "copies in the result's own file are left out"
+    );
+  }
 }
@@ -12,7 +12,10 @@
 //! Called when a ProjectActor starts watching a previously indexed project.
 //! If the project was never manually indexed, the scan is skipped.
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use sha2::{Digest, Sha256};
@@ -21,7 +24,10 @@ use tracing::{debug, info, trace, warn};
 use crate::{
   context::files::is_document_extension,
   db::{IndexedFile, ProjectDb},
-  domain::code::Language,
+  domain::{
+    code::Language,
+    path::{PathFilter, case_key, is_case_insensitive, relative_path},
+  },
 };
 
 /// Result of a startup scan
@@ -64,7 +70,7 @@ impl StartupScanResult {
 ///
 /// Returns `None` if the project was never indexed (no startup scan needed).
 /// Returns `Some(result)` with the detected changes if the project was indexed.
-pub async fn startup_scan(db: &ProjectDb, project_root: &PathBuf) -> Option<StartupScanResult> {
+pub async fn startup_scan(db: &ProjectDb, project_root: &PathBuf, filter: &PathFilter) -> Option<StartupScanResult> {
   let project_id = db.project_id.as_str();
 
   // Check if project was previously indexed
@@ -99,6 +105,18 @@ pub async fn startup_scan(db: &ProjectDb, project_root: &PathBuf) -> Option<Star
   let mut db_files: HashMap<String, IndexedFile> =
     indexed_files.into_iter().map(|f| (f.file_path.clone(), f)).collect();
 
+  // On case-insensitive filesystems, map folded paths back to their indexed
+  // casing so a case-only rename is a move rather than an add
+  let case_insensitive = is_case_insensitive(project_root).await;
+  let case_to_path: HashMap<String, String> = if case_insensitive {
+    db_files
+      .keys()
+      .map(|path| (case_key(path, true), path.clone()))
+      .collect()
+  } else {
+    HashMap::new()
+  };
+
   // Build hash -> path map for move detection
   let hash_to_path: HashMap<String, String> = db_files
     .iter()
@@ -114,12 +132,11 @@ pub async fn startup_scan(db: &ProjectDb, project_root: &PathBuf) -> Option<Star
     ..Default::default()
   };
 
-  let current_files = scan_source_files(project_root, gitignore.as_ref());
+  let current_files = scan_source_files(project_root, gitignore.as_ref(), filter);
 
   for full_path in current_files {
-    let relative = match full_path.strip_prefix(project_root) {
-      Ok(rel) => rel.to_string_lossy().to_string(),
-      Err(_) => continue,
+    let Some(relative) = relative_path(&full_path, project_root) else {
+      continue;
     };
 
     if let Some(db_file) = db_files.remove(&relative) {
@@ -138,6 +155,11 @@ pub async fn startup_scan(db: &ProjectDb, project_root: &PathBuf) -> Option<Star
           // Just mtime changed, content same - no reindex needed, but update DB
         }
       }
+    } else if let Some(old_path) = case_to_path.get(&case_key(&relative, true))
+      && db_files.remove(old_path).is_some()
+    {
+      trace!(from = %old_path, to = %relative, "File renamed (case only)");
+      result.moved.push((old_path.clone(), full_path));
     } else {
       // File on disk but not in DB
       // Check if it might be a move (same content hash exists elsewhere)
@@ -201,8 +223,8 @@ async fn compute_file_hash(path: &PathBuf) -> String {
   }
 }
 
-/// Scan for source files in a directory, respecting gitignore
-fn scan_source_files(root: &PathBuf, gitignore: Option<&Gitignore>) -> Vec<PathBuf> {
+/// Scan for source files in a directory, respecting gitignore and the configured globs
+fn scan_source_files(root: &PathBuf, gitignore: Option<&Gitignore>, filter: &PathFilter) -> Vec<PathBuf> {
   let mut files = Vec::new();
 
   // Use walkdir for recursive traversal
@@ -235,11 +257,11 @@ fn scan_source_files(root: &PathBuf, gitignore: Option<&Gitignore>) -> Vec<PathB
 
     // Check gitignore - must use relative path and check parent directories too
     // because patterns like "ignored_dir/" only match the directory itself
-    if let Some(gi) = gitignore {
-      let relative_path = path.strip_prefix(root).unwrap_or(path);
-      if gi.matched_path_or_any_parents(relative_path, false).is_ignore() {
-        continue;
-      }
+    if let Some(relative) = relative_path(path, root)
+      && (gitignore.is_some_and(|gi| gi.matched_path_or_any_parents(Path::new(&relative), false).is_ignore())
+        || filter.is_excluded(&relative, false))
+    {
+      continue;
     }
 
     // Check if this is a supported file type (code or document)
@@ -315,7 +337,7 @@ mod tests {
     let gi = gi.unwrap();
 
     // Test scan_source_files - this is what startup_scan uses
-    let files = scan_source_files(&root, Some(&gi));
+    let files = scan_source_files(&root, Some(&gi), &PathFilter::default());
 
     let file_names: Vec<_> = files
       .iter()
@@ -338,4 +360,24 @@ mod tests {
       file_names
     );
   }
+
+  #[test]
+  fn test_scan_source_files_respects_globs() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path().to_path_buf();
+
+    std::fs::create_dir_all(root.join("src/generated")).unwrap();
+    std::fs::create_dir_all(root.join("scripts")).unwrap();
+    std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(root.join("src/generated/api.rs"), "fn api() {}").unwrap();
+    std::fs::write(root.join("scripts/build.rs"), "fn build() {}").unwrap();
+
+    let filter = PathFilter::new(&root, &["src/**".to_string()], &["src/generated/".to_string()]);
+    let files: Vec<_> = scan_source_files(&root, None, &filter)
+      .iter()
+      .filter_map(|p| relative_path(p, &root))
+      .collect();
+
+    assert_eq!(files, vec!["src/main.rs"]);
+  }
 }
@@ -2,7 +2,7 @@
 //!
 //! Provides statistics about indexed code in a project.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::{db::ProjectDb, ipc::types::code::CodeStatsResult, service::util::ServiceError};
 
@@ -21,8 +21,8 @@ pub async fn get_stats(db: &ProjectDb) -> Result<CodeStatsResult, ServiceError>
 
   // Track unique files
   let mut files: std::collections::HashSet<String> = std::collections::HashSet::new();
-  let mut language_counts: HashMap<String, usize> = HashMap::new();
-  let mut type_counts: HashMap<String, usize> = HashMap::new();
+  let mut language_counts: BTreeMap<String, usize> = BTreeMap::new();
+  let mut type_counts: BTreeMap<String, usize> = BTreeMap::new();
   let mut total_tokens: u64 = 0;
   let mut total_lines: u64 = 0;
 
@@ -63,6 +63,7 @@ pub async fn get_stats(db: &ProjectDb) -> Result<CodeStatsResult, ServiceError>
     language_breakdown: language_counts,
     chunk_type_breakdown: type_counts,
     index_health_score: health_score,
+    reindex: None,
   })
 }
 
@@ -71,7 +72,7 @@ fn calculate_health_score(
   total_chunks: usize,
   total_files: usize,
   avg_chunks: f32,
-  type_counts: &HashMap<String, usize>,
+  type_counts: &BTreeMap<String, usize>,
 ) -> u32 {
   let mut score: f32 = 0.0;
 
@@ -124,14 +125,14 @@ mod tests {
 
   #[test]
   fn test_health_score_empty() {
-    let type_counts = HashMap::new();
+    let type_counts = BTreeMap::new();
     let score = calculate_health_score(0, 0, 0.0, &type_counts);
     assert_eq!(score, 0);
   }
 
   #[test]
   fn test_health_score_basic() {
-    let mut type_counts = HashMap::new();
+    let mut type_counts = BTreeMap::new();
     type_counts.insert("function".to_string(), 50);
     type_counts.insert("class".to_string(), 10);
 
@@ -141,7 +142,7 @@ mod tests {
 
   #[test]
   fn test_health_score_max() {
-    let mut type_counts = HashMap::new();
+    let mut type_counts = BTreeMap::new();
     type_counts.insert("function".to_string(), 500);
     type_counts.insert("class".to_string(), 100);
     type_counts.insert("module".to_string(), 50);
@@ -153,7 +154,7 @@ mod tests {
 
   #[test]
   fn test_health_score_too_many_chunks() {
-    let mut type_counts = HashMap::new();
+    let mut type_counts = BTreeMap::new();
     type_counts.insert("block".to_string(), 1000);
 
     // Way too many chunks per file
@@ -0,0 +1,298 @@
+//! Web page fetching for URL ingestion.
+//!
+//! Pages are fetched breadth-first from a start URL, following links on the
+//! same host up to a maximum depth. HTML is converted to readable text with the
+//! same extraction used for local HTML documents.
+
+use std::{
+  collections::{HashSet, VecDeque},
+  time::Duration,
+};
+
+use reqwest::Url;
+use tracing::{debug, warn};
+
+use crate::{
+  context::files::extract::{html_to_text, pdf_to_text},
+  service::util::ServiceError,
+};
+
+/// Timeout for a single page fetch
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Link targets that are never documents
+const SKIPPED_LINK_EXTENSIONS: &[&str] = &[
+  "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "css", "js", "mjs", "map", "zip", "gz", "tar", "tgz", "woff",
+  "woff2", "ttf", "mp3", "mp4", "webm", "mov",
+];
+
+/// Limits for a crawl.
+#[derive(Debug, Clone)]
+pub struct CrawlParams {
+  /// How many links deep to follow from the start page (0 = start page only)
+  pub max_depth: usize,
+  /// Maximum number of pages to fetch
+  pub max_pages: usize,
+  /// Pages larger than this are skipped
+  pub max_page_bytes: u64,
+}
+
+impl Default for CrawlParams {
+  fn default() -> Self {
+    Self {
+      max_depth: 0,
+      max_pages: 50,
+      max_page_bytes: 5 * 1024 * 1024,
+    }
+  }
+}
+
+/// A fetched page converted to text.
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+  /// Final URL after redirects
+  pub url: Url,
+  pub title: String,
+  pub text: String,
+  /// Size of the response body
+  pub bytes: u64,
+}
+
+/// Pages fetched by a crawl.
+#[derive(Debug, Clone, Default)]
+pub struct CrawlResult {
+  pub pages: Vec<FetchedPage>,
+  /// Number of pages that could not be fetched
+  pub failed: usize,
+}
+
+/// Parse and validate a URL for ingestion.
+pub fn parse_url(url: &str) -> Result<Url, ServiceError> {
+  let parsed = Url::parse(url.trim()).map_err(|e| ServiceError::Validation(format!("Invalid URL '{}': {}", url, e)))?;
+  match parsed.scheme() {
+    "http" | "https" => Ok(parsed),
+    scheme => Err(ServiceError::Validation(format!(
+      "Unsupported URL scheme '{}': only http and https are supported",
+      scheme
+    ))),
+  }
+}
+
+/// Fetch `start` and, up to `params.max_depth` links deep, same-host pages it links to.
+///
+/// Fails only when the start page itself cannot be fetched; later failures are
+/// counted in [`CrawlResult::failed`].
+#[tracing::instrument(level = "trace", skip(params), fields(start = %start))]
+pub async fn crawl(start: Url, params: &CrawlParams) -> Result<CrawlResult, ServiceError> {
+  let client = reqwest::Client::builder()
+    .timeout(FETCH_TIMEOUT)
+    .user_agent(concat!("ccengram/", env!("CARGO_PKG_VERSION")))
+    .build()
+    .map_err(|e| ServiceError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+  let host = start.host_str().map(str::to_string);
+  let mut seen = HashSet::from([start.to_string()]);
+  let mut queue = VecDeque::from([(start, 0usize)]);
+  let mut result = CrawlResult::default();
+
+  while let Some((url, depth)) = queue.pop_front() {
+    if result.pages.len() >= params.max_pages {
+      debug!(max_pages = params.max_pages, "Crawl page limit reached");
+      break;
+    }
+
+    match fetch(&client, &url, params.max_page_bytes).await {
+      Ok(Some((page, links))) => {
+        if depth < params.max_depth {
+          for link in links {
+            if link.host_str().map(str::to_string) == host && seen.insert(link.to_string()) {
+              queue.push_back((link, depth + 1));
+            }
+          }
+        }
+        result.pages.push(page);
+      }
+      Ok(None) => debug!(url = %url, "Skipping unsupported or oversized page"),
+      Err(e) if depth == 0 => return Err(e),
+      Err(e) => {
+        warn!(url = %url, "Failed to fetch page: {}", e);
+        result.failed += 1;
+      }
+    }
+  }
+
+  debug!(pages = result.pages.len(), failed = result.failed, "Crawl complete");
+  Ok(result)
+}
+
+/// Fetch one page, returning its text and outgoing links.
+///
+/// Returns `None` for content types that can't be converted to text and for
+/// pages over the size limit.
+async fn fetch(
+  client: &reqwest::Client,
+  url: &Url,
+  max_bytes: u64,
+) -> Result<Option<(FetchedPage, Vec<Url>)>, ServiceError> {
+  let fetch_error = |e: reqwest::Error| ServiceError::Internal(format!("Failed to fetch {}: {}", url, e));
+
+  let response = client
+    .get(url.clone())
+    .send()
+    .await
+    .and_then(|r| r.error_for_status())
+    .map_err(fetch_error)?;
+
+  if response.content_length().is_some_and(|len| len > max_bytes) {
+    return Ok(None);
+  }
+
+  let final_url = response.url().clone();
+  let content_type = response
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|v| v.to_str().ok())
+    .unwrap_or("text/html")
+    .to_lowercase();
+  let body = response.bytes().await.map_err(fetch_error)?;
+  let bytes = body.len() as u64;
+  if bytes > max_bytes {
+    return Ok(None);
+  }
+
+  let fallback_title = || final_url.to_string();
+
+  let (title, text, links) = if content_type.contains("html") {
+    let html = String::from_utf8_lossy(&body);
+    let title = extract_title(&html).unwrap_or_else(fallback_title);
+    (title, html_to_text(&html), extract_links(&html, &final_url))
+  } else if content_type.starts_with("text/") {
+    (
+      fallback_title(),
+      String::from_utf8_lossy(&body).into_owned(),
+      Vec::new(),
+    )
+  } else if content_type.contains("pdf") {
+    let text = tokio::task::spawn_blocking(move || pdf_to_text(&body))
+      .await
+      .map_err(|e| ServiceError::Internal(e.to_string()))?
+      .map_err(|e| ServiceError::Internal(format!("Failed to extract PDF text from {}: {}", final_url, e)))?;
+    (fallback_title(), text, Vec::new())
+  } else {
+    return Ok(None);
+  };
+
+  let page = FetchedPage {
+    url: final_url,
+    title,
+    text,
+    bytes,
+  };
+  Ok(Some((page, links)))
+}
+
+/// Text of the `<title>` element, if present and non-empty
+fn extract_title(html: &str) -> Option<String> {
+  let lower = html.to_ascii_lowercase();
+  let open = lower.find("<title")?;
+  let start = open + lower[open..].find('>')? + 1;
+  let end = start + lower[start..].find("</title")?;
+  let title = html_to_text(&html[start..end]);
+  (!title.is_empty()).then_some(title)
+}
+
+/// Absolute http(s) links from `href` attributes, without fragments
+fn extract_links(html: &str, base: &Url) -> Vec<Url> {
+  let lower = html.to_ascii_lowercase();
+  let mut links = Vec::new();
+  let mut pos = 0;
+
+  while let Some(rel) = lower[pos..].find("href=") {
+    let value_start = pos + rel + "href=".len();
+    let rest = &html[value_start..];
+    let (value, consumed) = match rest.chars().next() {
+      Some(quote @ ('"' | '\'')) => match rest[1..].find(quote) {
+        Some(end) => (&rest[1..end + 1], end + 2),
+        None => break,
+      },
+      _ => {
+        let end = rest.find(|c: char| c.is_whitespace() || c == '>').unwrap_or(rest.len());
+        (&rest[..end], end)
+      }
+    };
+    pos = value_start + consumed;
+
+    let Ok(mut link) = base.join(value.trim()) else {
+      continue;
+    };
+    if !matches!(link.scheme(), "http" | "https") || is_skipped_link(&link) {
+      continue;
+    }
+    link.set_fragment(None);
+    links.push(link);
+  }
+
+  links
+}
+
+fn is_skipped_link(url: &Url) -> bool {
+  url
+    .path()
+    .rsplit_once('.')
+    .is_some_and(|(_, ext)| SKIPPED_LINK_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_extract_links_resolves_and_filters() {
+    let base = Url::parse("https://docs.example.com/guide/intro.html").unwrap();
+    let html = r#"
+      <a href="setup.html#install">Setup</a>
+      <a href='/api/'>API</a>
+      <a href=https://other.example.org/page>Other</a>
+      <a href="mailto:team@example.com">Mail</a>
+      <img src="logo.png"><a href="diagram.PNG">Diagram</a>
+    "#;
+
+    let links: Vec<String> = extract_links(html, &base).iter().map(Url::to_string).collect();
+    assert_eq!(
+      links,
+      vec![
+        "https://docs.example.com/guide/setup.html",
+        "https://docs.example.com/api/",
+        "https://other.example.org/page",
+      ],
+      "links should be absolute, fragment-free and skip non-http and asset targets"
+    );
+  }
+
+  #[test]
+  fn test_extract_title() {
+    assert_eq!(
+      extract_title("<html><head><TITLE>Install &amp; Run</TITLE></head></html>"),
+      Some("Install & Run".to_string()),
+      "title should be case-insensitive and entity-decoded"
+    );
+    assert_eq!(
+      extract_title("<title> </title>"),
+      None,
+      "blank titles should be ignored"
+    );
+  }
+
+  #[test]
+  fn test_parse_url_rejects_non_http() {
+    assert!(parse_url("https://example.com/docs").is_ok());
+    assert!(
+      matches!(parse_url("file:///etc/passwd"), Err(ServiceError::Validation(_))),
+      "file URLs should be rejected"
+    );
+    assert!(
+      matches!(parse_url("not a url"), Err(ServiceError::Validation(_))),
+      "malformed URLs should be rejected"
+    );
+  }
+}
@@ -4,22 +4,26 @@
 //! using the `Indexer` which handles both code and document files.
 
 use std::{
+  collections::HashMap,
   path::{Path, PathBuf},
   sync::Arc,
   time::{Duration, Instant},
 };
 
 use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::debug;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
+use super::crawl::{self, CrawlParams, FetchedPage};
 use crate::{
   actor::{indexer::PipelineConfig, message::IndexProgress, pipeline::run_pipeline},
-  context::files::Indexer,
+  context::{files::Indexer, redact::Redactor},
   db::ProjectDb,
-  embedding::EmbeddingProvider,
+  domain::document::{ChunkParams, Document, DocumentChunk, DocumentId, DocumentSource, chunk_text},
+  embedding::{EmbeddingMode, EmbeddingProvider},
   ipc::types::docs::DocsIngestResult,
   service::util::ServiceError,
 };
@@ -126,6 +130,9 @@ impl Default for ScanParams {
         "adoc".to_string(),
         "asciidoc".to_string(),
         "org".to_string(),
+        "html".to_string(),
+        "htm".to_string(),
+        "pdf".to_string(),
       ],
     }
   }
@@ -142,6 +149,10 @@ pub struct IngestParams {
   pub directory: Option<String>,
   /// Single file to ingest (can be absolute or relative)
   pub file: Option<String>,
+  /// Web page to fetch and ingest
+  pub url: Option<String>,
+  /// How many links deep to crawl same-domain pages from `url`
+  pub depth: usize,
   /// Project ID for document chunks
   pub project_id: Uuid,
   /// Project root directory
@@ -253,12 +264,24 @@ pub struct IngestContext {
   pub db: Arc<ProjectDb>,
   /// Embedding provider
   pub embedding: Arc<dyn EmbeddingProvider>,
+  /// Secrets scrubbed from chunks before embedding
+  pub redactor: Redactor,
 }
 
 impl IngestContext {
   /// Create a new ingest context
   pub fn new(db: Arc<ProjectDb>, embedding: Arc<dyn EmbeddingProvider>) -> Self {
-    Self { db, embedding }
+    Self {
+      db,
+      embedding,
+      redactor: Redactor::default(),
+    }
+  }
+
+  /// Replace secrets in ingested chunks with placeholders
+  pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+    self.redactor = redactor;
+    self
   }
 }
 
@@ -268,10 +291,11 @@ impl IngestContext {
 
 /// Ingest documents using the streaming pipeline.
 ///
-/// Supports three modes:
-/// 1. Single file (params.file is set) - Ingest one file
-/// 2. Directory (params.directory is set) - Ingest all docs in a directory
-/// 3. Default (neither set) - Ingest all docs from project root
+/// Supports four modes:
+/// 1. URL (params.url is set) - Fetch a web page and same-domain links up to params.depth
+/// 2. Single file (params.file is set) - Ingest one file
+/// 3. Directory (params.directory is set) - Ingest all docs in a directory
+/// 4. Default (none set) - Ingest all docs from project root
 ///
 /// This uses the same high-throughput pipeline as code indexing, with:
 /// - Multi-stage processing with bounded channels
@@ -295,6 +319,11 @@ pub async fn ingest(
   let start = Instant::now();
   let scan_params = ScanParams::default();
 
+  // Handle URL ingest
+  if let Some(ref url) = params.url {
+    return ingest_url(ctx, &params, url, progress_tx, start).await;
+  }
+
   // Handle single file ingest
   if let Some(ref file_path) = params.file {
     return ingest_single_file(ctx, &params, file_path, &scan_params, start).await;
@@ -374,9 +403,10 @@ pub async fn ingest(
 
   // Run the pipeline with unified Indexer
   let pipeline_result = run_pipeline(
-    Indexer::new(params.project_id),
+    Indexer::for_documents(params.project_id).with_redactor(ctx.redactor.clone()),
     params.root.clone(),
     scan_result.files,
+    HashMap::new(),
     ctx.db.clone(),
     ctx.embedding.clone(),
     config,
@@ -479,9 +509,10 @@ async fn ingest_single_file(
 
   // Run the pipeline with unified Indexer for this one file
   let pipeline_result = run_pipeline(
-    Indexer::new(params.project_id),
+    Indexer::for_documents(params.project_id).with_redactor(ctx.redactor.clone()),
     effective_root,
     vec![path],
+    HashMap::new(),
     ctx.db.clone(),
     ctx.embedding.clone(),
     config,
@@ -518,6 +549,146 @@ async fn ingest_single_file(
   })
 }
 
+/// Fetch a web page (and linked same-domain pages) and ingest each as a document.
+async fn ingest_url(
+  ctx: &IngestContext,
+  params: &IngestParams,
+  url: &str,
+  progress_tx: Option<mpsc::Sender<IngestProgress>>,
+  start: Instant,
+) -> Result<IngestResult, ServiceError> {
+  let start_url = crawl::parse_url(url)?;
+  let crawl_params = CrawlParams {
+    max_depth: params.depth,
+    ..Default::default()
+  };
+
+  let crawled = crawl::crawl(start_url, &crawl_params).await?;
+  let fetch_duration = start.elapsed();
+  let total_pages = crawled.pages.len();
+  let total_bytes: u64 = crawled.pages.iter().map(|p| p.bytes).sum();
+
+  debug!(
+    pages = total_pages,
+    failed = crawled.failed,
+    fetch_ms = fetch_duration.as_millis() as u64,
+    "URL fetch complete"
+  );
+
+  let mut pages_ingested = 0;
+  let mut chunks_created = 0;
+  let mut failed_pages = crawled.failed;
+  let mut results = Vec::with_capacity(total_pages);
+
+  for (idx, page) in crawled.pages.iter().enumerate() {
+    match store_page(ctx, params.project_id, page).await {
+      Ok(result) => {
+        pages_ingested += 1;
+        chunks_created += result.chunks_created;
+        results.push(result);
+      }
+      Err(e) => {
+        warn!(url = %page.url, "Failed to ingest page: {}", e);
+        failed_pages += 1;
+      }
+    }
+
+    if let Some(ref tx) = progress_tx {
+      let progress = IngestProgress::new(idx + 1, total_pages)
+        .with_current_file(page.url.as_str())
+        .with_chunks_created(chunks_created)
+        .with_docs_ingested(pages_ingested);
+      let _ = tx.send(progress).await;
+    }
+  }
+
+  let total_duration = start.elapsed();
+  let ingest_duration = total_duration - fetch_duration;
+
+  Ok(IngestResult {
+    status: "complete".to_string(),
+    files_scanned: total_pages,
+    files_ingested: pages_ingested,
+    chunks_created,
+    failed_files: failed_pages,
+    scan_duration: fetch_duration,
+    ingest_duration,
+    total_duration,
+    files_per_second: total_pages as f64 / total_duration.as_secs_f64().max(0.001),
+    bytes_processed: total_bytes,
+    total_bytes,
+    results,
+  })
+}
+
+/// Chunk, embed and store a fetched page, replacing any earlier ingest of the same URL.
+async fn store_page(
+  ctx: &IngestContext,
+  project_id: Uuid,
+  page: &FetchedPage,
+) -> Result<DocsIngestResult, ServiceError> {
+  let source = page.url.to_string();
+  let raw_chunks = chunk_text(&page.text, &ChunkParams::default());
+  let total_chunks = raw_chunks.len();
+
+  // Keep the document ID stable across re-ingests so metadata is replaced, not duplicated
+  let document_id = match ctx.db.get_document_by_source(&source).await? {
+    Some(existing) => existing.id,
+    None => DocumentId::new(),
+  };
+
+  let chunks: Vec<DocumentChunk> = raw_chunks
+    .into_iter()
+    .enumerate()
+    .map(|(idx, (content, char_offset))| {
+      DocumentChunk::new(
+        document_id,
+        project_id,
+        ctx.redactor.redact_string(content),
+        page.title.clone(),
+        source.clone(),
+        DocumentSource::Url,
+        idx,
+        total_chunks,
+        char_offset,
+      )
+    })
+    .collect();
+
+  let texts: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+  let vectors = if texts.is_empty() {
+    Vec::new()
+  } else {
+    ctx.embedding.embed_batch(&texts, EmbeddingMode::Document).await?
+  };
+  ctx.db.upsert_document_chunks(&source, &chunks, &vectors).await?;
+
+  let content_hash = hex::encode(&Sha256::digest(page.text.as_bytes())[..8]);
+  let char_count = page.text.chars().count();
+  let mut document = Document::new(
+    project_id,
+    page.title.clone(),
+    source.clone(),
+    DocumentSource::Url,
+    content_hash.clone(),
+    char_count,
+    total_chunks,
+  );
+  document.id = document_id;
+  ctx.db.upsert_document_metadata(&document).await?;
+
+  Ok(DocsIngestResult {
+    document_id: document_id.to_string(),
+    title: page.title.clone(),
+    source,
+    source_type: DocumentSource::Url.as_str().to_string(),
+    content_hash,
+    char_count,
+    chunks_created: total_chunks,
+    total_chunks,
+  })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -561,6 +732,8 @@ mod tests {
     assert!(is_document_file(Path::new("README.md"), &params));
     assert!(is_document_file(Path::new("docs/guide.txt"), &params));
     assert!(is_document_file(Path::new("CHANGELOG.MD"), &params)); // case insensitive
+    assert!(is_document_file(Path::new("vendor/api.html"), &params));
+    assert!(is_document_file(Path::new("design/spec.pdf"), &params));
 
     assert!(!is_document_file(Path::new("main.rs"), &params));
     assert!(!is_document_file(Path::new("script.py"), &params));
@@ -3,15 +3,17 @@
 //! This module provides business logic for document operations including:
 //! - Document search with vector/text fallback
 //! - Document context retrieval (adjacent chunks)
-//! - Document ingestion from files with streaming progress
+//! - Document ingestion from files and URLs with streaming progress
 //!
 //! ## Services
 //!
 //! - [`search`] - Document search with vector/text fallback
 //! - [`context`] - Document context retrieval (adjacent chunks)
 //! - [`ingest`] - Document ingestion with streaming progress support
+//! - [`crawl`] - Web page fetching for URL ingestion
 
 pub mod context;
+pub mod crawl;
 pub mod ingest;
 pub mod search;
 
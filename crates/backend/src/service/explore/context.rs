@@ -15,13 +15,16 @@ use super::{
   util::truncate_preview,
 };
 use crate::{
-  db::{DbError, ProjectDb},
+  db::{FilterBuilder, ProjectDb},
   domain::{
     code::{ChunkType, CodeChunk},
     document::DocumentChunk,
-    memory::{Memory, MemoryId},
+    memory::Memory,
+  },
+  service::{
+    code::context as code_context,
+    util::{ResolvedEntity, Resolver, ServiceError},
   },
-  service::util::ServiceError,
 };
 
 // ============================================================================
@@ -81,47 +84,12 @@ pub async fn get_context(
 
 /// Fetch full context for an ID (auto-detects type).
 pub async fn fetch_context(db: &ProjectDb, id: &str, depth: usize) -> Result<ContextResult, String> {
-  // Validate ID length for prefix matching
-  if id.len() < 6 {
-    return Err("ID must be at least 6 characters".to_string());
-  }
-
-  // Try code chunk first
-  match db.get_code_chunk_by_id_or_prefix(id).await {
-    Ok(Some(chunk)) => {
-      return Ok(ContextResult::Code(build_code_context(db, chunk, depth).await));
-    }
-    Err(DbError::AmbiguousPrefix { prefix, count }) => {
-      return Err(format!(
-        "Ambiguous prefix '{}' matches {} items. Use more characters.",
-        prefix, count
-      ));
-    }
-    _ => {}
-  }
-
-  // Try memory
-  if let Ok(memory_id) = id.parse::<MemoryId>()
-    && let Ok(Some(memory)) = db.get_memory(&memory_id).await
-  {
-    return Ok(ContextResult::Memory(build_memory_context(db, memory, depth).await));
-  }
-
-  // Try document chunk
-  match db.get_document_chunk_by_id_or_prefix(id).await {
-    Ok(Some(chunk)) => {
-      return Ok(ContextResult::Doc(build_doc_context(db, chunk, depth).await));
-    }
-    Err(DbError::AmbiguousPrefix { prefix, count }) => {
-      return Err(format!(
-        "Ambiguous prefix '{}' matches {} items. Use more characters.",
-        prefix, count
-      ));
-    }
-    _ => {}
+  match Resolver::any(db, id).await {
+    Ok(ResolvedEntity::Code(chunk)) => Ok(ContextResult::Code(build_code_context(db, chunk, depth).await)),
+    Ok(ResolvedEntity::Memory(memory)) => Ok(ContextResult::Memory(build_memory_context(db, memory, depth).await)),
+    Ok(ResolvedEntity::Document(chunk)) => Ok(ContextResult::Doc(build_doc_context(db, chunk, depth).await)),
+    Err(e) => Err(e.to_string()),
   }
-
-  Err(format!("Item not found: {}", id))
 }
 
 // ============================================================================
@@ -161,87 +129,37 @@ async fn build_code_context(db: &ProjectDb, chunk: CodeChunk, depth: usize) -> C
   }
 }
 
-/// Get callers for a code chunk.
-///
-/// Uses symbol-based search to find chunks that call symbols defined in this chunk.
-/// This is a heuristic approach since we don't maintain a full call graph.
+/// Get callers for a code chunk from the call graph.
 pub async fn get_callers(db: &ProjectDb, chunk: &CodeChunk, limit: usize) -> Vec<CallInfo> {
-  let mut callers = Vec::new();
-  let mut seen_ids = std::collections::HashSet::new();
-
-  // Search for chunks that call symbols defined in this chunk
-  for symbol in &chunk.symbols {
-    if callers.len() >= limit {
-      break;
-    }
-
-    // Find chunks that have this symbol in their calls list
-    let filter = format!("calls LIKE '%\"{}%'", symbol.replace('\'', "''"));
-    if let Ok(chunks) = db.list_code_chunks(Some(&filter), Some(limit)).await {
-      for caller in chunks {
-        // Skip self-references and duplicates
-        if caller.id == chunk.id || seen_ids.contains(&caller.id.to_string()) {
-          continue;
-        }
-        seen_ids.insert(caller.id.to_string());
-
-        callers.push(CallInfo {
-          id: caller.id.to_string(),
-          file: caller.file_path.clone(),
-          lines: (caller.start_line, caller.end_line),
-          preview: truncate_preview(&caller.content, 100),
-          symbols: Some(caller.symbols.clone()),
-          signature: caller.signature.clone(),
-        });
-
-        if callers.len() >= limit {
-          break;
-        }
-      }
+  match code_context::find_callers(db, &chunk.symbols, Some(chunk.id), 1, limit).await {
+    Ok(callers) => callers.into_iter().map(|(caller, _)| call_info(&caller)).collect(),
+    Err(e) => {
+      debug!(chunk_id = %chunk.id, "Failed to get callers: {}", e);
+      Vec::new()
     }
   }
-
-  callers
 }
 
-/// Get callees for a code chunk.
-///
-/// Uses the chunk's calls list to find definitions of called symbols.
+/// Get callees for a code chunk from the call graph.
 pub async fn get_callees(db: &ProjectDb, chunk: &CodeChunk, limit: usize) -> Vec<CallInfo> {
-  let mut callees = Vec::new();
-  let mut seen_symbols = std::collections::HashSet::new();
-
-  // Look up definitions for each called symbol
-  for target_symbol in &chunk.calls {
-    if callees.len() >= limit {
-      break;
-    }
-
-    // Skip duplicates
-    if seen_symbols.contains(target_symbol) {
-      continue;
-    }
-    seen_symbols.insert(target_symbol.clone());
-
-    // Find chunk that defines this symbol
-    let filter = format!("symbols LIKE '%\"{}%'", target_symbol.replace('\'', "''"));
-    if let Ok(chunks) = db.list_code_chunks(Some(&filter), Some(1)).await
-      && let Some(callee) = chunks.into_iter().next()
-      && callee.id != chunk.id
-      && callee.symbols.iter().any(|s| s == target_symbol)
-    {
-      callees.push(CallInfo {
-        id: callee.id.to_string(),
-        file: callee.file_path.clone(),
-        lines: (callee.start_line, callee.end_line),
-        preview: truncate_preview(&callee.content, 100),
-        symbols: Some(callee.symbols.clone()),
-        signature: callee.signature.clone(),
-      });
+  match code_context::find_callees(db, chunk, 1, 1).await {
+    Ok((callees, _)) => callees.iter().take(limit).map(|r| call_info(&r.chunk)).collect(),
+    Err(e) => {
+      debug!(chunk_id = %chunk.id, "Failed to get callees: {}", e);
+      Vec::new()
     }
   }
+}
 
-  callees
+fn call_info(chunk: &CodeChunk) -> CallInfo {
+  CallInfo {
+    id: chunk.id.to_string(),
+    file: chunk.file_path.clone(),
+    lines: (chunk.start_line, chunk.end_line),
+    preview: truncate_preview(&chunk.content, 100),
+    symbols: Some(chunk.symbols.clone()),
+    signature: chunk.signature.clone(),
+  }
 }
 
 /// Get sibling symbols in the same file.
@@ -250,7 +168,11 @@ pub async fn get_siblings(db: &ProjectDb, chunk: &CodeChunk, limit: usize) -> Ve
 
   if let Ok(chunks) = db
     .list_code_chunks(
-      Some(&format!("file_path = '{}'", chunk.file_path.replace('\'', "''"))),
+      Some(
+        &FilterBuilder::new()
+          .add_eq("file_path", &chunk.file_path)
+          .build_or_empty(),
+      ),
       None,
     )
     .await
@@ -336,10 +258,12 @@ pub async fn get_related_memories_for_code(db: &ProjectDb, chunk: &CodeChunk, li
   if !file_name.is_empty()
     && let Ok(found) = db
       .list_memories(
-        Some(&format!(
-          "is_deleted = false AND content LIKE '%{}%'",
-          file_name.replace('\'', "''")
-        )),
+        Some(
+          &FilterBuilder::new()
+            .exclude_deleted()
+            .add_like("content", &file_name)
+            .build_or_empty(),
+        ),
         Some(limit),
       )
       .await
@@ -364,10 +288,12 @@ pub async fn get_related_memories_for_code(db: &ProjectDb, chunk: &CodeChunk, li
     && let Some(symbol) = chunk.symbols.first()
     && let Ok(found) = db
       .list_memories(
-        Some(&format!(
-          "is_deleted = false AND content LIKE '%{}%'",
-          symbol.replace('\'', "''")
-        )),
+        Some(
+          &FilterBuilder::new()
+            .exclude_deleted()
+            .add_like("content", symbol)
+            .build_or_empty(),
+        ),
         Some(limit - memories.len()),
       )
       .await
@@ -424,13 +350,15 @@ async fn get_memory_timeline(db: &ProjectDb, memory: &Memory, depth: usize) -> T
   let mut before = Vec::new();
   let mut after = Vec::new();
 
-  // Get memories before this one
-  let before_filter = format!(
-    "is_deleted = false AND created_at < '{}' ORDER BY created_at DESC",
-    memory.created_at.to_rfc3339()
-  );
-  if let Ok(memories) = db.list_memories(Some(&before_filter), Some(depth)).await {
-    for m in memories {
+  // Get memories before this one, nearest first. Filters can't order rows,
+  // so all candidates are fetched and sorted here.
+  let before_filter = FilterBuilder::new()
+    .exclude_deleted()
+    .add_before("created_at", memory.created_at)
+    .build_or_empty();
+  if let Ok(mut memories) = db.list_memories(Some(&before_filter), None).await {
+    memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    for m in memories.into_iter().take(depth) {
       before.push(TimelineEntry {
         id: m.id.to_string(),
         content: truncate_preview(&m.content, 100),
@@ -443,12 +371,13 @@ async fn get_memory_timeline(db: &ProjectDb, memory: &Memory, depth: usize) -> T
   }
 
   // Get memories after this one
-  let after_filter = format!(
-    "is_deleted = false AND created_at > '{}' ORDER BY created_at ASC",
-    memory.created_at.to_rfc3339()
-  );
-  if let Ok(memories) = db.list_memories(Some(&after_filter), Some(depth)).await {
-    for m in memories {
+  let after_filter = FilterBuilder::new()
+    .exclude_deleted()
+    .add_after("created_at", memory.created_at)
+    .build_or_empty();
+  if let Ok(mut memories) = db.list_memories(Some(&after_filter), None).await {
+    memories.sort_by_key(|m| m.created_at);
+    for m in memories.into_iter().take(depth) {
       after.push(TimelineEntry {
         id: m.id.to_string(),
         content: truncate_preview(&m.content, 100),
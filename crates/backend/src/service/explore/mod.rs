@@ -13,12 +13,20 @@
 //!
 //! - [`search`] - Unified search across code, memories, and documents
 //! - [`get_context`] - Get comprehensive context for an explore result
+//! - [`pack`] - Token-budgeted bundle of relevant memories, code and docs
+//! - [`plan`] - Multi-search, call-graph-following pack for a task
+//! - [`notebook`] - Saved explorations of queries, pinned results and notes
 
 pub mod context;
+pub mod notebook;
+mod pack;
+mod plan;
 mod search;
 mod types;
 mod util;
 
 pub use context::get_context;
+pub use pack::{DEFAULT_PACK_BUDGET, PackParams, pack};
+pub use plan::{DEFAULT_PLAN_HOPS, PlanParams, plan};
 pub use search::search;
 pub use types::*;
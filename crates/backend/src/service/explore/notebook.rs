@@ -0,0 +1,478 @@
+//! Saved explorations.
+//!
+//! Explore queries run with `record` set, results pinned along the way and
+//! free-form notes collect, in order, in a draft kept in the project's data
+//! directory, so it survives daemon restarts. Saving gives the draft a name
+//! and starts a new one. Saved explorations can be listed, re-opened in the
+//! TUI and exported as Markdown to resume or share an investigation.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use super::util::{semantic_code_preview, truncate_preview};
+use crate::{
+  db::ProjectDb,
+  ipc::{
+    exploration::{
+      Exploration, ExplorationDeleteResult, ExplorationEntry, ExplorationExportResult, ExplorationItem,
+      ExplorationPinParams, ExplorationSaveParams, ExplorationSummary,
+    },
+    search::ExploreResultItem,
+  },
+  service::util::{ResolvedEntity, Resolver, ServiceError},
+};
+
+/// Directory under the project data directory holding explorations
+const DIR: &str = "explorations";
+
+/// File name of the draft; saved names can't start with a dot
+const DRAFT_FILE: &str = ".draft.json";
+
+/// Results kept per recorded query
+const RESULTS_PER_QUERY: usize = 10;
+
+const PREVIEW_LEN: usize = 200;
+
+/// Add a query and its top results to the draft
+pub async fn record_query(
+  data_dir: &Path,
+  query: &str,
+  scope: Option<&str>,
+  results: &[ExploreResultItem],
+) -> Result<(), ServiceError> {
+  let mut draft = get(data_dir, None).await?;
+  push(
+    &mut draft,
+    ExplorationEntry::Query {
+      query: query.to_string(),
+      scope: scope.map(str::to_string),
+      results: results
+        .iter()
+        .take(RESULTS_PER_QUERY)
+        .map(|r| ExplorationItem {
+          id: r.id.clone(),
+          result_type: r.result_type.clone(),
+          preview: truncate_preview(&r.preview, PREVIEW_LEN),
+          file_path: r.file_path.clone(),
+          line: r.line,
+        })
+        .collect(),
+      at: Utc::now().to_rfc3339(),
+    },
+  );
+  write(&draft_path(data_dir), &draft).await
+}
+
+/// Pin a memory, code chunk or document chunk to the draft
+pub async fn pin(db: &ProjectDb, data_dir: &Path, params: &ExplorationPinParams) -> Result<Exploration, ServiceError> {
+  let mut draft = get(data_dir, None).await?;
+
+  // Results already seen in the draft are pinned as they were shown
+  let seen = draft.entries.iter().rev().find_map(|entry| match entry {
+    ExplorationEntry::Query { results, .. } => results.iter().find(|r| r.id.starts_with(&params.id)).cloned(),
+    _ => None,
+  });
+  let item = match seen {
+    Some(item) => item,
+    None => item_for(Resolver::any(db, &params.id).await?),
+  };
+
+  push(
+    &mut draft,
+    ExplorationEntry::Pin {
+      item,
+      note: params.note.clone().filter(|n| !n.trim().is_empty()),
+      at: Utc::now().to_rfc3339(),
+    },
+  );
+  write(&draft_path(data_dir), &draft).await?;
+  Ok(draft)
+}
+
+/// Add a note to the draft
+pub async fn note(data_dir: &Path, text: &str) -> Result<Exploration, ServiceError> {
+  if text.trim().is_empty() {
+    return Err(ServiceError::validation("Note is empty"));
+  }
+  let mut draft = get(data_dir, None).await?;
+  push(
+    &mut draft,
+    ExplorationEntry::Note {
+      text: text.trim().to_string(),
+      at: Utc::now().to_rfc3339(),
+    },
+  );
+  write(&draft_path(data_dir), &draft).await?;
+  Ok(draft)
+}
+
+/// A saved exploration, or the draft when `name` is None
+pub async fn get(data_dir: &Path, name: Option<&str>) -> Result<Exploration, ServiceError> {
+  let path = match name {
+    Some(name) => saved_path(data_dir, name)?,
+    None => draft_path(data_dir),
+  };
+  match tokio::fs::read_to_string(&path).await {
+    Ok(text) => serde_json::from_str(&text)
+      .map_err(|e| ServiceError::internal(format!("Failed to parse {}: {}", path.display(), e))),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => match name {
+      Some(name) => Err(ServiceError::not_found("exploration", name)),
+      None => Ok(Exploration::default()),
+    },
+    Err(e) => Err(ServiceError::internal(format!(
+      "Failed to read {}: {}",
+      path.display(),
+      e
+    ))),
+  }
+}
+
+/// Save the draft under a name and start a new one
+pub async fn save(data_dir: &Path, params: &ExplorationSaveParams) -> Result<Exploration, ServiceError> {
+  let path = saved_path(data_dir, &params.name)?;
+  let mut draft = get(data_dir, None).await?;
+  if draft.entries.is_empty() {
+    return Err(ServiceError::validation(
+      "Nothing to save: run explore queries, pin results or add notes first",
+    ));
+  }
+  if !params.overwrite.unwrap_or(false) && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+    return Err(ServiceError::validation(format!(
+      "An exploration named '{}' already exists",
+      params.name
+    )));
+  }
+
+  draft.name = Some(params.name.clone());
+  draft.saved_at = Some(Utc::now().to_rfc3339());
+  write(&path, &draft).await?;
+  clear(data_dir).await?;
+  Ok(draft)
+}
+
+/// Saved explorations, most recently saved first
+pub async fn list(data_dir: &Path) -> Result<Vec<ExplorationSummary>, ServiceError> {
+  let dir = data_dir.join(DIR);
+  let mut entries = match tokio::fs::read_dir(&dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => {
+      return Err(ServiceError::internal(format!(
+        "Failed to read {}: {}",
+        dir.display(),
+        e
+      )));
+    }
+  };
+
+  let mut summaries = Vec::new();
+  while let Ok(Some(entry)) = entries.next_entry().await {
+    let file_name = entry.file_name().to_string_lossy().to_string();
+    let Some(name) = file_name.strip_suffix(".json") else {
+      continue;
+    };
+    if name.starts_with('.') {
+      continue;
+    }
+    let exploration = get(data_dir, Some(name)).await?;
+    summaries.push(summarize(name, &exploration));
+  }
+  summaries.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+  Ok(summaries)
+}
+
+/// Render an exploration, or the draft, as Markdown
+pub async fn export(data_dir: &Path, name: Option<&str>) -> Result<ExplorationExportResult, ServiceError> {
+  let exploration = get(data_dir, name).await?;
+  Ok(ExplorationExportResult {
+    name: exploration.name.clone(),
+    markdown: to_markdown(&exploration),
+  })
+}
+
+pub async fn delete(data_dir: &Path, name: &str) -> Result<ExplorationDeleteResult, ServiceError> {
+  remove(&saved_path(data_dir, name)?).await
+}
+
+/// Discard the draft
+pub async fn clear(data_dir: &Path) -> Result<ExplorationDeleteResult, ServiceError> {
+  remove(&draft_path(data_dir)).await
+}
+
+/// Markdown notebook of an exploration, one section per entry
+pub fn to_markdown(exploration: &Exploration) -> String {
+  let mut md = match &exploration.name {
+    Some(name) => format!("# Exploration: {}\n", name),
+    None => "# Exploration (draft)\n".to_string(),
+  };
+  let dates: Vec<String> = [
+    exploration
+      .started_at
+      .as_deref()
+      .map(|at| format!("Started {}", when(at))),
+    exploration.saved_at.as_deref().map(|at| format!("saved {}", when(at))),
+  ]
+  .into_iter()
+  .flatten()
+  .collect();
+  if !dates.is_empty() {
+    md.push_str(&format!("\n{}\n", dates.join(", ")));
+  }
+
+  for entry in &exploration.entries {
+    match entry {
+      ExplorationEntry::Query {
+        query,
+        scope,
+        results,
+        at,
+      } => {
+        md.push_str(&format!("\n## Query: {}\n\n", query));
+        let scope = scope.as_deref().map(|s| format!(" in {}", s)).unwrap_or_default();
+        md.push_str(&format!("_{}{}_\n\n", when(at), scope));
+        if results.is_empty() {
+          md.push_str("No results.\n");
+        }
+        for (i, item) in results.iter().enumerate() {
+          md.push_str(&format!("{}. {} - {}\n", i + 1, label(item), one_line(&item.preview)));
+        }
+      }
+      ExplorationEntry::Pin { item, note, at } => {
+        md.push_str(&format!("\n## Pinned: {}\n\n_{}_\n\n", label(item), when(at)));
+        if let Some(note) = note {
+          md.push_str(&format!("{}\n\n", note));
+        }
+        let fence = if item.result_type == "code" { "```" } else { "" };
+        if fence.is_empty() {
+          md.push_str(&format!("> {}\n", one_line(&item.preview)));
+        } else {
+          md.push_str(&format!("{}\n{}\n{}\n", fence, item.preview, fence));
+        }
+      }
+      ExplorationEntry::Note { text, at } => {
+        md.push_str(&format!("\n## Note\n\n_{}_\n\n{}\n", when(at), text));
+      }
+    }
+  }
+  md
+}
+
+fn summarize(name: &str, exploration: &Exploration) -> ExplorationSummary {
+  let count = |f: fn(&ExplorationEntry) -> bool| exploration.entries.iter().filter(|e| f(e)).count();
+  ExplorationSummary {
+    name: name.to_string(),
+    started_at: exploration.started_at.clone(),
+    saved_at: exploration.saved_at.clone(),
+    queries: count(|e| matches!(e, ExplorationEntry::Query { .. })),
+    pins: count(|e| matches!(e, ExplorationEntry::Pin { .. })),
+    notes: count(|e| matches!(e, ExplorationEntry::Note { .. })),
+  }
+}
+
+fn push(exploration: &mut Exploration, entry: ExplorationEntry) {
+  if exploration.started_at.is_none() {
+    exploration.started_at = Some(Utc::now().to_rfc3339());
+  }
+  exploration.entries.push(entry);
+}
+
+fn item_for(resolved: ResolvedEntity) -> ExplorationItem {
+  match resolved {
+    ResolvedEntity::Memory(memory) => ExplorationItem {
+      id: memory.id.to_string(),
+      result_type: "memory".to_string(),
+      preview: truncate_preview(memory.summary.as_deref().unwrap_or(&memory.content), PREVIEW_LEN),
+      file_path: None,
+      line: None,
+    },
+    ResolvedEntity::Code(chunk) => ExplorationItem {
+      id: chunk.id.to_string(),
+      result_type: "code".to_string(),
+      preview: semantic_code_preview(&chunk, PREVIEW_LEN),
+      file_path: Some(chunk.file_path),
+      line: Some(chunk.start_line),
+    },
+    ResolvedEntity::Document(chunk) => ExplorationItem {
+      id: chunk.id.to_string(),
+      result_type: "doc".to_string(),
+      preview: truncate_preview(&chunk.content, PREVIEW_LEN),
+      file_path: Some(chunk.source),
+      line: None,
+    },
+  }
+}
+
+/// How an item is referred to in Markdown
+fn label(item: &ExplorationItem) -> String {
+  match (&item.file_path, item.line) {
+    (Some(path), Some(line)) => format!("`{}:{}`", path, line),
+    (Some(path), None) => format!("`{}`", path),
+    _ => format!("{} `{}`", item.result_type, &item.id[..8.min(item.id.len())]),
+  }
+}
+
+fn one_line(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn when(at: &str) -> String {
+  DateTime::parse_from_rfc3339(at)
+    .map(|t| t.with_timezone(&Utc).format("%Y-%m-%d %H:%M UTC").to_string())
+    .unwrap_or_else(|_| at.to_string())
+}
+
+fn draft_path(data_dir: &Path) -> PathBuf {
+  data_dir.join(DIR).join(DRAFT_FILE)
+}
+
+fn saved_path(data_dir: &Path, name: &str) -> Result<PathBuf, ServiceError> {
+  let valid = !name.is_empty()
+    && name.len() <= 64
+    && !name.starts_with('.')
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+  if !valid {
+    return Err(ServiceError::validation(format!(
+      "Invalid exploration name '{}': use letters, digits, '-', '_' and '.'",
+      name
+    )));
+  }
+  Ok(data_dir.join(DIR).join(format!("{}.json", name)))
+}
+
+/// Write an exploration, replacing the previous file atomically
+async fn write(path: &Path, exploration: &Exploration) -> Result<(), ServiceError> {
+  let json = serde_json::to_string_pretty(exploration)
+    .map_err(|e| ServiceError::internal(format!("Failed to serialize exploration: {}", e)))?;
+  if let Some(dir) = path.parent() {
+    tokio::fs::create_dir_all(dir)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+  }
+  let tmp = path.with_extension("json.tmp");
+  tokio::fs::write(&tmp, json)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", tmp.display(), e)))?;
+  tokio::fs::rename(&tmp, path)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+async fn remove(path: &Path) -> Result<ExplorationDeleteResult, ServiceError> {
+  match tokio::fs::remove_file(path).await {
+    Ok(()) => Ok(ExplorationDeleteResult { deleted: true }),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ExplorationDeleteResult { deleted: false }),
+    Err(e) => Err(ServiceError::internal(format!(
+      "Failed to remove {}: {}",
+      path.display(),
+      e
+    ))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn result(id: &str, path: &str) -> ExploreResultItem {
+    ExploreResultItem {
+      id: id.to_string(),
+      result_type: "code".to_string(),
+      preview: "fn refresh_token(session: &Session) -> Result<Token>".to_string(),
+      similarity: 0.8,
+      file_path: Some(path.to_string()),
+      line: Some(42),
+      symbols: Vec::new(),
+      more_in_file: None,
+      hints: None,
+      context: None,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_save_starts_a_new_draft() {
+    let temp = TempDir::new().unwrap();
+    let dir = temp.path();
+
+    record_query(
+      dir,
+      "token refresh",
+      Some("code"),
+      &[result("0192f3a1-aaaa", "src/auth.rs")],
+    )
+    .await
+    .unwrap();
+    note(dir, "Refresh races with logout").await.unwrap();
+
+    let params = ExplorationSaveParams {
+      name: "auth-race".to_string(),
+      overwrite: None,
+    };
+    let saved = save(dir, &params).await.unwrap();
+    assert_eq!(saved.entries.len(), 2);
+    assert!(get(dir, None).await.unwrap().entries.is_empty(), "draft is cleared");
+    assert_eq!(get(dir, Some("auth-race")).await.unwrap(), saved);
+
+    let listed = list(dir).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!((listed[0].queries, listed[0].pins, listed[0].notes), (1, 0, 1));
+
+    note(dir, "Second look").await.unwrap();
+    assert!(
+      save(dir, &params).await.is_err(),
+      "names are not overwritten by default"
+    );
+    assert!(
+      save(
+        dir,
+        &ExplorationSaveParams {
+          name: "../x".to_string(),
+          overwrite: None
+        }
+      )
+      .await
+      .is_err()
+    );
+  }
+
+  #[test]
+  fn test_markdown_keeps_entry_order() {
+    let item = ExplorationItem {
+      id: "0192f3a1-aaaa".to_string(),
+      result_type: "code".to_string(),
+      preview: "fn refresh_token()".to_string(),
+      file_path: Some("src/auth.rs".to_string()),
+      line: Some(42),
+    };
+    let exploration = Exploration {
+      name: Some("auth-race".to_string()),
+      started_at: None,
+      saved_at: None,
+      entries: vec![
+        ExplorationEntry::Query {
+          query: "token refresh".to_string(),
+          scope: None,
+          results: vec![item.clone()],
+          at: "2026-10-15T10:00:00Z".to_string(),
+        },
+        ExplorationEntry::Pin {
+          item,
+          note: Some("Called without the session lock".to_string()),
+          at: "2026-10-15T10:05:00Z".to_string(),
+        },
+      ],
+    };
+
+    let md = to_markdown(&exploration);
+    assert!(md.starts_with("# Exploration: auth-race\n"));
+    assert!(md.contains("1. `src/auth.rs:42` - fn refresh_token()"));
+    let query = md.find("## Query: token refresh").unwrap();
+    let pin = md.find("## Pinned: `src/auth.rs:42`").unwrap();
+    assert!(query < pin);
+    assert!(md.contains("Called without the session lock\n\n```\nfn refresh_token()\n```"));
+  }
+}
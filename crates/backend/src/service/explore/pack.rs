@@ -0,0 +1,292 @@
+//! Context packs for external tools.
+//!
+//! A pack is the best-scoring memories, code chunks and doc excerpts for a
+//! query, fitted to a token budget. Results come from the same hybrid search
+//! as explore, but carry full content instead of previews so the pack can be
+//! pasted into any LLM tool as project context.
+
+use super::{
+  search::{
+    MIN_SCORE_THRESHOLD, get_embedding, rerank_cross_domain, search_code_domain, search_docs_domain,
+    search_memory_domain,
+  },
+  types::{ExploreContext, ExploreScope},
+};
+use crate::{
+  domain::{code::CodeChunk, config::CHARS_PER_TOKEN},
+  ipc::search::{PackItem, PackResult},
+  service::util::ServiceError,
+};
+
+/// Token budget when none is given
+pub const DEFAULT_PACK_BUDGET: usize = 8000;
+
+/// Candidates fetched per domain before packing
+pub(super) const PACK_CANDIDATES: usize = 30;
+
+/// Estimated tokens of the heading and fences rendered around each item
+const ITEM_OVERHEAD_TOKENS: usize = 24;
+
+/// Smallest remaining budget worth filling with a truncated item
+const MIN_PARTIAL_TOKENS: usize = 120;
+
+/// Parameters for building a context pack.
+#[derive(Debug, Clone)]
+pub struct PackParams {
+  pub query: String,
+  pub scope: ExploreScope,
+  pub budget: usize,
+}
+
+/// Build a context pack for a query.
+///
+/// Candidates are taken best first. One that does not fit is cut to the
+/// remaining budget when enough is left, otherwise skipped in favour of
+/// smaller ones further down.
+pub async fn pack(ctx: &ExploreContext<'_>, params: &PackParams) -> Result<PackResult, ServiceError> {
+  if params.query.trim().is_empty() {
+    return Err(ServiceError::validation("Query cannot be empty"));
+  }
+  if params.budget == 0 {
+    return Err(ServiceError::validation("Budget must be greater than zero"));
+  }
+
+  let mut candidates = candidates(ctx, &params.query, params.scope, PACK_CANDIDATES).await?;
+  candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+  let (items, tokens, omitted) = fit_budget(candidates, params.budget);
+
+  Ok(PackResult {
+    query: params.query.clone(),
+    budget: params.budget,
+    tokens,
+    items,
+    omitted,
+    steps: Vec::new(),
+  })
+}
+
+/// Search every domain in `scope` for `query` and turn the results worth
+/// packing into items, unsorted.
+pub(super) async fn candidates(
+  ctx: &ExploreContext<'_>,
+  query: &str,
+  scope: ExploreScope,
+  limit: usize,
+) -> Result<Vec<PackItem>, ServiceError> {
+  let query_embedding = get_embedding(ctx, query).await?;
+  let fts_enabled = ctx.search_config.is_some_and(|c| c.fts_enabled);
+  let rrf_k = ctx.search_config.map_or(60, |c| c.rrf_k);
+
+  let (code_results, memory_results, doc_results) = tokio::join!(
+    search_code_domain(
+      ctx.db,
+      &query_embedding,
+      query,
+      limit,
+      scope.includes_code(),
+      false,
+      fts_enabled,
+      rrf_k
+    ),
+    search_memory_domain(
+      ctx.db,
+      &query_embedding,
+      query,
+      limit,
+      scope.includes_memory(),
+      fts_enabled,
+      rrf_k
+    ),
+    search_docs_domain(
+      ctx.db,
+      &query_embedding,
+      query,
+      limit,
+      scope.includes_docs(),
+      fts_enabled,
+      rrf_k
+    ),
+  );
+
+  let (code_results, memory_results, doc_results) = match ctx.reranker {
+    Some(reranker) => {
+      let rerank_candidates = ctx.search_config.map_or(30, |c| c.rerank_candidates);
+      rerank_cross_domain(
+        code_results,
+        memory_results,
+        doc_results,
+        query,
+        rerank_candidates,
+        reranker,
+      )
+      .await
+    }
+    None => (code_results, memory_results, doc_results),
+  };
+
+  let mut candidates: Vec<PackItem> = Vec::new();
+  for (memory, score) in memory_results {
+    if memory.is_superseded() {
+      continue;
+    }
+    let title = memory
+      .memory_type
+      .map(|t| t.as_str().to_string())
+      .unwrap_or_else(|| memory.sector.as_str().to_string());
+    candidates.push(item(
+      memory.id.to_string(),
+      "memory",
+      title,
+      memory.content.clone(),
+      score * memory.salience,
+    ));
+  }
+  for (chunk, score) in code_results {
+    candidates.push(code_item(&chunk, score));
+  }
+  for (chunk, score) in doc_results {
+    let mut doc = item(
+      chunk.id.to_string(),
+      "doc",
+      chunk.title.clone(),
+      chunk.content.clone(),
+      score,
+    );
+    doc.source = Some(chunk.source.clone());
+    candidates.push(doc);
+  }
+
+  candidates.retain(|c| c.score >= MIN_SCORE_THRESHOLD);
+  Ok(candidates)
+}
+
+/// A code chunk as a pack item
+pub(super) fn code_item(chunk: &CodeChunk, score: f32) -> PackItem {
+  let title = chunk
+    .definition_name
+    .clone()
+    .or_else(|| chunk.symbols.first().cloned())
+    .unwrap_or_else(|| chunk.file_path.clone());
+  let mut code = item(chunk.id.to_string(), "code", title, chunk.content.clone(), score);
+  code.source = Some(chunk.file_path.clone());
+  code.lines = Some((chunk.start_line, chunk.end_line));
+  code.language = Some(format!("{:?}", chunk.language).to_lowercase());
+  code
+}
+
+fn item(id: String, item_type: &str, title: String, content: String, score: f32) -> PackItem {
+  PackItem {
+    id,
+    item_type: item_type.to_string(),
+    title,
+    source: None,
+    lines: None,
+    language: None,
+    tokens: estimate_tokens(&content),
+    content,
+    score,
+    truncated: false,
+    provenance: Vec::new(),
+  }
+}
+
+/// Pick items best first until the budget is spent, then order them by type.
+///
+/// Returns the packed items, the tokens they use and how many were left out.
+pub(super) fn fit_budget(candidates: Vec<PackItem>, budget: usize) -> (Vec<PackItem>, usize, usize) {
+  let mut packed = Vec::new();
+  let mut used = 0;
+  let mut omitted = 0;
+
+  for mut candidate in candidates {
+    let remaining = budget.saturating_sub(used);
+    let cost = candidate.tokens + ITEM_OVERHEAD_TOKENS;
+    if cost <= remaining {
+      used += cost;
+      packed.push(candidate);
+    } else if remaining >= ITEM_OVERHEAD_TOKENS + MIN_PARTIAL_TOKENS {
+      let content_budget = remaining - ITEM_OVERHEAD_TOKENS;
+      candidate.content = truncate_to_tokens(&candidate.content, content_budget);
+      candidate.tokens = estimate_tokens(&candidate.content);
+      candidate.truncated = true;
+      used += candidate.tokens + ITEM_OVERHEAD_TOKENS;
+      packed.push(candidate);
+    } else {
+      omitted += 1;
+    }
+  }
+
+  // Stable sort keeps the score order within each type
+  packed.sort_by_key(|item| match item.item_type.as_str() {
+    "memory" => 0,
+    "code" => 1,
+    _ => 2,
+  });
+  (packed, used, omitted)
+}
+
+fn estimate_tokens(text: &str) -> usize {
+  text.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Cut text to about `tokens` tokens, ending on a line break when one is near
+fn truncate_to_tokens(text: &str, tokens: usize) -> String {
+  let max_bytes = tokens * CHARS_PER_TOKEN;
+  if text.len() <= max_bytes {
+    return text.to_string();
+  }
+
+  let mut end = max_bytes;
+  while end > 0 && !text.is_char_boundary(end) {
+    end -= 1;
+  }
+  let cut = &text[..end];
+  match cut.rfind('\n') {
+    Some(newline) if newline >= end / 2 => cut[..newline].to_string(),
+    _ => cut.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn candidate(item_type: &str, chars: usize, score: f32) -> PackItem {
+    item(
+      format!("{}-{}", item_type, chars),
+      item_type,
+      "title".to_string(),
+      "x".repeat(chars),
+      score,
+    )
+  }
+
+  #[test]
+  fn test_fit_budget_respects_budget() {
+    let candidates = vec![
+      candidate("code", 2000, 0.9),   // 500 tokens
+      candidate("memory", 400, 0.8),  // 100 tokens
+      candidate("doc", 4000, 0.7),    // 1000 tokens, cut to fit
+      candidate("memory", 4000, 0.6), // nothing left
+    ];
+
+    let (items, tokens, omitted) = fit_budget(candidates, 1000);
+    assert!(tokens <= 1000, "pack stays within budget, used {}", tokens);
+    assert_eq!(items.len(), 3, "two whole items and one cut item are packed");
+    assert_eq!(omitted, 1, "the item that no longer fits is counted");
+    assert_eq!(
+      items.iter().map(|i| i.item_type.as_str()).collect::<Vec<_>>(),
+      vec!["memory", "code", "doc"],
+      "items are grouped by type"
+    );
+    assert!(items[2].truncated, "the doc was cut to the remaining budget");
+  }
+
+  #[test]
+  fn test_truncate_prefers_line_breaks() {
+    let text = format!("{}\n{}", "a".repeat(300), "b".repeat(300));
+    let cut = truncate_to_tokens(&text, 100);
+    assert_eq!(cut, "a".repeat(300), "cut ends at the last line break");
+  }
+}
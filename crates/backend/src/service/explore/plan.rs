@@ -0,0 +1,305 @@
+//! Planned context packs.
+//!
+//! Where a pack runs one search for one query, a plan starts from a task
+//! description: it searches for the task and for each identifier, path and
+//! quoted phrase it names, then follows the call graph out from the best code
+//! results. Everything found is merged by ID, each item keeping the steps
+//! that found it, and the result is fitted to the token budget like a pack.
+
+use std::collections::HashMap;
+
+use tracing::debug;
+use uuid::Uuid;
+
+use super::{
+  pack::{PACK_CANDIDATES, candidates, code_item, fit_budget},
+  types::{ExploreContext, ExploreScope},
+};
+use crate::{
+  domain::code::CodeChunk,
+  ipc::search::{PackItem, PackResult, PackStep},
+  service::{code::context as code_context, util::ServiceError},
+};
+
+/// Call-graph hops when none are given
+pub const DEFAULT_PLAN_HOPS: usize = 1;
+
+/// Most call-graph hops a plan follows
+const MAX_PLAN_HOPS: usize = 3;
+
+/// Most searches a plan runs, the task itself included
+const MAX_QUERIES: usize = 5;
+
+/// Candidates fetched per domain for each identifier search
+const TERM_CANDIDATES: usize = 10;
+
+/// Code results whose callers and callees are followed on each hop
+const HOP_SEEDS: usize = 3;
+
+/// Callers and callees taken from each followed chunk
+const HOP_NEIGHBORS: usize = 4;
+
+/// Score kept by a chunk reached through the call graph, relative to the
+/// chunk it was reached from
+const HOP_DECAY: f32 = 0.7;
+
+/// Parameters for planning a context pack.
+#[derive(Debug, Clone)]
+pub struct PlanParams {
+  pub task: String,
+  pub scope: ExploreScope,
+  pub budget: usize,
+  pub hops: usize,
+}
+
+/// Plan and run the searches and call-graph hops for a task, then pack the
+/// results into the budget.
+pub async fn plan(ctx: &ExploreContext<'_>, params: &PlanParams) -> Result<PackResult, ServiceError> {
+  if params.task.trim().is_empty() {
+    return Err(ServiceError::validation("Task cannot be empty"));
+  }
+  if params.budget == 0 {
+    return Err(ServiceError::validation("Budget must be greater than zero"));
+  }
+
+  let mut found = Found::default();
+  let mut steps = Vec::new();
+
+  for (i, query) in plan_queries(&params.task).into_iter().enumerate() {
+    let limit = if i == 0 { PACK_CANDIDATES } else { TERM_CANDIDATES };
+    let results = candidates(ctx, &query, params.scope, limit).await?;
+    debug!(query = %query, results = results.len(), "Plan search");
+    steps.push(PackStep {
+      kind: "search".to_string(),
+      target: query.clone(),
+      found: results.len(),
+    });
+    for item in results {
+      found.add(item, format!("search: {}", query));
+    }
+  }
+
+  if params.scope.includes_code() {
+    let mut frontier = Vec::new();
+    for (id, score) in found.best_code(HOP_SEEDS) {
+      if let Ok(id) = Uuid::parse_str(&id)
+        && let Some(chunk) = ctx.db.get_code_chunk(&id).await?
+      {
+        frontier.push((chunk, score));
+      }
+    }
+
+    for _ in 0..params.hops.min(MAX_PLAN_HOPS) {
+      if frontier.is_empty() {
+        break;
+      }
+      let mut next = Vec::new();
+      for (chunk, score) in frontier {
+        let name = chunk_name(&chunk);
+        let callers: Vec<CodeChunk> =
+          code_context::find_callers(ctx.db, &chunk.symbols, Some(chunk.id), 1, HOP_NEIGHBORS)
+            .await?
+            .into_iter()
+            .map(|(caller, _)| caller)
+            .collect();
+        let (callees, _) = code_context::find_callees(ctx.db, &chunk, 1, 1).await?;
+        let callees: Vec<CodeChunk> = callees.into_iter().take(HOP_NEIGHBORS).map(|call| call.chunk).collect();
+
+        for (kind, neighbors) in [("callers", callers), ("callees", callees)] {
+          steps.push(PackStep {
+            kind: kind.to_string(),
+            target: name.clone(),
+            found: neighbors.len(),
+          });
+          let relation = if kind == "callers" { "caller" } else { "callee" };
+          for neighbor in neighbors {
+            let neighbor_score = score * HOP_DECAY;
+            let is_new = found.add(
+              code_item(&neighbor, neighbor_score),
+              format!("{} of {}", relation, name),
+            );
+            if is_new {
+              next.push((neighbor, neighbor_score));
+            }
+          }
+        }
+      }
+      next.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+      next.truncate(HOP_SEEDS);
+      frontier = next;
+    }
+  }
+
+  let mut candidates = found.into_items();
+  candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+  let (items, tokens, omitted) = fit_budget(candidates, params.budget);
+
+  Ok(PackResult {
+    query: params.task.clone(),
+    budget: params.budget,
+    tokens,
+    items,
+    omitted,
+    steps,
+  })
+}
+
+/// Items found so far, by ID, in the order first found
+#[derive(Default)]
+struct Found {
+  items: Vec<PackItem>,
+  index: HashMap<String, usize>,
+}
+
+impl Found {
+  /// Add an item found by `step`, or merge it into the copy found earlier.
+  ///
+  /// Returns whether the item was new.
+  fn add(&mut self, mut item: PackItem, step: String) -> bool {
+    match self.index.get(&item.id) {
+      Some(&i) => {
+        let existing = &mut self.items[i];
+        existing.score = existing.score.max(item.score);
+        if !existing.provenance.contains(&step) {
+          existing.provenance.push(step);
+        }
+        false
+      }
+      None => {
+        item.provenance.push(step);
+        self.index.insert(item.id.clone(), self.items.len());
+        self.items.push(item);
+        true
+      }
+    }
+  }
+
+  /// IDs and scores of the best-scoring code items
+  fn best_code(&self, n: usize) -> Vec<(String, f32)> {
+    let mut code: Vec<(String, f32)> = self
+      .items
+      .iter()
+      .filter(|item| item.item_type == "code")
+      .map(|item| (item.id.clone(), item.score))
+      .collect();
+    code.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    code.truncate(n);
+    code
+  }
+
+  fn into_items(self) -> Vec<PackItem> {
+    self.items
+  }
+}
+
+fn chunk_name(chunk: &CodeChunk) -> String {
+  chunk
+    .definition_name
+    .clone()
+    .or_else(|| chunk.symbols.first().cloned())
+    .unwrap_or_else(|| format!("{}:{}", chunk.file_path, chunk.start_line))
+}
+
+/// Queries to run for a task: the task itself, then the quoted phrases,
+/// paths and code identifiers it names.
+fn plan_queries(task: &str) -> Vec<String> {
+  let task = task.trim();
+  let mut queries = vec![task.to_string()];
+  let mut push = |query: &str| {
+    if queries.len() < MAX_QUERIES && !queries.iter().any(|q| q.eq_ignore_ascii_case(query)) {
+      queries.push(query.to_string());
+    }
+  };
+
+  for (i, part) in task.split(['`', '"']).enumerate() {
+    let part = part.trim();
+    if i % 2 == 1 && !part.is_empty() {
+      push(part);
+    }
+  }
+
+  for word in task.split_whitespace() {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '/' && c != ':' && c != '.');
+    let word = word.trim_end_matches(['.', ':']);
+    if is_identifier(word) {
+      push(word);
+    }
+  }
+
+  queries
+}
+
+/// Whether a word looks like a path or a name from code rather than prose
+fn is_identifier(word: &str) -> bool {
+  if word.len() < 3 {
+    return false;
+  }
+  let has_separator = word.contains('_') || word.contains("::") || word.contains('/');
+  let has_extension = word.rsplit_once('.').is_some_and(|(stem, ext)| {
+    stem.len() >= 2
+      && (1..=4).contains(&ext.len())
+      && ext.chars().all(|c| c.is_ascii_alphanumeric())
+      && ext.chars().any(|c| c.is_ascii_alphabetic())
+  });
+  let mixed_case = word
+    .chars()
+    .zip(word.chars().skip(1))
+    .any(|(a, b)| a.is_lowercase() && b.is_uppercase());
+  has_separator || has_extension || mixed_case
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_plan_queries_pick_out_code_names() {
+    let queries =
+      plan_queries("Make `retry budget` configurable in src/http/client.rs so HttpClient::send and retry_after agree.");
+
+    assert_eq!(
+      queries,
+      vec![
+        "Make `retry budget` configurable in src/http/client.rs so HttpClient::send and retry_after agree.",
+        "retry budget",
+        "src/http/client.rs",
+        "HttpClient::send",
+        "retry_after",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_found_merges_provenance() {
+    let item = |id: &str, score: f32| PackItem {
+      id: id.to_string(),
+      item_type: "code".to_string(),
+      title: id.to_string(),
+      source: None,
+      lines: None,
+      language: None,
+      content: String::new(),
+      tokens: 0,
+      score,
+      truncated: false,
+      provenance: Vec::new(),
+    };
+
+    let mut found = Found::default();
+    assert!(found.add(item("a", 0.5), "search: billing".to_string()));
+    assert!(found.add(item("b", 0.6), "search: billing".to_string()));
+    assert!(
+      !found.add(item("a", 0.8), "callee of charge".to_string()),
+      "repeats are merged"
+    );
+
+    assert_eq!(
+      found.best_code(1),
+      vec![("a".to_string(), 0.8)],
+      "the best score is kept"
+    );
+    let items = found.into_items();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].provenance, vec!["search: billing", "callee of charge"]);
+  }
+}
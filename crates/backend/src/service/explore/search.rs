@@ -12,10 +12,10 @@ use super::{
   util::{semantic_code_preview, truncate_preview},
 };
 use crate::{
-  db::ProjectDb,
+  db::{FilterBuilder, ProjectDb},
   domain::{code::CodeChunk, document::DocumentChunk, memory::Memory},
   rerank::{RerankCandidate, RerankRequest, RerankerProvider},
-  service::util::{ServiceError, fusion},
+  service::util::{Resolver, ServiceError, diversity::cap_per_file, fusion},
 };
 
 // ============================================================================
@@ -24,7 +24,10 @@ use crate::{
 
 /// Minimum similarity score threshold for results.
 /// Results below this threshold are filtered out as noise.
-const MIN_SCORE_THRESHOLD: f32 = 0.15;
+pub(super) const MIN_SCORE_THRESHOLD: f32 = 0.15;
+
+/// Leaves out chunks of generated files, which rarely answer an explore query
+const NOT_GENERATED: &str = "(generated IS NULL OR generated = false)";
 
 /// Unified search across code, memories, and documents.
 ///
@@ -41,6 +44,17 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
   if params.query.trim().is_empty() {
     return Err(ServiceError::validation("Query cannot be empty"));
   }
+  let expanded;
+  let params = match ctx.aliases {
+    Some(aliases) => {
+      expanded = SearchParams {
+        query: aliases.expand(&params.query),
+        ..params.clone()
+      };
+      &expanded
+    }
+    None => params,
+  };
 
   let query_embedding = get_embedding(ctx, &params.query).await?;
 
@@ -64,6 +78,7 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
       &params.query,
       oversample,
       search_code,
+      params.include_generated,
       fts_enabled,
       rrf_k
     ),
@@ -139,6 +154,7 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
         parent: chunk.parent_definition.clone(),
         imports,
         calls,
+        more_in_file: None,
       });
     }
   }
@@ -166,6 +182,7 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
         parent: None,
         imports: vec![],
         calls: vec![],
+        more_in_file: None,
       });
     }
   }
@@ -197,6 +214,7 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
         parent: None,
         imports: vec![],
         calls: vec![],
+        more_in_file: None,
       });
     }
   }
@@ -205,6 +223,11 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
   all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
   all_results.retain(|r| r.score >= MIN_SCORE_THRESHOLD);
 
+  // Keep a few hits per file so one large file can't fill the list
+  for (i, count) in cap_per_file(&mut all_results, params.max_per_file, |r| r.file.as_deref()) {
+    all_results[i].more_in_file = Some(count);
+  }
+
   // Expand top N results
   for (i, result) in all_results.iter_mut().enumerate() {
     if i >= params.expand_top {
@@ -225,7 +248,7 @@ pub async fn search(ctx: &ExploreContext<'_>, params: &SearchParams) -> Result<E
 }
 
 /// Get an embedding for the given text, if a provider is available
-async fn get_embedding(ctx: &ExploreContext<'_>, text: &str) -> Result<Vec<f32>, ServiceError> {
+pub(super) async fn get_embedding(ctx: &ExploreContext<'_>, text: &str) -> Result<Vec<f32>, ServiceError> {
   // Query mode - this is used for explore search queries
   Ok(
     ctx
@@ -242,24 +265,27 @@ async fn get_embedding(ctx: &ExploreContext<'_>, text: &str) -> Result<Vec<f32>,
 /// Search code chunks with hybrid FTS + vector search and RRF fusion.
 ///
 /// Returns `(CodeChunk, score)` where score is a similarity (higher = better).
+/// Generated code is left out unless `include_generated` is set.
 #[allow(clippy::too_many_arguments)]
-async fn search_code_domain(
+pub(super) async fn search_code_domain(
   db: &ProjectDb,
   embedding: &[f32],
   query: &str,
   limit: usize,
   enabled: bool,
+  include_generated: bool,
   fts_enabled: bool,
   rrf_k: u32,
 ) -> Vec<(CodeChunk, f32)> {
   if !enabled {
     return Vec::new();
   }
+  let filter = (!include_generated).then_some(NOT_GENERATED);
 
   if fts_enabled {
     let (vector_results, fts_results) = tokio::join!(
-      db.search_code_chunks(embedding, limit, None),
-      db.fts_search_code_chunks(query, limit, None),
+      db.search_code_chunks(embedding, limit, filter),
+      db.fts_search_code_chunks(query, limit, filter),
     );
 
     let vector_results = vector_results.unwrap_or_default();
@@ -276,7 +302,7 @@ async fn search_code_domain(
 
     fuse_rrf(vector_results, fts_results, rrf_k)
   } else {
-    db.search_code_chunks(embedding, limit, None)
+    db.search_code_chunks(embedding, limit, filter)
       .await
       .unwrap_or_default()
       .into_iter()
@@ -289,7 +315,7 @@ async fn search_code_domain(
 ///
 /// Returns `(Memory, score)` where score is a similarity (higher = better).
 #[allow(clippy::too_many_arguments)]
-async fn search_memory_domain(
+pub(super) async fn search_memory_domain(
   db: &ProjectDb,
   embedding: &[f32],
   query: &str,
@@ -337,7 +363,7 @@ async fn search_memory_domain(
 ///
 /// Returns `(DocumentChunk, score)` where score is a similarity (higher = better).
 #[allow(clippy::too_many_arguments)]
-async fn search_docs_domain(
+pub(super) async fn search_docs_domain(
   db: &ProjectDb,
   embedding: &[f32],
   query: &str,
@@ -412,7 +438,7 @@ fn fuse_rrf<T: Clone>(vector_results: Vec<(T, f32)>, fts_results: Vec<(T, f32)>,
 
 /// Cross-domain reranking: merge all domain results into a single pool,
 /// rerank once with the cross-encoder, then split back by domain.
-async fn rerank_cross_domain(
+pub(super) async fn rerank_cross_domain(
   code_results: Vec<(CodeChunk, f32)>,
   memory_results: Vec<(Memory, f32)>,
   doc_results: Vec<(DocumentChunk, f32)>,
@@ -551,7 +577,11 @@ async fn compute_code_hints(db: &ProjectDb, chunk: &CodeChunk) -> ExploreHints {
   // Count siblings (other chunks in same file)
   let siblings = db
     .list_code_chunks(
-      Some(&format!("file_path = '{}'", chunk.file_path.replace('\'', "''"))),
+      Some(
+        &FilterBuilder::new()
+          .add_eq("file_path", &chunk.file_path)
+          .build_or_empty(),
+      ),
       None,
     )
     .await
@@ -585,10 +615,12 @@ async fn count_related_memories(db: &ProjectDb, chunk: &CodeChunk) -> usize {
   if !file_name.is_empty()
     && let Ok(memories) = db
       .list_memories(
-        Some(&format!(
-          "is_deleted = false AND content LIKE '%{}%'",
-          file_name.replace('\'', "''")
-        )),
+        Some(
+          &FilterBuilder::new()
+            .exclude_deleted()
+            .add_like("content", &file_name)
+            .build_or_empty(),
+        ),
         Some(10),
       )
       .await
@@ -600,10 +632,12 @@ async fn count_related_memories(db: &ProjectDb, chunk: &CodeChunk) -> usize {
   for symbol in &chunk.symbols {
     if let Ok(memories) = db
       .list_memories(
-        Some(&format!(
-          "is_deleted = false AND content LIKE '%{}%'",
-          symbol.replace('\'', "''")
-        )),
+        Some(
+          &FilterBuilder::new()
+            .exclude_deleted()
+            .add_like("content", symbol)
+            .build_or_empty(),
+        ),
         Some(10),
       )
       .await
@@ -686,10 +720,7 @@ fn adaptive_content(content: &str, signature: Option<&str>) -> String {
 /// Expand a code result with full context.
 async fn expand_code_result(db: &ProjectDb, chunk_id: &str, depth: usize) -> Option<ExpandedContext> {
   // Look up the chunk
-  let chunk = match db.get_code_chunk_by_id_or_prefix(chunk_id).await {
-    Ok(Some(c)) => c,
-    _ => return None,
-  };
+  let chunk = Resolver::code_chunk(db, chunk_id).await.ok()?;
 
   // Use adaptive content to handle large chunks
   let content = adaptive_content(&chunk.content, chunk.signature.as_deref());
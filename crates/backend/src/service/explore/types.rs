@@ -4,7 +4,12 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{db::ProjectDb, domain::config::SearchConfig, embedding::EmbeddingProvider, rerank::RerankerProvider};
+use crate::{
+  db::ProjectDb,
+  domain::{alias::Aliases, config::SearchConfig},
+  embedding::EmbeddingProvider,
+  rerank::RerankerProvider,
+};
 
 // ============================================================================
 // Search Types
@@ -170,6 +175,10 @@ pub struct ExploreResult {
   /// Key function calls made by this chunk
   #[serde(skip_serializing_if = "Vec::is_empty")]
   pub calls: Vec<String>,
+  /// Results from this file left out by the per-file cap; set on the file's
+  /// last result shown
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub more_in_file: Option<usize>,
 }
 
 /// Full explore response
@@ -294,6 +303,8 @@ pub struct ExploreContext<'a> {
   pub search_config: Option<&'a SearchConfig>,
   /// Reranker provider for cross-encoder reranking
   pub reranker: Option<&'a dyn RerankerProvider>,
+  /// Project jargon, expanded in search queries
+  pub aliases: Option<&'a Aliases>,
 }
 
 impl<'a> ExploreContext<'a> {
@@ -309,8 +320,15 @@ impl<'a> ExploreContext<'a> {
       embedding,
       search_config,
       reranker,
+      aliases: None,
     }
   }
+
+  /// Expand search queries with the project's aliases
+  pub fn with_aliases(mut self, aliases: &'a Aliases) -> Self {
+    self.aliases = Some(aliases);
+    self
+  }
 }
 
 // ============================================================================
@@ -330,6 +348,10 @@ pub struct SearchParams {
   pub limit: usize,
   /// Context depth for expanded results
   pub depth: usize,
+  /// Search generated code too
+  pub include_generated: bool,
+  /// Most code and document results kept per file; 0 keeps all
+  pub max_per_file: usize,
 }
 
 impl Default for SearchParams {
@@ -340,6 +362,8 @@ impl Default for SearchParams {
       expand_top: 3,
       limit: 10,
       depth: 5,
+      include_generated: false,
+      max_per_file: 2,
     }
   }
 }
@@ -472,6 +496,7 @@ mod tests {
       parent: None,
       imports: vec![],
       calls: vec!["println".to_string()],
+      more_in_file: None,
     };
 
     let json = serde_json::to_value(&result).unwrap();
@@ -511,6 +536,7 @@ mod tests {
       parent: None,
       imports: vec![],
       calls: vec![],
+      more_in_file: None,
     };
 
     let json = serde_json::to_value(&result).unwrap();
@@ -550,6 +576,7 @@ mod tests {
         parent: None,
         imports: vec![],
         calls: vec![],
+        more_in_file: None,
       }],
       counts: {
         let mut m = HashMap::new();
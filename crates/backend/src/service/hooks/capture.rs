@@ -0,0 +1,379 @@
+//! Prompt/response capture for extraction debugging.
+//!
+//! When `hooks.debug_capture` is enabled, every LLM call made while extracting
+//! a segment is recorded and written to `{project_data_dir}/extraction_captures/{segment_id}.json`.
+//! Prompts and responses are truncated to the configured size and captures
+//! older than the retention window are removed whenever a new one is saved.
+
+use std::{
+  path::{Path, PathBuf},
+  time::{Duration, Instant, SystemTime},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use llm::{InferenceRequest, InferenceResponse, LlmProvider};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+  domain::config::HooksConfig,
+  ipc::types::memory::{CapturedExchange, ExtractionCapture},
+  service::util::{ServiceError, normalize_id},
+};
+
+/// Directory name for captures inside a project's data directory
+pub const CAPTURE_DIR_NAME: &str = "extraction_captures";
+
+/// Extraction path a capture was recorded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureKind {
+  Extraction,
+  HighPriority,
+}
+
+impl CaptureKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Extraction => "extraction",
+      Self::HighPriority => "high_priority",
+    }
+  }
+}
+
+/// On-disk store for extraction captures of a single project
+#[derive(Debug, Clone)]
+pub struct CaptureStore {
+  dir: PathBuf,
+  max_bytes: usize,
+  retention: Duration,
+}
+
+impl CaptureStore {
+  pub fn new(dir: PathBuf, max_bytes: usize, retention: Duration) -> Self {
+    Self {
+      dir,
+      max_bytes,
+      retention,
+    }
+  }
+
+  /// Create a store for a project, or `None` when capture is disabled
+  pub fn from_config(project_data_dir: &Path, config: &HooksConfig) -> Option<Self> {
+    config.debug_capture.then(|| {
+      Self::new(
+        project_data_dir.join(CAPTURE_DIR_NAME),
+        config.debug_capture_max_bytes,
+        Duration::from_secs(config.debug_capture_retention_hours * 3600),
+      )
+    })
+  }
+
+  /// Start recording the LLM calls made through `inner`
+  pub fn recorder<'a>(&self, inner: &'a dyn LlmProvider) -> SegmentRecorder<'a> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    SegmentRecorder {
+      provider: CapturingProvider {
+        inner,
+        exchanges: tx,
+        max_bytes: self.max_bytes,
+      },
+      exchanges: rx,
+    }
+  }
+
+  /// Write the exchanges recorded for a segment and prune expired captures.
+  ///
+  /// Failures are logged rather than returned so capture never breaks extraction.
+  #[tracing::instrument(level = "trace", skip(self, recorder, memory_ids))]
+  pub async fn save(&self, segment_id: Uuid, kind: CaptureKind, recorder: SegmentRecorder<'_>, memory_ids: &[String]) {
+    let exchanges = recorder.into_exchanges();
+    if exchanges.is_empty() {
+      return;
+    }
+
+    let capture = ExtractionCapture {
+      segment_id: segment_id.to_string(),
+      kind: kind.as_str().to_string(),
+      captured_at: Utc::now().to_rfc3339(),
+      memory_ids: memory_ids.to_vec(),
+      exchanges,
+    };
+
+    if let Err(e) = self.write(&capture).await {
+      warn!(segment_id = %segment_id, "Failed to save extraction capture: {}", e);
+      return;
+    }
+    debug!(segment_id = %segment_id, kind = kind.as_str(), "Saved extraction capture");
+
+    if let Err(e) = self.prune().await {
+      warn!("Failed to prune extraction captures: {}", e);
+    }
+  }
+
+  async fn write(&self, capture: &ExtractionCapture) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(&self.dir).await?;
+    let json = serde_json::to_vec_pretty(capture)?;
+    tokio::fs::write(self.dir.join(format!("{}.json", capture.segment_id)), json).await
+  }
+
+  /// Remove captures older than the retention window
+  #[tracing::instrument(level = "trace", skip(self))]
+  pub async fn prune(&self) -> std::io::Result<usize> {
+    let mut entries = match tokio::fs::read_dir(&self.dir).await {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+      Err(e) => return Err(e),
+    };
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    while let Some(entry) = entries.next_entry().await? {
+      let modified = entry.metadata().await?.modified()?;
+      if now.duration_since(modified).unwrap_or_default() > self.retention {
+        tokio::fs::remove_file(entry.path()).await?;
+        removed += 1;
+      }
+    }
+
+    if removed > 0 {
+      debug!(removed, "Pruned expired extraction captures");
+    }
+    Ok(removed)
+  }
+}
+
+/// Load a capture by segment ID or unique ID prefix
+#[tracing::instrument(level = "trace")]
+pub async fn load_capture(project_data_dir: &Path, segment_id: &str) -> Result<ExtractionCapture, ServiceError> {
+  let dir = project_data_dir.join(CAPTURE_DIR_NAME);
+  let prefix = normalize_id(segment_id);
+  let not_found = || ServiceError::not_found("Extraction capture", segment_id);
+
+  let mut entries = match tokio::fs::read_dir(&dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(not_found()),
+    Err(e) => return Err(ServiceError::Internal(e.to_string())),
+  };
+
+  let mut matches = Vec::new();
+  while let Some(entry) = entries
+    .next_entry()
+    .await
+    .map_err(|e| ServiceError::Internal(e.to_string()))?
+  {
+    let path = entry.path();
+    if path
+      .file_stem()
+      .and_then(|s| s.to_str())
+      .is_some_and(|stem| stem.starts_with(&prefix))
+    {
+      matches.push(path);
+    }
+  }
+
+  let path = match matches.as_slice() {
+    [] => return Err(not_found()),
+    [path] => path,
+    _ => {
+      return Err(ServiceError::Ambiguous {
+        prefix,
+        count: matches.len(),
+        candidates: matches
+          .iter()
+          .filter_map(|p| p.file_stem()?.to_str().map(str::to_string))
+          .collect(),
+      });
+    }
+  };
+
+  let content = tokio::fs::read(path)
+    .await
+    .map_err(|e| ServiceError::Internal(e.to_string()))?;
+  serde_json::from_slice(&content).map_err(|e| ServiceError::Internal(format!("Invalid capture file: {}", e)))
+}
+
+/// Records the LLM calls made for one segment
+pub struct SegmentRecorder<'a> {
+  provider: CapturingProvider<'a>,
+  exchanges: mpsc::UnboundedReceiver<CapturedExchange>,
+}
+
+impl<'a> SegmentRecorder<'a> {
+  /// Provider to use for the segment's LLM calls
+  pub fn provider(&self) -> &(dyn LlmProvider + 'a) {
+    &self.provider
+  }
+
+  fn into_exchanges(self) -> Vec<CapturedExchange> {
+    let Self {
+      provider,
+      mut exchanges,
+    } = self;
+    drop(provider);
+
+    let mut out = Vec::new();
+    while let Ok(exchange) = exchanges.try_recv() {
+      out.push(exchange);
+    }
+    out
+  }
+}
+
+/// Provider wrapper that forwards requests and records each exchange
+#[derive(Clone)]
+struct CapturingProvider<'a> {
+  inner: &'a dyn LlmProvider,
+  exchanges: mpsc::UnboundedSender<CapturedExchange>,
+  max_bytes: usize,
+}
+
+#[async_trait]
+impl LlmProvider for CapturingProvider<'_> {
+  fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  fn is_available(&self) -> bool {
+    self.inner.is_available()
+  }
+
+  async fn infer(&self, request: InferenceRequest) -> llm::Result<InferenceResponse> {
+    let (prompt, prompt_truncated) = truncate(&request.prompt, self.max_bytes);
+    let (system_prompt, system_truncated) = match &request.system_prompt {
+      Some(s) => {
+        let (s, truncated) = truncate(s, self.max_bytes);
+        (Some(s), truncated)
+      }
+      None => (None, false),
+    };
+    let mut exchange = CapturedExchange {
+      task: request.task.map(|t| format!("{:?}", t)),
+      model: request.model.clone(),
+      system_prompt,
+      prompt,
+      response: None,
+      error: None,
+      duration_ms: None,
+      truncated: prompt_truncated || system_truncated,
+    };
+
+    let start = Instant::now();
+    let result = self.inner.infer(request).await;
+    exchange.duration_ms = Some(start.elapsed().as_millis() as u64);
+
+    match &result {
+      Ok(response) => {
+        let (text, truncated) = truncate(&response.text, self.max_bytes);
+        exchange.response = Some(text);
+        exchange.truncated |= truncated;
+      }
+      Err(e) => exchange.error = Some(e.to_string()),
+    }
+
+    let _ = self.exchanges.send(exchange);
+    result
+  }
+}
+
+/// Cut `text` to at most `max_bytes`, respecting character boundaries
+fn truncate(text: &str, max_bytes: usize) -> (String, bool) {
+  if text.len() <= max_bytes {
+    return (text.to_string(), false);
+  }
+
+  let mut end = max_bytes;
+  while !text.is_char_boundary(end) {
+    end -= 1;
+  }
+  (text[..end].to_string(), true)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[derive(Clone)]
+  struct EchoProvider;
+
+  #[async_trait]
+  impl LlmProvider for EchoProvider {
+    fn name(&self) -> &str {
+      "echo"
+    }
+
+    fn is_available(&self) -> bool {
+      true
+    }
+
+    async fn infer(&self, request: InferenceRequest) -> llm::Result<InferenceResponse> {
+      Ok(InferenceResponse {
+        text: format!("echo: {}", request.prompt),
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+        duration_ms: 0,
+      })
+    }
+  }
+
+  #[test]
+  fn test_truncate_respects_char_boundaries() {
+    assert_eq!(truncate("short", 10), ("short".to_string(), false));
+    assert_eq!(
+      truncate("héllo", 2),
+      ("h".to_string(), true),
+      "truncation must not split a multi-byte character"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_capture_roundtrip_by_prefix() {
+    let dir = TempDir::new().unwrap();
+    let store = CaptureStore::new(dir.path().join(CAPTURE_DIR_NAME), 12, Duration::from_secs(3600));
+    let segment_id = Uuid::new_v4();
+
+    let recorder = store.recorder(&EchoProvider);
+    let request = InferenceRequest {
+      prompt: "extract memories from this segment".to_string(),
+      ..Default::default()
+    };
+    recorder.provider().infer(request).await.unwrap();
+    store
+      .save(segment_id, CaptureKind::Extraction, recorder, &["m1".to_string()])
+      .await;
+
+    let capture = load_capture(dir.path(), &segment_id.to_string()[..8]).await.unwrap();
+    assert_eq!(capture.exchanges.len(), 1, "one LLM call should be captured");
+    let exchange = &capture.exchanges[0];
+    assert_eq!(exchange.prompt, "extract memo", "prompt should be cut to max_bytes");
+    assert_eq!(exchange.response.as_deref(), Some("echo: extrac"));
+    assert!(exchange.truncated, "truncation should be flagged");
+    assert_eq!(capture.memory_ids, vec!["m1".to_string()]);
+  }
+
+  #[tokio::test]
+  async fn test_prune_removes_expired_captures() {
+    let dir = TempDir::new().unwrap();
+    let capture_dir = dir.path().join(CAPTURE_DIR_NAME);
+    let store = CaptureStore::new(capture_dir.clone(), 1024, Duration::from_secs(3600));
+
+    let recorder = store.recorder(&EchoProvider);
+    recorder.provider().infer(InferenceRequest::default()).await.unwrap();
+    store
+      .save(Uuid::new_v4(), CaptureKind::HighPriority, recorder, &[])
+      .await;
+    assert_eq!(store.prune().await.unwrap(), 0, "fresh capture should be kept");
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let expiring = CaptureStore::new(capture_dir, 1024, Duration::from_millis(1));
+    assert_eq!(expiring.prune().await.unwrap(), 1, "expired capture should be removed");
+    assert!(
+      load_capture(dir.path(), "").await.is_err(),
+      "no capture should remain after pruning"
+    );
+  }
+}
@@ -0,0 +1,163 @@
+//! Context carried across compaction.
+//!
+//! Compaction replaces the conversation with Claude Code's own summary, and
+//! what the session settled on can be lost in it. Once the PreCompact hook has
+//! extracted the segment, this condenses what the session established: the
+//! rolling session summary, open tasks, files modified, and the preferences,
+//! gotchas and decisions stored during the session. PreCompact cannot add
+//! context itself, so the handler keeps the text for the SessionStart hook
+//! that follows compaction (`source: "compact"`), which injects it.
+
+use std::collections::HashSet;
+
+use tracing::debug;
+
+use super::{context::SegmentContext, handler::HookContext, usage::Injected};
+use crate::{
+  db::{Injection, InjectionRule, UsageType},
+  domain::memory::{Memory, MemoryId, MemoryType},
+  service::util::ServiceError,
+};
+
+/// Memory types worth restating after compaction
+const HIGH_PRIORITY_TYPES: [MemoryType; 3] = [MemoryType::Preference, MemoryType::Gotcha, MemoryType::Decision];
+
+/// Most modified files listed
+const MAX_FILES: usize = 20;
+
+/// Longest a single memory is shown
+const MAX_ITEM_CHARS: usize = 300;
+
+/// Build the summary for a session about to be compacted, or `None` when
+/// there is nothing worth carrying over.
+///
+/// `created` holds memories stored by this compaction that are not yet
+/// linked to the session.
+pub(super) async fn build(
+  ctx: &HookContext<'_>,
+  session_id: &str,
+  segment: Option<&SegmentContext>,
+  created: &[String],
+) -> Result<Option<Injected>, ServiceError> {
+  let mut ids: Vec<String> = ctx
+    .db
+    .get_session_memory_links(session_id)
+    .await?
+    .into_iter()
+    .filter(|link| link.usage_type == UsageType::Created)
+    .map(|link| link.memory_id)
+    .collect();
+  ids.extend(created.iter().cloned());
+
+  let mut seen = HashSet::new();
+  let mut memories = Vec::new();
+  for id in ids {
+    if !seen.insert(id.clone()) {
+      continue;
+    }
+    let Ok(memory_id) = id.parse::<MemoryId>() else {
+      continue;
+    };
+    if let Some(memory) = ctx.db.get_memory(&memory_id).await?
+      && !memory.is_deleted
+      && !memory.is_superseded()
+      && memory.memory_type.is_some_and(|t| HIGH_PRIORITY_TYPES.contains(&t))
+    {
+      memories.push(memory);
+    }
+  }
+  memories.sort_by(|a, b| b.salience.total_cmp(&a.salience));
+  memories.truncate(ctx.config.compaction_context_limit);
+
+  debug!(session_id = %session_id, memories = memories.len(), "Built compaction context");
+  Ok(render(segment, &memories).map(|text| {
+    Injected {
+      text,
+      injections: memories
+        .iter()
+        .map(|memory| Injection {
+          memory_id: memory.id.to_string(),
+          rule: InjectionRule::Compaction,
+          score: Some(memory.salience),
+          detail: None,
+        })
+        .collect(),
+    }
+  }))
+}
+
+/// Render the summary as Markdown
+fn render(segment: Option<&SegmentContext>, memories: &[Memory]) -> Option<String> {
+  let mut out = String::from("## Before compaction (ccengram)\n");
+  let mut sections = 0;
+
+  if let Some(summary) = segment.and_then(|s| s.session_summary.as_deref()) {
+    out.push_str(&format!("\n### Session so far\n\n{}\n", summary.trim()));
+    sections += 1;
+  }
+
+  let tasks = segment.map(|s| s.pending_tasks.as_slice()).unwrap_or_default();
+  if !tasks.is_empty() {
+    out.push_str("\n### Open tasks\n\n");
+    for task in tasks {
+      out.push_str(&format!("- {}\n", task));
+    }
+    sections += 1;
+  }
+
+  let files = segment.map(|s| s.files_modified.as_slice()).unwrap_or_default();
+  if !files.is_empty() {
+    out.push_str("\n### Files modified\n\n");
+    for file in files.iter().take(MAX_FILES) {
+      out.push_str(&format!("- {}\n", file));
+    }
+    if files.len() > MAX_FILES {
+      out.push_str(&format!("- ...and {} more\n", files.len() - MAX_FILES));
+    }
+    sections += 1;
+  }
+
+  if !memories.is_empty() {
+    out.push_str("\n### Keep in mind\n\n");
+    for memory in memories {
+      let text = memory.summary.as_deref().unwrap_or(&memory.content);
+      let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+      let text = match text.char_indices().nth(MAX_ITEM_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+      };
+      let label = memory.memory_type.map(|t| t.as_str()).unwrap_or(memory.sector.as_str());
+      out.push_str(&format!("- [{}] {} (id: {})\n", label, text, memory.id));
+    }
+    sections += 1;
+  }
+
+  (sections > 0).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  #[test]
+  fn test_render_lists_each_section() {
+    assert_eq!(render(None, &[]), None);
+    assert_eq!(render(Some(&SegmentContext::default()), &[]), None);
+
+    let mut segment = SegmentContext::default();
+    segment.set_session_summary("Migrating the auth module to sessions.".to_string());
+    segment.pending_tasks = vec!["Update the login tests".to_string()];
+    segment.record_file_modified("src/auth.rs");
+    let mut memory = Memory::new(Uuid::nil(), "Never log session tokens".to_string(), Sector::Emotional);
+    memory.memory_type = Some(MemoryType::Preference);
+
+    let out = render(Some(&segment), &[memory]).unwrap();
+    assert!(out.contains("### Session so far\n\nMigrating the auth module to sessions."));
+    assert!(out.contains("### Open tasks\n\n- Update the login tests"));
+    assert!(out.contains("### Files modified\n\n- src/auth.rs"));
+    assert!(out.contains("- [preference] Never log session tokens"));
+  }
+}
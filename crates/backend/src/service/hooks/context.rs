@@ -7,6 +7,9 @@
 use llm::{ExtractionContext, ToolUse};
 use tracing::debug;
 
+/// Maximum length of the rolling session summary
+pub const MAX_SESSION_SUMMARY_CHARS: usize = 2000;
+
 /// Accumulated context from a session segment.
 ///
 /// Tracks all relevant information from a conversation segment
@@ -35,6 +38,13 @@ pub struct SegmentContext {
   pub last_assistant_message: Option<String>,
   /// Number of active subagents (skip extraction when > 0)
   pub subagent_depth: usize,
+  /// Rolling summary of earlier segments in this session, kept across resets
+  pub session_summary: Option<String>,
+  /// Unfinished tasks from the latest TodoWrite, kept across resets
+  pub pending_tasks: Vec<String>,
+  /// Set when the turn was marked `@ccengram-ignore` or touched a private
+  /// path; nothing is extracted from it
+  pub private: bool,
 }
 
 impl SegmentContext {
@@ -85,6 +95,7 @@ impl SegmentContext {
 
     ExtractionContext {
       user_prompt: self.user_prompt.clone(),
+      session_summary: self.session_summary.clone(),
       files_read: Vec::new(), // Don't include files_read in extraction context
       files_modified: self.files_modified.clone(),
       commands_run: self.commands_run.clone(),
@@ -97,7 +108,16 @@ impl SegmentContext {
     }
   }
 
-  /// Reset the context for a new segment
+  /// Replace the rolling session summary, truncated to [`MAX_SESSION_SUMMARY_CHARS`]
+  pub fn set_session_summary(&mut self, summary: String) {
+    let summary = match summary.char_indices().nth(MAX_SESSION_SUMMARY_CHARS) {
+      Some((end, _)) => summary[..end].to_string(),
+      None => summary,
+    };
+    self.session_summary = (!summary.is_empty()).then_some(summary);
+  }
+
+  /// Reset the context for a new segment, keeping the session summary and pending tasks
   pub fn reset(&mut self) {
     self.tool_uses.clear();
     self.user_prompt = None;
@@ -110,6 +130,15 @@ impl SegmentContext {
     self.completed_tasks.clear();
     self.last_assistant_message = None;
     self.subagent_depth = 0;
+    self.private = false;
+  }
+
+  /// Drop what the turn has recorded so far and keep the rest of it out of extraction
+  pub fn mark_private(&mut self) {
+    let subagent_depth = self.subagent_depth;
+    self.reset();
+    self.subagent_depth = subagent_depth;
+    self.private = true;
   }
 
   // ========================================================================
@@ -131,6 +160,42 @@ impl SegmentContext {
     self.tool_uses.push(tool_use);
   }
 
+  /// Record a tool use along with the files, commands, searches and tasks derived from it
+  pub fn track_tool_use(&mut self, tool_use: ToolUse) {
+    if let Some(path) = tool_use.file_path()
+      && !path.is_empty()
+    {
+      if tool_use.is_file_modification() {
+        self.record_file_modified(path);
+      } else if tool_use.is_file_read() {
+        self.record_file_read(path);
+      }
+    }
+
+    if let Some((cmd, exit_code)) = tool_use.command_info()
+      && !cmd.is_empty()
+    {
+      self.record_command(cmd.to_string(), exit_code);
+    }
+
+    if let Some(pattern) = tool_use.search_pattern()
+      && !pattern.is_empty()
+    {
+      self.record_search(pattern);
+    }
+
+    if let Some(tasks) = tool_use.completed_tasks() {
+      for task in tasks {
+        self.record_completed_task(task);
+      }
+    }
+    if let Some(tasks) = tool_use.pending_tasks() {
+      self.pending_tasks = tasks.to_vec();
+    }
+
+    self.record_tool_use(tool_use);
+  }
+
   /// Record a file read
   pub fn record_file_read(&mut self, path: &str) {
     if !self.files_read.contains(&path.to_string()) {
@@ -214,8 +279,13 @@ mod tests {
   fn test_segment_context_reset() {
     let mut ctx = SegmentContext {
       user_prompt: Some("Test".to_string()),
+      session_summary: Some("Earlier work".to_string()),
       ..Default::default()
     };
+    ctx.track_tool_use(ToolUse::TodoWrite {
+      completed_tasks: vec!["Write tests".to_string()],
+      pending_tasks: vec!["Update docs".to_string()],
+    });
     ctx.record_file_modified("test.rs");
     ctx.record_tool_use(ToolUse::Read {
       file_path: "test.rs".to_string(),
@@ -226,6 +296,33 @@ mod tests {
     assert!(ctx.user_prompt.is_none());
     assert!(ctx.files_modified.is_empty());
     assert!(ctx.tool_uses.is_empty());
+    assert_eq!(
+      ctx.session_summary.as_deref(),
+      Some("Earlier work"),
+      "session summary should survive segment resets"
+    );
+    assert_eq!(
+      ctx.pending_tasks,
+      vec!["Update docs".to_string()],
+      "pending tasks should survive segment resets"
+    );
+  }
+
+  #[test]
+  fn test_set_session_summary_truncates() {
+    let mut ctx = SegmentContext::default();
+    ctx.set_session_summary("é".repeat(MAX_SESSION_SUMMARY_CHARS + 10));
+    assert_eq!(
+      ctx.session_summary.as_ref().map(|s| s.chars().count()),
+      Some(MAX_SESSION_SUMMARY_CHARS),
+      "summary should be cut to the character limit"
+    );
+
+    ctx.set_session_summary(String::new());
+    assert!(
+      ctx.session_summary.is_none(),
+      "empty summaries should clear the summary"
+    );
   }
 
   #[test]
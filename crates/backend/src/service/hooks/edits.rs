@@ -0,0 +1,76 @@
+//! Files changed by Claude's own tool calls.
+//!
+//! Edit, MultiEdit, Write and NotebookEdit name the file in their input. Once
+//! they have run, Edit and Write also report the file as it was before the
+//! change (`originalFile`), which lets the indexer reparse incrementally.
+
+/// Tools that change a file, with the input field naming it
+const EDIT_TOOLS: [(&str, &str); 4] = [
+  ("Edit", "file_path"),
+  ("MultiEdit", "file_path"),
+  ("Write", "file_path"),
+  ("NotebookEdit", "notebook_path"),
+];
+
+/// A file changed by a tool call
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditedFile {
+  /// Path as given to the tool, usually absolute
+  pub path: String,
+  /// Content before the change, when the tool reported it
+  pub old_content: Option<String>,
+}
+
+/// Path of the file a tool call is about to change, from its input
+pub(super) fn target_file<'a>(tool_name: &str, tool_input: &'a serde_json::Value) -> Option<&'a str> {
+  EDIT_TOOLS
+    .iter()
+    .find(|(name, _)| *name == tool_name)
+    .and_then(|(_, field)| tool_input.get(field))
+    .and_then(|v| v.as_str())
+}
+
+/// The file a completed tool call changed, from PostToolUse hook data
+pub fn edited_file(params: &serde_json::Value) -> Option<EditedFile> {
+  let tool_name = params.get("tool_name")?.as_str()?;
+  let response = params.get("tool_response");
+  let path = params
+    .get("tool_input")
+    .and_then(|input| target_file(tool_name, input))
+    .or_else(|| response?.get("filePath")?.as_str())?;
+  let old_content = response
+    .and_then(|r| r.get("originalFile"))
+    .and_then(|v| v.as_str())
+    .map(String::from);
+
+  Some(EditedFile {
+    path: path.to_string(),
+    old_content,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_edited_file_from_post_tool_use() {
+    let edit = json!({
+      "tool_name": "Edit",
+      "tool_input": { "file_path": "/repo/src/lib.rs", "old_string": "a", "new_string": "b" },
+      "tool_response": { "filePath": "/repo/src/lib.rs", "originalFile": "fn a() {}\n" },
+    });
+    assert_eq!(
+      edited_file(&edit),
+      Some(EditedFile {
+        path: "/repo/src/lib.rs".to_string(),
+        old_content: Some("fn a() {}\n".to_string()),
+      })
+    );
+
+    let read = json!({ "tool_name": "Read", "tool_input": { "file_path": "/repo/src/lib.rs" } });
+    assert_eq!(edited_file(&read), None, "only tools that change files count");
+  }
+}
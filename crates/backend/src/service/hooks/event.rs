@@ -11,6 +11,7 @@ pub enum HookEvent {
   SessionStart,
   SessionEnd,
   UserPromptSubmit,
+  PreToolUse,
   PostToolUse,
   PreCompact,
   Stop,
@@ -25,6 +26,7 @@ impl std::fmt::Display for HookEvent {
       Self::SessionStart => write!(f, "SessionStart"),
       Self::SessionEnd => write!(f, "SessionEnd"),
       Self::UserPromptSubmit => write!(f, "UserPromptSubmit"),
+      Self::PreToolUse => write!(f, "PreToolUse"),
       Self::PostToolUse => write!(f, "PostToolUse"),
       Self::PreCompact => write!(f, "PreCompact"),
       Self::Stop => write!(f, "Stop"),
@@ -44,6 +46,7 @@ impl std::str::FromStr for HookEvent {
       "SessionStart" | "session-start" => Ok(Self::SessionStart),
       "SessionEnd" | "session-end" => Ok(Self::SessionEnd),
       "UserPromptSubmit" | "user-prompt" | "user-prompt-submit" => Ok(Self::UserPromptSubmit),
+      "PreToolUse" | "pre-tool" | "pre-tool-use" => Ok(Self::PreToolUse),
       "PostToolUse" | "post-tool" | "post-tool-use" => Ok(Self::PostToolUse),
       "PreCompact" | "pre-compact" => Ok(Self::PreCompact),
       "Stop" | "stop" => Ok(Self::Stop),
@@ -3,20 +3,37 @@
 //! This module handles extracting memories from session context using
 //! either LLM-based extraction or basic summary fallback.
 
+use std::{borrow::Cow, path::Path};
+
 use llm::{ExtractedMemory, LlmProvider, SignalClassification};
 use tracing::{debug, warn};
 use uuid::Uuid;
 
-use super::context::SegmentContext;
+use super::{
+  capture::{CaptureKind, CaptureStore},
+  context::SegmentContext,
+  queue::{self, RetryPolicy},
+};
 use crate::{
-  context::memory::extract::{
-    classifier::{extract_concepts, extract_files},
-    dedup::compute_hashes,
+  context::{
+    memory::extract::{
+      classifier::{ExtractionRules, extract_concepts, extract_files},
+      dedup::{adaptive_threshold, compute_hashes, hamming_distance, jaccard_similarity},
+    },
+    redact::Redactor,
+  },
+  db::{MemoryChange, ProjectDb},
+  domain::{
+    alias::Aliases,
+    config::{HooksConfig, VisibilityConfig},
+    memory::{GoalStatus, Memory, MemoryActor, MemoryEventAction, MemoryType, Sector, Visibility},
+    path,
   },
-  db::ProjectDb,
-  domain::memory::{Memory, Sector},
   embedding::EmbeddingProvider,
-  service::util::ServiceError,
+  service::{
+    memory::{MemoryContext, anchor::anchor_files, check_duplicate, entity, goals, record_history, stale},
+    util::ServiceError,
+  },
 };
 
 /// Context for memory extraction operations.
@@ -29,6 +46,26 @@ pub struct ExtractionContext<'a> {
   pub llm: Option<&'a dyn LlmProvider>,
   /// Project UUID for new memories
   pub project_id: Uuid,
+  /// Prompt/response capture, when debug capture is enabled
+  pub capture: Option<&'a CaptureStore>,
+  /// Token budget for a single extraction prompt
+  pub max_tokens: usize,
+  /// Tool uses repeated at the start of each split of a long segment
+  pub split_overlap: usize,
+  /// Project root, used to anchor referenced files with git blame
+  pub root: Option<&'a Path>,
+  /// Claude session the extracted segments belong to
+  pub session_id: Option<&'a str>,
+  /// Backoff for segments queued after a failed extraction
+  pub retry: RetryPolicy,
+  /// Deterministic capture and suppress rules
+  pub rules: Option<&'a ExtractionRules>,
+  /// Project jargon, for tag and entity normalization
+  pub aliases: Option<&'a Aliases>,
+  /// Default visibility of new memories per type
+  pub visibility: Option<&'a VisibilityConfig>,
+  /// Secrets scrubbed from memories before embedding and storage
+  pub redactor: Option<&'a Redactor>,
 }
 
 impl<'a> ExtractionContext<'a> {
@@ -39,14 +76,101 @@ impl<'a> ExtractionContext<'a> {
     llm: Option<&'a dyn LlmProvider>,
     project_id: Uuid,
   ) -> Self {
+    let defaults = HooksConfig::default();
     Self {
       db,
       embedding,
       llm,
       project_id,
+      capture: None,
+      max_tokens: defaults.extraction_max_tokens,
+      split_overlap: defaults.extraction_split_overlap,
+      root: None,
+      session_id: None,
+      retry: RetryPolicy::from_config(&defaults),
+      rules: None,
+      aliases: None,
+      visibility: None,
+      redactor: None,
+    }
+  }
+
+  /// Tag segments queued for retry with the session they came from
+  pub fn with_session(mut self, session_id: &'a str) -> Self {
+    self.session_id = Some(session_id);
+    self
+  }
+
+  /// Schedule retries of failed extractions with the given policy
+  pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+    self.retry = retry;
+    self
+  }
+
+  /// Anchor files referenced by new memories to git history under `root`
+  pub fn with_root(mut self, root: Option<&'a Path>) -> Self {
+    self.root = root;
+    self
+  }
+
+  /// Record LLM prompts and responses to the given store
+  pub fn with_capture(mut self, capture: Option<&'a CaptureStore>) -> Self {
+    self.capture = capture;
+    self
+  }
+
+  /// Apply the given extraction rules
+  pub fn with_rules(mut self, rules: Option<&'a ExtractionRules>) -> Self {
+    self.rules = rules;
+    self
+  }
+
+  /// Store tags and entities under the canonical terms of these aliases
+  pub fn with_aliases(mut self, aliases: Option<&'a Aliases>) -> Self {
+    self.aliases = aliases;
+    self
+  }
+
+  /// Give new memories the project's default visibility for their type
+  pub fn with_visibility(mut self, visibility: Option<&'a VisibilityConfig>) -> Self {
+    self.visibility = visibility;
+    self
+  }
+
+  /// Replace secrets in new memories with placeholders
+  pub fn with_redactor(mut self, redactor: Option<&'a Redactor>) -> Self {
+    self.redactor = redactor;
+    self
+  }
+
+  /// `text` with secrets replaced by placeholders
+  pub(super) fn redact<'b>(&self, text: &'b str) -> Cow<'b, str> {
+    match self.redactor {
+      Some(redactor) => redactor.redact(text),
+      None => Cow::Borrowed(text),
     }
   }
 
+  /// Visibility of a new memory of the given type
+  fn visibility_for(&self, memory_type: Option<MemoryType>) -> Visibility {
+    self
+      .visibility
+      .map(|config| config.for_type(memory_type))
+      .unwrap_or_default()
+  }
+
+  /// Whether a suppress rule drops this content
+  fn is_suppressed(&self, content: &str) -> bool {
+    self.rules.is_some_and(|rules| rules.suppresses(content))
+  }
+
+  /// Split segments whose prompts exceed `max_tokens`, overlapping by `overlap` tool uses
+  pub fn with_split(mut self, max_tokens: usize, overlap: usize) -> Self {
+    self.max_tokens = max_tokens;
+    self.split_overlap = overlap;
+    self
+  }
+
   /// Get an embedding for the given text
   async fn get_embedding(&self, text: &str) -> Result<Vec<f32>, ServiceError> {
     // Document mode - we're embedding memory content for storage
@@ -88,6 +212,12 @@ pub async fn extract_memory(
     );
     return Ok(ExtractMemoryResult { memory_id: None });
   }
+  if ctx.is_suppressed(content) {
+    debug!("Skipping memory extraction: suppressed by extraction rule");
+    return Ok(ExtractMemoryResult { memory_id: None });
+  }
+  let redacted = ctx.redact(content);
+  let content = redacted.as_ref();
 
   // Compute hashes for dedup
   let (content_hash, simhash) = compute_hashes(content);
@@ -103,16 +233,24 @@ pub async fn extract_memory(
 
   // Create memory
   let mut memory = Memory::new(ctx.project_id, content.to_string(), sector);
+  memory.visibility = ctx.visibility_for(None);
   memory.content_hash = content_hash.clone();
   memory.simhash = simhash;
   memory.concepts = extract_concepts(content);
   memory.files = extract_files(content);
+  if let Some(root) = ctx.root {
+    memory.file_anchors = anchor_files(root, &memory.files).await;
+  }
 
   // Generate embedding
   let vector = ctx.get_embedding(content).await?;
 
   // Store memory
   ctx.db.add_memory(&memory, &vector).await?;
+  record_added(ctx.db, &memory).await;
+  if let Err(e) = entity::link(ctx.db, &memory, &[], ctx.aliases).await {
+    warn!(memory_id = %memory.id, "Failed to link memory entities: {}", e);
+  }
 
   // Track hash
   seen_hashes.insert(content_hash);
@@ -128,6 +266,7 @@ pub async fn extract_memory(
 /// # Arguments
 /// * `ctx` - Extraction context with database and providers
 /// * `extracted` - The LLM-extracted memory data
+/// * `segment_id` - ID of the extraction segment the memory came from
 /// * `seen_hashes` - Set of already-seen content hashes for deduplication
 ///
 /// # Returns
@@ -136,6 +275,7 @@ pub async fn extract_memory(
 pub async fn store_extracted_memory(
   ctx: &ExtractionContext<'_>,
   extracted: &ExtractedMemory,
+  segment_id: Uuid,
   seen_hashes: &mut std::collections::HashSet<String>,
 ) -> Result<ExtractMemoryResult, ServiceError> {
   // Skip if content is too short
@@ -146,9 +286,14 @@ pub async fn store_extracted_memory(
     );
     return Ok(ExtractMemoryResult { memory_id: None });
   }
+  if ctx.is_suppressed(&extracted.content) {
+    debug!("Skipping extracted memory: suppressed by extraction rule");
+    return Ok(ExtractMemoryResult { memory_id: None });
+  }
+  let content = ctx.redact(&extracted.content);
 
   // Compute hashes for dedup
-  let (content_hash, simhash) = compute_hashes(&extracted.content);
+  let (content_hash, simhash) = compute_hashes(&content);
 
   // Check for duplicates
   if seen_hashes.contains(&content_hash) {
@@ -160,23 +305,46 @@ pub async fn store_extracted_memory(
   let sector = Sector::from_memory_type(extracted.memory_type);
 
   // Create memory
-  let mut memory = Memory::new(ctx.project_id, extracted.content.clone(), sector);
+  let mut memory = Memory::new(ctx.project_id, content.to_string(), sector);
   memory.content_hash = content_hash.clone();
   memory.simhash = simhash;
-  memory.concepts = extract_concepts(&extracted.content);
-  memory.files = extract_files(&extracted.content);
-  memory.tags = extracted.tags.clone();
+  memory.concepts = extract_concepts(&content);
+  memory.files = extract_files(&content);
+  if let Some(root) = ctx.root {
+    memory.file_anchors = anchor_files(root, &memory.files).await;
+  }
+  memory.tags = match ctx.aliases {
+    Some(aliases) => aliases.normalize_tags(extracted.tags.clone()),
+    None => extracted.tags.clone(),
+  };
   memory.salience = extracted.confidence;
   memory.memory_type = Some(extracted.memory_type);
+  memory.visibility = ctx.visibility_for(memory.memory_type);
+  if extracted.memory_type == MemoryType::Goal {
+    memory.goal_status = Some(GoalStatus::Open);
+  }
+  if stale::tracks_staleness(memory.memory_type) {
+    memory.code_refs = stale::record_code_refs(ctx.db, &memory.files, &memory.concepts).await;
+  }
+  memory.segment_id = Some(segment_id);
   if let Some(ref summary) = extracted.summary {
-    memory.summary = Some(summary.clone());
+    memory.summary = Some(ctx.redact(summary).into_owned());
   }
 
   // Generate embedding
-  let vector = ctx.get_embedding(&extracted.content).await?;
+  let vector = ctx.get_embedding(&content).await?;
 
   // Store memory
   ctx.db.add_memory(&memory, &vector).await?;
+  record_added(ctx.db, &memory).await;
+  if let Err(e) = entity::link(ctx.db, &memory, &extracted.entities, ctx.aliases).await {
+    warn!(memory_id = %memory.id, "Failed to link memory entities: {}", e);
+  }
+  if extracted.memory_type == MemoryType::TaskCompletion
+    && let Err(e) = goals::complete_matching(ctx.db, &memory, &vector).await
+  {
+    warn!(memory_id = %memory.id, "Failed to match task completion to goals: {}", e);
+  }
 
   // Track hash
   seen_hashes.insert(content_hash);
@@ -190,10 +358,46 @@ pub async fn store_extracted_memory(
   })
 }
 
+/// Store the memories forced by capture rules in `text`.
+///
+/// Runs without an LLM. Lines already stored as memories are skipped.
+pub async fn extract_with_rules(
+  ctx: &ExtractionContext<'_>,
+  text: &str,
+  seen_hashes: &mut std::collections::HashSet<String>,
+) -> Result<Vec<String>, ServiceError> {
+  let Some(rules) = ctx.rules.filter(|r| !r.is_empty()) else {
+    return Ok(Vec::new());
+  };
+
+  let segment_id = Uuid::now_v7();
+  let memory_ctx = MemoryContext::new(ctx.db, ctx.embedding, ctx.project_id);
+  let mut memories_created = Vec::new();
+  for extracted in &rules.capture(&ctx.redact(text)) {
+    let (content_hash, simhash) = compute_hashes(&extracted.content);
+    if let Some(duplicate) = check_duplicate(&memory_ctx, &extracted.content, &content_hash, simhash).await? {
+      debug!(existing = %duplicate.id, "Rule-captured memory already stored");
+      continue;
+    }
+    if let Some(id) = store_extracted_memory(ctx, extracted, segment_id, seen_hashes)
+      .await?
+      .memory_id
+    {
+      memories_created.push(id);
+    }
+  }
+
+  if !memories_created.is_empty() {
+    debug!(count = memories_created.len(), "Stored rule-captured memories");
+  }
+  Ok(memories_created)
+}
+
 /// Extract memories using LLM from segment context.
 ///
-/// Uses retry logic on failure (max 3 attempts). On final failure,
-/// discards the segment rather than falling back to low-quality extraction.
+/// Uses retry logic on failure (max 3 attempts). On final failure the
+/// segment is queued for a later retry rather than falling back to
+/// low-quality extraction.
 ///
 /// # Arguments
 /// * `ctx` - Extraction context with database and providers
@@ -212,52 +416,313 @@ pub async fn extract_with_llm(
     return Ok(Vec::new());
   }
 
-  let Some(llm) = ctx.llm else {
+  if ctx.llm.is_none() {
     // No LLM provider, skip extraction entirely
     debug!("No LLM provider available, skipping extraction");
     return Ok(Vec::new());
+  }
+
+  let context = segment.to_extraction_context();
+  match extract_context(ctx, &context, seen_hashes).await {
+    Ok(memories_created) => Ok(memories_created),
+    Err(e) => {
+      queue::enqueue(ctx, context, &e).await?;
+      Ok(Vec::new())
+    }
+  }
+}
+
+/// Run LLM extraction for one extraction context, capturing the exchange.
+///
+/// Fails only when no part of the context could be extracted.
+pub(super) async fn extract_context(
+  ctx: &ExtractionContext<'_>,
+  context: &llm::ExtractionContext,
+  seen_hashes: &mut std::collections::HashSet<String>,
+) -> Result<Vec<String>, llm::LlmError> {
+  let Some(llm) = ctx.llm else {
+    return Ok(Vec::new());
   };
 
-  let extraction_context = segment.to_extraction_context();
+  let segment_id = Uuid::now_v7();
+  let recorder = ctx.capture.map(|store| store.recorder(llm));
+  let provider = recorder.as_ref().map(|r| r.provider()).unwrap_or(llm);
+
+  let result = extract_segment(ctx, provider, context, segment_id, seen_hashes).await;
+
+  if let (Some(store), Some(recorder)) = (ctx.capture, recorder) {
+    let memories_created = result.as_deref().unwrap_or_default();
+    store
+      .save(segment_id, CaptureKind::Extraction, recorder, memories_created)
+      .await;
+  }
+
+  result
+}
+
+/// Fold a finished segment into the session's rolling summary.
+///
+/// Later segments are extracted with this summary as compressed history. On
+/// failure the previous summary is kept.
+pub async fn update_session_summary(ctx: &ExtractionContext<'_>, segment: &mut SegmentContext) {
+  if !segment.has_meaningful_work() {
+    return;
+  }
+  let Some(llm) = ctx.llm else {
+    return;
+  };
+
+  let context = segment.to_extraction_context();
+  match llm::extraction::summarize_session(llm, segment.session_summary.as_deref(), &context).await {
+    Ok(summary) => {
+      debug!(summary_len = summary.len(), "Updated rolling session summary");
+      segment.set_session_summary(summary);
+    }
+    Err(e) => warn!("Failed to update session summary: {}", e),
+  }
+}
+
+/// Store an LLM narrative of a finished turn as a `turn_summary` memory.
+///
+/// Best-effort: returns the new memory's ID, or `None` when there is no LLM,
+/// the turn did nothing worth summarizing or summarization failed.
+pub async fn store_turn_summary(
+  ctx: &ExtractionContext<'_>,
+  segment: &SegmentContext,
+  seen_hashes: &mut std::collections::HashSet<String>,
+) -> Option<String> {
+  if !segment.has_meaningful_work() {
+    return None;
+  }
+  let llm = ctx.llm?;
+
+  let turn = match llm::extraction::summarize_turn(llm, &segment.to_extraction_context()).await {
+    Ok(turn) if !turn.summary.is_empty() => turn,
+    Ok(_) => return None,
+    Err(e) => {
+      warn!("Failed to summarize turn: {}", e);
+      return None;
+    }
+  };
+
+  let files: Vec<String> = turn
+    .files_touched
+    .iter()
+    .map(|f| {
+      ctx
+        .root
+        .and_then(|root| path::relative_path(Path::new(f), root))
+        .unwrap_or_else(|| path::normalize(f))
+    })
+    .collect();
+  let content = ctx
+    .redact(&render_turn_summary(&turn.summary, &files, &turn.decisions))
+    .into_owned();
+  let (content_hash, simhash) = compute_hashes(&content);
+  if seen_hashes.contains(&content_hash) {
+    return None;
+  }
+
+  let mut memory = Memory::new(ctx.project_id, content.clone(), Sector::Reflective);
+  memory.content_hash = content_hash.clone();
+  memory.simhash = simhash;
+  memory.memory_type = Some(MemoryType::TurnSummary);
+  memory.visibility = ctx.visibility_for(memory.memory_type);
+  memory.summary = Some(ctx.redact(&turn.summary).into_owned());
+  memory.concepts = extract_concepts(&content);
+  memory.files = files;
+  memory.session_id = ctx.session_id.map(String::from);
+  if let Some(root) = ctx.root {
+    memory.file_anchors = anchor_files(root, &memory.files).await;
+  }
+
+  let stored = async {
+    let vector = ctx.get_embedding(&content).await?;
+    ctx.db.add_memory(&memory, &vector).await?;
+    Ok::<_, ServiceError>(())
+  };
+  if let Err(e) = stored.await {
+    warn!("Failed to store turn summary: {}", e);
+    return None;
+  }
+  record_added(ctx.db, &memory).await;
+  if let Err(e) = entity::link(ctx.db, &memory, &[], ctx.aliases).await {
+    warn!(memory_id = %memory.id, "Failed to link memory entities: {}", e);
+  }
+  seen_hashes.insert(content_hash);
+
+  debug!(memory_id = %memory.id, files = memory.files.len(), "Stored turn summary");
+  Some(memory.id.to_string())
+}
+
+/// Record an extracted memory in its history as added by a hook
+async fn record_added(db: &ProjectDb, memory: &Memory) {
+  let change = MemoryChange::new(memory.id, MemoryEventAction::Added).with_salience(None, Some(memory.salience));
+  record_history(db, MemoryActor::Hook, &[change]).await;
+}
+
+/// Content of a turn summary memory
+fn render_turn_summary(summary: &str, files: &[String], decisions: &[String]) -> String {
+  let mut content = summary.to_string();
+  if !files.is_empty() {
+    content.push_str(&format!("\n\nFiles touched: {}", files.join(", ")));
+  }
+  if !decisions.is_empty() {
+    content.push_str("\n\nDecisions:");
+    for decision in decisions {
+      content.push_str(&format!("\n- {}", decision));
+    }
+  }
+  content
+}
+
+/// Run LLM extraction for a segment, storing the results.
+///
+/// Segments over the token budget are split and each split is extracted with
+/// retries. Candidates from all splits are merged before storage, so the overlap
+/// between splits doesn't produce duplicate memories. Returns the last error
+/// when every split fails.
+async fn extract_segment(
+  ctx: &ExtractionContext<'_>,
+  llm: &dyn LlmProvider,
+  context: &llm::ExtractionContext,
+  segment_id: Uuid,
+  seen_hashes: &mut std::collections::HashSet<String>,
+) -> Result<Vec<String>, llm::LlmError> {
+  let splits = context.split_by_token_budget(ctx.max_tokens, ctx.split_overlap);
+  if splits.len() > 1 {
+    debug!(
+      segment_id = %segment_id,
+      splits = splits.len(),
+      max_tokens = ctx.max_tokens,
+      "Segment exceeds token budget, extracting in splits"
+    );
+  }
+
+  let mut candidates = Vec::new();
+  let mut failed = 0;
+  let mut last_error = None;
+  for split in &splits {
+    match extract_with_retries(llm, split, segment_id).await {
+      Ok(memories) => candidates.extend(memories),
+      Err(e) => {
+        failed += 1;
+        last_error = Some(e);
+      }
+    }
+  }
+
+  if let Some(e) = last_error
+    && failed == splits.len()
+  {
+    warn!(segment_id = %segment_id, "LLM extraction failed for all splits");
+    return Err(e);
+  }
+  if failed > 0 {
+    warn!(
+      segment_id = %segment_id,
+      "LLM extraction failed for {}/{} splits, keeping the rest",
+      failed,
+      splits.len()
+    );
+  }
+
+  let candidate_count = candidates.len();
   let mut memories_created = Vec::new();
+  for extracted in &merge_extracted(candidates) {
+    if let Ok(res) = store_extracted_memory(ctx, extracted, segment_id, seen_hashes).await
+      && let Some(id) = res.memory_id
+    {
+      memories_created.push(id);
+    }
+  }
+  debug!(
+    segment_id = %segment_id,
+    "LLM extraction completed: {} memories created from {} candidates",
+    memories_created.len(),
+    candidate_count
+  );
+  Ok(memories_created)
+}
 
+/// Extract memories from one extraction context, retrying on failure.
+///
+/// Returns the error of the last attempt when every attempt fails.
+async fn extract_with_retries(
+  llm: &dyn LlmProvider,
+  context: &llm::ExtractionContext,
+  segment_id: Uuid,
+) -> Result<Vec<ExtractedMemory>, llm::LlmError> {
   const MAX_ATTEMPTS: u32 = 3;
 
-  for attempt in 1..=MAX_ATTEMPTS {
-    match llm::extraction::extract_memories(llm, &extraction_context).await {
-      Ok(result) => {
-        for extracted in &result.memories {
-          if let Ok(res) = store_extracted_memory(ctx, extracted, seen_hashes).await
-            && let Some(id) = res.memory_id
-          {
-            memories_created.push(id);
-          }
-        }
-        debug!(
-          "LLM extraction completed: {} memories created from {} candidates",
-          memories_created.len(),
-          result.memories.len()
+  let mut attempt = 1;
+  loop {
+    match llm::extraction::extract_memories(llm, context).await {
+      Ok(result) => return Ok(result.memories),
+      Err(e) if attempt < MAX_ATTEMPTS => {
+        warn!(
+          "LLM extraction attempt {}/{} failed: {}, retrying",
+          attempt, MAX_ATTEMPTS, e
         );
-        return Ok(memories_created);
+        attempt += 1;
       }
       Err(e) => {
-        if attempt < MAX_ATTEMPTS {
-          warn!(
-            "LLM extraction attempt {}/{} failed: {}, retrying",
-            attempt, MAX_ATTEMPTS, e
-          );
-        } else {
-          warn!(
-            "LLM extraction failed after {} attempts: {}, discarding segment",
-            MAX_ATTEMPTS, e
-          );
+        warn!(
+          segment_id = %segment_id,
+          "LLM extraction failed after {} attempts: {}",
+          MAX_ATTEMPTS, e
+        );
+        return Err(e);
+      }
+    }
+  }
+}
+
+/// Jaccard similarity at which memories extracted from different splits are merged
+const SPLIT_MERGE_JACCARD: f32 = 0.7;
+
+/// Merge exact and near-duplicate memories extracted from overlapping splits.
+///
+/// The higher-confidence memory of each duplicate group is kept, with the tags
+/// of the group combined.
+pub(super) fn merge_extracted(candidates: Vec<ExtractedMemory>) -> Vec<ExtractedMemory> {
+  let mut merged: Vec<(ExtractedMemory, String, u64)> = Vec::new();
+
+  for candidate in candidates {
+    let (hash, simhash) = compute_hashes(&candidate.content);
+    let duplicate = merged.iter_mut().find(|(kept, kept_hash, kept_simhash)| {
+      *kept_hash == hash
+        || (hamming_distance(simhash, *kept_simhash) <= adaptive_threshold(candidate.content.len())
+          && jaccard_similarity(&candidate.content, &kept.content) >= SPLIT_MERGE_JACCARD)
+    });
+
+    match duplicate {
+      Some((kept, kept_hash, kept_simhash)) => {
+        let mut tags = std::mem::take(&mut kept.tags);
+        for tag in &candidate.tags {
+          if !tags.contains(tag) {
+            tags.push(tag.clone());
+          }
+        }
+        let mut entities = std::mem::take(&mut kept.entities);
+        for entity in &candidate.entities {
+          if !entities.contains(entity) {
+            entities.push(entity.clone());
+          }
         }
+        if candidate.confidence > kept.confidence {
+          *kept = candidate;
+          *kept_hash = hash;
+          *kept_simhash = simhash;
+        }
+        kept.tags = tags;
+        kept.entities = entities;
       }
+      None => merged.push((candidate, hash, simhash)),
     }
   }
 
-  // All retries exhausted - return empty (discard memory)
-  Ok(Vec::new())
+  merged.into_iter().map(|(memory, ..)| memory).collect()
 }
 
 /// Extract high-priority memories (corrections/preferences) immediately.
@@ -288,12 +753,15 @@ pub async fn extract_high_priority(
 
   debug!("High-priority signal detected: {:?}", classification.category);
 
+  let segment_id = Uuid::now_v7();
+  let recorder = ctx.capture.map(|store| store.recorder(llm));
+  let provider = recorder.as_ref().map(|r| r.provider()).unwrap_or(llm);
   let mut memories_created = Vec::new();
 
-  match llm::extraction::extract_high_priority(llm, user_message, classification).await {
+  match llm::extraction::extract_high_priority(provider, user_message, classification).await {
     Ok(result) => {
       for extracted in &result.memories {
-        if let Ok(res) = store_extracted_memory(ctx, extracted, seen_hashes).await
+        if let Ok(res) = store_extracted_memory(ctx, extracted, segment_id, seen_hashes).await
           && let Some(id) = res.memory_id
         {
           memories_created.push(id);
@@ -308,6 +776,12 @@ pub async fn extract_high_priority(
     }
   }
 
+  if let (Some(store), Some(recorder)) = (ctx.capture, recorder) {
+    store
+      .save(segment_id, CaptureKind::HighPriority, recorder, &memories_created)
+      .await;
+  }
+
   Ok(memories_created)
 }
 
@@ -323,3 +797,80 @@ pub async fn extract_high_priority(
 pub async fn classify_signal(llm: &dyn LlmProvider, user_message: &str) -> Result<SignalClassification, ServiceError> {
   Ok(llm::extraction::classify_signal(llm, user_message).await?)
 }
+
+#[cfg(test)]
+mod tests {
+  use llm::MemoryType;
+
+  use super::*;
+
+  fn extracted(content: &str, confidence: f32, tags: &[&str]) -> ExtractedMemory {
+    ExtractedMemory {
+      content: content.to_string(),
+      summary: None,
+      memory_type: MemoryType::Codebase,
+      tags: tags.iter().map(|t| t.to_string()).collect(),
+      confidence,
+      entities: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_merge_extracted_combines_split_duplicates() {
+    let candidates = vec![
+      extracted(
+        "The indexer batches embedding requests in groups of 64 to stay under provider rate limits",
+        0.6,
+        &["indexing"],
+      ),
+      extracted(
+        "Integration tests require a running Postgres container started with docker compose",
+        0.8,
+        &["testing"],
+      ),
+      extracted(
+        "The indexer batches embedding requests in groups of 64, to stay under provider rate limits.",
+        0.9,
+        &["embedding"],
+      ),
+      extracted(
+        "Integration tests require a running Postgres container started with docker compose",
+        0.5,
+        &["testing", "docker"],
+      ),
+    ];
+
+    let merged = merge_extracted(candidates);
+    assert_eq!(merged.len(), 2, "near and exact duplicates should be merged");
+
+    let indexer = &merged[0];
+    assert_eq!(indexer.confidence, 0.9, "higher-confidence duplicate should be kept");
+    assert!(
+      indexer.content.ends_with("limits."),
+      "content of the kept duplicate should win"
+    );
+    assert_eq!(indexer.tags, vec!["indexing".to_string(), "embedding".to_string()]);
+
+    assert_eq!(merged[1].confidence, 0.8);
+    assert_eq!(
+      merged[1].tags,
+      vec!["testing".to_string(), "docker".to_string()],
+      "tags of merged duplicates should be combined"
+    );
+  }
+
+  #[test]
+  fn test_render_turn_summary() {
+    let content = render_turn_summary(
+      "Added retry with backoff to the webhook sender.",
+      &["src/webhooks/send.rs".to_string()],
+      &["Cap retries at five so a dead endpoint cannot stall the queue".to_string()],
+    );
+    assert_eq!(
+      content,
+      "Added retry with backoff to the webhook sender.\n\nFiles touched: src/webhooks/send.rs\n\nDecisions:\n- Cap \
+       retries at five so a dead endpoint cannot stall the queue"
+    );
+    assert_eq!(render_turn_summary("Read the docs.", &[], &[]), "Read the docs.");
+  }
+}
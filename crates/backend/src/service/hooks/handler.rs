@@ -9,24 +9,36 @@
 //!
 //! Business logic lives in the service modules (extraction).
 
-use std::collections::HashSet;
+use std::{collections::HashSet, path::Path};
 
 use llm::LlmProvider;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use super::{
+  capture::CaptureStore,
+  compaction,
   context::SegmentContext,
   event::HookEvent,
   extraction::{self, ExtractionContext},
+  privacy::{self, PrivacyRules},
+  prompt_context,
+  queue::RetryPolicy,
+  session_context, tool_warnings,
+  usage::{self, Injected},
 };
 use crate::{
+  context::{memory::extract::classifier::ExtractionRules, redact::Redactor},
   db::ProjectDb,
-  domain::config::HooksConfig,
+  domain::{
+    alias::Aliases,
+    config::{HooksConfig, InjectionConfig, InjectionPolicy, VisibilityConfig},
+    memory::Memory,
+  },
   embedding::EmbeddingProvider,
   ipc::types::hook::{
-    PostToolUseHookResult, PreCompactHookResult, SessionEndHookResult, SessionStartHookResult, SimpleHookResult,
-    StopHookResult, UserPromptHookResult,
+    PostToolUseHookResult, PreCompactHookResult, PreToolUseHookResult, SessionEndHookResult, SessionStartHookResult,
+    SimpleHookResult, StopHookResult, UserPromptHookResult,
   },
   service::util::ServiceError,
 };
@@ -45,6 +57,22 @@ pub struct HookContext<'a> {
   pub project_id: Uuid,
   /// Hooks configuration
   pub config: &'a HooksConfig,
+  /// Prompt/response capture, when debug capture is enabled
+  pub capture: Option<&'a CaptureStore>,
+  /// Project root, used to anchor referenced files with git blame
+  pub root: Option<&'a Path>,
+  /// Extraction rules compiled from `config.extraction_rules`
+  pub rules: Option<&'a ExtractionRules>,
+  /// Project jargon, for tag and entity normalization
+  pub aliases: Option<&'a Aliases>,
+  /// Paths and directories whose work is never extracted
+  pub privacy: Option<&'a PrivacyRules>,
+  /// Default visibility of extracted memories per type
+  pub visibility: Option<&'a VisibilityConfig>,
+  /// Secrets scrubbed from extracted memories before storage
+  pub redactor: Option<&'a Redactor>,
+  /// Which memories are injected at session start and with prompts
+  pub injection: Option<&'a InjectionConfig>,
 }
 
 impl<'a> HookContext<'a> {
@@ -62,19 +90,120 @@ impl<'a> HookContext<'a> {
       llm,
       project_id,
       config,
+      capture: None,
+      root: None,
+      rules: None,
+      aliases: None,
+      privacy: None,
+      visibility: None,
+      redactor: None,
+      injection: None,
     }
   }
 
+  /// Anchor files referenced by extracted memories to git history under `root`
+  pub fn with_root(mut self, root: &'a Path) -> Self {
+    self.root = Some(root);
+    self
+  }
+
+  /// Record extraction prompts and responses to the given store
+  pub fn with_capture(mut self, capture: Option<&'a CaptureStore>) -> Self {
+    self.capture = capture;
+    self
+  }
+
+  /// Apply deterministic extraction rules before and alongside the LLM
+  pub fn with_rules(mut self, rules: &'a ExtractionRules) -> Self {
+    self.rules = Some(rules);
+    self
+  }
+
+  /// Store extracted tags and entities under the project's canonical terms
+  pub fn with_aliases(mut self, aliases: &'a Aliases) -> Self {
+    self.aliases = Some(aliases);
+    self
+  }
+
+  /// Keep work on private paths and in private directories out of extraction
+  pub fn with_privacy(mut self, privacy: &'a PrivacyRules) -> Self {
+    self.privacy = Some(privacy);
+    self
+  }
+
+  /// Give extracted memories the project's default visibility for their type
+  pub fn with_visibility(mut self, visibility: &'a VisibilityConfig) -> Self {
+    self.visibility = Some(visibility);
+    self
+  }
+
+  /// Replace secrets in extracted memories with placeholders
+  pub fn with_redactor(mut self, redactor: &'a Redactor) -> Self {
+    self.redactor = Some(redactor);
+    self
+  }
+
+  /// Inject memories at session start and with prompts per the project's policies
+  pub fn with_injection(mut self, injection: &'a InjectionConfig) -> Self {
+    self.injection = Some(injection);
+    self
+  }
+
+  /// When a memory may be injected
+  pub(super) fn injection_policy(&self, memory: &Memory) -> InjectionPolicy {
+    self
+      .injection
+      .map(|injection| injection.for_memory(memory))
+      .unwrap_or_default()
+  }
+
   /// Create an extraction context from this hook context
-  fn extraction_context(&self) -> ExtractionContext<'_> {
+  pub(super) fn extraction_context(&self) -> ExtractionContext<'_> {
     ExtractionContext::new(self.db, self.embedding, self.llm, self.project_id)
+      .with_capture(self.capture)
+      .with_root(self.root)
+      .with_split(self.config.extraction_max_tokens, self.config.extraction_split_overlap)
+      .with_retry(RetryPolicy::from_config(self.config))
+      .with_rules(self.rules)
+      .with_aliases(self.aliases)
+      .with_visibility(self.visibility)
+      .with_redactor(self.redactor)
+  }
+
+  /// Check if rolling session summaries are enabled
+  pub(super) fn session_summary_enabled(&self) -> bool {
+    self.config.enabled && self.config.session_summary && self.llm.is_some()
+  }
+
+  /// Check if turn summaries are stored on Stop
+  pub(super) fn turn_summaries_enabled(&self) -> bool {
+    self.config.enabled && self.config.turn_summaries && self.llm.is_some()
   }
 
   /// Check if hooks are enabled
-  fn is_enabled(&self) -> bool {
+  pub(super) fn is_enabled(&self) -> bool {
     self.config.enabled
   }
 
+  /// Check if the session behind a hook event works in a private directory
+  fn is_private_session(&self, params: &serde_json::Value) -> bool {
+    let cwd = params.get("cwd").and_then(|v| v.as_str());
+    self
+      .privacy
+      .zip(cwd)
+      .is_some_and(|(privacy, cwd)| privacy.is_private_dir(Path::new(cwd)))
+  }
+
+  /// Check if the turn or the session behind a hook event is private
+  fn is_private(&self, params: &serde_json::Value, segment: Option<&SegmentContext>) -> bool {
+    segment.is_some_and(|s| s.private) || self.is_private_session(params)
+  }
+
+  /// Check if hooks are enabled and the turn may be extracted from
+  fn may_extract(&self, params: &serde_json::Value, segment: Option<&SegmentContext>) -> bool {
+    self.is_enabled() && !self.is_private(params, segment)
+  }
+
   /// Check if background extraction is enabled
   fn use_background_extraction(&self) -> bool {
     self.config.background_extraction
@@ -95,6 +224,8 @@ pub struct HookState {
   pub session_contexts: std::collections::HashMap<String, SegmentContext>,
   /// Deduplication hash set
   pub seen_hashes: HashSet<String>,
+  /// Summaries built on PreCompact, injected by the SessionStart that follows compaction
+  pub compaction_summaries: std::collections::HashMap<String, Injected>,
 }
 
 impl HookState {
@@ -103,7 +234,22 @@ impl HookState {
     Self {
       session_contexts: std::collections::HashMap::new(),
       seen_hashes: HashSet::new(),
+      compaction_summaries: std::collections::HashMap::new(),
+    }
+  }
+
+  /// Unfinished TodoWrite tasks across live sessions, without duplicates
+  pub fn open_tasks(&self) -> Vec<String> {
+    let mut sessions: Vec<_> = self.session_contexts.iter().collect();
+    sessions.sort_by_key(|(id, _)| *id);
+
+    let mut tasks: Vec<String> = Vec::new();
+    for task in sessions.into_iter().flat_map(|(_, s)| &s.pending_tasks) {
+      if !tasks.contains(task) {
+        tasks.push(task.clone());
+      }
     }
+    tasks
   }
 
   /// Maximum number of hashes to keep before clearing
@@ -134,7 +280,7 @@ impl Default for HookState {
 /// Handle SessionStart hook event.
 pub async fn handle_session_start(
   ctx: &HookContext<'_>,
-  _state: &mut HookState,
+  state: &mut HookState,
   params: &serde_json::Value,
   project_info: SessionStartInfo,
 ) -> Result<SessionStartHookResult, ServiceError> {
@@ -148,12 +294,39 @@ pub async fn handle_session_start(
     .await
     .map_err(|e| ServiceError::internal(format!("Failed to create session: {}", e)))?;
 
+  // Injection is best effort: a session starts without it rather than failing
+  let memories = if ctx.config.session_start_context {
+    let cwd = params.get("cwd").and_then(|v| v.as_str()).map(Path::new);
+    session_context::build(ctx, cwd).await.unwrap_or_else(|e| {
+      warn!("Failed to build session start context: {}", e);
+      None
+    })
+  } else {
+    None
+  };
+
+  // After compaction, restate what the session had established before it
+  let source = params.get("source").and_then(|v| v.as_str());
+  let compacted = if source == Some("compact") {
+    state.compaction_summaries.remove(session_id)
+  } else {
+    None
+  };
+  for injected in compacted.iter().chain(&memories) {
+    usage::record_injected(ctx, session_id, injected, HookEvent::SessionStart).await;
+  }
+  let additional_context = match (compacted, memories) {
+    (Some(compacted), Some(memories)) => Some(format!("{}\n{}", compacted.text, memories.text)),
+    (compacted, memories) => compacted.or(memories).map(|injected| injected.text),
+  };
+
   Ok(SessionStartHookResult {
     status: "ok".to_string(),
     project_id: project_info.project_id.clone(),
     project_name: project_info.project_name.clone(),
     project_path: project_info.project_path.clone(),
     watcher_started: project_info.watcher_started,
+    additional_context,
   })
 }
 
@@ -180,7 +353,7 @@ pub async fn handle_session_end(
   let mut memories_promoted = 0;
 
   // Extract memory from session summary if provided
-  if ctx.is_enabled()
+  if ctx.may_extract(params, None)
     && let Some(summary_text) = summary
   {
     let ext_ctx = ctx.extraction_context();
@@ -190,6 +363,7 @@ pub async fn handle_session_end(
       memories_created.push(id);
     }
   }
+  usage::link_created(ctx, session_id, &memories_created, HookEvent::SessionEnd).await;
 
   // End the session in the database
   if let Err(e) = ctx.db.end_session(session_id, summary.map(String::from)).await {
@@ -240,34 +414,111 @@ pub async fn handle_user_prompt_submit(
 
   let mut memories_created = Vec::new();
 
-  // Get or create session context, record user prompt
+  // A marked prompt makes the whole turn private; it is never recorded
+  let segment_ctx = state.session_contexts.entry(session_id.to_string()).or_default();
+  if privacy::has_ignore_marker(prompt) {
+    debug!(session_id = %session_id, "Prompt marked {}, skipping turn", privacy::IGNORE_MARKER);
+    segment_ctx.mark_private();
+  }
+
+  // Record user prompt
   // Note: We don't reset here - tool uses accumulate until Stop/PreCompact
   // First prompt becomes user_prompt, subsequent ones go to additional_prompts
-  let segment_ctx = state.session_contexts.entry(session_id.to_string()).or_default();
-  segment_ctx.record_user_prompt(prompt.to_string());
+  if !ctx.is_private(params, Some(&*segment_ctx)) {
+    segment_ctx.record_user_prompt(prompt.to_string());
+    if ctx.is_enabled() {
+      memories_created.extend(extract_prompt_signals(ctx, prompt, &mut state.seen_hashes).await);
+    }
+  }
+  usage::link_created(ctx, session_id, &memories_created, HookEvent::UserPromptSubmit).await;
+
+  // Injection is best effort: the prompt goes ahead without it rather than failing
+  let context = prompt_context::build(ctx, session_id, prompt)
+    .await
+    .unwrap_or_else(|e| {
+      warn!("Failed to build prompt context: {}", e);
+      None
+    });
+  if let Some(injected) = &context {
+    usage::record_injected(ctx, session_id, injected, HookEvent::UserPromptSubmit).await;
+  }
+
+  state.maybe_clear_seen_hashes();
 
-  // Check for high-priority signals (corrections/preferences)
-  if ctx.is_enabled()
-    && ctx.high_priority_signals_enabled()
-    && !prompt.is_empty()
+  Ok(UserPromptHookResult {
+    status: "ok".to_string(),
+    memories_created,
+    additional_context: context.map(|injected| injected.text),
+  })
+}
+
+/// Extract memories from a user prompt.
+///
+/// Lines matched by capture rules are stored first, then high-priority signals
+/// (corrections/preferences) are extracted by the LLM.
+pub(super) async fn extract_prompt_signals(
+  ctx: &HookContext<'_>,
+  prompt: &str,
+  seen_hashes: &mut HashSet<String>,
+) -> Vec<String> {
+  let ext_ctx = ctx.extraction_context();
+  let mut memories_created = match extraction::extract_with_rules(&ext_ctx, prompt, seen_hashes).await {
+    Ok(ids) => ids,
+    Err(e) => {
+      warn!("Rule extraction failed: {}", e);
+      Vec::new()
+    }
+  };
+
+  if ctx.high_priority_signals_enabled()
     && prompt.len() >= 20
     && let Some(llm) = ctx.llm
     && let Ok(classification) = extraction::classify_signal(llm, prompt).await
     && classification.category.is_high_priority()
     && classification.is_extractable
   {
-    let ext_ctx = ctx.extraction_context();
-    if let Ok(ids) = extraction::extract_high_priority(&ext_ctx, prompt, &classification, &mut state.seen_hashes).await
-    {
+    if let Ok(ids) = extraction::extract_high_priority(&ext_ctx, prompt, &classification, seen_hashes).await {
       memories_created.extend(ids);
     }
   }
+  memories_created
+}
 
-  state.maybe_clear_seen_hashes();
+/// Handle PreToolUse hook event.
+///
+/// Warns about memories scoped to a file Claude is about to edit. Nothing is
+/// recorded; the tool's use is tracked once it has run, from PostToolUse.
+pub async fn handle_pre_tool_use(
+  ctx: &HookContext<'_>,
+  _state: &mut HookState,
+  params: &serde_json::Value,
+) -> Result<PreToolUseHookResult, ServiceError> {
+  let tool_name = params.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+  let tool_input = params
+    .get("tool_input")
+    .cloned()
+    .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
 
-  Ok(UserPromptHookResult {
+  // Warnings are best effort: the tool runs without them rather than failing
+  let warnings = if ctx.is_enabled() && ctx.config.pre_tool_warnings {
+    tool_warnings::build(ctx, tool_name, &tool_input)
+      .await
+      .unwrap_or_else(|e| {
+        warn!("Failed to build pre-tool warnings: {}", e);
+        None
+      })
+  } else {
+    None
+  };
+  if let Some(injected) = &warnings
+    && let Some(session_id) = params.get("session_id").and_then(|v| v.as_str())
+  {
+    usage::record_injected(ctx, session_id, injected, HookEvent::PreToolUse).await;
+  }
+
+  Ok(PreToolUseHookResult {
     status: "ok".to_string(),
-    memories_created,
+    additional_context: warnings.map(|injected| injected.text),
   })
 }
 
@@ -290,54 +541,35 @@ pub async fn handle_post_tool_use(
   // Accumulate tool use data in session context
   let segment_ctx = state.session_contexts.entry(session_id.to_string()).or_default();
 
-  // Parse typed tool use from hook event
-  let tool_use = llm::ToolUse::from_hook_event(tool_name, &tool_params, tool_result);
-
-  // Track derived data for quick access (files, commands, searches, tasks)
-  if let Some(path) = tool_use.file_path()
-    && !path.is_empty()
-  {
-    if tool_use.is_file_modification() {
-      segment_ctx.record_file_modified(path);
-    } else if tool_use.is_file_read() {
-      segment_ctx.record_file_read(path);
-    }
+  // Touching a private path makes the turn private, dropping what it recorded so far
+  if let Some(path) = ctx.privacy.and_then(|privacy| privacy.private_tool_path(&tool_params)) {
+    debug!(session_id = %session_id, path = %path, "Tool touched a private path, skipping turn");
+    segment_ctx.mark_private();
   }
 
-  if let Some((cmd, exit_code)) = tool_use.command_info()
-    && !cmd.is_empty()
-  {
-    segment_ctx.record_command(cmd.to_string(), exit_code);
-  }
-
-  if let Some(pattern) = tool_use.search_pattern()
-    && !pattern.is_empty()
-  {
-    segment_ctx.record_search(pattern);
-  }
-
-  if let Some(tasks) = tool_use.completed_tasks() {
-    for task in tasks {
-      segment_ctx.record_completed_task(task);
-    }
+  let private = ctx.is_private(params, Some(&*segment_ctx));
+  if !private {
+    // Parse typed tool use from hook event, tracking derived files, commands, searches and tasks
+    let tool_use = llm::ToolUse::from_hook_event(tool_name, &tool_params, tool_result);
+    segment_ctx.track_tool_use(tool_use);
   }
 
-  segment_ctx.record_tool_use(tool_use);
-
   // Check for todo completion trigger: ≥3 tasks completed AND ≥5 tool calls
   let should_trigger = segment_ctx.completed_tasks.len() >= 3 && segment_ctx.tool_call_count() >= 5;
 
-  if should_trigger && ctx.is_enabled() {
+  if should_trigger && ctx.is_enabled() && !private {
     debug!(
       "Todo completion trigger: extracting memories for session {}",
       session_id
     );
-    let ext_ctx = ctx.extraction_context();
-    if let Ok(_ids) = extraction::extract_with_llm(&ext_ctx, segment_ctx, &mut state.seen_hashes).await {
-      // Memories stored from todo_completion trigger
+    let ext_ctx = ctx.extraction_context().with_session(session_id);
+    if let Ok(ids) = extraction::extract_with_llm(&ext_ctx, segment_ctx, &mut state.seen_hashes).await {
+      usage::link_created(ctx, session_id, &ids, HookEvent::PostToolUse).await;
     }
   }
 
+  usage::track_memory_tool(ctx, session_id, tool_name, &tool_params, tool_result).await;
+
   state.maybe_clear_seen_hashes();
 
   Ok(PostToolUseHookResult {
@@ -359,24 +591,55 @@ pub async fn handle_pre_compact(
   let mut memories_created = Vec::new();
 
   // Extract from current segment before compaction
-  if let Some(segment_ctx) = state.session_contexts.get_mut(session_id) {
-    if ctx.is_enabled() && segment_ctx.has_meaningful_work() {
-      let ext_ctx = ctx.extraction_context();
+  let may_extract = ctx.may_extract(params, state.session_contexts.get(session_id));
+  if let Some(segment_ctx) = state.session_contexts.get_mut(session_id)
+    && may_extract
+  {
+    if segment_ctx.has_meaningful_work() {
+      let ext_ctx = ctx.extraction_context().with_session(session_id);
       match extraction::extract_with_llm(&ext_ctx, segment_ctx, &mut state.seen_hashes).await {
         Ok(ids) => memories_created.extend(ids),
         Err(e) => {
           warn!("LLM extraction failed in pre-compact: {}", e);
-          // No fallback - extract_with_llm already handles retries
+          // No fallback - failed segments are queued for retry
         }
       }
     }
+    if ctx.session_summary_enabled() {
+      extraction::update_session_summary(&ctx.extraction_context(), segment_ctx).await;
+    }
+  }
+
+  // Condense the session before the segment is reset, and keep it for the
+  // SessionStart that follows compaction, which records what it injects
+  let compacted = if ctx.config.compaction_context {
+    compaction::build(
+      ctx,
+      session_id,
+      state.session_contexts.get(session_id),
+      &memories_created,
+    )
+    .await
+    .unwrap_or_else(|e| {
+      warn!("Failed to build compaction context: {}", e);
+      None
+    })
+  } else {
+    None
+  };
+  let additional_context = compacted.as_ref().map(|injected| injected.text.clone());
+  if let Some(injected) = compacted {
+    state.compaction_summaries.insert(session_id.to_string(), injected);
+  }
+  // A private turn stays private once compaction is done
+  if let Some(segment_ctx) = state.session_contexts.get_mut(session_id) {
+    let private = segment_ctx.private;
     segment_ctx.reset();
+    segment_ctx.private = private;
   }
 
   // Also extract from provided summary if any
-  if ctx.is_enabled()
-    && let Some(summary_text) = summary
-  {
+  if may_extract && let Some(summary_text) = summary {
     let ext_ctx = ctx.extraction_context();
     if let Ok(res) = extraction::extract_memory(&ext_ctx, summary_text, &mut state.seen_hashes).await
       && let Some(id) = res.memory_id
@@ -384,6 +647,7 @@ pub async fn handle_pre_compact(
       memories_created.push(id);
     }
   }
+  usage::link_created(ctx, session_id, &memories_created, HookEvent::PreCompact).await;
 
   state.maybe_clear_seen_hashes();
 
@@ -391,6 +655,7 @@ pub async fn handle_pre_compact(
     status: "ok".to_string(),
     background_extraction: ctx.use_background_extraction(),
     memories_created,
+    additional_context,
   })
 }
 
@@ -407,25 +672,35 @@ pub async fn handle_stop(
 
   let mut memories_created = Vec::new();
 
-  // Final extraction from accumulated context
-  if let Some(segment_ctx) = state.session_contexts.remove(session_id)
-    && ctx.is_enabled()
-    && segment_ctx.has_meaningful_work()
-  {
-    let ext_ctx = ctx.extraction_context();
-    match extraction::extract_with_llm(&ext_ctx, &segment_ctx, &mut state.seen_hashes).await {
-      Ok(ids) => memories_created.extend(ids),
-      Err(e) => {
-        warn!("LLM extraction failed: {}", e);
-        // No fallback - extract_with_llm already handles retries
+  // Extract from the accumulated segment. The context is kept until SessionEnd
+  // so the rolling summary carries over to the next turn. A private turn is
+  // only reset.
+  let may_extract = ctx.may_extract(params, state.session_contexts.get(session_id));
+  if let Some(segment_ctx) = state.session_contexts.get_mut(session_id) {
+    if may_extract && segment_ctx.has_meaningful_work() {
+      let ext_ctx = ctx.extraction_context().with_session(session_id);
+      match extraction::extract_with_llm(&ext_ctx, segment_ctx, &mut state.seen_hashes).await {
+        Ok(ids) => memories_created.extend(ids),
+        Err(e) => {
+          warn!("LLM extraction failed: {}", e);
+          // No fallback - failed segments are queued for retry
+        }
+      }
+    }
+    if may_extract && ctx.turn_summaries_enabled() {
+      let ext_ctx = ctx.extraction_context().with_session(session_id);
+      if let Some(id) = extraction::store_turn_summary(&ext_ctx, segment_ctx, &mut state.seen_hashes).await {
+        memories_created.push(id);
       }
     }
+    if may_extract && ctx.session_summary_enabled() {
+      extraction::update_session_summary(&ctx.extraction_context(), segment_ctx).await;
+    }
+    segment_ctx.reset();
   }
 
   // Extract from provided summary
-  if ctx.is_enabled()
-    && let Some(summary_text) = summary
-  {
+  if may_extract && let Some(summary_text) = summary {
     let ext_ctx = ctx.extraction_context();
     if let Ok(res) = extraction::extract_memory(&ext_ctx, summary_text, &mut state.seen_hashes).await
       && let Some(id) = res.memory_id
@@ -433,6 +708,7 @@ pub async fn handle_stop(
       memories_created.push(id);
     }
   }
+  usage::link_created(ctx, session_id, &memories_created, HookEvent::Stop).await;
 
   state.maybe_clear_seen_hashes();
 
@@ -539,6 +815,10 @@ pub async fn dispatch(
       let result = handle_user_prompt_submit(ctx, state, params).await?;
       serde_json::to_value(result).map_err(|e| ServiceError::validation(e.to_string()))
     }
+    HookEvent::PreToolUse => {
+      let result = handle_pre_tool_use(ctx, state, params).await?;
+      serde_json::to_value(result).map_err(|e| ServiceError::validation(e.to_string()))
+    }
     HookEvent::PostToolUse => {
       let result = handle_post_tool_use(ctx, state, params).await?;
       serde_json::to_value(result).map_err(|e| ServiceError::validation(e.to_string()))
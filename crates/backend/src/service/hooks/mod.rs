@@ -18,9 +18,20 @@
 //! hooks/
 //! ├── mod.rs          # Re-exports and public API
 //! ├── event.rs        # HookEvent enum and parsing
+//! ├── capture.rs      # Prompt/response capture for extraction debugging
+//! ├── compaction.rs   # Context carried across compaction
 //! ├── context.rs      # SegmentContext for session accumulation
+//! ├── edits.rs        # Files changed by Claude's tool calls
 //! ├── extraction.rs   # Memory extraction service
-//! └── handler.rs      # Event dispatch and handling
+//! ├── handler.rs      # Event dispatch and handling
+//! ├── privacy.rs      # Ignore markers and private paths
+//! ├── prompt_context.rs # Memories injected with a prompt about them
+//! ├── queue.rs        # Retry queue for failed extractions
+//! ├── session_context.rs # Memories injected when a session starts
+//! ├── text.rs         # Manual extraction over user-provided text
+//! ├── tool_warnings.rs # Warnings before Claude edits a file
+//! ├── transcript.rs   # Claude Code transcript import
+//! └── usage.rs        # Session memory usage tracking
 //! ```
 //!
 //! ## Usage
@@ -42,11 +53,28 @@
 //! - **Services are testable** - Pure functions with injected dependencies
 //! - **State is explicit** - HookState passed through handlers, not hidden
 
+mod capture;
+mod compaction;
 mod context;
+mod edits;
 mod event;
 mod extraction;
 mod handler;
+mod privacy;
+mod prompt_context;
+mod queue;
+mod session_context;
+mod text;
+mod tool_warnings;
+mod transcript;
+mod usage;
 
 // Re-export public types
+pub use capture::{CaptureStore, load_capture};
+pub use edits::{EditedFile, edited_file};
 pub use event::HookEvent;
 pub use handler::{HookContext, HookState, SessionStartInfo, dispatch};
+pub use privacy::PrivacyRules;
+pub use queue::{RetryReport, list_extraction_jobs, retry_due_extractions, retry_extractions};
+pub use text::extract_text;
+pub use transcript::import_transcript;
@@ -0,0 +1,142 @@
+//! Privacy rules for hook capture.
+//!
+//! Some work should never become a memory. A prompt containing
+//! [`IGNORE_MARKER`] keeps its whole turn out of extraction. `private_paths`
+//! globs do the same for any turn whose tool calls name a matching file, and
+//! sessions started under one of the `private_dirs` are never extracted from.
+//! The handlers check these before a prompt or tool use is recorded, so
+//! nothing from private work reaches the database, the capture store or the
+//! LLM.
+
+use std::path::{Path, PathBuf};
+
+use crate::domain::{
+  config::HooksConfig,
+  path::{self, PathFilter},
+};
+
+/// Marker that keeps a prompt's turn out of extraction
+pub(super) const IGNORE_MARKER: &str = "@ccengram-ignore";
+
+/// Tool input fields that name a file or directory
+const PATH_FIELDS: [&str; 3] = ["file_path", "notebook_path", "path"];
+
+/// Whether a prompt asks not to be remembered
+pub(super) fn has_ignore_marker(prompt: &str) -> bool {
+  prompt.to_lowercase().contains(IGNORE_MARKER)
+}
+
+/// Compiled `private_paths` and `private_dirs` from `[hooks]`
+#[derive(Debug, Clone, Default)]
+pub struct PrivacyRules {
+  root: PathBuf,
+  /// Files no turn may touch and still be extracted
+  paths: PathFilter,
+  /// Session working directories that are never extracted from
+  dirs: Vec<PathBuf>,
+}
+
+impl PrivacyRules {
+  /// Compile the rules for a project rooted at `root`
+  pub fn from_config(root: &Path, config: &HooksConfig) -> Self {
+    Self {
+      root: root.to_path_buf(),
+      paths: PathFilter::new(root, &[], &config.private_paths),
+      dirs: config
+        .private_dirs
+        .iter()
+        .map(|dir| root.join(dir)) // joining an absolute path replaces the root
+        .collect(),
+    }
+  }
+
+  /// Whether a session working in `cwd` is private
+  pub fn is_private_dir(&self, cwd: &Path) -> bool {
+    self.dirs.iter().any(|dir| path::relative_path(cwd, dir).is_some())
+  }
+
+  /// Whether `file` (absolute, or relative to the root) matches `private_paths`
+  pub fn is_private_path(&self, file: &str) -> bool {
+    if self.paths.is_empty() {
+      return false;
+    }
+    let file = Path::new(file);
+    let relative = if file.is_absolute() {
+      path::relative_path(file, &self.root)
+    } else {
+      Some(path::normalize(&file.to_string_lossy()))
+    };
+    // Search tools name directories, so a path counts when it matches as either
+    relative.is_some_and(|relative| {
+      !relative.is_empty() && (self.paths.is_excluded(&relative, false) || self.paths.is_excluded(&relative, true))
+    })
+  }
+
+  /// The first private path a tool call names in its input
+  pub(super) fn private_tool_path<'a>(&self, tool_input: &'a serde_json::Value) -> Option<&'a str> {
+    PATH_FIELDS
+      .iter()
+      .filter_map(|field| tool_input.get(field)?.as_str())
+      .find(|file| self.is_private_path(file))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn rules() -> PrivacyRules {
+    let config = HooksConfig {
+      private_paths: vec!["secrets/".to_string(), "*.pem".to_string()],
+      private_dirs: vec!["clients/acme".to_string(), "/srv/private".to_string()],
+      ..HooksConfig::default()
+    };
+    PrivacyRules::from_config(Path::new("/repo"), &config)
+  }
+
+  #[test]
+  fn test_private_paths_match_tool_input() {
+    let rules = rules();
+
+    assert!(
+      rules.is_private_path("/repo/secrets/prod.env"),
+      "under a private directory"
+    );
+    assert!(rules.is_private_path("certs/server.pem"), "relative paths match too");
+    assert!(!rules.is_private_path("/repo/src/secrets.rs"));
+    assert!(
+      !rules.is_private_path("/elsewhere/secrets/prod.env"),
+      "outside the project"
+    );
+
+    let grep = json!({ "pattern": "API_KEY", "path": "/repo/secrets" });
+    assert_eq!(rules.private_tool_path(&grep), Some("/repo/secrets"));
+    let edit = json!({ "file_path": "/repo/src/lib.rs", "old_string": "a", "new_string": "b" });
+    assert_eq!(rules.private_tool_path(&edit), None);
+
+    assert!(!PrivacyRules::default().is_private_path("secrets/prod.env"), "no rules");
+  }
+
+  #[test]
+  fn test_private_dirs_and_marker() {
+    let rules = rules();
+
+    assert!(rules.is_private_dir(Path::new("/repo/clients/acme")));
+    assert!(
+      rules.is_private_dir(Path::new("/repo/clients/acme/api")),
+      "subdirectories too"
+    );
+    assert!(
+      rules.is_private_dir(Path::new("/srv/private")),
+      "absolute dirs are kept"
+    );
+    assert!(!rules.is_private_dir(Path::new("/repo/clients/acme-public")));
+    assert!(!rules.is_private_dir(Path::new("/repo")));
+
+    assert!(has_ignore_marker("rotate the keys @ccengram-ignore"));
+    assert!(has_ignore_marker("@CCENGRAM-IGNORE\nwhat is in prod.env?"));
+    assert!(!has_ignore_marker("remember to rotate the keys"));
+  }
+}
@@ -0,0 +1,212 @@
+//! Memories injected with a prompt.
+//!
+//! A UserPromptSubmit hook can hand Claude Code `additionalContext` along
+//! with the prompt. Memories whose `[injection]` policy is `prompt` or
+//! `always` are injected when the prompt is about them: the prompt is
+//! embedded, and the closest memories above `prompt_min_similarity` and
+//! `min_salience` are listed up to the limit and token budget. Memories
+//! already injected into the session are left out, so a long session isn't
+//! reminded of the same thing every turn.
+
+use std::collections::HashSet;
+
+use tracing::debug;
+
+use super::{
+  event::HookEvent,
+  handler::HookContext,
+  session_context::{item_label, item_text},
+  usage::{Injected, above_floor},
+};
+use crate::{
+  db::{Injection, InjectionRule},
+  domain::{
+    config::{CHARS_PER_TOKEN, InjectionConfig},
+    memory::Memory,
+  },
+  embedding::EmbeddingMode,
+  service::{memory::search::search_by_embedding, util::ServiceError},
+};
+
+/// Prompts shorter than this carry too little to match on
+const MIN_PROMPT_CHARS: usize = 20;
+
+/// Candidates fetched from the vector search before filtering
+const CANDIDATES: usize = 20;
+
+/// Candidate filter
+const CANDIDATE_FILTER: &str = "superseded_by IS NULL";
+
+/// Build the context for `prompt` in a session, or `None` when no memory
+/// injected with prompts is about it.
+pub(super) async fn build(
+  ctx: &HookContext<'_>,
+  session_id: &str,
+  prompt: &str,
+) -> Result<Option<Injected>, ServiceError> {
+  let Some(injection) = ctx.injection.filter(|injection| injection.prompt_enabled()) else {
+    return Ok(None);
+  };
+  let prompt = prompt.trim();
+  if prompt.len() < MIN_PROMPT_CHARS {
+    return Ok(None);
+  }
+
+  let vector = ctx.embedding.embed(prompt, EmbeddingMode::Query).await?;
+  let candidates = search_by_embedding(ctx.db, &vector, CANDIDATES, Some(CANDIDATE_FILTER)).await?;
+  let injected: HashSet<String> = ctx
+    .db
+    .get_session_injections(session_id)
+    .await?
+    .into_iter()
+    .map(|injection| injection.memory_id)
+    .collect();
+
+  let matches = select(candidates, injection, &injected);
+  debug!(
+    prompt_chars = prompt.len(),
+    matches = matches.len(),
+    "Built prompt context"
+  );
+
+  Ok(render(&matches, injection.prompt_max_tokens).map(|(text, listed)| {
+    Injected {
+      text,
+      injections: matches
+        .iter()
+        .take(listed)
+        .map(|(memory, similarity)| Injection {
+          memory_id: memory.id.to_string(),
+          rule: InjectionRule::Topical,
+          score: Some(*similarity),
+          detail: None,
+        })
+        .collect(),
+    }
+  }))
+}
+
+/// Candidates (nearest first, with their distance) that are injected with
+/// prompts, not yet in the session and above the floor, with their
+/// similarity
+fn select(
+  mut candidates: Vec<(Memory, f32)>,
+  injection: &InjectionConfig,
+  injected: &HashSet<String>,
+) -> Vec<(Memory, f32)> {
+  candidates
+    .retain(|(memory, _)| injection.for_memory(memory).on_prompt() && !injected.contains(&memory.id.to_string()));
+  above_floor(
+    candidates,
+    injection,
+    injection.prompt_min_similarity,
+    HookEvent::UserPromptSubmit,
+  )
+  .into_iter()
+  .map(|(memory, distance)| (memory, 1.0 - distance.min(1.0)))
+  .take(injection.prompt_limit)
+  .collect()
+}
+
+/// Render memories as Markdown, dropping them once `max_tokens` is reached.
+///
+/// Returns the text and how many memories it lists.
+fn render(matches: &[(Memory, f32)], max_tokens: usize) -> Option<(String, usize)> {
+  const HEADING: &str = "## Project memories about this prompt (ccengram)\n\n";
+  let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+
+  let mut out = HEADING.to_string();
+  let mut listed = 0;
+  for (memory, _) in matches {
+    let line = format!("- [{}] {} (id: {})\n", item_label(memory), item_text(memory), memory.id);
+    if out.len() + line.len() > budget {
+      break;
+    }
+    out.push_str(&line);
+    listed += 1;
+  }
+  (listed > 0).then_some((out, listed))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::{
+    config::InjectionPolicy,
+    memory::{MemoryType, Sector},
+  };
+
+  fn memory(content: &str, sector: Sector, memory_type: Option<MemoryType>) -> Memory {
+    let mut memory = Memory::new(Uuid::nil(), content.to_string(), sector);
+    memory.memory_type = memory_type;
+    memory
+  }
+
+  #[test]
+  fn test_select_applies_policy_similarity_and_session() {
+    let injection = InjectionConfig {
+      sectors: BTreeMap::from([("episodic".to_string(), InjectionPolicy::Prompt)]),
+      types: BTreeMap::from([("gotcha".to_string(), InjectionPolicy::Always)]),
+      prompt_limit: 2,
+      ..InjectionConfig::default()
+    };
+    let seen = memory("Debugged the flaky login test last week", Sector::Episodic, None);
+    let injected = HashSet::from([seen.id.to_string()]);
+    let mut faded = memory("Login lived in the web crate until March", Sector::Episodic, None);
+    faded.salience = 0.05;
+    let candidates = vec![
+      (seen, 0.05),
+      (
+        memory("Use pnpm, not npm", Sector::Semantic, Some(MemoryType::Preference)),
+        0.1,
+      ),
+      (
+        memory(
+          "Login tests need the mock OAuth server",
+          Sector::Semantic,
+          Some(MemoryType::Gotcha),
+        ),
+        0.15,
+      ),
+      (faded, 0.18),
+      (memory("Paired on the login redirect bug", Sector::Episodic, None), 0.2),
+      (memory("Fixed session cookie expiry", Sector::Episodic, None), 0.22),
+      (memory("Reworked the signup form", Sector::Episodic, None), 0.5),
+    ];
+
+    let selected = select(candidates, &injection, &injected);
+    let contents: Vec<&str> = selected.iter().map(|(m, _)| m.content.as_str()).collect();
+    assert_eq!(
+      contents,
+      vec![
+        "Login tests need the mock OAuth server",
+        "Paired on the login redirect bug"
+      ],
+      "session start memories, already injected ones, faded ones and the limit are left out"
+    );
+    assert!((selected[0].1 - 0.85).abs() < 1e-6, "scored by similarity");
+  }
+
+  #[test]
+  fn test_render_respects_budget() {
+    let matches: Vec<(Memory, f32)> = (0..10)
+      .map(|i| {
+        (
+          memory(&format!("Note {} {}", i, "x".repeat(200)), Sector::Episodic, None),
+          0.9,
+        )
+      })
+      .collect();
+
+    let (rendered, listed) = render(&matches, 150).expect("something fits");
+    assert!(rendered.len() <= 150 * CHARS_PER_TOKEN, "context fits the budget");
+    assert!(rendered.starts_with("## Project memories about this prompt (ccengram)"));
+    assert_eq!(rendered.matches("- [episodic]").count(), listed);
+    assert!(listed < matches.len(), "later memories are dropped");
+    assert!(render(&[], 150).is_none(), "nothing to inject without matches");
+  }
+}
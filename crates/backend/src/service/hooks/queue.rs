@@ -0,0 +1,314 @@
+//! Persistent queue of failed extractions.
+//!
+//! When LLM extraction of a segment fails, the segment is stored in the
+//! `extraction_jobs` table instead of being dropped. The scheduler retries
+//! due jobs with exponential backoff; once a job runs out of attempts it is
+//! dead-lettered and only retried through `ccengram extract retry`.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::{
+  extraction::{self, ExtractionContext},
+  handler::HookContext,
+  usage,
+};
+use crate::{
+  db::{ExtractionJob, ExtractionJobStatus, ProjectDb, UsageType},
+  domain::config::HooksConfig,
+  service::util::{ServiceError, normalize_id},
+};
+
+/// Longest wait between two attempts of a job
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
+
+/// Hook event recorded on session links of memories created by a retry
+const RETRY_SOURCE: &str = "ExtractionRetry";
+
+/// How failed extractions are retried
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+  /// Retries after the original failure before a job is dead-lettered
+  pub max_retries: u32,
+  /// Delay before the first retry; doubles with each further attempt
+  pub backoff: Duration,
+}
+
+impl RetryPolicy {
+  pub fn from_config(config: &HooksConfig) -> Self {
+    Self {
+      max_retries: config.extraction_retry_attempts,
+      backoff: Duration::seconds(config.extraction_retry_backoff_secs.min(MAX_BACKOFF_SECS as u64) as i64),
+    }
+  }
+
+  /// Delay after the given number of failed attempts
+  fn delay(&self, attempts: u32) -> Duration {
+    let factor = 1i64 << attempts.saturating_sub(1).min(20);
+    let secs = self.backoff.num_seconds().saturating_mul(factor);
+    Duration::seconds(secs.min(MAX_BACKOFF_SECS))
+  }
+
+  /// Record a failed attempt, scheduling the next one or dead-lettering the job
+  fn record_failure(&self, job: &mut ExtractionJob, error: String, now: DateTime<Utc>) {
+    job.attempts += 1;
+    job.last_error = Some(error);
+    if job.attempts > self.max_retries {
+      job.status = ExtractionJobStatus::Dead;
+      job.next_attempt_at = now;
+    } else {
+      job.next_attempt_at = now + self.delay(job.attempts);
+    }
+  }
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self::from_config(&HooksConfig::default())
+  }
+}
+
+/// Outcome of a pass over queued extractions
+#[derive(Debug, Default)]
+pub struct RetryReport {
+  /// Jobs attempted
+  pub attempted: usize,
+  /// Jobs extracted and removed from the queue
+  pub succeeded: usize,
+  /// Jobs that failed again and were dead-lettered
+  pub dead: usize,
+  /// Memories created from the extracted segments
+  pub memories_created: Vec<String>,
+}
+
+/// Queue a segment whose extraction failed
+pub async fn enqueue(
+  ctx: &ExtractionContext<'_>,
+  segment: llm::ExtractionContext,
+  error: &llm::LlmError,
+) -> Result<ExtractionJob, ServiceError> {
+  let now = Utc::now();
+  let mut job = ExtractionJob {
+    id: Uuid::now_v7(),
+    session_id: ctx.session_id.map(String::from),
+    segment,
+    status: ExtractionJobStatus::Pending,
+    attempts: 0,
+    last_error: None,
+    created_at: now,
+    next_attempt_at: now,
+  };
+  ctx.retry.record_failure(&mut job, error.to_string(), now);
+  ctx.db.save_extraction_job(&job).await?;
+
+  warn!(
+    job_id = %job.id,
+    status = job.status.as_str(),
+    next_attempt_at = %job.next_attempt_at,
+    "Extraction failed, queued segment for retry: {}",
+    error
+  );
+  Ok(job)
+}
+
+/// Retry every pending job whose backoff has elapsed
+pub async fn retry_due_extractions(
+  ctx: &HookContext<'_>,
+  seen_hashes: &mut HashSet<String>,
+) -> Result<RetryReport, ServiceError> {
+  let jobs = ctx.db.due_extraction_jobs(Utc::now()).await?;
+  if jobs.is_empty() || ctx.llm.is_none() {
+    return Ok(RetryReport::default());
+  }
+  debug!(jobs = jobs.len(), "Retrying queued extractions");
+  run(ctx, jobs, seen_hashes).await
+}
+
+/// Retry the given jobs (by ID or prefix) now, or every queued job when `ids` is empty.
+///
+/// Dead-lettered jobs are revived with a fresh set of attempts.
+pub async fn retry_extractions(
+  ctx: &HookContext<'_>,
+  ids: &[String],
+  seen_hashes: &mut HashSet<String>,
+) -> Result<RetryReport, ServiceError> {
+  if ctx.llm.is_none() {
+    return Err(ServiceError::Validation(
+      "No LLM provider is available to retry extractions".to_string(),
+    ));
+  }
+
+  let queued = ctx.db.list_extraction_jobs(None).await?;
+  let mut jobs = if ids.is_empty() { queued } else { select(queued, ids)? };
+
+  for job in &mut jobs {
+    if job.status == ExtractionJobStatus::Dead {
+      job.status = ExtractionJobStatus::Pending;
+      job.attempts = 0;
+    }
+  }
+  run(ctx, jobs, seen_hashes).await
+}
+
+/// Queued jobs with the given status, or all of them, oldest first
+pub async fn list_extraction_jobs(
+  db: &ProjectDb,
+  status: Option<ExtractionJobStatus>,
+) -> Result<Vec<ExtractionJob>, ServiceError> {
+  Ok(db.list_extraction_jobs(status).await?)
+}
+
+async fn run(
+  ctx: &HookContext<'_>,
+  jobs: Vec<ExtractionJob>,
+  seen_hashes: &mut HashSet<String>,
+) -> Result<RetryReport, ServiceError> {
+  let ext_ctx = ctx.extraction_context();
+  let mut report = RetryReport::default();
+
+  for mut job in jobs {
+    report.attempted += 1;
+    match extraction::extract_context(&ext_ctx, &job.segment, seen_hashes).await {
+      Ok(ids) => {
+        ctx.db.delete_extraction_job(&job.id).await?;
+        if let Some(session_id) = &job.session_id {
+          usage::link(ctx, session_id, &ids, UsageType::Created, RETRY_SOURCE).await;
+        }
+        debug!(job_id = %job.id, memories = ids.len(), "Queued extraction succeeded");
+        report.succeeded += 1;
+        report.memories_created.extend(ids);
+      }
+      Err(e) => {
+        ext_ctx.retry.record_failure(&mut job, e.to_string(), Utc::now());
+        if job.status == ExtractionJobStatus::Dead {
+          warn!(job_id = %job.id, attempts = job.attempts, "Extraction dead-lettered: {}", e);
+          report.dead += 1;
+        }
+        ctx.db.save_extraction_job(&job).await?;
+      }
+    }
+  }
+
+  if report.succeeded > 0 {
+    info!(
+      succeeded = report.succeeded,
+      memories = report.memories_created.len(),
+      "Recovered queued extractions"
+    );
+  }
+  Ok(report)
+}
+
+/// Pick jobs by full ID or unique prefix
+fn select(jobs: Vec<ExtractionJob>, ids: &[String]) -> Result<Vec<ExtractionJob>, ServiceError> {
+  let mut selected: Vec<ExtractionJob> = Vec::new();
+  for id in ids {
+    let prefix = normalize_id(id);
+    let matches: Vec<&ExtractionJob> = jobs.iter().filter(|j| j.id.to_string().starts_with(&prefix)).collect();
+    match matches.as_slice() {
+      [] => {
+        return Err(ServiceError::NotFound {
+          item_type: "Extraction job",
+          id: id.clone(),
+        });
+      }
+      [job] => {
+        if !selected.iter().any(|s| s.id == job.id) {
+          selected.push((*job).clone());
+        }
+      }
+      _ => {
+        return Err(ServiceError::Ambiguous {
+          prefix,
+          count: matches.len(),
+          candidates: matches.iter().map(|j| j.id.to_string()).collect(),
+        });
+      }
+    }
+  }
+  Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn job() -> ExtractionJob {
+    let now = Utc::now();
+    ExtractionJob {
+      id: Uuid::now_v7(),
+      session_id: None,
+      segment: llm::ExtractionContext::default(),
+      status: ExtractionJobStatus::Pending,
+      attempts: 0,
+      last_error: None,
+      created_at: now,
+      next_attempt_at: now,
+    }
+  }
+
+  #[test]
+  fn test_backoff_doubles_then_dead_letters() {
+    let policy = RetryPolicy {
+      max_retries: 3,
+      backoff: Duration::seconds(60),
+    };
+    let now = Utc::now();
+    let mut job = job();
+
+    let mut delays = Vec::new();
+    for _ in 0..3 {
+      policy.record_failure(&mut job, "timeout".to_string(), now);
+      assert_eq!(
+        job.status,
+        ExtractionJobStatus::Pending,
+        "job stays pending while retries remain"
+      );
+      delays.push((job.next_attempt_at - now).num_seconds());
+    }
+    assert_eq!(delays, vec![60, 120, 240], "delay doubles with each failure");
+
+    policy.record_failure(&mut job, "timeout".to_string(), now);
+    assert_eq!(
+      job.status,
+      ExtractionJobStatus::Dead,
+      "job is dead-lettered when out of retries"
+    );
+    assert_eq!(job.attempts, 4, "original attempt plus three retries");
+    assert_eq!(job.last_error.as_deref(), Some("timeout"), "last error is kept");
+  }
+
+  #[test]
+  fn test_backoff_is_capped() {
+    let policy = RetryPolicy {
+      max_retries: 100,
+      backoff: Duration::seconds(60),
+    };
+    assert_eq!(
+      policy.delay(40).num_seconds(),
+      MAX_BACKOFF_SECS,
+      "delay never exceeds the cap"
+    );
+  }
+
+  #[test]
+  fn test_select_by_prefix() {
+    let mut a = job();
+    a.id = Uuid::parse_str("11111111-0000-7000-8000-000000000000").unwrap();
+    let mut b = job();
+    b.id = Uuid::parse_str("22222222-0000-7000-8000-000000000000").unwrap();
+    let jobs = vec![a, b.clone()];
+
+    let picked = select(jobs.clone(), &["22222222".to_string()]).unwrap();
+    assert_eq!(picked.len(), 1, "unique prefix selects one job");
+    assert_eq!(picked[0].id, b.id, "prefix selects the matching job");
+
+    assert!(
+      select(jobs, &["33333333".to_string()]).is_err(),
+      "unknown ID is an error"
+    );
+  }
+}
@@ -0,0 +1,446 @@
+//! Memories injected when a session starts.
+//!
+//! A SessionStart hook can hand Claude Code `additionalContext` for the new
+//! session. This picks the memories most relevant to where the session
+//! starts: the working directory within the project and recent git activity,
+//! meaning the latest commit subjects, the files they touched and uncommitted
+//! changes. Candidates from a vector search are ranked like search results,
+//! memories about the working directory or a recently touched file are
+//! boosted, and the best are rendered up to the token budget, either as a
+//! plain list or through the configured `session_start_template`. Open and
+//! blocked goals are listed ahead of them, so the session is reminded of what
+//! is still being worked toward. Memories whose `[injection]` policy leaves
+//! out session start are skipped, as are those below the similarity or
+//! salience floor, so a session starting somewhere new isn't padded with
+//! unrelated memories.
+
+use std::{collections::BTreeMap, path::Path};
+
+use handlebars::{Handlebars, RenderError};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use super::{
+  event::HookEvent,
+  handler::HookContext,
+  usage::{Injected, above_floor},
+};
+use crate::{
+  db::{Injection, InjectionRule},
+  domain::{
+    config::{CHARS_PER_TOKEN, InjectionConfig},
+    git,
+    memory::Memory,
+  },
+  embedding::EmbeddingMode,
+  service::{
+    memory::{goals, rank_memories, search::search_by_embedding},
+    util::ServiceError,
+  },
+};
+
+/// Commits whose subjects and files describe recent activity
+const RECENT_COMMITS: usize = 10;
+
+/// Most recently touched files named in the query
+const QUERY_FILES: usize = 15;
+
+/// Candidates fetched from the vector search before ranking
+const CANDIDATES: usize = 50;
+
+/// Candidate filter; goals are listed on their own
+const CANDIDATE_FILTER: &str = "superseded_by IS NULL AND (memory_type IS NULL OR memory_type != 'goal')";
+
+/// Rank multiplier for memories about the working directory or a recently touched file
+const SCOPE_BOOST: f32 = 1.2;
+
+/// Longest a single memory is shown
+const MAX_ITEM_CHARS: usize = 300;
+
+/// Build the context for a session starting in `cwd`, or `None` when there
+/// is nothing to inject.
+pub(super) async fn build(ctx: &HookContext<'_>, cwd: Option<&Path>) -> Result<Option<Injected>, ServiceError> {
+  let Some(root) = ctx.root else {
+    return Ok(None);
+  };
+  let limit = ctx.config.session_start_context_limit;
+  if limit == 0 {
+    return Ok(None);
+  }
+
+  let directory = cwd
+    .and_then(|cwd| cwd.strip_prefix(root).ok())
+    .map(|dir| dir.to_string_lossy().to_string())
+    .filter(|dir| !dir.is_empty());
+  let activity = git::recent_activity(root, RECENT_COMMITS).await.unwrap_or_else(|e| {
+    debug!(err = %e, "No git activity for session context");
+    git::RecentActivity::default()
+  });
+  let files: Vec<&str> = activity.files.iter().take(QUERY_FILES).map(String::as_str).collect();
+
+  let query = session_query(root, directory.as_deref(), &activity.subjects, &files);
+  let vector = ctx.embedding.embed(&query, EmbeddingMode::Query).await?;
+  let mut candidates = search_by_embedding(ctx.db, &vector, CANDIDATES, Some(CANDIDATE_FILTER)).await?;
+  candidates.retain(|(memory, _)| ctx.injection_policy(memory).at_session_start());
+  let defaults = InjectionConfig::default();
+  let injection = ctx.injection.unwrap_or(&defaults);
+  let candidates = above_floor(candidates, injection, injection.min_similarity, HookEvent::SessionStart);
+  let mut goals = goals::active(ctx.db, ctx.config.session_start_goal_limit).await?;
+  goals.retain(|goal| ctx.injection_policy(goal).at_session_start());
+
+  let mut ranked: Vec<(Memory, Injection)> = rank_memories(candidates, CANDIDATES, None)
+    .into_iter()
+    .map(|(memory, _, score)| {
+      let (rule, boost) = if is_about(&memory, directory.as_deref(), &files) {
+        (InjectionRule::Scope, SCOPE_BOOST)
+      } else {
+        (InjectionRule::Relevance, 1.0)
+      };
+      let injection = Injection {
+        memory_id: memory.id.to_string(),
+        rule,
+        score: Some(score * boost),
+        detail: None,
+      };
+      (memory, injection)
+    })
+    .collect();
+  ranked.sort_by(|a, b| b.1.score.partial_cmp(&a.1.score).unwrap_or(std::cmp::Ordering::Equal));
+  ranked.truncate(limit);
+
+  let (memories, ranked_injections): (Vec<Memory>, Vec<Injection>) = ranked.into_iter().unzip();
+  debug!(
+    query_chars = query.len(),
+    goals = goals.len(),
+    memories = memories.len(),
+    "Built session start context"
+  );
+
+  // Goals come first in the output, so their injections do too
+  let mut injections: Vec<Injection> = goals
+    .iter()
+    .map(|goal| Injection {
+      memory_id: goal.id.to_string(),
+      rule: InjectionRule::Goal,
+      score: Some(goal.salience),
+      detail: goal.goal_status.map(|s| s.as_str().to_string()),
+    })
+    .collect();
+  injections.extend(ranked_injections);
+
+  let max_tokens = ctx.config.session_start_context_max_tokens;
+  let rendered = match ctx.config.session_start_template.as_deref() {
+    Some(template) => render_template(template, &goals, &memories, max_tokens).unwrap_or_else(|e| {
+      warn!("Session start template failed, using the default format: {}", e);
+      render(&goals, &memories, max_tokens)
+    }),
+    None => render(&goals, &memories, max_tokens),
+  };
+
+  Ok(rendered.map(|(text, listed)| {
+    injections.truncate(listed);
+    Injected { text, injections }
+  }))
+}
+
+/// Query describing where the session starts
+fn session_query(root: &Path, directory: Option<&str>, subjects: &[String], files: &[&str]) -> String {
+  let mut parts = Vec::new();
+  if let Some(name) = root.file_name() {
+    parts.push(name.to_string_lossy().to_string());
+  }
+  if let Some(directory) = directory {
+    parts.push(directory.to_string());
+  }
+  parts.extend(subjects.iter().cloned());
+  parts.extend(files.iter().map(|f| f.to_string()));
+  parts.join("\n")
+}
+
+/// Whether a memory is scoped to `directory` or names one of `files`
+fn is_about(memory: &Memory, directory: Option<&str>, files: &[&str]) -> bool {
+  let in_directory = match (directory, memory.scope_path.as_deref()) {
+    (Some(directory), Some(scope)) => directory.starts_with(scope) || scope.starts_with(directory),
+    _ => false,
+  };
+  in_directory || memory.files.iter().any(|f| files.contains(&f.as_str()))
+}
+
+/// Summary or content on one line, shortened to `MAX_ITEM_CHARS`
+pub(super) fn item_text(memory: &Memory) -> String {
+  let text = memory.summary.as_deref().unwrap_or(&memory.content);
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  match text.char_indices().nth(MAX_ITEM_CHARS) {
+    Some((end, _)) => format!("{}...", &text[..end]),
+    None => text,
+  }
+}
+
+/// Memory type, or sector for untyped memories
+pub(super) fn item_label(memory: &Memory) -> &'static str {
+  memory.memory_type.map(|t| t.as_str()).unwrap_or(memory.sector.as_str())
+}
+
+/// Goal status, with the due hint when there is one
+fn goal_line(goal: &Memory) -> String {
+  let due = goal
+    .due
+    .as_ref()
+    .map(|due| format!(" (due: {})", due))
+    .unwrap_or_default();
+  format!(
+    "- [{}] {}{} (id: {})\n",
+    goal.goal_status.unwrap_or_default().as_str(),
+    item_text(goal),
+    due,
+    goal.id
+  )
+}
+
+/// Render goals, then memories, as Markdown, dropping them once
+/// `max_tokens` is reached.
+///
+/// Returns the text and how many goals and memories it lists.
+fn render(goals: &[Memory], memories: &[Memory], max_tokens: usize) -> Option<(String, usize)> {
+  let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+  let sections = [
+    (
+      "## Open goals (ccengram)\n\n",
+      goals.iter().map(goal_line).collect::<Vec<_>>(),
+    ),
+    (
+      "## Relevant project memories (ccengram)\n\n",
+      memories
+        .iter()
+        .map(|memory| format!("- [{}] {} (id: {})\n", item_label(memory), item_text(memory), memory.id))
+        .collect(),
+    ),
+  ];
+
+  let mut out = String::new();
+  let mut listed = 0;
+  'sections: for (heading, lines) in sections {
+    for (i, line) in lines.iter().enumerate() {
+      let heading = match (i, out.is_empty()) {
+        (0, true) => heading.to_string(),
+        (0, false) => format!("\n{}", heading),
+        _ => String::new(),
+      };
+      if out.len() + heading.len() + line.len() > budget {
+        break 'sections;
+      }
+      out.push_str(&heading);
+      out.push_str(line);
+      listed += 1;
+    }
+  }
+  (listed > 0).then_some((out, listed))
+}
+
+/// A memory as seen by the session start template
+#[derive(Serialize)]
+struct TemplateMemory<'a> {
+  id: String,
+  #[serde(rename = "type")]
+  kind: &'static str,
+  sector: &'static str,
+  text: String,
+  content: &'a str,
+  summary: Option<&'a str>,
+  tags: &'a [String],
+  files: &'a [String],
+  salience: f32,
+}
+
+/// Memories of one type, in rank order
+#[derive(Serialize)]
+struct TemplateGroup<'a> {
+  #[serde(rename = "type")]
+  kind: &'static str,
+  memories: Vec<&'a TemplateMemory<'a>>,
+}
+
+/// An open or blocked goal as seen by the session start template
+#[derive(Serialize)]
+struct TemplateGoal<'a> {
+  id: String,
+  text: String,
+  status: &'static str,
+  due: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct TemplateData<'a> {
+  goals: &'a [TemplateGoal<'a>],
+  memories: &'a [TemplateMemory<'a>],
+  groups: &'a [TemplateGroup<'a>],
+  by_type: BTreeMap<&'static str, &'a [&'a TemplateMemory<'a>]>,
+}
+
+/// Render goals and memories through a Handlebars `template`, dropping the
+/// least relevant memories until the output fits `max_tokens`
+fn render_template(
+  template: &str,
+  goals: &[Memory],
+  memories: &[Memory],
+  max_tokens: usize,
+) -> Result<Option<(String, usize)>, RenderError> {
+  let mut handlebars = Handlebars::new();
+  handlebars.register_escape_fn(handlebars::no_escape);
+  let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+
+  let goals: Vec<TemplateGoal> = goals
+    .iter()
+    .map(|goal| TemplateGoal {
+      id: goal.id.to_string(),
+      text: item_text(goal),
+      status: goal.goal_status.unwrap_or_default().as_str(),
+      due: goal.due.as_deref(),
+    })
+    .collect();
+
+  let items: Vec<TemplateMemory> = memories
+    .iter()
+    .map(|memory| TemplateMemory {
+      id: memory.id.to_string(),
+      kind: item_label(memory),
+      sector: memory.sector.as_str(),
+      text: item_text(memory),
+      content: &memory.content,
+      summary: memory.summary.as_deref(),
+      tags: &memory.tags,
+      files: &memory.files,
+      salience: memory.salience,
+    })
+    .collect();
+
+  // Goals are never dropped, so they can render without any memory
+  let fewest = usize::from(goals.is_empty());
+  for count in (fewest..=items.len()).rev() {
+    let shown = &items[..count];
+    let mut groups: Vec<TemplateGroup> = Vec::new();
+    for item in shown {
+      match groups.iter_mut().find(|g| g.kind == item.kind) {
+        Some(group) => group.memories.push(item),
+        None => groups.push(TemplateGroup {
+          kind: item.kind,
+          memories: vec![item],
+        }),
+      }
+    }
+    let data = TemplateData {
+      goals: &goals,
+      memories: shown,
+      groups: &groups,
+      by_type: groups.iter().map(|g| (g.kind, g.memories.as_slice())).collect(),
+    };
+
+    let rendered = handlebars.render_template(template, &data)?;
+    if rendered.len() <= budget {
+      return Ok((!rendered.trim().is_empty()).then_some((rendered, goals.len() + count)));
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::{GoalStatus, MemoryType, Sector};
+
+  fn memory(content: &str) -> Memory {
+    Memory::new(Uuid::nil(), content.to_string(), Sector::Semantic)
+  }
+
+  #[test]
+  fn test_is_about_directory_and_files() {
+    let mut scoped = memory("Handlers return typed errors");
+    scoped.scope_path = Some("src/api".to_string());
+    let mut named = memory("Client retries twice");
+    named.files = vec!["src/http/client.rs".to_string()];
+    let unrelated = memory("Use pnpm");
+
+    let files = ["src/http/client.rs"];
+    assert!(is_about(&scoped, Some("src/api/handlers"), &files));
+    assert!(is_about(&named, None, &files));
+    assert!(!is_about(&unrelated, Some("src/api"), &files));
+  }
+
+  #[test]
+  fn test_render_respects_budget() {
+    let mut decision = memory("Keep API errors in RFC 7807 format");
+    decision.memory_type = Some(MemoryType::Decision);
+    let memories: Vec<Memory> = std::iter::once(decision)
+      .chain((0..20).map(|i| memory(&format!("Note {} {}", i, "x".repeat(200)))))
+      .collect();
+
+    let (rendered, listed) = render(&[], &memories, 200).expect("something fits");
+    assert!(rendered.len() <= 200 * CHARS_PER_TOKEN, "context fits the budget");
+    assert!(rendered.contains("- [decision] Keep API errors in RFC 7807 format"));
+    assert_eq!(rendered.matches("- [").count(), listed);
+    assert!(listed < memories.len(), "later memories are dropped");
+    assert!(render(&[], &[], 200).is_none(), "nothing to inject without memories");
+  }
+
+  #[test]
+  fn test_render_template_groups_by_type() {
+    let mut gotcha = memory("Migrations run before the seed step");
+    gotcha.memory_type = Some(MemoryType::Gotcha);
+    let mut decision = memory("Use <sqlx> for queries");
+    decision.memory_type = Some(MemoryType::Decision);
+    let mut second_gotcha = memory("CI caches node_modules");
+    second_gotcha.memory_type = Some(MemoryType::Gotcha);
+    let memories = vec![gotcha, decision, second_gotcha];
+
+    let template = "{{#each groups}}{{type}}:{{#each memories}} {{text}};{{/each}}\n{{/each}}";
+    let (rendered, _) = render_template(template, &[], &memories, 1000).unwrap().unwrap();
+    assert_eq!(
+      rendered,
+      "gotcha: Migrations run before the seed step; CI caches node_modules;\ndecision: Use <sqlx> for queries;\n"
+    );
+
+    let template = "{{#each by_type.decision}}{{text}}{{/each}}";
+    assert_eq!(
+      render_template(template, &[], &memories, 1000).unwrap(),
+      Some(("Use <sqlx> for queries".to_string(), 3))
+    );
+
+    let rendered = render_template("{{#each memories}}{{content}}\n{{/each}}", &[], &memories, 10).unwrap();
+    assert_eq!(
+      rendered,
+      Some(("Migrations run before the seed step\n".to_string(), 1)),
+      "later memories are dropped to fit"
+    );
+
+    assert!(render_template("{{#each memories}}", &[], &memories, 1000).is_err());
+  }
+
+  #[test]
+  fn test_goals_listed_before_memories() {
+    let mut goal = memory("Replace the hand-rolled retry loop with backoff");
+    goal.memory_type = Some(MemoryType::Goal);
+    goal.goal_status = Some(GoalStatus::Blocked);
+    goal.due = Some("before the 2.0 release".to_string());
+    let goals = vec![goal];
+    let memories = vec![memory("Client retries twice")];
+
+    let (rendered, listed) = render(&goals, &memories, 1000).unwrap();
+    assert_eq!(listed, 2);
+    assert!(rendered.starts_with(
+      "## Open goals (ccengram)\n\n- [blocked] Replace the hand-rolled retry loop with backoff (due: before the 2.0 release)"
+    ));
+    assert!(rendered.contains("\n\n## Relevant project memories (ccengram)\n\n- [semantic] Client retries twice"));
+
+    let template = "{{#each goals}}{{status}}: {{text}} ({{due}})\n{{/each}}{{#each memories}}{{content}}\n{{/each}}";
+    assert_eq!(
+      render_template(template, &goals, &memories, 25).unwrap(),
+      Some((
+        "blocked: Replace the hand-rolled retry loop with backoff (before the 2.0 release)\n".to_string(),
+        1
+      )),
+      "memories are dropped before goals"
+    );
+  }
+}
@@ -0,0 +1,161 @@
+//! Manual extraction over user-provided text.
+//!
+//! Meeting notes, PR descriptions and similar documents go through the same
+//! stages as hook extraction: capture rules store the lines they match, the
+//! signal classifier screens each part of the text, the LLM extracts
+//! candidates, candidates are merged and checked against existing memories,
+//! and the rest are stored.
+
+use std::collections::HashSet;
+
+use llm::{ExtractedMemory, LlmProvider};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use super::{
+  capture::CaptureKind,
+  extraction::{ExtractionContext, extract_with_rules, merge_extracted, store_extracted_memory},
+  handler::HookContext,
+};
+use crate::{
+  context::memory::extract::dedup::compute_hashes,
+  ipc::types::memory::MemoryExtractResult,
+  service::{
+    memory::{MemoryContext, check_duplicate},
+    util::ServiceError,
+  },
+};
+
+/// Shortest text worth sending to the LLM
+const MIN_TEXT_LEN: usize = 20;
+
+/// Extract memories from free-form text.
+///
+/// `source` names where the text came from (a file name, a PR) and is passed
+/// to the LLM. Without an LLM only capture rules run. Fails when there is
+/// neither, or every part fails to extract.
+pub async fn extract_text(
+  ctx: &HookContext<'_>,
+  text: &str,
+  source: Option<&str>,
+) -> Result<MemoryExtractResult, ServiceError> {
+  let has_rules = ctx.rules.is_some_and(|rules| !rules.is_empty());
+  if ctx.llm.is_none() && !has_rules {
+    return Err(ServiceError::validation(
+      "Extraction requires an LLM provider or hooks.extraction_rules",
+    ));
+  }
+  if text.trim().len() < MIN_TEXT_LEN {
+    return Err(ServiceError::validation(format!(
+      "Text too short to extract from (min {} chars)",
+      MIN_TEXT_LEN
+    )));
+  }
+
+  let ext_ctx = ctx.extraction_context();
+  let captured = extract_with_rules(&ext_ctx, text, &mut HashSet::new()).await?;
+  let Some(llm) = ctx.llm else {
+    info!(source = ?source, memories = captured.len(), "Text extraction complete (rules only)");
+    return Ok(MemoryExtractResult {
+      memories_created: captured,
+      ..Default::default()
+    });
+  };
+
+  let segment_id = Uuid::now_v7();
+  let recorder = ctx.capture.map(|store| store.recorder(llm));
+  let provider = recorder.as_ref().map(|r| r.provider()).unwrap_or(llm);
+
+  let result = extract_parts(&ext_ctx, provider, text, source, segment_id).await;
+
+  if let (Some(store), Some(recorder)) = (ctx.capture, recorder) {
+    let memories_created = result
+      .as_ref()
+      .map(|r| r.memories_created.as_slice())
+      .unwrap_or_default();
+    store
+      .save(segment_id, CaptureKind::Extraction, recorder, memories_created)
+      .await;
+  }
+
+  result.map(|mut result| {
+    result.memories_created.splice(0..0, captured);
+    result
+  })
+}
+
+async fn extract_parts(
+  ctx: &ExtractionContext<'_>,
+  llm: &dyn LlmProvider,
+  text: &str,
+  source: Option<&str>,
+  segment_id: Uuid,
+) -> Result<MemoryExtractResult, ServiceError> {
+  let parts = llm::split_text_by_token_budget(text, ctx.max_tokens);
+  let mut result = MemoryExtractResult {
+    parts: parts.len(),
+    segment_id: segment_id.to_string(),
+    ..Default::default()
+  };
+
+  let mut candidates: Vec<ExtractedMemory> = Vec::new();
+  let mut failed = 0;
+  let mut last_error = None;
+  for part in &parts {
+    match llm::extraction::classify_signal(llm, part).await {
+      Ok(classification) if !classification.is_extractable => {
+        debug!(category = ?classification.category, "Nothing to extract in text part");
+        result.skipped += 1;
+        continue;
+      }
+      Ok(_) => {}
+      // The classifier only screens; extraction decides on its own when it fails
+      Err(e) => debug!("Classification failed, extracting anyway: {}", e),
+    }
+
+    match llm::extraction::extract_from_text(llm, part, source).await {
+      Ok(extracted) => candidates.extend(extracted.memories),
+      Err(e) => {
+        failed += 1;
+        last_error = Some(e);
+      }
+    }
+  }
+
+  if let Some(e) = last_error
+    && failed == parts.len() - result.skipped
+  {
+    return Err(e.into());
+  }
+
+  let memory_ctx = MemoryContext::new(ctx.db, ctx.embedding, ctx.project_id);
+  let mut seen_hashes = HashSet::new();
+  for extracted in &merge_extracted(candidates) {
+    let content = ctx.redact(&extracted.content);
+    let (content_hash, simhash) = compute_hashes(&content);
+    if let Some(duplicate) = check_duplicate(&memory_ctx, &content, &content_hash, simhash).await? {
+      debug!(existing = %duplicate.id, reason = duplicate.reason, "Extracted memory already stored");
+      if !result.duplicates.contains(&duplicate.id) {
+        result.duplicates.push(duplicate.id);
+      }
+      continue;
+    }
+    if let Some(id) = store_extracted_memory(ctx, extracted, segment_id, &mut seen_hashes)
+      .await?
+      .memory_id
+    {
+      result.memories_created.push(id);
+    }
+  }
+
+  info!(
+    source = ?source,
+    parts = result.parts,
+    skipped = result.skipped,
+    failed = failed,
+    memories = result.memories_created.len(),
+    duplicates = result.duplicates.len(),
+    "Text extraction complete"
+  );
+  Ok(result)
+}
@@ -0,0 +1,142 @@
+//! Warnings before Claude edits a file.
+//!
+//! A PreToolUse hook can hand Claude Code `additionalContext` before a tool
+//! runs. When the tool edits or writes a file, this lists the memories of the
+//! configured types (gotchas and patterns by default) that name the file or
+//! are scoped to a directory containing it, so an invariant the module relies
+//! on is in view before the edit rather than after it breaks.
+
+use std::path::Path;
+
+use tracing::debug;
+
+use super::{edits::target_file, handler::HookContext, usage::Injected};
+use crate::{
+  db::{FilterBuilder, Injection, InjectionRule},
+  domain::memory::Memory,
+  service::util::ServiceError,
+};
+
+/// Longest a single memory is shown
+const MAX_ITEM_CHARS: usize = 300;
+
+/// Build the warning for a tool about to run, or `None` when the tool edits
+/// no file or nothing is recorded about it.
+pub(super) async fn build(
+  ctx: &HookContext<'_>,
+  tool_name: &str,
+  tool_input: &serde_json::Value,
+) -> Result<Option<Injected>, ServiceError> {
+  let config = ctx.config;
+  if config.pre_tool_warning_limit == 0 || config.pre_tool_warning_types.is_empty() {
+    return Ok(None);
+  }
+  let Some(file) = target_file(tool_name, tool_input) else {
+    return Ok(None);
+  };
+  let path = project_relative(ctx.root, file);
+
+  let types: Vec<&str> = config.pre_tool_warning_types.iter().map(String::as_str).collect();
+  let filter = FilterBuilder::new()
+    .exclude_deleted()
+    .exclude_superseded()
+    .add_in("memory_type", &types)
+    .add_min("salience", config.pre_tool_warning_min_salience)
+    .build();
+  let mut memories = ctx.db.list_memories(filter.as_deref(), None).await?;
+  memories.retain(|m| !m.is_superseded() && is_about(m, &path));
+  memories.sort_by(|a, b| b.salience.total_cmp(&a.salience));
+  memories.truncate(config.pre_tool_warning_limit);
+
+  debug!(tool = %tool_name, path = %path, memories = memories.len(), "Pre-tool warnings");
+  Ok(render(&path, &memories).map(|text| {
+    Injected {
+      text,
+      injections: memories
+        .iter()
+        .map(|memory| Injection {
+          memory_id: memory.id.to_string(),
+          rule: InjectionRule::EditWarning,
+          score: Some(memory.salience),
+          detail: Some(path.clone()),
+        })
+        .collect(),
+    }
+  }))
+}
+
+/// `file` relative to the project root, with `/` separators
+fn project_relative(root: Option<&Path>, file: &str) -> String {
+  let path = Path::new(file);
+  let relative = root.and_then(|root| path.strip_prefix(root).ok()).unwrap_or(path);
+  relative
+    .to_string_lossy()
+    .replace('\\', "/")
+    .trim_start_matches("./")
+    .to_string()
+}
+
+/// Whether a memory names `path` or is scoped to a directory containing it
+fn is_about(memory: &Memory, path: &str) -> bool {
+  let names_file = memory.files.iter().any(|f| f.trim_start_matches("./") == path);
+  let in_scope = memory
+    .scope_path
+    .as_deref()
+    .map(|scope| scope.trim_start_matches("./").trim_end_matches('/'))
+    .filter(|scope| !scope.is_empty() && *scope != ".")
+    .is_some_and(|scope| path == scope || path.strip_prefix(scope).is_some_and(|rest| rest.starts_with('/')));
+  names_file || in_scope
+}
+
+/// Render the warning as Markdown
+fn render(path: &str, memories: &[Memory]) -> Option<String> {
+  if memories.is_empty() {
+    return None;
+  }
+  let mut out = format!("## Before editing {} (ccengram)\n\n", path);
+  for memory in memories {
+    let text = memory.summary.as_deref().unwrap_or(&memory.content);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = match text.char_indices().nth(MAX_ITEM_CHARS) {
+      Some((end, _)) => format!("{}...", &text[..end]),
+      None => text,
+    };
+    let label = memory.memory_type.map(|t| t.as_str()).unwrap_or(memory.sector.as_str());
+    out.push_str(&format!("- [{}] {} (id: {})\n", label, text, memory.id));
+  }
+  Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn memory(content: &str) -> Memory {
+    Memory::new(Uuid::nil(), content.to_string(), Sector::Semantic)
+  }
+
+  #[test]
+  fn test_is_about_named_file_and_scope() {
+    let mut named = memory("The parser must stay allocation-free");
+    named.files = vec!["./src/parser.rs".to_string()];
+    let mut scoped = memory("Handlers return typed errors");
+    scoped.scope_path = Some("src/api/".to_string());
+    let mut whole_project = memory("Use pnpm");
+    whole_project.scope_path = Some(".".to_string());
+
+    assert!(is_about(&named, "src/parser.rs"));
+    assert!(is_about(&scoped, "src/api/users.rs"));
+    assert!(
+      !is_about(&scoped, "src/api_client.rs"),
+      "scope is a directory, not a prefix"
+    );
+    assert!(!is_about(&whole_project, "src/parser.rs"));
+    assert_eq!(
+      project_relative(Some(Path::new("/repo")), "/repo/src/parser.rs"),
+      "src/parser.rs"
+    );
+  }
+}
@@ -0,0 +1,295 @@
+//! Claude Code transcript import.
+//!
+//! Claude Code logs each conversation as JSONL under `~/.claude/projects/`.
+//! Importing a transcript rebuilds its turns (user prompt, tool uses, final
+//! assistant message) and runs them through the same segment extraction the
+//! Stop hook uses, so sessions from before installation can seed memories.
+
+use std::{collections::HashSet, path::Path};
+
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use super::{
+  context::SegmentContext,
+  extraction,
+  handler::{HookContext, extract_prompt_signals},
+  usage,
+};
+use crate::{db::UsageType, ipc::project::SessionImportResult, service::util::ServiceError};
+
+/// Provenance recorded on session links for imported memories
+const IMPORT_SOURCE: &str = "SessionImport";
+
+/// A tool call made by the assistant during a turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptTool {
+  /// Tool use ID, used to pair the call with its result
+  id: String,
+  pub name: String,
+  pub input: Value,
+  /// Whether the tool result was reported as an error
+  pub is_error: bool,
+}
+
+/// One user prompt and the assistant work that followed it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptTurn {
+  pub prompt: Option<String>,
+  pub tools: Vec<TranscriptTool>,
+  pub assistant_message: Option<String>,
+}
+
+/// A parsed Claude Code transcript
+#[derive(Debug, Default)]
+pub struct Transcript {
+  /// Session ID recorded in the transcript entries
+  pub session_id: Option<String>,
+  pub turns: Vec<TranscriptTurn>,
+  /// Lines that were not valid JSON
+  pub skipped_lines: usize,
+}
+
+/// Parse a Claude Code JSONL transcript into turns.
+///
+/// Subagent (sidechain) and meta entries are skipped, as are local command
+/// output echoes. Tool results are matched to their calls by tool use ID.
+pub fn parse_transcript(content: &str) -> Transcript {
+  let mut transcript = Transcript::default();
+  let mut turn: Option<TranscriptTurn> = None;
+
+  for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+      transcript.skipped_lines += 1;
+      continue;
+    };
+
+    if transcript.session_id.is_none() {
+      transcript.session_id = entry.get("sessionId").and_then(Value::as_str).map(String::from);
+    }
+    if is_true(&entry, "isSidechain") || is_true(&entry, "isMeta") {
+      continue;
+    }
+
+    let content = entry.get("message").and_then(|m| m.get("content"));
+    match entry.get("type").and_then(Value::as_str) {
+      Some("user") => match content {
+        Some(Value::String(text)) => start_turn(&mut transcript, &mut turn, text),
+        Some(Value::Array(blocks)) => {
+          let mut text = Vec::new();
+          for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+              Some("tool_result") => {
+                if is_true(block, "is_error")
+                  && let Some(id) = block.get("tool_use_id").and_then(Value::as_str)
+                  && let Some(tool) = turn
+                    .as_mut()
+                    .and_then(|t| t.tools.iter_mut().find(|tool| tool.id == id))
+                {
+                  tool.is_error = true;
+                }
+              }
+              Some("text") => text.extend(block.get("text").and_then(Value::as_str)),
+              _ => {}
+            }
+          }
+          if !text.is_empty() {
+            start_turn(&mut transcript, &mut turn, &text.join("\n"));
+          }
+        }
+        _ => {}
+      },
+      Some("assistant") => {
+        let Some(Value::Array(blocks)) = content else {
+          continue;
+        };
+        let current = turn.get_or_insert_with(TranscriptTurn::default);
+        for block in blocks {
+          match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+              if let Some(text) = block.get("text").and_then(Value::as_str)
+                && !text.trim().is_empty()
+              {
+                current.assistant_message = Some(text.to_string());
+              }
+            }
+            Some("tool_use") => current.tools.push(TranscriptTool {
+              id: block.get("id").and_then(Value::as_str).unwrap_or_default().to_string(),
+              name: block
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string(),
+              input: block.get("input").cloned().unwrap_or_else(|| json!({})),
+              is_error: false,
+            }),
+            _ => {}
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  transcript.turns.extend(turn);
+  transcript
+}
+
+/// Finish the current turn and start a new one for a user prompt
+fn start_turn(transcript: &mut Transcript, turn: &mut Option<TranscriptTurn>, prompt: &str) {
+  let prompt = prompt.trim();
+  if prompt.is_empty() || prompt.starts_with("<local-command-") {
+    return;
+  }
+  transcript.turns.extend(turn.take());
+  *turn = Some(TranscriptTurn {
+    prompt: Some(prompt.to_string()),
+    ..Default::default()
+  });
+}
+
+fn is_true(value: &Value, key: &str) -> bool {
+  value.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Import a transcript file, extracting memories turn by turn.
+///
+/// Each turn is treated as a segment ending in a Stop event: prompts are
+/// checked for high-priority signals, segments with meaningful work are
+/// extracted, and the rolling session summary carries across turns.
+pub async fn import_transcript(ctx: &HookContext<'_>, path: &Path) -> Result<SessionImportResult, ServiceError> {
+  if ctx.llm.is_none() {
+    return Err(ServiceError::validation(
+      "Transcript import requires an LLM provider for extraction",
+    ));
+  }
+
+  let content = tokio::fs::read(path)
+    .await
+    .map_err(|e| ServiceError::validation(format!("Failed to read transcript {}: {}", path.display(), e)))?;
+  let transcript = parse_transcript(&String::from_utf8_lossy(&content));
+
+  // Claude Code names transcripts after the session ID
+  let session_id = transcript
+    .session_id
+    .clone()
+    .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+    .unwrap_or_else(|| "imported".to_string());
+
+  info!(
+    session_id = %session_id,
+    turns = transcript.turns.len(),
+    "Importing transcript"
+  );
+
+  ctx.db.get_or_create_session(&session_id, ctx.project_id).await?;
+
+  let ext_ctx = ctx.extraction_context().with_session(&session_id);
+  let mut seen_hashes = HashSet::new();
+  let mut segment = SegmentContext::default();
+  let mut result = SessionImportResult {
+    session_id: session_id.clone(),
+    turns: transcript.turns.len(),
+    segments_extracted: 0,
+    memories_created: Vec::new(),
+    skipped_lines: transcript.skipped_lines,
+  };
+
+  for turn in &transcript.turns {
+    if let Some(prompt) = &turn.prompt {
+      segment.record_user_prompt(prompt.clone());
+      result
+        .memories_created
+        .extend(extract_prompt_signals(ctx, prompt, &mut seen_hashes).await);
+    }
+    for tool in &turn.tools {
+      let outcome = json!({ "exit_code": if tool.is_error { 1 } else { 0 } });
+      segment.track_tool_use(llm::ToolUse::from_hook_event(&tool.name, &tool.input, Some(&outcome)));
+    }
+    segment.last_assistant_message = turn.assistant_message.clone();
+
+    if segment.has_meaningful_work() {
+      result.segments_extracted += 1;
+      match extraction::extract_with_llm(&ext_ctx, &segment, &mut seen_hashes).await {
+        Ok(ids) => result.memories_created.extend(ids),
+        Err(e) => warn!("Extraction failed for imported turn: {}", e),
+      }
+    }
+    if ctx.session_summary_enabled() {
+      extraction::update_session_summary(&ext_ctx, &mut segment).await;
+    }
+    segment.reset();
+  }
+
+  usage::link(
+    ctx,
+    &session_id,
+    &result.memories_created,
+    UsageType::Created,
+    IMPORT_SOURCE,
+  )
+  .await;
+  ctx.db.end_session(&session_id, segment.session_summary.clone()).await?;
+
+  debug!(
+    session_id = %session_id,
+    segments = result.segments_extracted,
+    memories = result.memories_created.len(),
+    "Transcript import complete"
+  );
+
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TRANSCRIPT: &str = r#"
+{"type":"summary","summary":"Fix flaky test","leafUuid":"x"}
+{"type":"user","sessionId":"sess-1","message":{"role":"user","content":"Fix the flaky auth test"}}
+{"type":"assistant","sessionId":"sess-1","message":{"role":"assistant","content":[{"type":"text","text":"Looking into it."},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test auth"}}]}}
+{"type":"user","sessionId":"sess-1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"1 failed","is_error":true}]}}
+{"type":"assistant","sessionId":"sess-1","isSidechain":true,"message":{"role":"assistant","content":[{"type":"tool_use","id":"s1","name":"Read","input":{"file_path":"ignored.rs"}}]}}
+{"type":"assistant","sessionId":"sess-1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"src/auth.rs","old_string":"a"}}]}}
+{"type":"assistant","sessionId":"sess-1","message":{"role":"assistant","content":[{"type":"text","text":"Fixed the race in token refresh."}]}}
+not json
+{"type":"user","sessionId":"sess-1","message":{"role":"user","content":"<local-command-stdout>ok</local-command-stdout>"}}
+{"type":"user","sessionId":"sess-1","message":{"role":"user","content":[{"type":"text","text":"Now update the docs"}]}}
+"#;
+
+  #[test]
+  fn test_parse_transcript_turns() {
+    let transcript = parse_transcript(TRANSCRIPT);
+
+    assert_eq!(
+      transcript.session_id.as_deref(),
+      Some("sess-1"),
+      "session ID from entries"
+    );
+    assert_eq!(transcript.skipped_lines, 1, "invalid JSON lines are counted");
+    assert_eq!(transcript.turns.len(), 2, "one turn per user prompt");
+
+    let first = &transcript.turns[0];
+    assert_eq!(first.prompt.as_deref(), Some("Fix the flaky auth test"), "first prompt");
+    let tools: Vec<_> = first.tools.iter().map(|t| (t.name.as_str(), t.is_error)).collect();
+    assert_eq!(
+      tools,
+      vec![("Bash", true), ("Edit", false)],
+      "tool results pair with calls and sidechains are skipped"
+    );
+    assert_eq!(
+      first.assistant_message.as_deref(),
+      Some("Fixed the race in token refresh."),
+      "last assistant text"
+    );
+
+    let second = &transcript.turns[1];
+    assert_eq!(
+      second.prompt.as_deref(),
+      Some("Now update the docs"),
+      "text block prompt"
+    );
+    assert!(second.tools.is_empty(), "no tools in second turn");
+  }
+}
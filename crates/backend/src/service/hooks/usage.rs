@@ -0,0 +1,224 @@
+//! Session memory usage tracking.
+//!
+//! Records which memories a Claude Code session created, recalled, updated or
+//! reinforced in the `session_memories` table, along with the hook event that
+//! observed it. Memory tool calls are seen through PostToolUse, since the MCP
+//! server itself does not know which session it serves.
+//!
+//! Memories the hooks inject as `additionalContext` are recorded separately in
+//! `session_injections`, with the rule that picked each one and its score.
+//! Candidates below the `[injection]` similarity or salience floor are logged
+//! instead, so the floor can be tuned against what it actually left out.
+
+use serde_json::Value;
+use tracing::{info, warn};
+
+use super::{event::HookEvent, handler::HookContext};
+use crate::{
+  db::{Injection, UsageType},
+  domain::{config::InjectionConfig, memory::Memory},
+  service::util::Resolver,
+};
+
+/// Skipped candidates logged one by one per hook event; the rest are counted
+const SKIPPED_LOGGED: usize = 5;
+
+/// Context text for a hook to inject, and the memories it carries
+#[derive(Debug, Clone)]
+pub struct Injected {
+  pub text: String,
+  pub injections: Vec<Injection>,
+}
+
+/// Candidates (best first, with their distance) that clear the injection floor
+/// for `min_similarity`. The best of those left out are logged with their
+/// scores.
+pub(super) fn above_floor(
+  candidates: Vec<(Memory, f32)>,
+  injection: &InjectionConfig,
+  min_similarity: f32,
+  event: HookEvent,
+) -> Vec<(Memory, f32)> {
+  let (kept, skipped): (Vec<_>, Vec<_>) = candidates
+    .into_iter()
+    .partition(|(memory, distance)| injection.clears_floor(memory, 1.0 - distance.min(1.0), min_similarity));
+
+  for (memory, distance) in skipped.iter().take(SKIPPED_LOGGED) {
+    info!(
+      event = %event,
+      memory_id = %memory.id,
+      similarity = 1.0 - distance.min(1.0),
+      salience = memory.salience,
+      min_similarity,
+      min_salience = injection.min_salience,
+      "Skipped injecting a memory below the floor"
+    );
+  }
+  if skipped.len() > SKIPPED_LOGGED {
+    info!(
+      event = %event,
+      count = skipped.len() - SKIPPED_LOGGED,
+      "Skipped more memories below the floor"
+    );
+  }
+
+  kept
+}
+
+/// Record the memories injected by a hook event, logging rather than failing the hook on error
+pub async fn record_injected(ctx: &HookContext<'_>, session_id: &str, injected: &Injected, event: HookEvent) {
+  if let Err(e) = ctx
+    .db
+    .record_injections(session_id, &event.to_string(), &injected.injections)
+    .await
+  {
+    warn!(session_id = %session_id, "Failed to record session injections: {}", e);
+  }
+}
+
+/// Tool response attribute holding a memory ID in formatted search results
+const RESULT_ID_ATTR: &str = "id=\"";
+
+/// Link memories to a session, logging rather than failing the hook on error
+pub async fn link(ctx: &HookContext<'_>, session_id: &str, memory_ids: &[String], usage: UsageType, source: &str) {
+  if let Err(e) = ctx.db.link_memories(session_id, memory_ids, usage, Some(source)).await {
+    warn!(session_id = %session_id, "Failed to link session memories: {}", e);
+  }
+}
+
+/// Link memories created while handling a hook event
+pub async fn link_created(ctx: &HookContext<'_>, session_id: &str, memory_ids: &[String], event: HookEvent) {
+  link(ctx, session_id, memory_ids, UsageType::Created, &event.to_string()).await;
+}
+
+/// Record the memories a ccengram memory tool call used
+pub async fn track_memory_tool(
+  ctx: &HookContext<'_>,
+  session_id: &str,
+  tool_name: &str,
+  input: &Value,
+  response: Option<&Value>,
+) {
+  let Some((usage, references)) = memory_tool_usage(tool_name, input, response) else {
+    return;
+  };
+
+  let mut memory_ids = Vec::new();
+  for reference in references {
+    if let Ok(memory) = Resolver::memory(ctx.db, &reference).await {
+      let id = memory.id.to_string();
+      if !memory_ids.contains(&id) {
+        memory_ids.push(id);
+      }
+    }
+  }
+
+  link(ctx, session_id, &memory_ids, usage, &HookEvent::PostToolUse.to_string()).await;
+}
+
+/// Usage type and memory ID references for a ccengram memory tool call.
+///
+/// Tool names arrive namespaced by Claude Code (`mcp__<server>__<tool>`).
+/// Search results are formatted with short ID prefixes, which are resolved
+/// by the caller.
+fn memory_tool_usage(tool_name: &str, input: &Value, response: Option<&Value>) -> Option<(UsageType, Vec<String>)> {
+  let server_tool = tool_name.strip_prefix("mcp__").filter(|t| t.contains("ccengram"))?;
+  let tool = server_tool.rsplit("__").next()?;
+
+  let input_ids = |keys: &[&str]| -> Vec<String> {
+    keys
+      .iter()
+      .filter_map(|k| input.get(*k)?.as_str().map(String::from))
+      .collect()
+  };
+
+  match tool {
+    "memory_get" | "memory_related" | "memory_timeline" | "relationship_list" => {
+      Some((UsageType::Recalled, input_ids(&["memory_id"])))
+    }
+    "memory_search" => Some((UsageType::Recalled, response.map(result_ids).unwrap_or_default())),
+    "memory_reinforce" => Some((UsageType::Reinforced, input_ids(&["memory_id"]))),
+    "memory_deemphasize" => Some((UsageType::Updated, input_ids(&["memory_id"]))),
+    "memory_feedback" => Some((UsageType::Recalled, input_ids(&["memory_id"]))),
+    "memory_supersede" => Some((UsageType::Updated, input_ids(&["old_memory_id", "new_memory_id"]))),
+    _ => None,
+  }
+}
+
+/// IDs from `id="..."` attributes anywhere in a tool response
+fn result_ids(response: &Value) -> Vec<String> {
+  let mut ids = Vec::new();
+  collect_ids(response, &mut ids);
+  ids
+}
+
+fn collect_ids(value: &Value, ids: &mut Vec<String>) {
+  match value {
+    Value::String(text) => {
+      let mut rest = text.as_str();
+      while let Some(start) = rest.find(RESULT_ID_ATTR) {
+        rest = &rest[start + RESULT_ID_ATTR.len()..];
+        let Some(end) = rest.find('"') else {
+          break;
+        };
+        ids.push(rest[..end].to_string());
+        rest = &rest[end..];
+      }
+    }
+    Value::Array(items) => items.iter().for_each(|v| collect_ids(v, ids)),
+    Value::Object(map) => map.values().for_each(|v| collect_ids(v, ids)),
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_memory_tool_usage() {
+    let input = json!({ "memory_id": "019abc12" });
+    let usage = memory_tool_usage("mcp__plugin_ccengram_ccengram__memory_reinforce", &input, None);
+    assert_eq!(
+      usage,
+      Some((UsageType::Reinforced, vec!["019abc12".to_string()])),
+      "reinforce links the input memory"
+    );
+
+    let supersede = json!({ "old_memory_id": "a1", "new_memory_id": "b2" });
+    assert_eq!(
+      memory_tool_usage("mcp__ccengram__memory_supersede", &supersede, None),
+      Some((UsageType::Updated, vec!["a1".to_string(), "b2".to_string()])),
+      "supersede updates both memories"
+    );
+
+    assert_eq!(
+      memory_tool_usage("mcp__other__memory_get", &input, None),
+      None,
+      "other MCP servers are ignored"
+    );
+    assert_eq!(
+      memory_tool_usage("Read", &input, None),
+      None,
+      "built-in tools are ignored"
+    );
+  }
+
+  #[test]
+  fn test_search_result_ids() {
+    let response = json!([{
+      "type": "text",
+      "text": "<memory index=\"1\" id=\"019abc12\" sector=\"semantic\">\n...\n<memory index=\"2\" id=\"019def34\">"
+    }]);
+    assert_eq!(
+      memory_tool_usage("mcp__ccengram__memory_search", &json!({}), Some(&response)),
+      Some((
+        UsageType::Recalled,
+        vec!["019abc12".to_string(), "019def34".to_string()]
+      )),
+      "search results are recalled"
+    );
+  }
+}
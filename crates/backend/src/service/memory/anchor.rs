@@ -0,0 +1,129 @@
+//! Git blame anchors linking memories to the code they reference.
+//!
+//! When a memory mentions files, each is blamed at creation time and the
+//! newest commit touching the referenced lines is stored. Showing the memory
+//! blames the same lines again; a different newest commit means the code has
+//! changed since and the memory may be stale.
+
+use std::path::Path;
+
+use tracing::debug;
+
+use crate::{
+  domain::{git, memory::FileAnchor, path},
+  ipc::types::memory::MemoryFileAnchor,
+};
+
+/// Blame the files a memory references.
+///
+/// Files outside `root`, missing files and files git does not track are
+/// skipped.
+pub async fn anchor_files(root: &Path, files: &[String]) -> Vec<FileAnchor> {
+  let mut anchors = Vec::new();
+
+  for file in files {
+    let (file_path, lines) = parse_file_ref(file);
+    let Some(relative) = resolve(root, file_path).await else {
+      continue;
+    };
+
+    match git::blame_latest(root, &relative, lines).await {
+      Ok(blame) => anchors.push(FileAnchor {
+        path: relative,
+        start_line: lines.map(|(start, _)| start),
+        end_line: lines.map(|(_, end)| end),
+        commit: blame.commit,
+        author: blame.author,
+        committed_at: blame.committed_at,
+      }),
+      Err(e) => debug!(file = %relative, error = %e, "Skipping blame anchor"),
+    }
+  }
+
+  anchors
+}
+
+/// Compare anchors against the current blame of the same lines.
+pub async fn check_anchors(root: &Path, anchors: &[FileAnchor]) -> Vec<MemoryFileAnchor> {
+  let mut checked = Vec::with_capacity(anchors.len());
+
+  for anchor in anchors {
+    let lines = anchor.start_line.zip(anchor.end_line);
+    let current = git::blame_latest(root, &anchor.path, lines).await.ok();
+    let changed = current.as_ref().is_none_or(|c| c.commit != anchor.commit);
+
+    checked.push(MemoryFileAnchor {
+      path: anchor.path.clone(),
+      start_line: anchor.start_line,
+      end_line: anchor.end_line,
+      commit: anchor.commit.clone(),
+      author: anchor.author.clone(),
+      current_commit: current.map(|c| c.commit),
+      changed,
+    });
+  }
+
+  checked
+}
+
+/// Split an optional `:line` or `:start-end` suffix from a file reference.
+pub(super) fn parse_file_ref(file: &str) -> (&str, Option<(u32, u32)>) {
+  let Some((path, suffix)) = file.rsplit_once(':') else {
+    return (file, None);
+  };
+
+  let range = match suffix.split_once('-') {
+    Some((start, end)) => start.parse().ok().zip(end.parse().ok()),
+    None => suffix.parse().ok().map(|line| (line, line)),
+  };
+
+  match range {
+    Some((start, end)) if start > 0 && start <= end => (path, Some((start, end))),
+    _ => (file, None),
+  }
+}
+
+/// Relative path of an existing file under `root`.
+async fn resolve(root: &Path, file: &str) -> Option<String> {
+  let candidate = Path::new(file);
+  let relative = if candidate.is_absolute() {
+    path::relative_path(candidate, root)?
+  } else {
+    path::normalize(file)
+  };
+
+  let is_file = tokio::fs::metadata(root.join(&relative))
+    .await
+    .is_ok_and(|m| m.is_file());
+  (is_file && !relative.is_empty()).then_some(relative)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_file_ref_line_ranges() {
+    assert_eq!(parse_file_ref("src/main.rs"), ("src/main.rs", None), "plain path");
+    assert_eq!(
+      parse_file_ref("src/main.rs:12"),
+      ("src/main.rs", Some((12, 12))),
+      "single line"
+    );
+    assert_eq!(
+      parse_file_ref("src/main.rs:10-20"),
+      ("src/main.rs", Some((10, 20))),
+      "line range"
+    );
+    assert_eq!(
+      parse_file_ref("src/main.rs:20-10"),
+      ("src/main.rs:20-10", None),
+      "reversed ranges are not line references"
+    );
+    assert_eq!(
+      parse_file_ref(r"C:\repo\main.rs"),
+      (r"C:\repo\main.rs", None),
+      "drive letters are not line references"
+    );
+  }
+}
@@ -10,6 +10,7 @@ use tracing::debug;
 use super::MemoryContext;
 use crate::{
   context::memory::extract::dedup::{DuplicateChecker, DuplicateMatch},
+  db::ProjectDb,
   service::util::ServiceError,
 };
 
@@ -55,9 +56,22 @@ pub async fn check_duplicate(
 ) -> Result<Option<DuplicateResult>, ServiceError> {
   // Get embedding for similarity search
   let query_vec = ctx.get_embedding(content).await?;
+  find_duplicate(ctx.db, &query_vec, content, content_hash, simhash).await
+}
 
+/// Check for a duplicate among the memories nearest to an existing embedding.
+///
+/// Same checks as [`check_duplicate`], for content that already has a vector,
+/// such as memories copied from another project's store.
+pub async fn find_duplicate(
+  db: &ProjectDb,
+  query_vec: &[f32],
+  content: &str,
+  content_hash: &str,
+  simhash: u64,
+) -> Result<Option<DuplicateResult>, ServiceError> {
   // Search for similar memories
-  let candidates = match ctx.db.search_memories(&query_vec, 10, Some("is_deleted = false")).await {
+  let candidates = match db.search_memories(query_vec, 10, Some("is_deleted = false")).await {
     Ok(c) => c,
     Err(e) => {
       debug!("Vector search for dedup failed: {}", e);
@@ -0,0 +1,385 @@
+//! Named entity service.
+//!
+//! Links memories to the people, technologies, services and files they
+//! mention, and ranks entities by how many active memories mention them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use llm::ExtractedEntity;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::{
+  context::memory::extract::entities::extract_entities,
+  db::{FilterBuilder, ProjectDb},
+  domain::{
+    alias::Aliases,
+    config::SearchConfig,
+    memory::{Entity, EntityType, Memory, MemoryId},
+  },
+  ipc::types::{
+    code::CodeItem,
+    entity::{
+      EntityCoOccurrence, EntityDetail, EntityGetParams, EntityItem, EntityListParams, EntityShowParams,
+      EntityShowResult, EntityTimelineEntry, EntityTopParams,
+    },
+    memory::MemorySummary,
+  },
+  service::{
+    code::{CodeContext, RankingConfig, SearchParams},
+    util::{Resolver, ServiceError},
+  },
+};
+
+/// Default number of entities listed
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// Default number of top entities
+const DEFAULT_TOP_LIMIT: usize = 10;
+
+/// Memories listed with an entity
+const ENTITY_MEMORIES: usize = 20;
+
+/// Default number of memories and code chunks shown with an entity
+const DEFAULT_SHOW_LIMIT: usize = 5;
+
+/// Co-occurring entities shown with an entity
+const CO_OCCURRING: usize = 10;
+
+/// Longest entity name kept; longer ones are extraction noise
+const MAX_NAME_LEN: usize = 100;
+
+/// Record the entities a stored memory mentions.
+///
+/// `extracted` holds entities named by the LLM; those the rule-based
+/// extractor finds in the content are added. Entities named by an alias are
+/// stored under its canonical term. Returns the number linked.
+pub async fn link(
+  db: &ProjectDb,
+  memory: &Memory,
+  extracted: &[ExtractedEntity],
+  aliases: Option<&Aliases>,
+) -> Result<usize, ServiceError> {
+  let mut entities: Vec<Entity> = Vec::new();
+  for found in extracted.iter().cloned().chain(extract_entities(&memory.content)) {
+    let name = found.name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+      continue;
+    }
+    let name = aliases.and_then(|a| a.canonical(name)).unwrap_or(name);
+    let entity = Entity::new(name, found.entity_type);
+    if !entities.iter().any(|e| e.id == entity.id) {
+      entities.push(entity);
+    }
+  }
+
+  let ids: Vec<Uuid> = entities.iter().map(|e| e.id).collect();
+  db.add_entities(&entities).await?;
+  db.link_entities(&memory.id, &ids).await?;
+  debug!(memory_id = %memory.id, count = ids.len(), "Linked memory entities");
+  Ok(ids.len())
+}
+
+/// Link the entities of memories stored before entity extraction existed.
+///
+/// Runs only while no entity is stored, using the rule-based extractor.
+pub async fn backfill(db: &ProjectDb, aliases: Option<&Aliases>) -> Result<usize, ServiceError> {
+  if db.count_entities().await? > 0 {
+    return Ok(0);
+  }
+
+  let memories = db.list_memories(Some("is_deleted = false"), None).await?;
+  let mut linked = 0;
+  for memory in &memories {
+    if link(db, memory, &[], aliases).await? > 0 {
+      linked += 1;
+    }
+  }
+
+  if linked > 0 {
+    info!(memories = linked, "Backfilled memory entities");
+  }
+  Ok(linked)
+}
+
+/// Copy the entity links of one memory into another store
+pub async fn copy(from: &ProjectDb, into: &ProjectDb, memory_id: &MemoryId) -> Result<(), ServiceError> {
+  let links = from
+    .list_entity_links(Some(
+      &FilterBuilder::new()
+        .add_eq("memory_id", &memory_id.to_string())
+        .build_or_empty(),
+    ))
+    .await?;
+  let ids: Vec<String> = links.iter().map(|l| l.entity_id.to_string()).collect();
+  let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+  let Some(filter) = FilterBuilder::new().add_in("id", &ids).build() else {
+    return Ok(());
+  };
+
+  let entities = from.list_entities(Some(&filter)).await?;
+  into.add_entities(&entities).await?;
+  into
+    .link_entities(memory_id, &entities.iter().map(|e| e.id).collect::<Vec<_>>())
+    .await?;
+  Ok(())
+}
+
+/// List entities by name.
+///
+/// # Arguments
+/// * `db` - Project database
+/// * `params` - Optional entity type filter and limit
+///
+/// # Returns
+/// * `Ok(Vec<EntityItem>)` - Entities with their mention counts
+/// * `Err(ServiceError)` - If the type is unknown or the query fails
+pub async fn list(db: &ProjectDb, params: EntityListParams) -> Result<Vec<EntityItem>, ServiceError> {
+  let mut items = with_mentions(db, params.entity_type.as_deref()).await?;
+  items.sort_by_key(|item| item.name.to_lowercase());
+  items.truncate(params.limit.unwrap_or(DEFAULT_LIST_LIMIT));
+  Ok(items)
+}
+
+/// Entities mentioned by the most active memories.
+///
+/// Ties go to the entity mentioned most recently. Entities no active memory
+/// mentions are left out.
+pub async fn top(db: &ProjectDb, params: EntityTopParams) -> Result<Vec<EntityItem>, ServiceError> {
+  let mut items = with_mentions(db, params.entity_type.as_deref()).await?;
+  items.retain(|item| item.mentions > 0);
+  items.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| b.last_seen.cmp(&a.last_seen)));
+  items.truncate(params.limit.unwrap_or(DEFAULT_TOP_LIMIT));
+  Ok(items)
+}
+
+/// Get an entity by ID, ID prefix or name with the memories mentioning it.
+///
+/// A name that is an alias finds the entity stored under its canonical term.
+pub async fn get(
+  db: &ProjectDb,
+  params: EntityGetParams,
+  aliases: Option<&Aliases>,
+) -> Result<EntityDetail, ServiceError> {
+  let entity = resolve(db, &params.entity_id, aliases).await?;
+
+  let links = db
+    .list_entity_links(Some(
+      &FilterBuilder::new()
+        .add_eq("entity_id", &entity.id.to_string())
+        .build_or_empty(),
+    ))
+    .await?;
+  let memory_ids: Vec<String> = links.iter().map(|l| l.memory_id.clone()).collect();
+  let memory_ids: Vec<&str> = memory_ids.iter().map(String::as_str).collect();
+
+  let mut memories = if memory_ids.is_empty() {
+    Vec::new()
+  } else {
+    let filter = FilterBuilder::new()
+      .add_in("id", &memory_ids)
+      .exclude_deleted()
+      .build_or_empty();
+    db.list_memories(Some(&filter), None).await?
+  };
+  memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+  let last_seen = links.iter().map(|l| l.linked_at).max();
+  Ok(EntityDetail {
+    entity: item(&entity, memories.len(), last_seen),
+    memories: memories.iter().take(ENTITY_MEMORIES).map(MemorySummary::from).collect(),
+  })
+}
+
+/// Show what is known about an entity.
+///
+/// Gathers the days its memories were created, the entities those memories
+/// also mention, its most salient memories and related code: the chunks of
+/// the file for file entities, otherwise a code search for its name. Code
+/// search failures leave the code empty rather than failing the request.
+pub async fn show(
+  ctx: &CodeContext<'_>,
+  search_config: Option<&SearchConfig>,
+  params: EntityShowParams,
+) -> Result<EntityShowResult, ServiceError> {
+  let db = ctx.db;
+  let limit = params.limit.unwrap_or(DEFAULT_SHOW_LIMIT);
+  let entity = resolve(db, &params.entity_id, ctx.aliases).await?;
+
+  let links = db
+    .list_entity_links(Some(
+      &FilterBuilder::new()
+        .add_eq("entity_id", &entity.id.to_string())
+        .build_or_empty(),
+    ))
+    .await?;
+  let memory_ids: Vec<&str> = links.iter().map(|l| l.memory_id.as_str()).collect();
+  let mut memories = if memory_ids.is_empty() {
+    Vec::new()
+  } else {
+    let filter = FilterBuilder::new()
+      .add_in("id", &memory_ids)
+      .exclude_deleted()
+      .build_or_empty();
+    db.list_memories(Some(&filter), None).await?
+  };
+
+  let mut days: BTreeMap<String, usize> = BTreeMap::new();
+  for memory in &memories {
+    *days
+      .entry(memory.created_at.format("%Y-%m-%d").to_string())
+      .or_default() += 1;
+  }
+  let timeline = days
+    .into_iter()
+    .map(|(date, mentions)| EntityTimelineEntry { date, mentions })
+    .collect();
+
+  let co_occurring = co_occurring(db, &entity, &memories).await?;
+
+  let last_seen = links.iter().map(|l| l.linked_at).max();
+  let entity_item = item(&entity, memories.len(), last_seen);
+  memories.sort_by(|a, b| {
+    b.salience
+      .total_cmp(&a.salience)
+      .then_with(|| b.created_at.cmp(&a.created_at))
+  });
+  memories.truncate(limit);
+
+  let code = match related_code(ctx, search_config, &entity, limit).await {
+    Ok(code) => code,
+    Err(e) => {
+      warn!(entity = %entity.name, "Code lookup for entity failed: {}", e);
+      Vec::new()
+    }
+  };
+
+  Ok(EntityShowResult {
+    entity: entity_item,
+    timeline,
+    co_occurring,
+    memories: memories.iter().map(MemorySummary::from).collect(),
+    code,
+  })
+}
+
+/// Other entities mentioned by `memories`, by number of shared memories
+async fn co_occurring(
+  db: &ProjectDb,
+  entity: &Entity,
+  memories: &[Memory],
+) -> Result<Vec<EntityCoOccurrence>, ServiceError> {
+  let memory_ids: Vec<String> = memories.iter().map(|m| m.id.to_string()).collect();
+  let memory_ids: Vec<&str> = memory_ids.iter().map(String::as_str).collect();
+  let Some(filter) = FilterBuilder::new().add_in("memory_id", &memory_ids).build() else {
+    return Ok(Vec::new());
+  };
+
+  let mut shared: HashMap<Uuid, usize> = HashMap::new();
+  for link in db.list_entity_links(Some(&filter)).await? {
+    if link.entity_id != entity.id {
+      *shared.entry(link.entity_id).or_default() += 1;
+    }
+  }
+
+  let ids: Vec<String> = shared.keys().map(Uuid::to_string).collect();
+  let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+  let Some(filter) = FilterBuilder::new().add_in("id", &ids).build() else {
+    return Ok(Vec::new());
+  };
+  let mut items: Vec<EntityCoOccurrence> = db
+    .list_entities(Some(&filter))
+    .await?
+    .into_iter()
+    .map(|e| EntityCoOccurrence {
+      id: e.id.to_string(),
+      shared: shared.get(&e.id).copied().unwrap_or(0),
+      entity_type: e.entity_type.as_str().to_string(),
+      name: e.name,
+    })
+    .collect();
+  items.sort_by(|a, b| b.shared.cmp(&a.shared).then_with(|| a.name.cmp(&b.name)));
+  items.truncate(CO_OCCURRING);
+  Ok(items)
+}
+
+/// Code chunks of a file entity, or found by searching for the entity name
+async fn related_code(
+  ctx: &CodeContext<'_>,
+  search_config: Option<&SearchConfig>,
+  entity: &Entity,
+  limit: usize,
+) -> Result<Vec<CodeItem>, ServiceError> {
+  if entity.entity_type == EntityType::File {
+    let mut chunks = ctx.db.get_chunks_for_file(&entity.name).await?;
+    if !chunks.is_empty() {
+      chunks.sort_by_key(|c| c.start_line);
+      return Ok(chunks.iter().take(limit).map(CodeItem::from).collect());
+    }
+  }
+
+  let params = SearchParams {
+    query: entity.name.clone(),
+    limit: Some(limit),
+    ..Default::default()
+  };
+  let result = crate::service::code::search(ctx, params, &RankingConfig::default(), search_config, None).await?;
+  Ok(result.results)
+}
+
+/// All entities of a type with their mention counts
+async fn with_mentions(db: &ProjectDb, entity_type: Option<&str>) -> Result<Vec<EntityItem>, ServiceError> {
+  let entity_type = entity_type
+    .map(|t| t.parse::<EntityType>())
+    .transpose()
+    .map_err(ServiceError::Validation)?;
+  let filter = FilterBuilder::new()
+    .add_eq_opt("entity_type", entity_type.as_ref().map(EntityType::as_str))
+    .build();
+
+  let (entities, links, memories) = tokio::join!(
+    db.list_entities(filter.as_deref()),
+    db.list_entity_links(None),
+    db.list_memories(Some("is_deleted = false"), None)
+  );
+  let active: HashSet<String> = memories?.into_iter().map(|m| m.id.to_string()).collect();
+
+  let mut mentions: HashMap<Uuid, (usize, DateTime<Utc>)> = HashMap::new();
+  for link in links?.into_iter().filter(|l| active.contains(&l.memory_id)) {
+    let entry = mentions.entry(link.entity_id).or_insert((0, link.linked_at));
+    entry.0 += 1;
+    entry.1 = entry.1.max(link.linked_at);
+  }
+
+  Ok(
+    entities?
+      .iter()
+      .map(|entity| match mentions.get(&entity.id) {
+        Some((count, last_seen)) => item(entity, *count, Some(*last_seen)),
+        None => item(entity, 0, None),
+      })
+      .collect(),
+  )
+}
+
+fn item(entity: &Entity, mentions: usize, last_seen: Option<DateTime<Utc>>) -> EntityItem {
+  EntityItem {
+    id: entity.id.to_string(),
+    name: entity.name.clone(),
+    entity_type: entity.entity_type.as_str().to_string(),
+    mentions,
+    last_seen: last_seen.map(|t| t.to_rfc3339()),
+  }
+}
+
+/// Resolve an entity by ID, ID prefix or name, trying the canonical term
+/// of an alias first
+async fn resolve(db: &ProjectDb, input: &str, aliases: Option<&Aliases>) -> Result<Entity, ServiceError> {
+  if let Some(canonical) = aliases.and_then(|a| a.canonical(input))
+    && let Ok(entity) = Resolver::entity(db, canonical).await
+  {
+    return Ok(entity);
+  }
+  Ok(Resolver::entity(db, input).await?)
+}
@@ -0,0 +1,222 @@
+//! Goal tracking.
+//!
+//! Goals are memories of type `goal` with a status (open, blocked or done)
+//! and an optional free-form due hint. Active goals are listed at session
+//! start ahead of other memories, so a new session is reminded of what is
+//! still being worked toward rather than only of past facts. Storing a task
+//! completion that closely matches an active goal marks the goal done and
+//! links the completion to it.
+
+use chrono::Utc;
+use tracing::{debug, warn};
+
+use super::{MemoryContext, record_history, search::search_by_embedding};
+use crate::{
+  db::{FilterBuilder, MemoryChange, ProjectDb},
+  domain::memory::{GoalStatus, Memory, MemoryActor, MemoryEventAction, MemoryId, MemoryType, RelationshipType},
+  ipc::types::memory::{GoalUpdateParams, GoalsParams, MemoryItem},
+  service::util::{Resolver, ServiceError},
+};
+
+/// Goals listed when no limit is given
+const DEFAULT_LIMIT: usize = 20;
+
+/// Active goals compared against each new task completion
+const COMPLETION_CANDIDATES: usize = 5;
+
+/// Similarity above which a task completion is taken to finish a goal
+const COMPLETION_MATCH_SIMILARITY: f32 = 0.8;
+
+/// Statuses selected by a `goals` status filter
+fn statuses(filter: Option<&str>) -> Result<Vec<GoalStatus>, ServiceError> {
+  match filter.map(str::to_lowercase).as_deref() {
+    None | Some("active") => Ok(vec![GoalStatus::Open, GoalStatus::Blocked]),
+    Some("all") => Ok(vec![GoalStatus::Open, GoalStatus::Blocked, GoalStatus::Done]),
+    Some(s) => s
+      .parse::<GoalStatus>()
+      .map(|status| vec![status])
+      .map_err(ServiceError::validation),
+  }
+}
+
+/// Current goals with one of `statuses`
+fn goal_filter(statuses: &[GoalStatus]) -> FilterBuilder {
+  let statuses: Vec<&str> = statuses.iter().map(|s| s.as_str()).collect();
+  FilterBuilder::new()
+    .exclude_superseded()
+    .add_eq("memory_type", MemoryType::Goal.as_str())
+    .add_in("goal_status", &statuses)
+}
+
+/// Open goals first, then blocked, then done; newest first within each
+fn sort(goals: &mut [Memory]) {
+  let order = |goal: &Memory| match goal.goal_status.unwrap_or_default() {
+    GoalStatus::Open => 0,
+    GoalStatus::Blocked => 1,
+    GoalStatus::Done => 2,
+  };
+  goals.sort_by(|a, b| order(a).cmp(&order(b)).then(b.created_at.cmp(&a.created_at)));
+}
+
+/// Goals with one of `statuses`, sorted and capped at `limit`
+async fn find(db: &ProjectDb, statuses: &[GoalStatus], limit: usize) -> Result<Vec<Memory>, ServiceError> {
+  let filter = goal_filter(statuses).exclude_deleted().build();
+  let mut goals = db.list_memories(filter.as_deref(), None).await?;
+  sort(&mut goals);
+  goals.truncate(limit);
+  Ok(goals)
+}
+
+/// Open and blocked goals, for session start
+pub async fn active(db: &ProjectDb, limit: usize) -> Result<Vec<Memory>, ServiceError> {
+  find(db, &[GoalStatus::Open, GoalStatus::Blocked], limit).await
+}
+
+/// List goals, open and blocked ones unless a status is given.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Status filter (`open`, `blocked`, `done`, `active` or `all`) and limit
+///
+/// # Returns
+/// * `Ok(Vec<MemoryItem>)` - Goals, open first, then blocked, then done
+/// * `Err(ServiceError)` - If the status is unknown or database error
+pub async fn list(ctx: &MemoryContext<'_>, params: GoalsParams) -> Result<Vec<MemoryItem>, ServiceError> {
+  let statuses = statuses(params.status.as_deref())?;
+  let goals = find(ctx.db, &statuses, params.limit.unwrap_or(DEFAULT_LIMIT)).await?;
+  Ok(goals.iter().map(MemoryItem::from_list).collect())
+}
+
+/// Change the status or due hint of a goal.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Goal ID or prefix, new status and due hint (empty clears it)
+///
+/// # Returns
+/// * `Ok(MemoryItem)` - The updated goal
+/// * `Err(ServiceError)` - If the memory is not a goal, the status is unknown, or database error
+pub async fn update(ctx: &MemoryContext<'_>, params: GoalUpdateParams) -> Result<MemoryItem, ServiceError> {
+  if params.status.is_none() && params.due.is_none() {
+    return Err(ServiceError::validation("Nothing to update: give a status or due hint"));
+  }
+
+  let mut goal = Resolver::memory(ctx.db, &params.id).await?;
+  if goal.memory_type != Some(MemoryType::Goal) {
+    return Err(ServiceError::validation(format!("Memory {} is not a goal", goal.id)));
+  }
+
+  let mut changes = Vec::new();
+  if let Some(status) = params.status {
+    let status = status.parse::<GoalStatus>().map_err(ServiceError::validation)?;
+    goal.goal_status = Some(status);
+    changes.push(format!("status {}", status.as_str()));
+  }
+  if let Some(due) = params.due {
+    goal.due = Some(due.trim().to_string()).filter(|d| !d.is_empty());
+    changes.push(format!("due {}", goal.due.as_deref().unwrap_or("cleared")));
+  }
+  goal.updated_at = Utc::now();
+  ctx.db.update_memory(&goal, None).await?;
+  ctx
+    .record(MemoryChange::new(goal.id, MemoryEventAction::Updated).with_detail(changes.join(", ")))
+    .await;
+
+  Ok(MemoryItem::from(&goal))
+}
+
+/// Mark active goals done when `completion`, a task completion that was just
+/// stored with `vector`, closely matches them.
+///
+/// Returns the IDs of the completed goals.
+pub async fn complete_matching(
+  db: &ProjectDb,
+  completion: &Memory,
+  vector: &[f32],
+) -> Result<Vec<MemoryId>, ServiceError> {
+  let filter = goal_filter(&[GoalStatus::Open, GoalStatus::Blocked]).build();
+  let candidates = search_by_embedding(db, vector, COMPLETION_CANDIDATES, filter.as_deref()).await?;
+
+  let mut completed = Vec::new();
+  for (mut goal, distance) in candidates {
+    let similarity = 1.0 - distance.min(1.0);
+    if similarity < COMPLETION_MATCH_SIMILARITY {
+      continue;
+    }
+
+    goal.goal_status = Some(GoalStatus::Done);
+    goal.updated_at = Utc::now();
+    db.update_memory(&goal, None).await?;
+    let change = MemoryChange::new(goal.id, MemoryEventAction::Updated)
+      .with_detail(format!("status done, completed by {}", completion.id));
+    record_history(db, MemoryActor::System, &[change]).await;
+    if let Err(e) = db
+      .create_relationship(
+        &completion.id,
+        &goal.id,
+        RelationshipType::BuildsOn,
+        similarity,
+        "goal_tracker",
+      )
+      .await
+    {
+      warn!(goal_id = %goal.id, "Failed to link task completion to goal: {}", e);
+    }
+    debug!(goal_id = %goal.id, completion_id = %completion.id, similarity, "Goal completed");
+    completed.push(goal.id);
+  }
+
+  Ok(completed)
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn goal(content: &str, status: GoalStatus, age_days: i64) -> Memory {
+    let mut memory = Memory::new(Uuid::nil(), content.to_string(), Sector::Reflective);
+    memory.memory_type = Some(MemoryType::Goal);
+    memory.goal_status = Some(status);
+    memory.created_at -= Duration::days(age_days);
+    memory
+  }
+
+  #[test]
+  fn test_status_filters() {
+    assert_eq!(statuses(None).unwrap(), vec![GoalStatus::Open, GoalStatus::Blocked]);
+    assert_eq!(statuses(Some("Done")).unwrap(), vec![GoalStatus::Done]);
+    assert_eq!(statuses(Some("all")).unwrap().len(), 3);
+    assert!(statuses(Some("someday")).is_err());
+
+    let filter = goal_filter(&[GoalStatus::Open]).build().unwrap();
+    assert_eq!(
+      filter,
+      "superseded_by IS NULL AND memory_type = 'goal' AND goal_status IN ('open')"
+    );
+  }
+
+  #[test]
+  fn test_sort_open_before_blocked_before_done() {
+    let mut goals = vec![
+      goal("Ship the importer", GoalStatus::Done, 0),
+      goal("Migrate CI to the new runners", GoalStatus::Blocked, 1),
+      goal("Drop the legacy auth flow", GoalStatus::Open, 5),
+      goal("Add rate limiting to the API", GoalStatus::Open, 2),
+    ];
+    sort(&mut goals);
+    let order: Vec<&str> = goals.iter().map(|g| g.content.as_str()).collect();
+    assert_eq!(
+      order,
+      vec![
+        "Add rate limiting to the API",
+        "Drop the legacy auth flow",
+        "Migrate CI to the new runners",
+        "Ship the importer",
+      ]
+    );
+  }
+}
@@ -4,10 +4,13 @@
 //! - `reinforce` - Increase salience when memory is accessed/useful
 //! - `deemphasize` - Decrease salience when memory is less relevant
 //! - `supersede` - Mark a memory as replaced by a newer one
+//! - `feedback` - Count a search result as helpful or irrelevant
 
 use super::MemoryContext;
 use crate::{
-  ipc::types::memory::{MemorySupersedeResult, MemoryUpdateResult},
+  db::MemoryChange,
+  domain::memory::MemoryEventAction,
+  ipc::types::memory::{MemoryFeedbackParams, MemoryFeedbackResult, MemorySupersedeResult, MemoryUpdateResult},
   service::util::{Resolver, ServiceError},
 };
 
@@ -49,6 +52,12 @@ pub async fn reinforce(
 
   // Calculate expected new salience for response (approximate, may differ slightly due to race)
   let new_salience = (memory.salience + amount * (1.0 - memory.salience)).min(1.0);
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::Reinforced)
+        .with_salience(Some(memory.salience), Some(new_salience)),
+    )
+    .await;
 
   Ok(MemoryUpdateResult {
     id: memory.id.to_string(),
@@ -93,6 +102,12 @@ pub async fn deemphasize(
 
   // Calculate expected new salience for response (approximate, may differ slightly due to race)
   let new_salience = (memory.salience - amount).max(0.05);
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::Deemphasized)
+        .with_salience(Some(memory.salience), Some(new_salience)),
+    )
+    .await;
 
   Ok(MemoryUpdateResult {
     id: memory.id.to_string(),
@@ -135,6 +150,13 @@ pub async fn supersede(
 
   // Atomic update - marks old memory as superseded
   ctx.db.supersede_memory(&old_memory.id, &new_memory.id).await?;
+  ctx
+    .record(
+      MemoryChange::new(old_memory.id, MemoryEventAction::Superseded)
+        .with_salience(Some(old_memory.salience), Some(old_memory.salience))
+        .with_detail(new_memory.id.to_string()),
+    )
+    .await;
 
   Ok(MemorySupersedeResult {
     old_id: old_memory.id.to_string(),
@@ -197,6 +219,11 @@ pub async fn set_salience(
 
   // Atomic update - no read-modify-write race
   ctx.db.set_memory_salience(&memory.id, salience).await?;
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::SalienceSet).with_salience(Some(memory.salience), Some(salience)),
+    )
+    .await;
 
   Ok(MemoryUpdateResult {
     id: memory.id.to_string(),
@@ -204,3 +231,49 @@ pub async fn set_salience(
     message: "Salience updated".to_string(),
   })
 }
+
+/// Record whether a memory returned by a search was helpful or irrelevant.
+///
+/// Feedback accumulates into the memory's feedback score, which ranking adds
+/// with `search.feedback_weight`, so memories marked helpful rise in later
+/// searches and irrelevant ones sink. Salience is left alone.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Memory ID or prefix, the verdict, and the query it was returned for
+///
+/// # Returns
+/// * `Ok(MemoryFeedbackResult)` - Feedback counts and the new score
+/// * `Err(ServiceError)` - If memory not found or update fails
+pub async fn feedback(
+  ctx: &MemoryContext<'_>,
+  params: MemoryFeedbackParams,
+) -> Result<MemoryFeedbackResult, ServiceError> {
+  let mut memory = Resolver::memory(ctx.db, &params.memory_id).await?;
+
+  ctx.db.record_memory_feedback(&memory.id, params.helpful).await?;
+
+  // Expected counts for the response (may differ slightly due to race)
+  let verdict = if params.helpful {
+    memory.helpful_count += 1;
+    "helpful"
+  } else {
+    memory.irrelevant_count += 1;
+    "irrelevant"
+  };
+  let detail = match params.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+    Some(query) => format!("{} for \"{}\"", verdict, query),
+    None => verdict.to_string(),
+  };
+  ctx
+    .record(MemoryChange::new(memory.id, MemoryEventAction::Feedback).with_detail(detail))
+    .await;
+
+  Ok(MemoryFeedbackResult {
+    id: memory.id.to_string(),
+    helpful_count: memory.helpful_count,
+    irrelevant_count: memory.irrelevant_count,
+    feedback_score: memory.feedback_score(),
+    message: format!("Memory marked {}", verdict),
+  })
+}
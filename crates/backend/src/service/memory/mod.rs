@@ -17,43 +17,70 @@
 //! - [`get`] - Get a memory by ID or prefix
 //! - [`list`] - List memories with filters
 //! - [`delete`] - Soft or hard delete a memory
+//! - [`archive`] - Soft delete several memories as one batch
 //! - [`restore`] - Restore a soft-deleted memory
-//! - [`lifecycle`] - Reinforce, deemphasize, and supersede operations
+//! - [`history`] - Mutation history recorded for a memory
+//! - [`undo`] - Revert the most recent batch of deletes and supersessions
+//! - [`lifecycle`] - Reinforce, deemphasize, supersede, and search feedback operations
 //! - [`relationship`] - Add, delete, and list memory relationships
-
+//! - [`entity`] - Link memories to named entities and rank the entities
+//! - [`goals`] - Goal status tracking and completion matching
+//! - [`retro`] - Retrospectives comparing a task or goal's plan with its outcome
+//! - [`tags`] - List, rename, merge and delete tags across memories
+//! - [`anchor`] - Git blame anchors for files a memory references
+//! - [`stale`] - Validate codebase memories against the code index
+//! - [`sync`] - Share team memories through a git-tracked directory
+
+pub mod anchor;
 mod dedup;
 mod lifecycle;
 mod ranking;
 pub mod search;
+pub mod stale;
+pub mod sync;
+mod undo;
 
+pub mod entity;
+pub mod goals;
 pub mod relationship;
+pub mod retro;
+pub mod tags;
 
-use std::collections::HashSet;
+use std::{collections::HashSet, path::Path};
 
 use chrono::Utc;
-use tracing::debug;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 pub use self::{
-  dedup::check_duplicate,
-  lifecycle::{deemphasize, reinforce, set_salience, supersede},
-  ranking::RankingConfig,
+  dedup::{check_duplicate, find_duplicate},
+  lifecycle::{deemphasize, feedback, reinforce, set_salience, supersede},
+  ranking::{RankingConfig, rank_memories},
   search::search,
+  undo::undo,
 };
-use super::util::{FilterBuilder, Resolver};
+use super::util::Resolver;
 pub use crate::context::memory::extract::decay::{DecayStats, MemoryDecay};
 use crate::{
-  context::memory::extract::{
-    classifier::{extract_concepts, extract_files},
-    dedup::compute_hashes,
+  context::{
+    memory::extract::{
+      classifier::{extract_concepts, extract_files},
+      dedup::compute_hashes,
+    },
+    redact::Redactor,
+  },
+  db::{FilterBuilder, MemoryChange, ProjectDb},
+  domain::{
+    alias::Aliases,
+    config::VisibilityConfig,
+    memory::{GoalStatus, Memory, MemoryActor, MemoryEventAction, MemoryType, Sector, Visibility},
+    path,
   },
-  db::ProjectDb,
-  domain::memory::{Memory, MemoryType, Sector},
   embedding::EmbeddingProvider,
   ipc::types::memory::{
-    MemoryAddParams, MemoryAddResult, MemoryFullDetail, MemoryGetParams, MemoryItem, MemoryListParams,
-    MemoryRelatedItem, MemoryRelatedParams, MemoryRelatedResult, MemoryRelationshipItem, MemoryTimelineItem,
-    MemoryTimelineResult,
+    MemoryAddParams, MemoryAddResult, MemoryEventItem, MemoryFullDetail, MemoryGetParams, MemoryHistoryParams,
+    MemoryHistoryResult, MemoryItem, MemoryListParams, MemoryRelatedItem, MemoryRelatedParams, MemoryRelatedResult,
+    MemoryRelationshipItem, MemoryTimelineItem, MemoryTimelineResult,
   },
   service::util::ServiceError,
 };
@@ -68,6 +95,16 @@ pub struct MemoryContext<'a> {
   pub embedding: &'a dyn EmbeddingProvider,
   /// Project ID for new memories
   pub project_id: Uuid,
+  /// Project root, used to anchor referenced files with git blame
+  pub root: Option<&'a Path>,
+  /// Project jargon, for query expansion and tag and entity normalization
+  pub aliases: Option<&'a Aliases>,
+  /// Default visibility of new memories per type
+  pub visibility: Option<&'a VisibilityConfig>,
+  /// Secrets scrubbed from new memories before storage
+  pub redactor: Option<&'a Redactor>,
+  /// Who is making changes, recorded in each memory's history
+  pub actor: MemoryActor,
 }
 
 impl<'a> MemoryContext<'a> {
@@ -77,9 +114,49 @@ impl<'a> MemoryContext<'a> {
       db,
       embedding,
       project_id,
+      root: None,
+      aliases: None,
+      visibility: None,
+      redactor: None,
+      actor: MemoryActor::default(),
     }
   }
 
+  /// Anchor referenced files to git history under `root`
+  pub fn with_root(mut self, root: &'a Path) -> Self {
+    self.root = Some(root);
+    self
+  }
+
+  /// Expand queries and normalize tags and entities with the project's aliases
+  pub fn with_aliases(mut self, aliases: &'a Aliases) -> Self {
+    self.aliases = Some(aliases);
+    self
+  }
+
+  /// Give new memories that don't set a visibility the default for their type
+  pub fn with_visibility(mut self, visibility: &'a VisibilityConfig) -> Self {
+    self.visibility = Some(visibility);
+    self
+  }
+
+  /// Replace secrets in new memories with placeholders
+  pub fn with_redactor(mut self, redactor: &'a Redactor) -> Self {
+    self.redactor = Some(redactor);
+    self
+  }
+
+  /// Record changes in memory history as made by `actor`
+  pub fn with_actor(mut self, actor: MemoryActor) -> Self {
+    self.actor = actor;
+    self
+  }
+
+  /// Record a change in the memory's history
+  async fn record(&self, change: MemoryChange) {
+    record_history(self.db, self.actor, &[change]).await;
+  }
+
   /// Get an embedding for the given text, if a provider is available
   async fn get_embedding(&self, text: &str) -> Result<Vec<f32>, ServiceError> {
     // Query mode - this is used for memory search queries
@@ -96,6 +173,14 @@ impl<'a> MemoryContext<'a> {
 // Core Operations
 // ============================================================================
 
+/// Record changes in memory history, logging instead of failing the change
+/// itself when the history can't be written
+pub async fn record_history(db: &ProjectDb, actor: MemoryActor, changes: &[MemoryChange]) {
+  if let Err(e) = db.record_memory_events(actor, changes).await {
+    warn!(actor = actor.as_str(), "Failed to record memory history: {}", e);
+  }
+}
+
 /// Add a new memory with duplicate detection.
 ///
 /// # Arguments
@@ -105,7 +190,7 @@ impl<'a> MemoryContext<'a> {
 /// # Returns
 /// * `Ok(MemoryAddResult)` - Result with the new or existing (if duplicate) memory ID
 /// * `Err(ServiceError)` - If validation or database operation fails
-pub async fn add(ctx: &MemoryContext<'_>, params: MemoryAddParams) -> Result<MemoryAddResult, ServiceError> {
+pub async fn add(ctx: &MemoryContext<'_>, mut params: MemoryAddParams) -> Result<MemoryAddResult, ServiceError> {
   // Validate content length
   if params.content.len() < 5 {
     return Err(ServiceError::validation("Content too short (min 5 chars)"));
@@ -113,6 +198,9 @@ pub async fn add(ctx: &MemoryContext<'_>, params: MemoryAddParams) -> Result<Mem
   if params.content.len() > 32000 {
     return Err(ServiceError::validation("Content too long (max 32000 chars)"));
   }
+  if let Some(redactor) = ctx.redactor {
+    params.content = redactor.redact_string(params.content);
+  }
 
   // Parse sector
   let sector = params
@@ -124,6 +212,29 @@ pub async fn add(ctx: &MemoryContext<'_>, params: MemoryAddParams) -> Result<Mem
   // Parse memory type
   let memory_type = params.memory_type.as_deref().and_then(|t| t.parse::<MemoryType>().ok());
 
+  let visibility = match params.visibility.as_deref() {
+    Some(v) => v.parse::<Visibility>().map_err(ServiceError::validation)?,
+    None => ctx
+      .visibility
+      .map(|config| config.for_type(memory_type))
+      .unwrap_or_default(),
+  };
+
+  let goal_status = match (memory_type, params.status.as_deref()) {
+    (Some(MemoryType::Goal), status) => Some(
+      status
+        .map(str::parse::<GoalStatus>)
+        .transpose()
+        .map_err(ServiceError::validation)?
+        .unwrap_or_default(),
+    ),
+    (_, Some(_)) => return Err(ServiceError::validation("Only goal memories have a status")),
+    (_, None) => None,
+  };
+  if params.due.is_some() && goal_status.is_none() {
+    return Err(ServiceError::validation("Only goal memories have a due hint"));
+  }
+
   // Compute hashes for deduplication
   let (content_hash, simhash) = compute_hashes(&params.content);
 
@@ -146,20 +257,32 @@ pub async fn add(ctx: &MemoryContext<'_>, params: MemoryAddParams) -> Result<Mem
   // Extract concepts and files from content
   memory.concepts = extract_concepts(&params.content);
   memory.files = extract_files(&params.content);
+  if let Some(root) = ctx.root {
+    memory.file_anchors = anchor::anchor_files(root, &memory.files).await;
+  }
 
   // Apply optional fields
   memory.memory_type = memory_type;
+  memory.visibility = visibility;
+  memory.goal_status = goal_status;
+  memory.due = params.due.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+  if stale::tracks_staleness(memory_type) {
+    memory.code_refs = stale::record_code_refs(ctx.db, &memory.files, &memory.concepts).await;
+  }
   if let Some(ctx_str) = params.context {
     memory.context = Some(ctx_str);
   }
   if let Some(tags) = params.tags {
-    memory.tags = tags;
+    memory.tags = match ctx.aliases {
+      Some(aliases) => aliases.normalize_tags(tags),
+      None => tags,
+    };
   }
   if let Some(categories) = params.categories {
     memory.categories = categories;
   }
   if let Some(scope_path) = params.scope_path {
-    memory.scope_path = Some(scope_path);
+    memory.scope_path = Some(path::normalize(&scope_path));
   }
   if let Some(scope_module) = params.scope_module {
     memory.scope_module = Some(scope_module);
@@ -173,6 +296,17 @@ pub async fn add(ctx: &MemoryContext<'_>, params: MemoryAddParams) -> Result<Mem
 
   // Store in database
   ctx.db.add_memory(&memory, &vector).await?;
+  ctx
+    .record(MemoryChange::new(memory.id, MemoryEventAction::Added).with_salience(None, Some(memory.salience)))
+    .await;
+  if let Err(e) = entity::link(ctx.db, &memory, &[], ctx.aliases).await {
+    warn!(memory_id = %memory.id, "Failed to link memory entities: {}", e);
+  }
+  if memory_type == Some(MemoryType::TaskCompletion)
+    && let Err(e) = goals::complete_matching(ctx.db, &memory, &vector).await
+  {
+    warn!(memory_id = %memory.id, "Failed to match task completion to goals: {}", e);
+  }
 
   Ok(MemoryAddResult {
     id: memory.id.to_string(),
@@ -221,6 +355,12 @@ pub async fn get(ctx: &MemoryContext<'_>, params: MemoryGetParams) -> Result<Mem
     detail = detail.with_relationships(relationships);
   }
 
+  if let Some(root) = ctx.root
+    && !memory.file_anchors.is_empty()
+  {
+    detail = detail.with_file_anchors(anchor::check_anchors(root, &memory.file_anchors).await);
+  }
+
   Ok(detail)
 }
 
@@ -275,10 +415,59 @@ pub async fn delete(ctx: &MemoryContext<'_>, memory_id: &str) -> Result<Memory,
   let mut memory = Resolver::memory(ctx.db, memory_id).await?;
   memory.delete(Utc::now());
   ctx.db.update_memory(&memory, None).await?;
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::Deleted)
+        .with_salience(Some(memory.salience), Some(memory.salience)),
+    )
+    .await;
 
   Ok(memory)
 }
 
+/// Soft-delete several memories as one batch, so [`undo`] restores them together.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `memory_ids` - IDs or prefixes of the memories to archive
+///
+/// # Returns
+/// * `Ok(Vec<Memory>)` - The archived memories; ones already deleted are skipped
+/// * `Err(ServiceError)` - If a memory is not found or database error
+pub async fn archive(ctx: &MemoryContext<'_>, memory_ids: &[String]) -> Result<Vec<Memory>, ServiceError> {
+  // Resolve every ID first, so a typo doesn't leave the batch half done
+  let mut memories = Vec::with_capacity(memory_ids.len());
+  for memory_id in memory_ids {
+    memories.push(Resolver::memory(ctx.db, memory_id).await?);
+  }
+
+  let now = Utc::now();
+  let mut archived = Vec::new();
+  let mut result = Ok(());
+  for mut memory in memories.into_iter().filter(|m| !m.is_deleted) {
+    memory.delete(now);
+    if let Err(e) = ctx.db.update_memory(&memory, None).await {
+      result = Err(e);
+      break;
+    }
+    archived.push(memory);
+  }
+
+  // Record whatever was archived, even when a later update failed
+  let changes: Vec<MemoryChange> = archived
+    .iter()
+    .map(|memory| {
+      MemoryChange::new(memory.id, MemoryEventAction::Deleted)
+        .with_salience(Some(memory.salience), Some(memory.salience))
+        .with_detail("archive")
+    })
+    .collect();
+  record_history(ctx.db, ctx.actor, &changes).await;
+  result?;
+
+  Ok(archived)
+}
+
 /// Hard-delete a memory permanently.
 ///
 /// # Arguments
@@ -291,6 +480,9 @@ pub async fn delete(ctx: &MemoryContext<'_>, memory_id: &str) -> Result<Memory,
 pub async fn hard_delete(ctx: &MemoryContext<'_>, memory_id: &str) -> Result<String, ServiceError> {
   let memory = Resolver::memory(ctx.db, memory_id).await?;
   ctx.db.delete_memory(&memory.id).await?;
+  ctx
+    .record(MemoryChange::new(memory.id, MemoryEventAction::HardDeleted).with_salience(Some(memory.salience), None))
+    .await;
 
   Ok(memory.id.to_string())
 }
@@ -313,6 +505,9 @@ pub async fn restore(ctx: &MemoryContext<'_>, memory_id: &str) -> Result<Memory,
 
   memory.restore(Utc::now());
   ctx.db.update_memory(&memory, None).await?;
+  ctx
+    .record(MemoryChange::new(memory.id, MemoryEventAction::Restored))
+    .await;
 
   Ok(memory)
 }
@@ -365,10 +560,10 @@ pub async fn related(
 
   // Method 2: Shared concepts
   for concept in &memory.concepts {
-    let filter = format!(
-      "is_deleted = false AND concepts LIKE '%{}%'",
-      concept.replace('\'', "''")
-    );
+    let filter = FilterBuilder::new()
+      .exclude_deleted()
+      .add_like("concepts", concept)
+      .build_or_empty();
     if let Ok(matches) = ctx.db.list_memories(Some(&filter), Some(5)).await {
       for m in matches {
         if seen_ids.insert(m.id) {
@@ -398,7 +593,9 @@ pub async fn related(
   }
 
   // Find memories this one supersedes
-  let filter = format!("superseded_by = '{}'", memory.id);
+  let filter = FilterBuilder::new()
+    .add_eq("superseded_by", &memory.id.to_string())
+    .build_or_empty();
   if let Ok(superseded) = ctx.db.list_memories(Some(&filter), Some(5)).await {
     for m in superseded {
       if seen_ids.insert(m.id) {
@@ -458,37 +655,67 @@ pub async fn timeline(
   // Build timeline item for anchor
   let anchor = MemoryTimelineItem::from(&memory);
 
-  // Get memories before
-  let before_filter = format!(
-    "is_deleted = false AND created_at < '{}' ORDER BY created_at DESC",
-    memory.created_at.to_rfc3339()
-  );
-  let before: Vec<MemoryTimelineItem> = ctx
+  // Get memories before, nearest first. Filters can't order rows, so all
+  // candidates are fetched and sorted here before truncating.
+  let before_filter = FilterBuilder::new()
+    .exclude_deleted()
+    .add_before("created_at", memory.created_at)
+    .build_or_empty();
+  let mut before = ctx
     .db
-    .list_memories(Some(&before_filter), Some(before_count))
+    .list_memories(Some(&before_filter), None)
     .await
-    .unwrap_or_default()
-    .into_iter()
-    .map(|m| MemoryTimelineItem::from(&m))
-    .collect();
+    .unwrap_or_default();
+  before.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+  let before: Vec<MemoryTimelineItem> = before.iter().take(before_count).map(MemoryTimelineItem::from).collect();
 
-  // Get memories after
-  let after_filter = format!(
-    "is_deleted = false AND created_at > '{}' ORDER BY created_at ASC",
-    memory.created_at.to_rfc3339()
-  );
-  let after: Vec<MemoryTimelineItem> = ctx
+  // Get memories after, nearest first
+  let after_filter = FilterBuilder::new()
+    .exclude_deleted()
+    .add_after("created_at", memory.created_at)
+    .build_or_empty();
+  let mut after = ctx
     .db
-    .list_memories(Some(&after_filter), Some(after_count))
+    .list_memories(Some(&after_filter), None)
     .await
-    .unwrap_or_default()
-    .into_iter()
-    .map(|m| MemoryTimelineItem::from(&m))
-    .collect();
+    .unwrap_or_default();
+  after.sort_by_key(|m| m.created_at);
+  let after: Vec<MemoryTimelineItem> = after.iter().take(after_count).map(MemoryTimelineItem::from).collect();
 
   Ok(MemoryTimelineResult { anchor, before, after })
 }
 
+/// Get the mutation history of a memory.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Memory ID or prefix; a hard-deleted memory needs its full ID
+///
+/// # Returns
+/// * `Ok(MemoryHistoryResult)` - Recorded changes, oldest first
+/// * `Err(ServiceError)` - If memory not found or database error
+pub async fn history(
+  ctx: &MemoryContext<'_>,
+  params: MemoryHistoryParams,
+) -> Result<MemoryHistoryResult, ServiceError> {
+  let (memory_id, exists) = match Resolver::memory(ctx.db, &params.memory_id).await {
+    Ok(memory) => (memory.id.to_string(), true),
+    // Events outlive hard deletes, so a full ID still finds them
+    Err(ServiceError::NotFound { .. }) if Uuid::parse_str(&params.memory_id).is_ok() => (params.memory_id, false),
+    Err(e) => return Err(e),
+  };
+
+  let events = ctx.db.get_memory_events(&memory_id).await?;
+  if !exists && events.is_empty() {
+    return Err(ServiceError::not_found("memory", memory_id));
+  }
+
+  Ok(MemoryHistoryResult {
+    events: events.iter().map(MemoryEventItem::from).collect(),
+    memory_id,
+  })
+}
+
 /// Apply decay to all memories in the database.
 ///
 /// This is called periodically by the scheduler to gradually reduce
@@ -5,6 +5,7 @@
 //! - Salience score (memory importance over time)
 //! - Recency (time since last access)
 //! - Sector boost (different sectors have different importance)
+//! - Search feedback (thumbs up/down from earlier searches)
 
 use chrono::Utc;
 
@@ -22,6 +23,9 @@ pub struct RankingWeights {
   pub salience: f32,
   /// Weight for recency score (0.0 to 1.0)
   pub recency: f32,
+  /// Weight for the feedback score (-1.0 to 1.0). Not part of the sum above:
+  /// memories without feedback score 0 and are unaffected.
+  pub feedback: f32,
 }
 
 impl Default for RankingWeights {
@@ -30,6 +34,7 @@ impl Default for RankingWeights {
       semantic: 0.5,
       salience: 0.3,
       recency: 0.2,
+      feedback: 0.1,
     }
   }
 }
@@ -40,6 +45,7 @@ impl From<&SearchConfig> for RankingWeights {
       semantic: config.semantic_weight as f32,
       salience: config.salience_weight as f32,
       recency: config.recency_weight as f32,
+      feedback: config.feedback_weight as f32,
     }
   }
 }
@@ -74,7 +80,7 @@ impl From<&SearchConfig> for RankingConfig {
   }
 }
 
-/// Rank memories by combining vector similarity with salience, recency, and sector boosts.
+/// Rank memories by combining vector similarity with salience, recency, feedback, and sector boosts.
 ///
 /// # Arguments
 /// * `results` - Vector search results as (Memory, distance) tuples
@@ -90,7 +96,9 @@ impl From<&SearchConfig> for RankingConfig {
 /// ```text
 /// similarity = 1.0 - min(distance, 1.0)
 /// recency = exp(-decay_factor * days_since_last_access)
+/// feedback = (helpful - irrelevant) / (helpful + irrelevant + 2)
 /// base_score = (semantic_weight * similarity) + (salience_weight * salience) + (recency_weight * recency)
+///   + (feedback_weight * feedback)
 /// rank_score = base_score * sector_boost * supersession_penalty
 /// ```
 ///
@@ -127,10 +135,12 @@ pub fn rank_memories(
       };
 
       // Combined rank score
-      let rank_score =
-        (weights.semantic * similarity + weights.salience * m.salience + weights.recency * recency_score)
-          * sector_boost
-          * supersession_penalty;
+      let rank_score = (weights.semantic * similarity
+        + weights.salience * m.salience
+        + weights.recency * recency_score
+        + weights.feedback * m.feedback_score())
+        * sector_boost
+        * supersession_penalty;
 
       (m, distance, rank_score)
     })
@@ -199,6 +209,22 @@ mod tests {
     assert_eq!(ranked[1].0.sector, Sector::Episodic);
   }
 
+  #[test]
+  fn test_rank_memories_feedback() {
+    let mut helpful = create_test_memory(Sector::Semantic, 0.5, false);
+    helpful.helpful_count = 3;
+    let mut irrelevant = create_test_memory(Sector::Semantic, 0.5, false);
+    irrelevant.irrelevant_count = 3;
+    let plain = create_test_memory(Sector::Semantic, 0.5, false);
+
+    let results = vec![(irrelevant, 0.1), (plain, 0.1), (helpful, 0.1)];
+    let ranked = rank_memories(results, 3, None);
+
+    assert_eq!(ranked[0].0.helpful_count, 3);
+    assert_eq!((ranked[1].0.helpful_count, ranked[1].0.irrelevant_count), (0, 0));
+    assert_eq!(ranked[2].0.irrelevant_count, 3);
+  }
+
   #[test]
   fn test_rank_memories_limit() {
     let memories: Vec<_> = (0..10)
@@ -0,0 +1,308 @@
+//! Retrospectives.
+//!
+//! A retrospective looks back on a task or goal once the work is done. The
+//! memories that planned it, the task completions that finished parts of it,
+//! the gotchas hit along the way and the decisions made are gathered from the
+//! subject's relationships and from memories similar to it, and the LLM
+//! compares plan with outcome. The result is stored as a reflective memory
+//! linked to the subject, so later work on similar tasks recalls its lessons.
+
+use std::collections::HashSet;
+
+use tracing::{debug, warn};
+
+use super::{MemoryContext, add, search::search_by_embedding};
+use crate::{
+  db::{FilterBuilder, ProjectDb},
+  domain::memory::{Memory, MemoryId, MemoryType, RelationshipType},
+  ipc::types::memory::{MemoryAddParams, RetroParams, RetroResult},
+  service::util::{Resolver, ServiceError},
+};
+
+/// Similar memories considered alongside the subject's relationships
+const SIMILAR_CANDIDATES: usize = 30;
+
+/// Similarity above which a memory is taken to be about the same work
+const SIMILAR_MIN_SIMILARITY: f32 = 0.6;
+
+/// Memories given to the LLM per group
+const MAX_PER_GROUP: usize = 10;
+
+/// Tag marking memories that describe a plan
+const PLAN_TAG: &str = "plan";
+
+/// Tag given to stored retrospectives
+const RETRO_TAG: &str = "retro";
+
+/// What part of the work a memory records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+  Plan,
+  Completion,
+  Error,
+  Decision,
+}
+
+/// The group of `memory`, or `None` when it isn't part of a retrospective
+fn group(memory: &Memory) -> Option<Group> {
+  if memory.tags.iter().any(|t| t == RETRO_TAG) {
+    return None;
+  }
+  if memory.tags.iter().any(|t| t == PLAN_TAG) {
+    return Some(Group::Plan);
+  }
+  match memory.memory_type? {
+    MemoryType::Goal => Some(Group::Plan),
+    MemoryType::TaskCompletion => Some(Group::Completion),
+    MemoryType::Gotcha => Some(Group::Error),
+    MemoryType::Decision => Some(Group::Decision),
+    _ => None,
+  }
+}
+
+/// Current memories linked to or similar to `subject`, most relevant first
+async fn gather(db: &ProjectDb, subject: &Memory) -> Result<Vec<Memory>, ServiceError> {
+  let mut seen = HashSet::from([subject.id]);
+  let mut memories = Vec::new();
+
+  for relationship in db.get_all_relationships(&subject.id).await? {
+    let other = if relationship.from_memory_id == subject.id {
+      relationship.to_memory_id
+    } else {
+      relationship.from_memory_id
+    };
+    if !seen.insert(other) {
+      continue;
+    }
+    if let Some(memory) = db.get_memory(&other).await?
+      && !memory.is_deleted
+      && memory.superseded_by.is_none()
+    {
+      memories.push(memory);
+    }
+  }
+
+  if let Some(vector) = db.get_memory_embedding(&subject.id).await? {
+    let filter = FilterBuilder::new().exclude_superseded().build();
+    for (memory, distance) in search_by_embedding(db, &vector, SIMILAR_CANDIDATES, filter.as_deref()).await? {
+      let similarity = 1.0 - distance.min(1.0);
+      if similarity >= SIMILAR_MIN_SIMILARITY && seen.insert(memory.id) {
+        memories.push(memory);
+      }
+    }
+  }
+
+  Ok(memories)
+}
+
+/// Split `memories` into the retrospective's groups, each capped and oldest first
+fn build_input(subject: &Memory, memories: &[Memory]) -> llm::RetroInput {
+  let pick = |wanted: Group| {
+    let mut picked: Vec<&Memory> = memories
+      .iter()
+      .filter(|m| group(m) == Some(wanted))
+      .take(MAX_PER_GROUP)
+      .collect();
+    picked.sort_by_key(|m| m.created_at);
+    picked.into_iter().map(|m| m.content.clone()).collect()
+  };
+
+  llm::RetroInput {
+    subject: subject.content.clone(),
+    plan: pick(Group::Plan),
+    completions: pick(Group::Completion),
+    errors: pick(Group::Error),
+    decisions: pick(Group::Decision),
+  }
+}
+
+/// Render a retrospective as the content of its memory
+fn render(subject: &Memory, retro: &llm::Retrospective) -> String {
+  let mut content = format!(
+    "Retrospective: {}\n\n{}\n",
+    subject.content.lines().next().unwrap_or_default().trim(),
+    retro.summary
+  );
+  let sections = [
+    ("Went well", &retro.went_well),
+    ("Went wrong", &retro.went_wrong),
+    ("Deviations from plan", &retro.deviations),
+    ("Lessons", &retro.lessons),
+  ];
+  for (title, items) in sections {
+    if items.is_empty() {
+      continue;
+    }
+    content.push_str(&format!("\n{}:\n", title));
+    for item in items {
+      content.push_str(&format!("- {}\n", item));
+    }
+  }
+  content.trim_end().to_string()
+}
+
+/// Write a retrospective for a task or goal and store it as a reflective memory.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database and embedding provider
+/// * `llm` - LLM provider that writes the retrospective
+/// * `params` - Task completion or goal ID or prefix
+///
+/// # Returns
+/// * `Ok(RetroResult)` - The stored retrospective and the memories it was written from
+/// * `Err(ServiceError)` - If no LLM is available, the memory is not a task or goal, or database error
+pub async fn write(
+  ctx: &MemoryContext<'_>,
+  llm: Option<&dyn llm::LlmProvider>,
+  params: RetroParams,
+) -> Result<RetroResult, ServiceError> {
+  let Some(llm) = llm else {
+    return Err(ServiceError::validation("Retrospectives require an LLM provider"));
+  };
+
+  let subject = Resolver::memory(ctx.db, &params.id).await?;
+  if !matches!(subject.memory_type, Some(MemoryType::Goal | MemoryType::TaskCompletion)) {
+    return Err(ServiceError::validation(format!(
+      "Memory {} is not a task completion or goal",
+      subject.id
+    )));
+  }
+
+  let memories = gather(ctx.db, &subject).await?;
+  let input = build_input(&subject, &memories);
+  debug!(
+    subject_id = %subject.id,
+    plan = input.plan.len(),
+    completions = input.completions.len(),
+    errors = input.errors.len(),
+    decisions = input.decisions.len(),
+    "Gathered retrospective material"
+  );
+
+  let retro = llm::extraction::write_retrospective(llm, &input).await?;
+  let added = add(
+    ctx,
+    MemoryAddParams {
+      content: render(&subject, &retro),
+      sector: Some("reflective".to_string()),
+      memory_type: None,
+      context: Some(format!("Retrospective of {}", subject.id)),
+      tags: Some(vec![RETRO_TAG.to_string()]),
+      categories: None,
+      scope_path: subject.scope_path.clone(),
+      scope_module: subject.scope_module.clone(),
+      importance: None,
+      visibility: None,
+      status: None,
+      due: None,
+    },
+  )
+  .await?;
+
+  if !added.is_duplicate
+    && let Ok(retro_id) = added.id.parse::<MemoryId>()
+    && let Err(e) = ctx
+      .db
+      .create_relationship(&retro_id, &subject.id, RelationshipType::BuildsOn, 1.0, "retro")
+      .await
+  {
+    warn!(memory_id = %retro_id, "Failed to link retrospective to its subject: {}", e);
+  }
+
+  Ok(RetroResult {
+    id: added.id,
+    subject_id: subject.id.to_string(),
+    summary: retro.summary,
+    went_well: retro.went_well,
+    went_wrong: retro.went_wrong,
+    deviations: retro.deviations,
+    lessons: retro.lessons,
+    plan: input.plan.len(),
+    completions: input.completions.len(),
+    errors: input.errors.len(),
+    decisions: input.decisions.len(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn memory(content: &str, memory_type: Option<MemoryType>, tags: &[&str], age_days: i64) -> Memory {
+    let mut memory = Memory::new(Uuid::nil(), content.to_string(), Sector::Semantic);
+    memory.memory_type = memory_type;
+    memory.tags = tags.iter().map(|t| t.to_string()).collect();
+    memory.created_at -= Duration::days(age_days);
+    memory
+  }
+
+  #[test]
+  fn test_build_input_groups_memories() {
+    let subject = memory("Add rate limiting to the API", Some(MemoryType::Goal), &[], 10);
+    let memories = vec![
+      memory("Limit per API key, not per IP", Some(MemoryType::Decision), &[], 6),
+      memory(
+        "Rate limiter added as tower middleware",
+        Some(MemoryType::TaskCompletion),
+        &[],
+        2,
+      ),
+      memory(
+        "Redis INCR with EXPIRE races on first hit",
+        Some(MemoryType::Gotcha),
+        &[],
+        4,
+      ),
+      memory("Step 1: token bucket in Redis", None, &["plan"], 9),
+      memory(
+        "Burst limit added to the middleware",
+        Some(MemoryType::TaskCompletion),
+        &[],
+        1,
+      ),
+      memory("Retrospective: an earlier attempt", None, &["retro"], 20),
+      memory("API handlers live in src/api", Some(MemoryType::Codebase), &[], 30),
+    ];
+
+    let input = build_input(&subject, &memories);
+    assert_eq!(input.subject, "Add rate limiting to the API");
+    assert_eq!(
+      input.plan,
+      vec!["Step 1: token bucket in Redis"],
+      "retros and codebase facts are left out"
+    );
+    assert_eq!(
+      input.completions,
+      vec![
+        "Rate limiter added as tower middleware",
+        "Burst limit added to the middleware"
+      ],
+      "oldest first"
+    );
+    assert_eq!(input.errors.len(), 1);
+    assert_eq!(input.decisions.len(), 1);
+  }
+
+  #[test]
+  fn test_render_skips_empty_sections() {
+    let subject = memory("Add rate limiting to the API", Some(MemoryType::Goal), &[], 0);
+    let retro = llm::Retrospective {
+      summary: "Shipped as planned.".to_string(),
+      went_well: vec!["Middleware kept handlers unchanged".to_string()],
+      went_wrong: Vec::new(),
+      deviations: Vec::new(),
+      lessons: vec!["Test the first-hit path of Redis counters".to_string()],
+    };
+
+    assert_eq!(
+      render(&subject, &retro),
+      "Retrospective: Add rate limiting to the API\n\nShipped as planned.\n\n\
+       Went well:\n- Middleware kept handlers unchanged\n\n\
+       Lessons:\n- Test the first-hit path of Redis counters"
+    );
+  }
+}
@@ -16,13 +16,14 @@ use tracing::{debug, warn};
 
 use super::{MemoryContext, RankingConfig, ranking};
 use crate::{
-  domain::config::Config,
+  db::FilterBuilder,
+  domain::{config::Config, path},
   ipc::types::{
     code::SearchQuality,
-    memory::{MemoryItem, MemorySearchParams},
+    memory::{MemoryGroup, MemoryGroupBy, MemoryItem, MemorySearchParams},
   },
   rerank::{RerankCandidate, RerankRequest, RerankerProvider},
-  service::util::{FilterBuilder, ServiceError, fusion},
+  service::util::{ResolveError, Resolver, ServiceError, fusion, normalize_id},
 };
 
 /// Result of a memory search operation.
@@ -31,8 +32,13 @@ pub struct SearchResult {
   pub items: Vec<MemoryItem>,
   /// Search quality metadata
   pub search_quality: SearchQuality,
+  /// Results grouped by `group_by`; `items` is empty when set
+  pub groups: Option<Vec<MemoryGroup>>,
 }
 
+/// Top hits kept per group when `per_group` is not given
+const DEFAULT_PER_GROUP: usize = 3;
+
 /// Extended search parameters with internal config.
 pub struct SearchParams {
   /// Base parameters from the request
@@ -66,6 +72,25 @@ pub async fn search(
   let params = params.into();
   let base = params.base;
 
+  let group_by = base
+    .group_by
+    .as_deref()
+    .map(|g| g.parse::<MemoryGroupBy>())
+    .transpose()
+    .map_err(ServiceError::validation)?;
+  let per_group = base.per_group.unwrap_or(DEFAULT_PER_GROUP).max(1);
+
+  // Pruned sessions still tag memories, so IDs that don't resolve to a known
+  // session filter by the normalized input as an exact ID
+  let session_id = match base.session_id.as_deref() {
+    Some(id) => match Resolver::session(ctx.db, id).await {
+      Ok(full) => Some(full),
+      Err(ResolveError::NotFound { .. } | ResolveError::InvalidInput(_)) => Some(normalize_id(id)),
+      Err(e) => return Err(e.into()),
+    },
+    None => None,
+  };
+
   // Build filter from parameters
   let filter = FilterBuilder::new()
     .exclude_inactive(base.include_superseded)
@@ -73,20 +98,26 @@ pub async fn search(
     .add_eq_opt("tier", base.tier.as_deref())
     .add_eq_opt("memory_type", base.memory_type.as_deref())
     .add_min_opt("salience", base.min_salience)
-    .add_prefix_opt("scope_path", base.scope_path.as_deref())
+    .add_prefix_opt("scope_path", base.scope_path.as_deref().map(path::normalize).as_deref())
     .add_eq_opt("scope_module", base.scope_module.as_deref())
-    .add_eq_opt("session_id", base.session_id.as_deref())
+    .add_eq_opt("session_id", session_id.as_deref())
     .build();
 
+  // Grouped searches rank enough memories to fill `limit` groups
   let limit = base.limit.unwrap_or(config.search.default_limit);
-  let fetch_limit = limit * 2;
+  let rank_limit = if group_by.is_some() { limit * per_group } else { limit };
+  let fetch_limit = rank_limit * 2;
 
   let ranking_config = params
     .ranking_config
     .unwrap_or_else(|| RankingConfig::from(&config.search));
 
-  let query_vec = ctx.get_embedding(&base.query).await?;
-  debug!("Using vector search for query: {}", base.query);
+  let query = match ctx.aliases {
+    Some(aliases) => aliases.expand(&base.query),
+    None => base.query.clone(),
+  };
+  let query_vec = ctx.get_embedding(&query).await?;
+  debug!("Using vector search for query: {}", query);
 
   let fts_enabled = config.search.fts_enabled;
   let rrf_k = config.search.rrf_k;
@@ -98,7 +129,7 @@ pub async fn search(
 
     let (vector_results, fts_results) = tokio::join!(
       ctx.db.search_memories(&query_vec, oversample, filter.as_deref()),
-      ctx.db.fts_search_memories(&base.query, oversample, filter.as_deref()),
+      ctx.db.fts_search_memories(&query, oversample, filter.as_deref()),
     );
 
     let vector_results = vector_results?;
@@ -135,7 +166,7 @@ pub async fn search(
 
     // Optional reranking
     let ranked_ids = if let Some(reranker) = reranker {
-      rerank_memory_candidates(&candidates, &memory_map, reranker, &base.query).await
+      rerank_memory_candidates(&candidates, &memory_map, reranker, &query).await
     } else {
       candidates
     };
@@ -151,7 +182,7 @@ pub async fn search(
       })
       .collect();
 
-    let ranked = ranking::rank_memories(fused_results, limit, Some(&ranking_config));
+    let ranked = ranking::rank_memories(fused_results, rank_limit, Some(&ranking_config));
 
     let distances: Vec<f32> = ranked.iter().map(|(_, distance, _)| *distance).collect();
     let search_quality = SearchQuality::from_distances(&distances);
@@ -164,7 +195,7 @@ pub async fn search(
       })
       .collect();
 
-    Ok(SearchResult { items, search_quality })
+    Ok(grouped(items, search_quality, group_by, per_group, limit))
   } else {
     // Vector-only path
     let results = ctx
@@ -186,7 +217,7 @@ pub async fn search(
       let fused = fusion::reciprocal_rank_fusion(&[vector_ids], rrf_k);
       let candidates: Vec<(String, f32)> = fused.into_iter().take(rerank_candidates).collect();
 
-      let ranked_ids = rerank_memory_candidates(&candidates, &memory_map, reranker, &base.query).await;
+      let ranked_ids = rerank_memory_candidates(&candidates, &memory_map, reranker, &query).await;
 
       ranked_ids
         .into_iter()
@@ -201,7 +232,7 @@ pub async fn search(
       results
     };
 
-    let ranked = ranking::rank_memories(results, limit, Some(&ranking_config));
+    let ranked = ranking::rank_memories(results, rank_limit, Some(&ranking_config));
 
     let distances: Vec<f32> = ranked.iter().map(|(_, distance, _)| *distance).collect();
     let search_quality = SearchQuality::from_distances(&distances);
@@ -214,7 +245,33 @@ pub async fn search(
       })
       .collect();
 
-    Ok(SearchResult { items, search_quality })
+    Ok(grouped(items, search_quality, group_by, per_group, limit))
+  }
+}
+
+/// Build the result, grouping the ranked items when requested
+fn grouped(
+  items: Vec<MemoryItem>,
+  search_quality: SearchQuality,
+  group_by: Option<MemoryGroupBy>,
+  per_group: usize,
+  limit: usize,
+) -> SearchResult {
+  match group_by {
+    Some(by) => {
+      let mut groups = by.group(items, per_group);
+      groups.truncate(limit);
+      SearchResult {
+        items: Vec::new(),
+        search_quality,
+        groups: Some(groups),
+      }
+    }
+    None => SearchResult {
+      items,
+      search_quality,
+      groups: None,
+    },
   }
 }
 
@@ -312,4 +369,63 @@ mod tests {
     assert!(filter_str.contains("sector = 'semantic'"));
     assert!(filter_str.contains("scope_path LIKE 'src/%'"));
   }
+
+  #[test]
+  fn test_group_by_tag_keeps_each_memory_once() {
+    let item = |id: &str, tags: &[&str]| MemoryItem {
+      id: id.to_string(),
+      content: format!("memory {}", id),
+      sector: "semantic".to_string(),
+      tier: "project".to_string(),
+      summary: None,
+      memory_type: None,
+      similarity: None,
+      rank_score: None,
+      salience: 0.5,
+      importance: 0.5,
+      is_superseded: false,
+      superseded_by: None,
+      stale: false,
+      stale_reason: None,
+      tags: tags.iter().map(|t| t.to_string()).collect(),
+      categories: Vec::new(),
+      scope_path: None,
+      scope_module: None,
+      goal_status: None,
+      due: None,
+      created_at: String::new(),
+      last_accessed: String::new(),
+    };
+    let ranked = vec![
+      item("a", &["auth", "api"]),
+      item("b", &["api"]),
+      item("c", &["auth"]),
+      item("a", &["auth", "api"]),
+      item("d", &[]),
+      item("e", &["auth"]),
+    ];
+
+    let result = grouped(
+      ranked,
+      SearchQuality::from_distances(&[]),
+      Some(MemoryGroupBy::Tag),
+      2,
+      10,
+    );
+    let groups = result.groups.unwrap();
+    assert!(result.items.is_empty());
+
+    let summary: Vec<(&str, usize, Vec<&str>)> = groups
+      .iter()
+      .map(|g| (g.key.as_str(), g.count, g.items.iter().map(|i| i.id.as_str()).collect()))
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        ("auth", 3, vec!["a", "c"]),
+        ("api", 1, vec!["b"]),
+        ("(untagged)", 1, vec!["d"]),
+      ]
+    );
+  }
 }
@@ -0,0 +1,260 @@
+//! Stale memory detection against the code index.
+//!
+//! Codebase and gotcha memories record the indexed files and symbols they
+//! reference when created. Validation re-checks them against the current
+//! index: a file that is no longer indexed, a file whose content hash drifted,
+//! or a symbol without a definition marks the memory stale. Memories whose
+//! references check out again are cleared.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use super::{MemoryContext, anchor::parse_file_ref};
+use crate::{
+  db::{FilterBuilder, ProjectDb},
+  domain::{
+    memory::{CodeReference, CodeReferenceKind, Memory, MemoryType},
+    path,
+  },
+  service::util::ServiceError,
+};
+
+/// Symbol names per definition lookup query
+const SYMBOL_QUERY_BATCH: usize = 200;
+
+/// Statistics from a validation pass
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StaleStats {
+  /// Memories with code references that were checked
+  pub checked: usize,
+  /// Memories newly marked stale
+  pub marked: usize,
+  /// Previously stale memories whose references are valid again
+  pub cleared: usize,
+}
+
+/// Whether memories of this type are validated against the code index
+pub fn tracks_staleness(memory_type: Option<MemoryType>) -> bool {
+  matches!(memory_type, Some(MemoryType::Codebase | MemoryType::Gotcha))
+}
+
+/// Record the referenced files and symbols that are currently indexed.
+///
+/// Files are matched against the indexed files table; concepts that look like
+/// identifiers are matched against code chunk definitions.
+pub async fn record_code_refs(db: &ProjectDb, files: &[String], concepts: &[String]) -> Vec<CodeReference> {
+  let mut refs = Vec::new();
+
+  let mut seen = HashSet::new();
+  for file in files {
+    let relative = path::normalize(parse_file_ref(file).0);
+    if !seen.insert(relative.clone()) {
+      continue;
+    }
+    match db.get_indexed_file(db.project_id.as_str(), &relative).await {
+      Ok(Some(indexed)) => refs.push(CodeReference {
+        kind: CodeReferenceKind::File,
+        name: relative,
+        content_hash: Some(indexed.content_hash),
+      }),
+      Ok(None) => {}
+      Err(e) => debug!(file = %relative, error = %e, "Skipping file reference"),
+    }
+  }
+
+  let mut names: Vec<&str> = concepts
+    .iter()
+    .map(String::as_str)
+    .filter(|c| is_identifier(c))
+    .collect();
+  names.sort_unstable();
+  names.dedup();
+  match defined_symbols(db, &names).await {
+    Ok(defined) => refs.extend(
+      names
+        .into_iter()
+        .filter(|n| defined.contains(*n))
+        .map(|name| CodeReference {
+          kind: CodeReferenceKind::Symbol,
+          name: name.to_string(),
+          content_hash: None,
+        }),
+    ),
+    Err(e) => debug!(error = %e, "Skipping symbol references"),
+  }
+
+  refs
+}
+
+/// Validate codebase and gotcha memories against the current code index.
+///
+/// Does nothing for projects without an index, since every reference would
+/// look removed.
+pub async fn validate_stale(ctx: &MemoryContext<'_>) -> Result<StaleStats, ServiceError> {
+  let project_id = ctx.db.project_id.as_str();
+  if !ctx.db.has_indexed_files(project_id).await? {
+    return Ok(StaleStats::default());
+  }
+
+  let filter = FilterBuilder::new()
+    .exclude_deleted()
+    .exclude_superseded()
+    .add_in(
+      "memory_type",
+      &[MemoryType::Codebase.as_str(), MemoryType::Gotcha.as_str()],
+    )
+    .build();
+  let memories: Vec<Memory> = ctx
+    .db
+    .list_memories(filter.as_deref(), None)
+    .await?
+    .into_iter()
+    .filter(|m| !m.code_refs.is_empty())
+    .collect();
+
+  if memories.is_empty() {
+    return Ok(StaleStats::default());
+  }
+
+  let indexed: HashMap<String, String> = ctx
+    .db
+    .list_indexed_files(project_id)
+    .await?
+    .into_iter()
+    .map(|f| (f.file_path, f.content_hash))
+    .collect();
+
+  let mut names: Vec<&str> = memories
+    .iter()
+    .flat_map(|m| &m.code_refs)
+    .filter(|r| r.kind == CodeReferenceKind::Symbol)
+    .map(|r| r.name.as_str())
+    .collect();
+  names.sort_unstable();
+  names.dedup();
+  let defined = defined_symbols(ctx.db, &names).await?;
+
+  let mut stats = StaleStats {
+    checked: memories.len(),
+    ..Default::default()
+  };
+  let mut changed = Vec::new();
+
+  for mut memory in memories {
+    let reason = stale_reason(&memory.code_refs, &indexed, &defined);
+    if reason == memory.stale_reason && reason.is_some() == memory.stale {
+      continue;
+    }
+
+    match (memory.stale, reason.is_some()) {
+      (false, true) => stats.marked += 1,
+      (true, false) => stats.cleared += 1,
+      _ => {}
+    }
+    memory.stale = reason.is_some();
+    memory.stale_reason = reason;
+    changed.push(memory);
+  }
+
+  if !changed.is_empty() {
+    debug!(count = changed.len(), "Updating memory staleness");
+    ctx.db.batch_update_memories(&changed).await?;
+  }
+
+  Ok(stats)
+}
+
+/// Explain why references no longer match the index, `None` if they all do
+fn stale_reason(
+  refs: &[CodeReference],
+  indexed: &HashMap<String, String>,
+  defined: &HashSet<String>,
+) -> Option<String> {
+  let problems: Vec<String> = refs
+    .iter()
+    .filter_map(|r| match r.kind {
+      CodeReferenceKind::File => match indexed.get(&r.name) {
+        None => Some(format!("file removed: {}", r.name)),
+        Some(hash) if r.content_hash.as_ref().is_some_and(|h| h != hash) => Some(format!("file changed: {}", r.name)),
+        Some(_) => None,
+      },
+      CodeReferenceKind::Symbol => (!defined.contains(&r.name)).then(|| format!("symbol removed: {}", r.name)),
+    })
+    .collect();
+
+  (!problems.is_empty()).then(|| problems.join("; "))
+}
+
+/// Names among `names` that have a definition in the code index
+async fn defined_symbols(db: &ProjectDb, names: &[&str]) -> Result<HashSet<String>, ServiceError> {
+  let mut defined = HashSet::new();
+  for batch in names.chunks(SYMBOL_QUERY_BATCH) {
+    let filter = FilterBuilder::new().add_in("definition_name", batch).build();
+    let chunks = db.list_code_chunks(filter.as_deref(), None).await?;
+    defined.extend(chunks.into_iter().filter_map(|c| c.definition_name));
+  }
+  Ok(defined)
+}
+
+/// Whether a concept could name a code symbol
+fn is_identifier(concept: &str) -> bool {
+  let mut chars = concept.chars();
+  chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn file(name: &str, hash: &str) -> CodeReference {
+    CodeReference {
+      kind: CodeReferenceKind::File,
+      name: name.to_string(),
+      content_hash: Some(hash.to_string()),
+    }
+  }
+
+  fn symbol(name: &str) -> CodeReference {
+    CodeReference {
+      kind: CodeReferenceKind::Symbol,
+      name: name.to_string(),
+      content_hash: None,
+    }
+  }
+
+  #[test]
+  fn test_stale_reason() {
+    let indexed = HashMap::from([
+      ("src/a.rs".to_string(), "h1".to_string()),
+      ("src/b.rs".to_string(), "h2".to_string()),
+    ]);
+    let defined = HashSet::from(["parse_config".to_string()]);
+
+    let fresh = [file("src/a.rs", "h1"), symbol("parse_config")];
+    assert_eq!(stale_reason(&fresh, &indexed, &defined), None, "matching references");
+
+    let drifted = [file("src/b.rs", "old")];
+    assert_eq!(
+      stale_reason(&drifted, &indexed, &defined).as_deref(),
+      Some("file changed: src/b.rs"),
+      "content hash drift"
+    );
+
+    let removed = [file("src/gone.rs", "h"), symbol("OldType")];
+    assert_eq!(
+      stale_reason(&removed, &indexed, &defined).as_deref(),
+      Some("file removed: src/gone.rs; symbol removed: OldType"),
+      "missing file and symbol"
+    );
+  }
+
+  #[test]
+  fn test_is_identifier() {
+    assert!(is_identifier("parse_config"), "snake case");
+    assert!(is_identifier("ProjectDb"), "camel case");
+    assert!(!is_identifier("src/main.rs"), "paths are not symbols");
+    assert!(!is_identifier("foo()"), "call syntax is not a symbol");
+    assert!(!is_identifier("9lives"), "leading digit");
+  }
+}
@@ -0,0 +1,288 @@
+//! Three-way merge of synced memory records.
+//!
+//! The base is the version last read from or written to the sync directory.
+//! A field changed on one side takes that side. A field changed on both sides
+//! is settled by a fixed rule that gives the same answer on every machine:
+//! scores take the higher value, lists merge additions and removals, and
+//! anything else takes the more recently updated side. Content is the
+//! exception: two different edits, or an edit against a deletion, are a true
+//! conflict that keeps the local version until `ccengram sync resolve`.
+
+use serde::{Deserialize, Serialize};
+
+use super::SharedMemory;
+
+/// Why a memory could not be merged automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+  /// Both sides edited the content differently
+  Content,
+  /// One side edited the memory while the other deleted it
+  EditDelete,
+}
+
+impl ConflictKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Content => "content",
+      Self::EditDelete => "edit_delete",
+    }
+  }
+}
+
+/// Result of merging one memory
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Merged {
+  pub memory: SharedMemory,
+  pub conflict: Option<ConflictKind>,
+}
+
+impl Merged {
+  fn clean(memory: SharedMemory) -> Self {
+    Self { memory, conflict: None }
+  }
+
+  fn conflict(memory: SharedMemory, kind: ConflictKind) -> Self {
+    Self {
+      memory,
+      conflict: Some(kind),
+    }
+  }
+}
+
+/// Merge the local and remote versions of a memory against their common base.
+///
+/// Without a base (the memory was never synced from this store) the more
+/// recently changed side wins as a whole.
+pub(super) fn merge(base: Option<&SharedMemory>, local: &SharedMemory, remote: &SharedMemory) -> Merged {
+  if same_record(local, remote) {
+    return Merged::clean(local.clone());
+  }
+  let Some(base) = base else {
+    return Merged::clean(newer(local, remote).clone());
+  };
+
+  match (local.is_tombstone(), remote.is_tombstone()) {
+    (true, true) => return Merged::clean(newer(local, remote).clone()),
+    (false, true) if same_content(local, base) => return Merged::clean(remote.clone()),
+    (true, false) if same_content(remote, base) => return Merged::clean(local.clone()),
+    (false, true) | (true, false) => return Merged::conflict(local.clone(), ConflictKind::EditDelete),
+    (false, false) => {}
+  }
+
+  let newer = newer(local, remote);
+  let mut merged = local.clone();
+  let mut conflict = None;
+
+  match pick(
+    &(&base.content, &base.summary),
+    &(&local.content, &local.summary),
+    &(&remote.content, &remote.summary),
+  ) {
+    Some((content, summary)) => {
+      merged.content = content.clone();
+      merged.summary = summary.clone();
+    }
+    None => conflict = Some(ConflictKind::Content),
+  }
+
+  merged.memory_type = pick(&base.memory_type, &local.memory_type, &remote.memory_type).unwrap_or(newer.memory_type);
+  merged.sector = pick(&base.sector, &local.sector, &remote.sector).unwrap_or(newer.sector);
+  merged.scope_path =
+    pick(&base.scope_path, &local.scope_path, &remote.scope_path).unwrap_or_else(|| newer.scope_path.clone());
+  merged.scope_module =
+    pick(&base.scope_module, &local.scope_module, &remote.scope_module).unwrap_or_else(|| newer.scope_module.clone());
+
+  merged.importance =
+    pick(&base.importance, &local.importance, &remote.importance).unwrap_or(local.importance.max(remote.importance));
+  merged.confidence =
+    pick(&base.confidence, &local.confidence, &remote.confidence).unwrap_or(local.confidence.max(remote.confidence));
+  merged.salience =
+    pick(&base.salience, &local.salience, &remote.salience).unwrap_or(local.salience.max(remote.salience));
+
+  // Two different successors: keep the later one, by ID when edited together
+  merged.superseded_by = pick(&base.superseded_by, &local.superseded_by, &remote.superseded_by).unwrap_or_else(|| {
+    match local.updated_at.cmp(&remote.updated_at) {
+      std::cmp::Ordering::Less => remote.superseded_by,
+      std::cmp::Ordering::Greater => local.superseded_by,
+      std::cmp::Ordering::Equal => std::cmp::max_by_key(local.superseded_by, remote.superseded_by, |id| {
+        id.map(|id| id.as_uuid())
+      }),
+    }
+  });
+
+  merged.tags = merge_list(&base.tags, &local.tags, &remote.tags);
+  merged.files = merge_list(&base.files, &local.files, &remote.files);
+  merged.categories = merge_list(&base.categories, &local.categories, &remote.categories);
+
+  merged.created_at = local.created_at.min(remote.created_at);
+  merged.updated_at = match conflict {
+    Some(_) => local.updated_at,
+    None => local.updated_at.max(remote.updated_at),
+  };
+
+  Merged {
+    memory: merged,
+    conflict,
+  }
+}
+
+/// Three-way choice for one field: the side that changed wins.
+/// `None` when both sides changed it to different values.
+fn pick<T: PartialEq + Clone>(base: &T, local: &T, remote: &T) -> Option<T> {
+  if local == remote || remote == base {
+    Some(local.clone())
+  } else if local == base {
+    Some(remote.clone())
+  } else {
+    None
+  }
+}
+
+/// Keep items both sides kept, drop items either side removed, and add
+/// items either side added
+fn merge_list(base: &[String], local: &[String], remote: &[String]) -> Vec<String> {
+  let mut merged: Vec<String> = local
+    .iter()
+    .filter(|item| remote.contains(item) || !base.contains(item))
+    .cloned()
+    .collect();
+  merged.extend(
+    remote
+      .iter()
+      .filter(|item| !local.contains(item) && !base.contains(item))
+      .cloned(),
+  );
+  merged
+}
+
+/// The more recently changed side, with ties broken by content so both
+/// machines agree
+fn newer<'a>(local: &'a SharedMemory, remote: &'a SharedMemory) -> &'a SharedMemory {
+  let key = |m: &'a SharedMemory| (m.changed_at(), &m.content);
+  if key(remote) > key(local) { remote } else { local }
+}
+
+fn same_content(a: &SharedMemory, b: &SharedMemory) -> bool {
+  a.content == b.content && a.summary == b.summary
+}
+
+/// Equal apart from the file format version
+fn same_record(a: &SharedMemory, b: &SharedMemory) -> bool {
+  SharedMemory {
+    format: b.format,
+    ..a.clone()
+  } == *b
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{Duration, Utc};
+
+  use super::*;
+  use crate::domain::memory::{Memory, MemoryType, Sector};
+
+  fn base() -> SharedMemory {
+    let mut memory = Memory::new(
+      uuid::Uuid::new_v4(),
+      "Use LanceDB for storage".to_string(),
+      Sector::Semantic,
+    );
+    memory.memory_type = Some(MemoryType::Decision);
+    memory.tags = vec!["storage".to_string(), "db".to_string()];
+    SharedMemory::from_memory(&memory)
+  }
+
+  fn edited(base: &SharedMemory, minutes: i64) -> SharedMemory {
+    SharedMemory {
+      updated_at: base.updated_at + Duration::minutes(minutes),
+      ..base.clone()
+    }
+  }
+
+  #[test]
+  fn test_one_sided_edits_merge_cleanly() {
+    let base = base();
+    let mut local = edited(&base, 1);
+    local.salience = 0.4;
+    local.tags.push("lance".to_string());
+    let mut remote = edited(&base, 2);
+    remote.content = "Use LanceDB for every table".to_string();
+    remote.tags.retain(|t| t != "db");
+
+    let merged = merge(Some(&base), &local, &remote);
+    assert_eq!(merged.conflict, None);
+    assert_eq!(merged.memory.content, remote.content, "remote content edit is taken");
+    assert_eq!(merged.memory.salience, 0.4, "local salience change is kept");
+    assert_eq!(
+      merged.memory.tags,
+      vec!["storage", "lance"],
+      "tag edits from both sides"
+    );
+    assert_eq!(merged.memory.updated_at, remote.updated_at);
+  }
+
+  #[test]
+  fn test_both_sided_edits_resolve_deterministically() {
+    let base = base();
+    let mut local = edited(&base, 1);
+    local.importance = 0.9;
+    local.superseded_by = Some(crate::domain::memory::MemoryId::new());
+    let mut remote = edited(&base, 2);
+    remote.importance = 0.7;
+    remote.superseded_by = Some(crate::domain::memory::MemoryId::new());
+
+    let ours = merge(Some(&base), &local, &remote);
+    let theirs = merge(Some(&base), &remote, &local);
+    assert_eq!(ours.conflict, None, "only content edits conflict");
+    assert_eq!(ours.memory.importance, 0.9, "higher score wins");
+    assert_eq!(ours.memory.superseded_by, remote.superseded_by, "newer successor wins");
+    assert_eq!(ours.memory, theirs.memory, "both machines reach the same result");
+  }
+
+  #[test]
+  fn test_content_conflicts_keep_local() {
+    let base = base();
+    let mut local = edited(&base, 1);
+    local.content = "Use LanceDB, not SQLite".to_string();
+    let mut remote = edited(&base, 2);
+    remote.content = "Use LanceDB with one table per kind".to_string();
+
+    let merged = merge(Some(&base), &local, &remote);
+    assert_eq!(merged.conflict, Some(ConflictKind::Content));
+    assert_eq!(
+      merged.memory.content, local.content,
+      "local content kept until resolved"
+    );
+  }
+
+  #[test]
+  fn test_deletions() {
+    let base = base();
+    let mut deleted = edited(&base, 1);
+    deleted.content = String::new();
+    deleted.deleted_at = Some(Utc::now());
+
+    let untouched = merge(Some(&base), &base, &deleted);
+    assert!(untouched.memory.is_tombstone(), "unedited memory follows the deletion");
+    assert_eq!(untouched.conflict, None);
+
+    let mut local = edited(&base, 2);
+    local.content = "Use LanceDB and note why".to_string();
+    let conflict = merge(Some(&base), &local, &deleted);
+    assert_eq!(conflict.conflict, Some(ConflictKind::EditDelete));
+    assert!(!conflict.memory.is_tombstone(), "local edit kept until resolved");
+  }
+
+  #[test]
+  fn test_without_base_newer_side_wins() {
+    let base = base();
+    let mut remote = edited(&base, 5);
+    remote.content = "Newer remote wording".to_string();
+
+    let merged = merge(None, &base, &remote);
+    assert_eq!(merged.conflict, None);
+    assert_eq!(merged.memory, remote);
+  }
+}
@@ -0,0 +1,687 @@
+//! Team memory sync through a git-tracked directory.
+//!
+//! `push` writes each team-visible memory of a shared type to its own JSON
+//! file under the sync directory, so teammates exchange them with ordinary
+//! commits and merges. `pull` folds the directory back into the local store:
+//! unknown memories are added, and known ones are merged three ways against
+//! the version last synced (see [`merge`]). Content edited on both sides is a
+//! conflict, kept local until settled with [`resolve`].
+//!
+//! Deleting a shared memory leaves a tombstone file without content so the
+//! deletion reaches everyone. Making one private removes its file instead;
+//! teammates keep their copy.
+
+mod merge;
+mod state;
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use state::{SyncConflict, SyncState};
+use tracing::{debug, warn};
+
+use super::{MemoryContext, anchor, entity, stale};
+use crate::{
+  context::memory::extract::{classifier::extract_concepts, dedup::compute_hashes},
+  db::MemoryChange,
+  domain::{
+    config::SyncConfig,
+    memory::{Memory, MemoryEventAction, MemoryId, MemoryType, Sector, Visibility},
+  },
+  ipc::types::memory::{
+    SyncConflictItem, SyncPullParams, SyncPullResult, SyncPushParams, SyncPushResult, SyncResolveParams,
+    SyncResolveResult,
+  },
+  service::util::ServiceError,
+};
+
+/// Version of the memory file layout
+const SYNC_FORMAT: u32 = 1;
+
+const FILE_EXTENSION: &str = "json";
+
+/// One memory as stored in the sync directory.
+///
+/// Only what teammates should share: access counts, session and conversation
+/// context stay local, and git anchors are recomputed on pull.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SharedMemory {
+  format: u32,
+  id: MemoryId,
+  #[serde(rename = "type")]
+  memory_type: Option<MemoryType>,
+  sector: Sector,
+  /// Empty for tombstones
+  #[serde(default)]
+  content: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  summary: Option<String>,
+  importance: f32,
+  confidence: f32,
+  /// Rounded to two decimals so decay doesn't rewrite every file
+  #[serde(default = "default_salience")]
+  salience: f32,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  tags: Vec<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  files: Vec<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  categories: Vec<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  scope_path: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  scope_module: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  superseded_by: Option<MemoryId>,
+  created_at: DateTime<Utc>,
+  updated_at: DateTime<Utc>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  deleted_at: Option<DateTime<Utc>>,
+}
+
+fn default_salience() -> f32 {
+  1.0
+}
+
+impl SharedMemory {
+  fn from_memory(memory: &Memory) -> Self {
+    Self {
+      format: SYNC_FORMAT,
+      id: memory.id,
+      memory_type: memory.memory_type,
+      sector: memory.sector,
+      content: memory.content.clone(),
+      summary: memory.summary.clone(),
+      importance: memory.importance,
+      confidence: memory.confidence,
+      salience: (memory.salience * 100.0).round() / 100.0,
+      tags: memory.tags.clone(),
+      files: memory.files.clone(),
+      categories: memory.categories.clone(),
+      scope_path: memory.scope_path.clone(),
+      scope_module: memory.scope_module.clone(),
+      superseded_by: memory.superseded_by,
+      created_at: memory.created_at,
+      updated_at: memory.updated_at,
+      deleted_at: None,
+    }
+  }
+
+  /// Deletion marker for a memory that was shared and has been deleted
+  fn tombstone(memory: &Memory) -> Self {
+    Self {
+      content: String::new(),
+      summary: None,
+      tags: Vec::new(),
+      files: Vec::new(),
+      categories: Vec::new(),
+      scope_path: None,
+      scope_module: None,
+      deleted_at: memory.deleted_at.or(Some(memory.updated_at)),
+      ..Self::from_memory(memory)
+    }
+  }
+
+  /// The version of a local memory that would be written to the directory
+  fn from_local(memory: &Memory) -> Self {
+    if memory.is_deleted {
+      Self::tombstone(memory)
+    } else {
+      Self::from_memory(memory)
+    }
+  }
+
+  fn is_tombstone(&self) -> bool {
+    self.deleted_at.is_some()
+  }
+
+  /// Last time the memory was edited or deleted
+  fn changed_at(&self) -> DateTime<Utc> {
+    self.deleted_at.map_or(self.updated_at, |d| d.max(self.updated_at))
+  }
+
+  /// Copy the shared fields onto a local memory, restoring it if deleted
+  fn apply_to(&self, memory: &mut Memory) {
+    memory.memory_type = self.memory_type;
+    memory.sector = self.sector;
+    memory.content = self.content.clone();
+    memory.summary = self.summary.clone();
+    memory.importance = self.importance;
+    memory.confidence = self.confidence;
+    memory.salience = self.salience;
+    memory.tags = self.tags.clone();
+    memory.files = self.files.clone();
+    memory.categories = self.categories.clone();
+    memory.scope_path = self.scope_path.clone();
+    memory.scope_module = self.scope_module.clone();
+    memory.superseded_by = self.superseded_by;
+    memory.created_at = self.created_at;
+    memory.updated_at = self.updated_at;
+    memory.visibility = Visibility::Team;
+    memory.is_deleted = false;
+    memory.deleted_at = None;
+  }
+
+  fn to_json(&self) -> Result<String, ServiceError> {
+    serde_json::to_string_pretty(self)
+      .map(|json| json + "\n")
+      .map_err(|e| ServiceError::internal(format!("Failed to serialize memory {}: {}", self.id, e)))
+  }
+}
+
+/// Write shared memories to the sync directory.
+///
+/// Files of memories this store doesn't know are left alone: they belong to
+/// teammates and arrive with the next `pull`. So are files changed in the
+/// directory since the last sync, and memories with unresolved conflicts,
+/// so a push never overwrites a teammate's edit that hasn't been merged.
+///
+/// `state_dir` is the project's data directory, holding the sync state.
+pub async fn push(
+  ctx: &MemoryContext<'_>,
+  config: &SyncConfig,
+  state_dir: &Path,
+  params: SyncPushParams,
+) -> Result<SyncPushResult, ServiceError> {
+  let dir = sync_dir(ctx, config)?;
+  let mut state = SyncState::load(state_dir).await?;
+  let mut result = SyncPushResult {
+    dir: config.dir.clone(),
+    dry_run: params.dry_run,
+    ..Default::default()
+  };
+
+  if !params.dry_run {
+    tokio::fs::create_dir_all(&dir)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+  }
+
+  let memories = ctx.db.list_memories(None, None).await?;
+  for memory in memories {
+    if !memory.memory_type.is_some_and(|t| config.types.contains(&t)) {
+      continue;
+    }
+
+    let id = memory.id.to_string();
+    let path = dir.join(format!("{}.{}", id, FILE_EXTENSION));
+    let existing = tokio::fs::read_to_string(&path).await.ok();
+
+    // Retracting a memory made private wins over an unresolved conflict
+    if !memory.visibility.is_shared() {
+      if existing.is_some() {
+        result.removed += 1;
+        if !params.dry_run {
+          remove_file(&path).await?;
+        }
+      }
+      state.base.remove(&id);
+      state.conflicts.remove(&id);
+      continue;
+    }
+
+    if state.conflicts.contains_key(&id) {
+      result.conflicted += 1;
+      continue;
+    }
+
+    // Never shared, nothing to retract
+    if memory.is_deleted && existing.is_none() {
+      continue;
+    }
+
+    let shared = SharedMemory::from_local(&memory);
+    let json = shared.to_json()?;
+    if existing.as_deref() == Some(json.as_str()) {
+      result.unchanged += 1;
+      state.base.insert(id, shared);
+      continue;
+    }
+    if let Some(existing) = &existing {
+      let base = state.base.get(&id).map(SharedMemory::to_json).transpose()?;
+      if base.as_deref() != Some(existing.as_str()) {
+        result.stale += 1;
+        continue;
+      }
+    }
+
+    if shared.is_tombstone() {
+      result.tombstoned += 1;
+    } else {
+      result.written += 1;
+    }
+    if !params.dry_run {
+      tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+    }
+    state.base.insert(id, shared);
+  }
+
+  if !params.dry_run {
+    state.save(state_dir).await?;
+  }
+
+  debug!(
+    written = result.written,
+    tombstoned = result.tombstoned,
+    removed = result.removed,
+    stale = result.stale,
+    conflicted = result.conflicted,
+    dry_run = params.dry_run,
+    "Sync push complete"
+  );
+  Ok(result)
+}
+
+/// Merge the sync directory into the local memory store.
+///
+/// Memories kept private locally are never overwritten from the directory.
+pub async fn pull(
+  ctx: &MemoryContext<'_>,
+  config: &SyncConfig,
+  state_dir: &Path,
+  params: SyncPullParams,
+) -> Result<SyncPullResult, ServiceError> {
+  let dir = sync_dir(ctx, config)?;
+  let mut state = SyncState::load(state_dir).await?;
+  let mut result = SyncPullResult {
+    dir: config.dir.clone(),
+    dry_run: params.dry_run,
+    ..Default::default()
+  };
+
+  for path in memory_files(&dir).await? {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let remote = match read_shared(&path).await {
+      Ok(shared) => shared,
+      Err(reason) => {
+        warn!(file = %name, "Skipping sync file: {}", reason);
+        result.invalid.push(format!("{}: {}", name, reason));
+        continue;
+      }
+    };
+    let id = remote.id.to_string();
+
+    let Some(local) = ctx.db.get_memory(&remote.id).await? else {
+      if remote.is_tombstone() {
+        result.unchanged += 1;
+      } else {
+        result.added += 1;
+        if !params.dry_run {
+          add_shared(ctx, &remote).await?;
+        }
+      }
+      state.conflicts.remove(&id);
+      state.base.insert(id, remote);
+      continue;
+    };
+    if !local.visibility.is_shared() {
+      result.unchanged += 1;
+      continue;
+    }
+
+    let local_shared = SharedMemory::from_local(&local);
+    let base = state.base.get(&id).cloned();
+    let merged = merge::merge(base.as_ref(), &local_shared, &remote);
+    let changed = merged.memory != local_shared;
+
+    if let Some(kind) = merged.conflict {
+      result.conflicts += 1;
+      state.conflicts.insert(
+        id,
+        SyncConflict {
+          kind,
+          base,
+          local: local_shared,
+          remote,
+          detected_at: Utc::now(),
+        },
+      );
+    } else {
+      if !changed {
+        result.unchanged += 1;
+      } else if merged.memory.is_tombstone() && !local.is_deleted {
+        result.deleted += 1;
+      } else {
+        result.updated += 1;
+      }
+      state.conflicts.remove(&id);
+      state.base.insert(id, remote);
+    }
+
+    // Conflicts still take the fields that did merge
+    if changed && !params.dry_run {
+      apply_merged(ctx, local, &merged.memory).await?;
+    }
+  }
+
+  if !params.dry_run {
+    state.save(state_dir).await?;
+  }
+
+  debug!(
+    added = result.added,
+    updated = result.updated,
+    deleted = result.deleted,
+    conflicts = result.conflicts,
+    invalid = result.invalid.len(),
+    dry_run = params.dry_run,
+    "Sync pull complete"
+  );
+  Ok(result)
+}
+
+/// Conflicts waiting to be resolved, oldest first
+pub async fn conflicts(state_dir: &Path) -> Result<Vec<SyncConflictItem>, ServiceError> {
+  let state = SyncState::load(state_dir).await?;
+
+  let mut items: Vec<SyncConflictItem> = state
+    .conflicts
+    .into_iter()
+    .map(|(id, conflict)| SyncConflictItem {
+      id,
+      kind: conflict.kind.as_str().to_string(),
+      base: conflict.base.map(|b| b.content),
+      local: (!conflict.local.is_tombstone()).then_some(conflict.local.content),
+      remote: (!conflict.remote.is_tombstone()).then_some(conflict.remote.content),
+      local_updated_at: conflict.local.changed_at().to_rfc3339(),
+      remote_updated_at: conflict.remote.changed_at().to_rfc3339(),
+      detected_at: conflict.detected_at.to_rfc3339(),
+    })
+    .collect();
+  items.sort_by(|a, b| a.detected_at.cmp(&b.detected_at));
+  Ok(items)
+}
+
+/// Settle a conflict by keeping the local version, taking the remote one, or
+/// replacing the content with an edited version.
+///
+/// The result counts as a new local edit, so the next `push` writes it.
+pub async fn resolve(
+  ctx: &MemoryContext<'_>,
+  state_dir: &Path,
+  params: SyncResolveParams,
+) -> Result<SyncResolveResult, ServiceError> {
+  let mut state = SyncState::load(state_dir).await?;
+  let (id, conflict) = state.find_conflict(&params.id)?;
+  let (id, conflict) = (id.clone(), conflict.clone());
+  let local = ctx.db.get_memory(&conflict.remote.id).await?;
+  let now = Utc::now();
+
+  match (params.take.as_str(), local) {
+    ("local", Some(mut memory)) => {
+      memory.updated_at = now;
+      ctx.db.update_memory(&memory, None).await?;
+    }
+    ("local", None) => {}
+    ("remote", Some(memory)) => {
+      let remote = SharedMemory {
+        updated_at: now,
+        ..conflict.remote.clone()
+      };
+      apply_merged(ctx, memory, &remote).await?;
+    }
+    ("remote", None) if !conflict.remote.is_tombstone() => add_shared(ctx, &conflict.remote).await?,
+    ("remote", None) => {}
+    ("edit", memory) => {
+      let content = params
+        .content
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| ServiceError::validation("Edited content is empty"))?;
+      let mut memory = memory.ok_or_else(|| ServiceError::not_found("memory", id.clone()))?;
+      memory.restore(now);
+      memory.content = content.to_string();
+      refresh_derived(ctx, &mut memory).await;
+      let vector = ctx.get_embedding(&memory.content).await?;
+      ctx.db.update_memory(&memory, Some(&vector)).await?;
+      ctx
+        .record(MemoryChange::new(memory.id, MemoryEventAction::Updated).with_detail("sync conflict edited"))
+        .await;
+    }
+    (other, _) => {
+      return Err(ServiceError::validation(format!(
+        "Unknown resolution: {} (expected local, remote or edit)",
+        other
+      )));
+    }
+  }
+
+  // The remote version is now the common base, so the next pull keeps this
+  // resolution and the next push writes it
+  state.conflicts.remove(&id);
+  state.base.insert(id.clone(), conflict.remote);
+  state.save(state_dir).await?;
+
+  Ok(SyncResolveResult { id, take: params.take })
+}
+
+fn sync_dir(ctx: &MemoryContext<'_>, config: &SyncConfig) -> Result<PathBuf, ServiceError> {
+  let root = ctx
+    .root
+    .ok_or_else(|| ServiceError::project("Memory sync needs a project root"))?;
+  Ok(root.join(&config.dir))
+}
+
+/// Memory files in the sync directory, sorted by name
+async fn memory_files(dir: &Path) -> Result<Vec<PathBuf>, ServiceError> {
+  let read_error = |e: std::io::Error| ServiceError::internal(format!("Failed to read {}: {}", dir.display(), e));
+
+  let mut entries = match tokio::fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(read_error(e)),
+  };
+
+  let mut paths = Vec::new();
+  while let Some(entry) = entries.next_entry().await.map_err(read_error)? {
+    let path = entry.path();
+    if path.extension().is_some_and(|ext| ext == FILE_EXTENSION) {
+      paths.push(path);
+    }
+  }
+  paths.sort();
+  Ok(paths)
+}
+
+async fn read_shared(path: &Path) -> Result<SharedMemory, String> {
+  let text = tokio::fs::read_to_string(path).await.map_err(|e| e.to_string())?;
+  let shared: SharedMemory = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+  if shared.format > SYNC_FORMAT {
+    return Err(format!(
+      "written by a newer ccengram (format {}, this build reads {})",
+      shared.format, SYNC_FORMAT
+    ));
+  }
+  if !shared.is_tombstone() && shared.content.trim().is_empty() {
+    return Err("memory has no content".to_string());
+  }
+  Ok(shared)
+}
+
+async fn remove_file(path: &Path) -> Result<(), ServiceError> {
+  tokio::fs::remove_file(path)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to remove {}: {}", path.display(), e)))
+}
+
+/// Insert a memory a teammate shared, keeping its ID
+async fn add_shared(ctx: &MemoryContext<'_>, shared: &SharedMemory) -> Result<(), ServiceError> {
+  let mut memory = Memory::new(ctx.project_id, shared.content.clone(), shared.sector);
+  memory.id = shared.id;
+  shared.apply_to(&mut memory);
+  refresh_derived(ctx, &mut memory).await;
+
+  let vector = ctx.get_embedding(&memory.content).await?;
+  ctx.db.add_memory(&memory, &vector).await?;
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::Added)
+        .with_salience(None, Some(memory.salience))
+        .with_detail("synced from teammate"),
+    )
+    .await;
+  if let Err(e) = entity::link(ctx.db, &memory, &[], ctx.aliases).await {
+    warn!(memory_id = %memory.id, "Failed to link memory entities: {}", e);
+  }
+  Ok(())
+}
+
+/// Store a merged version of a local memory.
+///
+/// A tombstone soft-deletes the memory and keeps its content locally.
+async fn apply_merged(ctx: &MemoryContext<'_>, mut memory: Memory, merged: &SharedMemory) -> Result<(), ServiceError> {
+  if let Some(deleted_at) = merged.deleted_at {
+    memory.delete(deleted_at);
+    ctx.db.update_memory(&memory, None).await?;
+    ctx
+      .record(MemoryChange::new(memory.id, MemoryEventAction::Deleted).with_detail("synced from teammate"))
+      .await;
+    return Ok(());
+  }
+
+  let content_changed = memory.content != merged.content;
+  let salience_before = memory.salience;
+  merged.apply_to(&mut memory);
+  let vector = if content_changed {
+    refresh_derived(ctx, &mut memory).await;
+    Some(ctx.get_embedding(&memory.content).await?)
+  } else {
+    None
+  };
+  ctx.db.update_memory(&memory, vector.as_deref()).await?;
+  ctx
+    .record(
+      MemoryChange::new(memory.id, MemoryEventAction::Updated)
+        .with_salience(Some(salience_before), Some(memory.salience))
+        .with_detail("synced from teammate"),
+    )
+    .await;
+  Ok(())
+}
+
+/// Recompute what is derived from content and the local checkout
+async fn refresh_derived(ctx: &MemoryContext<'_>, memory: &mut Memory) {
+  let (content_hash, simhash) = compute_hashes(&memory.content);
+  memory.content_hash = content_hash;
+  memory.simhash = simhash;
+  memory.concepts = extract_concepts(&memory.content);
+  if let Some(root) = ctx.root {
+    memory.file_anchors = anchor::anchor_files(root, &memory.files).await;
+  }
+  if stale::tracks_staleness(memory.memory_type) {
+    memory.code_refs = stale::record_code_refs(ctx.db, &memory.files, &memory.concepts).await;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::{config::Config, db::ProjectDb, domain::project::ProjectId, embedding::OllamaProvider};
+
+  fn memory(content: &str) -> Memory {
+    let mut memory = Memory::new(uuid::Uuid::new_v4(), content.to_string(), Sector::Semantic);
+    memory.memory_type = Some(MemoryType::Decision);
+    memory.session_id = Some("session".to_string());
+    memory
+  }
+
+  #[test]
+  fn test_shared_memory_roundtrip_leaves_out_local_state() {
+    let mut memory = memory("Use LanceDB for every table");
+    memory.salience = 0.8342;
+    let json = SharedMemory::from_memory(&memory).to_json().unwrap();
+
+    assert!(!json.contains("session"), "session stays local: {}", json);
+    assert!(!json.contains("access_count"), "access count stays local: {}", json);
+    assert!(json.contains("0.83"), "salience is rounded: {}", json);
+
+    let parsed: SharedMemory = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, SharedMemory::from_memory(&memory), "file roundtrips");
+    assert!(!parsed.is_tombstone());
+  }
+
+  #[test]
+  fn test_tombstone_drops_content() {
+    let mut memory = memory("Old decision that was reverted");
+    memory.delete(Utc::now());
+
+    let tombstone = SharedMemory::from_local(&memory);
+    assert!(tombstone.is_tombstone());
+    assert!(tombstone.content.is_empty(), "deleted content is not kept in the repo");
+    assert_eq!(tombstone.deleted_at, memory.deleted_at);
+  }
+
+  #[tokio::test]
+  async fn test_push_retracts_conflicted_memory_made_private() {
+    let temp = TempDir::new().unwrap();
+    let config = Arc::new(Config::default());
+    let project_id = ProjectId::from_path(Path::new("/test")).await;
+    let db = ProjectDb::open_at_path(project_id, temp.path().join("test.lancedb"), config.clone())
+      .await
+      .unwrap();
+    let embedding = OllamaProvider::new(&config.embedding).unwrap();
+    let root = temp.path().join("repo");
+    let state_dir = temp.path().join("state");
+    let ctx = MemoryContext::new(&db, &embedding, uuid::Uuid::new_v4()).with_root(&root);
+    let sync = SyncConfig::default();
+
+    let mut memory = memory("Decision both sides edited");
+    db.add_memory(&memory, &vec![0.0; db.vector_dim]).await.unwrap();
+    push(&ctx, &sync, &state_dir, SyncPushParams::default()).await.unwrap();
+    let path = sync_dir(&ctx, &sync)
+      .unwrap()
+      .join(format!("{}.{}", memory.id, FILE_EXTENSION));
+    assert!(path.exists(), "shared memory is written");
+
+    let mut state = SyncState::load(&state_dir).await.unwrap();
+    let shared = SharedMemory::from_local(&memory);
+    state.conflicts.insert(
+      memory.id.to_string(),
+      SyncConflict {
+        kind: merge::ConflictKind::Content,
+        base: None,
+        local: shared.clone(),
+        remote: shared,
+        detected_at: Utc::now(),
+      },
+    );
+    state.save(&state_dir).await.unwrap();
+
+    memory.visibility = Visibility::Private;
+    db.update_memory(&memory, None).await.unwrap();
+    let result = push(&ctx, &sync, &state_dir, SyncPushParams::default()).await.unwrap();
+
+    assert_eq!(result.removed, 1, "private memory is retracted despite the conflict");
+    assert_eq!(result.conflicted, 0);
+    assert!(!path.exists(), "file is removed from the sync directory");
+    let state = SyncState::load(&state_dir).await.unwrap();
+    assert!(state.conflicts.is_empty(), "the moot conflict is dropped");
+  }
+
+  #[test]
+  fn test_apply_to_takes_remote_fields() {
+    let mut local = memory("Original wording of the decision");
+    local.visibility = Visibility::Private;
+    local.access_count = 7;
+
+    let mut remote = SharedMemory::from_memory(&local);
+    remote.content = "Edited wording of the decision".to_string();
+    remote.tags = vec!["storage".to_string()];
+    remote.salience = 0.5;
+    remote.apply_to(&mut local);
+
+    assert_eq!(local.content, "Edited wording of the decision");
+    assert_eq!(local.tags, vec!["storage"]);
+    assert_eq!(local.salience, 0.5, "salience is shared");
+    assert_eq!(local.visibility, Visibility::Team, "pulled memories are shared");
+    assert_eq!(local.access_count, 7, "access count stays local");
+  }
+}
@@ -0,0 +1,85 @@
+//! Local sync bookkeeping.
+//!
+//! Holds the last version of each memory seen in or written to the sync
+//! directory, used as the base of three-way merges, and the conflicts waiting
+//! for `ccengram sync resolve`. Lives in the project's data directory rather
+//! than the repository, since every clone has its own.
+
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{SharedMemory, merge::ConflictKind};
+use crate::service::util::ServiceError;
+
+/// File name of the sync state under the project data directory
+const STATE_FILE: &str = "sync-state.json";
+
+/// A memory that could not be merged automatically
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct SyncConflict {
+  pub kind: ConflictKind,
+  pub base: Option<SharedMemory>,
+  pub local: SharedMemory,
+  pub remote: SharedMemory,
+  pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct SyncState {
+  /// Merge base per memory ID
+  #[serde(default)]
+  pub base: BTreeMap<String, SharedMemory>,
+  /// Unresolved conflicts per memory ID
+  #[serde(default)]
+  pub conflicts: BTreeMap<String, SyncConflict>,
+}
+
+impl SyncState {
+  /// Load the state under `dir`, empty if nothing has been synced yet
+  pub async fn load(dir: &Path) -> Result<Self, ServiceError> {
+    let path = dir.join(STATE_FILE);
+    match tokio::fs::read_to_string(&path).await {
+      Ok(text) => serde_json::from_str(&text)
+        .map_err(|e| ServiceError::internal(format!("Failed to parse {}: {}", path.display(), e))),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(e) => Err(ServiceError::internal(format!(
+        "Failed to read {}: {}",
+        path.display(),
+        e
+      ))),
+    }
+  }
+
+  /// Write the state under `dir`, replacing the previous file atomically
+  pub async fn save(&self, dir: &Path) -> Result<(), ServiceError> {
+    let path = dir.join(STATE_FILE);
+    let tmp = path.with_extension("json.tmp");
+    let json = serde_json::to_string(self)
+      .map_err(|e| ServiceError::internal(format!("Failed to serialize sync state: {}", e)))?;
+
+    tokio::fs::create_dir_all(dir)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+    tokio::fs::write(&tmp, json)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", tmp.display(), e)))?;
+    tokio::fs::rename(&tmp, &path)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", path.display(), e)))
+  }
+
+  /// Find a conflict by memory ID or unique prefix
+  pub fn find_conflict(&self, id_or_prefix: &str) -> Result<(&String, &SyncConflict), ServiceError> {
+    let mut matches = self.conflicts.iter().filter(|(id, _)| id.starts_with(id_or_prefix));
+    match (matches.next(), matches.next()) {
+      (Some(found), None) => Ok(found),
+      (Some(_), Some(_)) => Err(ServiceError::validation(format!(
+        "Ambiguous conflict ID prefix: {}",
+        id_or_prefix
+      ))),
+      (None, _) => Err(ServiceError::not_found("sync conflict", id_or_prefix)),
+    }
+  }
+}
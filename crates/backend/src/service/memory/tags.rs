@@ -0,0 +1,268 @@
+//! Tag curation.
+//!
+//! Tags come from users and from LLM extraction, which tends to produce
+//! near-duplicates (`postgres`, `postgresql`, `pg`) and one-off noise. These
+//! operations work on every memory at once: list tags with how many memories
+//! use them, rename a tag, merge synonyms into one tag, or drop tags
+//! altogether. Deleted and superseded memories are retagged too, so restoring
+//! one doesn't bring an old tag back, but only current memories are counted.
+//! The memories a change touches share one batch in memory history.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{MemoryContext, record_history};
+use crate::{
+  db::{FilterBuilder, MemoryChange},
+  domain::memory::{Memory, MemoryEventAction},
+  ipc::types::memory::{TagDeleteParams, TagItem, TagListParams, TagMergeParams, TagRenameParams, TagUpdateResult},
+  service::util::ServiceError,
+};
+
+/// Tags listed when no limit is given
+const DEFAULT_LIMIT: usize = 100;
+
+/// `tag` trimmed, rejecting empty names
+fn tag_name(tag: &str) -> Result<String, ServiceError> {
+  let tag = tag.trim();
+  if tag.is_empty() {
+    return Err(ServiceError::validation("Tag names can't be empty"));
+  }
+  Ok(tag.to_string())
+}
+
+/// `tags` with every tag in `from` replaced by `into`, or removed when `into`
+/// is `None`. Order is kept and repeats dropped; `None` when nothing changed.
+fn retag(tags: &[String], from: &[String], into: Option<&str>) -> Option<Vec<String>> {
+  if !tags.iter().any(|t| from.contains(t)) {
+    return None;
+  }
+  let mut retagged: Vec<String> = Vec::with_capacity(tags.len());
+  for tag in tags {
+    let tag = if from.contains(tag) {
+      match into {
+        Some(into) => into,
+        None => continue,
+      }
+    } else {
+      tag.as_str()
+    };
+    if !retagged.iter().any(|t| t == tag) {
+      retagged.push(tag.to_string());
+    }
+  }
+  Some(retagged)
+}
+
+/// Tags used by `memories`, most used first, then by name
+fn count(memories: &[Memory]) -> Vec<TagItem> {
+  let mut tags: HashMap<&str, (usize, DateTime<Utc>)> = HashMap::new();
+  for memory in memories {
+    for tag in &memory.tags {
+      let (uses, last_used) = tags.entry(tag.as_str()).or_insert((0, memory.updated_at));
+      *uses += 1;
+      *last_used = (*last_used).max(memory.updated_at);
+    }
+  }
+
+  let mut items: Vec<TagItem> = tags
+    .into_iter()
+    .map(|(tag, (memories, last_used))| TagItem {
+      tag: tag.to_string(),
+      memories,
+      last_used: Some(last_used.to_rfc3339()),
+    })
+    .collect();
+  items.sort_by(|a, b| b.memories.cmp(&a.memories).then_with(|| a.tag.cmp(&b.tag)));
+  items
+}
+
+/// Replace or remove the `from` tags on every memory and record the change as one batch
+async fn apply(
+  ctx: &MemoryContext<'_>,
+  memories: Vec<Memory>,
+  from: &[String],
+  into: Option<&str>,
+  dry_run: bool,
+) -> Result<TagUpdateResult, ServiceError> {
+  let now = Utc::now();
+  let changed: Vec<Memory> = memories
+    .into_iter()
+    .filter_map(|mut memory| {
+      memory.tags = retag(&memory.tags, from, into)?;
+      memory.updated_at = now;
+      Some(memory)
+    })
+    .collect();
+
+  if !dry_run && !changed.is_empty() {
+    ctx.db.batch_update_memories(&changed).await?;
+    let detail = match into {
+      Some(into) => format!("tags {} -> {}", from.join(", "), into),
+      None => format!("tags {} removed", from.join(", ")),
+    };
+    let changes: Vec<MemoryChange> = changed
+      .iter()
+      .map(|memory| MemoryChange::new(memory.id, MemoryEventAction::Updated).with_detail(detail.clone()))
+      .collect();
+    record_history(ctx.db, ctx.actor, &changes).await;
+  }
+
+  Ok(TagUpdateResult {
+    memory_ids: changed.iter().map(|m| m.id.to_string()).collect(),
+    dry_run,
+  })
+}
+
+/// List the tags used by current memories, most used first.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Minimum number of memories per tag and limit
+///
+/// # Returns
+/// * `Ok(Vec<TagItem>)` - Tags with the number of memories using each
+/// * `Err(ServiceError)` - If database error
+pub async fn list(ctx: &MemoryContext<'_>, params: TagListParams) -> Result<Vec<TagItem>, ServiceError> {
+  let filter = FilterBuilder::new().exclude_deleted().exclude_superseded().build();
+  let memories = ctx.db.list_memories(filter.as_deref(), None).await?;
+
+  let min_count = params.min_count.unwrap_or(1);
+  let mut tags = count(&memories);
+  tags.retain(|t| t.memories >= min_count);
+  tags.truncate(params.limit.unwrap_or(DEFAULT_LIMIT));
+  Ok(tags)
+}
+
+/// Rename a tag on every memory using it.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Current and new tag name, and whether to only report what would change
+///
+/// # Returns
+/// * `Ok(TagUpdateResult)` - The memories retagged
+/// * `Err(ServiceError)` - If a name is empty, the new name is already in use, or database error
+pub async fn rename(ctx: &MemoryContext<'_>, params: TagRenameParams) -> Result<TagUpdateResult, ServiceError> {
+  let from = tag_name(&params.from)?;
+  let to = tag_name(&params.to)?;
+  if from == to {
+    return Err(ServiceError::validation(format!("Tag {} is already named that", from)));
+  }
+
+  let memories = ctx.db.list_memories(None, None).await?;
+  if memories.iter().any(|m| m.tags.contains(&to)) {
+    return Err(ServiceError::validation(format!(
+      "Tag {} is already in use; merge {} into it instead",
+      to, from
+    )));
+  }
+  apply(ctx, memories, &[from], Some(&to), params.dry_run).await
+}
+
+/// Replace several tags with one on every memory using them.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Tags to merge, the tag they become, and whether to only report what would change
+///
+/// # Returns
+/// * `Ok(TagUpdateResult)` - The memories retagged
+/// * `Err(ServiceError)` - If no other tag is given, a name is empty, or database error
+pub async fn merge(ctx: &MemoryContext<'_>, params: TagMergeParams) -> Result<TagUpdateResult, ServiceError> {
+  let into = tag_name(&params.into)?;
+  let mut from = Vec::with_capacity(params.tags.len());
+  for tag in &params.tags {
+    let tag = tag_name(tag)?;
+    if tag != into && !from.contains(&tag) {
+      from.push(tag);
+    }
+  }
+  if from.is_empty() {
+    return Err(ServiceError::validation(format!("No tags to merge into {}", into)));
+  }
+
+  let memories = ctx.db.list_memories(None, None).await?;
+  apply(ctx, memories, &from, Some(&into), params.dry_run).await
+}
+
+/// Remove tags from every memory.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Tags to remove, and whether to only report what would change
+///
+/// # Returns
+/// * `Ok(TagUpdateResult)` - The memories the tags were removed from
+/// * `Err(ServiceError)` - If no tag is given, a name is empty, or database error
+pub async fn delete(ctx: &MemoryContext<'_>, params: TagDeleteParams) -> Result<TagUpdateResult, ServiceError> {
+  if params.tags.is_empty() {
+    return Err(ServiceError::validation("No tags to delete"));
+  }
+  let tags = params.tags.iter().map(|t| tag_name(t)).collect::<Result<Vec<_>, _>>()?;
+
+  let memories = ctx.db.list_memories(None, None).await?;
+  apply(ctx, memories, &tags, None, params.dry_run).await
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|t| t.to_string()).collect()
+  }
+
+  #[test]
+  fn test_retag() {
+    let from = tags(&["postgres", "pg"]);
+    assert_eq!(
+      retag(&tags(&["db", "postgres", "auth"]), &from, Some("postgresql")),
+      Some(tags(&["db", "postgresql", "auth"]))
+    );
+    assert_eq!(
+      retag(&tags(&["pg", "postgresql", "postgres"]), &from, Some("postgresql")),
+      Some(tags(&["postgresql"])),
+      "merged synonyms collapse into one tag"
+    );
+    assert_eq!(
+      retag(&tags(&["db", "pg"]), &from, None),
+      Some(tags(&["db"])),
+      "no target removes the tags"
+    );
+    assert_eq!(retag(&tags(&["db", "auth"]), &from, Some("postgresql")), None);
+  }
+
+  #[test]
+  fn test_count_orders_by_use() {
+    let memory = |tags: &[&str], age_days: i64| {
+      let mut memory = Memory::new(Uuid::nil(), "Use pnpm".to_string(), Sector::Semantic);
+      memory.tags = tags.iter().map(|t| t.to_string()).collect();
+      memory.updated_at -= Duration::days(age_days);
+      memory
+    };
+    let memories = vec![
+      memory(&["redis", "cache"], 3),
+      memory(&["auth"], 2),
+      memory(&["redis"], 1),
+    ];
+
+    let counted = count(&memories);
+    let names: Vec<(&str, usize)> = counted.iter().map(|t| (t.tag.as_str(), t.memories)).collect();
+    assert_eq!(
+      names,
+      vec![("redis", 2), ("auth", 1), ("cache", 1)],
+      "most used first, then by name"
+    );
+    assert_eq!(
+      counted[0].last_used,
+      Some(memories[2].updated_at.to_rfc3339()),
+      "the most recent memory using the tag"
+    );
+  }
+}
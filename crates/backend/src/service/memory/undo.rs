@@ -0,0 +1,200 @@
+//! Undo for destructive memory operations.
+//!
+//! Changes recorded in memory history together share a batch ID, so a
+//! `memory archive` run is one batch however many memories it deletes. Undo
+//! finds the newest batch of soft deletes and supersessions that is still in
+//! effect and reverts it: deleted memories are restored, superseded ones made
+//! current again, and salience put back to what it was before the change.
+//! A batch whose changes were all reverted since, by an earlier undo or by
+//! hand, is skipped. Hard deletes can't be undone.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{MemoryContext, record_history};
+use crate::{
+  db::{MemoryChange, MemoryEvent},
+  domain::memory::{Memory, MemoryEventAction, MemoryId},
+  ipc::types::memory::{MemoryUndoItem, MemoryUndoParams, MemoryUndoResult},
+  service::util::ServiceError,
+};
+
+/// Actions undo reverts
+const UNDOABLE: &[MemoryEventAction] = &[MemoryEventAction::Deleted, MemoryEventAction::Superseded];
+
+/// Events grouped by batch, in the order the batches first appear
+fn batches(events: Vec<MemoryEvent>) -> Vec<(Uuid, Vec<MemoryEvent>)> {
+  let mut batches: Vec<(Uuid, Vec<MemoryEvent>)> = Vec::new();
+  for event in events {
+    match batches.iter_mut().find(|(id, _)| *id == event.batch_id) {
+      Some((_, batch)) => batch.push(event),
+      None => batches.push((event.batch_id, vec![event])),
+    }
+  }
+  batches
+}
+
+/// Whether the change `event` recorded still holds for `memory`
+fn in_effect(event: &MemoryEvent, memory: &Memory) -> bool {
+  match event.action {
+    MemoryEventAction::Deleted => memory.is_deleted,
+    MemoryEventAction::Superseded => memory
+      .superseded_by
+      .is_some_and(|id| event.detail.as_deref() == Some(id.to_string().as_str())),
+    _ => false,
+  }
+}
+
+/// Revert the change `event` recorded on `memory`
+fn revert(event: &MemoryEvent, memory: &mut Memory) {
+  let now = Utc::now();
+  match event.action {
+    MemoryEventAction::Deleted => memory.restore(now),
+    MemoryEventAction::Superseded => {
+      memory.superseded_by = None;
+      memory.valid_until = None;
+      memory.updated_at = now;
+    }
+    _ => {}
+  }
+  if let Some(salience) = event.salience_before {
+    memory.salience = salience;
+  }
+}
+
+/// Revert the most recent batch of deletes and supersessions still in effect.
+///
+/// # Arguments
+/// * `ctx` - Memory context with database
+/// * `params` - Whether to only report what would be reverted
+///
+/// # Returns
+/// * `Ok(MemoryUndoResult)` - The reverted batch, without a batch ID when there was nothing to undo
+/// * `Err(ServiceError)` - If database error
+pub async fn undo(ctx: &MemoryContext<'_>, params: MemoryUndoParams) -> Result<MemoryUndoResult, ServiceError> {
+  let events = ctx.db.list_memory_events(UNDOABLE).await?;
+
+  for (batch_id, batch) in batches(events) {
+    let mut pending: Vec<(MemoryEvent, Memory)> = Vec::new();
+    for event in batch {
+      let Ok(memory_id) = event.memory_id.parse::<MemoryId>() else {
+        continue;
+      };
+      if let Some(memory) = ctx.db.get_memory(&memory_id).await?
+        && in_effect(&event, &memory)
+      {
+        pending.push((event, memory));
+      }
+    }
+    let Some((first, _)) = pending.first() else {
+      continue;
+    };
+
+    let mut result = MemoryUndoResult {
+      batch_id: Some(batch_id.to_string()),
+      actor: Some(first.actor.as_str().to_string()),
+      created_at: Some(first.created_at.to_rfc3339()),
+      reverted: Vec::with_capacity(pending.len()),
+      dry_run: params.dry_run,
+    };
+
+    let mut changes = Vec::new();
+    for (event, mut memory) in pending {
+      let salience_before = memory.salience;
+      revert(&event, &mut memory);
+      if !params.dry_run {
+        ctx.db.update_memory(&memory, None).await?;
+        changes.push(
+          MemoryChange::new(memory.id, MemoryEventAction::Restored)
+            .with_salience(Some(salience_before), Some(memory.salience))
+            .with_detail(format!("undo {}", event.action.as_str())),
+        );
+      }
+      result.reverted.push(MemoryUndoItem {
+        memory_id: memory.id.to_string(),
+        action: event.action.as_str().to_string(),
+        content: memory.content.clone(),
+        salience: memory.salience,
+      });
+    }
+    record_history(ctx.db, ctx.actor, &changes).await;
+
+    return Ok(result);
+  }
+
+  Ok(MemoryUndoResult {
+    batch_id: None,
+    actor: None,
+    created_at: None,
+    reverted: Vec::new(),
+    dry_run: params.dry_run,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+
+  use super::*;
+  use crate::domain::memory::{MemoryActor, Sector};
+
+  fn event(memory: &Memory, action: MemoryEventAction, batch_id: Uuid) -> MemoryEvent {
+    MemoryEvent {
+      id: Uuid::now_v7(),
+      memory_id: memory.id.to_string(),
+      action,
+      actor: MemoryActor::Cli,
+      salience_before: Some(0.4),
+      salience_after: Some(0.4),
+      detail: None,
+      created_at: Utc::now(),
+      batch_id,
+    }
+  }
+
+  #[test]
+  fn test_batches_keep_newest_first_order() {
+    let memory = Memory::new(Uuid::nil(), "Use pnpm".to_string(), Sector::Semantic);
+    let (newer, older) = (Uuid::now_v7(), Uuid::now_v7());
+    let events = vec![
+      event(&memory, MemoryEventAction::Deleted, newer),
+      event(&memory, MemoryEventAction::Deleted, newer),
+      event(&memory, MemoryEventAction::Deleted, older),
+    ];
+
+    let grouped = batches(events);
+    assert_eq!(grouped.len(), 2);
+    assert_eq!(grouped[0].0, newer);
+    assert_eq!(grouped[0].1.len(), 2);
+  }
+
+  #[test]
+  fn test_revert_restores_flags_and_salience() {
+    let mut deleted = Memory::new(Uuid::nil(), "Use pnpm".to_string(), Sector::Semantic);
+    deleted.delete(Utc::now());
+    deleted.salience = 0.2;
+    let delete = event(&deleted, MemoryEventAction::Deleted, Uuid::now_v7());
+    assert!(in_effect(&delete, &deleted));
+    revert(&delete, &mut deleted);
+    assert!(!deleted.is_deleted);
+    assert_eq!(deleted.salience, 0.4, "salience from before the delete");
+    assert!(
+      !in_effect(&delete, &deleted),
+      "reverted changes are no longer in effect"
+    );
+
+    let replacement = MemoryId::new();
+    let mut superseded = Memory::new(Uuid::nil(), "Use npm".to_string(), Sector::Semantic);
+    superseded.supersede(replacement, Utc::now() - Duration::hours(1));
+    let mut supersede = event(&superseded, MemoryEventAction::Superseded, Uuid::now_v7());
+    supersede.detail = Some(MemoryId::new().to_string());
+    assert!(
+      !in_effect(&supersede, &superseded),
+      "superseded again by another memory since"
+    );
+    supersede.detail = Some(replacement.to_string());
+    assert!(in_effect(&supersede, &superseded));
+    revert(&supersede, &mut superseded);
+    assert!(superseded.is_active());
+  }
+}
@@ -11,6 +11,7 @@
 //! - [`memory`] - Memory search, ranking, deduplication, lifecycle
 //! - [`explore`] - Unified cross-domain search and context retrieval
 //! - [`project`] - Project info, stats, and cleanup
+//! - [`usage`] - LLM and embedding cost reports
 
 pub mod code;
 pub mod docs;
@@ -18,6 +19,7 @@ pub mod explore;
 pub mod hooks;
 pub mod memory;
 pub mod project;
+pub mod usage;
 pub mod util;
 
 #[cfg(test)]
@@ -0,0 +1,205 @@
+//! Memory primer for `CLAUDE.md`.
+//!
+//! Claude Code reads `CLAUDE.md` at the start of every session. A primer is
+//! a Markdown block of the project's highest-salience preferences, decisions
+//! and gotchas, fitted to a token budget, so a session starts with them
+//! without calling any MCP tool. Written to a file, it replaces only the text
+//! between the ccengram markers, like the active context file.
+
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use super::context_file::{item_text, splice_block};
+use crate::{
+  db::{FilterBuilder, ProjectDb},
+  domain::{
+    config::CHARS_PER_TOKEN,
+    memory::{Memory, MemoryType},
+  },
+  ipc::project::{ContextPackParams, ContextPackResult},
+  service::util::ServiceError,
+};
+
+/// File the primer is written to, relative to the project root
+pub const CLAUDE_MD: &str = "CLAUDE.md";
+
+/// Token budget when none is given
+pub const DEFAULT_PRIMER_BUDGET: usize = 4000;
+
+const BLOCK_START: &str = "<!-- ccengram:context-pack:start -->";
+const BLOCK_END: &str = "<!-- ccengram:context-pack:end -->";
+
+/// Memory types in the primer, in the order their sections appear
+const SECTIONS: [(MemoryType, &str); 3] = [
+  (MemoryType::Preference, "Preferences"),
+  (MemoryType::Decision, "Decisions"),
+  (MemoryType::Gotcha, "Gotchas"),
+];
+
+/// Build the primer and, when asked, write it into `CLAUDE.md`.
+///
+/// `root` is the project root; `params.file` overrides the file written.
+/// Nothing is written while there are no memories to list and the file has
+/// no primer yet.
+pub async fn pack(db: &ProjectDb, root: &Path, params: &ContextPackParams) -> Result<ContextPackResult, ServiceError> {
+  let budget = params.budget.unwrap_or(DEFAULT_PRIMER_BUDGET);
+  if budget == 0 {
+    return Err(ServiceError::validation("Budget must be greater than zero"));
+  }
+
+  let memories = load(db).await?;
+  let (markdown, included) = render(&memories, budget);
+  let mut result = ContextPackResult {
+    tokens: markdown.len().div_ceil(CHARS_PER_TOKEN),
+    markdown,
+    memories: included,
+    omitted: memories.len() - included,
+    path: None,
+    written: false,
+  };
+  if !params.write {
+    return Ok(result);
+  }
+
+  let path = params.file.as_ref().map_or_else(|| root.join(CLAUDE_MD), PathBuf::from);
+  result.path = Some(path.to_string_lossy().to_string());
+  let existing = match tokio::fs::read_to_string(&path).await {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(e) => {
+      return Err(ServiceError::internal(format!(
+        "Failed to read {}: {}",
+        path.display(),
+        e
+      )));
+    }
+  };
+  if included == 0 && !existing.contains(BLOCK_START) {
+    return Ok(result);
+  }
+
+  let block = format!("{}\n{}{}\n", BLOCK_START, result.markdown, BLOCK_END);
+  let updated = splice_block(&existing, &block, BLOCK_START, BLOCK_END);
+  if updated != existing {
+    tokio::fs::write(&path, updated)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+    result.written = true;
+  }
+
+  debug!(
+    path = %path.display(),
+    memories = result.memories,
+    tokens = result.tokens,
+    written = result.written,
+    "Context pack for CLAUDE.md"
+  );
+  Ok(result)
+}
+
+/// Live team preferences, decisions and gotchas, highest salience first.
+///
+/// `CLAUDE.md` is usually committed, so private memories are never listed.
+async fn load(db: &ProjectDb) -> Result<Vec<Memory>, ServiceError> {
+  let types: Vec<&str> = SECTIONS.iter().map(|(memory_type, _)| memory_type.as_str()).collect();
+  let filter = FilterBuilder::new()
+    .exclude_deleted()
+    .exclude_superseded()
+    .add_in("memory_type", &types)
+    .build_or_empty();
+  let mut memories = db.list_memories(Some(&filter), None).await?;
+  memories.retain(|m| !m.is_superseded() && m.visibility.is_shared());
+  memories.sort_by(|a, b| {
+    b.salience
+      .total_cmp(&a.salience)
+      .then_with(|| b.importance.total_cmp(&a.importance))
+  });
+  Ok(memories)
+}
+
+/// Render the primer from memories sorted best first.
+///
+/// Memories are taken in order while they fit `max_tokens`; one too long to
+/// fit is skipped so shorter ones after it can still be listed. Returns the
+/// Markdown and how many memories it lists.
+fn render(memories: &[Memory], max_tokens: usize) -> (String, usize) {
+  let header =
+    "## Project memory\n\n_Maintained by `ccengram context pack --write`; edits inside this block are overwritten._\n";
+  let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+  let mut used = header.len() + BLOCK_START.len() + BLOCK_END.len() + 2;
+  let mut sections: [Vec<String>; 3] = Default::default();
+  let mut included = 0;
+
+  for memory in memories {
+    let Some(section) = SECTIONS.iter().position(|(t, _)| Some(*t) == memory.memory_type) else {
+      continue;
+    };
+    let line = format!("- {}\n", item_text(memory));
+    let heading = if sections[section].is_empty() {
+      SECTIONS[section].1.len() + 6
+    } else {
+      0
+    };
+    if used + heading + line.len() > budget {
+      continue;
+    }
+    used += heading + line.len();
+    sections[section].push(line);
+    included += 1;
+  }
+
+  let mut out = header.to_string();
+  if included == 0 {
+    out.push_str("\nNo preferences, decisions or gotchas yet.\n");
+  }
+  for ((_, heading), lines) in SECTIONS.iter().zip(sections) {
+    if lines.is_empty() {
+      continue;
+    }
+    out.push_str(&format!("\n### {}\n", heading));
+    for line in lines {
+      out.push_str(&line);
+    }
+  }
+  (out, included)
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn memory(content: &str, memory_type: MemoryType) -> Memory {
+    let mut memory = Memory::new(Uuid::nil(), content.to_string(), Sector::Semantic);
+    memory.memory_type = Some(memory_type);
+    memory
+  }
+
+  #[test]
+  fn test_render_groups_by_type_within_budget() {
+    let memories = vec![
+      memory("Use pnpm, never npm", MemoryType::Preference),
+      memory("The cache must be warmed before the load test", MemoryType::Gotcha),
+      memory(&format!("Long decision {}", "x".repeat(2000)), MemoryType::Decision),
+      memory("Keep API errors in RFC 7807 format", MemoryType::Decision),
+    ];
+
+    let (markdown, included) = render(&memories, 100);
+    assert_eq!(included, 3, "the memory too long for the budget is skipped");
+    assert!(markdown.len() <= 100 * CHARS_PER_TOKEN, "primer fits the budget");
+    let preferences = markdown
+      .find("### Preferences\n- Use pnpm")
+      .expect("preferences listed");
+    let decisions = markdown
+      .find("### Decisions\n- Keep API errors")
+      .expect("shorter decision listed");
+    let gotchas = markdown.find("### Gotchas\n- The cache").expect("gotchas listed");
+    assert!(
+      preferences < decisions && decisions < gotchas,
+      "sections keep their order"
+    );
+  }
+}
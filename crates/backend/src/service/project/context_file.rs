@@ -0,0 +1,291 @@
+//! Active context file.
+//!
+//! Claude Code loads `.claude/CLAUDE.local.md` into every session, hooks or
+//! not. This keeps a short block of memory highlights there: pinned memories,
+//! recent decisions and the open tasks of running sessions. Only the text
+//! between the ccengram markers is replaced, so the rest of the file is kept.
+
+use std::path::Path;
+
+use chrono::{Duration, Utc};
+use tracing::debug;
+
+use crate::{
+  db::{FilterBuilder, ProjectDb},
+  domain::{
+    config::CHARS_PER_TOKEN,
+    memory::{Memory, MemoryType},
+  },
+  service::util::ServiceError,
+};
+
+/// Context file, relative to the project root
+pub const CONTEXT_FILE: &str = ".claude/CLAUDE.local.md";
+
+const BLOCK_START: &str = "<!-- ccengram:active-context:start -->";
+const BLOCK_END: &str = "<!-- ccengram:active-context:end -->";
+
+/// Importance at which a memory counts as pinned
+const PINNED_IMPORTANCE: f32 = 0.8;
+
+/// Age of the oldest decision listed
+const RECENT_DECISION_DAYS: i64 = 14;
+
+/// Most items listed per section
+const MAX_SECTION_ITEMS: usize = 10;
+
+/// Longest a single item is shown
+const MAX_ITEM_CHARS: usize = 300;
+
+/// Highlights rendered into the context file
+#[derive(Debug, Default)]
+pub struct ActiveContext {
+  pub pinned: Vec<Memory>,
+  pub decisions: Vec<Memory>,
+  pub open_tasks: Vec<String>,
+}
+
+impl ActiveContext {
+  pub fn is_empty(&self) -> bool {
+    self.pinned.is_empty() && self.decisions.is_empty() && self.open_tasks.is_empty()
+  }
+}
+
+/// Load pinned memories and recent decisions for the context file
+pub async fn load(db: &ProjectDb, open_tasks: Vec<String>) -> Result<ActiveContext, ServiceError> {
+  let mut pinned = db
+    .list_memories(
+      Some(&format!(
+        "is_deleted = false AND superseded_by IS NULL AND importance >= {}",
+        PINNED_IMPORTANCE
+      )),
+      None,
+    )
+    .await?;
+  pinned.retain(|m| !m.is_superseded());
+  pinned.sort_by(|a, b| {
+    b.importance
+      .total_cmp(&a.importance)
+      .then_with(|| b.salience.total_cmp(&a.salience))
+  });
+  pinned.truncate(MAX_SECTION_ITEMS);
+
+  let since = Utc::now() - Duration::days(RECENT_DECISION_DAYS);
+  let filter = FilterBuilder::new()
+    .exclude_deleted()
+    .exclude_superseded()
+    .add_eq("memory_type", MemoryType::Decision.as_str())
+    .add_since("created_at", since)
+    .build_or_empty();
+  let mut decisions = db.list_memories(Some(&filter), None).await?;
+  decisions.retain(|m| !m.is_superseded() && !pinned.iter().any(|p| p.id == m.id));
+  decisions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+  decisions.truncate(MAX_SECTION_ITEMS);
+
+  Ok(ActiveContext {
+    pinned,
+    decisions,
+    open_tasks,
+  })
+}
+
+/// Regenerate the highlights block in the project's context file.
+///
+/// Returns whether the file changed. Nothing is written while there are no
+/// highlights and the file has no block yet.
+pub async fn refresh(
+  db: &ProjectDb,
+  root: &Path,
+  open_tasks: Vec<String>,
+  max_tokens: usize,
+) -> Result<bool, ServiceError> {
+  let context = load(db, open_tasks).await?;
+  let path = root.join(CONTEXT_FILE);
+
+  let existing = match tokio::fs::read_to_string(&path).await {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(e) => {
+      return Err(ServiceError::internal(format!(
+        "Failed to read {}: {}",
+        path.display(),
+        e
+      )));
+    }
+  };
+  if context.is_empty() && !existing.contains(BLOCK_START) {
+    return Ok(false);
+  }
+
+  let updated = splice_block(&existing, &render(&context, max_tokens), BLOCK_START, BLOCK_END);
+  if updated == existing {
+    return Ok(false);
+  }
+
+  if let Some(parent) = path.parent() {
+    tokio::fs::create_dir_all(parent)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to create {}: {}", parent.display(), e)))?;
+  }
+  tokio::fs::write(&path, updated)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+
+  debug!(
+    path = %path.display(),
+    pinned = context.pinned.len(),
+    decisions = context.decisions.len(),
+    open_tasks = context.open_tasks.len(),
+    "Refreshed active context file"
+  );
+  Ok(true)
+}
+
+/// Render the highlights block, dropping items once `max_tokens` is reached
+fn render(context: &ActiveContext, max_tokens: usize) -> String {
+  let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+  let mut out = format!(
+    "{}\n## Project memory highlights\n\n_Maintained by ccengram; edits inside this block are overwritten._\n",
+    BLOCK_START
+  );
+
+  let sections: [(&str, Vec<String>); 3] = [
+    (
+      "Pinned",
+      context.pinned.iter().map(|m| format!("- {}", item_text(m))).collect(),
+    ),
+    (
+      "Recent decisions",
+      context
+        .decisions
+        .iter()
+        .map(|m| format!("- ({}) {}", m.created_at.format("%Y-%m-%d"), item_text(m)))
+        .collect(),
+    ),
+    (
+      "Open tasks",
+      context
+        .open_tasks
+        .iter()
+        .map(|t| format!("- [ ] {}", truncate(t)))
+        .collect(),
+    ),
+  ];
+
+  if context.is_empty() {
+    out.push_str("\nNo highlights yet.\n");
+  }
+  for (heading, lines) in sections {
+    let header = format!("\n### {}\n", heading);
+    if lines.is_empty() || out.len() + header.len() + lines[0].len() + BLOCK_END.len() > budget {
+      continue;
+    }
+    out.push_str(&header);
+    for line in lines {
+      if out.len() + line.len() + 1 + BLOCK_END.len() > budget {
+        break;
+      }
+      out.push_str(&line);
+      out.push('\n');
+    }
+  }
+
+  out.push_str(BLOCK_END);
+  out.push('\n');
+  out
+}
+
+pub(super) fn item_text(memory: &Memory) -> String {
+  truncate(memory.summary.as_deref().unwrap_or(&memory.content))
+}
+
+/// Flatten to one line and cut to [`MAX_ITEM_CHARS`]
+fn truncate(text: &str) -> String {
+  let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  match line.char_indices().nth(MAX_ITEM_CHARS) {
+    Some((end, _)) => format!("{}...", &line[..end]),
+    None => line,
+  }
+}
+
+/// Replace the block between `start_marker` and `end_marker` in `existing`,
+/// or append `block` when there is none
+pub(super) fn splice_block(existing: &str, block: &str, start_marker: &str, end_marker: &str) -> String {
+  if let Some(start) = existing.find(start_marker)
+    && let Some(end) = existing[start..].find(end_marker)
+  {
+    let mut end = start + end + end_marker.len();
+    if existing[end..].starts_with('\n') {
+      end += 1;
+    }
+    return format!("{}{}{}", &existing[..start], block, &existing[end..]);
+  }
+
+  let mut out = existing.to_string();
+  if !out.is_empty() {
+    if !out.ends_with('\n') {
+      out.push('\n');
+    }
+    out.push('\n');
+  }
+  out.push_str(block);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::domain::memory::Sector;
+
+  fn memory(content: &str) -> Memory {
+    Memory::new(Uuid::nil(), content.to_string(), Sector::Semantic)
+  }
+
+  #[test]
+  fn test_splice_keeps_surrounding_text() {
+    let first = splice_block(
+      "# My notes\n",
+      "<!-- ccengram:active-context:start -->\nold\n<!-- ccengram:active-context:end -->\n",
+      BLOCK_START,
+      BLOCK_END,
+    );
+    assert!(first.starts_with("# My notes\n\n"), "block is appended after user text");
+
+    let second = splice_block(
+      &format!("{}More notes\n", first),
+      "<!-- ccengram:active-context:start -->\nnew\n<!-- ccengram:active-context:end -->\n",
+      BLOCK_START,
+      BLOCK_END,
+    );
+    assert_eq!(
+      second,
+      "# My notes\n\n<!-- ccengram:active-context:start -->\nnew\n<!-- ccengram:active-context:end -->\nMore notes\n",
+      "only the block is replaced"
+    );
+  }
+
+  #[test]
+  fn test_render_respects_budget() {
+    let context = ActiveContext {
+      pinned: vec![memory("Always run migrations through the staging database first")],
+      decisions: (0..10)
+        .map(|i| memory(&format!("Decision {} {}", i, "x".repeat(200))))
+        .collect(),
+      open_tasks: vec!["Update the API docs".to_string()],
+    };
+
+    let block = render(&context, 150);
+    assert!(block.len() <= 150 * CHARS_PER_TOKEN, "block fits the budget");
+    assert!(
+      block.contains("### Pinned\n- Always run migrations"),
+      "pinned memories come first"
+    );
+    assert!(block.ends_with(&format!("{}\n", BLOCK_END)), "block is closed");
+    assert!(
+      block.matches("- (").count() < 10,
+      "decisions beyond the budget are dropped"
+    );
+  }
+}
@@ -0,0 +1,159 @@
+//! Merging the stores of two clones of one repository.
+//!
+//! Symlinked checkouts and re-clones get separate ProjectIds, and with them
+//! separate memory stores. Merging copies the memories of one store into the
+//! other, skipping those the duplicate checks find there already, removes the
+//! emptied store and records its project as merged so later requests for it
+//! use the kept store.
+
+use std::{path::Path, sync::Arc};
+
+use tracing::{debug, info};
+
+use super::registry;
+use crate::{
+  context::memory::extract::dedup::compute_hashes,
+  db::ProjectDb,
+  disk,
+  domain::{config::Config, project::ProjectMetadata},
+  ipc::project::{ProjectDuplicateGroup, ProjectDuplicateItem, ProjectMergeParams, ProjectMergeResult},
+  service::{
+    memory::{entity, find_duplicate},
+    util::ServiceError,
+  },
+};
+
+/// A resolved merge, ready to run once both projects are stopped
+#[derive(Debug, Clone)]
+pub struct MergePlan {
+  pub from: ProjectMetadata,
+  pub into: ProjectMetadata,
+}
+
+/// Duplicate groups with their store sizes, richest index first
+pub async fn duplicates(data_dir: &Path) -> Result<Vec<ProjectDuplicateGroup>, ServiceError> {
+  let mut groups = Vec::new();
+  for group in registry::find_duplicates(data_dir).await {
+    let mut projects = Vec::with_capacity(group.len());
+    for project in group {
+      projects.push(describe(data_dir, &project).await?);
+    }
+    projects.sort_by(|a, b| {
+      b.indexed_files
+        .cmp(&a.indexed_files)
+        .then_with(|| b.memories.cmp(&a.memories))
+    });
+    groups.push(ProjectDuplicateGroup { projects });
+  }
+  Ok(groups)
+}
+
+async fn describe(data_dir: &Path, project: &ProjectMetadata) -> Result<ProjectDuplicateItem, ServiceError> {
+  let db = open(data_dir, project).await?;
+  let (memories, indexed_files) = tokio::join!(
+    db.list_memories(Some("is_deleted = false"), None),
+    db.count_indexed_files(project.id.as_str())
+  );
+  Ok(ProjectDuplicateItem {
+    id: project.id.to_string(),
+    path: project.path.to_string_lossy().to_string(),
+    name: project.name.clone(),
+    memories: memories?.len(),
+    indexed_files: indexed_files?,
+  })
+}
+
+/// Resolve both projects and check they are clones of one repository.
+///
+/// `cwd` resolves relative paths in `params`.
+pub async fn prepare(data_dir: &Path, params: &ProjectMergeParams, cwd: &Path) -> Result<MergePlan, ServiceError> {
+  let from = recorded(data_dir, &params.from, cwd).await?;
+  let into = recorded(data_dir, &params.into, cwd).await?;
+
+  if from.id == into.id {
+    return Err(ServiceError::validation("Cannot merge a project into itself"));
+  }
+  if from.fingerprint.is_none() || from.fingerprint != into.fingerprint {
+    return Err(ServiceError::validation(format!(
+      "{} and {} are not clones of the same repository",
+      from.path.display(),
+      into.path.display()
+    )));
+  }
+
+  // Copied memories can take up to the size of the store they come from
+  let needed = disk::dir_size(&from.id.data_dir(data_dir));
+  disk::ensure_space(&into.id.data_dir(data_dir), needed).map_err(|e| ServiceError::project(e.to_string()))?;
+  Ok(MergePlan { from, into })
+}
+
+async fn recorded(data_dir: &Path, project: &str, cwd: &Path) -> Result<ProjectMetadata, ServiceError> {
+  let found = registry::find(data_dir, project, cwd).await?;
+  let metadata = registry::read(&found.id.data_dir(data_dir))
+    .await
+    .ok_or_else(|| ServiceError::not_found("Project record", project))?;
+  if let Some(into) = metadata.merged_into {
+    return Err(ServiceError::validation(format!(
+      "{} was already merged into project {}",
+      metadata.path.display(),
+      into
+    )));
+  }
+  Ok(metadata)
+}
+
+/// Copy memories from `plan.from` into `plan.into` and retire `plan.from`.
+pub async fn run(data_dir: &Path, plan: &MergePlan) -> Result<ProjectMergeResult, ServiceError> {
+  let from_db = open(data_dir, &plan.from).await?;
+  let into_db = open(data_dir, &plan.into).await?;
+  let into_uuid = plan.into.id.uuid();
+
+  let mut result = ProjectMergeResult {
+    kept_id: plan.into.id.to_string(),
+    kept_path: plan.into.path.to_string_lossy().to_string(),
+    removed_id: plan.from.id.to_string(),
+    removed_path: plan.from.path.to_string_lossy().to_string(),
+    memories_copied: 0,
+    duplicates_skipped: 0,
+  };
+
+  for mut memory in from_db.list_memories(Some("is_deleted = false"), None).await? {
+    let Some(vector) = from_db.get_memory_embedding(&memory.id).await? else {
+      debug!(memory_id = %memory.id, "Memory has no embedding, not copied");
+      continue;
+    };
+
+    let (content_hash, simhash) = compute_hashes(&memory.content);
+    if let Some(duplicate) = find_duplicate(&into_db, &vector, &memory.content, &content_hash, simhash).await? {
+      debug!(memory_id = %memory.id, existing = %duplicate.id, reason = duplicate.reason, "Memory already in kept store");
+      result.duplicates_skipped += 1;
+      continue;
+    }
+
+    memory.project_id = into_uuid;
+    into_db.add_memory(&memory, &vector).await?;
+    entity::copy(&from_db, &into_db, &memory.id).await?;
+    result.memories_copied += 1;
+  }
+
+  drop(from_db);
+  let from_dir = plan.from.id.data_dir(data_dir);
+  tokio::fs::remove_dir_all(&from_dir)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to remove {}: {}", from_dir.display(), e)))?;
+  registry::record_merged(data_dir, plan.from.clone(), &plan.into.id).await?;
+
+  info!(
+    from = %plan.from.id,
+    into = %plan.into.id,
+    copied = result.memories_copied,
+    skipped = result.duplicates_skipped,
+    "Merged project stores"
+  );
+  Ok(result)
+}
+
+async fn open(data_dir: &Path, project: &ProjectMetadata) -> Result<ProjectDb, ServiceError> {
+  let config = Arc::new(Config::load_for_project(&project.path).await);
+  Ok(ProjectDb::open(project.id.clone(), data_dir, config).await?)
+}
@@ -0,0 +1,223 @@
+//! Moving a project's data after the project directory moved.
+//!
+//! The data directory of the old ID is renamed to the ID of the new root,
+//! stored project IDs are rebound, and stored paths under the old root are
+//! rewritten to the new one. Actors of both IDs must be stopped between
+//! [`prepare`] and [`run`] so neither holds the databases open.
+
+use std::{collections::BTreeSet, path::Path, sync::Arc};
+
+use tracing::info;
+
+use super::registry::{self, ProjectData};
+use crate::{
+  db::ProjectDb,
+  domain::{
+    config::Config,
+    project::{ProjectId, resolve_project_path},
+  },
+  ipc::project::{ProjectMigrateParams, ProjectMigrateResult},
+  service::util::ServiceError,
+};
+
+/// A resolved migration, ready to run once both projects are stopped
+#[derive(Debug, Clone)]
+pub struct MigratePlan {
+  pub from: ProjectData,
+  pub to_id: ProjectId,
+  pub to_root: std::path::PathBuf,
+}
+
+/// Resolve the source data and the new project root.
+///
+/// `cwd` resolves relative paths in `params`.
+pub async fn prepare(data_dir: &Path, params: &ProjectMigrateParams, cwd: &Path) -> Result<MigratePlan, ServiceError> {
+  let from = registry::find(data_dir, &params.from, cwd).await?;
+
+  let to = cwd.join(&params.to);
+  if !to.is_dir() {
+    return Err(ServiceError::validation(format!(
+      "New project path {} is not a directory",
+      to.display()
+    )));
+  }
+  let to_root = resolve_project_path(&to).await;
+  let to_id = ProjectId::from_path(&to_root).await;
+  if to_id == from.id {
+    return Err(ServiceError::validation(format!(
+      "{} already uses the data of project {}",
+      to_root.display(),
+      to_id
+    )));
+  }
+
+  Ok(MigratePlan { from, to_id, to_root })
+}
+
+/// Move the data and rewrite stored paths.
+///
+/// Refuses when the new root already has memories or sessions of its own. A
+/// data directory holding only a code index, as created by opening the moved
+/// project before migrating, is replaced.
+pub async fn run(data_dir: &Path, plan: &MigratePlan) -> Result<ProjectMigrateResult, ServiceError> {
+  let old_dir = plan.from.id.data_dir(data_dir);
+  let new_dir = plan.to_id.data_dir(data_dir);
+  let config = Arc::new(Config::load_for_project(&plan.to_root).await);
+
+  clear_unused_store(data_dir, &plan.to_id, &plan.to_root, &config, "migrating").await?;
+
+  tokio::fs::rename(&old_dir, &new_dir).await.map_err(|e| {
+    ServiceError::internal(format!(
+      "Failed to move {} to {}: {}",
+      old_dir.display(),
+      new_dir.display(),
+      e
+    ))
+  })?;
+
+  let db = ProjectDb::open(plan.to_id.clone(), data_dir, config).await?;
+  db.rebind_project(&plan.from.id).await?;
+
+  let (memories_updated, documents_updated) = match &plan.from.path {
+    Some(old_root) => rewrite_paths(&db, old_root, &plan.to_root).await?,
+    None => (0, 0),
+  };
+
+  registry::record(data_dir, &plan.to_id, &plan.to_root).await?;
+
+  info!(
+    from = %plan.from.id,
+    to = %plan.to_id,
+    root = %plan.to_root.display(),
+    memories_updated,
+    documents_updated,
+    "Migrated project data"
+  );
+
+  Ok(ProjectMigrateResult {
+    from_id: plan.from.id.to_string(),
+    to_id: plan.to_id.to_string(),
+    from_path: plan.from.path.as_ref().map(|p| p.to_string_lossy().to_string()),
+    to_path: plan.to_root.to_string_lossy().to_string(),
+    memories_updated,
+    documents_updated,
+  })
+}
+
+/// Remove the data directory of `id` unless it has memories or sessions.
+///
+/// A store holding only a code index, as created by opening the project
+/// before its data was brought in, is replaced. `action` names what would
+/// replace it in the error.
+pub(super) async fn clear_unused_store(
+  data_dir: &Path,
+  id: &ProjectId,
+  root: &Path,
+  config: &Arc<Config>,
+  action: &str,
+) -> Result<(), ServiceError> {
+  let dir = id.data_dir(data_dir);
+  if !dir.exists() {
+    return Ok(());
+  }
+
+  let existing = ProjectDb::open(id.clone(), data_dir, Arc::clone(config)).await?;
+  let (memories, sessions) = tokio::join!(
+    existing.list_memories(None, Some(1)),
+    existing.list_sessions(None, Some(1))
+  );
+  if !memories?.is_empty() || !sessions?.is_empty() {
+    return Err(ServiceError::project(format!(
+      "{} already has memories of its own; {} would replace them",
+      root.display(),
+      action
+    )));
+  }
+  drop(existing);
+  tokio::fs::remove_dir_all(&dir)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to remove {}: {}", dir.display(), e)))
+}
+
+/// Rewrite memory file lists, memory scopes and document sources under `old_root`
+pub(super) async fn rewrite_paths(
+  db: &ProjectDb,
+  old_root: &Path,
+  new_root: &Path,
+) -> Result<(usize, usize), ServiceError> {
+  let old_root = old_root.to_string_lossy();
+  let new_root = new_root.to_string_lossy();
+
+  let mut changed = Vec::new();
+  for mut memory in db.list_memories(None, None).await? {
+    let mut touched = false;
+    for file in &mut memory.files {
+      if let Some(rebased) = rebase(file, &old_root, &new_root) {
+        *file = rebased;
+        touched = true;
+      }
+    }
+    if let Some(rebased) = memory
+      .scope_path
+      .as_deref()
+      .and_then(|s| rebase(s, &old_root, &new_root))
+    {
+      memory.scope_path = Some(rebased);
+      touched = true;
+    }
+    if touched {
+      changed.push(memory);
+    }
+  }
+  let memories_updated = db.batch_update_memories(&changed).await?;
+
+  let sources: BTreeSet<String> = db
+    .list_document_chunks(None, None)
+    .await?
+    .into_iter()
+    .map(|chunk| chunk.source)
+    .collect();
+  let mut documents_updated = 0;
+  for source in sources {
+    if let Some(rebased) = rebase(&source, &old_root, &new_root) {
+      db.rename_document(&source, &rebased).await?;
+      documents_updated += 1;
+    }
+  }
+
+  Ok((memories_updated, documents_updated))
+}
+
+/// `path` with `old_root` replaced by `new_root`, if it lies under `old_root`
+fn rebase(path: &str, old_root: &str, new_root: &str) -> Option<String> {
+  let rest = path.strip_prefix(old_root)?;
+  (rest.is_empty() || rest.starts_with(['/', '\\'])).then(|| format!("{}{}", new_root, rest))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rebase_only_paths_under_root() {
+    assert_eq!(
+      rebase("/home/me/app/src/lib.rs", "/home/me/app", "/work/app"),
+      Some("/work/app/src/lib.rs".to_string())
+    );
+    assert_eq!(
+      rebase("/home/me/app", "/home/me/app", "/work/app"),
+      Some("/work/app".to_string()),
+      "the root itself is rebased"
+    );
+    assert_eq!(
+      rebase("/home/me/app-old/lib.rs", "/home/me/app", "/work/app"),
+      None,
+      "a sibling sharing the prefix is left alone"
+    );
+    assert_eq!(
+      rebase("src/lib.rs", "/home/me/app", "/work/app"),
+      None,
+      "relative paths are left alone"
+    );
+  }
+}
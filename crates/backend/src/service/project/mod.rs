@@ -3,6 +3,26 @@
 //! Provides operations for project management including:
 //! - Project statistics
 //! - Project cleanup
+//! - Session browsing
+//! - The active context file (`.claude/CLAUDE.local.md`)
+//! - The memory primer written into `CLAUDE.md`
+//! - The project registry, moving data after a project moves and merging
+//!   the stores of duplicate clones
+//! - Repairing tables quarantined because they could not be read
+//! - Auditing stored data for secrets
+//! - Exporting and importing project snapshots
+//! - Query analytics from the search log
+
+pub mod audit;
+pub mod claude_md;
+pub mod context_file;
+pub mod merge;
+pub mod migrate;
+pub mod registry;
+pub mod repair;
+pub mod search_stats;
+pub mod sessions;
+pub mod snapshot;
 
 use std::path::Path;
 
@@ -11,7 +31,7 @@ use uuid::Uuid;
 use crate::{
   db::ProjectDb,
   domain::project::ProjectId,
-  ipc::project::{ProjectCleanResult, ProjectInfoResult, ProjectStatsResult},
+  ipc::project::{ProjectCleanResult, ProjectInfoResult, ProjectStatsResult, TableStatsItem},
   service::util::ServiceError,
 };
 
@@ -44,6 +64,8 @@ pub async fn info(db: &ProjectDb, project_id: &ProjectId, root: &Path) -> Result
     document_count: 0,
     session_count: 0,
     db_path: String::new(), // Caller can fill this in if needed
+    moved_from: None,
+    duplicates: None,
   })
 }
 
@@ -64,14 +86,15 @@ pub async fn stats(
   project_uuid: &Uuid,
   root: &Path,
 ) -> Result<ProjectStatsResult, ServiceError> {
-  use std::collections::HashMap;
+  use std::collections::BTreeMap;
 
-  // Run all four queries in parallel - they read from different tables
-  let (memories_result, code_result, doc_result, sessions_result) = tokio::join!(
+  // Run all queries in parallel - they read from different tables
+  let (memories_result, code_result, doc_result, sessions_result, health_result) = tokio::join!(
     db.list_memories(None, None),
     db.list_code_chunks(None, None),
     db.list_document_chunks(None, None),
-    db.count_sessions(project_uuid)
+    db.count_sessions(project_uuid),
+    db.table_health()
   );
 
   let memories_list = memories_result.unwrap_or_default();
@@ -79,7 +102,7 @@ pub async fn stats(
 
   // Calculate memory stats
   let (memories_by_sector, average_salience) = if !memories_list.is_empty() {
-    let mut by_sector: HashMap<String, usize> = HashMap::new();
+    let mut by_sector: BTreeMap<String, usize> = BTreeMap::new();
     let mut total_salience = 0.0f32;
 
     for m in &memories_list {
@@ -97,6 +120,22 @@ pub async fn stats(
   let documents = doc_result.map(|d| d.len()).unwrap_or(0);
   let sessions = sessions_result.unwrap_or(0);
 
+  let tables = health_result.ok().map(|health| {
+    health
+      .into_iter()
+      .map(|t| TableStatsItem {
+        name: t.name,
+        rows: t.rows,
+        bytes: t.bytes,
+        fragments: t.fragments,
+        small_fragments: t.small_fragments,
+        vector_index: t.vector_index,
+        indexed_rows: t.indexed_rows,
+        unindexed_rows: t.unindexed_rows,
+      })
+      .collect()
+  });
+
   Ok(ProjectStatsResult {
     project_id: project_id.to_string(),
     path: root.to_string_lossy().to_string(),
@@ -106,6 +145,7 @@ pub async fn stats(
     sessions,
     memories_by_sector,
     average_salience,
+    tables,
   })
 }
 
@@ -0,0 +1,278 @@
+//! Project registry.
+//!
+//! Each project's data directory holds a `project.json` recording the root it
+//! was opened from. A ProjectId is only a hash of that root, so the record is
+//! what links orphaned data back to a path after the project moves, and what
+//! tells clones of one repository apart from unrelated projects.
+
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use crate::{
+  domain::{
+    git,
+    project::{ProjectId, ProjectMetadata},
+  },
+  service::util::ServiceError,
+};
+
+/// Registry record, inside each project's data directory
+pub const METADATA_FILE: &str = "project.json";
+
+/// Record the root a project was opened from.
+///
+/// Keeps the original `created_at` when the project was recorded before.
+pub async fn record(data_dir: &Path, id: &ProjectId, root: &Path) -> Result<(), ServiceError> {
+  let project_dir = id.data_dir(data_dir);
+  let created_at = read(&project_dir)
+    .await
+    .map(|existing| existing.created_at)
+    .unwrap_or_else(chrono::Utc::now);
+  let metadata = ProjectMetadata {
+    id: id.clone(),
+    path: root.to_path_buf(),
+    name: root
+      .file_name()
+      .map(|n| n.to_string_lossy().to_string())
+      .unwrap_or_else(|| "unknown".to_string()),
+    created_at,
+    fingerprint: git::repo_fingerprint(root).await,
+    merged_into: None,
+  };
+  write(&project_dir, &metadata).await
+}
+
+/// Record that a project's store was merged into `into`.
+///
+/// The project's data directory keeps only this record, which sends later
+/// requests for it to `into`.
+pub async fn record_merged(
+  data_dir: &Path,
+  mut metadata: ProjectMetadata,
+  into: &ProjectId,
+) -> Result<(), ServiceError> {
+  metadata.merged_into = Some(into.clone());
+  write(&metadata.id.data_dir(data_dir), &metadata).await
+}
+
+/// The project that `id` was merged into, if any
+pub async fn merged_target(data_dir: &Path, id: &ProjectId) -> Option<ProjectMetadata> {
+  let into = read(&id.data_dir(data_dir)).await?.merged_into?;
+  read(&into.data_dir(data_dir)).await
+}
+
+async fn write(project_dir: &Path, metadata: &ProjectMetadata) -> Result<(), ServiceError> {
+  let json = serde_json::to_string_pretty(metadata)
+    .map_err(|e| ServiceError::internal(format!("Failed to serialize project metadata: {}", e)))?;
+  tokio::fs::create_dir_all(&project_dir)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to create {}: {}", project_dir.display(), e)))?;
+  tokio::fs::write(project_dir.join(METADATA_FILE), json)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to write project metadata: {}", e)))
+}
+
+/// Read the record in one project data directory
+pub async fn read(project_dir: &Path) -> Option<ProjectMetadata> {
+  let content = tokio::fs::read_to_string(project_dir.join(METADATA_FILE)).await.ok()?;
+  serde_json::from_str(&content)
+    .inspect_err(|e| debug!(dir = %project_dir.display(), "Unreadable project metadata: {}", e))
+    .ok()
+}
+
+/// All recorded projects under the data directory
+pub async fn list(data_dir: &Path) -> Vec<ProjectMetadata> {
+  let mut projects = Vec::new();
+  let Ok(mut entries) = tokio::fs::read_dir(data_dir.join("projects")).await else {
+    return projects;
+  };
+  while let Ok(Some(entry)) = entries.next_entry().await {
+    if let Some(metadata) = read(&entry.path()).await {
+      projects.push(metadata);
+    }
+  }
+  projects
+}
+
+/// Recorded projects that look like earlier locations of `root`.
+///
+/// A candidate's recorded root no longer exists and has the same directory
+/// name as `root`. Renamed projects are not matched; they can still be
+/// migrated by path or ID.
+pub async fn find_moved(data_dir: &Path, id: &ProjectId, root: &Path) -> Vec<ProjectMetadata> {
+  let Some(name) = root.file_name() else {
+    return Vec::new();
+  };
+  let mut candidates: Vec<ProjectMetadata> = list(data_dir)
+    .await
+    .into_iter()
+    .filter(|p| &p.id != id && p.merged_into.is_none() && p.path.file_name() == Some(name) && !p.path.exists())
+    .collect();
+  candidates.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+  candidates
+}
+
+/// Groups of recorded projects that are clones of the same repository.
+///
+/// Only projects whose root still exists and that were not merged already
+/// are grouped; each group has at least two projects.
+pub async fn find_duplicates(data_dir: &Path) -> Vec<Vec<ProjectMetadata>> {
+  let mut groups: BTreeMap<String, Vec<ProjectMetadata>> = BTreeMap::new();
+  for project in list(data_dir).await {
+    if project.merged_into.is_none()
+      && project.path.exists()
+      && let Some(fingerprint) = project.fingerprint.clone()
+    {
+      groups.entry(fingerprint).or_default().push(project);
+    }
+  }
+  groups.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// Other recorded projects that are clones of the same repository as `id`
+pub async fn duplicates_of(data_dir: &Path, id: &ProjectId) -> Vec<ProjectMetadata> {
+  find_duplicates(data_dir)
+    .await
+    .into_iter()
+    .find(|group| group.iter().any(|p| &p.id == id))
+    .map(|group| group.into_iter().filter(|p| &p.id != id).collect())
+    .unwrap_or_default()
+}
+
+/// Data of a recorded or unrecorded project, found by ID, ID prefix or path
+#[derive(Debug, Clone)]
+pub struct ProjectData {
+  pub id: ProjectId,
+  /// Root the data was recorded for, when known
+  pub path: Option<PathBuf>,
+}
+
+/// Find the data directory for a project ID, ID prefix or (old) path.
+///
+/// Relative paths are taken from `cwd`. Paths are looked up in the registry
+/// first, then by the ID they hash to, since the old root usually no longer
+/// exists to resolve a git root from.
+pub async fn find(data_dir: &Path, project: &str, cwd: &Path) -> Result<ProjectData, ServiceError> {
+  let registered = list(data_dir).await;
+
+  if project.len() >= 4 && project.chars().all(|c| c.is_ascii_hexdigit()) {
+    let matches: Vec<&ProjectMetadata> = registered
+      .iter()
+      .filter(|p| p.id.as_str().starts_with(project))
+      .collect();
+    match matches.as_slice() {
+      [only] => {
+        return Ok(ProjectData {
+          id: only.id.clone(),
+          path: Some(only.path.clone()),
+        });
+      }
+      [] => {}
+      many => {
+        return Err(ServiceError::Ambiguous {
+          prefix: project.to_string(),
+          count: many.len(),
+          candidates: many.iter().map(|p| format!("{}  {}", p.id, p.path.display())).collect(),
+        });
+      }
+    }
+  }
+
+  let path = cwd.join(project);
+  if let Some(found) = registered.iter().find(|p| p.path == path) {
+    return Ok(ProjectData {
+      id: found.id.clone(),
+      path: Some(found.path.clone()),
+    });
+  }
+
+  let id = if path.exists() {
+    ProjectId::from_path(&path).await
+  } else {
+    ProjectId::from_path_exact(&path)
+  };
+  if id.data_dir(data_dir).exists() {
+    return Ok(ProjectData { id, path: Some(path) });
+  }
+
+  Err(ServiceError::not_found("Project data", project))
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_find_moved_matches_vanished_root_by_name() {
+    let data = TempDir::new().expect("temp dir");
+    let work = TempDir::new().expect("temp dir");
+    let old_root = work.path().join("old").join("app");
+    let new_root = work.path().join("new").join("app");
+    std::fs::create_dir_all(&new_root).expect("create new root");
+
+    let old_id = ProjectId::from_path_exact(&old_root);
+    let new_id = ProjectId::from_path_exact(&new_root);
+    record(data.path(), &old_id, &old_root).await.expect("record old");
+    record(data.path(), &new_id, &new_root).await.expect("record new");
+
+    let moved = find_moved(data.path(), &new_id, &new_root).await;
+    assert_eq!(moved.len(), 1, "the vanished root is a candidate");
+    assert_eq!(moved[0].id, old_id);
+
+    std::fs::create_dir_all(&old_root).expect("recreate old root");
+    assert!(
+      find_moved(data.path(), &new_id, &new_root).await.is_empty(),
+      "a root that still exists is not a move"
+    );
+
+    let found = find(data.path(), &old_id.as_str()[..8], work.path())
+      .await
+      .expect("find by prefix");
+    assert_eq!(found.path.as_deref(), Some(old_root.as_path()));
+  }
+
+  #[tokio::test]
+  async fn test_find_duplicates_groups_clones_of_one_repo() {
+    let data = TempDir::new().expect("temp dir");
+    let work = TempDir::new().expect("temp dir");
+    let mut clones = Vec::new();
+    for (name, url) in [
+      ("app", "git@github.com:acme/app.git"),
+      ("app-copy", "https://github.com/acme/app"),
+      ("other", "git@github.com:acme/other.git"),
+    ] {
+      let root = work.path().join(name);
+      std::fs::create_dir_all(root.join(".git")).expect("create git dir");
+      std::fs::write(
+        root.join(".git/config"),
+        format!("[remote \"origin\"]\n\turl = {}\n", url),
+      )
+      .expect("write git config");
+      let id = ProjectId::from_path_exact(&root);
+      record(data.path(), &id, &root).await.expect("record");
+      clones.push(id);
+    }
+
+    let groups = find_duplicates(data.path()).await;
+    assert_eq!(groups.len(), 1, "only the two clones of acme/app form a group");
+    assert_eq!(groups[0].len(), 2);
+
+    let metadata = read(&clones[1].data_dir(data.path())).await.expect("recorded");
+    record_merged(data.path(), metadata, &clones[0]).await.expect("merge");
+    assert!(
+      find_duplicates(data.path()).await.is_empty(),
+      "merged clones are not duplicates"
+    );
+    assert_eq!(
+      merged_target(data.path(), &clones[1]).await.map(|p| p.id),
+      Some(clones[0].clone()),
+      "the merged clone points at the kept project"
+    );
+  }
+}
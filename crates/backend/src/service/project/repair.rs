@@ -0,0 +1,125 @@
+//! Repairing quarantined tables.
+//!
+//! A table that could not be read when the project opened was moved to the
+//! quarantine directory and recreated empty. Repair copies back the rows of
+//! the newest readable version of each one, then rebuilds what is derived
+//! from other data: call edges from code chunks and entity links from
+//! memories. Code, and docs that could not be salvaged, come back from the
+//! files by re-indexing, which the caller runs.
+
+use tracing::{info, warn};
+
+use crate::{
+  db::{ProjectDb, QuarantinedTable, Salvage},
+  ipc::project::{ProjectRepairResult, RepairedTableItem},
+  service::{memory::entity, util::ServiceError},
+};
+
+/// Where the rows of a quarantined table come back from
+enum Restore {
+  /// Copy rows from the quarantined copy, merged on these key columns
+  Salvage(&'static [&'static str]),
+  /// Salvage document rows, re-ingesting the docs if none can be read
+  Docs(&'static [&'static str]),
+  /// Salvage entity rows, then relink every memory
+  Entities(&'static [&'static str]),
+  /// Rebuild call edges from the stored code chunks
+  CallGraph,
+  /// Re-index the code from the project files
+  Code,
+}
+
+fn restore_for(table: &str) -> Restore {
+  match table {
+    "code_chunks" | "indexed_files" => Restore::Code,
+    "call_edges" => Restore::CallGraph,
+    "documents" => Restore::Docs(&["source", "chunk_index"]),
+    "document_metadata" => Restore::Docs(&["id"]),
+    "entities" => Restore::Entities(&["id"]),
+    "memory_entities" => Restore::Entities(&["memory_id", "entity_id"]),
+    "skipped_files" => Restore::Salvage(&["file_path"]),
+    _ => Restore::Salvage(&["id"]),
+  }
+}
+
+/// Restore every quarantined table of a project.
+///
+/// Quarantined copies are deleted once their table is restored. A copy none
+/// of whose versions can be read is kept so it can be inspected by hand.
+pub async fn repair(db: &ProjectDb) -> Result<ProjectRepairResult, ServiceError> {
+  db.check_writable()?;
+  let mut result = ProjectRepairResult::default();
+  let mut relink = false;
+
+  for entry in db.quarantined_tables().await {
+    let mut item = RepairedTableItem {
+      table: entry.table.clone(),
+      quarantined_at: entry.quarantined_at.to_rfc3339(),
+      reason: entry.reason.clone(),
+      status: "unrecovered".to_string(),
+      salvaged_version: None,
+      salvaged_rows: None,
+      kept_at: None,
+    };
+
+    let restore = restore_for(&entry.table);
+    if let Restore::Salvage(keys) | Restore::Docs(keys) | Restore::Entities(keys) = restore
+      && let Some(salvage) = salvage(db, &entry, keys).await
+    {
+      item.status = "salvaged".to_string();
+      item.salvaged_version = Some(salvage.version);
+      item.salvaged_rows = Some(salvage.rows);
+    }
+
+    match restore {
+      Restore::Code => {
+        item.status = "reindex".to_string();
+        result.reindex_code = true;
+      }
+      Restore::CallGraph => {
+        db.rebuild_call_edges().await?;
+        item.status = "rebuilt".to_string();
+      }
+      Restore::Docs(_) if item.salvaged_rows.is_none() => {
+        item.status = "reindex".to_string();
+        result.reingest_docs = true;
+      }
+      Restore::Entities(_) => {
+        relink = true;
+        if item.salvaged_rows.is_none() {
+          item.status = "rebuilt".to_string();
+        }
+      }
+      Restore::Salvage(_) | Restore::Docs(_) => {}
+    }
+
+    if item.status == "unrecovered" {
+      item.kept_at = Some(entry.path.to_string_lossy().to_string());
+    } else {
+      db.release_quarantined(&entry).await?;
+    }
+    info!(table = %item.table, status = %item.status, "Repaired quarantined table");
+    result.tables.push(item);
+  }
+
+  // Links skip entities already stored, so salvaged rows are kept as they are
+  if relink {
+    let memories = db.list_memories(Some("is_deleted = false"), None).await?;
+    for memory in &memories {
+      entity::link(db, memory, &[], None).await?;
+    }
+  }
+
+  Ok(result)
+}
+
+/// Salvage a quarantined table, treating a failure as nothing salvaged
+async fn salvage(db: &ProjectDb, entry: &QuarantinedTable, keys: &[&str]) -> Option<Salvage> {
+  match db.salvage_quarantined(entry, keys).await {
+    Ok(salvage) => salvage,
+    Err(e) => {
+      warn!(table = %entry.table, err = %e, "Failed to salvage quarantined table");
+      None
+    }
+  }
+}
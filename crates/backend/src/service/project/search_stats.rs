@@ -0,0 +1,176 @@
+//! Query analytics.
+//!
+//! Summarizes the `search_logs` table: which queries are run most, which
+//! find nothing, and how well results match. Queries are grouped by their
+//! trimmed, lowercased text so "Auth flow" and "auth flow " count together.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+  db::{ProjectDb, SearchLog},
+  ipc::project::{SearchQueryStat, SearchStatsParams, SearchStatsResult},
+  service::util::ServiceError,
+};
+
+/// Report on the searches of the last `params.days` days.
+pub async fn search_stats(db: &ProjectDb, params: &SearchStatsParams) -> Result<SearchStatsResult, ServiceError> {
+  let days = params.days.unwrap_or(30).max(1);
+  let logs = db.list_search_logs(Utc::now() - Duration::days(days as i64)).await?;
+  Ok(summarize(&logs, days, params.limit.unwrap_or(10)))
+}
+
+/// Running totals for one grouped query
+#[derive(Default)]
+struct QueryTotals {
+  /// Text of the most recent search in the group
+  query: String,
+  kinds: BTreeSet<&'static str>,
+  count: usize,
+  results: u64,
+  similarity_sum: f32,
+  scored: usize,
+  clicks: u32,
+  zero_results: usize,
+  last: Option<DateTime<Utc>>,
+}
+
+impl QueryTotals {
+  fn to_stat(&self) -> SearchQueryStat {
+    SearchQueryStat {
+      query: self.query.clone(),
+      kinds: self.kinds.iter().map(|k| k.to_string()).collect(),
+      count: self.count,
+      avg_results: self.results as f32 / self.count.max(1) as f32,
+      avg_similarity: (self.scored > 0).then(|| self.similarity_sum / self.scored as f32),
+      clicks: self.clicks,
+      last_searched: self.last.map(|t| t.to_rfc3339()).unwrap_or_default(),
+    }
+  }
+}
+
+/// Build the report from logs, newest first as `list_search_logs` returns them
+fn summarize(logs: &[SearchLog], days: u32, limit: usize) -> SearchStatsResult {
+  let mut groups: HashMap<String, QueryTotals> = HashMap::new();
+  let mut similarity_sum = 0.0f32;
+  let mut scored = 0usize;
+
+  for log in logs {
+    let key = log.query.trim().to_lowercase();
+    let totals = groups.entry(key).or_default();
+    if totals.last.is_none_or(|last| log.created_at > last) {
+      totals.query = log.query.trim().to_string();
+      totals.last = Some(log.created_at);
+    }
+    totals.kinds.insert(log.kind.as_str());
+    totals.count += 1;
+    totals.results += log.result_count as u64;
+    totals.clicks += log.clicks;
+    if log.result_count == 0 {
+      totals.zero_results += 1;
+    }
+    if let Some(similarity) = log.avg_similarity {
+      totals.similarity_sum += similarity;
+      totals.scored += 1;
+      similarity_sum += similarity;
+      scored += 1;
+    }
+  }
+
+  // Most searched first; ties go to the most recent
+  let mut ranked: Vec<&QueryTotals> = groups.values().collect();
+  ranked.sort_by(|a, b| b.count.cmp(&a.count).then(b.last.cmp(&a.last)));
+
+  let top_queries = ranked.iter().take(limit).map(|t| t.to_stat()).collect();
+  let zero_result_queries = ranked
+    .iter()
+    .filter(|t| t.zero_results > 0)
+    .take(limit)
+    .map(|t| SearchQueryStat {
+      count: t.zero_results,
+      ..t.to_stat()
+    })
+    .collect();
+
+  SearchStatsResult {
+    days,
+    searches: logs.len(),
+    zero_result_searches: logs.iter().filter(|l| l.result_count == 0).count(),
+    clicked_searches: logs.iter().filter(|l| l.clicks > 0).count(),
+    avg_similarity: (scored > 0).then(|| similarity_sum / scored as f32),
+    top_queries,
+    zero_result_queries,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::db::SearchKind;
+
+  fn log(
+    kind: SearchKind,
+    query: &str,
+    results: u32,
+    similarity: Option<f32>,
+    clicks: u32,
+    minutes_ago: i64,
+  ) -> SearchLog {
+    SearchLog {
+      id: Uuid::now_v7(),
+      kind,
+      query: query.to_string(),
+      result_count: results,
+      avg_similarity: similarity,
+      result_ids: Vec::new(),
+      clicks,
+      created_at: Utc::now() - Duration::minutes(minutes_ago),
+    }
+  }
+
+  #[test]
+  fn test_summarize_groups_and_ranks_queries() {
+    let logs = vec![
+      log(SearchKind::Code, "Retry Policy", 4, Some(0.8), 1, 1),
+      log(SearchKind::Docs, "kafka offsets", 0, None, 0, 2),
+      log(SearchKind::Memory, "retry policy ", 2, Some(0.6), 0, 3),
+      log(SearchKind::Memory, "kafka offsets", 0, None, 0, 4),
+      log(SearchKind::Memory, "auth flow", 3, Some(0.4), 2, 5),
+      log(SearchKind::Explore, "kafka offsets", 1, Some(0.3), 0, 6),
+    ];
+
+    let report = summarize(&logs, 7, 10);
+    assert_eq!(report.days, 7);
+    assert_eq!(report.searches, 6);
+    assert_eq!(report.zero_result_searches, 2);
+    assert_eq!(report.clicked_searches, 2);
+    assert!((report.avg_similarity.unwrap() - 0.525).abs() < 1e-6);
+
+    let top: Vec<(&str, usize)> = report.top_queries.iter().map(|q| (q.query.as_str(), q.count)).collect();
+    assert_eq!(top, vec![("kafka offsets", 3), ("Retry Policy", 2), ("auth flow", 1)]);
+
+    let retry = &report.top_queries[1];
+    assert_eq!(retry.kinds, vec!["code", "memory"]);
+    assert_eq!(retry.avg_results, 3.0);
+    assert!((retry.avg_similarity.unwrap() - 0.7).abs() < 1e-6);
+    assert_eq!(retry.clicks, 1);
+
+    assert_eq!(report.zero_result_queries.len(), 1);
+    assert_eq!(report.zero_result_queries[0].query, "kafka offsets");
+    assert_eq!(report.zero_result_queries[0].count, 2, "counts only the empty searches");
+    assert_eq!(report.zero_result_queries[0].kinds, vec!["docs", "explore", "memory"]);
+
+    assert_eq!(summarize(&logs, 7, 1).top_queries.len(), 1);
+  }
+
+  #[test]
+  fn test_summarize_empty_log() {
+    let report = summarize(&[], 30, 10);
+    assert_eq!(report.searches, 0);
+    assert_eq!(report.avg_similarity, None);
+    assert!(report.top_queries.is_empty() && report.zero_result_queries.is_empty());
+  }
+}
@@ -0,0 +1,137 @@
+//! Session browsing.
+//!
+//! Lists Claude Code sessions and the memories each one created, recalled,
+//! updated or reinforced, as recorded by the hooks in `session_memories`, and
+//! the memories the hooks injected into each, from `session_injections`.
+
+use std::collections::HashSet;
+
+use crate::{
+  db::{ProjectDb, SessionMemoryLink},
+  domain::memory::MemoryId,
+  ipc::project::{
+    SessionInjectionItem, SessionInjectionsParams, SessionInjectionsResult, SessionItem, SessionListParams,
+    SessionMemoryItem, SessionShowParams, SessionShowResult,
+  },
+  service::util::{Resolver, ServiceError},
+};
+
+/// List sessions, most recent first, with their linked memory counts.
+pub async fn list(db: &ProjectDb, params: &SessionListParams) -> Result<Vec<SessionItem>, ServiceError> {
+  let filter = params.active_only.unwrap_or(false).then_some("ended_at IS NULL");
+  let mut sessions = db.list_sessions(filter, None).await?;
+  sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+  if let Some(limit) = params.limit {
+    sessions.truncate(limit);
+  }
+
+  let mut items = Vec::with_capacity(sessions.len());
+  for s in sessions {
+    let links = db.get_session_memory_links(&s.id).await?;
+    items.push(SessionItem {
+      memory_count: Some(distinct_memories(&links)),
+      id: s.id,
+      started_at: s.started_at.to_rfc3339(),
+      ended_at: s.ended_at.map(|e| e.to_rfc3339()),
+      summary: s.summary,
+      user_prompt: s.user_prompt,
+    });
+  }
+  Ok(items)
+}
+
+/// Show a session and every memory link recorded for it.
+pub async fn show(db: &ProjectDb, params: &SessionShowParams) -> Result<SessionShowResult, ServiceError> {
+  let session_id = Resolver::session(db, &params.session_id).await?;
+  let session = db
+    .get_session(&session_id)
+    .await?
+    .ok_or_else(|| ServiceError::not_found("Session", &params.session_id))?;
+
+  let mut links = db.get_session_memory_links(&session_id).await?;
+  links.sort_by_key(|l| l.linked_at);
+
+  let mut memories = Vec::with_capacity(links.len());
+  for link in &links {
+    let memory = match link.memory_id.parse::<MemoryId>() {
+      Ok(id) => db.get_memory(&id).await?,
+      Err(_) => None,
+    };
+    memories.push(SessionMemoryItem {
+      memory_id: link.memory_id.clone(),
+      usage_type: link.usage_type.as_str().to_string(),
+      hook_event: link.hook_event.clone(),
+      linked_at: link.linked_at.to_rfc3339(),
+      is_deleted: memory.as_ref().is_none_or(|m| m.is_deleted),
+      content: memory.as_ref().map(|m| m.content.clone()),
+      summary: memory.as_ref().and_then(|m| m.summary.clone()),
+      memory_type: memory
+        .as_ref()
+        .and_then(|m| m.memory_type.map(|t| t.as_str().to_string())),
+      sector: memory.as_ref().map(|m| m.sector.as_str().to_string()),
+    });
+  }
+
+  Ok(SessionShowResult {
+    session: SessionItem {
+      memory_count: Some(distinct_memories(&links)),
+      id: session.id,
+      started_at: session.started_at.to_rfc3339(),
+      ended_at: session.ended_at.map(|e| e.to_rfc3339()),
+      summary: session.summary,
+      user_prompt: session.user_prompt,
+    },
+    memories,
+  })
+}
+
+/// List the memories injected into a session and why.
+///
+/// Without a session ID, uses the most recently started active session, which
+/// is the one asking when called from an MCP tool.
+pub async fn injections(
+  db: &ProjectDb,
+  params: &SessionInjectionsParams,
+) -> Result<SessionInjectionsResult, ServiceError> {
+  let session_id = match &params.session_id {
+    Some(id) => Resolver::session(db, id).await?,
+    None => db
+      .list_sessions(Some("ended_at IS NULL"), None)
+      .await?
+      .into_iter()
+      .max_by_key(|s| s.started_at)
+      .map(|s| s.id)
+      .ok_or_else(|| ServiceError::not_found("Session", "active"))?,
+  };
+
+  let mut items = Vec::new();
+  for injection in db.get_session_injections(&session_id).await? {
+    let memory = match injection.memory_id.parse::<MemoryId>() {
+      Ok(id) => db.get_memory(&id).await?,
+      Err(_) => None,
+    };
+    items.push(SessionInjectionItem {
+      memory_id: injection.memory_id,
+      hook_event: injection.hook_event,
+      rule: injection.rule.as_str().to_string(),
+      score: injection.score,
+      detail: injection.detail,
+      injected_at: injection.injected_at.to_rfc3339(),
+      is_deleted: memory.as_ref().is_none_or(|m| m.is_deleted),
+      content: memory.as_ref().map(|m| m.content.clone()),
+      summary: memory.as_ref().and_then(|m| m.summary.clone()),
+      memory_type: memory
+        .as_ref()
+        .and_then(|m| m.memory_type.map(|t| t.as_str().to_string())),
+    });
+  }
+
+  Ok(SessionInjectionsResult {
+    session_id,
+    injections: items,
+  })
+}
+
+fn distinct_memories(links: &[SessionMemoryLink]) -> usize {
+  links.iter().map(|l| l.memory_id.as_str()).collect::<HashSet<_>>().len()
+}
@@ -0,0 +1,506 @@
+//! Project snapshots for sharing a store or moving it to another machine.
+//!
+//! A snapshot is a zstd-compressed tar archive:
+//!
+//! ```text
+//! manifest.json         <- always the first entry
+//! data/                 <- the project's data directory (LanceDB tables, project.json)
+//! config/ccengram.toml  <- the project's config, when it has one
+//! ```
+//!
+//! Private memories are left out unless asked for: the archive is written
+//! from a copy of the data directory with them purged, so a snapshot handed
+//! to a team holds only team memories.
+//!
+//! The manifest records the snapshot format, the data layout version and the
+//! embedding dimensions the tables were written with. Import refuses
+//! archives this build cannot open as-is rather than converting them. Like
+//! [`super::migrate`], the project's actor must be stopped between
+//! `prepare_*` and the run so nothing holds the database open.
+
+use std::{
+  fs::File,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{
+  migrate,
+  registry::{self, ProjectData},
+};
+use crate::{
+  data_dir::{DATA_VERSION, copy_recursive},
+  db::ProjectDb,
+  domain::{
+    config::Config,
+    project::{ProjectId, resolve_project_path},
+  },
+  ipc::project::{ProjectExportParams, ProjectExportResult, ProjectImportParams, ProjectImportResult},
+  service::util::ServiceError,
+};
+
+/// Archive layout version written by this build
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DATA_PREFIX: &str = "data";
+const CONFIG_ENTRY: &str = "config/ccengram.toml";
+const ZSTD_LEVEL: i32 = 3;
+/// Scratch space under the data dir, outside `projects/` so the registry never lists it
+const STAGING_DIR: &str = "staging";
+
+/// What a snapshot holds, written as its first entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+  pub format: u32,
+  /// [`DATA_VERSION`] of the build that wrote the tables
+  pub data_version: u32,
+  pub ccengram_version: String,
+  pub project_id: ProjectId,
+  /// Root the project was exported from, for rewriting stored paths
+  pub project_path: Option<PathBuf>,
+  pub embedding_model: String,
+  pub embedding_dimensions: usize,
+  pub tables: Vec<String>,
+  /// Private memories were kept
+  #[serde(default)]
+  pub includes_private: bool,
+  pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SnapshotManifest {
+  /// Refuse snapshots this build cannot open without converting them
+  fn check(&self, config: &Config) -> Result<(), ServiceError> {
+    if self.format != SNAPSHOT_FORMAT {
+      return Err(ServiceError::validation(format!(
+        "Snapshot format {} is not supported (this build reads format {}); export it again with ccengram {}",
+        self.format,
+        SNAPSHOT_FORMAT,
+        env!("CARGO_PKG_VERSION")
+      )));
+    }
+    if self.data_version > DATA_VERSION {
+      return Err(ServiceError::validation(format!(
+        "Snapshot was written by ccengram {} with data version {}, newer than this build's {}; upgrade ccengram to import it",
+        self.ccengram_version, self.data_version, DATA_VERSION
+      )));
+    }
+    if self.data_version < DATA_VERSION {
+      return Err(ServiceError::validation(format!(
+        "Snapshot was written by ccengram {} with data version {}, older than this build's {}; run `ccengram data import` with it there first, then export again",
+        self.ccengram_version, self.data_version, DATA_VERSION
+      )));
+    }
+    if self.embedding_dimensions != config.embedding.dimensions {
+      return Err(ServiceError::validation(format!(
+        "Snapshot embeddings have {} dimensions ({}) but this project is configured for {} ({}); match [embedding] before importing",
+        self.embedding_dimensions, self.embedding_model, config.embedding.dimensions, config.embedding.model
+      )));
+    }
+    Ok(())
+  }
+}
+
+/// A resolved export, ready to run once the project is stopped
+#[derive(Debug, Clone)]
+pub struct ExportPlan {
+  pub project: ProjectData,
+  pub archive: PathBuf,
+  pub include_private: bool,
+}
+
+/// A read and checked snapshot, ready to import once the target is stopped
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+  pub archive: PathBuf,
+  pub manifest: SnapshotManifest,
+  pub to_id: ProjectId,
+  pub to_root: PathBuf,
+}
+
+/// Resolve the project to export and where the archive goes.
+///
+/// `cwd` resolves relative paths and is the project when none is given.
+pub async fn prepare_export(
+  data_dir: &Path,
+  params: &ProjectExportParams,
+  cwd: &Path,
+) -> Result<ExportPlan, ServiceError> {
+  let project = match &params.project {
+    Some(project) => registry::find(data_dir, project, cwd).await?,
+    None => {
+      let root = resolve_project_path(cwd).await;
+      let id = ProjectId::from_path(&root).await;
+      if !id.data_dir(data_dir).exists() {
+        return Err(ServiceError::not_found("Project data", root.to_string_lossy()));
+      }
+      ProjectData { id, path: Some(root) }
+    }
+  };
+
+  let archive = cwd.join(&params.path);
+  if archive.exists() && !params.force {
+    return Err(ServiceError::validation(format!(
+      "{} already exists; pass --force to overwrite it",
+      archive.display()
+    )));
+  }
+
+  Ok(ExportPlan {
+    project,
+    archive,
+    include_private: params.include_private,
+  })
+}
+
+/// Write the snapshot archive
+pub async fn export(data_dir: &Path, plan: &ExportPlan) -> Result<ProjectExportResult, ServiceError> {
+  let config = match &plan.project.path {
+    Some(root) => Config::load_for_project(root).await,
+    None => Config::load_global().await,
+  };
+  let staging = data_dir.join(STAGING_DIR).join(format!("{}.export", plan.project.id));
+  let (project_dir, private_excluded) = if plan.include_private {
+    (plan.project.id.data_dir(data_dir), 0)
+  } else {
+    let excluded = stage_shared(data_dir, &plan.project.id, &staging, &config).await;
+    if excluded.is_err() {
+      let _ = tokio::fs::remove_dir_all(&staging).await;
+    }
+    (staging.clone(), excluded?)
+  };
+  let config_file = plan
+    .project
+    .path
+    .as_deref()
+    .map(Config::project_config_path)
+    .filter(|path| path.is_file());
+
+  let manifest = SnapshotManifest {
+    format: SNAPSHOT_FORMAT,
+    data_version: DATA_VERSION,
+    ccengram_version: env!("CARGO_PKG_VERSION").to_string(),
+    project_id: plan.project.id.clone(),
+    project_path: plan.project.path.clone(),
+    embedding_model: config.embedding.model.clone(),
+    embedding_dimensions: config.embedding.dimensions,
+    tables: list_tables(&project_dir.join("lancedb")),
+    includes_private: plan.include_private,
+    created_at: chrono::Utc::now(),
+  };
+
+  let archive = plan.archive.clone();
+  let written = manifest.clone();
+  let config_included = config_file.is_some();
+  let written_archive =
+    tokio::task::spawn_blocking(move || write_archive(&archive, &written, &project_dir, config_file.as_deref())).await;
+  if !plan.include_private {
+    let _ = tokio::fs::remove_dir_all(&staging).await;
+  }
+  let bytes = written_archive
+    .map_err(|e| ServiceError::internal(format!("Export task failed: {}", e)))?
+    .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", plan.archive.display(), e)))?;
+
+  info!(
+    project = %plan.project.id,
+    archive = %plan.archive.display(),
+    bytes,
+    private_excluded,
+    "Exported project snapshot"
+  );
+
+  Ok(ProjectExportResult {
+    project_id: plan.project.id.to_string(),
+    project_path: plan.project.path.as_ref().map(|p| p.to_string_lossy().to_string()),
+    archive: plan.archive.to_string_lossy().to_string(),
+    bytes,
+    tables: manifest.tables,
+    config_included,
+    private_excluded,
+  })
+}
+
+/// Copy the project's data directory to `staging` with private memories purged.
+///
+/// Returns how many memories were left out.
+async fn stage_shared(data_dir: &Path, id: &ProjectId, staging: &Path, config: &Config) -> Result<usize, ServiceError> {
+  if staging.exists() {
+    tokio::fs::remove_dir_all(staging)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to remove {}: {}", staging.display(), e)))?;
+  }
+  copy_recursive(&id.data_dir(data_dir), staging)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to copy project data: {}", e)))?;
+
+  let db = ProjectDb::open_at_path(id.clone(), staging.join("lancedb"), Arc::new(config.clone())).await?;
+  Ok(db.purge_private_memories().await?)
+}
+
+/// Read the snapshot's manifest and check it against the target project.
+///
+/// `cwd` resolves relative paths and is the target when none is given.
+pub async fn prepare_import(params: &ProjectImportParams, cwd: &Path) -> Result<ImportPlan, ServiceError> {
+  let archive = cwd.join(&params.path);
+  if !archive.is_file() {
+    return Err(ServiceError::not_found("Snapshot", archive.to_string_lossy()));
+  }
+
+  let target = params
+    .project
+    .as_deref()
+    .map_or_else(|| cwd.to_path_buf(), |p| cwd.join(p));
+  if !target.is_dir() {
+    return Err(ServiceError::validation(format!(
+      "Project path {} is not a directory",
+      target.display()
+    )));
+  }
+  let to_root = resolve_project_path(&target).await;
+  let to_id = ProjectId::from_path(&to_root).await;
+
+  let path = archive.clone();
+  let manifest = tokio::task::spawn_blocking(move || read_manifest(&path))
+    .await
+    .map_err(|e| ServiceError::internal(format!("Import task failed: {}", e)))??;
+  manifest.check(&Config::load_for_project(&to_root).await)?;
+
+  Ok(ImportPlan {
+    archive,
+    manifest,
+    to_id,
+    to_root,
+  })
+}
+
+/// Unpack the snapshot into the target's data directory and rebind it.
+///
+/// Refuses when the target already has memories or sessions of its own. The
+/// snapshot's config is only written when the target has none.
+pub async fn import(data_dir: &Path, plan: &ImportPlan) -> Result<ProjectImportResult, ServiceError> {
+  let config = Arc::new(Config::load_for_project(&plan.to_root).await);
+  migrate::clear_unused_store(data_dir, &plan.to_id, &plan.to_root, &config, "importing").await?;
+
+  let staging = data_dir.join(STAGING_DIR).join(format!("{}.import", plan.to_id));
+  if staging.exists() {
+    tokio::fs::remove_dir_all(&staging)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to remove {}: {}", staging.display(), e)))?;
+  }
+  let (archive, dest) = (plan.archive.clone(), staging.clone());
+  tokio::task::spawn_blocking(move || unpack_archive(&archive, &dest))
+    .await
+    .map_err(|e| ServiceError::internal(format!("Import task failed: {}", e)))?
+    .map_err(|e| ServiceError::internal(format!("Failed to unpack {}: {}", plan.archive.display(), e)))?;
+
+  let new_dir = plan.to_id.data_dir(data_dir);
+  tokio::fs::rename(staging.join(DATA_PREFIX), &new_dir)
+    .await
+    .map_err(|e| ServiceError::internal(format!("Failed to move snapshot data to {}: {}", new_dir.display(), e)))?;
+
+  let config_file = Config::project_config_path(&plan.to_root);
+  let snapshot_config = staging.join(CONFIG_ENTRY);
+  let config_restored = !config_file.exists() && snapshot_config.is_file();
+  if config_restored {
+    if let Some(parent) = config_file.parent() {
+      let _ = tokio::fs::create_dir_all(parent).await;
+    }
+    tokio::fs::copy(&snapshot_config, &config_file)
+      .await
+      .map_err(|e| ServiceError::internal(format!("Failed to write {}: {}", config_file.display(), e)))?;
+  }
+  let _ = tokio::fs::remove_dir_all(&staging).await;
+
+  let db = ProjectDb::open(plan.to_id.clone(), data_dir, config).await?;
+  if plan.manifest.project_id != plan.to_id {
+    db.rebind_project(&plan.manifest.project_id).await?;
+  }
+  let (memories_updated, documents_updated) = match &plan.manifest.project_path {
+    Some(old_root) if old_root != &plan.to_root => migrate::rewrite_paths(&db, old_root, &plan.to_root).await?,
+    _ => (0, 0),
+  };
+
+  registry::record(data_dir, &plan.to_id, &plan.to_root).await?;
+
+  info!(
+    from = %plan.manifest.project_id,
+    to = %plan.to_id,
+    root = %plan.to_root.display(),
+    memories_updated,
+    documents_updated,
+    "Imported project snapshot"
+  );
+
+  Ok(ProjectImportResult {
+    from_id: plan.manifest.project_id.to_string(),
+    to_id: plan.to_id.to_string(),
+    from_path: plan
+      .manifest
+      .project_path
+      .as_ref()
+      .map(|p| p.to_string_lossy().to_string()),
+    to_path: plan.to_root.to_string_lossy().to_string(),
+    ccengram_version: plan.manifest.ccengram_version.clone(),
+    exported_at: plan.manifest.created_at.to_rfc3339(),
+    tables: plan.manifest.tables.clone(),
+    memories_updated,
+    documents_updated,
+    config_restored,
+  })
+}
+
+/// Names of the LanceDB tables in `db_dir`
+fn list_tables(db_dir: &Path) -> Vec<String> {
+  let mut tables: Vec<String> = std::fs::read_dir(db_dir)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .filter_map(|entry| {
+      let name = entry.file_name().to_string_lossy().to_string();
+      name.strip_suffix(".lance").map(str::to_string)
+    })
+    .collect();
+  tables.sort();
+  tables
+}
+
+/// Write the manifest, data directory and config into a tar.zst, returning its size
+fn write_archive(
+  archive: &Path,
+  manifest: &SnapshotManifest,
+  project_dir: &Path,
+  config_file: Option<&Path>,
+) -> std::io::Result<u64> {
+  if let Some(parent) = archive.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let encoder = zstd::stream::write::Encoder::new(File::create(archive)?, ZSTD_LEVEL)?;
+  let mut builder = tar::Builder::new(encoder);
+
+  let manifest = serde_json::to_vec_pretty(manifest)?;
+  let mut header = tar::Header::new_gnu();
+  header.set_size(manifest.len() as u64);
+  header.set_mode(0o644);
+  header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+  header.set_cksum();
+  builder.append_data(&mut header, MANIFEST_FILE, manifest.as_slice())?;
+
+  builder.append_dir_all(DATA_PREFIX, project_dir)?;
+  if let Some(config_file) = config_file {
+    builder.append_path_with_name(config_file, CONFIG_ENTRY)?;
+  }
+
+  builder.into_inner()?.finish()?;
+  Ok(std::fs::metadata(archive)?.len())
+}
+
+/// Read the manifest from the first entry of a snapshot
+fn read_manifest(archive: &Path) -> Result<SnapshotManifest, ServiceError> {
+  let not_snapshot = || ServiceError::validation(format!("{} is not a ccengram project snapshot", archive.display()));
+
+  let file =
+    File::open(archive).map_err(|e| ServiceError::internal(format!("Failed to open {}: {}", archive.display(), e)))?;
+  let decoder = zstd::stream::read::Decoder::new(file).map_err(|_| not_snapshot())?;
+  let mut tar = tar::Archive::new(decoder);
+  let mut entries = tar.entries().map_err(|_| not_snapshot())?;
+  let entry = entries.next().and_then(Result::ok).ok_or_else(not_snapshot)?;
+  if entry.path().ok().as_deref() != Some(Path::new(MANIFEST_FILE)) {
+    return Err(not_snapshot());
+  }
+  serde_json::from_reader(entry).map_err(|_| not_snapshot())
+}
+
+/// Unpack a snapshot into `dest`; tar refuses entries that escape it
+fn unpack_archive(archive: &Path, dest: &Path) -> std::io::Result<()> {
+  std::fs::create_dir_all(dest)?;
+  let decoder = zstd::stream::read::Decoder::new(File::open(archive)?)?;
+  tar::Archive::new(decoder).unpack(dest)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn manifest(config: &Config) -> SnapshotManifest {
+    SnapshotManifest {
+      format: SNAPSHOT_FORMAT,
+      data_version: DATA_VERSION,
+      ccengram_version: "0.0.0".to_string(),
+      project_id: ProjectId::from_path_exact(Path::new("/home/me/app")),
+      project_path: Some(PathBuf::from("/home/me/app")),
+      embedding_model: config.embedding.model.clone(),
+      embedding_dimensions: config.embedding.dimensions,
+      tables: vec!["memories".to_string()],
+      includes_private: false,
+      created_at: chrono::Utc::now(),
+    }
+  }
+
+  #[test]
+  fn test_manifest_check_refuses_other_versions() {
+    let config = Config::default();
+    assert!(manifest(&config).check(&config).is_ok());
+
+    let newer = SnapshotManifest {
+      data_version: DATA_VERSION + 1,
+      ..manifest(&config)
+    };
+    assert!(newer.check(&config).unwrap_err().to_string().contains("upgrade"));
+
+    let format = SnapshotManifest {
+      format: SNAPSHOT_FORMAT + 1,
+      ..manifest(&config)
+    };
+    assert!(format.check(&config).is_err());
+
+    let dimensions = SnapshotManifest {
+      embedding_dimensions: config.embedding.dimensions + 1,
+      ..manifest(&config)
+    };
+    assert!(
+      dimensions.check(&config).is_err(),
+      "embeddings of another size cannot be searched"
+    );
+  }
+
+  #[test]
+  fn test_archive_round_trip() {
+    let temp = TempDir::new().unwrap();
+    let project_dir = temp.path().join("project");
+    std::fs::create_dir_all(project_dir.join("lancedb/memories.lance/data")).unwrap();
+    std::fs::write(project_dir.join("lancedb/memories.lance/data/0.lance"), b"rows").unwrap();
+    std::fs::write(project_dir.join(registry::METADATA_FILE), b"{}").unwrap();
+    let config_file = temp.path().join("ccengram.toml");
+    std::fs::write(&config_file, "[embedding]\n").unwrap();
+
+    let config = Config::default();
+    let mut written = manifest(&config);
+    written.tables = list_tables(&project_dir.join("lancedb"));
+    assert_eq!(written.tables, vec!["memories".to_string()]);
+
+    let archive = temp.path().join("out/app.tar.zst");
+    let bytes = write_archive(&archive, &written, &project_dir, Some(&config_file)).unwrap();
+    assert!(bytes > 0);
+
+    let read = read_manifest(&archive).unwrap();
+    assert_eq!(read.project_id, written.project_id);
+    assert_eq!(read.tables, written.tables);
+
+    let dest = temp.path().join("unpacked");
+    unpack_archive(&archive, &dest).unwrap();
+    assert_eq!(
+      std::fs::read(dest.join("data/lancedb/memories.lance/data/0.lance")).unwrap(),
+      b"rows"
+    );
+    assert!(dest.join("data").join(registry::METADATA_FILE).is_file());
+    assert!(dest.join(CONFIG_ENTRY).is_file());
+
+    std::fs::write(temp.path().join("plain.tar.zst"), b"not an archive").unwrap();
+    assert!(read_manifest(&temp.path().join("plain.tar.zst")).is_err());
+  }
+}
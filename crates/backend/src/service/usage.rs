@@ -0,0 +1,216 @@
+//! LLM and embedding usage reports.
+//!
+//! Aggregates the daemon-wide usage log into per-day, per-project totals for
+//! `ccengram usage`, and into per-provider data egress for
+//! `ccengram stats --egress`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Duration, Utc};
+
+use super::util::ServiceError;
+use crate::{
+  db::{UsageKind, UsageRecord},
+  ipc::system::{EgressItem, EgressParams, EgressResult, UsageDayItem, UsageParams, UsageResult, UsageTotals},
+  usage::UsageTracker,
+};
+
+/// Default days of history in a usage report
+const DEFAULT_DAYS: u32 = 30;
+
+/// Default days of history in an egress report
+const DEFAULT_EGRESS_DAYS: u32 = 7;
+
+/// Summarize recorded usage over the requested window
+pub async fn summary(tracker: &UsageTracker, params: &UsageParams) -> Result<UsageResult, ServiceError> {
+  let days = params.days.unwrap_or(DEFAULT_DAYS).max(1);
+  let since = Utc::now() - Duration::days(days as i64);
+
+  let records = tracker.records_since(since).await?;
+  let records = records.into_iter().filter(|r| match &params.project {
+    Some(filter) => r.project_path.as_deref().is_some_and(|p| p.contains(filter.as_str())),
+    None => true,
+  });
+
+  let mut result = summarize(records);
+  result.days = days;
+  result.month_cost_usd = tracker.month_cost_usd();
+  result.monthly_budget_usd = tracker.monthly_budget_usd();
+  result.budget_exceeded = tracker.budget_exceeded();
+  Ok(result)
+}
+
+fn summarize(records: impl IntoIterator<Item = UsageRecord>) -> UsageResult {
+  let mut rows: BTreeMap<(String, Option<String>), (UsageTotals, UsageTotals)> = BTreeMap::new();
+  let mut llm = UsageTotals::default();
+  let mut embedding = UsageTotals::default();
+
+  for record in records {
+    let date = record.recorded_at.format("%Y-%m-%d").to_string();
+    let (row_llm, row_embedding) = rows.entry((date, record.project_path.clone())).or_default();
+    let (row, total) = match record.kind {
+      UsageKind::Llm => (row_llm, &mut llm),
+      UsageKind::Embedding => (row_embedding, &mut embedding),
+    };
+    add(row, &record);
+    add(total, &record);
+  }
+
+  UsageResult {
+    days: 0,
+    rows: rows
+      .into_iter()
+      .rev()
+      .map(|((date, project), (llm, embedding))| UsageDayItem {
+        date,
+        project,
+        llm,
+        embedding,
+      })
+      .collect(),
+    llm,
+    embedding,
+    month_cost_usd: 0.0,
+    monthly_budget_usd: None,
+    budget_exceeded: false,
+  }
+}
+
+/// Summarize what text was sent to which provider over the requested window
+pub async fn egress(
+  tracker: &UsageTracker,
+  params: &EgressParams,
+  strict_local: bool,
+) -> Result<EgressResult, ServiceError> {
+  let days = params.days.unwrap_or(DEFAULT_EGRESS_DAYS).max(1);
+  let since = Utc::now() - Duration::days(days as i64);
+
+  let records = tracker.records_since(since).await?;
+  let (remote, local) = summarize_egress(records);
+  Ok(EgressResult {
+    days,
+    strict_local,
+    remote,
+    local,
+  })
+}
+
+/// Group records by provider and model, split into remote and local.
+/// Each side is ordered by characters sent, largest first.
+fn summarize_egress(records: impl IntoIterator<Item = UsageRecord>) -> (Vec<EgressItem>, Vec<EgressItem>) {
+  let mut groups: BTreeMap<(bool, &'static str, String, String), (EgressItem, BTreeSet<String>)> = BTreeMap::new();
+
+  for record in records {
+    let key = (
+      record.remote,
+      record.kind.as_str(),
+      record.provider.clone(),
+      record.model.clone(),
+    );
+    let (item, projects) = groups.entry(key).or_insert_with(|| {
+      let item = EgressItem {
+        kind: record.kind.as_str().to_string(),
+        provider: record.provider.clone(),
+        model: record.model.clone(),
+        ..Default::default()
+      };
+      (item, BTreeSet::new())
+    });
+    item.requests += record.requests;
+    item.chars += record.input_chars;
+    item.input_tokens += record.input_tokens;
+    if let Some(path) = record.project_path {
+      projects.insert(path);
+    }
+  }
+
+  let (mut remote, mut local) = (Vec::new(), Vec::new());
+  for ((is_remote, ..), (mut item, projects)) in groups {
+    item.projects = projects.into_iter().collect();
+    if is_remote {
+      remote.push(item);
+    } else {
+      local.push(item);
+    }
+  }
+  remote.sort_by(|a, b| b.chars.cmp(&a.chars));
+  local.sort_by(|a, b| b.chars.cmp(&a.chars));
+  (remote, local)
+}
+
+fn add(totals: &mut UsageTotals, record: &UsageRecord) {
+  totals.requests += record.requests;
+  totals.input_tokens += record.input_tokens;
+  totals.output_tokens += record.output_tokens;
+  totals.cost_usd += record.cost_usd.unwrap_or(0.0);
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::TimeZone;
+
+  use super::*;
+
+  fn record(kind: UsageKind, day: u32, project: &str, cost_usd: Option<f64>) -> UsageRecord {
+    UsageRecord {
+      recorded_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+      kind,
+      project_id: None,
+      project_path: Some(project.to_string()),
+      provider: "test".to_string(),
+      model: "model".to_string(),
+      task: None,
+      requests: 1,
+      input_tokens: 100,
+      output_tokens: 10,
+      cost_usd,
+      input_chars: 400,
+      remote: true,
+    }
+  }
+
+  #[test]
+  fn test_summarize_groups_by_day_and_project() {
+    let result = summarize(vec![
+      record(UsageKind::Llm, 1, "/a", Some(0.5)),
+      record(UsageKind::Llm, 1, "/a", Some(0.25)),
+      record(UsageKind::Embedding, 1, "/a", None),
+      record(UsageKind::Llm, 2, "/b", Some(1.0)),
+    ]);
+
+    assert_eq!(result.rows.len(), 2, "one row per day and project");
+    assert_eq!(result.rows[0].date, "2026-03-02", "newest day first");
+    let first_day = &result.rows[1];
+    assert_eq!(first_day.llm.requests, 2, "llm calls are summed");
+    assert_eq!(first_day.llm.cost_usd, 0.75, "llm cost is summed");
+    assert_eq!(first_day.embedding.input_tokens, 100, "embedding tokens kept separate");
+    assert_eq!(first_day.embedding.cost_usd, 0.0, "unknown cost counts as free");
+    assert_eq!(result.llm.cost_usd, 1.75, "totals span all rows");
+  }
+
+  #[test]
+  fn test_summarize_egress_splits_remote_and_local() {
+    let mut local = record(UsageKind::Embedding, 1, "/a", None);
+    local.provider = "llamacpp".to_string();
+    local.remote = false;
+    let mut embedded = record(UsageKind::Embedding, 2, "/b", None);
+    embedded.provider = "openrouter".to_string();
+    embedded.requests = 32;
+    embedded.input_chars = 50_000;
+
+    let (remote, local) = summarize_egress(vec![
+      record(UsageKind::Llm, 1, "/a", Some(0.1)),
+      record(UsageKind::Llm, 2, "/b", Some(0.1)),
+      embedded,
+      local,
+    ]);
+
+    assert_eq!(remote.len(), 2, "one row per remote provider and model");
+    assert_eq!(remote[0].provider, "openrouter", "most characters first");
+    assert_eq!(remote[0].requests, 32, "embedded chunks are counted");
+    assert_eq!(remote[1].chars, 800, "llm characters are summed");
+    assert_eq!(remote[1].projects, vec!["/a", "/b"], "projects are listed once each");
+    assert_eq!(local.len(), 1, "local providers are reported separately");
+    assert_eq!(local[0].provider, "llamacpp");
+  }
+}
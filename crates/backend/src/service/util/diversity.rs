@@ -0,0 +1,71 @@
+//! Per-file caps on ranked results.
+//!
+//! A large file holds many chunks that match the same query, and left alone
+//! they can fill the top of a result list. Capping keeps the best few hits
+//! from each file and counts the rest, so callers can show that the file has
+//! more.
+
+use std::collections::HashMap;
+
+/// Keep at most `max` results per file, in their ranked order. Results
+/// without a file are always kept, and a `max` of 0 keeps everything.
+///
+/// Returns the number of results dropped from each capped file, keyed by the
+/// position in `results` of that file's last kept result.
+pub fn cap_per_file<T>(results: &mut Vec<T>, max: usize, file: impl Fn(&T) -> Option<&str>) -> HashMap<usize, usize> {
+  if max == 0 {
+    return HashMap::new();
+  }
+
+  let mut kept_per_file: HashMap<String, usize> = HashMap::new();
+  let mut last_kept: HashMap<String, usize> = HashMap::new();
+  let mut dropped: HashMap<String, usize> = HashMap::new();
+  let mut kept = Vec::with_capacity(results.len());
+
+  for result in results.drain(..) {
+    let Some(path) = file(&result).map(str::to_string) else {
+      kept.push(result);
+      continue;
+    };
+    let count = kept_per_file.entry(path.clone()).or_default();
+    if *count < max {
+      *count += 1;
+      last_kept.insert(path, kept.len());
+      kept.push(result);
+    } else {
+      *dropped.entry(path).or_default() += 1;
+    }
+  }
+  *results = kept;
+
+  dropped
+    .into_iter()
+    .map(|(path, count)| (last_kept[&path], count))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_cap_per_file() {
+    let mut results = vec![
+      ("a", Some("big.rs")),
+      ("b", Some("big.rs")),
+      ("c", None),
+      ("d", Some("big.rs")),
+      ("e", Some("small.rs")),
+      ("f", Some("big.rs")),
+    ];
+
+    let more = cap_per_file(&mut results, 2, |r| r.1);
+    let ids: Vec<&str> = results.iter().map(|r| r.0).collect();
+    assert_eq!(ids, vec!["a", "b", "c", "e"], "order kept, results without a file kept");
+    assert_eq!(more, HashMap::from([(1, 2)]), "counted on the file's last kept result");
+
+    let mut all = vec![("a", Some("big.rs")), ("b", Some("big.rs")), ("c", Some("big.rs"))];
+    assert!(cap_per_file(&mut all, 0, |r| r.1).is_empty());
+    assert_eq!(all.len(), 3, "0 disables the cap");
+  }
+}
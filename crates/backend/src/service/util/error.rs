@@ -15,8 +15,16 @@ pub enum ServiceError {
   #[error("{item_type} not found: {id}")]
   NotFound { item_type: &'static str, id: String },
   /// ID prefix matches multiple items.
-  #[error("Ambiguous prefix '{prefix}' matches {count} items")]
-  Ambiguous { prefix: String, count: usize },
+  #[error(
+    "Ambiguous prefix '{prefix}' matches {count} items{}",
+    super::resolve::format_candidates(.candidates)
+  )]
+  Ambiguous {
+    prefix: String,
+    count: usize,
+    /// Matching items as `id  description`, for disambiguation
+    candidates: Vec<String>,
+  },
   /// Input validation failed.
   #[error("Validation error: {0}")]
   Validation(String),
@@ -77,7 +85,15 @@ impl From<super::resolve::ResolveError> for ServiceError {
   fn from(e: super::resolve::ResolveError) -> Self {
     match e {
       super::resolve::ResolveError::NotFound { item_type, id } => Self::NotFound { item_type, id },
-      super::resolve::ResolveError::Ambiguous { prefix, count } => Self::Ambiguous { prefix, count },
+      super::resolve::ResolveError::Ambiguous {
+        prefix,
+        count,
+        candidates,
+      } => Self::Ambiguous {
+        prefix,
+        count,
+        candidates,
+      },
       super::resolve::ResolveError::InvalidInput(msg) => Self::Validation(msg),
       super::resolve::ResolveError::Database(msg) => Self::Database(DbError::Query(msg)),
     }
@@ -95,7 +111,8 @@ mod tests {
     assert_eq!(
       ServiceError::Ambiguous {
         prefix: "abc".to_string(),
-        count: 5
+        count: 5,
+        candidates: Vec::new(),
       }
       .code(),
       -32000
@@ -5,15 +5,14 @@
 //!
 //! - `error` - Unified error types for service operations
 //! - `resolve` - Generic ID/prefix resolution for all entity types
-//! - `filter` - SQL-injection-safe filter builder
+//! - `diversity` - Per-file caps on ranked results
 //! - `search` - Vector search with text fallback pattern
 //! - `format` - Response formatting for human-readable output
 
+pub mod diversity;
 mod error;
-mod filter;
 pub mod fusion;
 mod resolve;
 
 pub use error::ServiceError;
-pub use filter::FilterBuilder;
-pub use resolve::Resolver;
+pub use resolve::{ResolveError, ResolvedEntity, Resolver, normalize_id};
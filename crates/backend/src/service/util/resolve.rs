@@ -1,22 +1,44 @@
 //! Generic ID/prefix resolution for all entity types.
 //!
 //! This module provides a unified pattern for resolving entities by ID or prefix,
-//! replacing the duplicated resolution logic across handlers.
+//! replacing the duplicated resolution logic across handlers. User input is
+//! normalized before matching, and ambiguous prefixes list their candidates.
 
 use std::fmt;
 
 use crate::{
-  db::{DbError, ProjectDb},
-  domain::{code::CodeChunk, document::DocumentChunk, memory::Memory},
+  db::{DbError, FilterBuilder, ProjectDb},
+  domain::{
+    code::CodeChunk,
+    document::DocumentChunk,
+    memory::{Entity, EntityType, Memory},
+  },
 };
 
+/// Minimum prefix length accepted for prefix matching
+const MIN_PREFIX_LEN: usize = 6;
+
+/// Maximum number of candidates listed for an ambiguous prefix
+const MAX_CANDIDATES: usize = 10;
+
+/// Maximum length of a candidate description
+const CANDIDATE_DESC_LEN: usize = 60;
+
+/// Characters per allowed typo when matching entity names loosely
+const CHARS_PER_TYPO: usize = 4;
+
 /// Error type for resolution operations.
 #[derive(Debug)]
 pub enum ResolveError {
   /// Item was not found.
   NotFound { item_type: &'static str, id: String },
   /// ID prefix is ambiguous (matches multiple items).
-  Ambiguous { prefix: String, count: usize },
+  Ambiguous {
+    prefix: String,
+    count: usize,
+    /// Matching items as `id  description`, for disambiguation
+    candidates: Vec<String>,
+  },
   /// Input validation failed.
   InvalidInput(String),
   /// Database error.
@@ -27,11 +49,17 @@ impl fmt::Display for ResolveError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Self::NotFound { item_type, id } => write!(f, "{} not found: {}", item_type, id),
-      Self::Ambiguous { prefix, count } => {
+      Self::Ambiguous {
+        prefix,
+        count,
+        candidates,
+      } => {
         write!(
           f,
-          "Ambiguous prefix '{}' matches {} items. Use more characters.",
-          prefix, count
+          "Ambiguous prefix '{}' matches {} items. Use more characters.{}",
+          prefix,
+          count,
+          format_candidates(candidates)
         )
       }
       Self::InvalidInput(msg) => write!(f, "{}", msg),
@@ -45,17 +73,149 @@ impl std::error::Error for ResolveError {}
 impl From<DbError> for ResolveError {
   fn from(e: DbError) -> Self {
     match e {
-      DbError::AmbiguousPrefix { prefix, count } => Self::Ambiguous { prefix, count },
+      DbError::AmbiguousPrefix { prefix, count } => Self::Ambiguous {
+        prefix,
+        count,
+        candidates: Vec::new(),
+      },
       DbError::InvalidInput(msg) => Self::InvalidInput(msg),
       other => Self::Database(other.to_string()),
     }
   }
 }
 
+/// Render disambiguation candidates as an indented list, empty if there are none
+pub(crate) fn format_candidates(candidates: &[String]) -> String {
+  candidates.iter().map(|c| format!("\n  {}", c)).collect()
+}
+
+/// Normalize a user-supplied ID or ID prefix.
+///
+/// Accepts the forms IDs are commonly copied in: surrounding whitespace,
+/// quotes, backticks or brackets, a trailing ellipsis from truncated display,
+/// uppercase hex, and UUIDs with their hyphens stripped.
+pub fn normalize_id(input: &str) -> String {
+  let trimmed = input
+    .trim()
+    .trim_matches(|c: char| matches!(c, '`' | '"' | '\'' | '[' | ']' | '(' | ')' | '<' | '>'))
+    .trim_end_matches('…')
+    .trim_end_matches("...")
+    .trim()
+    .to_lowercase();
+
+  let bare_hex = trimmed.len() > 8 && trimmed.len() <= 32 && trimmed.chars().all(|c| c.is_ascii_hexdigit());
+  if !bare_hex {
+    return trimmed;
+  }
+
+  let mut id = String::with_capacity(36);
+  for (i, c) in trimmed.chars().enumerate() {
+    if matches!(i, 8 | 12 | 16 | 20) {
+      id.push('-');
+    }
+    id.push(c);
+  }
+  id
+}
+
+/// Validate a normalized ID prefix before it is used in a prefix query.
+fn check_prefix(prefix: &str) -> Result<(), ResolveError> {
+  if prefix.len() < MIN_PREFIX_LEN {
+    return Err(ResolveError::InvalidInput(format!(
+      "ID prefix must be at least {} characters",
+      MIN_PREFIX_LEN
+    )));
+  }
+  if !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+    return Err(ResolveError::InvalidInput(format!(
+      "'{}' is not a valid ID or ID prefix",
+      prefix
+    )));
+  }
+  Ok(())
+}
+
+/// Pick the single match for a prefix, or list the candidates.
+fn single_match<T>(
+  item_type: &'static str,
+  input: &str,
+  prefix: &str,
+  matches: Vec<T>,
+  describe: impl Fn(&T) -> String,
+) -> Result<T, ResolveError> {
+  match matches.len() {
+    0 => Err(ResolveError::NotFound {
+      item_type,
+      id: input.to_string(),
+    }),
+    1 => Ok(matches.into_iter().next().expect("just checked len")),
+    count => Err(ResolveError::Ambiguous {
+      prefix: prefix.to_string(),
+      count,
+      candidates: matches.iter().take(MAX_CANDIDATES).map(describe).collect(),
+    }),
+  }
+}
+
+/// Shorten text to its first line for a candidate description.
+fn describe(id: &str, text: &str) -> String {
+  let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+  let mut end = line.len().min(CANDIDATE_DESC_LEN);
+  while !line.is_char_boundary(end) {
+    end -= 1;
+  }
+  let ellipsis = if end < line.len() { "..." } else { "" };
+  format!("{}  {}{}", id, &line[..end], ellipsis)
+}
+
+/// Entities whose name loosely matches `query`, case-insensitively.
+///
+/// Names starting with the query win; failing that, names containing it or
+/// within a typo per few characters of it.
+fn match_entity_names(entities: Vec<Entity>, query: &str) -> Vec<Entity> {
+  let query = query.trim().to_lowercase();
+  if query.is_empty() {
+    return Vec::new();
+  }
+
+  let (prefixed, rest): (Vec<Entity>, Vec<Entity>) = entities
+    .into_iter()
+    .partition(|e| e.name.to_lowercase().starts_with(&query));
+  if !prefixed.is_empty() {
+    return prefixed;
+  }
+
+  let max_typos = (query.chars().count() / CHARS_PER_TYPO).max(1);
+  rest
+    .into_iter()
+    .filter(|e| {
+      let name = e.name.to_lowercase();
+      name.contains(&query) || edit_distance(&name, &query) <= max_typos
+    })
+    .collect()
+}
+
+/// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut current = vec![i + 1; b.len() + 1];
+    for (j, cb) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(ca != *cb);
+      current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
 /// Resolver for looking up entities by ID or prefix.
 ///
 /// Provides a consistent interface for resolving memories, code chunks,
-/// document chunks, and other entities by their full ID or a unique prefix.
+/// document chunks, and sessions by their full ID or a unique prefix.
+/// Input is normalized with [`normalize_id`] first, and ambiguous prefixes
+/// report the matching candidates.
 pub struct Resolver;
 
 impl Resolver {
@@ -71,14 +231,18 @@ impl Resolver {
   /// * `Ok(Memory)` - The resolved memory
   /// * `Err(ResolveError)` - Resolution failed
   pub async fn memory(db: &ProjectDb, id_or_prefix: &str) -> Result<Memory, ResolveError> {
-    match db.get_memory_by_id_or_prefix(id_or_prefix).await {
-      Ok(Some(memory)) => Ok(memory),
-      Ok(None) => Err(ResolveError::NotFound {
-        item_type: "Memory",
-        id: id_or_prefix.to_string(),
-      }),
-      Err(e) => Err(e.into()),
+    let id = normalize_id(id_or_prefix);
+    if let Ok(memory_id) = id.parse()
+      && let Some(memory) = db.get_memory(&memory_id).await?
+    {
+      return Ok(memory);
     }
+
+    check_prefix(&id)?;
+    let matches = db.find_by_prefix(&id).await?;
+    single_match("Memory", id_or_prefix, &id, matches, |m| {
+      describe(&m.id.to_string(), &m.content)
+    })
   }
 
   /// Resolve a code chunk by ID or prefix.
@@ -93,14 +257,22 @@ impl Resolver {
   /// * `Ok(CodeChunk)` - The resolved code chunk
   /// * `Err(ResolveError)` - Resolution failed
   pub async fn code_chunk(db: &ProjectDb, id_or_prefix: &str) -> Result<CodeChunk, ResolveError> {
-    match db.get_code_chunk_by_id_or_prefix(id_or_prefix).await {
-      Ok(Some(chunk)) => Ok(chunk),
-      Ok(None) => Err(ResolveError::NotFound {
-        item_type: "Code chunk",
-        id: id_or_prefix.to_string(),
-      }),
-      Err(e) => Err(e.into()),
+    let id = normalize_id(id_or_prefix);
+    if let Ok(chunk_id) = id.parse()
+      && let Some(chunk) = db.get_code_chunk(&chunk_id).await?
+    {
+      return Ok(chunk);
     }
+
+    check_prefix(&id)?;
+    let matches = db.find_code_chunks_by_prefix(&id).await?;
+    single_match("Code chunk", id_or_prefix, &id, matches, |c| {
+      let location = format!("{}:{}", c.file_path, c.start_line);
+      match &c.definition_name {
+        Some(name) => describe(&c.id.to_string(), &format!("{} ({})", location, name)),
+        None => describe(&c.id.to_string(), &location),
+      }
+    })
   }
 
   /// Resolve a document chunk by ID or prefix.
@@ -115,20 +287,108 @@ impl Resolver {
   /// * `Ok(DocumentChunk)` - The resolved document chunk
   /// * `Err(ResolveError)` - Resolution failed
   pub async fn document_chunk(db: &ProjectDb, id_or_prefix: &str) -> Result<DocumentChunk, ResolveError> {
-    match db.get_document_chunk_by_id_or_prefix(id_or_prefix).await {
-      Ok(Some(chunk)) => Ok(chunk),
-      Ok(None) => Err(ResolveError::NotFound {
-        item_type: "Document chunk",
-        id: id_or_prefix.to_string(),
-      }),
-      Err(e) => Err(e.into()),
+    let id = normalize_id(id_or_prefix);
+    if let Ok(chunk_id) = id.parse()
+      && let Some(chunk) = db.get_document_chunk(&chunk_id).await?
+    {
+      return Ok(chunk);
+    }
+
+    check_prefix(&id)?;
+    let matches = db.find_document_chunks_by_prefix(&id).await?;
+    single_match("Document chunk", id_or_prefix, &id, matches, |c| {
+      describe(
+        &c.id.to_string(),
+        &format!("{} [{}/{}]", c.title, c.chunk_index + 1, c.total_chunks),
+      )
+    })
+  }
+
+  /// Resolve a session ID or prefix to the full session ID.
+  ///
+  /// Session IDs are assigned by Claude Code and are not always UUIDs, so
+  /// only whitespace and quoting are normalized away.
+  ///
+  /// # Arguments
+  /// * `db` - The project database
+  /// * `id_or_prefix` - Full session ID or unique prefix (minimum 6 characters)
+  ///
+  /// # Returns
+  /// * `Ok(String)` - The full session ID
+  /// * `Err(ResolveError)` - Resolution failed
+  pub async fn session(db: &ProjectDb, id_or_prefix: &str) -> Result<String, ResolveError> {
+    let id = normalize_id(id_or_prefix);
+    if id.len() < MIN_PREFIX_LEN {
+      return Err(ResolveError::InvalidInput(format!(
+        "ID prefix must be at least {} characters",
+        MIN_PREFIX_LEN
+      )));
+    }
+
+    let filter = FilterBuilder::new().add_prefix("id", &id).build();
+    let matches = db.list_sessions(filter.as_deref(), Some(MAX_CANDIDATES)).await?;
+    if let Some(exact) = matches.iter().find(|s| s.id == id) {
+      return Ok(exact.id.clone());
+    }
+
+    let session = single_match("Session", id_or_prefix, &id, matches, |s| {
+      let started = s.started_at.format("%Y-%m-%d %H:%M").to_string();
+      match &s.summary {
+        Some(summary) => describe(&s.id, &format!("{} {}", started, summary)),
+        None => describe(&s.id, &started),
+      }
+    })?;
+    Ok(session.id)
+  }
+
+  /// Resolve a named entity by ID, ID prefix or name.
+  ///
+  /// Names match case-insensitively, exactly first, then by prefix, then
+  /// loosely allowing typos. A name shared by entities of several types, or
+  /// a partial name matching several entities, is ambiguous and lists them.
+  pub async fn entity(db: &ProjectDb, id_or_name: &str) -> Result<Entity, ResolveError> {
+    let id = normalize_id(id_or_name);
+    if let Ok(entity_id) = id.parse()
+      && let Some(entity) = db.get_entity(&entity_id).await?
+    {
+      return Ok(entity);
+    }
+
+    let name = id_or_name.trim();
+    let named_ids: Vec<String> = EntityType::ALL
+      .iter()
+      .map(|t| Entity::id_for(name, *t).to_string())
+      .collect();
+    let named_ids: Vec<&str> = named_ids.iter().map(String::as_str).collect();
+    let filter = FilterBuilder::new().add_in("id", &named_ids).build();
+    let named = db.list_entities(filter.as_deref()).await?;
+    if !named.is_empty() {
+      return single_match("Entity", id_or_name, name, named, |e| {
+        format!("{}  {} ({})", e.id, e.name, e.entity_type)
+      });
+    }
+
+    if check_prefix(&id).is_ok() {
+      let filter = FilterBuilder::new().add_prefix("id", &id).build();
+      let matches = db.list_entities(filter.as_deref()).await?;
+      if !matches.is_empty() {
+        return single_match("Entity", id_or_name, &id, matches, |e| {
+          describe(&e.id.to_string(), &e.name)
+        });
+      }
     }
+
+    let matches = match_entity_names(db.list_entities(None).await?, name);
+    single_match("Entity", id_or_name, name, matches, |e| {
+      format!("{}  {} ({})", e.id, e.name, e.entity_type)
+    })
   }
 
   /// Try to resolve any entity type by ID or prefix.
   ///
-  /// Attempts resolution in order: code chunk, memory, document chunk, entity.
-  /// Returns the first successful match.
+  /// Attempts resolution in order: code chunk, memory, document chunk.
+  /// Returns the first successful match. If no type matches uniquely but a
+  /// prefix is ambiguous within a type, the ambiguity is reported.
   ///
   /// # Arguments
   /// * `db` - The project database
@@ -138,32 +398,35 @@ impl Resolver {
   /// * `Ok(ResolvedEntity)` - The resolved entity with its type
   /// * `Err(ResolveError)` - Resolution failed for all types
   pub async fn any(db: &ProjectDb, id_or_prefix: &str) -> Result<ResolvedEntity, ResolveError> {
-    // Validate minimum length for prefix matching
-    if id_or_prefix.len() < 6 {
-      return Err(ResolveError::InvalidInput(
-        "ID must be at least 6 characters".to_string(),
-      ));
-    }
+    check_prefix(&normalize_id(id_or_prefix))?;
+
+    let mut ambiguous = None;
+    let mut note = |e: ResolveError| {
+      if ambiguous.is_none() && matches!(e, ResolveError::Ambiguous { .. }) {
+        ambiguous = Some(e);
+      }
+    };
 
     // Try code chunk first (most common case)
-    if let Ok(chunk) = Self::code_chunk(db, id_or_prefix).await {
-      return Ok(ResolvedEntity::Code(chunk));
+    match Self::code_chunk(db, id_or_prefix).await {
+      Ok(chunk) => return Ok(ResolvedEntity::Code(chunk)),
+      Err(e) => note(e),
     }
 
-    // Try memory
-    if let Ok(memory) = Self::memory(db, id_or_prefix).await {
-      return Ok(ResolvedEntity::Memory(memory));
+    match Self::memory(db, id_or_prefix).await {
+      Ok(memory) => return Ok(ResolvedEntity::Memory(memory)),
+      Err(e) => note(e),
     }
 
-    // Try document chunk
-    if let Ok(chunk) = Self::document_chunk(db, id_or_prefix).await {
-      return Ok(ResolvedEntity::Document(chunk));
+    match Self::document_chunk(db, id_or_prefix).await {
+      Ok(chunk) => return Ok(ResolvedEntity::Document(chunk)),
+      Err(e) => note(e),
     }
 
-    Err(ResolveError::NotFound {
+    Err(ambiguous.unwrap_or_else(|| ResolveError::NotFound {
       item_type: "Item",
       id: id_or_prefix.to_string(),
-    })
+    }))
   }
 }
 
@@ -193,3 +456,86 @@ impl ResolvedEntity {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalize_id() {
+    assert_eq!(normalize_id("  019ABCDE  "), "019abcde", "trims and lowercases");
+    assert_eq!(normalize_id("019abcde..."), "019abcde", "strips truncation ellipsis");
+    assert_eq!(normalize_id("`019abcde`"), "019abcde", "strips backticks");
+    assert_eq!(
+      normalize_id("019abcde12345678"),
+      "019abcde-1234-5678",
+      "reinserts UUID hyphens in a bare hex prefix"
+    );
+    assert_eq!(
+      normalize_id("0190f5e2a1b27c3d8e4f5a6b7c8d9e0f"),
+      "0190f5e2-a1b2-7c3d-8e4f-5a6b7c8d9e0f",
+      "reinserts UUID hyphens in a full bare UUID"
+    );
+    assert_eq!(
+      normalize_id("list-session-1"),
+      "list-session-1",
+      "non-hex IDs pass through"
+    );
+  }
+
+  #[test]
+  fn test_check_prefix() {
+    assert!(check_prefix("019abc").is_ok(), "six hex characters");
+    assert!(check_prefix("019ab").is_err(), "too short");
+    assert!(check_prefix("019abc' OR '1").is_err(), "non-ID characters are rejected");
+  }
+
+  #[test]
+  fn test_entity_names_match_by_prefix_then_loosely() {
+    let entities = || {
+      vec![
+        Entity::new("PostgreSQL", EntityType::Technology),
+        Entity::new("Postman", EntityType::Technology),
+        Entity::new("Redis", EntityType::Service),
+      ]
+    };
+    let names = |query: &str| -> Vec<String> {
+      match_entity_names(entities(), query)
+        .into_iter()
+        .map(|e| e.name)
+        .collect()
+    };
+
+    assert_eq!(names("postg"), vec!["PostgreSQL"], "unique prefix");
+    assert_eq!(names("reddis"), vec!["Redis"], "a typo still matches");
+    assert_eq!(names("gresql"), vec!["PostgreSQL"], "substring");
+    assert!(names("kafka").is_empty());
+
+    let err = single_match("Entity", "post", "post", match_entity_names(entities(), "post"), |e| {
+      e.name.clone()
+    })
+    .err()
+    .expect("two names share the prefix");
+    match err {
+      ResolveError::Ambiguous { count, candidates, .. } => {
+        assert_eq!(count, 2);
+        assert_eq!(candidates, vec!["PostgreSQL", "Postman"], "candidates are listed");
+      }
+      other => panic!("expected ambiguity, got {}", other),
+    }
+  }
+
+  #[test]
+  fn test_ambiguous_lists_candidates() {
+    let err = single_match("Memory", "019abc", "019abc", vec!["a", "b"], |s| {
+      describe(s, "first\nsecond")
+    })
+    .err()
+    .map(|e| e.to_string());
+    assert_eq!(
+      err.as_deref(),
+      Some("Ambiguous prefix '019abc' matches 2 items. Use more characters.\n  a  first\n  b  first"),
+      "candidates are listed with their first line"
+    );
+  }
+}
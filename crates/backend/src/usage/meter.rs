@@ -0,0 +1,162 @@
+//! Provider wrappers that report usage to the tracker
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use llm::{InferenceRequest, InferenceResponse, LlmProvider};
+
+use super::UsageTracker;
+use crate::{
+  config::CHARS_PER_TOKEN,
+  db::{UsageKind, UsageRecord},
+  embedding::{EmbeddingError, EmbeddingMode, EmbeddingProvider},
+};
+
+/// Project a metered provider records usage for
+#[derive(Debug, Clone)]
+pub struct ProjectTag {
+  pub id: String,
+  pub path: String,
+}
+
+impl ProjectTag {
+  fn record(&self, kind: UsageKind, provider: &str, model: &str) -> UsageRecord {
+    UsageRecord {
+      recorded_at: Utc::now(),
+      kind,
+      project_id: Some(self.id.clone()),
+      project_path: Some(self.path.clone()),
+      provider: provider.to_string(),
+      model: model.to_string(),
+      task: None,
+      requests: 1,
+      input_tokens: 0,
+      output_tokens: 0,
+      cost_usd: None,
+      input_chars: 0,
+      remote: false,
+    }
+  }
+}
+
+/// LLM provider that records tokens and cost of each successful call.
+///
+/// Reports itself unavailable once the monthly budget is exceeded, which
+/// hooks treat the same as having no LLM configured.
+#[derive(Clone)]
+pub struct MeteredLlm {
+  inner: Box<dyn LlmProvider>,
+  tracker: Arc<UsageTracker>,
+  project: ProjectTag,
+}
+
+impl MeteredLlm {
+  pub fn new(inner: Box<dyn LlmProvider>, tracker: Arc<UsageTracker>, project: ProjectTag) -> Self {
+    Self {
+      inner,
+      tracker,
+      project,
+    }
+  }
+}
+
+#[async_trait]
+impl LlmProvider for MeteredLlm {
+  fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  fn is_available(&self) -> bool {
+    self.inner.is_available() && !self.tracker.budget_exceeded()
+  }
+
+  async fn infer(&self, request: InferenceRequest) -> llm::Result<InferenceResponse> {
+    let task = request.task;
+    let input_chars = request.prompt.len() + request.system_prompt.as_ref().map_or(0, |s| s.len());
+    // The worker pool picks the model for tagged requests
+    let model = match task {
+      Some(task) => self.tracker.llm_routes.route(task).model.clone(),
+      None => request.model.clone(),
+    };
+
+    let response = self.inner.infer(request).await?;
+
+    let mut record = self.project.record(UsageKind::Llm, self.inner.name(), &model);
+    record.task = task.map(|t| t.as_str().to_string());
+    record.input_tokens = response.input_tokens as u64;
+    record.output_tokens = response.output_tokens as u64;
+    record.cost_usd = response.cost_usd;
+    record.input_chars = input_chars as u64;
+    // The only LLM provider is the Claude CLI, which calls Anthropic's API
+    record.remote = true;
+    self.tracker.record(record).await;
+
+    Ok(response)
+  }
+}
+
+/// Embedding provider that records the tokens sent to the provider.
+///
+/// Token counts are estimated from text length since embedding APIs do not
+/// all report them. Sits beneath the persistent cache so cache hits are free.
+pub struct MeteredEmbedding {
+  inner: Arc<dyn EmbeddingProvider>,
+  tracker: Arc<UsageTracker>,
+  project: ProjectTag,
+}
+
+impl MeteredEmbedding {
+  pub fn new(inner: Arc<dyn EmbeddingProvider>, tracker: Arc<UsageTracker>, project: ProjectTag) -> Self {
+    Self {
+      inner,
+      tracker,
+      project,
+    }
+  }
+
+  async fn record(&self, texts: &[&str]) {
+    let chars: usize = texts.iter().map(|t| t.len()).sum();
+    let tokens = chars.div_ceil(CHARS_PER_TOKEN) as u64;
+
+    let mut record = self
+      .project
+      .record(UsageKind::Embedding, self.inner.name(), self.inner.model_id());
+    record.requests = texts.len() as u64;
+    record.input_tokens = tokens;
+    record.cost_usd = self.tracker.embedding_cost(tokens);
+    record.input_chars = chars as u64;
+    record.remote = self.tracker.embedding_remote;
+    self.tracker.record(record).await;
+  }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MeteredEmbedding {
+  fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  fn model_id(&self) -> &str {
+    self.inner.model_id()
+  }
+
+  fn dimensions(&self) -> usize {
+    self.inner.dimensions()
+  }
+
+  async fn embed(&self, text: &str, mode: EmbeddingMode) -> Result<Vec<f32>, EmbeddingError> {
+    let vector = self.inner.embed(text, mode).await?;
+    self.record(&[text]).await;
+    Ok(vector)
+  }
+
+  async fn embed_batch(&self, texts: &[&str], mode: EmbeddingMode) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    if texts.is_empty() {
+      return self.inner.embed_batch(texts, mode).await;
+    }
+    let vectors = self.inner.embed_batch(texts, mode).await?;
+    self.record(texts).await;
+    Ok(vectors)
+  }
+}
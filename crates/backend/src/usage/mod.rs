@@ -0,0 +1,229 @@
+//! LLM and embedding usage accounting.
+//!
+//! Project actors get providers wrapped by [`MeteredLlm`] and
+//! [`MeteredEmbedding`], which report token counts and cost for every call
+//! to a daemon-wide [`UsageTracker`]. The tracker buffers records into the
+//! `usage` table and keeps a running total for the current month so hooks can
+//! pause LLM extraction once the configured budget is spent.
+
+mod meter;
+
+use std::{
+  path::Path,
+  sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+pub use meter::{MeteredEmbedding, MeteredLlm, ProjectTag};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::{
+  config::Config,
+  db::{DbError, UsageDb, UsageRecord},
+};
+
+/// Directory name of the usage database under the data directory
+pub const USAGE_DIR: &str = "usage";
+
+/// Buffered records written in one batch
+const FLUSH_THRESHOLD: usize = 64;
+
+/// Monthly spend is kept in millionths of a dollar so it can live in an atomic
+const MICROS_PER_USD: f64 = 1_000_000.0;
+
+/// Daemon-wide usage recorder and budget check
+pub struct UsageTracker {
+  db: UsageDb,
+  pending: Mutex<Vec<UsageRecord>>,
+  /// Calendar month (`year * 12 + month0`) the spend below belongs to
+  month: AtomicU32,
+  month_cost_micros: AtomicU64,
+  monthly_budget_usd: Option<f64>,
+  embedding_cost_per_million: Option<f64>,
+  /// Whether the embedding provider runs off this machine
+  embedding_remote: bool,
+  /// Task routing of the LLM worker pool, to name the model a call used
+  llm_routes: llm::TaskRoutes,
+}
+
+impl UsageTracker {
+  /// Open the usage log under `data_dir` and load this month's spend
+  pub async fn open(data_dir: &Path, config: &Config) -> Result<Self, DbError> {
+    let db = UsageDb::open(&data_dir.join(USAGE_DIR)).await?;
+
+    let now = Utc::now();
+    let spent: f64 = db
+      .list_since(month_start(now))
+      .await?
+      .iter()
+      .filter_map(|r| r.cost_usd)
+      .sum();
+    debug!(month_cost_usd = spent, "Loaded monthly usage");
+
+    Ok(Self {
+      db,
+      pending: Mutex::new(Vec::new()),
+      month: AtomicU32::new(month_key(now)),
+      month_cost_micros: AtomicU64::new(to_micros(spent)),
+      monthly_budget_usd: config.llm.monthly_budget_usd.filter(|b| *b > 0.0),
+      embedding_cost_per_million: config.embedding.cost_per_million_tokens,
+      embedding_remote: config.embedding.is_remote(),
+      llm_routes: config.llm.tasks.routes(),
+    })
+  }
+
+  pub fn monthly_budget_usd(&self) -> Option<f64> {
+    self.monthly_budget_usd
+  }
+
+  /// Spend recorded so far in the current calendar month (UTC)
+  pub fn month_cost_usd(&self) -> f64 {
+    if self.month.load(Ordering::Relaxed) != month_key(Utc::now()) {
+      return 0.0;
+    }
+    self.month_cost_micros.load(Ordering::Relaxed) as f64 / MICROS_PER_USD
+  }
+
+  /// Whether this month's spend has reached the budget
+  pub fn budget_exceeded(&self) -> bool {
+    self
+      .monthly_budget_usd
+      .is_some_and(|budget| self.month_cost_usd() >= budget)
+  }
+
+  /// Cost of embedding `tokens` tokens, if a price is configured
+  pub fn embedding_cost(&self, tokens: u64) -> Option<f64> {
+    self
+      .embedding_cost_per_million
+      .map(|price| tokens as f64 / 1_000_000.0 * price)
+  }
+
+  /// Record a provider call, writing buffered records once enough accumulate
+  pub async fn record(&self, record: UsageRecord) {
+    if let Some(cost) = record.cost_usd {
+      let was_exceeded = self.budget_exceeded();
+      self.add_month_cost(record.recorded_at, cost);
+      if !was_exceeded && self.budget_exceeded() {
+        warn!(
+          budget_usd = self.monthly_budget_usd.unwrap_or_default(),
+          spent_usd = self.month_cost_usd(),
+          "Monthly budget reached, pausing LLM extraction until next month"
+        );
+      }
+    }
+
+    let should_flush = {
+      let mut pending = self.pending.lock().await;
+      pending.push(record);
+      pending.len() >= FLUSH_THRESHOLD
+    };
+    if should_flush {
+      self.flush().await;
+    }
+  }
+
+  /// Write buffered records to the usage table.
+  ///
+  /// Failures are logged and the records dropped, so a broken usage table
+  /// never backs up provider calls.
+  pub async fn flush(&self) {
+    let records = std::mem::take(&mut *self.pending.lock().await);
+    if records.is_empty() {
+      return;
+    }
+    if let Err(e) = self.db.add_records(&records).await {
+      warn!(count = records.len(), "Failed to write usage records: {}", e);
+    }
+  }
+
+  /// All records since `since`, including ones not yet flushed
+  pub async fn records_since(&self, since: DateTime<Utc>) -> Result<Vec<UsageRecord>, DbError> {
+    self.flush().await;
+    self.db.list_since(since).await
+  }
+
+  fn add_month_cost(&self, at: DateTime<Utc>, cost: f64) {
+    let key = month_key(at);
+    let current = self.month.load(Ordering::Relaxed);
+    if key > current
+      && self
+        .month
+        .compare_exchange(current, key, Ordering::Relaxed, Ordering::Relaxed)
+        .is_ok()
+    {
+      self.month_cost_micros.store(0, Ordering::Relaxed);
+    }
+    if key == self.month.load(Ordering::Relaxed) {
+      self.month_cost_micros.fetch_add(to_micros(cost), Ordering::Relaxed);
+    }
+  }
+}
+
+/// Start of the calendar month (UTC) containing `at`
+pub fn month_start(at: DateTime<Utc>) -> DateTime<Utc> {
+  Utc
+    .with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+    .single()
+    .unwrap_or(at)
+}
+
+fn month_key(at: DateTime<Utc>) -> u32 {
+  (at.year().max(0) as u32) * 12 + at.month0()
+}
+
+fn to_micros(usd: f64) -> u64 {
+  (usd.max(0.0) * MICROS_PER_USD).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::db::UsageKind;
+
+  fn llm_record(cost_usd: f64) -> UsageRecord {
+    UsageRecord {
+      recorded_at: Utc::now(),
+      kind: UsageKind::Llm,
+      project_id: None,
+      project_path: None,
+      provider: "test".to_string(),
+      model: "haiku".to_string(),
+      task: Some("extraction".to_string()),
+      requests: 1,
+      input_tokens: 1000,
+      output_tokens: 100,
+      cost_usd: Some(cost_usd),
+      input_chars: 4000,
+      remote: true,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_budget_and_persistence() {
+    let temp = TempDir::new().unwrap();
+    let mut config = Config::default();
+    config.llm.monthly_budget_usd = Some(1.0);
+    config.embedding.cost_per_million_tokens = Some(0.02);
+
+    let tracker = UsageTracker::open(temp.path(), &config).await.unwrap();
+    assert!(!tracker.budget_exceeded(), "nothing spent yet");
+    assert_eq!(tracker.embedding_cost(500_000), Some(0.01), "embedding price applies");
+
+    tracker.record(llm_record(0.6)).await;
+    assert!(!tracker.budget_exceeded(), "under budget");
+    tracker.record(llm_record(0.5)).await;
+    assert!(tracker.budget_exceeded(), "over budget after second call");
+
+    let records = tracker.records_since(month_start(Utc::now())).await.unwrap();
+    assert_eq!(records.len(), 2, "buffered records are flushed before listing");
+
+    let reopened = UsageTracker::open(temp.path(), &config).await.unwrap();
+    assert!(
+      (reopened.month_cost_usd() - 1.1).abs() < 1e-6,
+      "monthly spend is reloaded on open"
+    );
+  }
+}
@@ -0,0 +1,666 @@
+//! Embedding model retrieval evaluation.
+//!
+//! Embeds a fixture corpus with each configured model, runs a labeled query
+//! set against the vectors and reports file-level recall@K and MRR next to
+//! indexing time and estimated cost. Providers are built in-process from the
+//! same `[embedding]` config the daemon uses, so models can be compared
+//! without reconfiguring or restarting the daemon between runs.
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use ccengram::{EmbeddingMode, EmbeddingProvider, config::EmbeddingConfig};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{BenchmarkError, Result, metrics::performance::LatencyStats};
+
+/// Chunks sent to a provider per request when the model sets no batch size.
+const DEFAULT_EVAL_BATCH_SIZE: usize = 32;
+
+/// Recall within this margin of the best model counts as equivalent when
+/// picking the cheapest recommendation.
+const RECALL_TOLERANCE: f64 = 0.02;
+
+fn default_extensions() -> Vec<String> {
+  vec!["rs".to_string()]
+}
+
+fn default_chunk_lines() -> usize {
+  60
+}
+
+/// Evaluation spec (TOML): corpus, candidate models and labeled queries.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalSpec {
+  pub corpus: CorpusSpec,
+  #[serde(default)]
+  pub models: Vec<ModelSpec>,
+  pub queries: Vec<LabeledQuery>,
+}
+
+/// Fixture corpus location and chunking.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpusSpec {
+  /// Corpus root, relative to the spec file
+  pub path: PathBuf,
+  /// File extensions to include
+  #[serde(default = "default_extensions")]
+  pub extensions: Vec<String>,
+  /// Lines per chunk
+  #[serde(default = "default_chunk_lines")]
+  pub chunk_lines: usize,
+}
+
+/// Candidate embedding model.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelSpec {
+  /// Display name in the report
+  pub name: String,
+  /// Provider price in USD per million input tokens (0 for local models)
+  #[serde(default)]
+  pub cost_per_million_tokens: f64,
+  /// Provider settings, same keys as the `[embedding]` config section
+  #[serde(flatten)]
+  pub embedding: EmbeddingConfig,
+}
+
+/// Query with the corpus files a good search should return.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabeledQuery {
+  pub query: String,
+  /// Paths relative to the corpus root
+  pub relevant: Vec<String>,
+}
+
+impl EvalSpec {
+  /// Load a spec, resolving the corpus path against the spec's directory.
+  pub async fn load(path: &Path) -> Result<Self> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let mut spec: EvalSpec = toml::from_str(&content)?;
+
+    if spec.corpus.path.is_relative()
+      && let Some(dir) = path.parent()
+    {
+      spec.corpus.path = dir.join(&spec.corpus.path);
+    }
+    if spec.queries.is_empty() {
+      return Err(BenchmarkError::Execution(format!("{} has no queries", path.display())));
+    }
+
+    Ok(spec)
+  }
+}
+
+/// A slice of a corpus file that gets embedded.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+  /// Path relative to the corpus root, with `/` separators
+  pub file: String,
+  pub text: String,
+}
+
+/// Split a file into fixed-size line windows, prefixed with its path.
+pub fn chunk_file(file: &str, content: &str, chunk_lines: usize) -> Vec<Chunk> {
+  let lines: Vec<&str> = content.lines().collect();
+  lines
+    .chunks(chunk_lines.max(1))
+    .map(|window| window.join("\n"))
+    .filter(|body| !body.trim().is_empty())
+    .map(|body| Chunk {
+      file: file.to_string(),
+      text: format!("// {}\n{}", file, body),
+    })
+    .collect()
+}
+
+/// Read and chunk every matching file under the corpus root, in path order.
+pub async fn load_corpus(corpus: &CorpusSpec) -> Result<Vec<Chunk>> {
+  let mut files = Vec::new();
+  let mut dirs = vec![corpus.path.clone()];
+
+  while let Some(dir) = dirs.pop() {
+    let mut entries = tokio::fs::read_dir(&dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+      let path = entry.path();
+      if entry.file_type().await?.is_dir() {
+        dirs.push(path);
+      } else if path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| corpus.extensions.iter().any(|e| e == ext))
+      {
+        files.push(path);
+      }
+    }
+  }
+  files.sort();
+
+  let mut chunks = Vec::new();
+  for path in files {
+    let Ok(relative) = path.strip_prefix(&corpus.path) else {
+      continue;
+    };
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let content = tokio::fs::read_to_string(&path).await?;
+    chunks.extend(chunk_file(&relative, &content, corpus.chunk_lines));
+  }
+
+  Ok(chunks)
+}
+
+/// Rough token count, matching the indexer's `chars / 4` estimate.
+fn estimate_tokens(text: &str) -> usize {
+  text.len().div_ceil(4)
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+  let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+  let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+  let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+  if norm_a == 0.0 || norm_b == 0.0 {
+    0.0
+  } else {
+    dot / (norm_a * norm_b)
+  }
+}
+
+/// Rank files by their best-scoring chunk, most similar first.
+pub fn rank_files(query: &[f32], chunks: &[Chunk], vectors: &[Vec<f32>]) -> Vec<String> {
+  let mut best: HashMap<&str, f32> = HashMap::new();
+  for (chunk, vector) in chunks.iter().zip(vectors) {
+    let score = cosine_similarity(query, vector);
+    let entry = best.entry(chunk.file.as_str()).or_insert(f32::MIN);
+    if score > *entry {
+      *entry = score;
+    }
+  }
+
+  let mut ranked: Vec<(&str, f32)> = best.into_iter().collect();
+  ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+  ranked.into_iter().map(|(file, _)| file.to_string()).collect()
+}
+
+/// 1-based rank of the first relevant file.
+pub fn first_relevant_rank(ranked: &[String], relevant: &[String]) -> Option<usize> {
+  ranked.iter().position(|f| relevant.contains(f)).map(|i| i + 1)
+}
+
+/// Fraction of relevant files that appear in the top `k`.
+pub fn recall_at(ranked: &[String], relevant: &[String], k: usize) -> f64 {
+  if relevant.is_empty() {
+    return 0.0;
+  }
+  let found = relevant
+    .iter()
+    .filter(|r| ranked.iter().take(k).any(|f| f == *r))
+    .count();
+  found as f64 / relevant.len() as f64
+}
+
+/// Per-query outcome for one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryOutcome {
+  pub query: String,
+  pub first_relevant_rank: Option<usize>,
+  pub top_files: Vec<String>,
+}
+
+/// Results for one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelResult {
+  pub name: String,
+  pub provider: String,
+  pub model: String,
+  pub dimensions: usize,
+  /// Set when the provider could not be built or failed to embed
+  pub error: Option<String>,
+  pub index_duration_ms: u64,
+  pub query_latency: LatencyStats,
+  /// Mean recall per K
+  pub recall_at_k: BTreeMap<usize, f64>,
+  pub mrr: f64,
+  /// Estimated tokens embedded for the corpus and queries
+  pub tokens: usize,
+  pub estimated_cost_usd: f64,
+  pub queries: Vec<QueryOutcome>,
+}
+
+impl ModelResult {
+  fn new(spec: &ModelSpec) -> Self {
+    Self {
+      name: spec.name.clone(),
+      provider: format!("{:?}", spec.embedding.provider).to_lowercase(),
+      model: spec.embedding.model.clone(),
+      dimensions: spec.embedding.dimensions,
+      error: None,
+      index_duration_ms: 0,
+      query_latency: LatencyStats::default(),
+      recall_at_k: BTreeMap::new(),
+      mrr: 0.0,
+      tokens: 0,
+      estimated_cost_usd: 0.0,
+      queries: Vec::new(),
+    }
+  }
+
+  fn failed(spec: &ModelSpec, error: String) -> Self {
+    Self {
+      error: Some(error),
+      ..Self::new(spec)
+    }
+  }
+
+  /// Recall at the largest evaluated K.
+  fn headline_recall(&self) -> f64 {
+    self.recall_at_k.values().next_back().copied().unwrap_or(0.0)
+  }
+
+  /// Score queries against ranked files.
+  fn score(&mut self, queries: &[LabeledQuery], rankings: Vec<Vec<String>>, ks: &[usize]) {
+    let n = queries.len().max(1) as f64;
+    let mut reciprocal_sum = 0.0;
+
+    for &k in ks {
+      let total: f64 = queries
+        .iter()
+        .zip(&rankings)
+        .map(|(q, ranked)| recall_at(ranked, &q.relevant, k))
+        .sum();
+      self.recall_at_k.insert(k, total / n);
+    }
+
+    for (query, ranked) in queries.iter().zip(rankings) {
+      let rank = first_relevant_rank(&ranked, &query.relevant);
+      if let Some(rank) = rank {
+        reciprocal_sum += 1.0 / rank as f64;
+      }
+      self.queries.push(QueryOutcome {
+        query: query.query.clone(),
+        first_relevant_rank: rank,
+        top_files: ranked
+          .into_iter()
+          .take(ks.iter().copied().max().unwrap_or(10))
+          .collect(),
+      });
+    }
+
+    self.mrr = reciprocal_sum / n;
+  }
+}
+
+/// Embedding evaluation report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingEvalReport {
+  pub timestamp: String,
+  pub version: String,
+  pub spec: String,
+  pub corpus_files: usize,
+  pub corpus_chunks: usize,
+  pub queries: usize,
+  pub ks: Vec<usize>,
+  pub models: Vec<ModelResult>,
+}
+
+impl EmbeddingEvalReport {
+  /// Best model by recall at the largest K, then MRR.
+  pub fn best(&self) -> Option<&ModelResult> {
+    self.successful().max_by(|a, b| {
+      a.headline_recall()
+        .total_cmp(&b.headline_recall())
+        .then_with(|| a.mrr.total_cmp(&b.mrr))
+    })
+  }
+
+  /// Cheapest model whose recall is within tolerance of the best.
+  pub fn recommended(&self) -> Option<&ModelResult> {
+    let best = self.best()?.headline_recall();
+    self
+      .successful()
+      .filter(|m| m.headline_recall() + RECALL_TOLERANCE >= best)
+      .min_by(|a, b| {
+        a.estimated_cost_usd
+          .total_cmp(&b.estimated_cost_usd)
+          .then_with(|| b.headline_recall().total_cmp(&a.headline_recall()))
+      })
+  }
+
+  fn successful(&self) -> impl Iterator<Item = &ModelResult> {
+    self.models.iter().filter(|m| m.error.is_none())
+  }
+
+  /// Render the report as Markdown.
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::new();
+
+    out.push_str("# Embedding Model Evaluation\n\n");
+    out.push_str(&format!("**Timestamp:** {}\n", self.timestamp));
+    out.push_str(&format!("**Version:** {}\n", self.version));
+    out.push_str(&format!("**Spec:** {}\n", self.spec));
+    out.push_str(&format!(
+      "**Corpus:** {} files, {} chunks\n",
+      self.corpus_files, self.corpus_chunks
+    ));
+    out.push_str(&format!("**Queries:** {}\n\n", self.queries));
+
+    out.push_str("## Results\n\n");
+    out.push_str("| Model | Dims |");
+    for k in &self.ks {
+      out.push_str(&format!(" R@{} |", k));
+    }
+    out.push_str(" MRR | Index Time | Query p50 | Est. Cost |\n");
+    out.push_str("|-------|------|");
+    for _ in &self.ks {
+      out.push_str("------|");
+    }
+    out.push_str("-----|------------|-----------|-----------|\n");
+
+    for model in &self.models {
+      if let Some(ref error) = model.error {
+        out.push_str(&format!(
+          "| {} | {} | failed: {} |\n",
+          model.name, model.dimensions, error
+        ));
+        continue;
+      }
+      out.push_str(&format!("| {} | {} |", model.name, model.dimensions));
+      for k in &self.ks {
+        let recall = model.recall_at_k.get(k).copied().unwrap_or(0.0);
+        out.push_str(&format!(" {:.1}% |", recall * 100.0));
+      }
+      out.push_str(&format!(
+        " {:.3} | {:.1}s | {} ms | ${:.4} |\n",
+        model.mrr,
+        model.index_duration_ms as f64 / 1000.0,
+        model.query_latency.p50_ms,
+        model.estimated_cost_usd
+      ));
+    }
+    out.push('\n');
+
+    if let (Some(best), Some(recommended)) = (self.best(), self.recommended()) {
+      out.push_str("## Recommendation\n\n");
+      out.push_str(&format!(
+        "- Best recall: **{}** ({:.1}% R@{}, MRR {:.3})\n",
+        best.name,
+        best.headline_recall() * 100.0,
+        self.ks.last().copied().unwrap_or(0),
+        best.mrr
+      ));
+      out.push_str(&format!(
+        "- Cheapest within {:.0} points of best: **{}** (${:.4} for this corpus)\n\n",
+        RECALL_TOLERANCE * 100.0,
+        recommended.name,
+        recommended.estimated_cost_usd
+      ));
+    }
+
+    let missed: Vec<(&str, &str)> = self
+      .successful()
+      .flat_map(|m| {
+        m.queries
+          .iter()
+          .filter(|q| q.first_relevant_rank.is_none())
+          .map(move |q| (m.name.as_str(), q.query.as_str()))
+      })
+      .collect();
+    if !missed.is_empty() {
+      out.push_str("## Missed Queries\n\n");
+      for (model, query) in missed {
+        out.push_str(&format!("- {}: \"{}\"\n", model, query));
+      }
+      out.push('\n');
+    }
+
+    out
+  }
+
+  /// Save JSON and Markdown reports.
+  pub async fn save(&self, output_dir: &PathBuf) -> Result<()> {
+    tokio::fs::create_dir_all(output_dir).await?;
+
+    // Save JSON
+    let json_path = output_dir.join("embedding_eval.json");
+    let json = serde_json::to_string_pretty(self)?;
+    tokio::fs::write(&json_path, json).await?;
+    info!("Saved JSON report: {}", json_path.display());
+
+    // Save Markdown
+    let md_path = output_dir.join("embedding_eval.md");
+    tokio::fs::write(&md_path, self.to_markdown()).await?;
+    info!("Saved Markdown report: {}", md_path.display());
+
+    Ok(())
+  }
+}
+
+/// Runs a spec against each of its models.
+pub struct EmbeddingEval {
+  spec_path: PathBuf,
+  spec: EvalSpec,
+  ks: Vec<usize>,
+}
+
+impl EmbeddingEval {
+  /// Create an evaluation, optionally restricted to the named models.
+  pub fn new(spec_path: PathBuf, mut spec: EvalSpec, ks: Vec<usize>, models: Option<&[String]>) -> Self {
+    if let Some(names) = models {
+      spec.models.retain(|m| names.contains(&m.name));
+    }
+    let mut ks = ks;
+    ks.sort_unstable();
+    ks.dedup();
+    Self { spec_path, spec, ks }
+  }
+
+  /// Embed the corpus and queries with every model and score the rankings.
+  pub async fn run(&self) -> Result<EmbeddingEvalReport> {
+    if self.spec.models.is_empty() {
+      return Err(BenchmarkError::Execution("No models to evaluate".to_string()));
+    }
+
+    let chunks = load_corpus(&self.spec.corpus).await?;
+    if chunks.is_empty() {
+      return Err(BenchmarkError::Execution(format!(
+        "No files found in corpus {}",
+        self.spec.corpus.path.display()
+      )));
+    }
+    let mut files: Vec<&str> = chunks.iter().map(|c| c.file.as_str()).collect();
+    files.dedup();
+    info!("Corpus: {} files, {} chunks", files.len(), chunks.len());
+
+    for query in &self.spec.queries {
+      for relevant in &query.relevant {
+        if !files.contains(&relevant.as_str()) {
+          warn!(
+            "Labeled file {} is not in the corpus (query: {})",
+            relevant, query.query
+          );
+        }
+      }
+    }
+
+    let mut models = Vec::new();
+    for spec in &self.spec.models {
+      info!("Evaluating {} ({})", spec.name, spec.embedding.model);
+      let result = match <dyn EmbeddingProvider>::from_config(&spec.embedding).await {
+        Ok(provider) => self.evaluate(spec, provider, &chunks).await,
+        Err(e) => Err(BenchmarkError::Execution(e.to_string())),
+      };
+      models.push(result.unwrap_or_else(|e| {
+        warn!("{} failed: {}", spec.name, e);
+        ModelResult::failed(spec, e.to_string())
+      }));
+    }
+
+    Ok(EmbeddingEvalReport {
+      timestamp: chrono::Utc::now().to_rfc3339(),
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      spec: self.spec_path.display().to_string(),
+      corpus_files: files.len(),
+      corpus_chunks: chunks.len(),
+      queries: self.spec.queries.len(),
+      ks: self.ks.clone(),
+      models,
+    })
+  }
+
+  async fn evaluate(
+    &self,
+    spec: &ModelSpec,
+    provider: Arc<dyn EmbeddingProvider>,
+    chunks: &[Chunk],
+  ) -> Result<ModelResult> {
+    let batch_size = spec.embedding.max_batch_size.unwrap_or(DEFAULT_EVAL_BATCH_SIZE).max(1);
+
+    let start = Instant::now();
+    let mut vectors = Vec::with_capacity(chunks.len());
+    for batch in chunks.chunks(batch_size) {
+      let texts: Vec<&str> = batch.iter().map(|c| c.text.as_str()).collect();
+      let embedded = provider
+        .embed_batch(&texts, EmbeddingMode::Document)
+        .await
+        .map_err(|e| BenchmarkError::Execution(e.to_string()))?;
+      vectors.extend(embedded);
+    }
+    let index_duration = start.elapsed();
+
+    let mut latencies: Vec<Duration> = Vec::with_capacity(self.spec.queries.len());
+    let mut rankings = Vec::with_capacity(self.spec.queries.len());
+    for query in &self.spec.queries {
+      let start = Instant::now();
+      let vector = provider
+        .embed(&query.query, EmbeddingMode::Query)
+        .await
+        .map_err(|e| BenchmarkError::Execution(e.to_string()))?;
+      latencies.push(start.elapsed());
+      rankings.push(rank_files(&vector, chunks, &vectors));
+    }
+
+    let tokens: usize = chunks.iter().map(|c| estimate_tokens(&c.text)).sum::<usize>()
+      + self
+        .spec
+        .queries
+        .iter()
+        .map(|q| estimate_tokens(&q.query))
+        .sum::<usize>();
+
+    let mut result = ModelResult::new(spec);
+    result.dimensions = provider.dimensions();
+    result.index_duration_ms = index_duration.as_millis() as u64;
+    result.query_latency = LatencyStats::from_durations(&latencies);
+    result.tokens = tokens;
+    result.estimated_cost_usd = tokens as f64 / 1_000_000.0 * spec.cost_per_million_tokens;
+    result.score(&self.spec.queries, rankings, &self.ks);
+
+    Ok(result)
+  }
+}
+
+/// Parse a comma-separated K list like `1,5,10`.
+pub fn parse_ks(s: &str) -> Option<Vec<usize>> {
+  let ks: Vec<usize> = s
+    .split(',')
+    .map(str::trim)
+    .filter(|p| !p.is_empty())
+    .map(|p| p.parse().ok().filter(|k| *k > 0))
+    .collect::<Option<_>>()?;
+  (!ks.is_empty()).then_some(ks)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn chunk(file: &str) -> Chunk {
+    Chunk {
+      file: file.to_string(),
+      text: String::new(),
+    }
+  }
+
+  fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+  }
+
+  #[test]
+  fn test_rank_files_uses_best_chunk_per_file() {
+    let chunks = vec![chunk("a.rs"), chunk("a.rs"), chunk("b.rs")];
+    let vectors = vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.7, 0.7]];
+
+    let ranked = rank_files(&[1.0, 0.0], &chunks, &vectors);
+    assert_eq!(
+      ranked,
+      strings(&["a.rs", "b.rs"]),
+      "a.rs should win on its best chunk and appear once"
+    );
+  }
+
+  #[test]
+  fn test_recall_and_mrr() {
+    let queries = vec![
+      LabeledQuery {
+        query: "first".to_string(),
+        relevant: strings(&["x.rs", "y.rs"]),
+      },
+      LabeledQuery {
+        query: "second".to_string(),
+        relevant: strings(&["z.rs"]),
+      },
+    ];
+    let rankings = vec![strings(&["x.rs", "w.rs", "y.rs"]), strings(&["w.rs", "x.rs", "y.rs"])];
+
+    let spec = ModelSpec {
+      name: "test".to_string(),
+      cost_per_million_tokens: 0.0,
+      embedding: EmbeddingConfig::default(),
+    };
+    let mut result = ModelResult::new(&spec);
+    result.score(&queries, rankings, &[1, 3]);
+
+    assert_eq!(result.recall_at_k[&1], 0.25, "half of one query's files at K=1");
+    assert_eq!(result.recall_at_k[&3], 0.5, "all of query one, none of query two");
+    assert_eq!(result.mrr, 0.5, "rank 1 for the first query, miss for the second");
+    assert_eq!(result.queries[1].first_relevant_rank, None, "miss should have no rank");
+  }
+
+  #[test]
+  fn test_chunk_file_windows() {
+    let content = (1..=5).map(|n| format!("line {}", n)).collect::<Vec<_>>().join("\n");
+    let chunks = chunk_file("src/lib.rs", &content, 2);
+    assert_eq!(chunks.len(), 3, "5 lines in windows of 2");
+    assert!(
+      chunks[0].text.starts_with("// src/lib.rs\nline 1"),
+      "chunk should be prefixed with its path"
+    );
+  }
+
+  #[test]
+  fn test_parse_ks() {
+    assert_eq!(parse_ks("1, 5,10"), Some(vec![1, 5, 10]), "comma list should parse");
+    assert_eq!(parse_ks("0,5"), None, "K must be positive");
+    assert_eq!(parse_ks(""), None, "empty list should be rejected");
+  }
+
+  #[tokio::test]
+  async fn test_bundled_spec_labels_exist() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("eval/embedding_eval.toml");
+    let spec = EvalSpec::load(&path).await.expect("bundled spec should parse");
+    assert!(!spec.models.is_empty(), "bundled spec should list models");
+
+    for query in &spec.queries {
+      for relevant in &query.relevant {
+        assert!(
+          spec.corpus.path.join(relevant).exists(),
+          "labeled file {} should exist in the corpus",
+          relevant
+        );
+      }
+    }
+  }
+}
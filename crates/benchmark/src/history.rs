@@ -0,0 +1,489 @@
+//! Benchmark history and trend detection.
+//!
+//! Each recorded run's summary is appended to a local JSON Lines store. Trend
+//! analysis fits a line through the most recent runs for every tracked metric
+//! and flags drift past a threshold, which catches slow degradations where no
+//! single run-to-run comparison crosses the regression threshold.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::{Result, reports::BenchmarkReport};
+
+/// Minimum number of runs before a trend is reported.
+const MIN_TREND_RUNS: usize = 3;
+
+/// Default location of the history store.
+pub fn default_history_path() -> PathBuf {
+  dirs::data_local_dir()
+    .unwrap_or_else(|| PathBuf::from(".local/share"))
+    .join("ccengram-bench")
+    .join("history.jsonl")
+}
+
+/// Summary of one benchmark run as stored in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+  /// When the benchmark report was generated
+  pub timestamp: DateTime<Utc>,
+  /// Optional label (e.g. "nightly", a branch name)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub label: Option<String>,
+  /// Git commit the run was built from (if available)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub git_commit: Option<String>,
+  /// Number of scenarios in the run
+  pub total_scenarios: usize,
+  /// Pass rate (0.0-1.0)
+  pub pass_rate: f64,
+  /// Average search latency p50 in ms
+  pub search_latency_p50_ms: f64,
+  /// Average search latency p95 in ms
+  pub search_latency_p95_ms: f64,
+  /// Average context latency p50 in ms
+  pub context_latency_p50_ms: f64,
+  /// Average file recall
+  pub file_recall: f64,
+  /// Average symbol recall
+  pub symbol_recall: f64,
+  /// Average MRR
+  pub mrr: f64,
+  /// Average noise ratio
+  pub noise_ratio: f64,
+}
+
+impl HistoryEntry {
+  /// Summarize a benchmark report.
+  pub fn from_report(report: &BenchmarkReport, label: Option<String>) -> Self {
+    let summary = &report.summary;
+    Self {
+      timestamp: report.metadata.timestamp,
+      label,
+      git_commit: report.metadata.git_commit.clone(),
+      total_scenarios: report.metadata.total_scenarios,
+      pass_rate: summary.pass_rate,
+      search_latency_p50_ms: summary.performance.avg_search_latency_p50_ms,
+      search_latency_p95_ms: summary.performance.avg_search_latency_p95_ms,
+      context_latency_p50_ms: summary.performance.avg_context_latency_p50_ms,
+      file_recall: summary.accuracy.avg_file_recall,
+      symbol_recall: summary.accuracy.avg_symbol_recall,
+      mrr: summary.accuracy.avg_mrr,
+      noise_ratio: summary.accuracy.avg_noise_ratio,
+    }
+  }
+}
+
+/// Append-only history store backed by a JSON Lines file.
+pub struct HistoryStore {
+  path: PathBuf,
+}
+
+impl HistoryStore {
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into() }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Append an entry to the store, creating it if needed.
+  pub async fn append(&self, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = self.path.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let mut file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)
+      .await?;
+    file.write_all(line.as_bytes()).await?;
+    Ok(())
+  }
+
+  /// Load all entries, oldest first. A missing store is empty.
+  pub async fn load(&self) -> Result<Vec<HistoryEntry>> {
+    let content = match tokio::fs::read_to_string(&self.path).await {
+      Ok(content) => content,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+      if line.trim().is_empty() {
+        continue;
+      }
+      match serde_json::from_str::<HistoryEntry>(line) {
+        Ok(entry) => entries.push(entry),
+        Err(e) => warn!("Skipping malformed history line {}: {}", i + 1, e),
+      }
+    }
+
+    entries.sort_by_key(|e| e.timestamp);
+    Ok(entries)
+  }
+}
+
+/// Metrics tracked for trends: (name, higher is better, accessor).
+const TRACKED_METRICS: &[(&str, bool, fn(&HistoryEntry) -> f64)] = &[
+  ("pass_rate", true, |e| e.pass_rate),
+  ("search_latency_p50_ms", false, |e| e.search_latency_p50_ms),
+  ("search_latency_p95_ms", false, |e| e.search_latency_p95_ms),
+  ("context_latency_p50_ms", false, |e| e.context_latency_p50_ms),
+  ("file_recall", true, |e| e.file_recall),
+  ("symbol_recall", true, |e| e.symbol_recall),
+  ("mrr", true, |e| e.mrr),
+  ("noise_ratio", false, |e| e.noise_ratio),
+];
+
+/// Direction of a metric's trend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendStatus {
+  Degrading,
+  Improving,
+  Stable,
+}
+
+/// Trend of one metric over the analysis window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricTrend {
+  /// Metric name
+  pub metric: String,
+  /// Value in the oldest run of the window
+  pub first: f64,
+  /// Value in the newest run of the window
+  pub latest: f64,
+  /// Change of the fitted line across the window, as a percentage of its start
+  pub drift_percent: f64,
+  /// Fitted change per week as a percentage of the window mean (None if all runs share a timestamp)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub weekly_percent: Option<f64>,
+  /// Largest single run-to-run change in the degrading direction, in percent
+  pub max_step_percent: f64,
+  /// Trend classification against the threshold
+  pub status: TrendStatus,
+}
+
+impl MetricTrend {
+  /// Degrading over the window without any single step crossing the threshold.
+  pub fn is_gradual(&self, threshold_percent: f64) -> bool {
+    self.status == TrendStatus::Degrading && self.max_step_percent < threshold_percent
+  }
+}
+
+/// Trend analysis over the most recent runs in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendReport {
+  /// Number of runs analyzed
+  pub runs: usize,
+  /// Timestamp of the oldest analyzed run
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub from: Option<DateTime<Utc>>,
+  /// Timestamp of the newest analyzed run
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub to: Option<DateTime<Utc>>,
+  /// Drift threshold percentage
+  pub threshold_percent: f64,
+  /// Per-metric trends (empty when there are too few runs)
+  pub trends: Vec<MetricTrend>,
+}
+
+impl TrendReport {
+  /// Analyze the last `window` entries (entries must be oldest first).
+  pub fn analyze(entries: &[HistoryEntry], window: usize, threshold_percent: f64) -> Self {
+    let start = entries.len().saturating_sub(window.max(MIN_TREND_RUNS));
+    let recent = &entries[start..];
+
+    let mut report = Self {
+      runs: recent.len(),
+      from: recent.first().map(|e| e.timestamp),
+      to: recent.last().map(|e| e.timestamp),
+      threshold_percent,
+      trends: Vec::new(),
+    };
+
+    if recent.len() < MIN_TREND_RUNS {
+      return report;
+    }
+
+    // X axis in days since the first run; fall back to run index if all runs share a timestamp
+    let origin = recent[0].timestamp;
+    let days: Vec<f64> = recent
+      .iter()
+      .map(|e| (e.timestamp - origin).num_seconds() as f64 / 86_400.0)
+      .collect();
+    let timed = days.iter().any(|d| *d > 0.0);
+    let xs: Vec<f64> = if timed {
+      days
+    } else {
+      (0..recent.len()).map(|i| i as f64).collect()
+    };
+
+    for (name, higher_is_better, value) in TRACKED_METRICS {
+      let ys: Vec<f64> = recent.iter().map(value).collect();
+      report.trends.push(Self::metric_trend(
+        name,
+        *higher_is_better,
+        &xs,
+        &ys,
+        timed,
+        threshold_percent,
+      ));
+    }
+
+    report
+  }
+
+  fn metric_trend(
+    metric: &str,
+    higher_is_better: bool,
+    xs: &[f64],
+    ys: &[f64],
+    timed: bool,
+    threshold_percent: f64,
+  ) -> MetricTrend {
+    let (slope, intercept) = linear_fit(xs, ys);
+    let fitted_start = intercept + slope * xs[0];
+    let fitted_end = intercept + slope * xs[xs.len() - 1];
+    let drift_percent = percent_change(fitted_start, fitted_end);
+
+    let mean = ys.iter().sum::<f64>() / ys.len() as f64;
+    let weekly_percent = (timed && mean.abs() > f64::EPSILON).then(|| slope * 7.0 / mean.abs() * 100.0);
+
+    // Signed so that positive always means worse
+    let badness = |change: f64| if higher_is_better { -change } else { change };
+
+    let max_step_percent = ys
+      .windows(2)
+      .map(|w| badness(percent_change(w[0], w[1])))
+      .fold(0.0, f64::max);
+
+    let status = if badness(drift_percent) > threshold_percent {
+      TrendStatus::Degrading
+    } else if -badness(drift_percent) > threshold_percent {
+      TrendStatus::Improving
+    } else {
+      TrendStatus::Stable
+    };
+
+    MetricTrend {
+      metric: metric.to_string(),
+      first: ys[0],
+      latest: ys[ys.len() - 1],
+      drift_percent,
+      weekly_percent,
+      max_step_percent,
+      status,
+    }
+  }
+
+  /// Metrics that degraded past the threshold.
+  pub fn degradations(&self) -> impl Iterator<Item = &MetricTrend> {
+    self.trends.iter().filter(|t| t.status == TrendStatus::Degrading)
+  }
+
+  /// Whether no metric is degrading.
+  pub fn passes(&self) -> bool {
+    self.degradations().next().is_none()
+  }
+
+  /// Save the trend report to JSON.
+  pub async fn save(&self, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(self)?;
+    tokio::fs::write(path, json).await?;
+    Ok(())
+  }
+
+  /// Generate markdown summary.
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::new();
+
+    out.push_str("# Benchmark Trends\n\n");
+    if let (Some(from), Some(to)) = (self.from, self.to) {
+      out.push_str(&format!("**Window:** {} runs ({} to {})\n", self.runs, from, to));
+    }
+    out.push_str(&format!("**Threshold:** {:.1}%\n\n", self.threshold_percent));
+
+    if self.trends.is_empty() {
+      out.push_str(&format!(
+        "Not enough history for trend analysis ({} runs, need {}).\n",
+        self.runs, MIN_TREND_RUNS
+      ));
+      return out;
+    }
+
+    let status = if self.passes() { "✅ PASS" } else { "❌ FAIL" };
+    out.push_str(&format!("## Summary: {}\n\n", status));
+    out.push_str("| Metric | First | Latest | Drift | Per Week | Max Step | Status |\n");
+    out.push_str("|--------|-------|--------|-------|----------|----------|--------|\n");
+    for t in &self.trends {
+      let weekly = t
+        .weekly_percent
+        .map(|w| format!("{:+.1}%", w))
+        .unwrap_or_else(|| "-".to_string());
+      let status = match t.status {
+        TrendStatus::Degrading if t.is_gradual(self.threshold_percent) => "❌ gradual",
+        TrendStatus::Degrading => "❌",
+        TrendStatus::Improving => "✅",
+        TrendStatus::Stable => "-",
+      };
+      out.push_str(&format!(
+        "| {} | {:.2} | {:.2} | {:+.1}% | {} | {:.1}% | {} |\n",
+        t.metric, t.first, t.latest, t.drift_percent, weekly, t.max_step_percent, status
+      ));
+    }
+    out.push('\n');
+
+    out
+  }
+}
+
+/// Least-squares fit returning (slope, intercept).
+fn linear_fit(xs: &[f64], ys: &[f64]) -> (f64, f64) {
+  let n = xs.len() as f64;
+  let mean_x = xs.iter().sum::<f64>() / n;
+  let mean_y = ys.iter().sum::<f64>() / n;
+
+  let mut covariance = 0.0;
+  let mut variance = 0.0;
+  for (x, y) in xs.iter().zip(ys) {
+    covariance += (x - mean_x) * (y - mean_y);
+    variance += (x - mean_x) * (x - mean_x);
+  }
+
+  if variance.abs() < f64::EPSILON {
+    return (0.0, mean_y);
+  }
+
+  let slope = covariance / variance;
+  (slope, mean_y - slope * mean_x)
+}
+
+fn percent_change(from: f64, to: f64) -> f64 {
+  if from.abs() < f64::EPSILON {
+    0.0
+  } else {
+    (to - from) / from.abs() * 100.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::Duration;
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn entry(days_ago: i64, search_p50: f64, file_recall: f64) -> HistoryEntry {
+    HistoryEntry {
+      timestamp: Utc::now() - Duration::days(days_ago),
+      label: None,
+      git_commit: None,
+      total_scenarios: 10,
+      pass_rate: 1.0,
+      search_latency_p50_ms: search_p50,
+      search_latency_p95_ms: search_p50 * 2.0,
+      context_latency_p50_ms: 20.0,
+      file_recall,
+      symbol_recall: 0.5,
+      mrr: 0.6,
+      noise_ratio: 0.2,
+    }
+  }
+
+  fn trend<'a>(report: &'a TrendReport, metric: &str) -> &'a MetricTrend {
+    report
+      .trends
+      .iter()
+      .find(|t| t.metric == metric)
+      .expect("metric should be tracked")
+  }
+
+  #[test]
+  fn test_gradual_latency_degradation_detected() {
+    // +3% per week for a month: no weekly step crosses 10%, the month does
+    let entries: Vec<_> = (0..5)
+      .map(|week| entry(28 - week * 7, 100.0 * 1.03f64.powi(week as i32), 0.8))
+      .collect();
+
+    let report = TrendReport::analyze(&entries, 10, 10.0);
+    let latency = trend(&report, "search_latency_p50_ms");
+
+    assert_eq!(
+      latency.status,
+      TrendStatus::Degrading,
+      "latency drift should be flagged"
+    );
+    assert!(latency.is_gradual(10.0), "no single step should cross the threshold");
+    assert!(
+      latency.weekly_percent.is_some_and(|w| (w - 3.0).abs() < 0.5),
+      "weekly change should be about 3%: {:?}",
+      latency.weekly_percent
+    );
+    assert_eq!(
+      trend(&report, "file_recall").status,
+      TrendStatus::Stable,
+      "flat metric should be stable"
+    );
+    assert!(!report.passes(), "report should fail on degradation");
+  }
+
+  #[test]
+  fn test_recall_drop_is_degradation_and_latency_drop_is_improvement() {
+    let entries: Vec<_> = (0..4)
+      .map(|i| entry(4 - i, 100.0 - i as f64 * 10.0, 0.9 - i as f64 * 0.05))
+      .collect();
+
+    let report = TrendReport::analyze(&entries, 10, 10.0);
+    assert_eq!(
+      trend(&report, "file_recall").status,
+      TrendStatus::Degrading,
+      "falling recall is a degradation"
+    );
+    assert_eq!(
+      trend(&report, "search_latency_p50_ms").status,
+      TrendStatus::Improving,
+      "falling latency is an improvement"
+    );
+  }
+
+  #[test]
+  fn test_window_limits_runs_and_short_history_has_no_trends() {
+    let entries: Vec<_> = (0..8).map(|i| entry(8 - i, 100.0, 0.8)).collect();
+    let report = TrendReport::analyze(&entries, 5, 10.0);
+    assert_eq!(report.runs, 5, "only the last 5 runs should be analyzed");
+
+    let report = TrendReport::analyze(&entries[..2], 5, 10.0);
+    assert!(report.trends.is_empty(), "two runs are not enough for a trend");
+    assert!(report.passes(), "no trends means nothing degrading");
+  }
+
+  #[tokio::test]
+  async fn test_store_append_and_load() {
+    let temp = TempDir::new().unwrap();
+    let store = HistoryStore::new(temp.path().join("nested").join("history.jsonl"));
+
+    assert!(store.load().await.unwrap().is_empty(), "missing store should be empty");
+
+    store.append(&entry(1, 120.0, 0.7)).await.unwrap();
+    store.append(&entry(2, 100.0, 0.8)).await.unwrap();
+
+    let entries = store.load().await.unwrap();
+    assert_eq!(entries.len(), 2, "both entries should be stored");
+    assert!(
+      entries[0].timestamp < entries[1].timestamp,
+      "entries should load oldest first"
+    );
+    assert_eq!(entries[0].search_latency_p50_ms, 100.0, "oldest entry first");
+  }
+}
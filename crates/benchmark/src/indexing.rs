@@ -282,7 +282,7 @@ impl IndexingBenchmark {
       info!("Benchmarking indexing for: {}", repo);
 
       // Prepare repository (download if needed)
-      let repo_path = prepare_repo(*repo, self.cache_dir.clone()).await?;
+      let repo_path = prepare_repo(repo, self.cache_dir.clone()).await?;
       info!("Repository path: {}", repo_path.display());
 
       for i in 0..iterations {
@@ -346,6 +346,7 @@ impl IndexingBenchmark {
       .call_streaming(CodeIndexParams {
         force: cold_start,
         stream: true,
+        since: None,
       })
       .await?;
 
@@ -572,7 +573,7 @@ impl IncrementalBenchmark {
       pb.set_message(format!("{}: preparing", repo));
       info!("Running incremental benchmark for: {}", repo);
 
-      let repo_path = prepare_repo(*repo, self.cache_dir.clone()).await?;
+      let repo_path = prepare_repo(repo, self.cache_dir.clone()).await?;
       self.client.change_cwd(repo_path.clone());
 
       // Ensure index exists first
@@ -665,7 +666,7 @@ impl IncrementalBenchmark {
 
     // Trigger startup scan to remove stale chunks from DB
     // (the files were deleted from disk, startup scan will detect and remove chunks)
-    let _ = self.client.call(WatchStartParams).await;
+    let _ = self.client.call(WatchStartParams::default()).await;
     self.wait_for_watcher_idle().await?;
     let _ = self.client.call(WatchStopParams).await;
 
@@ -693,7 +694,7 @@ impl IncrementalBenchmark {
     }
 
     // Index the setup files via startup_scan
-    let watch_result = self.client.call(WatchStartParams).await?;
+    let watch_result = self.client.call(WatchStartParams::default()).await?;
     if let Some(scan) = &watch_result.startup_scan {
       debug!(
         "Startup scan: was_indexed={}, added={}, modified={}, deleted={}, moved={}, queued={}",
@@ -772,7 +773,7 @@ impl IncrementalBenchmark {
     let start = Instant::now();
 
     // Start watcher - triggers startup_scan which should detect all changes
-    let watch_result = self.client.call(WatchStartParams).await?;
+    let watch_result = self.client.call(WatchStartParams::default()).await?;
     if let Some(scan) = &watch_result.startup_scan {
       debug!(
         "Detection scan: was_indexed={}, added={}, modified={}, deleted={}, moved={}, queued={}",
@@ -962,7 +963,7 @@ impl IncrementalBenchmark {
     let mut results = Vec::new();
 
     // Start watcher for large file tests
-    let _ = self.client.call(WatchStartParams).await;
+    let _ = self.client.call(WatchStartParams::default()).await;
     self.wait_for_watcher_idle().await?;
 
     for size_mb in sizes_mb {
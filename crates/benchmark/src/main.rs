@@ -2,7 +2,7 @@
 //!
 //! This crate provides comprehensive benchmarking for testing the exploration
 //! capabilities of CCEngram's `explore` and `context` tools against large
-//! real-world codebases (Zed, VSCode).
+//! real-world codebases (Zed, VSCode, or any repo defined in a registry file).
 //!
 //! ## Key Concepts
 //!
@@ -10,6 +10,7 @@
 //! - **Metrics**: Performance (latency, throughput) and accuracy (recall, noise ratio)
 //! - **Ground Truth**: Call graph analysis, noise patterns, optional annotations
 //! - **Reports**: JSON (machine-readable) and Markdown (human-readable)
+//! - **History**: Local store of run summaries with trend detection across runs
 
 use std::path::PathBuf;
 
@@ -20,16 +21,21 @@ use tracing::{info, warn};
 use tracing_subscriber::{EnvFilter, fmt};
 
 use self::{
+  embedding_eval::{EmbeddingEval, EvalSpec, parse_ks},
   fixtures::FixtureGenerator,
+  history::{HistoryEntry, HistoryStore, TrendReport, default_history_path},
   indexing::{IncrementalBenchConfig, IncrementalBenchmark, IndexingBenchmark, IndexingComparison, IndexingReport},
-  reports::{ComparisonReport, generate_reports},
+  reports::{BenchmarkReport, ComparisonReport, HtmlReport, generate_reports},
   repos::{RepoCache, RepoRegistry, TargetRepo, default_cache_dir, prepare_repo},
   scenarios::{Scenario, ScenarioRunner, filter_scenarios, load_scenarios_from_dir, run_scenarios_parallel},
+  stress::{SearchMix, SearchStressBenchmark, StressBenchConfig},
   watcher::{WatcherBenchConfig, WatcherBenchmark, WatcherTestType},
 };
 
+mod embedding_eval;
 mod fixtures;
 mod ground_truth;
+mod history;
 mod indexing;
 mod llm_judge;
 mod metrics;
@@ -37,6 +43,7 @@ mod reports;
 mod repos;
 mod scenarios;
 mod session;
+mod stress;
 mod watcher;
 
 /// Benchmark-specific errors
@@ -71,6 +78,11 @@ struct Cli {
   #[arg(short, long, global = true)]
   verbose: bool,
 
+  /// Repository registry file defining additional target repos
+  /// (default: ~/.config/ccengram-bench/repos.toml if it exists)
+  #[arg(long, global = true)]
+  registry: Option<PathBuf>,
+
   #[command(subcommand)]
   command: Commands,
 }
@@ -102,6 +114,14 @@ enum Commands {
     /// Name for this benchmark run
     #[arg(long)]
     name: Option<String>,
+
+    /// Baseline results file (JSON) to show regression deltas against in the HTML report
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Regression threshold percentage for the baseline comparison
+    #[arg(long, default_value = "10")]
+    threshold: f64,
   },
 
   /// Compare two benchmark results for regressions
@@ -123,11 +143,15 @@ enum Commands {
     /// Type of comparison: scenario (default) or indexing
     #[arg(long, default_value = "scenario")]
     compare_type: String,
+
+    /// Write an HTML report of the current results with regression deltas (scenario comparisons only)
+    #[arg(long)]
+    html: Option<PathBuf>,
   },
 
   /// Download repositories
   Download {
-    /// Repositories to download (comma-separated: zed,vscode or 'all')
+    /// Repositories to download (comma-separated names, or 'all')
     #[arg(short, long, default_value = "all")]
     repos: String,
 
@@ -142,7 +166,7 @@ enum Commands {
 
   /// Index repositories (code and docs) via daemon
   Index {
-    /// Repositories to index (comma-separated: zed,vscode or 'all')
+    /// Repositories to index (comma-separated names, or 'all')
     #[arg(short, long, default_value = "all")]
     repos: String,
 
@@ -204,7 +228,7 @@ enum Commands {
 
   /// Benchmark indexing performance
   IndexPerf {
-    /// Repositories to benchmark (comma-separated: zed,vscode or 'all')
+    /// Repositories to benchmark (comma-separated names, or 'all')
     #[arg(short, long, default_value = "all")]
     repos: String,
 
@@ -227,7 +251,7 @@ enum Commands {
 
   /// Benchmark incremental indexing performance
   IncrementalPerf {
-    /// Repositories to benchmark (comma-separated: zed,vscode or 'all')
+    /// Repositories to benchmark (comma-separated names, or 'all')
     #[arg(short, long, default_value = "all")]
     repos: String,
 
@@ -289,6 +313,105 @@ enum Commands {
     #[arg(long)]
     cache_dir: Option<PathBuf>,
   },
+
+  /// Stress concurrent searches against the daemon while a re-index runs
+  SearchStress {
+    /// Repository to benchmark
+    #[arg(short, long, default_value = "zed")]
+    repo: String,
+
+    /// Number of concurrent search workers
+    #[arg(short, long, default_value = "8")]
+    concurrency: usize,
+
+    /// Seconds to fire searches in each phase
+    #[arg(short, long, default_value = "30")]
+    duration: u64,
+
+    /// Workload mix weights (memory, code, docs)
+    #[arg(short, long, default_value = "memory=1,code=2,docs=1")]
+    mix: String,
+
+    /// Maximum p95 latency in ms while indexing
+    #[arg(long, default_value = "1000")]
+    target_p95_ms: u64,
+
+    /// Skip the idle baseline phase
+    #[arg(long)]
+    skip_idle: bool,
+
+    /// Output directory for results
+    #[arg(short, long, default_value = "./benchmark-results")]
+    output: PathBuf,
+
+    /// Cache directory for repositories
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+  },
+
+  /// Compare embedding models by recall@K and MRR on a labeled query set
+  EmbeddingEval {
+    /// Evaluation spec (TOML) with corpus, models and labeled queries
+    #[arg(short, long, default_value = "crates/benchmark/eval/embedding_eval.toml")]
+    spec: PathBuf,
+
+    /// Only evaluate these models (comma-separated names from the spec)
+    #[arg(short, long)]
+    models: Option<String>,
+
+    /// Cutoffs for recall@K (comma-separated)
+    #[arg(short, long, default_value = "1,5,10")]
+    k: String,
+
+    /// Output directory for results
+    #[arg(short, long, default_value = "./benchmark-results")]
+    output: PathBuf,
+  },
+
+  /// Track benchmark results over time and detect gradual degradations
+  History {
+    /// History store file (JSON Lines)
+    #[arg(long, global = true)]
+    history: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: HistoryCommand,
+  },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+  /// Record a benchmark results file in the history store
+  Record {
+    /// Results file (JSON) from `run`
+    report: PathBuf,
+
+    /// Label for this run (e.g. "nightly" or a branch name)
+    #[arg(short, long)]
+    label: Option<String>,
+  },
+
+  /// Show recorded runs
+  Show {
+    /// Number of most recent runs to show
+    #[arg(short = 'n', long, default_value = "20")]
+    last: usize,
+  },
+
+  /// Fit trends over recent runs and flag metrics that drifted past the threshold
+  Trend {
+    /// Number of most recent runs to analyze
+    #[arg(short, long, default_value = "10")]
+    window: usize,
+
+    /// Drift threshold percentage across the window
+    #[arg(short, long, default_value = "10")]
+    threshold: f64,
+
+    /// Output trend report (JSON)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+  },
 }
 
 #[tokio::main]
@@ -306,6 +429,8 @@ async fn main() -> anyhow::Result<()> {
     .finish();
   tracing::subscriber::set_global_default(subscriber)?;
 
+  RepoRegistry::load(cli.registry.as_deref()).await?.install()?;
+
   match cli.command {
     Commands::Run {
       output,
@@ -314,14 +439,20 @@ async fn main() -> anyhow::Result<()> {
       scenarios_dir,
       parallel,
       name,
-    } => run_benchmarks(output, scenarios, llm_judge, scenarios_dir, parallel, name).await,
+      baseline,
+      threshold,
+    } => {
+      let baseline = baseline.map(|path| (path, threshold));
+      run_benchmarks(output, scenarios, llm_judge, scenarios_dir, parallel, name, baseline).await
+    }
     Commands::Compare {
       baseline,
       current,
       threshold,
       output,
       compare_type,
-    } => compare_results(baseline, current, threshold, output, compare_type).await,
+      html,
+    } => compare_results(baseline, current, threshold, output, compare_type, html).await,
     Commands::Download {
       repos,
       force,
@@ -373,6 +504,38 @@ async fn main() -> anyhow::Result<()> {
       repo,
       cache_dir,
     } => run_large_file_benchmark(output, sizes_mb, repo, cache_dir).await,
+    Commands::SearchStress {
+      repo,
+      concurrency,
+      duration,
+      mix,
+      target_p95_ms,
+      skip_idle,
+      output,
+      cache_dir,
+    } => {
+      let mix = SearchMix::parse(&mix).ok_or_else(|| anyhow::anyhow!("Invalid search mix: {}", mix))?;
+      let config = StressBenchConfig {
+        concurrency,
+        duration: std::time::Duration::from_secs(duration),
+        mix,
+        skip_idle,
+        target_p95_ms,
+        ..Default::default()
+      };
+      run_search_stress(repo, config, output, cache_dir).await
+    }
+    Commands::EmbeddingEval {
+      spec,
+      models,
+      k,
+      output,
+    } => {
+      let ks = parse_ks(&k).ok_or_else(|| anyhow::anyhow!("Invalid K list: {}", k))?;
+      let models = models.map(|m| m.split(',').map(|s| s.trim().to_string()).collect::<Vec<_>>());
+      run_embedding_eval(spec, models, ks, output).await
+    }
+    Commands::History { history, command } => run_history(history, command).await,
   }
 }
 
@@ -383,9 +546,16 @@ async fn run_benchmarks(
   scenarios_dir: Option<PathBuf>,
   parallel: bool,
   run_name: Option<String>,
+  baseline: Option<(PathBuf, f64)>,
 ) -> anyhow::Result<()> {
   use std::collections::HashMap;
 
+  // Load the baseline up front so a bad path fails before running scenarios
+  let baseline = match baseline {
+    Some((path, threshold)) => Some((BenchmarkReport::load(&path).await?, threshold)),
+    None => None,
+  };
+
   let socket_path = ScenarioRunner::default_socket_path();
 
   // Load scenarios
@@ -421,7 +591,7 @@ async fn run_benchmarks(
   let mut scenarios_by_repo: HashMap<TargetRepo, Vec<&Scenario>> = HashMap::new();
   for scenario in &scenarios {
     scenarios_by_repo
-      .entry(scenario.metadata.repo)
+      .entry(scenario.metadata.repo.clone())
       .or_default()
       .push(scenario);
   }
@@ -430,7 +600,7 @@ async fn run_benchmarks(
   let mut repo_paths: HashMap<TargetRepo, PathBuf> = HashMap::new();
   for repo in scenarios_by_repo.keys() {
     // Ensure repo is downloaded
-    let repo_path = match prepare_repo(*repo, None).await {
+    let repo_path = match prepare_repo(repo, None).await {
       Ok(path) => path,
       Err(e) => {
         anyhow::bail!(
@@ -452,7 +622,7 @@ async fn run_benchmarks(
       );
     }
 
-    repo_paths.insert(*repo, repo_path.to_path_buf());
+    repo_paths.insert(repo.clone(), repo_path.to_path_buf());
   }
 
   // Run scenarios grouped by repo
@@ -572,7 +742,13 @@ async fn run_benchmarks(
 
   // Generate reports
   info!("Generating reports in: {}", output.display());
-  generate_reports(&results, &output, run_name.as_deref()).await?;
+  generate_reports(
+    &results,
+    &output,
+    run_name.as_deref(),
+    baseline.as_ref().map(|(report, threshold)| (report, *threshold)),
+  )
+  .await?;
 
   // Print summary
   let passed = results.iter().filter(|r| r.passed).count();
@@ -669,18 +845,7 @@ async fn index_repos_streaming(
     }
   }
 
-  let targets: Vec<TargetRepo> = if repos == "all" {
-    TargetRepo::all().to_vec()
-  } else {
-    repos
-      .split(',')
-      .filter_map(|s| TargetRepo::from_name(s.trim()))
-      .collect()
-  };
-
-  if targets.is_empty() {
-    anyhow::bail!("No valid repositories specified. Use: zed, vscode, or 'all'");
-  }
+  let targets = parse_targets(&repos)?;
 
   let socket_path = ScenarioRunner::default_socket_path();
 
@@ -704,7 +869,7 @@ async fn index_repos_streaming(
 
   for repo in targets {
     // Ensure repo is downloaded first
-    let repo_path = match prepare_repo(repo, cache_dir.clone()).await {
+    let repo_path = match prepare_repo(&repo, cache_dir.clone()).await {
       Ok(path) => path,
       Err(e) => {
         warn!("Repository {} not downloaded: {}", repo, e);
@@ -713,7 +878,7 @@ async fn index_repos_streaming(
       }
     };
 
-    let repo_config = RepoRegistry::get(repo);
+    let repo_config = RepoRegistry::global().get(&repo)?;
 
     // Index code
     info!("Indexing code for {} at {}", repo, repo_path.display());
@@ -741,7 +906,13 @@ async fn index_code_for_repo(_socket_path: &str, repo_path: &std::path::Path, fo
   use ccengram::ipc::{StreamUpdate, code::CodeIndexParams};
 
   let client = Client::connect(repo_path.to_path_buf()).await?;
-  let mut rx = client.call_streaming(CodeIndexParams { force, stream: true }).await?;
+  let mut rx = client
+    .call_streaming(CodeIndexParams {
+      force,
+      stream: true,
+      since: None,
+    })
+    .await?;
 
   let mp = MultiProgress::new();
   let mut stage_bars: HashMap<String, ProgressBar> = HashMap::new();
@@ -808,6 +979,8 @@ async fn index_docs_for_repo(
     .call_streaming(DocsIngestParams {
       directory: Some(docs_path.to_string_lossy().to_string()),
       file: None,
+      url: None,
+      depth: None,
       stream: true,
     })
     .await?;
@@ -868,6 +1041,7 @@ async fn compare_results(
   threshold: f64,
   output: Option<PathBuf>,
   compare_type: String,
+  html: Option<PathBuf>,
 ) -> anyhow::Result<()> {
   info!(
     "Comparing {} vs {} (threshold: {:.0}%, type: {})",
@@ -904,7 +1078,9 @@ async fn compare_results(
     }
     _ => {
       // Default: scenario comparison
-      let comparison = ComparisonReport::from_files(&baseline, &current, threshold).await?;
+      let baseline_report = BenchmarkReport::load(&baseline).await?;
+      let current_report = BenchmarkReport::load(&current).await?;
+      let comparison = ComparisonReport::compare(&baseline_report, &current_report, threshold);
 
       // Print markdown summary
       println!("{}", comparison.to_markdown());
@@ -915,6 +1091,13 @@ async fn compare_results(
         info!("Comparison saved to: {}", output.display());
       }
 
+      if let Some(html) = html {
+        HtmlReport::from_report(&current_report, Some(&comparison))
+          .save(&html)
+          .await?;
+        info!("HTML report saved to: {}", html.display());
+      }
+
       if !comparison.summary.passes {
         std::process::exit(1);
       }
@@ -924,33 +1107,185 @@ async fn compare_results(
   Ok(())
 }
 
-async fn download_repos(repos: String, force: bool, cache_dir: Option<PathBuf>) -> anyhow::Result<()> {
-  let cache_dir = cache_dir.unwrap_or_else(default_cache_dir);
-  let cache = RepoCache::new(cache_dir.clone());
+async fn run_search_stress(
+  repo: String,
+  config: StressBenchConfig,
+  output: PathBuf,
+  cache_dir: Option<PathBuf>,
+) -> anyhow::Result<()> {
+  let target = TargetRepo::from_name(&repo).ok_or_else(|| anyhow::anyhow!("Unknown repository: {}", repo))?;
 
-  let targets: Vec<TargetRepo> = if repos == "all" {
-    TargetRepo::all().to_vec()
-  } else {
-    repos
-      .split(',')
-      .filter_map(|s| TargetRepo::from_name(s.trim()))
-      .collect()
-  };
+  info!(
+    "Running search stress benchmark: {}, {} workers, mix {}",
+    target, config.concurrency, config.mix
+  );
+
+  // Create benchmark runner
+  let client = Client::connect(cache_dir.clone().unwrap_or_else(default_cache_dir)).await?;
+  let mut benchmark = SearchStressBenchmark::new(client, cache_dir).with_config(config);
+
+  // Run benchmark
+  let report = benchmark.run(target).await?;
+
+  // Save reports
+  report.save(&output).await?;
+
+  // Print summary
+  println!("\n{}", report.to_markdown());
+
+  if !report.summary.passes {
+    std::process::exit(1);
+  }
+
+  Ok(())
+}
+
+async fn run_embedding_eval(
+  spec_path: PathBuf,
+  models: Option<Vec<String>>,
+  ks: Vec<usize>,
+  output: PathBuf,
+) -> anyhow::Result<()> {
+  let spec = EvalSpec::load(&spec_path).await?;
+
+  info!(
+    "Running embedding evaluation: {} models, {} queries",
+    spec.models.len(),
+    spec.queries.len()
+  );
+
+  let eval = EmbeddingEval::new(spec_path, spec, ks, models.as_deref());
+  let report = eval.run().await?;
+
+  // Save reports
+  report.save(&output).await?;
+
+  // Print summary
+  println!("\n{}", report.to_markdown());
+
+  Ok(())
+}
+
+async fn run_history(history: Option<PathBuf>, command: HistoryCommand) -> anyhow::Result<()> {
+  let store = HistoryStore::new(history.unwrap_or_else(default_history_path));
+
+  match command {
+    HistoryCommand::Record { report, label } => {
+      let report = BenchmarkReport::load(&report).await?;
+      store.append(&HistoryEntry::from_report(&report, label)).await?;
+      info!(
+        "Recorded run from {} in {}",
+        report.metadata.timestamp,
+        store.path().display()
+      );
+    }
+    HistoryCommand::Show { last } => {
+      let entries = store.load().await?;
+      if entries.is_empty() {
+        println!("No runs recorded in {}", store.path().display());
+        return Ok(());
+      }
+
+      println!(
+        "{:<20} {:<12} {:<10} {:>6} {:>10} {:>10} {:>8} {:>8} {:>6}",
+        "TIMESTAMP", "LABEL", "COMMIT", "PASS", "SEARCH P50", "SEARCH P95", "F-RECALL", "S-RECALL", "MRR"
+      );
+      for entry in &entries[entries.len().saturating_sub(last)..] {
+        println!(
+          "{:<20} {:<12} {:<10} {:>5.0}% {:>8.1}ms {:>8.1}ms {:>7.1}% {:>7.1}% {:>6.3}",
+          entry.timestamp.format("%Y-%m-%d %H:%M"),
+          entry.label.as_deref().unwrap_or("-"),
+          entry.git_commit.as_deref().map(|c| &c[..c.len().min(8)]).unwrap_or("-"),
+          entry.pass_rate * 100.0,
+          entry.search_latency_p50_ms,
+          entry.search_latency_p95_ms,
+          entry.file_recall * 100.0,
+          entry.symbol_recall * 100.0,
+          entry.mrr
+        );
+      }
+    }
+    HistoryCommand::Trend {
+      window,
+      threshold,
+      output,
+    } => {
+      let entries = store.load().await?;
+      let report = TrendReport::analyze(&entries, window, threshold);
+
+      println!("{}", report.to_markdown());
+
+      if let Some(output) = output {
+        report.save(&output).await?;
+        info!("Trend report saved to: {}", output.display());
+      }
+
+      for trend in report.degradations() {
+        let kind = if trend.is_gradual(threshold) {
+          "Gradual degradation"
+        } else {
+          "Degradation"
+        };
+        warn!(
+          "{} in {}: {:+.1}% over {} runs ({:.2} -> {:.2})",
+          kind, trend.metric, trend.drift_percent, report.runs, trend.first, trend.latest
+        );
+      }
+
+      if !report.passes() {
+        std::process::exit(1);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Parse a comma-separated list of registry repo names, or `all`.
+fn parse_targets(repos: &str) -> anyhow::Result<Vec<TargetRepo>> {
+  if repos.trim() == "all" {
+    return Ok(TargetRepo::all());
+  }
+
+  let mut targets = Vec::new();
+  for name in repos.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+    match TargetRepo::from_name(name) {
+      Some(target) => targets.push(target),
+      None => anyhow::bail!("Unknown repository: {}. Use: {}, or 'all'", name, repo_names()),
+    }
+  }
 
   if targets.is_empty() {
-    anyhow::bail!("No valid repositories specified. Use: zed, vscode, or 'all'");
+    anyhow::bail!("No valid repositories specified. Use: {}, or 'all'", repo_names());
   }
+  Ok(targets)
+}
+
+/// Names of all repositories in the registry, for help and error messages.
+fn repo_names() -> String {
+  TargetRepo::all()
+    .iter()
+    .map(TargetRepo::name)
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+async fn download_repos(repos: String, force: bool, cache_dir: Option<PathBuf>) -> anyhow::Result<()> {
+  let cache_dir = cache_dir.unwrap_or_else(default_cache_dir);
+  let cache = RepoCache::new(cache_dir.clone());
+
+  let targets = parse_targets(&repos)?;
 
   for repo in targets {
-    let config = RepoRegistry::get(repo);
+    let config = RepoRegistry::global().get(&repo)?;
     info!("Downloading {} ({})", repo, config.release_tag);
 
     if force {
       info!("Removing existing cache for {}", repo);
-      cache.remove(repo).await?;
+      cache.remove(&repo).await?;
     }
 
-    match prepare_repo(repo, Some(cache_dir.clone())).await {
+    match prepare_repo(&repo, Some(cache_dir.clone())).await {
       Ok(path) => {
         info!("Repository downloaded to: {}", path.display());
       }
@@ -1007,18 +1342,7 @@ async fn run_indexing_benchmark(
 ) -> anyhow::Result<()> {
   let socket_path = ScenarioRunner::default_socket_path();
 
-  let targets: Vec<TargetRepo> = if repos == "all" {
-    TargetRepo::all().to_vec()
-  } else {
-    repos
-      .split(',')
-      .filter_map(|s| TargetRepo::from_name(s.trim()))
-      .collect()
-  };
-
-  if targets.is_empty() {
-    anyhow::bail!("No valid repositories specified. Use: zed, vscode, or 'all'");
-  }
+  let targets = parse_targets(&repos)?;
 
   info!(
     "Running indexing benchmark: {} repos, {} iterations, cold={}",
@@ -1081,7 +1405,7 @@ async fn clean_cache(
     if clean_dbs {
       // Clean databases for all known benchmark repos
       for target in TargetRepo::all() {
-        if let Some(project_dir) = get_project_data_dir(&cache, *target, &data_dir)
+        if let Some(project_dir) = get_project_data_dir(&cache, &target, &data_dir)
           && project_dir.exists()
         {
           tokio::fs::remove_dir_all(&project_dir).await?;
@@ -1097,12 +1421,12 @@ async fn clean_cache(
       info!("Cleaning data for {}", target);
 
       if clean_repos {
-        cache.remove(target).await?;
+        cache.remove(&target).await?;
         println!("Repository cache cleaned for {}", target);
       }
 
       if clean_dbs {
-        if let Some(project_dir) = get_project_data_dir(&cache, target, &data_dir) {
+        if let Some(project_dir) = get_project_data_dir(&cache, &target, &data_dir) {
           if project_dir.exists() {
             tokio::fs::remove_dir_all(&project_dir).await?;
             info!("Removed database: {}", project_dir.display());
@@ -1122,7 +1446,7 @@ async fn clean_cache(
     println!();
     println!("Options:");
     println!("  --all         Clean all repos and databases");
-    println!("  --repo <name> Clean specific repo (zed, vscode)");
+    println!("  --repo <name> Clean specific repo ({})", repo_names());
     println!("  --repos-only  Only clean repository caches");
     println!("  --db-only     Only clean LanceDB databases");
     println!();
@@ -1137,10 +1461,10 @@ async fn clean_cache(
 }
 
 /// Get the project data directory for a benchmark repo.
-fn get_project_data_dir(cache: &RepoCache, repo: TargetRepo, data_dir: &std::path::Path) -> Option<PathBuf> {
+fn get_project_data_dir(cache: &RepoCache, repo: &TargetRepo, data_dir: &std::path::Path) -> Option<PathBuf> {
   use ccengram::project::ProjectId;
 
-  let repo_path = cache.repo_path(repo);
+  let repo_path = cache.repo_path(repo).ok()?;
   if !repo_path.exists() {
     return None;
   }
@@ -1158,18 +1482,7 @@ async fn run_incremental_benchmark(
   output: PathBuf,
   cache_dir: Option<PathBuf>,
 ) -> anyhow::Result<()> {
-  let targets: Vec<TargetRepo> = if repos == "all" {
-    TargetRepo::all().to_vec()
-  } else {
-    repos
-      .split(',')
-      .filter_map(|s| TargetRepo::from_name(s.trim()))
-      .collect()
-  };
-
-  if targets.is_empty() {
-    anyhow::bail!("No valid repositories specified. Use: zed, vscode, or 'all'");
-  }
+  let targets = parse_targets(&repos)?;
 
   info!(
     "Running incremental indexing benchmark: {} repos, {} files/iter, {} iterations",
@@ -1296,7 +1609,7 @@ async fn run_large_file_benchmark(
 
   info!("Running large file benchmark: repo={}, sizes={:?} MB", target, sizes);
 
-  let repo_path = prepare_repo(target, cache_dir.clone()).await?;
+  let repo_path = prepare_repo(&target, cache_dir.clone()).await?;
   let client = Client::connect(repo_path.clone()).await?;
 
   // Get daemon PID for resource monitoring
@@ -1329,6 +1642,7 @@ async fn run_large_file_benchmark(
       .call(CodeIndexParams {
         force: false,
         stream: false,
+        since: None,
       })
       .await?;
 
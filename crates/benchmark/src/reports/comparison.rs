@@ -221,13 +221,6 @@ impl ComparisonReport {
     }
   }
 
-  /// Load comparison between two report files.
-  pub async fn from_files(baseline_path: &Path, current_path: &Path, threshold: f64) -> Result<Self> {
-    let baseline = BenchmarkReport::load(baseline_path).await?;
-    let current = BenchmarkReport::load(current_path).await?;
-    Ok(Self::compare(&baseline, &current, threshold))
-  }
-
   /// Save comparison to JSON.
   pub async fn save(&self, path: &Path) -> Result<()> {
     let json = serde_json::to_string_pretty(self)?;
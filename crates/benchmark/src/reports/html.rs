@@ -0,0 +1,580 @@
+//! HTML report generation.
+//!
+//! Produces a single self-contained file: styles are inlined and charts are
+//! rendered as inline SVG, so the report can be shared without a server or
+//! network access.
+
+use std::{fmt::Write as _, path::Path};
+
+use super::{comparison::ComparisonReport, json::BenchmarkReport};
+use crate::{
+  Result,
+  metrics::{MetricTargets, performance::LatencyStats},
+  scenarios::ScenarioResult,
+};
+
+/// Width of the label column in charts
+const LABEL_WIDTH: f64 = 220.0;
+/// Width of the plot area in charts
+const PLOT_WIDTH: f64 = 560.0;
+/// Height of one chart row
+const ROW_HEIGHT: f64 = 28.0;
+/// Height reserved for the axis below chart rows
+const AXIS_HEIGHT: f64 = 24.0;
+
+const GREEN: &str = "#2e9e5b";
+const RED: &str = "#d0453a";
+const BLUE: &str = "#3b6fd4";
+const GRAY: &str = "#8a8f98";
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 960px; color: #1f2328; padding: 0 1rem; }
+h1 { margin-bottom: 0.25rem; }
+h2 { margin-top: 2.5rem; border-bottom: 1px solid #d8dee4; padding-bottom: 0.3rem; }
+.meta { color: #59636e; margin-top: 0; }
+.cards { display: flex; gap: 1rem; flex-wrap: wrap; }
+.card { border: 1px solid #d8dee4; border-radius: 6px; padding: 0.75rem 1rem; min-width: 140px; }
+.card .value { font-size: 1.5rem; font-weight: 600; }
+.card .label { color: #59636e; font-size: 0.85rem; }
+table { border-collapse: collapse; width: 100%; font-size: 0.9rem; }
+th, td { border: 1px solid #d8dee4; padding: 0.35rem 0.6rem; text-align: left; }
+th { background: #f6f8fa; }
+.pass { color: #2e9e5b; font-weight: 600; }
+.fail { color: #d0453a; font-weight: 600; }
+.legend { font-size: 0.85rem; color: #59636e; }
+.legend span { display: inline-block; width: 0.8rem; height: 0.8rem; margin: 0 0.3rem 0 1rem; vertical-align: middle; }
+svg text { font-size: 12px; fill: #1f2328; }
+svg .axis { fill: #59636e; font-size: 11px; }
+"#;
+
+/// HTML report generator.
+pub struct HtmlReport {
+  content: String,
+}
+
+impl HtmlReport {
+  /// Create an HTML report from scenario results.
+  pub fn from_results(results: &[ScenarioResult]) -> Self {
+    Self::from_report(&BenchmarkReport::from_results(results), None)
+  }
+
+  /// Create an HTML report, including regression deltas when a comparison is given.
+  pub fn from_report(report: &BenchmarkReport, comparison: Option<&ComparisonReport>) -> Self {
+    let mut content = String::new();
+    let results = &report.scenarios;
+
+    let _ = writeln!(content, "<!DOCTYPE html>");
+    let _ = writeln!(content, "<html lang=\"en\">");
+    let _ = writeln!(content, "<head>");
+    let _ = writeln!(content, "<meta charset=\"utf-8\">");
+    let _ = writeln!(content, "<title>CCEngram Benchmark Report</title>");
+    let _ = writeln!(content, "<style>{}</style>", STYLE);
+    let _ = writeln!(content, "</head>");
+    let _ = writeln!(content, "<body>");
+
+    Self::write_header(&mut content, report);
+    Self::write_summary(&mut content, report);
+    Self::write_latency_section(&mut content, results);
+    Self::write_recall_section(&mut content, results);
+    if let Some(comparison) = comparison {
+      Self::write_regression_section(&mut content, comparison);
+    }
+    Self::write_scenario_table(&mut content, results);
+
+    let _ = writeln!(content, "</body>");
+    let _ = writeln!(content, "</html>");
+
+    Self { content }
+  }
+
+  /// The rendered HTML document.
+  pub fn content(&self) -> &str {
+    &self.content
+  }
+
+  fn write_header(out: &mut String, report: &BenchmarkReport) {
+    let _ = writeln!(out, "<h1>CCEngram Benchmark Report</h1>");
+    let mut meta = format!(
+      "Generated {} &middot; version {}",
+      report.metadata.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+      escape(&report.metadata.version)
+    );
+    if let Some(commit) = &report.metadata.git_commit {
+      let _ = write!(meta, " &middot; commit <code>{}</code>", escape(commit));
+    }
+    let _ = writeln!(out, "<p class=\"meta\">{}</p>", meta);
+  }
+
+  fn write_summary(out: &mut String, report: &BenchmarkReport) {
+    let summary = &report.summary;
+    let cards = [
+      (
+        format!("{:.1}%", summary.pass_rate * 100.0),
+        format!("Pass rate ({}/{})", summary.passed, summary.passed + summary.failed),
+      ),
+      (
+        format!("{:.0}%", summary.accuracy.avg_file_recall * 100.0),
+        "Avg file recall".to_string(),
+      ),
+      (
+        format!("{:.0}%", summary.accuracy.avg_symbol_recall * 100.0),
+        "Avg symbol recall".to_string(),
+      ),
+      (
+        format!("{:.0}ms", summary.performance.avg_search_latency_p50_ms),
+        "Avg search p50".to_string(),
+      ),
+      (
+        format!("{:.2}s", summary.total_time_ms as f64 / 1000.0),
+        "Total time".to_string(),
+      ),
+    ];
+
+    let _ = writeln!(out, "<h2>Summary</h2>");
+    let _ = writeln!(out, "<div class=\"cards\">");
+    for (value, label) in cards {
+      let _ = writeln!(
+        out,
+        "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>",
+        value, label
+      );
+    }
+    let _ = writeln!(out, "</div>");
+  }
+
+  fn write_latency_section(out: &mut String, results: &[ScenarioResult]) {
+    let _ = writeln!(out, "<h2>Latency Distributions</h2>");
+    let _ = writeln!(
+      out,
+      "<p class=\"legend\">Whiskers span min to max, boxes p50 to p95, the tick marks p99 and dots are individual steps.</p>"
+    );
+
+    let search: Vec<(&str, &LatencyStats, Vec<u64>)> = results
+      .iter()
+      .map(|r| {
+        let samples = r.steps.iter().map(|s| s.latency_ms).collect();
+        (r.scenario_id.as_str(), &r.performance.search_latency, samples)
+      })
+      .collect();
+    let _ = writeln!(out, "<h3>Search</h3>");
+    out.push_str(&latency_chart(&search));
+
+    let context: Vec<(&str, &LatencyStats, Vec<u64>)> = results
+      .iter()
+      .filter(|r| r.performance.context_latency.count > 0)
+      .map(|r| (r.scenario_id.as_str(), &r.performance.context_latency, Vec::new()))
+      .collect();
+    if !context.is_empty() {
+      let _ = writeln!(out, "<h3>Context</h3>");
+      out.push_str(&latency_chart(&context));
+    }
+  }
+
+  fn write_recall_section(out: &mut String, results: &[ScenarioResult]) {
+    let targets = MetricTargets::default();
+
+    let _ = writeln!(out, "<h2>Recall per Scenario</h2>");
+    let _ = writeln!(
+      out,
+      "<p class=\"legend\"><span style=\"background:{}\"></span>File recall<span style=\"background:{}\"></span>Symbol recall<span style=\"background:{}\"></span>MRR &middot; dashed ticks mark targets</p>",
+      BLUE, GREEN, GRAY
+    );
+    out.push_str(&recall_chart(results, &targets));
+  }
+
+  fn write_regression_section(out: &mut String, comparison: &ComparisonReport) {
+    let status = if comparison.summary.passes {
+      "<span class=\"pass\">PASS</span>"
+    } else {
+      "<span class=\"fail\">FAIL</span>"
+    };
+
+    let _ = writeln!(out, "<h2>Regression Deltas: {}</h2>", status);
+    let _ = writeln!(
+      out,
+      "<p class=\"meta\">Baseline {} &middot; {} regressed, {} improved, {} unchanged</p>",
+      escape(&comparison.baseline_timestamp),
+      comparison.summary.scenarios_regressed,
+      comparison.summary.scenarios_improved,
+      comparison.summary.scenarios_unchanged
+    );
+
+    if comparison.regressions.is_empty() && comparison.improvements.is_empty() {
+      let _ = writeln!(out, "<p>No changes beyond the threshold.</p>");
+      return;
+    }
+    let _ = writeln!(
+      out,
+      "<p class=\"legend\"><span style=\"background:{}\"></span>Regression<span style=\"background:{}\"></span>Improvement</p>",
+      RED, GREEN
+    );
+    out.push_str(&delta_chart(comparison));
+  }
+
+  fn write_scenario_table(out: &mut String, results: &[ScenarioResult]) {
+    let _ = writeln!(out, "<h2>Scenarios</h2>");
+    let _ = writeln!(out, "<table>");
+    let _ = writeln!(
+      out,
+      "<tr><th>Scenario</th><th>Status</th><th>File Recall</th><th>Symbol Recall</th><th>MRR</th><th>Noise</th><th>Search p50</th><th>Total</th></tr>"
+    );
+    for result in results {
+      let status = if result.passed {
+        "<span class=\"pass\">pass</span>"
+      } else {
+        "<span class=\"fail\">fail</span>"
+      };
+      let _ = writeln!(
+        out,
+        "<tr><td title=\"{}\">{}</td><td>{}</td><td>{:.0}%</td><td>{:.0}%</td><td>{:.2}</td><td>{:.0}%</td><td>{}ms</td><td>{:.2}s</td></tr>",
+        escape(&result.scenario_name),
+        escape(&result.scenario_id),
+        status,
+        result.accuracy.file_recall * 100.0,
+        result.accuracy.symbol_recall * 100.0,
+        result.accuracy.mrr,
+        result.accuracy.noise_ratio * 100.0,
+        result.performance.search_latency.p50_ms,
+        result.total_duration_ms as f64 / 1000.0
+      );
+    }
+    let _ = writeln!(out, "</table>");
+  }
+
+  /// Save to an HTML file.
+  pub async fn save(&self, path: &Path) -> Result<()> {
+    tokio::fs::write(path, &self.content).await?;
+    Ok(())
+  }
+}
+
+/// Horizontal box plot per row, scaled to the slowest sample
+fn latency_chart(rows: &[(&str, &LatencyStats, Vec<u64>)]) -> String {
+  let max = rows
+    .iter()
+    .flat_map(|(_, stats, samples)| samples.iter().copied().chain([stats.max_ms]))
+    .max()
+    .unwrap_or(0)
+    .max(1) as f64;
+  let x = |ms: u64| LABEL_WIDTH + ms as f64 / max * PLOT_WIDTH;
+  let mid = ROW_HEIGHT / 2.0;
+
+  let mut svg = open_svg(rows.len());
+  for (i, (label, stats, samples)) in rows.iter().enumerate() {
+    let y = i as f64 * ROW_HEIGHT;
+    let _ = writeln!(svg, "<g transform=\"translate(0,{})\">", y);
+    write_label(&mut svg, label);
+    let _ = writeln!(
+      svg,
+      "<line x1=\"{:.1}\" x2=\"{:.1}\" y1=\"{mid}\" y2=\"{mid}\" stroke=\"{GRAY}\"/>",
+      x(stats.min_ms),
+      x(stats.max_ms)
+    );
+    let _ = writeln!(
+      svg,
+      "<rect x=\"{:.1}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{BLUE}\" fill-opacity=\"0.35\" stroke=\"{BLUE}\"><title>p50 {}ms, p95 {}ms, p99 {}ms, mean {}ms</title></rect>",
+      x(stats.p50_ms),
+      mid - 7.0,
+      (x(stats.p95_ms) - x(stats.p50_ms)).max(1.0),
+      14.0,
+      stats.p50_ms,
+      stats.p95_ms,
+      stats.p99_ms,
+      stats.mean_ms
+    );
+    let _ = writeln!(
+      svg,
+      "<line x1=\"{0:.1}\" x2=\"{0:.1}\" y1=\"{1}\" y2=\"{2}\" stroke=\"{RED}\" stroke-width=\"2\"/>",
+      x(stats.p99_ms),
+      mid - 9.0,
+      mid + 9.0
+    );
+    for sample in samples {
+      let _ = writeln!(
+        svg,
+        "<circle cx=\"{:.1}\" cy=\"{mid}\" r=\"2.5\" fill=\"#1f2328\" fill-opacity=\"0.6\"/>",
+        x(*sample)
+      );
+    }
+    let _ = writeln!(svg, "</g>");
+  }
+  write_axis(&mut svg, rows.len(), "0ms", &format!("{}ms", max as u64));
+  svg.push_str("</svg>\n");
+  svg
+}
+
+/// Grouped horizontal bars of file recall, symbol recall and MRR per scenario
+fn recall_chart(results: &[ScenarioResult], targets: &MetricTargets) -> String {
+  let x = |ratio: f64| LABEL_WIDTH + ratio.clamp(0.0, 1.0) * PLOT_WIDTH;
+  let bar = (ROW_HEIGHT - 4.0) / 3.0;
+
+  let mut svg = open_svg(results.len());
+  for (i, result) in results.iter().enumerate() {
+    let y = i as f64 * ROW_HEIGHT;
+    let _ = writeln!(svg, "<g transform=\"translate(0,{})\">", y);
+    write_label(&mut svg, &result.scenario_id);
+
+    let metrics = [
+      ("File recall", result.accuracy.file_recall, targets.file_recall, BLUE),
+      (
+        "Symbol recall",
+        result.accuracy.symbol_recall,
+        targets.symbol_recall,
+        GREEN,
+      ),
+      ("MRR", result.accuracy.mrr, targets.mrr, GRAY),
+    ];
+    for (j, (name, value, target, color)) in metrics.into_iter().enumerate() {
+      let top = 2.0 + j as f64 * bar;
+      let _ = writeln!(
+        svg,
+        "<rect x=\"{LABEL_WIDTH}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{} {:.0}% (target {:.0}%)</title></rect>",
+        top,
+        x(value) - LABEL_WIDTH,
+        bar - 1.0,
+        color,
+        name,
+        value * 100.0,
+        target * 100.0
+      );
+      let _ = writeln!(
+        svg,
+        "<line x1=\"{0:.1}\" x2=\"{0:.1}\" y1=\"{1:.1}\" y2=\"{2:.1}\" stroke=\"#1f2328\" stroke-dasharray=\"2,1\"/>",
+        x(target),
+        top,
+        top + bar - 1.0
+      );
+    }
+    let _ = writeln!(svg, "</g>");
+  }
+  write_axis(&mut svg, results.len(), "0%", "100%");
+  svg.push_str("</svg>\n");
+  svg
+}
+
+/// Diverging bars of metric changes, centered on zero
+fn delta_chart(comparison: &ComparisonReport) -> String {
+  let deltas: Vec<_> = comparison.regressions.iter().chain(&comparison.improvements).collect();
+  let max = deltas.iter().map(|d| d.change_percent.abs()).fold(1.0_f64, f64::max);
+  let center = LABEL_WIDTH + PLOT_WIDTH / 2.0;
+  let half = PLOT_WIDTH / 2.0;
+
+  let mut svg = open_svg(deltas.len());
+  for (i, delta) in deltas.iter().enumerate() {
+    let y = i as f64 * ROW_HEIGHT;
+    let width = delta.change_percent.abs() / max * half;
+    let left = if delta.change_percent < 0.0 {
+      center - width
+    } else {
+      center
+    };
+    let color = if delta.is_degradation { RED } else { GREEN };
+
+    let _ = writeln!(svg, "<g transform=\"translate(0,{})\">", y);
+    write_label(&mut svg, &format!("{} · {}", delta.scenario_id, delta.metric));
+    let _ = writeln!(
+      svg,
+      "<rect x=\"{:.1}\" y=\"6\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"><title>{:.2} → {:.2}</title></rect>",
+      left,
+      width.max(1.0),
+      ROW_HEIGHT - 12.0,
+      color,
+      delta.baseline,
+      delta.current
+    );
+    let text_x = if delta.change_percent < 0.0 {
+      left - 4.0
+    } else {
+      left + width + 4.0
+    };
+    let anchor = if delta.change_percent < 0.0 { "end" } else { "start" };
+    let _ = writeln!(
+      svg,
+      "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"{}\">{:+.1}%</text>",
+      text_x,
+      ROW_HEIGHT / 2.0 + 4.0,
+      anchor,
+      delta.change_percent
+    );
+    let _ = writeln!(svg, "</g>");
+  }
+  let _ = writeln!(
+    svg,
+    "<line x1=\"{0}\" x2=\"{0}\" y1=\"0\" y2=\"{1}\" stroke=\"{GRAY}\"/>",
+    center,
+    deltas.len() as f64 * ROW_HEIGHT
+  );
+  write_axis(
+    &mut svg,
+    deltas.len(),
+    &format!("-{:.0}%", max),
+    &format!("+{:.0}%", max),
+  );
+  svg.push_str("</svg>\n");
+  svg
+}
+
+fn open_svg(rows: usize) -> String {
+  let width = LABEL_WIDTH + PLOT_WIDTH + 60.0;
+  let height = rows as f64 * ROW_HEIGHT + AXIS_HEIGHT;
+  format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" role=\"img\">\n",
+    width, height
+  )
+}
+
+fn write_label(svg: &mut String, label: &str) {
+  let _ = writeln!(
+    svg,
+    "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+    LABEL_WIDTH - 8.0,
+    ROW_HEIGHT / 2.0 + 4.0,
+    escape(label)
+  );
+}
+
+fn write_axis(svg: &mut String, rows: usize, start: &str, end: &str) {
+  let y = rows as f64 * ROW_HEIGHT;
+  let _ = writeln!(
+    svg,
+    "<line x1=\"{LABEL_WIDTH}\" x2=\"{}\" y1=\"{y}\" y2=\"{y}\" stroke=\"{GRAY}\"/>",
+    LABEL_WIDTH + PLOT_WIDTH
+  );
+  let _ = writeln!(
+    svg,
+    "<text class=\"axis\" x=\"{LABEL_WIDTH}\" y=\"{}\">{}</text>",
+    y + 16.0,
+    escape(start)
+  );
+  let _ = writeln!(
+    svg,
+    "<text class=\"axis\" x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+    LABEL_WIDTH + PLOT_WIDTH,
+    y + 16.0,
+    escape(end)
+  );
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' => out.push_str("&quot;"),
+      '\'' => out.push_str("&#39;"),
+      _ => out.push(c),
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+  use crate::metrics::{AccuracyMetrics, PerformanceMetrics};
+
+  fn sample_result(id: &str, file_recall: f64, p50_ms: u64) -> ScenarioResult {
+    ScenarioResult {
+      scenario_id: id.to_string(),
+      scenario_name: format!("Test <{}>", id),
+      passed: true,
+      performance: PerformanceMetrics {
+        search_latency: LatencyStats {
+          min_ms: 50,
+          max_ms: 200,
+          mean_ms: 100,
+          p50_ms,
+          p95_ms: 180,
+          p99_ms: 195,
+          count: 5,
+        },
+        context_latency: LatencyStats::default(),
+        total_time_ms: 500,
+        steps: vec![],
+        peak_memory_bytes: None,
+        avg_cpu_percent: None,
+      },
+      accuracy: AccuracyMetrics {
+        file_recall,
+        symbol_recall: 0.75,
+        steps_to_core: Some(2),
+        mrr: 0.6,
+        noise_ratio: 0.15,
+        top3_noise: 0.0,
+        hint_utility: 0.7,
+        suggestion_quality: 0.5,
+        convergence_rate: 0.85,
+        avg_info_gain: 0.4,
+        context_bloat: 0.1,
+        navigation_efficiency: 0.7,
+        dead_end_ratio: 0.1,
+        context_budget_efficiency: 0.8,
+        total_bytes_returned: 10000,
+        useful_bytes_returned: 8000,
+        max_consecutive_failures: 1,
+        rabbit_hole_steps: 0,
+        rabbit_hole_ratio: 0.0,
+        time_to_first_relevant_ms: Some(150),
+        avg_file_diversity_top5: 0.8,
+        diagnostics: None,
+        files_found: vec![],
+        files_missed: vec![],
+        symbols_found: vec![],
+        symbols_missed: vec![],
+      },
+      steps: vec![],
+      errors: vec![],
+      total_duration_ms: 500,
+      comprehension: None,
+      task_requirements_result: None,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_save_html() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("report.html");
+
+    let results = vec![sample_result("test-1", 0.8, 90), sample_result("test-2", 0.5, 120)];
+    HtmlReport::from_results(&results).save(&path).await.unwrap();
+
+    let content = tokio::fs::read_to_string(&path).await.unwrap();
+    assert!(content.starts_with("<!DOCTYPE html>"), "should be a full document");
+    assert_eq!(
+      content.matches("<svg").count(),
+      2,
+      "latency and recall charts should be embedded"
+    );
+    assert!(
+      !content.contains("<script") && !content.contains("src=\"http"),
+      "report should not load external resources"
+    );
+    assert!(
+      content.contains("Test &lt;test-1&gt;"),
+      "scenario names should be escaped"
+    );
+  }
+
+  #[test]
+  fn test_html_includes_regression_deltas() {
+    let baseline = BenchmarkReport::from_results(&[sample_result("test-1", 0.8, 90)]);
+    let current = BenchmarkReport::from_results(&[sample_result("test-1", 0.6, 90)]);
+    let comparison = ComparisonReport::compare(&baseline, &current, 10.0);
+
+    let report = HtmlReport::from_report(&current, Some(&comparison));
+    assert!(
+      report.content().contains("Regression Deltas"),
+      "comparison section should be rendered"
+    );
+    assert!(
+      report.content().contains("test-1 · file_recall"),
+      "each changed metric should get a bar"
+    );
+    assert!(report.content().contains("-25.0%"), "change should be labelled");
+  }
+}
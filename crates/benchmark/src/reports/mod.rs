@@ -2,25 +2,32 @@
 //!
 //! - JSON: Machine-readable format for CI and comparison
 //! - Markdown: Human-readable summary
+//! - HTML: Self-contained page with charts, for sharing outside the terminal
 //! - Comparison: Regression detection between runs
 
 mod comparison;
+mod html;
 mod json;
 mod markdown;
 
 use std::path::Path;
 
 pub use comparison::ComparisonReport;
+pub use html::HtmlReport;
 pub use json::BenchmarkReport;
 pub use markdown::MarkdownReport;
 
 use crate::scenarios::ScenarioResult;
 
 /// Generate all report formats for benchmark results.
+///
+/// When a baseline report is given, the HTML report includes regression deltas
+/// against it using `threshold_percent`.
 pub async fn generate_reports(
   results: &[ScenarioResult],
   output_dir: &Path,
   run_name: Option<&str>,
+  baseline: Option<(&BenchmarkReport, f64)>,
 ) -> crate::Result<()> {
   tokio::fs::create_dir_all(output_dir).await?;
 
@@ -36,5 +43,11 @@ pub async fn generate_reports(
   let md_report = MarkdownReport::from_results(results);
   md_report.save(&md_path).await?;
 
+  // Generate HTML report
+  let html_path = output_dir.join(format!("{}.html", run_name));
+  let comparison = baseline.map(|(baseline, threshold)| ComparisonReport::compare(baseline, &report, threshold));
+  let html_report = HtmlReport::from_report(&report, comparison.as_ref());
+  html_report.save(&html_path).await?;
+
   Ok(())
 }
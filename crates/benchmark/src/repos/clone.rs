@@ -1,6 +1,6 @@
 //! Repository downloading and cache management.
 
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use flate2::read::GzDecoder;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -26,51 +26,52 @@ impl RepoCache {
   }
 
   /// Get the path where a repo would be cached.
-  pub fn repo_path(&self, repo: TargetRepo) -> PathBuf {
-    let config = RepoRegistry::get(repo);
-    self.cache_dir.join(config.extracted_dir_name())
+  pub fn repo_path(&self, repo: &TargetRepo) -> Result<PathBuf> {
+    let config = RepoRegistry::global().get(repo)?;
+    Ok(self.cache_dir.join(config.extracted_dir_name()))
   }
 
   /// Check if a repo is already cached.
-  pub fn is_cached(&self, repo: TargetRepo) -> bool {
-    let path = self.repo_path(repo);
-    path.exists() && path.is_dir()
+  pub fn is_cached(&self, repo: &TargetRepo) -> bool {
+    self.repo_path(repo).is_ok_and(|path| path.is_dir())
   }
 
   /// Ensure a repo is available (download if needed).
-  pub async fn ensure_repo(&self, repo: TargetRepo) -> Result<PathBuf> {
-    let path = self.repo_path(repo);
+  pub async fn ensure_repo(&self, repo: &TargetRepo) -> Result<PathBuf> {
+    let path = self.repo_path(repo)?;
     if self.is_cached(repo) {
       info!("Using cached repository: {}", path.display());
-      return Ok(path);
+    } else {
+      info!("Downloading repository: {}", repo);
+      self.download_repo(repo).await?;
     }
 
-    info!("Downloading repository: {}", repo);
-    self.download_repo(repo).await?;
+    let config = RepoRegistry::global().get(repo)?;
+    write_ignore_file(&path, &config.ignore).await?;
     Ok(path)
   }
 
   /// Download a repository tarball and extract it.
-  async fn download_repo(&self, repo: TargetRepo) -> Result<()> {
-    let config = RepoRegistry::get(repo);
+  async fn download_repo(&self, repo: &TargetRepo) -> Result<()> {
+    let config = RepoRegistry::global().get(repo)?;
 
     // Ensure cache directory exists
     fs::create_dir_all(&self.cache_dir).await?;
 
-    let tarball_path = self.cache_dir.join(format!("{}.tar.gz", config.name));
+    let tarball_path = self.cache_dir.join(format!("{}.tar.gz", repo.name()));
 
     // Download the tarball
-    self.download_tarball(&config, &tarball_path).await?;
+    self.download_tarball(config, &tarball_path).await?;
 
     // Extract the tarball
-    self.extract_tarball(&tarball_path, &self.cache_dir)?;
+    self.extract_tarball(&tarball_path, &self.cache_dir.join(config.extracted_dir_name()))?;
 
     // Clean up tarball
     if let Err(e) = fs::remove_file(&tarball_path).await {
       warn!("Failed to remove tarball: {}", e);
     }
 
-    info!("Repository extracted to: {}", self.repo_path(repo).display());
+    info!("Repository extracted to: {}", self.repo_path(repo)?.display());
     Ok(())
   }
 
@@ -117,7 +118,7 @@ impl RepoCache {
     Ok(())
   }
 
-  /// Extract a tarball to a directory.
+  /// Extract a tarball to a directory, dropping the archive's top-level directory.
   fn extract_tarball(&self, tarball: &Path, dest: &Path) -> Result<()> {
     info!("Extracting {} to {}", tarball.display(), dest.display());
 
@@ -135,7 +136,13 @@ impl RepoCache {
     let mut count = 0;
     for entry in archive.entries()? {
       let mut entry = entry?;
-      entry.unpack_in(dest)?;
+      let Some(target) = strip_top_level(&entry.path()?).map(|p| dest.join(p)) else {
+        continue;
+      };
+      if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+      }
+      entry.unpack(&target)?;
       count += 1;
       if count % 100 == 0 {
         pb.set_message(format!("{} files", count));
@@ -147,8 +154,8 @@ impl RepoCache {
   }
 
   /// Remove a cached repository.
-  pub async fn remove(&self, repo: TargetRepo) -> Result<()> {
-    let path = self.repo_path(repo);
+  pub async fn remove(&self, repo: &TargetRepo) -> Result<()> {
+    let path = self.repo_path(repo)?;
     if path.exists() {
       fs::remove_dir_all(&path).await?;
       info!("Removed cached repository: {}", path.display());
@@ -165,3 +172,46 @@ impl RepoCache {
     Ok(())
   }
 }
+
+/// Archive path without its top-level directory, or `None` for the top level
+/// itself, archive metadata and paths escaping the destination.
+fn strip_top_level(path: &Path) -> Option<PathBuf> {
+  let stripped: PathBuf = path.components().skip(1).collect();
+  let safe = stripped.components().all(|c| matches!(c, Component::Normal(_)));
+  (safe && !stripped.as_os_str().is_empty()).then_some(stripped)
+}
+
+/// Write the repo's ignore patterns to `.ccengramignore` so indexing skips them.
+async fn write_ignore_file(repo_path: &Path, patterns: &[String]) -> Result<()> {
+  if patterns.is_empty() {
+    return Ok(());
+  }
+  let mut content = patterns.join("\n");
+  content.push('\n');
+  fs::write(repo_path.join(".ccengramignore"), content).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_strip_top_level() {
+    assert_eq!(
+      strip_top_level(Path::new("zed-0.220.3/crates/gpui/src/lib.rs")),
+      Some(PathBuf::from("crates/gpui/src/lib.rs"))
+    );
+    assert_eq!(
+      strip_top_level(Path::new("zed-0.220.3/")),
+      None,
+      "top level should be skipped"
+    );
+    assert_eq!(strip_top_level(Path::new("pax_global_header")), None);
+    assert_eq!(
+      strip_top_level(Path::new("repo/../../etc/passwd")),
+      None,
+      "paths escaping the destination should be skipped"
+    );
+  }
+}
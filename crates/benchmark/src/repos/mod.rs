@@ -1,7 +1,7 @@
 //! Repository management for benchmark targets.
 //!
 //! Handles downloading, caching, and managing target repositories
-//! (Zed, VSCode) for benchmarking.
+//! (Zed, VSCode and any defined in a registry file) for benchmarking.
 
 mod clone;
 mod registry;
@@ -22,7 +22,7 @@ pub fn default_cache_dir() -> PathBuf {
 }
 
 /// Prepare a repository for benchmarking (download if needed, return path).
-pub async fn prepare_repo(repo: TargetRepo, cache_dir: Option<PathBuf>) -> Result<PathBuf> {
+pub async fn prepare_repo(repo: &TargetRepo, cache_dir: Option<PathBuf>) -> Result<PathBuf> {
   let cache_dir = cache_dir.unwrap_or_else(default_cache_dir);
   let cache = RepoCache::new(cache_dir);
   cache.ensure_repo(repo).await
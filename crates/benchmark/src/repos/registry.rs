@@ -1,39 +1,78 @@
-//! Repository registry with predefined configurations.
+//! Repository registry with predefined and user-defined configurations.
+//!
+//! Zed and VSCode are built in. Additional repositories can be defined in a
+//! TOML registry file, which is loaded once at startup and installed as the
+//! global registry:
+//!
+//! ```toml
+//! [[repo]]
+//! name = "monorepo"
+//! url = "https://github.com/acme/monorepo"
+//! tag = "v2.3.0"
+//! language = "TypeScript"
+//! docs_dir = "docs"
+//! ignore = ["node_modules/", "dist/", "**/*.generated.ts"]
+//! ```
+
+use std::{
+  path::{Path, PathBuf},
+  sync::OnceLock,
+};
 
 use serde::{Deserialize, Serialize};
 
+use crate::{BenchmarkError, Result};
+
+/// Registry installed at startup, see [`RepoRegistry::install`]
+static REGISTRY: OnceLock<RepoRegistry> = OnceLock::new();
+
 /// Target repository for benchmarking.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum TargetRepo {
   Zed,
   Vscode,
+  /// Repository defined in a registry file
+  Custom(String),
 }
 
 impl TargetRepo {
-  /// Get all available target repositories.
-  pub fn all() -> &'static [TargetRepo] {
-    &[TargetRepo::Zed, TargetRepo::Vscode]
+  /// Get all repositories in the global registry.
+  pub fn all() -> Vec<TargetRepo> {
+    RepoRegistry::global().targets()
   }
 
   /// Get the repository name.
-  pub fn name(&self) -> &'static str {
+  pub fn name(&self) -> &str {
     match self {
       TargetRepo::Zed => "zed",
       TargetRepo::Vscode => "vscode",
+      TargetRepo::Custom(name) => name,
     }
   }
 
-  /// Parse from string.
+  /// Parse from string, returning `None` for repositories not in the global registry.
   pub fn from_name(name: &str) -> Option<Self> {
-    match name.to_lowercase().as_str() {
-      "zed" => Some(TargetRepo::Zed),
-      "vscode" => Some(TargetRepo::Vscode),
-      _ => None,
+    RepoRegistry::global().resolve(name)
+  }
+}
+
+impl From<String> for TargetRepo {
+  fn from(name: String) -> Self {
+    match name.trim().to_lowercase().as_str() {
+      "zed" => TargetRepo::Zed,
+      "vscode" => TargetRepo::Vscode,
+      other => TargetRepo::Custom(other.to_string()),
     }
   }
 }
 
+impl From<TargetRepo> for String {
+  fn from(repo: TargetRepo) -> Self {
+    repo.name().to_string()
+  }
+}
+
 impl std::fmt::Display for TargetRepo {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(f, "{}", self.name())
@@ -52,66 +91,168 @@ impl std::str::FromStr for TargetRepo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoConfig {
   /// Repository identifier
+  #[serde(rename = "name")]
   pub repo: TargetRepo,
-  /// GitHub organization/user
-  pub owner: String,
-  /// Repository name on GitHub
-  pub name: String,
+  /// Repository URL (GitHub), or a tarball URL where `{tag}` is replaced by the release tag
+  pub url: String,
   /// Release tag for tarball download
+  #[serde(rename = "tag")]
   pub release_tag: String,
   /// Primary programming language
+  #[serde(default)]
   pub language: String,
   /// Approximate lines of code
+  #[serde(default)]
   pub approx_loc: String,
   /// Documentation directory (if any)
+  #[serde(default)]
   pub docs_dir: Option<String>,
-  /// Directories to exclude from indexing
-  pub exclude_dirs: Vec<String>,
+  /// Gitignore-style patterns excluded from indexing
+  #[serde(default)]
+  pub ignore: Vec<String>,
 }
 
 impl RepoConfig {
   /// Get the tarball URL for downloading.
   pub fn tarball_url(&self) -> String {
-    format!(
-      "https://github.com/{}/{}/archive/refs/tags/{}.tar.gz",
-      self.owner, self.name, self.release_tag
-    )
+    let url = self.url.trim_end_matches('/');
+    if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
+      url.replace("{tag}", &self.release_tag)
+    } else {
+      format!(
+        "{}/archive/refs/tags/{}.tar.gz",
+        url.trim_end_matches(".git"),
+        self.release_tag
+      )
+    }
   }
 
-  /// Get the expected directory name after extraction.
+  /// Get the directory name the repository is extracted to.
   pub fn extracted_dir_name(&self) -> String {
-    // GitHub tarballs extract to repo-tag format
+    // Matches the repo-tag format of GitHub tarballs
     let tag = self.release_tag.trim_start_matches('v');
-    format!("{}-{}", self.name, tag)
+    format!("{}-{}", self.repo.name(), tag)
   }
+
+  fn validate(&self) -> Result<()> {
+    let name = self.repo.name();
+    if name.is_empty() || name == "all" || name.contains(['/', '\\', ',']) {
+      return Err(BenchmarkError::Repo(format!("Invalid repository name: '{}'", name)));
+    }
+    if self.url.trim().is_empty() {
+      return Err(BenchmarkError::Repo(format!("Repository {} has no url", name)));
+    }
+    if self.release_tag.trim().is_empty() {
+      return Err(BenchmarkError::Repo(format!("Repository {} has no tag", name)));
+    }
+    Ok(())
+  }
+}
+
+/// Registry file format
+#[derive(Debug, Deserialize)]
+struct RegistryFile {
+  #[serde(default)]
+  repo: Vec<RepoConfig>,
 }
 
 /// Registry of all benchmark target repositories.
-pub struct RepoRegistry;
+#[derive(Debug, Clone)]
+pub struct RepoRegistry {
+  repos: Vec<RepoConfig>,
+}
 
 impl RepoRegistry {
-  /// Get configuration for a specific repository.
-  pub fn get(repo: TargetRepo) -> RepoConfig {
-    match repo {
-      TargetRepo::Zed => Self::zed_config(),
-      TargetRepo::Vscode => Self::vscode_config(),
+  /// Registry with only the built-in repositories.
+  pub fn builtin() -> Self {
+    Self {
+      repos: vec![Self::zed_config(), Self::vscode_config()],
     }
   }
 
+  /// Default registry file location (`~/.config/ccengram-bench/repos.toml`).
+  pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("ccengram-bench").join("repos.toml"))
+  }
+
+  /// Load the built-in repositories plus those defined in a registry file.
+  ///
+  /// Without an explicit path, the default location is used if it exists.
+  pub async fn load(path: Option<&Path>) -> Result<Self> {
+    let mut registry = Self::builtin();
+    let path = match path {
+      Some(path) => path.to_path_buf(),
+      None => match Self::default_path().filter(|p| p.exists()) {
+        Some(path) => path,
+        None => return Ok(registry),
+      },
+    };
+
+    let content = tokio::fs::read_to_string(&path)
+      .await
+      .map_err(|e| BenchmarkError::Repo(format!("Failed to read registry {}: {}", path.display(), e)))?;
+    registry.extend_from_toml(&content)?;
+    Ok(registry)
+  }
+
+  /// Add the repositories defined in registry TOML, replacing any with the same name.
+  pub fn extend_from_toml(&mut self, content: &str) -> Result<()> {
+    let file: RegistryFile = toml::from_str(content)?;
+    for config in file.repo {
+      config.validate()?;
+      match self.repos.iter_mut().find(|c| c.repo == config.repo) {
+        Some(existing) => *existing = config,
+        None => self.repos.push(config),
+      }
+    }
+    Ok(())
+  }
+
+  /// Install this registry as the global registry.
+  pub fn install(self) -> Result<()> {
+    REGISTRY
+      .set(self)
+      .map_err(|_| BenchmarkError::Repo("Repository registry already installed".to_string()))
+  }
+
+  /// The installed registry, or the built-in one if none was installed.
+  pub fn global() -> &'static RepoRegistry {
+    REGISTRY.get_or_init(Self::builtin)
+  }
+
+  /// Get configuration for a specific repository.
+  pub fn get(&self, repo: &TargetRepo) -> Result<&RepoConfig> {
+    self
+      .repos
+      .iter()
+      .find(|c| c.repo == *repo)
+      .ok_or_else(|| BenchmarkError::Repo(format!("Unknown repository: {}", repo)))
+  }
+
+  /// Resolve a repository name against this registry.
+  pub fn resolve(&self, name: &str) -> Option<TargetRepo> {
+    let repo = TargetRepo::from(name.to_string());
+    self.repos.iter().any(|c| c.repo == repo).then_some(repo)
+  }
+
+  /// All repositories in this registry.
+  pub fn targets(&self) -> Vec<TargetRepo> {
+    self.repos.iter().map(|c| c.repo.clone()).collect()
+  }
+
   fn zed_config() -> RepoConfig {
     RepoConfig {
       repo: TargetRepo::Zed,
-      owner: "zed-industries".to_string(),
-      name: "zed".to_string(),
+      url: "https://github.com/zed-industries/zed".to_string(),
       release_tag: "v0.220.3".to_string(),
       language: "Rust".to_string(),
       approx_loc: "~1M".to_string(),
       docs_dir: Some("docs".to_string()),
-      exclude_dirs: vec![
-        "target".to_string(),
-        ".git".to_string(),
-        "node_modules".to_string(),
-        "assets".to_string(),
+      ignore: vec![
+        "target/".to_string(),
+        ".git/".to_string(),
+        "node_modules/".to_string(),
+        "assets/".to_string(),
       ],
     }
   }
@@ -119,18 +260,17 @@ impl RepoRegistry {
   fn vscode_config() -> RepoConfig {
     RepoConfig {
       repo: TargetRepo::Vscode,
-      owner: "microsoft".to_string(),
-      name: "vscode".to_string(),
+      url: "https://github.com/microsoft/vscode".to_string(),
       release_tag: "1.108.1".to_string(),
       language: "TypeScript".to_string(),
       approx_loc: "~1M".to_string(),
       docs_dir: Some("docs".to_string()),
-      exclude_dirs: vec![
-        "node_modules".to_string(),
-        ".git".to_string(),
-        "out".to_string(),
-        "out-build".to_string(),
-        ".build".to_string(),
+      ignore: vec![
+        "node_modules/".to_string(),
+        ".git/".to_string(),
+        "out/".to_string(),
+        "out-build/".to_string(),
+        ".build/".to_string(),
       ],
     }
   }
@@ -156,17 +296,77 @@ mod tests {
 
   #[test]
   fn test_repo_config_tarball_url() {
-    let config = RepoRegistry::get(TargetRepo::Zed);
+    let registry = RepoRegistry::builtin();
+    let config = registry.get(&TargetRepo::Zed).unwrap();
     assert!(config.tarball_url().contains("zed-industries/zed"));
     assert!(config.tarball_url().contains("v0.220.3"));
   }
 
   #[test]
   fn test_repo_config_extracted_dir() {
-    let config = RepoRegistry::get(TargetRepo::Zed);
+    let registry = RepoRegistry::builtin();
+    let config = registry.get(&TargetRepo::Zed).unwrap();
     assert_eq!(config.extracted_dir_name(), "zed-0.220.3");
 
-    let config = RepoRegistry::get(TargetRepo::Vscode);
+    let config = registry.get(&TargetRepo::Vscode).unwrap();
     assert_eq!(config.extracted_dir_name(), "vscode-1.108.1");
   }
+
+  #[test]
+  fn test_registry_loads_custom_repos() {
+    let mut registry = RepoRegistry::builtin();
+    registry
+      .extend_from_toml(
+        r#"
+[[repo]]
+name = "Monorepo"
+url = "https://git.example.com/acme/monorepo-{tag}.tar.gz"
+tag = "v2.3.0"
+docs_dir = "handbook"
+ignore = ["dist/", "**/*.generated.ts"]
+
+[[repo]]
+name = "zed"
+url = "https://github.com/zed-industries/zed"
+tag = "v0.221.0"
+"#,
+      )
+      .unwrap();
+
+    let repo = registry.resolve("monorepo").expect("custom repo should resolve");
+    assert_eq!(repo, TargetRepo::Custom("monorepo".to_string()));
+
+    let config = registry.get(&repo).unwrap();
+    assert_eq!(
+      config.tarball_url(),
+      "https://git.example.com/acme/monorepo-v2.3.0.tar.gz",
+      "tarball URLs should have the tag substituted"
+    );
+    assert_eq!(config.extracted_dir_name(), "monorepo-2.3.0");
+    assert_eq!(config.ignore.len(), 2);
+
+    assert_eq!(
+      registry.get(&TargetRepo::Zed).unwrap().release_tag,
+      "v0.221.0",
+      "registry entries should override built-in repos"
+    );
+    assert_eq!(registry.targets().len(), 3, "overrides should not add entries");
+  }
+
+  #[test]
+  fn test_registry_rejects_invalid_entries() {
+    let mut registry = RepoRegistry::builtin();
+    let result = registry.extend_from_toml(
+      r#"
+[[repo]]
+name = "all"
+url = "https://github.com/acme/all"
+tag = "v1"
+"#,
+    );
+    assert!(result.is_err(), "reserved names should be rejected");
+
+    let result = registry.extend_from_toml("[[repo]]\nname = \"missing-url\"\ntag = \"v1\"\n");
+    assert!(result.is_err(), "entries without a url should be rejected");
+  }
 }
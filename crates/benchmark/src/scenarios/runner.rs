@@ -391,6 +391,8 @@ impl ScenarioRunner {
         expand_top: Some(expand_top),
         limit: Some(10),
         depth: None,
+        include_generated: None,
+        record: None,
       })
       .await?;
     let latency = start.elapsed();
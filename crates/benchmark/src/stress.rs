@@ -0,0 +1,633 @@
+//! Concurrent search stress benchmarking.
+//!
+//! Fires a mixed memory/code/docs search workload from many concurrent
+//! clients, first against an idle daemon and then while a forced re-index of
+//! the same project runs. Comparing the two phases shows whether searches keep
+//! their latency and succeed while indexing competes for the daemon.
+
+use std::{
+  path::PathBuf,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use ccengram::ipc::{
+  Client,
+  code::{CodeIndexParams, CodeSearchParams},
+  docs::DocsSearchParams,
+  memory::MemorySearchParams,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::{
+  Result,
+  metrics::performance::LatencyStats,
+  repos::{TargetRepo, prepare_repo},
+};
+
+/// Queries cycled through by stress workers.
+const STRESS_QUERIES: &[&str] = &[
+  "error handling",
+  "configuration loading",
+  "parse command line arguments",
+  "network request retry",
+  "file system watcher",
+  "render text buffer",
+  "authentication token",
+  "cache invalidation",
+  "serialize to json",
+  "keyboard shortcut handling",
+];
+
+/// Kind of search request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+  Memory,
+  Code,
+  Docs,
+}
+
+impl SearchKind {
+  const ALL: [SearchKind; 3] = [SearchKind::Memory, SearchKind::Code, SearchKind::Docs];
+}
+
+impl std::fmt::Display for SearchKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SearchKind::Memory => write!(f, "memory"),
+      SearchKind::Code => write!(f, "code"),
+      SearchKind::Docs => write!(f, "docs"),
+    }
+  }
+}
+
+/// Relative weights of each search kind in the workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchMix {
+  pub memory: u32,
+  pub code: u32,
+  pub docs: u32,
+}
+
+impl Default for SearchMix {
+  fn default() -> Self {
+    Self {
+      memory: 1,
+      code: 2,
+      docs: 1,
+    }
+  }
+}
+
+impl SearchMix {
+  /// Parse a mix like `memory=1,code=2,docs=1`. Omitted kinds get weight 0.
+  pub fn parse(s: &str) -> Option<Self> {
+    let mut mix = Self {
+      memory: 0,
+      code: 0,
+      docs: 0,
+    };
+
+    for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+      let (kind, weight) = part.split_once('=')?;
+      let weight: u32 = weight.trim().parse().ok()?;
+      match kind.trim() {
+        "memory" => mix.memory = weight,
+        "code" => mix.code = weight,
+        "docs" => mix.docs = weight,
+        _ => return None,
+      }
+    }
+
+    (mix.total() > 0).then_some(mix)
+  }
+
+  fn total(&self) -> u32 {
+    self.memory + self.code + self.docs
+  }
+
+  fn weight(&self, kind: SearchKind) -> u32 {
+    match kind {
+      SearchKind::Memory => self.memory,
+      SearchKind::Code => self.code,
+      SearchKind::Docs => self.docs,
+    }
+  }
+
+  /// Deterministic weighted pick for the n-th request.
+  pub fn kind_for(&self, n: usize) -> SearchKind {
+    let mut slot = (n % self.total().max(1) as usize) as u32;
+    for kind in SearchKind::ALL {
+      let weight = self.weight(kind);
+      if slot < weight {
+        return kind;
+      }
+      slot -= weight;
+    }
+    SearchKind::Code
+  }
+}
+
+impl std::fmt::Display for SearchMix {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "memory={},code={},docs={}", self.memory, self.code, self.docs)
+  }
+}
+
+/// Configuration for search stress benchmarks.
+#[derive(Debug, Clone)]
+pub struct StressBenchConfig {
+  /// Number of concurrent search workers
+  pub concurrency: usize,
+  /// How long each phase fires searches
+  pub duration: Duration,
+  /// Workload mix
+  pub mix: SearchMix,
+  /// Result limit per search
+  pub limit: usize,
+  /// Skip the idle baseline phase
+  pub skip_idle: bool,
+  /// Maximum p95 latency under indexing load in ms
+  pub target_p95_ms: u64,
+  /// Maximum error rate under indexing load (0.0-1.0)
+  pub max_error_rate: f64,
+}
+
+impl Default for StressBenchConfig {
+  fn default() -> Self {
+    Self {
+      concurrency: 8,
+      duration: Duration::from_secs(30),
+      mix: SearchMix::default(),
+      limit: 10,
+      skip_idle: false,
+      target_p95_ms: 1000,
+      max_error_rate: 0.01,
+    }
+  }
+}
+
+/// One timed search request.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+  kind: SearchKind,
+  duration: Duration,
+  ok: bool,
+}
+
+/// Latency and errors for one search kind within a phase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KindResult {
+  pub kind: SearchKind,
+  pub requests: usize,
+  pub errors: usize,
+  pub latency: LatencyStats,
+}
+
+/// Results of one workload phase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseResult {
+  /// Phase name ("idle" or "under_index")
+  pub phase: String,
+  /// Wall time of the phase in milliseconds
+  pub duration_ms: u64,
+  /// Total requests sent
+  pub requests: usize,
+  /// Requests that returned an error
+  pub errors: usize,
+  /// Error rate (0.0-1.0)
+  pub error_rate: f64,
+  /// Requests per second across all workers
+  pub throughput_rps: f64,
+  /// Latency over all successful requests
+  pub latency: LatencyStats,
+  /// Breakdown per search kind
+  pub by_kind: Vec<KindResult>,
+}
+
+impl PhaseResult {
+  fn from_samples(phase: &str, samples: &[Sample], elapsed: Duration) -> Self {
+    let latencies = |kind: Option<SearchKind>| -> Vec<Duration> {
+      samples
+        .iter()
+        .filter(|s| s.ok && kind.is_none_or(|k| s.kind == k))
+        .map(|s| s.duration)
+        .collect()
+    };
+
+    let requests = samples.len();
+    let errors = samples.iter().filter(|s| !s.ok).count();
+
+    let by_kind = SearchKind::ALL
+      .into_iter()
+      .filter_map(|kind| {
+        let requests = samples.iter().filter(|s| s.kind == kind).count();
+        (requests > 0).then(|| KindResult {
+          kind,
+          requests,
+          errors: samples.iter().filter(|s| s.kind == kind && !s.ok).count(),
+          latency: LatencyStats::from_durations(&latencies(Some(kind))),
+        })
+      })
+      .collect();
+
+    let secs = elapsed.as_secs_f64();
+    Self {
+      phase: phase.to_string(),
+      duration_ms: elapsed.as_millis() as u64,
+      requests,
+      errors,
+      error_rate: if requests == 0 {
+        0.0
+      } else {
+        errors as f64 / requests as f64
+      },
+      throughput_rps: if secs > 0.0 { requests as f64 / secs } else { 0.0 },
+      latency: LatencyStats::from_durations(&latencies(None)),
+      by_kind,
+    }
+  }
+}
+
+/// Summary of the stress run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressSummary {
+  /// p95 under indexing divided by idle p95 (None without an idle phase)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub p95_slowdown: Option<f64>,
+  /// Whether p95 latency and error rate under load met their targets
+  pub passes: bool,
+}
+
+/// Search stress benchmark report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressReport {
+  pub timestamp: String,
+  pub version: String,
+  pub repo: String,
+  pub concurrency: usize,
+  pub mix: String,
+  pub target_p95_ms: u64,
+  pub max_error_rate: f64,
+  /// Baseline phase with no indexing
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub idle: Option<PhaseResult>,
+  /// Phase run while a forced re-index is in progress
+  pub under_index: PhaseResult,
+  /// How long the re-index took, if it finished
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub index_duration_ms: Option<u64>,
+  pub summary: StressSummary,
+}
+
+impl StressReport {
+  fn compute_summary(
+    idle: Option<&PhaseResult>,
+    under_index: &PhaseResult,
+    target_p95_ms: u64,
+    max_error_rate: f64,
+  ) -> StressSummary {
+    let p95_slowdown = idle
+      .filter(|idle| idle.latency.p95_ms > 0)
+      .map(|idle| under_index.latency.p95_ms as f64 / idle.latency.p95_ms as f64);
+
+    StressSummary {
+      p95_slowdown,
+      passes: under_index.requests > 0
+        && under_index.latency.p95_ms <= target_p95_ms
+        && under_index.error_rate <= max_error_rate,
+    }
+  }
+
+  /// Generate markdown report.
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::new();
+
+    out.push_str("# Search Stress Report\n\n");
+    out.push_str(&format!("**Timestamp:** {}\n", self.timestamp));
+    out.push_str(&format!("**Version:** {}\n", self.version));
+    out.push_str(&format!("**Repository:** {}\n", self.repo));
+    out.push_str(&format!("**Concurrency:** {} workers\n", self.concurrency));
+    out.push_str(&format!("**Mix:** {}\n\n", self.mix));
+
+    out.push_str("## Summary\n\n");
+    let status = if self.summary.passes { "PASS" } else { "FAIL" };
+    out.push_str(&format!("**Status:** {}\n\n", status));
+    out.push_str(&format!(
+      "- p95 under indexing: {} ms (target ≤ {} ms)\n",
+      self.under_index.latency.p95_ms, self.target_p95_ms
+    ));
+    out.push_str(&format!(
+      "- Error rate under indexing: {:.2}% (target ≤ {:.2}%)\n",
+      self.under_index.error_rate * 100.0,
+      self.max_error_rate * 100.0
+    ));
+    if let Some(slowdown) = self.summary.p95_slowdown {
+      out.push_str(&format!("- p95 slowdown vs idle: {:.2}x\n", slowdown));
+    }
+    match self.index_duration_ms {
+      Some(ms) => out.push_str(&format!("- Re-index duration: {} ms\n\n", ms)),
+      None => out.push_str("- Re-index duration: did not finish\n\n"),
+    }
+
+    out.push_str("## Phases\n\n");
+    out.push_str("| Phase | Kind | Requests | Errors | p50 (ms) | p95 (ms) | p99 (ms) | Max (ms) |\n");
+    out.push_str("|-------|------|----------|--------|----------|----------|----------|----------|\n");
+    for phase in self.idle.iter().chain(std::iter::once(&self.under_index)) {
+      out.push_str(&format!(
+        "| {} | all | {} | {} | {} | {} | {} | {} |\n",
+        phase.phase,
+        phase.requests,
+        phase.errors,
+        phase.latency.p50_ms,
+        phase.latency.p95_ms,
+        phase.latency.p99_ms,
+        phase.latency.max_ms
+      ));
+      for kind in &phase.by_kind {
+        out.push_str(&format!(
+          "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+          phase.phase,
+          kind.kind,
+          kind.requests,
+          kind.errors,
+          kind.latency.p50_ms,
+          kind.latency.p95_ms,
+          kind.latency.p99_ms,
+          kind.latency.max_ms
+        ));
+      }
+    }
+    out.push('\n');
+
+    out
+  }
+
+  /// Save report to files (JSON and Markdown).
+  pub async fn save(&self, output_dir: &PathBuf) -> Result<()> {
+    tokio::fs::create_dir_all(output_dir).await?;
+
+    // Save JSON
+    let json_path = output_dir.join("stress.json");
+    let json = serde_json::to_string_pretty(self)?;
+    tokio::fs::write(&json_path, json).await?;
+    info!("Saved JSON report: {}", json_path.display());
+
+    // Save Markdown
+    let md_path = output_dir.join("stress.md");
+    tokio::fs::write(&md_path, self.to_markdown()).await?;
+    info!("Saved Markdown report: {}", md_path.display());
+
+    Ok(())
+  }
+}
+
+/// Concurrent search stress benchmark runner.
+pub struct SearchStressBenchmark {
+  client: Client,
+  cache_dir: Option<PathBuf>,
+  config: StressBenchConfig,
+}
+
+impl SearchStressBenchmark {
+  /// Create a new stress benchmark runner.
+  pub fn new(client: Client, cache_dir: Option<PathBuf>) -> Self {
+    Self {
+      client,
+      cache_dir,
+      config: StressBenchConfig::default(),
+    }
+  }
+
+  /// Set benchmark configuration.
+  pub fn with_config(mut self, config: StressBenchConfig) -> Self {
+    self.config = config;
+    self
+  }
+
+  /// Run the idle and under-index phases for a repository.
+  pub async fn run(&mut self, repo: TargetRepo) -> Result<StressReport> {
+    info!("Running search stress benchmark for: {}", repo);
+
+    let repo_path = prepare_repo(&repo, self.cache_dir.clone()).await?;
+    self.client.change_cwd(repo_path);
+
+    let idle = if self.config.skip_idle {
+      None
+    } else {
+      info!(
+        "Idle phase: {} workers for {:?}",
+        self.config.concurrency, self.config.duration
+      );
+      Some(self.run_phase("idle").await)
+    };
+
+    // Start a forced re-index and hammer the daemon while it runs
+    info!("Starting forced re-index");
+    let index_client = self.client.clone();
+    let index_task = tokio::spawn(async move {
+      let start = Instant::now();
+      let result = index_client
+        .call(CodeIndexParams {
+          force: true,
+          stream: false,
+          since: None,
+        })
+        .await;
+      (result, start.elapsed())
+    });
+
+    info!(
+      "Under-index phase: {} workers for {:?}",
+      self.config.concurrency, self.config.duration
+    );
+    let under_index = self.run_phase("under_index").await;
+
+    if !index_task.is_finished() {
+      info!("Waiting for re-index to finish");
+    }
+    let index_duration_ms = match index_task.await {
+      Ok((Ok(_), elapsed)) => Some(elapsed.as_millis() as u64),
+      Ok((Err(e), _)) => {
+        warn!("Re-index failed: {}", e);
+        None
+      }
+      Err(e) => {
+        warn!("Re-index task panicked: {}", e);
+        None
+      }
+    };
+
+    let summary = StressReport::compute_summary(
+      idle.as_ref(),
+      &under_index,
+      self.config.target_p95_ms,
+      self.config.max_error_rate,
+    );
+
+    Ok(StressReport {
+      timestamp: chrono::Utc::now().to_rfc3339(),
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      repo: repo.to_string(),
+      concurrency: self.config.concurrency,
+      mix: self.config.mix.to_string(),
+      target_p95_ms: self.config.target_p95_ms,
+      max_error_rate: self.config.max_error_rate,
+      idle,
+      under_index,
+      index_duration_ms,
+      summary,
+    })
+  }
+
+  /// Run all workers until the phase duration elapses.
+  async fn run_phase(&self, phase: &str) -> PhaseResult {
+    let start = Instant::now();
+    let deadline = start + self.config.duration;
+    let config = Arc::new(self.config.clone());
+
+    let workers: Vec<_> = (0..self.config.concurrency.max(1))
+      .map(|worker| {
+        let client = self.client.clone();
+        let config = config.clone();
+        tokio::spawn(async move { Self::worker(client, config, worker, deadline).await })
+      })
+      .collect();
+
+    let mut samples = Vec::new();
+    for worker in workers {
+      match worker.await {
+        Ok(worker_samples) => samples.extend(worker_samples),
+        Err(e) => warn!("Stress worker panicked: {}", e),
+      }
+    }
+
+    PhaseResult::from_samples(phase, &samples, start.elapsed())
+  }
+
+  async fn worker(client: Client, config: Arc<StressBenchConfig>, worker: usize, deadline: Instant) -> Vec<Sample> {
+    let mut samples = Vec::new();
+    let mut n = worker;
+
+    while Instant::now() < deadline {
+      let kind = config.mix.kind_for(n);
+      let query = STRESS_QUERIES[n % STRESS_QUERIES.len()].to_string();
+      let limit = Some(config.limit);
+
+      let start = Instant::now();
+      let result = match kind {
+        SearchKind::Memory => client
+          .call(MemorySearchParams {
+            query,
+            limit,
+            ..Default::default()
+          })
+          .await
+          .map(|_| ()),
+        SearchKind::Code => client
+          .call(CodeSearchParams {
+            query,
+            limit,
+            ..Default::default()
+          })
+          .await
+          .map(|_| ()),
+        SearchKind::Docs => client.call(DocsSearchParams { query, limit }).await.map(|_| ()),
+      };
+      let duration = start.elapsed();
+
+      if let Err(e) = &result {
+        debug!(worker, %kind, "Search failed: {}", e);
+      }
+      samples.push(Sample {
+        kind,
+        duration,
+        ok: result.is_ok(),
+      });
+
+      n += config.concurrency.max(1);
+    }
+
+    samples
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sample(kind: SearchKind, ms: u64, ok: bool) -> Sample {
+    Sample {
+      kind,
+      duration: Duration::from_millis(ms),
+      ok,
+    }
+  }
+
+  #[test]
+  fn test_mix_parse_and_weighted_pick() {
+    let mix = SearchMix::parse("memory=1, code=2").expect("valid mix");
+    assert_eq!(mix.docs, 0, "omitted kinds should get weight 0");
+
+    let picks: Vec<_> = (0..6).map(|n| mix.kind_for(n)).collect();
+    assert_eq!(
+      picks.iter().filter(|k| **k == SearchKind::Code).count(),
+      4,
+      "code should get two thirds of requests"
+    );
+    assert!(
+      !picks.contains(&SearchKind::Docs),
+      "zero-weight kind should never be picked"
+    );
+
+    assert!(SearchMix::parse("graph=1").is_none(), "unknown kind should be rejected");
+    assert!(SearchMix::parse("code=0").is_none(), "all-zero mix should be rejected");
+  }
+
+  #[test]
+  fn test_phase_result_counts_errors_per_kind() {
+    let samples = vec![
+      sample(SearchKind::Code, 10, true),
+      sample(SearchKind::Code, 30, true),
+      sample(SearchKind::Code, 500, false),
+      sample(SearchKind::Memory, 20, true),
+    ];
+
+    let phase = PhaseResult::from_samples("under_index", &samples, Duration::from_secs(2));
+    assert_eq!(phase.requests, 4, "all requests counted");
+    assert_eq!(phase.errors, 1, "failed request counted");
+    assert_eq!(phase.latency.count, 3, "latency only over successful requests");
+    assert_eq!(phase.throughput_rps, 2.0, "4 requests over 2 seconds");
+
+    let code = phase
+      .by_kind
+      .iter()
+      .find(|k| k.kind == SearchKind::Code)
+      .expect("code breakdown");
+    assert_eq!(code.errors, 1, "error attributed to code search");
+    assert!(
+      phase.by_kind.iter().all(|k| k.kind != SearchKind::Docs),
+      "kinds without requests are omitted"
+    );
+  }
+
+  #[test]
+  fn test_summary_thresholds() {
+    let idle = PhaseResult::from_samples("idle", &[sample(SearchKind::Code, 50, true)], Duration::from_secs(1));
+    let loaded = PhaseResult::from_samples(
+      "under_index",
+      &[sample(SearchKind::Code, 150, true)],
+      Duration::from_secs(1),
+    );
+
+    let summary = StressReport::compute_summary(Some(&idle), &loaded, 1000, 0.01);
+    assert!(summary.passes, "p95 within target and no errors should pass");
+    assert_eq!(summary.p95_slowdown, Some(3.0), "slowdown relative to idle p95");
+
+    let summary = StressReport::compute_summary(None, &loaded, 100, 0.01);
+    assert!(!summary.passes, "p95 above target should fail");
+    assert_eq!(summary.p95_slowdown, None, "no slowdown without idle phase");
+  }
+}
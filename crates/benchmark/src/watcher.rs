@@ -108,7 +108,7 @@ impl WatcherBenchmark {
   pub async fn run(&mut self, repo: TargetRepo) -> Result<WatcherReport> {
     info!("Running watcher benchmarks for: {}", repo);
 
-    let repo_path = prepare_repo(repo, self.cache_dir.clone()).await?;
+    let repo_path = prepare_repo(&repo, self.cache_dir.clone()).await?;
     self.client.change_cwd(repo_path.clone());
 
     let mut lifecycle_results = Vec::new();
@@ -201,7 +201,7 @@ impl WatcherBenchmark {
 
     // Measure startup latency
     let start = Instant::now();
-    let _ = self.client.call(WatchStartParams).await?;
+    let _ = self.client.call(WatchStartParams::default()).await?;
 
     // Wait for watcher to be ready (not scanning)
     self.wait_for_watcher_ready().await?;
@@ -238,7 +238,7 @@ impl WatcherBenchmark {
     let mut fixtures = FixtureGenerator::new(repo_path).await?;
 
     // Start watcher
-    let _ = self.client.call(WatchStartParams).await?;
+    let _ = self.client.call(WatchStartParams::default()).await?;
     self.wait_for_watcher_ready().await?;
 
     // Create file with unique marker
@@ -277,7 +277,7 @@ impl WatcherBenchmark {
     let mut fixtures = FixtureGenerator::new(repo_path).await?;
 
     // Start watcher
-    let _ = self.client.call(WatchStartParams).await?;
+    let _ = self.client.call(WatchStartParams::default()).await?;
     self.wait_for_watcher_ready().await?;
 
     // Get initial chunk count
@@ -320,7 +320,7 @@ impl WatcherBenchmark {
     let mut fixtures = FixtureGenerator::new(repo_path).await?;
 
     // Start watcher
-    let _ = self.client.call(WatchStartParams).await?;
+    let _ = self.client.call(WatchStartParams::default()).await?;
     self.wait_for_watcher_ready().await?;
 
     // Test CREATE
@@ -549,7 +549,7 @@ impl WatcherBenchmark {
     let mut fixtures = FixtureGenerator::new(repo_path).await?;
 
     // Start watcher
-    let _ = self.client.call(WatchStartParams).await?;
+    let _ = self.client.call(WatchStartParams::default()).await?;
     self.wait_for_watcher_ready().await?;
 
     // Create files in ignored directory (node_modules)
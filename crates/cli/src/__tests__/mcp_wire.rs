@@ -0,0 +1,222 @@
+//! Golden wire fixtures for the MCP tools.
+//!
+//! Hooks, agents and editor plugins call the MCP tools directly, so each
+//! tool's input schema and the JSON its result carries are a public contract.
+//! Every listed tool has a fixture in `fixtures/mcp/<tool>.json` holding the
+//! `TOOL_API_VERSION` it was written for, the input schema, and a canned
+//! response. A schema that no longer matches its fixture, or a canned response
+//! that no longer survives a trip through the tool's response type, fails the
+//! test unless the version was bumped.
+//!
+//! After bumping the version, rewrite the fixtures with
+//! `CCENGRAM_UPDATE_FIXTURES=1 cargo test -p cli mcp_wire`. Canned responses
+//! for new tools are written by hand.
+
+use std::{fs, path::PathBuf};
+
+use ccengram::ipc::IpcRequest;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::tools::{TOOL_API_VERSION, all_tool_definitions};
+
+/// Set to rewrite fixtures after a version bump
+const UPDATE_ENV: &str = "CCENGRAM_UPDATE_FIXTURES";
+
+fn fixture_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/__tests__/fixtures/mcp")
+}
+
+/// Remove descriptions, which document the schema rather than shape it
+fn strip_descriptions(value: &mut Value) {
+  match value {
+    Value::Object(map) => {
+      if map.get("description").is_some_and(Value::is_string) {
+        map.remove("description");
+      }
+      map.values_mut().for_each(strip_descriptions);
+    }
+    Value::Array(items) => items.iter_mut().for_each(strip_descriptions),
+    _ => {}
+  }
+}
+
+/// Remove null fields, so fixtures can leave out `None`s either way
+fn strip_nulls(value: &mut Value) {
+  match value {
+    Value::Object(map) => {
+      map.retain(|_, v| !v.is_null());
+      map.values_mut().for_each(strip_nulls);
+    }
+    Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+    _ => {}
+  }
+}
+
+/// `response` parsed as the response type of `P` and serialized again
+fn reserialize<P>(response: &Value) -> Result<Value, serde_json::Error>
+where
+  P: IpcRequest,
+  P::Response: Serialize + DeserializeOwned,
+{
+  let typed: P::Response = serde_json::from_value(response.clone())?;
+  serde_json::to_value(typed)
+}
+
+/// A canned response of `tool` after a trip through the tool's response type
+fn roundtrip(tool: &str, response: &Value) -> Result<Value, serde_json::Error> {
+  use ccengram::ipc::{
+    code::*,
+    docs::*,
+    entity::*,
+    memory::*,
+    project::*,
+    relationship::*,
+    search::{ContextParams, ExploreParams, ExplorePlanParams},
+    system::*,
+    watch::*,
+  };
+
+  let mut value = match tool {
+    "explore" => reserialize::<ExploreParams>(response),
+    "context" => reserialize::<ContextParams>(response),
+    "explore_plan" => reserialize::<ExplorePlanParams>(response),
+    "memory_search" => reserialize::<MemorySearchParams>(response),
+    "memory_get" => reserialize::<MemoryGetParams>(response),
+    "memory_list" => reserialize::<MemoryListParams>(response),
+    "memory_add" => reserialize::<MemoryAddParams>(response),
+    "memory_reinforce" => reserialize::<MemoryReinforceParams>(response),
+    "memory_deemphasize" => reserialize::<MemoryDeemphasizeParams>(response),
+    "memory_feedback" => reserialize::<MemoryFeedbackParams>(response),
+    "memory_delete" => reserialize::<MemoryDeleteParams>(response),
+    "memory_supersede" => reserialize::<MemorySupersedeParams>(response),
+    "memory_timeline" => reserialize::<MemoryTimelineParams>(response),
+    "memory_related" => reserialize::<MemoryRelatedParams>(response),
+    "goals" => reserialize::<GoalsParams>(response),
+    "goal_update" => reserialize::<GoalUpdateParams>(response),
+    "code_search" => reserialize::<CodeSearchParams>(response),
+    "code_context" => reserialize::<CodeContextParams>(response),
+    "code_index" => reserialize::<CodeIndexParams>(response),
+    "code_list" => reserialize::<CodeListParams>(response),
+    "code_stats" => reserialize::<CodeStatsParams>(response),
+    "code_memories" => reserialize::<CodeMemoriesParams>(response),
+    "code_callers" => reserialize::<CodeCallersParams>(response),
+    "code_callees" => reserialize::<CodeCalleesParams>(response),
+    "code_definition" => reserialize::<CodeDefinitionParams>(response),
+    "code_references" => reserialize::<CodeReferencesParams>(response),
+    "code_related" => reserialize::<CodeRelatedParams>(response),
+    "code_context_full" => reserialize::<CodeContextFullParams>(response),
+    "watch_start" => reserialize::<WatchStartParams>(response),
+    "watch_stop" => reserialize::<WatchStopParams>(response),
+    "watch_status" => reserialize::<WatchStatusParams>(response),
+    "docs_search" => reserialize::<DocsSearchParams>(response),
+    "doc_context" => reserialize::<DocContextParams>(response),
+    "docs_ingest" => reserialize::<DocsIngestParams>(response),
+    "relationship_add" => reserialize::<RelationshipAddParams>(response),
+    "relationship_list" => reserialize::<RelationshipListParams>(response),
+    "relationship_delete" => reserialize::<RelationshipDeleteParams>(response),
+    "relationship_related" => reserialize::<RelationshipRelatedParams>(response),
+    "entity_list" => reserialize::<EntityListParams>(response),
+    "entity_get" => reserialize::<EntityGetParams>(response),
+    "entity_top" => reserialize::<EntityTopParams>(response),
+    "session_injections" => reserialize::<SessionInjectionsParams>(response),
+    "project_stats" => reserialize::<ProjectStatsParams>(response),
+    "health_check" => reserialize::<HealthCheckParams>(response),
+    _ => panic!("No response type for tool {}; add it next to mcp::call_tool's", tool),
+  }?;
+  strip_nulls(&mut value);
+  Ok(value)
+}
+
+fn write_fixture(path: &PathBuf, schema: &Value, response: &Value) {
+  let fixture = json!({
+    "version": TOOL_API_VERSION,
+    "input_schema": schema,
+    "response": response,
+  });
+  let text = serde_json::to_string_pretty(&fixture).expect("serialize fixture") + "\n";
+  fs::write(path, text).expect("write fixture");
+}
+
+/// Every listed tool's input schema and canned response still match its
+/// fixture, or the tool API version was bumped
+#[test]
+fn test_mcp_wire_fixtures() {
+  let update = std::env::var_os(UPDATE_ENV).is_some();
+  let definitions = all_tool_definitions();
+  let mut failures = Vec::new();
+
+  for tool in ccengram::config::ALL_TOOLS {
+    let mut schema = definitions[tool]["inputSchema"].clone();
+    strip_descriptions(&mut schema);
+    let path = fixture_dir().join(format!("{}.json", tool));
+
+    let Ok(text) = fs::read_to_string(&path) else {
+      if update {
+        write_fixture(&path, &schema, &Value::Null);
+      }
+      failures.push(format!(
+        "{}: no fixture; write a canned response into {}",
+        tool,
+        path.display()
+      ));
+      continue;
+    };
+    let fixture: Value =
+      serde_json::from_str(&text).unwrap_or_else(|e| panic!("{} is not JSON: {}", path.display(), e));
+
+    let mut expected = fixture["response"].clone();
+    if expected.is_null() {
+      failures.push(format!("{}: no canned response in {}", tool, path.display()));
+      continue;
+    }
+    strip_nulls(&mut expected);
+    let response = match roundtrip(tool, &expected) {
+      Ok(response) => response,
+      Err(e) => {
+        failures.push(format!(
+          "{}: canned response no longer parses as the tool's response type: {}",
+          tool, e
+        ));
+        continue;
+      }
+    };
+
+    let version = fixture["version"].as_u64().unwrap_or_default();
+    let changed = fixture["input_schema"] != schema || response != expected;
+    match (changed, version == u64::from(TOOL_API_VERSION)) {
+      (false, true) => {}
+      (true, true) => failures.push(format!(
+        "{}: wire format changed without a TOOL_API_VERSION bump (input schema or response no longer matches {})",
+        tool,
+        path.display()
+      )),
+      (_, false) if update => write_fixture(&path, &schema, &response),
+      (_, false) => failures.push(format!(
+        "{}: fixture is for tool API version {}, not {}; rerun with {}=1",
+        tool, version, TOOL_API_VERSION, UPDATE_ENV
+      )),
+    }
+  }
+
+  // A removed tool is a wire change too
+  for entry in fs::read_dir(fixture_dir()).expect("read fixture dir").flatten() {
+    let path = entry.path();
+    let Some(tool) = path.file_stem().and_then(|s| s.to_str()) else {
+      continue;
+    };
+    if !ccengram::config::ALL_TOOLS.contains(&tool) {
+      failures.push(format!(
+        "{}: fixture for a tool no longer listed; bump TOOL_API_VERSION and delete {}",
+        tool,
+        path.display()
+      ));
+    }
+  }
+
+  assert!(
+    failures.is_empty(),
+    "MCP wire fixtures out of date:\n{}",
+    failures.join("\n")
+  );
+}
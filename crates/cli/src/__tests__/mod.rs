@@ -0,0 +1 @@
+mod mcp_wire;
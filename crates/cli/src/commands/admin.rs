@@ -2,11 +2,16 @@
 
 use anyhow::{Context, Result};
 use ccengram::ipc::{
-  memory::{MemoryDeleteParams, MemoryListParams},
+  memory::{MemoryArchiveParams, MemoryListParams},
   system::{HealthCheckParams, MetricsParams, PingParams, ProjectStatsParams, StatusParams},
 };
 use tracing::error;
 
+use crate::{display, table::Table};
+
+/// Longest a health probe waits for the daemon
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Show statistics
 pub async fn cmd_stats() -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
@@ -36,14 +41,17 @@ pub async fn cmd_stats() -> Result<()> {
 
   // Requests
   println!(
-    "Requests:       {} total ({:.2}/s)",
-    metrics.requests.total, metrics.requests.per_second
+    "Requests:       {} total ({}/s)",
+    display::count(metrics.requests.total),
+    display::decimal(metrics.requests.per_second, 2)
   );
 
   // Sessions
   if metrics.sessions.active > 0 {
     println!("Sessions:       {} active", metrics.sessions.active);
-    for id in metrics.sessions.ids.iter().take(5) {
+    let mut ids = metrics.sessions.ids.clone();
+    ids.sort();
+    for id in ids.iter().take(5) {
       println!("                - {}", id);
     }
     if metrics.sessions.ids.len() > 5 {
@@ -55,7 +63,9 @@ pub async fn cmd_stats() -> Result<()> {
 
   // Projects
   println!("Projects:       {} loaded", metrics.projects.count);
-  for name in metrics.projects.names.iter().take(5) {
+  let mut names = metrics.projects.names.clone();
+  names.sort();
+  for name in names.iter().take(5) {
     println!("                - {}", name);
   }
   if metrics.projects.names.len() > 5 {
@@ -87,10 +97,34 @@ pub async fn cmd_stats() -> Result<()> {
   println!("\n--- Project Statistics ---");
   println!("Project ID:     {}", stats.project_id);
   println!("Path:           {}", stats.path);
-  println!("Total memories: {}", stats.memories);
-  println!("Code chunks:    {}", stats.code_chunks);
-  println!("Documents:      {}", stats.documents);
-  println!("Sessions:       {}", stats.sessions);
+  println!("Total memories: {}", display::count(stats.memories));
+  println!("Code chunks:    {}", display::count(stats.code_chunks));
+  println!("Documents:      {}", display::count(stats.documents));
+  println!("Sessions:       {}", display::count(stats.sessions));
+
+  if let Some(tables) = stats.tables {
+    println!("\n--- Storage ---");
+    let mut output = Table::new(&["Table", "Rows", "Size", "Fragments", "Vector index"])
+      .right(1)
+      .right(2)
+      .right(3);
+    let mut tables = tables;
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+    for table in tables {
+      let index = match table.vector_index {
+        Some(kind) => format!("{} ({} unindexed)", kind, display::count(table.unindexed_rows)),
+        None => "-".to_string(),
+      };
+      output.row([
+        table.name,
+        display::count(table.rows),
+        format_memory((table.bytes / 1024) as u64),
+        display::count(table.fragments),
+        index,
+      ]);
+    }
+    output.print();
+  }
 
   Ok(())
 }
@@ -162,6 +196,32 @@ pub async fn cmd_health() -> Result<()> {
   Ok(())
 }
 
+/// Health probe for supervisors, answered only through the exit code.
+///
+/// `liveness` passes when the daemon socket accepts a connection and answers
+/// a ping; `readiness` when the daemon also reports it is serving requests.
+/// Unlike `cmd_health`, this never starts the daemon.
+pub async fn cmd_health_probe(probe: &str) -> Result<()> {
+  let passed = tokio::time::timeout(PROBE_TIMEOUT, run_probe(probe))
+    .await
+    .unwrap_or(false);
+  std::process::exit(if passed { 0 } else { 1 });
+}
+
+async fn run_probe(probe: &str) -> bool {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let Ok(client) = ccengram::ipc::Client::connect(cwd).await else {
+    return false;
+  };
+  match probe {
+    "readiness" => client
+      .call(StatusParams)
+      .await
+      .is_ok_and(|status| status.status == "running"),
+    _ => client.call(PingParams).await.is_ok(),
+  }
+}
+
 /// Archive old low-salience memories
 pub async fn cmd_archive(before: Option<&str>, threshold: f32, dry_run: bool) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
@@ -236,16 +296,15 @@ pub async fn cmd_archive(before: Option<&str>, threshold: f32, dry_run: bool) ->
     return Ok(());
   }
 
-  // Archive (soft delete) each memory
-  let mut archived = 0;
-  for (id, _, _) in candidates {
-    match client.call(MemoryDeleteParams { memory_id: id.clone() }).await {
-      Ok(_) => archived += 1,
-      Err(e) => error!("Failed to archive memory {}: {}", id, e),
-    }
-  }
+  // Archive (soft delete) in one batch, so `memory undo` can revert the whole run
+  let memory_ids = candidates.into_iter().map(|(id, _, _)| id).collect();
+  let result = client
+    .call(MemoryArchiveParams { memory_ids })
+    .await
+    .context("Failed to archive memories")?;
 
-  println!("Archived {} memories (soft deleted)", archived);
+  println!("Archived {} memories (soft deleted)", result.archived.len());
+  println!("Run `ccengram memory undo` to restore them.");
   Ok(())
 }
 
@@ -374,8 +433,8 @@ fn format_memory(kb: u64) -> String {
   if kb < 1024 {
     format!("{} KB", kb)
   } else if kb < 1024 * 1024 {
-    format!("{:.1} MB", kb as f64 / 1024.0)
+    format!("{} MB", display::decimal(kb as f64 / 1024.0, 1))
   } else {
-    format!("{:.2} GB", kb as f64 / (1024.0 * 1024.0))
+    format!("{} GB", display::decimal(kb as f64 / (1024.0 * 1024.0), 2))
   }
 }
@@ -36,10 +36,10 @@ pub async fn cmd_agent(output: Option<&str>, force: bool) -> Result<()> {
   Ok(())
 }
 
-/// Launch interactive TUI
-pub async fn cmd_tui(project: Option<PathBuf>) -> Result<()> {
+/// Launch interactive TUI, optionally with a saved exploration open
+pub async fn cmd_tui(project: Option<PathBuf>, exploration: Option<String>) -> Result<()> {
   let path = project.unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-  crate::tui::run(path).await
+  crate::tui::run(path, exploration).await
 }
 
 /// Generate the SemExplore agent markdown content
@@ -0,0 +1,70 @@
+//! Data audit commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::project::ProjectAuditSecretsParams;
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// Scan stored memories and chunks for secrets
+pub async fn cmd_audit_secrets(json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let result = match client.call(ProjectAuditSecretsParams).await {
+    Ok(result) => result,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    return Ok(());
+  }
+
+  println!(
+    "Scanned {} memories, {} code chunks and {} document chunks",
+    display::count(result.memories_scanned),
+    display::count(result.code_chunks_scanned),
+    display::count(result.document_chunks_scanned)
+  );
+  if result.findings.is_empty() {
+    println!("No secrets found.");
+    return Ok(());
+  }
+
+  println!();
+  let mut table = Table::new(&["Type", "Location", "Kind", "Secret"]);
+  for item in &result.findings {
+    let location = match item.item_type.as_str() {
+      "memory" => format!("{} ({})", &item.id[..8.min(item.id.len())], item.location),
+      _ => item.location.clone(),
+    };
+    table.row([
+      item.item_type.clone(),
+      location,
+      item.kind.clone(),
+      item.preview.clone(),
+    ]);
+  }
+  table.print();
+
+  println!("\n{} secrets found.", result.findings.len());
+  let has = |item_type: &str| result.findings.iter().any(|f| f.item_type == item_type);
+  if has("memory") {
+    println!("Remove affected memories with `ccengram memory delete <id> --hard`.");
+  }
+  if has("code") {
+    println!("Run `ccengram index code --force` to re-index code with redaction applied.");
+  }
+  if has("document") {
+    println!("Run `ccengram index docs --force` to re-index docs with redaction applied.");
+  }
+  Ok(())
+}
@@ -0,0 +1,225 @@
+//! Dynamic shell completion values
+//!
+//! Backs `ccengram __complete <kind> [prefix]`, which the generated completion
+//! scripts call to offer real IDs, tags and projects. Completion must never
+//! block or fail loudly, so it only talks to an already running daemon and
+//! prints nothing on error.
+
+use std::{io::Write, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use ccengram::ipc::{
+  Client,
+  memory::{MemoryListDeletedParams, MemoryListParams, TagListParams},
+  project::{ProjectListParams, SessionListParams},
+};
+use clap::ValueEnum;
+use clap_complete::Shell;
+
+/// Maximum candidates printed per completion
+const MAX_CANDIDATES: usize = 50;
+
+/// Memories scanned when collecting recent IDs, and tags listed
+const SCAN_LIMIT: usize = 1000;
+
+/// Give up quickly so a slow daemon never stalls the shell
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Kinds of values the completion scripts can ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+  /// Recently created memory IDs
+  MemoryIds,
+  /// Soft-deleted memory IDs
+  DeletedMemoryIds,
+  /// Tags used by memories in the current project
+  Tags,
+  /// Registered project paths
+  Projects,
+  /// Recent Claude Code session IDs
+  Sessions,
+}
+
+/// Print completion candidates for `kind` that start with `prefix`, one per line
+pub async fn cmd_complete(kind: CompletionKind, prefix: &str) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  if !ccengram::dirs::is_daemon_running() {
+    return Ok(());
+  }
+
+  let candidates = match tokio::time::timeout(COMPLETION_TIMEOUT, candidates(cwd, kind)).await {
+    Ok(Ok(candidates)) => candidates,
+    _ => return Ok(()),
+  };
+
+  let mut stdout = std::io::stdout().lock();
+  for candidate in candidates
+    .into_iter()
+    .filter(|c| c.starts_with(prefix))
+    .take(MAX_CANDIDATES)
+  {
+    writeln!(stdout, "{}", candidate)?;
+  }
+  Ok(())
+}
+
+async fn candidates(cwd: PathBuf, kind: CompletionKind) -> Result<Vec<String>> {
+  let client = Client::connect(cwd).await?;
+
+  let values = match kind {
+    CompletionKind::MemoryIds => {
+      let mut memories = client
+        .call(MemoryListParams {
+          limit: Some(SCAN_LIMIT),
+          ..Default::default()
+        })
+        .await?;
+      memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+      memories.into_iter().map(|m| m.id).collect()
+    }
+    CompletionKind::DeletedMemoryIds => {
+      let memories = client
+        .call(MemoryListDeletedParams {
+          limit: Some(MAX_CANDIDATES),
+        })
+        .await?;
+      memories.into_iter().map(|m| m.id).collect()
+    }
+    CompletionKind::Tags => {
+      let tags = client
+        .call(TagListParams {
+          min_count: None,
+          limit: Some(SCAN_LIMIT),
+        })
+        .await?;
+      tags.into_iter().map(|t| t.tag).collect()
+    }
+    CompletionKind::Projects => {
+      let projects = client.call(ProjectListParams).await?;
+      projects.into_iter().map(|p| p.path).collect()
+    }
+    CompletionKind::Sessions => {
+      let mut sessions = client.call(SessionListParams::default()).await?;
+      sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+      sessions.into_iter().map(|s| s.id).collect()
+    }
+  };
+  Ok(values)
+}
+
+/// Shell code that routes dynamic arguments through `ccengram __complete`.
+///
+/// Appended to (or spliced into) the clap-generated script, which still
+/// handles subcommands and flags.
+pub fn dynamic_completion_script(shell: Shell, generated: &str) -> String {
+  match shell {
+    Shell::Bash => format!("{}\n{}", generated, BASH_DYNAMIC),
+    Shell::Zsh => {
+      // Register the wrapper in place of the generated function, both when
+      // sourced and when autoloaded from fpath
+      let spliced = generated
+        .replace("    _ccengram \"$@\"\nelse", "    _ccengram_dynamic \"$@\"\nelse")
+        .replace("compdef _ccengram ccengram", "compdef _ccengram_dynamic ccengram");
+      match spliced.find("if [ \"$funcstack[1]\" = \"_ccengram\" ]") {
+        Some(tail) => format!("{}{}\n{}", &spliced[..tail], ZSH_DYNAMIC, &spliced[tail..]),
+        None => format!("{}\n{}\ncompdef _ccengram_dynamic ccengram\n", spliced, ZSH_DYNAMIC),
+      }
+    }
+    Shell::Fish => format!("{}\n{}", generated, FISH_DYNAMIC),
+    _ => generated.to_string(),
+  }
+}
+
+const BASH_DYNAMIC: &str = r#"_ccengram_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" kind=""
+    case "$prev" in
+        --project) kind=projects ;;
+        --tag|--tags|--into) kind=tags ;;
+        --session|--session-id) kind=sessions ;;
+    esac
+    if [[ -z "$kind" && $COMP_CWORD -eq 3 ]]; then
+        case "${COMP_WORDS[1]} ${COMP_WORDS[2]}" in
+            "memory show"|"memory delete") kind=memory-ids ;;
+            "memory restore") kind=deleted-memory-ids ;;
+            "projects show"|"projects clean") kind=projects ;;
+            "sessions show") kind=sessions ;;
+            "tags rename"|"tags merge"|"tags delete") kind=tags ;;
+        esac
+    fi
+    if [[ -n "$kind" && "$cur" != -* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(ccengram __complete "$kind" "$cur" 2>/dev/null))
+        return 0
+    fi
+    _ccengram "$@"
+}
+
+complete -F _ccengram_dynamic -o nosort -o bashdefault -o default ccengram
+"#;
+
+const ZSH_DYNAMIC: &str = r#"_ccengram_dynamic() {
+    local kind=""
+    case "$words[CURRENT-1]" in
+        --project) kind=projects ;;
+        --tag|--tags|--into) kind=tags ;;
+        --session|--session-id) kind=sessions ;;
+    esac
+    if [[ -z "$kind" && $CURRENT -eq 4 ]]; then
+        case "$words[2] $words[3]" in
+            "memory show"|"memory delete") kind=memory-ids ;;
+            "memory restore") kind=deleted-memory-ids ;;
+            "projects show"|"projects clean") kind=projects ;;
+            "sessions show") kind=sessions ;;
+            "tags rename"|"tags merge"|"tags delete") kind=tags ;;
+        esac
+    fi
+    if [[ -n "$kind" && "$PREFIX" != -* ]]; then
+        local -a values
+        values=(${(f)"$(ccengram __complete $kind "$PREFIX" 2>/dev/null)"})
+        compadd -U -- $values
+        return 0
+    fi
+    _ccengram "$@"
+}
+"#;
+
+const FISH_DYNAMIC: &str = r#"complete -c ccengram -n "__fish_seen_subcommand_from memory; and __fish_seen_subcommand_from show delete" -f -a "(ccengram __complete memory-ids (commandline -ct) 2>/dev/null)"
+complete -c ccengram -n "__fish_seen_subcommand_from memory; and __fish_seen_subcommand_from restore" -f -a "(ccengram __complete deleted-memory-ids (commandline -ct) 2>/dev/null)"
+complete -c ccengram -n "__fish_seen_subcommand_from projects; and __fish_seen_subcommand_from show clean" -f -a "(ccengram __complete projects (commandline -ct) 2>/dev/null)"
+complete -c ccengram -n "__fish_seen_subcommand_from sessions; and __fish_seen_subcommand_from show" -f -a "(ccengram __complete sessions (commandline -ct) 2>/dev/null)"
+complete -c ccengram -n "__fish_seen_subcommand_from tags; and __fish_seen_subcommand_from rename merge delete" -f -a "(ccengram __complete tags (commandline -ct) 2>/dev/null)"
+complete -c ccengram -l project -f -r -a "(ccengram __complete projects (commandline -ct) 2>/dev/null)"
+"#;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ZSH_TAIL: &str = "#compdef ccengram\n_ccengram() {\n}\n\nif [ \"$funcstack[1]\" = \"_ccengram\" ]; then\n    _ccengram \"$@\"\nelse\n    compdef _ccengram ccengram\nfi\n";
+
+  #[test]
+  fn test_zsh_wrapper_replaces_registration() {
+    let script = dynamic_completion_script(Shell::Zsh, ZSH_TAIL);
+
+    assert!(
+      script.contains("compdef _ccengram_dynamic ccengram"),
+      "wrapper is registered"
+    );
+    assert!(
+      !script.contains("compdef _ccengram ccengram"),
+      "generated registration is replaced"
+    );
+    let wrapper = script.find("_ccengram_dynamic() {").unwrap_or(usize::MAX);
+    let tail = script.find("if [ \"$funcstack[1]\"").unwrap_or(0);
+    assert!(wrapper < tail, "wrapper is defined before the autoload tail runs");
+  }
+
+  #[test]
+  fn test_unsupported_shell_unchanged() {
+    assert_eq!(
+      dynamic_completion_script(Shell::PowerShell, "generated"),
+      "generated",
+      "powershell keeps the static script"
+    );
+  }
+}
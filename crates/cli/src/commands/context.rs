@@ -1,9 +1,14 @@
-//! Context retrieval commands for code and document chunks
+//! Context retrieval commands for code and document chunks, and the
+//! memory primer for CLAUDE.md
+
+use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use ccengram::ipc::{code::CodeContextParams, docs::DocContextParams};
+use ccengram::ipc::{code::CodeContextParams, docs::DocContextParams, project::ContextPackParams};
 use tracing::error;
 
+use crate::display;
+
 /// Get context around a chunk (auto-detects code vs document)
 pub async fn cmd_context(chunk_id: &str, before: Option<usize>, after: Option<usize>, json_output: bool) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
@@ -56,6 +61,57 @@ pub async fn cmd_context(chunk_id: &str, before: Option<usize>, after: Option<us
   }
 }
 
+/// Build the memory primer and print it, or write it into CLAUDE.md
+pub async fn cmd_context_pack(budget: usize, write: bool, file: Option<PathBuf>, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let file = file.map(|f| cwd.join(f).to_string_lossy().to_string());
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ContextPackParams {
+    budget: Some(budget),
+    write: write || file.is_some(),
+    file,
+  };
+  let result = match client.call(params).await {
+    Ok(result) => result,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    return Ok(());
+  }
+
+  let Some(path) = &result.path else {
+    print!("{}", result.markdown);
+    return Ok(());
+  };
+  let summary = format!(
+    "{} memories, ~{} tokens",
+    display::count(result.memories),
+    display::count(result.tokens)
+  );
+  if result.written {
+    println!("Updated {} ({})", path, summary);
+  } else if result.memories == 0 {
+    println!("No preferences, decisions or gotchas to write; {} left as it is", path);
+  } else {
+    println!("{} is up to date ({})", path, summary);
+  }
+  if result.omitted > 0 {
+    println!(
+      "{} more left out to stay within the budget",
+      display::count(result.omitted)
+    );
+  }
+  Ok(())
+}
+
 /// Print code context in a readable format
 fn print_code_context(result: &ccengram::ipc::code::CodeContextResponse) {
   println!("File: {} ({})", result.file_path, result.language);
@@ -69,6 +69,9 @@ pub async fn cmd_daemon(
     return Ok(());
   }
 
+  // Refuse ambiguous data dirs here so the message reaches the terminal
+  ccengram::data_dir::check(&ccengram::dirs::default_data_root()).await?;
+
   // Create config based on mode
   let mut config = ccengram::RuntimeConfig::load().await;
   config.foreground = foreground;
@@ -0,0 +1,132 @@
+//! Data directory commands (status, import, init)
+
+use anyhow::{Result, bail};
+use ccengram::{
+  config::Config,
+  data_dir::{self, DATA_VERSION, DataDirLayout, legacy::LegacyImportReport},
+};
+
+/// Show the data versions stored under the data root
+pub async fn cmd_data_status() -> Result<()> {
+  let root = ccengram::dirs::default_data_root();
+  let layout = DataDirLayout::scan(&root).await?;
+
+  println!("Data root: {}", root.display());
+  println!("This build: version {}", DATA_VERSION);
+  println!();
+
+  if !layout.unversioned && layout.versions.is_empty() {
+    println!("No data yet.");
+    return Ok(());
+  }
+  if layout.unversioned {
+    println!("  v0  (unversioned, in the root)");
+  }
+  for version in &layout.versions {
+    let marker = if *version == DATA_VERSION { "  <- in use" } else { "" };
+    println!(
+      "  v{}  {}{}",
+      version,
+      data_dir::version_dir(&root, *version).display(),
+      marker
+    );
+  }
+
+  if layout.needs_legacy_import() {
+    println!();
+    println!("Unversioned data will be imported when the daemon starts, or now with `ccengram data import`.");
+    if let Some(err) = data_dir::legacy::last_error(&data_dir::version_dir(&root, DATA_VERSION)).await {
+      println!("The last import failed: {}", err.trim());
+    }
+  } else if let Err(e) = data_dir::check(&root).await {
+    println!();
+    println!("{}", e);
+  }
+
+  if let Some(report) = data_dir::legacy::last_report(&data_dir::version_dir(&root, DATA_VERSION)).await {
+    println!();
+    println!(
+      "Imported from unversioned data on {}:",
+      report.imported_at.format("%Y-%m-%d %H:%M")
+    );
+    print_legacy_report(&report);
+  }
+  Ok(())
+}
+
+/// Import an older data version into this build's version
+pub async fn cmd_data_import(from: Option<u32>, move_data: bool) -> Result<()> {
+  // The daemon holds databases open; copying under it could capture half a write
+  if ccengram::dirs::is_daemon_running() {
+    bail!("Stop the daemon first with `ccengram daemon --stop`");
+  }
+
+  let root = ccengram::dirs::default_data_root();
+  let layout = DataDirLayout::scan(&root).await?;
+  let from_legacy = match from {
+    Some(from) => from == 0,
+    None => layout.needs_legacy_import(),
+  };
+  if from_legacy {
+    let config = Config::load_global().await;
+    println!("Converting unversioned data; vectors of another width are re-embedded, which can take a while...");
+    let report = data_dir::legacy::import(&root, &config, None, move_data).await?;
+    println!(
+      "Imported into {}:",
+      data_dir::version_dir(&root, DATA_VERSION).display()
+    );
+    print_legacy_report(&report);
+    if move_data {
+      println!("Removed the unversioned data.");
+    }
+    return Ok(());
+  }
+
+  let dir = data_dir::import(&root, from, move_data).await?;
+  println!(
+    "{} data into {}",
+    if move_data { "Moved" } else { "Copied" },
+    dir.display()
+  );
+  Ok(())
+}
+
+/// Start an empty store for this build's version
+pub async fn cmd_data_init() -> Result<()> {
+  let root = ccengram::dirs::default_data_root();
+  let dir = data_dir::init(&root).await?;
+  println!("Created {}", dir.display());
+  Ok(())
+}
+
+/// Print what a legacy import migrated, per project
+fn print_legacy_report(report: &LegacyImportReport) {
+  for project in &report.projects {
+    println!("  project {}", project.id);
+    for table in &project.tables {
+      let reembedded = match table.reembedded {
+        0 => String::new(),
+        n => format!(
+          " ({} re-embedded, {} -> {} dimensions)",
+          n,
+          table.from_dimensions.map_or("none".to_string(), |d| d.to_string()),
+          report.dimensions
+        ),
+      };
+      println!("    {:<20} {:>8} rows{}", table.name, table.rows, reembedded);
+    }
+    for skipped in &project.skipped {
+      println!("    skipped {}", skipped);
+    }
+  }
+  if !report.copied.is_empty() {
+    println!("  copied {}", report.copied.join(", "));
+  }
+  println!(
+    "  {} rows in {} projects, {} re-embedded with {}",
+    report.rows(),
+    report.projects.len(),
+    report.reembedded(),
+    report.embedding_model
+  );
+}
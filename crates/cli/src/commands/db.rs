@@ -0,0 +1,91 @@
+//! Database maintenance commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::project::{ProjectRepairParams, ProjectRepairResult};
+use tracing::error;
+
+use super::cmd_index;
+use crate::{IndexCommand, display, table::Table};
+
+/// Restore tables quarantined because they could not be read.
+///
+/// Code, and docs that could not be salvaged, are re-indexed afterwards
+/// unless the result is printed as JSON.
+pub async fn cmd_db_repair(json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let result = match client.call(ProjectRepairParams).await {
+    Ok(result) => result,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    return Ok(());
+  }
+
+  if result.tables.is_empty() {
+    println!("No quarantined tables; the database is readable.");
+    return Ok(());
+  }
+  print_repair(&result);
+
+  if result.reindex_code {
+    println!("\nRe-indexing code...");
+    cmd_index(Some(IndexCommand::Code {
+      force: true,
+      stats: false,
+      since: None,
+      skipped: false,
+      plan: false,
+    }))
+    .await?;
+  }
+  if result.reingest_docs {
+    println!("\nRe-indexing docs...");
+    cmd_index(Some(IndexCommand::Docs {
+      directory: None,
+      force: true,
+      stats: false,
+    }))
+    .await?;
+  }
+  Ok(())
+}
+
+fn print_repair(result: &ProjectRepairResult) {
+  let mut table = Table::new(&["Table", "Quarantined", "Status", "Rows"]).right(3);
+  for item in &result.tables {
+    let status = match item.status.as_str() {
+      "salvaged" => format!("salvaged from version {}", item.salvaged_version.unwrap_or_default()),
+      "rebuilt" => "rebuilt".to_string(),
+      "reindex" => "re-indexing".to_string(),
+      _ => "not recoverable".to_string(),
+    };
+    let rows = item.salvaged_rows.map(display::count).unwrap_or_default();
+    table.row([
+      item.table.clone(),
+      display::timestamp(&item.quarantined_at),
+      status,
+      rows,
+    ]);
+  }
+  table.print();
+
+  for item in result.tables.iter().filter(|i| i.kept_at.is_some()) {
+    println!(
+      "\nNo version of {} could be read ({}). The copy is kept at {}",
+      item.table,
+      item.reason,
+      item.kept_at.as_deref().unwrap_or_default()
+    );
+  }
+}
@@ -0,0 +1,95 @@
+//! Entity commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::entity::EntityShowParams;
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// Show what the project's memories and code say about an entity
+pub async fn cmd_entities_show(entity: &str, limit: usize, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = EntityShowParams {
+    entity_id: entity.to_string(),
+    limit: Some(limit),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let entity = &result.entity;
+      println!("Entity:    {} ({})", entity.name, entity.entity_type);
+      println!("ID:        {}", entity.id);
+      println!("Mentions:  {}", display::count(entity.mentions));
+      if let Some(last_seen) = &entity.last_seen {
+        println!("Last seen: {}", display::timestamp(last_seen));
+      }
+
+      if !result.timeline.is_empty() {
+        println!();
+        println!("Timeline:");
+        for day in &result.timeline {
+          println!("  {}  {}", day.date, display::count(day.mentions));
+        }
+      }
+
+      if !result.co_occurring.is_empty() {
+        println!();
+        let mut table = Table::new(&["Mentioned with", "Type", "Shared"]).right(2);
+        for other in &result.co_occurring {
+          table.row([
+            other.name.clone(),
+            other.entity_type.clone(),
+            display::count(other.shared),
+          ]);
+        }
+        table.print();
+      }
+
+      if !result.memories.is_empty() {
+        println!();
+        let mut table = Table::new(&["Memory", "Sector", "Salience", "Content"]).right(2);
+        for memory in &result.memories {
+          let short_id = memory.id.get(..8).unwrap_or(&memory.id);
+          let text = memory.summary.as_deref().unwrap_or(&memory.content);
+          table.row([
+            short_id.to_string(),
+            memory.sector.clone(),
+            display::decimal(memory.salience as f64, 2),
+            text.lines().next().unwrap_or_default().to_string(),
+          ]);
+        }
+        table.print();
+      }
+
+      if !result.code.is_empty() {
+        println!();
+        let mut table = Table::new(&["Code", "Lines", "Symbol"]);
+        for chunk in &result.code {
+          table.row([
+            chunk.file_path.clone(),
+            format!("{}-{}", chunk.start_line, chunk.end_line),
+            chunk.symbol_name.clone().unwrap_or_default(),
+          ]);
+        }
+        table.print();
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
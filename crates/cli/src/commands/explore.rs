@@ -0,0 +1,239 @@
+//! Saved exploration commands
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ccengram::ipc::{
+  exploration::{
+    Exploration, ExplorationClearParams, ExplorationDeleteParams, ExplorationEntry, ExplorationExportParams,
+    ExplorationListParams, ExplorationNoteParams, ExplorationPinParams, ExplorationSaveParams,
+  },
+  search::ExploreParams,
+};
+use tracing::error;
+
+use crate::{display, table::Table};
+
+async fn connect() -> Result<ccengram::ipc::Client> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")
+}
+
+/// Run an explore query and record it in the draft exploration
+pub async fn cmd_explore_query(query: &str, scope: Option<String>, limit: usize, json_output: bool) -> Result<()> {
+  let client = connect().await?;
+
+  let params = ExploreParams {
+    query: query.to_string(),
+    scope,
+    expand_top: Some(0),
+    limit: Some(limit),
+    depth: None,
+    include_generated: None,
+    record: Some(true),
+  };
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+      if result.results.is_empty() {
+        println!("No results for: {}", query);
+        return Ok(());
+      }
+      for (i, item) in result.results.iter().enumerate() {
+        let location = match (&item.file_path, item.line) {
+          (Some(path), Some(line)) => format!("{}:{}", path, line),
+          (Some(path), None) => path.clone(),
+          _ => String::new(),
+        };
+        println!(
+          "{}. [{}] {} {}",
+          i + 1,
+          item.result_type,
+          &item.id[..8.min(item.id.len())],
+          location
+        );
+        println!("   {}", display::fit(&display::preview(&item.preview, 160), 3));
+        if let Some(more) = item.more_in_file {
+          println!("   +{} more in this file", more);
+        }
+      }
+      println!("\nRecorded in the draft exploration. Pin results with `ccengram explore pin <id>`.");
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Pin a memory, code chunk or document chunk to the draft exploration
+pub async fn cmd_explore_pin(id: &str, note: Option<String>) -> Result<()> {
+  let client = connect().await?;
+
+  match client
+    .call(ExplorationPinParams {
+      id: id.to_string(),
+      note,
+    })
+    .await
+  {
+    Ok(draft) => println!("Pinned {} ({})", id, counts(&draft)),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Add a note to the draft exploration
+pub async fn cmd_explore_note(text: &str) -> Result<()> {
+  let client = connect().await?;
+
+  match client.call(ExplorationNoteParams { text: text.to_string() }).await {
+    Ok(draft) => println!("Noted ({})", counts(&draft)),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Save the draft exploration under a name
+pub async fn cmd_explore_save(name: &str, force: bool) -> Result<()> {
+  let client = connect().await?;
+
+  let params = ExplorationSaveParams {
+    name: name.to_string(),
+    overwrite: Some(force),
+  };
+  match client.call(params).await {
+    Ok(saved) => {
+      println!("Saved exploration '{}' ({})", name, counts(&saved));
+      println!(
+        "Open it with `ccengram explore open {}` or export it with `ccengram explore export {}`.",
+        name, name
+      );
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// List saved explorations
+pub async fn cmd_explore_list(json_output: bool) -> Result<()> {
+  let client = connect().await?;
+
+  let explorations = match client.call(ExplorationListParams).await {
+    Ok(explorations) => explorations,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&explorations)?);
+    return Ok(());
+  }
+  if explorations.is_empty() {
+    println!("No saved explorations. Save the draft with `ccengram explore save <name>`.");
+    return Ok(());
+  }
+
+  let mut table = Table::new(&["Name", "Saved", "Queries", "Pins", "Notes"])
+    .right(2)
+    .right(3)
+    .right(4);
+  for e in &explorations {
+    table.row([
+      e.name.clone(),
+      e.saved_at.as_deref().map(display::timestamp).unwrap_or_default(),
+      display::count(e.queries),
+      display::count(e.pins),
+      display::count(e.notes),
+    ]);
+  }
+  table.print();
+
+  Ok(())
+}
+
+/// Print or write an exploration as Markdown
+pub async fn cmd_explore_export(name: Option<String>, output: Option<&Path>) -> Result<()> {
+  let client = connect().await?;
+
+  let result = match client.call(ExplorationExportParams { name }).await {
+    Ok(result) => result,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  match output {
+    Some(path) => {
+      std::fs::write(path, &result.markdown).with_context(|| format!("Failed to write {}", path.display()))?;
+      println!("Wrote {}", path.display());
+    }
+    None => print!("{}", result.markdown),
+  }
+
+  Ok(())
+}
+
+/// Delete a saved exploration
+pub async fn cmd_explore_delete(name: &str) -> Result<()> {
+  let client = connect().await?;
+
+  match client.call(ExplorationDeleteParams { name: name.to_string() }).await {
+    Ok(result) if result.deleted => println!("Deleted exploration '{}'", name),
+    Ok(_) => println!("No exploration named '{}'", name),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Discard the draft exploration
+pub async fn cmd_explore_clear() -> Result<()> {
+  let client = connect().await?;
+
+  match client.call(ExplorationClearParams).await {
+    Ok(result) if result.deleted => println!("Draft exploration discarded"),
+    Ok(_) => println!("The draft exploration is already empty"),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+fn counts(exploration: &Exploration) -> String {
+  let count = |f: fn(&ExplorationEntry) -> bool| exploration.entries.iter().filter(|e| f(e)).count();
+  format!(
+    "{} queries, {} pins, {} notes",
+    count(|e| matches!(e, ExplorationEntry::Query { .. })),
+    count(|e| matches!(e, ExplorationEntry::Pin { .. })),
+    count(|e| matches!(e, ExplorationEntry::Note { .. }))
+  )
+}
@@ -0,0 +1,177 @@
+//! Extraction debugging commands
+
+use anyhow::{Context, Result};
+use ccengram::ipc::memory::{ExtractionCaptureParams, ExtractionJobsParams, ExtractionRetryParams};
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// Show the prompts and raw LLM responses captured for an extraction segment
+pub async fn cmd_extract_inspect(segment_id: &str, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ExtractionCaptureParams {
+    segment_id: segment_id.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(capture) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&capture)?);
+        return Ok(());
+      }
+
+      println!("Extraction Segment");
+      println!("==================\n");
+
+      println!("Segment:  {}", capture.segment_id);
+      println!("Kind:     {}", capture.kind);
+      println!("Captured: {}", display::timestamp(&capture.captured_at));
+      if capture.memory_ids.is_empty() {
+        println!("Memories: (none)");
+      } else {
+        println!("Memories: {}", capture.memory_ids.join(", "));
+      }
+
+      for (i, exchange) in capture.exchanges.iter().enumerate() {
+        println!();
+        println!(
+          "--- Call {}/{} [{}] model={}{} ---",
+          i + 1,
+          capture.exchanges.len(),
+          exchange.task.as_deref().unwrap_or("untagged"),
+          exchange.model,
+          exchange
+            .duration_ms
+            .map(|ms| format!(" duration={}ms", ms))
+            .unwrap_or_default()
+        );
+        if exchange.truncated {
+          println!("(truncated to the capture size limit)");
+        }
+
+        if let Some(system) = &exchange.system_prompt {
+          println!("\n[system]\n{}", system);
+        }
+        println!("\n[prompt]\n{}", exchange.prompt);
+
+        match (&exchange.response, &exchange.error) {
+          (Some(response), _) => println!("\n[response]\n{}", response),
+          (None, Some(err)) => println!("\n[error]\n{}", err),
+          (None, None) => println!("\n[no response]"),
+        }
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// List failed extractions waiting in the retry queue
+pub async fn cmd_extract_failed(dead: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(ExtractionJobsParams { dead }).await {
+    Ok(jobs) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&jobs)?);
+        return Ok(());
+      }
+
+      if jobs.is_empty() {
+        println!("No failed extractions");
+        return Ok(());
+      }
+
+      let mut table = Table::new(&["Job", "Status", "Attempts", "Next attempt", "Error"]).right(2);
+      for job in &jobs {
+        let next_attempt = match job.status.as_str() {
+          "dead" => "-".to_string(),
+          _ => display::timestamp(&job.next_attempt_at),
+        };
+        table.row([
+          job.id.clone(),
+          job.status.clone(),
+          job.attempts.to_string(),
+          next_attempt,
+          job
+            .last_error
+            .as_deref()
+            .unwrap_or("-")
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        ]);
+      }
+      table.print();
+
+      let dead_count = jobs.iter().filter(|j| j.status == "dead").count();
+      if dead_count > 0 {
+        println!();
+        println!(
+          "{} dead-lettered; run `ccengram extract retry` to try again",
+          display::count(dead_count)
+        );
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Retry failed extractions now, reviving dead-lettered ones
+pub async fn cmd_extract_retry(ids: Vec<String>, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(ExtractionRetryParams { ids }).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      if result.attempted == 0 {
+        println!("No failed extractions to retry");
+        return Ok(());
+      }
+
+      println!(
+        "Retried {} extractions: {} succeeded, {} failed",
+        display::count(result.attempted),
+        display::count(result.succeeded),
+        display::count(result.attempted - result.succeeded)
+      );
+      println!("Memories created: {}", display::count(result.memories_created.len()));
+      if result.dead > 0 {
+        println!(
+          "{} still failing and dead-lettered; see `ccengram extract failed`",
+          display::count(result.dead)
+        );
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
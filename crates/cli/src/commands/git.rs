@@ -0,0 +1,90 @@
+//! Git integration commands
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+/// Marker identifying hooks written by ccengram
+const HOOK_MARKER: &str = "# ccengram: reindex changed files";
+
+/// Hook name and the revision passed to `index changed --since`
+const HOOKS: &[(&str, &str)] = &[
+  ("post-commit", "HEAD~1"),
+  // First argument is the previous HEAD
+  ("post-checkout", "\"$1\""),
+  ("post-merge", "ORIG_HEAD"),
+];
+
+/// Install git hooks that reindex changed files after commit, checkout and merge
+pub async fn cmd_git_install_hooks(force: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let hooks_dir = hooks_dir(&cwd).await?;
+  tokio::fs::create_dir_all(&hooks_dir)
+    .await
+    .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+
+  let mut skipped = Vec::new();
+  for (name, since) in HOOKS {
+    let path = hooks_dir.join(name);
+    if let Ok(existing) = tokio::fs::read_to_string(&path).await
+      && !existing.contains(HOOK_MARKER)
+      && !force
+    {
+      skipped.push(*name);
+      continue;
+    }
+
+    tokio::fs::write(&path, hook_script(since))
+      .await
+      .with_context(|| format!("Failed to write {}", path.display()))?;
+    make_executable(&path).await?;
+    println!("Installed {}", path.display());
+  }
+
+  if !skipped.is_empty() {
+    println!();
+    println!(
+      "Skipped existing hooks: {}. Use --force to replace them.",
+      skipped.join(", ")
+    );
+  }
+
+  Ok(())
+}
+
+fn hook_script(since: &str) -> String {
+  // Runs in the background so git is never slowed down or failed by indexing
+  format!("#!/bin/sh\n{HOOK_MARKER}\n(ccengram index changed --since {since} >/dev/null 2>&1 &) || true\n")
+}
+
+/// Hooks directory for the repository, honoring worktrees and core.hooksPath
+async fn hooks_dir(cwd: &Path) -> Result<PathBuf> {
+  let output = tokio::process::Command::new("git")
+    .arg("-C")
+    .arg(cwd)
+    .args(["rev-parse", "--git-path", "hooks"])
+    .output()
+    .await
+    .context("Failed to run git")?;
+
+  if !output.status.success() {
+    bail!("Not a git repository: {}", cwd.display());
+  }
+
+  let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+  Ok(if path.is_absolute() { path } else { cwd.join(path) })
+}
+
+#[cfg(unix)]
+async fn make_executable(path: &Path) -> Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+
+  tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+    .await
+    .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+async fn make_executable(_path: &Path) -> Result<()> {
+  Ok(())
+}
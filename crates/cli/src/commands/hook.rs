@@ -2,11 +2,62 @@
 //!
 //! Hooks are fire-and-forget: we send the request to the daemon and exit immediately
 //! without waiting for a response. This ensures hooks don't block Claude Code.
+//!
+//! SessionStart and PreToolUse are the exceptions: they wait briefly for the
+//! memories the daemon picks (for the session, or about the file a tool is
+//! about to edit) and print them as `additionalContext`. UserPromptSubmit does
+//! the same when the project's `[injection]` config injects memories with
+//! prompts. A daemon too slow to answer still handles the event; Claude Code
+//! just goes on without them.
+//!
+//! PostToolUse fires for every tool call, so it is batched: each event is
+//! appended to a per-session buffer, and the hook that finds the buffer empty
+//! waits [`BATCH_WINDOW`] and sends everything buffered by then as one
+//! request. Any other event of the session takes the buffered events with it,
+//! so the daemon still sees them in order.
+//!
+//! If the daemon can't be reached, the event is appended to the offline spool
+//! and replayed by the daemon on its next start. PreToolUse is not spooled:
+//! it records nothing, and a warning is no use once the edit has happened.
 
-use std::io::Read;
+use std::{io::Read, path::PathBuf, time::Duration};
 
 use anyhow::{Context, Result};
-use ccengram::ipc::hook::HookParams;
+use ccengram::{
+  config::Config,
+  ipc::{
+    IpcError, batch,
+    hook::{HookBatchParams, HookParams},
+    spool,
+  },
+};
+
+/// Longest SessionStart waits for its context before the session starts without it
+const SESSION_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest PreToolUse waits for its warnings before the tool runs without them
+const PRE_TOOL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest UserPromptSubmit waits for memories about the prompt before it is sent without them
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long the hook that starts a batch waits for more events before sending it
+const BATCH_WINDOW: Duration = Duration::from_millis(100);
+
+/// Hooks that wait for `additionalContext`: Claude Code's event name and how long to wait
+fn context_hook(name: &str) -> Option<(&'static str, Duration)> {
+  match name {
+    "SessionStart" | "session-start" => Some(("SessionStart", SESSION_START_TIMEOUT)),
+    "PreToolUse" | "pre-tool" | "pre-tool-use" => Some(("PreToolUse", PRE_TOOL_TIMEOUT)),
+    "UserPromptSubmit" | "user-prompt" | "user-prompt-submit" => Some(("UserPromptSubmit", PROMPT_TIMEOUT)),
+    _ => None,
+  }
+}
+
+/// Hooks buffered and sent in batches
+fn is_batched(name: &str) -> bool {
+  matches!(name, "PostToolUse" | "post-tool" | "post-tool-use")
+}
 
 /// Read hook input from stdin (JSON parameters from Claude Code)
 fn read_hook_input() -> Result<serde_json::Value> {
@@ -59,18 +110,141 @@ pub async fn cmd_hook(name: &str) -> Result<()> {
     .map(std::path::PathBuf::from)
     .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from(".")));
 
+  let context = match context_hook(name) {
+    // Prompts only wait when some memories are injected with them
+    Some(("UserPromptSubmit", _)) if !Config::load_for_project(&cwd_path).await.injection.prompt_enabled() => None,
+    context => context,
+  };
+
+  if let Some((event, timeout)) = context {
+    // Tool uses still buffered from the last turn go first
+    if event == "UserPromptSubmit"
+      && let Some(session_id) = &params.session_id
+    {
+      let path = batch::buffer_path(&ccengram::dirs::default_batch_dir(), session_id);
+      match batch::drain(&path, false).await {
+        Ok(events) if !events.is_empty() => send_events(cwd_path.clone(), events).await,
+        Ok(_) => {}
+        Err(e) => eprintln!("ccengram: failed to read hook batch: {}", e),
+      }
+    }
+
+    // Auto-start daemon if not running
+    let client = match ccengram::Daemon::connect_or_start(cwd_path).await {
+      Ok(c) => c,
+      Err(e) => {
+        eprintln!("ccengram: failed to start daemon: {}", e);
+        if event != "PreToolUse" {
+          spool_hook(params).await;
+        }
+        return Ok(());
+      }
+    };
+    return call_for_context(&client, params, event, timeout).await;
+  }
+
+  let mut events = Vec::new();
+  if let Some(session_id) = &params.session_id {
+    let path = batch::buffer_path(&ccengram::dirs::default_batch_dir(), session_id);
+    if is_batched(name) {
+      return buffer_hook(&path, params, cwd_path).await;
+    }
+    // Events still buffered for the session go first
+    let session_end = matches!(name, "SessionEnd" | "session-end");
+    events = batch::drain(&path, session_end).await.unwrap_or_else(|e| {
+      eprintln!("ccengram: failed to read hook batch: {}", e);
+      Vec::new()
+    });
+  }
+  events.push(params);
+
+  send_events(cwd_path, events).await;
+  Ok(())
+}
+
+/// Buffer a batched event; the hook that finds the buffer empty sends the batch
+async fn buffer_hook(path: &std::path::Path, params: HookParams, cwd: PathBuf) -> Result<()> {
+  match batch::append(path, &params).await {
+    Ok(false) => return Ok(()),
+    Ok(true) => {}
+    Err(e) => {
+      eprintln!("ccengram: failed to buffer hook event: {}", e);
+      send_events(cwd, vec![params]).await;
+      return Ok(());
+    }
+  }
+
+  tokio::time::sleep(BATCH_WINDOW).await;
+  match batch::drain(path, false).await {
+    Ok(events) if !events.is_empty() => send_events(cwd, events).await,
+    Ok(_) => {}
+    Err(e) => eprintln!("ccengram: failed to read hook batch: {}", e),
+  }
+  Ok(())
+}
+
+/// Send events without waiting for a response, as one batch when there are
+/// several, spooling them if the daemon can't be reached
+async fn send_events(cwd: PathBuf, events: Vec<HookParams>) {
   // Auto-start daemon if not running
-  let client = match ccengram::Daemon::connect_or_start(cwd_path).await {
+  let client = match ccengram::Daemon::connect_or_start(cwd).await {
     Ok(c) => c,
     Err(e) => {
       eprintln!("ccengram: failed to start daemon: {}", e);
-      return Ok(());
+      for params in events {
+        spool_hook(params).await;
+      }
+      return;
     }
   };
 
-  if let Err(e) = client.fire_and_forget(params).await {
+  let sent = match events.as_slice() {
+    [params] => client.fire_and_forget(params.clone()).await,
+    _ => client.fire_and_forget(HookBatchParams { events: events.clone() }).await,
+  };
+  if let Err(e) = sent {
     eprintln!("ccengram: hook send failed: {}", e);
+    for params in events {
+      spool_hook(params).await;
+    }
   }
+}
 
+/// Send a hook event and print the context it returns in Claude Code's hook output format
+async fn call_for_context(
+  client: &ccengram::ipc::Client,
+  params: HookParams,
+  event: &str,
+  timeout: Duration,
+) -> Result<()> {
+  let result = match tokio::time::timeout(timeout, client.call(params.clone())).await {
+    Ok(Ok(result)) => result,
+    Ok(Err(IpcError::Connection(e))) => {
+      eprintln!("ccengram: hook send failed: {}", e);
+      if event != "PreToolUse" {
+        spool_hook(params).await;
+      }
+      return Ok(());
+    }
+    // The daemon got the event; it only failed or was slow to answer
+    Ok(Err(_)) | Err(_) => return Ok(()),
+  };
+
+  if let Some(context) = result.data.get("additional_context").and_then(|v| v.as_str()) {
+    let output = serde_json::json!({
+      "hookSpecificOutput": {
+        "hookEventName": event,
+        "additionalContext": context,
+      }
+    });
+    println!("{}", output);
+  }
   Ok(())
 }
+
+/// Queue a hook event for replay when the daemon next starts
+async fn spool_hook(params: HookParams) {
+  if let Err(e) = spool::append(&ccengram::dirs::default_spool_path(), params).await {
+    eprintln!("ccengram: failed to spool hook event: {}", e);
+  }
+}
@@ -5,25 +5,43 @@ use std::{collections::HashMap, io::IsTerminal, path::Path};
 use anyhow::{Context, Result};
 use ccengram::ipc::{
   StreamUpdate,
-  code::{CodeIndexParams, CodeIndexResult, CodeStatsParams},
+  code::{
+    CodeIndexChangedParams, CodeIndexParams, CodeIndexPlanParams, CodeIndexResult, CodeSkippedParams, CodeStatsParams,
+  },
   docs::{DocsIngestFullResult, DocsIngestParams},
   system::ProjectStatsParams,
 };
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use tracing::error;
 
-use crate::IndexCommand;
+use crate::{IndexCommand, display, table::Table};
 
 /// Manage code and document index
 pub async fn cmd_index(command: Option<IndexCommand>) -> Result<()> {
   match command {
-    Some(IndexCommand::Code { force, stats }) => cmd_index_code(force, stats).await,
+    Some(IndexCommand::Code {
+      force,
+      stats,
+      since,
+      skipped,
+      plan,
+    }) => {
+      if skipped {
+        cmd_index_skipped().await
+      } else if plan {
+        cmd_index_plan(since).await
+      } else {
+        cmd_index_code(force, stats, since).await
+      }
+    }
     Some(IndexCommand::Docs {
       directory,
       force,
       stats,
     }) => cmd_index_docs_impl(directory.as_deref(), force, stats).await,
     Some(IndexCommand::File { path, title, force }) => cmd_index_file(&path, title.as_deref(), force).await,
+    Some(IndexCommand::Url { url, depth }) => cmd_index_url(&url, depth).await,
+    Some(IndexCommand::Changed { since }) => cmd_index_changed(&since).await,
     None => {
       // Default: index code, and also docs if docs.directories is configured
       cmd_index_all(false).await
@@ -49,7 +67,11 @@ async fn cmd_index_all(force: bool) -> Result<()> {
     println!();
   }
 
-  let code_params = CodeIndexParams { force, stream: true };
+  let code_params = CodeIndexParams {
+    force,
+    stream: true,
+    since: None,
+  };
 
   let code_result = run_with_progress(&client, code_params, is_tty).await?;
   print_code_result(&code_result);
@@ -67,6 +89,8 @@ async fn cmd_index_all(force: bool) -> Result<()> {
       let docs_params = DocsIngestParams {
         directory: Some(docs_dir.clone()),
         file: None,
+        url: None,
+        depth: None,
         stream: true,
       };
 
@@ -157,14 +181,31 @@ fn print_code_result(result: &CodeIndexResult) {
   println!("Code indexing complete:");
   println!(
     "  Files: {} scanned, {} indexed",
-    result.files_scanned, result.files_indexed
+    display::count(result.files_scanned),
+    display::count(result.files_indexed)
   );
-  println!("  Chunks: {}", result.chunks_created);
+  println!("  Chunks: {}", display::count(result.chunks_created));
   println!(
-    "  Time: {:.1}s ({:.1} files/sec)",
-    result.total_duration_ms as f64 / 1000.0,
-    result.files_per_second
+    "  Time: {}s ({} files/sec)",
+    display::decimal(result.total_duration_ms as f64 / 1000.0, 1),
+    display::decimal(result.files_per_second, 1)
   );
+  if !result.decode_warnings.is_empty() {
+    println!(
+      "  Decoded best-effort ({} files not valid UTF-8):",
+      result.decode_warnings.len()
+    );
+    for warning in &result.decode_warnings {
+      if warning.replaced > 0 {
+        println!(
+          "    {} ({}, {} invalid sequences replaced)",
+          warning.file_path, warning.encoding, warning.replaced
+        );
+      } else {
+        println!("    {} ({})", warning.file_path, warning.encoding);
+      }
+    }
+  }
 }
 
 /// Print docs ingest result summary
@@ -172,13 +213,14 @@ fn print_docs_result(result: &DocsIngestFullResult) {
   println!("Document indexing complete:");
   println!(
     "  Files: {} scanned, {} ingested",
-    result.files_scanned, result.files_ingested
+    display::count(result.files_scanned),
+    display::count(result.files_ingested)
   );
-  println!("  Chunks: {}", result.chunks_created);
+  println!("  Chunks: {}", display::count(result.chunks_created));
   println!(
-    "  Time: {:.1}s ({:.1} files/sec)",
-    result.total_duration_ms as f64 / 1000.0,
-    result.files_per_second
+    "  Time: {}s ({} files/sec)",
+    display::decimal(result.total_duration_ms as f64 / 1000.0, 1),
+    display::decimal(result.files_per_second, 1)
   );
 }
 
@@ -214,6 +256,8 @@ pub async fn cmd_index_file(path: &str, title: Option<&str>, _force: bool) -> Re
     let params = DocsIngestParams {
       directory: None,
       file: Some(abs_path.to_string_lossy().to_string()),
+      url: None,
+      depth: None,
       stream: false,
     };
 
@@ -236,6 +280,7 @@ pub async fn cmd_index_file(path: &str, title: Option<&str>, _force: bool) -> Re
     let params = CodeIndexParams {
       force: true,
       stream: true,
+      since: None,
     };
 
     let is_tty = std::io::stdout().is_terminal();
@@ -256,6 +301,76 @@ pub async fn cmd_index_file(path: &str, title: Option<&str>, _force: bool) -> Re
   Ok(())
 }
 
+/// Fetch a web page (and same-domain links up to `depth`) and index it as documents
+/// Reindex files changed since a commit (run by git hooks)
+async fn cmd_index_changed(since: &str) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = CodeIndexChangedParams {
+    since: since.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      println!(
+        "Queued {} changed files, removed {} deleted files (since {})",
+        result.files_queued, result.files_deleted, result.since
+      );
+    }
+    Err(e) => {
+      error!("Index error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+pub async fn cmd_index_url(url: &str, depth: usize) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let is_tty = std::io::stdout().is_terminal();
+
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  println!("Indexing {}...", url);
+  if is_tty {
+    println!();
+  }
+
+  let params = DocsIngestParams {
+    directory: None,
+    file: None,
+    url: Some(url.to_string()),
+    depth: Some(depth),
+    stream: true,
+  };
+
+  match run_with_progress(&client, params, is_tty).await {
+    Ok(result) => {
+      println!("URL indexing complete:");
+      println!(
+        "  Pages: {} fetched, {} ingested, {} failed",
+        result.files_scanned, result.files_ingested, result.failed_files
+      );
+      println!("  Chunks: {}", result.chunks_created);
+      for page in &result.results {
+        println!("  - {} ({} chunks)", page.source, page.chunks_created);
+      }
+    }
+    Err(e) => {
+      error!("Index error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
 /// Index documents from a directory (internal impl)
 pub async fn cmd_index_docs_impl(directory: Option<&str>, _force: bool, stats: bool) -> Result<()> {
   use ccengram::config::Config;
@@ -322,6 +437,8 @@ pub async fn cmd_index_docs_impl(directory: Option<&str>, _force: bool, stats: b
     let params = DocsIngestParams {
       directory: Some(docs_dir_str.clone()),
       file: None,
+      url: None,
+      depth: None,
       stream: true,
     };
 
@@ -339,8 +456,118 @@ pub async fn cmd_index_docs_impl(directory: Option<&str>, _force: bool, stats: b
   Ok(())
 }
 
+/// List files the indexing guard rails left out
+pub async fn cmd_index_skipped() -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(CodeSkippedParams).await {
+    Ok(files) => {
+      if files.is_empty() {
+        println!("No files skipped.");
+        return Ok(());
+      }
+
+      let mut table = Table::new(&["File", "Reason", "Detail", "Skipped"]);
+      for file in &files {
+        table.row([
+          file.file_path.clone(),
+          file.reason.clone(),
+          file.detail.clone(),
+          display::timestamp(&file.skipped_at),
+        ]);
+      }
+      table.print();
+      println!();
+      println!(
+        "{} files skipped. Limits are set in the [index] config section.",
+        display::count(files.len())
+      );
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Estimate an index run without embedding or writing anything
+pub async fn cmd_index_plan(since: Option<String>) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(CodeIndexPlanParams { since }).await {
+    Ok(plan) => {
+      println!("Index Plan");
+      println!("==========");
+      println!();
+      println!("Files Scanned: {}", display::count(plan.files_scanned));
+      println!("Files to Index: {}", display::count(plan.files_to_index));
+      println!("Files Unchanged: {}", display::count(plan.files_unchanged));
+      if plan.files_shallow > 0 {
+        println!(
+          "Files Shallow: {} (definitions only)",
+          display::count(plan.files_shallow)
+        );
+      }
+      println!("Files Skipped: {}", display::count(plan.skipped.len()));
+      if plan.failed_files > 0 {
+        println!("Files Unreadable: {}", display::count(plan.failed_files));
+      }
+      println!(
+        "Chunks: {} ({} to embed, {} reused)",
+        display::count(plan.chunks),
+        display::count(plan.chunks_to_embed),
+        display::count(plan.chunks_reused)
+      );
+      println!("Estimated Tokens: {}", display::count(plan.estimated_tokens));
+      match plan.estimated_cost_usd {
+        Some(cost) => println!("Estimated Cost: ${}", display::decimal(cost, 4)),
+        None => println!("Estimated Cost: unknown (set embedding.cost_per_million_tokens)"),
+      }
+
+      if !plan.languages.is_empty() {
+        println!();
+        println!("Chunks to Embed by Language:");
+        let mut sorted: Vec<_> = plan.languages.iter().collect();
+        sorted.sort_by(|a, b| b.1.cmp(a.1));
+        for (lang, count) in sorted {
+          println!("  {}: {}", lang, display::count(*count));
+        }
+      }
+
+      if !plan.skipped.is_empty() {
+        println!();
+        let mut table = Table::new(&["Skipped", "Reason", "Detail"]);
+        for file in &plan.skipped {
+          table.row([file.file_path.clone(), file.reason.clone(), file.detail.clone()]);
+        }
+        table.print();
+      }
+
+      println!();
+      println!(
+        "Planned in {}ms. Nothing was embedded or written.",
+        display::count(plan.duration_ms)
+      );
+    }
+    Err(e) => {
+      error!("Plan error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
 /// Index code files
-pub async fn cmd_index_code(force: bool, stats: bool) -> Result<()> {
+pub async fn cmd_index_code(force: bool, stats: bool, since: Option<String>) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
   let client = ccengram::Daemon::connect_or_start(cwd.clone())
     .await
@@ -355,18 +582,22 @@ pub async fn cmd_index_code(force: bool, stats: bool) -> Result<()> {
         println!();
 
         println!("Health Score: {}%", result.index_health_score);
-        println!("Files Indexed: {}", result.total_files);
-        println!("Total Chunks: {}", result.total_chunks);
-        println!("Estimated Tokens: {}", result.total_tokens_estimate);
-        println!("Total Lines: {}", result.total_lines);
-        println!("Avg Chunks/File: {:.1}", result.average_chunks_per_file);
+        println!("Files Indexed: {}", display::count(result.total_files));
+        println!("Total Chunks: {}", display::count(result.total_chunks));
+        println!("Estimated Tokens: {}", display::count(result.total_tokens_estimate));
+        println!("Total Lines: {}", display::count(result.total_lines));
+        println!(
+          "Avg Chunks/File: {}",
+          display::decimal(result.average_chunks_per_file as f64, 1)
+        );
 
+        // Largest first; the map is ordered by name, so ties stay in name order
         println!();
         println!("Language Breakdown:");
         let mut sorted: Vec<_> = result.language_breakdown.iter().collect();
         sorted.sort_by(|a, b| b.1.cmp(a.1));
         for (lang, count) in sorted {
-          println!("  {}: {}", lang, count);
+          println!("  {}: {}", lang, display::count(*count));
         }
 
         println!();
@@ -374,7 +605,19 @@ pub async fn cmd_index_code(force: bool, stats: bool) -> Result<()> {
         let mut sorted: Vec<_> = result.chunk_type_breakdown.iter().collect();
         sorted.sort_by(|a, b| b.1.cmp(a.1));
         for (ctype, count) in sorted {
-          println!("  {}: {}", ctype, count);
+          println!("  {}: {}", ctype, display::count(*count));
+        }
+
+        if let Some(reindex) = result.reindex.filter(|r| r.files > 0) {
+          println!();
+          println!("Reindexing (this session):");
+          println!(
+            "  Files Parsed: {} ({} incremental)",
+            reindex.files, reindex.incremental_files
+          );
+          println!("  Chunks Embedded: {}", reindex.chunks_embedded);
+          println!("  Embeddings Saved: {}", reindex.embeddings_reused);
+          println!("  Unchanged by Edits: {}", reindex.ast_unchanged_chunks);
         }
       }
       Err(e) => {
@@ -389,13 +632,20 @@ pub async fn cmd_index_code(force: bool, stats: bool) -> Result<()> {
   let is_tty = std::io::stdout().is_terminal();
   let cwd_str = cwd.to_string_lossy().to_string();
 
-  println!("Indexing code in {}...", cwd_str);
+  match &since {
+    Some(rev) => println!("Indexing code changed since {} in {}...", rev, cwd_str),
+    None => println!("Indexing code in {}...", cwd_str),
+  }
 
   if is_tty {
     println!();
   }
 
-  let params = CodeIndexParams { force, stream: true };
+  let params = CodeIndexParams {
+    force,
+    stream: true,
+    since,
+  };
 
   match run_with_progress(&client, params, is_tty).await {
     Ok(result) => {
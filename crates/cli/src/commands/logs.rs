@@ -8,13 +8,31 @@ use std::{
 
 use anyhow::{Context, Result};
 
+use crate::table::Table;
+
 /// Get the log directory path (logs are stored in the data directory)
 fn log_dir() -> PathBuf {
   ccengram::dirs::default_data_dir()
 }
 
+/// Log file name for a `--source`
+fn log_name(source: Option<&str>) -> &'static str {
+  match source {
+    Some("hooks") => "ccengram-hooks.log",
+    Some("scheduler") => "ccengram-scheduler.log",
+    _ => "ccengram.log",
+  }
+}
+
 /// View daemon logs
-pub fn cmd_logs(follow: bool, lines: usize, date: Option<&str>, level: Option<&str>, open: bool) -> Result<()> {
+pub fn cmd_logs(
+  follow: bool,
+  lines: usize,
+  date: Option<&str>,
+  level: Option<&str>,
+  source: Option<&str>,
+  open: bool,
+) -> Result<()> {
   let log_directory = log_dir();
 
   // Handle --open flag: open log directory in file manager
@@ -37,10 +55,11 @@ pub fn cmd_logs(follow: bool, lines: usize, date: Option<&str>, level: Option<&s
   }
 
   // Determine which log file to read
+  let name = log_name(source);
   let log_file = if let Some(d) = date {
-    log_directory.join(format!("ccengram.log.{}", d))
+    log_directory.join(format!("{}.{}", name, d))
   } else {
-    log_directory.join("ccengram.log")
+    log_directory.join(name)
   };
 
   // Check if log file exists
@@ -57,16 +76,16 @@ pub fn cmd_logs(follow: bool, lines: usize, date: Option<&str>, level: Option<&s
 
       // List available log files
       if let Ok(entries) = std::fs::read_dir(&log_directory) {
-        let mut found_logs = false;
-        for entry in entries.flatten() {
-          let name = entry.file_name();
-          let name_str = name.to_string_lossy();
-          if name_str.starts_with("ccengram") && name_str.contains("log") {
-            println!("  {}", name_str);
-            found_logs = true;
-          }
+        let mut logs: Vec<String> = entries
+          .flatten()
+          .map(|entry| entry.file_name().to_string_lossy().into_owned())
+          .filter(|name| name.starts_with("ccengram") && name.contains("log"))
+          .collect();
+        logs.sort();
+        for name in &logs {
+          println!("  {}", name);
         }
-        if !found_logs {
+        if logs.is_empty() {
           println!("  (none)");
         }
       }
@@ -189,12 +208,13 @@ pub fn cmd_logs_list() -> Result<()> {
   });
 
   println!("Log Files:");
+  let mut table = Table::new(&["File", "Size"]).right(1).flex(0).indent(2);
   for entry in log_files {
     let name = entry.file_name();
     let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
-    let size_str = format_size(size);
-    println!("  {:40} {}", name.to_string_lossy(), size_str);
+    table.row([name.to_string_lossy().to_string(), format_size(size)]);
   }
+  table.print();
 
   Ok(())
 }
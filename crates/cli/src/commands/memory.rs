@@ -1,9 +1,19 @@
-//! Memory management commands (show, delete, deleted)
+//! Memory management commands (show, delete, deleted, history, undo, extract, goals, retro)
 
-use anyhow::{Context, Result};
-use ccengram::ipc::memory::{MemoryDeleteParams, MemoryGetParams, MemoryListDeletedParams, MemoryRestoreParams};
+use std::{
+  io::{IsTerminal, Read},
+  path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use ccengram::ipc::memory::{
+  GoalUpdateParams, GoalsParams, MemoryDeleteParams, MemoryExtractParams, MemoryGetParams, MemoryHistoryParams,
+  MemoryListDeletedParams, MemoryRestoreParams, MemoryUndoParams, RetroParams,
+};
 use tracing::error;
 
+use crate::{display, table::Table};
+
 /// Show detailed memory by ID
 pub async fn cmd_show(memory_id: &str, related: bool, json_output: bool) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
@@ -33,13 +43,29 @@ pub async fn cmd_show(memory_id: &str, related: bool, json_output: bool) -> Resu
       if let Some(mem_type) = &memory.memory_type {
         println!("Type:     {}", mem_type);
       }
+      if let Some(status) = &memory.goal_status {
+        println!("Status:   {}", status);
+      }
+      if let Some(due) = &memory.due {
+        println!("Due:      {}", due);
+      }
+      println!("Visibility: {}", memory.visibility);
       println!("Salience: {:.2}", memory.salience);
       println!("Importance: {:.2}", memory.importance);
-      println!("Created:  {}", memory.created_at);
-      println!("Accessed: {}", memory.last_accessed);
+      println!("Created:  {}", display::timestamp(&memory.created_at));
+      println!("Accessed: {}", display::timestamp(&memory.last_accessed));
       if let Some(superseded) = &memory.superseded_by {
         println!("Superseded by: {}", superseded);
       }
+      if memory.stale {
+        println!(
+          "Stale:    {}",
+          memory.stale_reason.as_deref().unwrap_or("referenced code changed")
+        );
+      }
+      if let Some(segment) = &memory.segment_id {
+        println!("Segment:  {}", segment);
+      }
 
       println!();
 
@@ -50,6 +76,30 @@ pub async fn cmd_show(memory_id: &str, related: bool, json_output: bool) -> Resu
         println!("\nTags: {}", memory.tags.join(", "));
       }
 
+      if let Some(anchors) = &memory.file_anchors
+        && !anchors.is_empty()
+      {
+        if memory.possibly_stale == Some(true) {
+          println!("\nReferenced code has changed since this memory was created; it may be stale.");
+        }
+        println!("\nReferenced Files ({}):", anchors.len());
+        for anchor in anchors {
+          let lines = match (anchor.start_line, anchor.end_line) {
+            (Some(start), Some(end)) if start == end => format!(":{}", start),
+            (Some(start), Some(end)) => format!(":{}-{}", start, end),
+            _ => String::new(),
+          };
+          let short = &anchor.commit[..anchor.commit.len().min(8)];
+          let author = anchor.author.as_deref().unwrap_or("unknown");
+          let status = match (&anchor.current_commit, anchor.changed) {
+            (None, _) => "missing".to_string(),
+            (Some(current), true) => format!("changed in {}", &current[..current.len().min(8)]),
+            (Some(_), false) => "unchanged".to_string(),
+          };
+          println!("  - {}{} ({} by {}) {}", anchor.path, lines, short, author, status);
+        }
+      }
+
       if related
         && let Some(relationships) = &memory.relationships
         && !relationships.is_empty()
@@ -146,26 +196,366 @@ pub async fn cmd_deleted(limit: usize, json_output: bool) -> Result<()> {
         return Ok(());
       }
 
-      println!("Deleted Memories ({}):", memories.len());
+      let mut memories = memories;
+      memories.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+
+      println!("Deleted Memories ({}):", display::count(memories.len()));
+      println!();
+
+      let mut table = Table::new(&["ID", "Sector", "Created", "Content"]);
+      for mem in &memories {
+        table.row([
+          mem.id.clone(),
+          mem.sector.clone(),
+          display::timestamp(&mem.created_at),
+          mem.content.replace('\n', " "),
+        ]);
+      }
+      table.print();
+      println!();
+
+      println!("Use 'ccengram memory restore <id>' to restore a memory.");
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// List goal memories, open and blocked ones unless a status is given
+pub async fn cmd_goals(status: Option<String>, limit: usize, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = GoalsParams {
+    status,
+    limit: Some(limit),
+  };
+
+  match client.call(params).await {
+    Ok(goals) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&goals)?);
+        return Ok(());
+      }
+
+      if goals.is_empty() {
+        println!("No goals found.");
+        return Ok(());
+      }
+
+      println!("Goals ({}):", display::count(goals.len()));
+      println!();
+
+      let mut table = Table::new(&["ID", "Status", "Due", "Created", "Goal"]);
+      for goal in &goals {
+        table.row([
+          goal.id.clone(),
+          goal.goal_status.clone().unwrap_or_else(|| "open".to_string()),
+          goal.due.clone().unwrap_or_else(|| "-".to_string()),
+          display::timestamp(&goal.created_at),
+          goal.content.replace('\n', " "),
+        ]);
+      }
+      table.print();
       println!();
 
-      for (i, mem) in memories.iter().enumerate() {
-        // Truncate content for preview
-        let preview: String = mem.content.chars().take(60).collect();
-        let preview = preview.replace('\n', " ");
-        let preview = if mem.content.len() > 60 {
-          format!("{}...", preview)
+      println!("Use 'ccengram memory goal <id> --status done' to close a goal.");
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Show every recorded change to a memory
+pub async fn cmd_history(memory_id: &str, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = MemoryHistoryParams {
+    memory_id: memory_id.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(history) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&history)?);
+        return Ok(());
+      }
+
+      if history.events.is_empty() {
+        println!("No changes recorded for {}.", history.memory_id);
+        return Ok(());
+      }
+
+      println!(
+        "History of {} ({} changes):",
+        history.memory_id,
+        display::count(history.events.len())
+      );
+      println!();
+
+      let salience = |s: Option<f32>| s.map(|s| format!("{:.2}", s)).unwrap_or_else(|| "-".to_string());
+      let mut table = Table::new(&["Time", "Action", "Actor", "Salience", "Detail"]);
+      for event in &history.events {
+        let change = match (event.salience_before, event.salience_after) {
+          (None, None) => "-".to_string(),
+          (before, after) => format!("{} -> {}", salience(before), salience(after)),
+        };
+        table.row([
+          display::timestamp(&event.created_at),
+          event.action.replace('_', " "),
+          event.actor.clone(),
+          change,
+          event.detail.clone().unwrap_or_default(),
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Revert the most recent batch of deletes and supersessions
+pub async fn cmd_undo(dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(MemoryUndoParams { dry_run }).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let Some(created_at) = result.created_at.as_deref() else {
+        println!("Nothing to undo.");
+        return Ok(());
+      };
+
+      println!(
+        "{} {} changes made by {} at {}:",
+        if result.dry_run { "Would revert" } else { "Reverted" },
+        display::count(result.reverted.len()),
+        result.actor.as_deref().unwrap_or("unknown"),
+        display::timestamp(created_at)
+      );
+      println!();
+
+      let mut table = Table::new(&["ID", "Undid", "Salience", "Content"]);
+      for item in &result.reverted {
+        let short_id = if item.memory_id.len() > 8 {
+          &item.memory_id[..8]
         } else {
-          preview
+          &item.memory_id
         };
+        table.row([
+          short_id.to_string(),
+          item.action.clone(),
+          format!("{:.2}", item.salience),
+          item
+            .content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(60)
+            .collect(),
+        ]);
+      }
+      table.print();
 
-        println!("{}. [{}] {}", i + 1, mem.sector, mem.id);
-        println!("   {}", preview);
-        println!("   Created: {}", mem.created_at);
+      if result.dry_run {
         println!();
+        println!("Dry run - no changes made");
       }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
 
-      println!("Use 'ccengram memory restore <id>' to restore a memory.");
+  Ok(())
+}
+
+/// Change the status or due hint of a goal
+pub async fn cmd_goal_update(id: &str, status: Option<String>, due: Option<String>) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = GoalUpdateParams {
+    id: id.to_string(),
+    status,
+    due,
+  };
+
+  match client.call(params).await {
+    Ok(goal) => {
+      let status = goal.goal_status.as_deref().unwrap_or("open");
+      match &goal.due {
+        Some(due) => println!("Goal {} is {} (due: {})", &goal.id[..8.min(goal.id.len())], status, due),
+        None => println!("Goal {} is {}", &goal.id[..8.min(goal.id.len())], status),
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Write a retrospective for a task or goal
+pub async fn cmd_retro(id: &str, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = RetroParams { id: id.to_string() };
+
+  match client.call(params).await {
+    Ok(retro) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&retro)?);
+        return Ok(());
+      }
+
+      println!(
+        "Retrospective {} of {} (from {} plan, {} completions, {} errors, {} decisions)",
+        &retro.id[..8.min(retro.id.len())],
+        &retro.subject_id[..8.min(retro.subject_id.len())],
+        display::count(retro.plan),
+        display::count(retro.completions),
+        display::count(retro.errors),
+        display::count(retro.decisions)
+      );
+      println!();
+      println!("{}", retro.summary);
+
+      let sections = [
+        ("Went well", &retro.went_well),
+        ("Went wrong", &retro.went_wrong),
+        ("Deviations from plan", &retro.deviations),
+        ("Lessons", &retro.lessons),
+      ];
+      for (title, items) in sections {
+        if items.is_empty() {
+          continue;
+        }
+        println!();
+        println!("{}:", title);
+        for item in items {
+          println!("  - {}", item);
+        }
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Extract memories from a file or stdin
+pub async fn cmd_extract_text(file: Option<&Path>, source: Option<String>, json_output: bool) -> Result<()> {
+  let file = file.filter(|f| *f != Path::new("-"));
+  let text = match file {
+    Some(path) => std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+    None => {
+      let mut stdin = std::io::stdin();
+      if stdin.is_terminal() {
+        bail!("Pass --file or pipe text on stdin");
+      }
+      let mut text = String::new();
+      stdin.read_to_string(&mut text).context("Failed to read stdin")?;
+      text
+    }
+  };
+  let source = source.or_else(|| {
+    file
+      .and_then(|f| f.file_name())
+      .map(|name| name.to_string_lossy().to_string())
+  });
+
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = MemoryExtractParams {
+    text,
+    source: source.clone(),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let from = source.map(|s| format!(" from {}", s)).unwrap_or_default();
+      println!(
+        "Created {} memories{} ({} parts, {} skipped)",
+        display::count(result.memories_created.len()),
+        from,
+        display::count(result.parts),
+        display::count(result.skipped)
+      );
+
+      if !result.memories_created.is_empty() {
+        println!();
+        let mut table = Table::new(&["ID", "Type", "Content"]);
+        for id in &result.memories_created {
+          let params = MemoryGetParams {
+            memory_id: id.clone(),
+            include_related: None,
+          };
+          match client.call(params).await {
+            Ok(memory) => table.row([
+              memory.id,
+              memory.memory_type.unwrap_or_else(|| memory.sector.clone()),
+              memory.content.replace('\n', " "),
+            ]),
+            Err(_) => table.row([id.as_str()]),
+          }
+        }
+        table.print();
+      }
+
+      if !result.duplicates.is_empty() {
+        println!();
+        println!(
+          "{} already stored: {}",
+          display::count(result.duplicates.len()),
+          result.duplicates.join(", ")
+        );
+      }
     }
     Err(e) => {
       error!("Error: {}", e);
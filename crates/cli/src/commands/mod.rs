@@ -2,31 +2,72 @@
 
 mod admin;
 mod agent;
+mod audit;
+mod complete;
 mod context;
 mod daemon;
+mod data;
+mod db;
+mod entities;
+mod explore;
+mod extract;
+mod git;
 mod hook;
 mod index;
 mod logs;
 mod memory;
+mod pack;
 mod projects;
+mod references;
+mod repl;
 mod search;
+mod sessions;
+mod sync;
+mod tags;
 mod update;
+mod usage;
 mod watch;
 
 #[cfg(all(unix, feature = "jemalloc-pprof"))]
 mod pprof;
 
-pub use admin::{cmd_archive, cmd_config_init, cmd_config_reset, cmd_config_show, cmd_health, cmd_stats};
+pub use admin::{
+  cmd_archive, cmd_config_init, cmd_config_reset, cmd_config_show, cmd_health, cmd_health_probe, cmd_stats,
+};
 pub use agent::{cmd_agent, cmd_tui};
-pub use context::cmd_context;
+pub use audit::cmd_audit_secrets;
+pub use complete::{CompletionKind, cmd_complete, dynamic_completion_script};
+pub use context::{cmd_context, cmd_context_pack};
 pub use daemon::cmd_daemon;
+pub use data::{cmd_data_import, cmd_data_init, cmd_data_status};
+pub use db::cmd_db_repair;
+pub use entities::cmd_entities_show;
+pub use explore::{
+  cmd_explore_clear, cmd_explore_delete, cmd_explore_export, cmd_explore_list, cmd_explore_note, cmd_explore_pin,
+  cmd_explore_query, cmd_explore_save,
+};
+pub use extract::{cmd_extract_failed, cmd_extract_inspect, cmd_extract_retry};
+pub use git::cmd_git_install_hooks;
 pub use hook::cmd_hook;
 pub use index::cmd_index;
 pub use logs::{cmd_logs, cmd_logs_list};
-pub use memory::{cmd_delete, cmd_deleted, cmd_restore, cmd_show};
+pub use memory::{
+  cmd_delete, cmd_deleted, cmd_extract_text, cmd_goal_update, cmd_goals, cmd_history, cmd_restore, cmd_retro, cmd_show,
+  cmd_undo,
+};
+pub use pack::cmd_pack;
 #[cfg(all(unix, feature = "jemalloc-pprof"))]
 pub use pprof::cmd_pprof;
-pub use projects::{cmd_projects_clean, cmd_projects_clean_all, cmd_projects_list, cmd_projects_show};
+pub use projects::{
+  cmd_projects_clean, cmd_projects_clean_all, cmd_projects_duplicates, cmd_projects_export, cmd_projects_import,
+  cmd_projects_list, cmd_projects_merge, cmd_projects_migrate, cmd_projects_show,
+};
+pub use references::cmd_references;
+pub use repl::cmd_repl;
 pub use search::{cmd_search, cmd_search_code, cmd_search_docs};
+pub use sessions::{cmd_sessions_import, cmd_sessions_injections, cmd_sessions_list, cmd_sessions_show};
+pub use sync::{cmd_sync_pull, cmd_sync_push, cmd_sync_resolve};
+pub use tags::{cmd_tags_delete, cmd_tags_list, cmd_tags_merge, cmd_tags_rename};
 pub use update::cmd_update;
+pub use usage::{cmd_egress, cmd_search_stats, cmd_usage};
 pub use watch::cmd_watch;
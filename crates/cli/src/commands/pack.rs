@@ -0,0 +1,219 @@
+//! Context pack command
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ccengram::ipc::search::{ExplorePlanParams, PackItem, PackParams, PackResult};
+use tracing::error;
+
+use crate::display;
+
+/// Build a token-budgeted bundle of memories, code and docs for a query.
+///
+/// `plan` is `Some` to treat the query as a task and plan the pack, holding
+/// the call-graph hops to follow if given.
+pub async fn cmd_pack(
+  query: &str,
+  budget: usize,
+  scope: Option<String>,
+  plan: Option<Option<usize>>,
+  format: &str,
+  output: Option<&Path>,
+) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let result = match plan {
+    Some(hops) => {
+      client
+        .call(ExplorePlanParams {
+          task: query.to_string(),
+          budget: Some(budget),
+          scope,
+          hops,
+        })
+        .await
+    }
+    None => {
+      client
+        .call(PackParams {
+          query: query.to_string(),
+          budget: Some(budget),
+          scope,
+        })
+        .await
+    }
+  };
+
+  match result {
+    Ok(pack) => {
+      let rendered = match format {
+        "json" => serde_json::to_string_pretty(&pack)?,
+        _ => render_markdown(&pack),
+      };
+
+      match output {
+        Some(path) => {
+          tokio::fs::write(path, &rendered)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+          eprintln!(
+            "Wrote {} items (~{} of {} tokens) to {}",
+            display::count(pack.items.len()),
+            display::count(pack.tokens),
+            display::count(pack.budget),
+            path.display()
+          );
+          if pack.omitted > 0 {
+            eprintln!(
+              "{} more results did not fit; raise --budget to include them",
+              display::count(pack.omitted)
+            );
+          }
+        }
+        None => print!("{}", rendered),
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Render a pack as Markdown, one section per item type
+fn render_markdown(pack: &PackResult) -> String {
+  let mut out = format!("# Project context: {}\n\n", pack.query);
+  out.push_str(&format!(
+    "_{} items, ~{} tokens. Generated by ccengram._\n",
+    pack.items.len(),
+    pack.tokens
+  ));
+  if !pack.steps.is_empty() {
+    let searches = pack.steps.iter().filter(|s| s.kind == "search").count();
+    out.push_str(&format!(
+      "_Planned from {} searches and {} call-graph lookups._\n",
+      searches,
+      pack.steps.len() - searches
+    ));
+  }
+
+  for (item_type, heading) in [("memory", "Memories"), ("code", "Code"), ("doc", "Documentation")] {
+    let items: Vec<&PackItem> = pack.items.iter().filter(|i| i.item_type == item_type).collect();
+    if items.is_empty() {
+      continue;
+    }
+    out.push_str(&format!("\n## {}\n", heading));
+    for item in items {
+      out.push('\n');
+      out.push_str(&render_item(item));
+    }
+  }
+
+  if pack.items.is_empty() {
+    out.push_str("\nNo relevant memories, code or docs found.\n");
+  }
+  out
+}
+
+fn render_item(item: &PackItem) -> String {
+  let truncated = if item.truncated { " (truncated)" } else { "" };
+  let content = item.content.trim_end();
+  let found_by = if item.provenance.is_empty() {
+    String::new()
+  } else {
+    format!("_Found by {}._\n\n", item.provenance.join("; "))
+  };
+
+  match item.item_type.as_str() {
+    "code" => {
+      let location = match (&item.source, item.lines) {
+        (Some(file), Some((start, end))) => format!("{}:{}-{}", file, start, end),
+        (Some(file), None) => file.clone(),
+        _ => String::new(),
+      };
+      format!(
+        "### `{}` ({}){}\n\n{}```{}\n{}\n```\n",
+        item.title,
+        location,
+        truncated,
+        found_by,
+        item.language.as_deref().unwrap_or_default(),
+        content
+      )
+    }
+    "doc" => {
+      let source = item.source.as_deref().map(|s| format!(" ({})", s)).unwrap_or_default();
+      format!("### {}{}{}\n\n{}{}\n", item.title, source, truncated, found_by, content)
+    }
+    _ => {
+      let found_by = if item.provenance.is_empty() {
+        String::new()
+      } else {
+        format!(" _(found by {})_", item.provenance.join("; "))
+      };
+      format!(
+        "- **{}**{}: {}{}\n",
+        item.title,
+        truncated,
+        content.replace('\n', "\n  "),
+        found_by
+      )
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(item_type: &str, title: &str, content: &str) -> PackItem {
+    PackItem {
+      id: "id".to_string(),
+      item_type: item_type.to_string(),
+      title: title.to_string(),
+      source: None,
+      lines: None,
+      language: None,
+      content: content.to_string(),
+      tokens: content.len() / 4,
+      score: 0.5,
+      truncated: false,
+      provenance: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_render_markdown_sections() {
+    let mut code = item("code", "charge_card", "fn charge_card() {}");
+    code.source = Some("src/payments.rs".to_string());
+    code.lines = Some((10, 12));
+    code.language = Some("rust".to_string());
+
+    let pack = PackResult {
+      query: "payments".to_string(),
+      budget: 8000,
+      tokens: 20,
+      items: vec![item("memory", "decision", "Payments go through Stripe"), code],
+      omitted: 0,
+      steps: Vec::new(),
+    };
+
+    let markdown = render_markdown(&pack);
+    assert!(
+      markdown.contains("## Memories\n\n- **decision**: Payments go through Stripe"),
+      "memories render as a list: {}",
+      markdown
+    );
+    assert!(
+      markdown.contains("### `charge_card` (src/payments.rs:10-12)\n\n```rust\nfn charge_card() {}\n```"),
+      "code renders fenced with its location: {}",
+      markdown
+    );
+    assert!(!markdown.contains("## Documentation"), "empty sections are skipped");
+  }
+}
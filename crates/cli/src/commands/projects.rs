@@ -1,11 +1,16 @@
-//! Project management commands (list, show, clean)
+//! Project management commands (list, show, clean, migrate, merge, export, import)
 
 use std::io::Write;
 
 use anyhow::{Context, Result};
-use ccengram::ipc::project::{ProjectCleanAllParams, ProjectCleanParams, ProjectInfoParams, ProjectListParams};
+use ccengram::ipc::project::{
+  ProjectCleanAllParams, ProjectCleanParams, ProjectDuplicatesParams, ProjectExportParams, ProjectImportParams,
+  ProjectInfoParams, ProjectListParams, ProjectMergeParams, ProjectMergeResult, ProjectMigrateParams,
+};
 use tracing::error;
 
+use crate::{display, table::Table};
+
 /// List all indexed projects
 pub async fn cmd_projects_list(json_output: bool) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
@@ -25,21 +30,18 @@ pub async fn cmd_projects_list(json_output: bool) -> Result<()> {
         return Ok(());
       }
 
-      println!("Indexed Projects ({})", projects.len());
+      let mut projects = projects;
+      projects.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+
+      println!("Indexed Projects ({})", display::count(projects.len()));
       println!("==================\n");
 
+      let mut table = Table::new(&["Name", "ID", "Path"]);
       for project in &projects {
-        // Truncate ID for display
-        let short_id = if project.id.len() > 8 {
-          &project.id[..8]
-        } else {
-          &project.id
-        };
-
-        println!("{} [{}]", project.name, short_id);
-        println!("  Path: {}", project.path);
-        println!();
+        let short_id = project.id.get(..8).unwrap_or(&project.id);
+        table.row([project.name.as_str(), short_id, project.path.as_str()]);
       }
+      table.print();
     }
     Err(e) => {
       error!("Error: {}", e);
@@ -77,13 +79,34 @@ pub async fn cmd_projects_show(project: &str, json_output: bool) -> Result<()> {
 
       println!();
       println!("Statistics:");
-      println!("  Memories:     {}", info.memory_count);
-      println!("  Code Chunks:  {}", info.code_chunk_count);
-      println!("  Documents:    {}", info.document_count);
-      println!("  Sessions:     {}", info.session_count);
+      println!("  Memories:     {}", display::count(info.memory_count));
+      println!("  Code Chunks:  {}", display::count(info.code_chunk_count));
+      println!("  Documents:    {}", display::count(info.document_count));
+      println!("  Sessions:     {}", display::count(info.session_count));
 
       println!();
       println!("Database Path: {}", info.db_path);
+
+      if let Some(moved) = &info.moved_from {
+        println!();
+        println!("This project has no memories yet, but looks like a moved project:");
+        for old in moved {
+          println!("  {}  {}", old.id.get(..8).unwrap_or(&old.id), old.path);
+        }
+        println!(
+          "Keep its data with: ccengram projects migrate <old-path-or-id> {}",
+          info.path
+        );
+      }
+
+      if let Some(clones) = &info.duplicates {
+        println!();
+        println!("Other clones of this repository have their own memories:");
+        for clone in clones {
+          println!("  {}  {}", clone.id.get(..8).unwrap_or(&clone.id), clone.path);
+        }
+        println!("Merge them with: ccengram projects duplicates");
+      }
     }
     Err(e) => {
       error!("Error: {}", e);
@@ -162,3 +185,226 @@ pub async fn cmd_projects_clean_all(force: bool) -> Result<()> {
 
   Ok(())
 }
+
+/// Move a project's data to its new location
+pub async fn cmd_projects_migrate(from: &str, to: &str) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ProjectMigrateParams {
+    from: from.to_string(),
+    to: to.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      println!(
+        "Moved project data: {} -> {}",
+        result.from_path.as_deref().unwrap_or(&result.from_id),
+        result.to_path
+      );
+      println!("  Project ID: {} -> {}", result.from_id, result.to_id);
+      println!("  Memories updated: {}", result.memories_updated);
+      println!("  Documents updated: {}", result.documents_updated);
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// List clones of the same repository and offer to merge their stores
+pub async fn cmd_projects_duplicates(json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let groups = match client.call(ProjectDuplicatesParams).await {
+    Ok(groups) => groups,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&groups)?);
+    return Ok(());
+  }
+  if groups.is_empty() {
+    println!("No duplicate projects found.");
+    return Ok(());
+  }
+
+  for group in &groups {
+    let Some((kept, others)) = group.projects.split_first() else {
+      continue;
+    };
+    println!("Clones of {}", kept.name);
+    let mut table = Table::new(&["ID", "Path", "Memories", "Indexed Files"]);
+    for project in &group.projects {
+      table.row([
+        project.id.get(..8).unwrap_or(&project.id).to_string(),
+        project.path.clone(),
+        display::count(project.memories),
+        display::count(project.indexed_files),
+      ]);
+    }
+    table.print();
+
+    // The first project has the richest index, so its store is kept
+    for other in others {
+      print!(
+        "Merge {} memories from {} into {} and remove its store? [y/N] ",
+        other.memories, other.path, kept.path
+      );
+      std::io::stdout().flush()?;
+      let mut input = String::new();
+      std::io::stdin().read_line(&mut input)?;
+      if !input.trim().eq_ignore_ascii_case("y") {
+        println!("Skipped.");
+        continue;
+      }
+
+      let params = ProjectMergeParams {
+        from: other.id.clone(),
+        into: kept.id.clone(),
+      };
+      match client.call(params).await {
+        Ok(result) => print_merge(&result),
+        Err(e) => error!("Error: {}", e),
+      }
+    }
+    println!();
+  }
+
+  Ok(())
+}
+
+/// Merge one clone's memories into another clone's store
+pub async fn cmd_projects_merge(from: &str, into: &str) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ProjectMergeParams {
+    from: from.to_string(),
+    into: into.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(result) => print_merge(&result),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Package a project's data into a snapshot archive
+pub async fn cmd_projects_export(
+  path: &str,
+  project: Option<String>,
+  force: bool,
+  include_private: bool,
+) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ProjectExportParams {
+    path: path.to_string(),
+    project,
+    force,
+    include_private,
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      println!(
+        "Exported {} to {}",
+        result.project_path.as_deref().unwrap_or(&result.project_id),
+        result.archive
+      );
+      println!("  Project ID: {}", result.project_id);
+      println!("  Tables: {}", result.tables.join(", "));
+      if result.private_excluded > 0 {
+        println!(
+          "  Private memories left out: {} (--include-private keeps them)",
+          result.private_excluded
+        );
+      }
+      println!(
+        "  Project config: {}",
+        if result.config_included { "included" } else { "none" }
+      );
+      println!(
+        "  Size: {} MB",
+        display::decimal(result.bytes as f64 / (1024.0 * 1024.0), 1)
+      );
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Restore a snapshot archive into a project
+pub async fn cmd_projects_import(path: &str, project: Option<String>) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = ProjectImportParams {
+    path: path.to_string(),
+    project,
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      println!(
+        "Imported {} into {}",
+        result.from_path.as_deref().unwrap_or(&result.from_id),
+        result.to_path
+      );
+      println!("  Project ID: {} -> {}", result.from_id, result.to_id);
+      println!(
+        "  Exported: {} by ccengram {}",
+        display::timestamp(&result.exported_at),
+        result.ccengram_version
+      );
+      println!("  Tables: {}", result.tables.join(", "));
+      println!("  Memories updated: {}", result.memories_updated);
+      println!("  Documents updated: {}", result.documents_updated);
+      if result.config_restored {
+        println!("  Project config restored to .claude/ccengram.toml");
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+fn print_merge(result: &ProjectMergeResult) {
+  println!("Merged {} into {}", result.removed_path, result.kept_path);
+  println!("  Memories copied: {}", result.memories_copied);
+  println!("  Duplicates skipped: {}", result.duplicates_skipped);
+}
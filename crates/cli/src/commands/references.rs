@@ -0,0 +1,67 @@
+//! Find-references command for code symbols
+
+use anyhow::{Context, Result};
+use ccengram::ipc::code::CodeReferencesParams;
+use tracing::error;
+
+/// Find code referencing a symbol
+pub async fn cmd_references(symbol: &str, file: Option<&str>, limit: usize, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = CodeReferencesParams {
+    symbol: symbol.to_string(),
+    file_path: file.map(String::from),
+    limit: Some(limit),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      if let Some(def) = &result.definition {
+        println!("Defined at {}:{}-{}", def.file_path, def.start_line, def.end_line);
+      }
+
+      if result.references.is_empty() {
+        println!("No references found for: {}", symbol);
+        return Ok(());
+      }
+
+      if result.total > result.count {
+        println!("Showing {} of {} references:\n", result.count, result.total);
+      } else {
+        println!("Found {} references:\n", result.count);
+      }
+      for (i, r) in result.references.iter().enumerate() {
+        println!(
+          "{}. {}:{}-{} [{}, {}]",
+          i + 1,
+          r.file_path,
+          r.start_line,
+          r.end_line,
+          r.kind,
+          r.proximity
+        );
+        if let Some(name) = &r.definition_name {
+          println!("   In: {}", name);
+        }
+        if !r.lines.is_empty() {
+          let lines: Vec<String> = r.lines.iter().map(|l| l.to_string()).collect();
+          println!("   Lines: {}", lines.join(", "));
+        }
+      }
+    }
+    Err(e) => {
+      error!("Find references failed: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
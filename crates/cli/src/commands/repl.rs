@@ -0,0 +1,261 @@
+//! Interactive REPL over a single daemon connection
+//!
+//! IDs from the last result list can be referenced as `$1`, `$2`, ... so
+//! search, show, context and link can be chained without typing full IDs.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use rustyline::{DefaultEditor, error::ReadlineError};
+use serde_json::{Value, json};
+
+const HELP: &str = "\
+Commands:
+  explore <query>            Search code and docs
+  search <query>             Search memories
+  code <query>               Search code
+  docs <query>               Search documents
+  show <id>                  Show a memory with related memories
+  context <id> [id...]       Full context for code, memory or doc items
+  related <id>               Memories related to a memory
+  timeline <id>              Memories around a memory in time
+  link <from> <to> [type]    Link two memories (default type: related_to)
+  ids                        List IDs from the last result
+  json                       Toggle raw JSON output
+  help                       Show this help
+  exit                       Leave the REPL
+
+IDs from the last result list can be used as $1, $2, ...";
+
+/// Result list keys checked, in order, when capturing IDs for `$N`
+const RESULT_KEYS: &[&str] = &["results", "items", "chunks", "related"];
+
+/// Interactive REPL state
+struct Repl {
+  client: ccengram::ipc::Client,
+  last_ids: Vec<String>,
+  json: bool,
+}
+
+/// Run the interactive REPL
+pub async fn cmd_repl(project: Option<PathBuf>) -> Result<()> {
+  let cwd = project
+    .or_else(|| std::env::current_dir().ok())
+    .unwrap_or_else(|| PathBuf::from("."));
+
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let mut editor = DefaultEditor::new().context("Failed to initialize line editor")?;
+  let history_path = ccengram::dirs::default_data_dir().join("repl_history");
+  // Missing on first run
+  let _ = editor.load_history(&history_path);
+
+  let mut repl = Repl {
+    client,
+    last_ids: Vec::new(),
+    json: false,
+  };
+
+  println!(
+    "ccengram {} - type 'help' for commands, 'exit' to quit",
+    env!("CARGO_PKG_VERSION")
+  );
+
+  loop {
+    let line = match tokio::task::block_in_place(|| editor.readline("ccengram> ")) {
+      Ok(line) => line,
+      Err(ReadlineError::Interrupted) => continue,
+      Err(ReadlineError::Eof) => break,
+      Err(e) => return Err(e).context("Failed to read input"),
+    };
+
+    let line = line.trim();
+    if line.is_empty() {
+      continue;
+    }
+    let _ = editor.add_history_entry(line);
+
+    if matches!(line, "exit" | "quit") {
+      break;
+    }
+    if let Err(e) = repl.run(line).await {
+      eprintln!("Error: {:#}", e);
+    }
+  }
+
+  if let Some(parent) = history_path.parent() {
+    let _ = tokio::fs::create_dir_all(parent).await;
+  }
+  if let Err(e) = editor.save_history(&history_path) {
+    tracing::debug!("Failed to save REPL history: {}", e);
+  }
+
+  Ok(())
+}
+
+impl Repl {
+  async fn run(&mut self, line: &str) -> Result<()> {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    let (tool, args) = match command {
+      "help" => {
+        println!("{}", HELP);
+        return Ok(());
+      }
+      "ids" => {
+        self.print_ids();
+        return Ok(());
+      }
+      "json" => {
+        self.json = !self.json;
+        println!("JSON output {}", if self.json { "on" } else { "off" });
+        return Ok(());
+      }
+      "explore" => ("explore", json!({ "query": require(rest, "query")? })),
+      "search" => ("memory_search", json!({ "query": require(rest, "query")? })),
+      "code" => ("code_search", json!({ "query": require(rest, "query")? })),
+      "docs" => ("docs_search", json!({ "query": require(rest, "query")? })),
+      "show" => (
+        "memory_get",
+        json!({ "memory_id": self.single_id(rest)?, "include_related": true }),
+      ),
+      "context" => {
+        let ids = self.ids(rest)?;
+        match ids.as_slice() {
+          [id] => ("context", json!({ "id": id })),
+          _ => ("context", json!({ "ids": ids })),
+        }
+      }
+      "related" => ("memory_related", json!({ "memory_id": self.single_id(rest)? })),
+      "timeline" => ("memory_timeline", json!({ "memory_id": self.single_id(rest)? })),
+      "link" => {
+        let args = self.ids(rest)?;
+        let (from, to, kind) = match args.as_slice() {
+          [from, to] => (from, to, "related_to"),
+          [from, to, kind] => (from, to, kind.as_str()),
+          _ => bail!("Usage: link <from> <to> [type]"),
+        };
+        (
+          "relationship_add",
+          json!({ "from_memory_id": from, "to_memory_id": to, "relationship_type": kind }),
+        )
+      }
+      _ => bail!("Unknown command: {}. Type 'help' for commands.", command),
+    };
+
+    let result = crate::mcp::call_tool(&self.client, tool, args).await?;
+
+    if self.json {
+      println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+      let text = crate::format::format_tool_result(tool, &result)
+        .unwrap_or_else(|| serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string()));
+      println!("{}", text);
+    }
+
+    let ids = result_ids(&result);
+    if !ids.is_empty() {
+      println!("\n($1..${} refer to these results)", ids.len());
+      self.last_ids = ids;
+    }
+
+    Ok(())
+  }
+
+  /// Split arguments and substitute `$N` references
+  fn ids(&self, args: &str) -> Result<Vec<String>> {
+    let ids = args
+      .split_whitespace()
+      .map(|arg| resolve(arg, &self.last_ids))
+      .collect::<Result<Vec<_>>>()?;
+    if ids.is_empty() {
+      bail!("Missing ID");
+    }
+    Ok(ids)
+  }
+
+  fn single_id(&self, args: &str) -> Result<String> {
+    match self.ids(args)?.as_slice() {
+      [id] => Ok(id.clone()),
+      _ => bail!("Expected a single ID"),
+    }
+  }
+
+  fn print_ids(&self) {
+    if self.last_ids.is_empty() {
+      println!("No results yet");
+      return;
+    }
+    for (i, id) in self.last_ids.iter().enumerate() {
+      println!("${:<3} {}", i + 1, id);
+    }
+  }
+}
+
+fn require<'a>(value: &'a str, name: &str) -> Result<&'a str> {
+  if value.is_empty() {
+    bail!("Missing {}", name);
+  }
+  Ok(value)
+}
+
+/// Substitute a `$N` reference with the Nth ID of the last result
+fn resolve(arg: &str, last_ids: &[String]) -> Result<String> {
+  let Some(index) = arg.strip_prefix('$') else {
+    return Ok(arg.to_string());
+  };
+  let n: usize = index.parse().with_context(|| format!("Invalid reference: {}", arg))?;
+  match n.checked_sub(1).and_then(|i| last_ids.get(i)) {
+    Some(id) => Ok(id.clone()),
+    None => bail!("No result {} (last result has {} IDs)", arg, last_ids.len()),
+  }
+}
+
+/// IDs of the items in a result list, in display order
+fn result_ids(result: &Value) -> Vec<String> {
+  let list = match result {
+    Value::Array(items) => Some(items),
+    Value::Object(map) => RESULT_KEYS.iter().find_map(|key| map.get(*key)?.as_array()),
+    _ => None,
+  };
+
+  list
+    .into_iter()
+    .flatten()
+    .filter_map(|item| item.get("id")?.as_str().map(str::to_string))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_resolve_references() {
+    let ids = vec!["abc".to_string(), "def".to_string()];
+    assert_eq!(resolve("$2", &ids).ok().as_deref(), Some("def"), "$N is one-based");
+    assert_eq!(
+      resolve("019abc", &ids).ok().as_deref(),
+      Some("019abc"),
+      "plain IDs pass through"
+    );
+    assert!(resolve("$0", &ids).is_err(), "$0 is out of range");
+    assert!(resolve("$3", &ids).is_err(), "past the last result");
+    assert!(resolve("$x", &ids).is_err(), "non-numeric reference");
+  }
+
+  #[test]
+  fn test_result_ids_from_lists() {
+    let explore = json!({ "query": "q", "results": [{ "id": "a" }, { "id": "b" }] });
+    assert_eq!(result_ids(&explore), vec!["a", "b"], "explore results");
+
+    let docs = json!([{ "id": "d1" }]);
+    assert_eq!(result_ids(&docs), vec!["d1"], "top-level arrays");
+
+    let detail = json!({ "id": "m1", "content": "x" });
+    assert!(result_ids(&detail).is_empty(), "single items keep previous IDs");
+  }
+}
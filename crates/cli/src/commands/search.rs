@@ -1,9 +1,24 @@
 //! Search commands for memories, code, and documents
 
 use anyhow::{Context, Result};
-use ccengram::ipc::{code::CodeSearchParams, docs::DocsSearchParams, memory::MemorySearchParams};
+use ccengram::{
+  config::Config,
+  ipc::{
+    code::CodeSearchParams,
+    docs::DocsSearchParams,
+    memory::{MemoryGroup, MemoryItem, MemorySearchParams},
+  },
+};
 use tracing::error;
 
+use crate::display;
+
+/// Characters of content shown per result, unless `--no-trunc`
+const PREVIEW_CHARS: usize = 200;
+
+/// Indent of the detail lines under each result
+const DETAIL_INDENT: usize = 3;
+
 /// Format an ID for display
 ///
 /// When `long` is false, shows only the first 8 characters with "..." suffix.
@@ -16,10 +31,10 @@ fn format_id(id: &str, long: bool) -> String {
   }
 }
 
-/// Search memories
+/// Search memories, starting from the saved search `saved` when given
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_search(
-  query: &str,
+  query: Option<&str>,
   limit: usize,
   project: Option<&str>,
   sector: Option<&str>,
@@ -27,6 +42,9 @@ pub async fn cmd_search(
   min_salience: Option<f32>,
   include_superseded: bool,
   scope: Option<&str>,
+  group_by: Option<&str>,
+  per_group: Option<usize>,
+  saved: Option<&str>,
   json_output: bool,
   long_ids: bool,
 ) -> Result<()> {
@@ -35,21 +53,51 @@ pub async fn cmd_search(
     .or_else(|| std::env::current_dir().ok())
     .unwrap_or_else(|| std::path::PathBuf::from("."));
 
+  let mut params = match saved {
+    Some(name) => {
+      let config = Config::load_for_project(&cwd).await;
+      let Some(saved) = config.search.saved_search(name) else {
+        let names: Vec<&str> = config.search.saved.iter().map(|s| s.name.as_str()).collect();
+        if names.is_empty() {
+          error!(
+            "No saved search '{}': add one under [[search.saved]] in the config",
+            name
+          );
+        } else {
+          error!("No saved search '{}'. Saved searches: {}", name, names.join(", "));
+        }
+        std::process::exit(1);
+      };
+      MemorySearchParams::from(saved)
+    }
+    None => MemorySearchParams::default(),
+  };
+  // Anything given on the command line replaces the saved value
+  if let Some(query) = query {
+    params.query = query.to_string();
+  }
+  if let Some(sector) = sector {
+    params.sector = Some(sector.to_string());
+  }
+  if let Some(memory_type) = memory_type {
+    params.memory_type = Some(memory_type.to_string());
+  }
+  if min_salience.is_some() {
+    params.min_salience = min_salience;
+  }
+  if let Some(scope) = scope {
+    params.scope_path = Some(scope.to_string());
+  }
+  params.include_superseded |= include_superseded;
+  params.limit = Some(limit);
+  params.group_by = group_by.map(|g| g.to_string());
+  params.per_group = per_group;
+  let query = params.query.clone();
+
   let client = ccengram::Daemon::connect_or_start(cwd)
     .await
     .context("Failed to connect to daemon")?;
 
-  let params = MemorySearchParams {
-    query: query.to_string(),
-    sector: sector.map(|s| s.to_string()),
-    memory_type: memory_type.map(|t| t.to_string()),
-    min_salience,
-    scope_path: scope.map(|s| s.to_string()),
-    limit: Some(limit),
-    include_superseded,
-    ..Default::default()
-  };
-
   match client.call(params).await {
     Ok(result) => {
       if json_output {
@@ -57,6 +105,13 @@ pub async fn cmd_search(
         return Ok(());
       }
 
+      if let Some(groups) = &result.groups
+        && !groups.is_empty()
+      {
+        print_groups(groups, group_by.unwrap_or_default(), long_ids);
+        return Ok(());
+      }
+
       let memories = &result.items;
       if memories.is_empty() {
         // Show suggestion if search quality indicates low confidence
@@ -79,19 +134,7 @@ pub async fn cmd_search(
         }
 
         for (i, memory) in memories.iter().enumerate() {
-          println!("{}. [{}] {}", i + 1, memory.sector, format_id(&memory.id, long_ids));
-          // Print first 200 chars
-          let content = &memory.content;
-          let preview = if content.len() > 200 {
-            format!("{}...", &content[..200])
-          } else {
-            content.to_string()
-          };
-          println!("   {}", preview.replace('\n', "\n   "));
-          if let Some(sim) = memory.similarity {
-            println!("   Similarity: {:.2}", sim);
-          }
-          println!();
+          print_memory(i + 1, memory, long_ids);
         }
 
         // Help message about prefix matching
@@ -109,6 +152,41 @@ pub async fn cmd_search(
   Ok(())
 }
 
+fn print_memory(index: usize, memory: &MemoryItem, long_ids: bool) {
+  let stale = if memory.stale { " (stale)" } else { "" };
+  println!(
+    "{}. [{}] {}{}",
+    index,
+    memory.sector,
+    format_id(&memory.id, long_ids),
+    stale
+  );
+  print_preview(&memory.content);
+  if let Some(sim) = memory.similarity {
+    println!("   Similarity: {:.2}", sim);
+  }
+  println!();
+}
+
+/// Print grouped results as one section per group
+fn print_groups(groups: &[MemoryGroup], group_by: &str, long_ids: bool) {
+  let total: usize = groups.iter().map(|g| g.count).sum();
+  println!("Found {} memories in {} groups by {}:\n", total, groups.len(), group_by);
+
+  for group in groups {
+    let hidden = group.count - group.items.len();
+    let more = if hidden > 0 {
+      format!(", {} more not shown", hidden)
+    } else {
+      String::new()
+    };
+    println!("== {} ({}{}) ==\n", group.key, group.count, more);
+    for (i, memory) in group.items.iter().enumerate() {
+      print_memory(i + 1, memory, long_ids);
+    }
+  }
+}
+
 /// Search code
 #[allow(clippy::too_many_arguments)]
 pub async fn cmd_search_code(
@@ -119,6 +197,10 @@ pub async fn cmd_search_code(
   chunk_type: Option<&str>,
   path: Option<&str>,
   symbol: Option<&str>,
+  current_branch: bool,
+  diff_branch: Option<&str>,
+  infer_language: bool,
+  include_generated: bool,
   json_output: bool,
 ) -> Result<()> {
   let cwd = project
@@ -151,6 +233,10 @@ pub async fn cmd_search_code(
     visibility: vec![],
     chunk_type: vec![],
     min_caller_count: None,
+    current_branch: current_branch.then_some(true),
+    diff_branch: diff_branch.map(String::from),
+    infer_language: Some(infer_language),
+    include_generated: include_generated.then_some(true),
   };
 
   match client.call(params).await {
@@ -165,7 +251,10 @@ pub async fn cmd_search_code(
       if chunks.is_empty() {
         println!("No code found for: {}", query);
       } else {
-        println!("Found {} code chunks:\n", chunks.len());
+        match &result.inferred_language {
+          Some(language) => println!("Found {} code chunks (language: {}):\n", chunks.len(), language),
+          None => println!("Found {} code chunks:\n", chunks.len()),
+        }
         for (i, chunk) in chunks.iter().enumerate() {
           println!(
             "{}. {}:{}-{} [{}]",
@@ -175,9 +264,27 @@ pub async fn cmd_search_code(
             chunk.end_line,
             chunk.language.as_deref().unwrap_or("?")
           );
+          if chunk.generated == Some(true) {
+            println!("   Generated");
+          }
 
           if !chunk.symbols.is_empty() {
-            println!("   Symbols: {}", chunk.symbols.join(", "));
+            let symbols = format!("Symbols: {}", chunk.symbols.join(", "));
+            println!("   {}", display::fit(&symbols, DETAIL_INDENT));
+          }
+
+          if !chunk.also_in.is_empty() {
+            let locations: Vec<String> = chunk
+              .also_in
+              .iter()
+              .map(|l| format!("{}:{}-{}", l.file_path, l.start_line, l.end_line))
+              .collect();
+            let also_in = format!("Also in: {}", locations.join(", "));
+            println!("   {}", display::fit(&also_in, DETAIL_INDENT));
+          }
+
+          if let Some(more) = chunk.more_in_file {
+            println!("   +{} more in this file", more);
           }
 
           if let Some(sim) = chunk.similarity {
@@ -237,12 +344,7 @@ pub async fn cmd_search_docs(
             format_id(&chunk.document_id, long_ids)
           );
 
-          let preview = if chunk.content.len() > 200 {
-            format!("{}...", &chunk.content[..200])
-          } else {
-            chunk.content.clone()
-          };
-          println!("   {}", preview.replace('\n', "\n   "));
+          print_preview(&chunk.content);
 
           if let Some(sim) = chunk.similarity {
             println!("   Similarity: {:.2}", sim);
@@ -264,3 +366,10 @@ pub async fn cmd_search_docs(
 
   Ok(())
 }
+
+/// Print the start of a result's content, each line fitted to the terminal
+fn print_preview(content: &str) {
+  for line in display::preview(content, PREVIEW_CHARS).lines() {
+    println!("{}{}", " ".repeat(DETAIL_INDENT), display::fit(line, DETAIL_INDENT));
+  }
+}
@@ -0,0 +1,263 @@
+//! Session commands
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ccengram::ipc::project::{SessionImportParams, SessionInjectionsParams, SessionListParams, SessionShowParams};
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// List recent sessions for the current project
+pub async fn cmd_sessions_list(limit: usize, active_only: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = SessionListParams {
+    limit: Some(limit),
+    active_only: Some(active_only),
+  };
+
+  match client.call(params).await {
+    Ok(sessions) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+        return Ok(());
+      }
+      if sessions.is_empty() {
+        println!("No sessions found");
+        return Ok(());
+      }
+
+      let mut table = Table::new(&["Session", "Started", "Status", "Memories", "Summary"]).right(3);
+      for session in &sessions {
+        let status = match &session.ended_at {
+          Some(_) => "ended",
+          None => "active",
+        };
+        let summary = session.summary.as_deref().or(session.user_prompt.as_deref());
+        table.row([
+          session.id.clone(),
+          display::timestamp(&session.started_at),
+          status.to_string(),
+          display::count(session.memory_count.unwrap_or(0)),
+          summary.map(first_line).unwrap_or_default().to_string(),
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Show a session and the memories it created or used
+pub async fn cmd_sessions_show(session_id: &str, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = SessionShowParams {
+    session_id: session_id.to_string(),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let session = &result.session;
+      println!("Session:  {}", session.id);
+      println!("Started:  {}", display::timestamp(&session.started_at));
+      match &session.ended_at {
+        Some(ended) => println!("Ended:    {}", display::timestamp(ended)),
+        None => println!("Ended:    (active)"),
+      }
+      if let Some(summary) = &session.summary {
+        println!("Summary:  {}", summary);
+      }
+      println!("Memories: {}", display::count(session.memory_count.unwrap_or(0)));
+
+      if result.memories.is_empty() {
+        return Ok(());
+      }
+      println!();
+      let mut table = Table::new(&["Linked", "Usage", "Event", "Memory", "Content"]);
+      for item in &result.memories {
+        let short_id = item.memory_id.get(..8).unwrap_or(&item.memory_id);
+        let text = item.summary.as_deref().or(item.content.as_deref());
+        let content = match text {
+          Some(text) if item.is_deleted => format!("(deleted) {}", first_line(text)),
+          Some(text) => first_line(text).to_string(),
+          None => "(memory no longer exists)".to_string(),
+        };
+        table.row([
+          display::timestamp(&item.linked_at),
+          item.usage_type.clone(),
+          item.hook_event.as_deref().unwrap_or("-").to_string(),
+          short_id.to_string(),
+          content,
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Show the memories hooks injected into a session, and why
+pub async fn cmd_sessions_injections(session_id: &str, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = SessionInjectionsParams {
+    session_id: Some(session_id.to_string()),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      println!("Session:    {}", result.session_id);
+      println!("Injections: {}", display::count(result.injections.len()));
+      if result.injections.is_empty() {
+        return Ok(());
+      }
+      println!();
+      let mut table = Table::new(&["Injected", "Event", "Rule", "Score", "Memory", "Content"]).right(3);
+      for item in &result.injections {
+        let short_id = item.memory_id.get(..8).unwrap_or(&item.memory_id);
+        let rule = match &item.detail {
+          Some(detail) => format!("{} ({})", item.rule, detail),
+          None => item.rule.clone(),
+        };
+        let text = item.summary.as_deref().or(item.content.as_deref());
+        let content = match text {
+          Some(text) if item.is_deleted => format!("(deleted) {}", first_line(text)),
+          Some(text) => first_line(text).to_string(),
+          None => "(memory no longer exists)".to_string(),
+        };
+        table.row([
+          display::timestamp(&item.injected_at),
+          item.hook_event.clone(),
+          rule,
+          item
+            .score
+            .map(|s| format!("{:.2}", s))
+            .unwrap_or_else(|| "-".to_string()),
+          short_id.to_string(),
+          content,
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Import Claude Code transcripts and extract memories from them
+pub async fn cmd_sessions_import(paths: &[PathBuf], project: Option<&Path>, json_output: bool) -> Result<()> {
+  let mut results = Vec::new();
+  let mut failed = false;
+
+  for path in paths {
+    let path = std::fs::canonicalize(path).with_context(|| format!("Transcript not found: {}", path.display()))?;
+
+    // Transcripts record the directory Claude Code ran in, which picks the project
+    let cwd = project
+      .map(Path::to_path_buf)
+      .or_else(|| transcript_cwd(&path))
+      .or_else(|| std::env::current_dir().ok())
+      .unwrap_or_else(|| PathBuf::from("."));
+
+    let client = ccengram::Daemon::connect_or_start(cwd.clone())
+      .await
+      .context("Failed to connect to daemon")?;
+
+    if !json_output {
+      println!("Importing {} into {}...", path.display(), cwd.display());
+    }
+
+    let params = SessionImportParams {
+      path: path.to_string_lossy().to_string(),
+    };
+
+    match client.call(params).await {
+      Ok(result) => {
+        if !json_output {
+          println!(
+            "  Session {}: {} turns, {} extracted, {} memories created",
+            result.session_id,
+            display::count(result.turns),
+            display::count(result.segments_extracted),
+            display::count(result.memories_created.len())
+          );
+          if result.skipped_lines > 0 {
+            println!("  Skipped {} unparseable lines", display::count(result.skipped_lines));
+          }
+        }
+        results.push(result);
+      }
+      Err(e) => {
+        error!("Failed to import {}: {}", path.display(), e);
+        failed = true;
+      }
+    }
+  }
+
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(&results)?);
+  } else if results.len() > 1 {
+    let memories: usize = results.iter().map(|r| r.memories_created.len()).sum();
+    println!(
+      "\nImported {} sessions, {} memories created",
+      display::count(results.len()),
+      display::count(memories)
+    );
+  }
+
+  if failed {
+    std::process::exit(1);
+  }
+
+  Ok(())
+}
+
+/// First line of text; tables cut it to the terminal width
+fn first_line(text: &str) -> &str {
+  text.lines().next().unwrap_or_default()
+}
+
+/// Working directory recorded in a transcript, if it still exists
+fn transcript_cwd(path: &Path) -> Option<PathBuf> {
+  let content = std::fs::read_to_string(path).ok()?;
+  content
+    .lines()
+    .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    .find_map(|entry| entry.get("cwd")?.as_str().map(PathBuf::from))
+    .filter(|cwd| cwd.is_dir())
+}
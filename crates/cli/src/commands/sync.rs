@@ -0,0 +1,255 @@
+//! Team memory sync commands
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::memory::{SyncConflictItem, SyncConflictsParams, SyncPullParams, SyncPushParams, SyncResolveParams};
+use tracing::error;
+
+use crate::display;
+
+/// Write shared memories to the project's sync directory
+pub async fn cmd_sync_push(dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(SyncPushParams { dry_run }).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let verb = if result.dry_run { "Would write" } else { "Wrote" };
+      println!(
+        "{} {} memories to {} ({} unchanged)",
+        verb, result.written, result.dir, result.unchanged
+      );
+      if result.tombstoned > 0 {
+        println!("  {} deleted memories marked for removal", result.tombstoned);
+      }
+      if result.removed > 0 {
+        println!("  {} files removed for memories that are now private", result.removed);
+      }
+      if result.stale > 0 {
+        println!(
+          "  {} files changed by teammates since the last sync; run `ccengram sync pull` first",
+          result.stale
+        );
+      }
+      if result.conflicted > 0 {
+        println!(
+          "  {} memories skipped until `ccengram sync resolve` settles their conflicts",
+          result.conflicted
+        );
+      }
+      if !result.dry_run && result.written + result.tombstoned + result.removed > 0 {
+        println!("\nCommit {} to share the changes.", result.dir);
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Merge teammates' memories from the sync directory into the local store
+pub async fn cmd_sync_pull(dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(SyncPullParams { dry_run }).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      let prefix = if result.dry_run { "Would merge" } else { "Merged" };
+      println!(
+        "{} {}: {} added, {} updated, {} deleted, {} unchanged",
+        prefix, result.dir, result.added, result.updated, result.deleted, result.unchanged
+      );
+      if !result.invalid.is_empty() {
+        println!("\nSkipped {} files:", result.invalid.len());
+        for invalid in &result.invalid {
+          println!("  {}", invalid);
+        }
+      }
+      if result.conflicts > 0 {
+        println!(
+          "\n{} memories were edited on both sides and keep the local version.",
+          result.conflicts
+        );
+        println!("Run `ccengram sync resolve` to settle them.");
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Settle sync conflicts, prompting for each unless `take` is given
+pub async fn cmd_sync_resolve(id: Option<&str>, take: Option<&str>) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let conflicts = match client.call(SyncConflictsParams {}).await {
+    Ok(conflicts) => conflicts,
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  };
+  let conflicts: Vec<SyncConflictItem> = conflicts
+    .into_iter()
+    .filter(|c| id.is_none_or(|id| c.id.starts_with(id)))
+    .collect();
+
+  if conflicts.is_empty() {
+    println!("No sync conflicts.");
+    return Ok(());
+  }
+
+  let total = conflicts.len();
+  let mut resolved = 0;
+  for (i, conflict) in conflicts.into_iter().enumerate() {
+    let (take, content) = match take {
+      Some(take) => (take.to_string(), None),
+      None => {
+        print_conflict(&conflict, i + 1, total);
+        match prompt_resolution(&conflict)? {
+          Resolution::Take(take, content) => (take, content),
+          Resolution::Skip => continue,
+          Resolution::Quit => break,
+        }
+      }
+    };
+
+    let params = SyncResolveParams {
+      id: conflict.id.clone(),
+      take,
+      content,
+    };
+    match client.call(params).await {
+      Ok(result) => {
+        resolved += 1;
+        println!("Resolved {} ({})", &result.id[..8.min(result.id.len())], result.take);
+      }
+      Err(e) => {
+        error!("Error: {}", e);
+        std::process::exit(1);
+      }
+    }
+  }
+
+  println!("\n{} of {} conflicts resolved.", resolved, total);
+  if resolved > 0 {
+    println!("Run `ccengram sync push` to share the resolutions.");
+  }
+  Ok(())
+}
+
+enum Resolution {
+  Take(String, Option<String>),
+  Skip,
+  Quit,
+}
+
+fn print_conflict(conflict: &SyncConflictItem, n: usize, total: usize) {
+  let kind = match conflict.kind.as_str() {
+    "edit_delete" => "edited on one side, deleted on the other",
+    _ => "content edited on both sides",
+  };
+  println!(
+    "\nConflict {}/{}: {} ({})",
+    n,
+    total,
+    &conflict.id[..8.min(conflict.id.len())],
+    kind
+  );
+
+  let version = |content: &Option<String>| content.clone().unwrap_or_else(|| "(deleted)".to_string());
+  if let Some(base) = &conflict.base {
+    println!("\n--- base ---\n{}", base);
+  }
+  println!(
+    "\n--- local ({}) ---\n{}",
+    display::timestamp(&conflict.local_updated_at),
+    version(&conflict.local)
+  );
+  println!(
+    "\n--- remote ({}) ---\n{}",
+    display::timestamp(&conflict.remote_updated_at),
+    version(&conflict.remote)
+  );
+}
+
+fn prompt_resolution(conflict: &SyncConflictItem) -> Result<Resolution> {
+  loop {
+    print!("\nKeep [l]ocal, [r]emote, [e]dit, [s]kip or [q]uit? ");
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+
+    match input.trim().to_lowercase().as_str() {
+      "l" | "local" => return Ok(Resolution::Take("local".to_string(), None)),
+      "r" | "remote" => return Ok(Resolution::Take("remote".to_string(), None)),
+      "e" | "edit" => {
+        let draft = conflict
+          .local
+          .as_ref()
+          .or(conflict.remote.as_ref())
+          .cloned()
+          .unwrap_or_default();
+        let content = edit_in_editor(&conflict.id, &draft)?;
+        if content.trim().is_empty() {
+          println!("Empty content, conflict left as is.");
+          continue;
+        }
+        return Ok(Resolution::Take("edit".to_string(), Some(content)));
+      }
+      "s" | "skip" => return Ok(Resolution::Skip),
+      "q" | "quit" | "" => return Ok(Resolution::Quit),
+      _ => println!("Please answer l, r, e, s or q."),
+    }
+  }
+}
+
+/// Open `$VISUAL` or `$EDITOR` on a draft and return what was saved
+fn edit_in_editor(id: &str, draft: &str) -> Result<String> {
+  let editor = std::env::var("VISUAL")
+    .or_else(|_| std::env::var("EDITOR"))
+    .unwrap_or_else(|_| "vi".to_string());
+  let path = std::env::temp_dir().join(format!("ccengram-resolve-{}.md", id));
+  std::fs::write(&path, draft).context("Failed to write draft")?;
+
+  // Editors like "code --wait" carry their own arguments
+  let mut parts = editor.split_whitespace();
+  let program = parts.next().unwrap_or("vi");
+  let status = std::process::Command::new(program)
+    .args(parts)
+    .arg(&path)
+    .status()
+    .with_context(|| format!("Failed to run editor '{}'", editor))?;
+
+  let content = std::fs::read_to_string(&path).context("Failed to read edited draft");
+  let _ = std::fs::remove_file(&path);
+  if !status.success() {
+    anyhow::bail!("Editor '{}' exited with {}", editor, status);
+  }
+  content
+}
@@ -0,0 +1,143 @@
+//! Tag commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::memory::{TagDeleteParams, TagListParams, TagMergeParams, TagRenameParams, TagUpdateResult};
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// List tags with the number of memories using each
+pub async fn cmd_tags_list(min_count: Option<usize>, limit: usize, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = TagListParams {
+    min_count,
+    limit: Some(limit),
+  };
+
+  match client.call(params).await {
+    Ok(tags) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&tags)?);
+        return Ok(());
+      }
+
+      if tags.is_empty() {
+        println!("No tags found");
+        return Ok(());
+      }
+
+      let mut table = Table::new(&["Tag", "Memories", "Last used"]).right(1);
+      for tag in &tags {
+        table.row([
+          tag.tag.clone(),
+          display::count(tag.memories),
+          tag.last_used.as_deref().map(display::timestamp).unwrap_or_default(),
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Rename a tag on every memory
+pub async fn cmd_tags_rename(from: &str, to: &str, dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = TagRenameParams {
+    from: from.to_string(),
+    to: to.to_string(),
+    dry_run,
+  };
+
+  match client.call(params).await {
+    Ok(result) => print_update(&result, &format!("Renamed {} to {} on", from, to), json_output),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+/// Merge tags into one on every memory
+pub async fn cmd_tags_merge(tags: &[String], into: &str, dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = TagMergeParams {
+    tags: tags.to_vec(),
+    into: into.to_string(),
+    dry_run,
+  };
+
+  match client.call(params).await {
+    Ok(result) => print_update(
+      &result,
+      &format!("Merged {} into {} on", tags.join(", "), into),
+      json_output,
+    ),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+/// Remove tags from every memory
+pub async fn cmd_tags_delete(tags: &[String], dry_run: bool, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = TagDeleteParams {
+    tags: tags.to_vec(),
+    dry_run,
+  };
+
+  match client.call(params).await {
+    Ok(result) => print_update(&result, &format!("Removed {} from", tags.join(", ")), json_output),
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+/// Print how many memories a tag change touched
+fn print_update(result: &TagUpdateResult, action: &str, json_output: bool) -> Result<()> {
+  if json_output {
+    println!("{}", serde_json::to_string_pretty(result)?);
+    return Ok(());
+  }
+
+  if result.memory_ids.is_empty() {
+    println!("No memories use those tags");
+    return Ok(());
+  }
+
+  let memories = format!("{} memories", display::count(result.memory_ids.len()));
+  if result.dry_run {
+    println!("Would change {}", memories);
+    println!("Dry run - no changes made");
+  } else {
+    println!("{} {}", action, memories);
+  }
+  Ok(())
+}
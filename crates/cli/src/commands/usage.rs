@@ -0,0 +1,237 @@
+//! Usage, data egress and search analytics commands
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use ccengram::ipc::{
+  project::{SearchQueryStat, SearchStatsParams},
+  system::{EgressItem, EgressParams, UsageParams, UsageTotals},
+};
+use tracing::error;
+
+use crate::{display, table::Table};
+
+/// Show LLM extraction and embedding token usage and cost
+pub async fn cmd_usage(days: u32, project: Option<String>, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = UsageParams {
+    days: Some(days),
+    project,
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      println!("Usage (last {} days)", result.days);
+      println!("  Extraction: {}", totals(&result.llm));
+      println!("  Embedding:  {}", totals(&result.embedding));
+
+      let month = format!("${}", display::decimal(result.month_cost_usd, 2));
+      match result.monthly_budget_usd {
+        Some(budget) => {
+          let status = if result.budget_exceeded {
+            "  (exceeded, extraction paused)"
+          } else {
+            ""
+          };
+          println!(
+            "  This month: {} of ${} budget{}",
+            month,
+            display::decimal(budget, 2),
+            status
+          );
+        }
+        None => println!("  This month: {} (no budget set)", month),
+      }
+
+      if result.rows.is_empty() {
+        return Ok(());
+      }
+      println!();
+      let mut table = Table::new(&["Date", "LLM calls", "LLM cost", "Emb tokens", "Emb cost", "Project"])
+        .right(1)
+        .right(2)
+        .right(3)
+        .right(4);
+      for row in &result.rows {
+        table.row([
+          row.date.clone(),
+          display::count(row.llm.requests),
+          format!("${}", display::decimal(row.llm.cost_usd, 4)),
+          display::count(row.embedding.input_tokens),
+          format!("${}", display::decimal(row.embedding.cost_usd, 4)),
+          row.project.clone().unwrap_or_else(|| "-".to_string()),
+        ]);
+      }
+      table.print();
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Show how much project text was sent to which provider
+pub async fn cmd_egress(days: u32, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  match client.call(EgressParams { days: Some(days) }).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      println!("Data egress (last {} days)", result.days);
+      println!(
+        "  Strict local: {}",
+        if result.strict_local {
+          "on (network providers refused)"
+        } else {
+          "off"
+        }
+      );
+
+      if result.remote.is_empty() {
+        println!("  Nothing was sent to network providers");
+      } else {
+        let chars: u64 = result.remote.iter().map(|item| item.chars).sum();
+        println!("  Sent off this machine: {} characters", display::count(chars));
+        println!();
+        egress_table(&result.remote).print();
+      }
+
+      if !result.local.is_empty() {
+        println!("\nStayed on this machine:");
+        egress_table(&result.local).print();
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+/// Show what this project's searches asked for and which found nothing
+pub async fn cmd_search_stats(days: u32, limit: usize, json_output: bool) -> Result<()> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?;
+
+  let params = SearchStatsParams {
+    days: Some(days),
+    limit: Some(limit),
+  };
+
+  match client.call(params).await {
+    Ok(result) => {
+      if json_output {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+      }
+
+      println!("Searches (last {} days)", result.days);
+      if result.searches == 0 {
+        println!("  No searches logged (search.log_queries turns logging on)");
+        return Ok(());
+      }
+      let share = |n: usize| display::decimal(n as f64 * 100.0 / result.searches as f64, 0);
+      println!("  Searches:       {}", display::count(result.searches));
+      println!(
+        "  No results:     {} ({}%)",
+        display::count(result.zero_result_searches),
+        share(result.zero_result_searches)
+      );
+      println!(
+        "  Results used:   {} ({}%)",
+        display::count(result.clicked_searches),
+        share(result.clicked_searches)
+      );
+      if let Some(similarity) = result.avg_similarity {
+        println!("  Avg similarity: {}", display::decimal(similarity as f64, 2));
+      }
+
+      println!("\nTop queries:");
+      query_table(&result.top_queries).print();
+
+      if !result.zero_result_queries.is_empty() {
+        println!("\nQueries with no results:");
+        query_table(&result.zero_result_queries).print();
+      }
+    }
+    Err(e) => {
+      error!("Error: {}", e);
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+fn query_table(queries: &[SearchQueryStat]) -> Table {
+  let mut table = Table::new(&["Query", "Kinds", "Searches", "Avg results", "Avg sim", "Used", "Last"])
+    .right(2)
+    .right(3)
+    .right(4)
+    .right(5);
+  for q in queries {
+    table.row([
+      display::truncate(&q.query, 40),
+      q.kinds.join(","),
+      display::count(q.count),
+      display::decimal(q.avg_results as f64, 1),
+      q.avg_similarity
+        .map(|s| display::decimal(s as f64, 2))
+        .unwrap_or_else(|| "-".to_string()),
+      display::count(q.clicks),
+      display::timestamp(&q.last_searched),
+    ]);
+  }
+  table
+}
+
+fn egress_table(items: &[EgressItem]) -> Table {
+  let mut table = Table::new(&["Kind", "Provider", "Model", "Chunks/calls", "Characters", "Projects"])
+    .right(3)
+    .right(4)
+    .right(5);
+  for item in items {
+    table.row([
+      item.kind.clone(),
+      item.provider.clone(),
+      item.model.clone(),
+      display::count(item.requests),
+      display::count(item.chars),
+      display::count(item.projects.len()),
+    ]);
+  }
+  table
+}
+
+fn totals(totals: &UsageTotals) -> String {
+  format!(
+    "{} calls, {} in / {} out tokens, ${}",
+    display::count(totals.requests),
+    display::count(totals.input_tokens),
+    display::count(totals.output_tokens),
+    display::decimal(totals.cost_usd, 4)
+  )
+}
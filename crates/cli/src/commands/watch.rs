@@ -1,7 +1,9 @@
 //! Watch command for file change monitoring
 
 use anyhow::{Context, Result};
-use ccengram::ipc::watch::{WatchStartParams, WatchStatusParams, WatchStopParams};
+use ccengram::ipc::watch::{
+  StartupScanInfo, WatchPauseParams, WatchResumeParams, WatchStartParams, WatchStatusParams, WatchStopParams,
+};
 use tracing::error;
 
 /// Watch for file changes
@@ -9,15 +11,22 @@ use tracing::error;
 /// # Arguments
 /// * `stop` - Stop any running watcher
 /// * `status` - Check watcher status
+/// * `pause` - Pause indexing of file changes, keeping the watcher running
+/// * `resume` - Resume a paused watcher with a reconciliation scan
 /// * `no_startup_scan` - Skip startup scan (don't reconcile with filesystem)
 /// * `startup_scan_mode` - Startup scan mode: deleted_only, deleted_and_new, full
 /// * `startup_scan_sync` - Wait for startup scan to complete before watching
+/// * `roots` - Further workspace roots to watch in the same session
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_watch(
   stop: bool,
   status: bool,
+  pause: bool,
+  resume: bool,
   _no_startup_scan: bool,
   _startup_scan_mode: Option<String>,
   _startup_scan_sync: bool,
+  roots: Vec<String>,
 ) -> Result<()> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
   let client = ccengram::Daemon::connect_or_start(cwd.clone())
@@ -28,6 +37,9 @@ pub async fn cmd_watch(
     match client.call(WatchStopParams).await {
       Ok(result) => {
         println!("File watcher stopped: {}", result.status);
+        for root in &result.roots {
+          println!("  {}: {}", root.path, root.status);
+        }
       }
       Err(e) => {
         error!("Stop error: {}", e);
@@ -37,10 +49,50 @@ pub async fn cmd_watch(
     return Ok(());
   }
 
+  if pause {
+    match client.call(WatchPauseParams).await {
+      Ok(result) => {
+        println!("File watcher paused: {}", result.path);
+        for root in &result.roots {
+          println!("  {}: {}", root.path, root.status);
+        }
+        println!("Run `ccengram watch --resume` to index the changes made meanwhile");
+      }
+      Err(e) => {
+        error!("Pause error: {}", e);
+        std::process::exit(1);
+      }
+    }
+    return Ok(());
+  }
+
+  if resume {
+    match client.call(WatchResumeParams).await {
+      Ok(result) => {
+        println!("File watcher resumed: {}", result.path);
+        print_reconcile(result.reconcile_scan.as_ref());
+        for root in &result.roots {
+          println!("  {}: {}", root.path, root.status);
+          print_reconcile(root.reconcile_scan.as_ref());
+        }
+      }
+      Err(e) => {
+        error!("Resume error: {}", e);
+        std::process::exit(1);
+      }
+    }
+    return Ok(());
+  }
+
   if status {
     match client.call(WatchStatusParams).await {
       Ok(result) => {
-        println!("Watcher Status: {}", if result.running { "RUNNING" } else { "STOPPED" });
+        let state = match (result.running, result.paused) {
+          (true, true) => "PAUSED",
+          (true, false) => "RUNNING",
+          (false, _) => "STOPPED",
+        };
+        println!("Watcher Status: {}", state);
 
         if result.running {
           if result.scanning {
@@ -54,6 +106,27 @@ pub async fn cmd_watch(
           if let Some(root) = &result.root {
             println!("Root: {}", root);
           }
+          if let Some(storm) = &result.last_storm {
+            println!(
+              "Last Event Storm: {} changes coalesced into one rescan ({}s ago)",
+              storm.changes, storm.secs_ago
+            );
+          }
+        }
+
+        for root in &result.roots {
+          println!(
+            "\nRoot: {} ({})",
+            root.root.as_deref().unwrap_or("?"),
+            if root.running { "RUNNING" } else { "STOPPED" }
+          );
+          if root.scanning
+            && let Some(progress) = root.scan_progress
+          {
+            println!("  Startup Scan: {}/{}", progress[0], progress[1]);
+          }
+          println!("  Pending Changes: {}", root.pending_changes);
+          println!("  Project ID: {}", root.project_id);
         }
       }
       Err(e) => {
@@ -65,11 +138,17 @@ pub async fn cmd_watch(
   }
 
   // Start watching
-  match client.call(WatchStartParams).await {
+  match client.call(WatchStartParams { roots }).await {
     Ok(result) => {
       println!("File watcher started: {}", result.status);
       println!("Path: {}", result.path);
       println!("Project ID: {}", result.project_id);
+      for root in &result.roots {
+        println!(
+          "Also watching: {} ({}, project {})",
+          root.path, root.status, root.project_id
+        );
+      }
       println!("Press Ctrl+C to stop watching");
     }
     Err(e) => {
@@ -87,3 +166,13 @@ pub async fn cmd_watch(
   println!("\nWatcher stopped");
   Ok(())
 }
+
+/// Print what the reconciliation scan on resume found
+fn print_reconcile(scan: Option<&StartupScanInfo>) {
+  if let Some(scan) = scan {
+    println!(
+      "  Reconciled: {} added, {} modified, {} deleted, {} moved ({} queued for reindex)",
+      scan.files_added, scan.files_modified, scan.files_deleted, scan.files_moved, scan.files_queued
+    );
+  }
+}
@@ -0,0 +1,249 @@
+//! Number, date and width formatting for human-readable command output.
+//!
+//! Numbers follow the digit grouping and decimal separator of the user's
+//! locale (`LC_ALL`, `LC_NUMERIC`, then `LANG`). Dates are shown in local time
+//! unless `--utc` is passed. `--iso` switches to plain numbers and RFC 3339
+//! timestamps so output is identical across machines for scripts and diffs.
+//! Long lines are cut to the terminal width unless `--no-trunc` is passed or
+//! stdout is not a terminal.
+
+use std::{io::IsTerminal, sync::OnceLock};
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Global output options from the top-level `--utc`, `--iso` and `--no-trunc` flags
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisplayOptions {
+  /// Show timestamps in UTC instead of local time
+  pub utc: bool,
+  /// Machine-stable output: RFC 3339 timestamps and ungrouped numbers
+  pub iso: bool,
+  /// Never cut output to the terminal width
+  pub no_trunc: bool,
+}
+
+static OPTIONS: OnceLock<DisplayOptions> = OnceLock::new();
+
+/// Terminal width captured at startup, before stdout may be sent to a pager
+static WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Set the output options for this process. Only the first call has effect.
+pub fn init(options: DisplayOptions) {
+  let _ = OPTIONS.set(options);
+  let _ = WIDTH.set(detect_width());
+}
+
+fn detect_width() -> Option<usize> {
+  if !std::io::stdout().is_terminal() {
+    return None;
+  }
+  std::env::var("COLUMNS")
+    .ok()
+    .and_then(|c| c.parse().ok())
+    .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+    .filter(|w| *w > 0)
+}
+
+/// Width to fit output into, or `None` when output should not be cut
+pub fn terminal_width() -> Option<usize> {
+  if options().no_trunc {
+    return None;
+  }
+  WIDTH.get().copied().flatten()
+}
+
+fn options() -> DisplayOptions {
+  OPTIONS.get().copied().unwrap_or_default()
+}
+
+/// Digit grouping and decimal separators for a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NumberFormat {
+  group: Option<char>,
+  decimal: char,
+}
+
+impl NumberFormat {
+  const PLAIN: Self = Self {
+    group: None,
+    decimal: '.',
+  };
+
+  fn current() -> Self {
+    static FORMAT: OnceLock<NumberFormat> = OnceLock::new();
+    if options().iso {
+      return Self::PLAIN;
+    }
+    *FORMAT.get_or_init(|| {
+      let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_default();
+      Self::for_locale(&locale)
+    })
+  }
+
+  /// Separators for a POSIX locale name such as `de_DE.UTF-8`
+  fn for_locale(locale: &str) -> Self {
+    let name = locale.split(['.', '@']).next().unwrap_or("");
+    let (language, region) = name.split_once(['_', '-']).unwrap_or((name, ""));
+
+    let (group, decimal) = match (language, region) {
+      ("" | "C" | "POSIX", _) => return Self::PLAIN,
+      ("de" | "it" | "fr", "CH") => ('\'', '.'),
+      ("de" | "nl" | "it" | "es" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl", _) => ('.', ','),
+      ("fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "uk" | "hu" | "bg", _) => ('\u{a0}', ','),
+      _ => (',', '.'),
+    };
+
+    Self {
+      group: Some(group),
+      decimal,
+    }
+  }
+
+  fn integer(&self, n: u64) -> String {
+    let digits = n.to_string();
+    let Some(group) = self.group else {
+      return digits;
+    };
+
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+      if i > 0 && (digits.len() - i) % 3 == 0 {
+        out.push(group);
+      }
+      out.push(c);
+    }
+    out
+  }
+
+  fn decimal(&self, value: f64, places: usize) -> String {
+    let formatted = format!("{:.*}", places, value.abs());
+    let (int_part, frac_part) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let int_value = int_part.parse::<u64>().unwrap_or(0);
+
+    let mut out = String::new();
+    if value < 0.0 && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+      out.push('-');
+    }
+    out.push_str(&self.integer(int_value));
+    if !frac_part.is_empty() {
+      out.push(self.decimal);
+      out.push_str(frac_part);
+    }
+    out
+  }
+}
+
+/// Format a count with locale digit grouping
+pub fn count(n: impl TryInto<u64>) -> String {
+  NumberFormat::current().integer(n.try_into().unwrap_or(0))
+}
+
+/// Format a number with a fixed number of decimal places
+pub fn decimal(value: f64, places: usize) -> String {
+  NumberFormat::current().decimal(value, places)
+}
+
+/// Format an RFC 3339 timestamp for display
+///
+/// Values that don't parse are returned unchanged.
+pub fn timestamp(value: &str) -> String {
+  match DateTime::parse_from_rfc3339(value) {
+    Ok(time) => datetime(time.with_timezone(&Utc)),
+    Err(_) => value.to_string(),
+  }
+}
+
+/// Format a point in time for display
+pub fn datetime(time: DateTime<Utc>) -> String {
+  let options = options();
+  match (options.iso, options.utc) {
+    (true, true) => time.to_rfc3339_opts(SecondsFormat::Secs, true),
+    (true, false) => time.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::Secs, false),
+    (false, true) => time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    (false, false) => time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+  }
+}
+
+/// Display width of `text` in terminal columns
+pub fn width(text: &str) -> usize {
+  text.width()
+}
+
+/// Cut `text` to at most `max` columns, marking the cut with an ellipsis
+pub fn truncate(text: &str, max: usize) -> String {
+  if text.width() <= max {
+    return text.to_string();
+  }
+  let mut out = String::new();
+  let mut used = 0;
+  for c in text.chars() {
+    let w = c.width().unwrap_or(0);
+    if used + w + 1 > max {
+      break;
+    }
+    out.push(c);
+    used += w;
+  }
+  if max > 0 {
+    out.push('…');
+  }
+  out
+}
+
+/// Fit a line printed after `indent` columns into the terminal width
+pub fn fit(line: &str, indent: usize) -> String {
+  match terminal_width() {
+    Some(width) => truncate(line, width.saturating_sub(indent)),
+    None => line.to_string(),
+  }
+}
+
+/// First `max_chars` characters of `text` as a preview, unless `--no-trunc`
+pub fn preview(text: &str, max_chars: usize) -> String {
+  if options().no_trunc || text.chars().count() <= max_chars {
+    return text.to_string();
+  }
+  let cut: String = text.chars().take(max_chars).collect();
+  format!("{}...", cut)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_number_format_for_locales() {
+    let en = NumberFormat::for_locale("en_US.UTF-8");
+    assert_eq!(en.integer(1234567), "1,234,567", "english groups with commas");
+    assert_eq!(en.decimal(1234.5, 2), "1,234.50", "english decimal point");
+
+    let de = NumberFormat::for_locale("de_DE.UTF-8");
+    assert_eq!(de.integer(1234567), "1.234.567", "german groups with dots");
+    assert_eq!(de.decimal(-1234.56, 1), "-1.234,6", "german decimal comma");
+
+    let ch = NumberFormat::for_locale("de_CH");
+    assert_eq!(ch.integer(10000), "10'000", "swiss apostrophe grouping");
+
+    let plain = NumberFormat::for_locale("C");
+    assert_eq!(plain.integer(1234567), "1234567", "C locale is ungrouped");
+    assert_eq!(plain.decimal(1.23456, 3), "1.235", "C locale decimal point");
+    assert_eq!(plain.integer(999), "999", "short numbers are never grouped");
+  }
+
+  #[test]
+  fn test_truncate_respects_display_width() {
+    assert_eq!(truncate("short", 10), "short", "text that fits is unchanged");
+    assert_eq!(truncate("abcdefghij", 5), "abcd…", "cut text ends in an ellipsis");
+    assert_eq!(truncate("日本語テキスト", 7), "日本語…", "wide characters count double");
+    assert_eq!(width("日本"), 4, "width counts columns, not chars");
+  }
+
+  #[test]
+  fn test_timestamp_passes_through_unparseable_values() {
+    assert_eq!(timestamp("not a date"), "not a date", "unparseable input is kept");
+  }
+}
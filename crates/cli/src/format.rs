@@ -5,17 +5,21 @@
 
 use ccengram::ipc::{
   code::{
-    CodeCalleesResponse, CodeCallersResponse, CodeContextFullResponse, CodeContextResponse, CodeIndexResult, CodeItem,
-    CodeMemoriesResponse, CodeRelatedResponse, CodeSearchResult, CodeStatsResult,
+    CodeCalleesResponse, CodeCallersResponse, CodeContextFullResponse, CodeContextResponse, CodeDefinitionResponse,
+    CodeIndexResult, CodeItem, CodeMemoriesResponse, CodeReferencesResponse, CodeRelatedResponse, CodeSearchResult,
+    CodeStatsResult,
   },
   docs::{DocContextResult, DocSearchItem, DocsIngestFullResult},
+  entity::{EntityDetail, EntityItem},
   memory::{
-    MemoryAddResult, MemoryDeleteResult, MemoryFullDetail, MemoryItem, MemoryRelatedResult, MemorySearchResult,
-    MemorySupersedeResult, MemoryTimelineResult, MemoryUpdateResult,
+    MemoryAddResult, MemoryDeleteResult, MemoryFeedbackResult, MemoryFullDetail, MemoryItem, MemoryRelatedResult,
+    MemorySearchResult, MemorySupersedeResult, MemoryTimelineResult, MemoryUpdateResult,
+  },
+  project::{
+    ProjectCleanAllResult, ProjectCleanResult, ProjectInfoResult, ProjectStatsResult, SessionInjectionsResult,
   },
-  project::{ProjectCleanAllResult, ProjectCleanResult, ProjectInfoResult, ProjectStatsResult},
   relationship::{DeletedResult, RelatedMemoryItem, RelationshipListItem, RelationshipResult},
-  search::{ContextItem, ExploreResult},
+  search::{ContextItem, ExploreResult, PackResult},
   system::HealthCheckResult,
   watch::{WatchStartResult, WatchStatusResult, WatchStopResult},
 };
@@ -32,6 +36,9 @@ pub fn format_tool_result(tool_name: &str, result: &serde_json::Value) -> Option
     "context" => serde_json::from_value(result.clone())
       .ok()
       .map(|r: Vec<ContextItem>| format_context(&r)),
+    "explore_plan" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_explore_plan(&r)),
 
     // Code tools
     "code_search" => serde_json::from_value(result.clone())
@@ -58,6 +65,12 @@ pub fn format_tool_result(tool_name: &str, result: &serde_json::Value) -> Option
     "code_callees" => serde_json::from_value(result.clone())
       .ok()
       .map(|r| format_code_callees(&r)),
+    "code_definition" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_code_definition(&r)),
+    "code_references" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_code_references(&r)),
     "code_related" => serde_json::from_value(result.clone())
       .ok()
       .map(|r| format_code_related(&r)),
@@ -81,6 +94,9 @@ pub fn format_tool_result(tool_name: &str, result: &serde_json::Value) -> Option
     "memory_reinforce" | "memory_deemphasize" => serde_json::from_value(result.clone())
       .ok()
       .map(|r| format_memory_update(&r)),
+    "memory_feedback" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_memory_feedback(&r)),
     "memory_delete" => serde_json::from_value(result.clone())
       .ok()
       .map(|r| format_memory_delete(&r)),
@@ -119,6 +135,17 @@ pub fn format_tool_result(tool_name: &str, result: &serde_json::Value) -> Option
       .ok()
       .map(|r: Vec<RelatedMemoryItem>| format_relationship_related(&r)),
 
+    // Entity tools
+    "entity_list" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r: Vec<EntityItem>| format_entity_list("Entities", &r)),
+    "entity_top" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r: Vec<EntityItem>| format_entity_list("Top Entities", &r)),
+    "entity_get" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_entity_get(&r)),
+
     // Watch tools
     "watch_start" => serde_json::from_value(result.clone())
       .ok()
@@ -140,6 +167,9 @@ pub fn format_tool_result(tool_name: &str, result: &serde_json::Value) -> Option
     "project_clean_all" => serde_json::from_value(result.clone())
       .ok()
       .map(|r| format_project_clean_all(&r)),
+    "session_injections" => serde_json::from_value(result.clone())
+      .ok()
+      .map(|r| format_session_injections(&r)),
 
     // System tools
     "project_stats" => serde_json::from_value(result.clone())
@@ -263,6 +293,62 @@ fn format_explore(result: &ExploreResult) -> String {
   out
 }
 
+fn format_explore_plan(result: &PackResult) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!("# Plan: {}\n\n", result.query));
+  out.push_str(&format!(
+    "{} items, ~{} of {} tokens",
+    result.items.len(),
+    result.tokens,
+    result.budget
+  ));
+  if result.omitted > 0 {
+    out.push_str(&format!(" ({} more did not fit)", result.omitted));
+  }
+  out.push_str("\n\n");
+
+  if !result.steps.is_empty() {
+    out.push_str("Steps:\n");
+    for step in &result.steps {
+      out.push_str(&format!("  - {} {} ({} found)\n", step.kind, step.target, step.found));
+    }
+    out.push('\n');
+  }
+
+  for (i, item) in result.items.iter().enumerate() {
+    out.push_str(&format!(
+      "<item index=\"{}\" type=\"{}\" id=\"{}\"",
+      i + 1,
+      item.item_type,
+      &item.id[..8.min(item.id.len())]
+    ));
+    if let Some(ref source) = item.source {
+      out.push_str(&format!(" source=\"{}\"", source));
+    }
+    if let Some((start, end)) = item.lines {
+      out.push_str(&format!(" lines=\"{}-{}\"", start, end));
+    }
+    if item.truncated {
+      out.push_str(" truncated=\"true\"");
+    }
+    out.push_str(">\n");
+
+    out.push_str(&format!("{}\n", item.title));
+    if !item.provenance.is_empty() {
+      out.push_str(&format!("Found by: {}\n", item.provenance.join("; ")));
+    }
+    out.push('\n');
+    out.push_str(&format_preview(&item.content, item.language.as_deref()));
+    out.push_str("</item>\n\n");
+  }
+
+  if result.items.is_empty() {
+    out.push_str("No relevant memories, code or docs found.\n");
+  }
+  out
+}
+
 fn format_context(items: &[ContextItem]) -> String {
   let mut out = String::new();
 
@@ -349,6 +435,13 @@ fn format_code_search(result: &CodeSearchResult) -> String {
 
   out.push_str(&format!("# Code Search: {}\n\n", result.query));
 
+  if let Some(ref language) = result.inferred_language {
+    out.push_str(&format!(
+      "Language filter inferred from the query: {} (pass infer_language: false to search all)\n\n",
+      language
+    ));
+  }
+
   // Quality indicator
   if let Some(ref q) = result.search_quality
     && q.low_confidence
@@ -391,6 +484,12 @@ fn format_code_item(item: &CodeItem, index: usize) -> String {
   if let Some(sim) = item.similarity {
     out.push_str(&format!(" score=\"{:.2}\"", sim));
   }
+  if let Some(depth) = item.depth {
+    out.push_str(&format!(" depth=\"{}\"", depth));
+  }
+  if item.generated == Some(true) {
+    out.push_str(" generated=\"true\"");
+  }
   out.push_str(">\n");
 
   // Symbols
@@ -532,6 +631,18 @@ fn format_code_stats(result: &CodeStatsResult) -> String {
     }
   }
 
+  if let Some(reindex) = &result.reindex
+    && reindex.files > 0
+  {
+    out.push_str("\nReindexing (this session):\n");
+    out.push_str(&format!(
+      "  - Files parsed: {} ({} incremental)\n",
+      reindex.files, reindex.incremental_files
+    ));
+    out.push_str(&format!("  - Chunks embedded: {}\n", reindex.chunks_embedded));
+    out.push_str(&format!("  - Embeddings saved: {}\n", reindex.embeddings_reused));
+  }
+
   out
 }
 
@@ -562,7 +673,14 @@ fn format_code_callers(result: &CodeCallersResponse) -> String {
   let mut out = String::new();
 
   out.push_str(&format!("# Callers of: {}\n\n", result.symbol));
-  out.push_str(&format!("Found {} callers\n\n", result.count));
+  if result.depth > 1 {
+    out.push_str(&format!(
+      "Found {} callers (up to {} levels)\n\n",
+      result.count, result.depth
+    ));
+  } else {
+    out.push_str(&format!("Found {} callers\n\n", result.count));
+  }
 
   for (i, caller) in result.callers.iter().enumerate() {
     out.push_str(&format_code_item(caller, i + 1));
@@ -586,8 +704,10 @@ fn format_code_callees(result: &CodeCalleesResponse) -> String {
 
   out.push_str(&format!("Resolved ({}):\n", result.callees.len()));
   for callee in &result.callees {
+    let indent = "  ".repeat(callee.depth.unwrap_or(1));
     out.push_str(&format!(
-      "  - {} → [{}] {}:{}\n",
+      "{}- {} → [{}] {}:{}\n",
+      indent,
       callee.call,
       &callee.id[..8.min(callee.id.len())],
       callee.file_path,
@@ -605,6 +725,89 @@ fn format_code_callees(result: &CodeCalleesResponse) -> String {
   out
 }
 
+fn format_code_definition(result: &CodeDefinitionResponse) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!("# Definition of: {}\n\n", result.symbol));
+  if result.definitions.is_empty() {
+    out.push_str("No definition found\n");
+    return out;
+  }
+
+  for def in &result.definitions {
+    out.push_str(&format!(
+      "- [{}] {}:{}-{} ({})",
+      &def.id[..8.min(def.id.len())],
+      def.file_path,
+      def.start_line,
+      def.end_line,
+      def.resolution
+    ));
+    if let Some(ref kind) = def.definition_kind {
+      out.push_str(&format!(" {}", kind));
+    }
+    out.push('\n');
+    if let Some(ref signature) = def.signature {
+      out.push_str(&format!("  {}\n", signature));
+    }
+    if let Some(ref doc) = def.docstring {
+      for line in doc.lines() {
+        out.push_str(&format!("  {}\n", line));
+      }
+    }
+  }
+
+  out
+}
+
+fn format_code_references(result: &CodeReferencesResponse) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!("# References to: {}\n\n", result.symbol));
+  if let Some(ref def) = result.definition {
+    out.push_str(&format!(
+      "Defined at {}:{}-{}\n",
+      def.file_path, def.start_line, def.end_line
+    ));
+  }
+  if result.references.is_empty() {
+    out.push_str("No references found\n");
+    return out;
+  }
+  if result.truncated {
+    out.push_str(&format!(
+      "Showing {} of at least {} references\n\n",
+      result.count, result.total
+    ));
+  } else if result.total > result.count {
+    out.push_str(&format!("Showing {} of {} references\n\n", result.count, result.total));
+  } else {
+    out.push_str(&format!("Found {} references\n\n", result.count));
+  }
+
+  for r in &result.references {
+    out.push_str(&format!(
+      "- [{}] {}:{}-{} ({}, {})",
+      &r.id[..8.min(r.id.len())],
+      r.file_path,
+      r.start_line,
+      r.end_line,
+      r.kind,
+      r.proximity
+    ));
+    if let Some(ref name) = r.definition_name {
+      out.push_str(&format!(" in {}", name));
+    }
+    out.push('\n');
+    if !r.lines.is_empty() {
+      let lines: Vec<String> = r.lines.iter().map(|l| l.to_string()).collect();
+      out.push_str(&format!("  lines: {}\n", lines.join(", ")));
+    }
+  }
+
+  out
+}
+
 fn format_code_related(result: &CodeRelatedResponse) -> String {
   let mut out = String::new();
 
@@ -740,6 +943,27 @@ fn format_memory_search(result: &MemorySearchResult) -> String {
     out.push('\n');
   }
 
+  if let Some(ref groups) = result.groups {
+    let total: usize = groups.iter().map(|g| g.count).sum();
+    out.push_str(&format!("Found {} memories in {} groups\n\n", total, groups.len()));
+
+    let mut index = 0;
+    for group in groups {
+      out.push_str(&format!(
+        "## {} ({} memories, {} shown)\n\n",
+        group.key,
+        group.count,
+        group.items.len()
+      ));
+      for mem in &group.items {
+        index += 1;
+        out.push_str(&format_memory_item(mem, index));
+        out.push('\n');
+      }
+    }
+    return out;
+  }
+
   out.push_str(&format!("Found {} memories\n\n", result.items.len()));
 
   for (i, mem) in result.items.iter().enumerate() {
@@ -767,8 +991,15 @@ fn format_memory_item(item: &MemoryItem, index: usize) -> String {
   if item.is_superseded {
     out.push_str(" superseded=\"true\"");
   }
+  if item.stale {
+    out.push_str(" stale=\"true\"");
+  }
   out.push_str(">\n");
 
+  if let Some(ref reason) = item.stale_reason {
+    out.push_str(&format!("Stale: {}\n", reason));
+  }
+
   // Type and tier
   if let Some(ref t) = item.memory_type {
     out.push_str(&format!("Type: {} | Tier: {}\n", t, item.tier));
@@ -796,6 +1027,7 @@ fn format_memory_get(result: &MemoryFullDetail) -> String {
   if let Some(ref t) = result.memory_type {
     out.push_str(&format!("Type: {}\n", t));
   }
+  out.push_str(&format!("Visibility: {}\n", result.visibility));
   out.push_str(&format!(
     "Salience: {:.2} | Importance: {:.2} | Confidence: {:.2}\n",
     result.salience, result.importance, result.confidence
@@ -808,6 +1040,12 @@ fn format_memory_get(result: &MemoryFullDetail) -> String {
   if let Some(ref by) = result.superseded_by {
     out.push_str(&format!("Superseded by: {}\n", &by[..8.min(by.len())]));
   }
+  if result.stale {
+    out.push_str(&format!(
+      "⚠️ STALE: {}\n",
+      result.stale_reason.as_deref().unwrap_or("referenced code changed")
+    ));
+  }
 
   out.push_str(&format!("\nCreated: {}\n", result.created_at));
   out.push_str(&format!("Updated: {}\n", result.updated_at));
@@ -874,6 +1112,17 @@ fn format_memory_update(result: &MemoryUpdateResult) -> String {
   )
 }
 
+fn format_memory_feedback(result: &MemoryFeedbackResult) -> String {
+  format!(
+    "✓ {}: {} (helpful: {}, irrelevant: {}, score: {:+.2})",
+    result.message,
+    &result.id[..8.min(result.id.len())],
+    result.helpful_count,
+    result.irrelevant_count,
+    result.feedback_score
+  )
+}
+
 fn format_memory_delete(result: &MemoryDeleteResult) -> String {
   let mut out = format!("✓ Memory deleted: {}\n", &result.id[..8.min(result.id.len())]);
   if result.hard_delete {
@@ -1130,22 +1379,80 @@ fn format_relationship_related(items: &[RelatedMemoryItem]) -> String {
   out
 }
 
+// ============================================================================
+// Entity formatters
+// ============================================================================
+
+fn format_entity_list(title: &str, items: &[EntityItem]) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!("# {} ({} items)\n\n", title, items.len()));
+
+  for item in items {
+    out.push_str(&format!(
+      "  [{}] {} ({}) mentions={}\n",
+      &item.id[..8.min(item.id.len())],
+      item.name,
+      item.entity_type,
+      item.mentions
+    ));
+  }
+
+  out
+}
+
+fn format_entity_get(detail: &EntityDetail) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!(
+    "# {} ({})\n\nID: {}\nMentions: {}\n",
+    detail.entity.name, detail.entity.entity_type, detail.entity.id, detail.entity.mentions
+  ));
+  if let Some(ref last_seen) = detail.entity.last_seen {
+    out.push_str(&format!("Last seen: {}\n", last_seen));
+  }
+  out.push('\n');
+
+  for memory in &detail.memories {
+    out.push_str(&format!(
+      "<memory id=\"{}\" sector=\"{}\">\n",
+      &memory.id[..8.min(memory.id.len())],
+      memory.sector
+    ));
+    out.push_str(&truncate(&memory.content, 150));
+    out.push_str("\n</memory>\n\n");
+  }
+
+  out
+}
+
 // ============================================================================
 // Watch formatters
 // ============================================================================
 
 fn format_watch_start(result: &WatchStartResult) -> String {
-  format!(
+  let mut out = format!(
     "✓ Watcher started\nPath: {}\nProject: {}\n",
     result.path, result.project_id
-  )
+  );
+  for root in &result.roots {
+    out.push_str(&format!(
+      "Also watching: {} ({}, project {})\n",
+      root.path, root.status, root.project_id
+    ));
+  }
+  out
 }
 
 fn format_watch_stop(result: &WatchStopResult) -> String {
-  format!(
+  let mut out = format!(
     "✓ Watcher stopped\nPath: {}\nProject: {}\n",
     result.path, result.project_id
-  )
+  );
+  for root in &result.roots {
+    out.push_str(&format!("Also stopped: {} ({})\n", root.path, root.status));
+  }
+  out
 }
 
 fn format_watch_status(result: &WatchStatusResult) -> String {
@@ -1154,11 +1461,20 @@ fn format_watch_status(result: &WatchStatusResult) -> String {
   out.push_str("# Watcher Status\n\n");
 
   out.push_str(&format!("Running: {}\n", if result.running { "yes" } else { "no" }));
+  if result.paused {
+    out.push_str("Paused: yes (file changes are not being indexed)\n");
+  }
   if let Some(ref root) = result.root {
     out.push_str(&format!("Root: {}\n", root));
   }
   out.push_str(&format!("Project: {}\n", result.project_id));
   out.push_str(&format!("Pending changes: {}\n", result.pending_changes));
+  if let Some(ref storm) = result.last_storm {
+    out.push_str(&format!(
+      "Last event storm: {} changes coalesced into one rescan ({}s ago)\n",
+      storm.changes, storm.secs_ago
+    ));
+  }
 
   if result.scanning {
     out.push_str("⏳ Scanning in progress");
@@ -1168,6 +1484,21 @@ fn format_watch_status(result: &WatchStatusResult) -> String {
     out.push('\n');
   }
 
+  for root in &result.roots {
+    out.push_str(&format!(
+      "\n## Root: {}\n\nRunning: {}\nProject: {}\nPending changes: {}\n",
+      root.root.as_deref().unwrap_or("?"),
+      if root.running { "yes" } else { "no" },
+      root.project_id,
+      root.pending_changes
+    ));
+    if root.scanning
+      && let Some([current, total]) = root.scan_progress
+    {
+      out.push_str(&format!("⏳ Scanning in progress ({}/{})\n", current, total));
+    }
+  }
+
   out
 }
 
@@ -1207,6 +1538,47 @@ fn format_project_clean_all(result: &ProjectCleanAllResult) -> String {
   format!("✓ {} projects removed\n", result.projects_removed)
 }
 
+fn format_session_injections(result: &SessionInjectionsResult) -> String {
+  let mut out = String::new();
+
+  out.push_str(&format!(
+    "# Injected Context: session {}
+
+",
+    result.session_id
+  ));
+  if result.injections.is_empty() {
+    out.push_str("No memories were injected into this session.\n");
+    return out;
+  }
+
+  for item in &result.injections {
+    let text = item.summary.as_deref().or(item.content.as_deref());
+    let text = match text {
+      Some(text) if item.is_deleted => format!("(deleted) {}", text.lines().next().unwrap_or("")),
+      Some(text) => text.lines().next().unwrap_or("").to_string(),
+      None => "(memory no longer exists)".to_string(),
+    };
+    let mut why = item.rule.clone();
+    if let Some(score) = item.score {
+      why.push_str(&format!(", score {:.2}", score));
+    }
+    if let Some(ref detail) = item.detail {
+      why.push_str(&format!(", {}", detail));
+    }
+    out.push_str(&format!(
+      "- [{}] {} ({}: {}) id={}\n",
+      item.memory_type.as_deref().unwrap_or("memory"),
+      text,
+      item.hook_event,
+      why,
+      item.memory_id
+    ));
+  }
+
+  out
+}
+
 fn format_project_stats(result: &ProjectStatsResult) -> String {
   let mut out = String::new();
 
@@ -1220,6 +1592,27 @@ fn format_project_stats(result: &ProjectStatsResult) -> String {
   out.push_str(&format!("Documents: {}\n", result.documents));
   out.push_str(&format!("Sessions: {}\n", result.sessions));
 
+  if let Some(ref tables) = result.tables {
+    out.push_str("\n## Storage\n\n");
+    out.push_str("| Table | Rows | Size | Fragments | Vector index |\n");
+    out.push_str("|-------|------|------|-----------|--------------|\n");
+    for table in tables {
+      let index = match table.vector_index {
+        Some(ref kind) => format!("{} ({} unindexed)", kind, table.unindexed_rows),
+        None => "-".to_string(),
+      };
+      out.push_str(&format!(
+        "| {} | {} | {} | {} ({} small) | {} |\n",
+        table.name,
+        table.rows,
+        format_size(table.bytes),
+        table.fragments,
+        table.small_fragments,
+        index
+      ));
+    }
+  }
+
   out
 }
 
@@ -1267,6 +1660,16 @@ fn format_preview(content: &str, language: Option<&str>) -> String {
   }
 }
 
+fn format_size(bytes: usize) -> String {
+  if bytes < 1024 {
+    format!("{} B", bytes)
+  } else if bytes < 1024 * 1024 {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+  } else {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+  }
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
   let s = s.trim().replace('\n', " ");
   if s.len() <= max_len {
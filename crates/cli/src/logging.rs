@@ -1,8 +1,32 @@
 //! Logging utilities for CLI commands and daemon
 
-use ccengram::config::Config;
+use std::{
+  fs::{File, OpenOptions},
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+use ccengram::config::{Config, DaemonConfig, LoggingConfig};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+  EnvFilter, Layer, Registry,
+  filter::{FilterExt, filter_fn},
+  fmt::{MakeWriter, format::FmtSpan},
+  layer::SubscriberExt,
+  util::SubscriberInitExt,
+};
+
+/// Main daemon log file
+const LOG_FILE: &str = "ccengram.log";
+
+/// Files activity goes to with `logging.split_files`, by target prefix.
+/// Everything else stays in [`LOG_FILE`].
+const SPLIT_FILES: [(&str, &str); 2] = [
+  ("ccengram-hooks.log", "ccengram::service::hooks"),
+  ("ccengram-scheduler.log", "ccengram::actor::scheduler"),
+];
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
 
 /// Initialize logging for CLI commands (console only)
 pub fn init_cli_logging() {
@@ -29,95 +53,228 @@ fn parse_log_level(level: &str) -> tracing::Level {
   }
 }
 
+/// Filter directives from the config: the daemon level for ccengram and llm,
+/// then the per-module overrides, which win for their modules.
+fn filter_directives(daemon: &DaemonConfig, logging: &LoggingConfig) -> String {
+  // debug builds trace everything
+  let level = if cfg!(debug_assertions) {
+    "trace"
+  } else {
+    daemon.log_level.as_str()
+  };
+
+  let mut directives = vec![format!("ccengram={}", level), format!("llm={}", level)];
+  directives.extend(
+    logging
+      .modules
+      .iter()
+      .map(|(module, level)| format!("{}={}", module, level)),
+  );
+  directives.join(",")
+}
+
 /// Initialize logging for daemon with config-driven settings.
 ///
-/// In foreground mode: Logs to console only with colors
+/// In foreground mode: Logs to console with colors, and to file
 /// In background mode: Logs to file only (no ANSI)
 ///
-/// Returns the guard that must be kept alive for the duration of the program
-pub async fn init_daemon_logging_with_config(foreground: bool) -> Option<WorkerGuard> {
+/// Returns the guards that must be kept alive for the duration of the program
+pub async fn init_daemon_logging_with_config(foreground: bool) -> Vec<WorkerGuard> {
   // Load config from current directory (or defaults)
   let config = Config::load_global().await;
   let daemon_config = &config.daemon;
+  let logging = &config.logging;
 
   // Parse log level from config
   let level = parse_log_level(&daemon_config.log_level);
-  let default_directive = level.into();
-
-  // directives for debug builds
-  #[cfg(debug_assertions)]
-  let filter_directives = if let Ok(filter) = std::env::var("RUST_LOG") {
-    filter
-  } else {
-    "ccengram=trace,llm=trace".to_string()
+  let directives = std::env::var("RUST_LOG").unwrap_or_else(|_| filter_directives(daemon_config, logging));
+  let env_filter = || {
+    EnvFilter::builder()
+      .with_default_directive(level.into())
+      .parse_lossy(&directives)
   };
 
-  #[cfg(not(debug_assertions))]
-  let filter_directives = if let Ok(filter) = std::env::var("RUST_LOG") {
-    filter
-  } else {
-    format!("ccengram={},llm={}", &daemon_config.log_level, &daemon_config.log_level)
-  };
-
-  let env_filter = EnvFilter::builder()
-    .with_default_directive(default_directive)
-    .parse_lossy(filter_directives);
-
   // Setup file logging
   let log_dir = ccengram::dirs::default_data_dir();
   if tokio::fs::create_dir_all(&log_dir).await.is_err() {
     // Fall back to console-only logging
     init_cli_logging();
-    return None;
+    return Vec::new();
   }
 
-  // Create rolling file appender based on config
-  let file_appender = match daemon_config.log_rotation.as_str() {
-    "hourly" => tracing_appender::rolling::hourly(&log_dir, "ccengram.log"),
-    "never" => tracing_appender::rolling::never(&log_dir, "ccengram.log"),
-    _ => tracing_appender::rolling::daily(&log_dir, "ccengram.log"),
-  };
-
-  let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+  let json = logging.format.eq_ignore_ascii_case("json");
+  let mut guards = Vec::new();
+  let mut layers: Vec<BoxedLayer> = Vec::new();
 
   if foreground {
-    // Foreground mode: both console (with colors) and file logging
-    let subscriber = tracing_subscriber::registry()
-      .with(env_filter)
-      .with(
-        tracing_subscriber::fmt::layer()
-          .with_ansi(true)
-          .with_writer(std::io::stdout)
-          .with_span_events(FmtSpan::CLOSE)
-          .with_target(true),
-      )
-      .with(
-        tracing_subscriber::fmt::layer()
-          .with_ansi(false)
-          .with_writer(file_writer)
-          .with_span_events(FmtSpan::CLOSE)
-          .with_target(true),
+    layers.push(fmt_layer(std::io::stdout, true, json).with_filter(env_filter()).boxed());
+  }
+
+  if logging.split_files {
+    for (file, prefix) in SPLIT_FILES {
+      let (writer, guard) = tracing_appender::non_blocking(open_log(&log_dir, file, daemon_config, logging));
+      guards.push(guard);
+      let only_prefix = filter_fn(move |meta| meta.target().starts_with(prefix));
+      layers.push(
+        fmt_layer(writer, false, json)
+          .with_filter(env_filter().and(only_prefix))
+          .boxed(),
       );
+    }
+  }
 
-    #[cfg(feature = "tracy")]
-    let subscriber = subscriber.with(tracing_tracy::TracyLayer::default());
+  let split = logging.split_files;
+  let (writer, guard) = tracing_appender::non_blocking(open_log(&log_dir, LOG_FILE, daemon_config, logging));
+  guards.push(guard);
+  let rest = filter_fn(move |meta| !split || !SPLIT_FILES.iter().any(|(_, p)| meta.target().starts_with(p)));
+  layers.push(
+    fmt_layer(writer, false, json)
+      .with_filter(env_filter().and(rest))
+      .boxed(),
+  );
 
-    subscriber.init();
-  } else {
-    // Background mode: file logging only (no ANSI)
-    let subscriber = tracing_subscriber::registry().with(env_filter).with(
-      tracing_subscriber::fmt::layer()
-        .with_ansi(false)
-        .with_writer(file_writer)
-        .with_span_events(FmtSpan::CLOSE)
-        .with_target(true),
-    );
+  let subscriber = tracing_subscriber::registry().with(layers);
 
-    #[cfg(feature = "tracy")]
-    let subscriber = subscriber.with(tracing_tracy::TracyLayer::default());
+  #[cfg(feature = "tracy")]
+  let subscriber = subscriber.with(tracing_tracy::TracyLayer::default());
 
-    subscriber.init();
+  subscriber.init();
+
+  guards
+}
+
+/// Formatting layer writing text or JSON lines to `writer`
+fn fmt_layer<W>(writer: W, ansi: bool, json: bool) -> BoxedLayer
+where
+  W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+  let layer = tracing_subscriber::fmt::layer()
+    .with_ansi(ansi)
+    .with_writer(writer)
+    .with_span_events(FmtSpan::CLOSE)
+    .with_target(true);
+  if json { layer.json().boxed() } else { layer.boxed() }
+}
+
+/// Open a log file, rotated by size when `logging.max_file_mb` is set and by
+/// `daemon.log_rotation` otherwise
+fn open_log(dir: &Path, file: &str, daemon: &DaemonConfig, logging: &LoggingConfig) -> Box<dyn Write + Send> {
+  if logging.max_file_mb > 0 {
+    match SizeRotatingWriter::open(dir.join(file), logging.max_file_mb * 1024 * 1024, logging.max_files) {
+      Ok(writer) => return Box::new(writer),
+      Err(e) => eprintln!("ccengram: failed to open {}: {}", dir.join(file).display(), e),
+    }
+  }
+
+  // Create rolling file appender based on config
+  match daemon.log_rotation.as_str() {
+    "hourly" => Box::new(tracing_appender::rolling::hourly(dir, file)),
+    "never" => Box::new(tracing_appender::rolling::never(dir, file)),
+    _ => Box::new(tracing_appender::rolling::daily(dir, file)),
+  }
+}
+
+/// Log file rotated once it reaches a size limit.
+///
+/// On rotation `name` becomes `name.1`, `name.1` becomes `name.2` and so on;
+/// files beyond `max_files` are deleted.
+struct SizeRotatingWriter {
+  path: PathBuf,
+  max_bytes: u64,
+  max_files: usize,
+  file: File,
+  written: u64,
+}
+
+impl SizeRotatingWriter {
+  fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let written = file.metadata()?.len();
+    Ok(Self {
+      path,
+      max_bytes,
+      max_files,
+      file,
+      written,
+    })
+  }
+
+  fn rotated(&self, n: usize) -> PathBuf {
+    let mut name = self.path.clone().into_os_string();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+  }
+
+  fn rotate(&mut self) -> std::io::Result<()> {
+    self.file.flush()?;
+    if self.max_files == 0 {
+      std::fs::remove_file(&self.path)?;
+    } else {
+      let _ = std::fs::remove_file(self.rotated(self.max_files));
+      for n in (1..self.max_files).rev() {
+        let from = self.rotated(n);
+        if from.exists() {
+          std::fs::rename(&from, self.rotated(n + 1))?;
+        }
+      }
+      std::fs::rename(&self.path, self.rotated(1))?;
+    }
+    self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+    self.written = 0;
+    Ok(())
   }
+}
+
+impl Write for SizeRotatingWriter {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+      self.rotate()?;
+    }
+    let n = self.file.write(buf)?;
+    self.written += n as u64;
+    Ok(n)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    self.file.flush()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-  Some(guard)
+  #[test]
+  fn test_module_overrides_follow_the_daemon_level() {
+    let logging = LoggingConfig {
+      modules: [("ccengram::service::hooks".to_string(), "debug".to_string())].into(),
+      ..Default::default()
+    };
+    let directives = filter_directives(&DaemonConfig::default(), &logging);
+    assert!(
+      directives.ends_with(",ccengram::service::hooks=debug"),
+      "overrides come last so they win: {directives}"
+    );
+  }
+
+  #[test]
+  fn test_size_rotation_keeps_max_files() {
+    let dir = std::env::temp_dir().join(format!("ccengram-log-rotation-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.log");
+
+    let mut writer = SizeRotatingWriter::open(path.clone(), 10, 2).unwrap();
+    for line in ["first line\n", "second line\n", "third line\n", "fourth line\n"] {
+      writer.write_all(line.as_bytes()).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+    assert_eq!(read(&path), "fourth line\n");
+    assert_eq!(read(&dir.join("test.log.1")), "third line\n");
+    assert_eq!(read(&dir.join("test.log.2")), "second line\n");
+    assert!(!dir.join("test.log.3").exists(), "older files are deleted");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
 }
@@ -1,25 +1,39 @@
 //! CCEngram CLI - Intelligent memory and code search for Claude Code
 
-use std::{io, path::PathBuf};
+use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
 
 mod commands;
+mod display;
 mod format;
 mod logging;
 mod mcp;
+mod pager;
+mod table;
 mod tools;
 mod tui;
 
+#[cfg(test)]
+mod __tests__;
+
 #[cfg(all(unix, feature = "jemalloc-pprof"))]
 use commands::cmd_pprof;
 use commands::{
-  cmd_agent, cmd_archive, cmd_config_init, cmd_config_reset, cmd_config_show, cmd_context, cmd_daemon, cmd_delete,
-  cmd_deleted, cmd_health, cmd_hook, cmd_index, cmd_logs, cmd_logs_list, cmd_projects_clean, cmd_projects_clean_all,
-  cmd_projects_list, cmd_projects_show, cmd_restore, cmd_search, cmd_search_code, cmd_search_docs, cmd_show, cmd_stats,
-  cmd_tui, cmd_update, cmd_watch,
+  CompletionKind, cmd_agent, cmd_archive, cmd_audit_secrets, cmd_complete, cmd_config_init, cmd_config_reset,
+  cmd_config_show, cmd_context, cmd_context_pack, cmd_daemon, cmd_data_import, cmd_data_init, cmd_data_status,
+  cmd_db_repair, cmd_delete, cmd_deleted, cmd_egress, cmd_entities_show, cmd_explore_clear, cmd_explore_delete,
+  cmd_explore_export, cmd_explore_list, cmd_explore_note, cmd_explore_pin, cmd_explore_query, cmd_explore_save,
+  cmd_extract_failed, cmd_extract_inspect, cmd_extract_retry, cmd_extract_text, cmd_git_install_hooks, cmd_goal_update,
+  cmd_goals, cmd_health, cmd_health_probe, cmd_history, cmd_hook, cmd_index, cmd_logs, cmd_logs_list, cmd_pack,
+  cmd_projects_clean, cmd_projects_clean_all, cmd_projects_duplicates, cmd_projects_export, cmd_projects_import,
+  cmd_projects_list, cmd_projects_merge, cmd_projects_migrate, cmd_projects_show, cmd_references, cmd_repl,
+  cmd_restore, cmd_retro, cmd_search, cmd_search_code, cmd_search_docs, cmd_search_stats, cmd_sessions_import,
+  cmd_sessions_injections, cmd_sessions_list, cmd_sessions_show, cmd_show, cmd_stats, cmd_sync_pull, cmd_sync_push,
+  cmd_sync_resolve, cmd_tags_delete, cmd_tags_list, cmd_tags_merge, cmd_tags_rename, cmd_tui, cmd_undo, cmd_update,
+  cmd_usage, cmd_watch, dynamic_completion_script,
 };
 use logging::{init_cli_logging, init_daemon_logging_with_config};
 use mcp::cmd_mcp;
@@ -41,6 +55,18 @@ COMMON WORKFLOWS:
 struct Cli {
   #[command(subcommand)]
   command: Commands,
+  /// Show timestamps in UTC instead of local time
+  #[arg(long, global = true)]
+  utc: bool,
+  /// Stable output for scripts: RFC 3339 timestamps and ungrouped numbers
+  #[arg(long, global = true)]
+  iso: bool,
+  /// Show full content instead of cutting lines to the terminal width
+  #[arg(long, global = true)]
+  no_trunc: bool,
+  /// Print long output directly instead of through $PAGER
+  #[arg(long, global = true)]
+  no_pager: bool,
 }
 
 /// Subcommands for `ccengram index`
@@ -54,6 +80,15 @@ pub enum IndexCommand {
     /// Show index statistics
     #[arg(long)]
     stats: bool,
+    /// Only index files changed since this git revision (skips the full scan)
+    #[arg(long, conflicts_with = "stats")]
+    since: Option<String>,
+    /// List files left out by the indexing guard rails, and why
+    #[arg(long, conflicts_with_all = ["stats", "since", "force"])]
+    skipped: bool,
+    /// Estimate files, chunks, embedding tokens and cost without indexing
+    #[arg(long, conflicts_with_all = ["stats", "force", "skipped"])]
+    plan: bool,
   },
   /// Index documents from a directory
   Docs {
@@ -78,6 +113,31 @@ pub enum IndexCommand {
     #[arg(long)]
     force: bool,
   },
+  /// Fetch a web page and index it as a document
+  Url {
+    /// Page URL (http or https)
+    url: String,
+    /// Follow same-domain links this many levels deep
+    #[arg(short, long, default_value = "0")]
+    depth: usize,
+  },
+  /// Reindex files git reports as changed since a commit
+  Changed {
+    /// Commit to diff the working tree against
+    #[arg(long)]
+    since: String,
+  },
+}
+
+/// Subcommands for `ccengram git`
+#[derive(Subcommand)]
+pub enum GitCommand {
+  /// Install git hooks that reindex after commit, checkout and merge
+  InstallHooks {
+    /// Overwrite existing hooks not written by ccengram
+    #[arg(long)]
+    force: bool,
+  },
 }
 
 /// Subcommands for `ccengram search`
@@ -89,11 +149,17 @@ pub enum SearchCommand {
     after_help = "\
 NOTE:
   IDs are shown as 8-character prefixes by default. Use --long to show full IDs.
-  You can use these prefixes directly in commands like 'memory show <prefix>'."
+  You can use these prefixes directly in commands like 'memory show <prefix>'.
+
+SAVED SEARCHES:
+  --saved runs a [[search.saved]] entry from the config. A query or filter
+  given alongside it replaces the saved one, so 'search memories --saved
+  auth-gotchas \"token refresh\"' keeps the saved filters with a new query."
   )]
   Memories {
     /// Search query
-    query: String,
+    #[arg(required_unless_present = "saved")]
+    query: Option<String>,
     #[arg(short, long, default_value = "10")]
     limit: usize,
     /// Project path (default: current directory)
@@ -114,6 +180,15 @@ NOTE:
     /// Filter by scope path prefix
     #[arg(long)]
     scope: Option<String>,
+    /// Group results by scope path, memory type or tag (limit then counts groups)
+    #[arg(long, value_parser = ["scope_path", "memory_type", "tag"])]
+    group_by: Option<String>,
+    /// Top hits shown per group (default: 3)
+    #[arg(long, requires = "group_by")]
+    per_group: Option<usize>,
+    /// Run a saved search from [[search.saved]] in the config
+    #[arg(long, value_name = "NAME")]
+    saved: Option<String>,
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -142,6 +217,18 @@ NOTE:
     /// Filter by symbol name
     #[arg(long)]
     symbol: Option<String>,
+    /// Drop code indexed on another branch that has changed since
+    #[arg(long)]
+    current_branch: bool,
+    /// Only search files that differ between this branch and HEAD
+    #[arg(long, value_name = "BRANCH")]
+    diff_branch: Option<String>,
+    /// Don't infer a language filter from languages named in the query
+    #[arg(long)]
+    no_infer_language: bool,
+    /// Rank generated code alongside hand-written code
+    #[arg(long)]
+    include_generated: bool,
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -222,6 +309,128 @@ EXAMPLES:
     #[arg(long)]
     json: bool,
   },
+  /// Show every recorded change to a memory
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram memory history 3f2a9c         # Adds, updates, reinforcements, deletes
+  ccengram memory history 3f2a9c --json")]
+  History {
+    /// Memory ID (prefix match supported; the full ID for hard-deleted memories)
+    id: String,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Revert the most recent delete, supersede or archive run
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram memory undo                   # Restore what the last archive run deleted
+  ccengram memory undo --dry-run         # Show what would be restored")]
+  Undo {
+    /// Show what would be reverted without reverting it
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// List goal memories and their status
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram memory goals                  # Open and blocked goals
+  ccengram memory goals --status done    # Goals already reached
+  ccengram memory goals --status all --json")]
+  Goals {
+    /// active (open and blocked), open, blocked, done or all
+    #[arg(short, long)]
+    status: Option<String>,
+    /// Maximum number of goals to show
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Change the status or due hint of a goal
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram memory goal 3f2a9c --status blocked
+  ccengram memory goal 3f2a9c --status done
+  ccengram memory goal 3f2a9c --due \"end of sprint\"")]
+  Goal {
+    /// Goal memory ID (prefix match supported)
+    id: String,
+    /// open, blocked or done
+    #[arg(short, long)]
+    status: Option<String>,
+    /// Due hint; an empty string clears it
+    #[arg(long)]
+    due: Option<String>,
+  },
+  /// Extract memories from notes, PR descriptions or other text
+  #[command(
+    long_about = "Extract memories from notes, PR descriptions or other text.\n\n\
+    Runs the same pipeline as hook extraction: each part of the text is classified, \
+    memories are extracted by the LLM, checked against existing memories and stored. \
+    Reads stdin when no file is given. Requires an LLM provider.",
+    after_help = "\
+EXAMPLES:
+  ccengram memory extract --file notes.md             # Extract from a file
+  gh pr view 42 --json body -q .body | ccengram memory extract --source \"PR #42\""
+  )]
+  Extract {
+    /// File to read, or - for stdin
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+    /// Where the text came from, shown to the LLM (defaults to the file name)
+    #[arg(long)]
+    source: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram extract`
+#[derive(Subcommand)]
+pub enum ExtractCommand {
+  /// Show the LLM prompts and raw responses captured for an extraction segment
+  #[command(
+    long_about = "Show the LLM prompts and raw responses captured for an extraction segment.\n\n\
+    Requires `debug_capture = true` in the [hooks] config section. The segment ID \
+    of an extracted memory is shown by `ccengram memory show`."
+  )]
+  Inspect {
+    /// Segment ID (or unique prefix)
+    segment_id: String,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// List failed extractions waiting to be retried
+  #[command(long_about = "List failed extractions waiting to be retried.\n\n\
+    Segments whose LLM extraction fails are queued and retried with exponential \
+    backoff. After `extraction_retry_attempts` retries they are dead-lettered and \
+    only retried by `ccengram extract retry`.")]
+  Failed {
+    /// Only show dead-lettered extractions
+    #[arg(long)]
+    dead: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Retry failed extractions now
+  #[command(long_about = "Retry failed extractions now.\n\n\
+    Retries the given jobs, or every queued job when none are given. \
+    Dead-lettered jobs get a fresh set of attempts.")]
+  Retry {
+    /// Job IDs (or unique prefixes)
+    ids: Vec<String>,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
 }
 
 /// Subcommands for `ccengram config`
@@ -247,6 +456,124 @@ pub enum ConfigCommand {
   Reset,
 }
 
+/// Subcommands for `ccengram sessions`
+#[derive(Subcommand)]
+pub enum SessionsCommand {
+  /// List recent Claude Code sessions
+  List {
+    /// Maximum number of sessions to show
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+    /// Only show sessions that have not ended
+    #[arg(long)]
+    active: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Show the memories a session created, recalled, updated or reinforced
+  Show {
+    /// Session ID or prefix
+    id: String,
+    /// Show the memories hooks injected into the session and why, instead
+    #[arg(long)]
+    injections: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Extract memories from past Claude Code session transcripts
+  #[command(long_about = "Extract memories from past Claude Code session transcripts.\n\n\
+    Transcripts are the JSONL files under ~/.claude/projects/. Each user turn is \
+    replayed through the same extraction that runs when a session stops, so \
+    sessions from before ccengram was installed can seed memories. Requires an \
+    LLM provider.")]
+  Import {
+    /// Transcript JSONL files
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+    /// Project to import into (default: the directory recorded in the transcript)
+    #[arg(short, long)]
+    project: Option<PathBuf>,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram entities`
+#[derive(Subcommand)]
+pub enum EntitiesCommand {
+  /// Show an entity's mention timeline, co-occurring entities, memories and code
+  Show {
+    /// Entity name, ID or ID prefix
+    entity: String,
+    /// Maximum memories and code chunks to show
+    #[arg(short, long, default_value = "5")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram tags`
+#[derive(Subcommand)]
+pub enum TagsCommand {
+  /// List tags with the number of memories using each, most used first
+  List {
+    /// Only tags used by at least this many memories
+    #[arg(long)]
+    min: Option<usize>,
+    /// Maximum tags to show
+    #[arg(short, long, default_value = "100")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Rename a tag on every memory using it
+  Rename {
+    /// Current tag name
+    from: String,
+    /// New tag name (must not be in use; merge into existing tags instead)
+    to: String,
+    /// Show how many memories would change without changing them
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Replace several tags with one on every memory using them
+  Merge {
+    /// Tags to merge
+    #[arg(required = true)]
+    tags: Vec<String>,
+    /// Tag they become
+    #[arg(long)]
+    into: String,
+    /// Show how many memories would change without changing them
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Remove tags from every memory
+  Delete {
+    /// Tags to remove
+    #[arg(required = true)]
+    tags: Vec<String>,
+    /// Show how many memories would change without changing them
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
 /// Subcommands for `ccengram projects`
 #[derive(Subcommand)]
 pub enum ProjectsCommand {
@@ -278,6 +605,223 @@ pub enum ProjectsCommand {
     #[arg(long)]
     force: bool,
   },
+  /// Move a project's data to its new location after the project was moved or renamed
+  Migrate {
+    /// Old project path, project ID or ID prefix
+    from: String,
+    /// New project path
+    to: String,
+  },
+  /// Find projects that are clones of the same repository and offer to merge them
+  Duplicates {
+    /// List duplicates as JSON without merging
+    #[arg(long)]
+    json: bool,
+  },
+  /// Merge one clone's memories into another clone of the same repository
+  Merge {
+    /// Project path, ID or ID prefix whose memories are moved and whose store is removed
+    from: String,
+    /// Project path, ID or ID prefix whose store is kept
+    into: String,
+  },
+  /// Package a project's tables, config and metadata into a snapshot archive
+  Export {
+    /// Archive to write (e.g. project.tar.zst)
+    path: String,
+    /// Project path, ID or ID prefix (default: current project)
+    #[arg(short, long)]
+    project: Option<String>,
+    /// Overwrite an existing archive
+    #[arg(long)]
+    force: bool,
+    /// Keep private memories (for moving to another of your own machines)
+    #[arg(long)]
+    include_private: bool,
+  },
+  /// Restore a snapshot archive into a project on this machine
+  Import {
+    /// Archive written by `projects export`
+    path: String,
+    /// Project path to import into (default: current directory)
+    #[arg(short, long)]
+    project: Option<String>,
+  },
+}
+
+/// Subcommands for `ccengram data`
+#[derive(Subcommand)]
+pub enum DataCommand {
+  /// Show the data versions in the data directory
+  Status,
+  /// Copy data from an older ccengram into this build's data version
+  Import {
+    /// Version to import from (default: newest older version; 0 is unversioned data, which is
+    /// converted to the current schema and re-embedded where vector widths differ)
+    #[arg(long)]
+    from: Option<u32>,
+    /// Move the data instead of copying it (the older version can no longer use it)
+    #[arg(long = "move")]
+    move_data: bool,
+  },
+  /// Start an empty data store for this build, leaving other versions alone
+  Init,
+}
+
+/// Subcommands for `ccengram sync`
+#[derive(Subcommand)]
+pub enum SyncCommand {
+  /// Write shared memories to the sync directory
+  Push {
+    /// Show what would change without writing files
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Merge memories from the sync directory into the local store
+  Pull {
+    /// Show what would change without touching the store
+    #[arg(long)]
+    dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Settle memories edited on both sides
+  Resolve {
+    /// Memory ID or prefix (all conflicts if omitted)
+    id: Option<String>,
+    /// Resolve without prompting by keeping one side
+    #[arg(long, value_parser = ["local", "remote"])]
+    take: Option<String>,
+  },
+}
+
+/// Subcommands for `ccengram context`
+#[derive(Subcommand)]
+pub enum ContextCommand {
+  /// Assemble top preferences, decisions and gotchas into a Markdown block for CLAUDE.md
+  Pack {
+    /// Token budget for the block
+    #[arg(short, long, default_value = "4000")]
+    budget: usize,
+    /// Write the block into the managed section of CLAUDE.md instead of printing it
+    #[arg(short, long)]
+    write: bool,
+    /// File to write instead of CLAUDE.md at the project root (implies --write)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram stats`
+#[derive(Subcommand)]
+pub enum StatsCommand {
+  /// Top queries, queries with no results and average similarity for this project
+  Search {
+    /// Days of searches to cover
+    #[arg(short, long, default_value = "30")]
+    days: u32,
+    /// Queries listed in each table
+    #[arg(short, long, default_value = "10")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram explore`
+#[derive(Subcommand)]
+pub enum ExploreCommand {
+  /// Search and record the query and its top results in the draft
+  Query {
+    /// Search query
+    query: String,
+    /// Scope: all, code, memory, docs or a comma-separated mix
+    #[arg(short, long)]
+    scope: Option<String>,
+    /// Maximum results
+    #[arg(short, long, default_value = "10")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Pin a memory, code chunk or document chunk to the draft
+  Pin {
+    /// ID or ID prefix
+    id: String,
+    /// Why it matters
+    #[arg(short, long)]
+    note: Option<String>,
+  },
+  /// Add a note to the draft
+  Note {
+    /// Note text
+    text: String,
+  },
+  /// Save the draft under a name and start a new one
+  Save {
+    /// Name (letters, digits, '-', '_' and '.')
+    name: String,
+    /// Replace an exploration with the same name
+    #[arg(short, long)]
+    force: bool,
+  },
+  /// List saved explorations
+  List {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+  /// Print a saved exploration, or the draft, as Markdown
+  Export {
+    /// Saved exploration (default: the draft)
+    name: Option<String>,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+  },
+  /// Open a saved exploration in the TUI
+  Open {
+    /// Saved exploration
+    name: String,
+  },
+  /// Delete a saved exploration
+  Delete {
+    /// Saved exploration
+    name: String,
+  },
+  /// Discard the draft
+  Clear,
+}
+
+/// Subcommands for `ccengram audit`
+#[derive(Subcommand)]
+pub enum AuditCommand {
+  /// Scan stored memories and chunks for secrets
+  Secrets {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
+}
+
+/// Subcommands for `ccengram db`
+#[derive(Subcommand)]
+pub enum DbCommand {
+  /// Restore tables set aside because they could not be read
+  Repair {
+    /// Output as JSON (skips re-indexing)
+    #[arg(long)]
+    json: bool,
+  },
 }
 
 #[derive(Subcommand)]
@@ -332,6 +876,24 @@ NOTE:
     #[command(subcommand)]
     command: MemoryCommand,
   },
+  /// Write a retrospective for a task or goal
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram retro 3f2a9c                  # Retrospective of a goal or task completion
+  ccengram retro 3f2a9c --json
+
+USAGE:
+  Gathers the plan memories, task completions, gotchas and decisions linked
+  to or similar to the task or goal, and asks the LLM to compare the plan
+  with what happened. The retrospective is stored as a reflective memory
+  tagged retro and linked to the task or goal. Requires an LLM provider.")]
+  Retro {
+    /// Task completion or goal memory ID (prefix match supported)
+    id: String,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
   /// Manage code and document index
   #[command(after_help = "\
 WORKFLOW:
@@ -339,8 +901,13 @@ WORKFLOW:
 
   ccengram index code             # Index source files
   ccengram index code --force     # Re-index everything
+  ccengram index code --since origin/main  # Index only files changed since a revision
+  ccengram index code --skipped   # Files left out (too large, binary, minified, ...)
+  ccengram index code --plan      # Estimate chunks, tokens and cost before indexing
   ccengram index docs             # Index documentation
   ccengram index file <path>      # Index a single file
+  ccengram index url <url>        # Fetch and index a web page
+  ccengram index changed --since HEAD~1  # Reindex files changed since a commit
 
 SUPPORTED LANGUAGES:
   Rust, Python, TypeScript, JavaScript, Go, and more via tree-sitter")]
@@ -355,7 +922,7 @@ PRESETS:
   standard  - Above + memory_add, memory_reinforce, memory_deemphasize,
               code_index, code_stats, watch_start, watch_stop,
               watch_status, project_stats (11 tools)
-  full      - All 34 available tools
+  full      - All 36 available tools
 
 CONFIG LOCATIONS:
   Project: .claude/ccengram.toml
@@ -372,6 +939,12 @@ CONFIG LOCATIONS:
     /// Check watcher status
     #[arg(long)]
     status: bool,
+    /// Pause indexing of file changes (e.g. during a build) without stopping the watcher
+    #[arg(long, conflicts_with_all = ["stop", "status", "resume"])]
+    pause: bool,
+    /// Resume a paused watcher, reindexing files changed while it was paused
+    #[arg(long, conflicts_with_all = ["stop", "status"])]
+    resume: bool,
     /// Skip startup scan (don't reconcile with filesystem on start)
     #[arg(long)]
     no_startup_scan: bool,
@@ -381,21 +954,37 @@ CONFIG LOCATIONS:
     /// Wait for startup scan to complete before watching
     #[arg(long)]
     startup_scan_sync: bool,
+    /// Also watch another workspace root, e.g. a sibling shared library (repeatable)
+    #[arg(long = "root", value_name = "PATH")]
+    roots: Vec<String>,
   },
   /// Get surrounding context for a code or document chunk
-  #[command(after_help = "\
+  #[command(
+    after_help = "\
 EXAMPLES:
   ccengram context 019abc                    # Get context (auto-detects type)
   ccengram context 019abc --before 30        # Get 30 lines/chunks before
   ccengram context 019abc --json             # Output as JSON
+  ccengram context pack --budget 4000        # Memory primer for CLAUDE.md
+  ccengram context pack --write              # Update the primer in CLAUDE.md
 
 USAGE:
   Use chunk IDs from 'search code' or 'search docs' results.
   For code chunks: --before/--after specify lines (default: 20, max: 500)
-  For doc chunks: --before/--after specify chunks (default: 1, max: 10)")]
+  For doc chunks: --before/--after specify chunks (default: 1, max: 10)
+
+  'context pack' lists the highest-salience preferences, decisions and
+  gotchas. With --write it replaces only the block between the ccengram
+  markers, so Claude Code starts every session primed without MCP calls.",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+  )]
   Context {
+    #[command(subcommand)]
+    command: Option<ContextCommand>,
     /// Chunk ID from search results (8+ character prefix works)
-    chunk_id: String,
+    #[arg(required = true)]
+    chunk_id: Option<String>,
     /// Lines/chunks to include before (code: 20, docs: 1)
     #[arg(short, long)]
     before: Option<usize>,
@@ -406,10 +995,142 @@ USAGE:
     #[arg(long)]
     json: bool,
   },
+  /// Bundle relevant memories, code and docs into a token-budgeted context pack
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram pack -q \"payments refactor\"                   # Markdown to stdout
+  ccengram pack -q \"payments refactor\" -b 4000 -o ctx.md # Smaller pack written to a file
+  ccengram pack -q \"auth flow\" --scope code --format json
+  ccengram pack -q \"Make retry_after honour the Retry-After header\" --plan
+
+USAGE:
+  Results are taken best first until the budget is spent; the last one that
+  fits partly is cut. Paste the pack into any LLM tool to give it the
+  project's memory.
+
+  With --plan the query is a task description: ccengram also searches for
+  the identifiers and paths it names, follows callers and callees of the
+  best code results, and notes under each item how it was found.")]
+  Pack {
+    /// What the pack should be about
+    #[arg(short, long)]
+    query: String,
+    /// Token budget for the packed content
+    #[arg(short, long, default_value = "8000")]
+    budget: usize,
+    /// Sources to include: code, memory, docs or all
+    #[arg(long, value_parser = ["code", "memory", "docs", "all"])]
+    scope: Option<String>,
+    /// Treat the query as a task and plan several searches and call-graph hops
+    #[arg(long)]
+    plan: bool,
+    /// Call-graph hops to follow with --plan (default 1, max 3)
+    #[arg(long, requires = "plan")]
+    hops: Option<usize>,
+    /// Output format
+    #[arg(long, default_value = "markdown", value_parser = ["markdown", "json"])]
+    format: String,
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+  },
+  /// Find code referencing a symbol
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram references ProjectDb                       # All references, nearest to the definition first
+  ccengram references Config::load                    # Qualified symbols pick the matching definition
+  ccengram references load --file src/main.rs         # Resolve the definition through a file's imports
+  ccengram references ProjectDb --json                # Output as JSON
+
+USAGE:
+  Combines indexed call sites with identifier matches in code chunks.
+  Results are ranked by proximity to the definition: same file, files
+  importing its module, same directory, then everything else.")]
+  References {
+    /// Symbol name, optionally qualified (e.g. Config::load)
+    symbol: String,
+    /// File the symbol is referenced from, used to pick its definition
+    #[arg(short, long)]
+    file: Option<String>,
+    /// Maximum results
+    #[arg(short, long, default_value = "50")]
+    limit: usize,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
   /// Show statistics
-  Stats,
+  #[command(
+    after_help = "\
+EXAMPLES:
+  ccengram stats                   # Daemon and project statistics
+  ccengram stats --egress          # Text sent to each provider this week
+  ccengram stats --egress --days 30
+  ccengram stats search            # What this project searches for, last 30 days
+  ccengram stats search --days 7 --json
+
+EGRESS:
+  Counts the characters and chunks sent to each embedding and LLM provider,
+  split into providers off this machine and local ones. Set
+  daemon.strict_local = true to refuse network providers entirely.
+
+SEARCH:
+  Reads the project's search log: the most frequent queries, those that
+  returned nothing, and how many searches had a result opened or marked
+  helpful afterwards. Set search.log_queries = false to stop logging.",
+    args_conflicts_with_subcommands = true
+  )]
+  Stats {
+    #[command(subcommand)]
+    command: Option<StatsCommand>,
+    /// Report data sent to each provider instead
+    #[arg(long)]
+    egress: bool,
+    /// Days of history in the egress report
+    #[arg(short, long, default_value = "7", requires = "egress")]
+    days: u32,
+    /// Output the egress report as JSON
+    #[arg(long, requires = "egress")]
+    json: bool,
+  },
   /// Health check
-  Health,
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram health                      # Full report (starts the daemon if needed)
+  ccengram health --probe liveness     # Exit 0 if the daemon answers a ping
+  ccengram health --probe readiness    # Exit 0 if the daemon is serving requests
+
+PROBES:
+  Probes print nothing, never start the daemon and give up after 2 seconds.
+  The exit code is 0 when the check passes and 1 otherwise, for systemd
+  watchdogs, container healthchecks and editor plugins polling status.")]
+  Health {
+    /// Run a silent, exit-code-only check: liveness or readiness
+    #[arg(long, value_parser = ["liveness", "readiness"])]
+    probe: Option<String>,
+  },
+  /// Show LLM and embedding token usage and cost
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram usage                   # Last 30 days, all projects
+  ccengram usage --days 7          # Last week
+  ccengram usage --project myapp   # Projects whose path contains 'myapp'
+
+BUDGET:
+  Set llm.monthly_budget_usd in the config to pause background extraction
+  once a calendar month's spend reaches it. Embedding cost is reported when
+  embedding.cost_per_million_tokens is set.")]
+  Usage {
+    /// Days of history to show
+    #[arg(short, long, default_value = "30")]
+    days: u32,
+    /// Only show projects whose path contains this
+    #[arg(short, long)]
+    project: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+  },
   /// Check for updates or update to latest version
   Update {
     /// Only check for updates without installing
@@ -433,17 +1154,172 @@ USAGE:
     /// Project path (default: current directory)
     #[arg(short, long)]
     project: Option<PathBuf>,
+    /// Open a saved exploration in the Search view
+    #[arg(short, long)]
+    exploration: Option<String>,
+  },
+  /// Interactive command prompt over one daemon connection
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram> search auth token refresh
+  ccengram> show $1
+  ccengram> context $2
+  ccengram> link $1 $3 builds_on
+
+IDs from the last result list can be used as $1, $2, ...
+Type 'help' at the prompt for all commands.")]
+  Repl {
+    /// Project path (default: current directory)
+    #[arg(short, long)]
+    project: Option<PathBuf>,
+  },
+  /// Integrate with git
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram git install-hooks          # Reindex after commit, checkout and merge
+  ccengram git install-hooks --force  # Replace existing hooks")]
+  Git {
+    #[command(subcommand)]
+    command: GitCommand,
   },
   /// Manage indexed projects
   #[command(after_help = "\
 EXAMPLES:
   ccengram projects list                  # List all indexed projects
   ccengram projects show /path/to/project # Show project details
-  ccengram projects clean /path/to/project # Remove project data")]
+  ccengram projects clean /path/to/project # Remove project data
+  ccengram projects migrate ~/old/app ~/src/app # Keep data after moving a project
+  ccengram projects duplicates            # Find clones of one repo and merge their memories
+  ccengram projects export app.tar.zst    # Snapshot this project for a teammate or another machine
+  ccengram projects import app.tar.zst    # Restore a snapshot into this project")]
   Projects {
     #[command(subcommand)]
     command: ProjectsCommand,
   },
+  /// Manage the versioned data directory
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram data status            # Show data versions and which one is in use
+  ccengram data import            # Copy older data into this build's version
+  ccengram data import --move     # Move it instead (older ccengram loses it)
+  ccengram data init              # Start empty next to newer data")]
+  Data {
+    #[command(subcommand)]
+    command: DataCommand,
+  },
+  /// Share team memories through a directory committed with the project
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram sync push              # Write shared memories to .ccengram/memories
+  ccengram sync push --dry-run    # Show what would be written
+  ccengram sync pull              # Merge teammates' memories after a git pull
+  ccengram sync resolve           # Walk through conflicts left by a pull
+  ccengram sync resolve abc --take remote
+
+Only team-visible memories of the types in sync.types are written (decision,
+pattern, gotcha and codebase by default). Each memory is one JSON file, so
+git merges them like any other file. On pull each memory is merged against
+the version last synced: fields changed on one side are taken, scores keep
+the higher value and tag edits combine. Content edited differently on both
+sides is a conflict that keeps the local version until resolved.")]
+  Sync {
+    #[command(subcommand)]
+    command: SyncCommand,
+  },
+  /// Repair the project's database
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram db repair              # Restore quarantined tables and re-index what they held
+  ccengram db repair --json       # Report only; re-index yourself if asked to
+
+USAGE:
+  A table that can't be read when the project opens is moved to the
+  project's quarantine directory and recreated empty, so everything else
+  keeps working. Repair copies back the newest readable version of each
+  such table and rebuilds code, docs, call edges and entity links from
+  their sources.")]
+  Db {
+    #[command(subcommand)]
+    command: DbCommand,
+  },
+  /// Record, save and export explorations of the codebase
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram explore query \"token refresh\"        # Search and record in the draft
+  ccengram explore pin a1b2c3 -n \"races logout\" # Pin a result with a note
+  ccengram explore note \"Lock is not held here\"
+  ccengram explore save auth-race                 # Name the draft and start a new one
+  ccengram explore open auth-race                 # Re-open it in the TUI
+  ccengram explore export auth-race -o auth-race.md
+
+USAGE:
+  Queries run here or in the TUI Search view, results pinned (p in the TUI)
+  and notes collect in order in a draft exploration. Saving names the draft
+  so the investigation can be resumed in the TUI or shared as Markdown.
+  Explore calls made by Claude through MCP are not recorded.")]
+  Explore {
+    #[command(subcommand)]
+    command: ExploreCommand,
+  },
+  /// Audit stored data
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram audit secrets          # List secrets found in memories, code and docs
+  ccengram audit secrets --json
+
+USAGE:
+  Secrets are redacted before memories and chunks are stored (see the
+  [redaction] config), but data stored before redaction was enabled may
+  still hold them. The audit scans everything with every detector and
+  shows where each secret is, masked to its first few characters.")]
+  Audit {
+    #[command(subcommand)]
+    command: AuditCommand,
+  },
+  /// Browse and import Claude Code sessions
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram sessions list --active
+  ccengram sessions show 0192f3a1
+  ccengram sessions show 0192f3a1 --injections
+  ccengram sessions import ~/.claude/projects/-home-me-app/0192f3a1.jsonl
+  ccengram sessions import ~/.claude/projects/-home-me-app/*.jsonl
+  ccengram sessions import session.jsonl --project ~/app")]
+  Sessions {
+    #[command(subcommand)]
+    command: SessionsCommand,
+  },
+  /// Explore the people, technologies, services and files memories mention
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram entities show Redis          # What do we know about Redis?
+  ccengram entities show src/billing.rs # Memories and code for a file")]
+  Entities {
+    #[command(subcommand)]
+    command: EntitiesCommand,
+  },
+  /// Curate the tags on memories
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram tags list --min 2                        # Tags used more than once
+  ccengram tags rename postgres postgresql          # Rename a tag everywhere
+  ccengram tags merge pg postgres --into postgresql # Fold synonyms into one tag
+  ccengram tags delete misc todo --dry-run          # Show what dropping noise tags would touch")]
+  Tags {
+    #[command(subcommand)]
+    command: TagsCommand,
+  },
+  /// Debug memory extraction
+  #[command(after_help = "\
+EXAMPLES:
+  ccengram extract inspect 0192f3a1       # Show prompts and responses for a segment
+  ccengram extract failed                 # List extractions waiting to be retried
+  ccengram extract retry                  # Retry all failed extractions now
+  ccengram extract retry 0192f3a1-7c2e    # Retry one failed extraction")]
+  Extract {
+    #[command(subcommand)]
+    command: ExtractCommand,
+  },
   /// View daemon logs
   #[command(after_help = "\
 EXAMPLES:
@@ -451,6 +1327,7 @@ EXAMPLES:
   ccengram logs -f                 # Follow logs in real-time
   ccengram logs -n 100             # Show last 100 lines
   ccengram logs --level error      # Filter by log level
+  ccengram logs --source hooks     # Hook activity (with logging.split_files)
   ccengram logs --open             # Open log directory")]
   Logs {
     /// Follow log output in real-time (like tail -f)
@@ -465,6 +1342,9 @@ EXAMPLES:
     /// Filter logs by level (error, warn, info, debug, trace)
     #[arg(long)]
     level: Option<String>,
+    /// Which log to show when `logging.split_files` is on
+    #[arg(long, value_parser = ["daemon", "hooks", "scheduler"])]
+    source: Option<String>,
     /// Open log directory in file manager
     #[arg(long)]
     open: bool,
@@ -497,12 +1377,25 @@ INSTALLATION:
     ccengram completions fish > ~/.config/fish/completions/ccengram.fish
 
   PowerShell:
-    ccengram completions powershell >> $PROFILE")]
+    ccengram completions powershell >> $PROFILE
+
+Bash, zsh and fish scripts also complete memory IDs, project paths, session
+IDs and tags from the running daemon.")]
   Completions {
     /// Shell to generate completions for
     #[arg(value_enum)]
     shell: Shell,
   },
+  /// Print dynamic completion values (used by the completion scripts)
+  #[command(name = "__complete", hide = true)]
+  Complete {
+    /// Kind of value to complete
+    #[arg(value_enum)]
+    kind: CompletionKind,
+    /// Prefix typed so far
+    #[arg(default_value = "")]
+    prefix: String,
+  },
 
   /// Download heap profile from daemon (requires jemalloc-pprof feature)
   #[cfg(all(unix, feature = "jemalloc-pprof"))]
@@ -521,19 +1414,70 @@ USAGE:
   },
 }
 
+/// Commands whose output can run past one screen
+fn pages_output(command: &Commands) -> bool {
+  matches!(
+    command,
+    Commands::Search { .. }
+      | Commands::Memory {
+        command: MemoryCommand::Show { .. }
+          | MemoryCommand::Deleted { .. }
+          | MemoryCommand::History { .. }
+          | MemoryCommand::Goals { .. }
+      }
+      | Commands::Context { .. }
+      | Commands::References { .. }
+      | Commands::Stats { .. }
+      | Commands::Usage { .. }
+      | Commands::Projects {
+        command: ProjectsCommand::List { .. } | ProjectsCommand::Show { .. }
+      }
+      | Commands::Sessions {
+        command: SessionsCommand::List { .. } | SessionsCommand::Show { .. }
+      }
+      | Commands::Entities { .. }
+      | Commands::Tags {
+        command: TagsCommand::List { .. }
+      }
+      | Commands::Extract { .. }
+      | Commands::Logs {
+        follow: false,
+        open: false,
+        ..
+      }
+  )
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
   let cli = Cli::parse();
+  display::init(display::DisplayOptions {
+    utc: cli.utc,
+    iso: cli.iso,
+    no_trunc: cli.no_trunc,
+  });
 
   // Use file logging for daemon (background mode), console-only for other commands
-  let _guard = match &cli.command {
+  let _guards = match &cli.command {
     Commands::Daemon { foreground, .. } => init_daemon_logging_with_config(*foreground).await,
     _ => {
       init_cli_logging();
-      None
+      Vec::new()
     }
   };
 
+  // CLI and TUI results can be large, so ask the daemon for binary framing.
+  // MCP stays on JSON lines, and hooks send too little to gain from it.
+  if !matches!(cli.command, Commands::Mcp | Commands::Hook { .. }) {
+    ccengram::ipc::framing::prefer(ccengram::ipc::framing::Framing::Cbor);
+  }
+
+  let _pager = if !cli.no_pager && pages_output(&cli.command) {
+    pager::Pager::start()
+  } else {
+    None
+  };
+
   match cli.command {
     Commands::Daemon {
       stop,
@@ -556,11 +1500,14 @@ async fn main() -> Result<()> {
         min_salience,
         include_superseded,
         scope,
+        group_by,
+        per_group,
+        saved,
         json,
         long,
       } => {
         cmd_search(
-          &query,
+          query.as_deref(),
           limit,
           project.as_deref(),
           sector.as_deref(),
@@ -568,6 +1515,9 @@ async fn main() -> Result<()> {
           min_salience,
           include_superseded,
           scope.as_deref(),
+          group_by.as_deref(),
+          per_group,
+          saved.as_deref(),
           json,
           long,
         )
@@ -581,6 +1531,10 @@ async fn main() -> Result<()> {
         chunk_type,
         path,
         symbol,
+        current_branch,
+        diff_branch,
+        no_infer_language,
+        include_generated,
         json,
       } => {
         cmd_search_code(
@@ -591,6 +1545,10 @@ async fn main() -> Result<()> {
           chunk_type.as_deref(),
           path.as_deref(),
           symbol.as_deref(),
+          current_branch,
+          diff_branch.as_deref(),
+          !no_infer_language,
+          include_generated,
           json,
         )
         .await
@@ -615,7 +1573,13 @@ async fn main() -> Result<()> {
       } => cmd_archive(before.as_deref(), threshold, dry_run).await,
       MemoryCommand::Restore { id } => cmd_restore(&id).await,
       MemoryCommand::Deleted { limit, json } => cmd_deleted(limit, json).await,
+      MemoryCommand::History { id, json } => cmd_history(&id, json).await,
+      MemoryCommand::Undo { dry_run, json } => cmd_undo(dry_run, json).await,
+      MemoryCommand::Goals { status, limit, json } => cmd_goals(status, limit, json).await,
+      MemoryCommand::Goal { id, status, due } => cmd_goal_update(&id, status, due).await,
+      MemoryCommand::Extract { file, source, json } => cmd_extract_text(file.as_deref(), source, json).await,
     },
+    Commands::Retro { id, json } => cmd_retro(&id, json).await,
 
     Commands::Index { command } => cmd_index(command).await,
 
@@ -629,28 +1593,178 @@ async fn main() -> Result<()> {
     Commands::Watch {
       stop,
       status,
+      pause,
+      resume,
       no_startup_scan,
       startup_scan_mode,
       startup_scan_sync,
-    } => cmd_watch(stop, status, no_startup_scan, startup_scan_mode, startup_scan_sync).await,
+      roots,
+    } => {
+      cmd_watch(
+        stop,
+        status,
+        pause,
+        resume,
+        no_startup_scan,
+        startup_scan_mode,
+        startup_scan_sync,
+        roots,
+      )
+      .await
+    }
+    Commands::Context {
+      command: Some(ContextCommand::Pack {
+        budget,
+        write,
+        file,
+        json,
+      }),
+      ..
+    } => cmd_context_pack(budget, write, file, json).await,
     Commands::Context {
       chunk_id,
       before,
       after,
       json,
-    } => cmd_context(&chunk_id, before, after, json).await,
-    Commands::Stats => cmd_stats().await,
-    Commands::Health => cmd_health().await,
+      command: None,
+    } => cmd_context(&chunk_id.unwrap_or_default(), before, after, json).await,
+    Commands::Pack {
+      query,
+      budget,
+      scope,
+      plan,
+      hops,
+      format,
+      output,
+    } => {
+      let hops = plan.then_some(hops);
+      cmd_pack(&query, budget, scope, hops, &format, output.as_deref()).await
+    }
+    Commands::References {
+      symbol,
+      file,
+      limit,
+      json,
+    } => cmd_references(&symbol, file.as_deref(), limit, json).await,
+    Commands::Stats {
+      command: Some(StatsCommand::Search { days, limit, json }),
+      ..
+    } => cmd_search_stats(days, limit, json).await,
+    Commands::Stats {
+      egress: true,
+      days,
+      json,
+      ..
+    } => cmd_egress(days, json).await,
+    Commands::Stats { .. } => cmd_stats().await,
+    Commands::Health { probe: Some(probe) } => cmd_health_probe(&probe).await,
+    Commands::Health { probe: None } => cmd_health().await,
+    Commands::Usage { days, project, json } => cmd_usage(days, project, json).await,
     Commands::Update { check, version } => cmd_update(check, version).await,
     Commands::Agent { output, force } => cmd_agent(output.as_deref(), force).await,
-    Commands::Tui { project } => cmd_tui(project).await,
+    Commands::Tui { project, exploration } => cmd_tui(project, exploration).await,
+    Commands::Repl { project } => cmd_repl(project).await,
 
     // Projects subcommands
+    Commands::Git { command } => match command {
+      GitCommand::InstallHooks { force } => cmd_git_install_hooks(force).await,
+    },
+
     Commands::Projects { command } => match command {
       ProjectsCommand::List { json } => cmd_projects_list(json).await,
       ProjectsCommand::Show { project, json } => cmd_projects_show(&project, json).await,
       ProjectsCommand::Clean { project, force } => cmd_projects_clean(&project, force).await,
       ProjectsCommand::CleanAll { force } => cmd_projects_clean_all(force).await,
+      ProjectsCommand::Migrate { from, to } => cmd_projects_migrate(&from, &to).await,
+      ProjectsCommand::Duplicates { json } => cmd_projects_duplicates(json).await,
+      ProjectsCommand::Merge { from, into } => cmd_projects_merge(&from, &into).await,
+      ProjectsCommand::Export {
+        path,
+        project,
+        force,
+        include_private,
+      } => cmd_projects_export(&path, project, force, include_private).await,
+      ProjectsCommand::Import { path, project } => cmd_projects_import(&path, project).await,
+    },
+
+    Commands::Data { command } => match command {
+      DataCommand::Status => cmd_data_status().await,
+      DataCommand::Import { from, move_data } => cmd_data_import(from, move_data).await,
+      DataCommand::Init => cmd_data_init().await,
+    },
+
+    Commands::Sync { command } => match command {
+      SyncCommand::Push { dry_run, json } => cmd_sync_push(dry_run, json).await,
+      SyncCommand::Pull { dry_run, json } => cmd_sync_pull(dry_run, json).await,
+      SyncCommand::Resolve { id, take } => cmd_sync_resolve(id.as_deref(), take.as_deref()).await,
+    },
+
+    Commands::Db { command } => match command {
+      DbCommand::Repair { json } => cmd_db_repair(json).await,
+    },
+
+    Commands::Explore { command } => match command {
+      ExploreCommand::Query {
+        query,
+        scope,
+        limit,
+        json,
+      } => cmd_explore_query(&query, scope, limit, json).await,
+      ExploreCommand::Pin { id, note } => cmd_explore_pin(&id, note).await,
+      ExploreCommand::Note { text } => cmd_explore_note(&text).await,
+      ExploreCommand::Save { name, force } => cmd_explore_save(&name, force).await,
+      ExploreCommand::List { json } => cmd_explore_list(json).await,
+      ExploreCommand::Export { name, output } => cmd_explore_export(name, output.as_deref()).await,
+      ExploreCommand::Open { name } => cmd_tui(None, Some(name)).await,
+      ExploreCommand::Delete { name } => cmd_explore_delete(&name).await,
+      ExploreCommand::Clear => cmd_explore_clear().await,
+    },
+
+    Commands::Audit { command } => match command {
+      AuditCommand::Secrets { json } => cmd_audit_secrets(json).await,
+    },
+
+    // Sessions subcommands
+    Commands::Sessions { command } => match command {
+      SessionsCommand::List { limit, active, json } => cmd_sessions_list(limit, active, json).await,
+      SessionsCommand::Show { id, injections, json } => {
+        if injections {
+          cmd_sessions_injections(&id, json).await
+        } else {
+          cmd_sessions_show(&id, json).await
+        }
+      }
+      SessionsCommand::Import { paths, project, json } => cmd_sessions_import(&paths, project.as_deref(), json).await,
+    },
+
+    // Entities subcommands
+    Commands::Entities { command } => match command {
+      EntitiesCommand::Show { entity, limit, json } => cmd_entities_show(&entity, limit, json).await,
+    },
+
+    // Tags subcommands
+    Commands::Tags { command } => match command {
+      TagsCommand::List { min, limit, json } => cmd_tags_list(min, limit, json).await,
+      TagsCommand::Rename {
+        from,
+        to,
+        dry_run,
+        json,
+      } => cmd_tags_rename(&from, &to, dry_run, json).await,
+      TagsCommand::Merge {
+        tags,
+        into,
+        dry_run,
+        json,
+      } => cmd_tags_merge(&tags, &into, dry_run, json).await,
+      TagsCommand::Delete { tags, dry_run, json } => cmd_tags_delete(&tags, dry_run, json).await,
+    },
+
+    // Extract subcommands
+    Commands::Extract { command } => match command {
+      ExtractCommand::Inspect { segment_id, json } => cmd_extract_inspect(&segment_id, json).await,
+      ExtractCommand::Failed { dead, json } => cmd_extract_failed(dead, json).await,
+      ExtractCommand::Retry { ids, json } => cmd_extract_retry(ids, json).await,
     },
 
     // Logs command
@@ -659,13 +1773,21 @@ async fn main() -> Result<()> {
       lines,
       date,
       level,
+      source,
       open,
       list,
     } => {
       if list {
         cmd_logs_list()
       } else {
-        cmd_logs(follow, lines, date.as_deref(), level.as_deref(), open)
+        cmd_logs(
+          follow,
+          lines,
+          date.as_deref(),
+          level.as_deref(),
+          source.as_deref(),
+          open,
+        )
       }
     }
 
@@ -674,6 +1796,7 @@ async fn main() -> Result<()> {
       print_completions(shell);
       Ok(())
     }
+    Commands::Complete { kind, prefix } => cmd_complete(kind, &prefix).await,
 
     // Pprof command (unix + jemalloc-pprof feature only)
     #[cfg(all(unix, feature = "jemalloc-pprof"))]
@@ -681,7 +1804,10 @@ async fn main() -> Result<()> {
   }
 }
 
-/// Print shell completions to stdout
+/// Print shell completions to stdout, with dynamic value completion where supported
 fn print_completions(shell: Shell) {
-  clap_complete::generate(shell, &mut Cli::command(), "ccengram", &mut io::stdout());
+  let mut generated = Vec::new();
+  clap_complete::generate(shell, &mut Cli::command(), "ccengram", &mut generated);
+  let script = dynamic_completion_script(shell, &String::from_utf8_lossy(&generated));
+  print!("{}", script);
 }
@@ -39,6 +39,8 @@ struct InitializeResult {
   capabilities: McpCapabilities,
   #[serde(rename = "serverInfo")]
   server_info: McpServerInfo,
+  #[serde(rename = "_meta")]
+  meta: McpMeta,
 }
 
 #[derive(Serialize)]
@@ -55,6 +57,12 @@ struct McpServerInfo {
   version: &'static str,
 }
 
+#[derive(Serialize)]
+struct McpMeta {
+  #[serde(rename = "toolApiVersion")]
+  tool_api_version: u32,
+}
+
 #[derive(Serialize)]
 struct ToolsListResult {
   tools: serde_json::Value,
@@ -99,6 +107,12 @@ fn mcp_error(id: Option<serde_json::Value>, code: i32, message: &str) -> McpResp
 pub async fn cmd_mcp() -> Result<()> {
   // Tool definitions are loaded from cli::tools and filtered based on config
 
+  // Tool profile named by the environment, else picked by the client's name
+  let requested_profile = std::env::var(ccengram::config::TOOL_PROFILE_ENV)
+    .ok()
+    .filter(|p| !p.trim().is_empty());
+  let mut client_name: Option<String> = None;
+
   // Use async IO for proper non-blocking behavior with MCP
   let stdin = tokio::io::stdin();
   let mut stdout = tokio::io::stdout();
@@ -124,31 +138,44 @@ pub async fn cmd_mcp() -> Result<()> {
 
     let response = match mcp_request.method.as_str() {
       // MCP protocol methods
-      "initialize" => mcp_success(
-        mcp_request.id,
-        serde_json::to_value(InitializeResult {
-          protocol_version: "2024-11-05",
-          capabilities: McpCapabilities {
-            tools: McpToolsCapability {},
-          },
-          server_info: McpServerInfo {
-            name: "ccengram",
-            version: env!("CARGO_PKG_VERSION"),
-          },
-        })
-        .unwrap_or_default(),
-      ),
+      "initialize" => {
+        client_name = mcp_request
+          .params
+          .get("clientInfo")
+          .and_then(|info| info.get("name"))
+          .and_then(|name| name.as_str())
+          .map(String::from);
+        mcp_success(
+          mcp_request.id,
+          serde_json::to_value(InitializeResult {
+            protocol_version: "2024-11-05",
+            capabilities: McpCapabilities {
+              tools: McpToolsCapability {},
+            },
+            server_info: McpServerInfo {
+              name: "ccengram",
+              version: env!("CARGO_PKG_VERSION"),
+            },
+            meta: McpMeta {
+              tool_api_version: crate::tools::TOOL_API_VERSION,
+            },
+          })
+          .unwrap_or_default(),
+        )
+      }
       "notifications/initialized" => {
         // No response needed for notification
         continue;
       }
-      "tools/list" => mcp_success(
-        mcp_request.id,
-        serde_json::to_value(ToolsListResult {
-          tools: crate::tools::get_tool_definitions_for_cwd().await,
-        })
-        .unwrap_or_default(),
-      ),
+      "tools/list" => {
+        match crate::tools::get_tool_definitions_for_cwd(requested_profile.as_deref(), client_name.as_deref()).await {
+          Ok(tools) => mcp_success(
+            mcp_request.id,
+            serde_json::to_value(ToolsListResult { tools }).unwrap_or_default(),
+          ),
+          Err(e) => mcp_error(mcp_request.id, -32603, &e.to_string()),
+        }
+      }
       "tools/call" => {
         // Extract tool name and arguments
         let tool_name = mcp_request.params.get("name").and_then(|v| v.as_str()).unwrap_or("");
@@ -170,8 +197,14 @@ pub async fn cmd_mcp() -> Result<()> {
           );
         }
 
-        // Dispatch tool call to daemon
-        match dispatch_tool_call(tool_name, args).await {
+        // Dispatch tool call to daemon, unless the tool profile leaves it out
+        let result =
+          match crate::tools::check_tool_allowed(tool_name, requested_profile.as_deref(), client_name.as_deref()).await
+          {
+            Ok(()) => dispatch_tool_call(tool_name, args).await,
+            Err(e) => Err(e),
+          };
+        match result {
           Ok(result) => {
             // Format the result for LLM consumption, falling back to JSON if no formatter
             let text = crate::format::format_tool_result(tool_name, &result)
@@ -219,22 +252,33 @@ pub async fn cmd_mcp() -> Result<()> {
 
 /// Dispatch a tool call to the daemon using typed IPC
 async fn dispatch_tool_call(tool_name: &str, args: serde_json::Value) -> Result<serde_json::Value> {
+  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+  let client = ccengram::Daemon::connect_or_start(cwd)
+    .await
+    .context("Failed to connect to daemon")?
+    .with_actor(ccengram::ipc::MemoryActor::Mcp);
+
+  call_tool(&client, tool_name, args).await
+}
+
+/// Call a tool by name over an existing daemon connection
+pub(crate) async fn call_tool(
+  client: &ccengram::ipc::Client,
+  tool_name: &str,
+  args: serde_json::Value,
+) -> Result<serde_json::Value> {
   use ccengram::ipc::{
     code::*,
     docs::*,
+    entity::*,
     memory::*,
     project::*,
     relationship::*,
-    search::{ContextParams, ExploreParams},
+    search::{ContextParams, ExploreParams, ExplorePlanParams},
     system::*,
     watch::*,
   };
 
-  let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-  let client = ccengram::Daemon::connect_or_start(cwd)
-    .await
-    .context("Failed to connect to daemon")?;
-
   // Macro to reduce boilerplate: deserialize args, call client, serialize result
   macro_rules! call {
     ($params:ty) => {{
@@ -249,6 +293,7 @@ async fn dispatch_tool_call(tool_name: &str, args: serde_json::Value) -> Result<
     // Unified exploration tools
     "explore" => call!(ExploreParams),
     "context" => call!(ContextParams),
+    "explore_plan" => call!(ExplorePlanParams),
 
     // Memory tools
     "memory_search" => call!(MemorySearchParams),
@@ -257,10 +302,13 @@ async fn dispatch_tool_call(tool_name: &str, args: serde_json::Value) -> Result<
     "memory_add" => call!(MemoryAddParams),
     "memory_reinforce" => call!(MemoryReinforceParams),
     "memory_deemphasize" => call!(MemoryDeemphasizeParams),
+    "memory_feedback" => call!(MemoryFeedbackParams),
     "memory_delete" => call!(MemoryDeleteParams),
     "memory_supersede" => call!(MemorySupersedeParams),
     "memory_timeline" => call!(MemoryTimelineParams),
     "memory_related" => call!(MemoryRelatedParams),
+    "goals" => call!(GoalsParams),
+    "goal_update" => call!(GoalUpdateParams),
 
     // Code tools
     "code_search" => call!(CodeSearchParams),
@@ -271,6 +319,8 @@ async fn dispatch_tool_call(tool_name: &str, args: serde_json::Value) -> Result<
     "code_memories" => call!(CodeMemoriesParams),
     "code_callers" => call!(CodeCallersParams),
     "code_callees" => call!(CodeCalleesParams),
+    "code_definition" => call!(CodeDefinitionParams),
+    "code_references" => call!(CodeReferencesParams),
     "code_related" => call!(CodeRelatedParams),
     "code_context_full" => call!(CodeContextFullParams),
 
@@ -290,11 +340,17 @@ async fn dispatch_tool_call(tool_name: &str, args: serde_json::Value) -> Result<
     "relationship_delete" => call!(RelationshipDeleteParams),
     "relationship_related" => call!(RelationshipRelatedParams),
 
+    // Entity tools
+    "entity_list" => call!(EntityListParams),
+    "entity_get" => call!(EntityGetParams),
+    "entity_top" => call!(EntityTopParams),
+
     // Project tools
     "project_list" => call!(ProjectListParams),
     "project_info" => call!(ProjectInfoParams),
     "project_clean" => call!(ProjectCleanParams),
     "project_clean_all" => call!(ProjectCleanAllParams),
+    "session_injections" => call!(SessionInjectionsParams),
 
     // System tools
     "project_stats" => call!(ProjectStatsParams),
@@ -0,0 +1,97 @@
+//! Paging of long command output.
+//!
+//! When stdout is a terminal, list-style commands send their output through
+//! `$PAGER` (default `less` with `LESS=FRX`, which exits right away when the
+//! output fits on one screen). Stdout is redirected at the file descriptor
+//! level, so commands keep using `println!`. `--no-pager` or an empty `PAGER`
+//! disables it.
+
+/// Pager used when `$PAGER` is not set
+#[cfg(unix)]
+const DEFAULT_PAGER: &str = "less";
+
+/// Options given to `less` when `$LESS` is not set: quit if the output fits,
+/// pass colors through, and leave the output on screen afterwards
+#[cfg(unix)]
+const DEFAULT_LESS: &str = "FRX";
+
+/// Stdout redirected into a running pager, restored when dropped
+pub struct Pager {
+  #[cfg(unix)]
+  child: std::process::Child,
+  #[cfg(unix)]
+  saved_stdout: std::os::fd::OwnedFd,
+}
+
+impl Pager {
+  /// Start paging stdout if it is a terminal and a pager is configured
+  #[cfg(unix)]
+  pub fn start() -> Option<Self> {
+    use std::{
+      io::{IsTerminal, Write},
+      os::fd::{AsFd, AsRawFd},
+      process::{Command, Stdio},
+    };
+
+    if !std::io::stdout().is_terminal() {
+      return None;
+    }
+    let pager = std::env::var("CCENGRAM_PAGER")
+      .or_else(|_| std::env::var("PAGER"))
+      .unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let pager = pager.trim();
+    if pager.is_empty() || pager == "cat" {
+      return None;
+    }
+
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(pager).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+      command.env("LESS", DEFAULT_LESS);
+    }
+    let mut child = command.spawn().ok()?;
+    let pipe = child.stdin.take()?;
+
+    let _ = std::io::stdout().flush();
+    let saved_stdout = match std::io::stdout().as_fd().try_clone_to_owned() {
+      Ok(fd) => fd,
+      Err(_) => {
+        let _ = child.kill();
+        return None;
+      }
+    };
+    // SAFETY: both descriptors are open for the duration of the call
+    if unsafe { libc::dup2(pipe.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+      let _ = child.kill();
+      return None;
+    }
+    // Quitting the pager early closes the pipe; exit quietly instead of
+    // panicking on the next write
+    // SAFETY: restoring the default disposition of a signal is always sound
+    unsafe {
+      libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+
+    Some(Self { child, saved_stdout })
+  }
+
+  #[cfg(not(unix))]
+  pub fn start() -> Option<Self> {
+    None
+  }
+}
+
+#[cfg(unix)]
+impl Drop for Pager {
+  fn drop(&mut self) {
+    use std::{io::Write, os::fd::AsRawFd};
+
+    let _ = std::io::stdout().flush();
+    // Restoring stdout closes the pipe, which lets the pager see end of input
+    // SAFETY: the saved descriptor is owned by self and still open
+    unsafe {
+      libc::dup2(self.saved_stdout.as_raw_fd(), libc::STDOUT_FILENO);
+    }
+    let _ = self.child.wait();
+  }
+}
@@ -0,0 +1,173 @@
+//! Column-aligned table output for list-style commands.
+//!
+//! Columns are sized to their widest cell. When the table is wider than the
+//! terminal, the flexible column (the last one unless set otherwise) is cut
+//! so each row fits on one line; `--no-trunc` keeps every cell whole.
+
+use crate::display;
+
+/// Gap between columns
+const GAP: &str = "  ";
+
+/// Narrowest the flexible column is cut to before giving up on fitting
+const MIN_FLEX_WIDTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+  Left,
+  Right,
+}
+
+/// A table of text cells rendered with aligned columns
+#[derive(Debug, Clone)]
+pub struct Table {
+  headers: Vec<String>,
+  align: Vec<Align>,
+  rows: Vec<Vec<String>>,
+  flex: usize,
+  indent: usize,
+}
+
+impl Table {
+  pub fn new(headers: &[&str]) -> Self {
+    Self {
+      headers: headers.iter().map(|h| h.to_string()).collect(),
+      align: vec![Align::Left; headers.len()],
+      rows: Vec::new(),
+      flex: headers.len().saturating_sub(1),
+      indent: 0,
+    }
+  }
+
+  /// Right-align a column, for numbers
+  pub fn right(mut self, column: usize) -> Self {
+    if let Some(align) = self.align.get_mut(column) {
+      *align = Align::Right;
+    }
+    self
+  }
+
+  /// Column that is cut when the table does not fit the terminal
+  pub fn flex(mut self, column: usize) -> Self {
+    self.flex = column.min(self.headers.len().saturating_sub(1));
+    self
+  }
+
+  /// Indent every line by `columns` spaces
+  pub fn indent(mut self, columns: usize) -> Self {
+    self.indent = columns;
+    self
+  }
+
+  /// Add a row; missing cells are left empty and extra cells ignored
+  pub fn row(&mut self, cells: impl IntoIterator<Item = impl Into<String>>) {
+    let mut row: Vec<String> = cells.into_iter().map(Into::into).collect();
+    row.resize(self.headers.len(), String::new());
+    self.rows.push(row);
+  }
+
+  /// Render to lines, fitting `max_width` columns when given
+  pub fn render(&self, max_width: Option<usize>) -> Vec<String> {
+    let mut widths: Vec<usize> = self.headers.iter().map(|h| display::width(h)).collect();
+    for row in &self.rows {
+      for (width, cell) in widths.iter_mut().zip(row) {
+        *width = (*width).max(display::width(cell));
+      }
+    }
+
+    if let Some(max_width) = max_width
+      && !widths.is_empty()
+    {
+      let total = self.indent + widths.iter().sum::<usize>() + GAP.len() * (widths.len() - 1);
+      if total > max_width {
+        let excess = total - max_width;
+        let flex = &mut widths[self.flex];
+        *flex = flex.saturating_sub(excess).max(MIN_FLEX_WIDTH.min(*flex));
+      }
+    }
+
+    std::iter::once(&self.headers)
+      .chain(&self.rows)
+      .map(|row| self.render_row(row, &widths))
+      .collect()
+  }
+
+  /// Print the table fitted to the terminal
+  pub fn print(&self) {
+    for line in self.render(display::terminal_width()) {
+      println!("{}", line);
+    }
+  }
+
+  fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+    let last = widths.len().saturating_sub(1);
+    let mut line = " ".repeat(self.indent);
+    for (i, (cell, &width)) in row.iter().zip(widths).enumerate() {
+      if i > 0 {
+        line.push_str(GAP);
+      }
+      let cell = display::truncate(cell, width);
+      let padding = " ".repeat(width.saturating_sub(display::width(&cell)));
+      match self.align[i] {
+        Align::Right => {
+          line.push_str(&padding);
+          line.push_str(&cell);
+        }
+        // Trailing padding on the last column only adds whitespace
+        Align::Left if i == last => line.push_str(&cell),
+        Align::Left => {
+          line.push_str(&cell);
+          line.push_str(&padding);
+        }
+      }
+    }
+    line
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sample() -> Table {
+    let mut table = Table::new(&["Name", "Count", "Path"]).right(1);
+    table.row(["alpha", "3", "/home/me/projects/alpha"]);
+    table.row(["b", "1200", "/tmp/b"]);
+    table
+  }
+
+  #[test]
+  fn test_columns_are_aligned() {
+    let lines = sample().render(None);
+    assert_eq!(
+      lines,
+      vec![
+        "Name   Count  Path",
+        "alpha      3  /home/me/projects/alpha",
+        "b       1200  /tmp/b",
+      ],
+      "columns padded to the widest cell, numbers right-aligned"
+    );
+  }
+
+  #[test]
+  fn test_flex_column_is_cut_to_fit() {
+    let lines = sample().render(Some(30));
+    assert!(
+      lines.iter().all(|l| display::width(l) <= 30),
+      "every line fits the width: {:?}",
+      lines
+    );
+    assert_eq!(
+      lines[1], "alpha      3  /home/me/projec…",
+      "path is cut with an ellipsis"
+    );
+  }
+
+  #[test]
+  fn test_short_rows_are_padded() {
+    let mut table = Table::new(&["A", "B"]);
+    table.row(["x"]);
+    assert_eq!(table.render(None)[1], "x  ", "missing cells render empty");
+  }
+}
@@ -2,9 +2,15 @@
 
 use std::collections::HashMap;
 
-use ccengram::config::Config;
+use anyhow::Result;
+use ccengram::config::{Config, INTERNAL_TOOLS, ToolProfile};
 use serde_json::{Value, json};
 
+/// Version of the MCP tool wire format: each tool's input schema and the JSON
+/// its result carries. Bump it with any change a consumer could notice, then
+/// regenerate the golden fixtures in `src/__tests__/fixtures/mcp`.
+pub const TOOL_API_VERSION: u32 = 1;
+
 /// Get all tool definitions as a map of name -> definition
 pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
   let mut tools = HashMap::new();
@@ -38,6 +44,10 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
                     "type": "number",
                     "description": "Max results per scope (default: 10)"
                 },
+                "include_generated": {
+                    "type": "boolean",
+                    "description": "Include code from generated files, left out by default"
+                },
             },
             "required": ["query"]
         }
@@ -70,6 +80,37 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     }),
   );
 
+  tools.insert(
+    "explore_plan",
+    json!({
+        "name": "explore_plan",
+        "description": "Gather context for a task in one call. Searches for the task and for the identifiers, paths and quoted phrases it names, follows callers and callees of the best code results, and returns full content deduplicated and fitted to a token budget. Each item lists the searches or call-graph hops that found it.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "task": {
+                    "type": "string",
+                    "description": "What you are about to work on; name functions, types or files in backticks to search for them directly"
+                },
+                "budget": {
+                    "type": "number",
+                    "description": "Token budget for the returned content (default: 8000)"
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["code", "memory", "docs", "all"],
+                    "description": "Where to search. Default searches code+docs only; use 'all' to include memories."
+                },
+                "hops": {
+                    "type": "number",
+                    "description": "Call-graph hops from the best code results (default: 1, max: 3, 0 to skip)"
+                }
+            },
+            "required": ["task"]
+        }
+    }),
+  );
+
   // ============================================================================
   // Memory tools
   // ============================================================================
@@ -85,7 +126,10 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
                     "query": { "type": "string", "description": "Search query" },
                     "sector": { "type": "string", "enum": ["episodic", "semantic", "procedural", "emotional", "reflective"], "description": "Filter by memory sector" },
                     "limit": { "type": "number", "description": "Max results (default: 10)" },
-                    "include_superseded": { "type": "boolean", "description": "Include superseded memories (default: false)" }
+                    "session_id": { "type": "string", "description": "Filter by session ID (can use ID prefix)" },
+                    "include_superseded": { "type": "boolean", "description": "Include superseded memories (default: false)" },
+                    "group_by": { "type": "string", "enum": ["scope_path", "memory_type", "tag"], "description": "Group results; limit then caps the number of groups" },
+                    "per_group": { "type": "number", "description": "Top hits per group when grouping (default: 3)" }
                 },
                 "required": ["query"]
             }
@@ -100,7 +144,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to retrieve" }
+                "memory_id": { "type": "string", "description": "Memory ID to retrieve (can use ID prefix)" }
             },
             "required": ["memory_id"]
         }
@@ -150,10 +194,13 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
                 "properties": {
                     "content": { "type": "string", "description": "Memory content" },
                     "sector": { "type": "string", "enum": ["episodic", "semantic", "procedural", "emotional", "reflective"], "description": "Memory sector" },
-                    "type": { "type": "string", "enum": ["preference", "codebase", "decision", "gotcha", "pattern", "turn_summary", "task_completion"], "description": "Memory type" },
+                    "type": { "type": "string", "enum": ["preference", "codebase", "decision", "gotcha", "pattern", "turn_summary", "task_completion", "goal"], "description": "Memory type" },
+                    "status": { "type": "string", "enum": ["open", "blocked", "done"], "description": "Goal status (type goal only, default: open)" },
+                    "due": { "type": "string", "description": "When a goal is due, e.g. 2026-11-01 or 'before the 2.0 release' (type goal only)" },
                     "context": { "type": "string", "description": "Context of discovery" },
                     "tags": { "type": "array", "items": { "type": "string" }, "description": "Tags" },
-                    "importance": { "type": "number", "description": "Importance 0-1 (default: 0.5)" }
+                    "importance": { "type": "number", "description": "Importance 0-1 (default: 0.5)" },
+                    "visibility": { "type": "string", "enum": ["private", "team"], "description": "private keeps a personal note out of shared snapshots and CLAUDE.md (default: from [visibility] config)" }
                 },
                 "required": ["content"]
             }
@@ -168,7 +215,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to reinforce" },
+                "memory_id": { "type": "string", "description": "Memory ID to reinforce (can use ID prefix)" },
                 "amount": { "type": "number", "description": "Reinforcement amount 0-1 (default: 0.1)" }
             },
             "required": ["memory_id"]
@@ -184,7 +231,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to de-emphasize" },
+                "memory_id": { "type": "string", "description": "Memory ID to de-emphasize (can use ID prefix)" },
                 "amount": { "type": "number", "description": "De-emphasis amount 0-1 (default: 0.2)" }
             },
             "required": ["memory_id"]
@@ -192,6 +239,23 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     }),
   );
 
+  tools.insert(
+    "memory_feedback",
+    json!({
+        "name": "memory_feedback",
+        "description": "Mark a memory returned by a search as helpful or irrelevant for the query. Feedback accumulates and raises or lowers the memory in later searches.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "memory_id": { "type": "string", "description": "Memory ID from the search results (can use ID prefix)" },
+                "helpful": { "type": "boolean", "description": "true if the memory helped, false if it was irrelevant" },
+                "query": { "type": "string", "description": "The query the memory was returned for" }
+            },
+            "required": ["memory_id", "helpful"]
+        }
+    }),
+  );
+
   tools.insert(
     "memory_delete",
     json!({
@@ -200,7 +264,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to delete" },
+                "memory_id": { "type": "string", "description": "Memory ID to delete (can use ID prefix)" },
                 "hard": { "type": "boolean", "description": "Permanently delete (default: false)" }
             },
             "required": ["memory_id"]
@@ -216,8 +280,8 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "old_memory_id": { "type": "string", "description": "ID of memory being superseded" },
-                "new_memory_id": { "type": "string", "description": "ID of newer memory that supersedes it" }
+                "old_memory_id": { "type": "string", "description": "ID of memory being superseded (can use ID prefix)" },
+                "new_memory_id": { "type": "string", "description": "ID of newer memory that supersedes it (can use ID prefix)" }
             },
             "required": ["old_memory_id", "new_memory_id"]
         }
@@ -232,7 +296,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to find related memories for" },
+                "memory_id": { "type": "string", "description": "Memory ID to find related memories for (can use ID prefix)" },
                 "methods": {
                     "type": "array",
                     "items": { "type": "string", "enum": ["relationships", "entities", "similar", "supersedes"] },
@@ -245,6 +309,38 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     }),
   );
 
+  tools.insert(
+    "goals",
+    json!({
+        "name": "goals",
+        "description": "List goal memories with their status (open, blocked, done) and due hints. Open and blocked goals by default. Goals are marked done automatically when a matching task completion is stored.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "status": { "type": "string", "enum": ["active", "open", "blocked", "done", "all"], "description": "Which goals to list (default: active, meaning open and blocked)" },
+                "limit": { "type": "number", "description": "Max results (default: 20)" }
+            }
+        }
+    }),
+  );
+
+  tools.insert(
+    "goal_update",
+    json!({
+        "name": "goal_update",
+        "description": "Change the status or due hint of a goal memory.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "description": "Goal memory ID (can use ID prefix)" },
+                "status": { "type": "string", "enum": ["open", "blocked", "done"], "description": "New status" },
+                "due": { "type": "string", "description": "New due hint, e.g. 'before the 2.0 release'; empty to clear" }
+            },
+            "required": ["id"]
+        }
+    }),
+  );
+
   // Code tools
   tools.insert(
     "code_search",
@@ -256,7 +352,11 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
             "properties": {
                 "query": { "type": "string", "description": "Search query" },
                 "language": { "type": "string", "description": "Filter by programming language" },
-                "limit": { "type": "number", "description": "Max results (default: 10)" }
+                "infer_language": { "type": "boolean", "description": "Infer the language filter from languages or frameworks named in the query (default: true)" },
+                "limit": { "type": "number", "description": "Max results (default: 10)" },
+                "current_branch": { "type": "boolean", "description": "Drop code indexed on another branch that has changed since" },
+                "diff_branch": { "type": "string", "description": "Only search files that differ between this branch and HEAD" },
+                "include_generated": { "type": "boolean", "description": "Rank generated code alongside hand-written code (default: false)" }
             },
             "required": ["query"]
         }
@@ -332,7 +432,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "chunk_id": { "type": "string", "description": "Code chunk ID" },
+                "chunk_id": { "type": "string", "description": "Code chunk ID (can use ID prefix)" },
                 "file_path": { "type": "string", "description": "File path (alternative to chunk_id)" },
                 "limit": { "type": "number", "description": "Max results (default: 10)" }
             }
@@ -344,13 +444,14 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     "code_callers",
     json!({
         "name": "code_callers",
-        "description": "Find all code that calls a function/method. Essential for understanding impact of changes.",
+        "description": "Find all code that calls a function/method. Essential for understanding impact of changes. Use depth > 1 to include indirect callers.",
         "inputSchema": {
             "type": "object",
             "properties": {
-                "chunk_id": { "type": "string", "description": "Code chunk ID defining the function" },
+                "chunk_id": { "type": "string", "description": "Code chunk ID defining the function (can use ID prefix)" },
                 "symbol": { "type": "string", "description": "Function/method name to search for" },
-                "limit": { "type": "number", "description": "Max results (default: 20)" }
+                "limit": { "type": "number", "description": "Max results (default: 20)" },
+                "depth": { "type": "number", "description": "Levels of callers to follow, 1-5 (default: 1)" }
             }
         }
     }),
@@ -360,18 +461,53 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     "code_callees",
     json!({
         "name": "code_callees",
-        "description": "Find functions that a code chunk calls. Returns resolved definitions and unresolved external calls.",
+        "description": "Find functions that a code chunk calls. Returns resolved definitions and unresolved external calls. Use depth > 1 to include indirect callees.",
         "inputSchema": {
             "type": "object",
             "properties": {
-                "chunk_id": { "type": "string", "description": "Code chunk ID to analyze" },
-                "limit": { "type": "number", "description": "Max results per call (default: 3)" }
+                "chunk_id": { "type": "string", "description": "Code chunk ID to analyze (can use ID prefix)" },
+                "limit": { "type": "number", "description": "Max results per call (default: 3)" },
+                "depth": { "type": "number", "description": "Levels of callees to follow, 1-5 (default: 1)" }
             },
             "required": ["chunk_id"]
         }
     }),
   );
 
+  tools.insert(
+    "code_definition",
+    json!({
+        "name": "code_definition",
+        "description": "Go to the definition of a symbol. Pass the file it is referenced from to resolve it through that file's imports. Returns file, line range, signature and docstring, most likely first.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "symbol": { "type": "string", "description": "Symbol name, optionally qualified (e.g. Config::load)" },
+                "file_path": { "type": "string", "description": "File the symbol is referenced from" },
+                "limit": { "type": "number", "description": "Max results (default: 5)" }
+            },
+            "required": ["symbol"]
+        }
+    }),
+  );
+
+  tools.insert(
+    "code_references",
+    json!({
+        "name": "code_references",
+        "description": "Find all code referencing a symbol: calls plus other uses of the identifier (types, imports, arguments). Ranked by proximity to the symbol's definition, with the lines mentioning it.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "symbol": { "type": "string", "description": "Symbol name, optionally qualified (e.g. Config::load)" },
+                "file_path": { "type": "string", "description": "File the symbol is referenced from, to pick the right definition" },
+                "limit": { "type": "number", "description": "Max results (default: 50)" }
+            },
+            "required": ["symbol"]
+        }
+    }),
+  );
+
   tools.insert(
     "code_related",
     json!({
@@ -380,7 +516,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "chunk_id": { "type": "string", "description": "Code chunk ID" },
+                "chunk_id": { "type": "string", "description": "Code chunk ID (can use ID prefix)" },
                 "methods": {
                     "type": "array",
                     "items": { "type": "string", "enum": ["same_file", "shared_imports", "similar", "callers", "callees"] },
@@ -401,7 +537,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "chunk_id": { "type": "string", "description": "Code chunk ID" },
+                "chunk_id": { "type": "string", "description": "Code chunk ID (can use ID prefix)" },
                 "file_path": { "type": "string", "description": "File path (alternative)" },
                 "symbol": { "type": "string", "description": "Symbol name (used with file_path)" },
                 "limit_per_section": { "type": "number", "description": "Max items per section (default: 5)" }
@@ -415,10 +551,16 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     "watch_start",
     json!({
         "name": "watch_start",
-        "description": "Start the file watcher for automatic re-indexing.",
+        "description": "Start the file watcher for automatic re-indexing. Pass roots to watch further workspace folders (e.g. a sibling shared library) in the same session; each is indexed as its own project.",
         "inputSchema": {
             "type": "object",
-            "properties": {}
+            "properties": {
+                "roots": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Further workspace roots to watch, absolute or relative to the working directory"
+                }
+            }
         }
     }),
   );
@@ -447,6 +589,21 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     }),
   );
 
+  // Session tools
+  tools.insert(
+    "session_injections",
+    json!({
+        "name": "session_injections",
+        "description": "List the memories ccengram's hooks injected into this session's context (at session start, after compaction and before edits) and why: the rule that picked each one and its score.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "session_id": {"type": "string", "description": "Session ID or prefix (default: the most recently started active session)"}
+            }
+        }
+    }),
+  );
+
   // Document tools
   tools.insert(
     "docs_search",
@@ -503,11 +660,11 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "entity_list",
         json!({
             "name": "entity_list",
-            "description": "List known entities (people, technologies, concepts).",
+            "description": "List entities mentioned in memories (people, technologies, services, files).",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "entity_type": { "type": "string", "enum": ["person", "technology", "concept", "organization", "project"], "description": "Filter by entity type" },
+                    "entity_type": { "type": "string", "enum": ["person", "technology", "service", "file"], "description": "Filter by entity type" },
                     "limit": { "type": "number", "description": "Max results (default: 50)" }
                 }
             }
@@ -518,11 +675,11 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
     "entity_get",
     json!({
         "name": "entity_get",
-        "description": "Get details about a specific entity.",
+        "description": "Get an entity and the memories that mention it.",
         "inputSchema": {
             "type": "object",
             "properties": {
-                "entity_id": { "type": "string", "description": "Entity ID to retrieve" }
+                "entity_id": { "type": "string", "description": "Entity ID, ID prefix or name" }
             },
             "required": ["entity_id"]
         }
@@ -533,11 +690,11 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "entity_top",
         json!({
             "name": "entity_top",
-            "description": "Get top entities by mention count.",
+            "description": "Get the entities mentioned by the most memories.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "entity_type": { "type": "string", "enum": ["person", "technology", "concept", "organization", "project"], "description": "Filter by entity type" },
+                    "entity_type": { "type": "string", "enum": ["person", "technology", "service", "file"], "description": "Filter by entity type" },
                     "limit": { "type": "number", "description": "Max results (default: 10)" }
                 }
             }
@@ -553,8 +710,8 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "source_id": { "type": "string", "description": "Source memory ID" },
-                    "target_id": { "type": "string", "description": "Target memory ID" },
+                    "source_id": { "type": "string", "description": "Source memory ID (can use ID prefix)" },
+                    "target_id": { "type": "string", "description": "Target memory ID (can use ID prefix)" },
                     "relationship_type": { "type": "string", "enum": ["supersedes", "contradicts", "related_to", "elaborates", "causes", "derived_from", "supports", "opposes"], "description": "Type of relationship" },
                     "confidence": { "type": "number", "description": "Confidence score 0-1" }
                 },
@@ -571,7 +728,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to list relationships for" },
+                "memory_id": { "type": "string", "description": "Memory ID to list relationships for (can use ID prefix)" },
                 "relationship_type": { "type": "string", "description": "Filter by relationship type" }
             },
             "required": ["memory_id"]
@@ -602,7 +759,7 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
         "inputSchema": {
             "type": "object",
             "properties": {
-                "memory_id": { "type": "string", "description": "Memory ID to find related memories for" },
+                "memory_id": { "type": "string", "description": "Memory ID to find related memories for (can use ID prefix)" },
                 "max_depth": { "type": "number", "description": "Maximum relationship traversal depth (default: 1)" }
             },
             "required": ["memory_id"]
@@ -638,10 +795,10 @@ pub fn all_tool_definitions() -> HashMap<&'static str, Value> {
   tools
 }
 
-/// Get filtered tool definitions based on config
-pub fn get_filtered_tool_definitions(config: &Config) -> Value {
+/// Get filtered tool definitions based on config and the session's tool profile
+pub fn get_filtered_tool_definitions(config: &Config, profile: Option<&ToolProfile>) -> Value {
   let all_tools = all_tool_definitions();
-  let enabled = config.enabled_tool_set();
+  let enabled = config.profile_tool_set(profile);
 
   let filtered: Vec<Value> = all_tools
     .into_iter()
@@ -652,11 +809,46 @@ pub fn get_filtered_tool_definitions(config: &Config) -> Value {
   json!(filtered)
 }
 
-/// Get tool definitions filtered by the config loaded from current directory
-pub async fn get_tool_definitions_for_cwd() -> Value {
+/// Load the config for the current directory and select the session's tool
+/// profile: the one named by `requested`, else one listing `client`
+async fn load_for_cwd(
+  requested: Option<&str>,
+  client: Option<&str>,
+) -> Result<(Config, Option<(String, ToolProfile)>)> {
   let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
   let config = Config::load_for_project(&cwd).await;
-  get_filtered_tool_definitions(&config)
+  let profile = config
+    .tools
+    .select_profile(requested, client)
+    .map_err(anyhow::Error::msg)?
+    .map(|(name, profile)| (name.to_string(), profile.clone()));
+  Ok((config, profile))
+}
+
+/// Get tool definitions filtered by the config loaded from current directory
+/// and the session's tool profile
+pub async fn get_tool_definitions_for_cwd(requested: Option<&str>, client: Option<&str>) -> Result<Value> {
+  let (config, profile) = load_for_cwd(requested, client).await?;
+  Ok(get_filtered_tool_definitions(
+    &config,
+    profile.as_ref().map(|(_, profile)| profile),
+  ))
+}
+
+/// Fail unless the session's tool profile includes `tool`. Sessions without
+/// a profile may call any tool.
+pub async fn check_tool_allowed(tool: &str, requested: Option<&str>, client: Option<&str>) -> Result<()> {
+  if requested.is_none() && client.is_none() {
+    return Ok(());
+  }
+  let (config, profile) = load_for_cwd(requested, client).await?;
+  if let Some((name, profile)) = profile
+    && !INTERNAL_TOOLS.contains(&tool)
+    && !config.profile_tool_set(Some(&profile)).contains(tool)
+  {
+    anyhow::bail!("Tool '{}' is not available in the '{}' tool profile", tool, name);
+  }
+  Ok(())
 }
 
 #[cfg(test)]
@@ -688,7 +880,7 @@ mod tests {
       ..Default::default()
     };
 
-    let filtered = get_filtered_tool_definitions(&config);
+    let filtered = get_filtered_tool_definitions(&config, None);
     let arr = filtered.as_array().unwrap();
 
     assert_eq!(arr.len(), 2);
@@ -708,7 +900,7 @@ mod tests {
       ..Default::default()
     };
 
-    let filtered = get_filtered_tool_definitions(&config);
+    let filtered = get_filtered_tool_definitions(&config, None);
     let arr = filtered.as_array().unwrap();
 
     assert_eq!(arr.len(), 11);
@@ -724,9 +916,40 @@ mod tests {
       ..Default::default()
     };
 
-    let filtered = get_filtered_tool_definitions(&config);
+    let filtered = get_filtered_tool_definitions(&config, None);
     let arr = filtered.as_array().unwrap();
 
     assert_eq!(arr.len(), ccengram::config::ALL_TOOLS.len());
   }
+
+  #[test]
+  fn test_read_only_profile_filtering() {
+    let config = Config {
+      tools: ToolConfig {
+        preset: ToolPreset::Full,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let profile = ToolProfile {
+      read_only: true,
+      ..Default::default()
+    };
+
+    let filtered = get_filtered_tool_definitions(&config, Some(&profile));
+    let names: Vec<&str> = filtered
+      .as_array()
+      .unwrap()
+      .iter()
+      .filter_map(|t| t.get("name")?.as_str())
+      .collect();
+
+    assert!(names.contains(&"memory_search"));
+    assert!(!names.contains(&"memory_add"));
+    assert!(!names.contains(&"memory_delete"));
+    assert_eq!(
+      names.len(),
+      ccengram::config::ALL_TOOLS.len() - ccengram::config::WRITE_TOOLS.len()
+    );
+  }
 }
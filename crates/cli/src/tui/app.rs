@@ -1,15 +1,19 @@
 use std::{io, path::PathBuf, time::Duration};
 
 use anyhow::Result;
-use ccengram::ipc::{
-  Client,
-  code::{CodeContextParams, CodeListParams, CodeStatsParams},
-  docs::{DocContextParams, DocsSearchParams},
-  memory::{MemoryDeemphasizeParams, MemoryListParams, MemoryReinforceParams},
-  project::SessionListParams,
-  search::ExploreParams,
-  system::{HealthCheckParams, MetricsParams, ProjectStatsParams, ShutdownParams},
-  watch::WatchStatusParams,
+use ccengram::{
+  config::Config,
+  ipc::{
+    Client,
+    code::{CodeContextParams, CodeListParams, CodeStatsParams},
+    docs::{DocContextParams, DocsSearchParams},
+    exploration::{ExplorationGetParams, ExplorationPinParams},
+    memory::{MemoryDeemphasizeParams, MemoryListParams, MemoryReinforceParams, MemorySearchParams},
+    project::SessionListParams,
+    search::ExploreParams,
+    system::{HealthCheckParams, MetricsParams, ProjectStatsParams, ShutdownParams},
+    watch::WatchStatusParams,
+  },
 };
 use crossterm::{
   event::{self, Event as CrosstermEvent, KeyEventKind},
@@ -31,11 +35,13 @@ use crate::tui::{
   event::{Action, key_to_action},
   theme::Theme,
   views::{
-    CodeView, DashboardView, DocumentView, MemoryView, SearchView, SessionView,
+    CodeView, DashboardView, DocumentView, LogsView, MemoryView, SavedView, SearchView, SessionView,
     code::CodeState,
     dashboard::DashboardState,
     document::DocumentState,
+    logs::LogsState,
     memory::MemoryState,
+    saved::SavedState,
     search::{SearchResult, SearchResultType, SearchState},
     session::SessionState,
   },
@@ -51,6 +57,8 @@ pub enum View {
   Document,
   Session,
   Search,
+  Logs,
+  Saved,
 }
 
 impl View {
@@ -62,6 +70,8 @@ impl View {
       View::Document => "Docs",
       View::Session => "Sessions",
       View::Search => "Search",
+      View::Logs => "Logs",
+      View::Saved => "Saved",
     }
   }
 
@@ -73,6 +83,8 @@ impl View {
       View::Document => 3,
       View::Session => 4,
       View::Search => 5,
+      View::Logs => 6,
+      View::Saved => 7,
     }
   }
 
@@ -84,6 +96,8 @@ impl View {
       3 => View::Document,
       4 => View::Session,
       5 => View::Search,
+      6 => View::Logs,
+      7 => View::Saved,
       _ => View::Dashboard,
     }
   }
@@ -115,6 +129,8 @@ pub struct App {
   pub document: DocumentState,
   pub session: SessionState,
   pub search: SearchState,
+  pub logs: LogsState,
+  pub saved: SavedState,
 }
 
 impl App {
@@ -141,6 +157,8 @@ impl App {
       document: DocumentState::new(),
       session: SessionState::new(),
       search: SearchState::new(),
+      logs: LogsState::new(),
+      saved: SavedState::new(),
     })
   }
 
@@ -251,6 +269,15 @@ impl App {
       View::Search => {
         // Search is triggered explicitly, not on refresh
       }
+      View::Logs => self.logs.reload(),
+      View::Saved => {
+        // Saved searches are re-read so config edits show up on refresh
+        let config = Config::load_for_project(&self.project_path).await;
+        self.saved.set_searches(config.search.saved);
+        if self.saved.results_for.is_some() {
+          self.run_saved_search().await;
+        }
+      }
     }
   }
 
@@ -294,6 +321,10 @@ impl App {
       Action::CycleSort => self.cycle_sort(),
       Action::ToggleSearchMemories => self.toggle_search_memories().await,
       Action::ToggleSearchCode => self.toggle_search_code().await,
+      Action::Pin => self.pin_search_result().await,
+      Action::NextError if self.current_view == View::Logs => self.logs.next_error(),
+      Action::PrevError if self.current_view == View::Logs => self.logs.prev_error(),
+      Action::NextError | Action::PrevError => {}
       Action::None => {}
     }
   }
@@ -305,6 +336,8 @@ impl App {
       View::Document => self.document.select_prev(),
       View::Session => self.session.select_prev(),
       View::Search => self.search.select_prev(),
+      View::Logs => self.logs.select_prev(),
+      View::Saved => self.saved.select_prev(),
       _ => {}
     }
   }
@@ -316,6 +349,8 @@ impl App {
       View::Document => self.document.select_next(),
       View::Session => self.session.select_next(),
       View::Search => self.search.select_next(),
+      View::Logs => self.logs.select_next(),
+      View::Saved => self.saved.select_next(),
       _ => {}
     }
   }
@@ -359,6 +394,7 @@ impl App {
           self.toggle_search_context().await;
         }
       }
+      View::Saved => self.run_saved_search().await,
       _ => {}
     }
   }
@@ -496,6 +532,7 @@ impl App {
         if self.current_view == View::Search {
           self.search.input_active = false;
         }
+        self.logs.input_active = false;
       }
       InputMode::Filter => {
         // Cancel filter input, clear filter text (don't apply)
@@ -510,6 +547,8 @@ impl App {
         } else if self.current_view == View::Search && self.search.filter_active {
           // Clear active filter first
           self.search.clear_filter();
+        } else if self.current_view == View::Logs && !self.logs.search_query.is_empty() {
+          self.logs.clear_search();
         } else {
           self.current_view = View::Dashboard;
         }
@@ -519,7 +558,10 @@ impl App {
 
   fn open_search(&mut self) {
     self.input_mode = InputMode::Search;
-    if self.current_view == View::Search {
+    if self.current_view == View::Logs {
+      // Search within the log instead of switching views
+      self.logs.input_active = true;
+    } else if self.current_view == View::Search {
       self.search.input_active = true;
     } else {
       // Switch to search view
@@ -529,6 +571,16 @@ impl App {
   }
 
   fn open_filter(&mut self) {
+    // In the Logs view, f cycles the level filter instead
+    if self.current_view == View::Logs {
+      self.logs.cycle_level();
+      return;
+    }
+    // In the Memory view, f cycles the grouping
+    if self.current_view == View::Memory {
+      self.memory.cycle_group();
+      return;
+    }
     // Filter only works in Search view
     if self.current_view == View::Search {
       self.input_mode = InputMode::Filter;
@@ -585,6 +637,11 @@ impl App {
 
   async fn submit(&mut self) {
     match self.input_mode {
+      InputMode::Search if self.current_view == View::Logs => {
+        // The log is filtered as the query is typed
+        self.input_mode = InputMode::Normal;
+        self.logs.input_active = false;
+      }
       InputMode::Search => {
         self.execute_search().await;
         self.input_mode = InputMode::Normal;
@@ -603,7 +660,10 @@ impl App {
   fn input_char(&mut self, c: char) {
     match self.input_mode {
       InputMode::Search => {
-        if self.current_view == View::Search {
+        if self.current_view == View::Logs {
+          self.logs.search_query.push(c);
+          self.logs.filter_changed();
+        } else if self.current_view == View::Search {
           self.search.query.push(c);
         } else {
           self.memory.search_query.push(c);
@@ -621,7 +681,10 @@ impl App {
   fn delete_char(&mut self) {
     match self.input_mode {
       InputMode::Search => {
-        if self.current_view == View::Search {
+        if self.current_view == View::Logs {
+          self.logs.search_query.pop();
+          self.logs.filter_changed();
+        } else if self.current_view == View::Search {
           self.search.query.pop();
         } else {
           self.memory.search_query.pop();
@@ -661,6 +724,8 @@ impl App {
       }
       View::Session => self.session.selected = 0,
       View::Search => self.search.selected = 0,
+      View::Logs => self.logs.go_to_top(),
+      View::Saved => self.saved.go_to_top(),
       _ => {}
     }
   }
@@ -695,6 +760,8 @@ impl App {
           self.search.selected = display_len - 1;
         }
       }
+      View::Logs => self.logs.go_to_bottom(),
+      View::Saved => self.saved.go_to_bottom(),
       _ => {}
     }
   }
@@ -708,8 +775,10 @@ impl App {
       View::Memory => self.memory.toggle_focus(),
       View::Search => self.search.toggle_focus(),
       View::Session => self.session.toggle_focus(),
+      View::Logs => self.logs.cycle_source(),
+      View::Saved => self.saved.toggle_focus(),
       _ => {
-        let next = (self.current_view.index() + 1) % 6;
+        let next = (self.current_view.index() + 1) % 8;
         self.current_view = View::from_index(next);
       }
     }
@@ -752,6 +821,65 @@ impl App {
     }
   }
 
+  /// Open a saved exploration in the Search view
+  pub async fn open_exploration(&mut self, name: String) -> Result<()> {
+    let exploration = self.client.call(ExplorationGetParams { name: Some(name) }).await?;
+    self.search.open_exploration(exploration);
+    self.current_view = View::Search;
+    Ok(())
+  }
+
+  /// Pin the selected search result to the draft exploration
+  async fn pin_search_result(&mut self) {
+    if self.current_view != View::Search || self.search.input_active {
+      return;
+    }
+    let Some(id) = self
+      .search
+      .selected_result()
+      .and_then(|r| r.data.get("id"))
+      .and_then(|v| v.as_str())
+      .map(str::to_string)
+    else {
+      return;
+    };
+
+    match self.client.call(ExplorationPinParams { id, note: None }).await {
+      Ok(draft) => {
+        // An opened saved exploration stays on screen; pins go to the draft
+        if self.search.exploration.as_ref().is_none_or(|e| e.name.is_none()) {
+          self.search.exploration = Some(draft);
+        }
+      }
+      Err(e) => {
+        self.search.error = Some(format!("Pin failed: {}", e));
+      }
+    }
+  }
+
+  /// Run the selected saved search
+  async fn run_saved_search(&mut self) {
+    let Some(saved) = self.saved.selected_search() else {
+      return;
+    };
+    let params = MemorySearchParams {
+      limit: Some(50),
+      ..MemorySearchParams::from(saved)
+    };
+
+    self.saved.loading = true;
+    match self.client.call(params).await {
+      Ok(result) => {
+        self.saved.set_results(result.items);
+        self.saved.error = None;
+      }
+      Err(e) => {
+        self.saved.error = Some(format!("Search failed: {}", e));
+      }
+    }
+    self.saved.loading = false;
+  }
+
   async fn execute_search(&mut self) {
     if self.search.query.is_empty() {
       return;
@@ -786,6 +914,8 @@ impl App {
         expand_top: Some(3),
         limit: Some(50),
         depth: None,
+        include_generated: None,
+        record: Some(true),
       })
       .await
     {
@@ -843,6 +973,11 @@ impl App {
           .collect();
 
         self.search.set_results(results);
+
+        // Searches are recorded in the draft, which replaces an opened exploration
+        if let Ok(draft) = self.client.call(ExplorationGetParams { name: None }).await {
+          self.search.exploration = Some(draft);
+        }
       }
       Err(e) => {
         self.search.error = Some(format!("Search failed: {}", e));
@@ -864,7 +999,7 @@ impl App {
 }
 
 /// Run the TUI application
-pub async fn run(project_path: PathBuf) -> Result<()> {
+pub async fn run(project_path: PathBuf, exploration: Option<String>) -> Result<()> {
   // Setup terminal
   enable_raw_mode()?;
   let mut stdout = io::stdout();
@@ -876,11 +1011,20 @@ pub async fn run(project_path: PathBuf) -> Result<()> {
   let mut app = App::new(project_path).await?;
 
   // Initial data load
-  app.refresh_current_view().await;
+  match exploration {
+    Some(name) => {
+      if let Err(e) = app.open_exploration(name).await {
+        app.search.error = Some(format!("Failed to open exploration: {}", e));
+        app.current_view = View::Search;
+      }
+    }
+    None => app.refresh_current_view().await,
+  }
 
   // Event loop with adaptive refresh
   let mut current_refresh_interval = Duration::from_secs(30);
   let mut refresh_interval = interval(current_refresh_interval);
+  let mut log_tail_interval = interval(Duration::from_secs(1));
 
   loop {
     // Draw
@@ -902,6 +1046,11 @@ pub async fn run(project_path: PathBuf) -> Result<()> {
                 }
             }
         }
+        _ = log_tail_interval.tick() => {
+            if app.current_view == View::Logs {
+                app.logs.reload();
+            }
+        }
         result = tokio::task::spawn_blocking(|| {
             if event::poll(Duration::from_millis(100)).ok()? {
                 event::read().ok()
@@ -968,6 +1117,8 @@ fn render_app(app: &App, area: Rect, buf: &mut Buffer) {
     View::Document => DocumentView::new(&app.document).render(chunks[1], buf),
     View::Session => SessionView::new(&app.session).render(chunks[1], buf),
     View::Search => SearchView::new(&app.search).render(chunks[1], buf),
+    View::Logs => LogsView::new(&app.logs).render(chunks[1], buf),
+    View::Saved => SavedView::new(&app.saved).render(chunks[1], buf),
   }
 
   // Render footer
@@ -993,6 +1144,8 @@ fn render_header(app: &App, area: Rect, buf: &mut Buffer) {
     View::Document,
     View::Session,
     View::Search,
+    View::Logs,
+    View::Saved,
   ];
 
   let mut x = tabs_x;
@@ -1035,9 +1188,11 @@ fn render_header(app: &App, area: Rect, buf: &mut Buffer) {
 fn render_footer(app: &App, area: Rect, buf: &mut Buffer) {
   let keybindings = match app.input_mode {
     InputMode::Normal => match app.current_view {
-      View::Memory => "q:Quit  1-7:Views  j/k:Nav  /:Search  s:Sort  ?:Help  r/d:Salience",
-      View::Search => "q:Quit  /:Search  f:Filter  m/c/d:Scopes  j/k:Nav  Esc:Clear  ?:Help",
-      _ => "q:Quit  1-7:Views  j/k:Nav  /:Search  ?:Help  R:Refresh",
+      View::Memory => "q:Quit  1-8:Views  j/k:Nav  /:Search  s:Sort  f:Group  ?:Help  r/d:Salience",
+      View::Search => "q:Quit  /:Search  f:Filter  m/c/d:Scopes  p:Pin  j/k:Nav  Esc:Clear  ?:Help",
+      View::Logs => "q:Quit  /:Search  f:Level  e/E:Errors  Tab:Source  G:Follow  Esc:Clear  ?:Help",
+      View::Saved => "q:Quit  1-8:Views  j/k:Nav  Enter:Run  Tab:Results  R:Reload  ?:Help",
+      _ => "q:Quit  1-8:Views  j/k:Nav  /:Search  ?:Help  R:Refresh",
     },
     InputMode::Search => "Enter:Search  Esc:Cancel  Type to search...",
     InputMode::Filter => {
@@ -1068,7 +1223,7 @@ fn render_footer_with_filter(app: &App, area: Rect, buf: &mut Buffer, text: &str
 fn render_help_overlay(area: Rect, buf: &mut Buffer) {
   // Center the help box
   let help_width = 55;
-  let help_height = 24;
+  let help_height = 37;
   let x = area.x + (area.width.saturating_sub(help_width)) / 2;
   let y = area.y + (area.height.saturating_sub(help_height)) / 2;
 
@@ -1091,7 +1246,7 @@ fn render_help_overlay(area: Rect, buf: &mut Buffer) {
 
   let help_text = [
     "NAVIGATION",
-    "  1-7      Switch views",
+    "  1-8      Switch views",
     "  Tab      Cycle views",
     "  j/k      Navigate up/down",
     "  h/l      Scroll detail left/right",
@@ -1102,6 +1257,7 @@ fn render_help_overlay(area: Rect, buf: &mut Buffer) {
     "  /        Open search",
     "  f        Open filter (Search view)",
     "  s        Cycle sort (Memory view)",
+    "  f        Cycle grouping (Memory view)",
     "  r        Reinforce memory",
     "  d        Deemphasize memory",
     "  R        Refresh view",
@@ -1112,6 +1268,17 @@ fn render_help_overlay(area: Rect, buf: &mut Buffer) {
     "  m        Toggle memories scope",
     "  c        Toggle code scope",
     "  d        Toggle documents scope",
+    "  p        Pin result to the exploration",
+    "",
+    "LOGS VIEW",
+    "  f        Cycle level filter",
+    "  e/E      Next/previous error",
+    "  Tab      Daemon/hooks/scheduler log",
+    "  G        Jump to end and follow",
+    "",
+    "SAVED VIEW",
+    "  Enter    Run the selected search",
+    "  R        Reload [[search.saved]] and rerun",
   ];
 
   for (i, line) in help_text.iter().enumerate() {
@@ -53,6 +53,12 @@ pub enum Action {
   ToggleSearchMemories,
   /// Toggle search code scope
   ToggleSearchCode,
+  /// Pin the selected result to the draft exploration (Search view)
+  Pin,
+  /// Jump to the next error (Logs view)
+  NextError,
+  /// Jump to the previous error (Logs view)
+  PrevError,
   /// No action
   None,
 }
@@ -75,7 +81,7 @@ pub fn key_to_action(key: KeyEvent, in_input_mode: bool) -> Action {
       KeyCode::Char('q') => Action::Quit,
       KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
 
-      // View switching (1-7)
+      // View switching (1-8)
       KeyCode::Char('1') => Action::SwitchView(0),
       KeyCode::Char('2') => Action::SwitchView(1),
       KeyCode::Char('3') => Action::SwitchView(2),
@@ -83,6 +89,7 @@ pub fn key_to_action(key: KeyEvent, in_input_mode: bool) -> Action {
       KeyCode::Char('5') => Action::SwitchView(4),
       KeyCode::Char('6') => Action::SwitchView(5),
       KeyCode::Char('7') => Action::SwitchView(6),
+      KeyCode::Char('8') => Action::SwitchView(7),
 
       // Navigation
       KeyCode::Char('j') | KeyCode::Down => Action::NavigateDown,
@@ -113,6 +120,11 @@ pub fn key_to_action(key: KeyEvent, in_input_mode: bool) -> Action {
       // Search scope toggles (work in Search view)
       KeyCode::Char('m') => Action::ToggleSearchMemories,
       KeyCode::Char('c') => Action::ToggleSearchCode,
+      KeyCode::Char('p') => Action::Pin,
+
+      // Error navigation (work in Logs view)
+      KeyCode::Char('e') => Action::NextError,
+      KeyCode::Char('E') => Action::PrevError,
 
       _ => Action::None,
     }
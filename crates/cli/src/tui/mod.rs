@@ -4,6 +4,6 @@ mod theme;
 mod views;
 mod widgets;
 
-pub async fn run(project_path: std::path::PathBuf) -> anyhow::Result<()> {
-  app::run(project_path).await
+pub async fn run(project_path: std::path::PathBuf, exploration: Option<String>) -> anyhow::Result<()> {
+  app::run(project_path, exploration).await
 }
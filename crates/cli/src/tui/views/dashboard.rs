@@ -25,9 +25,11 @@ pub struct DashboardState {
 
   // Watcher status
   pub watcher_running: bool,
+  pub watcher_paused: bool,
   pub watcher_scanning: bool,
   pub watcher_pending_changes: usize,
   pub watcher_scan_progress: Option<(usize, usize)>, // (processed, total)
+  pub watcher_last_storm: Option<(usize, u64)>,      // (changes, seconds ago)
 
   // Index quality (from code_stats)
   pub index_health_score: u32,
@@ -123,8 +125,10 @@ impl DashboardState {
   /// Update watch status from daemon response
   pub fn set_watch_status(&mut self, status: WatchStatusResult) {
     self.watcher_running = status.running;
+    self.watcher_paused = status.paused;
     self.watcher_scanning = status.scanning;
     self.watcher_pending_changes = status.pending_changes;
+    self.watcher_last_storm = status.last_storm.map(|storm| (storm.changes, storm.secs_ago));
 
     // Parse scan progress if available
     if let Some(progress) = status.scan_progress {
@@ -146,7 +150,7 @@ impl DashboardState {
     self.index_total_lines = stats.total_lines;
     self.index_total_files = stats.total_files;
 
-    // Convert language breakdown from HashMap to sorted Vec
+    // Convert language breakdown to a Vec sorted by count
     let mut langs: Vec<(String, usize)> = stats.language_breakdown.into_iter().collect();
     langs.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by count descending
     self.language_breakdown = langs;
@@ -389,6 +393,8 @@ impl DashboardView<'_> {
     // Status indicator
     let (indicator, status_text, color) = if self.state.watcher_scanning {
       ("◐", "Scanning", Theme::WARNING)
+    } else if self.state.watcher_paused {
+      ("◌", "Paused", Theme::WARNING)
     } else if self.state.watcher_running {
       ("●", "Running", Theme::SUCCESS)
     } else {
@@ -430,6 +436,15 @@ impl DashboardView<'_> {
       let pending_color = if pending > 0 { Theme::WARNING } else { Theme::TEXT };
       let line = format!("Pending: {}", pending);
       buf.set_string(inner.x, y, &line, Style::default().fg(pending_color));
+      y += 1;
+    }
+
+    // Last event storm coalesced into a rescan
+    if let Some((changes, secs_ago)) = self.state.watcher_last_storm
+      && y < inner.y + inner.height
+    {
+      let line = format!("Storm: {} changes ({}s ago)", changes, secs_ago);
+      buf.set_string(inner.x, y, &line, Style::default().fg(Theme::MUTED));
     }
   }
 
@@ -0,0 +1,406 @@
+use std::{
+  fs::File,
+  io::{Read, Seek, SeekFrom},
+  path::{Path, PathBuf},
+};
+
+use ratatui::{
+  buffer::Buffer,
+  layout::Rect,
+  style::{Color, Style},
+  widgets::{Block, Borders, Widget},
+};
+
+use crate::tui::theme::Theme;
+
+/// How much of the end of the log file is read on each refresh
+const TAIL_BYTES: u64 = 512 * 1024;
+
+/// Log level of a line, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+impl LogLevel {
+  fn parse(s: &str) -> Option<Self> {
+    match s {
+      "ERROR" => Some(Self::Error),
+      "WARN" => Some(Self::Warn),
+      "INFO" => Some(Self::Info),
+      "DEBUG" => Some(Self::Debug),
+      "TRACE" => Some(Self::Trace),
+      _ => None,
+    }
+  }
+
+  fn name(&self) -> &'static str {
+    match self {
+      Self::Error => "error",
+      Self::Warn => "warn",
+      Self::Info => "info",
+      Self::Debug => "debug",
+      Self::Trace => "trace",
+    }
+  }
+
+  fn color(&self) -> Color {
+    match self {
+      Self::Error => Theme::ERROR,
+      Self::Warn => Theme::WARNING,
+      Self::Info => Theme::TEXT,
+      Self::Debug => Theme::SUBTEXT,
+      Self::Trace => Theme::MUTED,
+    }
+  }
+}
+
+/// Which log file is shown; hooks and scheduler only have their own files
+/// with `logging.split_files`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogSource {
+  #[default]
+  Daemon,
+  Hooks,
+  Scheduler,
+}
+
+impl LogSource {
+  fn file_name(&self) -> &'static str {
+    match self {
+      Self::Daemon => "ccengram.log",
+      Self::Hooks => "ccengram-hooks.log",
+      Self::Scheduler => "ccengram-scheduler.log",
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+  pub text: String,
+  pub level: Option<LogLevel>,
+}
+
+/// Logs view state
+#[derive(Debug)]
+pub struct LogsState {
+  pub lines: Vec<LogLine>,
+  pub source: LogSource,
+  /// File the lines were read from
+  pub path: Option<PathBuf>,
+  /// Least severe level shown (None = all)
+  pub min_level: Option<LogLevel>,
+  pub search_query: String,
+  pub input_active: bool,
+  /// Selected line, as an index into the visible lines
+  pub selected: usize,
+  /// Keep the newest line selected as the file grows
+  pub follow: bool,
+  pub error: Option<String>,
+}
+
+impl Default for LogsState {
+  fn default() -> Self {
+    Self {
+      lines: Vec::new(),
+      source: LogSource::default(),
+      path: None,
+      min_level: None,
+      search_query: String::new(),
+      input_active: false,
+      selected: 0,
+      follow: true,
+      error: None,
+    }
+  }
+}
+
+impl LogsState {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Re-read the end of the newest log file for the current source
+  pub fn reload(&mut self) {
+    let Some(path) = newest_log(self.source.file_name()) else {
+      self.lines.clear();
+      self.path = None;
+      self.error = Some(format!(
+        "No {} found in {}",
+        self.source.file_name(),
+        ccengram::dirs::default_data_dir().display()
+      ));
+      return;
+    };
+
+    match read_tail(&path) {
+      Ok(text) => {
+        self.set_lines(&text);
+        self.error = None;
+      }
+      Err(e) => self.error = Some(format!("Failed to read {}: {}", path.display(), e)),
+    }
+    self.path = Some(path);
+  }
+
+  fn set_lines(&mut self, text: &str) {
+    // Continuation lines (multi-line messages) take the level of the line above
+    let mut level = None;
+    self.lines = text
+      .lines()
+      .map(|line| {
+        if let Some(parsed) = parse_level(line) {
+          level = Some(parsed);
+        }
+        LogLine {
+          text: line.to_string(),
+          level,
+        }
+      })
+      .collect();
+
+    let visible = self.visible().len();
+    if self.follow {
+      self.selected = visible.saturating_sub(1);
+    } else {
+      self.selected = self.selected.min(visible.saturating_sub(1));
+    }
+  }
+
+  /// Lines passing the level filter and search, oldest first
+  pub fn visible(&self) -> Vec<&LogLine> {
+    let query = self.search_query.to_lowercase();
+    self
+      .lines
+      .iter()
+      .filter(|line| match (self.min_level, line.level) {
+        (Some(min), Some(level)) => level <= min,
+        (Some(_), None) => false,
+        (None, _) => true,
+      })
+      .filter(|line| query.is_empty() || line.text.to_lowercase().contains(&query))
+      .collect()
+  }
+
+  pub fn select_next(&mut self) {
+    let len = self.visible().len();
+    if len == 0 {
+      return;
+    }
+    self.selected = (self.selected + 1).min(len - 1);
+    self.follow = self.selected == len - 1;
+  }
+
+  pub fn select_prev(&mut self) {
+    self.selected = self.selected.saturating_sub(1);
+    self.follow = false;
+  }
+
+  pub fn go_to_top(&mut self) {
+    self.selected = 0;
+    self.follow = false;
+  }
+
+  /// Jump to the newest line and follow the file from there
+  pub fn go_to_bottom(&mut self) {
+    self.selected = self.visible().len().saturating_sub(1);
+    self.follow = true;
+  }
+
+  /// Cycle the level filter: all, error, warn, info, debug
+  pub fn cycle_level(&mut self) {
+    self.min_level = match self.min_level {
+      None => Some(LogLevel::Error),
+      Some(LogLevel::Error) => Some(LogLevel::Warn),
+      Some(LogLevel::Warn) => Some(LogLevel::Info),
+      Some(LogLevel::Info) => Some(LogLevel::Debug),
+      Some(LogLevel::Debug) | Some(LogLevel::Trace) => None,
+    };
+    self.filter_changed();
+  }
+
+  /// Switch between the daemon, hooks and scheduler logs
+  pub fn cycle_source(&mut self) {
+    self.source = match self.source {
+      LogSource::Daemon => LogSource::Hooks,
+      LogSource::Hooks => LogSource::Scheduler,
+      LogSource::Scheduler => LogSource::Daemon,
+    };
+    self.follow = true;
+    self.reload();
+  }
+
+  /// Called after the search query or level filter changes
+  pub fn filter_changed(&mut self) {
+    let len = self.visible().len();
+    self.selected = if self.follow {
+      len.saturating_sub(1)
+    } else {
+      self.selected.min(len.saturating_sub(1))
+    };
+  }
+
+  pub fn clear_search(&mut self) {
+    self.search_query.clear();
+    self.filter_changed();
+  }
+
+  /// Select the next error after the selected line, wrapping around
+  pub fn next_error(&mut self) {
+    let errors = self.error_positions();
+    if let Some(&next) = errors.iter().find(|&&i| i > self.selected).or(errors.first()) {
+      self.selected = next;
+      self.follow = false;
+    }
+  }
+
+  /// Select the error before the selected line, wrapping around
+  pub fn prev_error(&mut self) {
+    let errors = self.error_positions();
+    if let Some(&prev) = errors.iter().rev().find(|&&i| i < self.selected).or(errors.last()) {
+      self.selected = prev;
+      self.follow = false;
+    }
+  }
+
+  fn error_positions(&self) -> Vec<usize> {
+    // Only the first line of a multi-line error counts
+    self
+      .visible()
+      .iter()
+      .enumerate()
+      .filter(|(_, line)| line.level == Some(LogLevel::Error) && parse_level(&line.text).is_some())
+      .map(|(i, _)| i)
+      .collect()
+  }
+}
+
+/// Newest file in the data directory whose name starts with `prefix`; rolled
+/// files are named `<prefix>.<date>` or `<prefix>.<n>`
+fn newest_log(prefix: &str) -> Option<PathBuf> {
+  std::fs::read_dir(ccengram::dirs::default_data_dir())
+    .ok()?
+    .flatten()
+    .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+    .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+    .max_by_key(|(modified, _)| *modified)
+    .map(|(_, path)| path)
+}
+
+/// The last [`TAIL_BYTES`] of a file, starting at a line boundary
+fn read_tail(path: &Path) -> std::io::Result<String> {
+  let mut file = File::open(path)?;
+  let len = file.metadata()?.len();
+  let start = len.saturating_sub(TAIL_BYTES);
+  file.seek(SeekFrom::Start(start))?;
+
+  let mut bytes = Vec::new();
+  file.read_to_end(&mut bytes)?;
+  let text = String::from_utf8_lossy(&bytes).into_owned();
+  if start > 0 {
+    // Drop the partial first line
+    return Ok(
+      text
+        .split_once('\n')
+        .map(|(_, rest)| rest.to_string())
+        .unwrap_or_default(),
+    );
+  }
+  Ok(text)
+}
+
+/// Level of a text (`<timestamp>  INFO target: ...`) or JSON log line
+fn parse_level(line: &str) -> Option<LogLevel> {
+  if line.starts_with('{') {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    return LogLevel::parse(value.get("level")?.as_str()?);
+  }
+  line.split_whitespace().take(2).find_map(LogLevel::parse)
+}
+
+/// Logs view widget
+pub struct LogsView<'a> {
+  state: &'a LogsState,
+}
+
+impl<'a> LogsView<'a> {
+  pub fn new(state: &'a LogsState) -> Self {
+    Self { state }
+  }
+}
+
+impl Widget for LogsView<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let visible = self.state.visible();
+
+    let file = self
+      .state
+      .path
+      .as_ref()
+      .and_then(|p| p.file_name())
+      .map(|n| n.to_string_lossy().to_string())
+      .unwrap_or_else(|| self.state.source.file_name().to_string());
+    let mut title = format!("LOGS {} ({} lines)", file, visible.len());
+    if let Some(level) = self.state.min_level {
+      title.push_str(&format!(" level≤{}", level.name()));
+    }
+    if !self.state.search_query.is_empty() || self.state.input_active {
+      let cursor = if self.state.input_active { "_" } else { "" };
+      title.push_str(&format!(" /{}{}", self.state.search_query, cursor));
+    }
+    if self.state.follow {
+      title.push_str(" [following]");
+    }
+
+    let block = Block::default()
+      .title(title)
+      .title_style(Style::default().fg(Theme::ACCENT).bold())
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(Theme::ACCENT));
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if visible.is_empty() {
+      let msg = if let Some(ref err) = self.state.error {
+        err.as_str()
+      } else if self.state.lines.is_empty() {
+        "Log is empty"
+      } else {
+        "No lines match the filter"
+      };
+      buf.set_string(inner.x, inner.y, msg, Style::default().fg(Theme::MUTED));
+      return;
+    }
+
+    let height = inner.height as usize;
+    let start = if self.state.selected >= height {
+      self.state.selected - height + 1
+    } else {
+      0
+    };
+
+    for (i, line) in visible.iter().enumerate().skip(start).take(height) {
+      let y = inner.y + (i - start) as u16;
+      let is_selected = i == self.state.selected;
+      if is_selected {
+        for x in inner.x..inner.x + inner.width {
+          buf[(x, y)].set_bg(Theme::SURFACE);
+        }
+      }
+
+      let fg = line.level.map(|l| l.color()).unwrap_or(Theme::SUBTEXT);
+      let text: String = line.text.chars().take(inner.width as usize).collect();
+      let style = if is_selected {
+        Style::default().fg(fg).bg(Theme::SURFACE)
+      } else {
+        Style::default().fg(fg)
+      };
+      buf.set_string(inner.x, y, &text, style);
+    }
+  }
+}
@@ -1,4 +1,6 @@
-use ccengram::ipc::memory::MemoryItem;
+use std::collections::HashMap;
+
+use ccengram::ipc::memory::{MemoryGroupBy, MemoryItem};
 use ratatui::{
   buffer::Buffer,
   layout::{Constraint, Direction, Layout, Rect},
@@ -52,6 +54,8 @@ pub struct MemoryState {
   pub search_query: String,
   pub filter_sector: Option<String>,
   pub sort_by: MemorySortBy,
+  /// Show the list in sections by scope path, type or tag
+  pub group_by: Option<MemoryGroupBy>,
   pub detail_scroll: usize,
   /// Which panel is focused
   pub focus: Panel,
@@ -68,6 +72,7 @@ impl MemoryState {
 
   pub fn set_memories(&mut self, memories: Vec<MemoryItem>) {
     self.memories = memories;
+    self.regroup();
     if self.selected >= self.memories.len() && !self.memories.is_empty() {
       self.selected = self.memories.len() - 1;
     }
@@ -136,6 +141,31 @@ impl MemoryState {
     self.apply_sort();
   }
 
+  /// Cycle grouping: none, scope path, type, tag
+  pub fn cycle_group(&mut self) {
+    self.group_by = match self.group_by {
+      None => Some(MemoryGroupBy::ScopePath),
+      Some(MemoryGroupBy::ScopePath) => Some(MemoryGroupBy::MemoryType),
+      Some(MemoryGroupBy::MemoryType) => Some(MemoryGroupBy::Tag),
+      Some(MemoryGroupBy::Tag) => None,
+    };
+    self.apply_sort();
+  }
+
+  /// Bring each group's memories together, keeping the sort order within
+  /// groups and ordering groups by their first memory
+  fn regroup(&mut self) {
+    let Some(by) = self.group_by else {
+      return;
+    };
+    let mut order: HashMap<String, usize> = HashMap::new();
+    for memory in &self.memories {
+      let next = order.len();
+      order.entry(by.key(memory).to_string()).or_insert(next);
+    }
+    self.memories.sort_by_key(|m| order[by.key(m)]);
+  }
+
   /// Apply current sort order to memories
   pub fn apply_sort(&mut self) {
     match self.sort_by {
@@ -153,6 +183,7 @@ impl MemoryState {
         self.memories.sort_by(|a, b| a.sector.cmp(&b.sector));
       }
     }
+    self.regroup();
     // Keep selection valid
     if self.selected >= self.memories.len() && !self.memories.is_empty() {
       self.selected = self.memories.len() - 1;
@@ -161,6 +192,14 @@ impl MemoryState {
   }
 }
 
+/// A line of the memory list
+enum ListRow<'a> {
+  /// Group key and its memory count
+  Header(&'a str, usize),
+  /// Index into the memories
+  Memory(usize),
+}
+
 /// Memory browser view widget
 pub struct MemoryView<'a> {
   state: &'a MemoryState,
@@ -193,7 +232,10 @@ impl MemoryView<'_> {
     let is_focused = self.state.focus == Panel::Left;
     let border_color = if is_focused { Theme::ACCENT } else { Theme::OVERLAY };
 
-    let sort_label = self.state.sort_by.label();
+    let sort_label = match self.state.group_by {
+      Some(by) => format!("{}, by {}", self.state.sort_by.label(), by.as_str()),
+      None => self.state.sort_by.label().to_string(),
+    };
     let title = if !self.state.search_query.is_empty() {
       format!(
         "MEMORIES ({}) - Search: {} [{}]",
@@ -233,23 +275,57 @@ impl MemoryView<'_> {
       return;
     }
 
-    // Render memory list items
+    // Rows are memories, with a header above each group when grouped
+    let rows = self.rows();
+    let selected_row = rows
+      .iter()
+      .position(|row| matches!(row, ListRow::Memory(i) if *i == self.state.selected))
+      .unwrap_or(0);
+
     let visible_height = inner.height as usize;
-    let start = if self.state.selected >= visible_height {
-      self.state.selected - visible_height + 1
+    let start = if selected_row >= visible_height {
+      selected_row - visible_height + 1
     } else {
       0
     };
 
-    for (i, memory) in self.state.memories.iter().enumerate().skip(start).take(visible_height) {
-      let y = inner.y + (i - start) as u16;
-      if y >= inner.y + inner.height {
-        break;
+    for (r, row) in rows.iter().enumerate().skip(start).take(visible_height) {
+      let y = inner.y + (r - start) as u16;
+      match row {
+        ListRow::Header(key, count) => {
+          let header = format!("── {} ({}) ", key, count);
+          buf.set_string(inner.x, y, &header, Style::default().fg(Theme::ACCENT).bold());
+        }
+        ListRow::Memory(i) => {
+          let is_selected = *i == self.state.selected;
+          self.render_memory_item(&self.state.memories[*i], inner.x, y, inner.width, is_selected, buf);
+        }
       }
+    }
+  }
+
+  fn rows(&self) -> Vec<ListRow<'_>> {
+    let memories = &self.state.memories;
+    let Some(by) = self.state.group_by else {
+      return (0..memories.len()).map(ListRow::Memory).collect();
+    };
 
-      let is_selected = i == self.state.selected;
-      self.render_memory_item(memory, inner.x, y, inner.width, is_selected, buf);
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for memory in memories {
+      *counts.entry(by.key(memory)).or_default() += 1;
     }
+
+    let mut rows = Vec::new();
+    let mut current = None;
+    for (i, memory) in memories.iter().enumerate() {
+      let key = by.key(memory);
+      if current != Some(key) {
+        rows.push(ListRow::Header(key, counts[key]));
+        current = Some(key);
+      }
+      rows.push(ListRow::Memory(i));
+    }
+    rows
   }
 
   fn render_memory_item(&self, memory: &MemoryItem, x: u16, y: u16, width: u16, selected: bool, buf: &mut Buffer) {
@@ -292,6 +368,15 @@ impl MemoryView<'_> {
     let empty = 5 - filled;
     let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
     buf.set_string(salience_x, y, &bar, Style::default().fg(salience_color));
+
+    if memory.stale {
+      buf.set_string(
+        salience_x.saturating_sub(2),
+        y,
+        "!",
+        Style::default().fg(Theme::WARNING).bold(),
+      );
+    }
   }
 
   fn render_detail(&self, area: Rect, buf: &mut Buffer) {
@@ -378,6 +463,19 @@ impl MemoryView<'_> {
     buf.set_string(inner.x + 10, y, &date, Style::default().fg(Theme::TEXT));
     y += 1;
 
+    if memory.stale {
+      let reason = memory.stale_reason.as_deref().unwrap_or("referenced code changed");
+      buf.set_string(inner.x, y, "Stale: ", Style::default().fg(Theme::WARNING).bold());
+      let max_width = inner.width.saturating_sub(7) as usize;
+      let reason = if reason.len() > max_width {
+        format!("{}...", &reason[..max_width.saturating_sub(3)])
+      } else {
+        reason.to_string()
+      };
+      buf.set_string(inner.x + 7, y, &reason, Style::default().fg(Theme::WARNING));
+      y += 1;
+    }
+
     y += 1; // Separator
 
     // Content header
@@ -1,13 +1,17 @@
 pub mod code;
 pub mod dashboard;
 pub mod document;
+pub mod logs;
 pub mod memory;
+pub mod saved;
 pub mod search;
 pub mod session;
 
 pub use code::CodeView;
 pub use dashboard::DashboardView;
 pub use document::DocumentView;
+pub use logs::LogsView;
 pub use memory::MemoryView;
+pub use saved::SavedView;
 pub use search::SearchView;
 pub use session::SessionView;
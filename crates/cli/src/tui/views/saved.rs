@@ -0,0 +1,333 @@
+use ccengram::{config::SavedSearchConfig, ipc::memory::MemoryItem};
+use ratatui::{
+  buffer::Buffer,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::Style,
+  widgets::{Block, Borders, Widget},
+};
+
+use crate::tui::theme::Theme;
+
+/// Which panel is focused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Panel {
+  #[default]
+  Left,
+  Right,
+}
+
+/// Saved searches view state
+#[derive(Debug, Default)]
+pub struct SavedState {
+  /// `[[search.saved]]` entries from the project config
+  pub searches: Vec<SavedSearchConfig>,
+  pub selected: usize,
+  /// Results of the search last run
+  pub results: Vec<MemoryItem>,
+  /// Index of the search the results belong to
+  pub results_for: Option<usize>,
+  pub result_selected: usize,
+  /// Which panel is focused
+  pub focus: Panel,
+  pub loading: bool,
+  pub error: Option<String>,
+}
+
+impl SavedState {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn set_searches(&mut self, searches: Vec<SavedSearchConfig>) {
+    self.searches = searches;
+    if self.selected >= self.searches.len() {
+      self.selected = self.searches.len().saturating_sub(1);
+    }
+  }
+
+  pub fn selected_search(&self) -> Option<&SavedSearchConfig> {
+    self.searches.get(self.selected)
+  }
+
+  pub fn set_results(&mut self, results: Vec<MemoryItem>) {
+    self.results = results;
+    self.results_for = Some(self.selected);
+    self.result_selected = 0;
+  }
+
+  pub fn selected_result(&self) -> Option<&MemoryItem> {
+    self.results.get(self.result_selected)
+  }
+
+  /// Toggle focus between panels
+  pub fn toggle_focus(&mut self) {
+    self.focus = match self.focus {
+      Panel::Left => Panel::Right,
+      Panel::Right => Panel::Left,
+    };
+  }
+
+  pub fn select_next(&mut self) {
+    match self.focus {
+      Panel::Left => {
+        if !self.searches.is_empty() {
+          self.selected = (self.selected + 1).min(self.searches.len() - 1);
+        }
+      }
+      Panel::Right => {
+        if !self.results.is_empty() {
+          self.result_selected = (self.result_selected + 1).min(self.results.len() - 1);
+        }
+      }
+    }
+  }
+
+  pub fn select_prev(&mut self) {
+    match self.focus {
+      Panel::Left => self.selected = self.selected.saturating_sub(1),
+      Panel::Right => self.result_selected = self.result_selected.saturating_sub(1),
+    }
+  }
+
+  pub fn go_to_top(&mut self) {
+    match self.focus {
+      Panel::Left => self.selected = 0,
+      Panel::Right => self.result_selected = 0,
+    }
+  }
+
+  pub fn go_to_bottom(&mut self) {
+    match self.focus {
+      Panel::Left => self.selected = self.searches.len().saturating_sub(1),
+      Panel::Right => self.result_selected = self.results.len().saturating_sub(1),
+    }
+  }
+}
+
+/// The filters of a saved search, e.g. `type=gotcha scope=src/auth`
+fn describe_filters(saved: &SavedSearchConfig) -> String {
+  let mut filters = Vec::new();
+  if let Some(sector) = &saved.sector {
+    filters.push(format!("sector={}", sector));
+  }
+  if let Some(memory_type) = &saved.memory_type {
+    filters.push(format!("type={}", memory_type));
+  }
+  if let Some(min_salience) = saved.min_salience {
+    filters.push(format!("salience>={:.1}", min_salience));
+  }
+  if let Some(scope) = &saved.scope {
+    filters.push(format!("scope={}", scope));
+  }
+  if saved.include_superseded {
+    filters.push("superseded".to_string());
+  }
+  filters.join(" ")
+}
+
+/// `text` cut to `width` characters
+fn fit(text: &str, width: usize) -> String {
+  if text.chars().count() > width {
+    let cut: String = text.chars().take(width.saturating_sub(3)).collect();
+    format!("{}...", cut)
+  } else {
+    text.to_string()
+  }
+}
+
+/// Saved searches view widget
+pub struct SavedView<'a> {
+  state: &'a SavedState,
+}
+
+impl<'a> SavedView<'a> {
+  pub fn new(state: &'a SavedState) -> Self {
+    Self { state }
+  }
+}
+
+impl Widget for SavedView<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    // Split into saved search list and results panels
+    let chunks = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+      .split(area);
+
+    self.render_searches(chunks[0], buf);
+    self.render_results(chunks[1], buf);
+  }
+}
+
+impl SavedView<'_> {
+  fn render_searches(&self, area: Rect, buf: &mut Buffer) {
+    let is_focused = self.state.focus == Panel::Left;
+    let border_color = if is_focused { Theme::ACCENT } else { Theme::OVERLAY };
+
+    let block = Block::default()
+      .title(format!("SAVED SEARCHES ({}) [Tab]", self.state.searches.len()))
+      .title_style(Style::default().fg(Theme::ACCENT).bold())
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(border_color));
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if self.state.searches.is_empty() {
+      let lines = [
+        "No saved searches",
+        "",
+        "Add them to the config:",
+        "  [[search.saved]]",
+        "  name = \"auth-gotchas\"",
+        "  query = \"authentication\"",
+        "  type = \"gotcha\"",
+      ];
+      for (i, line) in lines.iter().enumerate().take(inner.height as usize) {
+        buf.set_string(inner.x, inner.y + i as u16, line, Style::default().fg(Theme::MUTED));
+      }
+      return;
+    }
+
+    // Two lines per search: name, then query and filters
+    let visible = (inner.height / 2) as usize;
+    let start = if self.state.selected >= visible {
+      self.state.selected - visible + 1
+    } else {
+      0
+    };
+    let width = inner.width as usize;
+
+    for (i, saved) in self.state.searches.iter().enumerate().skip(start).take(visible) {
+      let y = inner.y + ((i - start) * 2) as u16;
+      let selected = i == self.state.selected;
+      let bg = if selected { Theme::SURFACE } else { Theme::BG };
+      for line in 0..2 {
+        for x in 0..inner.width {
+          buf[(inner.x + x, y + line)].set_bg(bg);
+        }
+      }
+
+      let indicator = if selected { "▶ " } else { "  " };
+      buf.set_string(inner.x, y, indicator, Style::default().fg(Theme::ACCENT));
+      let name_style = if selected {
+        Style::default().fg(Theme::TEXT).bold()
+      } else {
+        Style::default().fg(Theme::SUBTEXT)
+      };
+      buf.set_string(inner.x + 2, y, fit(&saved.name, width.saturating_sub(2)), name_style);
+
+      let filters = describe_filters(saved);
+      let detail = if filters.is_empty() {
+        format!("\"{}\"", saved.query)
+      } else {
+        format!("\"{}\" {}", saved.query, filters)
+      };
+      buf.set_string(
+        inner.x + 4,
+        y + 1,
+        fit(&detail, width.saturating_sub(4)),
+        Style::default().fg(Theme::MUTED),
+      );
+    }
+  }
+
+  fn render_results(&self, area: Rect, buf: &mut Buffer) {
+    let is_focused = self.state.focus == Panel::Right;
+    let border_color = if is_focused { Theme::ACCENT } else { Theme::OVERLAY };
+
+    let title = match self.state.results_for.and_then(|i| self.state.searches.get(i)) {
+      Some(saved) => format!("RESULTS: {} ({})", saved.name, self.state.results.len()),
+      None => "RESULTS".to_string(),
+    };
+    let block = Block::default()
+      .title(title)
+      .title_style(Style::default().fg(Theme::ACCENT).bold())
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(border_color));
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if self.state.results.is_empty() {
+      let msg = if self.state.loading {
+        "Searching..."
+      } else if let Some(ref err) = self.state.error {
+        err
+      } else if self.state.results_for.is_some() {
+        "No memories found"
+      } else {
+        "Press Enter to run the selected search"
+      };
+      buf.set_string(inner.x, inner.y, msg, Style::default().fg(Theme::MUTED));
+      return;
+    }
+
+    // Result list on top, content of the selected result below
+    let chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+      .split(inner);
+    let list = chunks[0];
+    let width = list.width as usize;
+
+    let visible = list.height as usize;
+    let start = if self.state.result_selected >= visible {
+      self.state.result_selected - visible + 1
+    } else {
+      0
+    };
+    for (i, memory) in self.state.results.iter().enumerate().skip(start).take(visible) {
+      let y = list.y + (i - start) as u16;
+      let selected = i == self.state.result_selected;
+      let bg = if selected { Theme::SURFACE } else { Theme::BG };
+      for x in 0..list.width {
+        buf[(list.x + x, y)].set_bg(bg);
+      }
+
+      let indicator = if selected { "▶ " } else { "  " };
+      buf.set_string(list.x, y, indicator, Style::default().fg(Theme::ACCENT));
+      let badge = format!("[{}] ", &memory.sector[..3.min(memory.sector.len())].to_uppercase());
+      buf.set_string(
+        list.x + 2,
+        y,
+        &badge,
+        Style::default().fg(Theme::sector_color(&memory.sector)).bold(),
+      );
+
+      let similarity = memory.similarity.map(|s| format!(" {:.2}", s)).unwrap_or_default();
+      let preview_x = list.x + 2 + badge.len() as u16;
+      let preview_width = width.saturating_sub(2 + badge.len() + similarity.len());
+      let preview = memory.content.lines().next().unwrap_or("").trim();
+      let fg = if selected { Theme::TEXT } else { Theme::SUBTEXT };
+      buf.set_string(preview_x, y, fit(preview, preview_width), Style::default().fg(fg));
+      buf.set_string(
+        list.x + width.saturating_sub(similarity.len()) as u16,
+        y,
+        &similarity,
+        Style::default().fg(Theme::MUTED),
+      );
+    }
+
+    let Some(memory) = self.state.selected_result() else {
+      return;
+    };
+    let detail = chunks[1];
+    for x in 0..detail.width {
+      buf[(detail.x + x, detail.y)].set_char('─').set_fg(Theme::OVERLAY);
+    }
+    for (i, line) in memory
+      .content
+      .lines()
+      .take(detail.height.saturating_sub(1) as usize)
+      .enumerate()
+    {
+      buf.set_string(
+        detail.x,
+        detail.y + 1 + i as u16,
+        fit(line, detail.width as usize),
+        Style::default().fg(Theme::TEXT),
+      );
+    }
+  }
+}
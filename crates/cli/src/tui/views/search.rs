@@ -1,4 +1,8 @@
-use ccengram::ipc::{code::CodeContextResponse, docs::DocContextResult};
+use ccengram::ipc::{
+  code::CodeContextResponse,
+  docs::DocContextResult,
+  exploration::{Exploration, ExplorationEntry, ExplorationItem},
+};
 use ratatui::{
   buffer::Buffer,
   layout::{Constraint, Direction, Layout, Rect},
@@ -40,6 +44,42 @@ pub struct SearchResult {
   pub similarity: f32,
 }
 
+impl SearchResult {
+  /// A result saved in an exploration, carrying its pin note if any
+  pub fn from_exploration_item(item: &ExplorationItem, note: Option<&str>) -> Option<Self> {
+    let result_type = match item.result_type.as_str() {
+      "code" => SearchResultType::Code,
+      "memory" => SearchResultType::Memory,
+      "doc" => SearchResultType::Document,
+      _ => return None,
+    };
+
+    let mut data = serde_json::json!({
+      "id": item.id,
+      "preview": item.preview,
+      "content": item.preview,
+    });
+    if let Some(file_path) = &item.file_path {
+      data["file_path"] = serde_json::json!(file_path);
+    }
+    if let Some(line) = item.line {
+      data["start_line"] = serde_json::json!(line);
+    }
+    if result_type == SearchResultType::Document {
+      data["title"] = serde_json::json!(item.file_path.as_deref().unwrap_or(&item.preview));
+    }
+    if let Some(note) = note {
+      data["note"] = serde_json::json!(note);
+    }
+
+    Some(Self {
+      result_type,
+      data,
+      similarity: 1.0,
+    })
+  }
+}
+
 /// Unified search view state
 #[derive(Debug, Default)]
 pub struct SearchState {
@@ -62,6 +102,8 @@ pub struct SearchState {
   pub detail_scroll: usize,
   /// Expanded context for the currently selected result
   pub expanded_context: Option<ExpandedContext>,
+  /// The opened saved exploration, or the draft being recorded
+  pub exploration: Option<Exploration>,
 }
 
 impl SearchState {
@@ -80,6 +122,45 @@ impl SearchState {
     }
   }
 
+  /// Show a saved exploration: its pinned results, or the results of its
+  /// queries when nothing was pinned, with the last query in the search bar
+  pub fn open_exploration(&mut self, exploration: Exploration) {
+    let pins: Vec<SearchResult> = exploration
+      .entries
+      .iter()
+      .filter_map(|entry| match entry {
+        ExplorationEntry::Pin { item, note, .. } => SearchResult::from_exploration_item(item, note.as_deref()),
+        _ => None,
+      })
+      .collect();
+    let results = if pins.is_empty() {
+      let mut seen = std::collections::HashSet::new();
+      exploration
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+          ExplorationEntry::Query { results, .. } => Some(results),
+          _ => None,
+        })
+        .flatten()
+        .filter(|item| seen.insert(item.id.clone()))
+        .filter_map(|item| SearchResult::from_exploration_item(item, None))
+        .collect()
+    } else {
+      pins
+    };
+
+    if let Some(query) = exploration.entries.iter().rev().find_map(|entry| match entry {
+      ExplorationEntry::Query { query, .. } => Some(query.clone()),
+      _ => None,
+    }) {
+      self.query = query;
+    }
+    self.selected = 0;
+    self.set_results(results);
+    self.exploration = Some(exploration);
+  }
+
   /// Toggle focus between panels
   pub fn toggle_focus(&mut self) {
     self.focus = match self.focus {
@@ -457,6 +538,22 @@ impl SearchView<'_> {
     if self.state.filter_active {
       let filter_label = format!("│ Filter: \"{}\"", self.state.filter_text);
       buf.set_string(x, y, &filter_label, Style::default().fg(Theme::ACCENT));
+      x += filter_label.chars().count() as u16 + 2;
+    }
+
+    // Exploration indicator
+    if let Some(exploration) = &self.state.exploration
+      && !exploration.entries.is_empty()
+    {
+      let count = |f: fn(&ExplorationEntry) -> bool| exploration.entries.iter().filter(|e| f(e)).count();
+      let label = format!(
+        "│ {}: {} queries, {} pins (p), {} notes",
+        exploration.name.as_deref().unwrap_or("Draft exploration"),
+        count(|e| matches!(e, ExplorationEntry::Query { .. })),
+        count(|e| matches!(e, ExplorationEntry::Pin { .. })),
+        count(|e| matches!(e, ExplorationEntry::Note { .. })),
+      );
+      buf.set_string(x, y, &label, Style::default().fg(Theme::SUBTEXT));
     }
   }
 
@@ -623,9 +720,28 @@ impl SearchView<'_> {
       .borders(Borders::ALL)
       .border_style(Style::default().fg(border_color));
 
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
     block.render(area, buf);
 
+    // Pin note from an opened exploration
+    if self.state.expanded_context.is_none()
+      && let Some(note) = self
+        .state
+        .selected_result()
+        .and_then(|r| r.data.get("note"))
+        .and_then(|n| n.as_str())
+    {
+      buf.set_stringn(
+        inner.x,
+        inner.y,
+        format!("Note: {}", note),
+        inner.width as usize,
+        Style::default().fg(Theme::ACCENT),
+      );
+      inner.y += 2;
+      inner.height = inner.height.saturating_sub(2);
+    }
+
     // If we have expanded context, render that instead
     if let Some(ref context) = self.state.expanded_context {
       let scroll = self.state.detail_scroll;
@@ -900,6 +1016,18 @@ impl SearchView<'_> {
       });
     }
 
+    // Stale flag
+    if data.get("stale").and_then(|s| s.as_bool()) == Some(true) {
+      let reason = data
+        .get("stale_reason")
+        .and_then(|r| r.as_str())
+        .unwrap_or("referenced code changed");
+      render_line!({
+        buf.set_string(area.x, y, "Stale: ", Style::default().fg(Theme::WARNING).bold());
+        buf.set_string(area.x + 7, y, reason, Style::default().fg(Theme::WARNING));
+      });
+    }
+
     // Blank line
     render_line!({});
 
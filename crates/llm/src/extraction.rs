@@ -3,20 +3,29 @@
 //! This module provides provider-agnostic functions for:
 //! - Signal classification (detecting extractable user inputs)
 //! - Memory extraction (extracting memories from conversation context)
+//! - Text extraction (extracting memories from notes and other documents)
 //! - Superseding detection (finding memories that should be marked superseded)
+//! - Session summarization (rolling summary of earlier segments)
+//! - Retrospectives (comparing a plan with its outcome)
 
 use serde::de::DeserializeOwned;
 use tracing::{debug, info, trace, warn};
 
 use crate::{
-  ExtractionContext, ExtractionResult, InferenceRequest, LlmProvider, Result, SignalCategory, SignalClassification,
-  SupersedingResult,
+  ExtractedMemory, ExtractionContext, ExtractionResult, InferenceRequest, InferenceResponse, LlmProvider, LlmTask,
+  Result, RetroInput, Retrospective, SessionSummary, SignalCategory, SignalClassification, SupersedingResult,
+  TurnSummary,
   prompts::{
-    EXTRACTION_SCHEMA, EXTRACTION_SYSTEM_PROMPT, SIGNAL_CLASSIFICATION_SCHEMA, SUPERSEDING_SCHEMA,
-    build_extraction_prompt, build_signal_classification_prompt, build_superseding_prompt,
+    EXTRACTION_SCHEMA, EXTRACTION_SYSTEM_PROMPT, RETRO_SCHEMA, SESSION_SUMMARY_SCHEMA, SIGNAL_CLASSIFICATION_SCHEMA,
+    SUPERSEDING_SCHEMA, TURN_SUMMARY_SCHEMA, build_extraction_prompt, build_repair_prompt, build_retro_prompt,
+    build_session_summary_prompt, build_signal_classification_prompt, build_superseding_prompt,
+    build_text_extraction_prompt, build_turn_summary_prompt,
   },
 };
 
+/// Re-prompts allowed after a response fails schema validation
+const MAX_REPAIR_ATTEMPTS: usize = 2;
+
 /// Parse JSON from an LLM response text
 ///
 /// Handles responses that may be wrapped in markdown code blocks:
@@ -75,6 +84,88 @@ fn extract_code_block(text: &str) -> Option<&str> {
   Some(after_fence[..end].trim())
 }
 
+/// Run inference and parse the response as `T`, re-prompting with the
+/// validation error when the response doesn't match the schema.
+///
+/// Inference failures are returned immediately. Once repairs are exhausted the
+/// last parse error is returned alongside the last response, so callers can
+/// salvage what they can from it.
+async fn infer_structured<T: DeserializeOwned>(
+  provider: &dyn LlmProvider,
+  request: InferenceRequest,
+) -> Result<(std::result::Result<T, serde_json::Error>, InferenceResponse)> {
+  let original_prompt = request.prompt.clone();
+  let mut response = provider.infer(request.clone()).await?;
+
+  for attempt in 1..=MAX_REPAIR_ATTEMPTS {
+    let error = match parse_json::<T>(&response.text) {
+      Ok(parsed) => return Ok((Ok(parsed), response)),
+      Err(e) => e,
+    };
+
+    debug!(
+      task = ?request.task,
+      attempt,
+      max_attempts = MAX_REPAIR_ATTEMPTS,
+      err = %error,
+      "Structured response failed validation, requesting repair"
+    );
+
+    let repair = InferenceRequest {
+      prompt: build_repair_prompt(&original_prompt, &response.text, &error.to_string()),
+      ..request.clone()
+    };
+    response = provider.infer(repair).await?;
+  }
+
+  Ok((parse_json(&response.text), response))
+}
+
+/// Keep the valid memories from an extraction response that failed validation.
+///
+/// Accepts `{"memories": [...]}` or a bare array. Returns `None` when the
+/// response isn't JSON or no element is a valid memory.
+fn parse_partial_extraction(text: &str) -> Option<ExtractionResult> {
+  let value: serde_json::Value = parse_json(text).ok()?;
+  let items = match &value {
+    serde_json::Value::Array(items) => items,
+    _ => value.get("memories")?.as_array()?,
+  };
+
+  let memories: Vec<ExtractedMemory> = items
+    .iter()
+    .filter_map(|item| match serde_json::from_value(item.clone()) {
+      Ok(memory) => Some(memory),
+      Err(e) => {
+        debug!(err = %e, "Dropping invalid extracted memory");
+        None
+      }
+    })
+    .collect();
+
+  if memories.is_empty() {
+    return None;
+  }
+
+  warn!(
+    accepted = memories.len(),
+    rejected = items.len() - memories.len(),
+    "Accepting valid memories from partially invalid extraction response"
+  );
+  Some(ExtractionResult { memories })
+}
+
+/// Parse an extraction response, falling back to partial acceptance
+fn accept_extraction(
+  parsed: std::result::Result<ExtractionResult, serde_json::Error>,
+  text: &str,
+) -> Result<ExtractionResult> {
+  match parsed {
+    Ok(result) => Ok(result),
+    Err(e) => parse_partial_extraction(text).ok_or_else(|| e.into()),
+  }
+}
+
 /// Classify a user message to determine if it contains extractable signals
 pub async fn classify_signal(provider: &dyn LlmProvider, user_message: &str) -> Result<SignalClassification> {
   debug!(
@@ -91,11 +182,12 @@ pub async fn classify_signal(provider: &dyn LlmProvider, user_message: &str) ->
     model: "haiku".to_string(),
     timeout_secs: 30,
     json_schema: SIGNAL_CLASSIFICATION_SCHEMA.to_string(),
+    task: Some(LlmTask::Classification),
     ..Default::default()
   };
 
-  let response = provider.infer(request).await?;
-  let classification: SignalClassification = parse_json(&response.text)?;
+  let (parsed, _response) = infer_structured::<SignalClassification>(provider, request).await?;
+  let classification = parsed?;
 
   debug!(
       category = ?classification.category,
@@ -149,11 +241,12 @@ pub async fn extract_memories(provider: &dyn LlmProvider, context: &ExtractionCo
     model: "haiku".to_string(),
     timeout_secs: 60,
     json_schema: EXTRACTION_SCHEMA.to_string(),
+    task: Some(LlmTask::Extraction),
   };
 
   debug!("Calling LLM for memory extraction");
-  let response = provider.infer(request).await?;
-  let result: ExtractionResult = parse_json(&response.text)?;
+  let (parsed, response) = infer_structured(provider, request).await?;
+  let result = accept_extraction(parsed, &response.text)?;
 
   if result.memories.is_empty() {
     debug!(
@@ -191,6 +284,47 @@ pub async fn extract_memories(provider: &dyn LlmProvider, context: &ExtractionCo
   Ok(result)
 }
 
+/// Extract memories from free-form text such as meeting notes or a PR description
+///
+/// `source` names where the text came from and is shown to the model.
+pub async fn extract_from_text(
+  provider: &dyn LlmProvider,
+  text: &str,
+  source: Option<&str>,
+) -> Result<ExtractionResult> {
+  debug!(
+    provider = provider.name(),
+    text_len = text.len(),
+    source = ?source,
+    "Starting text extraction"
+  );
+
+  if text.trim().is_empty() {
+    return Ok(ExtractionResult { memories: Vec::new() });
+  }
+
+  let request = InferenceRequest {
+    prompt: build_text_extraction_prompt(text, source),
+    system_prompt: Some(EXTRACTION_SYSTEM_PROMPT.to_string()),
+    model: "haiku".to_string(),
+    timeout_secs: 60,
+    json_schema: EXTRACTION_SCHEMA.to_string(),
+    task: Some(LlmTask::Extraction),
+  };
+
+  let (parsed, response) = infer_structured(provider, request).await?;
+  let result = accept_extraction(parsed, &response.text)?;
+
+  info!(
+    memories_extracted = result.memories.len(),
+    input_tokens = response.input_tokens,
+    output_tokens = response.output_tokens,
+    "Text extraction completed"
+  );
+
+  Ok(result)
+}
+
 /// Detect if a new memory supersedes any existing memories
 ///
 /// Takes the new memory content and a list of candidate existing memories
@@ -231,12 +365,13 @@ pub async fn detect_superseding(
     model: "haiku".to_string(),
     timeout_secs: 30,
     json_schema: SUPERSEDING_SCHEMA.to_string(),
+    task: Some(LlmTask::Superseding),
     ..Default::default()
   };
 
   debug!("Calling LLM for superseding detection");
-  let response = provider.infer(request).await?;
-  let result: SupersedingResult = parse_json(&response.text)?;
+  let (parsed, _response) = infer_structured::<SupersedingResult>(provider, request).await?;
+  let result = parsed?;
 
   if result.supersedes {
     info!(
@@ -257,6 +392,120 @@ pub async fn detect_superseding(
   Ok(result)
 }
 
+/// Fold a finished segment into the rolling summary of its session
+///
+/// Returns the updated summary covering `previous_summary` and the segment.
+pub async fn summarize_session(
+  provider: &dyn LlmProvider,
+  previous_summary: Option<&str>,
+  context: &ExtractionContext,
+) -> Result<String> {
+  debug!(
+    provider = provider.name(),
+    has_previous_summary = previous_summary.is_some(),
+    tool_call_count = context.tool_call_count,
+    "Updating session summary"
+  );
+
+  let prompt = build_session_summary_prompt(previous_summary, context);
+  trace!(prompt_len = prompt.len(), "Built session summary prompt");
+
+  let request = InferenceRequest {
+    prompt,
+    model: "haiku".to_string(),
+    timeout_secs: 30,
+    json_schema: SESSION_SUMMARY_SCHEMA.to_string(),
+    task: Some(LlmTask::Summarization),
+    ..Default::default()
+  };
+
+  let (parsed, response) = infer_structured::<SessionSummary>(provider, request).await?;
+  let summary = parsed?.summary.trim().to_string();
+
+  debug!(
+    summary_len = summary.len(),
+    input_tokens = response.input_tokens,
+    output_tokens = response.output_tokens,
+    "Session summary updated"
+  );
+
+  Ok(summary)
+}
+
+/// Summarize a finished turn: what was done, files touched and decisions made
+pub async fn summarize_turn(provider: &dyn LlmProvider, context: &ExtractionContext) -> Result<TurnSummary> {
+  debug!(
+    provider = provider.name(),
+    tool_call_count = context.tool_call_count,
+    "Summarizing turn"
+  );
+
+  let prompt = build_turn_summary_prompt(context);
+  trace!(prompt_len = prompt.len(), "Built turn summary prompt");
+
+  let request = InferenceRequest {
+    prompt,
+    model: "haiku".to_string(),
+    timeout_secs: 30,
+    json_schema: TURN_SUMMARY_SCHEMA.to_string(),
+    task: Some(LlmTask::Summarization),
+    ..Default::default()
+  };
+
+  let (parsed, response) = infer_structured::<TurnSummary>(provider, request).await?;
+  let mut summary = parsed?;
+  summary.summary = summary.summary.trim().to_string();
+
+  debug!(
+    summary_len = summary.summary.len(),
+    files = summary.files_touched.len(),
+    decisions = summary.decisions.len(),
+    input_tokens = response.input_tokens,
+    output_tokens = response.output_tokens,
+    "Turn summarized"
+  );
+
+  Ok(summary)
+}
+
+/// Write a retrospective comparing the plan for a piece of work with what happened
+pub async fn write_retrospective(provider: &dyn LlmProvider, input: &RetroInput) -> Result<Retrospective> {
+  debug!(
+    provider = provider.name(),
+    plan = input.plan.len(),
+    completions = input.completions.len(),
+    errors = input.errors.len(),
+    decisions = input.decisions.len(),
+    "Writing retrospective"
+  );
+
+  let prompt = build_retro_prompt(input);
+  trace!(prompt_len = prompt.len(), "Built retrospective prompt");
+
+  let request = InferenceRequest {
+    prompt,
+    model: "haiku".to_string(),
+    timeout_secs: 60,
+    json_schema: RETRO_SCHEMA.to_string(),
+    task: Some(LlmTask::Summarization),
+    ..Default::default()
+  };
+
+  let (parsed, response) = infer_structured::<Retrospective>(provider, request).await?;
+  let mut retro = parsed?;
+  retro.summary = retro.summary.trim().to_string();
+
+  debug!(
+    summary_len = retro.summary.len(),
+    lessons = retro.lessons.len(),
+    input_tokens = response.input_tokens,
+    output_tokens = response.output_tokens,
+    "Retrospective written"
+  );
+
+  Ok(retro)
+}
+
 /// High-priority extraction for corrections and preferences
 ///
 /// Triggered immediately when a high-priority signal is detected.
@@ -309,11 +558,12 @@ pub async fn extract_high_priority(
     model: "haiku".to_string(),
     timeout_secs: 30,
     json_schema: EXTRACTION_SCHEMA.to_string(),
+    task: Some(LlmTask::HighPriority),
   };
 
   debug!("Calling LLM for high-priority extraction");
-  let response = provider.infer(request).await?;
-  let result: ExtractionResult = parse_json(&response.text)?;
+  let (parsed, response) = infer_structured(provider, request).await?;
+  let result = accept_extraction(parsed, &response.text)?;
 
   if result.memories.is_empty() {
     warn!(
@@ -339,9 +589,201 @@ pub async fn extract_high_priority(
 
 #[cfg(test)]
 mod tests {
+  use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+  };
+
+  use async_trait::async_trait;
+
   use super::*;
   use crate::create_provider;
 
+  /// Provider returning canned responses in order, recording prompts
+  #[derive(Clone, Default)]
+  struct ScriptedProvider {
+    responses: Arc<Mutex<VecDeque<String>>>,
+    prompts: Arc<Mutex<Vec<String>>>,
+  }
+
+  impl ScriptedProvider {
+    fn new(responses: &[&str]) -> Self {
+      Self {
+        responses: Arc::new(Mutex::new(responses.iter().map(|r| r.to_string()).collect())),
+        prompts: Arc::default(),
+      }
+    }
+  }
+
+  #[async_trait]
+  impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &str {
+      "scripted"
+    }
+
+    fn is_available(&self) -> bool {
+      true
+    }
+
+    async fn infer(&self, request: InferenceRequest) -> Result<InferenceResponse> {
+      self.prompts.lock().unwrap().push(request.prompt);
+      let text = self.responses.lock().unwrap().pop_front().unwrap_or_default();
+      Ok(InferenceResponse {
+        text,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+        duration_ms: 0,
+      })
+    }
+  }
+
+  fn meaningful_context() -> ExtractionContext {
+    ExtractionContext {
+      user_prompt: Some("Switch the config loader to TOML".into()),
+      files_modified: vec!["src/config.rs".into()],
+      tool_call_count: 5,
+      ..Default::default()
+    }
+  }
+
+  #[tokio::test]
+  async fn test_extraction_repairs_invalid_response() {
+    let provider = ScriptedProvider::new(&[
+      r#"{"memories": [{"content": "Config is loaded from TOML", "memory_type": "codebase", "confidence": "high"}]}"#,
+      r#"{"memories": [{"content": "Config is loaded from TOML", "memory_type": "codebase", "confidence": 0.9}]}"#,
+    ]);
+
+    let result = extract_memories(&provider, &meaningful_context()).await.unwrap();
+    assert_eq!(result.memories.len(), 1, "repaired response should be accepted");
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert_eq!(prompts.len(), 2, "one repair request should be sent");
+    assert!(
+      prompts[1].contains("Validation error") && prompts[1].contains("\"confidence\": \"high\""),
+      "repair prompt should include the error and the invalid response"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_extraction_accepts_valid_elements_after_repairs() {
+    let invalid = r#"```json
+{"memories": [
+  {"content": "Tests run with cargo nextest", "memory_type": "codebase", "confidence": 0.8},
+  {"content": "Missing type", "confidence": 0.5}
+]}
+```"#;
+    let provider = ScriptedProvider::new(&[invalid, invalid, invalid]);
+
+    let result = extract_memories(&provider, &meaningful_context()).await.unwrap();
+    assert_eq!(
+      result.memories.len(),
+      1,
+      "only the valid memory should be kept once repairs are exhausted"
+    );
+    assert_eq!(result.memories[0].content, "Tests run with cargo nextest");
+    assert_eq!(
+      provider.prompts.lock().unwrap().len(),
+      1 + MAX_REPAIR_ATTEMPTS,
+      "repairs should be limited"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_extract_from_text_names_source() {
+    let provider = ScriptedProvider::new(&[
+      r#"{"memories": [{"content": "Payments retry failed charges twice before alerting", "memory_type": "decision", "confidence": 0.8}]}"#,
+    ]);
+
+    let result = extract_from_text(&provider, "We agreed to retry failed charges twice.", Some("notes.md"))
+      .await
+      .unwrap();
+    assert_eq!(result.memories.len(), 1, "memories parsed from the response");
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert!(prompts[0].contains("Source: notes.md"), "source is named in the prompt");
+    assert!(
+      prompts[0].ends_with("We agreed to retry failed charges twice."),
+      "document text ends the prompt"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_extraction_parses_entities() {
+    let provider = ScriptedProvider::new(&[
+      r#"{"memories": [{"content": "Webhooks from Stripe are verified in src/billing.rs", "memory_type": "codebase", "confidence": 0.8, "entities": [{"name": "Stripe", "entity_type": "service"}, {"name": "src/billing.rs", "entity_type": "file"}]}]}"#,
+    ]);
+
+    let result = extract_memories(&provider, &meaningful_context()).await.unwrap();
+    let entities = &result.memories[0].entities;
+    assert_eq!(entities.len(), 2, "entities parsed with the memory");
+    assert_eq!(entities[0].entity_type, crate::EntityType::Service);
+    assert_eq!(entities[1].name, "src/billing.rs");
+  }
+
+  #[tokio::test]
+  async fn test_summarize_session_includes_previous_summary() {
+    let provider = ScriptedProvider::new(&[r#"{"summary": "  Moved config to TOML, then fixed the loader tests.  "}"#]);
+
+    let summary = summarize_session(&provider, Some("Moved config to TOML."), &meaningful_context())
+      .await
+      .unwrap();
+    assert_eq!(
+      summary, "Moved config to TOML, then fixed the loader tests.",
+      "summary should be trimmed"
+    );
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert!(
+      prompts[0].contains("Summary so far:\nMoved config to TOML."),
+      "previous summary should be part of the prompt"
+    );
+    assert!(
+      prompts[0].contains("src/config.rs"),
+      "segment activity should be part of the prompt"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_write_retrospective_lists_material() {
+    let provider = ScriptedProvider::new(&[
+      r#"{"summary": " Shipped the TOML loader a day late. ", "went_well": ["Loader tests caught the path bug"], "went_wrong": [], "deviations": ["Kept YAML support for one release"], "lessons": []}"#,
+    ]);
+    let input = RetroInput {
+      subject: "Switch the config loader to TOML".into(),
+      plan: vec!["Replace the YAML loader outright".into()],
+      completions: vec!["Added the TOML loader behind a flag".into()],
+      errors: Vec::new(),
+      decisions: vec!["Keep YAML for one release".into()],
+    };
+
+    let retro = write_retrospective(&provider, &input).await.unwrap();
+    assert_eq!(
+      retro.summary, "Shipped the TOML loader a day late.",
+      "summary should be trimmed"
+    );
+    assert_eq!(retro.deviations.len(), 1);
+
+    let prompts = provider.prompts.lock().unwrap();
+    assert!(prompts[0].contains("Plan:\n- Replace the YAML loader outright"));
+    assert!(
+      prompts[0].contains("Errors and pitfalls:\n(none recorded)"),
+      "empty groups are marked rather than left blank"
+    );
+  }
+
+  #[test]
+  fn test_parse_partial_extraction_rejects_non_json() {
+    assert!(
+      parse_partial_extraction("not json").is_none(),
+      "non-JSON has nothing to salvage"
+    );
+    assert!(
+      parse_partial_extraction(r#"{"memories": [{"content": "x"}]}"#).is_none(),
+      "all-invalid arrays should not be accepted"
+    );
+  }
+
   // These tests require an LLM provider to be available
 
   #[tokio::test]
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 pub mod extraction;
+mod pool;
 mod prompts;
 mod provider;
 
@@ -9,7 +10,8 @@ mod claude;
 
 // Re-export provider trait and types
 // Re-export prompts and context types
-pub use prompts::{ExtractionContext, ToolUse};
+pub use pool::{TaskRoute, TaskRoutes, WorkerPool};
+pub use prompts::{ExtractionContext, ToolUse, split_text_by_token_budget};
 pub use provider::{LlmProvider, Result};
 
 /// Semantic type for extracted memories
@@ -32,6 +34,8 @@ pub enum MemoryType {
   TurnSummary,
   /// Record of completed task
   TaskCompletion,
+  /// Objective still being worked toward
+  Goal,
 }
 
 impl MemoryType {
@@ -44,6 +48,7 @@ impl MemoryType {
       MemoryType::Pattern => "pattern",
       MemoryType::TurnSummary => "turn_summary",
       MemoryType::TaskCompletion => "task_completion",
+      MemoryType::Goal => "goal",
     }
   }
 }
@@ -66,6 +71,7 @@ impl std::str::FromStr for MemoryType {
       "pattern" => Ok(MemoryType::Pattern),
       "turn_summary" | "turnsummary" => Ok(MemoryType::TurnSummary),
       "task_completion" | "taskcompletion" => Ok(MemoryType::TaskCompletion),
+      "goal" => Ok(MemoryType::Goal),
       _ => Err(()),
     }
   }
@@ -93,6 +99,36 @@ pub fn create_provider() -> Result<Box<dyn LlmProvider>> {
   }
 }
 
+/// Kind of work an inference request performs
+///
+/// Used by [`WorkerPool`] to route each request to the model configured for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmTask {
+  /// Cheap classification of user prompts
+  Classification,
+  /// Full memory extraction from a conversation segment
+  Extraction,
+  /// Immediate extraction for corrections and preferences
+  HighPriority,
+  /// Detecting memories superseded by a new one
+  Superseding,
+  /// Rolling summary of earlier segments in a session
+  Summarization,
+}
+
+impl LlmTask {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Classification => "classification",
+      Self::Extraction => "extraction",
+      Self::HighPriority => "high_priority",
+      Self::Superseding => "superseding",
+      Self::Summarization => "summarization",
+    }
+  }
+}
+
 /// Request for LLM inference
 #[derive(Debug, Clone, Default)]
 pub struct InferenceRequest {
@@ -106,6 +142,8 @@ pub struct InferenceRequest {
   pub timeout_secs: u64,
   /// Optional JSON schema for structured output
   pub json_schema: String,
+  /// Task this request performs, used for model routing
+  pub task: Option<LlmTask>,
 }
 
 impl InferenceRequest {
@@ -116,6 +154,7 @@ impl InferenceRequest {
       model: Default::default(),
       timeout_secs: 60,
       json_schema,
+      task: None,
     }
   }
 }
@@ -135,6 +174,65 @@ pub struct InferenceResponse {
   pub duration_ms: u64,
 }
 
+/// Kind of named entity mentioned in a memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityType {
+  /// Someone involved in the project
+  Person,
+  /// Language, library, framework or tool
+  Technology,
+  /// External or internal service the project talks to
+  Service,
+  /// File in the project
+  File,
+}
+
+impl EntityType {
+  pub const ALL: [EntityType; 4] = [
+    EntityType::Person,
+    EntityType::Technology,
+    EntityType::Service,
+    EntityType::File,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      EntityType::Person => "person",
+      EntityType::Technology => "technology",
+      EntityType::Service => "service",
+      EntityType::File => "file",
+    }
+  }
+}
+
+impl std::fmt::Display for EntityType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl std::str::FromStr for EntityType {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "person" => Ok(EntityType::Person),
+      "technology" => Ok(EntityType::Technology),
+      "service" => Ok(EntityType::Service),
+      "file" => Ok(EntityType::File),
+      _ => Err(format!("Unknown entity type: {}", s)),
+    }
+  }
+}
+
+/// Named entity mentioned in an extracted memory
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExtractedEntity {
+  pub name: String,
+  pub entity_type: EntityType,
+}
+
 /// Structured extraction result for memory extraction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionResult {
@@ -150,6 +248,8 @@ pub struct ExtractedMemory {
   #[serde(default)]
   pub tags: Vec<String>,
   pub confidence: f32,
+  #[serde(default)]
+  pub entities: Vec<ExtractedEntity>,
 }
 
 /// Signal classification result
@@ -195,6 +295,48 @@ pub struct SupersedingResult {
   pub confidence: f32,
 }
 
+/// Rolling session summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+  pub summary: String,
+}
+
+/// Narrative of one turn, from the user prompt to Stop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnSummary {
+  pub summary: String,
+  #[serde(default)]
+  pub files_touched: Vec<String>,
+  #[serde(default)]
+  pub decisions: Vec<String>,
+}
+
+/// What was planned and what happened for a piece of work, for a retrospective
+#[derive(Debug, Clone, Default)]
+pub struct RetroInput {
+  /// The task or goal the retrospective is about
+  pub subject: String,
+  pub plan: Vec<String>,
+  pub completions: Vec<String>,
+  pub errors: Vec<String>,
+  pub decisions: Vec<String>,
+}
+
+/// Retrospective comparing a plan with its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Retrospective {
+  pub summary: String,
+  #[serde(default)]
+  pub went_well: Vec<String>,
+  #[serde(default)]
+  pub went_wrong: Vec<String>,
+  /// Where the outcome departed from the plan
+  #[serde(default)]
+  pub deviations: Vec<String>,
+  #[serde(default)]
+  pub lessons: Vec<String>,
+}
+
 /// Errors that can occur during LLM inference
 #[derive(Debug, thiserror::Error)]
 pub enum LlmError {
@@ -210,6 +352,8 @@ pub enum LlmError {
   NoResponse,
   #[error("No LLM provider available. Enable a provider feature (e.g., 'claude').")]
   NoProviderAvailable,
+  #[error("LLM worker pool is closed")]
+  PoolClosed,
   #[cfg(feature = "claude")]
   #[error("Claude executable not found. Ensure 'claude' is in your PATH.")]
   ClaudeNotFound,
@@ -0,0 +1,205 @@
+//! Bounded LLM worker pool with per-task model routing
+//!
+//! `WorkerPool` wraps another provider and limits how many inference calls run
+//! at once across every clone of the pool. Requests tagged with an [`LlmTask`]
+//! are routed to the model and timeout configured for that task, so cheap
+//! classification can run on a small model while extraction uses a stronger one.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+use tracing::{debug, trace};
+
+use crate::{InferenceRequest, InferenceResponse, LlmError, LlmProvider, LlmTask, Result};
+
+/// Model and timeout used for one kind of task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRoute {
+  pub model: String,
+  pub timeout_secs: u64,
+}
+
+impl TaskRoute {
+  pub fn new(model: impl Into<String>, timeout_secs: u64) -> Self {
+    Self {
+      model: model.into(),
+      timeout_secs,
+    }
+  }
+}
+
+/// Routing table from task type to model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRoutes {
+  pub classification: TaskRoute,
+  pub extraction: TaskRoute,
+  pub high_priority: TaskRoute,
+  pub superseding: TaskRoute,
+  pub summarization: TaskRoute,
+}
+
+impl TaskRoutes {
+  pub fn route(&self, task: LlmTask) -> &TaskRoute {
+    match task {
+      LlmTask::Classification => &self.classification,
+      LlmTask::Extraction => &self.extraction,
+      LlmTask::HighPriority => &self.high_priority,
+      LlmTask::Superseding => &self.superseding,
+      LlmTask::Summarization => &self.summarization,
+    }
+  }
+}
+
+impl Default for TaskRoutes {
+  fn default() -> Self {
+    Self {
+      classification: TaskRoute::new("haiku", 30),
+      extraction: TaskRoute::new("haiku", 60),
+      high_priority: TaskRoute::new("haiku", 30),
+      superseding: TaskRoute::new("haiku", 30),
+      summarization: TaskRoute::new("haiku", 30),
+    }
+  }
+}
+
+/// Provider wrapper that bounds concurrency and routes tasks to models
+///
+/// Cloning the pool shares its permits, so a single pool created at daemon
+/// startup bounds LLM work across all projects.
+#[derive(Clone)]
+pub struct WorkerPool {
+  inner: Box<dyn LlmProvider>,
+  permits: Arc<Semaphore>,
+  routes: Arc<TaskRoutes>,
+}
+
+impl WorkerPool {
+  /// Create a pool allowing at most `max_concurrency` in-flight requests
+  pub fn new(inner: Box<dyn LlmProvider>, max_concurrency: usize, routes: TaskRoutes) -> Self {
+    Self {
+      inner,
+      permits: Arc::new(Semaphore::new(max_concurrency.max(1))),
+      routes: Arc::new(routes),
+    }
+  }
+}
+
+#[async_trait]
+impl LlmProvider for WorkerPool {
+  fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  fn is_available(&self) -> bool {
+    self.inner.is_available()
+  }
+
+  async fn infer(&self, mut request: InferenceRequest) -> Result<InferenceResponse> {
+    if let Some(task) = request.task {
+      let route = self.routes.route(task);
+      request.model = route.model.clone();
+      request.timeout_secs = route.timeout_secs;
+    }
+
+    trace!(
+      task = ?request.task,
+      model = %request.model,
+      available = self.permits.available_permits(),
+      "Waiting for LLM worker"
+    );
+    let _permit = self.permits.acquire().await.map_err(|_| LlmError::PoolClosed)?;
+
+    debug!(task = ?request.task, model = %request.model, "Running LLM inference");
+    self.inner.infer(request).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+  };
+
+  use super::*;
+
+  #[derive(Clone, Default)]
+  struct MockProvider {
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+  }
+
+  #[async_trait]
+  impl LlmProvider for MockProvider {
+    fn name(&self) -> &str {
+      "mock"
+    }
+
+    fn is_available(&self) -> bool {
+      true
+    }
+
+    async fn infer(&self, request: InferenceRequest) -> Result<InferenceResponse> {
+      let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+      self.peak.fetch_max(now, Ordering::SeqCst);
+      tokio::time::sleep(Duration::from_millis(20)).await;
+      self.running.fetch_sub(1, Ordering::SeqCst);
+
+      Ok(InferenceResponse {
+        text: request.model,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+        duration_ms: 0,
+      })
+    }
+  }
+
+  #[tokio::test]
+  async fn test_pool_bounds_concurrency() {
+    let mock = MockProvider::default();
+    let pool = WorkerPool::new(Box::new(mock.clone()), 2, TaskRoutes::default());
+
+    let calls = (0..6).map(|_| {
+      let pool = pool.clone();
+      tokio::spawn(async move { pool.infer(InferenceRequest::default()).await })
+    });
+    for call in calls.collect::<Vec<_>>() {
+      call.await.unwrap().unwrap();
+    }
+
+    assert_eq!(
+      mock.peak.load(Ordering::SeqCst),
+      2,
+      "no more than max_concurrency requests should run at once"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_pool_routes_task_to_model() {
+    let routes = TaskRoutes {
+      extraction: TaskRoute::new("sonnet", 120),
+      ..Default::default()
+    };
+    let pool = WorkerPool::new(Box::new(MockProvider::default()), 1, routes);
+
+    let request = InferenceRequest {
+      model: "haiku".to_string(),
+      task: Some(LlmTask::Extraction),
+      ..Default::default()
+    };
+    let response = pool.infer(request).await.unwrap();
+    assert_eq!(
+      response.text, "sonnet",
+      "extraction should be routed to its configured model"
+    );
+
+    let untagged = InferenceRequest {
+      model: "opus".to_string(),
+      ..Default::default()
+    };
+    let response = pool.infer(untagged).await.unwrap();
+    assert_eq!(response.text, "opus", "untagged requests should keep their model");
+  }
+}
@@ -2,8 +2,11 @@
 //!
 //! Uses JSON schemas for structured output validation.
 
+use serde::{Deserialize, Serialize};
 use tracing::trace;
 
+use crate::RetroInput;
+
 /// JSON schema for signal classification response
 pub const SIGNAL_CLASSIFICATION_SCHEMA: &str = r#"{
   "type": "object",
@@ -31,10 +34,21 @@ pub const EXTRACTION_SCHEMA: &str = r#"{
           "summary": { "type": ["string", "null"] },
           "memory_type": {
             "type": "string",
-            "enum": ["preference", "codebase", "decision", "gotcha", "pattern", "turn_summary", "task_completion"]
+            "enum": ["preference", "codebase", "decision", "gotcha", "pattern", "turn_summary", "task_completion", "goal"]
           },
           "tags": { "type": "array", "items": { "type": "string" } },
-          "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+          "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+          "entities": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "properties": {
+                "name": { "type": "string" },
+                "entity_type": { "type": "string", "enum": ["person", "technology", "service", "file"] }
+              },
+              "required": ["name", "entity_type"]
+            }
+          }
         },
         "required": ["content", "memory_type", "confidence"]
       }
@@ -81,12 +95,14 @@ Memory types:
 - pattern: Recurring pattern or best practice
 - turn_summary: Summary of what was accomplished
 - task_completion: Record of completed task
+- goal: Objective the user wants reached that is not done yet
 
 Guidelines:
 - Only extract memories with confidence >= 0.6
 - Return EMPTY ARRAY if nothing worth extracting (routine file reads, simple searches, trivial changes)
 - Extract MULTIPLE memories when the segment contains distinct valuable insights
 - Each memory should be self-contained and useful in isolation
+- List the entities each memory names: people (person), languages, libraries and tools (technology), APIs and hosted services (service), and project files (file)
 
 Do NOT create memories for:
 - Routine exploration (reading files to understand code)
@@ -100,10 +116,116 @@ DO create memories for:
 - Architectural decisions with rationale
 - Bugs found and their causes
 - Patterns that should be followed
+- Goals the user sets for upcoming work, with any deadline mentioned
 
 Conversation:
 "#;
 
+/// Prompt for extracting memories from free-form text such as notes or PR descriptions
+pub const TEXT_EXTRACTION_PROMPT: &str = r#"Extract valuable long-term memories about this project from the document below.
+
+Memory types:
+- preference: Stated preference of the user or team
+- codebase: Knowledge about code structure/behavior
+- decision: Design or implementation decision with rationale
+- gotcha: Pitfall or warning to remember
+- pattern: Recurring pattern or best practice
+- task_completion: Record of completed work
+- goal: Objective the project is working toward that is not done yet
+
+Guidelines:
+- Only extract memories with confidence >= 0.6
+- Return EMPTY ARRAY if the document has nothing of lasting value
+- Extract MULTIPLE memories when the document contains distinct valuable insights
+- Each memory should be self-contained and useful in isolation
+- List the entities each memory names: people (person), languages, libraries and tools (technology), APIs and hosted services (service), and project files (file)
+- Skip scheduling, greetings and action items that carry no lasting context
+
+"#;
+
+/// JSON schema for session summaries
+pub const SESSION_SUMMARY_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "summary": { "type": "string" }
+  },
+  "required": ["summary"]
+}"#;
+
+/// Prompt for folding a finished segment into the rolling session summary
+pub const SESSION_SUMMARY_PROMPT: &str = r#"Update the running summary of this coding session with the segment below.
+
+Keep what later work needs to make sense of: the goal, decisions made and why, approaches that failed, problems found and which files are involved. Drop routine details. Write at most {max_words} words of plain prose.
+
+Summary so far:
+{previous_summary}
+
+New segment:
+{segment}
+"#;
+
+/// JSON schema for turn summaries
+pub const TURN_SUMMARY_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "summary": { "type": "string" },
+    "files_touched": { "type": "array", "items": { "type": "string" } },
+    "decisions": { "type": "array", "items": { "type": "string" } }
+  },
+  "required": ["summary", "files_touched", "decisions"]
+}"#;
+
+/// Prompt for summarizing one turn (user prompt to Stop) of a coding session
+pub const TURN_SUMMARY_PROMPT: &str = r#"Summarize what happened in this turn of a coding session.
+
+summary: a short narrative of the work, at most {max_words} words of plain prose: what was asked, what was done, and how it ended (done, blocked, or left for later).
+files_touched: project files that were created or modified (not files only read).
+decisions: choices made during the turn and why, one sentence each. Empty when none were made.
+
+Turn:
+{segment}
+"#;
+
+/// JSON schema for retrospectives
+pub const RETRO_SCHEMA: &str = r#"{
+  "type": "object",
+  "properties": {
+    "summary": { "type": "string" },
+    "went_well": { "type": "array", "items": { "type": "string" } },
+    "went_wrong": { "type": "array", "items": { "type": "string" } },
+    "deviations": { "type": "array", "items": { "type": "string" } },
+    "lessons": { "type": "array", "items": { "type": "string" } }
+  },
+  "required": ["summary", "went_well", "went_wrong", "deviations", "lessons"]
+}"#;
+
+/// Prompt for a retrospective comparing a plan with its outcome
+pub const RETRO_PROMPT: &str = r#"Write a retrospective for this piece of work in a software project, comparing what was planned with what actually happened.
+
+summary: at most {max_words} words of plain prose on how the work went overall.
+went_well: what worked, one sentence each.
+went_wrong: problems, errors and dead ends, one sentence each.
+deviations: where the outcome departed from the plan and why, one sentence each. Empty when the plan was followed.
+lessons: concrete advice for similar work in future, one sentence each.
+
+Only use what the material below supports. Leave a list empty rather than guessing.
+
+Work:
+{subject}
+
+Plan:
+{plan}
+
+Completed tasks:
+{completions}
+
+Errors and pitfalls:
+{errors}
+
+Decisions:
+{decisions}
+"#;
+
 /// Prompt for detecting if new memory supersedes existing ones
 pub const SUPERSEDING_DETECTION_PROMPT: &str = r#"Does the new memory supersede any existing memory?
 
@@ -117,6 +239,21 @@ Existing memories:
 {existing_memories}
 "#;
 
+/// Prompt for repairing a response that failed JSON schema validation
+pub const JSON_REPAIR_PROMPT: &str = r#"Your previous response was not valid JSON for the required schema.
+
+Validation error:
+{error}
+
+Previous response:
+{response}
+
+Respond again with only the corrected JSON. Keep all valid content and fix the invalid fields.
+
+Original request:
+{prompt}
+"#;
+
 /// System prompt for extraction context
 pub const EXTRACTION_SYSTEM_PROMPT: &str = r#"You are CCEngram's memory extraction system. Extract valuable information from Claude Code conversations that would be useful in future sessions.
 
@@ -165,11 +302,168 @@ pub fn build_signal_classification_prompt(user_message: &str) -> String {
   prompt
 }
 
+/// Characters of the last assistant message included in extraction prompts
+const ASSISTANT_MESSAGE_LIMIT: usize = 1000;
+
+/// Word limit requested for rolling session summaries
+const SESSION_SUMMARY_MAX_WORDS: usize = 200;
+
+/// Word limit requested for turn summaries
+const TURN_SUMMARY_MAX_WORDS: usize = 120;
+
+/// Word limit requested for retrospective summaries
+const RETRO_SUMMARY_MAX_WORDS: usize = 150;
+
+/// Approximate characters per token, used to budget extraction prompts
+const CHARS_PER_TOKEN: usize = 4;
+
 /// Build a memory extraction prompt for a conversation segment
 pub fn build_extraction_prompt(context: &ExtractionContext) -> String {
   let mut prompt = String::new();
   prompt.push_str(MEMORY_EXTRACTION_PROMPT);
 
+  if let Some(summary) = &context.session_summary {
+    prompt.push_str("\nEarlier in this session: ");
+    prompt.push_str(summary);
+    prompt.push('\n');
+  }
+
+  prompt.push_str(&format_segment(context));
+
+  trace!(
+    template_len = MEMORY_EXTRACTION_PROMPT.len(),
+    total_len = prompt.len(),
+    has_user_prompt = context.user_prompt.is_some(),
+    has_session_summary = context.session_summary.is_some(),
+    tool_uses_count = context.tool_uses.len(),
+    files_read_count = context.files_read.len(),
+    files_modified_count = context.files_modified.len(),
+    commands_run_count = context.commands_run.len(),
+    errors_count = context.errors_encountered.len(),
+    tasks_count = context.completed_tasks.len(),
+    has_assistant_message = context.last_assistant_message.is_some(),
+    "Built memory extraction prompt"
+  );
+
+  prompt
+}
+
+/// Build a memory extraction prompt for free-form text
+pub fn build_text_extraction_prompt(text: &str, source: Option<&str>) -> String {
+  let mut prompt = String::from(TEXT_EXTRACTION_PROMPT);
+  if let Some(source) = source {
+    prompt.push_str(&format!("Source: {}\n\n", source));
+  }
+  prompt.push_str("Document:\n");
+  prompt.push_str(text.trim());
+
+  trace!(
+    template_len = TEXT_EXTRACTION_PROMPT.len(),
+    text_len = text.len(),
+    total_len = prompt.len(),
+    "Built text extraction prompt"
+  );
+
+  prompt
+}
+
+/// Split free-form text into parts whose extraction prompts fit within `max_tokens`.
+///
+/// Parts break between paragraphs where possible, then between lines; a single
+/// line longer than the budget is cut at a character boundary.
+pub fn split_text_by_token_budget(text: &str, max_tokens: usize) -> Vec<String> {
+  // Room for the template and source line, never less than a few paragraphs
+  let available = max_tokens
+    .saturating_mul(CHARS_PER_TOKEN)
+    .saturating_sub(TEXT_EXTRACTION_PROMPT.len() + 256)
+    .max(1024);
+
+  // Pieces with the separator that joins them to the previous one
+  let mut pieces: Vec<(&str, &str)> = Vec::new();
+  for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+    if paragraph.len() <= available {
+      pieces.push((paragraph, "\n\n"));
+      continue;
+    }
+    let mut separator = "\n\n";
+    for line in paragraph.lines() {
+      let mut rest = line;
+      while rest.len() > available {
+        let mut end = available;
+        while !rest.is_char_boundary(end) {
+          end -= 1;
+        }
+        pieces.push((&rest[..end], separator));
+        rest = &rest[end..];
+        separator = "";
+      }
+      if !rest.trim().is_empty() {
+        pieces.push((rest, separator));
+      }
+      separator = "\n";
+    }
+  }
+
+  let mut parts = Vec::new();
+  let mut current = String::new();
+  for (piece, separator) in pieces {
+    if !current.is_empty() && current.len() + separator.len() + piece.len() > available {
+      parts.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+      current.push_str(separator);
+    }
+    current.push_str(piece);
+  }
+  if !current.is_empty() {
+    parts.push(current);
+  }
+  parts
+}
+
+/// Build a prompt folding a segment into the rolling session summary
+pub fn build_session_summary_prompt(previous_summary: Option<&str>, context: &ExtractionContext) -> String {
+  SESSION_SUMMARY_PROMPT
+    .replace("{max_words}", &SESSION_SUMMARY_MAX_WORDS.to_string())
+    .replace("{previous_summary}", previous_summary.unwrap_or("(none)"))
+    .replace("{segment}", format_segment(context).trim_start())
+}
+
+/// Build a prompt summarizing a finished turn
+pub fn build_turn_summary_prompt(context: &ExtractionContext) -> String {
+  TURN_SUMMARY_PROMPT
+    .replace("{max_words}", &TURN_SUMMARY_MAX_WORDS.to_string())
+    .replace("{segment}", format_segment(context).trim_start())
+}
+
+/// Build a prompt for a retrospective on a piece of work
+pub fn build_retro_prompt(input: &RetroInput) -> String {
+  let list = |items: &[String]| {
+    if items.is_empty() {
+      "(none recorded)".to_string()
+    } else {
+      items
+        .iter()
+        .map(|item| format!("- {}", item))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+  };
+
+  // Replace the subject last so placeholders inside memory content aren't substituted
+  RETRO_PROMPT
+    .replace("{max_words}", &RETRO_SUMMARY_MAX_WORDS.to_string())
+    .replace("{plan}", &list(&input.plan))
+    .replace("{completions}", &list(&input.completions))
+    .replace("{errors}", &list(&input.errors))
+    .replace("{decisions}", &list(&input.decisions))
+    .replace("{subject}", &input.subject)
+}
+
+/// Render a segment's prompt, tool uses, errors and outcome for inclusion in a prompt
+fn format_segment(context: &ExtractionContext) -> String {
+  let mut prompt = String::new();
+
   if let Some(user_prompt) = &context.user_prompt {
     prompt.push_str("\nUser prompt: ");
     prompt.push_str(user_prompt);
@@ -217,24 +511,10 @@ pub fn build_extraction_prompt(context: &ExtractionContext) -> String {
 
   if let Some(assistant_msg) = &context.last_assistant_message {
     prompt.push_str("\nLast assistant response (truncated): ");
-    let truncated: String = assistant_msg.chars().take(1000).collect();
+    let truncated: String = assistant_msg.chars().take(ASSISTANT_MESSAGE_LIMIT).collect();
     prompt.push_str(&truncated);
   }
 
-  trace!(
-    template_len = MEMORY_EXTRACTION_PROMPT.len(),
-    total_len = prompt.len(),
-    has_user_prompt = context.user_prompt.is_some(),
-    tool_uses_count = context.tool_uses.len(),
-    files_read_count = context.files_read.len(),
-    files_modified_count = context.files_modified.len(),
-    commands_run_count = context.commands_run.len(),
-    errors_count = context.errors_encountered.len(),
-    tasks_count = context.completed_tasks.len(),
-    has_assistant_message = context.last_assistant_message.is_some(),
-    "Built memory extraction prompt"
-  );
-
   prompt
 }
 
@@ -269,8 +549,54 @@ pub fn build_superseding_prompt(new_memory: &str, existing_memories: &[(String,
   prompt
 }
 
+/// Build a prompt asking the model to fix an invalid structured response
+pub fn build_repair_prompt(original_prompt: &str, invalid_response: &str, error: &str) -> String {
+  // One pass over the template, so placeholders inside the substituted content stay literal
+  let prompt = fill_template(
+    JSON_REPAIR_PROMPT,
+    &[
+      ("{prompt}", original_prompt),
+      ("{response}", invalid_response),
+      ("{error}", error),
+    ],
+  );
+
+  trace!(
+    error = error,
+    invalid_response_len = invalid_response.len(),
+    total_len = prompt.len(),
+    "Built JSON repair prompt"
+  );
+
+  prompt
+}
+
+/// Replace each placeholder in `template` with its value, scanning the
+/// template once so values are never themselves searched for placeholders
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+  let mut filled = String::with_capacity(template.len());
+  let mut rest = template;
+  while let Some(start) = rest.find('{') {
+    filled.push_str(&rest[..start]);
+    let tail = &rest[start..];
+    match values.iter().find(|(placeholder, _)| tail.starts_with(placeholder)) {
+      Some((placeholder, value)) => {
+        filled.push_str(value);
+        rest = &tail[placeholder.len()..];
+      }
+      None => {
+        filled.push('{');
+        rest = &tail[1..];
+      }
+    }
+  }
+  filled.push_str(rest);
+  filled
+}
+
 /// Typed tool use data for extraction context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "tool", rename_all = "snake_case")]
 pub enum ToolUse {
   /// File read operation
   Read { file_path: String },
@@ -473,6 +799,14 @@ impl ToolUse {
     }
   }
 
+  /// Get tasks not yet completed if this is a TodoWrite
+  pub fn pending_tasks(&self) -> Option<&[String]> {
+    match self {
+      ToolUse::TodoWrite { pending_tasks, .. } => Some(pending_tasks),
+      _ => None,
+    }
+  }
+
   /// Format for LLM prompt inclusion
   pub fn format_for_prompt(&self) -> String {
     match self {
@@ -523,10 +857,15 @@ impl ToolUse {
 }
 
 /// Context for memory extraction
-#[derive(Debug, Default, Clone)]
+///
+/// Serializable so failed segments can be queued and extracted again later.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExtractionContext {
   /// The user's prompt that started this segment
   pub user_prompt: Option<String>,
+  /// Compressed summary of earlier segments in the same session
+  pub session_summary: Option<String>,
   /// Files that were read during this segment
   pub files_read: Vec<String>,
   /// Files that were modified during this segment
@@ -561,6 +900,124 @@ impl ExtractionContext {
       || !self.errors_encountered.is_empty()
   }
 
+  /// Approximate size of this context's extraction prompt in tokens
+  pub fn estimated_tokens(&self) -> usize {
+    build_extraction_prompt(self).len() / CHARS_PER_TOKEN
+  }
+
+  /// Split into contexts whose extraction prompts fit within `max_tokens`.
+  ///
+  /// Tool uses are packed in order into consecutive windows, each starting with
+  /// the last `overlap` tool uses of the previous window for continuity. Errors
+  /// are spread across the windows in order, the user prompt (truncated if
+  /// needed) and session summary are kept in every window and the last assistant
+  /// message only in the final one.
+  pub fn split_by_token_budget(&self, max_tokens: usize, overlap: usize) -> Vec<ExtractionContext> {
+    if self.estimated_tokens() <= max_tokens {
+      return vec![self.clone()];
+    }
+
+    let budget = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+    let user_prompt = self
+      .user_prompt
+      .as_ref()
+      .map(|p| p.chars().take(budget / 4).collect::<String>());
+    if self.tool_uses.is_empty() {
+      return vec![ExtractionContext {
+        user_prompt,
+        ..self.clone()
+      }];
+    }
+
+    let n = self.tool_uses.len();
+    let mut errors_by_tool: Vec<Vec<&String>> = vec![Vec::new(); n];
+    for (i, error) in self.errors_encountered.iter().enumerate() {
+      errors_by_tool[i * n / self.errors_encountered.len()].push(error);
+    }
+
+    // Per-line prefix ("\n  - ") plus section headers
+    const LINE_OVERHEAD: usize = 6;
+    let fixed = MEMORY_EXTRACTION_PROMPT.len()
+      + user_prompt.as_ref().map_or(0, |p| p.len() + 64)
+      + self.session_summary.as_ref().map_or(0, |s| s.len() + 64)
+      + ASSISTANT_MESSAGE_LIMIT
+      + 128;
+    let available = budget.saturating_sub(fixed);
+    let costs: Vec<usize> = self
+      .tool_uses
+      .iter()
+      .zip(&errors_by_tool)
+      .map(|(tool_use, errors)| {
+        let tasks = tool_use.completed_tasks().unwrap_or_default();
+        tool_use.format_for_prompt().len()
+          + tasks
+            .iter()
+            .chain(errors.iter().copied())
+            .map(|s| s.len() + LINE_OVERHEAD)
+            .sum::<usize>()
+          + LINE_OVERHEAD
+      })
+      .collect();
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    loop {
+      let mut end = start;
+      let mut used = 0;
+      while end < n && (end == start || used + costs[end] <= available) {
+        used += costs[end];
+        end += 1;
+      }
+      windows.push(start..end);
+      if end == n {
+        break;
+      }
+      start = if end - start > overlap { end - overlap } else { end };
+    }
+
+    let last = windows.len() - 1;
+    windows
+      .into_iter()
+      .enumerate()
+      .map(|(i, range)| {
+        let tool_uses = self.tool_uses[range.clone()].to_vec();
+        let mut split = ExtractionContext {
+          user_prompt: user_prompt.clone(),
+          session_summary: self.session_summary.clone(),
+          errors_encountered: errors_by_tool[range].iter().flatten().map(|e| e.to_string()).collect(),
+          last_assistant_message: if i == last {
+            self.last_assistant_message.clone()
+          } else {
+            None
+          },
+          tool_call_count: tool_uses.len(),
+          ..Default::default()
+        };
+
+        for tool_use in &tool_uses {
+          if let Some(path) = tool_use.file_path() {
+            if tool_use.is_file_modification() {
+              push_unique(&mut split.files_modified, path);
+            } else if tool_use.is_file_read() {
+              push_unique(&mut split.files_read, path);
+            }
+          }
+          if let Some((command, exit_code)) = tool_use.command_info() {
+            split.commands_run.push((command.to_string(), exit_code));
+          }
+          if let Some(pattern) = tool_use.search_pattern() {
+            push_unique(&mut split.searches_performed, pattern);
+          }
+          for task in tool_use.completed_tasks().unwrap_or_default() {
+            push_unique(&mut split.completed_tasks, task);
+          }
+        }
+        split.tool_uses = tool_uses;
+        split
+      })
+      .collect()
+  }
+
   /// Check if this segment has high-priority signals requiring immediate extraction
   pub fn has_high_priority_signals(&self) -> bool {
     // Corrections or preferences trigger immediate extraction
@@ -568,3 +1025,130 @@ impl ExtractionContext {
     false // Placeholder - actual check happens via LLM classification
   }
 }
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+  if !values.iter().any(|v| v == value) {
+    values.push(value.to_string());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_repair_prompt_leaves_placeholders_in_content() {
+    let prompt = build_repair_prompt("extract {response}", r#"{"a": "{prompt} {error}"}"#, "missing field");
+    assert!(
+      prompt.contains("extract {response}"),
+      "original prompt is inserted as is"
+    );
+    assert!(
+      prompt.contains(r#"{"a": "{prompt} {error}"}"#),
+      "placeholders in the invalid response are not expanded"
+    );
+    assert!(prompt.contains("missing field"));
+    assert_eq!(prompt.matches("extract").count(), 1);
+  }
+
+  fn bash(i: usize, exit_code: i32) -> ToolUse {
+    ToolUse::Bash {
+      command: format!("cargo test --package crate_{} -- --nocapture {}", i, "x".repeat(200)),
+      exit_code,
+    }
+  }
+
+  #[test]
+  fn test_split_keeps_small_context_whole() {
+    let ctx = ExtractionContext {
+      user_prompt: Some("fix the build".to_string()),
+      tool_uses: (0..5).map(|i| bash(i, 0)).collect(),
+      tool_call_count: 5,
+      ..Default::default()
+    };
+
+    let splits = ctx.split_by_token_budget(10_000, 2);
+    assert_eq!(splits.len(), 1, "context within budget should not be split");
+    assert_eq!(splits[0].tool_uses.len(), 5);
+  }
+
+  #[test]
+  fn test_split_text_by_token_budget() {
+    let paragraph = "word ".repeat(300);
+    let text = vec![paragraph.trim(); 10].join("\n\n");
+
+    assert_eq!(
+      split_text_by_token_budget(&text, 100_000),
+      vec![text.clone()],
+      "text within budget should not be split"
+    );
+
+    let parts = split_text_by_token_budget(&text, 1_000);
+    assert!(parts.len() > 1, "oversized text should be split");
+    for part in &parts {
+      assert!(
+        build_text_extraction_prompt(part, None).len() / CHARS_PER_TOKEN <= 1_000,
+        "each part should fit the budget"
+      );
+      assert!(part.ends_with("word"), "parts should break between paragraphs");
+    }
+    assert_eq!(parts.join("\n\n"), text, "no text should be lost or repeated");
+  }
+
+  #[test]
+  fn test_split_by_token_budget_with_overlap() {
+    let mut tool_uses: Vec<ToolUse> = (0..60).map(|i| bash(i, if i == 50 { 1 } else { 0 })).collect();
+    tool_uses.push(ToolUse::Edit {
+      file_path: "src/lib.rs".to_string(),
+      change_preview: None,
+    });
+    let ctx = ExtractionContext {
+      user_prompt: Some("run the whole test suite".to_string()),
+      errors_encountered: vec!["Command 'cargo test' failed with exit code 1".to_string()],
+      last_assistant_message: Some("All done".to_string()),
+      tool_call_count: tool_uses.len(),
+      tool_uses,
+      ..Default::default()
+    };
+    let max_tokens = 2_000;
+
+    let splits = ctx.split_by_token_budget(max_tokens, 3);
+    assert!(splits.len() > 1, "oversized context should be split");
+    for split in &splits {
+      assert!(
+        split.estimated_tokens() <= max_tokens,
+        "each split should fit the budget, got {} tokens",
+        split.estimated_tokens()
+      );
+      assert_eq!(
+        split.user_prompt, ctx.user_prompt,
+        "user prompt should be kept in every split"
+      );
+      assert_eq!(split.tool_call_count, split.tool_uses.len());
+    }
+
+    for pair in splits.windows(2) {
+      let formatted = |tools: &[ToolUse]| tools.iter().map(ToolUse::format_for_prompt).collect::<Vec<_>>();
+      assert_eq!(
+        formatted(&pair[1].tool_uses[..3]),
+        formatted(&pair[0].tool_uses[pair[0].tool_uses.len() - 3..]),
+        "each split should start with the tail of the previous one"
+      );
+    }
+
+    let last = splits.last().unwrap();
+    assert_eq!(last.files_modified, vec!["src/lib.rs".to_string()]);
+    assert_eq!(last.last_assistant_message.as_deref(), Some("All done"));
+    assert!(
+      splits[..splits.len() - 1]
+        .iter()
+        .all(|s| s.last_assistant_message.is_none()),
+      "only the last split should carry the assistant message"
+    );
+    assert_eq!(
+      splits.iter().map(|s| s.errors_encountered.len()).sum::<usize>(),
+      1,
+      "errors should be distributed, not repeated"
+    );
+  }
+}